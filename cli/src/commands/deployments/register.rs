@@ -10,6 +10,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::num::NonZeroU32;
 use std::str::FromStr;
 
 use anyhow::Result;
@@ -47,6 +48,11 @@ pub struct Register {
     /// discovered.
     assume_role_arn: Option<String>,
 
+    #[clap(long)]
+    /// The AWS region that Restate server will use to assume the role and invoke the Lambda.
+    /// Defaults to the region of the Lambda ARN.
+    region: Option<String>,
+
     /// Additional header that will be sent to the endpoint during the discovery request.
     ///
     /// Use `--extra-header name=value` format and repeat --extra-header for each additional header.
@@ -58,6 +64,11 @@ pub struct Register {
     #[clap(long = "use-http1.1")]
     use_http_11: bool,
 
+    /// Maximum number of invocations each node executes concurrently on this deployment.
+    /// Invocations exceeding the limit are queued until a running invocation completes.
+    #[clap(long)]
+    concurrency_limit: Option<NonZeroU32>,
+
     /// The URL or ARN that Restate server needs to fetch service information from.
     ///
     /// The URL must be network-accessible from Restate server. In case of using
@@ -182,13 +193,16 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
             uri: uri.clone(),
             additional_headers: headers.clone().map(Into::into),
             use_http_11: discover_opts.use_http_11,
+            concurrency_limit: discover_opts.concurrency_limit,
             force,
             dry_run,
         },
         DeploymentEndpoint::Lambda(arn) => RegisterDeploymentRequest::Lambda {
             arn: arn.to_string(),
             assume_role_arn: discover_opts.assume_role_arn.clone(),
+            region: discover_opts.region.clone(),
             additional_headers: headers.clone().map(Into::into),
+            concurrency_limit: discover_opts.concurrency_limit,
             force,
             dry_run,
         },
@@ -69,6 +69,11 @@ fn write_out_edit_toml(w: &mut impl io::Write, service_type: ServiceType) -> Res
     writeln!(w, "# public = true")?;
     writeln!(w)?;
 
+    write_prefixed_lines(w, "# ", super::view::ALLOWED_CALLERS)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# allowed_callers = [\"greeter.Orchestrator\"]")?;
+    writeln!(w)?;
+
     write_prefixed_lines(
         w,
         "# ",
@@ -85,6 +90,21 @@ fn write_out_edit_toml(w: &mut impl io::Write, service_type: ServiceType) -> Res
         writeln!(w)?;
     }
 
+    write_prefixed_lines(w, "# ", super::patch::COMPLETION_RETENTION_EDIT_DESCRIPTION)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# completion_retention = \"1hour\"")?;
+    writeln!(w)?;
+
+    writeln!(
+        w,
+        "# Retention of single handlers, overriding the retention of the service."
+    )?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# [handlers.my_handler]")?;
+    writeln!(w, "# idempotency_retention = \"1hour\"")?;
+    writeln!(w, "# completion_retention = \"7days\"")?;
+    writeln!(w)?;
+
     write_prefixed_lines(w, "# ", super::patch::INACTIVITY_TIMEOUT_EDIT_DESCRIPTION)?;
     writeln!(w, "# Example:")?;
     writeln!(w, "# inactivity_timeout = \"1min\"")?;
@@ -95,6 +115,53 @@ fn write_out_edit_toml(w: &mut impl io::Write, service_type: ServiceType) -> Res
     writeln!(w, "# abort_timeout = \"10min\"")?;
     writeln!(w)?;
 
+    if service_type == ServiceType::Service {
+        write_prefixed_lines(w, "# ", super::view::MIRRORING)?;
+        writeln!(w, "# Example:")?;
+        writeln!(w, "# [mirroring]")?;
+        writeln!(w, "# deployment_id = \"dp_15VqmTOnXH3Vv2pl5HOG7UB\"")?;
+        writeln!(w, "# fraction = 0.1")?;
+        writeln!(w)?;
+    }
+
+    write_prefixed_lines(w, "# ", super::view::ROUTING)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# [routing]")?;
+    writeln!(w, "# deployment_id = \"dp_15VqmTOnXH3Vv2pl5HOG7UB\"")?;
+    writeln!(w, "# percentage = 10.0")?;
+    writeln!(w, "# header = {{ name = \"x-canary\", value = \"true\" }}")?;
+    writeln!(w)?;
+
+    if service_type == ServiceType::VirtualObject {
+        write_prefixed_lines(w, "# ", super::view::SHARED_HANDLER_CONCURRENCY)?;
+        writeln!(w, "# Example:")?;
+        writeln!(w, "# shared_handler_concurrency = 10")?;
+        writeln!(w)?;
+    }
+
+    write_prefixed_lines(w, "# ", super::view::CONCURRENCY_LIMIT)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# concurrency_limit = 100")?;
+    writeln!(w)?;
+
+    write_prefixed_lines(w, "# ", super::view::RETRY_POLICY)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# [retry_policy]")?;
+    writeln!(w, "# max_duration = \"1day\"")?;
+    writeln!(w, "# [retry_policy.transport_errors]")?;
+    writeln!(w, "# type = \"exponential\"")?;
+    writeln!(w, "# initial-interval = \"100ms\"")?;
+    writeln!(w, "# factor = 2.0")?;
+    writeln!(w, "# max-interval = \"10s\"")?;
+    writeln!(w, "# [retry_policy.terminal_errors]")?;
+    writeln!(w, "# type = \"none\"")?;
+    writeln!(w)?;
+
+    write_prefixed_lines(w, "# ", super::patch::EXECUTION_TIMEOUT_EDIT_DESCRIPTION)?;
+    writeln!(w, "# Example:")?;
+    writeln!(w, "# execution_timeout = \"1hour\"")?;
+    writeln!(w)?;
+
     Ok(())
 }
 
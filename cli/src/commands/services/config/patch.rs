@@ -30,6 +30,11 @@ pub(super) const WORKFLOW_RETENTION_EDIT_DESCRIPTION: &str = concatcp!(
     "\n",
     DURATION_EDIT_DESCRIPTION
 );
+pub(super) const COMPLETION_RETENTION_EDIT_DESCRIPTION: &str = concatcp!(
+    super::view::COMPLETION_RETENTION,
+    "\n",
+    DURATION_EDIT_DESCRIPTION
+);
 pub(super) const INACTIVITY_TIMEOUT_EDIT_DESCRIPTION: &str = concatcp!(
     super::view::INACTIVITY_TIMEOUT,
     "\n",
@@ -37,6 +42,11 @@ pub(super) const INACTIVITY_TIMEOUT_EDIT_DESCRIPTION: &str = concatcp!(
 );
 pub(super) const ABORT_TIMEOUT_EDIT_DESCRIPTION: &str =
     concatcp!(super::view::ABORT_TIMEOUT, "\n", DURATION_EDIT_DESCRIPTION);
+pub(super) const EXECUTION_TIMEOUT_EDIT_DESCRIPTION: &str = concatcp!(
+    super::view::EXECUTION_TIMEOUT,
+    "\n",
+    DURATION_EDIT_DESCRIPTION
+);
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_patch")]
@@ -44,18 +54,33 @@ pub struct Patch {
     #[clap(long, help = super::view::PUBLIC_DESCRIPTION)]
     public: Option<bool>,
 
+    #[clap(long, alias = "allowed_callers", value_delimiter = ',', help = super::view::ALLOWED_CALLERS)]
+    allowed_callers: Option<Vec<String>>,
+
     #[clap(long, alias = "idempotency_retention", help = IDEMPOTENCY_RETENTION_EDIT_DESCRIPTION)]
     idempotency_retention: Option<String>,
 
     #[clap(long, alias = "workflow_completion_retention", help = WORKFLOW_RETENTION_EDIT_DESCRIPTION)]
     workflow_completion_retention: Option<String>,
 
+    #[clap(long, alias = "completion_retention", help = COMPLETION_RETENTION_EDIT_DESCRIPTION)]
+    completion_retention: Option<String>,
+
     #[clap(long, alias = "inactivity_retention", help = INACTIVITY_TIMEOUT_EDIT_DESCRIPTION)]
     inactivity_timeout: Option<String>,
 
     #[clap(long, alias = "abort_retention", help = ABORT_TIMEOUT_EDIT_DESCRIPTION)]
     abort_timeout: Option<String>,
 
+    #[clap(long, alias = "shared_handler_concurrency", help = super::view::SHARED_HANDLER_CONCURRENCY)]
+    shared_handler_concurrency: Option<u32>,
+
+    #[clap(long, alias = "concurrency_limit", help = super::view::CONCURRENCY_LIMIT)]
+    concurrency_limit: Option<u32>,
+
+    #[clap(long, alias = "execution_timeout", help = EXECUTION_TIMEOUT_EDIT_DESCRIPTION)]
+    execution_timeout: Option<String>,
+
     /// Service name
     service: String,
 }
@@ -68,6 +93,14 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
     let admin_client = AdminClient::new(env).await?;
     let modify_request = ModifyServiceRequest {
         public: opts.public,
+        // An empty value clears the allowed callers
+        allowed_callers: opts.allowed_callers.as_ref().map(|allowed_callers| {
+            allowed_callers
+                .iter()
+                .filter(|caller| !caller.is_empty())
+                .cloned()
+                .collect()
+        }),
         idempotency_retention: opts
             .idempotency_retention
             .as_ref()
@@ -83,6 +116,12 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
                     .context("Cannot parse workflow_completion_retention")
             })
             .transpose()?,
+        completion_retention: opts
+            .completion_retention
+            .as_ref()
+            .map(|s| DurationString::parse_duration(s).context("Cannot parse completion_retention"))
+            .transpose()?,
+        handlers: Default::default(),
         inactivity_timeout: opts
             .inactivity_timeout
             .as_ref()
@@ -93,6 +132,16 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
             .as_ref()
             .map(|s| DurationString::parse_duration(s).context("Cannot parse abort_timeout"))
             .transpose()?,
+        mirroring: None,
+        routing: None,
+        shared_handler_concurrency: opts.shared_handler_concurrency,
+        concurrency_limit: opts.concurrency_limit,
+        retry_policy: None,
+        execution_timeout: opts
+            .execution_timeout
+            .as_ref()
+            .map(|s| DurationString::parse_duration(s).context("Cannot parse execution_timeout"))
+            .transpose()?,
     };
 
     apply_service_configuration_patch(opts.service.clone(), admin_client, modify_request).await
@@ -105,10 +154,19 @@ pub(super) async fn apply_service_configuration_patch(
 ) -> Result<()> {
     // Check if any change was made
     if modify_request.public.is_none()
+        && modify_request.allowed_callers.is_none()
         && modify_request.workflow_completion_retention.is_none()
         && modify_request.idempotency_retention.is_none()
+        && modify_request.completion_retention.is_none()
+        && modify_request.handlers.is_empty()
         && modify_request.inactivity_timeout.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.mirroring.is_none()
+        && modify_request.routing.is_none()
+        && modify_request.shared_handler_concurrency.is_none()
+        && modify_request.concurrency_limit.is_none()
+        && modify_request.retry_policy.is_none()
+        && modify_request.execution_timeout.is_none()
     {
         c_println!("No changes requested");
         return Ok(());
@@ -119,6 +177,16 @@ pub(super) async fn apply_service_configuration_patch(
     if let Some(public) = &modify_request.public {
         table.add_kv_row("Public:", public);
     }
+    if let Some(allowed_callers) = &modify_request.allowed_callers {
+        table.add_kv_row(
+            "Allowed callers:",
+            if allowed_callers.is_empty() {
+                "<ANY>".to_string()
+            } else {
+                allowed_callers.join(", ")
+            },
+        );
+    }
     if let Some(idempotency_retention) = &modify_request.idempotency_retention {
         table.add_kv_row(
             "Idempotent requests retention:",
@@ -131,6 +199,38 @@ pub(super) async fn apply_service_configuration_patch(
             humantime::Duration::from(*workflow_completion_retention),
         );
     }
+    if let Some(completion_retention) = &modify_request.completion_retention {
+        table.add_kv_row(
+            "Completion retention:",
+            humantime::Duration::from(*completion_retention),
+        );
+    }
+    for (handler, handler_request) in &modify_request.handlers {
+        if let Some(idempotency_retention) = &handler_request.idempotency_retention {
+            table.add_kv_row(
+                &format!("Idempotent requests retention of '{handler}':"),
+                humantime::Duration::from(*idempotency_retention),
+            );
+        }
+        if let Some(completion_retention) = &handler_request.completion_retention {
+            table.add_kv_row(
+                &format!("Completion retention of '{handler}':"),
+                humantime::Duration::from(*completion_retention),
+            );
+        }
+        if let Some(retry_policy) = &handler_request.retry_policy {
+            table.add_kv_row(
+                &format!("Retry policy of '{handler}':"),
+                super::view::format_retry_policy(retry_policy),
+            );
+        }
+        if let Some(execution_timeout) = &handler_request.execution_timeout {
+            table.add_kv_row(
+                &format!("Execution timeout of '{handler}':"),
+                humantime::Duration::from(*execution_timeout),
+            );
+        }
+    }
     if let Some(inactivity_timeout) = &modify_request.inactivity_timeout {
         table.add_kv_row(
             "Inactivity timeout:",
@@ -140,6 +240,36 @@ pub(super) async fn apply_service_configuration_patch(
     if let Some(abort_timeout) = &modify_request.abort_timeout {
         table.add_kv_row("Abort timeout:", humantime::Duration::from(*abort_timeout));
     }
+    if let Some(mirroring) = &modify_request.mirroring {
+        table.add_kv_row(
+            "Mirroring:",
+            format!(
+                "{} of the requests to {}",
+                mirroring.fraction, mirroring.deployment_id
+            ),
+        );
+    }
+    if let Some(routing) = &modify_request.routing {
+        table.add_kv_row("Routing:", super::view::format_routing(routing));
+    }
+    if let Some(shared_handler_concurrency) = &modify_request.shared_handler_concurrency {
+        table.add_kv_row("Shared handler concurrency:", shared_handler_concurrency);
+    }
+    if let Some(concurrency_limit) = &modify_request.concurrency_limit {
+        table.add_kv_row("Concurrency limit:", concurrency_limit);
+    }
+    if let Some(retry_policy) = &modify_request.retry_policy {
+        table.add_kv_row(
+            "Retry policy:",
+            super::view::format_retry_policy(retry_policy),
+        );
+    }
+    if let Some(execution_timeout) = &modify_request.execution_timeout {
+        table.add_kv_row(
+            "Execution timeout:",
+            humantime::Duration::from(*execution_timeout),
+        );
+    }
     c_println!("{table}");
     confirm_or_exit("Are you sure you want to apply these changes?")?;
 
@@ -17,6 +17,7 @@ use indoc::indoc;
 use restate_cli_util::ui::console::StyledTable;
 use restate_cli_util::{c_println, c_tip};
 use restate_types::invocation::ServiceType;
+use restate_types::schema::service::{RetryPolicyOverrides, ServiceRouting};
 
 // TODO we could infer this text from the OpenAPI docs!
 pub(super) const PUBLIC_DESCRIPTION: &str = indoc! {
@@ -24,6 +25,11 @@ pub(super) const PUBLIC_DESCRIPTION: &str = indoc! {
     If true, the service can be invoked through the ingress.
     If false, the service can be invoked only from another Restate service."
 };
+pub(super) const ALLOWED_CALLERS: &str = indoc! {
+    "The Restate services which can invoke this service.
+    Invocations through the ingress are governed by the public flag instead.
+    Set it to an empty list to allow any service to invoke this service."
+};
 pub(super) const IDEMPOTENCY_RETENTION: &str = indoc! {
     "The retention duration of idempotent requests for this service.
     The retention period starts once the invocation completes (with either success or failure).
@@ -34,6 +40,12 @@ pub(super) const WORKFLOW_RETENTION: &str = indoc! {
     The retention period starts once the invocation completes (with either success or failure).
     After the retention period, the invocation response together with the workflow state and promises will be forgotten."
 };
+pub(super) const COMPLETION_RETENTION: &str = indoc! {
+    "The retention duration of completed invocations of this service, regardless of whether an
+    idempotency key was used. Workflow handlers use the workflow retention instead.
+    The retention period starts once the invocation completes (with either success or failure).
+    After the retention period, the invocation response will be forgotten."
+};
 pub(super) const INACTIVITY_TIMEOUT: &str = indoc! {
     "This timer guards against stalled service/handler invocations. Once it expires,
     Restate triggers a graceful termination by asking the service invocation to
@@ -55,6 +67,43 @@ pub(super) const ABORT_TIMEOUT: &str = indoc! {
 
     This overrides the default abort timeout set in invoker options."
 };
+pub(super) const MIRRORING: &str = indoc! {
+    "Duplicates a fraction of the ingress requests to a shadow deployment, to validate a new
+    service version under real traffic. Responses of mirrored requests are discarded.
+    The fraction must be between 0 and 1, set it to 0 to disable mirroring."
+};
+pub(super) const ROUTING: &str = indoc! {
+    "Routes a percentage of the new invocations to a canary deployment, to roll out a new
+    service version gradually. Ingress requests carrying the configured header are always routed
+    to the canary deployment. Running invocations keep running on the deployment they started on.
+    The percentage must be between 0 and 100, set it to 0 without a header to disable routing."
+};
+pub(super) const SHARED_HANDLER_CONCURRENCY: &str = indoc! {
+    "Maximum number of concurrent executions of the shared handlers of this virtual object, per key.
+    Invocations exceeding the limit wait until an execution of a shared handler of the same key completes.
+    Set it to 0 to remove the limit."
+};
+pub(super) const CONCURRENCY_LIMIT: &str = indoc! {
+    "Maximum number of invocations of this service each node executes concurrently.
+    Invocations exceeding the limit are queued until a running invocation of the service completes or suspends.
+    Set it to 0 to remove the limit."
+};
+pub(super) const RETRY_POLICY: &str = indoc! {
+    "Retry policies of the invocations of this service, overriding the retry policy of the invoker.
+    Transport errors, retryable errors and terminal errors (journal mismatches and protocol violations) can use different policies.
+    The max duration bounds the time an invocation is retried for since its first failure.
+    Set it to an empty table to remove the overrides."
+};
+pub(super) const EXECUTION_TIMEOUT: &str = indoc! {
+    "Maximum wall-clock time an invocation of this service can execute for, from the moment it starts running
+    until it completes, including the time spent waiting for retries or while suspended.
+    Once it expires, the invocation is aborted and fails with a timeout error, even if it keeps making progress.
+    Handlers can override it. Set it to 0 to remove the timeout."
+};
+
+pub(super) fn format_retry_policy(retry_policy: &RetryPolicyOverrides) -> String {
+    serde_json::to_string(retry_policy).unwrap_or_else(|err| err.to_string())
+}
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_view")]
@@ -83,6 +132,18 @@ async fn view(env: &CliEnv, opts: &View) -> Result<()> {
     c_tip!("{}", PUBLIC_DESCRIPTION);
     c_println!();
 
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Allowed callers:",
+        service
+            .allowed_callers
+            .map(|callers| callers.join(", "))
+            .unwrap_or("<ANY>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", ALLOWED_CALLERS);
+    c_println!();
+
     let mut table = Table::new_styled();
     table.add_kv_row(
         "Idempotent requests retention:",
@@ -105,6 +166,18 @@ async fn view(env: &CliEnv, opts: &View) -> Result<()> {
         c_println!();
     }
 
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Completion retention:",
+        service
+            .completion_retention
+            .map(|d| d.to_string())
+            .unwrap_or("<NONE>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", COMPLETION_RETENTION);
+    c_println!();
+
     let mut table = Table::new_styled();
     table.add_kv_row(
         "Inactivity timeout:",
@@ -129,5 +202,96 @@ async fn view(env: &CliEnv, opts: &View) -> Result<()> {
     c_tip!("{}", ABORT_TIMEOUT);
     c_println!();
 
+    if service.ty == ServiceType::Service {
+        let mut table = Table::new_styled();
+        table.add_kv_row(
+            "Mirroring:",
+            service
+                .mirroring
+                .map(|m| format!("{} of the requests to {}", m.fraction, m.deployment_id))
+                .unwrap_or("<DISABLED>".to_string()),
+        );
+        c_println!("{table}");
+        c_tip!("{}", MIRRORING);
+        c_println!();
+    }
+
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Routing:",
+        service
+            .routing
+            .map(|r| format_routing(&r))
+            .unwrap_or("<DISABLED>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", ROUTING);
+    c_println!();
+
+    if service.ty == ServiceType::VirtualObject {
+        let mut table = Table::new_styled();
+        table.add_kv_row(
+            "Shared handler concurrency:",
+            service
+                .shared_handler_concurrency
+                .map(|n| n.to_string())
+                .unwrap_or("<UNLIMITED>".to_string()),
+        );
+        c_println!("{table}");
+        c_tip!("{}", SHARED_HANDLER_CONCURRENCY);
+        c_println!();
+    }
+
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Concurrency limit:",
+        service
+            .concurrency_limit
+            .map(|n| n.to_string())
+            .unwrap_or("<UNLIMITED>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", CONCURRENCY_LIMIT);
+    c_println!();
+
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Retry policy:",
+        service
+            .retry_policy
+            .as_ref()
+            .map(format_retry_policy)
+            .unwrap_or("<DEFAULT>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", RETRY_POLICY);
+    c_println!();
+
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Execution timeout:",
+        service
+            .execution_timeout
+            .map(|d| d.to_string())
+            .unwrap_or("<NONE>".to_string()),
+    );
+    c_println!("{table}");
+    c_tip!("{}", EXECUTION_TIMEOUT);
+    c_println!();
+
     Ok(())
 }
+
+pub(super) fn format_routing(routing: &ServiceRouting) -> String {
+    let mut formatted = format!(
+        "{}% of the invocations to {}",
+        routing.percentage, routing.deployment_id
+    );
+    if let Some(header) = &routing.header {
+        formatted.push_str(&format!(
+            ", requests with header '{}: {}'",
+            header.name, header.value
+        ));
+    }
+    formatted
+}
@@ -93,61 +93,87 @@ pub fn render_active_invocations(active_inv: i64) -> Cell {
 
 pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
     table.add_kv_row("Deployment Type:", render_deployment_type(deployment));
-    let (additional_headers, created_at, min_protocol_version, max_protocol_version) =
-        match &deployment {
-            Deployment::Http {
-                uri,
-                protocol_type,
-                http_version: _,
-                additional_headers,
+    let (
+        additional_headers,
+        concurrency_limit,
+        created_at,
+        min_protocol_version,
+        max_protocol_version,
+        draining,
+    ) = match &deployment {
+        Deployment::Http {
+            uri,
+            protocol_type,
+            http_version: _,
+            additional_headers,
+            concurrency_limit,
+            created_at,
+            min_protocol_version,
+            max_protocol_version,
+            draining,
+        } => {
+            let protocol_type = match protocol_type {
+                ProtocolType::RequestResponse => "Request/Response",
+                ProtocolType::BidiStream => "Streaming",
+            }
+            .to_string();
+            table.add_kv_row("Protocol Style:", protocol_type);
+
+            table.add_kv_row("Endpoint:", uri);
+            (
+                additional_headers.clone(),
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
-            } => {
-                let protocol_type = match protocol_type {
-                    ProtocolType::RequestResponse => "Request/Response",
-                    ProtocolType::BidiStream => "Streaming",
-                }
-                .to_string();
-                table.add_kv_row("Protocol Style:", protocol_type);
-
-                table.add_kv_row("Endpoint:", uri);
-                (
-                    additional_headers.clone(),
-                    created_at,
-                    min_protocol_version,
-                    max_protocol_version,
-                )
-            }
-            Deployment::Lambda {
-                arn,
-                assume_role_arn,
-                additional_headers,
+                draining,
+            )
+        }
+        Deployment::Lambda {
+            arn,
+            assume_role_arn,
+            region,
+            additional_headers,
+            concurrency_limit,
+            created_at,
+            min_protocol_version,
+            max_protocol_version,
+            draining,
+        } => {
+            table.add_kv_row("Protocol Style:", "Request/Response");
+            table.add_kv_row_if(
+                || assume_role_arn.is_some(),
+                "Deployment Assume Role ARN:",
+                || assume_role_arn.as_ref().unwrap(),
+            );
+            table.add_kv_row_if(
+                || region.is_some(),
+                "Deployment Region:",
+                || region.as_ref().unwrap(),
+            );
+
+            table.add_kv_row("Endpoint:", arn);
+            (
+                additional_headers.clone(),
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
-            } => {
-                table.add_kv_row("Protocol Style:", "Request/Response");
-                table.add_kv_row_if(
-                    || assume_role_arn.is_some(),
-                    "Deployment Assume Role ARN:",
-                    || assume_role_arn.as_ref().unwrap(),
-                );
-
-                table.add_kv_row("Endpoint:", arn);
-                (
-                    additional_headers.clone(),
-                    created_at,
-                    min_protocol_version,
-                    max_protocol_version,
-                )
-            }
-        };
+                draining,
+            )
+        }
+    };
 
     let additional_headers: HashMap<http::HeaderName, http::HeaderValue> =
         additional_headers.into();
 
     table.add_kv_row("Created at:", created_at);
+    table.add_kv_row_if(|| *draining, "Draining:", || "true");
+    table.add_kv_row_if(
+        || concurrency_limit.is_some(),
+        "Concurrency limit:",
+        || concurrency_limit.unwrap(),
+    );
     for (header, value) in additional_headers.iter() {
         table.add_kv_row(
             "Deployment Additional Header:",
@@ -18,6 +18,7 @@ use restate_types::schema::deployment::{DeploymentMetadata, ProtocolType};
 use restate_types::schema::service::ServiceMetadata;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::num::NonZeroU32;
 use std::time::SystemTime;
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -36,25 +37,42 @@ pub enum Deployment {
         #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
         #[serde(default)]
         additional_headers: SerdeableHeaderHashMap,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        #[cfg_attr(feature = "schema", schemars(with = "Option<u32>"))]
+        concurrency_limit: Option<NonZeroU32>,
         #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
         #[cfg_attr(feature = "schema", schemars(with = "String"))]
         created_at: humantime::Timestamp,
         min_protocol_version: i32,
         max_protocol_version: i32,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        #[serde(default)]
+        draining: bool,
     },
     Lambda {
         arn: LambdaARN,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(default)]
         assume_role_arn: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        region: Option<String>,
         #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
         #[serde(default)]
         additional_headers: SerdeableHeaderHashMap,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        #[cfg_attr(feature = "schema", schemars(with = "Option<u32>"))]
+        concurrency_limit: Option<NonZeroU32>,
         #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
         #[cfg_attr(feature = "schema", schemars(with = "String"))]
         created_at: humantime::Timestamp,
         min_protocol_version: i32,
         max_protocol_version: i32,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        #[serde(default)]
+        draining: bool,
     },
 }
 
@@ -72,23 +90,34 @@ enum DeploymentShadow {
         #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
         #[serde(default)]
         additional_headers: SerdeableHeaderHashMap,
+        #[serde(default)]
+        concurrency_limit: Option<NonZeroU32>,
         #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
         created_at: humantime::Timestamp,
         min_protocol_version: i32,
         max_protocol_version: i32,
+        #[serde(default)]
+        draining: bool,
     },
     Lambda {
         arn: LambdaARN,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(default)]
         assume_role_arn: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        region: Option<String>,
         #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
         #[serde(default)]
         additional_headers: SerdeableHeaderHashMap,
+        #[serde(default)]
+        concurrency_limit: Option<NonZeroU32>,
         #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
         created_at: humantime::Timestamp,
         min_protocol_version: i32,
         max_protocol_version: i32,
+        #[serde(default)]
+        draining: bool,
     },
 }
 
@@ -100,33 +129,43 @@ impl From<DeploymentShadow> for Deployment {
                 protocol_type,
                 http_version,
                 additional_headers,
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
+                draining,
             } => Self::Http {
                 uri,
                 protocol_type,
                 http_version: http_version
                     .unwrap_or_else(|| DeploymentType::backfill_http_version(protocol_type)),
                 additional_headers,
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
+                draining,
             },
             DeploymentShadow::Lambda {
                 arn,
                 assume_role_arn,
+                region,
                 additional_headers,
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
+                draining,
             } => Self::Lambda {
                 arn,
                 assume_role_arn,
+                region,
                 additional_headers,
+                concurrency_limit,
                 created_at,
                 min_protocol_version,
                 max_protocol_version,
+                draining,
             },
         }
     }
@@ -170,20 +209,26 @@ impl From<DeploymentMetadata> for Deployment {
                 protocol_type,
                 http_version,
                 additional_headers: value.delivery_options.additional_headers.into(),
+                concurrency_limit: value.delivery_options.concurrency_limit,
                 created_at: SystemTime::from(value.created_at).into(),
                 min_protocol_version: *value.supported_protocol_versions.start(),
                 max_protocol_version: *value.supported_protocol_versions.end(),
+                draining: value.draining,
             },
             DeploymentType::Lambda {
                 arn,
                 assume_role_arn,
+                region,
             } => Self::Lambda {
                 arn,
                 assume_role_arn: assume_role_arn.map(Into::into),
+                region: region.map(Into::into),
                 additional_headers: value.delivery_options.additional_headers.into(),
+                concurrency_limit: value.delivery_options.concurrency_limit,
                 created_at: SystemTime::from(value.created_at).into(),
                 min_protocol_version: *value.supported_protocol_versions.start(),
                 max_protocol_version: *value.supported_protocol_versions.end(),
+                draining: value.draining,
             },
         }
     }
@@ -219,6 +264,15 @@ pub enum RegisterDeploymentRequest {
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         use_http_11: bool,
 
+        /// # Concurrency limit
+        ///
+        /// Maximum number of invocations the invoker of each node executes concurrently on this
+        /// deployment. Invocations exceeding the limit are queued until a running invocation of
+        /// the deployment completes or suspends. If unset, the deployment is not limited.
+        #[serde(default)]
+        #[cfg_attr(feature = "schema", schemars(with = "Option<u32>"))]
+        concurrency_limit: Option<NonZeroU32>,
+
         /// # Force
         ///
         /// If `true`, it will override, if existing, any deployment using the same `uri`.
@@ -248,11 +302,28 @@ pub enum RegisterDeploymentRequest {
         /// Optional ARN of a role to assume when invoking the addressed Lambda, to support role chaining
         assume_role_arn: Option<String>,
 
+        /// # Region
+        ///
+        /// Optional AWS region of the endpoints used to assume the role and invoke the addressed
+        /// Lambda, overriding the region of the ARN.
+        #[serde(default)]
+        region: Option<String>,
+
         /// # Additional headers
         ///
         /// Additional headers added to the discover/invoke requests to the deployment.
         ///
         additional_headers: Option<SerdeableHeaderHashMap>,
+
+        /// # Concurrency limit
+        ///
+        /// Maximum number of invocations the invoker of each node executes concurrently on this
+        /// deployment. Invocations exceeding the limit are queued until a running invocation of
+        /// the deployment completes or suspends. If unset, the deployment is not limited.
+        #[serde(default)]
+        #[cfg_attr(feature = "schema", schemars(with = "Option<u32>"))]
+        concurrency_limit: Option<NonZeroU32>,
+
         /// # Force
         ///
         /// If `true`, it will override, if existing, any deployment using the same `uri`.
@@ -320,3 +391,15 @@ pub struct DetailedDeploymentResponse {
     /// List of services exposed by this deployment.
     pub services: Vec<ServiceMetadata>,
 }
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifyDeploymentRequest {
+    /// # Draining
+    ///
+    /// If `true`, no new invocations are started on this deployment, while the invocations
+    /// already running on it complete. New invocations of its services are started on the most
+    /// recent deployment of the service which is not draining.
+    #[serde(default)]
+    pub draining: Option<bool>,
+}
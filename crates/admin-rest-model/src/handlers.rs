@@ -8,12 +8,90 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
-use restate_types::schema::service::HandlerMetadata;
+use restate_types::identifiers::InvocationId;
+use restate_types::schema::service::{HandlerMetadata, RetryPolicyOverrides};
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListServiceHandlersResponse {
     pub handlers: Vec<HandlerMetadata>,
 }
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModifyServiceHandlerRequest {
+    /// # Idempotency retention
+    ///
+    /// Modify the retention of idempotent requests for this handler, overriding the one of the service.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub idempotency_retention: Option<Duration>,
+
+    /// # Completion retention
+    ///
+    /// Modify the retention of completed invocations of this handler, overriding the completion
+    /// retention of the service, or the workflow completion retention for workflow handlers.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub completion_retention: Option<Duration>,
+
+    /// # Retry policy
+    ///
+    /// Modify the retry policies of the invocations of this handler, overriding the ones of the
+    /// service. Unset policies fall back to the ones of the service.
+    ///
+    /// Set it to an empty object to remove the overrides of the handler.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+
+    /// # Execution timeout
+    ///
+    /// Modify the execution timeout of this handler, overriding the one of the service.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    ///
+    /// Set it to 0 to remove the override of the handler.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub execution_timeout: Option<Duration>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimulateInvocationRequest {
+    /// # Argument
+    ///
+    /// JSON value passed as input to the handler. If not provided, the handler is invoked with an empty input.
+    #[serde(default)]
+    pub argument: Option<serde_json::Value>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimulateInvocationResponse {
+    /// # Invocation id
+    ///
+    /// Id of the dry-run invocation, which can be used to inspect its progress.
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub invocation_id: InvocationId,
+}
@@ -10,6 +10,9 @@
 
 pub mod deployments;
 pub mod handlers;
+pub mod logs;
+pub mod schedules;
+pub mod schema_versions;
 pub mod services;
 pub mod subscriptions;
 pub mod version;
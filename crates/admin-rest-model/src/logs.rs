@@ -0,0 +1,68 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use restate_types::logs::metadata::ProviderKind;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SealAndExtendLogRequest {
+    /// # Segment index
+    ///
+    /// Index of the segment to seal. The operation fails if this is not the current tail segment
+    /// of the log. If not provided, the current tail segment is sealed.
+    #[serde(default)]
+    pub segment_index: Option<u32>,
+    /// # Minimum logs metadata version
+    ///
+    /// The operation waits until the node has observed at least this version of the logs metadata.
+    #[serde(default)]
+    pub min_version: Option<u32>,
+    /// # Provider
+    ///
+    /// Loglet provider of the new segment which receives all subsequent writes.
+    pub provider: ProviderKind,
+    /// # Params
+    ///
+    /// Provider specific parameters of the new segment's loglet.
+    #[serde(default)]
+    pub params: String,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SealAndExtendLogResponse {
+    /// # Sealed segment index
+    pub sealed_segment_index: u32,
+    /// # Sealed segment provider
+    pub sealed_provider: ProviderKind,
+    /// # Sealed segment params
+    pub sealed_params: String,
+    /// # Tail LSN
+    ///
+    /// Tail of the sealed segment. This is the base LSN of the new segment.
+    pub tail_lsn: u64,
+    /// # New segment index
+    pub new_segment_index: u32,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetLogProviderRequest {
+    /// # Provider
+    ///
+    /// Loglet provider of the log. If the current segment of the log uses a different provider,
+    /// the cluster controller seals it and continues the log with a new segment of this provider.
+    /// If not provided, the provider of the log is removed and the log stays on the provider of
+    /// its current segment.
+    #[serde(default)]
+    pub provider: Option<ProviderKind>,
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use restate_types::identifiers::ScheduleId;
+use restate_types::schedule::MisfirePolicy;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateScheduleRequest {
+    /// # Service
+    ///
+    /// Fully qualified name of the service to invoke.
+    pub service: String,
+
+    /// # Handler
+    ///
+    /// Name of the handler to invoke.
+    pub handler: String,
+
+    /// # Key
+    ///
+    /// Key of the virtual object or workflow to invoke. Must be unset for services.
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// # Cron
+    ///
+    /// Cron expression with the fields `<minute> <hour> <day of month> <month> <day of week>`,
+    /// optionally followed by an IANA timezone in brackets, e.g. `0 9 * * MON-FRI[Europe/Berlin]`.
+    /// The handler is invoked at every occurrence of the expression.
+    pub cron: String,
+
+    /// # Misfire policy
+    ///
+    /// What to do with the occurrences missed e.g. while the partition was unavailable: `skip`
+    /// them, `fire-once` for all of them, or `fire-all` of them one after the other. Defaults to
+    /// `fire-once`.
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+
+    /// # Argument
+    ///
+    /// JSON value passed as input to the handler. If not provided, the handler is invoked with an empty input.
+    #[serde(default)]
+    pub argument: Option<serde_json::Value>,
+
+    /// # Headers
+    ///
+    /// Headers passed to the handler on every invocation.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateScheduleResponse {
+    /// # Schedule id
+    ///
+    /// Id of the schedule, which can be used to delete it. Schedules can be inspected using the sys_schedule table.
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub id: ScheduleId,
+
+    /// # Next fire time
+    ///
+    /// Time of the first invocation of the schedule.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub next_fire_time: humantime::Timestamp,
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use restate_types::identifiers::{DeploymentId, ServiceRevision, SubscriptionId};
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSchemaVersionsResponse {
+    /// # Current version
+    ///
+    /// Version of the schema currently in use.
+    pub current_version: u32,
+
+    /// # Versions
+    ///
+    /// The current version of the schema, followed by the versions which can be rolled back to,
+    /// from the newest to the oldest.
+    pub versions: Vec<SchemaVersionResponse>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaVersionResponse {
+    pub version: u32,
+
+    /// # Superseded at
+    ///
+    /// Time at which this version of the schema was replaced by a newer one. Unset for the
+    /// current version.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub superseded_at: Option<humantime::Timestamp>,
+
+    /// # Rolled back to
+    ///
+    /// Set in the response of a rollback, to the version the schema was rolled back to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolled_back_to: Option<u32>,
+
+    /// # Services
+    ///
+    /// Number of registered services.
+    pub services: usize,
+
+    /// # Deployments
+    ///
+    /// Number of registered deployments.
+    pub deployments: usize,
+
+    /// # Subscriptions
+    ///
+    /// Number of registered subscriptions.
+    pub subscriptions: usize,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SchemaDiffResponse {
+    pub from_version: u32,
+    pub to_version: u32,
+
+    /// # Added services
+    ///
+    /// Services registered in the target version, but not in the source version.
+    pub added_services: Vec<String>,
+
+    /// # Removed services
+    ///
+    /// Services registered in the source version, but not in the target version.
+    pub removed_services: Vec<String>,
+
+    /// # Modified services
+    ///
+    /// Services whose revision, deployment or configuration differs between the two versions.
+    pub modified_services: Vec<ModifiedServiceResponse>,
+
+    pub added_deployments: Vec<DeploymentId>,
+    pub removed_deployments: Vec<DeploymentId>,
+    pub added_subscriptions: Vec<SubscriptionId>,
+    pub removed_subscriptions: Vec<SubscriptionId>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifiedServiceResponse {
+    pub name: String,
+    pub from_revision: ServiceRevision,
+    pub to_revision: ServiceRevision,
+    pub from_deployment_id: DeploymentId,
+    pub to_deployment_id: DeploymentId,
+}
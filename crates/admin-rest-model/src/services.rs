@@ -10,10 +10,14 @@
 
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
-use restate_types::schema::service::ServiceMetadata;
+use restate_types::schema::service::{
+    RetryPolicyOverrides, ServiceMetadata, ServiceMirroring, ServiceRouting,
+};
+
+use crate::handlers::ModifyServiceHandlerRequest;
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,15 +26,24 @@ pub struct ListServicesResponse {
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModifyServiceRequest {
     /// # Public
     ///
     /// If true, the service can be invoked through the ingress.
     /// If false, the service can be invoked only from another Restate service.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub public: Option<bool>,
 
+    /// # Allowed callers
+    ///
+    /// Restrict the Restate services which can invoke this service. Invocations through the
+    /// ingress are governed by `public` instead.
+    ///
+    /// Set an empty list to allow any service to invoke this service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_callers: Option<Vec<String>>,
+
     /// # Idempotency retention
     ///
     /// Modify the retention of idempotent requests for this service.
@@ -38,7 +51,8 @@ pub struct ModifyServiceRequest {
     /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
     #[serde(
         default,
-        with = "serde_with::As::<Option<restate_serde_util::DurationString>>"
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub idempotency_retention: Option<Duration>,
@@ -50,11 +64,33 @@ pub struct ModifyServiceRequest {
     /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
     #[serde(
         default,
-        with = "serde_with::As::<Option<restate_serde_util::DurationString>>"
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub workflow_completion_retention: Option<Duration>,
 
+    /// # Completion retention
+    ///
+    /// Modify the retention of completed invocations of this service, regardless of whether they
+    /// were submitted with an idempotency key. Workflow handlers use the workflow completion
+    /// retention instead. Set it to 0 to retain only idempotent requests and workflows.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub completion_retention: Option<Duration>,
+
+    /// # Handlers
+    ///
+    /// Modify the retention of single handlers, overriding the retention of the service.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub handlers: BTreeMap<String, ModifyServiceHandlerRequest>,
+
     /// # Inactivity timeout
     ///
     /// This timer guards against stalled service/handler invocations. Once it expires,
@@ -69,7 +105,8 @@ pub struct ModifyServiceRequest {
     /// This overrides the default inactivity timeout set in invoker options.
     #[serde(
         default,
-        with = "serde_with::As::<Option<restate_serde_util::DurationString>>"
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub inactivity_timeout: Option<Duration>,
@@ -89,10 +126,80 @@ pub struct ModifyServiceRequest {
     /// This overrides the default abort timeout set in invoker options.
     #[serde(
         default,
-        with = "serde_with::As::<Option<restate_serde_util::DurationString>>"
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
     )]
     #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<Duration>,
+
+    /// # Mirroring
+    ///
+    /// Duplicate a fraction of the ingress requests to a shadow deployment, to validate a new
+    /// service version under real traffic. Responses of mirrored requests are discarded.
+    /// This can be set only for services, and the deployment must expose the service.
+    ///
+    /// Set the fraction to 0 to disable mirroring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirroring: Option<ServiceMirroring>,
+
+    /// # Routing
+    ///
+    /// Route part of the new invocations of this service to a canary deployment, to roll out a
+    /// new service version gradually. Invocations are routed either by percentage, or because
+    /// the ingress request carries the configured header. Invocations already running keep
+    /// running on the deployment they started on. The deployment must expose the service.
+    ///
+    /// Set the percentage to 0 without a header to disable routing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<ServiceRouting>,
+
+    /// # Shared handler concurrency
+    ///
+    /// Limit the number of concurrent executions of the shared handlers of this virtual object,
+    /// per key. Invocations exceeding the limit wait until an execution of a shared handler of
+    /// the same key completes. This can be set only for virtual objects.
+    ///
+    /// Set it to 0 to remove the limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_handler_concurrency: Option<u32>,
+
+    /// # Concurrency limit
+    ///
+    /// Limit the number of invocations of this service the invoker of each node executes
+    /// concurrently. Invocations exceeding the limit are queued until a running invocation of the
+    /// service completes or suspends.
+    ///
+    /// Set it to 0 to remove the limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<u32>,
+
+    /// # Retry policy
+    ///
+    /// Modify the retry policies of the invocations of this service, overriding the retry policy
+    /// of the invoker. Transport errors, retryable errors and terminal errors reported by the SDK
+    /// can be retried with different policies. Unset policies fall back to the invoker retry policy.
+    ///
+    /// Set it to an empty object to remove the overrides of the service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+
+    /// # Execution timeout
+    ///
+    /// Modify the maximum wall-clock time an invocation of this service can execute for, from the
+    /// moment it starts running until it completes, including the time spent waiting for retries
+    /// or while suspended. Once it expires, the invocation is aborted and fails with a timeout error,
+    /// even if it keeps making progress. Handlers can override it.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    ///
+    /// Set it to 0 to remove the timeout.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub execution_timeout: Option<Duration>,
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
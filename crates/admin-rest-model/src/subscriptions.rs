@@ -14,8 +14,9 @@ use http::Uri;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use restate_serde_util::REDACTED;
 use restate_types::identifiers::SubscriptionId;
-use restate_types::schema::subscriptions::Subscription;
+use restate_types::schema::subscriptions::{is_secret_option, Subscription};
 
 #[serde_as]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -26,6 +27,12 @@ pub struct CreateSubscriptionRequest {
     /// Source uri. Accepted forms:
     ///
     /// * `kafka://<cluster_name>/<topic_name>`, e.g. `kafka://my-cluster/my-topic`
+    /// * `sqs://<region>/<account_id>/<queue_name>`, e.g. `sqs://eu-central-1/123456789012/my-queue`.
+    ///   SNS topics can be ingested by subscribing an SQS queue to them.
+    /// * `nats://<server>/<stream_name>`, e.g. `nats://localhost:4222/orders`, to ingest the
+    ///   messages of a NATS JetStream stream.
+    /// * `webhook://<provider>`, with provider one of `stripe`, `github` or `hmac`, to ingest the
+    ///   events posted to the ingress at `/restate/webhooks/<subscription_id>`.
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schema", schemars(with = "String"))]
     pub source: Uri,
@@ -39,7 +46,61 @@ pub struct CreateSubscriptionRequest {
     pub sink: Uri,
     /// # Options
     ///
-    /// Additional options to apply to the subscription.
+    /// Additional options to apply to the subscription. Options are passed to the Kafka client,
+    /// except the ones interpreted by Restate:
+    ///
+    /// * `restate.dead-letter-topic`: topic of the same cluster where the events which cannot be
+    ///   converted to an invocation are published to, with the error in the `restate.error`
+    ///   header. Failures to dispatch the events are retried indefinitely instead.
+    ///
+    /// SQS sources use the AWS credentials of the Restate process, and accept only the following
+    /// options:
+    ///
+    /// * `restate.key-attribute`: message attribute containing the key of the event, used to
+    ///   target virtual objects. Defaults to the message group id of FIFO queues.
+    /// * `restate.sns-envelope`: if `true`, the messages are SNS notifications whose `Message`
+    ///   is ingested as payload of the event.
+    /// * `restate.endpoint-url`: endpoint to use instead of the AWS SQS endpoint of the region.
+    ///
+    /// Retries and dead lettering of SQS messages are configured through the redrive policy
+    /// of the queue.
+    ///
+    /// NATS sources are consumed through a durable consumer named after the subscription id,
+    /// and accept only the following options:
+    ///
+    /// * `restate.filter-subject`: subject filter of the consumer.
+    /// * `restate.key-header`: message header containing the key of the event, used to target
+    ///   virtual objects. Defaults to the subject of the message.
+    /// * `restate.max-ack-pending`: max number of messages delivered and not acknowledged yet.
+    ///   Defaults to 1000. Set it to 1 to ingest the messages in the order of the stream also
+    ///   across redeliveries.
+    /// * `restate.user` and `restate.password`, `restate.token` or `restate.credentials-file`:
+    ///   credentials to authenticate to the server, at most one of them.
+    /// * `restate.tls`: if `true`, the connection to the server must use TLS.
+    /// * `restate.tls-ca-file`: PEM file with additional root certificates to verify the server.
+    ///
+    /// Failed dispatches of NATS messages are retried indefinitely, while the messages which
+    /// cannot be converted to an invocation are terminated. Redeliveries are discarded through
+    /// the idempotency key of the invocation.
+    ///
+    /// Webhook sources verify the signature of the requests, and discard the replayed events
+    /// through the idempotency key of the invocation. They accept only the following options:
+    ///
+    /// * `restate.secret`: signing secret of the webhook. Required.
+    /// * `restate.key-header`: request header containing the key of the event, used to target
+    ///   virtual objects.
+    /// * `restate.signature-header`: for `hmac` webhooks, request header containing the hex
+    ///   encoded HMAC-SHA256 of `<timestamp>.<body>`. Defaults to `x-signature`.
+    /// * `restate.id-header`: for `hmac` webhooks, request header containing the unique id of
+    ///   the event. Defaults to the signature.
+    /// * `restate.timestamp-header`: for `hmac` webhooks, request header containing the time the
+    ///   request was signed at, in seconds since the Unix epoch. The signature covers
+    ///   `<timestamp>.<body>`. Defaults to `x-timestamp`.
+    /// * `restate.timestamp-tolerance`: for `stripe` and `hmac` webhooks, max age of the signed
+    ///   requests, older requests are rejected. Defaults to 5 minutes. GitHub doesn't sign a
+    ///   timestamp, so its replayed deliveries are only discarded through their delivery id.
+    ///
+    /// The values of the secret options, like `restate.secret`, are redacted in the responses.
     pub options: Option<HashMap<String, String>>,
 }
 
@@ -53,12 +114,23 @@ pub struct SubscriptionResponse {
 }
 
 impl From<Subscription> for SubscriptionResponse {
+    /// Describes the subscription with the values of its secret options redacted.
     fn from(value: Subscription) -> Self {
         Self {
             id: value.id(),
             source: value.source().to_string(),
             sink: value.sink().to_string(),
-            options: value.metadata().clone(),
+            options: value
+                .metadata()
+                .iter()
+                .map(|(key, option)| {
+                    if is_secret_option(key) {
+                        (key.clone(), REDACTED.to_owned())
+                    } else {
+                        (key.clone(), option.clone())
+                    }
+                })
+                .collect(),
         }
     }
 }
@@ -75,3 +147,36 @@ pub struct ListSubscriptionsParams {
 pub struct ListSubscriptionsResponse {
     pub subscriptions: Vec<SubscriptionResponse>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::schema::subscriptions::{
+        EventInvocationTargetTemplate, Sink, Source, WebhookProvider, WEBHOOK_SECRET_OPTION,
+    };
+
+    #[test]
+    fn response_redacts_secret_options() {
+        let subscription = Subscription::new(
+            SubscriptionId::new(),
+            Source::Webhook {
+                provider: WebhookProvider::Github,
+            },
+            Sink::Invocation {
+                event_invocation_target_template: EventInvocationTargetTemplate::Service {
+                    name: "Greeter".to_owned(),
+                    handler: "greet".to_owned(),
+                },
+            },
+            HashMap::from([
+                (WEBHOOK_SECRET_OPTION.to_owned(), "my-secret".to_owned()),
+                ("restate.key-header".to_owned(), "x-key".to_owned()),
+            ]),
+        );
+
+        let response = SubscriptionResponse::from(subscription);
+        assert_eq!(response.options[WEBHOOK_SECRET_OPTION], REDACTED);
+        assert_eq!(response.options["restate.key-header"], "x-key");
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Audit log of the mutations done through the Admin APIs.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use http::header::USER_AGENT;
+use http::{HeaderMap, HeaderName, Method};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use restate_serde_util::REDACTED;
+use restate_types::config::AdminOptions;
+
+const AUDIT_LOG_FILE_NAME: &str = "audit.log";
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+/// Read-only endpoint using POST to carry the query, which isn't recorded.
+const QUERY_PATH: &str = "/query";
+/// Fields of the summaries holding secrets, e.g. the additional headers of the deployments.
+const SECRET_FIELDS: &[&str] = &["additional_headers"];
+
+/// Identity of the caller of the Admin APIs, as reported by the request headers.
+#[derive(Debug, Default, Clone, Serialize)]
+struct Caller {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forwarded_for: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
+}
+
+impl Caller {
+    fn from_headers(headers: &HeaderMap, caller_header: Option<&HeaderName>) -> Self {
+        let header = |name: &HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+
+        Self {
+            identity: caller_header.and_then(header),
+            forwarded_for: header(&X_FORWARDED_FOR),
+            user_agent: header(&USER_AGENT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AuditEntry {
+    timestamp: String,
+    caller: Caller,
+    method: String,
+    path: String,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<serde_json::Value>,
+}
+
+/// Summary of the change done by a request, filled in by the handler.
+///
+/// The handlers of the mutations extract it from the request extensions, where
+/// [`record_mutations`] inserts it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditRecord(Arc<Mutex<AuditSummary>>);
+
+#[derive(Debug, Default)]
+struct AuditSummary {
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+}
+
+impl AuditRecord {
+    /// Records the state affected by the request, before applying it.
+    pub(crate) fn before(&self, before: impl Serialize) {
+        self.0.lock().before = serde_json::to_value(before).ok().map(redact);
+    }
+
+    /// Records the state affected by the request, after applying it.
+    pub(crate) fn after(&self, after: impl Serialize) {
+        self.0.lock().after = serde_json::to_value(after).ok().map(redact);
+    }
+}
+
+/// Replaces the values of the [`SECRET_FIELDS`] of the summary with [`REDACTED`].
+fn redact(mut value: serde_json::Value) -> serde_json::Value {
+    fn redact_in_place(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (name, field) in fields.iter_mut() {
+                    if SECRET_FIELDS.contains(&name.as_str()) {
+                        redact_secret(field);
+                    } else {
+                        redact_in_place(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(redact_in_place),
+            _ => {}
+        }
+    }
+
+    fn redact_secret(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Null => {}
+            serde_json::Value::Object(fields) => fields.values_mut().for_each(redact_secret),
+            _ => *value = serde_json::Value::String(REDACTED.to_owned()),
+        }
+    }
+
+    redact_in_place(&mut value);
+    value
+}
+
+/// Handle to append entries to the audit log, which are written by the [`AuditLogWriter`].
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    tx: Option<mpsc::UnboundedSender<AuditEntry>>,
+    caller_header: Option<HeaderName>,
+}
+
+impl AuditLog {
+    pub fn new(opts: &AdminOptions) -> (Self, Option<AuditLogWriter>) {
+        if !opts.audit_log_enabled {
+            return (Self::disabled(), None);
+        }
+
+        let caller_header = HeaderName::try_from(opts.audit_log_caller_header.as_str())
+            .inspect_err(|err| {
+                warn!(
+                    "Ignoring the invalid audit log caller header '{}': {err}",
+                    opts.audit_log_caller_header
+                )
+            })
+            .ok();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        (
+            Self {
+                tx: Some(tx),
+                caller_header,
+            },
+            Some(AuditLogWriter {
+                rx,
+                dir: opts.audit_log_dir(),
+                max_file_size: opts.audit_log_max_file_size.get() as u64,
+                max_files: opts.audit_log_max_files,
+                file: None,
+            }),
+        )
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            tx: None,
+            caller_header: None,
+        }
+    }
+}
+
+/// Middleware recording the requests of the Admin APIs mutating the cluster in the audit log.
+/// It wraps all the routers of the Admin APIs, so that every mutation is recorded even if its
+/// handler doesn't summarize the change.
+pub(crate) async fn record_mutations(
+    State(audit_log): State<AuditLog>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) || request.uri().path() == QUERY_PATH
+    {
+        return next.run(request).await;
+    }
+
+    let record = AuditRecord::default();
+    request.extensions_mut().insert(record.clone());
+
+    let Some(tx) = &audit_log.tx else {
+        return next.run(request).await;
+    };
+
+    let caller = Caller::from_headers(request.headers(), audit_log.caller_header.as_ref());
+    let method = request.method().to_string();
+    let path = request.uri().path().to_owned();
+
+    let response = next.run(request).await;
+
+    let AuditSummary { before, after } = std::mem::take(&mut *record.0.lock());
+    let entry = AuditEntry {
+        timestamp: humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+        caller,
+        method,
+        path,
+        status: response.status().as_u16(),
+        before,
+        after,
+    };
+    if tx.send(entry).is_err() {
+        debug!("Audit log writer is gone, dropping the audit log entry");
+    }
+
+    response
+}
+
+/// Writes the entries of the audit log as JSON lines, rotating the file once it exceeds the
+/// max file size. The rotated files are suffixed with their generation, `.1` being the most
+/// recent one.
+pub struct AuditLogWriter {
+    rx: mpsc::UnboundedReceiver<AuditEntry>,
+    dir: PathBuf,
+    max_file_size: u64,
+    max_files: usize,
+    file: Option<(File, u64)>,
+}
+
+impl AuditLogWriter {
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        while let Some(entry) = self.rx.recv().await {
+            if let Err(err) = self.write(&entry).await {
+                warn!("Failed writing the audit log entry {entry:?}: {err}");
+                // Reopen the file on the next write
+                self.file = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        if self
+            .file
+            .as_ref()
+            .is_some_and(|(_, size)| *size > 0 && size + line.len() as u64 > self.max_file_size)
+        {
+            self.file = None;
+            rotate(&self.dir, self.max_files).await?;
+        }
+
+        let (file, size) = match &mut self.file {
+            Some(file) => file,
+            None => {
+                tokio::fs::create_dir_all(&self.dir).await?;
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.dir.join(AUDIT_LOG_FILE_NAME))
+                    .await?;
+                let size = file.metadata().await?.len();
+                self.file.insert((file, size))
+            }
+        };
+
+        file.write_all(&line).await?;
+        file.flush().await?;
+        *size += line.len() as u64;
+        Ok(())
+    }
+}
+
+async fn rotate(dir: &Path, max_files: usize) -> io::Result<()> {
+    let rotated = |generation: usize| dir.join(format!("{AUDIT_LOG_FILE_NAME}.{generation}"));
+
+    if max_files == 0 {
+        return tokio::fs::remove_file(dir.join(AUDIT_LOG_FILE_NAME)).await;
+    }
+
+    match tokio::fs::remove_file(rotated(max_files)).await {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    for generation in (1..max_files).rev() {
+        match tokio::fs::rename(rotated(generation), rotated(generation + 1)).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    tokio::fs::rename(dir.join(AUDIT_LOG_FILE_NAME), rotated(1)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+            caller: Caller::default(),
+            method: "POST".to_owned(),
+            path: path.to_owned(),
+            status: 201,
+            before: None,
+            after: Some(serde_json::json!({"id": "dp_11"})),
+        }
+    }
+
+    #[test]
+    fn redact_secrets_of_summary() {
+        let record = AuditRecord::default();
+        record.after(serde_json::json!({
+            "id": "dp_11",
+            "deployment": {
+                "uri": "http://greeter:9080/",
+                "additional_headers": {"authorization": "Bearer secret"},
+            },
+            "services": [{"name": "Greeter", "additional_headers": null}],
+        }));
+
+        assert_eq!(
+            record.0.lock().after,
+            Some(serde_json::json!({
+                "id": "dp_11",
+                "deployment": {
+                    "uri": "http://greeter:9080/",
+                    "additional_headers": {"authorization": REDACTED},
+                },
+                "services": [{"name": "Greeter", "additional_headers": null}],
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn rotate_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let line_len = serde_json::to_vec(&entry("/deployments")).unwrap().len() as u64 + 1;
+        let mut writer = AuditLogWriter {
+            rx,
+            dir: dir.path().to_owned(),
+            max_file_size: 2 * line_len,
+            max_files: 2,
+            file: None,
+        };
+
+        for _ in 0..7 {
+            writer.write(&entry("/deployments")).await.unwrap();
+        }
+
+        let read_lines = |name: &str| {
+            std::fs::read_to_string(dir.path().join(name))
+                .unwrap()
+                .lines()
+                .count()
+        };
+        assert_eq!(read_lines("audit.log"), 1);
+        assert_eq!(read_lines("audit.log.1"), 2);
+        assert_eq!(read_lines("audit.log.2"), 2);
+        assert!(!dir.path().join("audit.log.3").exists());
+    }
+}
@@ -8,13 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 use std::time::Instant;
 
 use tokio::sync::watch;
 use tracing::{debug, trace};
 
+use restate_core::network::failure_detector::{ClusterLiveness, NodeLiveness};
 use restate_core::network::rpc_router::RpcRouter;
 use restate_core::network::{
     MessageRouterBuilder, NetworkError, Networking, Outgoing, TransportConnect,
@@ -27,18 +28,23 @@ use restate_types::cluster::cluster_state::{
 };
 use restate_types::net::node::GetNodeState;
 use restate_types::time::MillisSinceEpoch;
-use restate_types::Version;
+use restate_types::{PlainNodeId, Version};
 
 pub struct ClusterStateRefresher<T> {
     network_sender: Networking<T>,
     get_state_router: RpcRouter<GetNodeState>,
+    cluster_liveness: ClusterLiveness,
     in_flight_refresh: Option<TaskHandle<anyhow::Result<()>>>,
     cluster_state_update_rx: watch::Receiver<Arc<ClusterState>>,
     cluster_state_update_tx: Arc<watch::Sender<Arc<ClusterState>>>,
 }
 
 impl<T: TransportConnect> ClusterStateRefresher<T> {
-    pub fn new(network_sender: Networking<T>, router_builder: &mut MessageRouterBuilder) -> Self {
+    pub fn new(
+        network_sender: Networking<T>,
+        cluster_liveness: ClusterLiveness,
+        router_builder: &mut MessageRouterBuilder,
+    ) -> Self {
         let get_state_router = RpcRouter::new(router_builder);
 
         let initial_state = ClusterState {
@@ -54,6 +60,7 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
         Self {
             network_sender,
             get_state_router,
+            cluster_liveness,
             in_flight_refresh: None,
             cluster_state_update_rx,
             cluster_state_update_tx: Arc::new(cluster_state_update_tx),
@@ -70,6 +77,11 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
         }
     }
 
+    /// Watches the nodes detected alive by the failure detector.
+    pub fn alive_nodes_watcher(&self) -> watch::Receiver<BTreeSet<PlainNodeId>> {
+        self.cluster_liveness.alive_nodes_watcher()
+    }
+
     pub async fn next_cluster_state_update(&mut self) -> Arc<ClusterState> {
         self.cluster_state_update_rx
             .changed()
@@ -92,6 +104,7 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
         self.in_flight_refresh = Self::start_refresh_task(
             self.get_state_router.clone(),
             self.network_sender.clone(),
+            self.cluster_liveness.clone(),
             Arc::clone(&self.cluster_state_update_tx),
         )?;
 
@@ -101,6 +114,7 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
     fn start_refresh_task(
         get_state_router: RpcRouter<GetNodeState>,
         network_sender: Networking<T>,
+        cluster_liveness: ClusterLiveness,
         cluster_state_tx: Arc<watch::Sender<Arc<ClusterState>>>,
     ) -> Result<Option<TaskHandle<anyhow::Result<()>>>, ShutdownError> {
         let refresh = async move {
@@ -125,6 +139,20 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
             let mut join_set = tokio::task::JoinSet::new();
             for (_, node_config) in nodes_config.iter() {
                 let node_id = node_config.current_generation;
+                // Don't wait for the connection timeouts of the nodes already detected dead
+                if let NodeLiveness::Dead { last_seen_alive } =
+                    cluster_liveness.node_liveness(node_id.as_plain())
+                {
+                    trace!("Node {node_id} is marked dead by the failure detector");
+                    nodes.insert(
+                        node_id.as_plain(),
+                        NodeState::Dead(DeadNode {
+                            last_seen_alive: Some(last_seen_alive),
+                        }),
+                    );
+                    continue;
+                }
+
                 let rpc_router = get_state_router.clone();
                 let network_sender = network_sender.clone();
                 join_set
@@ -165,6 +193,7 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
                                 last_heartbeat_at: MillisSinceEpoch::now(),
                                 generational_node_id: peer,
                                 partitions: msg.partition_processor_state.unwrap_or_default(),
+                                load: msg.load,
                             }),
                         );
                     }
@@ -185,10 +214,24 @@ impl<T: TransportConnect> ClusterStateRefresher<T> {
                             }),
                         );
                     }
+                    Err(err)
+                        if matches!(
+                            cluster_liveness.node_liveness(node_id.as_plain()),
+                            NodeLiveness::Alive { .. }
+                        ) =>
+                    {
+                        // The node is still gossiping its heartbeats, the request might have
+                        // failed for a transient reason.
+                        debug!("Node {node_id} is marked as Suspect: {err}");
+                        nodes.insert(
+                            node_id.as_plain(),
+                            NodeState::Suspect(SuspectNode {
+                                generational_node_id: node_id,
+                                last_attempt: MillisSinceEpoch::now(),
+                            }),
+                        );
+                    }
                     Err(err) => {
-                        // todo: implement a more robust failure detector
-                        // This is a naive mechanism for failure detection and is just a stop-gap measure.
-                        // A single connection error or timeout will cause a node to be marked as dead.
                         trace!("Node {node_id} is marked dead: {err}");
                         let last_seen_alive = last_state.nodes.get(&node_id.as_plain()).and_then(
                             |state| match state {
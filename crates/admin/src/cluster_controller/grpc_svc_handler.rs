@@ -11,6 +11,8 @@
 use std::time::Duration;
 
 use bytes::{Bytes, BytesMut};
+use futures::stream::BoxStream;
+use futures::StreamExt;
 use tonic::{async_trait, Request, Response, Status};
 use tracing::info;
 
@@ -19,19 +21,20 @@ use restate_core::MetadataWriter;
 use restate_metadata_store::MetadataStoreClient;
 use restate_types::identifiers::PartitionId;
 use restate_types::logs::metadata::{Logs, ProviderKind, SegmentIndex};
-use restate_types::logs::{LogId, Lsn, SequenceNumber};
+use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
 use restate_types::metadata_store::keys::{BIFROST_CONFIG_KEY, NODES_CONFIG_KEY};
 use restate_types::nodes_config::NodesConfiguration;
 use restate_types::storage::{StorageCodec, StorageEncode};
 use restate_types::{Version, Versioned};
 
 use crate::cluster_controller::protobuf::cluster_ctrl_svc_server::ClusterCtrlSvc;
+use crate::cluster_controller::protobuf::tail_log_response;
 use crate::cluster_controller::protobuf::{
     ClusterStateRequest, ClusterStateResponse, CreatePartitionSnapshotRequest,
     CreatePartitionSnapshotResponse, DescribeLogRequest, DescribeLogResponse, FindTailRequest,
     FindTailResponse, ListLogsRequest, ListLogsResponse, ListNodesRequest, ListNodesResponse,
-    SealAndExtendChainRequest, SealAndExtendChainResponse, SealedSegment, TailState,
-    TrimLogRequest,
+    SealAndExtendChainRequest, SealAndExtendChainResponse, SealedSegment, SplitPartitionRequest,
+    SplitPartitionResponse, TailLogRequest, TailLogResponse, TailState, TrimLogRequest,
 };
 
 use super::ClusterControllerHandle;
@@ -206,6 +209,33 @@ impl ClusterCtrlSvc for ClusterCtrlSvcHandler {
         }
     }
 
+    /// Handles partition split requests, as sent by `restatectl partitions split`.
+    async fn split_partition(
+        &self,
+        request: Request<SplitPartitionRequest>,
+    ) -> Result<Response<SplitPartitionResponse>, Status> {
+        let request = request.into_inner();
+        let partition_id = PartitionId::from(
+            u16::try_from(request.partition_id)
+                .map_err(|id| Status::invalid_argument(format!("Invalid partition id: {id}")))?,
+        );
+
+        match self
+            .controller_handle
+            .split_partition(partition_id, request.split_key)
+            .await
+            .map_err(|_| Status::aborted("Node is shutting down"))?
+        {
+            Err(err) => {
+                info!("Failed splitting partition: {err}");
+                Err(Status::internal(err.to_string()))
+            }
+            Ok(child_partition_id) => Ok(Response::new(SplitPartitionResponse {
+                child_partition_id: u32::from(child_partition_id),
+            })),
+        }
+    }
+
     async fn seal_and_extend_chain(
         &self,
         request: Request<SealAndExtendChainRequest>,
@@ -286,6 +316,58 @@ impl ClusterCtrlSvc for ClusterCtrlSvcHandler {
 
         Ok(Response::new(response))
     }
+
+    type TailLogStream = BoxStream<'static, Result<TailLogResponse, Status>>;
+
+    async fn tail_log(
+        &self,
+        request: Request<TailLogRequest>,
+    ) -> Result<Response<Self::TailLogStream>, Status> {
+        let request = request.into_inner();
+        let log_id: LogId = request.log_id.into();
+        let from_lsn = request.from_lsn.map(Lsn::from).unwrap_or(Lsn::OLDEST);
+        let filter = if request.from_partition_key.is_some() || request.to_partition_key.is_some() {
+            KeyFilter::Within(
+                request.from_partition_key.unwrap_or(0)
+                    ..=request.to_partition_key.unwrap_or(u64::MAX),
+            )
+        } else {
+            KeyFilter::Any
+        };
+
+        let reader = self
+            .bifrost
+            .create_reader(log_id, filter, from_lsn, Lsn::MAX)
+            .map_err(|err| match err {
+                BiforstError::UnknownLogId(_) => Status::invalid_argument("Unknown log-id"),
+                err => Status::internal(err.to_string()),
+            })?;
+
+        let stream = reader.map(|entry| {
+            let entry = entry.map_err(|err| Status::internal(err.to_string()))?;
+            let lsn = entry.sequence_number();
+            let record = match entry.trim_gap_to_sequence_number() {
+                Some(trim_gap_to) => tail_log_response::Record::TrimGapToLsn(trim_gap_to.into()),
+                None => {
+                    let mut buf = BytesMut::new();
+                    entry
+                        .into_record()
+                        .expect("data record")
+                        .body()
+                        .encode(&mut buf)
+                        .map_err(|err| Status::internal(err.to_string()))?;
+                    tail_log_response::Record::Data(buf.freeze())
+                }
+            };
+
+            Ok(TailLogResponse {
+                lsn: lsn.into(),
+                record: Some(record),
+            })
+        });
+
+        Ok(Response::new(stream.boxed()))
+    }
 }
 
 fn serialize_value<T: StorageEncode>(value: T) -> Bytes {
@@ -175,9 +175,12 @@ impl LogState {
     }
 
     /// Checks whether the current segment requires reconfiguration and, therefore, needs to be
-    /// sealed. The method returns [`true`] if the state was moved to sealing.
+    /// sealed. This is also the case if the logs configuration in the metadata store overrides the
+    /// provider of the log with a different provider than the one of the current segment. The
+    /// method returns [`true`] if the state was moved to sealing.
     fn try_transition_to_sealing(
         &mut self,
+        provider_override: Option<ProviderKind>,
         nodes_config: &NodesConfiguration,
         observed_cluster_state: &ObservedClusterState,
     ) -> bool {
@@ -187,9 +190,12 @@ impl LogState {
                 configuration,
                 segment_index,
             } => {
-                if configuration
+                let current_configuration = configuration
                     .as_ref()
-                    .expect("configuration must be present")
+                    .expect("configuration must be present");
+                if provider_override.is_some_and(|provider_kind| {
+                    provider_kind != current_configuration.as_provider()
+                }) || current_configuration
                     .requires_reconfiguration(nodes_config, observed_cluster_state)
                 {
                     *self = LogState::Sealing {
@@ -221,6 +227,7 @@ impl LogState {
             } => {
                 if let Some(loglet_configuration) = try_provisioning(
                     *log_id,
+                    SegmentIndex::OLDEST,
                     *provider_kind,
                     observed_cluster_state,
                     node_set_selector_hints,
@@ -248,11 +255,17 @@ impl LogState {
         Ok(())
     }
 
-    fn try_reconfiguring<F, G>(
+    /// Creates a new segment configuration for a sealed log. If the logs configuration overrides
+    /// the provider of the log with a different provider than the one of the sealed segment, the
+    /// new segment is provisioned with the overriding provider. Otherwise, the log stays with the
+    /// provider of the sealed segment.
+    fn try_reconfiguring<F>(
         &mut self,
+        log_id: LogId,
+        provider_override: Option<ProviderKind>,
         observed_cluster_state: &ObservedClusterState,
         mut append_segment: F,
-        preferred_sequencer_hint: G,
+        node_set_selector_hints: impl NodeSetSelectorHints,
     ) -> Result<()>
     where
         F: FnMut(
@@ -260,18 +273,37 @@ impl LogState {
             ProviderKind,
             LogletParams,
         ) -> Result<SegmentIndex, logs::builder::BuilderError>,
-        G: Fn() -> Option<NodeId>,
     {
         match self {
             // We can only reconfigure if we are in state Sealed
             LogState::Sealed {
                 seal_lsn,
                 configuration,
-                ..
+                segment_index,
             } => {
-                if let Some(loglet_configuration) = configuration
-                    .try_reconfiguring(observed_cluster_state, preferred_sequencer_hint())
-                {
+                let provider_kind = provider_override.unwrap_or(configuration.as_provider());
+                let new_configuration = if configuration.as_provider() == provider_kind {
+                    configuration.try_reconfiguring(
+                        observed_cluster_state,
+                        node_set_selector_hints.preferred_sequencer(&log_id),
+                    )
+                } else {
+                    debug!(
+                        %log_id,
+                        "Switching log provider from '{}' to '{}'",
+                        configuration.as_provider(),
+                        provider_kind
+                    );
+                    try_provisioning(
+                        log_id,
+                        segment_index.next(),
+                        provider_kind,
+                        observed_cluster_state,
+                        node_set_selector_hints,
+                    )
+                };
+
+                if let Some(loglet_configuration) = new_configuration {
                     let segment_index = append_segment(
                         *seal_lsn,
                         loglet_configuration.as_provider(),
@@ -303,10 +335,11 @@ impl LogState {
     }
 }
 
-/// Try provisioning a new log for the given [`ProviderKind`] based on the observed cluster state.
-/// If this is possible, then this function returns some [`LogletConfiguration`].
+/// Try provisioning a new segment for the given [`ProviderKind`] based on the observed cluster
+/// state. If this is possible, then this function returns some [`LogletConfiguration`].
 fn try_provisioning(
     log_id: LogId,
+    segment_index: SegmentIndex,
     provider_kind: ProviderKind,
     observed_cluster_state: &ObservedClusterState,
     node_set_selector_hints: impl NodeSetSelectorHints,
@@ -323,7 +356,7 @@ fn try_provisioning(
         }
         #[cfg(feature = "replicated-loglet")]
         ProviderKind::Replicated => build_new_replicated_loglet_configuration(
-            ReplicatedLogletId::new(log_id, SegmentIndex::OLDEST),
+            ReplicatedLogletId::new(log_id, segment_index),
             &Metadata::with_current(|m| m.nodes_config_ref()),
             observed_cluster_state,
             None,
@@ -615,7 +648,7 @@ enum Event {
 /// complexity of handling multiple in-flight logs writes, the controller waits until the
 /// in-flight write completes or a newer log is detected.
 struct LogsControllerInner {
-    // todo make configurable
+    // fallback for provisioning new logs if the logs configuration doesn't specify a provider
     default_provider: ProviderKind,
 
     logs_state: HashMap<LogId, LogState, Xxh3Builder>,
@@ -697,7 +730,15 @@ impl LogsControllerInner {
         effects: &mut Vec<Effect>,
     ) {
         for (log_id, log_state) in &mut self.logs_state {
-            if log_state.try_transition_to_sealing(nodes_config, observed_cluster_state) {
+            // Only a provider stored in the logs configuration moves an existing log to a
+            // different provider. The default provider of this node's configuration only applies
+            // to new logs, since it can differ between admin nodes and across restarts.
+            let provider_override = self.current_logs.configuration().provider_for(*log_id);
+            if log_state.try_transition_to_sealing(
+                provider_override,
+                nodes_config,
+                observed_cluster_state,
+            ) {
                 effects.push(Effect::Seal {
                     log_id: *log_id,
                     segment_index: log_state
@@ -733,7 +774,10 @@ impl LogsControllerInner {
         node_set_selector_hints: impl NodeSetSelectorHints,
     ) -> Result<()> {
         for (log_id, log_state) in &mut self.logs_state {
+            let provider_override = self.current_logs.configuration().provider_for(*log_id);
             log_state.try_reconfiguring(
+                *log_id,
+                provider_override,
                 observed_cluster_state,
                 |seal_lsn, provider_kind, loglet_params| {
                     let mut chain_builder = logs_builder
@@ -742,7 +786,7 @@ impl LogsControllerInner {
 
                     chain_builder.append_segment(seal_lsn, provider_kind, loglet_params)
                 },
-                || node_set_selector_hints.preferred_sequencer(log_id),
+                &node_set_selector_hints,
             )?;
         }
 
@@ -887,10 +931,15 @@ impl LogsControllerInner {
                 .entry((*partition_id).into())
                 .or_insert_with(|| {
                     let log_id = (*partition_id).into();
-                    debug!(%log_id, "Try provisioning log with provider '{}'", self.default_provider);
+                    let provider_kind = self
+                        .current_logs
+                        .configuration()
+                        .provider_for(log_id)
+                        .unwrap_or(self.default_provider);
+                    debug!(%log_id, "Try provisioning log with provider '{}'", provider_kind);
                     LogState::Provisioning {
                         log_id,
-                        provider_kind: self.default_provider,
+                        provider_kind,
                     }
                 });
         }
@@ -1286,8 +1335,18 @@ pub mod tests {
     };
     use restate_types::{GenerationalNodeId, NodeId, PlainNodeId};
 
+    use arc_swap::ArcSwap;
+    use restate_types::live::Pinned;
+    use restate_types::logs::builder::LogsBuilder;
+    use restate_types::logs::metadata::{Chain, LogletParams, Logs, ProviderKind, SegmentIndex};
+    use restate_types::logs::{LogId, Lsn};
+    use restate_types::partition_table::PartitionTable;
+    use restate_types::retries::RetryPolicy;
+    use restate_types::Version;
+
     use crate::cluster_controller::logs_controller::{
-        build_new_replicated_loglet_configuration, LogletConfiguration,
+        build_new_replicated_loglet_configuration, Effect, LogletConfiguration,
+        LogsControllerInner, NodeSetSelectorHints,
     };
     use crate::cluster_controller::observed_cluster_state::ObservedClusterState;
 
@@ -1633,4 +1692,123 @@ pub mod tests {
         // we can now further expand the nodeset to exceed the 2f + 1 fault tolerance
         assert_eq!(config.nodeset, NodeSet::from([1, 2, 3, 6]));
     }
+
+    struct NoHints;
+
+    impl NodeSetSelectorHints for NoHints {
+        fn preferred_sequencer(&self, _log_id: &LogId) -> Option<NodeId> {
+            None
+        }
+    }
+
+    const LOG_ID: LogId = LogId::new(0);
+
+    /// Logs with a single log whose local segment was sealed at lsn 10 and extended with an
+    /// in-memory segment.
+    fn moved_log(provider_override: Option<ProviderKind>) -> Logs {
+        let mut builder = LogsBuilder::default();
+        builder
+            .add_log(
+                LOG_ID,
+                Chain::new(ProviderKind::Local, LogletParams::from("1")),
+            )
+            .unwrap()
+            .append_segment(
+                Lsn::new(10),
+                ProviderKind::InMemory,
+                LogletParams::from("2"),
+            )
+            .unwrap();
+        builder.set_provider(LOG_ID, provider_override);
+        builder.build()
+    }
+
+    fn logs_controller(logs: Logs, default_provider: ProviderKind) -> LogsControllerInner {
+        LogsControllerInner::new(
+            Pinned::new(&ArcSwap::from_pointee(logs)),
+            &PartitionTable::with_equally_sized_partitions(Version::MIN, 1),
+            default_provider,
+            RetryPolicy::None,
+        )
+        .unwrap()
+    }
+
+    fn run_controller(controller: &mut LogsControllerInner) -> Vec<Effect> {
+        let nodes = MockNodes::builder().with_all_roles_node(0).build();
+        let mut effects = Vec::new();
+        controller
+            .on_observed_cluster_state_update(
+                &nodes.nodes_config,
+                &nodes.observed_state,
+                &mut effects,
+                NoHints,
+            )
+            .unwrap();
+        effects
+    }
+
+    #[test]
+    fn migrated_log_stays_on_new_provider() {
+        // the log was moved from the local to the in-memory loglet through seal and extend,
+        // which records the new provider for the log
+        let mut controller =
+            logs_controller(moved_log(Some(ProviderKind::InMemory)), ProviderKind::Local);
+
+        let effects = run_controller(&mut controller);
+        assert!(
+            effects.is_empty(),
+            "the migrated log must neither be sealed nor be moved back to the default provider"
+        );
+        assert_eq!(
+            controller
+                .current_logs
+                .chain(&LOG_ID)
+                .unwrap()
+                .tail()
+                .config
+                .kind,
+            ProviderKind::InMemory
+        );
+    }
+
+    #[test]
+    fn default_provider_of_node_does_not_move_logs() {
+        // the log has no provider in the logs configuration, so the admin node's default provider
+        // must not seal and move it
+        let mut controller = logs_controller(moved_log(None), ProviderKind::Local);
+
+        let effects = run_controller(&mut controller);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn provider_override_seals_and_switches_log() {
+        let mut controller =
+            logs_controller(moved_log(Some(ProviderKind::Local)), ProviderKind::InMemory);
+
+        // the tail segment uses a different provider than the one configured for the log
+        let effects = run_controller(&mut controller);
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::Seal {
+                log_id: LOG_ID,
+                segment_index,
+                ..
+            }] if *segment_index == SegmentIndex::from(1)
+        ));
+
+        // no further seal is issued while the segment is being sealed
+        assert!(run_controller(&mut controller).is_empty());
+
+        controller.on_logs_sealed(LOG_ID, SegmentIndex::from(1), Lsn::new(20));
+
+        let effects = run_controller(&mut controller);
+        let [Effect::WriteLogs { logs, .. }] = effects.as_slice() else {
+            panic!("expected the logs with the new segment to be written");
+        };
+        let tail = logs.chain(&LOG_ID).unwrap().tail();
+        assert_eq!(tail.index(), SegmentIndex::from(2));
+        assert_eq!(tail.base_lsn, Lsn::new(20));
+        assert_eq!(tail.config.kind, ProviderKind::Local);
+    }
 }
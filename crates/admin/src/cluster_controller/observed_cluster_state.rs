@@ -9,10 +9,11 @@
 // by the Apache License, Version 2.0.
 
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use xxhash_rust::xxh3::Xxh3Builder;
 
-use restate_types::cluster::cluster_state::{ClusterState, NodeState, RunMode};
+use restate_types::cluster::cluster_state::{ClusterState, NodeLoad, NodeState, RunMode};
 use restate_types::identifiers::PartitionId;
 use restate_types::{GenerationalNodeId, NodeId, PlainNodeId};
 
@@ -24,6 +25,7 @@ pub struct ObservedClusterState {
     pub alive_nodes: HashMap<PlainNodeId, GenerationalNodeId, Xxh3Builder>,
     pub dead_nodes: HashSet<PlainNodeId, Xxh3Builder>,
     pub nodes_to_partitions: HashMap<PlainNodeId, HashSet<PartitionId, Xxh3Builder>, Xxh3Builder>,
+    pub node_loads: HashMap<PlainNodeId, ObservedNodeLoad, Xxh3Builder>,
 }
 
 impl ObservedClusterState {
@@ -72,6 +74,7 @@ impl ObservedClusterState {
     fn update_partitions(&mut self, cluster_state: &ClusterState) {
         // remove dead nodes
         for dead_node in cluster_state.dead_nodes() {
+            self.node_loads.remove(dead_node);
             if let Some(partitions) = self.nodes_to_partitions.remove(dead_node) {
                 for partition_id in partitions {
                     if let Some(partition) = self.partitions.get_mut(&partition_id) {
@@ -84,6 +87,10 @@ impl ObservedClusterState {
         // update node_sets and leaders of partitions
         for alive_node in cluster_state.alive_nodes() {
             let mut current_partitions = HashSet::default();
+            let mut node_load = ObservedNodeLoad {
+                load: alive_node.load,
+                apply_lags: HashMap::default(),
+            };
 
             let node_id = alive_node.generational_node_id.as_plain();
 
@@ -92,7 +99,11 @@ impl ObservedClusterState {
                 partition.upsert_partition_processor(node_id, status.effective_mode);
 
                 current_partitions.insert(*partition_id);
+                if let Some(apply_lag) = status.apply_lag {
+                    node_load.apply_lags.insert(*partition_id, apply_lag);
+                }
             }
+            self.node_loads.insert(node_id, node_load);
 
             if let Some(previous_partitions) = self.nodes_to_partitions.get(&node_id) {
                 // remove partitions that are no longer running on the given node
@@ -113,6 +124,14 @@ impl ObservedClusterState {
     }
 }
 
+/// Load of a node together with the apply lags of its partition processors, as last reported by
+/// the node.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ObservedNodeLoad {
+    pub load: NodeLoad,
+    pub apply_lags: HashMap<PartitionId, Duration, Xxh3Builder>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct ObservedPartitionState {
     pub partition_processors: HashMap<PlainNodeId, RunMode, Xxh3Builder>,
@@ -136,7 +155,7 @@ mod tests {
     use googletest::prelude::{empty, eq};
     use googletest::{assert_that, elements_are, unordered_elements_are};
     use restate_types::cluster::cluster_state::{
-        AliveNode, ClusterState, DeadNode, NodeState, PartitionProcessorStatus, RunMode,
+        AliveNode, ClusterState, DeadNode, NodeLoad, NodeState, PartitionProcessorStatus, RunMode,
     };
     use restate_types::identifiers::PartitionId;
     use restate_types::time::MillisSinceEpoch;
@@ -204,6 +223,7 @@ mod tests {
             generational_node_id,
             last_heartbeat_at: MillisSinceEpoch::now(),
             partitions,
+            load: NodeLoad::default(),
         })
     }
 
@@ -20,6 +20,7 @@ use restate_core::metadata_store::{
 };
 use restate_core::network::{NetworkSender, Networking, Outgoing, TransportConnect};
 use restate_core::{Metadata, ShutdownError, SyncError, TaskCenter, TaskKind};
+use restate_types::cluster::cluster_state::RunMode;
 use restate_types::cluster_controller::{
     ReplicationStrategy, SchedulingPlan, SchedulingPlanBuilder, TargetPartitionState,
 };
@@ -40,6 +41,12 @@ use crate::cluster_controller::observed_cluster_state::ObservedClusterState;
 
 type HashSet<T> = std::collections::HashSet<T, Xxh3Builder>;
 
+/// Nodes whose CPU usage exceeds this threshold don't take over leaderships when rebalancing.
+const REBALANCING_MAX_CPU_USAGE: f32 = 0.8;
+/// Followers whose apply lag exceeds this threshold don't take over leaderships when rebalancing,
+/// since they would first have to catch up with the log.
+const REBALANCING_MAX_APPLY_LAG: Duration = Duration::from_secs(1);
+
 #[derive(Debug, thiserror::Error)]
 #[error("failed reading scheduling plan from metadata store: {0}")]
 pub struct BuildError(#[from] ReadWriteError);
@@ -91,6 +98,7 @@ impl<T: PartitionProcessorPlacementHints> PartitionProcessorPlacementHints for &
 pub struct Scheduler<T> {
     scheduling_plan: SchedulingPlan,
     last_updated_scheduling_plan: Instant,
+    last_rebalanced_leadership: Instant,
     metadata_store_client: MetadataStoreClient,
     networking: Networking<T>,
 }
@@ -117,6 +125,7 @@ impl<T: TransportConnect> Scheduler<T> {
         Ok(Self {
             scheduling_plan,
             last_updated_scheduling_plan: Instant::now(),
+            last_rebalanced_leadership: Instant::now(),
             metadata_store_client,
             networking,
         })
@@ -136,8 +145,13 @@ impl<T: TransportConnect> Scheduler<T> {
             .filter(|node_id| nodes_config.has_worker_role(node_id))
             .collect();
 
-        self.update_scheduling_plan(&alive_workers, nodes_config, placement_hints)
-            .await?;
+        self.update_scheduling_plan(
+            &alive_workers,
+            nodes_config,
+            observed_cluster_state,
+            placement_hints,
+        )
+        .await?;
         self.instruct_nodes(observed_cluster_state)?;
 
         Ok(())
@@ -161,8 +175,12 @@ impl<T: TransportConnect> Scheduler<T> {
 
                 // add the partition to the scheduling plan if we aren't already scheduling it
                 if !builder.contains_partition(&partition_id) {
-                    // check whether the provisioned log is actually needed
-                    if let Some(partition) = partition_table.get_partition(&partition_id) {
+                    // check whether the provisioned log is actually needed. Partitions which are
+                    // still being split off don't have a partition store yet.
+                    if let Some(partition) = partition_table
+                        .get_partition(&partition_id)
+                        .filter(|partition| !partition.splitting)
+                    {
                         builder.insert_partition(
                             partition_id,
                             TargetPartitionState::new(
@@ -174,6 +192,18 @@ impl<T: TransportConnect> Scheduler<T> {
                 }
             }
 
+            // keep the key ranges in sync with the partition table, e.g. after a partition split
+            for (partition_id, partition) in partition_table.partitions() {
+                builder.modify_partition(partition_id, |target_state| {
+                    if target_state.partition_key_range != partition.key_range {
+                        target_state.partition_key_range = partition.key_range.clone();
+                        true
+                    } else {
+                        false
+                    }
+                });
+            }
+
             if let Some(scheduling_plan) = builder.build_if_modified() {
                 let scheduling_plan = self.try_update_scheduling_plan(scheduling_plan).await?;
                 match scheduling_plan {
@@ -198,6 +228,7 @@ impl<T: TransportConnect> Scheduler<T> {
         &mut self,
         alive_workers: &HashSet<PlainNodeId>,
         nodes_config: &NodesConfiguration,
+        observed_cluster_state: &ObservedClusterState,
         placement_hints: impl PartitionProcessorPlacementHints,
     ) -> Result<(), Error> {
         // todo temporary band-aid to ensure convergence of multiple schedulers. Remove once we
@@ -218,7 +249,26 @@ impl<T: TransportConnect> Scheduler<T> {
         let mut builder = self.scheduling_plan.clone().into_builder();
 
         self.ensure_replication(&mut builder, alive_workers, nodes_config, &placement_hints);
-        self.ensure_leadership(&mut builder, placement_hints);
+        self.ensure_leadership(&mut builder, observed_cluster_state, &placement_hints);
+
+        let (rebalancing, rebalancing_interval, max_moves) = {
+            let config = Configuration::pinned();
+            (
+                config.admin.leadership_rebalancing,
+                config.admin.leadership_rebalancing_interval.into(),
+                config.admin.leadership_rebalancing_max_moves.get(),
+            )
+        };
+        if rebalancing && self.last_rebalanced_leadership.elapsed() >= rebalancing_interval {
+            rebalance_leadership(
+                &mut builder,
+                alive_workers,
+                observed_cluster_state,
+                &placement_hints,
+                max_moves,
+            );
+            self.last_rebalanced_leadership = Instant::now();
+        }
 
         if let Some(scheduling_plan) = builder.build_if_modified() {
             let scheduling_plan = self
@@ -412,6 +462,7 @@ impl<T: TransportConnect> Scheduler<T> {
     fn ensure_leadership(
         &self,
         scheduling_plan_builder: &mut SchedulingPlanBuilder,
+        observed_cluster_state: &ObservedClusterState,
         placement_hints: impl PartitionProcessorPlacementHints,
     ) {
         let partition_ids: Vec<_> = scheduling_plan_builder.partition_ids().cloned().collect();
@@ -419,8 +470,28 @@ impl<T: TransportConnect> Scheduler<T> {
             scheduling_plan_builder.modify_partition(&partition_id, |target_state| {
                 let preferred_leader = placement_hints.preferred_leader(&partition_id);
                 if target_state.leader.is_none() {
-                    target_state.leader =
-                        self.select_leader_from(&target_state.node_set, preferred_leader);
+                    // Followers continuously apply the log, promoting one of them only requires
+                    // it to become leader instead of replaying the log from its last snapshot
+                    let running_processors = observed_cluster_state
+                        .partitions
+                        .get(&partition_id)
+                        .map(|partition| &partition.partition_processors);
+                    let warm_candidates: HashSet<_> = target_state
+                        .node_set
+                        .iter()
+                        .filter(|node_id| {
+                            running_processors.is_some_and(|processors| {
+                                processors.get(node_id) == Some(&RunMode::Follower)
+                            })
+                        })
+                        .cloned()
+                        .collect();
+
+                    target_state.leader = if warm_candidates.is_empty() {
+                        self.select_leader_from(&target_state.node_set, preferred_leader)
+                    } else {
+                        self.select_leader_from(&warm_candidates, preferred_leader)
+                    };
                     // check whether we modified the leader
                     return target_state.leader.is_some();
                 } else if preferred_leader.is_some_and(|preferred_leader| {
@@ -536,6 +607,122 @@ impl<T: TransportConnect> Scheduler<T> {
     }
 }
 
+/// Moves partition leaderships from the nodes leading more partitions than their share, relative
+/// to their capacity, to followers on nodes leading less, moving at most `max_moves` leaderships.
+/// Returns the number of moved leaderships.
+///
+/// A follower only takes over a leadership if it has caught up with the log and its node isn't
+/// overloaded. Every move strictly reduces the imbalance, so that consecutive rounds don't move
+/// leaderships back and forth. Leaderships pinned by a placement hint, e.g. to the sequencer of
+/// the partition's log, are never moved.
+fn rebalance_leadership(
+    scheduling_plan_builder: &mut SchedulingPlanBuilder,
+    alive_workers: &HashSet<PlainNodeId>,
+    observed_cluster_state: &ObservedClusterState,
+    placement_hints: impl PartitionProcessorPlacementHints,
+    max_moves: usize,
+) -> usize {
+    let relative_load = |node_id: &PlainNodeId, leaderships: usize| {
+        let capacity = observed_cluster_state
+            .node_loads
+            .get(node_id)
+            .map_or(1, |node_load| node_load.load.capacity.max(1));
+        leaderships as f64 / f64::from(capacity)
+    };
+    let can_take_over = |node_id: &PlainNodeId, partition_id: &PartitionId| {
+        let is_follower = observed_cluster_state
+            .partitions
+            .get(partition_id)
+            .is_some_and(|partition| {
+                partition.partition_processors.get(node_id) == Some(&RunMode::Follower)
+            });
+        let node_load = observed_cluster_state.node_loads.get(node_id);
+        let is_caught_up = node_load
+            .and_then(|node_load| node_load.apply_lags.get(partition_id))
+            .is_some_and(|apply_lag| *apply_lag <= REBALANCING_MAX_APPLY_LAG);
+        let is_overloaded = node_load
+            .and_then(|node_load| node_load.load.cpu_usage)
+            .is_some_and(|cpu_usage| cpu_usage > REBALANCING_MAX_CPU_USAGE);
+        is_follower && is_caught_up && !is_overloaded
+    };
+
+    let mut leaderships: BTreeMap<PlainNodeId, usize> =
+        alive_workers.iter().map(|node_id| (*node_id, 0)).collect();
+    let mut movable: BTreeMap<PlainNodeId, Vec<(PartitionId, Vec<PlainNodeId>)>> =
+        BTreeMap::default();
+    for (partition_id, target_state) in scheduling_plan_builder.iter() {
+        let Some(leader) = target_state.leader else {
+            continue;
+        };
+        if let Some(count) = leaderships.get_mut(&leader) {
+            *count += 1;
+        }
+        if placement_hints.preferred_leader(partition_id).is_none() {
+            let followers = target_state
+                .node_set
+                .iter()
+                .filter(|node_id| **node_id != leader)
+                .cloned()
+                .collect();
+            movable
+                .entry(leader)
+                .or_default()
+                .push((*partition_id, followers));
+        }
+    }
+
+    let mut moves = 0;
+    while moves < max_moves {
+        let mut sources: Vec<_> = leaderships.keys().cloned().collect();
+        sources.sort_by(|a, b| {
+            relative_load(b, leaderships[b]).total_cmp(&relative_load(a, leaderships[a]))
+        });
+
+        let next_move = sources.into_iter().find_map(|source| {
+            let load_after_move = relative_load(&source, leaderships[&source].saturating_sub(1));
+            movable
+                .get(&source)?
+                .iter()
+                .enumerate()
+                .find_map(|(idx, (partition_id, followers))| {
+                    followers
+                        .iter()
+                        .filter(|node_id| {
+                            leaderships.get(*node_id).is_some_and(|count| {
+                                relative_load(*node_id, count + 1) <= load_after_move
+                            }) && can_take_over(*node_id, partition_id)
+                        })
+                        .min_by(|a, b| {
+                            relative_load(*a, leaderships[*a] + 1)
+                                .total_cmp(&relative_load(*b, leaderships[*b] + 1))
+                        })
+                        .map(|target| (source, idx, *target))
+                })
+        });
+
+        let Some((source, idx, target)) = next_move else {
+            break;
+        };
+        let (partition_id, _) = movable
+            .get_mut(&source)
+            .expect("source leads movable partitions")
+            .swap_remove(idx);
+        debug!(
+            %partition_id,
+            "Moving the leadership from {source} to {target} to rebalance the leaderships"
+        );
+        scheduling_plan_builder.modify_partition(&partition_id, |target_state| {
+            target_state.leader = Some(target);
+            true
+        });
+        *leaderships.get_mut(&source).expect("source is alive") -= 1;
+        *leaderships.get_mut(&target).expect("target is alive") += 1;
+        moves += 1;
+    }
+
+    moves
+}
+
 /// Placement hints for the [`logs_controller::LogsController`] based on the current
 /// [`SchedulingPlan`].
 pub struct SchedulingPlanNodeSetSelectorHints<'a> {
@@ -578,14 +765,16 @@ mod tests {
 
     use crate::cluster_controller::observed_cluster_state::ObservedClusterState;
     use crate::cluster_controller::scheduler::{
-        HashSet, PartitionProcessorPlacementHints, Scheduler,
+        rebalance_leadership, HashSet, PartitionProcessorPlacementHints, Scheduler,
     };
     use restate_core::network::{ForwardingHandler, Incoming, MessageCollectorMockConnector};
     use restate_core::{Metadata, TestCoreEnv, TestCoreEnvBuilder};
     use restate_types::cluster::cluster_state::{
-        AliveNode, ClusterState, DeadNode, NodeState, PartitionProcessorStatus, RunMode,
+        AliveNode, ClusterState, DeadNode, NodeLoad, NodeState, PartitionProcessorStatus, RunMode,
+    };
+    use restate_types::cluster_controller::{
+        ReplicationStrategy, SchedulingPlan, SchedulingPlanBuilder,
     };
-    use restate_types::cluster_controller::{ReplicationStrategy, SchedulingPlan};
     use restate_types::config::Configuration;
     use restate_types::identifiers::PartitionId;
     use restate_types::metadata_store::keys::SCHEDULING_PLAN_KEY;
@@ -663,6 +852,213 @@ mod tests {
         Ok(())
     }
 
+    #[test(restate_core::test(start_paused = true))]
+    async fn elect_running_follower_as_leader() -> googletest::Result<()> {
+        let node_ids: Vec<_> = (1..=3)
+            .map(|idx| GenerationalNodeId::new(idx, idx))
+            .collect();
+        let mut nodes_config = NodesConfiguration::new(Version::MIN, "test-cluster".to_owned());
+        for node_id in &node_ids {
+            nodes_config.upsert_node(NodeConfig::new(
+                format!("{node_id}"),
+                *node_id,
+                AdvertisedAddress::Http(Uri::default()),
+                Role::Worker.into(),
+                LogServerConfig::default(),
+            ));
+        }
+
+        let (tx, _control_recv) = mpsc::channel(100);
+        let builder = TestCoreEnvBuilder::with_transport_connector(
+            MessageCollectorMockConnector::new(10, tx),
+        );
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 1);
+        let metadata_store_client = builder.metadata_store_client.clone();
+        let networking = builder.networking.clone();
+        let _env = builder
+            .set_nodes_config(nodes_config)
+            .set_partition_table(partition_table.clone())
+            .set_scheduling_plan(SchedulingPlan::from(
+                &partition_table,
+                ReplicationStrategy::OnAllNodes,
+            ))
+            .build()
+            .await;
+        let mut scheduler = Scheduler::init(
+            Configuration::pinned().as_ref(),
+            metadata_store_client.clone(),
+            networking,
+        )
+        .await?;
+
+        // only the second node runs a (follower) partition processor for the partition
+        let partition_id = PartitionId::from(0);
+        let warm_node = node_ids[1];
+        let nodes = node_ids
+            .iter()
+            .map(|node_id| {
+                let mut partitions = BTreeMap::default();
+                if *node_id == warm_node {
+                    partitions.insert(partition_id, PartitionProcessorStatus::new());
+                }
+                (
+                    node_id.as_plain(),
+                    NodeState::Alive(AliveNode {
+                        generational_node_id: *node_id,
+                        last_heartbeat_at: MillisSinceEpoch::now(),
+                        partitions,
+                        load: NodeLoad::default(),
+                    }),
+                )
+            })
+            .collect();
+        let mut observed_cluster_state = ObservedClusterState::default();
+        observed_cluster_state.update(&ClusterState {
+            last_refreshed: None,
+            nodes_config_version: Version::MIN,
+            partition_table_version: Version::MIN,
+            logs_metadata_version: Version::MIN,
+            nodes,
+        });
+
+        scheduler
+            .on_observed_cluster_state(
+                &observed_cluster_state,
+                &Metadata::with_current(|m| m.nodes_config_ref()),
+                NoPlacementHints,
+            )
+            .await?;
+
+        let scheduling_plan = metadata_store_client
+            .get::<SchedulingPlan>(SCHEDULING_PLAN_KEY.clone())
+            .await?
+            .expect("the scheduler should have created a scheduling plan");
+        let target_state = scheduling_plan
+            .get(&partition_id)
+            .expect("partition exists");
+        assert_eq!(target_state.node_set.len(), 3);
+        assert_eq!(target_state.leader, Some(warm_node.as_plain()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rebalance_leadership_by_capacity() {
+        let num_partitions = 8;
+        let node_ids: Vec<_> = (1..=3)
+            .map(|idx| GenerationalNodeId::new(idx, idx))
+            .collect();
+        let capacities = [1, 1, 2];
+        let alive_workers: HashSet<_> = node_ids.iter().map(|node_id| node_id.as_plain()).collect();
+
+        // the first node leads all partitions
+        let partition_table =
+            PartitionTable::with_equally_sized_partitions(Version::MIN, num_partitions);
+        let mut builder =
+            SchedulingPlan::from(&partition_table, ReplicationStrategy::OnAllNodes).into_builder();
+        for idx in 0..num_partitions {
+            builder.modify_partition(&PartitionId::from(idx), |target_state| {
+                target_state.node_set.clone_from(&alive_workers);
+                target_state.leader = Some(node_ids[0].as_plain());
+                true
+            });
+        }
+
+        let cluster_state = |cpu_usage: [Option<f32>; 3]| {
+            let nodes = node_ids
+                .iter()
+                .enumerate()
+                .map(|(idx, node_id)| {
+                    let partitions = (0..num_partitions)
+                        .map(|partition| {
+                            let mut status = PartitionProcessorStatus::new();
+                            status.apply_lag = Some(Duration::ZERO);
+                            if idx == 0 {
+                                status.planned_mode = RunMode::Leader;
+                                status.effective_mode = RunMode::Leader;
+                            }
+                            (PartitionId::from(partition), status)
+                        })
+                        .collect();
+                    (
+                        node_id.as_plain(),
+                        NodeState::Alive(AliveNode {
+                            generational_node_id: *node_id,
+                            last_heartbeat_at: MillisSinceEpoch::now(),
+                            partitions,
+                            load: NodeLoad {
+                                capacity: capacities[idx],
+                                cpu_usage: cpu_usage[idx],
+                            },
+                        }),
+                    )
+                })
+                .collect();
+            ClusterState {
+                last_refreshed: None,
+                nodes_config_version: Version::MIN,
+                partition_table_version: Version::MIN,
+                logs_metadata_version: Version::MIN,
+                nodes,
+            }
+        };
+        let leaderships = |builder: &SchedulingPlanBuilder| {
+            node_ids
+                .iter()
+                .map(|node_id| {
+                    builder
+                        .iter()
+                        .filter(|(_, target_state)| target_state.leader == Some(node_id.as_plain()))
+                        .count()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the second node is overloaded
+        let mut observed_cluster_state = ObservedClusterState::default();
+        observed_cluster_state.update(&cluster_state([None, Some(0.95), Some(0.1)]));
+        assert_eq!(
+            rebalance_leadership(
+                &mut builder,
+                &alive_workers,
+                &observed_cluster_state,
+                NoPlacementHints,
+                2
+            ),
+            2
+        );
+        assert_eq!(leaderships(&builder), vec![6, 0, 2]);
+        rebalance_leadership(
+            &mut builder,
+            &alive_workers,
+            &observed_cluster_state,
+            NoPlacementHints,
+            usize::MAX,
+        );
+        assert_eq!(leaderships(&builder), vec![3, 0, 5]);
+
+        // once it isn't overloaded anymore, leaderships are spread proportionally to capacity
+        observed_cluster_state.update(&cluster_state([None, Some(0.1), Some(0.1)]));
+        rebalance_leadership(
+            &mut builder,
+            &alive_workers,
+            &observed_cluster_state,
+            NoPlacementHints,
+            usize::MAX,
+        );
+        assert_eq!(leaderships(&builder), vec![2, 2, 4]);
+        assert_eq!(
+            rebalance_leadership(
+                &mut builder,
+                &alive_workers,
+                &observed_cluster_state,
+                NoPlacementHints,
+                usize::MAX
+            ),
+            0
+        );
+    }
+
     async fn schedule_partitions(
         replication_strategy: ReplicationStrategy,
     ) -> googletest::Result<()> {
@@ -946,6 +1342,7 @@ mod tests {
             generational_node_id: node_id,
             last_heartbeat_at: MillisSinceEpoch::now(),
             partitions,
+            load: NodeLoad::default(),
         }
     }
 
@@ -13,16 +13,17 @@ mod state;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use codederror::CodedError;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time;
 use tokio::time::{Instant, Interval, MissedTickBehavior};
 use tonic::codec::CompressionEncoding;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use restate_bifrost::{Bifrost, BifrostAdmin};
 use restate_core::metadata_store::{retry_on_network_error, MetadataStoreClient};
+use restate_core::network::failure_detector::ClusterLiveness;
 use restate_core::network::rpc_router::RpcRouter;
 use restate_core::network::{
     MessageRouterBuilder, NetworkSender, NetworkServerBuilder, Networking, TransportConnect,
@@ -34,13 +35,16 @@ use restate_core::{
 use restate_types::cluster::cluster_state::ClusterState;
 use restate_types::config::{AdminOptions, Configuration};
 use restate_types::health::HealthStatus;
-use restate_types::identifiers::{PartitionId, SnapshotId};
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::live::Live;
 use restate_types::logs::{LogId, Lsn};
 use restate_types::metadata_store::keys::PARTITION_TABLE_KEY;
 use restate_types::net::metadata::MetadataKind;
-use restate_types::net::partition_processor_manager::CreateSnapshotRequest;
-use restate_types::partition_table::PartitionTable;
+use restate_types::net::partition_processor_manager::{
+    CreateSnapshotRequest, SplitPartitionError, SplitPartitionRequest,
+};
+use restate_types::nodes_config::ClusterVersion;
+use restate_types::partition_table::{BuilderError, PartitionTable, PartitionTableBuilder};
 use restate_types::protobuf::common::AdminStatus;
 use restate_types::{GenerationalNodeId, Version};
 
@@ -83,6 +87,7 @@ where
         health_status: HealthStatus<AdminStatus>,
         bifrost: Bifrost,
         networking: Networking<T>,
+        cluster_liveness: ClusterLiveness,
         router_builder: &mut MessageRouterBuilder,
         server_builder: &mut NetworkServerBuilder,
         metadata_writer: MetadataWriter,
@@ -91,7 +96,7 @@ where
         let (command_tx, command_rx) = mpsc::channel(2);
 
         let cluster_state_refresher =
-            ClusterStateRefresher::new(networking.clone(), router_builder);
+            ClusterStateRefresher::new(networking.clone(), cluster_liveness, router_builder);
 
         let processor_manager_client =
             PartitionProcessorManagerClient::new(networking.clone(), router_builder);
@@ -153,6 +158,11 @@ enum ClusterControllerCommand {
         partition_id: PartitionId,
         response_tx: oneshot::Sender<anyhow::Result<SnapshotId>>,
     },
+    SplitPartition {
+        partition_id: PartitionId,
+        split_key: Option<PartitionKey>,
+        response_tx: oneshot::Sender<anyhow::Result<PartitionId>>,
+    },
 }
 
 pub struct ClusterControllerHandle {
@@ -205,6 +215,25 @@ impl ClusterControllerHandle {
 
         rx.await.map_err(|_| ShutdownError)
     }
+
+    pub async fn split_partition(
+        &self,
+        partition_id: PartitionId,
+        split_key: Option<PartitionKey>,
+    ) -> Result<Result<PartitionId, anyhow::Error>, ShutdownError> {
+        let (tx, rx) = oneshot::channel();
+
+        let _ = self
+            .tx
+            .send(ClusterControllerCommand::SplitPartition {
+                partition_id,
+                split_key,
+                response_tx: tx,
+            })
+            .await;
+
+        rx.await.map_err(|_| ShutdownError)
+    }
 }
 
 impl<T: TransportConnect> Service<T> {
@@ -219,6 +248,7 @@ impl<T: TransportConnect> Service<T> {
 
         let mut config_watcher = Configuration::watcher();
         let mut cluster_state_watcher = self.cluster_state_refresher.cluster_state_watcher();
+        let mut alive_nodes_watcher = self.cluster_state_refresher.alive_nodes_watcher();
 
         TaskCenter::spawn_child(
             TaskKind::SystemService,
@@ -244,6 +274,10 @@ impl<T: TransportConnect> Service<T> {
                     // Ignore error if system is shutting down
                     let _ = self.cluster_state_refresher.schedule_refresh();
                 },
+                Ok(()) = alive_nodes_watcher.changed() => {
+                    // React to the detected failures without waiting for the next heartbeat
+                    let _ = self.cluster_state_refresher.schedule_refresh();
+                },
                 Ok(cluster_state) = cluster_state_watcher.next_cluster_state() => {
                     self.observed_cluster_state.update(&cluster_state);
                     state.update(&self).await?;
@@ -357,6 +391,59 @@ impl<T: TransportConnect> Service<T> {
         };
     }
 
+    /// Splits the given partition by moving the keys starting at the split key into a new
+    /// partition. The node hosting the active leader creates the partition store of the new
+    /// partition before the split is completed in the partition table.
+    async fn split_partition(
+        &self,
+        partition_id: PartitionId,
+        split_key: Option<PartitionKey>,
+        response_tx: oneshot::Sender<anyhow::Result<PartitionId>>,
+    ) {
+        let cluster_state = self.cluster_state_refresher.get_cluster_state();
+
+        let leader_node = cluster_state
+            .alive_nodes()
+            .filter_map(|node| {
+                node.partitions
+                    .get(&partition_id)
+                    .filter(|status| status.is_effective_leader())
+                    .map(|_| node.generational_node_id)
+            })
+            .next();
+
+        let Some(node_id) = leader_node else {
+            let _ = response_tx.send(Err(anyhow!(
+                "Can not find the leader of partition {partition_id} to split it"
+            )));
+            return;
+        };
+
+        debug!(%node_id, ?partition_id, "Asking node to split partition");
+
+        let metadata_store_client = self.metadata_store_client.clone();
+        let metadata_writer = self.metadata_writer.clone();
+        let node_rpc_client = self.processor_manager_client.clone();
+        let _ = TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "split-partition-response",
+            async move {
+                let _ = response_tx.send(
+                    split_partition(
+                        metadata_store_client,
+                        metadata_writer,
+                        node_rpc_client,
+                        node_id,
+                        partition_id,
+                        split_key,
+                    )
+                    .await,
+                );
+                Ok(())
+            },
+        );
+    }
+
     async fn on_cluster_cmd(
         &self,
         command: ClusterControllerCommand,
@@ -386,10 +473,188 @@ impl<T: TransportConnect> Service<T> {
                 self.create_partition_snapshot(partition_id, response_tx)
                     .await;
             }
+            ClusterControllerCommand::SplitPartition {
+                partition_id,
+                split_key,
+                response_tx,
+            } => {
+                info!(
+                    ?partition_id,
+                    ?split_key,
+                    "Split partition command received"
+                );
+                self.split_partition(partition_id, split_key, response_tx)
+                    .await;
+            }
         }
     }
 }
 
+/// Splits the partition unless a split of it is in progress already, in which case that split is
+/// resumed. A split which was not started by the node running the leader is aborted. Once the
+/// leader may have started the split, it is never aborted since the replicas of the partition
+/// apply it eventually; splitting the partition again completes it.
+async fn split_partition<N>(
+    metadata_store_client: MetadataStoreClient,
+    metadata_writer: MetadataWriter,
+    mut processor_manager_client: PartitionProcessorManagerClient<N>,
+    node_id: GenerationalNodeId,
+    partition_id: PartitionId,
+    split_key: Option<PartitionKey>,
+) -> anyhow::Result<PartitionId>
+where
+    N: NetworkSender + 'static,
+{
+    // nodes which don't know the split command can't apply it
+    let cluster_version = Metadata::with_current(|m| m.nodes_config_ref().cluster_version());
+    if cluster_version < ClusterVersion::V1 {
+        bail!(
+            "Splitting partitions requires cluster version {} but the cluster has version {cluster_version}",
+            ClusterVersion::V1
+        );
+    }
+
+    let partition_table: PartitionTable = metadata_store_client
+        .get(PARTITION_TABLE_KEY.clone())
+        .await?
+        .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+    let (child_partition_id, partition_table, resumed) = match partition_table
+        .splitting_child(&partition_id)
+    {
+        Some(child_partition_id) => {
+            let child_start = partition_table
+                .get_partition(&child_partition_id)
+                .map(|child| *child.key_range.start());
+            if split_key.is_some_and(|split_key| Some(split_key) != child_start) {
+                bail!(
+                    "Partition {child_partition_id} is being split off from partition {partition_id} \
+                    at a different split key, split the partition without a split key to complete it"
+                );
+            }
+            info!(
+                ?partition_id,
+                ?child_partition_id,
+                "Resuming partition split"
+            );
+            (child_partition_id, partition_table, true)
+        }
+        None => {
+            let mut child_partition_id = PartitionId::MIN;
+            let partition_table = metadata_store_client
+                .read_modify_write(
+                    PARTITION_TABLE_KEY.clone(),
+                    |partition_table: Option<PartitionTable>| {
+                        let partition_table =
+                            partition_table.ok_or(BuilderError::UnknownPartition(partition_id))?;
+                        let key_range = partition_table
+                            .get_partition(&partition_id)
+                            .ok_or(BuilderError::UnknownPartition(partition_id))?
+                            .key_range
+                            .clone();
+                        // split in the middle of the key range unless told otherwise
+                        let split_key = split_key.unwrap_or_else(|| {
+                            (key_range.start() + (key_range.end() - key_range.start()) / 2)
+                                .saturating_add(1)
+                        });
+
+                        let mut builder = PartitionTableBuilder::from(partition_table);
+                        child_partition_id = builder.allocate_partition_id()?;
+                        builder.split_partition(partition_id, split_key, child_partition_id)?;
+                        Ok::<_, BuilderError>(builder.build())
+                    },
+                )
+                .await
+                .map_err(|err| anyhow!("Failed to split partition {partition_id}: {err}"))?;
+            (child_partition_id, partition_table, false)
+        }
+    };
+
+    let split_version = partition_table.version();
+    metadata_writer.update(Arc::new(partition_table)).await?;
+
+    let split_result = processor_manager_client
+        .split_partition(node_id, partition_id, child_partition_id, split_version)
+        .await;
+
+    let split_lsn = match split_result {
+        Ok(Ok(split_lsn)) => split_lsn,
+        Ok(Err(SplitPartitionError::SplitFailed(err))) if !resumed => {
+            finalize_split(
+                &metadata_store_client,
+                &metadata_writer,
+                child_partition_id,
+                PartitionTableBuilder::abort_split,
+            )
+            .await?;
+            warn!(?partition_id, "Partition split aborted: {err}");
+            bail!("Failed to split partition {partition_id}: {err}");
+        }
+        Ok(Err(err)) => {
+            warn!(
+                ?partition_id,
+                ?child_partition_id,
+                "Partition split did not complete: {err:?}"
+            );
+            bail!(
+                "Split of partition {partition_id} did not complete, split it again to complete it: {err:?}"
+            );
+        }
+        Err(err) => {
+            warn!(
+                ?partition_id,
+                ?child_partition_id,
+                "Partition split did not complete: {err}"
+            );
+            bail!(
+                "Split of partition {partition_id} did not complete, split it again to complete it: {err}"
+            );
+        }
+    };
+
+    finalize_split(
+        &metadata_store_client,
+        &metadata_writer,
+        child_partition_id,
+        PartitionTableBuilder::complete_split,
+    )
+    .await?;
+    info!(
+        ?partition_id,
+        ?child_partition_id,
+        %split_lsn,
+        "Partition split completed"
+    );
+
+    Ok(child_partition_id)
+}
+
+async fn finalize_split(
+    metadata_store_client: &MetadataStoreClient,
+    metadata_writer: &MetadataWriter,
+    child_partition_id: PartitionId,
+    finalize: fn(&mut PartitionTableBuilder, PartitionId) -> Result<(), BuilderError>,
+) -> anyhow::Result<()> {
+    let partition_table = metadata_store_client
+        .read_modify_write(
+            PARTITION_TABLE_KEY.clone(),
+            |partition_table: Option<PartitionTable>| {
+                let partition_table =
+                    partition_table.ok_or(BuilderError::UnknownPartition(child_partition_id))?;
+                let mut builder = PartitionTableBuilder::from(partition_table);
+                finalize(&mut builder, child_partition_id)?;
+                Ok::<_, BuilderError>(builder.build())
+            },
+        )
+        .await
+        .map_err(|err| {
+            anyhow!("Failed to finalize the split of partition {child_partition_id}: {err}")
+        })?;
+    metadata_writer.update(Arc::new(partition_table)).await?;
+
+    Ok(())
+}
+
 async fn sync_cluster_controller_metadata() -> anyhow::Result<()> {
     // todo make this configurable
     let mut interval = time::interval(Duration::from_secs(10));
@@ -427,6 +692,7 @@ where
 {
     network_sender: N,
     create_snapshot_router: RpcRouter<CreateSnapshotRequest>,
+    split_partition_router: RpcRouter<SplitPartitionRequest>,
 }
 
 impl<N> PartitionProcessorManagerClient<N>
@@ -435,10 +701,12 @@ where
 {
     pub fn new(network_sender: N, router_builder: &mut MessageRouterBuilder) -> Self {
         let create_snapshot_router = RpcRouter::new(router_builder);
+        let split_partition_router = RpcRouter::new(router_builder);
 
         PartitionProcessorManagerClient {
             network_sender,
             create_snapshot_router,
+            split_partition_router,
         }
     }
 
@@ -462,6 +730,30 @@ where
             .result
             .map_err(|e| anyhow!("Failed to create snapshot: {:?}", e))
     }
+
+    pub async fn split_partition(
+        &mut self,
+        node_id: GenerationalNodeId,
+        partition_id: PartitionId,
+        child_partition_id: PartitionId,
+        min_partition_table_version: Version,
+    ) -> anyhow::Result<Result<Lsn, SplitPartitionError>> {
+        // splitting includes applying the split and uploading a snapshot of the new partition
+        let response = tokio::time::timeout(
+            Duration::from_secs(60),
+            self.split_partition_router.call(
+                &self.network_sender,
+                node_id,
+                SplitPartitionRequest {
+                    partition_id,
+                    child_partition_id,
+                    min_partition_table_version,
+                },
+            ),
+        )
+        .await?;
+        Ok(response?.into_body().result)
+    }
 }
 
 #[cfg(test)]
@@ -479,12 +771,13 @@ mod tests {
 
     use restate_bifrost::providers::memory_loglet;
     use restate_bifrost::{Bifrost, BifrostService};
+    use restate_core::network::failure_detector::ClusterLiveness;
     use restate_core::network::{
         FailingConnector, Incoming, MessageHandler, MockPeerConnection, NetworkServerBuilder,
     };
     use restate_core::test_env::NoOpMessageHandler;
     use restate_core::{TaskCenter, TaskKind, TestCoreEnv, TestCoreEnvBuilder};
-    use restate_types::cluster::cluster_state::PartitionProcessorStatus;
+    use restate_types::cluster::cluster_state::{NodeLoad, PartitionProcessorStatus};
     use restate_types::config::{AdminOptions, Configuration};
     use restate_types::health::HealthStatus;
     use restate_types::identifiers::PartitionId;
@@ -508,6 +801,7 @@ mod tests {
             HealthStatus::default(),
             bifrost.clone(),
             builder.networking.clone(),
+            ClusterLiveness::default(),
             &mut builder.router_builder,
             &mut NetworkServerBuilder::default(),
             builder.metadata_writer.clone(),
@@ -560,6 +854,7 @@ mod tests {
             let state = [(PartitionId::MIN, partition_processor_status)].into();
             let response = msg.to_rpc_response(NodeStateResponse {
                 partition_processor_state: Some(state),
+                load: NodeLoad::default(),
             });
 
             // We are not really sending something back to target, we just need to provide a known
@@ -771,6 +1066,76 @@ mod tests {
         Ok(())
     }
 
+    #[test(restate_core::test(start_paused = true))]
+    async fn auto_log_trim_up_to_archived_lsn() -> anyhow::Result<()> {
+        const LOG_ID: LogId = LogId::new(0);
+
+        let mut admin_options = AdminOptions::default();
+        admin_options.log_trim_threshold = 0;
+        admin_options.log_trim_retention = 1;
+        let interval_duration = Duration::from_secs(10);
+        admin_options.log_trim_interval = Some(interval_duration.into());
+        let config = Configuration {
+            admin: admin_options,
+            ..Default::default()
+        };
+
+        let persisted_lsn = Arc::new(AtomicU64::new(0));
+        let archived_lsn = Arc::new(AtomicU64::new(0));
+
+        // node 1 doesn't respond, only node 2 reports the state of its partition processor
+        let get_node_state_handler = Arc::new(NodeStateHandler {
+            persisted_lsn: Arc::clone(&persisted_lsn),
+            archived_lsn: Arc::clone(&archived_lsn),
+            block_list: BTreeSet::from([GenerationalNodeId::new(1, 1)]),
+        });
+
+        let (node_env, bifrost) = create_test_env(config, |builder| {
+            builder
+                .add_message_handler(get_node_state_handler.clone())
+                .add_message_handler(NoOpMessageHandler::<ControlProcessors>::default())
+        })
+        .await?;
+
+        let node_2 = MockPeerConnection::connect(
+            GenerationalNodeId::new(2, 2),
+            node_env.metadata.nodes_config_version(),
+            node_env
+                .metadata
+                .nodes_config_ref()
+                .cluster_name()
+                .to_owned(),
+            node_env.networking.connection_manager(),
+            10,
+        )
+        .await?;
+        let (_node_2, _node2_reactor) =
+            node_2.process_with_message_handler(get_node_state_handler)?;
+
+        let mut appender = bifrost.create_appender(LOG_ID)?;
+        for i in 1..=5 {
+            let lsn = appender.append(format!("record{}", i)).await?;
+            assert_eq!(Lsn::from(i), lsn);
+        }
+
+        persisted_lsn.store(3, Ordering::Relaxed);
+        archived_lsn.store(4, Ordering::Relaxed);
+
+        tokio::time::sleep(interval_duration * 10).await;
+        // the log is not trimmed beyond the persisted lsn of the replica lagging behind the
+        // snapshot; we retain one entry below it
+        assert_eq!(Lsn::from(2), bifrost.get_trim_point(LOG_ID).await?);
+
+        persisted_lsn.store(5, Ordering::Relaxed);
+
+        tokio::time::sleep(interval_duration * 10).await;
+        // not all nodes reported their persisted lsn but a snapshot covering lsn 4 is available;
+        // we retain one entry below the archived lsn
+        assert_eq!(Lsn::from(3), bifrost.get_trim_point(LOG_ID).await?);
+
+        Ok(())
+    }
+
     async fn create_test_env<F>(
         config: Configuration,
         mut modify_builder: F,
@@ -789,6 +1154,7 @@ mod tests {
             HealthStatus::default(),
             bifrost.clone(),
             builder.networking.clone(),
+            ClusterLiveness::default(),
             &mut builder.router_builder,
             &mut server_builder,
             builder.metadata_writer.clone(),
@@ -21,7 +21,7 @@ use restate_bifrost::{Bifrost, BifrostAdmin};
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::network::TransportConnect;
 use restate_core::{my_node_id, Metadata, MetadataWriter};
-use restate_types::cluster::cluster_state::{AliveNode, NodeState};
+use restate_types::cluster::cluster_state::{AliveNode, ClusterState, NodeState};
 use restate_types::config::{AdminOptions, Configuration};
 use restate_types::identifiers::PartitionId;
 use restate_types::live::Live;
@@ -153,6 +153,7 @@ pub struct Leader<T> {
     cluster_state_watcher: ClusterStateWatcher,
     logs: Live<Logs>,
     log_trim_threshold: Lsn,
+    log_trim_retention: u64,
 }
 
 impl<T> Leader<T>
@@ -182,6 +183,7 @@ where
 
         let (log_trim_interval, log_trim_threshold) =
             create_log_trim_interval(&configuration.admin);
+        let log_trim_retention = configuration.admin.log_trim_retention;
 
         let mut find_logs_tail_interval =
             time::interval(configuration.admin.log_tail_update_interval.into());
@@ -201,6 +203,7 @@ where
             find_logs_tail_interval,
             log_trim_interval,
             log_trim_threshold,
+            log_trim_retention,
             logs_controller,
             scheduler,
         };
@@ -235,6 +238,7 @@ where
     fn reconfigure(&mut self, configuration: &Configuration) {
         (self.log_trim_interval, self.log_trim_threshold) =
             create_log_trim_interval(&configuration.admin);
+        self.log_trim_retention = configuration.admin.log_trim_retention;
     }
 
     async fn run(&mut self) -> anyhow::Result<LeaderEvent> {
@@ -315,59 +319,98 @@ where
 
         let cluster_state = self.cluster_state_watcher.current();
 
-        let mut persisted_lsns_per_partition: BTreeMap<
-            PartitionId,
-            BTreeMap<GenerationalNodeId, Lsn>,
-        > = BTreeMap::default();
-
-        for node_state in cluster_state.nodes.values() {
-            match node_state {
-                NodeState::Alive(AliveNode {
-                    generational_node_id,
-                    partitions,
-                    ..
-                }) => {
-                    for (partition_id, partition_processor_status) in partitions.iter() {
-                        let lsn = partition_processor_status
-                            .last_persisted_log_lsn
-                            .unwrap_or(Lsn::INVALID);
-                        persisted_lsns_per_partition
+        for (partition_id, safe_trim_point) in find_safe_trim_points(&cluster_state) {
+            let log_id = LogId::from(partition_id);
+
+            let Some(safe_trim_point) = safe_trim_point else {
+                warn!("Stop automatically trimming log '{log_id}' because not all nodes are running a partition processor applying this log and no snapshot is available.");
+                continue;
+            };
+
+            let trim_point = Lsn::new(
+                safe_trim_point
+                    .as_u64()
+                    .saturating_sub(self.log_trim_retention),
+            );
+            // trim point is before the oldest record
+            let current_trim_point = bifrost_admin.get_trim_point(log_id).await?;
+
+            if trim_point > current_trim_point
+                && trim_point >= current_trim_point + self.log_trim_threshold
+            {
+                debug!("Automatic trim log '{log_id}' for all records before='{trim_point}'");
+                bifrost_admin.trim(log_id, trim_point).await?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Determines for every partition the highest LSN up to which its log can be trimmed without
+/// preventing any partition processor from rebuilding its state.
+///
+/// The log is never trimmed beyond the minimum durably applied LSN of the partition processors
+/// reporting their state. If a snapshot of the partition has been archived to the snapshot
+/// repository, nodes which don't report their state can bootstrap from the snapshot, hence the
+/// log can be trimmed up to the snapshot's LSN. Otherwise, the log can only be trimmed if the
+/// durably applied LSN is known for all nodes. Partitions for which neither condition holds map
+/// to `None`.
+fn find_safe_trim_points(cluster_state: &ClusterState) -> BTreeMap<PartitionId, Option<Lsn>> {
+    let mut persisted_lsns_per_partition: BTreeMap<PartitionId, BTreeMap<GenerationalNodeId, Lsn>> =
+        BTreeMap::default();
+    let mut archived_lsns_per_partition: BTreeMap<PartitionId, Lsn> = BTreeMap::default();
+
+    for node_state in cluster_state.nodes.values() {
+        match node_state {
+            NodeState::Alive(AliveNode {
+                generational_node_id,
+                partitions,
+                ..
+            }) => {
+                for (partition_id, partition_processor_status) in partitions.iter() {
+                    let lsn = partition_processor_status
+                        .last_persisted_log_lsn
+                        .unwrap_or(Lsn::INVALID);
+                    persisted_lsns_per_partition
+                        .entry(*partition_id)
+                        .or_default()
+                        .insert(*generational_node_id, lsn);
+
+                    if let Some(archived_lsn) = partition_processor_status
+                        .last_archived_log_lsn
+                        .filter(|lsn| *lsn > Lsn::INVALID)
+                    {
+                        let entry = archived_lsns_per_partition
                             .entry(*partition_id)
-                            .or_default()
-                            .insert(*generational_node_id, lsn);
+                            .or_insert(archived_lsn);
+                        *entry = (*entry).max(archived_lsn);
                     }
                 }
-                NodeState::Dead(_) | NodeState::Suspect(_) => {
-                    // nothing to do
-                }
+            }
+            NodeState::Dead(_) | NodeState::Suspect(_) => {
+                // nothing to do
             }
         }
+    }
 
-        for (partition_id, persisted_lsns) in persisted_lsns_per_partition.into_iter() {
-            let log_id = LogId::from(partition_id);
+    persisted_lsns_per_partition
+        .into_iter()
+        .map(|(partition_id, persisted_lsns)| {
+            let reported_all = persisted_lsns.len() >= cluster_state.nodes.len();
+            let min_persisted_lsn = persisted_lsns.into_values().min().unwrap_or(Lsn::INVALID);
 
-            // todo: Remove once Restate nodes can share partition processor snapshots
-            // only try to trim if we know about the persisted lsns of all known nodes; otherwise we
-            // risk that a node cannot fully replay the log; this assumes that no new nodes join the
-            // cluster after the first trimming has happened
-            if persisted_lsns.len() >= cluster_state.nodes.len() {
-                let min_persisted_lsn = persisted_lsns.into_values().min().unwrap_or(Lsn::INVALID);
-                // trim point is before the oldest record
-                let current_trim_point = bifrost_admin.get_trim_point(log_id).await?;
-
-                if min_persisted_lsn >= current_trim_point + self.log_trim_threshold {
-                    debug!(
-                    "Automatic trim log '{log_id}' for all records before='{min_persisted_lsn}'"
-                );
-                    bifrost_admin.trim(log_id, min_persisted_lsn).await?
-                }
-            } else {
-                warn!("Stop automatically trimming log '{log_id}' because not all nodes are running a partition processor applying this log.");
+            if let Some(archived_lsn) = archived_lsns_per_partition.get(&partition_id) {
+                // a replica which lags behind the snapshot would need to fetch it to continue
+                return (partition_id, Some(min_persisted_lsn.min(*archived_lsn)));
             }
-        }
 
-        Ok(())
-    }
+            // only trim based on the persisted lsns if we know about them for all known nodes;
+            // otherwise we risk that a node cannot fully replay the log; this assumes that no new
+            // nodes join the cluster after the first trimming has happened
+            (partition_id, reported_all.then_some(min_persisted_lsn))
+        })
+        .collect()
 }
 
 fn create_log_trim_interval(options: &AdminOptions) -> (Option<Interval>, Lsn) {
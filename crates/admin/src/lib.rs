@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod audit;
 pub mod cluster_controller;
 mod error;
 mod rest_api;
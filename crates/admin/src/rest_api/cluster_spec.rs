@@ -0,0 +1,136 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+use super::subscriptions;
+use crate::audit::AuditRecord;
+use crate::schema_registry::descriptor::StaticDescriptor;
+use crate::state::AdminServiceState;
+
+use axum::extract::{FromRequest, Request, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use bytes::Bytes;
+use okapi_operation::anyhow::Error;
+use okapi_operation::okapi::openapi3::MediaType;
+use okapi_operation::*;
+use restate_types::schema::subscriptions::SubscriptionValidator;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::{info, warn};
+
+const APPLICATION_YAML: &str = "application/yaml";
+
+/// YAML encoded request or response body.
+pub struct Yaml<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for Yaml<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        serde_yaml::from_slice(&body)
+            .map(Yaml)
+            .map_err(|err| MetaApiError::InvalidField("body", err.to_string()).into_response())
+    }
+}
+
+impl<T: Serialize> IntoResponse for Yaml<T> {
+    fn into_response(self) -> Response {
+        match serde_yaml::to_string(&self.0) {
+            Ok(body) => ([(header::CONTENT_TYPE, APPLICATION_YAML)], body).into_response(),
+            Err(err) => MetaApiError::Internal(err.to_string()).into_response(),
+        }
+    }
+}
+
+impl<T> ToMediaTypes for Yaml<T> {
+    fn generate(_components: &mut Components) -> Result<okapi::Map<String, MediaType>, Error> {
+        Ok(okapi::map! {
+            APPLICATION_YAML.into() => MediaType::default()
+        })
+    }
+}
+
+/// Export the cluster spec
+#[openapi(
+    summary = "Export cluster spec",
+    description = "Export the logical configuration of the cluster as a YAML document: the registered \
+    deployments, the options of the services including their routing and mirroring rules, and the \
+    subscriptions. The document has the format of the static descriptor, and can be applied again. \
+    Secrets, i.e. the additional headers of the deployments and the secret options of the \
+    subscriptions, are redacted.",
+    operation_id = "export_cluster_spec",
+    tags = "schema",
+    responses(
+        ignore_return_type = true,
+        response(status = "200", description = "OK", content = "Yaml<StaticDescriptor>"),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn export_cluster_spec<V>(
+    State(state): State<AdminServiceState<V>>,
+) -> Result<Yaml<StaticDescriptor>, MetaApiError> {
+    Ok(Yaml(state.schema_registry.export_descriptor()))
+}
+
+/// Apply a cluster spec
+#[openapi(
+    summary = "Apply cluster spec",
+    description = "Apply a YAML document describing deployments, services and subscriptions, as \
+    returned by the export. Missing deployments and subscriptions are registered, the ones whose options \
+    differ from the document are updated, and the listed service options are applied. All the changes \
+    are written at once as a single new version of the schema, and nothing is written if the schema \
+    already matches, hence applying the same document multiple times is a no-op. Redacted values keep \
+    the registered ones, and entries missing from the document are left untouched. Returns the cluster \
+    spec after applying the document.",
+    operation_id = "apply_cluster_spec",
+    tags = "schema",
+    responses(
+        ignore_return_type = true,
+        response(status = "200", description = "OK", content = "Yaml<StaticDescriptor>"),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn apply_cluster_spec<V: SubscriptionValidator>(
+    State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
+    #[request_body(required = true)] Yaml(spec): Yaml<StaticDescriptor>,
+) -> Result<Yaml<StaticDescriptor>, MetaApiError> {
+    spec.validate()?;
+    for subscription in &spec.subscriptions {
+        subscriptions::ensure_source_supported(&subscription.source)?;
+    }
+
+    audit.before(state.schema_registry.export_descriptor());
+    info!(
+        "Applying the cluster spec with {} deployments, {} services and {} subscriptions",
+        spec.deployments.len(),
+        spec.services.len(),
+        spec.subscriptions.len()
+    );
+    state
+        .schema_registry
+        .apply_descriptor(&spec)
+        .await
+        .inspect_err(|err| warn!("Failed applying the cluster spec: {err}"))?;
+
+    let applied = state.schema_registry.export_descriptor();
+    audit.after(&applied);
+    Ok(Yaml(applied))
+}
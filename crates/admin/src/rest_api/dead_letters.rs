@@ -0,0 +1,89 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+use std::sync::Arc;
+
+use crate::rest_api::{create_envelope_header, ensure_cluster_version};
+use crate::state::AdminServiceState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use okapi_operation::*;
+use restate_core::Metadata;
+use restate_types::identifiers::PartitionId;
+use restate_types::logs::Lsn;
+use restate_types::nodes_config::ClusterVersion;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use tracing::{info, warn};
+
+/// Re-inject a dead-lettered record
+#[openapi(
+    summary = "Re-inject dead letter",
+    description = "Applies a record which was moved to the dead-letter table of a partition again. \
+    The dead letter is removed from the table once the record has been applied. Dead letters can be \
+    inspected using the sys_dead_letter table.",
+    operation_id = "reinject_dead_letter",
+    tags = "partition",
+    parameters(
+        path(
+            name = "partition_id",
+            description = "Partition identifier.",
+            schema = "u16"
+        ),
+        path(
+            name = "lsn",
+            description = "LSN of the dead-lettered record in the partition's log.",
+            schema = "u64"
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn reinject_dead_letter<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path((partition_id, lsn)): Path<(u16, u64)>,
+) -> Result<StatusCode, MetaApiError> {
+    ensure_cluster_version("re-inject dead letters", ClusterVersion::V1)?;
+    let partition_id = PartitionId::from(partition_id);
+    let lsn = Lsn::from(lsn);
+
+    let partition_key = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .get_partition(&partition_id)
+            .map(|partition| *partition.key_range.start())
+    })
+    .ok_or(MetaApiError::PartitionNotFound(partition_id))?;
+
+    info!(%partition_id, %lsn, "Re-injecting dead letter");
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(partition_key),
+            Command::ReinjectDeadLetter(lsn),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append dead letter re-injection command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending dead letter re-injection to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
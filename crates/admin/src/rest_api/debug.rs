@@ -0,0 +1,85 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Debug endpoints which bypass the regular validation of the admin and ingress APIs. These
+//! endpoints are only registered if `admin.enable-debug-endpoints` is set and are not part of the
+//! OpenAPI specification.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Json;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use restate_types::identifiers::{InvocationId, PartitionKey, WithPartitionKey};
+use restate_types::invocation::ServiceInvocation;
+use restate_types::logs::{LogId, Lsn};
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+
+use super::create_envelope_header;
+use super::error::MetaApiError;
+use crate::state::AdminServiceState;
+
+pub fn create_router<V>(state: AdminServiceState<V>) -> axum::Router<()>
+where
+    V: Send + Sync + Clone + 'static,
+{
+    axum::Router::new()
+        .route("/debug/invocations", post(inject_invocation::<V>))
+        .with_state(state)
+}
+
+#[derive(Debug, Serialize)]
+pub struct InjectInvocationResponse {
+    invocation_id: InvocationId,
+    partition_key: PartitionKey,
+    log_id: LogId,
+    lsn: Lsn,
+}
+
+/// Appends the given [`ServiceInvocation`] as an invoke command directly to the log of the
+/// partition owning the invocation id. All fields, including source, span context and idempotency
+/// key, are taken as is. No validation against the registered services is performed, which makes
+/// it possible to exercise state machine paths that are unreachable via the ingress.
+pub async fn inject_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Json(service_invocation): Json<ServiceInvocation>,
+) -> Result<(StatusCode, Json<InjectInvocationResponse>), MetaApiError> {
+    let invocation_id = service_invocation.invocation_id;
+    let partition_key = service_invocation.partition_key();
+
+    let (log_id, lsn) = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(partition_key),
+            Command::Invoke(service_invocation),
+        )),
+    )
+    .await
+    .map_err(|err| {
+        warn!("Could not append synthetic invocation to Bifrost: {err}");
+        MetaApiError::Internal("Failed sending synthetic invocation to the cluster.".to_owned())
+    })?;
+
+    info!(%invocation_id, %log_id, %lsn, "Injected synthetic invocation");
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(InjectInvocationResponse {
+            invocation_id,
+            partition_key,
+            log_id,
+            lsn,
+        }),
+    ))
+}
@@ -8,22 +8,31 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use super::create_envelope_header;
 use super::error::*;
+use crate::audit::AuditRecord;
 use crate::state::AdminServiceState;
+use std::sync::Arc;
 
 use crate::schema_registry::{ApplyMode, Force};
 use axum::extract::{Path, Query, State};
 use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
+use axum::Extension;
 use axum::Json;
 use http::uri::Scheme;
 use okapi_operation::*;
 use restate_admin_rest_model::deployments::*;
+use restate_core::Metadata;
 use restate_errors::warn_it;
-use restate_service_client::Endpoint;
+use restate_service_client::{Endpoint, LambdaInvokeOptions};
 use restate_service_protocol::discovery::DiscoverEndpoint;
-use restate_types::identifiers::{DeploymentId, InvalidLambdaARN};
+use restate_types::deployment::{DeploymentMigration, MigrationTarget};
+use restate_types::identifiers::{DeploymentId, InvalidLambdaARN, ServiceRevision};
+use restate_types::schema::deployment::Deployment as DeploymentSchema;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
+use tracing::{info, warn};
 
 /// Create deployment and return discovered services.
 #[openapi(
@@ -43,13 +52,15 @@ use serde::Deserialize;
 )]
 pub async fn create_deployment<V>(
     State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
     #[request_body(required = true)] Json(payload): Json<RegisterDeploymentRequest>,
 ) -> Result<impl IntoResponse, MetaApiError> {
-    let (discover_endpoint, force, dry_run) = match payload {
+    let (discover_endpoint, concurrency_limit, force, dry_run) = match payload {
         RegisterDeploymentRequest::Http {
             uri,
             additional_headers,
             use_http_11,
+            concurrency_limit,
             force,
             dry_run,
         } => {
@@ -81,6 +92,7 @@ pub async fn create_deployment<V>(
                     ),
                     additional_headers.unwrap_or_default().into(),
                 ),
+                concurrency_limit,
                 force,
                 dry_run,
             )
@@ -88,7 +100,9 @@ pub async fn create_deployment<V>(
         RegisterDeploymentRequest::Lambda {
             arn,
             assume_role_arn,
+            region,
             additional_headers,
+            concurrency_limit,
             force,
             dry_run,
         } => (
@@ -97,10 +111,14 @@ pub async fn create_deployment<V>(
                     arn.parse().map_err(|e: InvalidLambdaARN| {
                         MetaApiError::InvalidField("arn", e.to_string())
                     })?,
-                    assume_role_arn.map(Into::into),
+                    LambdaInvokeOptions {
+                        assume_role_arn: assume_role_arn.map(Into::into),
+                        region: region.map(Into::into),
+                    },
                 ),
                 additional_headers.unwrap_or_default().into(),
             ),
+            concurrency_limit,
             force,
             dry_run,
         ),
@@ -116,11 +134,14 @@ pub async fn create_deployment<V>(
 
     let (id, services) = state
         .schema_registry
-        .register_deployment(discover_endpoint, force, apply_mode)
+        .register_deployment(discover_endpoint, concurrency_limit, force, apply_mode)
         .await
         .inspect_err(|e| warn_it!(e))?;
 
     let response_body = RegisterDeploymentResponse { id, services };
+    if !dry_run {
+        audit.after(&response_body);
+    }
 
     Ok((
         StatusCode::CREATED,
@@ -188,9 +209,75 @@ pub async fn list_deployments<V>(
     ListDeploymentsResponse { deployments }.into()
 }
 
+/// Modify a deployment
+#[openapi(
+    summary = "Modify deployment",
+    description = "Modify a registered deployment. A draining deployment doesn't start new invocations, \
+    which are started on the most recent deployment of their service which is not draining instead. \
+    The invocations already running on a draining deployment are not affected.",
+    operation_id = "modify_deployment",
+    tags = "deployment",
+    parameters(path(
+        name = "deployment",
+        description = "Deployment identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn modify_deployment<V>(
+    State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
+    Path(deployment_id): Path<DeploymentId>,
+    #[request_body(required = true)] Json(ModifyDeploymentRequest { draining }): Json<
+        ModifyDeploymentRequest,
+    >,
+) -> Result<Json<DetailedDeploymentResponse>, MetaApiError> {
+    let (deployment, services) = match draining {
+        Some(draining) => {
+            info!(restate.deployment.id = %deployment_id, draining, "Modifying deployment");
+            if let Some(before) = detailed_deployment(&state, deployment_id) {
+                audit.before(&before);
+            }
+            state
+                .schema_registry
+                .set_deployment_draining(deployment_id, draining)
+                .await
+                .inspect_err(|e| warn_it!(e))?
+        }
+        None => state
+            .schema_registry
+            .get_deployment(deployment_id)
+            .ok_or_else(|| MetaApiError::DeploymentNotFound(deployment_id))?,
+    };
+
+    let response = DetailedDeploymentResponse {
+        id: deployment.id,
+        deployment: deployment.metadata.into(),
+        services,
+    };
+    if draining.is_some() {
+        audit.after(&response);
+    }
+    Ok(response.into())
+}
+
+fn detailed_deployment<V>(
+    state: &AdminServiceState<V>,
+    deployment_id: DeploymentId,
+) -> Option<DetailedDeploymentResponse> {
+    state
+        .schema_registry
+        .get_deployment(deployment_id)
+        .map(|(deployment, services)| DetailedDeploymentResponse {
+            id: deployment.id,
+            deployment: deployment.metadata.into(),
+            services,
+        })
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct DeleteDeploymentParams {
     pub force: Option<bool>,
+    pub migrate_invocations: Option<bool>,
 }
 
 /// Discover endpoint and return discovered endpoints.
@@ -212,6 +299,14 @@ pub struct DeleteDeploymentParams {
             style = "simple",
             allow_empty_value = false,
             schema = "bool",
+        ),
+        query(
+            name = "migrate_invocations",
+            description = "If true, together with force, the invocations pinned to the deployment are re-pinned to the most recent other deployment of their service, if it supports their service protocol version. The other invocations are left pinned to the deleted deployment.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "bool",
         )
     ),
     responses(
@@ -231,10 +326,20 @@ pub struct DeleteDeploymentParams {
 )]
 pub async fn delete_deployment<V>(
     State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
     Path(deployment_id): Path<DeploymentId>,
-    Query(DeleteDeploymentParams { force }): Query<DeleteDeploymentParams>,
+    Query(DeleteDeploymentParams {
+        force,
+        migrate_invocations,
+    }): Query<DeleteDeploymentParams>,
 ) -> Result<StatusCode, MetaApiError> {
     if let Some(true) = force {
+        if let Some(before) = detailed_deployment(&state, deployment_id) {
+            audit.before(&before);
+        }
+        if let Some(true) = migrate_invocations {
+            migrate_deployment_invocations(&state, deployment_id).await?;
+        }
         state
             .schema_registry
             .delete_deployment(deployment_id)
@@ -245,3 +350,79 @@ pub async fn delete_deployment<V>(
         Ok(StatusCode::NOT_IMPLEMENTED)
     }
 }
+
+/// Asks every partition to re-pin the invocations of the deployment to the most recent other
+/// deployment of their service, preferring the deployments which are not draining.
+async fn migrate_deployment_invocations<V>(
+    state: &AdminServiceState<V>,
+    deployment_id: DeploymentId,
+) -> Result<(), MetaApiError> {
+    let (_, services) = state
+        .schema_registry
+        .get_deployment(deployment_id)
+        .ok_or_else(|| MetaApiError::DeploymentNotFound(deployment_id))?;
+    let deployments = state.schema_registry.list_deployments();
+    let targets: Vec<_> = services
+        .into_iter()
+        .filter_map(|service| migration_target(deployment_id, service.name, &deployments))
+        .collect();
+
+    let partition_keys: Vec<_> = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .partitions()
+            .map(|(_, partition)| *partition.key_range.start())
+            .collect()
+    });
+
+    info!(
+        restate.deployment.id = %deployment_id,
+        "Migrating the invocations of the deployment to {} other deployments",
+        targets.len()
+    );
+    for partition_key in partition_keys {
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(partition_key),
+                Command::MigrateDeployment(DeploymentMigration {
+                    deployment_id,
+                    targets: targets.clone(),
+                }),
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append deployment migration command to Bifrost: {err}");
+            return Err(MetaApiError::Internal(
+                "Failed sending the deployment migration to the cluster.".to_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn migration_target(
+    deployment_id: DeploymentId,
+    service_name: String,
+    deployments: &[(DeploymentSchema, Vec<(String, ServiceRevision)>)],
+) -> Option<MigrationTarget> {
+    let (target, _) = deployments
+        .iter()
+        .filter(|(deployment, services)| {
+            deployment.id != deployment_id && services.iter().any(|(name, _)| *name == service_name)
+        })
+        .max_by_key(|(deployment, _)| {
+            (
+                !deployment.metadata.draining,
+                deployment.metadata.created_at(),
+            )
+        })?;
+
+    Some(MigrationTarget {
+        service_name: service_name.into(),
+        deployment_id: target.id,
+        supported_protocol_versions: target.metadata.supported_protocol_versions.clone(),
+    })
+}
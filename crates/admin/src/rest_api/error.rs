@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use crate::schema_registry::descriptor::DescriptorError;
 use crate::schema_registry::error::{
     DeploymentError, SchemaError, SchemaRegistryError, ServiceError,
 };
@@ -20,8 +21,10 @@ use okapi_operation::okapi::map;
 use okapi_operation::okapi::openapi3::Responses;
 use okapi_operation::{okapi, Components, ToMediaTypes, ToResponses};
 use restate_core::ShutdownError;
-use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use restate_types::identifiers::{DeploymentId, PartitionId, SubscriptionId};
 use restate_types::invocation::ServiceType;
+use restate_types::logs::LogId;
+use restate_types::nodes_config::ClusterVersion;
 use schemars::JsonSchema;
 use serde::Serialize;
 
@@ -42,8 +45,19 @@ pub enum MetaApiError {
     },
     #[error("The requested subscription '{0}' does not exist")]
     SubscriptionNotFound(SubscriptionId),
+    #[error("The requested log '{0}' does not exist")]
+    LogNotFound(LogId),
+    #[error("The requested partition '{0}' does not exist")]
+    PartitionNotFound(PartitionId),
     #[error("Cannot {0} for service type {1}")]
     UnsupportedOperation(&'static str, ServiceType),
+    #[error("The deployment '{0}' does not support dry-run invocations. Dry-runs require a deployment which accepted the dry-run protocol extension when it was discovered")]
+    DryRunNotSupported(DeploymentId),
+    #[error("Cannot {operation} before all nodes of the cluster are upgraded to the cluster version {required}")]
+    UnsupportedClusterVersion {
+        operation: &'static str,
+        required: ClusterVersion,
+    },
     #[error(transparent)]
     Schema(#[from] SchemaError),
     #[error(transparent)]
@@ -70,10 +84,13 @@ impl IntoResponse for MetaApiError {
             MetaApiError::ServiceNotFound(_)
             | MetaApiError::HandlerNotFound { .. }
             | MetaApiError::DeploymentNotFound(_)
-            | MetaApiError::SubscriptionNotFound(_) => StatusCode::NOT_FOUND,
-            MetaApiError::InvalidField(_, _) | MetaApiError::UnsupportedOperation(_, _) => {
-                StatusCode::BAD_REQUEST
-            }
+            | MetaApiError::SubscriptionNotFound(_)
+            | MetaApiError::LogNotFound(_)
+            | MetaApiError::PartitionNotFound(_) => StatusCode::NOT_FOUND,
+            MetaApiError::InvalidField(_, _)
+            | MetaApiError::UnsupportedOperation(_, _)
+            | MetaApiError::DryRunNotSupported(_) => StatusCode::BAD_REQUEST,
+            MetaApiError::UnsupportedClusterVersion { .. } => StatusCode::CONFLICT,
             MetaApiError::Schema(schema_error) => match schema_error {
                 SchemaError::NotFound(_) => StatusCode::NOT_FOUND,
                 SchemaError::Override(_)
@@ -147,6 +164,21 @@ impl From<SchemaRegistryError> for MetaApiError {
     }
 }
 
+impl From<DescriptorError> for MetaApiError {
+    fn from(value: DescriptorError) -> Self {
+        match value {
+            DescriptorError::SchemaRegistry(err) => err.into(),
+            err @ (DescriptorError::RelativeUri(_) | DescriptorError::InvalidLambdaArn(_)) => {
+                MetaApiError::InvalidField("deployments", err.to_string())
+            }
+            err @ DescriptorError::Redacted(_) => {
+                MetaApiError::InvalidField("spec", err.to_string())
+            }
+            err => MetaApiError::Internal(err.to_string()),
+        }
+    }
+}
+
 impl From<ShutdownError> for MetaApiError {
     fn from(value: ShutdownError) -> Self {
         MetaApiError::Internal(value.to_string())
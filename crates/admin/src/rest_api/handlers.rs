@@ -8,14 +8,26 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use super::create_envelope_header;
 use super::error::*;
 
+use crate::audit::AuditRecord;
+use crate::schema_registry::ModifyServiceChange;
 use crate::state::AdminServiceState;
+use std::sync::Arc;
+
 use axum::extract::{Path, State};
-use axum::Json;
+use axum::{Extension, Json};
+use http::StatusCode;
 use okapi_operation::*;
 use restate_admin_rest_model::handlers::*;
+use restate_errors::warn_it;
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::invocation::{InvocationTarget, ServiceInvocation, ServiceType, Source};
 use restate_types::schema::service::HandlerMetadata;
+use restate_types::service_protocol::ProtocolExtension;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use tracing::warn;
 
 /// List discovered handlers for service
 #[openapi(
@@ -73,3 +85,170 @@ pub async fn get_service_handler<V>(
         }),
     }
 }
+
+/// Modify a handler of a service
+#[openapi(
+    summary = "Modify service handler",
+    description = "Modify the retention of a handler, overriding the retention of its service.",
+    operation_id = "modify_service_handler",
+    tags = "service_handler",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "handler",
+            description = "Handler name.",
+            schema = "std::string::String"
+        )
+    )
+)]
+pub async fn modify_service_handler<V>(
+    State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
+    Path((service_name, handler_name)): Path<(String, String)>,
+    #[request_body(required = true)] Json(modify_service_handler_request): Json<
+        ModifyServiceHandlerRequest,
+    >,
+) -> Result<Json<HandlerMetadata>, MetaApiError> {
+    let changes = ModifyServiceChange::from_handler_request(
+        handler_name.clone(),
+        modify_service_handler_request,
+    );
+    if changes.is_empty() {
+        // No need to do anything
+        return get_service_handler(State(state), Path((service_name, handler_name))).await;
+    }
+
+    if let Some(before) = state
+        .schema_registry
+        .get_service_handler(&service_name, &handler_name)
+    {
+        audit.before(&before);
+    }
+    let service = state
+        .schema_registry
+        .modify_service(service_name.clone(), changes)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    service
+        .handlers
+        .into_iter()
+        .find(|handler| handler.name == handler_name)
+        .inspect(|handler| audit.after(handler))
+        .map(Into::into)
+        .ok_or(MetaApiError::HandlerNotFound {
+            service_name,
+            handler_name,
+        })
+}
+
+/// Simulate an invocation of a handler
+#[openapi(
+    summary = "Simulate invocation",
+    description = "Invoke the handler of a service in dry-run mode. The SDK executes the handler, but skips the external side effects, such as the ones executed within `ctx.run`. Calls to other services fail, while one-way calls and awakeable completions are dropped. The deployment of the service must have accepted the dry-run protocol extension when it was discovered.",
+    operation_id = "simulate_invocation",
+    tags = "service_handler",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "handler",
+            description = "Handler name.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "Json<SimulateInvocationResponse>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn simulate_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path((service_name, handler_name)): Path<(String, String)>,
+    #[request_body(required = true)] Json(SimulateInvocationRequest { argument }): Json<
+        SimulateInvocationRequest,
+    >,
+) -> Result<(StatusCode, Json<SimulateInvocationResponse>), MetaApiError> {
+    let Some(service) = state.schema_registry.get_service(&service_name) else {
+        return Err(MetaApiError::ServiceNotFound(service_name));
+    };
+    if service.ty != ServiceType::Service {
+        // Dry-runs of virtual objects and workflows would still acquire the exclusive lock
+        // of the key, interfering with the real invocations.
+        return Err(MetaApiError::UnsupportedOperation(
+            "simulate invocation",
+            service.ty,
+        ));
+    }
+    if state
+        .schema_registry
+        .get_service_handler(&service_name, &handler_name)
+        .is_none()
+    {
+        return Err(MetaApiError::HandlerNotFound {
+            service_name,
+            handler_name,
+        });
+    }
+
+    // SDKs which didn't accept the dry-run protocol extension ignore the dry-run header
+    let supports_dry_run = state
+        .schema_registry
+        .get_deployment(service.deployment_id)
+        .is_some_and(|(deployment, _)| {
+            deployment
+                .metadata
+                .supports_protocol_extension(ProtocolExtension::DryRun)
+        });
+    if !supports_dry_run {
+        return Err(MetaApiError::DryRunNotSupported(service.deployment_id));
+    }
+
+    let invocation_target = InvocationTarget::service(service_name, handler_name);
+    let invocation_id = InvocationId::generate(&invocation_target, None);
+
+    let mut builder =
+        ServiceInvocation::builder(invocation_id, invocation_target, Source::Internal)
+            .dry_run(true);
+    if let Some(argument) = argument {
+        builder = builder.argument(
+            serde_json::to_vec(&argument).map_err(|e| MetaApiError::Internal(e.to_string()))?,
+        );
+    }
+    let service_invocation = builder
+        .build()
+        .map_err(|e| MetaApiError::Internal(e.to_string()))?;
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(invocation_id.partition_key()),
+            Command::Invoke(service_invocation),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append invocation command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending invocation command to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok((
+            StatusCode::ACCEPTED,
+            SimulateInvocationResponse { invocation_id }.into(),
+        ))
+    }
+}
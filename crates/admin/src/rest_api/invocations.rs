@@ -11,16 +11,22 @@
 use super::error::*;
 use std::sync::Arc;
 
+use crate::audit::AuditRecord;
 use crate::rest_api::create_envelope_header;
 use crate::state::AdminServiceState;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::Extension;
 use okapi_operation::*;
+use restate_core::Metadata;
 use restate_types::identifiers::{InvocationId, WithPartitionKey};
-use restate_types::invocation::{InvocationTermination, PurgeInvocationRequest};
+use restate_types::invocation::{
+    BulkInvocationTermination, InvocationTargetFilter, InvocationTermination,
+    PurgeInvocationRequest, RetryInvocationRequest, TerminationFlavor,
+};
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
-use tracing::warn;
+use tracing::{info, warn};
 
 #[derive(Debug, Default, Deserialize, JsonSchema)]
 pub enum DeletionMode {
@@ -75,6 +81,7 @@ pub struct DeleteInvocationParams {
 )]
 pub async fn delete_invocation<V>(
     State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
     Path(invocation_id): Path<String>,
     Query(DeleteInvocationParams { mode }): Query<DeleteInvocationParams>,
 ) -> Result<StatusCode, MetaApiError> {
@@ -82,7 +89,13 @@ pub async fn delete_invocation<V>(
         .parse::<InvocationId>()
         .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
 
-    let cmd = match mode.unwrap_or_default() {
+    let mode = mode.unwrap_or_default();
+    audit.after(serde_json::json!({
+        "invocation_id": invocation_id.to_string(),
+        "mode": format!("{mode:?}"),
+    }));
+
+    let cmd = match mode {
         DeletionMode::Cancel => {
             Command::TerminateInvocation(InvocationTermination::cancel(invocation_id))
         }
@@ -109,3 +122,216 @@ pub async fn delete_invocation<V>(
         Ok(StatusCode::ACCEPTED)
     }
 }
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TerminateServiceInvocationsParams {
+    pub handler: Option<String>,
+    pub key_prefix: Option<String>,
+    pub mode: Option<DeletionMode>,
+}
+
+/// Terminate the invocations of a service
+#[openapi(
+    summary = "Terminate invocations of a service",
+    description = "Terminate all in-flight and inboxed invocations of the given service, optionally \
+    restricted to a handler and to virtual object or workflow keys starting with a prefix. \
+    Each partition terminates its matching invocations atomically when applying the request. \
+    By default, invocations are gracefully cancelled. Alternatively, they can be killed, see the \
+    delete invocation operation for the difference between the two modes.",
+    operation_id = "terminate_service_invocations",
+    tags = "invocation",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "handler",
+            description = "If set, only terminate invocations of this handler.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "key_prefix",
+            description = "If set, only terminate invocations of virtual objects or workflows whose key starts with this prefix.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "mode",
+            description = "If cancel, it will gracefully terminate the invocations. \
+            If kill, it will terminate the invocations with a hard stop. Purge is not supported.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "DeletionMode",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn terminate_service_invocations<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(service_name): Path<String>,
+    Query(TerminateServiceInvocationsParams {
+        handler,
+        key_prefix,
+        mode,
+    }): Query<TerminateServiceInvocationsParams>,
+) -> Result<StatusCode, MetaApiError> {
+    let flavor = match mode.unwrap_or_default() {
+        DeletionMode::Cancel => TerminationFlavor::Cancel,
+        DeletionMode::Kill => TerminationFlavor::Kill,
+        DeletionMode::Purge => {
+            return Err(MetaApiError::InvalidField(
+                "mode",
+                "purge is not supported when terminating the invocations of a service".to_owned(),
+            ))
+        }
+    };
+
+    let mut filter = InvocationTargetFilter::service(service_name);
+    if let Some(handler) = handler {
+        filter = filter.with_handler_name(handler);
+    }
+    if let Some(key_prefix) = key_prefix {
+        filter = filter.with_key_prefix(key_prefix);
+    }
+
+    // Every partition terminates the matching invocations it owns
+    let partition_keys: Vec<_> = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .partitions()
+            .map(|(_, partition)| *partition.key_range.start())
+            .collect()
+    });
+
+    info!(?filter, ?flavor, "Terminating invocations of service");
+    for partition_key in partition_keys {
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(partition_key),
+                Command::BulkTerminateInvocations(BulkInvocationTermination {
+                    filter: filter.clone(),
+                    flavor,
+                }),
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append bulk invocation termination command to Bifrost: {err}");
+            return Err(MetaApiError::Internal(
+                "Failed sending invocation termination to the cluster.".to_owned(),
+            ));
+        }
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Retry an invocation
+#[openapi(
+    summary = "Retry an invocation",
+    description = "Retry the given invocation right away, without waiting for its next retry attempt \
+    or, if it's suspended, for the completions it's waiting on. The invocation is retried from its \
+    current journal. Invocations that are neither running nor suspended are left unaffected.",
+    operation_id = "retry_invocation",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn retry_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+) -> Result<StatusCode, MetaApiError> {
+    append_retry_invocation(&state, &invocation_id, false).await
+}
+
+/// Restart an invocation
+#[openapi(
+    summary = "Restart an invocation",
+    description = "Restart the given invocation from scratch, truncating its journal to the input \
+    entry and retrying it right away on the latest deployment. State changes, calls and other \
+    side effects of the previous attempts are not rolled back. The restart is ignored if the \
+    invocation is neither running nor suspended, or if some journal entry is still waiting for \
+    its completion.",
+    operation_id = "restart_invocation",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn restart_invocation<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+) -> Result<StatusCode, MetaApiError> {
+    append_retry_invocation(&state, &invocation_id, true).await
+}
+
+async fn append_retry_invocation<V>(
+    state: &AdminServiceState<V>,
+    invocation_id: &str,
+    restart: bool,
+) -> Result<StatusCode, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(invocation_id.partition_key()),
+            Command::RetryInvocation(RetryInvocationRequest {
+                invocation_id,
+                restart,
+            }),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append invocation retry command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending invocation retry to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+
+use crate::state::AdminServiceState;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use okapi_operation::*;
+use restate_admin_rest_model::logs::*;
+use restate_bifrost::{BifrostAdmin, Error as BifrostError};
+use restate_types::logs::metadata::SegmentIndex;
+use restate_types::logs::LogId;
+use restate_types::Version;
+use tracing::{info, warn};
+
+/// Seal the tail segment of a log and extend it with a new segment
+#[openapi(
+    summary = "Seal and extend log",
+    description = "Seal the current tail segment of the log and append a new segment using the given loglet provider. \
+    All subsequent writes go to the new segment, which allows to move a log to a different loglet provider, \
+    e.g. from the local to the replicated loglet, without stopping the partitions writing to it. \
+    The provider is recorded in the logs configuration, so that all future segments of the log use it as well.",
+    operation_id = "seal_and_extend_log",
+    tags = "log",
+    parameters(path(name = "log_id", description = "Log identifier.", schema = "u32")),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "200",
+            description = "OK",
+            content = "Json<SealAndExtendLogResponse>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn seal_and_extend_log<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(log_id): Path<u32>,
+    #[request_body(required = true)] Json(SealAndExtendLogRequest {
+        segment_index,
+        min_version,
+        provider,
+        params,
+    }): Json<SealAndExtendLogRequest>,
+) -> Result<Json<SealAndExtendLogResponse>, MetaApiError> {
+    let log_id = LogId::from(log_id);
+    let admin = BifrostAdmin::new(
+        &state.bifrost,
+        &state.metadata_writer,
+        &state.metadata_store_client,
+    );
+
+    info!(%log_id, %provider, "Sealing and extending log");
+    let sealed_segment = admin
+        .seal_and_extend_chain(
+            log_id,
+            segment_index.map(SegmentIndex::from),
+            min_version.map(Version::from).unwrap_or(Version::MIN),
+            provider,
+            params.into(),
+        )
+        .await
+        .map_err(|err| match err {
+            BifrostError::UnknownLogId(log_id) => MetaApiError::LogNotFound(log_id),
+            err => {
+                warn!(%log_id, "Could not seal and extend log: {err}");
+                MetaApiError::Internal(format!("Failed sealing and extending log: {err}"))
+            }
+        })?;
+
+    Ok(Json(SealAndExtendLogResponse {
+        sealed_segment_index: sealed_segment.segment_index.into(),
+        sealed_provider: sealed_segment.provider,
+        sealed_params: sealed_segment.params.to_string(),
+        tail_lsn: sealed_segment.tail.offset().as_u64(),
+        new_segment_index: sealed_segment.segment_index.next().into(),
+    }))
+}
+
+/// Set the loglet provider of a log
+#[openapi(
+    summary = "Set log provider",
+    description = "Set the loglet provider of the log in the logs configuration. \
+    If the current segment of the log uses a different provider, the cluster controller seals it \
+    and continues the log with a new segment of the given provider. This allows to move a log to \
+    a different loglet provider without choosing the parameters of the new segment.",
+    operation_id = "set_log_provider",
+    tags = "log",
+    parameters(path(name = "log_id", description = "Log identifier.", schema = "u32")),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn set_log_provider<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(log_id): Path<u32>,
+    #[request_body(required = true)] Json(SetLogProviderRequest { provider }): Json<
+        SetLogProviderRequest,
+    >,
+) -> Result<StatusCode, MetaApiError> {
+    let log_id = LogId::from(log_id);
+    let admin = BifrostAdmin::new(
+        &state.bifrost,
+        &state.metadata_writer,
+        &state.metadata_store_client,
+    );
+
+    info!(%log_id, ?provider, "Setting log provider");
+    admin
+        .set_provider(log_id, provider)
+        .await
+        .map_err(|err| match err {
+            BifrostError::UnknownLogId(log_id) => MetaApiError::LogNotFound(log_id),
+            err => {
+                warn!(%log_id, "Could not set log provider: {err}");
+                MetaApiError::Internal(format!("Failed setting log provider: {err}"))
+            }
+        })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
@@ -10,23 +10,33 @@
 
 //! This module implements the Meta API endpoint.
 
+mod cluster_spec;
+mod dead_letters;
+mod debug;
 mod deployments;
 mod error;
 mod handlers;
 mod health;
 mod invocations;
+mod logs;
+mod schedules;
+mod schema_versions;
 mod services;
 mod subscriptions;
 mod version;
 
-use okapi_operation::axum_integration::{delete, get, patch, post};
+use okapi_operation::axum_integration::{delete, get, patch, post, put};
 use okapi_operation::*;
+use restate_core::Metadata;
 use restate_types::identifiers::PartitionKey;
+use restate_types::nodes_config::ClusterVersion;
 use restate_types::schema::subscriptions::SubscriptionValidator;
 use restate_wal_protocol::{Destination, Header, Source};
 
 use crate::state::AdminServiceState;
 
+pub use debug::create_router as create_debug_router;
+
 pub fn create_router<V>(state: AdminServiceState<V>) -> axum::Router<()>
 where
     V: SubscriptionValidator + Send + Sync + Clone + 'static,
@@ -49,7 +59,15 @@ where
             "/deployments/:deployment",
             delete(openapi_handler!(deployments::delete_deployment)),
         )
+        .route(
+            "/deployments/:deployment",
+            patch(openapi_handler!(deployments::modify_deployment)),
+        )
         .route("/services", get(openapi_handler!(services::list_services)))
+        .route(
+            "/openapi/services",
+            get(openapi_handler!(services::get_services_openapi)),
+        )
         .route(
             "/services/:service",
             get(openapi_handler!(services::get_service)),
@@ -66,6 +84,14 @@ where
             "/services/:service/state",
             post(openapi_handler!(services::modify_service_state)),
         )
+        .route(
+            "/services/:service/pause",
+            post(openapi_handler!(services::pause_service)),
+        )
+        .route(
+            "/services/:service/resume",
+            post(openapi_handler!(services::resume_service)),
+        )
         .route(
             "/services/:service/handlers",
             get(openapi_handler!(handlers::list_service_handlers)),
@@ -74,10 +100,70 @@ where
             "/services/:service/handlers/:handler",
             get(openapi_handler!(handlers::get_service_handler)),
         )
+        .route(
+            "/services/:service/handlers/:handler",
+            patch(openapi_handler!(handlers::modify_service_handler)),
+        )
+        .route(
+            "/services/:service/handlers/:handler/simulate",
+            post(openapi_handler!(handlers::simulate_invocation)),
+        )
         .route(
             "/invocations/:invocation_id",
             delete(openapi_handler!(invocations::delete_invocation)),
         )
+        .route(
+            "/invocations/:invocation_id/retry",
+            post(openapi_handler!(invocations::retry_invocation)),
+        )
+        .route(
+            "/invocations/:invocation_id/restart",
+            post(openapi_handler!(invocations::restart_invocation)),
+        )
+        .route(
+            "/services/:service/invocations",
+            delete(openapi_handler!(invocations::terminate_service_invocations)),
+        )
+        .route(
+            "/logs/:log_id/seal-and-extend",
+            post(openapi_handler!(logs::seal_and_extend_log)),
+        )
+        .route(
+            "/logs/:log_id/provider",
+            put(openapi_handler!(logs::set_log_provider)),
+        )
+        .route(
+            "/partitions/:partition_id/dead-letters/:lsn/reinject",
+            post(openapi_handler!(dead_letters::reinject_dead_letter)),
+        )
+        .route(
+            "/schedules",
+            post(openapi_handler!(schedules::create_schedule)),
+        )
+        .route(
+            "/schedules/:schedule_id",
+            delete(openapi_handler!(schedules::delete_schedule)),
+        )
+        .route(
+            "/cluster-spec",
+            get(openapi_handler!(cluster_spec::export_cluster_spec)),
+        )
+        .route(
+            "/cluster-spec",
+            put(openapi_handler!(cluster_spec::apply_cluster_spec)),
+        )
+        .route(
+            "/schema/versions",
+            get(openapi_handler!(schema_versions::list_schema_versions)),
+        )
+        .route(
+            "/schema/versions/diff",
+            get(openapi_handler!(schema_versions::diff_schema_versions)),
+        )
+        .route(
+            "/schema/versions/:version/rollback",
+            post(openapi_handler!(schema_versions::rollback_schema)),
+        )
         .route(
             "/subscriptions",
             post(openapi_handler!(subscriptions::create_subscription)),
@@ -101,7 +187,7 @@ where
         .with_state(state)
 }
 
-fn create_envelope_header(partition_key: PartitionKey) -> Header {
+pub(crate) fn create_envelope_header(partition_key: PartitionKey) -> Header {
     Header {
         source: Source::ControlPlane {},
         dest: Destination::Processor {
@@ -110,3 +196,18 @@ fn create_envelope_header(partition_key: PartitionKey) -> Header {
         },
     }
 }
+
+/// Fails if not every node of the cluster understands the given cluster version yet, e.g. because
+/// the operation writes commands which older nodes cannot apply.
+pub(crate) fn ensure_cluster_version(
+    operation: &'static str,
+    required: ClusterVersion,
+) -> Result<(), error::MetaApiError> {
+    if Metadata::with_current(|m| m.nodes_config_ref().cluster_version()) < required {
+        return Err(error::MetaApiError::UnsupportedClusterVersion {
+            operation,
+            required,
+        });
+    }
+    Ok(())
+}
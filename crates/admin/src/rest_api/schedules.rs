@@ -0,0 +1,213 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::create_envelope_header;
+use super::error::*;
+
+use crate::state::AdminServiceState;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use http::StatusCode;
+use okapi_operation::*;
+use restate_admin_rest_model::schedules::*;
+use restate_core::Metadata;
+use restate_types::identifiers::{InvocationId, ScheduleId, WithPartitionKey};
+use restate_types::invocation::{
+    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
+    WorkflowHandlerType,
+};
+use restate_types::schedule::{CronSchedule, Schedule};
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::time::MillisSinceEpoch;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+use tracing::{info, warn};
+
+/// Create a schedule
+#[openapi(
+    summary = "Create schedule",
+    description = "Create a schedule invoking the given handler at every occurrence of a cron expression. \
+    Schedules can be inspected using the sys_schedule table.",
+    operation_id = "create_schedule",
+    tags = "schedule",
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "201",
+            description = "Created",
+            content = "Json<CreateScheduleResponse>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn create_schedule<V>(
+    State(state): State<AdminServiceState<V>>,
+    #[request_body(required = true)] Json(CreateScheduleRequest {
+        service,
+        handler,
+        key,
+        cron,
+        misfire_policy,
+        argument,
+        headers,
+    }): Json<CreateScheduleRequest>,
+) -> Result<(StatusCode, Json<CreateScheduleResponse>), MetaApiError> {
+    let cron = cron
+        .parse::<CronSchedule>()
+        .map_err(|e| MetaApiError::InvalidField("cron", e.to_string()))?;
+
+    let Some(invocation_target_meta) =
+        Metadata::with_current(|m| m.schema()).resolve_latest_invocation_target(&service, &handler)
+    else {
+        return Err(if state.schema_registry.get_service(&service).is_none() {
+            MetaApiError::ServiceNotFound(service)
+        } else {
+            MetaApiError::HandlerNotFound {
+                service_name: service,
+                handler_name: handler,
+            }
+        });
+    };
+
+    let invocation_target = match (invocation_target_meta.target_ty, key) {
+        (InvocationTargetType::Service, None) => InvocationTarget::service(service, handler),
+        (InvocationTargetType::VirtualObject(handler_ty), Some(key)) => {
+            InvocationTarget::virtual_object(service, key, handler, handler_ty)
+        }
+        // The workflow run handler can be invoked only once per key
+        (InvocationTargetType::Workflow(WorkflowHandlerType::Workflow), _) => {
+            return Err(MetaApiError::InvalidField(
+                "handler",
+                "the workflow run handler cannot be scheduled".to_owned(),
+            ))
+        }
+        (InvocationTargetType::Workflow(handler_ty), Some(key)) => {
+            InvocationTarget::workflow(service, key, handler, handler_ty)
+        }
+        _ => {
+            return Err(MetaApiError::InvalidField(
+                "key",
+                "must be set for virtual object and workflow handlers only".to_owned(),
+            ))
+        }
+    };
+
+    let body = argument
+        .map(|argument| serde_json::to_vec(&argument))
+        .transpose()
+        .map_err(|e| MetaApiError::Internal(e.to_string()))?
+        .unwrap_or_default();
+
+    // The id of the template is ignored, every fired invocation gets its own id
+    let mut invocation_request_header = InvocationRequestHeader::initialize(
+        InvocationId::generate(&invocation_target, None),
+        invocation_target,
+    );
+    invocation_request_header.completion_retention_duration =
+        invocation_target_meta.compute_retention(false);
+    invocation_request_header.headers = headers
+        .iter()
+        .map(|(name, value)| Header::new(name.as_str(), value.as_str()))
+        .collect();
+    invocation_request_header.shared_concurrency_limit =
+        invocation_target_meta.shared_concurrency_limit;
+
+    let schedule = Schedule {
+        id: ScheduleId::generate(&invocation_request_header.target),
+        cron,
+        misfire_policy,
+        invocation: InvocationRequest::new(invocation_request_header, body.into()),
+        created_at: MillisSinceEpoch::now(),
+    };
+    let schedule_id = schedule.id;
+    let Some(next_fire_time) = schedule.first_fire_time() else {
+        return Err(MetaApiError::InvalidField(
+            "cron",
+            "the expression has no upcoming occurrence".to_owned(),
+        ));
+    };
+
+    info!(%schedule_id, cron = %schedule.cron, "Creating schedule");
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(schedule_id.partition_key()),
+            Command::UpsertSchedule(schedule),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append schedule command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending schedule command to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok((
+            StatusCode::CREATED,
+            CreateScheduleResponse {
+                id: schedule_id,
+                next_fire_time: SystemTime::from(next_fire_time).into(),
+            }
+            .into(),
+        ))
+    }
+}
+
+/// Delete a schedule
+#[openapi(
+    summary = "Delete schedule",
+    description = "Delete the given schedule. Invocations already fired by the schedule are not affected.",
+    operation_id = "delete_schedule",
+    tags = "schedule",
+    parameters(path(
+        name = "schedule_id",
+        description = "Schedule identifier.",
+        schema = "std::string::String"
+    )),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "okapi_operation::Empty",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn delete_schedule<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(schedule_id): Path<String>,
+) -> Result<StatusCode, MetaApiError> {
+    let schedule_id = schedule_id
+        .parse::<ScheduleId>()
+        .map_err(|e| MetaApiError::InvalidField("schedule_id", e.to_string()))?;
+
+    info!(%schedule_id, "Deleting schedule");
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(schedule_id.partition_key()),
+            Command::DeleteSchedule(schedule_id),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append schedule deletion command to Bifrost: {err}");
+        Err(MetaApiError::Internal(
+            "Failed sending schedule deletion to the cluster.".to_owned(),
+        ))
+    } else {
+        Ok(StatusCode::ACCEPTED)
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+use crate::schema_registry::Force;
+use crate::state::AdminServiceState;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use okapi_operation::*;
+use restate_admin_rest_model::schema_versions::*;
+use restate_core::Metadata;
+use restate_errors::warn_it;
+use restate_types::schema::Schema;
+use restate_types::Version;
+use serde::Deserialize;
+use std::time::SystemTime;
+use tracing::info;
+
+/// List schema versions
+#[openapi(
+    summary = "List schema versions",
+    description = "List the versions of the schema registry kept in the history, from the newest to the oldest. \
+    Every registration or modification of deployments, services and subscriptions writes a new version.",
+    operation_id = "list_schema_versions",
+    tags = "schema"
+)]
+pub async fn list_schema_versions<V>(
+    State(state): State<AdminServiceState<V>>,
+) -> Result<Json<ListSchemaVersionsResponse>, MetaApiError> {
+    let (current, snapshots) = state.schema_registry.list_schema_versions().await?;
+
+    Ok(ListSchemaVersionsResponse {
+        current_version: current.version.into(),
+        versions: std::iter::once(schema_version_response(&current, None))
+            .chain(snapshots.iter().map(|snapshot| {
+                schema_version_response(
+                    &snapshot.schema,
+                    Some(SystemTime::from(snapshot.superseded_at).into()),
+                )
+            }))
+            .collect(),
+    }
+    .into())
+}
+
+fn schema_version_response(
+    schema: &Schema,
+    superseded_at: Option<humantime::Timestamp>,
+) -> SchemaVersionResponse {
+    SchemaVersionResponse {
+        version: schema.version.into(),
+        superseded_at,
+        rolled_back_to: None,
+        services: schema.services.len(),
+        deployments: schema.deployments.len(),
+        subscriptions: schema.subscriptions.len(),
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiffSchemaVersionsParams {
+    pub from: u32,
+    pub to: Option<u32>,
+}
+
+/// Diff schema versions
+#[openapi(
+    summary = "Diff schema versions",
+    description = "Compare two versions of the schema registry, listing the services, deployments and \
+    subscriptions which were added, removed or modified between them.",
+    operation_id = "diff_schema_versions",
+    tags = "schema",
+    parameters(
+        query(
+            name = "from",
+            description = "Version to compare from.",
+            required = true,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        ),
+        query(
+            name = "to",
+            description = "Version to compare to. Defaults to the current version.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        )
+    )
+)]
+pub async fn diff_schema_versions<V>(
+    State(state): State<AdminServiceState<V>>,
+    Query(DiffSchemaVersionsParams { from, to }): Query<DiffSchemaVersionsParams>,
+) -> Result<Json<SchemaDiffResponse>, MetaApiError> {
+    let to = match to {
+        Some(to) => Version::from(to),
+        None => Metadata::with_current(|m| m.schema_version()),
+    };
+
+    Ok(state
+        .schema_registry
+        .diff_schema_versions(Version::from(from), to)
+        .await?
+        .into())
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RollbackSchemaParams {
+    pub force: Option<bool>,
+}
+
+/// Roll back the schema
+#[openapi(
+    summary = "Roll back schema",
+    description = "Roll back the schema registry to a previous version of the history. The rolled back \
+    schema is written atomically as a new version, so the rollback itself can be rolled back. \
+    Like registering a deployment, a rollback which removes services or handlers, or changes the type of a service, \
+    fails unless `force` is set to `true`. \
+    Invocations already running keep running on the deployment they are pinned to. \
+    Pausing and resuming services requires the dedicated endpoints, which also notify the partitions.",
+    operation_id = "rollback_schema",
+    tags = "schema",
+    parameters(
+        path(
+            name = "version",
+            description = "Version to roll back to.",
+            schema = "u32"
+        ),
+        query(
+            name = "force",
+            description = "If true, the rollback is applied even if it removes services or handlers, or changes the type of services. This might break in-flight invocations, use with caution.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "bool",
+        )
+    )
+)]
+pub async fn rollback_schema<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(version): Path<u32>,
+    Query(RollbackSchemaParams { force }): Query<RollbackSchemaParams>,
+) -> Result<Json<SchemaVersionResponse>, MetaApiError> {
+    let version = Version::from(version);
+    let force = if force.unwrap_or_default() {
+        Force::Yes
+    } else {
+        Force::No
+    };
+
+    info!("Rolling back the schema to version {version}");
+    let schema = state
+        .schema_registry
+        .rollback_schema(version, force)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(SchemaVersionResponse {
+        rolled_back_to: Some(version.into()),
+        ..schema_version_response(&schema, None)
+    }
+    .into())
+}
@@ -10,23 +10,25 @@
 
 use super::create_envelope_header;
 use super::error::*;
+use crate::audit::AuditRecord;
 use crate::schema_registry::ModifyServiceChange;
 use crate::state::AdminServiceState;
 use std::sync::Arc;
 
 use axum::extract::{Path, State};
-use axum::Json;
+use axum::{Extension, Json};
 use bytes::Bytes;
 use http::StatusCode;
 use okapi_operation::*;
 use restate_admin_rest_model::services::ListServicesResponse;
 use restate_admin_rest_model::services::*;
+use restate_core::Metadata;
 use restate_errors::warn_it;
 use restate_types::identifiers::{ServiceId, WithPartitionKey};
 use restate_types::schema::service::ServiceMetadata;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 /// List services
 #[openapi(
@@ -95,6 +97,28 @@ pub async fn get_service_openapi<V>(
         .ok_or_else(|| MetaApiError::ServiceNotFound(service_name))
 }
 
+/// Get the OpenAPI definition of all services
+#[openapi(
+    summary = "Get services OpenAPI",
+    description = "Get the OpenAPI 3.1 contract of all the public services, including the attach and output endpoints of the invocations.",
+    operation_id = "get_services_openapi",
+    tags = "service",
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "200",
+            description = "OpenAPI 3.1 of the services",
+            content = "Json<serde_json::Value>",
+        ),
+        from_type = "MetaApiError",
+    )
+)]
+pub async fn get_services_openapi<V>(
+    State(state): State<AdminServiceState<V>>,
+) -> Result<Json<serde_json::Value>, MetaApiError> {
+    Ok(state.schema_registry.get_services_openapi().into())
+}
+
 /// Modify a service
 #[openapi(
     summary = "Modify a service",
@@ -109,46 +133,116 @@ pub async fn get_service_openapi<V>(
 )]
 pub async fn modify_service<V>(
     State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
     Path(service_name): Path<String>,
-    #[request_body(required = true)] Json(ModifyServiceRequest {
-        public,
-        idempotency_retention,
-        workflow_completion_retention,
-        inactivity_timeout,
-        abort_timeout,
-    }): Json<ModifyServiceRequest>,
+    #[request_body(required = true)] Json(modify_service_request): Json<ModifyServiceRequest>,
 ) -> Result<Json<ServiceMetadata>, MetaApiError> {
-    let mut modify_request = vec![];
-    if let Some(new_public_value) = public {
-        modify_request.push(ModifyServiceChange::Public(new_public_value));
-    }
-    if let Some(new_idempotency_retention) = idempotency_retention {
-        modify_request.push(ModifyServiceChange::IdempotencyRetention(
-            new_idempotency_retention,
-        ));
-    }
-    if let Some(new_workflow_completion_retention) = workflow_completion_retention {
-        modify_request.push(ModifyServiceChange::WorkflowCompletionRetention(
-            new_workflow_completion_retention,
-        ));
-    }
-    if let Some(inactivity_timeout) = inactivity_timeout {
-        modify_request.push(ModifyServiceChange::InactivityTimeout(inactivity_timeout));
-    }
-    if let Some(abort_timeout) = abort_timeout {
-        modify_request.push(ModifyServiceChange::AbortTimeout(abort_timeout));
-    }
+    let modify_request = ModifyServiceChange::from_request(modify_service_request);
 
     if modify_request.is_empty() {
         // No need to do anything
         return get_service(State(state), Path(service_name)).await;
     }
 
+    if let Some(before) = state.schema_registry.get_service(&service_name) {
+        audit.before(&before);
+    }
     let response = state
         .schema_registry
         .modify_service(service_name, modify_request)
         .await
         .inspect_err(|e| warn_it!(e))?;
+    audit.after(&response);
+
+    Ok(response.into())
+}
+
+/// Pause a service
+#[openapi(
+    summary = "Pause a service",
+    description = "Pause the given service. New invocations of a paused service are enqueued \
+    without being executed, until the service is resumed. Invocations already running, or \
+    waiting in the inbox of a virtual object, are not affected.",
+    operation_id = "pause_service",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn pause_service<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(service_name): Path<String>,
+) -> Result<Json<ServiceMetadata>, MetaApiError> {
+    set_service_paused(state, service_name, true).await
+}
+
+/// Resume a service
+#[openapi(
+    summary = "Resume a service",
+    description = "Resume the given paused service, executing the invocations enqueued while it \
+    was paused in the order they were received.",
+    operation_id = "resume_service",
+    tags = "service",
+    parameters(path(
+        name = "service",
+        description = "Fully qualified service name.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn resume_service<V>(
+    State(state): State<AdminServiceState<V>>,
+    Path(service_name): Path<String>,
+) -> Result<Json<ServiceMetadata>, MetaApiError> {
+    set_service_paused(state, service_name, false).await
+}
+
+async fn set_service_paused<V>(
+    state: AdminServiceState<V>,
+    service_name: String,
+    paused: bool,
+) -> Result<Json<ServiceMetadata>, MetaApiError> {
+    let response = state
+        .schema_registry
+        .modify_service(
+            service_name.clone(),
+            vec![ModifyServiceChange::Paused(paused)],
+        )
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    // Every partition pauses or resumes the invocations it owns
+    let partition_keys: Vec<_> = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .partitions()
+            .map(|(_, partition)| *partition.key_range.start())
+            .collect()
+    });
+
+    info!(rpc.service = %service_name, paused, "Changing paused state of service");
+    for partition_key in partition_keys {
+        let command = if paused {
+            Command::PauseService(service_name.as_str().into())
+        } else {
+            Command::ResumeService(service_name.as_str().into())
+        };
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(partition_key),
+                command,
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append service pause command to Bifrost: {err}");
+            return Err(MetaApiError::Internal(
+                "Failed sending the service pause to the cluster.".to_owned(),
+            ));
+        }
+    }
 
     Ok(response.into())
 }
@@ -176,6 +270,7 @@ pub async fn modify_service<V>(
 )]
 pub async fn modify_service_state<V>(
     State(state): State<AdminServiceState<V>>,
+    Extension(audit): Extension<AuditRecord>,
     Path(service_name): Path<String>,
     #[request_body(required = true)] Json(ModifyServiceStateRequest {
         version,
@@ -197,6 +292,14 @@ pub async fn modify_service_state<V>(
         return Err(MetaApiError::ServiceNotFound(service_name));
     }
 
+    // The state values aren't recorded, as they may contain sensitive data
+    audit.after(serde_json::json!({
+        "service": service_name,
+        "key": object_key,
+        "version": version,
+        "keys": new_state.keys().collect::<Vec<_>>(),
+    }));
+
     let service_id = ServiceId::new(service_name, object_key);
 
     let new_state = new_state
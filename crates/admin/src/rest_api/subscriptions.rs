@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use super::ensure_cluster_version;
 use super::error::*;
 use crate::state::AdminServiceState;
 
@@ -21,6 +22,7 @@ use axum::{http, Json};
 use okapi_operation::*;
 use restate_errors::warn_it;
 use restate_types::identifiers::SubscriptionId;
+use restate_types::nodes_config::ClusterVersion;
 
 /// Create subscription.
 #[openapi(
@@ -42,6 +44,8 @@ pub async fn create_subscription<V: SubscriptionValidator>(
     State(state): State<AdminServiceState<V>>,
     #[request_body(required = true)] Json(payload): Json<CreateSubscriptionRequest>,
 ) -> Result<impl axum::response::IntoResponse, MetaApiError> {
+    ensure_source_supported(&payload.source)?;
+
     let subscription = state
         .schema_registry
         .create_subscription(payload.source, payload.sink, payload.options)
@@ -58,6 +62,18 @@ pub async fn create_subscription<V: SubscriptionValidator>(
     ))
 }
 
+/// Fails if the source cannot be understood by every node of the cluster yet. Nodes
+/// deserialize the whole schema, so a single subscription with an unknown source would make
+/// the older nodes fail to load it.
+pub(crate) fn ensure_source_supported(source: &http::Uri) -> Result<(), MetaApiError> {
+    match source.scheme_str() {
+        Some("sqs") => ensure_cluster_version("create SQS subscriptions", ClusterVersion::V1)?,
+        Some("nats") => ensure_cluster_version("create NATS subscriptions", ClusterVersion::V1)?,
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Get subscription.
 #[openapi(
     summary = "Get subscription",
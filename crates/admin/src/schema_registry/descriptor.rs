@@ -0,0 +1,848 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Declarative description of deployments, services and subscriptions which is applied when the
+//! admin service starts. Applying the descriptor converges the schema to it: absent entries are
+//! registered, and existing ones whose options drifted are updated, so that the descriptor can be
+//! applied on every start of every node running the admin role:
+//!
+//! ```yaml
+//! deployments:
+//!   - uri: http://greeter:9080
+//!   - arn: arn:aws:lambda:eu-central-1:1234567890:function:greeter:1
+//! services:
+//!   - name: Greeter
+//!     public: false
+//!     idempotency_retention: 1d
+//! subscriptions:
+//!   - source: kafka://my-cluster/orders
+//!     sink: service://Greeter/greet
+//! ```
+//!
+//! Services are modified after all deployments were registered, hence the descriptor can
+//! configure the services exposed by its own deployments. All the changes are written at once in
+//! a single version of the schema, and nothing is written if the schema already matches.
+//!
+//! The descriptor of a running cluster can be exported with [`StaticDescriptor::from_schema`],
+//! which is how the admin API exposes the logical configuration of the cluster as a declarative
+//! spec that can be applied again. Secrets, i.e. the additional headers of the deployments and the
+//! secret options of the subscriptions, are exported as [`REDACTED`]. Applying a redacted value keeps the value currently registered.
+
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use http::uri::Scheme;
+use http::{HeaderName, HeaderValue, Uri};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tracing::{debug, info, warn};
+
+use restate_admin_rest_model::handlers::ModifyServiceHandlerRequest;
+use restate_admin_rest_model::services::ModifyServiceRequest;
+use restate_core::metadata_store::{ReadError, ReadModifyWriteError};
+use restate_core::Metadata;
+use restate_serde_util::{SerdeableHeaderHashMap, REDACTED};
+use restate_service_client::{Endpoint, LambdaInvokeOptions};
+use restate_service_protocol::discovery::DiscoverEndpoint;
+use restate_types::identifiers::{InvalidLambdaARN, LambdaARN};
+use restate_types::metadata_store::keys::SCHEMA_INFORMATION_KEY;
+use restate_types::schema::deployment::{
+    Deployment, DeploymentMetadata, DeploymentResolver, DeploymentType,
+};
+use restate_types::schema::service::ServiceSchemas;
+use restate_types::schema::subscriptions::{
+    is_secret_option, ListSubscriptionFilter, Subscription, SubscriptionResolver,
+    SubscriptionValidator,
+};
+use restate_types::schema::Schema;
+
+use super::error::{SchemaError, SchemaRegistryError};
+use super::updater::SchemaUpdater;
+use super::{ModifyServiceChange, SchemaRegistry};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DescriptorError {
+    #[error("cannot read the descriptor file '{}': {1}", .0.display())]
+    Read(PathBuf, #[source] std::io::Error),
+    #[error("cannot parse the descriptor file '{}': {1}", .0.display())]
+    Parse(PathBuf, #[source] serde_yaml::Error),
+    #[error("the uri '{0}' of the deployment is not absolute, only absolute URIs can be used")]
+    RelativeUri(Uri),
+    #[error("invalid lambda arn of the deployment: {0}")]
+    InvalidLambdaArn(#[from] InvalidLambdaARN),
+    #[error("{0} is redacted, but there is no registered value to keep")]
+    Redacted(String),
+    #[error("cannot read the schema: {0}")]
+    ReadSchema(#[from] ReadError),
+    #[error(transparent)]
+    SchemaRegistry(#[from] SchemaRegistryError),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StaticDescriptor {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deployments: Vec<DeploymentDescriptor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<ServiceDescriptor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscriptions: Vec<SubscriptionDescriptor>,
+}
+
+impl StaticDescriptor {
+    /// Reads and validates the descriptor file.
+    pub fn load(path: &Path) -> Result<Self, DescriptorError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| DescriptorError::Read(path.to_owned(), err))?;
+        let descriptor: StaticDescriptor = serde_yaml::from_str(&content)
+            .map_err(|err| DescriptorError::Parse(path.to_owned(), err))?;
+        descriptor.validate()?;
+
+        Ok(descriptor)
+    }
+
+    /// Checks that the endpoints of the deployments can be discovered.
+    pub fn validate(&self) -> Result<(), DescriptorError> {
+        for deployment in &self.deployments {
+            deployment.discover_endpoint()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deployments.is_empty() && self.services.is_empty() && self.subscriptions.is_empty()
+    }
+
+    /// Describes the deployments, services and subscriptions of the schema. Entries are sorted,
+    /// so that exporting the same schema twice yields the same document. Service options are
+    /// described only if set, and handler options only if they override the ones of the service.
+    pub fn from_schema(schema: &Schema) -> Self {
+        let mut deployments: Vec<_> = schema
+            .get_deployments()
+            .iter()
+            .map(|(deployment, _)| DeploymentDescriptor::from(deployment))
+            .collect();
+        deployments.sort_by_cached_key(DeploymentDescriptor::normalized_address);
+
+        let mut services: Vec<_> = schema
+            .services
+            .iter()
+            .map(|(name, service)| ServiceDescriptor::from_schemas(name, service))
+            .collect();
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut subscriptions: Vec<_> = schema
+            .list_subscriptions(&[])
+            .iter()
+            .filter_map(|subscription| {
+                SubscriptionDescriptor::try_from(subscription)
+                    .inspect_err(|err| {
+                        warn!(
+                            restate.subscription.id = %subscription.id(),
+                            "Cannot describe subscription: {err}"
+                        )
+                    })
+                    .ok()
+            })
+            .collect();
+        subscriptions.sort_by_cached_key(|subscription| {
+            (
+                subscription.source.to_string(),
+                subscription.sink.to_string(),
+            )
+        });
+
+        Self {
+            deployments,
+            services,
+            subscriptions,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DeploymentDescriptor {
+    Http {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        uri: Uri,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        additional_headers: Option<SerdeableHeaderHashMap>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        use_http_11: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        concurrency_limit: Option<NonZeroU32>,
+    },
+    Lambda {
+        arn: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        assume_role_arn: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        region: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        additional_headers: Option<SerdeableHeaderHashMap>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        concurrency_limit: Option<NonZeroU32>,
+    },
+}
+
+impl From<&Deployment> for DeploymentDescriptor {
+    /// Describes the deployment with its secrets redacted.
+    fn from(deployment: &Deployment) -> Self {
+        Self::describe(&deployment.metadata, true)
+    }
+}
+
+impl DeploymentDescriptor {
+    fn describe(metadata: &DeploymentMetadata, redact: bool) -> Self {
+        let delivery_options = &metadata.delivery_options;
+        let additional_headers = (!delivery_options.additional_headers.is_empty()).then(|| {
+            let mut headers = delivery_options.additional_headers.clone();
+            if redact {
+                for value in headers.values_mut() {
+                    *value = HeaderValue::from_static(REDACTED);
+                }
+            }
+            headers.into()
+        });
+
+        match &metadata.ty {
+            DeploymentType::Http {
+                address,
+                http_version,
+                ..
+            } => DeploymentDescriptor::Http {
+                uri: address.clone(),
+                additional_headers,
+                use_http_11: *http_version == http::Version::HTTP_11,
+                concurrency_limit: delivery_options.concurrency_limit,
+            },
+            DeploymentType::Lambda {
+                arn,
+                assume_role_arn,
+                region,
+            } => DeploymentDescriptor::Lambda {
+                arn: arn.to_string(),
+                assume_role_arn: assume_role_arn.as_ref().map(ToString::to_string),
+                region: region.as_ref().map(ToString::to_string),
+                additional_headers,
+                concurrency_limit: delivery_options.concurrency_limit,
+            },
+        }
+    }
+
+    /// Replaces the redacted values with the ones of the registered deployment.
+    fn resolve_redacted(
+        &self,
+        registered: Option<&DeploymentMetadata>,
+    ) -> Result<Self, DescriptorError> {
+        let mut resolved = self.clone();
+        let (DeploymentDescriptor::Http {
+            additional_headers, ..
+        }
+        | DeploymentDescriptor::Lambda {
+            additional_headers, ..
+        }) = &mut resolved;
+
+        if let Some(headers) = additional_headers.take() {
+            let mut headers: HashMap<HeaderName, HeaderValue> = headers.into();
+            for (name, value) in headers.iter_mut() {
+                if *value == REDACTED {
+                    *value = registered
+                        .and_then(|metadata| metadata.delivery_options.additional_headers.get(name))
+                        .cloned()
+                        .ok_or_else(|| {
+                            DescriptorError::Redacted(format!(
+                                "the header '{name}' of the deployment '{}'",
+                                self.normalized_address()
+                            ))
+                        })?;
+                }
+            }
+            *additional_headers = (!headers.is_empty()).then(|| headers.into());
+        }
+
+        Ok(resolved)
+    }
+
+    fn discover_endpoint(&self) -> Result<DiscoverEndpoint, DescriptorError> {
+        Ok(match self {
+            DeploymentDescriptor::Http {
+                uri,
+                additional_headers,
+                use_http_11,
+                ..
+            } => {
+                if uri.scheme().is_none() || uri.authority().is_none() {
+                    return Err(DescriptorError::RelativeUri(uri.clone()));
+                }
+
+                let http_version = if *use_http_11 {
+                    Some(http::Version::HTTP_11)
+                } else if uri.scheme() == Some(&Scheme::HTTPS) {
+                    // ALPN will sort this out
+                    None
+                } else {
+                    // By default, we use h2c on HTTP
+                    Some(http::Version::HTTP_2)
+                };
+
+                DiscoverEndpoint::new(
+                    Endpoint::Http(uri.clone(), http_version),
+                    additional_headers.clone().unwrap_or_default().into(),
+                )
+            }
+            DeploymentDescriptor::Lambda {
+                arn,
+                assume_role_arn,
+                region,
+                additional_headers,
+                ..
+            } => DiscoverEndpoint::new(
+                Endpoint::Lambda(
+                    arn.parse::<LambdaARN>()?,
+                    LambdaInvokeOptions {
+                        assume_role_arn: assume_role_arn.clone().map(Into::into),
+                        region: region.clone().map(Into::into),
+                    },
+                ),
+                additional_headers.clone().unwrap_or_default().into(),
+            ),
+        })
+    }
+
+    fn concurrency_limit(&self) -> Option<NonZeroU32> {
+        match self {
+            DeploymentDescriptor::Http {
+                concurrency_limit, ..
+            }
+            | DeploymentDescriptor::Lambda {
+                concurrency_limit, ..
+            } => *concurrency_limit,
+        }
+    }
+
+    /// Address identifying the deployment, in the same form as
+    /// [`restate_types::schema::deployment::DeploymentType::normalized_address`].
+    fn normalized_address(&self) -> String {
+        match self {
+            DeploymentDescriptor::Http { uri, .. } => format!(
+                "{}{}",
+                uri.authority().map(|a| a.as_str()).unwrap_or_default(),
+                uri.path()
+            ),
+            DeploymentDescriptor::Lambda { arn, .. } => arn
+                .parse::<LambdaARN>()
+                .map(|arn| arn.to_string())
+                .unwrap_or_else(|_| arn.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDescriptor {
+    pub name: String,
+    #[serde(flatten)]
+    pub options: ModifyServiceRequest,
+}
+
+impl ServiceDescriptor {
+    fn from_schemas(name: &str, service: &ServiceSchemas) -> Self {
+        let handlers = service
+            .handlers
+            .iter()
+            .filter(|(_, handler)| {
+                handler.idempotency_retention.is_some()
+                    || handler.completion_retention.is_some()
+                    || handler.retry_policy.is_some()
+                    || handler.execution_timeout.is_some()
+            })
+            .map(|(handler_name, handler)| {
+                (
+                    handler_name.clone(),
+                    ModifyServiceHandlerRequest {
+                        idempotency_retention: handler.idempotency_retention,
+                        completion_retention: handler.completion_retention,
+                        retry_policy: handler.retry_policy.clone(),
+                        execution_timeout: handler.execution_timeout,
+                    },
+                )
+            })
+            .collect();
+
+        ServiceDescriptor {
+            name: name.to_owned(),
+            options: ModifyServiceRequest {
+                public: Some(service.location.public),
+                allowed_callers: service.allowed_callers.clone(),
+                idempotency_retention: Some(service.idempotency_retention),
+                workflow_completion_retention: service.workflow_completion_retention,
+                completion_retention: service.completion_retention,
+                handlers,
+                inactivity_timeout: service.inactivity_timeout,
+                abort_timeout: service.abort_timeout,
+                mirroring: service.mirroring.clone(),
+                routing: service.routing.clone(),
+                shared_handler_concurrency: service.shared_handler_concurrency.map(NonZeroU32::get),
+                concurrency_limit: service.concurrency_limit.map(NonZeroU32::get),
+                retry_policy: service.retry_policy.clone(),
+                execution_timeout: service.execution_timeout,
+            },
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionDescriptor {
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub source: Uri,
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub sink: Uri,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<BTreeMap<String, String>>,
+}
+
+impl TryFrom<&Subscription> for SubscriptionDescriptor {
+    type Error = http::uri::InvalidUri;
+
+    /// Describes the subscription with its secret options redacted.
+    fn try_from(subscription: &Subscription) -> Result<Self, Self::Error> {
+        Ok(SubscriptionDescriptor {
+            source: subscription.source().to_string().parse()?,
+            sink: subscription.sink().to_string().parse()?,
+            options: (!subscription.metadata().is_empty()).then(|| {
+                subscription
+                    .metadata()
+                    .iter()
+                    .map(|(key, value)| {
+                        if is_secret_option(key) {
+                            (key.clone(), REDACTED.to_owned())
+                        } else {
+                            (key.clone(), value.clone())
+                        }
+                    })
+                    .collect()
+            }),
+        })
+    }
+}
+
+impl SubscriptionDescriptor {
+    /// Options of the subscription, with the redacted values replaced by the ones of the
+    /// registered subscription.
+    fn resolve_options(
+        &self,
+        registered: Option<&Subscription>,
+    ) -> Result<HashMap<String, String>, DescriptorError> {
+        let mut options = HashMap::from_iter(self.options.clone().unwrap_or_default());
+        for (key, value) in options.iter_mut() {
+            if *value == REDACTED {
+                *value = registered
+                    .and_then(|subscription| subscription.metadata().get(key))
+                    .cloned()
+                    .ok_or_else(|| {
+                        DescriptorError::Redacted(format!(
+                            "the option '{key}' of the subscription from '{}' to '{}'",
+                            self.source, self.sink
+                        ))
+                    })?;
+            }
+        }
+        Ok(options)
+    }
+}
+
+impl<V> SchemaRegistry<V> {
+    /// Describes the deployments, services and subscriptions of the current schema.
+    pub fn export_descriptor(&self) -> StaticDescriptor {
+        StaticDescriptor::from_schema(&Metadata::with_current(|m| m.schema()))
+    }
+}
+
+/// Outcome of the descriptor application, other than the new schema.
+enum ApplyError {
+    Unchanged,
+    Failed(DescriptorError),
+}
+
+impl From<DescriptorError> for ApplyError {
+    fn from(err: DescriptorError) -> Self {
+        ApplyError::Failed(err)
+    }
+}
+
+impl From<SchemaError> for ApplyError {
+    fn from(err: SchemaError) -> Self {
+        ApplyError::Failed(SchemaRegistryError::Schema(err).into())
+    }
+}
+
+impl<V> SchemaRegistry<V>
+where
+    V: SubscriptionValidator,
+{
+    /// Converges the schema to the descriptor. The endpoints of the deployments which are absent
+    /// or whose options drifted are discovered first, then all the changes are applied at once to
+    /// the latest schema, and written as a single new version of it. Nothing is written if the
+    /// schema already matches the descriptor, so applying the same descriptor multiple times is
+    /// a no-op.
+    ///
+    /// Drifted deployments are updated in place, keeping their services, and drifted
+    /// subscriptions are replaced keeping their id. Options which are not part of the descriptor
+    /// are left untouched.
+    pub async fn apply_descriptor(
+        &self,
+        descriptor: &StaticDescriptor,
+    ) -> Result<(), DescriptorError> {
+        let schema: Schema = self
+            .metadata_store_client
+            .get(SCHEMA_INFORMATION_KEY.clone())
+            .await?
+            .unwrap_or_default();
+        let registered_deployments = schema.get_deployments();
+
+        let mut discovered = Vec::with_capacity(descriptor.deployments.len());
+        for deployment in &descriptor.deployments {
+            let address = deployment.normalized_address();
+            let registered = registered_deployments
+                .iter()
+                .map(|(registered, _)| &registered.metadata)
+                .find(|registered| registered.ty.normalized_address() == address);
+            let deployment = deployment.resolve_redacted(registered)?;
+
+            if registered.is_some_and(|registered| {
+                DeploymentDescriptor::describe(registered, false) == deployment
+            }) {
+                debug!(
+                    "Deployment '{}' of the descriptor is already registered",
+                    address
+                );
+                continue;
+            }
+
+            discovered.push((
+                address,
+                self.discover_deployment(
+                    deployment.discover_endpoint()?,
+                    deployment.concurrency_limit(),
+                )
+                .await?,
+            ));
+        }
+
+        let mut applied_changes = vec![];
+        let result = self
+            .update_schema(|schema: Option<Schema>| -> Result<Schema, ApplyError> {
+                applied_changes.clear();
+                let schema = schema.unwrap_or_default();
+                let version = schema.version;
+
+                let registered_ids: Vec<_> = discovered
+                    .iter()
+                    .map(|(_, (metadata, _))| {
+                        schema
+                            .find_existing_deployment_by_endpoint(&metadata.ty)
+                            .map(|(id, _)| *id)
+                    })
+                    .collect();
+                let subscriptions = descriptor
+                    .subscriptions
+                    .iter()
+                    .map(|subscription| {
+                        let registered = schema
+                            .list_subscriptions(&[
+                                ListSubscriptionFilter::ExactMatchSource(
+                                    subscription.source.to_string(),
+                                ),
+                                ListSubscriptionFilter::ExactMatchSink(
+                                    subscription.sink.to_string(),
+                                ),
+                            ])
+                            .into_iter()
+                            .next();
+                        let options = subscription.resolve_options(registered.as_ref())?;
+                        Ok((subscription, registered, options))
+                    })
+                    .collect::<Result<Vec<_>, DescriptorError>>()?;
+
+                let mut updater =
+                    SchemaUpdater::new(schema, self.experimental_feature_kafka_ingress_next);
+
+                for ((address, (metadata, services)), registered) in
+                    discovered.iter().zip(registered_ids)
+                {
+                    if let Some(deployment_id) = registered {
+                        updater.update_deployment_endpoint(deployment_id, metadata.clone())?;
+                        applied_changes.push(format!("updated deployment '{address}'"));
+                    } else {
+                        updater.add_deployment(None, metadata.clone(), services.clone(), false)?;
+                        applied_changes.push(format!("registered deployment '{address}'"));
+                    }
+                }
+
+                for service in &descriptor.services {
+                    let changes = ModifyServiceChange::from_request(service.options.clone());
+                    if !changes.is_empty() {
+                        updater.modify_service(service.name.clone(), changes)?;
+                    }
+                }
+
+                for (subscription, registered, options) in subscriptions {
+                    let id = match registered {
+                        Some(registered)
+                            if options.iter().all(|(key, value)| {
+                                registered.metadata().get(key) == Some(value)
+                            }) =>
+                        {
+                            continue;
+                        }
+                        Some(registered) => {
+                            updater.remove_subscription(registered.id());
+                            applied_changes.push(format!(
+                                "updated subscription from '{}' to '{}'",
+                                subscription.source, subscription.sink
+                            ));
+                            Some(registered.id())
+                        }
+                        None => {
+                            applied_changes.push(format!(
+                                "created subscription from '{}' to '{}'",
+                                subscription.source, subscription.sink
+                            ));
+                            None
+                        }
+                    };
+                    updater.add_subscription(
+                        id,
+                        subscription.source.clone(),
+                        subscription.sink.clone(),
+                        Some(options),
+                        &self.subscription_validator,
+                    )?;
+                }
+
+                let schema = updater.into_inner();
+                if schema.version == version {
+                    return Err(ApplyError::Unchanged);
+                }
+                Ok(schema)
+            })
+            .await;
+
+        match result {
+            Ok(schema) => {
+                info!(
+                    "Applied the descriptor in schema version '{}': {}",
+                    schema.version,
+                    applied_changes.join(", ")
+                );
+                self.publish_schema(schema).await?;
+            }
+            Err(ReadModifyWriteError::FailedOperation(ApplyError::Unchanged)) => {
+                debug!("The schema already matches the descriptor");
+            }
+            Err(ReadModifyWriteError::FailedOperation(ApplyError::Failed(err))) => return Err(err),
+            Err(ReadModifyWriteError::ReadWrite(err)) => {
+                return Err(SchemaRegistryError::Internal(err.to_string()).into())
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::schema::subscriptions::{
+        EventReceiverServiceType, Sink, Source, WEBHOOK_SECRET_OPTION,
+    };
+
+    #[test]
+    fn parse_descriptor() {
+        let descriptor: StaticDescriptor = serde_yaml::from_str(
+            r#"
+deployments:
+  - uri: http://greeter:9080/
+    additional_headers:
+      x-token: secret
+  - arn: arn:aws:lambda:eu-central-1:1234567890:function:greeter:1
+    concurrency_limit: 100
+services:
+  - name: Greeter
+    public: false
+    idempotency_retention: 1d
+subscriptions:
+  - source: kafka://my-cluster/orders
+    sink: service://Greeter/greet
+    options:
+      auto.offset.reset: earliest
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(descriptor.deployments.len(), 2);
+        assert!(matches!(
+            &descriptor.deployments[0],
+            DeploymentDescriptor::Http { use_http_11: false, additional_headers: Some(headers), .. } if !headers.is_empty()
+        ));
+        assert_eq!(
+            descriptor.deployments[0].normalized_address(),
+            "greeter:9080/"
+        );
+        assert!(matches!(
+            &descriptor.deployments[1],
+            DeploymentDescriptor::Lambda { .. }
+        ));
+        descriptor.deployments[1].discover_endpoint().unwrap();
+        assert_eq!(
+            descriptor.deployments[1].concurrency_limit(),
+            NonZeroU32::new(100)
+        );
+
+        assert_eq!(descriptor.services[0].name, "Greeter");
+        assert_eq!(descriptor.services[0].options.public, Some(false));
+        assert_eq!(
+            ModifyServiceChange::from_request(descriptor.services[0].options.clone()).len(),
+            2
+        );
+
+        assert_eq!(
+            descriptor.subscriptions[0].sink.to_string(),
+            "service://Greeter/greet"
+        );
+    }
+
+    #[test]
+    fn serialized_descriptor_can_be_parsed() {
+        let yaml = r#"
+deployments:
+  - uri: http://greeter:9080/
+    use_http_11: true
+  - arn: arn:aws:lambda:eu-central-1:1234567890:function:greeter:1
+services:
+  - name: Greeter
+    public: true
+    idempotency_retention: 1d
+    handlers:
+      greet:
+        completion_retention: 1h
+subscriptions:
+  - source: kafka://my-cluster/orders
+    sink: service://Greeter/greet
+"#;
+        let descriptor: StaticDescriptor = serde_yaml::from_str(yaml).unwrap();
+        let serialized = serde_yaml::to_string(&descriptor).unwrap();
+
+        // unset options are omitted, so that the exported document only contains the set ones
+        assert!(!serialized.contains("null"));
+        assert!(!serialized.contains("options"));
+
+        let parsed: StaticDescriptor = serde_yaml::from_str(&serialized).unwrap();
+        assert!(matches!(
+            &parsed.deployments[0],
+            DeploymentDescriptor::Http {
+                use_http_11: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &parsed.deployments[1],
+            DeploymentDescriptor::Lambda { .. }
+        ));
+        assert_eq!(parsed.services[0].options.public, Some(true));
+        assert_eq!(
+            ModifyServiceChange::from_request(parsed.services[0].options.clone()).len(),
+            3
+        );
+        assert_eq!(
+            parsed.subscriptions[0].source.to_string(),
+            "kafka://my-cluster/orders"
+        );
+    }
+
+    #[test]
+    fn export_redacts_deployment_secrets() {
+        let mut deployment = Deployment::mock_with_uri("http://greeter:9080/");
+        deployment.metadata.delivery_options.additional_headers = HashMap::from([(
+            HeaderName::from_static("x-token"),
+            HeaderValue::from_static("secret"),
+        )]);
+        let exported = DeploymentDescriptor::from(&deployment);
+        assert!(matches!(
+            &exported,
+            DeploymentDescriptor::Http { additional_headers: Some(headers), .. }
+                if HashMap::from(headers.clone())[&HeaderName::from_static("x-token")] == REDACTED
+        ));
+
+        // Applying the exported descriptor keeps the registered values
+        assert_eq!(
+            exported
+                .resolve_redacted(Some(&deployment.metadata))
+                .unwrap(),
+            DeploymentDescriptor::describe(&deployment.metadata, false)
+        );
+        assert!(matches!(
+            exported.resolve_redacted(None),
+            Err(DescriptorError::Redacted(_))
+        ));
+    }
+
+    #[test]
+    fn export_redacts_subscription_secrets() {
+        let subscription = Subscription::new(
+            Default::default(),
+            Source::Kafka {
+                cluster: "my-cluster".to_owned(),
+                topic: "orders".to_owned(),
+            },
+            Sink::DeprecatedService {
+                name: "Greeter".to_owned(),
+                handler: "greet".to_owned(),
+                ty: EventReceiverServiceType::Service,
+            },
+            HashMap::from([
+                ("sasl.password".to_owned(), "password".to_owned()),
+                (WEBHOOK_SECRET_OPTION.to_owned(), "secret".to_owned()),
+                ("auto.offset.reset".to_owned(), "earliest".to_owned()),
+            ]),
+        );
+
+        let exported = SubscriptionDescriptor::try_from(&subscription).unwrap();
+        let options = exported.options.as_ref().unwrap();
+        assert_eq!(options["sasl.password"], REDACTED);
+        assert_eq!(options[WEBHOOK_SECRET_OPTION], REDACTED);
+        assert_eq!(options["auto.offset.reset"], "earliest");
+
+        assert_eq!(
+            &exported.resolve_options(Some(&subscription)).unwrap(),
+            subscription.metadata()
+        );
+        assert!(matches!(
+            exported.resolve_options(None),
+            Err(DescriptorError::Redacted(_))
+        ));
+    }
+
+    #[test]
+    fn reject_relative_deployment_uri() {
+        let descriptor: StaticDescriptor =
+            serde_yaml::from_str("deployments:\n  - uri: /greeter\n").unwrap();
+        assert!(matches!(
+            descriptor.deployments[0].discover_endpoint(),
+            Err(DescriptorError::RelativeUri(_))
+        ));
+    }
+}
@@ -81,6 +81,9 @@ pub enum ServiceError {
     #[error("detected a new service '{0}' revision with a service type different from the previous revision. Service type cannot be changed across revisions")]
     #[code(restate_errors::META0006)]
     DifferentType(ServiceName),
+    #[error("the service '{0}' would be removed")]
+    #[code(restate_errors::META0006)]
+    RemovedService(ServiceName),
     #[error("the service '{0}' already exists but the new revision removed the handlers {1:?}")]
     #[code(restate_errors::META0006)]
     RemovedHandlers(ServiceName, Vec<String>),
@@ -102,20 +105,53 @@ pub enum ServiceError {
         #[source]
         error: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("the protobuf message for {service}/{handler} {position} is invalid: {error}")]
+    #[code(unknown)]
+    BadProtobufMessage {
+        service: String,
+        handler: String,
+        position: &'static str,
+        #[source]
+        error: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
+    #[error("mirroring requests for service type {0} is unsupported")]
+    #[code(unknown)]
+    CannotMirror(ServiceType),
+    #[error("the mirroring fraction must be between 0 and 1, but was {0}")]
+    #[code(unknown)]
+    BadMirroringFraction(f64),
+    #[error("cannot mirror requests to deployment {0}: {1}")]
+    #[code(unknown)]
+    BadMirroringDeployment(DeploymentId, &'static str),
+    #[error("the routing percentage must be between 0 and 100, but was {0}")]
+    #[code(unknown)]
+    BadRoutingPercentage(f64),
+    #[error("cannot route invocations to deployment {0}: {1}")]
+    #[code(unknown)]
+    BadRoutingDeployment(DeploymentId, &'static str),
+    #[error("limiting the concurrency of shared handlers for service type {0} is unsupported")]
+    #[code(unknown)]
+    CannotLimitSharedHandlerConcurrency(ServiceType),
 }
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
 #[code(restate_errors::META0009)]
 pub enum SubscriptionError {
     #[error(
-        "invalid source URI '{0}': must have a scheme segment, with supported schemes: [kafka]."
+        "invalid source URI '{0}': must have a scheme segment, with supported schemes: [kafka, sqs, nats, webhook]."
     )]
     InvalidSourceScheme(Uri),
     #[error("invalid source URI '{0}': source URI of Kafka type must have a authority segment containing the cluster name.")]
     InvalidKafkaSourceAuthority(Uri),
+    #[error("invalid source URI '{0}': source URI of SQS type must be in the format sqs://<region>/<account_id>/<queue_name>.")]
+    InvalidSqsSource(Uri),
+    #[error("invalid source URI '{0}': source URI of NATS type must be in the format nats://<server>/<stream_name>.")]
+    InvalidNatsSource(Uri),
+    #[error("invalid source URI '{0}': source URI of webhook type must be in the format webhook://<provider>, with supported providers: [stripe, github, hmac].")]
+    InvalidWebhookSource(Uri),
 
     #[error(
         "invalid sink URI '{0}': must have a scheme segment, with supported schemes: [service]."
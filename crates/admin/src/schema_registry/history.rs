@@ -0,0 +1,178 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_admin_rest_model::schema_versions::{ModifiedServiceResponse, SchemaDiffResponse};
+use restate_types::schema::Schema;
+
+/// Computes the changes to apply to `from` to obtain `to`.
+pub(super) fn diff_schemas(from: &Schema, to: &Schema) -> SchemaDiffResponse {
+    let mut diff = SchemaDiffResponse {
+        from_version: from.version.into(),
+        to_version: to.version.into(),
+        ..Default::default()
+    };
+
+    for (name, to_service) in &to.services {
+        let Some(from_service) = from.services.get(name) else {
+            diff.added_services.push(name.clone());
+            continue;
+        };
+        // The service metadata exposes the whole configuration of the service
+        let modified = from_service.revision != to_service.revision
+            || serde_json::to_value(from_service.as_service_metadata(name.clone())).ok()
+                != serde_json::to_value(to_service.as_service_metadata(name.clone())).ok();
+        if modified {
+            diff.modified_services.push(ModifiedServiceResponse {
+                name: name.clone(),
+                from_revision: from_service.revision,
+                to_revision: to_service.revision,
+                from_deployment_id: from_service.location.latest_deployment,
+                to_deployment_id: to_service.location.latest_deployment,
+            });
+        }
+    }
+    diff.removed_services = from
+        .services
+        .keys()
+        .filter(|name| !to.services.contains_key(*name))
+        .cloned()
+        .collect();
+
+    diff.added_deployments = to
+        .deployments
+        .keys()
+        .filter(|id| !from.deployments.contains_key(*id))
+        .copied()
+        .collect();
+    diff.removed_deployments = from
+        .deployments
+        .keys()
+        .filter(|id| !to.deployments.contains_key(*id))
+        .copied()
+        .collect();
+
+    diff.added_subscriptions = to
+        .subscriptions
+        .keys()
+        .filter(|id| !from.subscriptions.contains_key(*id))
+        .copied()
+        .collect();
+    diff.removed_subscriptions = from
+        .subscriptions
+        .keys()
+        .filter(|id| !to.subscriptions.contains_key(*id))
+        .copied()
+        .collect();
+
+    // Stable output
+    diff.added_services.sort();
+    diff.removed_services.sort();
+    diff.modified_services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema_registry::updater::SchemaUpdater;
+    use crate::schema_registry::ModifyServiceChange;
+    use restate_test_util::assert_eq;
+    use restate_types::endpoint_manifest;
+    use restate_types::schema::deployment::Deployment;
+
+    fn service(name: &str) -> endpoint_manifest::Service {
+        endpoint_manifest::Service {
+            documentation: None,
+            ty: endpoint_manifest::ServiceType::Service,
+            name: name.parse().unwrap(),
+            handlers: vec![endpoint_manifest::Handler {
+                documentation: None,
+                name: "greet".parse().unwrap(),
+                ty: None,
+                input: None,
+                output: None,
+                metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
+                workflow_completion_retention: None,
+            }],
+            metadata: Default::default(),
+            abort_timeout: None,
+            idempotency_retention: None,
+            inactivity_timeout: None,
+            ingress_private: None,
+            journal_retention: None,
+        }
+    }
+
+    #[test]
+    fn diff_added_removed_and_modified_services() {
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+        let deployment_3 = Deployment::mock_with_uri("http://localhost:9082");
+
+        let mut updater = SchemaUpdater::default();
+        updater
+            .add_deployment(
+                Some(deployment_1.id),
+                deployment_1.metadata.clone(),
+                vec![service("greeter.Greeter")],
+                false,
+            )
+            .unwrap();
+        updater
+            .add_deployment(
+                Some(deployment_3.id),
+                deployment_3.metadata.clone(),
+                vec![service("greeter.Removed")],
+                false,
+            )
+            .unwrap();
+        let from = updater.into_inner();
+
+        let mut updater = SchemaUpdater::new(from.clone(), false);
+        updater
+            .add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata.clone(),
+                vec![service("greeter.Added")],
+                false,
+            )
+            .unwrap();
+        updater
+            .modify_service(
+                "greeter.Greeter".to_owned(),
+                vec![ModifyServiceChange::Public(false)],
+            )
+            .unwrap();
+        updater.remove_deployment(deployment_3.id);
+        let to = updater.into_inner();
+
+        let diff = diff_schemas(&from, &to);
+        assert_eq!(diff.added_services, vec!["greeter.Added".to_owned()]);
+        assert_eq!(diff.removed_services, vec!["greeter.Removed".to_owned()]);
+        assert_eq!(diff.modified_services.len(), 1);
+        assert_eq!(diff.modified_services[0].name, "greeter.Greeter");
+        assert_eq!(diff.added_deployments, vec![deployment_2.id]);
+        assert_eq!(diff.removed_deployments, vec![deployment_3.id]);
+
+        // Diffing a schema with itself yields no changes
+        let diff = diff_schemas(&to, &to);
+        assert!(diff.added_services.is_empty());
+        assert!(diff.removed_services.is_empty());
+        assert!(diff.modified_services.is_empty());
+        assert!(diff.added_deployments.is_empty());
+    }
+}
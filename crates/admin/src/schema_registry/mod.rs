@@ -8,35 +8,54 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod descriptor;
 pub mod error;
+mod history;
 mod updater;
 
 use http::Uri;
 
 use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::subscriber::NoSubscriber;
+use tracing::{debug, warn};
 
-use restate_core::metadata_store::MetadataStoreClient;
+use restate_admin_rest_model::handlers::ModifyServiceHandlerRequest;
+use restate_admin_rest_model::schema_versions::SchemaDiffResponse;
+use restate_admin_rest_model::services::ModifyServiceRequest;
+use restate_core::metadata_store::{
+    MetadataStoreClient, Precondition, ReadModifyWriteError, ReadWriteError, WriteError,
+};
 use restate_core::{Metadata, MetadataWriter};
 use restate_service_protocol::discovery::{DiscoverEndpoint, DiscoveredEndpoint, ServiceDiscovery};
+use restate_types::endpoint_manifest;
 use restate_types::identifiers::{DeploymentId, ServiceRevision, SubscriptionId};
-use restate_types::metadata_store::keys::SCHEMA_INFORMATION_KEY;
+use restate_types::metadata_store::keys::{schema_version_key, SCHEMA_INFORMATION_KEY};
+use restate_types::retries::RetryPolicy;
 use restate_types::schema::deployment::{
     DeliveryOptions, Deployment, DeploymentMetadata, DeploymentResolver,
 };
-use restate_types::schema::service::{HandlerMetadata, ServiceMetadata, ServiceMetadataResolver};
+use restate_types::schema::history::SchemaVersionSnapshot;
+use restate_types::schema::service::{
+    HandlerMetadata, RetryPolicyOverrides, ServiceMetadata, ServiceMetadataResolver,
+    ServiceMirroring, ServiceRouting,
+};
 use restate_types::schema::subscriptions::{
     ListSubscriptionFilter, Subscription, SubscriptionResolver, SubscriptionValidator,
 };
 use restate_types::schema::Schema;
+use restate_types::{Version, Versioned};
 
 use crate::schema_registry::error::{SchemaError, SchemaRegistryError, ServiceError};
 use crate::schema_registry::updater::SchemaUpdater;
 
+/// Number of superseded versions of the schema kept in the history.
+const MAX_SCHEMA_HISTORY_ENTRIES: usize = 32;
+
 /// Whether to force the registration of an existing endpoint or not
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Force {
@@ -67,10 +86,154 @@ impl ApplyMode {
 #[derive(Debug, Clone)]
 pub enum ModifyServiceChange {
     Public(bool),
+    /// An empty list allows any service to call the service.
+    AllowedCallers(Vec<String>),
     IdempotencyRetention(Duration),
     WorkflowCompletionRetention(Duration),
+    /// A zero completion retention disables it.
+    CompletionRetention(Duration),
+    /// Overrides the retention of the service for a single handler.
+    HandlerRetention {
+        handler: String,
+        idempotency_retention: Option<Duration>,
+        completion_retention: Option<Duration>,
+    },
     InactivityTimeout(Duration),
     AbortTimeout(Duration),
+    /// Mirroring with a zero fraction disables it.
+    Mirroring(ServiceMirroring),
+    /// Routing with a zero percentage and no header disables it.
+    Routing(ServiceRouting),
+    /// A zero limit disables it.
+    SharedHandlerConcurrency(u32),
+    /// A zero limit disables it.
+    ConcurrencyLimit(u32),
+    /// Empty overrides remove them.
+    RetryPolicy(RetryPolicyOverrides),
+    /// Overrides the retry policies of the service for a single handler. Empty overrides remove
+    /// them.
+    HandlerRetryPolicy {
+        handler: String,
+        retry_policy: RetryPolicyOverrides,
+    },
+    /// A zero timeout disables it.
+    ExecutionTimeout(Duration),
+    /// Overrides the execution timeout of the service for a single handler. A zero timeout
+    /// removes the override.
+    HandlerExecutionTimeout {
+        handler: String,
+        execution_timeout: Duration,
+    },
+    /// Only changes the flag of the schema, the partitions are paused and resumed separately.
+    Paused(bool),
+}
+
+impl ModifyServiceChange {
+    /// Changes requested by the given request, empty if the request doesn't modify anything.
+    pub fn from_request(
+        ModifyServiceRequest {
+            public,
+            allowed_callers,
+            idempotency_retention,
+            workflow_completion_retention,
+            completion_retention,
+            handlers,
+            inactivity_timeout,
+            abort_timeout,
+            mirroring,
+            routing,
+            shared_handler_concurrency,
+            concurrency_limit,
+            retry_policy,
+            execution_timeout,
+        }: ModifyServiceRequest,
+    ) -> Vec<Self> {
+        let mut changes = vec![];
+        if let Some(new_public_value) = public {
+            changes.push(ModifyServiceChange::Public(new_public_value));
+        }
+        if let Some(allowed_callers) = allowed_callers {
+            changes.push(ModifyServiceChange::AllowedCallers(allowed_callers));
+        }
+        if let Some(new_idempotency_retention) = idempotency_retention {
+            changes.push(ModifyServiceChange::IdempotencyRetention(
+                new_idempotency_retention,
+            ));
+        }
+        if let Some(new_workflow_completion_retention) = workflow_completion_retention {
+            changes.push(ModifyServiceChange::WorkflowCompletionRetention(
+                new_workflow_completion_retention,
+            ));
+        }
+        if let Some(completion_retention) = completion_retention {
+            changes.push(ModifyServiceChange::CompletionRetention(
+                completion_retention,
+            ));
+        }
+        for (handler, request) in handlers {
+            changes.extend(Self::from_handler_request(handler, request));
+        }
+        if let Some(inactivity_timeout) = inactivity_timeout {
+            changes.push(ModifyServiceChange::InactivityTimeout(inactivity_timeout));
+        }
+        if let Some(abort_timeout) = abort_timeout {
+            changes.push(ModifyServiceChange::AbortTimeout(abort_timeout));
+        }
+        if let Some(mirroring) = mirroring {
+            changes.push(ModifyServiceChange::Mirroring(mirroring));
+        }
+        if let Some(routing) = routing {
+            changes.push(ModifyServiceChange::Routing(routing));
+        }
+        if let Some(shared_handler_concurrency) = shared_handler_concurrency {
+            changes.push(ModifyServiceChange::SharedHandlerConcurrency(
+                shared_handler_concurrency,
+            ));
+        }
+        if let Some(concurrency_limit) = concurrency_limit {
+            changes.push(ModifyServiceChange::ConcurrencyLimit(concurrency_limit));
+        }
+        if let Some(retry_policy) = retry_policy {
+            changes.push(ModifyServiceChange::RetryPolicy(retry_policy));
+        }
+        if let Some(execution_timeout) = execution_timeout {
+            changes.push(ModifyServiceChange::ExecutionTimeout(execution_timeout));
+        }
+        changes
+    }
+
+    /// Changes requested by the given handler request, empty if the request doesn't modify anything.
+    pub fn from_handler_request(
+        handler: String,
+        ModifyServiceHandlerRequest {
+            idempotency_retention,
+            completion_retention,
+            retry_policy,
+            execution_timeout,
+        }: ModifyServiceHandlerRequest,
+    ) -> Vec<Self> {
+        let mut changes = vec![];
+        if idempotency_retention.is_some() || completion_retention.is_some() {
+            changes.push(ModifyServiceChange::HandlerRetention {
+                handler: handler.clone(),
+                idempotency_retention,
+                completion_retention,
+            });
+        }
+        if let Some(retry_policy) = retry_policy {
+            changes.push(ModifyServiceChange::HandlerRetryPolicy {
+                handler: handler.clone(),
+                retry_policy,
+            });
+        }
+        if let Some(execution_timeout) = execution_timeout {
+            changes.push(ModifyServiceChange::HandlerExecutionTimeout {
+                handler,
+                execution_timeout,
+            });
+        }
+        changes
+    }
 }
 
 /// Responsible for updating the registered schema information. This includes the discovery of
@@ -105,30 +268,13 @@ impl<V> SchemaRegistry<V> {
     pub async fn register_deployment(
         &self,
         discover_endpoint: DiscoverEndpoint,
+        concurrency_limit: Option<NonZeroU32>,
         force: Force,
         apply_mode: ApplyMode,
     ) -> Result<(DeploymentId, Vec<ServiceMetadata>), SchemaRegistryError> {
-        // The number of concurrent discovery calls is bound by the number of concurrent
-        // register_deployment calls. If it should become a problem that a user tries to register
-        // the same endpoint too often, then we need to add a synchronization mechanism which
-        // ensures that only a limited number of discover calls per endpoint are running.
-        let discovered_metadata = self.service_discovery.discover(discover_endpoint).await?;
-
-        let deployment_metadata = match discovered_metadata.endpoint {
-            DiscoveredEndpoint::Http(uri, http_version) => DeploymentMetadata::new_http(
-                uri.clone(),
-                discovered_metadata.protocol_type,
-                http_version,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-            DiscoveredEndpoint::Lambda(arn, assume_role_arn) => DeploymentMetadata::new_lambda(
-                arn,
-                assume_role_arn,
-                DeliveryOptions::new(discovered_metadata.headers),
-                discovered_metadata.supported_protocol_versions,
-            ),
-        };
+        let (deployment_metadata, discovered_services) = self
+            .discover_deployment(discover_endpoint, concurrency_limit)
+            .await?;
 
         let (id, services) = if !apply_mode.should_apply() {
             let mut updater = SchemaUpdater::new(
@@ -141,7 +287,7 @@ impl<V> SchemaRegistry<V> {
                 updater.add_deployment(
                     None,
                     deployment_metadata,
-                    discovered_metadata.services,
+                    discovered_services,
                     force.force_enabled(),
                 )
             })?;
@@ -155,24 +301,20 @@ impl<V> SchemaRegistry<V> {
         } else {
             let mut new_deployment_id = None;
             let schema_information = self
-                .metadata_store_client
-                .read_modify_write(
-                    SCHEMA_INFORMATION_KEY.clone(),
-                    |schema_information: Option<Schema>| {
-                        let mut updater = SchemaUpdater::new(
-                            schema_information.unwrap_or_default(),
-                            self.experimental_feature_kafka_ingress_next,
-                        );
+                .update_schema(|schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information.unwrap_or_default(),
+                        self.experimental_feature_kafka_ingress_next,
+                    );
 
-                        new_deployment_id = Some(updater.add_deployment(
-                            None,
-                            deployment_metadata.clone(),
-                            discovered_metadata.services.clone(),
-                            force.force_enabled(),
-                        )?);
-                        Ok(updater.into_inner())
-                    },
-                )
+                    new_deployment_id = Some(updater.add_deployment(
+                        None,
+                        deployment_metadata.clone(),
+                        discovered_services.clone(),
+                        force.force_enabled(),
+                    )?);
+                    Ok(updater.into_inner())
+                })
                 .await?;
 
             let new_deployment_id = new_deployment_id.expect("deployment was just added");
@@ -180,9 +322,7 @@ impl<V> SchemaRegistry<V> {
                 .get_deployment_and_services(&new_deployment_id)
                 .expect("deployment was just added");
 
-            self.metadata_writer
-                .update(Arc::new(schema_information))
-                .await?;
+            self.publish_schema(schema_information).await?;
 
             (new_deployment_id, services)
         };
@@ -190,77 +330,126 @@ impl<V> SchemaRegistry<V> {
         Ok((id, services))
     }
 
+    /// Discovers the services of the endpoint, and describes the deployment serving them.
+    async fn discover_deployment(
+        &self,
+        discover_endpoint: DiscoverEndpoint,
+        concurrency_limit: Option<NonZeroU32>,
+    ) -> Result<(DeploymentMetadata, Vec<endpoint_manifest::Service>), SchemaRegistryError> {
+        // The number of concurrent discovery calls is bound by the number of concurrent
+        // register_deployment calls. If it should become a problem that a user tries to register
+        // the same endpoint too often, then we need to add a synchronization mechanism which
+        // ensures that only a limited number of discover calls per endpoint are running.
+        let discovered_metadata = self.service_discovery.discover(discover_endpoint).await?;
+
+        let deployment_metadata = match discovered_metadata.endpoint {
+            DiscoveredEndpoint::Http(uri, http_version) => DeploymentMetadata::new_http(
+                uri.clone(),
+                discovered_metadata.protocol_type,
+                http_version,
+                DeliveryOptions::new(discovered_metadata.headers)
+                    .with_concurrency_limit(concurrency_limit),
+                discovered_metadata.supported_protocol_versions,
+            )
+            .with_protocol_extensions(discovered_metadata.protocol_extensions),
+            DiscoveredEndpoint::Lambda(arn, invoke_options) => DeploymentMetadata::new_lambda(
+                arn,
+                invoke_options.assume_role_arn,
+                invoke_options.region,
+                DeliveryOptions::new(discovered_metadata.headers)
+                    .with_concurrency_limit(concurrency_limit),
+                discovered_metadata.supported_protocol_versions,
+            )
+            .with_protocol_extensions(discovered_metadata.protocol_extensions),
+        };
+
+        Ok((deployment_metadata, discovered_metadata.services))
+    }
+
     pub async fn delete_deployment(
         &self,
         deployment_id: DeploymentId,
     ) -> Result<(), SchemaRegistryError> {
         let schema_registry = self
-            .metadata_store_client
-            .read_modify_write(
-                SCHEMA_INFORMATION_KEY.clone(),
-                |schema_registry: Option<Schema>| {
-                    let schema_information: Schema = schema_registry.unwrap_or_default();
-
-                    if schema_information.get_deployment(&deployment_id).is_some() {
-                        let mut updater = SchemaUpdater::new(
-                            schema_information,
-                            self.experimental_feature_kafka_ingress_next,
-                        );
-                        updater.remove_deployment(deployment_id);
-                        Ok(updater.into_inner())
-                    } else {
-                        Err(SchemaError::NotFound(format!(
-                            "deployment with id '{deployment_id}'"
-                        )))
-                    }
-                },
-            )
-            .await?;
-        self.metadata_writer
-            .update(Arc::new(schema_registry))
+            .update_schema(|schema_registry: Option<Schema>| {
+                let schema_information: Schema = schema_registry.unwrap_or_default();
+
+                if schema_information.get_deployment(&deployment_id).is_some() {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information,
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.remove_deployment(deployment_id);
+                    Ok(updater.into_inner())
+                } else {
+                    Err(SchemaError::NotFound(format!(
+                        "deployment with id '{deployment_id}'"
+                    )))
+                }
+            })
             .await?;
+        self.publish_schema(schema_registry).await?;
 
         Ok(())
     }
 
+    pub async fn set_deployment_draining(
+        &self,
+        deployment_id: DeploymentId,
+        draining: bool,
+    ) -> Result<(Deployment, Vec<ServiceMetadata>), SchemaRegistryError> {
+        let schema_information = self
+            .update_schema(|schema_information: Option<Schema>| {
+                let mut updater = SchemaUpdater::new(
+                    schema_information.unwrap_or_default(),
+                    self.experimental_feature_kafka_ingress_next,
+                );
+                updater.set_deployment_draining(deployment_id, draining)?;
+                Ok(updater.into_inner())
+            })
+            .await?;
+
+        let response = schema_information
+            .get_deployment_and_services(&deployment_id)
+            .expect("deployment was just modified");
+
+        self.publish_schema(schema_information).await?;
+
+        Ok(response)
+    }
+
     pub async fn modify_service(
         &self,
         service_name: String,
         changes: Vec<ModifyServiceChange>,
     ) -> Result<ServiceMetadata, SchemaRegistryError> {
         let schema_information = self
-            .metadata_store_client
-            .read_modify_write(
-                SCHEMA_INFORMATION_KEY.clone(),
-                |schema_information: Option<Schema>| {
-                    let schema_information = schema_information.unwrap_or_default();
-
-                    if schema_information
-                        .resolve_latest_service(&service_name)
-                        .is_some()
-                    {
-                        let mut updater = SchemaUpdater::new(
-                            schema_information,
-                            self.experimental_feature_kafka_ingress_next,
-                        );
-                        updater.modify_service(service_name.clone(), changes.clone())?;
-                        Ok(updater.into_inner())
-                    } else {
-                        Err(SchemaError::NotFound(format!(
-                            "service with name '{service_name}'"
-                        )))
-                    }
-                },
-            )
+            .update_schema(|schema_information: Option<Schema>| {
+                let schema_information = schema_information.unwrap_or_default();
+
+                if schema_information
+                    .resolve_latest_service(&service_name)
+                    .is_some()
+                {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information,
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.modify_service(service_name.clone(), changes.clone())?;
+                    Ok(updater.into_inner())
+                } else {
+                    Err(SchemaError::NotFound(format!(
+                        "service with name '{service_name}'"
+                    )))
+                }
+            })
             .await?;
 
         let response = schema_information
             .resolve_latest_service(&service_name)
             .expect("service was just modified");
 
-        self.metadata_writer
-            .update(Arc::new(schema_information))
-            .await?;
+        self.publish_schema(schema_information).await?;
 
         Ok(response)
     }
@@ -270,31 +459,213 @@ impl<V> SchemaRegistry<V> {
         subscription_id: SubscriptionId,
     ) -> Result<(), SchemaRegistryError> {
         let schema_information = self
-            .metadata_store_client
-            .read_modify_write(
-                SCHEMA_INFORMATION_KEY.clone(),
-                |schema_information: Option<Schema>| {
-                    let schema_information = schema_information.unwrap_or_default();
-
-                    if schema_information
-                        .get_subscription(subscription_id)
-                        .is_some()
-                    {
-                        let mut updater = SchemaUpdater::new(
-                            schema_information,
-                            self.experimental_feature_kafka_ingress_next,
+            .update_schema(|schema_information: Option<Schema>| {
+                let schema_information = schema_information.unwrap_or_default();
+
+                if schema_information
+                    .get_subscription(subscription_id)
+                    .is_some()
+                {
+                    let mut updater = SchemaUpdater::new(
+                        schema_information,
+                        self.experimental_feature_kafka_ingress_next,
+                    );
+                    updater.remove_subscription(subscription_id);
+                    Ok(updater.into_inner())
+                } else {
+                    Err(SchemaError::NotFound(format!(
+                        "subscription with id '{subscription_id}'"
+                    )))
+                }
+            })
+            .await?;
+
+        self.publish_schema(schema_information).await?;
+
+        Ok(())
+    }
+
+    /// Rolls the schema back to the given version of the history. The rolled back schema is
+    /// written as a new version, so that the rollback can be undone as well.
+    pub async fn rollback_schema(
+        &self,
+        version: Version,
+        force: Force,
+    ) -> Result<Schema, SchemaRegistryError> {
+        let target = self
+            .get_schema_version(version)
+            .await?
+            .ok_or_else(|| SchemaError::NotFound(format!("schema version '{version}'")))?
+            .schema;
+
+        let schema_information = self
+            .update_schema(|schema_information: Option<Schema>| {
+                let mut updater = SchemaUpdater::new(
+                    schema_information.unwrap_or_default(),
+                    self.experimental_feature_kafka_ingress_next,
+                );
+                updater.rollback_to(target.clone(), force.force_enabled())?;
+                Ok::<_, SchemaError>(updater.into_inner())
+            })
+            .await?;
+
+        self.publish_schema(schema_information.clone()).await?;
+
+        Ok(schema_information)
+    }
+
+    /// Lists the current version of the schema, followed by the superseded versions kept in the
+    /// history, from the newest to the oldest.
+    pub async fn list_schema_versions(
+        &self,
+    ) -> Result<(Schema, Vec<SchemaVersionSnapshot>), SchemaRegistryError> {
+        let current = Metadata::with_current(|m| m.schema()).deref().clone();
+
+        let current_version = u32::from(current.version);
+        let oldest_version = current_version.saturating_sub(MAX_SCHEMA_HISTORY_ENTRIES as u32);
+        let mut versions = Vec::with_capacity(MAX_SCHEMA_HISTORY_ENTRIES);
+        for version in (oldest_version.max(1)..current_version).rev() {
+            // Versions written before the history was kept have no snapshot
+            if let Some(snapshot) = self.get_schema_version(Version::from(version)).await? {
+                versions.push(snapshot);
+            }
+        }
+
+        Ok((current, versions))
+    }
+
+    pub async fn diff_schema_versions(
+        &self,
+        from: Version,
+        to: Version,
+    ) -> Result<SchemaDiffResponse, SchemaRegistryError> {
+        let current = Metadata::with_current(|m| m.schema());
+        Ok(history::diff_schemas(
+            &self.resolve_schema_version(&current, from).await?,
+            &self.resolve_schema_version(&current, to).await?,
+        ))
+    }
+
+    async fn resolve_schema_version(
+        &self,
+        current: &Schema,
+        version: Version,
+    ) -> Result<Schema, SchemaRegistryError> {
+        if current.version == version {
+            return Ok(current.clone());
+        }
+        Ok(self
+            .get_schema_version(version)
+            .await?
+            .ok_or_else(|| SchemaError::NotFound(format!("schema version '{version}'")))?
+            .schema)
+    }
+
+    async fn get_schema_version(
+        &self,
+        version: Version,
+    ) -> Result<Option<SchemaVersionSnapshot>, SchemaRegistryError> {
+        self.metadata_store_client
+            .get::<SchemaVersionSnapshot>(schema_version_key(version))
+            .await
+            .map_err(|err| SchemaRegistryError::Internal(err.to_string()))
+    }
+
+    /// Reads the schema, modifies it and writes it back, like
+    /// [`MetadataStoreClient::read_modify_write`]. The read version of the schema is archived
+    /// under its own key before it gets overwritten, so that the history holds every superseded
+    /// version, no matter at which point the update fails.
+    async fn update_schema<F, E>(&self, mut modify: F) -> Result<Schema, ReadModifyWriteError<E>>
+    where
+        F: FnMut(Option<Schema>) -> Result<Schema, E>,
+    {
+        let retry_policy = RetryPolicy::exponential(
+            Duration::from_millis(10),
+            2.0,
+            Some(10),
+            Some(Duration::from_secs(1)),
+        );
+        let mut backoff_policy = retry_policy.iter();
+
+        loop {
+            let current = self
+                .metadata_store_client
+                .get::<Schema>(SCHEMA_INFORMATION_KEY.clone())
+                .await
+                .map_err(ReadWriteError::from)?;
+            let precondition = current
+                .as_ref()
+                .map(|schema| Precondition::MatchesVersion(schema.version()))
+                .unwrap_or(Precondition::DoesNotExist);
+
+            let new_schema =
+                modify(current.clone()).map_err(ReadModifyWriteError::FailedOperation)?;
+
+            if let Some(current) = current {
+                self.archive_schema_version(current).await?;
+            }
+
+            match self
+                .metadata_store_client
+                .put(SCHEMA_INFORMATION_KEY.clone(), &new_schema, precondition)
+                .await
+            {
+                Ok(()) => return Ok(new_schema),
+                Err(WriteError::FailedPrecondition(msg)) => {
+                    if let Some(backoff) = backoff_policy.next() {
+                        debug!(
+                            "Concurrent schema update: {msg}; retrying in '{}'",
+                            humantime::format_duration(backoff)
                         );
-                        updater.remove_subscription(subscription_id);
-                        Ok(updater.into_inner())
+                        tokio::time::sleep(backoff).await;
                     } else {
-                        Err(SchemaError::NotFound(format!(
-                            "subscription with id '{subscription_id}'"
-                        )))
+                        return Err(ReadWriteError::RetriesExhausted(
+                            SCHEMA_INFORMATION_KEY.clone(),
+                        )
+                        .into());
                     }
-                },
+                }
+                Err(err) => return Err(ReadModifyWriteError::ReadWrite(err.into())),
+            }
+        }
+    }
+
+    /// Stores the snapshot of a version of the schema which is about to be superseded, and prunes
+    /// the snapshots falling out of the history.
+    async fn archive_schema_version(&self, schema: Schema) -> Result<(), ReadWriteError> {
+        let version = schema.version;
+        match self
+            .metadata_store_client
+            .put(
+                schema_version_key(version),
+                &SchemaVersionSnapshot::new(schema),
+                Precondition::DoesNotExist,
             )
-            .await?;
+            .await
+        {
+            // A version is written only once, so an existing snapshot holds the same schema
+            Ok(()) | Err(WriteError::FailedPrecondition(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
 
+        if let Some(pruned) = u32::from(version).checked_sub(MAX_SCHEMA_HISTORY_ENTRIES as u32) {
+            if let Err(err) = self
+                .metadata_store_client
+                .delete(
+                    schema_version_key(Version::from(pruned)),
+                    Precondition::None,
+                )
+                .await
+            {
+                warn!("Failed to prune schema version '{pruned}' from the history: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates the local metadata with the written schema.
+    async fn publish_schema(&self, schema_information: Schema) -> Result<(), SchemaRegistryError> {
         self.metadata_writer
             .update(Arc::new(schema_information))
             .await?;
@@ -314,6 +685,10 @@ impl<V> SchemaRegistry<V> {
         Metadata::with_current(|m| m.schema()).resolve_latest_service_openapi(&service_name)
     }
 
+    pub fn get_services_openapi(&self) -> serde_json::Value {
+        Metadata::with_current(|m| m.schema()).resolve_latest_services_openapi()
+    }
+
     pub fn get_deployment(
         &self,
         deployment_id: DeploymentId,
@@ -370,33 +745,27 @@ where
         let mut subscription_id = None;
 
         let schema_information = self
-            .metadata_store_client
-            .read_modify_write(
-                SCHEMA_INFORMATION_KEY.clone(),
-                |schema_information: Option<Schema>| {
-                    let mut updater = SchemaUpdater::new(
-                        schema_information.unwrap_or_default(),
-                        self.experimental_feature_kafka_ingress_next,
-                    );
-                    subscription_id = Some(updater.add_subscription(
-                        None,
-                        source.clone(),
-                        sink.clone(),
-                        options.clone(),
-                        &self.subscription_validator,
-                    )?);
+            .update_schema(|schema_information: Option<Schema>| {
+                let mut updater = SchemaUpdater::new(
+                    schema_information.unwrap_or_default(),
+                    self.experimental_feature_kafka_ingress_next,
+                );
+                subscription_id = Some(updater.add_subscription(
+                    None,
+                    source.clone(),
+                    sink.clone(),
+                    options.clone(),
+                    &self.subscription_validator,
+                )?);
 
-                    Ok::<_, SchemaError>(updater.into_inner())
-                },
-            )
+                Ok::<_, SchemaError>(updater.into_inner())
+            })
             .await?;
 
         let subscription = schema_information
             .get_subscription(subscription_id.expect("subscription was just added"))
             .expect("subscription was just added");
-        self.metadata_writer
-            .update(Arc::new(schema_information))
-            .await?;
+        self.publish_schema(schema_information).await?;
 
         Ok(subscription)
     }
@@ -12,7 +12,9 @@ use crate::schema_registry::error::{
     DeploymentError, SchemaError, ServiceError, SubscriptionError,
 };
 use crate::schema_registry::{ModifyServiceChange, ServiceName};
+use base64::prelude::{Engine, BASE64_STANDARD};
 use http::{HeaderValue, Uri};
+use restate_types::deployment::PinnedDeployment;
 use restate_types::endpoint_manifest;
 use restate_types::identifiers::{DeploymentId, SubscriptionId};
 use restate_types::invocation::{
@@ -21,17 +23,23 @@ use restate_types::invocation::{
 use restate_types::schema::deployment::DeploymentMetadata;
 use restate_types::schema::deployment::DeploymentSchemas;
 use restate_types::schema::invocation_target::{
-    InputRules, InputValidationRule, InvocationTargetMetadata, OutputContentTypeRule, OutputRules,
+    InputRules, InputValidationRule, InvocationTargetMetadata, InvocationTargetMirroring,
+    InvocationTargetRouting, OutputContentTypeRule, OutputRules, ProtobufMessage,
     DEFAULT_IDEMPOTENCY_RETENTION, DEFAULT_WORKFLOW_COMPLETION_RETENTION,
 };
-use restate_types::schema::service::{HandlerSchemas, ServiceLocation, ServiceSchemas};
+use restate_types::schema::service::{
+    HandlerSchemas, ServiceLocation, ServiceMirroring, ServiceRouting, ServiceSchemas,
+};
 use restate_types::schema::subscriptions::{
     EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Source, Subscription,
     SubscriptionValidator,
 };
 use restate_types::schema::Schema;
+use restate_types::service_protocol::{ProtocolExtension, ServiceProtocolVersion};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::time::Duration;
 use tracing::{info, warn};
 
 /// Responsible for updating the provided [`Schema`] with new
@@ -133,6 +141,7 @@ impl SchemaUpdater {
         // Compute service schemas
         for (service_name, service) in proposed_services {
             let service_type = ServiceType::from(service.ty);
+            let service_options = DiscoveredServiceOptions::from_schema(&service);
             let handlers = DiscoveredHandlerMetadata::compute_handlers(
                 service
                     .handlers
@@ -200,14 +209,79 @@ impl SchemaUpdater {
                 service_schemas.revision = existing_service.revision.wrapping_add(1);
                 service_schemas.ty = service_type;
                 service_schemas.handlers = handlers;
+                // Keep the retry policy and execution timeout overrides of the handlers which
+                // still exist. The options declared by the SDK replace the ones of the previous
+                // revision, except for the options set through the admin API.
+                for (name, handler) in service_schemas.handlers.iter_mut() {
+                    if let Some(existing_handler) = existing_service.handlers.get(name) {
+                        handler.retry_policy = existing_handler.retry_policy.clone();
+                        handler.execution_timeout = existing_handler.execution_timeout;
+                    }
+                }
+                service_options.apply(&mut service_schemas);
+                service_schemas.apply_admin_overrides();
+                service_schemas.apply_retention_policies();
+                service_schemas.apply_visibility();
+                if service_type != ServiceType::VirtualObject {
+                    service_schemas.shared_handler_concurrency = None;
+                }
+                service_schemas.apply_shared_handler_concurrency();
                 service_schemas.location.latest_deployment = deployment_id;
                 service_schemas.service_openapi_cache = Default::default();
                 service_schemas.documentation = service.documentation;
                 service_schemas.metadata = service.metadata;
 
+                if let Some(mirroring) = service_schemas.mirroring.take() {
+                    // The shadow deployment might not be compatible with the new revision
+                    match Self::resolve_mirroring(
+                        &self.schema_information.deployments,
+                        service_name.as_ref(),
+                        &mirroring,
+                    ) {
+                        Ok(target_mirroring) => {
+                            for h in service_schemas.handlers.values_mut() {
+                                h.target_meta.mirroring = Some(target_mirroring.clone());
+                            }
+                            service_schemas.mirroring = Some(mirroring);
+                        }
+                        Err(err) => {
+                            warn!(
+                                rpc.service = %service_name,
+                                "Disabling request mirroring to deployment {}: {}",
+                                mirroring.deployment_id,
+                                err
+                            );
+                        }
+                    }
+                }
+
+                if let Some(routing) = service_schemas.routing.take() {
+                    // The canary deployment might not be compatible with the new revision
+                    match Self::resolve_routing(
+                        &self.schema_information.deployments,
+                        service_name.as_ref(),
+                        &routing,
+                    ) {
+                        Ok(target_routing) => {
+                            for h in service_schemas.handlers.values_mut() {
+                                h.target_meta.routing = Some(target_routing.clone());
+                            }
+                            service_schemas.routing = Some(routing);
+                        }
+                        Err(err) => {
+                            warn!(
+                                rpc.service = %service_name,
+                                "Disabling routing to canary deployment {}: {}",
+                                routing.deployment_id,
+                                err
+                            );
+                        }
+                    }
+                }
+
                 service_schemas
             } else {
-                ServiceSchemas {
+                let mut service_schemas = ServiceSchemas {
                     revision: 1,
                     handlers,
                     ty: service_type,
@@ -221,12 +295,26 @@ impl SchemaUpdater {
                     } else {
                         None
                     },
+                    completion_retention: None,
                     inactivity_timeout: None,
                     abort_timeout: None,
+                    mirroring: None,
+                    routing: None,
+                    shared_handler_concurrency: None,
+                    concurrency_limit: None,
+                    retry_policy: None,
+                    execution_timeout: None,
+                    paused: false,
+                    allowed_callers: None,
+                    admin_overrides: Default::default(),
                     service_openapi_cache: Default::default(),
                     documentation: service.documentation,
                     metadata: service.metadata,
-                }
+                };
+                service_options.apply(&mut service_schemas);
+                service_schemas.apply_retention_policies();
+                service_schemas.apply_visibility();
+                service_schemas
             };
 
             services_to_add.insert(service_name, service_schema);
@@ -254,6 +342,7 @@ impl SchemaUpdater {
                 metadata: deployment_metadata,
             },
         );
+        self.revalidate_mirroring_to(deployment_id);
 
         self.modified = true;
 
@@ -276,8 +365,127 @@ impl SchemaUpdater {
                     _ => {}
                 }
             }
+            for schemas in self.schema_information.services.values_mut() {
+                if schemas
+                    .mirroring
+                    .as_ref()
+                    .is_some_and(|mirroring| mirroring.deployment_id == deployment_id)
+                {
+                    schemas.mirroring = None;
+                    for h in schemas.handlers.values_mut() {
+                        h.target_meta.mirroring = None;
+                    }
+                }
+                if schemas
+                    .routing
+                    .as_ref()
+                    .is_some_and(|routing| routing.deployment_id == deployment_id)
+                {
+                    schemas.routing = None;
+                    for h in schemas.handlers.values_mut() {
+                        h.target_meta.routing = None;
+                    }
+                }
+            }
+            self.modified = true;
+        }
+    }
+
+    pub fn set_deployment_draining(
+        &mut self,
+        deployment_id: DeploymentId,
+        draining: bool,
+    ) -> Result<(), SchemaError> {
+        let deployment = self
+            .schema_information
+            .deployments
+            .get_mut(&deployment_id)
+            .ok_or_else(|| {
+                SchemaError::NotFound(format!("deployment with id '{deployment_id}'"))
+            })?;
+        if deployment.metadata.draining != draining {
+            deployment.metadata.draining = draining;
+            self.modified = true;
+        }
+        if draining {
+            // New invocations must not be routed to the draining deployment anymore
+            for schemas in self.schema_information.services.values_mut() {
+                if schemas
+                    .routing
+                    .as_ref()
+                    .is_some_and(|routing| routing.deployment_id == deployment_id)
+                {
+                    schemas.routing = None;
+                    for h in schemas.handlers.values_mut() {
+                        h.target_meta.routing = None;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the endpoint and the delivery options of an existing deployment in place, keeping
+    /// its services. The address of the endpoint is expected to be the same.
+    pub fn update_deployment_endpoint(
+        &mut self,
+        deployment_id: DeploymentId,
+        deployment_metadata: DeploymentMetadata,
+    ) -> Result<(), SchemaError> {
+        let deployment = self
+            .schema_information
+            .deployments
+            .get_mut(&deployment_id)
+            .ok_or_else(|| {
+                SchemaError::NotFound(format!("deployment with id '{deployment_id}'"))
+            })?;
+        let metadata = &mut deployment.metadata;
+        if metadata.ty != deployment_metadata.ty
+            || metadata.delivery_options != deployment_metadata.delivery_options
+            || metadata.supported_protocol_versions
+                != deployment_metadata.supported_protocol_versions
+            || metadata.protocol_extensions != deployment_metadata.protocol_extensions
+        {
+            metadata.ty = deployment_metadata.ty;
+            metadata.delivery_options = deployment_metadata.delivery_options;
+            metadata.supported_protocol_versions = deployment_metadata.supported_protocol_versions;
+            metadata.protocol_extensions = deployment_metadata.protocol_extensions;
+            self.revalidate_mirroring_to(deployment_id);
             self.modified = true;
         }
+        Ok(())
+    }
+
+    /// Disables the request mirroring of the services mirroring to the given deployment, if the
+    /// deployment cannot be a shadow deployment anymore. Mirrored requests run as dry-runs, hence
+    /// this happens when the deployment was updated and didn't accept the dry-run protocol
+    /// extension again.
+    fn revalidate_mirroring_to(&mut self, deployment_id: DeploymentId) {
+        for (service_name, schemas) in self.schema_information.services.iter_mut() {
+            let Some(mirroring) = schemas
+                .mirroring
+                .as_ref()
+                .filter(|mirroring| mirroring.deployment_id == deployment_id)
+            else {
+                continue;
+            };
+            if let Err(err) = Self::resolve_mirroring(
+                &self.schema_information.deployments,
+                service_name,
+                mirroring,
+            ) {
+                warn!(
+                    rpc.service = %service_name,
+                    "Disabling request mirroring to deployment {}: {}",
+                    deployment_id,
+                    err
+                );
+                schemas.mirroring = None;
+                for h in schemas.handlers.values_mut() {
+                    h.target_meta.mirroring = None;
+                }
+            }
+        }
     }
 
     pub fn add_subscription<V: SubscriptionValidator>(
@@ -317,6 +525,56 @@ impl SchemaUpdater {
                     topic: topic_name.to_string(),
                 }
             }
+            Some("sqs") => {
+                let region = source.authority().map(|authority| authority.as_str());
+                let mut path = source.path()[1..].splitn(2, '/');
+                match (region, path.next(), path.next()) {
+                    (Some(region), Some(account_id), Some(queue))
+                        if !account_id.is_empty() && !queue.is_empty() =>
+                    {
+                        Source::Sqs {
+                            region: region.to_string(),
+                            account_id: account_id.to_string(),
+                            queue: queue.to_string(),
+                        }
+                    }
+                    _ => {
+                        return Err(SchemaError::Subscription(
+                            SubscriptionError::InvalidSqsSource(source),
+                        ))
+                    }
+                }
+            }
+            Some("nats") => {
+                let stream_name = &source.path()[1..];
+                match source.authority() {
+                    Some(server) if !stream_name.is_empty() && !stream_name.contains('/') => {
+                        Source::Nats {
+                            server: server.to_string(),
+                            stream: stream_name.to_string(),
+                        }
+                    }
+                    _ => {
+                        return Err(SchemaError::Subscription(
+                            SubscriptionError::InvalidNatsSource(source),
+                        ))
+                    }
+                }
+            }
+            Some("webhook") => {
+                let provider = source
+                    .authority()
+                    .filter(|_| matches!(source.path(), "" | "/"))
+                    .and_then(|provider| provider.as_str().parse().ok());
+                match provider {
+                    Some(provider) => Source::Webhook { provider },
+                    None => {
+                        return Err(SchemaError::Subscription(
+                            SubscriptionError::InvalidWebhookSource(source),
+                        ))
+                    }
+                }
+            }
             _ => {
                 return Err(SchemaError::Subscription(
                     SubscriptionError::InvalidSourceScheme(source),
@@ -441,62 +699,299 @@ impl SchemaUpdater {
         }
     }
 
+    /// Replaces the schema with a previous version of it. Like the registration of a deployment,
+    /// removing services or handlers and changing the type of services requires `force`. The
+    /// revisions of the services which change are bumped, so that they keep increasing.
+    pub fn rollback_to(&mut self, target: Schema, force: bool) -> Result<(), SchemaError> {
+        let mut target = target;
+
+        for (name, existing_service) in &self.schema_information.services {
+            let Some(target_service) = target.services.get_mut(name) else {
+                if force {
+                    warn!(
+                        "Going to remove service {} due to a forced rollback to schema version {}",
+                        name, target.version
+                    );
+                    continue;
+                }
+                return Err(SchemaError::Service(ServiceError::RemovedService(
+                    ServiceName(name.clone()),
+                )));
+            };
+
+            let removed_handlers: Vec<String> = existing_service
+                .handlers
+                .keys()
+                .filter(|handler| !target_service.handlers.contains_key(*handler))
+                .cloned()
+                .collect();
+            if !removed_handlers.is_empty() {
+                if force {
+                    warn!(
+                        "Going to remove the following methods from service type {} due to a forced rollback to schema version {}: {:?}.",
+                        name, target.version, removed_handlers
+                    );
+                } else {
+                    return Err(SchemaError::Service(ServiceError::RemovedHandlers(
+                        ServiceName(name.clone()),
+                        removed_handlers,
+                    )));
+                }
+            }
+
+            if existing_service.ty != target_service.ty {
+                if force {
+                    warn!(
+                        "Going to overwrite service type {} due to a forced rollback to schema version {}: {:?} != {:?}. This is a potentially dangerous operation, and might result in data loss.",
+                        name, target.version, existing_service.ty, target_service.ty
+                    );
+                } else {
+                    return Err(SchemaError::Service(ServiceError::DifferentType(
+                        ServiceName(name.clone()),
+                    )));
+                }
+            }
+
+            if existing_service.revision != target_service.revision
+                || existing_service.location.latest_deployment
+                    != target_service.location.latest_deployment
+            {
+                target_service.revision = existing_service.revision.wrapping_add(1);
+            }
+        }
+
+        target.version = self.schema_information.version;
+        self.schema_information = target;
+        self.modified = true;
+
+        Ok(())
+    }
+
     pub fn modify_service(
         &mut self,
         name: String,
         changes: Vec<ModifyServiceChange>,
     ) -> Result<(), SchemaError> {
-        if let Some(schemas) = self.schema_information.services.get_mut(&name) {
-            for command in changes {
-                match command {
-                    ModifyServiceChange::Public(new_public_value) => {
-                        schemas.location.public = new_public_value;
-                        for h in schemas.handlers.values_mut() {
-                            h.target_meta.public = new_public_value;
-                        }
-                        // Cleanup generated OpenAPI
-                        schemas.service_openapi_cache = Default::default();
+        let Some(schemas) = self.schema_information.services.get_mut(&name) else {
+            return Err(SchemaError::NotFound(format!("service with name '{name}'")));
+        };
+        let before = serde_json::to_value(&*schemas).ok();
+
+        for command in changes {
+            match command {
+                ModifyServiceChange::Public(new_public_value) => {
+                    schemas.location.public = new_public_value;
+                    schemas.admin_overrides.public = Some(new_public_value);
+                    schemas.apply_visibility();
+                    // Cleanup generated OpenAPI
+                    schemas.service_openapi_cache = Default::default();
+                }
+                ModifyServiceChange::AllowedCallers(allowed_callers) => {
+                    schemas.allowed_callers =
+                        (!allowed_callers.is_empty()).then_some(allowed_callers);
+                    schemas.apply_visibility();
+                }
+                ModifyServiceChange::IdempotencyRetention(new_idempotency_retention) => {
+                    schemas.idempotency_retention = new_idempotency_retention;
+                    schemas.admin_overrides.idempotency_retention = Some(new_idempotency_retention);
+                    schemas.apply_retention_policies();
+                }
+                ModifyServiceChange::WorkflowCompletionRetention(
+                    new_workflow_completion_retention,
+                ) => {
+                    if schemas.ty != ServiceType::Workflow {
+                        return Err(SchemaError::Service(
+                            ServiceError::CannotModifyRetentionTime(schemas.ty),
+                        ));
                     }
-                    ModifyServiceChange::IdempotencyRetention(new_idempotency_retention) => {
-                        schemas.idempotency_retention = new_idempotency_retention;
-                        for h in schemas.handlers.values_mut() {
-                            h.target_meta.idempotency_retention = new_idempotency_retention;
-                        }
+                    schemas.workflow_completion_retention = Some(new_workflow_completion_retention);
+                    schemas.apply_retention_policies();
+                }
+                ModifyServiceChange::CompletionRetention(new_completion_retention) => {
+                    schemas.completion_retention =
+                        (!new_completion_retention.is_zero()).then_some(new_completion_retention);
+                    schemas.admin_overrides.completion_retention = Some(new_completion_retention);
+                    schemas.apply_retention_policies();
+                }
+                ModifyServiceChange::HandlerRetention {
+                    handler,
+                    idempotency_retention,
+                    completion_retention,
+                } => {
+                    let Some(handler_schemas) = schemas.handlers.get_mut(&handler) else {
+                        return Err(SchemaError::NotFound(format!(
+                            "handler '{handler}' of service '{name}'"
+                        )));
+                    };
+                    let handler_overrides =
+                        schemas.admin_overrides.handlers.entry(handler).or_default();
+                    if idempotency_retention.is_some() {
+                        handler_schemas.idempotency_retention = idempotency_retention;
+                        handler_overrides.idempotency_retention = idempotency_retention;
                     }
-                    ModifyServiceChange::WorkflowCompletionRetention(
-                        new_workflow_completion_retention,
-                    ) => {
-                        if schemas.ty != ServiceType::Workflow {
-                            return Err(SchemaError::Service(
-                                ServiceError::CannotModifyRetentionTime(schemas.ty),
-                            ));
-                        }
-                        schemas.workflow_completion_retention =
-                            Some(new_workflow_completion_retention);
-                        for h in schemas.handlers.values_mut().filter(|w| {
-                            w.target_meta.target_ty
-                                == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
-                        }) {
-                            h.target_meta.completion_retention =
-                                Some(new_workflow_completion_retention);
-                        }
+                    if completion_retention.is_some() {
+                        handler_schemas.completion_retention = completion_retention;
+                        handler_overrides.completion_retention = completion_retention;
+                    }
+                    schemas.apply_retention_policies();
+                }
+                ModifyServiceChange::InactivityTimeout(inactivity_timeout) => {
+                    schemas.inactivity_timeout = Some(inactivity_timeout);
+                    schemas.admin_overrides.inactivity_timeout = Some(inactivity_timeout);
+                }
+                ModifyServiceChange::AbortTimeout(abort_timeout) => {
+                    schemas.abort_timeout = Some(abort_timeout);
+                    schemas.admin_overrides.abort_timeout = Some(abort_timeout);
+                }
+                ModifyServiceChange::Mirroring(mirroring) => {
+                    if schemas.ty != ServiceType::Service {
+                        return Err(SchemaError::Service(ServiceError::CannotMirror(schemas.ty)));
+                    }
+                    if !(0.0..=1.0).contains(&mirroring.fraction) {
+                        return Err(SchemaError::Service(ServiceError::BadMirroringFraction(
+                            mirroring.fraction,
+                        )));
+                    }
+
+                    let target_mirroring = if mirroring.fraction > 0.0 {
+                        Some(Self::resolve_mirroring(
+                            &self.schema_information.deployments,
+                            &name,
+                            &mirroring,
+                        )?)
+                    } else {
+                        None
+                    };
+
+                    schemas.mirroring = target_mirroring.is_some().then_some(mirroring);
+                    for h in schemas.handlers.values_mut() {
+                        h.target_meta.mirroring = target_mirroring.clone();
+                    }
+                }
+                ModifyServiceChange::Routing(routing) => {
+                    if !(0.0..=100.0).contains(&routing.percentage) {
+                        return Err(SchemaError::Service(ServiceError::BadRoutingPercentage(
+                            routing.percentage,
+                        )));
                     }
-                    ModifyServiceChange::InactivityTimeout(inactivity_timeout) => {
-                        schemas.inactivity_timeout = Some(inactivity_timeout);
+
+                    let target_routing = if routing.percentage > 0.0 || routing.header.is_some() {
+                        Some(Self::resolve_routing(
+                            &self.schema_information.deployments,
+                            &name,
+                            &routing,
+                        )?)
+                    } else {
+                        None
+                    };
+
+                    schemas.routing = target_routing.is_some().then_some(routing);
+                    for h in schemas.handlers.values_mut() {
+                        h.target_meta.routing = target_routing.clone();
                     }
-                    ModifyServiceChange::AbortTimeout(abort_timeout) => {
-                        schemas.abort_timeout = Some(abort_timeout);
+                }
+                ModifyServiceChange::SharedHandlerConcurrency(shared_handler_concurrency) => {
+                    if schemas.ty != ServiceType::VirtualObject {
+                        return Err(SchemaError::Service(
+                            ServiceError::CannotLimitSharedHandlerConcurrency(schemas.ty),
+                        ));
                     }
+                    schemas.shared_handler_concurrency =
+                        NonZeroU32::new(shared_handler_concurrency);
+                    schemas.apply_shared_handler_concurrency();
+                }
+                ModifyServiceChange::ConcurrencyLimit(concurrency_limit) => {
+                    schemas.concurrency_limit = NonZeroU32::new(concurrency_limit);
+                }
+                ModifyServiceChange::RetryPolicy(retry_policy) => {
+                    schemas.retry_policy = (!retry_policy.is_empty()).then_some(retry_policy);
+                }
+                ModifyServiceChange::HandlerRetryPolicy {
+                    handler,
+                    retry_policy,
+                } => {
+                    let Some(handler_schemas) = schemas.handlers.get_mut(&handler) else {
+                        return Err(SchemaError::NotFound(format!(
+                            "handler '{handler}' of service '{name}'"
+                        )));
+                    };
+                    handler_schemas.retry_policy =
+                        (!retry_policy.is_empty()).then_some(retry_policy);
+                }
+                ModifyServiceChange::ExecutionTimeout(execution_timeout) => {
+                    schemas.execution_timeout =
+                        (!execution_timeout.is_zero()).then_some(execution_timeout);
+                }
+                ModifyServiceChange::HandlerExecutionTimeout {
+                    handler,
+                    execution_timeout,
+                } => {
+                    let Some(handler_schemas) = schemas.handlers.get_mut(&handler) else {
+                        return Err(SchemaError::NotFound(format!(
+                            "handler '{handler}' of service '{name}'"
+                        )));
+                    };
+                    handler_schemas.execution_timeout =
+                        (!execution_timeout.is_zero()).then_some(execution_timeout);
+                }
+                ModifyServiceChange::Paused(paused) => {
+                    schemas.paused = paused;
                 }
             }
         }
 
-        self.modified = true;
+        // Re-applying the current options must not create a new version of the schema
+        if serde_json::to_value(&*schemas).ok() != before {
+            self.modified = true;
+        }
 
         Ok(())
     }
 }
 
+/// Service options declared by the SDK in the endpoint manifest. When set, they overwrite the
+/// corresponding service configuration at registration time, unless it was set through the
+/// admin API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DiscoveredServiceOptions {
+    inactivity_timeout: Option<Duration>,
+    abort_timeout: Option<Duration>,
+    idempotency_retention: Option<Duration>,
+    journal_retention: Option<Duration>,
+    ingress_private: Option<bool>,
+}
+
+impl DiscoveredServiceOptions {
+    fn from_schema(service: &endpoint_manifest::Service) -> Self {
+        Self {
+            inactivity_timeout: service.inactivity_timeout.map(Duration::from_millis),
+            abort_timeout: service.abort_timeout.map(Duration::from_millis),
+            idempotency_retention: service.idempotency_retention.map(Duration::from_millis),
+            journal_retention: service.journal_retention.map(Duration::from_millis),
+            ingress_private: service.ingress_private,
+        }
+    }
+
+    fn apply(&self, service_schemas: &mut ServiceSchemas) {
+        if let Some(inactivity_timeout) = self.inactivity_timeout {
+            service_schemas.inactivity_timeout = Some(inactivity_timeout);
+        }
+        if let Some(abort_timeout) = self.abort_timeout {
+            service_schemas.abort_timeout = Some(abort_timeout);
+        }
+        if let Some(idempotency_retention) = self.idempotency_retention {
+            service_schemas.idempotency_retention = idempotency_retention;
+        }
+        if let Some(journal_retention) = self.journal_retention {
+            service_schemas.completion_retention =
+                (!journal_retention.is_zero()).then_some(journal_retention);
+        }
+        if let Some(ingress_private) = self.ingress_private {
+            service_schemas.location.public = !ingress_private;
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct DiscoveredHandlerMetadata {
     name: String,
@@ -505,6 +1000,11 @@ struct DiscoveredHandlerMetadata {
     metadata: HashMap<String, String>,
     input: InputRules,
     output: OutputRules,
+    inactivity_timeout: Option<Duration>,
+    abort_timeout: Option<Duration>,
+    idempotency_retention: Option<Duration>,
+    completion_retention: Option<Duration>,
+    ingress_private: bool,
 }
 
 impl DiscoveredHandlerMetadata {
@@ -538,9 +1038,23 @@ impl DiscoveredHandlerMetadata {
             }
         };
 
+        // The workflow completion retention applies to the workflow handler, the journal
+        // retention to all the other handlers.
+        let completion_retention =
+            if ty == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow) {
+                handler.workflow_completion_retention
+            } else {
+                handler.journal_retention
+            };
+
         Ok(Self {
             name: handler.name.to_string(),
             ty,
+            inactivity_timeout: handler.inactivity_timeout.map(Duration::from_millis),
+            abort_timeout: handler.abort_timeout.map(Duration::from_millis),
+            idempotency_retention: handler.idempotency_retention.map(Duration::from_millis),
+            completion_retention: completion_retention.map(Duration::from_millis),
+            ingress_private: handler.ingress_private.unwrap_or(false),
             documentation: handler.documentation,
             metadata: handler.metadata,
             input: handler
@@ -611,8 +1125,21 @@ impl DiscoveredHandlerMetadata {
             }
         }
 
+        let protobuf_message = schema
+            .protobuf_message
+            .map(|protobuf_message| {
+                DiscoveredHandlerMetadata::protobuf_message_from_schema(
+                    svc_name,
+                    handler_name,
+                    "input",
+                    protobuf_message,
+                )
+            })
+            .transpose()?;
+
         Ok(InputRules {
             input_validation_rules,
+            protobuf_message,
         })
     }
 
@@ -641,12 +1168,134 @@ impl DiscoveredHandlerMetadata {
                     has_json_schema: schema.json_schema.is_some(),
                 },
                 json_schema: schema.json_schema,
+                protobuf_message: schema
+                    .protobuf_message
+                    .map(|protobuf_message| {
+                        DiscoveredHandlerMetadata::protobuf_message_from_schema(
+                            svc_name,
+                            handler_name,
+                            "output",
+                            protobuf_message,
+                        )
+                    })
+                    .transpose()?,
             }
         } else {
             OutputRules {
                 content_type_rule: OutputContentTypeRule::None,
                 json_schema: None,
+                protobuf_message: None,
+            }
+        })
+    }
+
+    fn protobuf_message_from_schema(
+        svc_name: &str,
+        handler_name: &str,
+        position: &'static str,
+        protobuf_message: endpoint_manifest::ProtobufMessage,
+    ) -> Result<ProtobufMessage, ServiceError> {
+        let bad_protobuf_message = |error: Box<dyn std::error::Error + Send + Sync + 'static>| {
+            ServiceError::BadProtobufMessage {
+                service: svc_name.to_owned(),
+                handler: handler_name.to_owned(),
+                position,
+                error,
             }
+        };
+
+        let descriptor_set = BASE64_STANDARD
+            .decode(&protobuf_message.descriptor_set)
+            .map_err(|e| bad_protobuf_message(Box::new(e)))?;
+        ProtobufMessage::new(&protobuf_message.name, descriptor_set.into())
+            .map_err(|e| bad_protobuf_message(Box::new(e)))
+    }
+
+    fn resolve_mirroring(
+        deployments: &HashMap<DeploymentId, DeploymentSchemas>,
+        service_name: &str,
+        mirroring: &ServiceMirroring,
+    ) -> Result<InvocationTargetMirroring, SchemaError> {
+        let deployment = deployments.get(&mirroring.deployment_id).ok_or_else(|| {
+            SchemaError::NotFound(format!("deployment with id '{}'", mirroring.deployment_id))
+        })?;
+        if !deployment
+            .services
+            .iter()
+            .any(|service| service.name == service_name)
+        {
+            return Err(SchemaError::Service(ServiceError::BadMirroringDeployment(
+                mirroring.deployment_id,
+                "the deployment does not expose the service",
+            )));
+        }
+        let service_protocol_version = ServiceProtocolVersion::choose_max_supported_version(
+            &deployment.metadata.supported_protocol_versions,
+        )
+        .ok_or(SchemaError::Service(ServiceError::BadMirroringDeployment(
+            mirroring.deployment_id,
+            "the deployment does not support any compatible service protocol version",
+        )))?;
+        // The side effects of mirrored requests are suppressed by running them as dry-runs, which
+        // SDKs that didn't accept the extension would ignore
+        if !deployment
+            .metadata
+            .supports_protocol_extension(ProtocolExtension::DryRun)
+        {
+            return Err(SchemaError::Service(ServiceError::BadMirroringDeployment(
+                mirroring.deployment_id,
+                "the deployment does not support dry-run invocations, because it didn't accept the dry-run protocol extension when it was discovered",
+            )));
+        }
+
+        Ok(InvocationTargetMirroring {
+            pinned_deployment: PinnedDeployment::new(
+                mirroring.deployment_id,
+                service_protocol_version,
+            ),
+            fraction: mirroring.fraction,
+        })
+    }
+
+    fn resolve_routing(
+        deployments: &HashMap<DeploymentId, DeploymentSchemas>,
+        service_name: &str,
+        routing: &ServiceRouting,
+    ) -> Result<InvocationTargetRouting, SchemaError> {
+        let deployment = deployments.get(&routing.deployment_id).ok_or_else(|| {
+            SchemaError::NotFound(format!("deployment with id '{}'", routing.deployment_id))
+        })?;
+        if !deployment
+            .services
+            .iter()
+            .any(|service| service.name == service_name)
+        {
+            return Err(SchemaError::Service(ServiceError::BadRoutingDeployment(
+                routing.deployment_id,
+                "the deployment does not expose the service",
+            )));
+        }
+        if deployment.metadata.draining {
+            return Err(SchemaError::Service(ServiceError::BadRoutingDeployment(
+                routing.deployment_id,
+                "the deployment is draining",
+            )));
+        }
+        let service_protocol_version = ServiceProtocolVersion::choose_max_supported_version(
+            &deployment.metadata.supported_protocol_versions,
+        )
+        .ok_or(SchemaError::Service(ServiceError::BadRoutingDeployment(
+            routing.deployment_id,
+            "the deployment does not support any compatible service protocol version",
+        )))?;
+
+        Ok(InvocationTargetRouting {
+            pinned_deployment: PinnedDeployment::new(
+                routing.deployment_id,
+                service_protocol_version,
+            ),
+            percentage: routing.percentage,
+            header: routing.header.clone(),
         })
     }
 
@@ -660,7 +1309,7 @@ impl DiscoveredHandlerMetadata {
                     handler.name,
                     HandlerSchemas {
                         target_meta: InvocationTargetMetadata {
-                            public: true,
+                            public: !handler.ingress_private,
                             idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
                             completion_retention: if handler.ty
                                 == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
@@ -672,7 +1321,18 @@ impl DiscoveredHandlerMetadata {
                             target_ty: handler.ty,
                             input_rules: handler.input,
                             output_rules: handler.output,
+                            mirroring: None,
+                            routing: None,
+                            shared_concurrency_limit: None,
+                            allowed_callers: None,
                         },
+                        idempotency_retention: handler.idempotency_retention,
+                        completion_retention: handler.completion_retention,
+                        retry_policy: None,
+                        execution_timeout: None,
+                        inactivity_timeout: handler.inactivity_timeout,
+                        abort_timeout: handler.abort_timeout,
+                        ingress_private: handler.ingress_private,
                         documentation: handler.documentation,
                         metadata: handler.metadata,
                     },
@@ -686,9 +1346,15 @@ impl DiscoveredHandlerMetadata {
 mod tests {
     use super::*;
 
+    use enumset::EnumSet;
     use restate_test_util::{assert, assert_eq, let_assert};
+    use restate_types::config::IngressOptions;
+    use restate_types::identifiers::InvocationId;
+    use restate_types::retries::RetryPolicy;
     use restate_types::schema::deployment::{Deployment, DeploymentResolver};
-    use restate_types::schema::service::ServiceMetadataResolver;
+    use restate_types::schema::invocation_target::InvocationTargetResolver;
+    use restate_types::schema::service::{RetryPolicyOverrides, ServiceMetadataResolver};
+    use restate_types::schema::subscriptions::{SubscriptionResolver, WebhookProvider};
 
     use restate_types::Versioned;
     use test_log::test;
@@ -708,8 +1374,19 @@ mod tests {
                 input: None,
                 output: None,
                 metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
+                workflow_completion_retention: None,
             }],
             metadata: Default::default(),
+            abort_timeout: None,
+            idempotency_retention: None,
+            inactivity_timeout: None,
+            ingress_private: None,
+            journal_retention: None,
         }
     }
 
@@ -725,8 +1402,19 @@ mod tests {
                 input: None,
                 output: None,
                 metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
+                workflow_completion_retention: None,
             }],
             metadata: Default::default(),
+            abort_timeout: None,
+            idempotency_retention: None,
+            inactivity_timeout: None,
+            ingress_private: None,
+            journal_retention: None,
         }
     }
 
@@ -742,8 +1430,19 @@ mod tests {
                 input: None,
                 output: None,
                 metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
+                workflow_completion_retention: None,
             }],
             metadata: Default::default(),
+            abort_timeout: None,
+            idempotency_retention: None,
+            inactivity_timeout: None,
+            ingress_private: None,
+            journal_retention: None,
         }
     }
 
@@ -774,6 +1473,90 @@ mod tests {
         schema.assert_service_handler(GREETER_SERVICE_NAME, "greet");
     }
 
+    #[test]
+    fn rollback_requires_force_to_remove_services() {
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+
+        let mut updater = SchemaUpdater::default();
+        updater
+            .add_deployment(
+                Some(deployment_1.id),
+                deployment_1.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let v1 = updater.into_inner();
+
+        let mut updater = SchemaUpdater::new(v1.clone(), false);
+        updater
+            .add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata.clone(),
+                vec![greeter_service(), another_greeter_service()],
+                false,
+            )
+            .unwrap();
+        let v2 = updater.into_inner();
+        v2.assert_service_revision(GREETER_SERVICE_NAME, 2);
+
+        let mut updater = SchemaUpdater::new(v2.clone(), false);
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::RemovedService(_))) =
+                updater.rollback_to(v1.clone(), false)
+        );
+
+        let mut updater = SchemaUpdater::new(v2.clone(), false);
+        updater.rollback_to(v1, true).unwrap();
+        let v3 = updater.into_inner();
+
+        assert!(v2.version() < v3.version());
+        assert!(v3
+            .resolve_latest_service(ANOTHER_GREETER_SERVICE_NAME)
+            .is_none());
+        // The revision keeps increasing, while the deployment is the rolled back one
+        v3.assert_service_revision(GREETER_SERVICE_NAME, 3);
+        v3.assert_service_deployment(GREETER_SERVICE_NAME, deployment_1.id);
+    }
+
+    #[test]
+    fn rollback_without_removals_does_not_require_force() {
+        let deployment = Deployment::mock();
+
+        let mut updater = SchemaUpdater::default();
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let v1 = updater.into_inner();
+
+        let mut updater = SchemaUpdater::new(v1.clone(), false);
+        updater
+            .modify_service(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::Public(false)],
+            )
+            .unwrap();
+        let v2 = updater.into_inner();
+
+        let mut updater = SchemaUpdater::new(v2.clone(), false);
+        updater.rollback_to(v1, false).unwrap();
+        let v3 = updater.into_inner();
+
+        assert!(v2.version() < v3.version());
+        assert!(
+            v3.resolve_latest_service(GREETER_SERVICE_NAME)
+                .unwrap()
+                .public
+        );
+        v3.assert_service_revision(GREETER_SERVICE_NAME, 1);
+    }
+
     #[test]
     fn register_new_deployment_add_unregistered_service() {
         let mut updater = SchemaUpdater::default();
@@ -857,18 +1640,781 @@ mod tests {
         Ok(())
     }
 
-    mod change_instance_type {
-        use super::*;
-
-        use restate_test_util::assert;
-        use test_log::test;
-
-        #[test]
-        fn register_new_deployment_fails_changing_instance_type() {
-            let mut updater = SchemaUpdater::default();
+    #[test]
+    fn modify_allowed_callers() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
 
-            let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
-            let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::AllowedCallers(vec![
+                ANOTHER_GREETER_SERVICE_NAME.to_owned(),
+            ])],
+        )?;
+        let schemas = updater.into_inner();
+
+        let target = schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert!(target.is_caller_allowed(ANOTHER_GREETER_SERVICE_NAME));
+        assert!(!target.is_caller_allowed("greeter.Intruder"));
+
+        // The allowed callers survive the registration of a new revision
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+        let target = schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert!(!target.is_caller_allowed("greeter.Intruder"));
+
+        // An empty list allows any caller
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::AllowedCallers(vec![])],
+        )?;
+        let schemas = updater.into_inner();
+        assert!(schemas
+            .assert_service(GREETER_SERVICE_NAME)
+            .allowed_callers
+            .is_none());
+        let target = schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert!(target.is_caller_allowed("greeter.Intruder"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn modify_retention_policies() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![
+                ModifyServiceChange::CompletionRetention(Duration::from_secs(60 * 60)),
+                ModifyServiceChange::IdempotencyRetention(Duration::from_secs(5 * 60 * 60)),
+                ModifyServiceChange::HandlerRetention {
+                    handler: "greet".to_owned(),
+                    idempotency_retention: Some(Duration::from_secs(2 * 60 * 60)),
+                    completion_retention: None,
+                },
+            ],
+        )?;
+        assert!(updater
+            .modify_service(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::HandlerRetention {
+                    handler: "unknown".to_owned(),
+                    idempotency_retention: Some(Duration::ZERO),
+                    completion_retention: None,
+                }],
+            )
+            .is_err());
+
+        // The handler overrides survive the registration of a new revision
+        let mut updater = SchemaUpdater::new(updater.into_inner(), false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+
+        let target = schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert_eq!(
+            target.idempotency_retention,
+            Duration::from_secs(2 * 60 * 60)
+        );
+        assert_eq!(
+            target.completion_retention,
+            Some(Duration::from_secs(60 * 60))
+        );
+        assert_eq!(
+            schemas
+                .assert_service(GREETER_SERVICE_NAME)
+                .completion_retention,
+            Some(Duration::from_secs(60 * 60).into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn modify_shared_handler_concurrency() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        let mut virtual_object = greeter_virtual_object();
+        virtual_object.handlers.push(endpoint_manifest::Handler {
+            documentation: None,
+            name: "get_greetings".parse().unwrap(),
+            ty: Some(endpoint_manifest::HandlerType::Shared),
+            input: None,
+            output: None,
+            metadata: Default::default(),
+            abort_timeout: None,
+            idempotency_retention: None,
+            inactivity_timeout: None,
+            ingress_private: None,
+            journal_retention: None,
+            workflow_completion_retention: None,
+        });
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![virtual_object, another_greeter_service()],
+            false,
+        )?;
+
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::SharedHandlerConcurrency(3)],
+        )?;
+        let_assert!(
+            Err(SchemaError::Service(
+                ServiceError::CannotLimitSharedHandlerConcurrency(ServiceType::Service)
+            )) = updater.modify_service(
+                ANOTHER_GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::SharedHandlerConcurrency(3)],
+            )
+        );
+        let schemas = updater.into_inner();
+
+        // Only the shared handlers are limited
+        assert_eq!(
+            schemas
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "get_greetings")
+                .unwrap()
+                .shared_concurrency_limit,
+            NonZeroU32::new(3)
+        );
+        assert_eq!(
+            schemas
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+                .unwrap()
+                .shared_concurrency_limit,
+            None
+        );
+
+        // A zero limit removes it
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::SharedHandlerConcurrency(0)],
+        )?;
+        let schemas = updater.into_inner();
+        assert_eq!(
+            schemas
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "get_greetings")
+                .unwrap()
+                .shared_concurrency_limit,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn concurrency_limit_survives_new_revision() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+
+        updater.add_deployment(
+            Some(deployment_1.id),
+            deployment_1.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::ConcurrencyLimit(10)],
+        )?;
+        updater.add_deployment(
+            Some(deployment_2.id),
+            deployment_2.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        let schemas = updater.into_inner();
+
+        schemas.assert_service_revision(GREETER_SERVICE_NAME, 2);
+        assert_eq!(
+            schemas
+                .resolve_latest_service(GREETER_SERVICE_NAME)
+                .unwrap()
+                .concurrency_limit,
+            NonZeroU32::new(10)
+        );
+
+        // A zero limit removes it
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::ConcurrencyLimit(0)],
+        )?;
+        let schemas = updater.into_inner();
+        assert_eq!(
+            schemas
+                .resolve_latest_service(GREETER_SERVICE_NAME)
+                .unwrap()
+                .concurrency_limit,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn modify_service_without_changes_keeps_version() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock_with_uri("http://localhost:9080");
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::ConcurrencyLimit(10)],
+        )?;
+        let schemas = updater.into_inner();
+        let version = schemas.version();
+
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::ConcurrencyLimit(10)],
+        )?;
+        let schemas = updater.into_inner();
+        assert_eq!(schemas.version(), version);
+
+        let mut updater = SchemaUpdater::new(schemas, false);
+        assert!(matches!(
+            updater.modify_service(
+                "Unknown".to_owned(),
+                vec![ModifyServiceChange::ConcurrencyLimit(10)],
+            ),
+            Err(SchemaError::NotFound(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_deployment_endpoint_keeps_services() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock_with_uri("http://localhost:9080");
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        let schemas = updater.into_inner();
+        let version = schemas.version();
+
+        // Unchanged endpoint
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.update_deployment_endpoint(deployment.id, deployment.metadata.clone())?;
+        let schemas = updater.into_inner();
+        assert_eq!(schemas.version(), version);
+
+        let mut metadata = deployment.metadata.clone();
+        metadata.delivery_options.concurrency_limit = NonZeroU32::new(5);
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.update_deployment_endpoint(deployment.id, metadata)?;
+        let schemas = updater.into_inner();
+        assert_eq!(schemas.version(), version.next());
+
+        let (updated, services) = schemas.get_deployment_and_services(&deployment.id).unwrap();
+        assert_eq!(
+            updated.metadata.delivery_options.concurrency_limit,
+            NonZeroU32::new(5)
+        );
+        assert_eq!(services.len(), 1);
+        schemas.assert_service_revision(GREETER_SERVICE_NAME, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn modify_retry_policy() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![
+                ModifyServiceChange::RetryPolicy(RetryPolicyOverrides {
+                    transport_errors: Some(RetryPolicy::fixed_delay(Duration::from_secs(1), None)),
+                    max_duration: Some(Duration::from_secs(60 * 60).into()),
+                    ..Default::default()
+                }),
+                ModifyServiceChange::HandlerRetryPolicy {
+                    handler: "greet".to_owned(),
+                    retry_policy: RetryPolicyOverrides {
+                        terminal_errors: Some(RetryPolicy::None),
+                        max_duration: Some(Duration::from_secs(60).into()),
+                        ..Default::default()
+                    },
+                },
+            ],
+        )?;
+        assert!(updater
+            .modify_service(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::HandlerRetryPolicy {
+                    handler: "unknown".to_owned(),
+                    retry_policy: RetryPolicyOverrides::default(),
+                }],
+            )
+            .is_err());
+
+        // The handler overrides survive the registration of a new revision
+        let mut updater = SchemaUpdater::new(updater.into_inner(), false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        let handler_retry_policy = service.handlers[0].retry_policy.as_ref().unwrap();
+        assert!(handler_retry_policy.transport_errors.is_some());
+        assert!(handler_retry_policy.retryable_errors.is_none());
+        assert!(matches!(
+            handler_retry_policy.terminal_errors,
+            Some(RetryPolicy::None)
+        ));
+        assert_eq!(
+            handler_retry_policy.max_duration,
+            Some(Duration::from_secs(60).into())
+        );
+
+        // Empty overrides remove them
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![
+                ModifyServiceChange::RetryPolicy(RetryPolicyOverrides::default()),
+                ModifyServiceChange::HandlerRetryPolicy {
+                    handler: "greet".to_owned(),
+                    retry_policy: RetryPolicyOverrides::default(),
+                },
+            ],
+        )?;
+        let schemas = updater.into_inner();
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        assert!(service.retry_policy.is_none());
+        assert!(service.handlers[0].retry_policy.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn modify_execution_timeout() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::ExecutionTimeout(Duration::from_secs(
+                60 * 60,
+            ))],
+        )?;
+        let schemas = updater.into_inner();
+
+        // Handlers inherit the execution timeout of the service
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        assert_eq!(
+            service.execution_timeout,
+            Some(Duration::from_secs(60 * 60).into())
+        );
+        assert_eq!(
+            service.handlers[0].execution_timeout,
+            Some(Duration::from_secs(60 * 60).into())
+        );
+
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![
+                ModifyServiceChange::ExecutionTimeout(Duration::ZERO),
+                ModifyServiceChange::HandlerExecutionTimeout {
+                    handler: "greet".to_owned(),
+                    execution_timeout: Duration::from_secs(60),
+                },
+            ],
+        )?;
+
+        // The handler override survives the registration of a new revision
+        let mut updater = SchemaUpdater::new(updater.into_inner(), false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        assert!(service.execution_timeout.is_none());
+        assert_eq!(
+            service.handlers[0].execution_timeout,
+            Some(Duration::from_secs(60).into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_sdk_declared_options() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        let mut service = greeter_service();
+        service.inactivity_timeout = Some(30_000);
+        service.journal_retention = Some(60_000);
+        service.handlers[0].abort_timeout = Some(5_000);
+        service.handlers[0].idempotency_retention = Some(120_000);
+        service.handlers[0].ingress_private = Some(true);
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![service],
+            false,
+        )?;
+        let schemas = updater.into_inner();
+
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        assert!(service.public);
+        assert_eq!(
+            service.inactivity_timeout,
+            Some(Duration::from_secs(30).into())
+        );
+        assert_eq!(
+            service.completion_retention,
+            Some(Duration::from_secs(60).into())
+        );
+        let handler = &service.handlers[0];
+        assert!(!handler.public);
+        assert_eq!(
+            handler.inactivity_timeout,
+            Some(Duration::from_secs(30).into())
+        );
+        assert_eq!(handler.abort_timeout, Some(Duration::from_secs(5).into()));
+        assert_eq!(
+            handler.idempotency_retention,
+            Some(Duration::from_secs(120).into())
+        );
+
+        // The ingress private handler stays private when the service is made public again
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::Public(true)],
+        )?;
+        let schemas = updater.into_inner();
+        let target = schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert!(!target.public);
+
+        // Undeclaring the options on the next revision drops the handler ones
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        let handler = &service.handlers[0];
+        assert!(handler.public);
+        assert_eq!(
+            handler.inactivity_timeout,
+            Some(Duration::from_secs(30).into())
+        );
+        assert!(handler.abort_timeout.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_sqs_subscription() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            "sqs://eu-central-1/123456789012/my-queue.fifo"
+                .parse()
+                .unwrap(),
+            "service://greeter.Greeter/greet".parse().unwrap(),
+            None,
+            &IngressOptions::default(),
+        )?;
+        let schemas = updater.into_inner();
+
+        let subscription = schemas.get_subscription(subscription_id).unwrap();
+        assert_eq!(
+            subscription.source(),
+            &Source::Sqs {
+                region: "eu-central-1".to_owned(),
+                account_id: "123456789012".to_owned(),
+                queue: "my-queue.fifo".to_owned(),
+            }
+        );
+        assert_eq!(
+            subscription.source().sqs_queue_url(None).unwrap(),
+            "https://sqs.eu-central-1.amazonaws.com/123456789012/my-queue.fifo"
+        );
+
+        // The queue name is required
+        let mut updater = SchemaUpdater::new(schemas, false);
+        let_assert!(
+            Err(SchemaError::Subscription(
+                SubscriptionError::InvalidSqsSource(_)
+            )) = updater.add_subscription(
+                None,
+                "sqs://eu-central-1/123456789012".parse().unwrap(),
+                "service://greeter.Greeter/greet".parse().unwrap(),
+                None,
+                &IngressOptions::default(),
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_nats_subscription() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            "nats://localhost:4222/orders".parse().unwrap(),
+            "service://greeter.Greeter/greet".parse().unwrap(),
+            Some(HashMap::from([(
+                "restate.filter-subject".to_owned(),
+                "orders.*".to_owned(),
+            )])),
+            &IngressOptions::default(),
+        )?;
+        let schemas = updater.into_inner();
+
+        let subscription = schemas.get_subscription(subscription_id).unwrap();
+        assert_eq!(
+            subscription.source(),
+            &Source::Nats {
+                server: "localhost:4222".to_owned(),
+                stream: "orders".to_owned(),
+            }
+        );
+        assert_eq!(subscription.nats_filter_subject(), Some("orders.*"));
+
+        // Dead letter topics are not supported
+        let mut updater = SchemaUpdater::new(schemas, false);
+        let_assert!(
+            Err(SchemaError::Subscription(SubscriptionError::Validation(_))) = updater
+                .add_subscription(
+                    None,
+                    "nats://localhost:4222/orders".parse().unwrap(),
+                    "service://greeter.Greeter/greet".parse().unwrap(),
+                    Some(HashMap::from([(
+                        "restate.dead-letter-topic".to_owned(),
+                        "orders-dlq".to_owned(),
+                    )])),
+                    &IngressOptions::default(),
+                )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_webhook_subscription() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let subscription_id = updater.add_subscription(
+            None,
+            "webhook://github".parse().unwrap(),
+            "service://greeter.Greeter/greet".parse().unwrap(),
+            Some(HashMap::from([(
+                "restate.secret".to_owned(),
+                "my-secret".to_owned(),
+            )])),
+            &IngressOptions::default(),
+        )?;
+        let schemas = updater.into_inner();
+
+        let subscription = schemas.get_subscription(subscription_id).unwrap();
+        assert_eq!(
+            subscription.source(),
+            &Source::Webhook {
+                provider: WebhookProvider::Github
+            }
+        );
+        assert_eq!(subscription.webhook_secret(), Some("my-secret"));
+
+        // The secret is required
+        let mut updater = SchemaUpdater::new(schemas, false);
+        let_assert!(
+            Err(SchemaError::Subscription(SubscriptionError::Validation(_))) = updater
+                .add_subscription(
+                    None,
+                    "webhook://stripe".parse().unwrap(),
+                    "service://greeter.Greeter/greet".parse().unwrap(),
+                    None,
+                    &IngressOptions::default(),
+                )
+        );
+        let_assert!(
+            Err(SchemaError::Subscription(
+                SubscriptionError::InvalidWebhookSource(_)
+            )) = updater.add_subscription(
+                None,
+                "webhook://unknown".parse().unwrap(),
+                "service://greeter.Greeter/greet".parse().unwrap(),
+                None,
+                &IngressOptions::default(),
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn admin_overrides_take_precedence_over_sdk_declared_options() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+
+        let mut service = greeter_service();
+        service.inactivity_timeout = Some(30_000);
+        service.handlers[0].idempotency_retention = Some(120_000);
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![service.clone()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![
+                ModifyServiceChange::InactivityTimeout(Duration::from_secs(10)),
+                ModifyServiceChange::Public(false),
+                ModifyServiceChange::HandlerRetention {
+                    handler: "greet".to_owned(),
+                    idempotency_retention: Some(Duration::from_secs(60)),
+                    completion_retention: None,
+                },
+            ],
+        )?;
+        let schemas = updater.into_inner();
+
+        // Registering a new revision declaring the same options keeps the admin overrides
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![service],
+            true,
+        )?;
+        let schemas = updater.into_inner();
+
+        schemas.assert_service_revision(GREETER_SERVICE_NAME, 2);
+        let service = schemas.assert_service(GREETER_SERVICE_NAME);
+        assert!(!service.public);
+        assert_eq!(
+            service.inactivity_timeout,
+            Some(Duration::from_secs(10).into())
+        );
+        let handler = &service.handlers[0];
+        assert!(!handler.public);
+        assert_eq!(
+            handler.idempotency_retention,
+            Some(Duration::from_secs(60).into())
+        );
+
+        Ok(())
+    }
+
+    mod change_instance_type {
+        use super::*;
+
+        use restate_test_util::assert;
+        use test_log::test;
+
+        #[test]
+        fn register_new_deployment_fails_changing_instance_type() {
+            let mut updater = SchemaUpdater::default();
+
+            let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+            let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
 
             updater
                 .add_deployment(
@@ -1031,6 +2577,250 @@ mod tests {
         assert!(schemas.get_deployment(&deployment_1.id).is_none());
     }
 
+    #[test]
+    fn draining_deployment_is_not_resolved_for_new_invocations() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+
+        updater.add_deployment(
+            Some(deployment_1.id),
+            deployment_1.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.add_deployment(
+            Some(deployment_2.id),
+            deployment_2.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        updater.set_deployment_draining(deployment_2.id, true)?;
+        let schemas = updater.into_inner();
+        assert_eq!(
+            schemas
+                .resolve_latest_deployment_for_service(GREETER_SERVICE_NAME)
+                .unwrap()
+                .id,
+            deployment_1.id
+        );
+        // The service still belongs to the latest deployment
+        schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_2.id);
+
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.set_deployment_draining(deployment_1.id, true)?;
+        assert!(updater
+            .into_inner()
+            .resolve_latest_deployment_for_service(GREETER_SERVICE_NAME)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn route_new_invocations_to_canary_deployment() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+
+        updater.add_deployment(
+            Some(deployment_1.id),
+            deployment_1.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.add_deployment(
+            Some(deployment_2.id),
+            deployment_2.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::Routing(ServiceRouting {
+                deployment_id: deployment_1.id,
+                percentage: 100.0,
+                header: None,
+            })],
+        )?;
+        let schemas = updater.into_inner();
+
+        let invocation_id = InvocationId::mock_random();
+        assert_eq!(
+            schemas
+                .resolve_deployment_for_invocation(GREETER_SERVICE_NAME, &invocation_id)
+                .unwrap()
+                .id,
+            deployment_1.id
+        );
+        assert_eq!(
+            schemas
+                .resolve_latest_deployment_for_service(GREETER_SERVICE_NAME)
+                .unwrap()
+                .id,
+            deployment_2.id
+        );
+        let_assert!(
+            Some(routing) = schemas
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+                .unwrap()
+                .routing
+        );
+        assert_eq!(routing.pinned_deployment.deployment_id, deployment_1.id);
+
+        // Removing the canary deployment disables the routing
+        let mut updater = SchemaUpdater::new(schemas, false);
+        updater.remove_deployment(deployment_1.id);
+        let schemas = updater.into_inner();
+        assert!(schemas
+            .resolve_latest_service(GREETER_SERVICE_NAME)
+            .unwrap()
+            .routing
+            .is_none());
+        assert_eq!(
+            schemas
+                .resolve_deployment_for_invocation(GREETER_SERVICE_NAME, &invocation_id)
+                .unwrap()
+                .id,
+            deployment_2.id
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_mirroring_to_deployment_without_dry_run_support() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+
+        let mut deployment = Deployment::mock_with_uri("http://localhost:9080");
+        deployment.metadata.protocol_extensions = EnumSet::only(ProtocolExtension::DryRun);
+        let shadow_deployment = Deployment::mock_with_uri("http://localhost:9081");
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.add_deployment(
+            Some(shadow_deployment.id),
+            shadow_deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::BadMirroringDeployment(
+                deployment_id,
+                _
+            ))) = updater.modify_service(
+                GREETER_SERVICE_NAME.to_owned(),
+                vec![ModifyServiceChange::Mirroring(ServiceMirroring {
+                    deployment_id: shadow_deployment.id,
+                    fraction: 0.5,
+                })],
+            )
+        );
+        assert_eq!(deployment_id, shadow_deployment.id);
+
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::Mirroring(ServiceMirroring {
+                deployment_id: deployment.id,
+                fraction: 0.5,
+            })],
+        )?;
+        let_assert!(
+            Some(mirroring) = updater
+                .into_inner()
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+                .unwrap()
+                .mirroring
+        );
+        assert_eq!(mirroring.pinned_deployment.deployment_id, deployment.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn disable_mirroring_when_shadow_deployment_loses_dry_run_support() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment = Deployment::mock_with_uri("http://localhost:9080");
+        let mut shadow_deployment = Deployment::mock_with_uri("http://localhost:9081");
+        shadow_deployment.metadata.protocol_extensions = EnumSet::only(ProtocolExtension::DryRun);
+
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.add_deployment(
+            Some(shadow_deployment.id),
+            shadow_deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+        updater.modify_service(
+            GREETER_SERVICE_NAME.to_owned(),
+            vec![ModifyServiceChange::Mirroring(ServiceMirroring {
+                deployment_id: shadow_deployment.id,
+                fraction: 0.5,
+            })],
+        )?;
+
+        // The shadow deployment is updated with an SDK ignoring the dry-run header
+        let mut updated_metadata = shadow_deployment.metadata.clone();
+        updated_metadata.protocol_extensions = EnumSet::empty();
+        updater.update_deployment_endpoint(shadow_deployment.id, updated_metadata)?;
+
+        let schemas = updater.into_inner();
+        assert!(schemas
+            .resolve_latest_service(GREETER_SERVICE_NAME)
+            .unwrap()
+            .mirroring
+            .is_none());
+        assert!(schemas
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap()
+            .mirroring
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_routing_percentage_out_of_range() -> Result<(), SchemaError> {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment = Deployment::mock();
+        updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata.clone(),
+            vec![greeter_service()],
+            false,
+        )?;
+
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::BadRoutingPercentage(_))) = updater
+                .modify_service(
+                    GREETER_SERVICE_NAME.to_owned(),
+                    vec![ModifyServiceChange::Routing(ServiceRouting {
+                        deployment_id: deployment.id,
+                        percentage: 150.0,
+                        header: None,
+                    })],
+                )
+        );
+
+        Ok(())
+    }
+
     mod remove_method {
         use super::*;
 
@@ -1050,6 +2840,12 @@ mod tests {
                         input: None,
                         output: None,
                         metadata: Default::default(),
+                        abort_timeout: None,
+                        idempotency_retention: None,
+                        inactivity_timeout: None,
+                        ingress_private: None,
+                        journal_retention: None,
+                        workflow_completion_retention: None,
                     },
                     endpoint_manifest::Handler {
                         documentation: None,
@@ -1058,9 +2854,20 @@ mod tests {
                         input: None,
                         output: None,
                         metadata: Default::default(),
+                        abort_timeout: None,
+                        idempotency_retention: None,
+                        inactivity_timeout: None,
+                        ingress_private: None,
+                        journal_retention: None,
+                        workflow_completion_retention: None,
                     },
                 ],
                 metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
             }
         }
 
@@ -1076,8 +2883,19 @@ mod tests {
                     input: None,
                     output: None,
                     metadata: Default::default(),
+                    abort_timeout: None,
+                    idempotency_retention: None,
+                    inactivity_timeout: None,
+                    ingress_private: None,
+                    journal_retention: None,
+                    workflow_completion_retention: None,
                 }],
                 metadata: Default::default(),
+                abort_timeout: None,
+                idempotency_retention: None,
+                inactivity_timeout: None,
+                ingress_private: None,
+                journal_retention: None,
             }
         }
 
@@ -9,23 +9,30 @@
 // by the Apache License, Version 2.0.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::error_handling::HandleErrorLayer;
 use http::StatusCode;
 use restate_bifrost::Bifrost;
-use restate_types::config::AdminOptions;
+use restate_types::config::{AdminOptions, Configuration};
 use restate_types::live::LiveLoad;
 use tower::ServiceBuilder;
+use tracing::{info, warn};
 
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::network::net_util;
-use restate_core::MetadataWriter;
+use restate_core::{MetadataWriter, TaskCenter, TaskKind};
+use restate_partition_store::snapshots::SnapshotRepository;
 use restate_service_protocol::discovery::ServiceDiscovery;
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::net::BindAddress;
+use restate_types::retries::RetryPolicy;
 use restate_types::schema::subscriptions::SubscriptionValidator;
 
+use crate::audit::{self, AuditLog};
+use crate::schema_registry::descriptor::StaticDescriptor;
 use crate::schema_registry::SchemaRegistry;
+use crate::storage_query::SnapshotInspector;
 use crate::{rest_api, state, storage_query};
 
 #[derive(Debug, thiserror::Error)]
@@ -34,6 +41,8 @@ pub struct BuildError(#[from] restate_service_client::BuildError);
 
 pub struct AdminService<V> {
     bifrost: Bifrost,
+    metadata_writer: MetadataWriter,
+    metadata_store_client: MetadataStoreClient,
     schema_registry: SchemaRegistry<V>,
     query_context: Option<QueryContext>,
 }
@@ -53,6 +62,8 @@ where
     ) -> Self {
         Self {
             bifrost,
+            metadata_writer: metadata_writer.clone(),
+            metadata_store_client: metadata_store_client.clone(),
             schema_registry: SchemaRegistry::new(
                 metadata_store_client,
                 metadata_writer,
@@ -70,12 +81,69 @@ where
     ) -> anyhow::Result<()> {
         let opts = updateable_config.live_load();
 
-        let rest_state = state::AdminServiceState::new(self.schema_registry, self.bifrost);
+        if let Some(path) = &opts.static_descriptor {
+            // fail the start-up on an invalid descriptor, but don't block the admin api on
+            // the discovery of its deployments.
+            let descriptor = StaticDescriptor::load(path)?;
+            if !descriptor.is_empty() {
+                let schema_registry = self.schema_registry.clone();
+                TaskCenter::spawn_child(
+                    TaskKind::Disposable,
+                    "apply-static-descriptor",
+                    async move {
+                        // deployments might not be reachable yet while the cluster starts up
+                        RetryPolicy::exponential(
+                            Duration::from_millis(500),
+                            2.0,
+                            Some(10),
+                            Some(Duration::from_secs(30)),
+                        )
+                        .retry(|| async {
+                            schema_registry
+                                .apply_descriptor(&descriptor)
+                                .await
+                                .inspect_err(|err| {
+                                    warn!("Failed applying the static deployment descriptor: {err}")
+                                })
+                        })
+                        .await?;
+                        info!("Applied the static deployment descriptor");
+                        Ok(())
+                    },
+                )?;
+            }
+        }
+
+        let (audit_log, audit_log_writer) = AuditLog::new(&opts);
+        if let Some(audit_log_writer) = audit_log_writer {
+            TaskCenter::spawn_child(
+                TaskKind::Disposable,
+                "audit-log-writer",
+                audit_log_writer.run(),
+            )?;
+        }
+
+        let rest_state = state::AdminServiceState::new(
+            self.schema_registry,
+            self.bifrost.clone(),
+            self.metadata_writer,
+            self.metadata_store_client,
+            audit_log.clone(),
+        );
+
+        // the snapshots uploaded by the partition processors can be queried as well
+        let snapshot_inspector =
+            SnapshotRepository::create_if_configured(&Configuration::pinned().worker.snapshots)?
+                .map(|repository| SnapshotInspector::new(repository, opts.snapshot_queries_dir()));
 
         let router = self
             .query_context
             .map(|query_context| {
-                let query_state = Arc::new(state::QueryServiceState { query_context });
+                let query_state = Arc::new(state::QueryServiceState {
+                    query_context,
+                    bifrost: self.bifrost,
+                    snapshots: snapshot_inspector,
+                });
 
                 axum::Router::new().merge(storage_query::create_router(query_state))
             })
@@ -85,17 +153,33 @@ where
         #[cfg(feature = "serve-web-ui")]
         let router = router.merge(crate::web_ui::web_ui_router());
 
+        // Merge debug API router
+        let router = if opts.enable_debug_endpoints {
+            warn!("Admin debug endpoints are enabled. Do not use this setting in production.");
+            router.merge(rest_api::create_debug_router(rest_state.clone()))
+        } else {
+            router
+        };
+
         // Merge meta API router
-        let router = router.merge(rest_api::create_router(rest_state)).layer(
-            ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(|_| async {
-                    StatusCode::TOO_MANY_REQUESTS
-                }))
-                .layer(tower::load_shed::LoadShedLayer::new())
-                .layer(tower::limit::GlobalConcurrencyLimitLayer::new(
-                    opts.concurrent_api_requests_limit(),
-                )),
-        );
+        let router = router.merge(rest_api::create_router(rest_state));
+
+        // Record the mutations of all the merged routers in the audit log
+        let router = router
+            .layer(axum::middleware::from_fn_with_state(
+                audit_log,
+                audit::record_mutations,
+            ))
+            .layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(|_| async {
+                        StatusCode::TOO_MANY_REQUESTS
+                    }))
+                    .layer(tower::load_shed::LoadShedLayer::new())
+                    .layer(tower::limit::GlobalConcurrencyLimitLayer::new(
+                        opts.concurrent_api_requests_limit(),
+                    )),
+            );
 
         let service = hyper_util::service::TowerToHyperService::new(router.into_service());
 
@@ -103,6 +187,7 @@ where
             &BindAddress::Socket(opts.bind_address),
             service,
             "admin-api-server",
+            None,
             || (),
             || (),
         )
@@ -8,26 +8,45 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use crate::audit::AuditLog;
 use crate::schema_registry::SchemaRegistry;
+use crate::storage_query::SnapshotInspector;
 use restate_bifrost::Bifrost;
+use restate_core::metadata_store::MetadataStoreClient;
+use restate_core::MetadataWriter;
 use restate_storage_query_datafusion::context::QueryContext;
 
 #[derive(Clone, derive_builder::Builder)]
 pub struct AdminServiceState<V> {
     pub schema_registry: SchemaRegistry<V>,
     pub bifrost: Bifrost,
+    pub metadata_writer: MetadataWriter,
+    pub metadata_store_client: MetadataStoreClient,
+    pub audit_log: AuditLog,
 }
 
 #[derive(Clone)]
 pub struct QueryServiceState {
     pub query_context: QueryContext,
+    pub bifrost: Bifrost,
+    /// Set if a snapshot repository is configured
+    pub snapshots: Option<SnapshotInspector>,
 }
 
 impl<V> AdminServiceState<V> {
-    pub fn new(schema_registry: SchemaRegistry<V>, bifrost: Bifrost) -> Self {
+    pub fn new(
+        schema_registry: SchemaRegistry<V>,
+        bifrost: Bifrost,
+        metadata_writer: MetadataWriter,
+        metadata_store_client: MetadataStoreClient,
+        audit_log: AuditLog,
+    ) -> Self {
         Self {
             schema_registry,
             bifrost,
+            metadata_writer,
+            metadata_store_client,
+            audit_log,
         }
     }
 }
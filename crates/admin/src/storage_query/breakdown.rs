@@ -0,0 +1,422 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{Date64Type, UInt32Type, UInt64Type};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use okapi_operation::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use restate_types::identifiers::InvocationId;
+use restate_types::time::MillisSinceEpoch;
+
+use super::error::StorageQueryError;
+use crate::state::QueryServiceState;
+
+/// # Invocation breakdown
+///
+/// Where an invocation spent its time, in milliseconds. The phases are derived from the status
+/// transition timestamps of the invocation. Only the current suspension and retry backoff can be
+/// attributed, earlier suspensions and backoffs are accounted as running time.
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct InvocationBreakdown {
+    /// # Invocation id
+    pub invocation_id: String,
+    /// # Status
+    ///
+    /// Same as the `status` column of `sys_invocation`.
+    pub status: String,
+    /// # Total
+    ///
+    /// Time since the creation of the invocation until its completion, or until now.
+    pub total_ms: u64,
+    /// # Scheduled
+    ///
+    /// Time waiting for the requested execution time of a delayed invocation.
+    pub scheduled_ms: u64,
+    /// # Queued
+    ///
+    /// Time waiting to be started, e.g. in the inbox of a virtual object.
+    pub queued_ms: u64,
+    /// # Running
+    ///
+    /// Time the invocation was executing on the service endpoint.
+    pub running_ms: u64,
+    /// # Suspended
+    ///
+    /// Time the invocation has been suspended for, if it is currently suspended.
+    pub suspended_ms: u64,
+    /// # Retry backoff
+    ///
+    /// Time left until the next attempt, if the invocation is currently backing off.
+    pub retry_backoff_ms: u64,
+    /// # Retry count
+    pub retry_count: u64,
+    /// # Journal size
+    pub journal_size: u32,
+    /// # Awaiting
+    ///
+    /// Uncompleted journal entries the invocation is waiting on.
+    pub awaiting: Vec<AwaitedEntry>,
+}
+
+/// # Awaited entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct AwaitedEntry {
+    pub index: u32,
+    pub kind: AwaitedEntryKind,
+    pub entry_type: String,
+    /// # Sleep wake up time
+    ///
+    /// Milliseconds since the unix epoch at which a sleep completes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wakeup_at: Option<u64>,
+    /// # Invoked id
+    ///
+    /// Id of the invocation a call is waiting on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoked_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AwaitedEntryKind {
+    Sleep,
+    Call,
+    Awakeable,
+    Promise,
+}
+
+impl AwaitedEntryKind {
+    fn from_entry_type(entry_type: &str) -> Option<Self> {
+        match entry_type {
+            "Sleep" => Some(AwaitedEntryKind::Sleep),
+            "Call" | "AttachInvocation" | "GetInvocationOutput" => Some(AwaitedEntryKind::Call),
+            "Awakeable" => Some(AwaitedEntryKind::Awakeable),
+            "GetPromise" => Some(AwaitedEntryKind::Promise),
+            _ => None,
+        }
+    }
+}
+
+/// Status timestamps of an invocation, in milliseconds since the unix epoch.
+#[derive(Debug, Default)]
+struct StatusRow {
+    status: String,
+    created_at: u64,
+    modified_at: u64,
+    inboxed_at: Option<u64>,
+    scheduled_at: Option<u64>,
+    running_at: Option<u64>,
+    completed_at: Option<u64>,
+    next_retry_at: Option<u64>,
+    retry_count: u64,
+    journal_size: u32,
+}
+
+/// Invocation latency breakdown
+#[openapi(
+    summary = "Invocation latency breakdown",
+    description = "Computes where the given invocation spent its time, from its status timestamps \
+    and journal.",
+    operation_id = "invocation_breakdown",
+    tags = "storage",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn invocation_breakdown(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(invocation_id): Path<String>,
+) -> Result<Json<InvocationBreakdown>, StorageQueryError> {
+    // parsing guarantees that the id can be safely embedded in the queries
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| StorageQueryError::InvalidInvocationId(e.to_string()))?;
+
+    let status = query_batches(
+        &state,
+        format!(
+            "SELECT status, created_at, modified_at, inboxed_at, scheduled_at, running_at, \
+            completed_at, next_retry_at, retry_count, journal_size \
+            FROM sys_invocation WHERE id = '{invocation_id}'"
+        ),
+    )
+    .await?;
+    let status = read_status_row(&status)?
+        .ok_or_else(|| StorageQueryError::InvocationNotFound(invocation_id.to_string()))?;
+
+    let journal = query_batches(
+        &state,
+        format!(
+            "SELECT index, entry_type, completed, sleep_wakeup_at, invoked_id \
+            FROM sys_journal WHERE id = '{invocation_id}' ORDER BY index"
+        ),
+    )
+    .await?;
+    let awaiting = read_awaited_entries(&journal)?;
+
+    Ok(Json(compute_breakdown(
+        invocation_id.to_string(),
+        status,
+        awaiting,
+        MillisSinceEpoch::now().as_u64(),
+    )))
+}
+
+pub(super) async fn query_batches(
+    state: &QueryServiceState,
+    query: String,
+) -> Result<Vec<RecordBatch>, StorageQueryError> {
+    Ok(state
+        .query_context
+        .execute(&query)
+        .await?
+        .try_collect()
+        .await?)
+}
+
+fn compute_breakdown(
+    invocation_id: String,
+    status: StatusRow,
+    awaiting: Vec<AwaitedEntry>,
+    now: u64,
+) -> InvocationBreakdown {
+    let end = status.completed_at.unwrap_or(now);
+    let started = status.running_at.unwrap_or(end);
+
+    // a delayed invocation waits for its execution time before it is inboxed or started
+    let (scheduled_ms, queued_since) = if status.scheduled_at.is_some() {
+        let ready_at = status.inboxed_at.unwrap_or(started);
+        (ready_at.saturating_sub(status.created_at), ready_at)
+    } else {
+        (0, status.created_at)
+    };
+
+    let active_ms = status
+        .running_at
+        .map(|running_at| end.saturating_sub(running_at))
+        .unwrap_or_default();
+    let suspended_ms = if status.status == "suspended" {
+        now.saturating_sub(status.modified_at)
+    } else {
+        0
+    };
+    let retry_backoff_ms = if status.status == "backing-off" {
+        status
+            .next_retry_at
+            .map(|next_retry_at| next_retry_at.saturating_sub(now))
+            .unwrap_or_default()
+    } else {
+        0
+    };
+
+    InvocationBreakdown {
+        invocation_id,
+        total_ms: end.saturating_sub(status.created_at),
+        scheduled_ms,
+        queued_ms: started.saturating_sub(queued_since),
+        running_ms: active_ms.saturating_sub(suspended_ms),
+        suspended_ms,
+        retry_backoff_ms,
+        retry_count: status.retry_count,
+        journal_size: status.journal_size,
+        status: status.status,
+        awaiting,
+    }
+}
+
+fn read_status_row(batches: &[RecordBatch]) -> Result<Option<StatusRow>, StorageQueryError> {
+    let Some(batch) = batches.iter().find(|batch| batch.num_rows() > 0) else {
+        return Ok(None);
+    };
+
+    Ok(Some(StatusRow {
+        status: string_value(batch, "status", 0)?.unwrap_or_default(),
+        created_at: date_value(batch, "created_at", 0)?.unwrap_or_default(),
+        modified_at: date_value(batch, "modified_at", 0)?.unwrap_or_default(),
+        inboxed_at: date_value(batch, "inboxed_at", 0)?,
+        scheduled_at: date_value(batch, "scheduled_at", 0)?,
+        running_at: date_value(batch, "running_at", 0)?,
+        completed_at: date_value(batch, "completed_at", 0)?,
+        next_retry_at: date_value(batch, "next_retry_at", 0)?,
+        retry_count: column(batch, "retry_count")?
+            .as_primitive_opt::<UInt64Type>()
+            .filter(|array| array.is_valid(0))
+            .map(|array| array.value(0))
+            .unwrap_or_default(),
+        journal_size: column(batch, "journal_size")?
+            .as_primitive_opt::<UInt32Type>()
+            .filter(|array| array.is_valid(0))
+            .map(|array| array.value(0))
+            .unwrap_or_default(),
+    }))
+}
+
+fn read_awaited_entries(batches: &[RecordBatch]) -> Result<Vec<AwaitedEntry>, StorageQueryError> {
+    let mut awaiting = Vec::new();
+    for batch in batches {
+        let indexes = column(batch, "index")?
+            .as_primitive_opt::<UInt32Type>()
+            .ok_or_else(|| unexpected_type("index"))?;
+        let completed = column(batch, "completed")?
+            .as_boolean_opt()
+            .ok_or_else(|| unexpected_type("completed"))?;
+
+        for row in 0..batch.num_rows() {
+            if completed.is_valid(row) && completed.value(row) {
+                continue;
+            }
+            let Some(entry_type) = string_value(batch, "entry_type", row)? else {
+                continue;
+            };
+            let Some(kind) = AwaitedEntryKind::from_entry_type(&entry_type) else {
+                continue;
+            };
+
+            awaiting.push(AwaitedEntry {
+                index: indexes.value(row),
+                kind,
+                entry_type,
+                wakeup_at: date_value(batch, "sleep_wakeup_at", row)?,
+                invoked_id: string_value(batch, "invoked_id", row)?,
+            });
+        }
+    }
+    Ok(awaiting)
+}
+
+pub(super) fn column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a dyn Array, StorageQueryError> {
+    batch
+        .column_by_name(name)
+        .map(|column| column.as_ref())
+        .ok_or_else(|| StorageQueryError::UnexpectedResult(format!("missing column '{name}'")))
+}
+
+pub(super) fn string_value(
+    batch: &RecordBatch,
+    name: &str,
+    row: usize,
+) -> Result<Option<String>, StorageQueryError> {
+    let array = column(batch, name)?
+        .as_string_opt::<i64>()
+        .ok_or_else(|| unexpected_type(name))?;
+    Ok(array.is_valid(row).then(|| array.value(row).to_owned()))
+}
+
+pub(super) fn date_value(
+    batch: &RecordBatch,
+    name: &str,
+    row: usize,
+) -> Result<Option<u64>, StorageQueryError> {
+    let array = column(batch, name)?
+        .as_primitive_opt::<Date64Type>()
+        .ok_or_else(|| unexpected_type(name))?;
+    Ok(array
+        .is_valid(row)
+        .then(|| u64::try_from(array.value(row)).unwrap_or_default()))
+}
+
+pub(super) fn unexpected_type(name: &str) -> StorageQueryError {
+    StorageQueryError::UnexpectedResult(format!("unexpected type of column '{name}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn awaited_sleep() -> AwaitedEntry {
+        AwaitedEntry {
+            index: 2,
+            kind: AwaitedEntryKind::Sleep,
+            entry_type: "Sleep".to_owned(),
+            wakeup_at: Some(10_000),
+            invoked_id: None,
+        }
+    }
+
+    #[test]
+    fn breakdown_of_suspended_invocation() {
+        let status = StatusRow {
+            status: "suspended".to_owned(),
+            created_at: 1_000,
+            modified_at: 1_500,
+            inboxed_at: Some(1_000),
+            running_at: Some(1_200),
+            retry_count: 1,
+            journal_size: 3,
+            ..Default::default()
+        };
+
+        let breakdown = compute_breakdown("inv".to_owned(), status, vec![awaited_sleep()], 2_000);
+
+        assert_eq!(breakdown.total_ms, 1_000);
+        assert_eq!(breakdown.scheduled_ms, 0);
+        assert_eq!(breakdown.queued_ms, 200);
+        assert_eq!(breakdown.running_ms, 300);
+        assert_eq!(breakdown.suspended_ms, 500);
+        assert_eq!(breakdown.retry_backoff_ms, 0);
+        assert_eq!(breakdown.awaiting, vec![awaited_sleep()]);
+    }
+
+    #[test]
+    fn breakdown_of_delayed_completed_invocation() {
+        let status = StatusRow {
+            status: "completed".to_owned(),
+            created_at: 1_000,
+            modified_at: 5_000,
+            scheduled_at: Some(1_000),
+            inboxed_at: Some(3_000),
+            running_at: Some(3_500),
+            completed_at: Some(5_000),
+            ..Default::default()
+        };
+
+        let breakdown = compute_breakdown("inv".to_owned(), status, vec![], 10_000);
+
+        assert_eq!(breakdown.total_ms, 4_000);
+        assert_eq!(breakdown.scheduled_ms, 2_000);
+        assert_eq!(breakdown.queued_ms, 500);
+        assert_eq!(breakdown.running_ms, 1_500);
+        assert_eq!(breakdown.suspended_ms, 0);
+    }
+
+    #[test]
+    fn breakdown_of_backing_off_invocation() {
+        let status = StatusRow {
+            status: "backing-off".to_owned(),
+            created_at: 1_000,
+            modified_at: 1_100,
+            running_at: Some(1_100),
+            next_retry_at: Some(2_500),
+            retry_count: 3,
+            ..Default::default()
+        };
+
+        let breakdown = compute_breakdown("inv".to_owned(), status, vec![], 2_000);
+
+        assert_eq!(breakdown.queued_ms, 100);
+        assert_eq!(breakdown.running_ms, 900);
+        assert_eq!(breakdown.retry_backoff_ms, 500);
+        assert_eq!(breakdown.retry_count, 3);
+    }
+}
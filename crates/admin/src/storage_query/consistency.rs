@@ -0,0 +1,225 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::UInt64Type;
+use datafusion::arrow::record_batch::RecordBatch;
+use okapi_operation::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use restate_storage_api::consistency::ConsistencyRepair;
+use restate_types::identifiers::{IdempotencyId, InvocationId, ServiceId, WithPartitionKey};
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+
+use super::breakdown::{column, query_batches, string_value, unexpected_type};
+use super::error::StorageQueryError;
+use crate::rest_api::create_envelope_header;
+use crate::state::QueryServiceState;
+
+const VIOLATION_COLUMNS: &str = "partition_key, kind, invocation_id, service_name, service_key, \
+    service_handler, idempotency_key, inbox_sequence_number, description, repairable";
+
+/// # Consistency violations
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ConsistencyViolationsResponse {
+    /// # Violations
+    pub violations: Vec<ConsistencyViolationResponse>,
+}
+
+/// # Consistency violation
+///
+/// Invariant between the tables of a partition which doesn't hold.
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ConsistencyViolationResponse {
+    /// # Partition key
+    pub partition_key: u64,
+    /// # Kind
+    ///
+    /// Same as the `kind` column of `sys_consistency_violation`.
+    pub kind: String,
+    /// # Invocation id
+    ///
+    /// The invocation the violating record refers to.
+    pub invocation_id: String,
+    /// # Description
+    pub description: String,
+    /// # Repairable
+    ///
+    /// If true, the violation is known to be benign and is repaired by the repair endpoint.
+    pub repairable: bool,
+}
+
+/// List the consistency violations
+#[openapi(
+    summary = "List consistency violations",
+    description = "Checks the invariants between the tables of every partition, e.g. that every \
+    inbox entry points at an inboxed invocation, and lists the violated ones. The check reads \
+    all the tables of the partitions, and can report spurious violations of invocations which \
+    progress while it runs.",
+    operation_id = "list_consistency_violations",
+    tags = "storage",
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn list_consistency_violations(
+    State(state): State<Arc<QueryServiceState>>,
+) -> Result<Json<ConsistencyViolationsResponse>, StorageQueryError> {
+    let batches = query_batches(
+        &state,
+        format!(
+            "SELECT {VIOLATION_COLUMNS} FROM sys_consistency_violation \
+            ORDER BY partition_key, kind"
+        ),
+    )
+    .await?;
+
+    Ok(Json(ConsistencyViolationsResponse {
+        violations: read_violations(&batches)?
+            .into_iter()
+            .map(|(violation, _)| violation)
+            .collect(),
+    }))
+}
+
+/// Repair the benign consistency violations
+#[openapi(
+    summary = "Repair consistency violations",
+    description = "Repairs the consistency violations which are known to be benign, i.e. \
+    orphaned inbox entries and dangling idempotency keys, by deleting the violating records. \
+    Each repair is applied by the partition as a command, after checking that the violation \
+    still holds. Returns the violations whose repair was requested.",
+    operation_id = "repair_consistency_violations",
+    tags = "storage",
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn repair_consistency_violations(
+    State(state): State<Arc<QueryServiceState>>,
+) -> Result<Json<ConsistencyViolationsResponse>, StorageQueryError> {
+    let batches = query_batches(
+        &state,
+        format!(
+            "SELECT {VIOLATION_COLUMNS} FROM sys_consistency_violation WHERE repairable \
+            ORDER BY partition_key, kind"
+        ),
+    )
+    .await?;
+
+    let mut violations = Vec::new();
+    for (violation, repair) in read_violations(&batches)? {
+        let Some(repair) = repair else {
+            continue;
+        };
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(repair.partition_key()),
+                Command::RepairConsistency(repair),
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append consistency repair command to Bifrost: {err}");
+            return Err(StorageQueryError::Append(err.to_string()));
+        }
+        violations.push(violation);
+    }
+    info!(
+        "Requested the repair of {} consistency violations",
+        violations.len()
+    );
+
+    Ok(Json(ConsistencyViolationsResponse { violations }))
+}
+
+fn read_violations(
+    batches: &[RecordBatch],
+) -> Result<Vec<(ConsistencyViolationResponse, Option<ConsistencyRepair>)>, StorageQueryError> {
+    let mut violations = Vec::new();
+    for batch in batches {
+        let partition_keys = column(batch, "partition_key")?
+            .as_primitive_opt::<UInt64Type>()
+            .ok_or_else(|| unexpected_type("partition_key"))?;
+        let inbox_sequence_numbers = column(batch, "inbox_sequence_number")?
+            .as_primitive_opt::<UInt64Type>()
+            .ok_or_else(|| unexpected_type("inbox_sequence_number"))?;
+        let repairables = column(batch, "repairable")?
+            .as_boolean_opt()
+            .ok_or_else(|| unexpected_type("repairable"))?;
+
+        for row in 0..batch.num_rows() {
+            let kind = string_value(batch, "kind", row)?.unwrap_or_default();
+            let invocation_id = string_value(batch, "invocation_id", row)?.unwrap_or_default();
+            let repairable = repairables.is_valid(row) && repairables.value(row);
+
+            let repair = if repairable {
+                let service_name = required_string_value(batch, "service_name", row)?;
+                let service_key = string_value(batch, "service_key", row)?;
+                let invocation_id = invocation_id.parse::<InvocationId>().map_err(|err| {
+                    StorageQueryError::UnexpectedResult(format!("invalid invocation id: {err}"))
+                })?;
+                match kind.as_str() {
+                    "orphaned_inbox_entry" if inbox_sequence_numbers.is_valid(row) => {
+                        Some(ConsistencyRepair::DeleteInboxEntry {
+                            service_id: ServiceId::new(
+                                service_name,
+                                service_key.unwrap_or_default(),
+                            ),
+                            inbox_sequence_number: inbox_sequence_numbers.value(row),
+                            invocation_id,
+                        })
+                    }
+                    "dangling_idempotency_key" => Some(ConsistencyRepair::DeleteIdempotencyKey {
+                        idempotency_id: IdempotencyId::new(
+                            service_name.into(),
+                            service_key.map(Into::into),
+                            required_string_value(batch, "service_handler", row)?.into(),
+                            required_string_value(batch, "idempotency_key", row)?.into(),
+                        ),
+                        invocation_id,
+                    }),
+                    _ => {
+                        return Err(StorageQueryError::UnexpectedResult(format!(
+                            "unexpected repairable violation '{kind}'"
+                        )))
+                    }
+                }
+            } else {
+                None
+            };
+
+            violations.push((
+                ConsistencyViolationResponse {
+                    partition_key: partition_keys.value(row),
+                    kind,
+                    invocation_id,
+                    description: string_value(batch, "description", row)?.unwrap_or_default(),
+                    repairable,
+                },
+                repair,
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+fn required_string_value(
+    batch: &RecordBatch,
+    name: &str,
+    row: usize,
+) -> Result<String, StorageQueryError> {
+    string_value(batch, name, row)?
+        .ok_or_else(|| StorageQueryError::UnexpectedResult(format!("missing value of '{name}'")))
+}
@@ -25,6 +25,26 @@ use serde::Serialize;
 pub enum StorageQueryError {
     #[error("datafusion failed: {0}")]
     DataFusion(#[from] DataFusionError),
+    #[error("invalid invocation id: {0}")]
+    InvalidInvocationId(String),
+    #[error("invocation '{0}' not found")]
+    InvocationNotFound(String),
+    #[error("unexpected query result: {0}")]
+    UnexpectedResult(String),
+    #[error("invalid field '{0}': {1}")]
+    InvalidField(&'static str, String),
+    #[error("state key '{0}' not found")]
+    StateKeyNotFound(String),
+    #[error("expected state version '{0}' but the current version is '{1}'")]
+    StateVersionMismatch(String, String),
+    #[error("failed sending the command to the cluster: {0}")]
+    Append(String),
+    #[error("no snapshot repository is configured")]
+    SnapshotsNotConfigured,
+    #[error("snapshot '{0}' not found")]
+    SnapshotNotFound(String),
+    #[error("failed reading the snapshot: {0}")]
+    Snapshot(String),
 }
 
 /// # Error description response
@@ -37,7 +57,19 @@ struct ErrorDescriptionResponse {
 
 impl IntoResponse for StorageQueryError {
     fn into_response(self) -> Response {
-        let status_code = StatusCode::INTERNAL_SERVER_ERROR;
+        let status_code = match &self {
+            StorageQueryError::InvalidInvocationId(_)
+            | StorageQueryError::InvalidField(..)
+            | StorageQueryError::SnapshotsNotConfigured => StatusCode::BAD_REQUEST,
+            StorageQueryError::InvocationNotFound(_)
+            | StorageQueryError::StateKeyNotFound(_)
+            | StorageQueryError::SnapshotNotFound(_) => StatusCode::NOT_FOUND,
+            StorageQueryError::StateVersionMismatch(..) => StatusCode::CONFLICT,
+            StorageQueryError::DataFusion(_)
+            | StorageQueryError::UnexpectedResult(_)
+            | StorageQueryError::Append(_)
+            | StorageQueryError::Snapshot(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
 
         (
             status_code,
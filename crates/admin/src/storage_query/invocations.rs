@@ -0,0 +1,535 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use datafusion::arrow::record_batch::RecordBatch;
+use okapi_operation::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use restate_core::Metadata;
+use restate_types::identifiers::{InvocationId, PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::invocation::{BulkInvocationOperation, InvocationOperation, TerminationFlavor};
+use restate_types::partition_table::FindPartition;
+use restate_types::time::MillisSinceEpoch;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+
+use super::breakdown::{date_value, query_batches, string_value};
+use super::error::StorageQueryError;
+use crate::rest_api::create_envelope_header;
+use crate::state::QueryServiceState;
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+/// # Invocation status
+///
+/// Same as the `status` column of `sys_invocation`.
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum InvocationStatusFilter {
+    Pending,
+    Scheduled,
+    Ready,
+    Running,
+    BackingOff,
+    Suspended,
+    Completed,
+}
+
+impl InvocationStatusFilter {
+    fn as_str(&self) -> &'static str {
+        match self {
+            InvocationStatusFilter::Pending => "pending",
+            InvocationStatusFilter::Scheduled => "scheduled",
+            InvocationStatusFilter::Ready => "ready",
+            InvocationStatusFilter::Running => "running",
+            InvocationStatusFilter::BackingOff => "backing-off",
+            InvocationStatusFilter::Suspended => "suspended",
+            InvocationStatusFilter::Completed => "completed",
+        }
+    }
+}
+
+/// # Invocations filter
+///
+/// Invocations match the filter if they match all the set fields.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct InvocationsFilter {
+    /// # Service
+    ///
+    /// Fully qualified name of the target service.
+    pub service: Option<String>,
+    /// # Handler
+    ///
+    /// Name of the target handler.
+    pub handler: Option<String>,
+    /// # Status
+    pub status: Option<InvocationStatusFilter>,
+    /// # Older than
+    ///
+    /// Only invocations created at least this long ago match.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format or the ISO8601.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<restate_serde_util::DurationString>>"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub older_than: Option<Duration>,
+    /// # Deployment
+    ///
+    /// Id of the deployment the invocations are pinned to.
+    pub deployment: Option<String>,
+}
+
+impl InvocationsFilter {
+    fn is_empty(&self) -> bool {
+        self.service.is_none()
+            && self.handler.is_none()
+            && self.status.is_none()
+            && self.older_than.is_none()
+            && self.deployment.is_none()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ListInvocationsParams {
+    #[serde(flatten)]
+    pub filter: InvocationsFilter,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+/// # Bulk operation
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// Gracefully cancel the matching invocations which are not completed.
+    Cancel,
+    /// Kill the matching invocations which are not completed.
+    Kill,
+    /// Purge the matching completed invocations.
+    Purge,
+}
+
+/// # Bulk invocations request
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulkInvocationsRequest {
+    /// # Operation
+    pub operation: BulkOperation,
+    /// # Filter
+    ///
+    /// At least one field of the filter must be set.
+    pub filter: InvocationsFilter,
+    /// # Dry run
+    ///
+    /// If true, only return the matching invocations without applying the operation.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// # Limit
+    ///
+    /// Maximum number of invocations to apply the operation to, defaults to 100 and can be at most 1000.
+    pub limit: Option<u32>,
+    /// # Cursor
+    ///
+    /// The `next_cursor` of the previous response, to continue with the next matching invocations.
+    pub cursor: Option<String>,
+}
+
+/// # Invocation
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct InvocationSummary {
+    /// # Invocation id
+    pub id: String,
+    /// # Target
+    pub target: String,
+    /// # Status
+    ///
+    /// Same as the `status` column of `sys_invocation`.
+    pub status: String,
+    /// # Created at
+    ///
+    /// Milliseconds since the unix epoch.
+    pub created_at: u64,
+    /// # Deployment
+    ///
+    /// Id of the deployment the invocation is pinned to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<String>,
+}
+
+/// # List invocations response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ListInvocationsResponse {
+    pub invocations: Vec<InvocationSummary>,
+    /// # Next cursor
+    ///
+    /// Set if there are more matching invocations, pass it as `cursor` to get them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// # Bulk invocations response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BulkInvocationsResponse {
+    /// # Dry run
+    pub dry_run: bool,
+    /// # Invocations
+    ///
+    /// Invocations the operation was, or in case of a dry run would be, applied to.
+    pub invocations: Vec<InvocationSummary>,
+    /// # Next cursor
+    ///
+    /// Set if there are more matching invocations, pass it as `cursor` to apply the operation to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// List invocations
+#[openapi(
+    summary = "List invocations",
+    description = "List the invocations matching the filter, ordered by id.",
+    operation_id = "list_invocations",
+    tags = "invocation",
+    parameters(
+        query(
+            name = "service",
+            description = "If set, only list invocations of this service.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "handler",
+            description = "If set, only list invocations of this handler.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "status",
+            description = "If set, only list invocations with this status.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "InvocationStatusFilter",
+        ),
+        query(
+            name = "older_than",
+            description = "If set, only list invocations created at least this long ago.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "deployment",
+            description = "If set, only list invocations pinned to this deployment.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "limit",
+            description = "Maximum number of invocations to return, defaults to 100 and can be at most 1000.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        ),
+        query(
+            name = "cursor",
+            description = "The next_cursor of the previous response, to get the next page.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn list_invocations(
+    State(state): State<Arc<QueryServiceState>>,
+    Query(ListInvocationsParams {
+        filter,
+        limit,
+        cursor,
+    }): Query<ListInvocationsParams>,
+) -> Result<Json<ListInvocationsResponse>, StorageQueryError> {
+    let (invocations, next_cursor) =
+        query_invocations(&state, &filter, None, limit, cursor).await?;
+
+    Ok(Json(ListInvocationsResponse {
+        invocations,
+        next_cursor,
+    }))
+}
+
+/// Cancel, kill or purge invocations
+#[openapi(
+    summary = "Bulk invocation operation",
+    description = "Cancel, kill or purge up to limit invocations matching the filter, ordered by id. \
+    The operation is applied by each partition to its matching invocations as a single command. \
+    Use dry_run to list the invocations the operation would be applied to, and next_cursor to \
+    continue with the next matching invocations.",
+    operation_id = "bulk_invocation_operation",
+    tags = "invocation",
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn bulk_invocation_operation(
+    State(state): State<Arc<QueryServiceState>>,
+    #[request_body(required = true)] Json(BulkInvocationsRequest {
+        operation,
+        filter,
+        dry_run,
+        limit,
+        cursor,
+    }): Json<BulkInvocationsRequest>,
+) -> Result<Json<BulkInvocationsResponse>, StorageQueryError> {
+    if filter.is_empty() {
+        return Err(StorageQueryError::InvalidField(
+            "filter",
+            "at least one field of the filter must be set".to_owned(),
+        ));
+    }
+
+    let (invocations, next_cursor) =
+        query_invocations(&state, &filter, Some(operation), limit, cursor).await?;
+
+    if dry_run {
+        return Ok(Json(BulkInvocationsResponse {
+            dry_run,
+            invocations,
+            next_cursor,
+        }));
+    }
+
+    let operation = match operation {
+        BulkOperation::Cancel => InvocationOperation::Terminate(TerminationFlavor::Cancel),
+        BulkOperation::Kill => InvocationOperation::Terminate(TerminationFlavor::Kill),
+        BulkOperation::Purge => InvocationOperation::Purge,
+    };
+    let invocations_by_partition = group_by_partition(&invocations)?;
+
+    info!(
+        ?operation,
+        "Applying bulk operation to {} invocations",
+        invocations.len()
+    );
+    for (partition_key, invocation_ids) in invocations_by_partition.into_values() {
+        let result = append_envelope_to_bifrost(
+            &state.bifrost,
+            Arc::new(Envelope::new(
+                create_envelope_header(partition_key),
+                Command::BulkInvocationOperation(BulkInvocationOperation {
+                    invocation_ids,
+                    operation,
+                }),
+            )),
+        )
+        .await;
+
+        if let Err(err) = result {
+            warn!("Could not append bulk invocation operation command to Bifrost: {err}");
+            return Err(StorageQueryError::Append(err.to_string()));
+        }
+    }
+
+    Ok(Json(BulkInvocationsResponse {
+        dry_run,
+        invocations,
+        next_cursor,
+    }))
+}
+
+/// Returns the matching invocations, and the cursor of the next page if there are more.
+async fn query_invocations(
+    state: &QueryServiceState,
+    filter: &InvocationsFilter,
+    operation: Option<BulkOperation>,
+    limit: Option<u32>,
+    cursor: Option<String>,
+) -> Result<(Vec<InvocationSummary>, Option<String>), StorageQueryError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(StorageQueryError::InvalidField(
+            "limit",
+            format!("must be between 1 and {MAX_LIMIT}"),
+        ));
+    }
+    let cursor = cursor
+        .map(|cursor| cursor.parse::<InvocationId>())
+        .transpose()
+        .map_err(|e| StorageQueryError::InvalidField("cursor", e.to_string()))?;
+
+    let query = build_query(
+        filter,
+        operation,
+        cursor,
+        limit,
+        MillisSinceEpoch::now().as_u64(),
+    );
+    let batches = query_batches(state, query).await?;
+    let mut invocations = read_invocations(&batches)?;
+
+    // One more row than the limit is queried, to know whether there is a next page
+    let next_cursor = if invocations.len() > limit as usize {
+        invocations.truncate(limit as usize);
+        invocations.last().map(|invocation| invocation.id.clone())
+    } else {
+        None
+    };
+
+    Ok((invocations, next_cursor))
+}
+
+fn build_query(
+    filter: &InvocationsFilter,
+    operation: Option<BulkOperation>,
+    cursor: Option<InvocationId>,
+    limit: u32,
+    now: u64,
+) -> String {
+    let mut conditions = vec![];
+    if let Some(service) = &filter.service {
+        conditions.push(format!("target_service_name = {}", quote(service)));
+    }
+    if let Some(handler) = &filter.handler {
+        conditions.push(format!("target_handler_name = {}", quote(handler)));
+    }
+    if let Some(status) = filter.status {
+        conditions.push(format!("status = '{}'", status.as_str()));
+    }
+    if let Some(older_than) = filter.older_than {
+        let created_before = now.saturating_sub(older_than.as_millis() as u64);
+        conditions.push(format!("CAST(created_at AS BIGINT) < {created_before}"));
+    }
+    if let Some(deployment) = &filter.deployment {
+        conditions.push(format!("pinned_deployment_id = {}", quote(deployment)));
+    }
+    match operation {
+        Some(BulkOperation::Cancel | BulkOperation::Kill) => {
+            conditions.push("status <> 'completed'".to_owned())
+        }
+        Some(BulkOperation::Purge) => conditions.push("status = 'completed'".to_owned()),
+        None => {}
+    }
+    if let Some(cursor) = cursor {
+        // parsing guarantees that the id can be safely embedded in the query
+        conditions.push(format!("id > '{cursor}'"));
+    }
+
+    let mut query = "SELECT id, target, status, created_at, pinned_deployment_id \
+        FROM sys_invocation"
+        .to_owned();
+    if !conditions.is_empty() {
+        query.push_str(" WHERE ");
+        query.push_str(&conditions.join(" AND "));
+    }
+    query.push_str(&format!(" ORDER BY id LIMIT {}", limit + 1));
+    query
+}
+
+pub(super) fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn read_invocations(batches: &[RecordBatch]) -> Result<Vec<InvocationSummary>, StorageQueryError> {
+    let mut invocations = Vec::new();
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            invocations.push(InvocationSummary {
+                id: string_value(batch, "id", row)?.unwrap_or_default(),
+                target: string_value(batch, "target", row)?.unwrap_or_default(),
+                status: string_value(batch, "status", row)?.unwrap_or_default(),
+                created_at: date_value(batch, "created_at", row)?.unwrap_or_default(),
+                deployment: string_value(batch, "pinned_deployment_id", row)?,
+            });
+        }
+    }
+    Ok(invocations)
+}
+
+/// Groups the invocation ids by the partition owning them, together with a key of the partition.
+fn group_by_partition(
+    invocations: &[InvocationSummary],
+) -> Result<HashMap<PartitionId, (PartitionKey, Vec<InvocationId>)>, StorageQueryError> {
+    Metadata::with_current(|m| {
+        let partition_table = m.partition_table_ref();
+        let mut invocations_by_partition: HashMap<_, (_, Vec<_>)> = HashMap::new();
+        for invocation in invocations {
+            let invocation_id = invocation.id.parse::<InvocationId>().map_err(|e| {
+                StorageQueryError::UnexpectedResult(format!("invalid invocation id: {e}"))
+            })?;
+            let partition_key = invocation_id.partition_key();
+            let partition_id = partition_table
+                .find_partition_id(partition_key)
+                .map_err(|e| StorageQueryError::Append(e.to_string()))?;
+            invocations_by_partition
+                .entry(partition_id)
+                .or_insert_with(|| (partition_key, Vec::new()))
+                .1
+                .push(invocation_id);
+        }
+        Ok(invocations_by_partition)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_with_filter_and_cursor() {
+        let cursor = InvocationId::mock_random();
+        let filter = InvocationsFilter {
+            service: Some("Greeter".to_owned()),
+            handler: Some("it's".to_owned()),
+            status: Some(InvocationStatusFilter::BackingOff),
+            older_than: Some(Duration::from_secs(60)),
+            deployment: None,
+        };
+
+        assert_eq!(
+            build_query(
+                &filter,
+                Some(BulkOperation::Kill),
+                Some(cursor),
+                10,
+                100_000
+            ),
+            format!(
+                "SELECT id, target, status, created_at, pinned_deployment_id FROM sys_invocation \
+                WHERE target_service_name = 'Greeter' AND target_handler_name = 'it''s' \
+                AND status = 'backing-off' AND CAST(created_at AS BIGINT) < 40000 \
+                AND status <> 'completed' AND id > '{cursor}' ORDER BY id LIMIT 11"
+            )
+        );
+    }
+
+    #[test]
+    fn query_without_filter() {
+        assert_eq!(
+            build_query(&InvocationsFilter::default(), None, None, 100, 0),
+            "SELECT id, target, status, created_at, pinned_deployment_id FROM sys_invocation \
+            ORDER BY id LIMIT 101"
+        );
+    }
+}
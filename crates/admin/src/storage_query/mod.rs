@@ -8,17 +8,69 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod breakdown;
+mod consistency;
 mod error;
+mod invocations;
 mod query;
+mod snapshots;
+mod state;
+mod timeline;
 
-use axum::{routing::post, Router};
+use axum::routing::{get, post, put};
+use axum::Router;
 use std::sync::Arc;
 
 use crate::state::QueryServiceState;
 
+pub use snapshots::SnapshotInspector;
+
 pub fn create_router(state: Arc<QueryServiceState>) -> Router<()> {
     // Setup the router
     axum::Router::new()
         .route("/query", post(query::query))
+        .route("/invocations", get(invocations::list_invocations))
+        .route(
+            "/invocations/bulk",
+            post(invocations::bulk_invocation_operation),
+        )
+        .route(
+            "/consistency/violations",
+            get(consistency::list_consistency_violations),
+        )
+        .route(
+            "/consistency/repair",
+            post(consistency::repair_consistency_violations),
+        )
+        .route(
+            "/invocations/:invocation_id/breakdown",
+            get(breakdown::invocation_breakdown),
+        )
+        .route(
+            "/invocations/:invocation_id/timeline",
+            get(timeline::invocation_timeline),
+        )
+        .route(
+            "/partitions/:partition_id/snapshots",
+            get(snapshots::list_snapshots),
+        )
+        .route(
+            "/partitions/:partition_id/snapshots/:snapshot_id/records",
+            get(snapshots::list_snapshot_records),
+        )
+        .route(
+            "/partitions/:partition_id/snapshots/:snapshot_id/invocations/:invocation_id",
+            get(snapshots::get_snapshot_invocation),
+        )
+        .route(
+            "/services/:service/objects/:object_key/state",
+            get(state::get_object_state),
+        )
+        .route(
+            "/services/:service/objects/:object_key/state/:state_key",
+            get(state::get_object_state_key)
+                .put(state::set_object_state_key)
+                .delete(state::delete_object_state_key),
+        )
         .with_state(state)
 }
@@ -17,25 +17,40 @@ use axum::extract::State;
 use axum::response::{IntoResponse, Response};
 use axum::{http, Json};
 use bytes::Bytes;
+use datafusion::arrow::csv;
 use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::ipc::writer::StreamWriter;
 use datafusion::arrow::json::writer::JsonArray;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::DataFusionError;
 use datafusion::execution::SendableRecordBatchStream;
-use futures::{ready, Stream, StreamExt, TryStreamExt};
-use http::{HeaderMap, HeaderValue};
+use futures::{ready, Stream, StreamExt};
+use http::HeaderMap;
 use http_body::Frame;
 use http_body_util::StreamBody;
 use okapi_operation::*;
 use parking_lot::Mutex;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::format::KeyValue;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_with::serde_as;
 
+use restate_storage_query_datafusion::context::QueryWarnings;
+
 use super::error::StorageQueryError;
 use crate::state::QueryServiceState;
 
+/// Size of the buffered row group after which the parquet writer flushes it, to bound the memory
+/// used to export large result sets.
+const PARQUET_MAX_ROW_GROUP_BYTES: usize = 16 * 1024 * 1024;
+
+/// Trailer reporting the warnings of partial results, for every result format.
+const WARNINGS_TRAILER: &str = "x-restate-query-warnings";
+
 #[serde_as]
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QueryRequest {
@@ -50,7 +65,7 @@ pub struct QueryRequest {
 /// Query storage
 #[openapi(
     summary = "Query storage",
-    description = "Query the storage API",
+    description = "Query the storage API. The results are streamed as Arrow IPC stream (default), JSON, CSV or Parquet, depending on the Accept header. Partitions which cannot be scanned, e.g. because their node is unreachable, are left out of the results, and reported in the `x-restate-query-warnings` trailer of the response. JSON results also include them under the `warnings` key, Parquet results in the `restate.warnings` key-value metadata.",
     operation_id = "query",
     tags = "storage",
     responses(ignore_return_type = true, from_type = "StorageQueryError")
@@ -60,29 +75,71 @@ pub async fn query(
     headers: HeaderMap,
     #[request_body(required = true)] Json(payload): Json<QueryRequest>,
 ) -> Result<impl IntoResponse, StorageQueryError> {
-    let record_batch_stream = state.query_context.execute(&payload.query).await?;
+    let (record_batch_stream, warnings) = state
+        .query_context
+        .execute_with_partial_results(&payload.query)
+        .await?;
 
-    let (result_stream, content_type) = match headers.get(http::header::ACCEPT) {
-        Some(v) if v == HeaderValue::from_static("application/json") => (
-            WriteRecordBatchStream::<JsonWriter>::new(record_batch_stream)?
-                .map_ok(Frame::data)
-                .left_stream(),
+    let (result_stream, content_type) = match ResultFormat::negotiate(&headers) {
+        ResultFormat::Json => (
+            WriteRecordBatchStream::<JsonWriter>::new(record_batch_stream, warnings)?.boxed(),
             "application/json",
         ),
-        _ => (
-            WriteRecordBatchStream::<StreamWriter<Vec<u8>>>::new(record_batch_stream)?
-                .map_ok(Frame::data)
-                .right_stream(),
+        ResultFormat::Csv => (
+            WriteRecordBatchStream::<CsvWriter>::new(record_batch_stream, warnings)?.boxed(),
+            "text/csv",
+        ),
+        ResultFormat::Parquet => (
+            WriteRecordBatchStream::<ParquetWriter>::new(record_batch_stream, warnings)?.boxed(),
+            "application/vnd.apache.parquet",
+        ),
+        ResultFormat::ArrowStream => (
+            WriteRecordBatchStream::<StreamWriter<Vec<u8>>>::new(record_batch_stream, warnings)?
+                .boxed(),
             "application/vnd.apache.arrow.stream",
         ),
     };
 
     Ok(Response::builder()
         .header(http::header::CONTENT_TYPE, content_type)
+        .header(http::header::TRAILER, WARNINGS_TRAILER)
         .body(StreamBody::new(result_stream))
         .expect("content-type header is correct"))
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultFormat {
+    ArrowStream,
+    Json,
+    Csv,
+    Parquet,
+}
+
+impl ResultFormat {
+    /// Picks the first media type of the Accept header which is supported, falling back to the
+    /// Arrow IPC stream.
+    fn negotiate(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(http::header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(|media_type| {
+                let media_type = media_type.split(';').next().unwrap_or_default().trim();
+                match media_type.to_ascii_lowercase().as_str() {
+                    "application/vnd.apache.arrow.stream" => Some(ResultFormat::ArrowStream),
+                    "application/json" => Some(ResultFormat::Json),
+                    "text/csv" => Some(ResultFormat::Csv),
+                    "application/vnd.apache.parquet" | "application/x-parquet" => {
+                        Some(ResultFormat::Parquet)
+                    }
+                    _ => None,
+                }
+            })
+            .unwrap_or(ResultFormat::ArrowStream)
+    }
+}
+
 trait RecordBatchWriter
 where
     Self: Sized,
@@ -93,8 +150,9 @@ where
     /// Write a single batch to the writer.
     fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, DataFusionError>;
 
-    /// Write footer or termination data, then mark the writer as done.
-    fn finish(&mut self) -> Result<Bytes, DataFusionError>;
+    /// Write footer or termination data, then mark the writer as done. The warnings of the
+    /// query are included, if the format has room for them.
+    fn finish(&mut self, warnings: &[String]) -> Result<Bytes, DataFusionError>;
 }
 
 impl RecordBatchWriter for StreamWriter<Vec<u8>> {
@@ -109,7 +167,7 @@ impl RecordBatchWriter for StreamWriter<Vec<u8>> {
         Ok(bytes)
     }
 
-    fn finish(&mut self) -> Result<Bytes, DataFusionError> {
+    fn finish(&mut self, _warnings: &[String]) -> Result<Bytes, DataFusionError> {
         self.finish()?;
         let bytes = Bytes::copy_from_slice(self.get_ref());
         self.get_mut().clear();
@@ -170,51 +228,158 @@ impl RecordBatchWriter for JsonWriter {
         Ok(Bytes::from(self.lock_writer.take()))
     }
 
-    fn finish(&mut self) -> Result<Bytes, DataFusionError> {
+    fn finish(&mut self, warnings: &[String]) -> Result<Bytes, DataFusionError> {
         if !self.finished {
             self.finished = true;
             self.json_writer.finish()?;
-            if self.started {
-                // if we've started, json writer has set the tailing ]
-                self.lock_writer.write_all(b"}")?;
-            } else {
+            if !self.started {
                 // if we haven't started, json writer has written nothing, so we must write the []
-                self.lock_writer.write_all(b"[]}")?;
+                self.lock_writer.write_all(b"[]")?;
+            }
+            if !warnings.is_empty() {
+                self.lock_writer.write_all(br#","warnings":"#)?;
+                serde_json::to_writer(&mut self.lock_writer, warnings)
+                    .map_err(|e| DataFusionError::External(e.into()))?;
+            }
+            self.lock_writer.write_all(b"}")?;
+        }
+        Ok(Bytes::from(self.lock_writer.take()))
+    }
+}
+
+struct CsvWriter {
+    csv_writer: csv::Writer<LockWriter>,
+    lock_writer: LockWriter,
+    schema: Arc<Schema>,
+    started: bool,
+}
+
+impl RecordBatchWriter for CsvWriter {
+    fn new(schema: &Schema) -> Result<Self, DataFusionError> {
+        let lock_writer = LockWriter::new();
+        Ok(Self {
+            csv_writer: csv::WriterBuilder::new()
+                .with_header(true)
+                .build(lock_writer.clone()),
+            lock_writer,
+            schema: Arc::new(schema.clone()),
+            started: false,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, DataFusionError> {
+        self.started = true;
+        self.csv_writer.write(batch)?;
+        Ok(Bytes::from(self.lock_writer.take()))
+    }
+
+    fn finish(&mut self, _warnings: &[String]) -> Result<Bytes, DataFusionError> {
+        if !self.started {
+            // the header is written together with the first batch
+            self.write(&RecordBatch::new_empty(Arc::clone(&self.schema)))?;
+        }
+        Ok(Bytes::from(self.lock_writer.take()))
+    }
+}
+
+struct ParquetWriter {
+    // taken when finishing, as closing the writer consumes it
+    arrow_writer: Option<ArrowWriter<LockWriter>>,
+    lock_writer: LockWriter,
+}
+
+impl RecordBatchWriter for ParquetWriter {
+    fn new(schema: &Schema) -> Result<Self, DataFusionError> {
+        let lock_writer = LockWriter::new();
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        Ok(Self {
+            arrow_writer: Some(
+                ArrowWriter::try_new(
+                    lock_writer.clone(),
+                    Arc::new(schema.clone()),
+                    Some(properties),
+                )
+                .map_err(parquet_error)?,
+            ),
+            lock_writer,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, DataFusionError> {
+        let arrow_writer = self
+            .arrow_writer
+            .as_mut()
+            .expect("write is not called after finish");
+        arrow_writer.write(batch).map_err(parquet_error)?;
+        // rows are buffered until the row group is flushed, don't let it grow unbounded
+        if arrow_writer.in_progress_size() >= PARQUET_MAX_ROW_GROUP_BYTES {
+            arrow_writer.flush().map_err(parquet_error)?;
+        }
+        Ok(Bytes::from(self.lock_writer.take()))
+    }
+
+    fn finish(&mut self, warnings: &[String]) -> Result<Bytes, DataFusionError> {
+        if let Some(mut arrow_writer) = self.arrow_writer.take() {
+            if !warnings.is_empty() {
+                arrow_writer.append_key_value_metadata(KeyValue::new(
+                    "restate.warnings".to_owned(),
+                    warnings.join("\n"),
+                ));
             }
+            // flushes the last row group and writes the footer
+            arrow_writer.close().map_err(parquet_error)?;
         }
         Ok(Bytes::from(self.lock_writer.take()))
     }
 }
 
+fn parquet_error(err: ParquetError) -> DataFusionError {
+    DataFusionError::External(err.into())
+}
+
 struct WriteRecordBatchStream<W> {
     done: bool,
     record_batch_stream: SendableRecordBatchStream,
     stream_writer: W,
+    warnings: QueryWarnings,
+    // sent once the last data frame has been written
+    trailers: Option<HeaderMap>,
 }
 
 impl<W: RecordBatchWriter> WriteRecordBatchStream<W> {
-    fn new(record_batch_stream: SendableRecordBatchStream) -> Result<Self, DataFusionError> {
+    fn new(
+        record_batch_stream: SendableRecordBatchStream,
+        warnings: QueryWarnings,
+    ) -> Result<Self, DataFusionError> {
         Ok(WriteRecordBatchStream {
             done: false,
             stream_writer: W::new(&record_batch_stream.schema())?,
             record_batch_stream,
+            warnings,
+            trailers: None,
         })
     }
 }
 
 impl<W: RecordBatchWriter + Unpin> Stream for WriteRecordBatchStream<W> {
-    type Item = Result<Bytes, DataFusionError>;
+    type Item = Result<Frame<Bytes>, DataFusionError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         if self.done {
-            return Poll::Ready(None);
+            return Poll::Ready(
+                self.trailers
+                    .take()
+                    .map(|trailers| Ok(Frame::trailers(trailers))),
+            );
         }
 
         let record_batch = ready!(self.record_batch_stream.poll_next_unpin(cx));
 
         if let Some(record_batch) = record_batch {
             match record_batch.and_then(|record_batch| self.stream_writer.write(&record_batch)) {
-                Ok(bytes) => Poll::Ready(Some(Ok(bytes))),
+                Ok(bytes) => Poll::Ready(Some(Ok(Frame::data(bytes)))),
                 Err(err) => {
                     self.done = true;
                     Poll::Ready(Some(Err(err)))
@@ -222,10 +387,116 @@ impl<W: RecordBatchWriter + Unpin> Stream for WriteRecordBatchStream<W> {
             }
         } else {
             self.done = true;
-            match self.stream_writer.finish() {
+            // the stream is exhausted, so all the warnings are collected
+            let warnings = self.warnings.get();
+            self.trailers = warnings_trailers(&warnings);
+            match self.stream_writer.finish(&warnings) {
                 Err(err) => Poll::Ready(Some(Err(err))),
-                Ok(bytes) => Poll::Ready(Some(Ok(bytes))),
+                Ok(bytes) => Poll::Ready(Some(Ok(Frame::data(bytes)))),
             }
         }
     }
 }
+
+fn warnings_trailers(warnings: &[String]) -> Option<HeaderMap> {
+    if warnings.is_empty() {
+        return None;
+    }
+    // header values can only hold visible ASCII characters
+    let value: String = warnings
+        .join("; ")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect();
+    let mut trailers = HeaderMap::new();
+    trailers.insert(
+        WARNINGS_TRAILER,
+        http::HeaderValue::from_str(&value).expect("value is visible ASCII"),
+    );
+    Some(trailers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::ACCEPT, http::HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn negotiate_result_format() {
+        assert_eq!(
+            ResultFormat::negotiate(&HeaderMap::new()),
+            ResultFormat::ArrowStream
+        );
+        assert_eq!(
+            ResultFormat::negotiate(&accept("*/*")),
+            ResultFormat::ArrowStream
+        );
+        assert_eq!(
+            ResultFormat::negotiate(&accept("application/json")),
+            ResultFormat::Json
+        );
+        assert_eq!(
+            ResultFormat::negotiate(&accept("text/html, text/csv;charset=utf-8")),
+            ResultFormat::Csv
+        );
+        assert_eq!(
+            ResultFormat::negotiate(&accept("application/vnd.apache.parquet")),
+            ResultFormat::Parquet
+        );
+    }
+
+    #[test]
+    fn csv_of_empty_result_has_header() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("status", DataType::Utf8, false),
+        ]);
+
+        let mut writer = CsvWriter::new(&schema).unwrap();
+        assert_eq!(
+            writer.finish(&[]).unwrap(),
+            Bytes::from_static(b"id,status\n")
+        );
+    }
+
+    #[test]
+    fn json_with_warnings() {
+        let schema = Schema::new(vec![Field::new("id", DataType::Utf8, false)]);
+
+        let mut writer = JsonWriter::new(&schema).unwrap();
+        assert_eq!(
+            writer
+                .finish(&["partition 1 could not be scanned".to_owned()])
+                .unwrap(),
+            Bytes::from_static(br#"{"rows":[],"warnings":["partition 1 could not be scanned"]}"#)
+        );
+    }
+
+    #[test]
+    fn warnings_in_trailers() {
+        assert!(warnings_trailers(&[]).is_none());
+
+        let trailers = warnings_trailers(&[
+            "partition 1 could not be scanned".to_owned(),
+            "partition 2: ünreachable\n".to_owned(),
+        ])
+        .unwrap();
+        assert_eq!(
+            trailers.get(WARNINGS_TRAILER).unwrap(),
+            "partition 1 could not be scanned; partition 2: ?nreachable?"
+        );
+    }
+}
@@ -0,0 +1,526 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Queries against the state of a partition as of one of its uploaded snapshots, e.g. to find
+//! out what the status of an invocation was before an incident. The snapshot is downloaded from
+//! the snapshot repository and imported into a separate, read-only database, hence the partition
+//! store of the node is never touched.
+
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use base64::prelude::{Engine, BASE64_URL_SAFE_NO_PAD};
+use okapi_operation::*;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+use restate_partition_store::inspect::{key_kind_by_name, DecodedRecord, ReadOnlyPartitionStoreDb};
+use restate_partition_store::keys::KeyKind;
+use restate_partition_store::snapshots::SnapshotRepository;
+use restate_storage_api::Result as StorageResult;
+use restate_types::identifiers::{
+    InvocationId, PartitionId, PartitionKey, SnapshotId, WithPartitionKey,
+};
+use restate_types::logs::Lsn;
+
+use super::error::StorageQueryError;
+use crate::state::QueryServiceState;
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+/// Imports the snapshots which are queried. Only the most recently queried snapshot is kept,
+/// since consecutive queries usually target the same snapshot. Each snapshot is imported into
+/// its own directory, which is deleted once the last query of the snapshot completes.
+pub struct SnapshotInspector {
+    repository: SnapshotRepository,
+    base_dir: PathBuf,
+    next_dir_id: AtomicU64,
+    current: Mutex<Option<CurrentSnapshot>>,
+}
+
+/// The most recently queried snapshot. It is downloaded and imported at most once, by the first
+/// query, while concurrent queries of the same snapshot wait for it.
+struct CurrentSnapshot {
+    partition_id: PartitionId,
+    snapshot_id: SnapshotId,
+    opened: Arc<OnceCell<Arc<OpenedSnapshot>>>,
+}
+
+struct OpenedSnapshot {
+    snapshot_id: SnapshotId,
+    min_applied_lsn: Lsn,
+    key_range: RangeInclusive<PartitionKey>,
+    db: ReadOnlyPartitionStoreDb,
+    // declared after the database so that it's closed before its directory is deleted
+    _dir: SnapshotDir,
+}
+
+/// Directory a snapshot is imported into, deleted when dropped.
+struct SnapshotDir(PathBuf);
+
+impl Drop for SnapshotDir {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_dir_all(&self.0) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!(
+                    "Failed to delete the imported snapshot '{}': {err}",
+                    self.0.display()
+                );
+            }
+        }
+    }
+}
+
+impl SnapshotInspector {
+    pub fn new(repository: SnapshotRepository, base_dir: PathBuf) -> Self {
+        // snapshots imported before a restart are not used anymore
+        drop(SnapshotDir(base_dir.clone()));
+        Self {
+            repository,
+            base_dir,
+            next_dir_id: AtomicU64::new(0),
+            current: Mutex::new(None),
+        }
+    }
+
+    async fn open(
+        &self,
+        partition_id: PartitionId,
+        snapshot_id: SnapshotId,
+    ) -> Result<Arc<OpenedSnapshot>, StorageQueryError> {
+        let opened = {
+            let mut current = self.current.lock();
+            match current.as_ref().filter(|snapshot| {
+                snapshot.partition_id == partition_id && snapshot.snapshot_id == snapshot_id
+            }) {
+                Some(snapshot) => Arc::clone(&snapshot.opened),
+                None => {
+                    // queries in flight keep the previous snapshot until they complete
+                    let opened = Arc::default();
+                    *current = Some(CurrentSnapshot {
+                        partition_id,
+                        snapshot_id,
+                        opened: Arc::clone(&opened),
+                    });
+                    opened
+                }
+            }
+        };
+
+        // the lock isn't held while downloading, hence other snapshots can be queried meanwhile
+        opened
+            .get_or_try_init(|| self.import(partition_id, snapshot_id))
+            .await
+            .cloned()
+    }
+
+    async fn import(
+        &self,
+        partition_id: PartitionId,
+        snapshot_id: SnapshotId,
+    ) -> Result<Arc<OpenedSnapshot>, StorageQueryError> {
+        let dir = SnapshotDir(self.base_dir.join(format!(
+            "{snapshot_id}-{}",
+            self.next_dir_id.fetch_add(1, Ordering::Relaxed)
+        )));
+
+        let staging_dir = dir.0.join("staging");
+        let snapshot = self
+            .repository
+            .get(partition_id, snapshot_id, &staging_dir)
+            .await
+            .map_err(|err| StorageQueryError::Snapshot(err.to_string()))?
+            .ok_or_else(|| StorageQueryError::SnapshotNotFound(snapshot_id.to_string()))?;
+
+        let db_dir = dir.0.clone();
+        let min_applied_lsn = snapshot.min_applied_lsn;
+        let key_range = snapshot.key_range.clone();
+        let db = tokio::task::spawn_blocking(move || {
+            ReadOnlyPartitionStoreDb::import_snapshot(&db_dir, partition_id, &snapshot)
+        })
+        .await
+        .map_err(|err| StorageQueryError::Snapshot(err.to_string()))?
+        .map_err(|err| StorageQueryError::Snapshot(err.to_string()))?;
+        // the imported database holds its own copy of the snapshot files
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+
+        info!(
+            %partition_id,
+            %snapshot_id,
+            "Opened partition snapshot as of lsn {min_applied_lsn} for queries"
+        );
+        Ok(Arc::new(OpenedSnapshot {
+            snapshot_id,
+            min_applied_lsn,
+            key_range,
+            db,
+            _dir: dir,
+        }))
+    }
+}
+
+impl OpenedSnapshot {
+    fn response(&self) -> SnapshotResponse {
+        SnapshotResponse {
+            snapshot_id: self.snapshot_id.to_string(),
+            min_applied_lsn: self.min_applied_lsn.into(),
+        }
+    }
+
+    /// Runs a blocking query against the database of the snapshot.
+    async fn query<T: Send + 'static>(
+        self: Arc<Self>,
+        query: impl FnOnce(&ReadOnlyPartitionStoreDb) -> StorageResult<T> + Send + 'static,
+    ) -> Result<T, StorageQueryError> {
+        tokio::task::spawn_blocking(move || query(&self.db))
+            .await
+            .map_err(|err| StorageQueryError::Snapshot(err.to_string()))?
+            .map_err(|err| StorageQueryError::Snapshot(err.to_string()))
+    }
+}
+
+/// # Partition snapshots
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ListSnapshotsResponse {
+    /// # Snapshots
+    ///
+    /// Complete snapshots of the partition, from the oldest to the most recent one.
+    pub snapshots: Vec<SnapshotResponse>,
+}
+
+/// # Partition snapshot
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SnapshotResponse {
+    /// # Snapshot id
+    pub snapshot_id: String,
+    /// # Minimum applied LSN
+    ///
+    /// The partition state of the snapshot includes at least the log records up to this LSN.
+    pub min_applied_lsn: u64,
+}
+
+/// # Invocation as of snapshot
+///
+/// The records of an invocation stored in a partition snapshot.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SnapshotInvocationResponse {
+    /// # Invocation id
+    pub invocation_id: String,
+    /// # Snapshot
+    pub snapshot: SnapshotResponse,
+    /// # Records
+    ///
+    /// The invocation status and the journal entries of the invocation, decoded into a
+    /// human-readable form.
+    pub records: Vec<SnapshotRecord>,
+}
+
+/// # Records as of snapshot
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SnapshotRecordsResponse {
+    /// # Snapshot
+    pub snapshot: SnapshotResponse,
+    /// # Records
+    ///
+    /// The records ordered by key, decoded into a human-readable form.
+    pub records: Vec<SnapshotRecord>,
+    /// # Next cursor
+    ///
+    /// Set if there may be more records, pass it as `cursor` to get them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// # Snapshot record
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SnapshotRecord {
+    /// # Key kind
+    pub key_kind: String,
+    /// # Key
+    pub key: String,
+    /// # Value
+    pub value: String,
+}
+
+/// List the snapshots of a partition
+#[openapi(
+    summary = "List partition snapshots",
+    description = "List the complete snapshots of the partition which are stored in the snapshot \
+    repository, and can be queried.",
+    operation_id = "list_partition_snapshots",
+    tags = "storage",
+    parameters(path(
+        name = "partition_id",
+        description = "Partition identifier.",
+        schema = "u16"
+    )),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn list_snapshots(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(partition_id): Path<u16>,
+) -> Result<Json<ListSnapshotsResponse>, StorageQueryError> {
+    let inspector = inspector(&state)?;
+    let snapshots = inspector
+        .repository
+        .list(PartitionId::from(partition_id))
+        .await
+        .map_err(|err| StorageQueryError::Snapshot(err.to_string()))?;
+
+    Ok(Json(ListSnapshotsResponse {
+        snapshots: snapshots
+            .into_iter()
+            .map(|snapshot| SnapshotResponse {
+                snapshot_id: snapshot.snapshot_id.to_string(),
+                min_applied_lsn: snapshot.min_applied_lsn.into(),
+            })
+            .collect(),
+    }))
+}
+
+/// Get an invocation as of a snapshot
+#[openapi(
+    summary = "Get invocation as of snapshot",
+    description = "Get the invocation status and the journal of an invocation as they were stored \
+    in the given snapshot of the partition. The snapshot is imported into a read-only database the \
+    first time it is queried, which can take a while for large partitions.",
+    operation_id = "get_snapshot_invocation",
+    tags = "storage",
+    parameters(
+        path(
+            name = "partition_id",
+            description = "Partition identifier.",
+            schema = "u16"
+        ),
+        path(
+            name = "snapshot_id",
+            description = "Snapshot identifier.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "invocation_id",
+            description = "Invocation identifier.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn get_snapshot_invocation(
+    State(state): State<Arc<QueryServiceState>>,
+    Path((partition_id, snapshot_id, invocation_id)): Path<(u16, String, String)>,
+) -> Result<Json<SnapshotInvocationResponse>, StorageQueryError> {
+    let inspector = inspector(&state)?;
+    let snapshot_id = snapshot_id
+        .parse::<SnapshotId>()
+        .map_err(|err| StorageQueryError::InvalidField("snapshot_id", err.to_string()))?;
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| StorageQueryError::InvalidInvocationId(e.to_string()))?;
+
+    let snapshot = inspector
+        .open(PartitionId::from(partition_id), snapshot_id)
+        .await?;
+    if !snapshot.key_range.contains(&invocation_id.partition_key()) {
+        return Err(StorageQueryError::InvalidField(
+            "invocation_id",
+            format!("the invocation doesn't belong to partition {partition_id}"),
+        ));
+    }
+
+    let response = snapshot.response();
+    let records = snapshot
+        .query(move |db| db.invocation(&invocation_id))
+        .await?
+        .map(|(_, records)| records)
+        .ok_or_else(|| StorageQueryError::InvocationNotFound(invocation_id.to_string()))?;
+
+    Ok(Json(SnapshotInvocationResponse {
+        invocation_id: invocation_id.to_string(),
+        snapshot: response,
+        records: records.into_iter().map(SnapshotRecord::from).collect(),
+    }))
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ListSnapshotRecordsParams {
+    pub key_kind: String,
+    pub partition_key: Option<PartitionKey>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+/// List the records of a snapshot
+#[openapi(
+    summary = "List records as of snapshot",
+    description = "List the records of the given key kind as they were stored in the given snapshot \
+    of the partition, ordered by key. The snapshot is imported into a read-only database the first \
+    time it is queried, which can take a while for large partitions.",
+    operation_id = "list_snapshot_records",
+    tags = "storage",
+    parameters(
+        path(
+            name = "partition_id",
+            description = "Partition identifier.",
+            schema = "u16"
+        ),
+        path(
+            name = "snapshot_id",
+            description = "Snapshot identifier.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "key_kind",
+            description = "Key kind of the records, e.g. InvocationStatus, Inbox or State.",
+            required = true,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "partition_key",
+            description = "If set, only list the records of this partition key. Not supported by \
+            the Fsm and Deduplication key kinds, whose records aren't keyed by partition key.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u64",
+        ),
+        query(
+            name = "limit",
+            description = "Maximum number of records to return, defaults to 100 and can be at most 1000.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "u32",
+        ),
+        query(
+            name = "cursor",
+            description = "The next_cursor of the previous response, to get the next page.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn list_snapshot_records(
+    State(state): State<Arc<QueryServiceState>>,
+    Path((partition_id, snapshot_id)): Path<(u16, String)>,
+    Query(ListSnapshotRecordsParams {
+        key_kind,
+        partition_key,
+        limit,
+        cursor,
+    }): Query<ListSnapshotRecordsParams>,
+) -> Result<Json<SnapshotRecordsResponse>, StorageQueryError> {
+    let inspector = inspector(&state)?;
+    let snapshot_id = snapshot_id
+        .parse::<SnapshotId>()
+        .map_err(|err| StorageQueryError::InvalidField("snapshot_id", err.to_string()))?;
+    let key_kind = key_kind_by_name(&key_kind).ok_or_else(|| {
+        StorageQueryError::InvalidField("key_kind", format!("unknown key kind '{key_kind}'"))
+    })?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(StorageQueryError::InvalidField(
+            "limit",
+            format!("must be between 1 and {MAX_LIMIT}"),
+        ));
+    }
+
+    let mut prefix = key_kind.as_bytes().to_vec();
+    if let Some(partition_key) = partition_key {
+        if matches!(key_kind, KeyKind::Fsm | KeyKind::Deduplication) {
+            return Err(StorageQueryError::InvalidField(
+                "partition_key",
+                format!("the records of key kind {key_kind} aren't keyed by partition key"),
+            ));
+        }
+        // the partition key is encoded big endian right after the key kind
+        prefix.extend_from_slice(&partition_key.to_be_bytes());
+    }
+    let cursor = cursor
+        .map(|cursor| BASE64_URL_SAFE_NO_PAD.decode(cursor))
+        .transpose()
+        .map_err(|err| StorageQueryError::InvalidField("cursor", err.to_string()))?;
+    if cursor
+        .as_ref()
+        .is_some_and(|cursor| !cursor.starts_with(&prefix))
+    {
+        return Err(StorageQueryError::InvalidField(
+            "cursor",
+            "the cursor belongs to a different query".to_owned(),
+        ));
+    }
+
+    let partition_id = PartitionId::from(partition_id);
+    let snapshot = inspector.open(partition_id, snapshot_id).await?;
+    let response = snapshot.response();
+    let (records, next_cursor) = snapshot
+        .query(move |db| db.scan_prefix(partition_id, &prefix, cursor.as_deref(), limit as usize))
+        .await?;
+
+    Ok(Json(SnapshotRecordsResponse {
+        snapshot: response,
+        records: records.into_iter().map(SnapshotRecord::from).collect(),
+        next_cursor: next_cursor.map(|cursor| BASE64_URL_SAFE_NO_PAD.encode(cursor)),
+    }))
+}
+
+impl From<StorageResult<DecodedRecord>> for SnapshotRecord {
+    fn from(record: StorageResult<DecodedRecord>) -> Self {
+        match record {
+            Ok(record) => SnapshotRecord {
+                key_kind: record.key_kind.to_string(),
+                key: record.key,
+                value: record.value,
+            },
+            Err(err) => SnapshotRecord {
+                key_kind: "<undecodable>".to_owned(),
+                key: String::new(),
+                value: err.to_string(),
+            },
+        }
+    }
+}
+
+fn inspector(state: &QueryServiceState) -> Result<&SnapshotInspector, StorageQueryError> {
+    state
+        .snapshots
+        .as_ref()
+        .ok_or(StorageQueryError::SnapshotsNotConfigured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_dir_is_deleted_on_drop() {
+        let base_dir = tempfile::tempdir().expect("temp dir");
+        let path = base_dir.path().join("snapshot-0");
+        std::fs::create_dir_all(path.join("staging")).expect("dir is created");
+        std::fs::write(path.join("staging").join("000001.sst"), b"sst").expect("file is written");
+
+        drop(SnapshotDir(path.clone()));
+        assert!(!path.exists());
+        // deleting a directory which doesn't exist is fine
+        drop(SnapshotDir(path));
+    }
+}
@@ -0,0 +1,454 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use bytes::Bytes;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::record_batch::RecordBatch;
+use okapi_operation::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use restate_types::identifiers::{ServiceId, WithPartitionKey};
+use restate_types::state_mut::{ExternalStateMutation, StateMutationVersion};
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
+
+use super::breakdown::{column, query_batches, string_value};
+use super::error::StorageQueryError;
+use super::invocations::quote;
+use crate::audit::AuditRecord;
+use crate::rest_api::create_envelope_header;
+use crate::state::QueryServiceState;
+
+/// # State entry
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct StateEntry {
+    /// # Key
+    pub key: String,
+    /// # Value
+    ///
+    /// Base64 encoded value.
+    pub value: String,
+    /// # Value as UTF-8
+    ///
+    /// The value, if it's valid UTF-8, e.g. because it was serialized as JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_utf8: Option<String>,
+}
+
+/// # Object state response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ObjectStateResponse {
+    /// # Version
+    ///
+    /// Version of the whole state of the virtual object or workflow, to use as precondition when
+    /// modifying it.
+    pub version: String,
+    /// # Entries
+    pub entries: Vec<StateEntry>,
+}
+
+/// # State key response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StateKeyResponse {
+    /// # Version
+    ///
+    /// Version of the whole state of the virtual object or workflow, to use as precondition when
+    /// modifying it.
+    pub version: String,
+    #[serde(flatten)]
+    pub entry: StateEntry,
+}
+
+/// # Set state key request
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetStateKeyRequest {
+    /// # Value
+    ///
+    /// Base64 encoded value.
+    pub value: String,
+    /// # Version
+    ///
+    /// If set, the state is modified only if its version is still this one.
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DeleteStateKeyParams {
+    pub version: Option<String>,
+}
+
+/// # State mutation response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StateMutationResponse {
+    /// # Version
+    ///
+    /// Version of the state once the mutation is applied. The mutation is applied asynchronously,
+    /// and is dropped if the state changes in the meantime.
+    pub version: String,
+}
+
+/// Get the state of a virtual object
+#[openapi(
+    summary = "Get object state",
+    description = "Get all the state entries of the given virtual object or workflow, together with the state version.",
+    operation_id = "get_object_state",
+    tags = "service",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "object_key",
+            description = "Key of the virtual object or workflow.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn get_object_state(
+    State(state): State<Arc<QueryServiceState>>,
+    Path((service_name, object_key)): Path<(String, String)>,
+) -> Result<Json<ObjectStateResponse>, StorageQueryError> {
+    let service_id = ServiceId::new(service_name, object_key);
+    let (version, entries) = read_state(&state, &service_id).await?;
+
+    Ok(Json(ObjectStateResponse {
+        version: version.into_inner(),
+        entries: entries.into_iter().map(to_state_entry).collect(),
+    }))
+}
+
+/// Get a state key of a virtual object
+#[openapi(
+    summary = "Get object state key",
+    description = "Get the value of a state key of the given virtual object or workflow, together with the state version.",
+    operation_id = "get_object_state_key",
+    tags = "service",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "object_key",
+            description = "Key of the virtual object or workflow.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "state_key",
+            description = "State key.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn get_object_state_key(
+    State(state): State<Arc<QueryServiceState>>,
+    Path((service_name, object_key, state_key)): Path<(String, String, String)>,
+) -> Result<Json<StateKeyResponse>, StorageQueryError> {
+    let service_id = ServiceId::new(service_name, object_key);
+    let (version, entries) = read_state(&state, &service_id).await?;
+
+    let entry = entries
+        .into_iter()
+        .find(|(key, _)| key == state_key.as_bytes())
+        .ok_or(StorageQueryError::StateKeyNotFound(state_key))?;
+
+    Ok(Json(StateKeyResponse {
+        version: version.into_inner(),
+        entry: to_state_entry(entry),
+    }))
+}
+
+/// Set a state key of a virtual object
+#[openapi(
+    summary = "Set object state key",
+    description = "Set the value of a state key of the given virtual object or workflow, leaving the other keys untouched. \
+    If the object is running an invocation, the change is applied once the invocation completes. \
+    The change is dropped if the state is modified in the meantime.",
+    operation_id = "set_object_state_key",
+    tags = "service",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "object_key",
+            description = "Key of the virtual object or workflow.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "state_key",
+            description = "State key.",
+            schema = "std::string::String"
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "Json<StateMutationResponse>",
+        ),
+        from_type = "StorageQueryError",
+    )
+)]
+pub async fn set_object_state_key(
+    State(state): State<Arc<QueryServiceState>>,
+    Extension(audit): Extension<AuditRecord>,
+    Path((service_name, object_key, state_key)): Path<(String, String, String)>,
+    #[request_body(required = true)] Json(SetStateKeyRequest { value, version }): Json<
+        SetStateKeyRequest,
+    >,
+) -> Result<(StatusCode, Json<StateMutationResponse>), StorageQueryError> {
+    let value = BASE64_STANDARD
+        .decode(value)
+        .map_err(|e| StorageQueryError::InvalidField("value", e.to_string()))?;
+
+    mutate_state_key(
+        &state,
+        &audit,
+        ServiceId::new(service_name, object_key),
+        state_key,
+        Some(Bytes::from(value)),
+        version,
+    )
+    .await
+}
+
+/// Delete a state key of a virtual object
+#[openapi(
+    summary = "Delete object state key",
+    description = "Delete a state key of the given virtual object or workflow, leaving the other keys untouched. \
+    If the object is running an invocation, the change is applied once the invocation completes. \
+    The change is dropped if the state is modified in the meantime.",
+    operation_id = "delete_object_state_key",
+    tags = "service",
+    parameters(
+        path(
+            name = "service",
+            description = "Fully qualified service name.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "object_key",
+            description = "Key of the virtual object or workflow.",
+            schema = "std::string::String"
+        ),
+        path(
+            name = "state_key",
+            description = "State key.",
+            schema = "std::string::String"
+        ),
+        query(
+            name = "version",
+            description = "If set, the state is modified only if its version is still this one.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
+    responses(
+        ignore_return_type = true,
+        response(
+            status = "202",
+            description = "Accepted",
+            content = "Json<StateMutationResponse>",
+        ),
+        from_type = "StorageQueryError",
+    )
+)]
+pub async fn delete_object_state_key(
+    State(state): State<Arc<QueryServiceState>>,
+    Extension(audit): Extension<AuditRecord>,
+    Path((service_name, object_key, state_key)): Path<(String, String, String)>,
+    Query(DeleteStateKeyParams { version }): Query<DeleteStateKeyParams>,
+) -> Result<(StatusCode, Json<StateMutationResponse>), StorageQueryError> {
+    mutate_state_key(
+        &state,
+        &audit,
+        ServiceId::new(service_name, object_key),
+        state_key,
+        None,
+        version,
+    )
+    .await
+}
+
+/// Sets or, if the value is none, deletes the state key. As state mutations replace the whole
+/// state, the mutation is built from the current state, and is conditional on its version so
+/// that concurrent changes to the other keys are not overwritten. The audit log records the
+/// versions of the state, but not the values.
+async fn mutate_state_key(
+    state: &QueryServiceState,
+    audit: &AuditRecord,
+    service_id: ServiceId,
+    state_key: String,
+    value: Option<Bytes>,
+    expected_version: Option<String>,
+) -> Result<(StatusCode, Json<StateMutationResponse>), StorageQueryError> {
+    let (current_version, entries) = read_state(state, &service_id).await?;
+    if let Some(expected_version) = expected_version {
+        if expected_version != current_version.as_str() {
+            return Err(StorageQueryError::StateVersionMismatch(
+                expected_version,
+                current_version.into_inner(),
+            ));
+        }
+    }
+
+    let mut new_state: HashMap<_, _> = entries.into_iter().collect();
+    let is_set = value.is_some();
+    match value {
+        Some(value) => {
+            new_state.insert(Bytes::from(state_key.clone()), value);
+        }
+        None => {
+            if new_state.remove(&Bytes::from(state_key.clone())).is_none() {
+                return Err(StorageQueryError::StateKeyNotFound(state_key));
+            }
+        }
+    }
+    let new_version = StateMutationVersion::from_user_state(
+        &new_state
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>(),
+    );
+
+    audit.before(serde_json::json!({
+        "service": service_id.service_name.to_string(),
+        "object_key": service_id.key.to_string(),
+        "state_key": state_key,
+        "version": current_version.as_str(),
+    }));
+    info!(
+        rpc.service = %service_id.service_name,
+        restate.object.key = %service_id.key,
+        restate.state.key = state_key,
+        restate.state.version = %current_version,
+        "{} object state key through the admin API",
+        if is_set { "Setting" } else { "Deleting" }
+    );
+
+    let partition_key = service_id.partition_key();
+    let result = append_envelope_to_bifrost(
+        &state.bifrost,
+        Arc::new(Envelope::new(
+            create_envelope_header(partition_key),
+            Command::PatchState(ExternalStateMutation {
+                service_id,
+                version: Some(current_version.into_inner()),
+                state: new_state,
+            }),
+        )),
+    )
+    .await;
+
+    if let Err(err) = result {
+        warn!("Could not append state patching command to Bifrost: {err}");
+        return Err(StorageQueryError::Append(err.to_string()));
+    }
+
+    audit.after(serde_json::json!({
+        "state_key": state_key,
+        "version": new_version.as_str(),
+        "deleted": !is_set,
+    }));
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(StateMutationResponse {
+            version: new_version.into_inner(),
+        }),
+    ))
+}
+
+/// Reads the state entries of the object, sorted by key, together with the state version.
+async fn read_state(
+    state: &QueryServiceState,
+    service_id: &ServiceId,
+) -> Result<(StateMutationVersion, Vec<(Bytes, Bytes)>), StorageQueryError> {
+    let batches = query_batches(state, build_query(service_id)).await?;
+    let entries = read_entries(&batches)?;
+
+    Ok((StateMutationVersion::from_user_state(&entries), entries))
+}
+
+fn build_query(service_id: &ServiceId) -> String {
+    format!(
+        "SELECT key, value FROM state WHERE partition_key = {} AND service_name = {} \
+        AND service_key = {} ORDER BY key",
+        service_id.partition_key(),
+        quote(&service_id.service_name),
+        quote(&service_id.key)
+    )
+}
+
+fn read_entries(batches: &[RecordBatch]) -> Result<Vec<(Bytes, Bytes)>, StorageQueryError> {
+    let mut entries = Vec::new();
+    for batch in batches {
+        let values = column(batch, "value")?
+            .as_binary_opt::<i64>()
+            .ok_or_else(|| {
+                StorageQueryError::UnexpectedResult("unexpected type of column 'value'".to_owned())
+            })?;
+        for row in 0..batch.num_rows() {
+            let key = string_value(batch, "key", row)?.unwrap_or_default();
+            let value = if values.is_valid(row) {
+                Bytes::copy_from_slice(values.value(row))
+            } else {
+                Bytes::new()
+            };
+            entries.push((Bytes::from(key), value));
+        }
+    }
+    Ok(entries)
+}
+
+fn to_state_entry((key, value): (Bytes, Bytes)) -> StateEntry {
+    StateEntry {
+        key: String::from_utf8_lossy(&key).into_owned(),
+        value: BASE64_STANDARD.encode(&value),
+        value_utf8: std::str::from_utf8(&value).ok().map(str::to_owned),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_of_object_state() {
+        let service_id = ServiceId::new("Counter", "it's");
+
+        assert_eq!(
+            build_query(&service_id),
+            format!(
+                "SELECT key, value FROM state WHERE partition_key = {} \
+                AND service_name = 'Counter' AND service_key = 'it''s' ORDER BY key",
+                service_id.partition_key()
+            )
+        );
+    }
+}
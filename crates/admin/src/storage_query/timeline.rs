@@ -0,0 +1,154 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{UInt32Type, UInt64Type};
+use datafusion::arrow::record_batch::RecordBatch;
+use okapi_operation::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use restate_types::identifiers::InvocationId;
+
+use super::breakdown::{column, date_value, query_batches, string_value, unexpected_type};
+use super::error::StorageQueryError;
+use crate::state::QueryServiceState;
+
+/// # Invocation timeline
+///
+/// Lifecycle events of an invocation, in the order they happened. Only the 64 most recent events
+/// of each invocation are retained, and they are deleted together with the invocation.
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct InvocationTimeline {
+    /// # Invocation id
+    pub invocation_id: String,
+    /// # Events
+    pub events: Vec<InvocationTimelineEvent>,
+}
+
+/// # Invocation timeline event
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct InvocationTimelineEvent {
+    /// # Sequence number
+    pub seq: u64,
+    /// # Timestamp
+    ///
+    /// Milliseconds since the unix epoch at which the event was recorded.
+    pub timestamp: u64,
+    /// # Kind
+    ///
+    /// Same as the `kind` column of `sys_invocation_timeline`.
+    pub kind: String,
+    /// # Attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
+    /// # Deployment id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment_id: Option<String>,
+    /// # Execution time
+    ///
+    /// Milliseconds since the unix epoch at which a scheduled invocation will start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_time: Option<u64>,
+    /// # Waiting for entries
+    ///
+    /// Journal entries a suspended invocation is waiting for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub waiting_for_entries: Vec<u32>,
+    /// # Failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+/// Invocation timeline
+#[openapi(
+    summary = "Invocation timeline",
+    description = "Lists the lifecycle events of the given invocation, such as its attempts, \
+    suspensions and retries.",
+    operation_id = "invocation_timeline",
+    tags = "storage",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    )),
+    responses(from_type = "StorageQueryError")
+)]
+pub async fn invocation_timeline(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(invocation_id): Path<String>,
+) -> Result<Json<InvocationTimeline>, StorageQueryError> {
+    // parsing guarantees that the id can be safely embedded in the query
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| StorageQueryError::InvalidInvocationId(e.to_string()))?;
+
+    let batches = query_batches(
+        &state,
+        format!(
+            "SELECT seq, timestamp, kind, attempt, deployment_id, execution_time, \
+            waiting_for_entries, failure \
+            FROM sys_invocation_timeline WHERE id = '{invocation_id}' ORDER BY seq"
+        ),
+    )
+    .await?;
+
+    Ok(Json(InvocationTimeline {
+        invocation_id: invocation_id.to_string(),
+        events: read_events(&batches)?,
+    }))
+}
+
+fn read_events(batches: &[RecordBatch]) -> Result<Vec<InvocationTimelineEvent>, StorageQueryError> {
+    let mut events = Vec::new();
+    for batch in batches {
+        let seqs = column(batch, "seq")?
+            .as_primitive_opt::<UInt64Type>()
+            .ok_or_else(|| unexpected_type("seq"))?;
+        let attempts = column(batch, "attempt")?
+            .as_primitive_opt::<UInt32Type>()
+            .ok_or_else(|| unexpected_type("attempt"))?;
+
+        for row in 0..batch.num_rows() {
+            let waiting_for_entries = string_value(batch, "waiting_for_entries", row)?
+                .map(|entries| parse_entry_indexes(&entries))
+                .transpose()?
+                .unwrap_or_default();
+
+            events.push(InvocationTimelineEvent {
+                seq: seqs.value(row),
+                timestamp: date_value(batch, "timestamp", row)?.unwrap_or_default(),
+                kind: string_value(batch, "kind", row)?.unwrap_or_default(),
+                attempt: attempts.is_valid(row).then(|| attempts.value(row)),
+                deployment_id: string_value(batch, "deployment_id", row)?,
+                execution_time: date_value(batch, "execution_time", row)?,
+                waiting_for_entries,
+                failure: string_value(batch, "failure", row)?,
+            });
+        }
+    }
+    Ok(events)
+}
+
+fn parse_entry_indexes(entries: &str) -> Result<Vec<u32>, StorageQueryError> {
+    entries
+        .split(',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry.parse().map_err(|_| {
+                StorageQueryError::UnexpectedResult(format!("invalid entry index '{entry}'"))
+            })
+        })
+        .collect()
+}
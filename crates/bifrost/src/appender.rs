@@ -11,6 +11,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use metrics::histogram;
 use tracing::{debug, info, instrument, warn};
 
 use restate_core::Metadata;
@@ -24,6 +25,7 @@ use restate_types::storage::StorageEncode;
 use crate::bifrost::BifrostInner;
 use crate::loglet::AppendError;
 use crate::loglet_wrapper::LogletWrapper;
+use crate::metric_definitions::{BIFROST_APPEND_BATCH_SIZE, BIFROST_APPEND_DURATION};
 use crate::{Error, InputRecord, Result};
 
 #[derive(Clone, derive_more::Debug)]
@@ -93,6 +95,8 @@ impl Appender {
             .append_retry_policy()
             .into_iter();
 
+        histogram!(BIFROST_APPEND_BATCH_SIZE).record(batch.len() as f64);
+        let start = Instant::now();
         let mut attempt = 0;
         loop {
             attempt += 1;
@@ -103,7 +107,10 @@ impl Appender {
                 Some(wrapper) => wrapper,
             };
             match loglet.append_batch(batch.clone()).await {
-                Ok(lsn) => return Ok(lsn),
+                Ok(lsn) => {
+                    histogram!(BIFROST_APPEND_DURATION).record(start.elapsed());
+                    return Ok(lsn);
+                }
                 Err(AppendError::Sealed) => {
                     info!(
                         attempt = attempt,
@@ -222,6 +222,21 @@ impl Bifrost {
         self.inner.get_trim_point(log_id).await
     }
 
+    /// The loglet providers which are enabled on this node. Empty if bifrost hasn't been started
+    /// yet.
+    pub fn enabled_providers(&self) -> Vec<ProviderKind> {
+        self.inner
+            .providers
+            .get()
+            .map(|providers| {
+                providers
+                    .iter()
+                    .filter_map(|(kind, provider)| provider.as_ref().map(|_| kind))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Read a full log with the given id. To be used only in tests!!!
     #[cfg(any(test, feature = "test-util"))]
     pub async fn read_all(&self, log_id: LogId) -> Result<Vec<crate::LogEntry>> {
@@ -964,6 +979,13 @@ mod tests {
             );
         }
 
+        // the provider of the new segments is recorded as the provider of the log
+        let logs = Metadata::with_current(|m| m.logs_ref());
+        assert_that!(
+            logs.configuration().providers.get(&LOG_ID),
+            some(eq(&ProviderKind::Local))
+        );
+
         // make sure that appends are still happening.
         let mut append_counter_after_seal = append_counter.load(Ordering::Relaxed);
         tokio::time::sleep(Duration::from_millis(100)).await;
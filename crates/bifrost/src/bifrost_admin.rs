@@ -82,6 +82,9 @@ impl<'a> BifrostAdmin<'a> {
     ///     version to be synced (set to `Version::MIN` to ignore this step)
     ///   - if segment_index is set, the tail loglet must match segment_index.
     ///
+    /// The new segment uses the given `provider`, which is also recorded as the provider of the
+    /// log in the logs configuration. This moves all future segments of the log to it.
+    ///
     /// This will continue to retry sealing for seal retryable errors automatically.
     #[instrument(level = "debug", skip(self), err)]
     pub async fn seal_and_extend_chain(
@@ -116,6 +119,33 @@ impl<'a> BifrostAdmin<'a> {
         Ok(sealed_segment)
     }
 
+    /// Sets the provider of the log in the logs configuration. If the current segment of the log
+    /// uses a different provider, the logs controller seals it and continues the log with a new
+    /// segment of the given provider. Passing `None` removes the provider of the log, which
+    /// leaves the log on the provider of its current segment unless the logs configuration
+    /// specifies a default provider.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn set_provider(&self, log_id: LogId, provider: Option<ProviderKind>) -> Result<()> {
+        self.bifrost.inner.fail_if_shutting_down()?;
+        let logs = self
+            .metadata_store_client
+            .read_modify_write(BIFROST_CONFIG_KEY.clone(), move |logs: Option<Logs>| {
+                let logs = logs.ok_or(Error::UnknownLogId(log_id))?;
+                if logs.chain(&log_id).is_none() {
+                    return Err(Error::UnknownLogId(log_id));
+                }
+
+                let mut builder = logs.into_builder();
+                builder.set_provider(log_id, provider);
+                Ok(builder.build())
+            })
+            .await
+            .map_err(|e| e.transpose())?;
+
+        self.metadata_writer.update(Arc::new(logs)).await?;
+        Ok(())
+    }
+
     pub async fn writeable_loglet(&self, log_id: LogId) -> Result<LogletWrapper> {
         self.inner.writeable_loglet(log_id).await
     }
@@ -166,7 +196,7 @@ impl<'a> BifrostAdmin<'a> {
         })
     }
 
-    /// Adds a segment to the end of the chain
+    /// Adds a segment to the end of the chain and sets `provider` as the provider of the log.
     ///
     /// The loglet must be sealed first. This operations assumes that the loglet with
     /// `last_segment_index` has been sealed prior to this call.
@@ -196,15 +226,19 @@ impl<'a> BifrostAdmin<'a> {
                     }));
                 }
 
-                match chain_builder.append_segment(base_lsn, provider, params.clone()) {
-                    Err(e) => match e {
+                if let Err(e) = chain_builder.append_segment(base_lsn, provider, params.clone()) {
+                    return match e {
                         BuilderError::SegmentConflict(lsn) => {
                             Err(Error::from(AdminError::SegmentConflict(lsn)))
                         }
                         _ => unreachable!("the log must exist at this point"),
-                    },
-                    Ok(_) => Ok(builder.build()),
+                    };
                 }
+
+                // Record the provider in the same update so that the logs controller keeps
+                // creating new segments of this log with it instead of moving the log back.
+                builder.set_provider(log_id, Some(provider));
+                Ok(builder.build())
             })
             .await
             .map_err(|e| e.transpose())?;
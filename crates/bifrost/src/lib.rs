@@ -15,7 +15,9 @@ mod bifrost_admin;
 mod error;
 pub mod loglet;
 mod loglet_wrapper;
+mod metric_definitions;
 pub mod providers;
+mod read_ahead;
 mod read_stream;
 mod record;
 mod service;
@@ -27,7 +29,8 @@ pub use background_appender::{AppenderHandle, BackgroundAppender, CommitToken, L
 pub use bifrost::Bifrost;
 pub use bifrost_admin::BifrostAdmin;
 pub use error::{Error, Result};
-pub use read_stream::LogReadStream;
+pub use read_ahead::ReadAheadStream;
+pub use read_stream::{LogReadStream, RecordFilter};
 pub use record::{InputRecord, LogEntry};
 pub use service::BifrostService;
 pub use types::*;
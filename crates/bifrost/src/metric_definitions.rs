@@ -0,0 +1,30 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+/// Optional to have but adds description/help message to the metrics emitted to
+/// the metrics' sink.
+use metrics::{describe_histogram, Unit};
+
+pub(crate) const BIFROST_APPEND_DURATION: &str = "restate.bifrost.append_duration.seconds";
+pub(crate) const BIFROST_APPEND_BATCH_SIZE: &str = "restate.bifrost.append_batch_size";
+
+pub(crate) fn describe_metrics() {
+    describe_histogram!(
+        BIFROST_APPEND_DURATION,
+        Unit::Seconds,
+        "Time taken to append a batch of records to a log, including the retries"
+    );
+
+    describe_histogram!(
+        BIFROST_APPEND_BATCH_SIZE,
+        Unit::Count,
+        "Number of records in the batches appended to a log"
+    );
+}
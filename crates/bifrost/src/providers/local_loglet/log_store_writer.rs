@@ -12,17 +12,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::BytesMut;
-use futures::StreamExt as FutureStreamExt;
 use metrics::histogram;
 use rocksdb::{BoundColumnFamily, WriteBatch};
 use tokio::sync::{mpsc, oneshot};
-use tokio_stream::wrappers::ReceiverStream;
-use tokio_stream::StreamExt as TokioStreamExt;
 use tracing::{debug, error, trace, warn};
 
 use restate_core::{cancellation_watcher, ShutdownError, TaskCenter, TaskKind};
 use restate_rocksdb::{IoMode, Priority, RocksDb};
-use restate_types::config::LocalLogletOptions;
+use restate_types::config::{FsyncPolicy, LocalLogletOptions};
 use restate_types::live::BoxedLiveLoad;
 use restate_types::logs::{LogletOffset, Record, SequenceNumber};
 
@@ -45,6 +42,7 @@ pub struct LogStoreWriteCommand {
     loglet_id: u64,
     data_update: Option<DataUpdate>,
     log_state_updates: Option<LogStateUpdates>,
+    fsync_policy: FsyncPolicy,
     ack: Option<Ack>,
 }
 
@@ -63,6 +61,14 @@ pub(crate) struct LogStoreWriter {
     rocksdb: Arc<RocksDb>,
     batch_acks_buf: Vec<Ack>,
     buffer: BytesMut,
+    /// The group commit which is currently being staged
+    write_batch: WriteBatch,
+    staged_commands: usize,
+    /// Whether the staged group commit contains a command which must be synced before it's acked
+    sync_required: bool,
+    /// Whether unsynced writes of logs with the `interval` fsync policy were committed since the
+    /// last WAL sync
+    pending_interval_sync: bool,
 }
 
 impl LogStoreWriter {
@@ -71,6 +77,10 @@ impl LogStoreWriter {
             rocksdb,
             batch_acks_buf: Vec::default(),
             buffer: BytesMut::with_capacity(INITIAL_SERDE_BUFFER_SIZE),
+            write_batch: WriteBatch::default(),
+            staged_commands: 0,
+            sync_required: false,
+            pending_interval_sync: false,
         }
     }
 
@@ -83,39 +93,35 @@ impl LogStoreWriter {
         let batch_size = std::cmp::max(1, updateable.live_load().writer_batch_commit_count);
         // leave twice as much space in the the channel to ensure we can enqueue up-to a full batch in
         // the backlog while we process this one.
-        let (sender, receiver) = mpsc::channel(batch_size * 2);
+        let (sender, mut receiver) = mpsc::channel(batch_size * 2);
 
         TaskCenter::spawn_child(
             TaskKind::LogletProvider,
             "local-loglet-writer",
             async move {
                 debug!("Start running LogStoreWriter");
-                let opts = updateable.live_load();
-                let batch_size = std::cmp::max(1, opts.writer_batch_commit_count);
-                let batch_duration: Duration = opts.writer_batch_commit_duration.into();
-                // We don't want to use chunks_timeout if time-based batching is disabled, why?
-                // because even if duration is zero, tokio's timer resolution is 1ms which means
-                // that we will delay every batch by 1ms for no reason.
-                let receiver = if batch_duration == Duration::ZERO {
-                    ReceiverStream::new(receiver)
-                        .ready_chunks(batch_size)
-                        .boxed()
-                } else {
-                    ReceiverStream::new(receiver)
-                        .chunks_timeout(batch_size, batch_duration)
-                        .boxed()
-                };
-                tokio::pin!(receiver);
-
+                // only started once a log with the `interval` fsync policy committed a write
+                let mut fsync_timer: Option<tokio::time::Interval> = None;
                 loop {
+                    // wait for the command which opens the next group commit
                     tokio::select! {
                         biased;
                         _ = cancellation_watcher() => {
                             break;
                         }
-                        Some(cmds) = TokioStreamExt::next(&mut receiver) => {
-                                let opts = updateable.live_load();
-                                self.handle_commands(opts, cmds).await;
+                        Some(command) = receiver.recv() => {
+                            let opts = updateable.live_load();
+                            self.stage(opts, command);
+                            self.fill_group_commit(opts, &mut receiver).await;
+                            self.commit(opts).await;
+                            if self.pending_interval_sync && fsync_timer.is_none() {
+                                fsync_timer = Some(Self::fsync_timer(opts.fsync_interval.into()));
+                            }
+                        }
+                        _ = async {
+                            fsync_timer.as_mut().expect("fsync timer is started").tick().await
+                        }, if fsync_timer.is_some() => {
+                            self.sync_wal().await;
                         }
                     }
                 }
@@ -126,76 +132,128 @@ impl LogStoreWriter {
         Ok(RocksDbLogWriterHandle { sender })
     }
 
-    async fn handle_commands(
+    fn fsync_timer(fsync_interval: Duration) -> tokio::time::Interval {
+        let mut fsync_timer =
+            tokio::time::interval_at(tokio::time::Instant::now() + fsync_interval, fsync_interval);
+        fsync_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        fsync_timer
+    }
+
+    /// Stages further commands into the open group commit until it is full, or until the group
+    /// commit window elapses. Commands which are immediately available are always staged, even if
+    /// the window is zero.
+    async fn fill_group_commit(
         &mut self,
         opts: &LocalLogletOptions,
-        commands: Vec<LogStoreWriteCommand>,
+        receiver: &mut mpsc::Receiver<LogStoreWriteCommand>,
     ) {
-        let mut write_batch = WriteBatch::default();
-        self.batch_acks_buf.clear();
-        self.batch_acks_buf.reserve(commands.len());
-        let batch_acks = &mut self.batch_acks_buf;
-        let buffer = &mut self.buffer;
-        {
-            let data_cf = self
-                .rocksdb
-                .inner()
-                .cf_handle(DATA_CF)
-                .expect("data cf exists");
-            let metadata_cf = self
-                .rocksdb
-                .inner()
-                .cf_handle(METADATA_CF)
-                .expect("metadata cf exists");
-
-            for command in commands {
-                match command.data_update {
-                    Some(DataUpdate::PutRecords {
-                        first_offset,
-                        payloads,
-                    }) => Self::put_records(
-                        &data_cf,
-                        buffer,
-                        &mut write_batch,
-                        command.loglet_id,
-                        first_offset,
-                        payloads,
-                    ),
-                    Some(DataUpdate::TrimLog {
-                        old_trim_point,
-                        new_trim_point,
-                    }) => Self::trim_log(
-                        &data_cf,
-                        buffer,
-                        &mut write_batch,
-                        command.loglet_id,
-                        old_trim_point,
-                        new_trim_point,
-                    ),
-                    None => {}
+        let batch_size = std::cmp::max(1, opts.writer_batch_commit_count);
+        let batch_bytes = opts.writer_batch_commit_size.get();
+        let window: Duration = opts.writer_batch_commit_duration.into();
+        let deadline = tokio::time::Instant::now() + window;
+
+        while self.staged_commands < batch_size && self.write_batch.size_in_bytes() < batch_bytes {
+            match receiver.try_recv() {
+                Ok(command) => {
+                    self.stage(opts, command);
+                    continue;
                 }
+                Err(mpsc::error::TryRecvError::Empty) => {}
+                Err(mpsc::error::TryRecvError::Disconnected) => break,
+            }
+            // We don't want to wait if the window is zero, why? because even if duration is
+            // zero, tokio's timer resolution is 1ms which means that we will delay every batch
+            // by 1ms for no reason.
+            if window.is_zero() {
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                command = receiver.recv() => match command {
+                    Some(command) => self.stage(opts, command),
+                    None => break,
+                },
+            }
+        }
+    }
 
-                // todo: future optimization. pre-merge all updates within a batch before writing
-                // the merge to rocksdb.
-                if let Some(logstate_updates) = command.log_state_updates {
-                    Self::update_log_state(
-                        &metadata_cf,
-                        &mut write_batch,
-                        command.loglet_id,
-                        logstate_updates,
-                        buffer,
-                    )
-                }
+    /// Syncs the WAL if writes of logs with the `interval` fsync policy were committed without a
+    /// sync since the last tick.
+    async fn sync_wal(&mut self) {
+        if !self.pending_interval_sync {
+            return;
+        }
+        self.pending_interval_sync = false;
+        if let Err(e) = self.rocksdb.flush_wal(true).await {
+            warn!("Failed to sync local loglet WAL: {}", e);
+            // retry on the next tick
+            self.pending_interval_sync = true;
+        }
+    }
 
-                if let Some(ack) = command.ack {
-                    batch_acks.push(ack);
-                }
-            }
+    fn stage(&mut self, opts: &LocalLogletOptions, command: LogStoreWriteCommand) {
+        let buffer = &mut self.buffer;
+        let write_batch = &mut self.write_batch;
+        let data_cf = self
+            .rocksdb
+            .inner()
+            .cf_handle(DATA_CF)
+            .expect("data cf exists");
+        let metadata_cf = self
+            .rocksdb
+            .inner()
+            .cf_handle(METADATA_CF)
+            .expect("metadata cf exists");
+
+        match command.data_update {
+            Some(DataUpdate::PutRecords {
+                first_offset,
+                payloads,
+            }) => Self::put_records(
+                &data_cf,
+                buffer,
+                write_batch,
+                command.loglet_id,
+                first_offset,
+                payloads,
+                opts.record_checksums,
+            ),
+            Some(DataUpdate::TrimLog {
+                old_trim_point,
+                new_trim_point,
+            }) => Self::trim_log(
+                &data_cf,
+                buffer,
+                write_batch,
+                command.loglet_id,
+                old_trim_point,
+                new_trim_point,
+            ),
+            None => {}
         }
 
-        histogram!(BIFROST_LOCAL_WRITE_BATCH_SIZE_BYTES).record(write_batch.size_in_bytes() as f64);
-        histogram!(BIFROST_LOCAL_WRITE_BATCH_COUNT).record(write_batch.len() as f64);
-        self.commit(opts, write_batch).await;
+        // todo: future optimization. pre-merge all updates within a batch before writing
+        // the merge to rocksdb.
+        if let Some(logstate_updates) = command.log_state_updates {
+            Self::update_log_state(
+                &metadata_cf,
+                write_batch,
+                command.loglet_id,
+                logstate_updates,
+                buffer,
+            )
+        }
+
+        match command.fsync_policy {
+            FsyncPolicy::Always => self.sync_required = true,
+            FsyncPolicy::Interval => self.pending_interval_sync = true,
+            FsyncPolicy::OsBuffered => {}
+        }
+
+        if let Some(ack) = command.ack {
+            self.batch_acks_buf.push(ack);
+        }
+        self.staged_commands += 1;
     }
 
     fn update_log_state(
@@ -220,12 +278,17 @@ impl LogStoreWriter {
         id: u64,
         mut offset: LogletOffset,
         payloads: Arc<[Record]>,
+        with_checksum: bool,
     ) {
         serde_buffer.reserve(payloads.len() * RECORD_SIZE_GUESS);
         for payload in payloads.iter() {
             let key_bytes = RecordKey::new(id, offset).encode_and_split(serde_buffer);
-            let value_bytes =
-                encode_record_and_split(FORMAT_FOR_NEW_APPENDS, payload, serde_buffer);
+            let value_bytes = encode_record_and_split(
+                FORMAT_FOR_NEW_APPENDS,
+                payload,
+                with_checksum,
+                serde_buffer,
+            );
             write_batch.put_cf(data_cf, key_bytes, value_bytes);
             // advance the offset for the next record
             offset = offset.next();
@@ -256,10 +319,22 @@ impl LogStoreWriter {
         write_batch.delete_range_cf(data_cf, from_bytes, to_bytes);
     }
 
-    async fn commit(&mut self, opts: &LocalLogletOptions, write_batch: WriteBatch) {
+    /// Commits the staged group commit with a single write (and WAL sync) to RocksDB.
+    async fn commit(&mut self, opts: &LocalLogletOptions) {
+        let write_batch = std::mem::take(&mut self.write_batch);
+        self.staged_commands = 0;
+        let sync = std::mem::take(&mut self.sync_required);
+        if sync {
+            // the sync of this batch covers all earlier writes as well
+            self.pending_interval_sync = false;
+        }
+
+        histogram!(BIFROST_LOCAL_WRITE_BATCH_SIZE_BYTES).record(write_batch.size_in_bytes() as f64);
+        histogram!(BIFROST_LOCAL_WRITE_BATCH_COUNT).record(write_batch.len() as f64);
+
         let mut write_opts = rocksdb::WriteOptions::new();
         write_opts.disable_wal(opts.rocksdb.rocksdb_disable_wal());
-        write_opts.set_sync(!opts.rocksdb_disable_wal_fsync());
+        write_opts.set_sync(sync);
 
         trace!(
             "Committing local loglet current write batch: {} items",
@@ -303,6 +378,7 @@ pub struct RocksDbLogWriterHandle {
 }
 
 impl RocksDbLogWriterHandle {
+    /// Seals are always synced, regardless of the fsync policy of the log.
     pub async fn enqueue_seal(&self, loglet_id: u64) -> Result<AckRecv, ShutdownError> {
         let (ack, receiver) = oneshot::channel();
         let log_state_updates = Some(LogStateUpdates::default().seal());
@@ -310,6 +386,7 @@ impl RocksDbLogWriterHandle {
             loglet_id,
             data_update: None,
             log_state_updates,
+            fsync_policy: FsyncPolicy::Always,
             ack: Some(ack),
         })
         .await?;
@@ -321,6 +398,7 @@ impl RocksDbLogWriterHandle {
         loglet_id: u64,
         start_offset: LogletOffset,
         payloads: Arc<[Record]>,
+        fsync_policy: FsyncPolicy,
     ) -> Result<AckRecv, ShutdownError> {
         let (ack, receiver) = oneshot::channel();
         // Do not allow more than 65k records in a single batch!
@@ -337,6 +415,7 @@ impl RocksDbLogWriterHandle {
             loglet_id,
             data_update: Some(data_update),
             log_state_updates,
+            fsync_policy,
             ack: Some(ack),
         })
         .await?;
@@ -359,6 +438,8 @@ impl RocksDbLogWriterHandle {
             loglet_id,
             data_update: Some(data_update),
             log_state_updates,
+            // trims are not acknowledged and can be safely repeated if lost
+            fsync_policy: FsyncPolicy::OsBuffered,
             ack: None,
         })
         .await
@@ -27,6 +27,12 @@ pub(crate) const BIFROST_LOCAL_TRIM: &str = "restate.bifrost.localloglet.trim.to
 
 pub(crate) const BIFROST_LOCAL_TRIM_LENGTH: &str = "restate.bifrost.localloglet.trim.length";
 
+pub(crate) const BIFROST_LOCAL_CORRUPTED_RECORDS: &str =
+    "restate.bifrost.localloglet.corrupted_records.total";
+
+pub(crate) const BIFROST_LOCAL_SKIPPED_RECORDS: &str =
+    "restate.bifrost.localloglet.skipped_records.total";
+
 pub(crate) fn describe_metrics() {
     describe_counter!(
         BIFROST_LOCAL_APPEND,
@@ -61,4 +67,14 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Lengths of bifrost's local loglet trims"
     );
+    describe_counter!(
+        BIFROST_LOCAL_CORRUPTED_RECORDS,
+        Unit::Count,
+        "Number of corrupted records read from bifrost's local loglet"
+    );
+    describe_counter!(
+        BIFROST_LOCAL_SKIPPED_RECORDS,
+        Unit::Count,
+        "Number of corrupted records skipped by readers of bifrost's local loglet"
+    );
 }
@@ -16,8 +16,10 @@ mod metric_definitions;
 mod provider;
 mod read_stream;
 mod record_format;
+mod tiering;
 
 pub use self::provider::Factory;
+pub use self::record_format::RecordCorruptionError;
 
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
@@ -29,6 +31,7 @@ use tokio::sync::Mutex;
 use tracing::{debug, warn};
 
 use restate_core::ShutdownError;
+use restate_types::config::{CorruptionPolicy, FsyncPolicy};
 use restate_types::logs::{KeyFilter, LogletOffset, Record, SequenceNumber, TailState};
 
 use self::log_store::LogStoreError;
@@ -36,6 +39,7 @@ use self::log_store::RocksDbLogStore;
 use self::log_store_writer::RocksDbLogWriterHandle;
 use self::metric_definitions::{BIFROST_LOCAL_APPEND, BIFROST_LOCAL_APPEND_DURATION};
 use self::read_stream::LocalLogletReadStream;
+use self::tiering::LogletTier;
 use crate::loglet::util::TailOffsetWatch;
 use crate::loglet::{Loglet, LogletCommit, OperationError, SendableLogletReadStream};
 use crate::providers::local_loglet::metric_definitions::{
@@ -50,6 +54,13 @@ struct LocalLoglet {
     log_store: RocksDbLogStore,
     #[debug(skip)]
     log_writer: RocksDbLogWriterHandle,
+    // records are offloaded to this tier before they are trimmed, if configured
+    #[debug(skip)]
+    tier: Option<Arc<LogletTier>>,
+    // how readers react to corrupted records
+    corruption_policy: CorruptionPolicy,
+    // durability guarantee of acknowledged appends
+    fsync_policy: FsyncPolicy,
     // internal offset _before_ the loglet head. Loglet head is trim_point_offset.next()
     trim_point_offset: AtomicU32,
     // used to order concurrent trim operations :-(
@@ -72,6 +83,9 @@ impl LocalLoglet {
         loglet_id: u64,
         log_store: RocksDbLogStore,
         log_writer: RocksDbLogWriterHandle,
+        tier: Option<Arc<LogletTier>>,
+        corruption_policy: CorruptionPolicy,
+        fsync_policy: FsyncPolicy,
     ) -> Result<Self, OperationError> {
         // Fetch the log metadata from the store
         let log_state = log_store
@@ -91,6 +105,9 @@ impl LocalLoglet {
             loglet_id,
             log_store,
             log_writer,
+            tier,
+            corruption_policy,
+            fsync_policy,
             trim_point_offset,
             trim_point_lock: Mutex::new(()),
             next_write_offset,
@@ -163,7 +180,12 @@ impl Loglet for LocalLoglet {
             // lock acquired
             let receiver = self
                 .log_writer
-                .enqueue_put_records(self.loglet_id, *next_offset_guard, payloads)
+                .enqueue_put_records(
+                    self.loglet_id,
+                    *next_offset_guard,
+                    payloads,
+                    self.fsync_policy,
+                )
                 .await?;
             // next offset points to the next available slot.
             *next_offset_guard = *next_offset_guard + num_payloads;
@@ -223,6 +245,27 @@ impl Loglet for LocalLoglet {
             self.last_committed_offset.load(Ordering::Relaxed),
         ));
 
+        if let Some(tier) = &self.tier {
+            let current_trim_point =
+                LogletOffset::new(self.trim_point_offset.load(Ordering::Relaxed));
+            if current_trim_point >= effective_trim_point {
+                // nothing to do since we have already trimmed beyond new_trim_point
+                return Ok(());
+            }
+
+            // Records must be durably stored in the tier before they are removed locally. The
+            // upload doesn't hold the trim point lock, concurrent trims upload overlapping
+            // ranges at worst.
+            tier.offload(
+                &self.log_store,
+                self.loglet_id,
+                current_trim_point.next(),
+                effective_trim_point,
+            )
+            .await
+            .map_err(OperationError::other)?;
+        }
+
         // the lock is needed to prevent concurrent trim operations from over taking each other :-(
         // The problem is that the LogStoreWriter is not the component holding the ground truth
         // but the LocalLoglet is. The bad thing that could happen is that we might not trim earlier
@@ -276,7 +319,8 @@ impl Loglet for LocalLoglet {
 
 #[cfg(test)]
 mod tests {
-    use futures::TryStreamExt;
+    use bytes::BytesMut;
+    use futures::{StreamExt, TryStreamExt};
     use googletest::prelude::eq;
     use googletest::{assert_that, elements_are, IntoTestResult};
     use test_log::test;
@@ -284,11 +328,12 @@ mod tests {
     use crate::loglet::Loglet;
     use restate_core::{TaskCenter, TestCoreEnvBuilder};
     use restate_rocksdb::RocksDbManager;
-    use restate_types::config::Configuration;
+    use restate_types::config::{Configuration, LocalLogletTieringOptions};
     use restate_types::live::Live;
     use restate_types::logs::metadata::{LogletParams, ProviderKind};
     use restate_types::logs::Keys;
 
+    use super::keys::RecordKey;
     use super::*;
 
     macro_rules! run_test {
@@ -307,12 +352,22 @@ mod tests {
     }
 
     async fn create_loglet() -> anyhow::Result<Arc<LocalLoglet>> {
+        create_loglet_with(None, CorruptionPolicy::Halt, FsyncPolicy::Always).await
+    }
+
+    async fn create_loglet_with(
+        tier: Option<Arc<LogletTier>>,
+        corruption_policy: CorruptionPolicy,
+        fsync_policy: FsyncPolicy,
+    ) -> anyhow::Result<Arc<LocalLoglet>> {
         let _node_env = TestCoreEnvBuilder::with_incoming_only_connector()
             .set_provider_kind(ProviderKind::Local)
             .build()
             .await;
 
-        let config = Live::from_value(Configuration::default());
+        let mut config = Configuration::default();
+        config.bifrost.local.record_checksums = true;
+        let config = Live::from_value(config);
         RocksDbManager::init(config.clone().map(|c| &c.common));
         let params = LogletParams::from("42".to_string());
 
@@ -332,6 +387,9 @@ mod tests {
                 .expect("loglet params can be converted into u64"),
             log_store,
             log_writer,
+            tier,
+            corruption_policy,
+            fsync_policy,
         )?);
 
         Ok(loglet)
@@ -343,6 +401,17 @@ mod tests {
     run_test!(append_after_seal);
     run_test!(seal_empty);
 
+    #[restate_core::test]
+    async fn local_loglet_interval_fsync_smoke_test() -> googletest::Result<()> {
+        let loglet = create_loglet_with(None, CorruptionPolicy::Halt, FsyncPolicy::Interval)
+            .await
+            .into_test_result()?;
+        crate::loglet::loglet_tests::gapless_loglet_smoke_test(loglet).await?;
+        TaskCenter::shutdown_node("test completed", 0).await;
+        RocksDbManager::get().shutdown().await;
+        Ok(())
+    }
+
     #[restate_core::test(flavor = "multi_thread", worker_threads = 4)]
     async fn local_loglet_append_after_seal_concurrent() -> googletest::Result<()> {
         let _node_env = TestCoreEnvBuilder::with_incoming_only_connector()
@@ -369,6 +438,9 @@ mod tests {
                 i,
                 log_store.clone(),
                 log_writer.clone(),
+                None,
+                CorruptionPolicy::Halt,
+                FsyncPolicy::Always,
             )?);
             crate::loglet::loglet_tests::append_after_seal_concurrent(loglet).await?;
         }
@@ -416,4 +488,122 @@ mod tests {
 
         Ok(())
     }
+
+    #[test(restate_core::test)]
+    async fn read_stream_from_object_store_tier() -> googletest::Result<()> {
+        let tier_dir = tempfile::tempdir()?;
+        let options = LocalLogletTieringOptions {
+            destination: Some(format!("file://{}", tier_dir.path().display())),
+            ..Default::default()
+        };
+        let tier = LogletTier::create_if_configured(&options)?.map(Arc::new);
+        let loglet = create_loglet_with(tier, CorruptionPolicy::Halt, FsyncPolicy::Always)
+            .await
+            .into_test_result()?;
+
+        let batch: Arc<[Record]> = (1..=10)
+            .map(|i| Record::from(format!("record-{}", i)))
+            .collect();
+        let tail = loglet.enqueue_batch(batch).await?.await?;
+
+        // records [1..5] are only available in the object store tier afterward
+        loglet.trim(LogletOffset::from(5)).await?;
+        assert_eq!(Some(LogletOffset::from(5)), loglet.get_trim_point().await?);
+
+        let records: Vec<_> = loglet
+            .clone()
+            .create_read_stream(KeyFilter::Any, LogletOffset::OLDEST, Some(tail))
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|log_entry| {
+                (
+                    log_entry.sequence_number(),
+                    log_entry.decode_unchecked::<String>(),
+                )
+            })
+            .collect();
+
+        let expected: Vec<_> = (1..=10)
+            .map(|i| (LogletOffset::from(i), format!("record-{}", i)))
+            .collect();
+        assert_eq!(expected, records);
+
+        Ok(())
+    }
+
+    fn corrupt_record(loglet: &LocalLoglet, offset: LogletOffset) -> anyhow::Result<()> {
+        let mut buf = BytesMut::new();
+        let key = RecordKey::new(loglet.loglet_id, offset).encode_and_split(&mut buf);
+        let data_cf = loglet.log_store.data_cf();
+        let db = loglet.log_store.db();
+        let mut value = db.get_cf(&data_cf, &key)?.expect("record exists");
+        // flip a bit of the payload
+        *value.last_mut().unwrap() ^= 0x01;
+        db.put_cf(&data_cf, &key, value)?;
+        Ok(())
+    }
+
+    #[test(restate_core::test)]
+    async fn read_stream_halts_on_corrupted_record() -> googletest::Result<()> {
+        let loglet = create_loglet_with(None, CorruptionPolicy::Halt, FsyncPolicy::Always)
+            .await
+            .into_test_result()?;
+        let batch: Arc<[Record]> = (1..=3)
+            .map(|i| Record::from(format!("record-{}", i)))
+            .collect();
+        let tail = loglet.enqueue_batch(batch).await?.await?;
+        corrupt_record(&loglet, LogletOffset::from(2)).into_test_result()?;
+
+        let mut read_stream = loglet
+            .clone()
+            .create_read_stream(KeyFilter::Any, LogletOffset::OLDEST, Some(tail))
+            .await?;
+
+        let first = read_stream.next().await.unwrap()?;
+        assert_that!(first.sequence_number(), eq(LogletOffset::from(1)));
+        let err = read_stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(read_stream.next().await.is_none());
+
+        Ok(())
+    }
+
+    #[test(restate_core::test)]
+    async fn read_stream_skips_corrupted_record() -> googletest::Result<()> {
+        let loglet = create_loglet_with(None, CorruptionPolicy::Skip, FsyncPolicy::Always)
+            .await
+            .into_test_result()?;
+        let batch: Arc<[Record]> = (1..=3)
+            .map(|i| Record::from(format!("record-{}", i)))
+            .collect();
+        let tail = loglet.enqueue_batch(batch).await?.await?;
+        corrupt_record(&loglet, LogletOffset::from(2)).into_test_result()?;
+
+        let records: Vec<_> = loglet
+            .clone()
+            .create_read_stream(KeyFilter::Any, LogletOffset::OLDEST, Some(tail))
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|log_entry| {
+                (
+                    log_entry.sequence_number(),
+                    log_entry.decode_unchecked::<String>(),
+                )
+            })
+            .collect();
+
+        assert_that!(
+            records,
+            elements_are![
+                eq((LogletOffset::from(1), "record-1".to_owned())),
+                eq((LogletOffset::from(3), "record-3".to_owned()))
+            ]
+        );
+
+        Ok(())
+    }
 }
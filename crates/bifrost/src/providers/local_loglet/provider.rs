@@ -22,6 +22,7 @@ use restate_types::logs::LogId;
 
 use super::log_store::RocksDbLogStore;
 use super::log_store_writer::RocksDbLogWriterHandle;
+use super::tiering::LogletTier;
 use super::{metric_definitions, LocalLoglet};
 use crate::loglet::{Loglet, LogletProvider, LogletProviderFactory, OperationError};
 use crate::Error;
@@ -60,12 +61,19 @@ impl LogletProviderFactory for Factory {
         let log_store = RocksDbLogStore::create(opts, rocksdb_opts)
             .await
             .map_err(OperationError::other)?;
+        let tier = LogletTier::create_if_configured(&opts.tiering)
+            .map_err(OperationError::other)?
+            .map(Arc::new);
+        // corruption policies are resolved once per loglet, changing them requires a restart
+        let opts = opts.clone();
         let log_writer = log_store.create_writer().start(options)?;
         debug!("Started a bifrost local loglet provider");
         Ok(Arc::new(LocalLogletProvider {
             log_store,
             active_loglets: Default::default(),
             log_writer,
+            tier,
+            opts,
         }))
     }
 }
@@ -74,6 +82,8 @@ pub(crate) struct LocalLogletProvider {
     log_store: RocksDbLogStore,
     active_loglets: Mutex<HashMap<(LogId, SegmentIndex), Arc<LocalLoglet>>>,
     log_writer: RocksDbLogWriterHandle,
+    tier: Option<Arc<LogletTier>>,
+    opts: LocalLogletOptions,
 }
 
 #[async_trait]
@@ -95,6 +105,9 @@ impl LogletProvider for LocalLogletProvider {
                         .expect("loglet params can be converted into u64"),
                     self.log_store.clone(),
                     self.log_writer.clone(),
+                    self.tier.clone(),
+                    self.opts.corruption_policy(log_id),
+                    self.opts.fsync_policy(log_id),
                 )?;
                 let loglet = entry.insert(Arc::new(loglet));
                 Arc::clone(loglet)
@@ -8,19 +8,23 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::VecDeque;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::task::Poll;
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
+use futures::future::BoxFuture;
 use futures::stream::BoxStream;
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
+use metrics::counter;
 use rocksdb::{DBRawIteratorWithThreadMode, DB};
 use tracing::{debug, error, warn};
 
 use restate_core::ShutdownError;
 use restate_rocksdb::RocksDbPerfGuard;
-use restate_types::logs::{KeyFilter, LogletOffset, SequenceNumber, TailState};
+use restate_types::config::CorruptionPolicy;
+use restate_types::logs::{KeyFilter, LogletOffset, Record, SequenceNumber, TailState};
 
 use crate::loglet::{Loglet, LogletReadStream, OperationError};
 use crate::providers::local_loglet::record_format::decode_and_filter_record;
@@ -28,6 +32,9 @@ use crate::providers::local_loglet::LogStoreError;
 use crate::{LogEntry, Result};
 
 use super::keys::RecordKey;
+use super::metric_definitions::{BIFROST_LOCAL_CORRUPTED_RECORDS, BIFROST_LOCAL_SKIPPED_RECORDS};
+use super::record_format::RecordCorruptionError;
+use super::tiering::{TieredChunk, TieringError};
 use super::LocalLoglet;
 
 pub(crate) struct LocalLogletReadStream {
@@ -43,6 +50,9 @@ pub(crate) struct LocalLogletReadStream {
     read_to: Option<LogletOffset>,
     iterator: DBRawIteratorWithThreadMode<'static, DB>,
     tail_watch: BoxStream<'static, TailState<LogletOffset>>,
+    /// Records below the local trim point which were fetched from the object store tier.
+    tiered_records: VecDeque<(LogletOffset, Bytes)>,
+    tier_fetch: Option<BoxFuture<'static, Result<Option<TieredChunk>, TieringError>>>,
     terminated: bool,
     // IMPORTANT: Do not reorder, this should be dropped last since `iterator` holds a reference
     // into the underlying database.
@@ -121,12 +131,52 @@ impl LocalLogletReadStream {
             iterator: iter,
             terminated: false,
             tail_watch,
+            tiered_records: VecDeque::new(),
+            tier_fetch: None,
             last_known_tail,
             read_to: to,
         })
     }
 }
 
+impl LocalLogletReadStream {
+    /// Decodes the record at `offset` and applies the loglet's corruption policy if it fails
+    /// checksum verification or decoding.
+    fn decode_record(
+        &self,
+        offset: LogletOffset,
+        raw_value: &[u8],
+    ) -> Result<Option<Record>, OperationError> {
+        let reason = match decode_and_filter_record(raw_value, &self.filter) {
+            Ok(maybe_record) => return Ok(maybe_record),
+            Err(reason) => reason,
+        };
+
+        counter!(BIFROST_LOCAL_CORRUPTED_RECORDS).increment(1);
+        let err = RecordCorruptionError::new(self.loglet_id, offset, reason);
+        match self.loglet.corruption_policy {
+            CorruptionPolicy::Halt => {
+                error!(
+                    loglet_id = self.loglet_id,
+                    %offset,
+                    "Halting read stream on corrupted record: {err}"
+                );
+                Err(OperationError::other(err))
+            }
+            CorruptionPolicy::Skip => {
+                // readers never observe the skipped record, which is data loss
+                counter!(BIFROST_LOCAL_SKIPPED_RECORDS).increment(1);
+                error!(
+                    loglet_id = self.loglet_id,
+                    %offset,
+                    "Skipping corrupted record, the record is lost: {err}"
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
 impl LogletReadStream for LocalLogletReadStream {
     /// Current read pointer. This points to the next offset to be read.
     fn read_pointer(&self) -> LogletOffset {
@@ -198,6 +248,62 @@ impl Stream for LocalLogletReadStream {
             assert!(self.read_pointer > LogletOffset::from(0));
 
             if self.read_pointer < head_offset {
+                if let Some(tier) = self.loglet.tier.clone() {
+                    // Serve records below the local trim point from the object store tier
+                    if let Some((offset, raw_value)) = self.tiered_records.pop_front() {
+                        if offset < self.read_pointer {
+                            continue;
+                        }
+                        if offset > self.read_pointer {
+                            // The tier doesn't hold the records in between
+                            let trim_gap = LogEntry::new_trim_gap(self.read_pointer, offset.prev());
+                            self.tiered_records.push_front((offset, raw_value));
+                            self.read_pointer = offset;
+                            return Poll::Ready(Some(Ok(trim_gap)));
+                        }
+
+                        self.read_pointer = offset.next();
+                        let maybe_record = match self.decode_record(offset, &raw_value) {
+                            Ok(maybe_record) => maybe_record,
+                            Err(e) => {
+                                self.terminated = true;
+                                return Poll::Ready(Some(Err(e)));
+                            }
+                        };
+                        if let Some(record) = maybe_record {
+                            return Poll::Ready(Some(Ok(LogEntry::new_data(offset, record))));
+                        }
+                        continue;
+                    }
+
+                    let loglet_id = self.loglet_id;
+                    let read_pointer = self.read_pointer;
+                    let fetch = self.tier_fetch.get_or_insert_with(|| {
+                        async move { tier.fetch(loglet_id, read_pointer).await }.boxed()
+                    });
+                    let fetch_result = match fetch.poll_unpin(cx) {
+                        Poll::Ready(result) => result,
+                        Poll::Pending => {
+                            perf_guard.forget();
+                            return Poll::Pending;
+                        }
+                    };
+                    self.tier_fetch = None;
+
+                    match fetch_result {
+                        Ok(Some(chunk)) if !chunk.is_empty() => {
+                            self.tiered_records = chunk.into_records();
+                            continue;
+                        }
+                        // Nothing tiered at or after the read pointer, fall back to a trim gap
+                        Ok(_) => {}
+                        Err(e) => {
+                            self.terminated = true;
+                            return Poll::Ready(Some(Err(OperationError::other(e))));
+                        }
+                    }
+                }
+
                 let trim_gap = LogEntry::new_trim_gap(self.read_pointer, trim_point);
                 // next record should be beyond at the head
                 self.read_pointer = head_offset;
@@ -261,8 +367,13 @@ impl Stream for LocalLogletReadStream {
             self.read_pointer = loaded_key.offset.next();
             let raw_value = self.iterator.value().expect("log record exists");
 
-            let maybe_record = decode_and_filter_record(raw_value, &self.filter)
-                .map_err(OperationError::terminal)?;
+            let maybe_record = match self.decode_record(loaded_key.offset, raw_value) {
+                Ok(maybe_record) => maybe_record,
+                Err(e) => {
+                    self.terminated = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            };
 
             // The record matches the filter, good to return.
             if let Some(record) = maybe_record {
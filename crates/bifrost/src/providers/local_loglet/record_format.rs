@@ -12,8 +12,9 @@ use std::ops::Deref;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
+use restate_types::errors::MaybeRetryableError;
 use restate_types::flexbuffers_storage_encode_decode;
-use restate_types::logs::{KeyFilter, Keys, MatchKeyQuery, Record};
+use restate_types::logs::{KeyFilter, Keys, LogletOffset, MatchKeyQuery, Record};
 use restate_types::storage::{PolyBytes, StorageCodec, StorageCodecKind, StorageDecodeError};
 use restate_types::time::NanosSinceEpoch;
 
@@ -21,12 +22,20 @@ use restate_types::time::NanosSinceEpoch;
 // to allow for backwards compatibility.
 pub(super) const FORMAT_FOR_NEW_APPENDS: RecordFormat = RecordFormat::Legacy;
 
+/// CRC32C (Castagnoli) used to checksum records.
+const CRC32C: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+/// Size of the envelope of checksummed records, 1 byte format + 4 bytes checksum.
+const CHECKSUM_ENVELOPE_SIZE: usize = 5;
+
 #[derive(Debug, derive_more::TryFrom, Eq, PartialEq, Ord, PartialOrd)]
 #[try_from(repr)]
 #[repr(u8)]
 pub(super) enum RecordFormat {
     Legacy = 0x02, // matches  StorageCodecKind::FlexBufferSerde
     CustomEncoding = 0x03,
+    /// Envelope around a record of any other format, see [`write_checksummed_record`].
+    Checksummed = 0x04,
 }
 
 static_assertions::const_assert!(
@@ -34,13 +43,49 @@ static_assertions::const_assert!(
 );
 
 #[derive(Debug, thiserror::Error)]
-#[error("Record decode error: {0}")]
 pub(super) enum RecordDecodeError {
+    #[error("Record decode error: unsupported format version {0}")]
     UnsupportedFormatVersion(u8),
+    #[error("Record decode error: unsupported key style {0}")]
     UnsupportedKeyStyle(u8),
+    #[error("Record decode error: truncated record")]
+    Truncated,
+    #[error("Record checksum mismatch: stored {stored:#010x}, computed {computed:#010x}")]
+    ChecksumMismatch { stored: u32, computed: u32 },
+    #[error("Record decode error: {0}")]
     DecodeError(#[from] StorageDecodeError),
 }
 
+/// A record of the local loglet which failed checksum verification or couldn't be decoded.
+#[derive(Debug, thiserror::Error)]
+#[error("corrupted record at offset {offset} of loglet {loglet_id}: {reason}")]
+pub struct RecordCorruptionError {
+    pub loglet_id: u64,
+    pub offset: LogletOffset,
+    reason: RecordDecodeError,
+}
+
+impl RecordCorruptionError {
+    pub(super) fn new(loglet_id: u64, offset: LogletOffset, reason: RecordDecodeError) -> Self {
+        Self {
+            loglet_id,
+            offset,
+            reason,
+        }
+    }
+
+    /// Whether the record was written with a checksum which didn't match its content
+    pub fn is_checksum_mismatch(&self) -> bool {
+        matches!(self.reason, RecordDecodeError::ChecksumMismatch { .. })
+    }
+}
+
+impl MaybeRetryableError for RecordCorruptionError {
+    fn retryable(&self) -> bool {
+        false
+    }
+}
+
 /// Deprecated. This is the header for format-version 0x02.
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub(super) struct LegacyHeader {
@@ -82,11 +127,18 @@ enum KeyStyle {
 pub(super) fn encode_record_and_split(
     format_version: RecordFormat,
     record: &Record,
+    with_checksum: bool,
     serde_buffer: &mut BytesMut,
 ) -> BytesMut {
-    match format_version {
+    let encoded = match format_version {
         RecordFormat::Legacy => write_legacy_payload(record, serde_buffer),
         RecordFormat::CustomEncoding => write_record(record, serde_buffer),
+        RecordFormat::Checksummed => unreachable!("checksummed is not a payload format"),
+    };
+    if with_checksum {
+        write_checksummed_record(&encoded, serde_buffer)
+    } else {
+        encoded
     }
 }
 
@@ -115,7 +167,47 @@ pub(super) fn decode_and_filter_record(
             Ok(record)
         }
         RecordFormat::CustomEncoding => decode_custom_encoded_record(buffer, filter),
+        RecordFormat::Checksummed => {
+            let inner = verify_checksum(buffer)?;
+            if inner.len() < 2 {
+                return Err(RecordDecodeError::Truncated);
+            }
+            if inner[0] == RecordFormat::Checksummed as u8 {
+                // checksummed records are never nested
+                return Err(RecordDecodeError::UnsupportedFormatVersion(inner[0]));
+            }
+            decode_and_filter_record(inner, filter)
+        }
+    }
+}
+
+/// Checksummed record layout. The checksum covers the complete inner record, byte order is
+/// little-endian.
+///
+///    [1 byte]        Format version (0x04)
+///    [4 bytes]       CRC32C of the inner record
+///    [remaining]     Inner record, encoded in any of the other formats
+fn write_checksummed_record(inner: &[u8], buf: &mut BytesMut) -> BytesMut {
+    buf.reserve(CHECKSUM_ENVELOPE_SIZE + inner.len());
+    buf.put_u8(RecordFormat::Checksummed as u8);
+    buf.put_u32_le(CRC32C.checksum(inner));
+    buf.put_slice(inner);
+    buf.split()
+}
+
+// Verifies the checksum of a checksummed record and returns the inner record
+fn verify_checksum(buffer: &[u8]) -> Result<&[u8], RecordDecodeError> {
+    if buffer.len() < CHECKSUM_ENVELOPE_SIZE {
+        return Err(RecordDecodeError::Truncated);
+    }
+    let mut header = &buffer[1..CHECKSUM_ENVELOPE_SIZE];
+    let stored = header.get_u32_le();
+    let inner = &buffer[CHECKSUM_ENVELOPE_SIZE..];
+    let computed = CRC32C.checksum(inner);
+    if stored != computed {
+        return Err(RecordDecodeError::ChecksumMismatch { stored, computed });
     }
+    Ok(inner)
 }
 
 fn write_legacy_payload(record: &Record, serde_buffer: &mut BytesMut) -> BytesMut {
@@ -271,7 +363,11 @@ mod tests {
             RecordFormat::try_from(0x03).unwrap(),
             RecordFormat::CustomEncoding
         );
-        assert!(RecordFormat::try_from(0x04).is_err());
+        assert_eq!(
+            RecordFormat::try_from(0x04).unwrap(),
+            RecordFormat::Checksummed
+        );
+        assert!(RecordFormat::try_from(0x05).is_err());
     }
 
     #[test]
@@ -286,7 +382,7 @@ mod tests {
         let mut buffer = BytesMut::new();
 
         // encode with the old format, make sure we can decode and check filter.
-        let encoded = encode_record_and_split(RecordFormat::Legacy, &record, &mut buffer);
+        let encoded = encode_record_and_split(RecordFormat::Legacy, &record, false, &mut buffer);
 
         // no match
         let filter = KeyFilter::Include(15);
@@ -309,7 +405,8 @@ mod tests {
 
         // do the same but encode with new format
         // encode with the old format, make sure we can decode and check filter.
-        let encoded = encode_record_and_split(RecordFormat::CustomEncoding, &record, &mut buffer);
+        let encoded =
+            encode_record_and_split(RecordFormat::CustomEncoding, &record, false, &mut buffer);
 
         // no match
         let filter = KeyFilter::Include(15);
@@ -332,4 +429,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_checksummed_records() -> googletest::Result<()> {
+        let record = Record::from_parts(
+            NanosSinceEpoch::from(100),
+            Keys::Single(14),
+            PolyBytes::Typed(Arc::new("hello".to_owned())),
+        );
+
+        let mut buffer = BytesMut::new();
+        for format in [RecordFormat::Legacy, RecordFormat::CustomEncoding] {
+            let encoded = encode_record_and_split(format, &record, true, &mut buffer);
+            assert_that!(encoded[0], eq(RecordFormat::Checksummed as u8));
+
+            // filters apply to the inner record
+            let decoded = decode_and_filter_record(&encoded, &KeyFilter::Include(15))?;
+            assert!(decoded.is_none());
+
+            let decoded = decode_and_filter_record(&encoded, &KeyFilter::Any)?.unwrap();
+            assert_that!(decoded.keys(), eq(&Keys::Single(14)));
+            assert_that!(decoded.decode::<String>().unwrap(), eq("hello"));
+
+            // flip a bit of the payload
+            let mut corrupted = encoded.clone();
+            let last = corrupted.len() - 1;
+            corrupted[last] ^= 0x01;
+            assert!(matches!(
+                decode_and_filter_record(&corrupted, &KeyFilter::Any),
+                Err(RecordDecodeError::ChecksumMismatch { .. })
+            ));
+
+            // truncated records are detected as well
+            assert!(matches!(
+                decode_and_filter_record(&encoded[..3], &KeyFilter::Any),
+                Err(RecordDecodeError::Truncated)
+            ));
+        }
+
+        Ok(())
+    }
 }
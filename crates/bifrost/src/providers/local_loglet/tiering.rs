@@ -0,0 +1,333 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Offloads records of the local loglet to an object store before they are trimmed. Records are
+//! uploaded in chunks, each chunk is stored as an immutable object whose key encodes the loglet id
+//! and the inclusive offset range it covers:
+//!
+//! `<prefix>/loglet-<loglet_id>/<first_offset>-<last_offset>.bin`
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use bytes::{Bytes, BytesMut};
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::PutPayload;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use restate_object_store_util::ObjectStoreDestination;
+use restate_types::config::LocalLogletTieringOptions;
+use restate_types::errors::MaybeRetryableError;
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::logs::{LogletOffset, SequenceNumber};
+use restate_types::storage::{StorageCodec, StorageDecodeError, StorageEncodeError};
+
+use super::keys::RecordKey;
+use super::log_store::{LogStoreError, RocksDbLogStore};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TieringError {
+    #[error(transparent)]
+    Destination(#[from] restate_object_store_util::Error),
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[error(transparent)]
+    LogStore(#[from] LogStoreError),
+    #[error(transparent)]
+    Encode(#[from] StorageEncodeError),
+    #[error(transparent)]
+    Decode(#[from] StorageDecodeError),
+    #[error("failed reading the records to offload: {0}")]
+    ReadTask(#[from] tokio::task::JoinError),
+}
+
+impl MaybeRetryableError for TieringError {
+    fn retryable(&self) -> bool {
+        match self {
+            TieringError::Destination(_) => false,
+            TieringError::ObjectStore(_) => true,
+            TieringError::LogStore(err) => err.retryable(),
+            TieringError::Encode(_) => false,
+            TieringError::Decode(_) => false,
+            TieringError::ReadTask(_) => false,
+        }
+    }
+}
+
+/// A contiguous range of records of a single loglet. Records are kept in their on-disk record
+/// format so that they can be decoded and filtered like records read from RocksDB.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TieredChunk {
+    records: Vec<(u32, Bytes)>,
+}
+
+flexbuffers_storage_encode_decode!(TieredChunk);
+
+impl TieredChunk {
+    fn first_offset(&self) -> Option<LogletOffset> {
+        self.records
+            .first()
+            .map(|(offset, _)| LogletOffset::new(*offset))
+    }
+
+    fn last_offset(&self) -> Option<LogletOffset> {
+        self.records
+            .last()
+            .map(|(offset, _)| LogletOffset::new(*offset))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn into_records(self) -> VecDeque<(LogletOffset, Bytes)> {
+        self.records
+            .into_iter()
+            .map(|(offset, record)| (LogletOffset::new(offset), record))
+            .collect()
+    }
+}
+
+/// Object store tier shared by all loglets of the local loglet provider.
+#[derive(Debug)]
+pub struct LogletTier {
+    destination: ObjectStoreDestination,
+    chunk_size: usize,
+    index: Mutex<HashMap<u64, ChunkIndex>>,
+}
+
+/// The chunks of a loglet known to exist in the object store.
+#[derive(Debug, Default)]
+struct ChunkIndex {
+    /// last_offset -> first_offset
+    chunks: BTreeMap<u32, u32>,
+    /// Whether the chunks uploaded by previous incarnations of this node have been listed. Only
+    /// this node uploads the chunks of its loglets, hence the index is complete afterwards and
+    /// misses don't need to list the object store again.
+    listed: bool,
+}
+
+impl LogletTier {
+    /// Returns `None` if no tiering destination is configured.
+    pub fn create_if_configured(
+        options: &LocalLogletTieringOptions,
+    ) -> Result<Option<Self>, TieringError> {
+        let Some(destination) = options.destination.as_ref() else {
+            return Ok(None);
+        };
+
+        let destination = ObjectStoreDestination::create(destination, &options.object_store)?;
+        Ok(Some(Self {
+            destination,
+            chunk_size: options.chunk_size.get(),
+            index: Mutex::default(),
+        }))
+    }
+
+    /// Uploads all records in the inclusive range `[from, to]` of the given loglet. Once this
+    /// method returns successfully, the range can safely be trimmed from the local log store.
+    pub async fn offload(
+        &self,
+        log_store: &RocksDbLogStore,
+        loglet_id: u64,
+        from: LogletOffset,
+        to: LogletOffset,
+    ) -> Result<(), TieringError> {
+        let mut from = from;
+        while from <= to {
+            let chunk = {
+                let log_store = log_store.clone();
+                let chunk_size = self.chunk_size;
+                tokio::task::spawn_blocking(move || {
+                    Self::read_chunk(&log_store, chunk_size, loglet_id, from, to)
+                })
+                .await??
+            };
+            let (Some(first_offset), Some(last_offset)) =
+                (chunk.first_offset(), chunk.last_offset())
+            else {
+                break;
+            };
+
+            let mut buf = BytesMut::new();
+            StorageCodec::encode(&chunk, &mut buf)?;
+            let size = buf.len();
+            self.destination
+                .object_store
+                .put(
+                    &self.chunk_path(loglet_id, first_offset, last_offset),
+                    PutPayload::from_bytes(buf.freeze()),
+                )
+                .await?;
+
+            self.index
+                .lock()
+                .entry(loglet_id)
+                .or_default()
+                .chunks
+                .insert(*last_offset, *first_offset);
+
+            debug!(
+                loglet_id,
+                %first_offset,
+                %last_offset,
+                "Offloaded {} bytes of local loglet records to object store",
+                size
+            );
+            from = last_offset.next();
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the chunk containing `offset`, or if no such chunk exists, the first chunk after
+    /// it. Returns `None` if the object store holds no records at or after `offset`.
+    pub async fn fetch(
+        &self,
+        loglet_id: u64,
+        offset: LogletOffset,
+    ) -> Result<Option<TieredChunk>, TieringError> {
+        let mut located = self.locate(loglet_id, offset);
+        if located.is_none() && !self.is_listed(loglet_id) {
+            // the chunk might have been uploaded by a previous incarnation of this node
+            self.list_chunks(loglet_id).await?;
+            located = self.locate(loglet_id, offset);
+        }
+        let Some((first_offset, last_offset)) = located else {
+            return Ok(None);
+        };
+
+        let chunk = self
+            .destination
+            .object_store
+            .get(&self.chunk_path(loglet_id, first_offset, last_offset))
+            .await?
+            .bytes()
+            .await?;
+        let chunk = StorageCodec::decode::<TieredChunk, _>(&mut chunk.as_ref())?;
+
+        Ok(Some(chunk))
+    }
+
+    fn locate(&self, loglet_id: u64, offset: LogletOffset) -> Option<(LogletOffset, LogletOffset)> {
+        self.index.lock().get(&loglet_id).and_then(|index| {
+            index
+                .chunks
+                .range(*offset..)
+                .next()
+                .map(|(last, first)| (LogletOffset::new(*first), LogletOffset::new(*last)))
+        })
+    }
+
+    fn is_listed(&self, loglet_id: u64) -> bool {
+        self.index
+            .lock()
+            .get(&loglet_id)
+            .is_some_and(|index| index.listed)
+    }
+
+    async fn list_chunks(&self, loglet_id: u64) -> Result<(), TieringError> {
+        let prefix = self.loglet_prefix(loglet_id);
+        let objects: Vec<_> = self
+            .destination
+            .object_store
+            .list(Some(&prefix))
+            .try_collect()
+            .await?;
+
+        let chunks = objects
+            .iter()
+            .filter_map(|object| parse_chunk_name(object.location.filename()?))
+            .map(|(first, last)| (last, first));
+
+        let mut index = self.index.lock();
+        let index = index.entry(loglet_id).or_default();
+        // keep the chunks which have been uploaded concurrently to the listing
+        index.chunks.extend(chunks);
+        index.listed = true;
+        Ok(())
+    }
+
+    /// Reads the records starting at `from` until the chunk size is reached. Blocks on RocksDB,
+    /// hence it runs on the blocking thread pool.
+    fn read_chunk(
+        log_store: &RocksDbLogStore,
+        chunk_size: usize,
+        loglet_id: u64,
+        from: LogletOffset,
+        to: LogletOffset,
+    ) -> Result<TieredChunk, LogStoreError> {
+        let mut serde_buffer = BytesMut::with_capacity(2 * RecordKey::serialized_size());
+        let mut read_opts = rocksdb::ReadOptions::default();
+        read_opts.set_prefix_same_as_start(true);
+        read_opts.set_total_order_seek(false);
+        read_opts.set_iterate_lower_bound(
+            RecordKey::new(loglet_id, from).encode_and_split(&mut serde_buffer),
+        );
+        read_opts.set_iterate_upper_bound(
+            RecordKey::new(loglet_id, to.next()).encode_and_split(&mut serde_buffer),
+        );
+
+        let data_cf = log_store.data_cf();
+        let mut iterator = log_store.db().raw_iterator_cf_opt(&data_cf, read_opts);
+        iterator.seek_to_first();
+
+        let mut chunk = TieredChunk::default();
+        let mut size = 0;
+        while iterator.valid() {
+            let key = RecordKey::from_slice(iterator.key().expect("log record exists"));
+            let record = iterator.value().expect("log record exists");
+            size += record.len();
+            chunk
+                .records
+                .push((*key.offset, Bytes::copy_from_slice(record)));
+            if size >= chunk_size {
+                break;
+            }
+            iterator.next();
+        }
+        iterator.status()?;
+
+        Ok(chunk)
+    }
+
+    fn loglet_prefix(&self, loglet_id: u64) -> Path {
+        self.destination.path(&format!("loglet-{}", loglet_id))
+    }
+
+    fn chunk_path(&self, loglet_id: u64, first: LogletOffset, last: LogletOffset) -> Path {
+        self.destination.path(&format!(
+            "loglet-{}/{:010}-{:010}.bin",
+            loglet_id, *first, *last
+        ))
+    }
+}
+
+fn parse_chunk_name(name: &str) -> Option<(u32, u32)> {
+    let (first, last) = name.strip_suffix(".bin")?.split_once('-')?;
+    Some((first.parse().ok()?, last.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_chunk_name;
+
+    #[test]
+    fn chunk_name_roundtrip() {
+        assert_eq!(
+            Some((1, 42)),
+            parse_chunk_name(&format!("{:010}-{:010}.bin", 1, 42))
+        );
+        assert_eq!(None, parse_chunk_name("latest.json"));
+        assert_eq!(None, parse_chunk_name("0000000001.bin"));
+    }
+}
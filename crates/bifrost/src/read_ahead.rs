@@ -0,0 +1,153 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{Stream, StreamExt};
+use tokio::sync::mpsc;
+use tracing::trace;
+
+use restate_core::{ShutdownError, TaskCenter, TaskKind};
+use restate_types::logs::LogId;
+
+use crate::{LogEntry, LogReadStream, Result};
+
+/// A read stream which reads and transforms records on a background task ahead of its consumer.
+///
+/// Up to `window` transformed records are buffered. This decouples the consumer from the latency
+/// of reading individual records from the loglet, e.g. when a partition processor catches up on a
+/// long backlog. The background task stops once the underlying stream terminates or once this
+/// stream is dropped.
+#[must_use = "streams do nothing unless polled"]
+pub struct ReadAheadStream<T> {
+    log_id: LogId,
+    rx: mpsc::Receiver<Result<T>>,
+}
+
+impl<T> ReadAheadStream<T>
+where
+    T: Send + 'static,
+{
+    pub(crate) fn create<F>(
+        mut read_stream: LogReadStream,
+        window: NonZeroUsize,
+        mut transform: F,
+    ) -> Result<Self, ShutdownError>
+    where
+        F: FnMut(LogEntry) -> T + Send + 'static,
+    {
+        let log_id = read_stream.log_id();
+        let (tx, rx) = mpsc::channel(window.get());
+
+        TaskCenter::spawn_child(
+            TaskKind::BifrostReadAhead,
+            "bifrost-read-ahead",
+            async move {
+                loop {
+                    let next = tokio::select! {
+                        // stop reading if the consumer went away, even if the log is idle
+                        _ = tx.closed() => break,
+                        next = read_stream.next() => next,
+                    };
+                    let Some(entry) = next else {
+                        break;
+                    };
+                    let is_err = entry.is_err();
+                    if tx.send(entry.map(&mut transform)).await.is_err() || is_err {
+                        break;
+                    }
+                }
+                trace!(%log_id, "Read-ahead task for log completed");
+                Ok(())
+            },
+        )?;
+
+        Ok(Self { log_id, rx })
+    }
+
+    pub fn log_id(&self) -> LogId {
+        self.log_id
+    }
+
+    /// Number of records which have been read ahead and are ready to be consumed.
+    pub fn buffered(&self) -> usize {
+        self.rx.len()
+    }
+}
+
+impl<T> Stream for ReadAheadStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl LogReadStream {
+    /// Moves this stream to a background task which reads up to `window` records ahead of the
+    /// consumer. Every record is passed through `transform` on the background task, which allows
+    /// to decode records off the consumer's critical path.
+    pub fn read_ahead<T, F>(
+        self,
+        window: NonZeroUsize,
+        transform: F,
+    ) -> Result<ReadAheadStream<T>, ShutdownError>
+    where
+        T: Send + 'static,
+        F: FnMut(LogEntry) -> T + Send + 'static,
+    {
+        ReadAheadStream::create(self, window, transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use futures::StreamExt;
+    use googletest::prelude::*;
+
+    use restate_core::TestCoreEnvBuilder;
+    use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
+
+    use crate::Bifrost;
+
+    #[restate_core::test]
+    async fn read_ahead_decodes_records_in_order() -> googletest::Result<()> {
+        const LOG_ID: LogId = LogId::new(0);
+        let _ = TestCoreEnvBuilder::with_incoming_only_connector()
+            .build()
+            .await;
+        let bifrost = Bifrost::init_in_memory().await;
+
+        let mut appender = bifrost.create_appender(LOG_ID)?;
+        for i in 1..=10 {
+            appender.append(format!("record{}", i)).await?;
+        }
+
+        let mut read_ahead = bifrost
+            .create_reader(LOG_ID, KeyFilter::Any, Lsn::OLDEST, Lsn::from(10))?
+            .read_ahead(NonZeroUsize::new(3).unwrap(), |entry| {
+                (entry.sequence_number(), entry.decode_unchecked::<String>())
+            })?;
+
+        for i in 1..=10 {
+            let (lsn, record) = read_ahead.next().await.unwrap()?;
+            assert_that!(lsn, eq(Lsn::from(i)));
+            assert_that!(record, eq(format!("record{}", i)));
+        }
+        // the underlying stream terminates at the end lsn
+        assert!(read_ahead.next().await.is_none());
+
+        Ok(())
+    }
+}
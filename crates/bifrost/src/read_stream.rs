@@ -29,7 +29,7 @@ use restate_types::logs::KeyFilter;
 use restate_types::logs::MatchKeyQuery;
 use restate_types::logs::SequenceNumber;
 use restate_types::logs::TailState;
-use restate_types::logs::{LogId, Lsn};
+use restate_types::logs::{LogId, Lsn, Record};
 use restate_types::Version;
 use restate_types::Versioned;
 
@@ -41,6 +41,9 @@ use crate::Error;
 use crate::LogEntry;
 use crate::Result;
 
+/// Predicate on data records, see [`LogReadStream::with_record_filter`].
+pub type RecordFilter = Arc<dyn Fn(&Record) -> bool + Send + Sync>;
+
 /// A read stream reads from the virtual log. The stream provides a unified view over
 /// the virtual log addressing space in the face of seals, reconfiguration, and trims.
 ///
@@ -52,6 +55,8 @@ pub struct LogReadStream {
     log_id: LogId,
     /// Chooses which records to read/return.
     filter: KeyFilter,
+    /// Additional filter applied to data records before they are returned.
+    record_filter: Option<RecordFilter>,
     /// inclusive max LSN to read to
     end_lsn: Lsn,
     /// Represents the next record to read.
@@ -120,6 +125,7 @@ impl LogReadStream {
             bifrost_inner,
             log_id,
             filter,
+            record_filter: None,
             read_pointer: start_lsn,
             end_lsn,
             substream: None,
@@ -127,6 +133,22 @@ impl LogReadStream {
         })
     }
 
+    pub fn log_id(&self) -> LogId {
+        self.log_id
+    }
+
+    /// Skips data records for which `filter` returns false. The filter is evaluated on the
+    /// records as returned by the loglet, before their body is decoded, which allows readers to
+    /// cheaply skip records they are not interested in (e.g. by peeking at the record type).
+    /// Trim gaps are always returned.
+    pub fn with_record_filter(
+        mut self,
+        filter: impl Fn(&Record) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.record_filter = Some(Arc::new(filter));
+        self
+    }
+
     /// Current read pointer. This is the next (possible) record to be read.
     pub fn read_pointer(&self) -> Lsn {
         self.read_pointer
@@ -320,6 +342,13 @@ impl Stream for LogReadStream {
                                     // record and fast-forward.
                                     continue;
                                 }
+                                if this
+                                    .record_filter
+                                    .as_ref()
+                                    .is_some_and(|filter| !filter(data_record))
+                                {
+                                    continue;
+                                }
                             }
 
                             return Poll::Ready(Some(Ok(record)));
@@ -450,7 +479,7 @@ mod tests {
     use restate_types::config::{CommonOptions, Configuration};
     use restate_types::live::{Constant, Live};
     use restate_types::logs::metadata::{new_single_node_loglet_params, ProviderKind};
-    use restate_types::logs::{KeyFilter, SequenceNumber};
+    use restate_types::logs::{KeyFilter, Keys, SequenceNumber, WithKeys};
     use restate_types::metadata_store::keys::BIFROST_CONFIG_KEY;
     use restate_types::Versioned;
 
@@ -536,6 +565,50 @@ mod tests {
         Ok(())
     }
 
+    #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+    #[traced_test]
+    async fn test_read_stream_with_record_filter() -> anyhow::Result<()> {
+        const LOG_ID: LogId = LogId::new(0);
+
+        let _ = TestCoreEnvBuilder::with_incoming_only_connector()
+            .set_provider_kind(ProviderKind::Local)
+            .build()
+            .await;
+        let config = Live::from_value(Configuration::default());
+        RocksDbManager::init(Constant::new(CommonOptions::default()));
+
+        let svc = BifrostService::new().enable_local_loglet(&config);
+        let bifrost = svc.handle();
+        svc.start().await.expect("loglet must start");
+
+        let mut appender = bifrost.create_appender(LOG_ID)?;
+        for i in 1..=10 {
+            appender
+                .append(format!("record{}", i).with_keys(Keys::Single(i)))
+                .await?;
+        }
+
+        // only records with even keys within [3, 8]
+        let mut read_stream = bifrost
+            .create_reader(LOG_ID, KeyFilter::Within(3..=8), Lsn::OLDEST, Lsn::from(10))?
+            .with_record_filter(
+                |record| matches!(record.keys(), Keys::Single(key) if key % 2 == 0),
+            );
+
+        for i in [4, 6, 8] {
+            let record = read_stream.next().await.unwrap()?;
+            assert_that!(record.sequence_number(), eq(Lsn::from(i)));
+            assert_that!(
+                record.decode_unchecked::<String>(),
+                eq(format!("record{}", i))
+            );
+        }
+        assert!(read_stream.next().await.is_none());
+        assert_that!(read_stream.read_pointer(), eq(Lsn::from(11)));
+
+        Ok(())
+    }
+
     #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
     #[traced_test]
     async fn test_read_stream_with_trim() -> anyhow::Result<()> {
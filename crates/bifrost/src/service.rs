@@ -21,6 +21,7 @@ use restate_types::live::Live;
 use restate_types::logs::metadata::ProviderKind;
 
 use crate::bifrost::BifrostInner;
+use crate::metric_definitions;
 use crate::providers::local_loglet;
 #[cfg(any(test, feature = "memory-loglet"))]
 use crate::providers::memory_loglet;
@@ -85,6 +86,8 @@ impl BifrostService {
     ///
     /// This requires to run within a task_center context.
     pub async fn start(self) -> anyhow::Result<()> {
+        metric_definitions::describe_metrics();
+
         // Perform an initial metadata sync.
         self.inner
             .sync_metadata()
@@ -17,16 +17,19 @@ use enum_map::{enum_map, EnumMap};
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 
+use restate_types::config::NetworkingOptions;
 use restate_types::net::codec::Targeted;
-use restate_types::net::codec::{serialize_message, WireEncode};
+use restate_types::net::codec::{serialize_message, PayloadCodec, WireEncode};
 use restate_types::net::metadata::MetadataKind;
-use restate_types::net::ProtocolVersion;
+use restate_types::net::{CodecError, ProtocolVersion};
 use restate_types::protobuf::node::message;
 use restate_types::protobuf::node::Header;
 use restate_types::protobuf::node::Message;
+use restate_types::protobuf::node::MessageCompression;
 use restate_types::{GenerationalNodeId, Version};
+use tracing::warn;
 
-use super::metric_definitions::MESSAGE_SENT;
+use super::metric_definitions::{MESSAGE_DROPPED_TOO_LARGE, MESSAGE_SENT};
 use super::NetworkError;
 use super::Outgoing;
 use crate::Metadata;
@@ -39,6 +42,7 @@ pub struct OwnedSendPermit<M> {
 
 pub struct SendPermit<'a, M> {
     protocol_version: ProtocolVersion,
+    codec: PayloadCodec,
     permit: mpsc::Permit<'a, Message>,
     _phantom: std::marker::PhantomData<M>,
 }
@@ -70,8 +74,21 @@ where
             message.msg_id(),
             message.in_response_to(),
         );
-        let body = serialize_message(message.into_body(), self.protocol_version)
+        let mut body = serialize_message(message.into_body(), self.protocol_version)
             .expect("message encoding infallible");
+        if let message::Body::Encoded(binary_message) = &mut body {
+            if let Err(err) = self.codec.encode(binary_message) {
+                // The permit is released, the peer won't receive the message
+                warn!(
+                    target = ?binary_message.target(),
+                    "Dropping the message, it cannot be sent: {err}"
+                );
+                if matches!(err, CodecError::MessageTooLarge { .. }) {
+                    MESSAGE_DROPPED_TOO_LARGE.increment(1);
+                }
+                return;
+            }
+        }
         self.send_raw(Message::new(header, body));
     }
 }
@@ -97,6 +114,7 @@ impl<'a, M> SendPermit<'a, M> {
 pub struct OwnedConnection {
     pub(crate) peer: GenerationalNodeId,
     pub(crate) protocol_version: ProtocolVersion,
+    pub(crate) codec: PayloadCodec,
     pub(crate) sender: mpsc::Sender<Message>,
     pub(crate) created: Instant,
 }
@@ -105,11 +123,13 @@ impl OwnedConnection {
     pub(crate) fn new(
         peer: GenerationalNodeId,
         protocol_version: ProtocolVersion,
+        codec: PayloadCodec,
         sender: mpsc::Sender<Message>,
     ) -> Self {
         Self {
             peer,
             protocol_version,
+            codec,
             sender,
             created: Instant::now(),
         }
@@ -121,7 +141,8 @@ impl OwnedConnection {
         protocol_version: ProtocolVersion,
         sender: mpsc::Sender<Message>,
     ) -> Arc<Self> {
-        Arc::new(Self::new(peer, protocol_version, sender))
+        let codec = PayloadCodec::new(MessageCompression::None, &NetworkingOptions::default());
+        Arc::new(Self::new(peer, protocol_version, codec, sender))
     }
 
     /// The node id at the other end of this connection
@@ -169,6 +190,7 @@ impl OwnedConnection {
         Some(SendPermit {
             permit,
             protocol_version: self.protocol_version,
+            codec: self.codec,
             _phantom: std::marker::PhantomData,
         })
     }
@@ -187,6 +209,7 @@ impl OwnedConnection {
         Ok(SendPermit {
             permit,
             protocol_version: self.protocol_version,
+            codec: self.codec,
             _phantom: std::marker::PhantomData,
         })
     }
@@ -212,6 +235,7 @@ impl OwnedConnection {
         Ok(SendPermit {
             permit,
             protocol_version: self.protocol_version,
+            codec: self.codec,
             _phantom: std::marker::PhantomData,
         })
     }
@@ -444,6 +468,7 @@ pub mod test_util {
             Some(SendPermit {
                 permit,
                 protocol_version: self.protocol_version,
+                codec: PayloadCodec::new(MessageCompression::None, &NetworkingOptions::default()),
                 _phantom: std::marker::PhantomData,
             })
         }
@@ -601,7 +626,11 @@ pub mod test_util {
             let selected_protocol_version = negotiate_protocol_version(&hello)?;
 
             // Enqueue the welcome message
-            let welcome = Welcome::new(my_node_id, selected_protocol_version);
+            let welcome = Welcome::new(
+                my_node_id,
+                selected_protocol_version,
+                MessageCompression::None,
+            );
 
             let welcome = Message::new(
                 Header::new(
@@ -22,24 +22,27 @@ use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, info, trace, warn, Instrument, Span};
 
 use restate_types::config::NetworkingOptions;
-use restate_types::net::codec::MessageBodyExt;
+use restate_types::net::codec::{MessageBodyExt, PayloadCodec};
 use restate_types::net::metadata::MetadataKind;
+use restate_types::net::CodecError;
 use restate_types::nodes_config::NodesConfiguration;
 use restate_types::protobuf::node::message::{self, ConnectionControl};
-use restate_types::protobuf::node::{Header, Hello, Message, Welcome};
+use restate_types::protobuf::node::{Header, Hello, Message, MessageCompression, Welcome};
 use restate_types::{GenerationalNodeId, NodeId, PlainNodeId, Version};
 
 use super::connection::{OwnedConnection, WeakConnection};
 use super::error::{NetworkError, ProtocolError};
 use super::handshake::wait_for_welcome;
 use super::metric_definitions::{
-    self, CONNECTION_DROPPED, INCOMING_CONNECTION, MESSAGE_PROCESSING_DURATION, MESSAGE_RECEIVED,
-    ONGOING_DRAIN, OUTGOING_CONNECTION,
+    self, CONNECTION_DROPPED, INCOMING_CONNECTION, MESSAGE_DROPPED_TOO_LARGE,
+    MESSAGE_PROCESSING_DURATION, MESSAGE_RECEIVED, ONGOING_DRAIN, OUTGOING_CONNECTION,
 };
 use super::transport_connector::TransportConnect;
 use super::{Handler, MessageRouter};
 use crate::metadata::Urgency;
-use crate::network::handshake::{negotiate_protocol_version, wait_for_hello};
+use crate::network::handshake::{
+    accepted_compressions, negotiate_compression, negotiate_protocol_version, wait_for_hello,
+};
 use crate::network::{Incoming, PeerMetadataVersion};
 use crate::{Metadata, TaskCenter, TaskContext, TaskId, TaskKind};
 
@@ -214,13 +217,20 @@ impl<T: TransportConnect> ConnectionManager<T> {
             selected_protocol_version
         );
 
+        let compression =
+            negotiate_compression(&hello, self.networking_options.message_compression);
+        debug!(
+            "Negotiated message compression {:?} with client",
+            compression
+        );
+
         self.verify_node_id(peer_node_id, &header, &nodes_config)?;
 
         let (tx, output_stream) =
             mpsc::channel(self.networking_options.outbound_queue_length.into());
         let output_stream = ReceiverStream::new(output_stream);
         // Enqueue the welcome message
-        let welcome = Welcome::new(my_node_id, selected_protocol_version);
+        let welcome = Welcome::new(my_node_id, selected_protocol_version, compression);
 
         let welcome = Message::new(
             Header::new(
@@ -236,7 +246,12 @@ impl<T: TransportConnect> ConnectionManager<T> {
 
         tx.try_send(welcome)
             .expect("channel accept Welcome message");
-        let connection = OwnedConnection::new(peer_node_id, selected_protocol_version, tx);
+        let connection = OwnedConnection::new(
+            peer_node_id,
+            selected_protocol_version,
+            PayloadCodec::new(compression, &self.networking_options),
+            tx,
+        );
 
         INCOMING_CONNECTION.increment(1);
         // Register the connection.
@@ -290,7 +305,9 @@ impl<T: TransportConnect> ConnectionManager<T> {
         let (tx, output_stream) =
             mpsc::channel(self.networking_options.outbound_queue_length.into());
         let output_stream = ReceiverStream::new(output_stream);
-        let hello = Hello::new(my_node_id, cluster_name);
+        let hello = Hello::new(my_node_id, cluster_name).with_accepted_compressions(
+            accepted_compressions(self.networking_options.message_compression),
+        );
 
         // perform handshake.
         let hello = Message::new(
@@ -325,6 +342,17 @@ impl<T: TransportConnect> ConnectionManager<T> {
             return Err(ProtocolError::UnsupportedVersion(protocol_version.into()).into());
         }
 
+        let compression = welcome.compression();
+        if compression != MessageCompression::None
+            && !accepted_compressions(self.networking_options.message_compression)
+                .contains(&compression)
+        {
+            return Err(ProtocolError::HandshakeFailed(
+                "Peer selected a message compression we don't accept",
+            )
+            .into());
+        }
+
         // sanity checks
         let peer_node_id: NodeId = welcome
             .my_node_id
@@ -348,6 +376,7 @@ impl<T: TransportConnect> ConnectionManager<T> {
                 .as_generational()
                 .expect("must be generational id"),
             protocol_version,
+            PayloadCodec::new(compression, &self.networking_options),
             tx,
         );
 
@@ -360,6 +389,7 @@ impl<T: TransportConnect> ConnectionManager<T> {
         let connection = OwnedConnection::new(
             self.metadata.my_node_id(),
             restate_types::net::CURRENT_PROTOCOL_VERSION,
+            PayloadCodec::new(MessageCompression::None, &self.networking_options),
             tx,
         );
 
@@ -580,7 +610,15 @@ where
             break;
         }
 
-        match body.try_as_binary_body(connection.protocol_version) {
+        match body
+            .try_as_binary_body(connection.protocol_version)
+            .and_then(|mut msg| connection.codec.decode(&mut msg).map(|()| msg))
+        {
+            Err(err @ CodecError::MessageTooLarge { .. }) => {
+                warn!(peer = %connection.peer, "Dropping incoming message: {err}");
+                MESSAGE_DROPPED_TOO_LARGE.increment(1);
+                MESSAGE_PROCESSING_DURATION.record(processing_started.elapsed());
+            }
             Ok(msg) => {
                 trace!(
                     peer = %connection.peer,
@@ -625,6 +663,7 @@ where
     ONGOING_DRAIN.increment(1.0);
     on_connection_draining(&connection, &connection_manager);
     let protocol_version = connection.protocol_version;
+    let codec = connection.codec;
     let peer_node_id = connection.peer;
     let connection_created_at = connection.created;
     // dropping the connection since it's the owner of sender stream.
@@ -641,7 +680,10 @@ where
         };
         if let Some(body) = msg.body {
             // we ignore non-deserializable messages (serde errors, or control signals in drain)
-            if let Ok(msg) = body.try_as_binary_body(protocol_version) {
+            if let Ok(msg) = body
+                .try_as_binary_body(protocol_version)
+                .and_then(|mut msg| codec.decode(&mut msg).map(|()| msg))
+            {
                 drain_counter += 1;
                 let parent_context = header.span_context.as_ref().map(|span_ctx| {
                     global::get_text_map_propagator(|propagator| propagator.extract(span_ctx))
@@ -835,6 +877,7 @@ mod tests {
             max_protocol_version: ProtocolVersion::Unknown.into(),
             my_node_id: Some(my_node_id.into()),
             cluster_name: metadata.nodes_config_ref().cluster_name().to_owned(),
+            accepted_compressions: vec![],
         };
         let hello = Message::new(
             Header::new(
@@ -870,6 +913,7 @@ mod tests {
             max_protocol_version: CURRENT_PROTOCOL_VERSION.into(),
             my_node_id: Some(my_node_id.into()),
             cluster_name: "Random-cluster".to_owned(),
+            accepted_compressions: vec![],
         };
         let hello = Message::new(
             Header::new(
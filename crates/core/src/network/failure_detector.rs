@@ -0,0 +1,337 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Gossip based failure detection.
+//!
+//! Every node increments its own heartbeat at every gossip round, and sends the heartbeats it
+//! knows about to a few random peers, which merge them into their own view. A node whose
+//! heartbeat didn't advance within the failure timeout is considered dead. Failures are detected
+//! within a few gossip rounds, without requiring every node to ping every other node.
+
+use std::collections::{BTreeSet, HashMap};
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+use tokio::sync::watch;
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::{debug, trace};
+
+use restate_types::config::{Configuration, NetworkingOptions};
+use restate_types::net::node::{Gossip, NodeHeartbeat};
+use restate_types::time::MillisSinceEpoch;
+use restate_types::{GenerationalNodeId, PlainNodeId};
+
+use super::{
+    Incoming, MessageRouterBuilder, MessageStream, Networking, Outgoing, TransportConnect,
+};
+use crate::{cancellation_watcher, ShutdownError, TaskCenter, TaskId, TaskKind};
+
+/// Liveness of a node, as observed through its gossiped heartbeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeLiveness {
+    /// The heartbeat of the node advanced within the failure timeout.
+    Alive {
+        generational_node_id: GenerationalNodeId,
+        last_heartbeat_at: MillisSinceEpoch,
+    },
+    /// The heartbeat of the node didn't advance within the failure timeout.
+    Dead { last_seen_alive: MillisSinceEpoch },
+    /// No heartbeat of the node has been received yet.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ObservedHeartbeat {
+    heartbeat: NodeHeartbeat,
+    observed_at: Instant,
+    observed_at_millis: MillisSinceEpoch,
+}
+
+/// View of the liveness of the nodes of the cluster, updated by the [`FailureDetector`]. Can be
+/// cheaply cloned.
+#[derive(Clone)]
+pub struct ClusterLiveness {
+    heartbeats: Arc<Mutex<HashMap<PlainNodeId, ObservedHeartbeat>>>,
+    alive_nodes: Arc<watch::Sender<BTreeSet<PlainNodeId>>>,
+}
+
+impl Default for ClusterLiveness {
+    fn default() -> Self {
+        Self {
+            heartbeats: Arc::default(),
+            alive_nodes: Arc::new(watch::Sender::new(BTreeSet::new())),
+        }
+    }
+}
+
+impl ClusterLiveness {
+    pub fn node_liveness(&self, node_id: PlainNodeId) -> NodeLiveness {
+        let failure_timeout = Configuration::pinned()
+            .networking
+            .gossip_failure_timeout
+            .into();
+        let heartbeats = self.heartbeats.lock();
+        let Some(observed) = heartbeats.get(&node_id) else {
+            return NodeLiveness::Unknown;
+        };
+
+        if observed.observed_at.elapsed() < failure_timeout {
+            NodeLiveness::Alive {
+                generational_node_id: observed.heartbeat.node_id,
+                last_heartbeat_at: observed.observed_at_millis,
+            }
+        } else {
+            NodeLiveness::Dead {
+                last_seen_alive: observed.observed_at_millis,
+            }
+        }
+    }
+
+    /// Watches the set of the alive nodes, which changes as soon as a failure or a recovery is
+    /// detected.
+    pub fn alive_nodes_watcher(&self) -> watch::Receiver<BTreeSet<PlainNodeId>> {
+        self.alive_nodes.subscribe()
+    }
+
+    /// Merges the heartbeat into the view, if it's newer than the known one.
+    fn observe(&self, heartbeat: NodeHeartbeat) {
+        let mut heartbeats = self.heartbeats.lock();
+        let is_newer = heartbeats
+            .get(&heartbeat.node_id.as_plain())
+            .is_none_or(|known| {
+                heartbeat.node_id.is_newer_than(known.heartbeat.node_id)
+                    || (heartbeat.node_id == known.heartbeat.node_id
+                        && heartbeat.counter > known.heartbeat.counter)
+            });
+
+        if is_newer {
+            heartbeats.insert(
+                heartbeat.node_id.as_plain(),
+                ObservedHeartbeat {
+                    heartbeat,
+                    observed_at: Instant::now(),
+                    observed_at_millis: MillisSinceEpoch::now(),
+                },
+            );
+        }
+    }
+
+    fn known_heartbeats(&self) -> Vec<NodeHeartbeat> {
+        self.heartbeats
+            .lock()
+            .values()
+            .map(|observed| observed.heartbeat)
+            .collect()
+    }
+
+    /// Notifies the watchers if the set of the alive nodes changed.
+    fn update_alive_nodes(&self, failure_timeout: std::time::Duration) {
+        let alive_nodes: BTreeSet<_> = self
+            .heartbeats
+            .lock()
+            .iter()
+            .filter(|(_, observed)| observed.observed_at.elapsed() < failure_timeout)
+            .map(|(node_id, _)| *node_id)
+            .collect();
+
+        self.alive_nodes.send_if_modified(|current| {
+            if *current != alive_nodes {
+                debug!("Alive nodes changed from {current:?} to {alive_nodes:?}");
+                *current = alive_nodes;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// Gossips the heartbeats of the nodes with the peers, and updates the [`ClusterLiveness`].
+pub struct FailureDetector<T> {
+    networking: Networking<T>,
+    incoming_gossip: MessageStream<Gossip>,
+    cluster_liveness: ClusterLiveness,
+    heartbeat: u64,
+}
+
+impl<T: TransportConnect> FailureDetector<T> {
+    pub fn new(networking: Networking<T>, router_builder: &mut MessageRouterBuilder) -> Self {
+        Self {
+            networking,
+            incoming_gossip: router_builder.subscribe_to_stream(64),
+            cluster_liveness: ClusterLiveness::default(),
+            heartbeat: 0,
+        }
+    }
+
+    pub fn cluster_liveness(&self) -> ClusterLiveness {
+        self.cluster_liveness.clone()
+    }
+
+    fn create_gossip_interval(options: &NetworkingOptions) -> Interval {
+        let mut gossip_interval = tokio::time::interval(options.gossip_interval.into());
+        gossip_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        gossip_interval
+    }
+
+    async fn run(mut self) -> anyhow::Result<()> {
+        debug!("Failure detector started");
+
+        let mut config_watcher = Configuration::watcher();
+        let mut gossip_interval = Self::create_gossip_interval(&Configuration::pinned().networking);
+        let mut cancel = pin!(cancellation_watcher());
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel => {
+                    debug!("Failure detector stopped");
+                    break;
+                }
+                _ = gossip_interval.tick() => {
+                    self.gossip_round();
+                }
+                Some(gossip) = self.incoming_gossip.next() => {
+                    self.on_gossip(gossip);
+                }
+                _ = config_watcher.changed() => {
+                    gossip_interval =
+                        Self::create_gossip_interval(&Configuration::pinned().networking);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_gossip(&self, gossip: Incoming<Gossip>) {
+        trace!(peer = %gossip.peer(), "Received gossip");
+        for heartbeat in gossip.into_body().heartbeats {
+            self.cluster_liveness.observe(heartbeat);
+        }
+    }
+
+    fn gossip_round(&mut self) {
+        let (gossip_timeout, fanout, failure_timeout) = {
+            let config = Configuration::pinned();
+            (
+                config.networking.gossip_interval.into(),
+                config.networking.gossip_fanout.get(),
+                config.networking.gossip_failure_timeout.into(),
+            )
+        };
+
+        let my_node_id = self.networking.my_node_id();
+        self.heartbeat += 1;
+        self.cluster_liveness.observe(NodeHeartbeat {
+            node_id: my_node_id,
+            counter: self.heartbeat,
+        });
+        self.cluster_liveness.update_alive_nodes(failure_timeout);
+
+        let peers: Vec<_> = self
+            .networking
+            .metadata()
+            .nodes_config_ref()
+            .iter()
+            .map(|(_, node_config)| node_config.current_generation)
+            .filter(|node_id| node_id.as_plain() != my_node_id.as_plain())
+            .collect();
+
+        let gossip = Gossip {
+            heartbeats: self.cluster_liveness.known_heartbeats(),
+        };
+        for peer in peers.choose_multiple(&mut rand::thread_rng(), fanout) {
+            let peer = *peer;
+            let networking = self.networking.clone();
+            let gossip = gossip.clone();
+            // Unreachable peers must not delay the next rounds, they are detected as dead by
+            // the other nodes too.
+            let _ = TaskCenter::spawn(TaskKind::Disposable, "gossip-send", async move {
+                match tokio::time::timeout(gossip_timeout, networking.node_connection(peer)).await {
+                    Ok(Ok(connection)) => {
+                        let _ = Outgoing::new(peer, gossip)
+                            .assign_connection(connection)
+                            .try_send();
+                    }
+                    Ok(Err(err)) => trace!("Failed sending gossip to {peer}: {err}"),
+                    Err(_) => trace!("Timed out connecting to {peer} to send gossip"),
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+pub fn spawn_failure_detector<T: TransportConnect>(
+    failure_detector: FailureDetector<T>,
+) -> Result<TaskId, ShutdownError> {
+    TaskCenter::spawn(
+        TaskKind::SystemService,
+        "failure-detector",
+        failure_detector.run(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn heartbeat(node_id: GenerationalNodeId, counter: u64) -> NodeHeartbeat {
+        NodeHeartbeat { node_id, counter }
+    }
+
+    #[test]
+    fn merge_newer_heartbeats() {
+        let liveness = ClusterLiveness::default();
+        let n1 = GenerationalNodeId::new(1, 1);
+
+        liveness.observe(heartbeat(n1, 5));
+        liveness.observe(heartbeat(n1, 3));
+        assert_eq!(liveness.known_heartbeats(), vec![heartbeat(n1, 5)]);
+
+        // a new generation restarts the counter
+        let n1_restarted = GenerationalNodeId::new(1, 2);
+        liveness.observe(heartbeat(n1_restarted, 1));
+        assert_eq!(
+            liveness.known_heartbeats(),
+            vec![heartbeat(n1_restarted, 1)]
+        );
+
+        liveness.observe(heartbeat(n1, 10));
+        assert_eq!(
+            liveness.known_heartbeats(),
+            vec![heartbeat(n1_restarted, 1)]
+        );
+    }
+
+    #[test]
+    fn watch_alive_nodes() {
+        let liveness = ClusterLiveness::default();
+        let mut alive_nodes = liveness.alive_nodes_watcher();
+
+        liveness.observe(heartbeat(GenerationalNodeId::new(1, 1), 1));
+        liveness.observe(heartbeat(GenerationalNodeId::new(2, 1), 1));
+        liveness.update_alive_nodes(Duration::from_secs(60));
+        assert!(alive_nodes.has_changed().unwrap());
+        assert_eq!(
+            *alive_nodes.borrow_and_update(),
+            BTreeSet::from([PlainNodeId::new(1), PlainNodeId::new(2)])
+        );
+
+        liveness.update_alive_nodes(Duration::ZERO);
+        assert!(alive_nodes.has_changed().unwrap());
+        assert!(alive_nodes.borrow_and_update().is_empty());
+    }
+}
@@ -11,8 +11,9 @@
 use std::time::Duration;
 
 use futures::Stream;
+use restate_types::config::NetworkMessageCompression;
 use restate_types::net::{ProtocolVersion, CURRENT_PROTOCOL_VERSION};
-use restate_types::protobuf::node::{message, Header, Hello, Message, Welcome};
+use restate_types::protobuf::node::{message, Header, Hello, Message, MessageCompression, Welcome};
 use tokio_stream::StreamExt;
 
 use super::error::ProtocolError;
@@ -81,6 +82,34 @@ pub fn negotiate_protocol_version(hello: &Hello) -> Result<ProtocolVersion, Prot
     Ok(selected_proto_version)
 }
 
+/// Compressions the client accepts, with the configured one first. Empty if the compression is
+/// disabled.
+pub fn accepted_compressions(configured: NetworkMessageCompression) -> Vec<MessageCompression> {
+    match configured {
+        NetworkMessageCompression::None => vec![],
+        NetworkMessageCompression::Zstd => vec![MessageCompression::Zstd, MessageCompression::Lz4],
+        NetworkMessageCompression::Lz4 => vec![MessageCompression::Lz4, MessageCompression::Zstd],
+    }
+}
+
+/// Selects the configured compression if the client accepts it, otherwise messages are sent
+/// uncompressed.
+pub fn negotiate_compression(
+    hello: &Hello,
+    configured: NetworkMessageCompression,
+) -> MessageCompression {
+    let configured = MessageCompression::from(configured);
+    if configured != MessageCompression::None
+        && hello
+            .accepted_compressions()
+            .any(|compression| compression == configured)
+    {
+        configured
+    } else {
+        MessageCompression::None
+    }
+}
+
 pub async fn wait_for_welcome<S>(
     response_stream: &mut S,
     timeout: Duration,
@@ -19,6 +19,7 @@ const NETWORK_CONNECTION_DROPPED: &str = "restate.network.connection_dropped.tot
 const NETWORK_ONGOING_DRAINS: &str = "restate.network.ongoing_drains";
 const NETWORK_MESSAGE_SENT: &str = "restate.network.message_sent.total";
 const NETWORK_MESSAGE_RECEIVED: &str = "restate.network.message_received.total";
+const NETWORK_MESSAGE_DROPPED: &str = "restate.network.message_dropped.total";
 
 const NETWORK_CONNECTION_SEND_DURATION: &str = "restate.network.connection_send_duration.seconds";
 const NETWORK_MESSAGE_PROCESSING_DURATION: &str =
@@ -35,6 +36,8 @@ pub static ONGOING_DRAIN: Lazy<Gauge> = Lazy::new(|| gauge!(NETWORK_ONGOING_DRAI
 
 pub static MESSAGE_SENT: Lazy<Counter> = Lazy::new(|| counter!(NETWORK_MESSAGE_SENT));
 pub static MESSAGE_RECEIVED: Lazy<Counter> = Lazy::new(|| counter!(NETWORK_MESSAGE_RECEIVED));
+pub static MESSAGE_DROPPED_TOO_LARGE: Lazy<Counter> =
+    Lazy::new(|| counter!(NETWORK_MESSAGE_DROPPED, "reason" => "too_large"));
 
 pub static CONNECTION_SEND_DURATION: Lazy<Histogram> =
     Lazy::new(|| histogram!(NETWORK_CONNECTION_SEND_DURATION));
@@ -67,6 +70,12 @@ pub fn describe_metrics() {
         "Number of messages received"
     );
 
+    describe_counter!(
+        NETWORK_MESSAGE_DROPPED,
+        Unit::Count,
+        "Number of messages dropped, either when sending or receiving them"
+    );
+
     describe_histogram!(
         NETWORK_CONNECTION_SEND_DURATION,
         Unit::Seconds,
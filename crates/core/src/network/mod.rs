@@ -11,6 +11,7 @@
 mod connection;
 mod connection_manager;
 mod error;
+pub mod failure_detector;
 mod handshake;
 mod message_router;
 pub(crate) mod metric_definitions;
@@ -22,6 +23,7 @@ pub mod partition_processor_rpc_client;
 pub mod protobuf;
 pub mod rpc_router;
 mod server_builder;
+pub mod tls;
 pub mod transport_connector;
 mod types;
 
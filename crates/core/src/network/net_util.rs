@@ -18,8 +18,8 @@ use http::Uri;
 use hyper::body::{Body, Incoming};
 use hyper::rt::{Read, Write};
 use hyper_util::rt::TokioIo;
-use tokio::io;
-use tokio::net::{TcpListener, UnixListener, UnixStream};
+use tokio::io::{self, AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
 use tokio_util::net::Listener;
 use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, instrument, Span};
@@ -28,6 +28,7 @@ use restate_types::config::{MetadataStoreClientOptions, NetworkingOptions};
 use restate_types::errors::GenericError;
 use restate_types::net::{AdvertisedAddress, BindAddress};
 
+use super::tls::{self, NetworkTls, TlsError};
 use crate::{cancellation_watcher, task_center, ShutdownError, TaskCenter, TaskKind};
 
 pub fn create_tonic_channel_from_advertised_address<T: CommonClientConnectionOptions>(
@@ -46,15 +47,44 @@ pub fn create_tonic_channel_from_advertised_address<T: CommonClientConnectionOpt
                     }
                 }))
         }
-        AdvertisedAddress::Http(uri) => Channel::builder(uri)
-            .connect_timeout(options.connect_timeout())
-            .http2_keep_alive_interval(options.keep_alive_interval())
-            .keep_alive_timeout(options.keep_alive_timeout())
-            .http2_adaptive_window(options.http2_adaptive_window())
-            .connect_lazy(),
+        AdvertisedAddress::Http(uri) => {
+            let endpoint = Channel::builder(uri)
+                .connect_timeout(options.connect_timeout())
+                .http2_keep_alive_interval(options.keep_alive_interval())
+                .keep_alive_timeout(options.keep_alive_timeout())
+                .http2_adaptive_window(options.http2_adaptive_window());
+
+            match tls::current() {
+                Some(network_tls) => {
+                    endpoint.connect_with_connector_lazy(tower::service_fn(move |uri: Uri| {
+                        connect_tls(network_tls, uri)
+                    }))
+                }
+                None => endpoint.connect_lazy(),
+            }
+        }
     }
 }
 
+async fn connect_tls(
+    network_tls: &'static NetworkTls,
+    uri: Uri,
+) -> Result<TokioIo<tokio_rustls::client::TlsStream<TcpStream>>, io::Error> {
+    let host = uri
+        .host()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the uri has no host"))?;
+    let port = uri.port_u16().unwrap_or(80);
+
+    let stream =
+        TcpStream::connect((host.trim_start_matches('[').trim_end_matches(']'), port)).await?;
+    stream.set_nodelay(true)?;
+    let stream = network_tls
+        .connect(host, stream)
+        .await
+        .map_err(io::Error::other)?;
+    Ok(TokioIo::new(stream))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("failed binding to address '{address}': {source}")]
@@ -69,6 +99,8 @@ pub enum Error {
         #[source]
         source: io::Error,
     },
+    #[error("failed the TLS handshake: {0}")]
+    Tls(#[from] TlsError),
     #[error("failed handling hyper connection: {0}")]
     HandlingConnection(#[from] GenericError),
     #[error("failed listening on incoming connections: {0}")]
@@ -82,6 +114,7 @@ pub async fn run_hyper_server<S, B>(
     bind_address: &BindAddress,
     service: S,
     server_name: &'static str,
+    tls: Option<&'static NetworkTls>,
     on_bind: impl Fn(),
     on_stop: impl Fn(),
 ) -> Result<(), Error>
@@ -111,7 +144,7 @@ where
             info!("Server listening");
             on_bind();
 
-            run_listener_loop(unix_listener, service, server_name).await?;
+            run_listener_loop(unix_listener, service, server_name, None).await?;
         }
         BindAddress::Socket(socket_addr) => {
             let tcp_listener =
@@ -132,7 +165,7 @@ where
             info!("Server listening");
             on_bind();
 
-            run_listener_loop(tcp_listener, service, server_name).await?;
+            run_listener_loop(tcp_listener, service, server_name, tls).await?;
         }
     }
     on_stop();
@@ -146,6 +179,7 @@ async fn run_listener_loop<L, S, B>(
     mut listener: L,
     service: S,
     server_name: &'static str,
+    tls: Option<&'static NetworkTls>,
 ) -> Result<(), Error>
 where
     L: Listener,
@@ -170,14 +204,14 @@ where
             }
             incoming_connection = listener.accept() => {
                 let (stream, remote_addr) = incoming_connection?;
-                let io = TokioIo::new(stream);
                 debug!(?remote_addr, "Accepting incoming connection");
 
-                TaskCenter::spawn_child(TaskKind::RpcConnection, server_name, handle_connection(
+                TaskCenter::spawn_child(TaskKind::RpcConnection, server_name, accept_connection(
                     server_name,
-                    io,
+                    stream,
                     service.clone(),
                     remote_addr,
+                    tls,
                 ))?;
             }
         }
@@ -186,6 +220,36 @@ where
     Ok(())
 }
 
+async fn accept_connection<S, B, I, A>(
+    server_name: &'static str,
+    stream: I,
+    service: S,
+    remote_addr: A,
+    tls: Option<&'static NetworkTls>,
+) -> anyhow::Result<()>
+where
+    S: hyper::service::Service<http::Request<Incoming>, Response = hyper::Response<B>>
+        + Send
+        + Clone
+        + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    I: AsyncRead + AsyncWrite + Unpin + 'static,
+    A: Send + Debug,
+{
+    match tls {
+        Some(network_tls) => {
+            // Handshake in the connection task, to not block the accept loop
+            let stream = network_tls.accept(stream).await.map_err(Error::Tls)?;
+            handle_connection(server_name, TokioIo::new(stream), service, remote_addr).await
+        }
+        None => handle_connection(server_name, TokioIo::new(stream), service, remote_addr).await,
+    }
+}
+
 async fn handle_connection<S, B, I, A>(
     server_name: &'static str,
     io: I,
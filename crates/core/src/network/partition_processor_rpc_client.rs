@@ -11,15 +11,18 @@
 use assert2::let_assert;
 use tracing::trace;
 
-use restate_types::identifiers::{PartitionId, PartitionProcessorRpcRequestId, WithPartitionKey};
+use restate_types::identifiers::{
+    PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
+};
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::live::Live;
 use restate_types::net::partition_processor::{
-    AppendInvocationReplyOn, GetInvocationOutputResponseMode, InvocationOutput,
+    AppendInvocationReplyOn, GetInvocationOutputResponseMode, InvocationOutput, InvocationProgress,
     PartitionProcessorRpcError, PartitionProcessorRpcRequest, PartitionProcessorRpcRequestInner,
     PartitionProcessorRpcResponse, SubmittedInvocationNotification,
 };
 use restate_types::partition_table::{FindPartition, PartitionTable, PartitionTableError};
+use restate_types::schedule::Schedule;
 
 use crate::network::rpc_router::{ConnectionAwareRpcError, ConnectionAwareRpcRouter, RpcError};
 use crate::network::{HasConnection, Networking, Outgoing, TransportConnect};
@@ -52,6 +55,8 @@ pub enum PartitionProcessorRpcClientError {
     Starting,
     #[error("partition processor stopping")]
     Stopping,
+    #[error("partition '{0}' is overloaded, rejecting new invocations")]
+    Overloaded(PartitionId),
 }
 
 impl PartitionProcessorRpcClientError {
@@ -68,7 +73,8 @@ impl PartitionProcessorRpcClientError {
             | PartitionProcessorRpcClientError::UnknownNode(_)
             | PartitionProcessorRpcClientError::NotLeader(_)
             | PartitionProcessorRpcClientError::Starting
-            | PartitionProcessorRpcClientError::Stopping => {
+            | PartitionProcessorRpcClientError::Stopping
+            | PartitionProcessorRpcClientError::Overloaded(_) => {
                 // These are pre-flight error that we can distinguish,
                 // and for which we know for certain that no message was proposed yet to the log.
                 true
@@ -93,6 +99,9 @@ impl From<PartitionProcessorRpcError> for PartitionProcessorRpcClientError {
             }
             PartitionProcessorRpcError::Starting => PartitionProcessorRpcClientError::Starting,
             PartitionProcessorRpcError::Stopping => PartitionProcessorRpcClientError::Stopping,
+            PartitionProcessorRpcError::Overloaded(partition_id) => {
+                PartitionProcessorRpcClientError::Overloaded(partition_id)
+            }
         }
     }
 }
@@ -124,6 +133,14 @@ pub enum GetInvocationOutputResponse {
     Ready(InvocationOutput),
 }
 
+#[derive(Debug, Clone)]
+pub enum GetInvocationProgressResponse {
+    NotFound,
+    /// Returned when the invocation hasn't an idempotency key, nor it's a workflow run.
+    NotSupported,
+    Progress(InvocationProgress),
+}
+
 pub struct PartitionProcessorRpcClient<C> {
     networking: Networking<C>,
     rpc_router: ConnectionAwareRpcRouter<PartitionProcessorRpcRequest>,
@@ -186,6 +203,29 @@ where
         Ok(())
     }
 
+    /// Append a batch of invocations to the log, returning as soon as all of them were
+    /// successfully appended. All the invocations must belong to the same partition, see
+    /// [`Self::find_partition_id`].
+    pub async fn append_invocations(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_requests: Vec<InvocationRequest>,
+    ) -> Result<(), PartitionProcessorRpcClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::AppendInvocations(invocation_requests),
+            )
+            .await?;
+
+        let_assert!(
+            PartitionProcessorRpcResponse::Appended = response,
+            "Expecting PartitionProcessorRpcResponse::Appended"
+        );
+
+        Ok(())
+    }
+
     /// Append the invocation to the log, waiting for the submit notification emitted by the PartitionProcessor.
     pub async fn append_invocation_and_wait_submit_notification(
         &self,
@@ -299,6 +339,32 @@ where
         })
     }
 
+    pub async fn get_invocation_progress(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_query: InvocationQuery,
+    ) -> Result<GetInvocationProgressResponse, PartitionProcessorRpcClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::GetInvocationProgress(invocation_query),
+            )
+            .await?;
+
+        Ok(match response {
+            PartitionProcessorRpcResponse::NotFound => GetInvocationProgressResponse::NotFound,
+            PartitionProcessorRpcResponse::NotSupported => {
+                GetInvocationProgressResponse::NotSupported
+            }
+            PartitionProcessorRpcResponse::Progress(progress) => {
+                GetInvocationProgressResponse::Progress(progress)
+            }
+            _ => {
+                panic!("Expecting either PartitionProcessorRpcResponse::Progress or PartitionProcessorRpcResponse::NotFound or PartitionProcessorRpcResponse::NotSupported")
+            }
+        })
+    }
+
     pub async fn append_invocation_response(
         &self,
         request_id: PartitionProcessorRpcRequestId,
@@ -319,15 +385,45 @@ where
         Ok(())
     }
 
+    /// Append the creation (or replacement) of a recurring schedule to the log, returning as soon
+    /// as it was successfully appended.
+    pub async fn append_schedule(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        schedule: Schedule,
+    ) -> Result<(), PartitionProcessorRpcClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::AppendSchedule(schedule),
+            )
+            .await?;
+
+        let_assert!(
+            PartitionProcessorRpcResponse::Appended = response,
+            "Expecting PartitionProcessorRpcResponse::Appended"
+        );
+
+        Ok(())
+    }
+
+    /// Returns the partition the given partition key belongs to.
+    pub fn find_partition_id(
+        &self,
+        partition_key: PartitionKey,
+    ) -> Result<PartitionId, PartitionProcessorRpcClientError> {
+        Ok(self
+            .partition_table
+            .pinned()
+            .find_partition_id(partition_key)?)
+    }
+
     async fn resolve_partition_id_and_send(
         &self,
         request_id: PartitionProcessorRpcRequestId,
         inner_request: PartitionProcessorRpcRequestInner,
     ) -> Result<PartitionProcessorRpcResponse, PartitionProcessorRpcClientError> {
-        let partition_id = self
-            .partition_table
-            .pinned()
-            .find_partition_id(inner_request.partition_key())?;
+        let partition_id = self.find_partition_id(inner_request.partition_key())?;
 
         let node_id = self
             .partition_routing
@@ -92,6 +92,7 @@ impl NetworkServerBuilder {
             bind_address,
             service,
             "node-rpc-server",
+            super::tls::current(),
             || node_health.update(NodeStatus::Alive),
             || node_health.update(NodeStatus::ShuttingDown),
         )
@@ -0,0 +1,357 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Mutual TLS between the nodes of the cluster, see [`NetworkTlsOptions`].
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use notify::RecommendedWatcher;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use parking_lot::Mutex;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::verify_server_cert_signed_by_trust_anchor;
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms};
+use rustls::pki_types::{CertificateDer, InvalidDnsNameError, ServerName, UnixTime};
+use rustls::server::{ParsedCertificate, VerifierBuilderError, WebPkiClientVerifier};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, ServerConfig,
+    SignatureScheme,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+use tracing::{info, warn};
+use x509_parser::extensions::GeneralName;
+
+use restate_tls_util::{read_certificates, read_private_key, PemError};
+use restate_types::config::{NetworkTlsOptions, NetworkingOptions};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const SPIFFE_SCHEME: &str = "spiffe://";
+
+static NETWORK_TLS: OnceLock<NetworkTls> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error(transparent)]
+    Pem(#[from] PemError),
+    #[error("invalid CA certificate in '{}': {source}", path.display())]
+    InvalidCaCertificate {
+        path: PathBuf,
+        #[source]
+        source: rustls::Error,
+    },
+    #[error("invalid node certificate: {0}")]
+    InvalidCertificate(#[from] rustls::Error),
+    #[error("failed to build the verifier of the peer certificates: {0}")]
+    Verifier(#[from] VerifierBuilderError),
+    #[error("failed to watch the certificate files: {0}")]
+    Watch(#[from] notify::Error),
+    #[error("invalid server name: {0}")]
+    InvalidServerName(#[from] InvalidDnsNameError),
+    #[error("TLS handshake failed: {0}")]
+    Handshake(#[from] io::Error),
+    #[error("TLS handshake timed out")]
+    HandshakeTimeout,
+    #[error("the peer certificate has no SPIFFE ID of the trust domain '{0}'")]
+    UnauthorizedPeer(String),
+}
+
+/// Loads the certificates configured in the networking options, and starts watching them for
+/// changes. Must be called at startup, before the node serves or opens any connection to other
+/// nodes; without it, connections are in plaintext.
+pub fn init(options: &NetworkingOptions) -> Result<(), TlsError> {
+    let Some(tls_options) = &options.tls else {
+        return Ok(());
+    };
+    if NETWORK_TLS.get().is_some() {
+        return Ok(());
+    }
+
+    let configs = TlsConfigs::load(tls_options)?;
+    let network_tls = NETWORK_TLS.get_or_init(|| NetworkTls {
+        options: tls_options.clone(),
+        configs: ArcSwap::from_pointee(configs),
+        watcher: Mutex::default(),
+    });
+    network_tls.watch()?;
+
+    info!("Enabled TLS for the connections between nodes");
+    Ok(())
+}
+
+/// The network TLS of the node, if enabled with [`init`].
+pub fn current() -> Option<&'static NetworkTls> {
+    NETWORK_TLS.get()
+}
+
+/// TLS configurations of the node, reloaded whenever the certificate files change.
+pub struct NetworkTls {
+    options: NetworkTlsOptions,
+    configs: ArcSwap<TlsConfigs>,
+    watcher: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+}
+
+impl NetworkTls {
+    /// Performs the server side of the handshake, requiring a certificate from the client.
+    pub async fn accept<IO>(&self, stream: IO) -> Result<server::TlsStream<IO>, TlsError>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let config = Arc::clone(&self.configs.load().server);
+        let stream =
+            tokio::time::timeout(HANDSHAKE_TIMEOUT, TlsAcceptor::from(config).accept(stream))
+                .await
+                .map_err(|_| TlsError::HandshakeTimeout)??;
+
+        // The client certificate is already verified against the CA, only the SPIFFE ID is left
+        if let Some(trust_domain) = &self.options.spiffe_trust_domain {
+            let authorized = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certificates| certificates.first())
+                .is_some_and(|certificate| has_spiffe_id(certificate, trust_domain));
+            if !authorized {
+                return Err(TlsError::UnauthorizedPeer(trust_domain.clone()));
+            }
+        }
+
+        Ok(stream)
+    }
+
+    /// Performs the client side of the handshake with the node at `host`.
+    pub async fn connect<IO>(
+        &self,
+        host: &str,
+        stream: IO,
+    ) -> Result<client::TlsStream<IO>, TlsError>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let config = Arc::clone(&self.configs.load().client);
+        // IPv6 hosts of URIs are enclosed in brackets
+        let server_name =
+            ServerName::try_from(host.trim_start_matches('[').trim_end_matches(']'))?.to_owned();
+
+        tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            TlsConnector::from(config).connect(server_name, stream),
+        )
+        .await
+        .map_err(|_| TlsError::HandshakeTimeout)?
+        .map_err(Into::into)
+    }
+
+    fn watch(&'static self) -> Result<(), TlsError> {
+        // Watch the directories rather than the files, as rotations often replace the files,
+        // e.g. Kubernetes swaps the symlinks of the mounted secrets.
+        let directories: HashSet<_> = [
+            &self.options.certificate_file,
+            &self.options.private_key_file,
+            &self.options.ca_certificate_file,
+        ]
+        .into_iter()
+        .filter_map(|path| path.parent())
+        .map(|directory| {
+            if directory.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                directory
+            }
+        })
+        .collect();
+
+        let mut debouncer = new_debouncer(
+            Duration::from_secs(3),
+            move |res: DebounceEventResult| match res {
+                Ok(_) => self.reload(),
+                Err(err) => warn!("Error watching the network TLS certificates: {err:?}"),
+            },
+        )?;
+        for directory in directories {
+            debouncer
+                .watcher()
+                .watch(directory, notify::RecursiveMode::NonRecursive)?;
+        }
+
+        *self.watcher.lock() = Some(debouncer);
+        Ok(())
+    }
+
+    fn reload(&self) {
+        match TlsConfigs::load(&self.options) {
+            Ok(configs) => {
+                self.configs.store(Arc::new(configs));
+                info!("Reloaded the network TLS certificates");
+            }
+            Err(err) => {
+                warn!("Failed reloading the network TLS certificates, keeping the previous ones: {err}");
+            }
+        }
+    }
+}
+
+struct TlsConfigs {
+    server: Arc<ServerConfig>,
+    client: Arc<ClientConfig>,
+}
+
+impl TlsConfigs {
+    fn load(options: &NetworkTlsOptions) -> Result<Self, TlsError> {
+        let root_certificates = Arc::new(root_cert_store(&options.ca_certificate_file)?);
+        let certificate_chain = read_certificates(&options.certificate_file)?;
+        let private_key = read_private_key(&options.private_key_file)?;
+
+        let mut server = ServerConfig::builder()
+            .with_client_cert_verifier(
+                WebPkiClientVerifier::builder(Arc::clone(&root_certificates)).build()?,
+            )
+            .with_single_cert(certificate_chain.clone(), private_key.clone_key())?;
+        // The node server multiplexes gRPC and HTTP
+        server.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        let client = match &options.spiffe_trust_domain {
+            Some(trust_domain) => ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(SpiffeServerVerifier {
+                    root_certificates,
+                    trust_domain: trust_domain.clone(),
+                    algorithms: rustls::crypto::ring::default_provider()
+                        .signature_verification_algorithms,
+                })),
+            None => ClientConfig::builder().with_root_certificates(root_certificates),
+        };
+        let mut client = client.with_client_auth_cert(certificate_chain, private_key)?;
+        client.alpn_protocols = vec![b"h2".to_vec()];
+
+        Ok(Self {
+            server: Arc::new(server),
+            client: Arc::new(client),
+        })
+    }
+}
+
+/// Verifies the certificate of the server against the CA and its SPIFFE ID, instead of its host
+/// name.
+#[derive(Debug)]
+struct SpiffeServerVerifier {
+    root_certificates: Arc<RootCertStore>,
+    trust_domain: String,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for SpiffeServerVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let certificate = ParsedCertificate::try_from(end_entity)?;
+        verify_server_cert_signed_by_trust_anchor(
+            &certificate,
+            &self.root_certificates,
+            intermediates,
+            now,
+            self.algorithms.all,
+        )?;
+
+        if !has_spiffe_id(end_entity, &self.trust_domain) {
+            return Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ));
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+fn has_spiffe_id(certificate: &CertificateDer<'_>, trust_domain: &str) -> bool {
+    let Ok((_, certificate)) = x509_parser::parse_x509_certificate(certificate) else {
+        return false;
+    };
+    let Ok(Some(subject_alternative_name)) = certificate.subject_alternative_name() else {
+        return false;
+    };
+
+    subject_alternative_name.value.general_names.iter().any(|name| {
+        matches!(name, GeneralName::URI(uri) if spiffe_trust_domain(uri) == Some(trust_domain))
+    })
+}
+
+fn spiffe_trust_domain(uri: &str) -> Option<&str> {
+    uri.strip_prefix(SPIFFE_SCHEME)?
+        .split('/')
+        .next()
+        .filter(|trust_domain| !trust_domain.is_empty())
+}
+
+fn root_cert_store(path: &Path) -> Result<RootCertStore, TlsError> {
+    let mut root_cert_store = RootCertStore::empty();
+    for certificate in read_certificates(path)? {
+        root_cert_store
+            .add(certificate)
+            .map_err(|source| TlsError::InvalidCaCertificate {
+                path: path.to_owned(),
+                source,
+            })?;
+    }
+    Ok(root_cert_store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_spiffe_trust_domain() {
+        assert_eq!(
+            spiffe_trust_domain("spiffe://cluster.local/ns/restate/sa/restate"),
+            Some("cluster.local")
+        );
+        assert_eq!(
+            spiffe_trust_domain("spiffe://cluster.local"),
+            Some("cluster.local")
+        );
+        assert_eq!(spiffe_trust_domain("spiffe:///ns/restate"), None);
+        assert_eq!(
+            spiffe_trust_domain("https://cluster.local/ns/restate"),
+            None
+        );
+    }
+}
@@ -79,7 +79,10 @@ impl TransportConnect for GrpcConnector {
         };
 
         // Establish the connection
-        let mut client = NodeSvcClient::new(channel);
+        let max_message_size = self.networking_options.grpc_max_message_size();
+        let mut client = NodeSvcClient::new(channel)
+            .max_decoding_message_size(max_message_size)
+            .max_encoding_message_size(max_message_size);
         let incoming = client.create_connection(output_stream).await?.into_inner();
         Ok(incoming.map(|x| x.map_err(ProtocolError::from)))
     }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod builder;
+mod dump;
 mod extensions;
 mod handle;
 mod monitoring;
@@ -17,6 +18,7 @@ mod task;
 mod task_kind;
 
 pub use builder::*;
+pub use dump::*;
 pub use extensions::*;
 pub use handle::*;
 pub use monitoring::*;
@@ -29,17 +31,19 @@ use std::future::Future;
 use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::sync::{Arc, OnceLock};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use futures::FutureExt;
 use metrics::counter;
 use parking_lot::Mutex;
+use strum::IntoEnumIterator;
 use tokio::sync::oneshot;
 use tokio::task::LocalSet;
 use tokio::task_local;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 
+use restate_types::config::{RuntimeOptions, ShutdownPhasesOptions};
 use restate_types::identifiers::PartitionId;
 use restate_types::GenerationalNodeId;
 
@@ -47,6 +51,7 @@ use crate::metric_definitions::{
     self, TC_FINISHED, TC_SPAWN, TC_STATUS_COMPLETED, TC_STATUS_FAILED,
 };
 use crate::{Metadata, ShutdownError, ShutdownSourceErr};
+use runtime::{apply_runtime_options, pin_current_thread};
 
 const EXIT_CODE_FAILURE: i32 = 1;
 
@@ -175,18 +180,28 @@ impl TaskCenter {
 
     /// Starts the `root_future` on a new runtime. The runtime is stopped once the root future
     /// completes.
+    ///
+    /// The runtime is forcefully torn down if it doesn't stop within `shutdown_timeout` after it
+    /// has been asked to shut down. Defaults to `common.runtime-shutdown-timeout` if `None`.
     #[track_caller]
     pub fn start_runtime<F>(
         root_task_kind: TaskKind,
         runtime_name: &'static str,
         partition_id: Option<PartitionId>,
+        shutdown_timeout: Option<Duration>,
         root_future: impl FnOnce() -> F + Send + 'static,
     ) -> Result<RuntimeTaskHandle<anyhow::Result<()>>, RuntimeError>
     where
         F: Future<Output = anyhow::Result<()>> + 'static,
     {
         Self::with_current(|tc| {
-            tc.start_runtime(root_task_kind, runtime_name, partition_id, root_future)
+            tc.start_runtime(
+                root_task_kind,
+                runtime_name,
+                partition_id,
+                shutdown_timeout,
+                root_future,
+            )
         })
     }
 
@@ -264,6 +279,13 @@ struct TaskCenterInner {
     default_runtime_handle: tokio::runtime::Handle,
     ingress_runtime_handle: tokio::runtime::Handle,
     managed_runtimes: Mutex<HashMap<&'static str, OwnedRuntimeHandle>>,
+    /// Time a managed runtime is given to stop after it has been asked to shut down before it is
+    /// forcefully torn down, unless the runtime was started with its own timeout.
+    default_runtime_shutdown_timeout: Duration,
+    /// Deadlines of the shutdown phases
+    shutdown_phases: ShutdownPhasesOptions,
+    /// Deadline of the last shutdown phase
+    shutdown_timeout: Duration,
     start_time: Instant,
     /// We hold on to the owned Runtime to ensure it's dropped when task center is dropped. If this
     /// is None, it means that it's the responsibility of the Handle owner to correctly drop
@@ -272,6 +294,11 @@ struct TaskCenterInner {
     default_runtime: Option<tokio::runtime::Runtime>,
     #[allow(dead_code)]
     ingress_runtime: Option<tokio::runtime::Runtime>,
+    /// Runtimes dedicated to the tasks of a role, if configured
+    invoker_runtime: Option<tokio::runtime::Runtime>,
+    metadata_store_runtime: Option<tokio::runtime::Runtime>,
+    /// Resource budget of the runtimes started for partition processors, if configured
+    partition_processor_runtime_options: Option<RuntimeOptions>,
     global_cancel_token: CancellationToken,
     shutdown_requested: AtomicBool,
     current_exit_code: AtomicI32,
@@ -286,6 +313,12 @@ impl TaskCenterInner {
         ingress_runtime_handle: tokio::runtime::Handle,
         default_runtime: Option<tokio::runtime::Runtime>,
         ingress_runtime: Option<tokio::runtime::Runtime>,
+        invoker_runtime: Option<tokio::runtime::Runtime>,
+        metadata_store_runtime: Option<tokio::runtime::Runtime>,
+        partition_processor_runtime_options: Option<RuntimeOptions>,
+        runtime_shutdown_timeout: Duration,
+        shutdown_phases: ShutdownPhasesOptions,
+        shutdown_timeout: Duration,
         // used in tests to start all runtimes with clock paused. Note that this only impacts
         // partition processor runtimes
         pause_time: bool,
@@ -305,12 +338,18 @@ impl TaskCenterInner {
             default_runtime,
             ingress_runtime_handle,
             ingress_runtime,
+            invoker_runtime,
+            metadata_store_runtime,
+            partition_processor_runtime_options,
             global_cancel_token: CancellationToken::new(),
             shutdown_requested: AtomicBool::new(false),
             current_exit_code: AtomicI32::new(0),
             managed_tasks: Mutex::new(HashMap::new()),
             global_metadata: OnceLock::new(),
             managed_runtimes: Mutex::new(HashMap::with_capacity(64)),
+            runtime_shutdown_timeout,
+            shutdown_phases,
+            shutdown_timeout,
             root_task_context,
             pause_time,
         }
@@ -493,6 +532,7 @@ impl TaskCenterInner {
 
         let task = Arc::new(Task {
             context: context.clone(),
+            spawned_at: SystemTime::now(),
             handle: Mutex::new(None),
         });
 
@@ -525,6 +565,7 @@ impl TaskCenterInner {
         root_task_kind: TaskKind,
         runtime_name: &'static str,
         partition_id: Option<PartitionId>,
+        shutdown_timeout: Option<Duration>,
         root_future: impl FnOnce() -> F + Send + 'static,
     ) -> Result<RuntimeTaskHandle<anyhow::Result<()>>, RuntimeError>
     where
@@ -546,9 +587,18 @@ impl TaskCenterInner {
             return Err(RuntimeError::AlreadyExists(runtime_name.to_owned()));
         }
 
-        // todo: configure the runtime according to a new runtime kind perhaps?
         let thread_builder = std::thread::Builder::new().name(format!("rt:{}", runtime_name));
         let mut builder = tokio::runtime::Builder::new_current_thread();
+        let runtime_options = self
+            .partition_processor_runtime_options
+            .as_ref()
+            .filter(|_| root_task_kind == TaskKind::PartitionProcessor);
+        if let Some(runtime_options) = runtime_options {
+            apply_runtime_options(&mut builder, runtime_options);
+        }
+        let cpu_cores = runtime_options
+            .map(|runtime_options| runtime_options.cpu_cores.clone())
+            .unwrap_or_default();
 
         #[cfg(any(test, feature = "test-util"))]
         builder.start_paused(self.pause_time);
@@ -580,15 +630,43 @@ impl TaskCenterInner {
 
         let (result_tx, result_rx) = oneshot::channel();
 
+        // used to tear down the runtime if it doesn't stop within its shutdown timeout
+        let force_cancel = CancellationToken::new();
+        let completed = CancellationToken::new();
+        self.default_runtime_handle
+            .spawn(self.clone().watch_runtime_shutdown(
+                runtime_name,
+                partition_id,
+                shutdown_timeout.unwrap_or(self.default_runtime_shutdown_timeout),
+                cancel.clone(),
+                force_cancel.clone(),
+                completed.clone(),
+            ));
+
         // start the work on the runtime
         let _ = thread_builder
             .spawn(move || {
+                // the worker thread of a single-threaded runtime is the calling thread
+                if !cpu_cores.is_empty() {
+                    pin_current_thread(&cpu_cores);
+                }
                 let local_set = LocalSet::new();
                 let result = rt_handle.block_on(local_set.run_until(unmanaged_wrapper(
                     tc.clone(),
                     context,
-                    root_future(),
+                    async move {
+                        tokio::select! {
+                            result = root_future() => result,
+                            _ = force_cancel.cancelled() => Err(anyhow::anyhow!(
+                                "runtime {} was forcefully torn down after exceeding its shutdown timeout",
+                                runtime_name
+                            )),
+                        }
+                    },
                 )));
+                completed.cancel();
+                // drop all tasks which are still lingering on the runtime's local set
+                drop(local_set);
 
                 debug!("Runtime {} completed", runtime_name);
                 drop(rt_handle);
@@ -602,6 +680,65 @@ impl TaskCenterInner {
         Ok(RuntimeTaskHandle::new(runtime_name, cancel, result_rx))
     }
 
+    /// Waits until the runtime has been asked to shut down and forcefully tears it down if it
+    /// hasn't completed within `timeout`.
+    async fn watch_runtime_shutdown(
+        self: Arc<Self>,
+        runtime_name: &'static str,
+        partition_id: Option<PartitionId>,
+        timeout: Duration,
+        cancel: CancellationToken,
+        force_cancel: CancellationToken,
+        completed: CancellationToken,
+    ) {
+        tokio::select! {
+            _ = cancel.cancelled() => {}
+            _ = completed.cancelled() => return,
+        }
+
+        if tokio::time::timeout(timeout, completed.cancelled())
+            .await
+            .is_ok()
+        {
+            return;
+        }
+
+        let alive_tasks = self
+            .managed_runtimes
+            .lock()
+            .get(runtime_name)
+            .map(|runtime| runtime.runtime_handle().metrics().num_alive_tasks());
+        let lingering_tasks: Vec<_> = self
+            .managed_tasks
+            .lock()
+            .values()
+            .filter(|task| partition_id.is_some() && task.partition_id() == partition_id)
+            .map(|task| format!("{}({})", task.name(), task.id()))
+            .collect();
+        warn!(
+            runtime = runtime_name,
+            ?partition_id,
+            ?alive_tasks,
+            ?lingering_tasks,
+            "Runtime did not shut down within {:?}, forcefully tearing it down",
+            timeout
+        );
+        force_cancel.cancel();
+
+        // The root future is dropped on the next poll of the runtime. If that doesn't happen, the
+        // runtime thread is blocked and there is nothing more we can do.
+        if tokio::time::timeout(timeout, completed.cancelled())
+            .await
+            .is_err()
+        {
+            error!(
+                runtime = runtime_name,
+                ?partition_id,
+                "Runtime thread is blocked and could not be torn down"
+            );
+        }
+    }
+
     /// Runs **only** after the inner main thread has completed work and no other owner exists for
     /// the runtime handle.
     fn drop_runtime(self: &Arc<Self>, name: &'static str) {
@@ -650,6 +787,7 @@ impl TaskCenterInner {
         };
         let task = Arc::new(Task {
             context: context.clone(),
+            spawned_at: SystemTime::now(),
             handle: Mutex::new(None),
         });
 
@@ -684,6 +822,14 @@ impl TaskCenterInner {
             crate::AsyncRuntime::Inherit => &tokio::runtime::Handle::current(),
             crate::AsyncRuntime::Default => &self.default_runtime_handle,
             crate::AsyncRuntime::Ingress => &self.ingress_runtime_handle,
+            crate::AsyncRuntime::Invoker => match &self.invoker_runtime {
+                Some(runtime) => runtime.handle(),
+                None => &tokio::runtime::Handle::current(),
+            },
+            crate::AsyncRuntime::MetadataStore => match &self.metadata_store_runtime {
+                Some(runtime) => runtime.handle(),
+                None => &tokio::runtime::Handle::current(),
+            },
         };
         let inner_handle = tokio_task
             .spawn_on(fut, runtime)
@@ -783,14 +929,73 @@ impl TaskCenterInner {
         } else {
             info!(%reason, "** Shutdown requested");
         }
-        self.initiate_managed_runtimes_shutdown();
-        self.cancel_tasks(None, None).await;
+        for phase in ShutdownPhase::iter() {
+            self.shutdown_phase(phase).await;
+        }
         self.shutdown_managed_runtimes();
         // notify outer components that we have completed the shutdown.
         self.global_cancel_token.cancel();
         info!("** Shutdown completed in {:?}", start.elapsed());
     }
 
+    /// Cancels the tasks of the shutdown phase and waits for them to stop, up to the deadline of
+    /// the phase. Lingering tasks are logged and left behind.
+    async fn shutdown_phase(self: &Arc<Self>, phase: ShutdownPhase) {
+        let timeout = match phase {
+            ShutdownPhase::Ingress => self.shutdown_phases.ingress_timeout.into(),
+            ShutdownPhase::Invoker => self.shutdown_phases.invoker_timeout.into(),
+            ShutdownPhase::PartitionProcessors => {
+                self.shutdown_phases.partition_processors_timeout.into()
+            }
+            ShutdownPhase::Bifrost => self.shutdown_phases.bifrost_timeout.into(),
+            ShutdownPhase::Other => self.shutdown_timeout,
+        };
+        let start = Instant::now();
+        info!("** Shutdown phase {} started", phase);
+
+        let drained = async {
+            if phase == ShutdownPhase::PartitionProcessors {
+                self.initiate_managed_runtimes_shutdown();
+            }
+            self.cancel_tasks_matching(|task| task.kind().shutdown_phase() == phase)
+                .await;
+            if phase == ShutdownPhase::PartitionProcessors {
+                // runtimes remove themselves once they completed
+                while !self.managed_runtimes.lock().is_empty() {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        };
+        if tokio::time::timeout(timeout, drained).await.is_ok() {
+            info!(
+                "** Shutdown phase {} completed in {:?}",
+                phase,
+                start.elapsed()
+            );
+            return;
+        }
+
+        let lingering_tasks: Vec<_> = self
+            .managed_tasks
+            .lock()
+            .values()
+            .filter(|task| task.kind().shutdown_phase() == phase)
+            .map(|task| format!("{}({})", task.name(), task.id()))
+            .collect();
+        let lingering_runtimes: Vec<_> = if phase == ShutdownPhase::PartitionProcessors {
+            self.managed_runtimes.lock().keys().copied().collect()
+        } else {
+            Vec::new()
+        };
+        warn!(
+            ?lingering_tasks,
+            ?lingering_runtimes,
+            "** Shutdown phase {} did not complete within {:?}, moving on",
+            phase,
+            timeout
+        );
+    }
+
     /// Take control over the running task from task-center. This returns None if the task was not
     /// found, completed, or has been cancelled.
     pub fn take_task(self: &Arc<Self>, task_id: TaskId) -> Option<TaskHandle<()>> {
@@ -840,14 +1045,20 @@ impl TaskCenterInner {
         kind: Option<TaskKind>,
         partition_id: Option<PartitionId>,
     ) {
+        self.cancel_tasks_matching(|task| {
+            (kind.is_none() || Some(task.context.kind) == kind)
+                && (partition_id.is_none() || task.context.partition_id == partition_id)
+        })
+        .await
+    }
+
+    async fn cancel_tasks_matching(self: &Arc<Self>, predicate: impl Fn(&Task) -> bool) {
         let mut victims = Vec::new();
 
         {
             let tasks = self.managed_tasks.lock();
             for task in tasks.values() {
-                if (kind.is_none() || Some(task.context.kind) == kind)
-                    && (partition_id.is_none() || task.context.partition_id == partition_id)
-                {
+                if predicate(task) {
                     task.context.cancellation_token.cancel();
                     victims.push((Arc::clone(task), task.kind(), task.partition_id()));
                 }
@@ -1012,8 +1223,10 @@ pub fn is_cancellation_requested() -> bool {
 mod tests {
     use super::*;
 
+    use std::num::NonZeroUsize;
+
     use googletest::prelude::*;
-    use restate_types::config::CommonOptionsBuilder;
+    use restate_types::config::{CommonOptions, CommonOptionsBuilder};
     use tracing_test::traced_test;
 
     #[tokio::test(start_paused = true)]
@@ -1044,4 +1257,123 @@ mod tests {
         assert!(start.elapsed() >= Duration::from_secs(10));
         Ok(())
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_runtime_forced_shutdown() -> Result<()> {
+        let common_opts = CommonOptionsBuilder::default()
+            .runtime_shutdown_timeout(Duration::from_secs(5).into())
+            .build()
+            .unwrap();
+        let tc = TaskCenterBuilder::default()
+            .options(common_opts)
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?
+            .into_handle();
+
+        // the root futures ignore the cancellation request
+        let runtime_handle = tc.start_runtime(
+            TaskKind::PartitionProcessor,
+            "wedged-runtime",
+            Some(PartitionId::MIN),
+            None,
+            || futures::future::pending::<anyhow::Result<()>>(),
+        )?;
+        let long_runtime_handle = tc.start_runtime(
+            TaskKind::PartitionProcessor,
+            "wedged-runtime-with-timeout",
+            Some(PartitionId::MAX),
+            Some(Duration::from_secs(20)),
+            || futures::future::pending::<anyhow::Result<()>>(),
+        )?;
+
+        let start = tokio::time::Instant::now();
+        runtime_handle.cancel();
+        long_runtime_handle.cancel();
+
+        let result = runtime_handle.await;
+        assert!(result.is_err());
+        assert_that!(start.elapsed(), lt(Duration::from_secs(20)));
+
+        let result = long_runtime_handle.await;
+        assert!(result.is_err());
+        assert_that!(start.elapsed(), ge(Duration::from_secs(20)));
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_phase_deadline() -> Result<()> {
+        let mut common_opts = CommonOptions::default();
+        common_opts.shutdown_phases.invoker_timeout = Duration::from_secs(5).into();
+        let tc = TaskCenterBuilder::default()
+            .options(common_opts)
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?
+            .into_handle();
+        // the invoker ignores the cancellation request
+        tc.spawn(TaskKind::Invoker, "invoker", async {
+            futures::future::pending::<()>().await;
+            Ok(())
+        })?;
+        let (tx, rx) = oneshot::channel();
+        tc.spawn(TaskKind::SystemService, "service", async move {
+            cancellation_watcher().await;
+            let _ = tx.send(());
+            Ok(())
+        })?;
+
+        let start = tokio::time::Instant::now();
+        tc.shutdown_node("test", 0).await;
+        assert_that!(start.elapsed(), ge(Duration::from_secs(5)));
+        // the tasks of the later phases are still stopped
+        assert_that!(rx.await, ok(eq(())));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dump_tasks() -> Result<()> {
+        let tc = TaskCenterBuilder::default()
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?
+            .into_handle();
+        let task_id = tc.spawn(TaskKind::RoleRunner, "worker-role", async {
+            cancellation_watcher().await;
+            Ok(())
+        })?;
+
+        let tasks = tc.dump_tasks().await;
+        assert_that!(
+            tasks,
+            elements_are![all![
+                field!(TaskDump.id, eq(task_id)),
+                field!(TaskDump.kind, eq("RoleRunner")),
+                field!(TaskDump.name, eq("worker-role")),
+                field!(TaskDump.cancellation_requested, eq(false)),
+            ]]
+        );
+
+        tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedicated_invoker_runtime() -> Result<()> {
+        let mut common_opts = CommonOptions::default();
+        common_opts.runtimes.invoker = Some(RuntimeOptions {
+            worker_threads: NonZeroUsize::new(1),
+            ..Default::default()
+        });
+        let tc = TaskCenterBuilder::default().options(common_opts).build()?;
+
+        let thread_name = tc.block_on(async {
+            TaskCenter::spawn_unmanaged(TaskKind::Invoker, "invoker", async {
+                std::thread::current().name().map(str::to_owned)
+            })?
+            .await
+        })?;
+        assert_that!(thread_name, some(starts_with("rs:invoker-")));
+        Ok(())
+    }
 }
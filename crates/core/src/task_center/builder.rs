@@ -12,8 +12,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use tracing::error;
 
-use restate_types::config::CommonOptions;
+use restate_types::config::{CommonOptions, RuntimeOptions};
 
+use super::runtime::apply_runtime_options;
 use super::{OwnedHandle, TaskCenterInner};
 
 static WORKER_ID: AtomicUsize = const { AtomicUsize::new(0) };
@@ -88,12 +89,30 @@ impl TaskCenterBuilder {
         }
 
         if self.ingress_runtime_handle.is_none() {
-            let mut ingress_runtime_builder = tokio_builder("ingress", &options);
+            let mut ingress_runtime_builder = match &options.runtimes.ingress {
+                Some(runtime_options) => dedicated_tokio_builder("ingress", runtime_options),
+                None => tokio_builder("ingress", &options),
+            };
             let ingress_runtime = ingress_runtime_builder.build()?;
             self.ingress_runtime_handle = Some(ingress_runtime.handle().clone());
             self.ingress_runtime = Some(ingress_runtime);
         }
 
+        let invoker_runtime = options
+            .runtimes
+            .invoker
+            .as_ref()
+            .map(|runtime_options| dedicated_tokio_builder("invoker", runtime_options).build())
+            .transpose()?;
+        let metadata_store_runtime = options
+            .runtimes
+            .metadata_store
+            .as_ref()
+            .map(|runtime_options| {
+                dedicated_tokio_builder("metadata-store", runtime_options).build()
+            })
+            .transpose()?;
+
         if cfg!(any(test, feature = "test-util")) {
             eprintln!("!!!! Runnning with test-util enabled !!!!");
         }
@@ -102,6 +121,12 @@ impl TaskCenterBuilder {
             self.ingress_runtime_handle.unwrap(),
             self.default_runtime,
             self.ingress_runtime,
+            invoker_runtime,
+            metadata_store_runtime,
+            options.runtimes.partition_processor.clone(),
+            options.runtime_shutdown_timeout.into(),
+            options.shutdown_phases.clone(),
+            options.shutdown_grace_period(),
             self.pause_time,
         )))
     }
@@ -118,3 +143,19 @@ fn tokio_builder(prefix: &'static str, common_opts: &CommonOptions) -> tokio::ru
 
     builder
 }
+
+fn dedicated_tokio_builder(
+    prefix: &'static str,
+    runtime_options: &RuntimeOptions,
+) -> tokio::runtime::Builder {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all().thread_name_fn(move || {
+        let id = WORKER_ID.fetch_add(1, Ordering::Relaxed);
+        format!("rs:{}-{}", prefix, id)
+    });
+
+    builder.worker_threads(runtime_options.worker_threads());
+    apply_runtime_options(&mut builder, runtime_options);
+
+    builder
+}
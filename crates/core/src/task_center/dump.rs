@@ -0,0 +1,106 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use restate_types::identifiers::PartitionId;
+
+use super::{TaskCenterInner, TaskId};
+
+/// Snapshot of a task managed by task-center, e.g. to find out which tasks hold up a shutdown.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskDump {
+    pub id: TaskId,
+    pub kind: &'static str,
+    pub name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_id: Option<PartitionId>,
+    /// RFC 3339 timestamp of when the task was spawned
+    pub spawned_at: String,
+    pub cancellation_requested: bool,
+    /// Async backtrace of the task, only available when built with `--cfg tokio_taskdump`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+}
+
+impl TaskCenterInner {
+    pub(super) async fn dump_tasks(self: &Arc<Self>) -> Vec<TaskDump> {
+        let mut backtraces = self.async_backtraces().await;
+
+        let tasks: Vec<_> = self.managed_tasks.lock().values().cloned().collect();
+        let mut dump: Vec<_> = tasks
+            .into_iter()
+            .map(|task| {
+                let tokio_id = task
+                    .handle
+                    .lock()
+                    .as_ref()
+                    .map(|handle| handle.inner_handle.id());
+                TaskDump {
+                    id: task.id(),
+                    kind: task.kind().into(),
+                    name: task.name(),
+                    partition_id: task.partition_id(),
+                    spawned_at: humantime::format_rfc3339_millis(task.spawned_at).to_string(),
+                    cancellation_requested: task.context.cancellation_token.is_cancelled(),
+                    backtrace: tokio_id.and_then(|id| backtraces.remove(&id)),
+                }
+            })
+            .collect();
+        dump.sort_by_key(|task| task.id);
+        dump
+    }
+
+    #[cfg(tokio_taskdump)]
+    async fn async_backtraces(&self) -> HashMap<tokio::task::Id, String> {
+        const DUMP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+        let mut runtimes = vec![
+            ("default", self.default_runtime_handle.clone()),
+            ("ingress", self.ingress_runtime_handle.clone()),
+        ];
+        runtimes.extend(
+            [
+                ("invoker", &self.invoker_runtime),
+                ("metadata-store", &self.metadata_store_runtime),
+            ]
+            .into_iter()
+            .filter_map(|(name, runtime)| Some((name, runtime.as_ref()?.handle().clone()))),
+        );
+        runtimes.extend(
+            self.managed_runtimes
+                .lock()
+                .iter()
+                .map(|(name, runtime)| (*name, runtime.runtime_handle().clone())),
+        );
+
+        let mut backtraces = HashMap::new();
+        for (name, runtime) in runtimes {
+            // A wedged runtime never reaches the point where its tasks can be traced
+            match tokio::time::timeout(DUMP_TIMEOUT, runtime.dump()).await {
+                Ok(dump) => backtraces.extend(
+                    dump.tasks()
+                        .iter()
+                        .map(|task| (task.id(), task.trace().to_string())),
+                ),
+                Err(_) => tracing::warn!("Timed out dumping the tasks of runtime '{name}'"),
+            }
+        }
+        backtraces
+    }
+
+    #[cfg(not(tokio_taskdump))]
+    async fn async_backtraces(&self) -> HashMap<tokio::task::Id, String> {
+        HashMap::new()
+    }
+}
@@ -11,6 +11,7 @@
 use std::future::Future;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use restate_types::identifiers::PartitionId;
 use tokio_util::sync::CancellationToken;
@@ -19,7 +20,8 @@ use tracing::instrument;
 use crate::{Metadata, ShutdownError};
 
 use super::{
-    RuntimeError, RuntimeTaskHandle, TaskCenterInner, TaskContext, TaskHandle, TaskId, TaskKind,
+    RuntimeError, RuntimeTaskHandle, TaskCenterInner, TaskContext, TaskDump, TaskHandle, TaskId,
+    TaskKind,
 };
 
 #[derive(Clone, derive_more::Debug)]
@@ -82,13 +84,19 @@ impl Handle {
         root_task_kind: TaskKind,
         runtime_name: &'static str,
         partition_id: Option<PartitionId>,
+        shutdown_timeout: Option<Duration>,
         root_future: impl FnOnce() -> F + Send + 'static,
     ) -> Result<RuntimeTaskHandle<anyhow::Result<()>>, RuntimeError>
     where
         F: Future<Output = anyhow::Result<()>> + 'static,
     {
-        self.inner
-            .start_runtime(root_task_kind, runtime_name, partition_id, root_future)
+        self.inner.start_runtime(
+            root_task_kind,
+            runtime_name,
+            partition_id,
+            shutdown_timeout,
+            root_future,
+        )
     }
 
     /// Launch a new task
@@ -146,6 +154,11 @@ impl Handle {
         self.inner.spawn_local(kind, name, future)
     }
 
+    /// Dumps the tasks managed by task-center, sorted by their id.
+    pub async fn dump_tasks(&self) -> Vec<TaskDump> {
+        self.inner.dump_tasks().await
+    }
+
     pub fn metadata(&self) -> Option<Metadata> {
         self.inner.metadata()
     }
@@ -20,6 +20,8 @@ pub trait TaskCenterMonitoring {
 
     fn ingress_runtime_metrics(&self) -> RuntimeMetrics;
 
+    fn dedicated_runtime_metrics(&self) -> Vec<(&'static str, RuntimeMetrics)>;
+
     fn managed_runtime_metrics(&self) -> Vec<(&'static str, RuntimeMetrics)>;
 
     /// How long has the task-center been running?
@@ -38,6 +40,16 @@ impl TaskCenterMonitoring for Handle {
         self.inner.ingress_runtime_handle.metrics()
     }
 
+    fn dedicated_runtime_metrics(&self) -> Vec<(&'static str, RuntimeMetrics)> {
+        [
+            ("invoker", &self.inner.invoker_runtime),
+            ("metadata-store", &self.inner.metadata_store_runtime),
+        ]
+        .into_iter()
+        .filter_map(|(name, runtime)| Some((name, runtime.as_ref()?.metrics())))
+        .collect()
+    }
+
     fn managed_runtime_metrics(&self) -> Vec<(&'static str, RuntimeMetrics)> {
         let guard = self.inner.managed_runtimes.lock();
         guard
@@ -55,6 +67,9 @@ impl TaskCenterMonitoring for Handle {
     fn submit_metrics(&self) {
         submit_runtime_metrics("default", self.default_runtime_metrics());
         submit_runtime_metrics("ingress", self.ingress_runtime_metrics());
+        for (name, metrics) in self.dedicated_runtime_metrics() {
+            submit_runtime_metrics(name, metrics);
+        }
 
         // Partition processor runtimes
         let processor_runtimes = self.managed_runtime_metrics();
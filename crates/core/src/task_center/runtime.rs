@@ -16,6 +16,9 @@ use std::task::{ready, Context, Poll};
 use futures::FutureExt;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use restate_types::config::RuntimeOptions;
 
 /// A handle for a dedicated runtime managed by task-center
 pub struct RuntimeTaskHandle<T> {
@@ -100,3 +103,52 @@ impl OwnedRuntimeHandle {
         self.inner
     }
 }
+
+/// Applies the resource budget of the options to the runtime being built. The number of worker
+/// threads is left to the caller since it doesn't apply to single-threaded runtimes.
+pub(super) fn apply_runtime_options(
+    builder: &mut tokio::runtime::Builder,
+    options: &RuntimeOptions,
+) {
+    if let Some(max_blocking_threads) = options.max_blocking_threads {
+        builder.max_blocking_threads(max_blocking_threads.get());
+    }
+    if !options.cpu_cores.is_empty() {
+        if cfg!(target_os = "linux") {
+            let cpu_cores = options.cpu_cores.clone();
+            builder.on_thread_start(move || pin_current_thread(&cpu_cores));
+        } else {
+            warn!(
+                "Ignoring the CPU cores of the runtime, pinning threads is only supported on Linux"
+            );
+        }
+    }
+}
+
+/// Pins the current thread to the given CPU cores.
+#[cfg(target_os = "linux")]
+pub(super) fn pin_current_thread(cpu_cores: &[usize]) {
+    // SAFETY: cpu_set_t is a plain bitmask for which all zeros is a valid value
+    let mut cpu_set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for cpu_core in cpu_cores {
+        if *cpu_core >= libc::CPU_SETSIZE as usize {
+            warn!("Ignoring the CPU core {cpu_core}, it exceeds the maximum CPU core id");
+            continue;
+        }
+        // SAFETY: the CPU core is within the bounds of the set
+        unsafe { libc::CPU_SET(*cpu_core, &mut cpu_set) };
+    }
+
+    // SAFETY: the set is initialized and its size is passed along, 0 is the current thread
+    let result =
+        unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpu_set) };
+    if result != 0 {
+        warn!(
+            "Failed pinning the thread to the CPU cores {cpu_cores:?}: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn pin_current_thread(_cpu_cores: &[usize]) {}
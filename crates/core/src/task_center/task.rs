@@ -10,6 +10,7 @@
 
 use std::pin::Pin;
 use std::task::{ready, Poll};
+use std::time::SystemTime;
 
 use futures::FutureExt;
 use parking_lot::Mutex;
@@ -88,6 +89,7 @@ impl TaskContext {
 
 pub(super) struct Task<R = ()> {
     pub(super) context: TaskContext,
+    pub(super) spawned_at: SystemTime,
     pub(super) handle: Mutex<Option<TaskHandle<R>>>,
 }
 
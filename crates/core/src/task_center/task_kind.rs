@@ -26,6 +26,7 @@ static NEXT_TASK_ID: AtomicU64 = const { AtomicU64::new(1) };
     derive_more::Display,
     derive_more::From,
     derive_more::Into,
+    serde::Serialize,
 )]
 pub struct TaskId(u64);
 
@@ -57,6 +58,10 @@ impl TaskId {
 ///   * `OnError`  - What to do if the task returned Err(_)
 ///     - `log`                   - Log an error
 ///     - `shutdown` (default)    - Shutdown the node (task center global shutdown)
+///
+///   * `ShutdownPhase` - In which [`ShutdownPhase`] the task is cancelled on shutdown:
+///     - `ingress`, `invoker`, `partition-processors`, `bifrost`
+///     - `other` (default)
 #[derive(
     Clone,
     Copy,
@@ -89,48 +94,78 @@ pub enum TaskKind {
     RpcResponse,
     /// A type for ingress until we start enforcing timeouts for inflight requests. This enables us
     /// to shut down cleanly without waiting indefinitely.
-    #[strum(props(OnCancel = "abort", runtime = "ingress"))]
+    #[strum(props(OnCancel = "abort", runtime = "ingress", ShutdownPhase = "ingress"))]
     IngressServer,
     RoleRunner,
     SystemService,
-    #[strum(props(OnCancel = "abort", runtime = "ingress"))]
+    #[strum(props(OnCancel = "abort", runtime = "ingress", ShutdownPhase = "ingress"))]
     Ingress,
     /// Kafka ingestion related task
+    #[strum(props(ShutdownPhase = "ingress"))]
     Kafka,
+    /// SQS ingestion related task
+    #[strum(props(ShutdownPhase = "ingress"))]
+    SqsIngress,
+    #[strum(props(ShutdownPhase = "partition-processors"))]
     PartitionProcessor,
+    /// Runs the invoker of a partition processor. Runs on the runtime of the partition processor
+    /// unless a dedicated invoker runtime is configured.
+    #[strum(props(runtime = "invoker", ShutdownPhase = "invoker"))]
+    Invoker,
     /// Longer-running, low-priority tasks that is responsible for the export, and potentially
     /// upload to remote storage, of partition store snapshots.
-    #[strum(props(OnCancel = "abort", OnError = "log"))]
+    #[strum(props(
+        OnCancel = "abort",
+        OnError = "log",
+        ShutdownPhase = "partition-processors"
+    ))]
     PartitionSnapshotProducer,
     #[strum(props(OnError = "log"))]
     ConnectionReactor,
+    #[strum(props(ShutdownPhase = "partition-processors"))]
     Shuffle,
+    #[strum(props(ShutdownPhase = "partition-processors"))]
     Cleaner,
+    /// Delivers the prepared messages of a partition to their destination partitions.
+    #[strum(props(ShutdownPhase = "partition-processors"))]
+    PreparedMessageReconciler,
+    #[strum(props(runtime = "metadata-store"))]
     MetadataStore,
     Background,
     // -- Bifrost Tasks
     /// A background task that the system needs for its operation. The task requires a system
     /// shutdown on errors and the system will wait for its graceful cancellation on shutdown.
-    #[strum(props(runtime = "default"))]
+    #[strum(props(runtime = "default", ShutdownPhase = "bifrost"))]
     BifrostBackgroundHighPriority,
-    #[strum(props(OnCancel = "abort", runtime = "default"))]
+    #[strum(props(OnCancel = "abort", runtime = "default", ShutdownPhase = "bifrost"))]
     BifrostBackgroundLowPriority,
     /// A background appender. The task will log on errors but the system will wait for its
     /// graceful cancellation on shutdown.
-    #[strum(props(OnCancel = "wait", OnError = "log"))]
+    #[strum(props(OnCancel = "wait", OnError = "log", ShutdownPhase = "bifrost"))]
     BifrostAppender,
     #[strum(props(OnCancel = "abort", OnError = "log"))]
     Disposable,
+    /// Reads records ahead of a log read stream's consumer. Runs on the default runtime to
+    /// decouple reading and decoding from the consumer's runtime.
+    #[strum(props(
+        OnCancel = "abort",
+        OnError = "log",
+        runtime = "default",
+        ShutdownPhase = "bifrost"
+    ))]
+    BifrostReadAhead,
+    #[strum(props(ShutdownPhase = "bifrost"))]
     LogletProvider,
-    #[strum(props(OnCancel = "abort"))]
+    #[strum(props(OnCancel = "abort", ShutdownPhase = "bifrost"))]
     Watchdog,
-    #[strum(props(OnCancel = "wait", runtime = "default"))]
+    #[strum(props(OnCancel = "wait", runtime = "default", ShutdownPhase = "bifrost"))]
     SequencerAppender,
     // -- Replicated loglet tasks
     /// Receives messages from remote sequencers on nodes with local sequencer. This is also used
     /// in remote sequencer to handle responses of rpc messages.
     #[strum(props(OnCancel = "abort", runtime = "default"))]
     NetworkMessageHandler,
+    #[strum(props(ShutdownPhase = "bifrost"))]
     ReplicatedLogletReadStream,
     #[strum(props(OnCancel = "abort"))]
     // -- Log-server tasks
@@ -158,16 +193,49 @@ impl TaskKind {
         self.get_str("OnError").unwrap_or("shutdown")
     }
 
+    pub fn shutdown_phase(&self) -> ShutdownPhase {
+        match self.get_str("ShutdownPhase").unwrap_or("other") {
+            "ingress" => ShutdownPhase::Ingress,
+            "invoker" => ShutdownPhase::Invoker,
+            "partition-processors" => ShutdownPhase::PartitionProcessors,
+            "bifrost" => ShutdownPhase::Bifrost,
+            "other" => ShutdownPhase::Other,
+            _ => panic!("Invalid shutdown phase for task kind: {}", self),
+        }
+    }
+
     pub fn runtime(&self) -> AsyncRuntime {
         match self.get_str("runtime").unwrap_or("inherit") {
             "inherit" => AsyncRuntime::Inherit,
             "default" => AsyncRuntime::Default,
             "ingress" => AsyncRuntime::Ingress,
+            "invoker" => AsyncRuntime::Invoker,
+            "metadata-store" => AsyncRuntime::MetadataStore,
             _ => panic!("Invalid runtime for task kind: {}", self),
         }
     }
 }
 
+/// Phases of the shutdown of a node, in the order in which they run. The tasks of a phase are
+/// cancelled once the tasks of the previous phase have stopped or the deadline of the previous
+/// phase has been exceeded.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, strum::IntoStaticStr, strum::Display, strum::EnumIter,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ShutdownPhase {
+    /// Stops accepting new requests
+    Ingress,
+    /// Stops the in-flight invocations
+    Invoker,
+    /// Stops the partition processors and their runtimes
+    PartitionProcessors,
+    /// Flushes the pending appends and stops the loglets
+    Bifrost,
+    /// Stops all the remaining tasks, like the networking and the metadata store
+    Other,
+}
+
 pub enum FailureBehaviour {
     Shutdown,
 }
@@ -180,4 +248,8 @@ pub enum AsyncRuntime {
     Default,
     /// Run on ingress runtime
     Ingress,
+    /// Run on the dedicated invoker runtime if configured, otherwise inherit
+    Invoker,
+    /// Run on the dedicated metadata store runtime if configured, otherwise inherit
+    MetadataStore,
 }
@@ -83,6 +83,8 @@ pub enum SnapshotError {
     SnapshotExportError(PartitionId, #[source] anyhow::Error),
     #[error("Snapshot failed for partition {0}: {1}")]
     SnapshotMetadataHeaderError(PartitionId, #[source] io::Error),
+    #[error("Snapshot upload failed for partition {0}: {1}")]
+    RepositoryError(PartitionId, #[source] anyhow::Error),
     #[error("Internal error creating snapshot for partition {0}: {1}")]
     Internal(PartitionId, String),
 }
@@ -95,7 +97,28 @@ impl SnapshotError {
             SnapshotError::InvalidState(partition_id) => *partition_id,
             SnapshotError::SnapshotExportError(partition_id, _) => *partition_id,
             SnapshotError::SnapshotMetadataHeaderError(partition_id, _) => *partition_id,
+            SnapshotError::RepositoryError(partition_id, _) => *partition_id,
             SnapshotError::Internal(partition_id, _) => *partition_id,
         }
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum SplitPartitionError {
+    #[error("Partition {0} not found")]
+    PartitionNotFound(PartitionId),
+    #[error("Partition processor state does not allow splitting partition {0}")]
+    InvalidState(PartitionId),
+    #[error("Partition {0} is not being split off from partition {1}")]
+    NotSplitting(PartitionId, PartitionId),
+    /// The split was started but it is not known whether it has been applied. The split must not
+    /// be aborted in this case but can be completed by retrying it.
+    #[error("Split of partition {0} did not complete: {1}")]
+    Incomplete(PartitionId, String),
+    #[error(transparent)]
+    Snapshot(#[from] SnapshotError),
+    #[error("Failed creating the partition store for partition {0}: {1}")]
+    Storage(PartitionId, #[source] anyhow::Error),
+    #[error("Internal error splitting partition {0}: {1}")]
+    Internal(PartitionId, String),
+}
@@ -36,8 +36,8 @@ mod helper;
 
 declare_restate_error_codes!(
     RT0001, RT0002, RT0003, RT0004, RT0005, RT0006, RT0007, RT0009, RT0010, RT0011, RT0012, RT0013,
-    RT0014, RT0015, META0003, META0004, META0005, META0006, META0009, META0010, META0011, META0012,
-    META0013, META0014, META0015
+    RT0014, RT0015, RT0016, RT0017, RT0018, META0003, META0004, META0005, META0006, META0009,
+    META0010, META0011, META0012, META0013, META0014, META0015
 );
 
 // -- Some commonly used errors
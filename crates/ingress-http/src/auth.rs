@@ -0,0 +1,521 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Authentication and authorization of ingress requests, see [`IngressAuthOptions`].
+//!
+//! Requests carry a bearer token, which is either one of the static API keys or a JWT signed by
+//! one of the keys of the JSON Web Key Set (JWKS) of the configured issuer. The JWKS is fetched
+//! in the background by [`run_jwks_refresh`], so that rotated keys are picked up.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use http::{header, HeaderMap};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use restate_core::cancellation_watcher;
+use restate_types::config::{
+    IngressAuthOptions, JwtAlgorithm, JwtValidationOptions, ServiceAccessRule,
+};
+
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Retry interval of the JWKS fetch until the first key set has been loaded.
+const JWKS_INITIAL_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// Tolerated clock skew when validating the time based claims of a JWT.
+const JWT_LEEWAY_SECONDS: u64 = 60;
+
+/// The authenticated identity of a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Principal {
+    ApiKey(String),
+    Jwt {
+        subject: Option<String>,
+        scopes: Vec<String>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AuthError {
+    #[error("missing bearer token")]
+    MissingToken,
+    #[error("invalid credential")]
+    InvalidCredential,
+    #[error("invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("token signed with the algorithm {0:?}, which is not accepted")]
+    AlgorithmNotAccepted(Algorithm),
+    #[error("token signed with unknown key")]
+    UnknownKey,
+    #[error("the signing keys of the token issuer have not been loaded yet")]
+    KeysUnavailable,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+    #[serde(default)]
+    sub: Option<String>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenIdConfiguration {
+    jwks_uri: String,
+}
+
+/// Authenticates requests and authorizes them to invoke services.
+pub(crate) struct Authenticator {
+    api_keys: Vec<(String, String)>,
+    jwt: Option<JwtValidator>,
+    services: HashMap<String, ServiceAccessRule>,
+}
+
+impl Authenticator {
+    pub(crate) fn new(options: &IngressAuthOptions) -> Self {
+        Self {
+            api_keys: options
+                .api_keys
+                .iter()
+                .map(|api_key| (api_key.key.clone(), api_key.name.clone()))
+                .collect(),
+            jwt: options.jwt.clone().map(JwtValidator::new),
+            services: options.services.clone(),
+        }
+    }
+
+    pub(crate) fn has_jwt_validation(&self) -> bool {
+        self.jwt.is_some()
+    }
+
+    pub(crate) fn has_service_rules(&self) -> bool {
+        !self.services.is_empty()
+    }
+
+    pub(crate) fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, AuthError> {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or(AuthError::MissingToken)?;
+
+        if let Some((_, name)) = self
+            .api_keys
+            .iter()
+            .find(|(key, _)| constant_time_eq(key.as_bytes(), token.as_bytes()))
+        {
+            return Ok(Principal::ApiKey(name.clone()));
+        }
+
+        match &self.jwt {
+            Some(jwt) => jwt.validate(token),
+            None => Err(AuthError::InvalidCredential),
+        }
+    }
+
+    /// Returns true if the principal is allowed to invoke the given service.
+    pub(crate) fn is_allowed(&self, principal: &Principal, service_name: &str) -> bool {
+        let Some(rule) = self.services.get(service_name) else {
+            return true;
+        };
+
+        match principal {
+            Principal::ApiKey(name) => rule.api_keys.contains(name),
+            Principal::Jwt { subject, scopes } => {
+                subject
+                    .as_ref()
+                    .is_some_and(|subject| rule.subjects.contains(subject))
+                    || scopes.iter().any(|scope| rule.scopes.contains(scope))
+            }
+        }
+    }
+}
+
+struct JwtValidator {
+    options: JwtValidationOptions,
+    algorithms: Vec<Algorithm>,
+    keys: ArcSwap<Vec<(Option<String>, DecodingKey)>>,
+}
+
+impl JwtValidator {
+    fn new(options: JwtValidationOptions) -> Self {
+        Self {
+            algorithms: options.algorithms.iter().copied().map(algorithm).collect(),
+            options,
+            keys: Default::default(),
+        }
+    }
+
+    fn validate(&self, token: &str) -> Result<Principal, AuthError> {
+        let header = jsonwebtoken::decode_header(token)?;
+        // Never trust the algorithm of the header alone, it must be one of the configured ones
+        if !self.algorithms.contains(&header.alg) {
+            return Err(AuthError::AlgorithmNotAccepted(header.alg));
+        }
+
+        let keys = self.keys.load();
+        if keys.is_empty() {
+            return Err(AuthError::KeysUnavailable);
+        }
+        let key = match &header.kid {
+            Some(kid) => keys
+                .iter()
+                .find(|(key_id, _)| key_id.as_ref() == Some(kid))
+                .map(|(_, key)| key),
+            // Tokens without key id can only be validated if there is no ambiguity
+            None if keys.len() == 1 => Some(&keys[0].1),
+            None => None,
+        }
+        .ok_or(AuthError::UnknownKey)?;
+
+        // The algorithm must also belong to the family of the key, which is checked when decoding
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = JWT_LEEWAY_SECONDS;
+        validation.set_issuer(&[&self.options.issuer]);
+        if self.options.audience.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.options.audience);
+        }
+
+        let claims = jsonwebtoken::decode::<Claims>(token, key, &validation)?.claims;
+        Ok(Principal::Jwt {
+            subject: claims.sub,
+            scopes: claims
+                .scope
+                .map(|scope| scope.split_whitespace().map(str::to_owned).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Replaces the signing keys with the ones of the given set, returning the number of keys.
+    fn update_keys(&self, jwk_set: &JwkSet) -> usize {
+        let keys: Vec<_> = jwk_set
+            .keys
+            .iter()
+            .filter_map(|jwk| match DecodingKey::from_jwk(jwk) {
+                Ok(key) => Some((jwk.common.key_id.clone(), key)),
+                Err(err) => {
+                    debug!(
+                        "Ignoring key {:?} of the JSON Web Key Set: {}",
+                        jwk.common.key_id, err
+                    );
+                    None
+                }
+            })
+            .collect();
+        let len = keys.len();
+        self.keys.store(Arc::new(keys));
+        len
+    }
+
+    async fn fetch_jwk_set(
+        &self,
+        client: &reqwest::Client,
+        jwks_uri: &mut Option<String>,
+    ) -> anyhow::Result<JwkSet> {
+        let uri = match jwks_uri {
+            Some(uri) => uri.clone(),
+            None => {
+                let discovery_uri = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.options.issuer.trim_end_matches('/')
+                );
+                let configuration: OpenIdConfiguration = client
+                    .get(discovery_uri)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                jwks_uri.insert(configuration.jwks_uri).clone()
+            }
+        };
+
+        Ok(client
+            .get(uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// Periodically fetches the JSON Web Key Set of the JWT issuer, until the node shuts down.
+pub(crate) async fn run_jwks_refresh(authenticator: Arc<Authenticator>) -> anyhow::Result<()> {
+    let Some(jwt) = &authenticator.jwt else {
+        return Ok(());
+    };
+    let client = reqwest::Client::builder()
+        .timeout(JWKS_FETCH_TIMEOUT)
+        .build()?;
+    let mut jwks_uri = jwt.options.jwks_uri.as_ref().map(ToString::to_string);
+
+    let shutdown = cancellation_watcher();
+    tokio::pin!(shutdown);
+
+    loop {
+        match jwt.fetch_jwk_set(&client, &mut jwks_uri).await {
+            Ok(jwk_set) => {
+                let keys = jwt.update_keys(&jwk_set);
+                debug!(
+                    "Loaded {} signing keys of the JWT issuer '{}'",
+                    keys, jwt.options.issuer
+                );
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to fetch the JSON Web Key Set of the JWT issuer '{}', keeping the previous keys: {}",
+                    jwt.options.issuer, err
+                );
+            }
+        }
+
+        let next_refresh = if jwt.keys.load().is_empty() {
+            JWKS_INITIAL_RETRY_INTERVAL
+        } else {
+            jwt.options.jwks_refresh_interval()
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(next_refresh) => {}
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+fn algorithm(algorithm: JwtAlgorithm) -> Algorithm {
+    match algorithm {
+        JwtAlgorithm::HS256 => Algorithm::HS256,
+        JwtAlgorithm::HS384 => Algorithm::HS384,
+        JwtAlgorithm::HS512 => Algorithm::HS512,
+        JwtAlgorithm::RS256 => Algorithm::RS256,
+        JwtAlgorithm::RS384 => Algorithm::RS384,
+        JwtAlgorithm::RS512 => Algorithm::RS512,
+        JwtAlgorithm::PS256 => Algorithm::PS256,
+        JwtAlgorithm::PS384 => Algorithm::PS384,
+        JwtAlgorithm::PS512 => Algorithm::PS512,
+        JwtAlgorithm::ES256 => Algorithm::ES256,
+        JwtAlgorithm::ES384 => Algorithm::ES384,
+        JwtAlgorithm::EdDSA => Algorithm::EdDSA,
+    }
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde::Serialize;
+
+    const ISSUER: &str = "https://issuer.example.com";
+    const SECRET: &[u8] = b"my-very-secret-signing-key";
+
+    #[derive(Serialize)]
+    struct TestClaims<'a> {
+        iss: &'a str,
+        aud: &'a str,
+        sub: &'a str,
+        scope: &'a str,
+        exp: u64,
+    }
+
+    fn authenticator() -> Authenticator {
+        let options: IngressAuthOptions = serde_json::from_value(serde_json::json!({
+            "api-keys": [{"name": "frontend", "key": "secret-key"}],
+            "jwt": {"issuer": ISSUER, "audience": ["restate"], "algorithms": ["HS256"]},
+            "services": {
+                "Greeter": {"api-keys": ["frontend"]},
+                "Admin": {"subjects": ["admin"], "scopes": ["admin:write"]},
+            }
+        }))
+        .unwrap();
+        let authenticator = Authenticator::new(&options);
+
+        let jwk_set: JwkSet = serde_json::from_value(serde_json::json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": "key-1",
+                "alg": "HS256",
+                "k": base64_url(SECRET),
+            }]
+        }))
+        .unwrap();
+        assert_eq!(authenticator.jwt.as_ref().unwrap().update_keys(&jwk_set), 1);
+        authenticator
+    }
+
+    fn base64_url(input: &[u8]) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(input)
+    }
+
+    fn token(kid: &str, iss: &str, aud: &str, sub: &str, scope: &str) -> String {
+        token_with_algorithm(Algorithm::HS256, kid, iss, aud, sub, scope)
+    }
+
+    fn token_with_algorithm(
+        alg: Algorithm,
+        kid: &str,
+        iss: &str,
+        aud: &str,
+        sub: &str,
+        scope: &str,
+    ) -> String {
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let header = Header {
+            kid: Some(kid.to_owned()),
+            ..Header::new(alg)
+        };
+        jsonwebtoken::encode(
+            &header,
+            &TestClaims {
+                iss,
+                aud,
+                sub,
+                scope,
+                exp,
+            },
+            &EncodingKey::from_secret(SECRET),
+        )
+        .unwrap()
+    }
+
+    fn headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn authenticate_api_key() {
+        let authenticator = authenticator();
+
+        assert_eq!(
+            authenticator.authenticate(&headers("secret-key")).unwrap(),
+            Principal::ApiKey("frontend".to_owned())
+        );
+        assert!(matches!(
+            authenticator.authenticate(&HeaderMap::new()),
+            Err(AuthError::MissingToken)
+        ));
+        assert!(authenticator.authenticate(&headers("other-key")).is_err());
+    }
+
+    #[test]
+    fn authenticate_jwt() {
+        let authenticator = authenticator();
+
+        let principal = authenticator
+            .authenticate(&headers(&token(
+                "key-1",
+                ISSUER,
+                "restate",
+                "admin",
+                "admin:read admin:write",
+            )))
+            .unwrap();
+        assert_eq!(
+            principal,
+            Principal::Jwt {
+                subject: Some("admin".to_owned()),
+                scopes: vec!["admin:read".to_owned(), "admin:write".to_owned()],
+            }
+        );
+
+        // Wrong issuer, audience or key
+        assert!(authenticator
+            .authenticate(&headers(&token(
+                "key-1",
+                "https://other.example.com",
+                "restate",
+                "admin",
+                ""
+            )))
+            .is_err());
+        assert!(authenticator
+            .authenticate(&headers(&token("key-1", ISSUER, "other", "admin", "")))
+            .is_err());
+        assert!(matches!(
+            authenticator.authenticate(&headers(&token("key-2", ISSUER, "restate", "admin", ""))),
+            Err(AuthError::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn jwt_with_algorithm_not_accepted() {
+        let authenticator = authenticator();
+
+        // Signed with the right key, but with an algorithm which isn't configured
+        assert!(matches!(
+            authenticator.authenticate(&headers(&token_with_algorithm(
+                Algorithm::HS512,
+                "key-1",
+                ISSUER,
+                "restate",
+                "admin",
+                ""
+            ))),
+            Err(AuthError::AlgorithmNotAccepted(Algorithm::HS512))
+        ));
+    }
+
+    #[test]
+    fn jwt_without_keys() {
+        let options: IngressAuthOptions = serde_json::from_value(
+            serde_json::json!({"jwt": {"issuer": ISSUER, "algorithms": ["HS256"]}}),
+        )
+        .unwrap();
+        let authenticator = Authenticator::new(&options);
+
+        assert!(matches!(
+            authenticator.authenticate(&headers(&token("key-1", ISSUER, "restate", "admin", ""))),
+            Err(AuthError::KeysUnavailable)
+        ));
+    }
+
+    #[test]
+    fn service_access_rules() {
+        let authenticator = authenticator();
+        let api_key = Principal::ApiKey("frontend".to_owned());
+        let user = Principal::Jwt {
+            subject: Some("user".to_owned()),
+            scopes: vec!["admin:write".to_owned()],
+        };
+
+        assert!(authenticator.is_allowed(&api_key, "Greeter"));
+        assert!(!authenticator.is_allowed(&user, "Greeter"));
+        assert!(!authenticator.is_allowed(&api_key, "Admin"));
+        assert!(authenticator.is_allowed(&user, "Admin"));
+
+        // Services without rules are open to all the authenticated principals
+        assert!(authenticator.is_allowed(&api_key, "Other"));
+        assert!(authenticator.is_allowed(&user, "Other"));
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use http::{header, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Full};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+use restate_types::identifiers::{InvocationId, WithInvocationId};
+use restate_types::invocation::{
+    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
+    WorkflowHandlerType,
+};
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+
+use super::{Handler, HandlerError, APPLICATION_JSON};
+use crate::auth::Principal;
+use crate::rate_limit::RateLimitPermit;
+use crate::RequestDispatcher;
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchSendRequest {
+    pub(crate) invocations: Vec<BatchSendItem>,
+}
+
+/// A send-only invocation of the batch.
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchSendItem {
+    pub(crate) service: String,
+    pub(crate) handler: String,
+    /// Key of the virtual object or workflow, must be unset for services.
+    #[serde(default)]
+    pub(crate) key: Option<String>,
+    #[serde(default)]
+    pub(crate) idempotency_key: Option<String>,
+    #[serde(default)]
+    pub(crate) headers: HashMap<String, String>,
+    /// JSON input of the handler. If unset, the handler is invoked without input.
+    #[serde(default)]
+    pub(crate) body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchSendResponse {
+    pub(crate) invocations: Vec<BatchSendResult>,
+}
+
+/// Result of each item of the batch, in the order of the request.
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(Deserialize))]
+#[serde(untagged)]
+pub(crate) enum BatchSendResult {
+    Accepted {
+        #[serde(rename = "invocationId")]
+        invocation_id: InvocationId,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    /// Appends a batch of send-only invocations. Invalid items are reported in the response,
+    /// without failing the other items of the batch.
+    pub(crate) async fn handle_batch_send<B: http_body::Body>(
+        self,
+        req: Request<B>,
+    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    where
+        <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
+    {
+        if req.method() != Method::POST {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+
+        let principal = req.extensions().get::<Principal>().cloned();
+        let body = req
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| HandlerError::Body(e.into()))?
+            .to_bytes();
+        let batch: BatchSendRequest =
+            serde_json::from_slice(&body).map_err(HandlerError::BadBatchRequest)?;
+        trace!(
+            "Processing batch of {} invocations",
+            batch.invocations.len()
+        );
+
+        let mut results = Vec::with_capacity(batch.invocations.len());
+        let mut invocation_requests = Vec::with_capacity(batch.invocations.len());
+        // Index of the result of each of the invocation requests
+        let mut result_indexes = Vec::with_capacity(batch.invocations.len());
+        // Keep the permits until the batch is appended, to count the invocations as in-flight
+        let mut permits = Vec::new();
+
+        for item in batch.invocations {
+            match self.prepare_batch_item(item, principal.as_ref()) {
+                Ok((invocation_request, permit)) => {
+                    result_indexes.push(results.len());
+                    results.push(BatchSendResult::Accepted {
+                        invocation_id: invocation_request.invocation_id(),
+                    });
+                    invocation_requests.push(invocation_request);
+                    permits.extend(permit);
+                }
+                Err(err) => results.push(BatchSendResult::Failed {
+                    error: err.to_string(),
+                }),
+            }
+        }
+
+        let send_results = self.dispatcher.send_batch(invocation_requests).await;
+        drop(permits);
+
+        for (idx, send_result) in result_indexes.into_iter().zip(send_results) {
+            if let Err(err) = send_result {
+                debug!("Failed to append invocation of the batch: {}", err);
+                results[idx] = BatchSendResult::Failed {
+                    error: HandlerError::from(err).to_string(),
+                };
+            }
+        }
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, APPLICATION_JSON)
+            .body(Full::new(
+                serde_json::to_vec(&BatchSendResponse {
+                    invocations: results,
+                })
+                .expect("Serializing the BatchSendResponse must not fail")
+                .into(),
+            ))
+            .unwrap())
+    }
+
+    fn prepare_batch_item(
+        &self,
+        item: BatchSendItem,
+        principal: Option<&Principal>,
+    ) -> Result<(InvocationRequest, Option<RateLimitPermit>), HandlerError> {
+        let BatchSendItem {
+            service,
+            handler,
+            key,
+            idempotency_key,
+            headers,
+            body,
+        } = item;
+
+        // Every item is authorized and rate limited like a single request to its service
+        if let (Some(authenticator), Some(principal)) = (&self.authenticator, principal) {
+            if !authenticator.is_allowed(principal, &service) {
+                return Err(HandlerError::Forbidden(service));
+            }
+        }
+
+        let invocation_target_meta = self
+            .schemas
+            .pinned()
+            .resolve_latest_invocation_target(&service, &handler)
+            .ok_or_else(|| {
+                HandlerError::ServiceHandlerNotFound(service.clone(), handler.clone())
+            })?;
+        if !invocation_target_meta.public {
+            return Err(HandlerError::PrivateService);
+        }
+        if idempotency_key.is_some()
+            && invocation_target_meta.target_ty
+                == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+        {
+            return Err(HandlerError::UnsupportedIdempotencyKey);
+        }
+
+        let invocation_target = match (invocation_target_meta.target_ty, key) {
+            (InvocationTargetType::Service, None) => {
+                InvocationTarget::service(&*service, &*handler)
+            }
+            (InvocationTargetType::VirtualObject(handler_ty), Some(key)) => {
+                InvocationTarget::virtual_object(&*service, key, &*handler, handler_ty)
+            }
+            (InvocationTargetType::Workflow(handler_ty), Some(key)) => {
+                InvocationTarget::workflow(&*service, key, &*handler, handler_ty)
+            }
+            _ => return Err(HandlerError::BadBatchItemKey),
+        };
+
+        let body = body
+            .map(|body| {
+                Bytes::from(serde_json::to_vec(&body).expect("Serializing JSON must not fail"))
+            })
+            .unwrap_or_default();
+        invocation_target_meta
+            .input_rules
+            .validate((!body.is_empty()).then_some("application/json"), &body)?;
+
+        let permit = self
+            .rate_limiter
+            .as_ref()
+            .map(|rate_limiter| rate_limiter.try_admit(Some(&service)))
+            .transpose()
+            .map_err(HandlerError::RateLimited)?;
+
+        let invocation_id = InvocationId::generate(&invocation_target, idempotency_key.as_deref());
+        let mut invocation_request_header =
+            InvocationRequestHeader::initialize(invocation_id, invocation_target);
+        invocation_request_header.completion_retention_duration =
+            invocation_target_meta.compute_retention(idempotency_key.is_some());
+        invocation_request_header.idempotency_key = idempotency_key.map(ByteString::from);
+        invocation_request_header.headers = headers
+            .iter()
+            .map(|(name, value)| Header::new(name.as_str(), value.as_str()))
+            .collect();
+        invocation_request_header.shared_concurrency_limit =
+            invocation_target_meta.shared_concurrency_limit;
+
+        Ok((
+            InvocationRequest::new(invocation_request_header, body),
+            permit,
+        ))
+    }
+}
@@ -10,6 +10,7 @@
 
 use super::APPLICATION_JSON;
 
+use crate::auth::AuthError;
 use crate::RequestDispatcherError;
 use bytes::Bytes;
 use http::{header, Response, StatusCode};
@@ -17,6 +18,7 @@ use restate_types::errors::{IdDecodeError, InvocationError};
 use restate_types::schema::invocation_target::InputValidationError;
 use serde::Serialize;
 use std::string;
+use std::time::Duration;
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum HandlerError {
@@ -44,12 +46,28 @@ pub(crate) enum HandlerError {
     "bad path, expected either /restate/workflow/:workflow_name/:workflow_key/output or /restate/workflow/:workflow_name/:workflow_key/attach"
     )]
     BadWorkflowPath,
+    #[error("bad path, expected /restate/webhooks/:subscription_id")]
+    BadWebhookPath,
     #[error("not implemented")]
     NotImplemented,
     #[error("bad header {0}: {1:?}")]
     BadHeader(header::HeaderName, #[source] header::ToStrError),
     #[error("bad delay query parameter, must be a ISO8601 duration: {0}")]
     BadDelayDuration(String),
+    #[error("bad at query parameter, must be a local date time with a timezone, e.g. '2025-03-30T09:00:00[Europe/Berlin]': {0}")]
+    BadExecutionTime(String),
+    #[error("bad priority query parameter, must be a 32 bit signed integer: {0}")]
+    BadPriority(String),
+    #[error("bad deadline query parameter, must be a local date time with a timezone, e.g. '2025-03-30T09:00:00[Europe/Berlin]': {0}")]
+    BadDeadline(String),
+    #[error("cannot use the delay and the at query parameters together")]
+    DelayAndExecutionTime,
+    #[error("bad x-restate-cron header, must be a cron expression optionally followed by a timezone, e.g. '0 9 * * MON-FRI[Europe/Berlin]': {0}")]
+    BadCron(String),
+    #[error(
+        "bad x-restate-cron-misfire header '{0}', must be either 'skip', 'fire-once' or 'fire-all'"
+    )]
+    BadMisfirePolicy(String),
     #[error("bad path, cannot decode key: {0:?}")]
     UrlDecodingError(string::FromUtf8Error),
     #[error("the invoked service is not public")]
@@ -70,10 +88,16 @@ pub(crate) enum HandlerError {
     Invocation(InvocationError),
     #[error("input validation error: {0}")]
     InputValidation(#[from] InputValidationError),
+    #[error("cannot transcode the JSON body to the protobuf message '{0}': {1}")]
+    BadProtobufInput(String, serde_json::Error),
     #[error(
-        "cannot use the delay query parameter with calls. The delay is supported only with sends"
+        "cannot use the delay or at query parameters with calls. Scheduling is supported only with sends"
     )]
     UnsupportedDelay,
+    #[error(
+        "cannot use the x-restate-cron header with calls, workflow handlers, the idempotency key, or the delay and at query parameters"
+    )]
+    UnsupportedCron,
     #[error(
     "cannot use the idempotency key with workflow handlers. The handler invocation will already be idempotent by the workflow key itself."
     )]
@@ -84,6 +108,22 @@ pub(crate) enum HandlerError {
     BadInvocationId(String, IdDecodeError),
     #[error("dispatcher error: {0}")]
     DispatcherError(#[from] RequestDispatcherError),
+    #[error("unauthorized: {0}")]
+    Unauthorized(#[from] AuthError),
+    #[error("not allowed to invoke service '{0}'")]
+    Forbidden(String),
+    #[error("too many requests, retry after {0:?}")]
+    RateLimited(Duration),
+    #[error("bad batch request body: {0}")]
+    BadBatchRequest(serde_json::Error),
+    #[error("the key must be set for virtual object and workflow handlers only")]
+    BadBatchItemKey,
+    #[error("bad webhook signature: {0}")]
+    BadWebhookSignature(&'static str),
+    #[error("bad webhook event: {0}")]
+    BadWebhookEvent(String),
+    #[error("the webhook event exceeds the limit of {0} bytes")]
+    WebhookEventTooLarge(usize),
 }
 
 // IMPORTANT! If you touch this, please update crates/types/src/schema/openapi.rs too
@@ -102,11 +142,8 @@ pub enum ErrorResponse {
 }
 
 impl HandlerError {
-    pub(crate) fn fill_builder<B: http_body::Body + Default + From<Bytes>>(
-        self,
-        res_builder: http::response::Builder,
-    ) -> Response<B> {
-        let status_code = match &self {
+    pub(crate) fn status_code(&self) -> StatusCode {
+        match self {
             HandlerError::NotFound
             | HandlerError::ServiceNotFound(_)
             | HandlerError::ServiceHandlerNotFound(_, _)
@@ -115,20 +152,44 @@ impl HandlerError {
             | HandlerError::PrivateService
             | HandlerError::UrlDecodingError(_)
             | HandlerError::BadDelayDuration(_)
+            | HandlerError::BadExecutionTime(_)
+            | HandlerError::BadPriority(_)
+            | HandlerError::BadDeadline(_)
+            | HandlerError::DelayAndExecutionTime
             | HandlerError::BadAwakeablesPath
             | HandlerError::UnsupportedDelay
+            | HandlerError::BadCron(_)
+            | HandlerError::BadMisfirePolicy(_)
+            | HandlerError::UnsupportedCron
             | HandlerError::BadHeader(_, _)
             | HandlerError::BadAwakeableId(_, _)
             | HandlerError::BadInvocationPath
             | HandlerError::BadInvocationId(_, _)
             | HandlerError::BadWorkflowPath
             | HandlerError::InputValidation(_)
+            | HandlerError::BadProtobufInput(_, _)
             | HandlerError::UnsupportedIdempotencyKey
+            | HandlerError::BadBatchRequest(_)
+            | HandlerError::BadBatchItemKey
+            | HandlerError::BadWebhookPath
+            | HandlerError::BadWebhookEvent(_)
             | HandlerError::UnsupportedGetOutput => StatusCode::BAD_REQUEST,
+            HandlerError::DispatcherError(RequestDispatcherError::Overloaded(_)) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
             HandlerError::DispatcherError(_) => {
                 // TODO add more distinctions between different dispatcher errors (unavailable, etc)
                 StatusCode::INTERNAL_SERVER_ERROR
             }
+            HandlerError::Unauthorized(AuthError::KeysUnavailable) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            HandlerError::Unauthorized(_) | HandlerError::BadWebhookSignature(_) => {
+                StatusCode::UNAUTHORIZED
+            }
+            HandlerError::Forbidden(_) => StatusCode::FORBIDDEN,
+            HandlerError::WebhookEventTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            HandlerError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
             HandlerError::Body(_) => StatusCode::INTERNAL_SERVER_ERROR,
             HandlerError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
             HandlerError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
@@ -137,6 +198,24 @@ impl HandlerError {
                 StatusCode::from_u16(e.code().into()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
             }
             HandlerError::NotReady => StatusCode::from_u16(470).unwrap(),
+        }
+    }
+
+    pub(crate) fn fill_builder<B: http_body::Body + Default + From<Bytes>>(
+        self,
+        res_builder: http::response::Builder,
+    ) -> Response<B> {
+        let status_code = self.status_code();
+        let res_builder = match &self {
+            HandlerError::RateLimited(retry_after) => res_builder.header(
+                header::RETRY_AFTER,
+                // Retry-After is expressed in whole seconds
+                (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0))
+                    .max(1)
+                    .to_string(),
+            ),
+            HandlerError::Unauthorized(_) => res_builder.header(header::WWW_AUTHENTICATE, "Bearer"),
+            _ => res_builder,
         };
 
         let error_response = match self {
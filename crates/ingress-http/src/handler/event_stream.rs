@@ -0,0 +1,287 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Attach to an invocation streaming its progress as [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+//!
+//! The stream starts with a `progress` event, followed by a new `progress` event every time the
+//! status or the journal length of the invocation changes. It ends with either:
+//!
+//! * an `output` event, containing the output of the invocation,
+//! * a `failure` event, containing the `code` and `message` of the invocation failure,
+//! * an `error` event, containing the `message` of the error that interrupted the stream.
+
+use std::convert::Infallible;
+use std::future::ready;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::{stream, FutureExt, StreamExt};
+use http::{header, HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use http_body::Frame;
+use http_body_util::{BodyExt, StreamBody};
+use serde::Serialize;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tracing::debug;
+
+use restate_core::network::partition_processor_rpc_client::{
+    AttachInvocationResponse, GetInvocationProgressResponse,
+};
+use restate_types::invocation::InvocationQuery;
+use restate_types::net::partition_processor::{
+    IngressResponseResult, InvocationProgress, InvocationProgressStatus,
+};
+
+use super::responses::X_RESTATE_ID;
+use super::{Handler, HandlerError, ResponseBody};
+use crate::rate_limit::RateLimitPermit;
+use crate::{RequestDispatcher, RequestDispatcherError};
+
+const TEXT_EVENT_STREAM: HeaderValue = HeaderValue::from_static("text/event-stream");
+
+/// Interval between the reads of the invocation progress, while waiting for its output.
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns true if the client accepts server-sent events.
+pub(crate) fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers.get_all(header::ACCEPT).iter().any(|value| {
+        value.to_str().is_ok_and(|value| {
+            value
+                .split(',')
+                .any(|media_type| media_type.trim().starts_with("text/event-stream"))
+        })
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressEventData {
+    status: &'static str,
+    journal_length: u32,
+}
+
+#[derive(Serialize)]
+struct FailureEventData<'a> {
+    code: u16,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct ErrorEventData {
+    message: String,
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    pub(crate) async fn handle_attach_event_stream<B: http_body::Body>(
+        self,
+        req: Request<B>,
+        invocation_query: InvocationQuery,
+        permit: Option<RateLimitPermit>,
+    ) -> Result<Response<ResponseBody>, HandlerError> {
+        // Check HTTP Method
+        if req.method() != Method::GET {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+
+        let progress = match self
+            .dispatcher
+            .get_invocation_progress(invocation_query.clone())
+            .await?
+        {
+            GetInvocationProgressResponse::NotFound => {
+                return Err(HandlerError::NotFound);
+            }
+            GetInvocationProgressResponse::NotSupported => {
+                return Err(HandlerError::NotImplemented);
+            }
+            GetInvocationProgressResponse::Progress(progress) => progress,
+        };
+        let invocation_id = progress.invocation_id;
+
+        // The output is sent back through the response sink of the attach request
+        let output = {
+            let dispatcher = self.dispatcher.clone();
+            let invocation_query = invocation_query.clone();
+            async move { dispatcher.attach_invocation(invocation_query).await }.boxed()
+        };
+        let mut progress_interval = tokio::time::interval_at(
+            Instant::now() + PROGRESS_POLL_INTERVAL,
+            PROGRESS_POLL_INTERVAL,
+        );
+        progress_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let first_event = progress_event(&progress);
+        let event_stream = EventStream {
+            dispatcher: self.dispatcher,
+            invocation_query,
+            output,
+            progress_interval,
+            last_progress: progress,
+            _permit: permit,
+        };
+        let events = stream::once(ready(first_event)).chain(stream::unfold(
+            Some(event_stream),
+            |event_stream| async move {
+                let mut event_stream = event_stream?;
+                let (event, is_last) = event_stream.next_event().await;
+                Some((event, (!is_last).then_some(event_stream)))
+            },
+        ));
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, TEXT_EVENT_STREAM)
+            .header(header::CACHE_CONTROL, "no-cache")
+            .header(X_RESTATE_ID, invocation_id.to_string())
+            .body(
+                StreamBody::new(events.map(|event| Ok::<_, Infallible>(Frame::data(event))))
+                    .boxed_unsync(),
+            )
+            .unwrap())
+    }
+}
+
+struct EventStream<Dispatcher> {
+    dispatcher: Dispatcher,
+    invocation_query: InvocationQuery,
+    output: BoxFuture<'static, Result<AttachInvocationResponse, RequestDispatcherError>>,
+    progress_interval: Interval,
+    last_progress: InvocationProgress,
+    // Counts the stream as in-flight request until it ends
+    _permit: Option<RateLimitPermit>,
+}
+
+impl<Dispatcher: RequestDispatcher> EventStream<Dispatcher> {
+    /// Waits for the next event, returning it together with whether it's the last one.
+    async fn next_event(&mut self) -> (Bytes, bool) {
+        loop {
+            tokio::select! {
+                output = &mut self.output => return (output_event(output), true),
+                _ = self.progress_interval.tick() => {
+                    match self
+                        .dispatcher
+                        .get_invocation_progress(self.invocation_query.clone())
+                        .await
+                    {
+                        Ok(GetInvocationProgressResponse::Progress(progress))
+                            if progress != self.last_progress =>
+                        {
+                            let event = progress_event(&progress);
+                            self.last_progress = progress;
+                            return (event, false);
+                        }
+                        Ok(_) => {}
+                        Err(err) => debug!("Failed to read the invocation progress: {}", err),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn progress_event(progress: &InvocationProgress) -> Bytes {
+    event(
+        "progress",
+        &serde_json::to_string(&ProgressEventData {
+            // Same names of the statuses of the sys_invocation table
+            status: match progress.status {
+                InvocationProgressStatus::Scheduled => "scheduled",
+                InvocationProgressStatus::Inboxed => "pending",
+                InvocationProgressStatus::Invoked => "running",
+                InvocationProgressStatus::Suspended => "suspended",
+                InvocationProgressStatus::Completed => "completed",
+            },
+            journal_length: progress.journal_length,
+        })
+        .expect("Serializing the progress must not fail"),
+    )
+}
+
+fn output_event(output: Result<AttachInvocationResponse, RequestDispatcherError>) -> Bytes {
+    let error = match output {
+        Ok(AttachInvocationResponse::Ready(output)) => match output.response {
+            IngressResponseResult::Success(_, payload) => match std::str::from_utf8(&payload) {
+                Ok(payload) => return event("output", payload),
+                Err(_) => "the output is not valid UTF-8, read it from the output endpoint instead"
+                    .to_owned(),
+            },
+            IngressResponseResult::Failure(err) => {
+                return event(
+                    "failure",
+                    &serde_json::to_string(&FailureEventData {
+                        code: err.code().into(),
+                        message: err.message(),
+                    })
+                    .expect("Serializing the failure must not fail"),
+                )
+            }
+        },
+        Ok(AttachInvocationResponse::NotFound) => HandlerError::NotFound.to_string(),
+        Ok(AttachInvocationResponse::NotSupported) => HandlerError::NotImplemented.to_string(),
+        Err(err) => HandlerError::from(err).to_string(),
+    };
+
+    event(
+        "error",
+        &serde_json::to_string(&ErrorEventData { message: error })
+            .expect("Serializing the error must not fail"),
+    )
+}
+
+/// Encodes an event, splitting its data in multiple lines if needed. Events always have at least
+/// one data line, as clients ignore events without data.
+fn event(name: &str, data: &str) -> Bytes {
+    let mut event = format!("event: {name}\n");
+    for line in data.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        event.push_str("data: ");
+        event.push_str(line);
+        event.push('\n');
+    }
+    event.push('\n');
+    Bytes::from(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_event_stream_header() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_event_stream(&headers));
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!accepts_event_stream(&headers));
+
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json, text/event-stream;q=0.9"),
+        );
+        assert!(accepts_event_stream(&headers));
+    }
+
+    #[test]
+    fn multiline_event() {
+        assert_eq!(
+            event("output", "{\n  \"greeting\": \"Hi\"\n}"),
+            Bytes::from_static(
+                b"event: output\ndata: {\ndata:   \"greeting\": \"Hi\"\ndata: }\n\n"
+            )
+        );
+        assert_eq!(
+            event("output", ""),
+            Bytes::from_static(b"event: output\ndata: \n\n")
+        );
+    }
+}
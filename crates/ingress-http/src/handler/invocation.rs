@@ -9,16 +9,17 @@
 // by the Apache License, Version 2.0.
 
 use bytes::Bytes;
-use http::{Method, Request, Response};
+use http::{header, Method, Request, Response};
 use http_body_util::Full;
 use tracing::warn;
 
-use super::path_parsing::{InvocationRequestType, InvocationTargetType, TargetType};
+use super::path_parsing::{InvocationRequestType, InvocationTargetType, RequestType, TargetType};
 use super::Handler;
 use super::HandlerError;
+use crate::auth::{AuthError, Principal};
 use crate::RequestDispatcher;
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
 };
 use restate_types::identifiers::IdempotencyId;
 use restate_types::invocation::InvocationQuery;
@@ -55,6 +56,59 @@ where
         }
     }
 
+    /// Authorizes attaching to or getting the output of an invocation by id, whose target
+    /// service is not part of the request path, against the access rule of its target service.
+    pub(crate) async fn authorize_invocation_by_id<B>(
+        &self,
+        req: &Request<B>,
+        request_type: &RequestType,
+    ) -> Result<(), HandlerError> {
+        let Some(authenticator) = self
+            .authenticator
+            .as_ref()
+            .filter(|authenticator| authenticator.has_service_rules())
+        else {
+            return Ok(());
+        };
+        let RequestType::Invocation(
+            InvocationRequestType::Attach(InvocationTargetType::InvocationId(id))
+            | InvocationRequestType::GetOutput(InvocationTargetType::InvocationId(id)),
+        ) = request_type
+        else {
+            return Ok(());
+        };
+        let principal = req
+            .extensions()
+            .get::<Principal>()
+            .ok_or(HandlerError::Unauthorized(AuthError::MissingToken))?;
+        let invocation_query = id
+            .parse()
+            .map(InvocationQuery::Invocation)
+            .map_err(|e| HandlerError::BadInvocationId(id.clone(), e))?;
+
+        match self
+            .dispatcher
+            .get_invocation_progress(invocation_query)
+            .await?
+        {
+            // The request itself fails the same way, without exposing anything of the invocation
+            GetInvocationProgressResponse::NotFound
+            | GetInvocationProgressResponse::NotSupported => Ok(()),
+            GetInvocationProgressResponse::Progress(progress) => match progress.invocation_target {
+                Some(invocation_target)
+                    if authenticator.is_allowed(principal, invocation_target.service_name()) =>
+                {
+                    Ok(())
+                }
+                Some(invocation_target) => Err(HandlerError::Forbidden(
+                    invocation_target.service_name().to_string(),
+                )),
+                // Older nodes don't report the target, so it can't be authorized yet
+                None => Err(HandlerError::Unavailable),
+            },
+        }
+    }
+
     pub(crate) fn convert_to_invocation_query(
         invocation_target_type: InvocationTargetType,
     ) -> Result<InvocationQuery, HandlerError> {
@@ -108,15 +162,19 @@ where
             AttachInvocationResponse::Ready(response) => response,
         };
 
-        Self::reply_with_invocation_response(response, move |invocation_target| {
-            self.schemas
-                .pinned()
-                .resolve_latest_invocation_target(
-                    invocation_target.service_name(),
-                    invocation_target.handler_name(),
-                )
-                .ok_or(HandlerError::NotFound)
-        })
+        Self::reply_with_invocation_response(
+            response,
+            req.headers().get(header::ACCEPT),
+            move |invocation_target| {
+                self.schemas
+                    .pinned()
+                    .resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                    .ok_or(HandlerError::NotFound)
+            },
+        )
     }
 
     pub(crate) async fn handle_invocation_get_output<B: http_body::Body>(
@@ -155,14 +213,18 @@ where
             }
         };
 
-        Self::reply_with_invocation_response(response, move |invocation_target| {
-            self.schemas
-                .pinned()
-                .resolve_latest_invocation_target(
-                    invocation_target.service_name(),
-                    invocation_target.handler_name(),
-                )
-                .ok_or(HandlerError::NotFound)
-        })
+        Self::reply_with_invocation_response(
+            response,
+            req.headers().get(header::ACCEPT),
+            move |invocation_target| {
+                self.schemas
+                    .pinned()
+                    .resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                    .ok_or(HandlerError::NotFound)
+            },
+        )
     }
 }
@@ -9,39 +9,61 @@
 // by the Apache License, Version 2.0.
 
 mod awakeables;
+mod batch;
 mod error;
+mod event_stream;
 mod health;
 mod invocation;
+mod openapi;
 mod path_parsing;
 mod responses;
 mod service_handler;
+mod slo;
 #[cfg(test)]
 mod tests;
 mod tracing;
+mod transcoding;
+mod webhook;
 mod workflow;
 
 use std::convert::Infallible;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use error::HandlerError;
 use futures::future::BoxFuture;
 use futures::FutureExt;
-use http_body_util::Full;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full};
 use hyper::http::HeaderValue;
 use hyper::{Request, Response};
-use path_parsing::RequestType;
+use metrics::counter;
+use path_parsing::{InvocationRequestType, RequestType, WorkflowRequestType};
+use restate_types::identifiers::ServiceId;
+use restate_types::invocation::InvocationQuery;
 use restate_types::live::Live;
 use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::schema::subscriptions::SubscriptionResolver;
 
 use super::*;
+use crate::auth::Authenticator;
+use crate::metric_definitions::{INGRESS_REQUESTS, REQUEST_DENIED_THROTTLE};
+use crate::rate_limit::{RateLimitPermit, RateLimiter};
+use crate::slo::SloTracker;
 
 const APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
 
+/// Body of the ingress responses, either buffered or streamed as server-sent events.
+pub(crate) type ResponseBody = UnsyncBoxBody<Bytes, Infallible>;
+
 #[derive(Clone)]
 pub(crate) struct Handler<Schemas, Dispatcher> {
     schemas: Live<Schemas>,
     dispatcher: Dispatcher,
+    slo_tracker: Option<Arc<SloTracker>>,
+    authenticator: Option<Arc<Authenticator>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
@@ -49,19 +71,43 @@ impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
         Self {
             schemas,
             dispatcher,
+            slo_tracker: None,
+            authenticator: None,
+            rate_limiter: None,
         }
     }
+
+    pub(crate) fn with_slo_tracker(mut self, slo_tracker: Option<Arc<SloTracker>>) -> Self {
+        self.slo_tracker = slo_tracker;
+        self
+    }
+
+    pub(crate) fn with_authenticator(mut self, authenticator: Option<Arc<Authenticator>>) -> Self {
+        self.authenticator = authenticator;
+        self
+    }
+
+    pub(crate) fn with_rate_limiter(mut self, rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
 }
 
 impl<Schemas, Dispatcher, Body> tower::Service<Request<Body>> for Handler<Schemas, Dispatcher>
 where
-    Schemas: ServiceMetadataResolver + InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
     Body: http_body::Body + Send + 'static,
     <Body as http_body::Body>::Data: Send + 'static,
     <Body as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
 {
-    type Response = Response<Full<Bytes>>;
+    type Response = Response<ResponseBody>;
     type Error = Infallible;
     type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -69,32 +115,148 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: Request<Body>) -> Self::Future {
-        let res = self.parse_path(req.uri());
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let res = self.authorize_request(&mut req).and_then(|request_type| {
+            let permit = self.admit_request(&request_type)?;
+            Ok((request_type, permit))
+        });
 
-        let mut this = self.clone();
+        let this = self.clone();
         async move {
-            match res? {
-                RequestType::Health => this.handle_health(req),
-                RequestType::OpenAPI => {
-                    // TODO
-                    Err(HandlerError::NotImplemented)
-                }
-                RequestType::Awakeable(awakeable_request) => {
-                    this.handle_awakeable(req, awakeable_request).await
+            let (request_type, permit) = res?;
+            this.authorize_invocation_by_id(&req, &request_type).await?;
+            match request_type {
+                // Event streams keep the permit until they end
+                RequestType::Invocation(InvocationRequestType::Attach(invocation_target_type))
+                    if event_stream::accepts_event_stream(req.headers()) =>
+                {
+                    let invocation_query =
+                        Self::convert_to_invocation_query(invocation_target_type)?;
+                    this.handle_attach_event_stream(req, invocation_query, permit)
+                        .await
                 }
-                RequestType::Service(service_request) => {
-                    this.handle_service_request(req, service_request).await
+                RequestType::Workflow(WorkflowRequestType::Attach(name, key))
+                    if event_stream::accepts_event_stream(req.headers()) =>
+                {
+                    let invocation_query = InvocationQuery::Workflow(ServiceId::new(name, key));
+                    this.handle_attach_event_stream(req, invocation_query, permit)
+                        .await
                 }
-                RequestType::Invocation(invocation_request) => {
-                    this.handle_invocation(req, invocation_request).await
-                }
-                RequestType::Workflow(workflow_request) => {
-                    this.handle_workflow(req, workflow_request).await
+                request_type => {
+                    // Keep the permit until the response is ready, to count the request as in-flight
+                    let _permit = permit;
+                    this.handle_request(req, request_type)
+                        .await
+                        .map(|response| response.map(BodyExt::boxed_unsync))
                 }
             }
         }
-        .map(|r| Ok::<_, Infallible>(r.unwrap_or_else(|e| e.into_response())))
+        .map(|r| {
+            Ok::<_, Infallible>(
+                r.unwrap_or_else(|e| e.into_response::<Full<Bytes>>().map(BodyExt::boxed_unsync)),
+            )
+        })
         .boxed()
     }
 }
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    async fn handle_request<Body>(
+        mut self,
+        req: Request<Body>,
+        request_type: RequestType,
+    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    where
+        Body: http_body::Body + Send + 'static,
+        <Body as http_body::Body>::Data: Send + 'static,
+        <Body as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
+    {
+        match request_type {
+            RequestType::Health => self.handle_health(req),
+            RequestType::Slo => self.handle_slo(req),
+            RequestType::OpenAPI => self.handle_openapi(req),
+            RequestType::Awakeable(awakeable_request) => {
+                self.handle_awakeable(req, awakeable_request).await
+            }
+            RequestType::Service(service_request) => {
+                self.handle_service_request(req, service_request).await
+            }
+            RequestType::Invocation(invocation_request) => {
+                self.handle_invocation(req, invocation_request).await
+            }
+            RequestType::Workflow(workflow_request) => {
+                self.handle_workflow(req, workflow_request).await
+            }
+            RequestType::BatchSend => self.handle_batch_send(req).await,
+            RequestType::Webhook(subscription_id) => {
+                self.handle_webhook(req, subscription_id).await
+            }
+        }
+    }
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: ServiceMetadataResolver + Clone + Send + Sync + 'static,
+{
+    /// Authenticates the request before parsing its path, so that unauthenticated clients can't
+    /// probe which services exist.
+    fn authorize_request<Body>(
+        &mut self,
+        req: &mut Request<Body>,
+    ) -> Result<RequestType, HandlerError> {
+        let Some(authenticator) = self.authenticator.clone() else {
+            return self.parse_path(req.uri());
+        };
+        if req.uri().path() == "/restate/health" {
+            return Ok(RequestType::Health);
+        }
+        // Webhooks are authenticated by the signature of their provider
+        if req.uri().path().starts_with("/restate/webhooks/") {
+            return self.parse_path(req.uri());
+        }
+
+        let principal = authenticator.authenticate(req.headers())?;
+        let request_type = self.parse_path(req.uri())?;
+        if let Some(service_name) = request_type.service_name() {
+            if !authenticator.is_allowed(&principal, service_name) {
+                return Err(HandlerError::Forbidden(service_name.to_owned()));
+            }
+        }
+        // Used by the batch handler to authorize each of its invocations
+        req.extensions_mut().insert(principal);
+        Ok(request_type)
+    }
+
+    /// Applies the rate limits to the request. Health checks are always admitted, while the
+    /// invocations of batches are admitted one by one by the batch handler.
+    fn admit_request(
+        &self,
+        request_type: &RequestType,
+    ) -> Result<Option<RateLimitPermit>, HandlerError> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(None);
+        };
+        if matches!(request_type, RequestType::Health | RequestType::BatchSend) {
+            return Ok(None);
+        }
+
+        match rate_limiter.try_admit(request_type.service_name()) {
+            Ok(permit) => Ok(Some(permit)),
+            Err(retry_after) => {
+                counter!(INGRESS_REQUESTS, "status" => REQUEST_DENIED_THROTTLE).increment(1);
+                Err(HandlerError::RateLimited(retry_after))
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::Bytes;
+use http::{header, Method, Request, Response, StatusCode};
+use http_body_util::Full;
+
+use restate_types::schema::service::ServiceMetadataResolver;
+
+use super::{Handler, APPLICATION_JSON};
+use crate::handler::error::HandlerError;
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: ServiceMetadataResolver + Send + Sync + 'static,
+{
+    /// Returns the OpenAPI document of all the public services.
+    pub(crate) fn handle_openapi<B: http_body::Body>(
+        &mut self,
+        req: Request<B>,
+    ) -> Result<Response<Full<Bytes>>, HandlerError> {
+        if req.method() != Method::GET {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+        let openapi = self.schemas.pinned().resolve_latest_services_openapi();
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, APPLICATION_JSON)
+            .body(Full::new(
+                serde_json::to_vec(&openapi)
+                    .expect("Serializing the OpenAPI document must not fail")
+                    .into(),
+            ))
+            .unwrap())
+    }
+}
@@ -12,6 +12,7 @@ use http::Uri;
 
 use super::Handler;
 use super::HandlerError;
+use restate_types::identifiers::SubscriptionId;
 use restate_types::schema::service::ServiceMetadataResolver;
 
 pub(crate) enum WorkflowRequestType {
@@ -214,11 +215,33 @@ impl ServiceRequestType {
 
 pub(crate) enum RequestType {
     Health,
+    Slo,
     OpenAPI,
     Awakeable(AwakeableRequestType),
     Invocation(InvocationRequestType),
     Service(ServiceRequestType),
     Workflow(WorkflowRequestType),
+    BatchSend,
+    Webhook(SubscriptionId),
+}
+
+impl RequestType {
+    /// Name of the service targeted by the request, if the request names one.
+    pub(crate) fn service_name(&self) -> Option<&str> {
+        match self {
+            RequestType::Service(ServiceRequestType { name, .. })
+            | RequestType::Workflow(
+                WorkflowRequestType::Attach(name, _) | WorkflowRequestType::GetOutput(name, _),
+            )
+            | RequestType::Invocation(
+                InvocationRequestType::Attach(InvocationTargetType::IdempotencyId { name, .. })
+                | InvocationRequestType::GetOutput(InvocationTargetType::IdempotencyId {
+                    name, ..
+                }),
+            ) => Some(name),
+            _ => None,
+        }
+    }
 }
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
@@ -235,6 +258,7 @@ where
         match first_segment {
             "restate" => match path_parts.next().ok_or(HandlerError::NotFound)? {
                 "health" => Ok(RequestType::Health),
+                "slo" => Ok(RequestType::Slo),
                 "awakeables" | "a" => Ok(RequestType::Awakeable(
                     AwakeableRequestType::from_path_chunks(path_parts)?,
                 )),
@@ -244,6 +268,18 @@ where
                 "workflow" => Ok(RequestType::Workflow(
                     WorkflowRequestType::from_path_chunks(path_parts)?,
                 )),
+                "batch" => match (path_parts.next(), path_parts.next()) {
+                    (Some("send"), None) => Ok(RequestType::BatchSend),
+                    _ => Err(HandlerError::NotFound),
+                },
+                "webhooks" => match (path_parts.next(), path_parts.next()) {
+                    (Some(subscription_id), None) => Ok(RequestType::Webhook(
+                        subscription_id
+                            .parse()
+                            .map_err(|_| HandlerError::BadWebhookPath)?,
+                    )),
+                    _ => Err(HandlerError::BadWebhookPath),
+                },
                 _ => Err(HandlerError::NotFound),
             },
             "openapi" => Ok(RequestType::OpenAPI),
@@ -10,11 +10,12 @@
 
 use bytes::Bytes;
 use chrono::DateTime;
-use http::{header, HeaderName, Response};
+use http::{header, HeaderName, HeaderValue, Response};
 use http_body_util::Full;
 use tracing::{info, trace};
 
 use crate::handler::error::HandlerError;
+use crate::handler::transcoding::transcode_response;
 use crate::handler::Handler;
 use restate_types::invocation::InvocationTarget;
 use restate_types::net::partition_processor::{IngressResponseResult, InvocationOutput};
@@ -32,6 +33,7 @@ impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
             completion_expiry_time,
             ..
         }: InvocationOutput,
+        accept: Option<&HeaderValue>,
         invocation_target_metadata_retriever: impl FnOnce(
             &InvocationTarget,
         ) -> Result<
@@ -58,7 +60,7 @@ impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
         }
 
         match response {
-            IngressResponseResult::Success(invocation_target, response_payload) => {
+            IngressResponseResult::Success(invocation_target, mut response_payload) => {
                 trace!(rpc.response = ?response_payload, "Complete external HTTP request successfully");
 
                 // Resolve invocation target metadata.
@@ -66,18 +68,20 @@ impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
                 let invocation_target_metadata =
                     invocation_target_metadata_retriever(&invocation_target)?;
 
-                // Write out the content-type, if any
+                // Write out the content-type, if any, transcoding the response if needed
                 // TODO fix https://github.com/restatedev/restate/issues/1496
                 if let Some(ct) = invocation_target_metadata
                     .output_rules
                     .infer_content_type(response_payload.is_empty())
                 {
-                    response_builder = response_builder.header(
-                        header::CONTENT_TYPE,
-                        // TODO we need this to_str().unwrap() because these two HeaderValue come from two different http crates
-                        //  We can remove it once https://github.com/restatedev/restate/issues/96 is done
-                        ct.to_str().unwrap(),
-                    )
+                    let (ct, payload) = transcode_response(
+                        &invocation_target_metadata.output_rules,
+                        ct,
+                        accept,
+                        response_payload,
+                    );
+                    response_payload = payload;
+                    response_builder = response_builder.header(header::CONTENT_TYPE, ct)
                 }
 
                 Ok(response_builder.body(Full::new(response_payload)).unwrap())
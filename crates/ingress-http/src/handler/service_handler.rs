@@ -8,38 +8,51 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::num::ParseIntError;
 use std::time::{Duration, Instant, SystemTime};
 
 use bytes::Bytes;
 use bytestring::ByteString;
-use http::{header, HeaderMap, HeaderName, Method, Request, Response, StatusCode};
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode};
 use http_body_util::{BodyExt, Full};
 use metrics::{counter, histogram};
 use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use tracing::{info, trace, trace_span, Instrument};
+use tracing::{debug, info, trace, trace_span, Instrument};
 
-use restate_types::identifiers::{InvocationId, WithInvocationId};
+use restate_core::{TaskCenter, TaskKind};
+use restate_types::identifiers::{InvocationId, ScheduleId, WithInvocationId};
 use restate_types::invocation::{
-    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
-    SpanRelation, WorkflowHandlerType,
+    Header, InvocationPriority, InvocationRequest, InvocationRequestHeader, InvocationTarget,
+    InvocationTargetType, SpanRelation, WorkflowHandlerType,
 };
+use restate_types::schedule::{CronParseError, CronSchedule, MisfirePolicy, Schedule};
 use restate_types::schema::invocation_target::{
-    InvocationTargetMetadata, InvocationTargetResolver,
+    InvocationTargetMetadata, InvocationTargetMirroring, InvocationTargetResolver,
 };
+use restate_types::time::{MillisSinceEpoch, ZonedDateTime, ZonedDateTimeParseError};
 
 use super::path_parsing::{InvokeType, ServiceRequestType, TargetType};
 use super::tracing::prepare_tracing_span;
+use super::transcoding::transcode_request;
 use super::HandlerError;
 use super::{Handler, APPLICATION_JSON};
 use crate::handler::responses::{IDEMPOTENCY_EXPIRES, X_RESTATE_ID};
-use crate::metric_definitions::{INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, REQUEST_COMPLETED};
+use crate::metric_definitions::{
+    INGRESS_MIRRORED_REQUESTS, INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, REQUEST_COMPLETED,
+};
 use crate::RequestDispatcher;
 
 pub(crate) const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
 const DELAY_QUERY_PARAM: &str = "delay";
+const AT_QUERY_PARAM: &str = "at";
+const PRIORITY_QUERY_PARAM: &str = "priority";
+const DEADLINE_QUERY_PARAM: &str = "deadline";
 const X_RESTATE_INGRESS_PATH: ByteString = ByteString::from_static("x-restate-ingress-path");
+const X_RESTATE_CRON: HeaderName = HeaderName::from_static("x-restate-cron");
+const X_RESTATE_CRON_MISFIRE: HeaderName = HeaderName::from_static("x-restate-cron-misfire");
+const X_RESTATE_SCHEDULE_ID: HeaderName = HeaderName::from_static("x-restate-schedule-id");
 
 #[derive(Debug, Serialize)]
 #[cfg_attr(test, derive(serde::Deserialize))]
@@ -60,7 +73,17 @@ pub(crate) struct SendResponse {
         default
     )]
     execution_time: Option<humantime::Timestamp>,
-    status: SendStatus,
+    pub(crate) status: SendStatus,
+}
+
+// IMPORTANT! If you touch this, please update crates/types/src/schema/openapi.rs too
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduleResponse {
+    pub(crate) schedule_id: ScheduleId,
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    next_fire_time: humantime::Timestamp,
 }
 
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
@@ -77,6 +100,7 @@ where
         <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     {
         let start_time = Instant::now();
+        let slo_tracker = self.slo_tracker.clone();
 
         let ServiceRequestType {
             name: service_name,
@@ -146,7 +170,7 @@ where
 
             info!("Processing ingress request");
 
-            let (parts, body) = req.into_parts();
+            let (mut parts, body) = req.into_parts();
 
             // Check HTTP Method
             if parts.method != Method::GET && parts.method != Method::POST {
@@ -161,51 +185,119 @@ where
                 .to_bytes();
             trace!(rpc.request = ?body);
 
-            // Validate content-type and body
-            invocation_target_meta.input_rules.validate(
-                parts
-                    .headers
-                    .get(header::CONTENT_TYPE)
-                    .map(|h| {
-                        h.to_str()
-                            .map_err(|e| HandlerError::BadHeader(header::CONTENT_TYPE, e))
-                    })
-                    .transpose()?,
+            // Transcode the body to the content type declared by the handler, if needed
+            let transcoded = transcode_request(
+                &invocation_target_meta.input_rules,
+                parse_content_type(&parts.headers)?,
                 &body,
             )?;
+            let body = if let Some((content_type, body)) = transcoded {
+                parts.headers.insert(header::CONTENT_TYPE, content_type);
+                body
+            } else {
+                body
+            };
+
+            // Validate content-type and body
+            invocation_target_meta
+                .input_rules
+                .validate(parse_content_type(&parts.headers)?, &body)?;
 
-            // Parse delay query parameter
+            // Parse delay and at query parameters
             let delay = parse_delay(parts.uri.query())?;
+            let at = parse_at(parts.uri.query())?;
+            if delay.is_some() && at.is_some() {
+                return Err(HandlerError::DelayAndExecutionTime);
+            }
+            let priority = parse_priority(parts.uri.query())?;
+
+            // Parse the cron header, sends with a cron expression create a recurring schedule
+            let cron = parse_cron(&parts.headers)?;
+            let misfire_policy = parse_misfire_policy(&parts.headers)?;
+            if cron.is_some()
+                && (matches!(invoke_ty, InvokeType::Call)
+                    || delay.is_some()
+                    || at.is_some()
+                    || idempotency_key.is_some()
+                    || invocation_target_meta.target_ty
+                        == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow))
+            {
+                return Err(HandlerError::UnsupportedCron);
+            }
 
             // Get headers
+            let accept = parts.headers.get(header::ACCEPT).cloned();
             let headers = parse_headers(parts)?;
 
             // Prepare service invocation
             let mut invocation_request_header =
                 InvocationRequestHeader::initialize(invocation_id, invocation_target);
-            invocation_request_header.with_related_span(SpanRelation::Parent(ingress_span_context));
+            invocation_request_header
+                .with_related_span(SpanRelation::Parent(ingress_span_context.clone()));
             invocation_request_header.completion_retention_duration =
                 invocation_target_meta.compute_retention(idempotency_key.is_some());
             if let Some(key) = idempotency_key {
                 invocation_request_header.idempotency_key = Some(key);
             }
             invocation_request_header.headers = headers;
+            invocation_request_header.priority = priority;
+            invocation_request_header.shared_concurrency_limit =
+                invocation_target_meta.shared_concurrency_limit;
+
+            // Pin the invocation to the canary deployment, if the routing rules select it
+            if let Some(routing) = invocation_target_meta.routing.as_ref().filter(|routing| {
+                routing.should_route(&invocation_id, &invocation_request_header.headers)
+            }) {
+                invocation_request_header.pinned_deployment =
+                    Some(routing.pinned_deployment.clone());
+            }
+
+            // Delayed, scheduled and recurring requests are not mirrored
+            if let Some(mirroring) = invocation_target_meta
+                .mirroring
+                .as_ref()
+                .filter(|mirroring| {
+                    delay.is_none() && at.is_none() && cron.is_none() && mirroring.should_mirror()
+                })
+            {
+                Self::mirror_request(
+                    &invocation_request_header,
+                    body.clone(),
+                    mirroring,
+                    SpanRelation::Linked(ingress_span_context),
+                    self.dispatcher.clone(),
+                );
+            }
 
             match invoke_ty {
                 InvokeType::Call => {
-                    if delay.is_some() {
+                    if delay.is_some() || at.is_some() {
                         return Err(HandlerError::UnsupportedDelay);
                     }
                     Self::handle_service_call(
                         InvocationRequest::new(invocation_request_header, body),
                         invocation_target_meta,
+                        accept,
                         self.dispatcher,
                     )
                     .await
                 }
                 InvokeType::Send => {
-                    invocation_request_header.execution_time =
-                        delay.map(|d| SystemTime::now() + d).map(Into::into);
+                    if let Some(cron) = cron {
+                        return Self::handle_schedule_send(
+                            cron,
+                            misfire_policy,
+                            InvocationRequest::new(invocation_request_header, body),
+                            self.dispatcher,
+                        )
+                        .await;
+                    }
+
+                    invocation_request_header.execution_time = match at {
+                        Some(at) => Some(at.resolve()),
+                        None => delay.map(|d| SystemTime::now() + d).map(Into::into),
+                    };
+                    invocation_request_header.execution_wall_clock_time = at;
 
                     Self::handle_service_send(
                         InvocationRequest::new(invocation_request_header, body),
@@ -218,6 +310,20 @@ where
         .instrument(runtime_span)
         .await;
 
+        let elapsed = start_time.elapsed();
+        if let Some(slo_tracker) = slo_tracker {
+            let status = match &result {
+                Ok(response) => response.status(),
+                Err(err) => err.status_code(),
+            };
+            slo_tracker.record(
+                &service_name,
+                status.is_server_error(),
+                elapsed,
+                Instant::now(),
+            );
+        }
+
         // Note that we only record (mostly) successful requests here. We might want to
         // change this in the _near_ future.
         histogram!(
@@ -225,7 +331,7 @@ where
             "rpc.service" => service_name.clone(),
             "rpc.method" => handler_name.clone(),
         )
-        .record(start_time.elapsed());
+        .record(elapsed);
 
         counter!(
             INGRESS_REQUESTS,
@@ -240,6 +346,7 @@ where
     async fn handle_service_call(
         invocation_request: InvocationRequest,
         invocation_target_metadata: InvocationTargetMetadata,
+        accept: Option<HeaderValue>,
         dispatcher: Dispatcher,
     ) -> Result<Response<Full<Bytes>>, HandlerError> {
         let response = dispatcher
@@ -247,10 +354,52 @@ where
             .instrument(trace_span!("Waiting for response"))
             .await?;
 
-        Self::reply_with_invocation_response(response, move |_| Ok(invocation_target_metadata))
+        Self::reply_with_invocation_response(response, accept.as_ref(), move |_| {
+            Ok(invocation_target_metadata)
+        })
+    }
+
+    /// Duplicates the request to the shadow deployment of the mirroring configuration. The
+    /// mirrored request is a fresh dry-run invocation: its response is discarded, and failing to
+    /// submit it doesn't affect the original request.
+    fn mirror_request(
+        invocation_request_header: &InvocationRequestHeader,
+        body: Bytes,
+        mirroring: &InvocationTargetMirroring,
+        span_relation: SpanRelation,
+        dispatcher: Dispatcher,
+    ) {
+        let target = invocation_request_header.target.clone();
+        let mut mirrored_header =
+            InvocationRequestHeader::initialize(InvocationId::generate(&target, None), target);
+        mirrored_header.with_related_span(span_relation);
+        mirrored_header.headers = invocation_request_header.headers.clone();
+        mirrored_header.pinned_deployment = Some(mirroring.pinned_deployment.clone());
+        // Isolate the side effects of the shadow deployment
+        mirrored_header.dry_run = true;
+
+        let service_name = mirrored_header.target.service_name().to_string();
+        let mirrored_invocation_id = mirrored_header.id;
+        let mirrored_request = InvocationRequest::new(mirrored_header, body);
+
+        let _ = TaskCenter::spawn_child(TaskKind::Ingress, "ingress-mirror", async move {
+            match dispatcher.send(mirrored_request).await {
+                Ok(_) => {
+                    counter!(INGRESS_MIRRORED_REQUESTS, "rpc.service" => service_name).increment(1);
+                }
+                Err(err) => {
+                    debug!(
+                        restate.invocation.id = %mirrored_invocation_id,
+                        "Failed to mirror ingress request: {}",
+                        err
+                    );
+                }
+            }
+            Ok(())
+        });
     }
 
-    async fn handle_service_send(
+    pub(super) async fn handle_service_send(
         invocation_request: InvocationRequest,
         dispatcher: Dispatcher,
     ) -> Result<Response<Full<Bytes>>, HandlerError> {
@@ -280,9 +429,48 @@ where
             ))
             .unwrap())
     }
+
+    /// Creates a schedule sending the request at every occurrence of the cron expression.
+    async fn handle_schedule_send(
+        cron: CronSchedule,
+        misfire_policy: MisfirePolicy,
+        invocation_request: InvocationRequest,
+        dispatcher: Dispatcher,
+    ) -> Result<Response<Full<Bytes>>, HandlerError> {
+        let schedule = Schedule {
+            id: ScheduleId::generate(&invocation_request.header.target),
+            cron,
+            misfire_policy,
+            invocation: invocation_request,
+            created_at: MillisSinceEpoch::now(),
+        };
+        let schedule_id = schedule.id;
+        let Some(next_fire_time) = schedule.first_fire_time() else {
+            return Err(HandlerError::BadCron(
+                "the expression has no upcoming occurrence".to_owned(),
+            ));
+        };
+
+        dispatcher.create_schedule(schedule).await?;
+
+        trace!("Complete external HTTP schedule request successfully");
+        Ok(Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(header::CONTENT_TYPE, APPLICATION_JSON)
+            .header(X_RESTATE_SCHEDULE_ID, schedule_id.to_string())
+            .body(Full::new(
+                serde_json::to_vec(&ScheduleResponse {
+                    schedule_id,
+                    next_fire_time: SystemTime::from(next_fire_time).into(),
+                })
+                .unwrap()
+                .into(),
+            ))
+            .unwrap())
+    }
 }
 
-fn parse_headers(parts: http::request::Parts) -> Result<Vec<Header>, HandlerError> {
+pub(super) fn parse_headers(parts: http::request::Parts) -> Result<Vec<Header>, HandlerError> {
     let mut headers = Vec::with_capacity(1 + parts.headers.keys_len());
 
     if let Some(path_and_query) = parts.uri.path_and_query() {
@@ -298,6 +486,8 @@ fn parse_headers(parts: http::request::Parts) -> Result<Vec<Header>, HandlerErro
             || k == header::HOST
             || k == IDEMPOTENCY_KEY
             || k == IDEMPOTENCY_EXPIRES
+            || k == X_RESTATE_CRON
+            || k == X_RESTATE_CRON_MISFIRE
         {
             continue;
         }
@@ -336,6 +526,80 @@ fn parse_delay(query: Option<&str>) -> Result<Option<Duration>, HandlerError> {
     Ok(None)
 }
 
+fn parse_at(query: Option<&str>) -> Result<Option<ZonedDateTime>, HandlerError> {
+    let Some(query) = query else {
+        return Ok(None);
+    };
+
+    for (k, v) in url::form_urlencoded::parse(query.as_bytes()) {
+        if k.eq_ignore_ascii_case(AT_QUERY_PARAM) {
+            return v.parse().map(Some).map_err(|e: ZonedDateTimeParseError| {
+                HandlerError::BadExecutionTime(e.to_string())
+            });
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parses the inbox priority of the invocation from the priority and deadline query parameters.
+fn parse_priority(query: Option<&str>) -> Result<InvocationPriority, HandlerError> {
+    let Some(query) = query else {
+        return Ok(InvocationPriority::default());
+    };
+
+    let mut priority = InvocationPriority::default();
+    for (k, v) in url::form_urlencoded::parse(query.as_bytes()) {
+        if k.eq_ignore_ascii_case(PRIORITY_QUERY_PARAM) {
+            priority.priority = v
+                .parse()
+                .map_err(|e: ParseIntError| HandlerError::BadPriority(e.to_string()))?;
+        } else if k.eq_ignore_ascii_case(DEADLINE_QUERY_PARAM) {
+            let deadline: ZonedDateTime = v
+                .parse()
+                .map_err(|e: ZonedDateTimeParseError| HandlerError::BadDeadline(e.to_string()))?;
+            priority.deadline = Some(deadline.resolve());
+        }
+    }
+
+    Ok(priority)
+}
+
+fn parse_cron(headers: &HeaderMap) -> Result<Option<CronSchedule>, HandlerError> {
+    let Some(cron) = headers.get(X_RESTATE_CRON) else {
+        return Ok(None);
+    };
+
+    cron.to_str()
+        .map_err(|e| HandlerError::BadHeader(X_RESTATE_CRON, e))?
+        .parse()
+        .map(Some)
+        .map_err(|e: CronParseError| HandlerError::BadCron(e.to_string()))
+}
+
+fn parse_misfire_policy(headers: &HeaderMap) -> Result<MisfirePolicy, HandlerError> {
+    let Some(misfire_policy) = headers.get(X_RESTATE_CRON_MISFIRE) else {
+        return Ok(MisfirePolicy::default());
+    };
+
+    let misfire_policy = misfire_policy
+        .to_str()
+        .map_err(|e| HandlerError::BadHeader(X_RESTATE_CRON_MISFIRE, e))?;
+    misfire_policy
+        .parse()
+        .map_err(|_| HandlerError::BadMisfirePolicy(misfire_policy.to_owned()))
+}
+
+fn parse_content_type(headers: &HeaderMap) -> Result<Option<&str>, HandlerError> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .map(|h| {
+            h.to_str()
+                .map_err(|e| HandlerError::BadHeader(header::CONTENT_TYPE, e))
+        })
+        .transpose()
+}
+
 fn parse_idempotency(headers: &HeaderMap) -> Result<Option<ByteString>, HandlerError> {
     let idempotency_key = if let Some(idempotency_key) = headers.get(IDEMPOTENCY_KEY) {
         ByteString::from(
@@ -377,4 +641,52 @@ mod tests {
             Duration::from_millis(60000),
         );
     }
+
+    #[test]
+    fn at() {
+        assert_eq!(
+            parse_at(Some("at=2025-03-30T09%3A00%3A00%5BEurope%2FBerlin%5D"))
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "2025-03-30T09:00:00[Europe/Berlin]",
+        );
+        assert_eq!(
+            parse_at(Some("at=2025-03-30T09:00[America/New_York]"))
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "2025-03-30T09:00:00[America/New_York]",
+        );
+        assert!(parse_at(Some("delay=60sec")).unwrap().is_none());
+        assert!(matches!(
+            parse_at(Some("at=2025-03-30T09:00:00")),
+            Err(HandlerError::BadExecutionTime(_))
+        ));
+    }
+
+    #[test]
+    fn priority() {
+        assert_eq!(parse_priority(None).unwrap(), InvocationPriority::default());
+        assert_eq!(
+            parse_priority(Some("priority=-3")).unwrap(),
+            InvocationPriority::new(-3, None)
+        );
+        let deadline: ZonedDateTime = "2025-03-30T09:00:00[Europe/Berlin]".parse().unwrap();
+        assert_eq!(
+            parse_priority(Some(
+                "priority=7&deadline=2025-03-30T09%3A00%3A00%5BEurope%2FBerlin%5D"
+            ))
+            .unwrap(),
+            InvocationPriority::new(7, Some(deadline.resolve()))
+        );
+        assert!(matches!(
+            parse_priority(Some("priority=high")),
+            Err(HandlerError::BadPriority(_))
+        ));
+        assert!(matches!(
+            parse_priority(Some("deadline=2025-03-30T09:00:00")),
+            Err(HandlerError::BadDeadline(_))
+        ));
+    }
 }
@@ -0,0 +1,54 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Instant;
+
+use bytes::Bytes;
+use http::{header, Method, Request, Response, StatusCode};
+use http_body_util::Full;
+use serde::Serialize;
+
+use super::{Handler, APPLICATION_JSON};
+use crate::handler::error::HandlerError;
+use crate::slo::SloStatus;
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SloResponse {
+    pub(crate) objectives: Vec<SloStatus>,
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher> {
+    pub(crate) fn handle_slo<B: http_body::Body>(
+        &mut self,
+        req: Request<B>,
+    ) -> Result<Response<Full<Bytes>>, HandlerError> {
+        if req.method() != Method::GET {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+        let response = SloResponse {
+            objectives: self
+                .slo_tracker
+                .as_ref()
+                .map(|slo_tracker| slo_tracker.evaluate(Instant::now()))
+                .unwrap_or_default(),
+        };
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, APPLICATION_JSON)
+            .body(Full::new(
+                serde_json::to_vec(&response)
+                    .expect("Serializing the SloResponse must not fail")
+                    .into(),
+            ))
+            .unwrap())
+    }
+}
@@ -10,7 +10,7 @@
 
 use std::future::ready;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use bytes::Bytes;
 use bytestring::ByteString;
@@ -18,34 +18,51 @@ use futures::FutureExt;
 use http::StatusCode;
 use http::{HeaderValue, Method, Request, Response};
 use http_body_util::{BodyExt, Empty, Full};
+use restate_types::config::{IngressAuthOptions, IngressRateLimitOptions};
 use restate_types::live::Live;
 use tower::ServiceExt;
 use tracing_test::traced_test;
 
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
 };
 use restate_core::TestCoreEnv;
 use restate_test_util::{assert, assert_eq};
+use restate_types::deployment::PinnedDeployment;
+use restate_types::identifiers::DeploymentId;
+use restate_types::identifiers::SubscriptionId;
 use restate_types::identifiers::{IdempotencyId, InvocationId, ServiceId, WithInvocationId};
 use restate_types::invocation::{
     InvocationQuery, InvocationTarget, InvocationTargetType, VirtualObjectHandlerType,
     WorkflowHandlerType,
 };
 use restate_types::net::partition_processor::{
-    IngressResponseResult, InvocationOutput, SubmittedInvocationNotification,
+    IngressResponseResult, InvocationOutput, InvocationProgress, InvocationProgressStatus,
+    SubmittedInvocationNotification,
 };
+use restate_types::schedule::MisfirePolicy;
 use restate_types::schema::invocation_target::{
     InputContentType, InputRules, InputValidationRule, InvocationTargetMetadata,
-    OutputContentTypeRule, OutputRules,
+    InvocationTargetMirroring, InvocationTargetRouting, OutputContentTypeRule, OutputRules,
 };
+use restate_types::schema::service::RoutingHeaderMatch;
+use restate_types::schema::subscriptions::{
+    EventInvocationTargetTemplate, Sink, Source, Subscription, WebhookProvider,
+    WEBHOOK_SECRET_OPTION,
+};
+use restate_types::service_protocol::ServiceProtocolVersion;
 
+use super::batch::{BatchSendResponse, BatchSendResult};
 use super::health::HealthResponse;
 use super::mocks::*;
 use super::service_handler::*;
+use super::slo::SloResponse;
+use super::webhook::sign;
 use super::ConnectInfo;
-use super::Handler;
+use super::{Handler, ResponseBody};
+use crate::auth::Authenticator;
 use crate::handler::responses::X_RESTATE_ID;
+use crate::rate_limit::RateLimiter;
 use crate::MockRequestDispatcher;
 
 #[restate_core::test]
@@ -150,6 +167,7 @@ async fn call_service_with_get() {
             InvocationTargetMetadata {
                 input_rules: InputRules {
                     input_validation_rules: vec![InputValidationRule::NoBodyAndContentType],
+                    protobuf_message: None,
                 },
                 ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
             },
@@ -165,6 +183,175 @@ async fn call_service_with_get() {
     assert_eq!(response_value.greeting, "Igal");
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn call_service_with_mirroring() {
+    let greeting_req = GreetingRequest {
+        person: "Francesco".to_string(),
+    };
+
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&greeting_req).unwrap(),
+        )))
+        .unwrap();
+
+    let pinned_deployment = PinnedDeployment::new(DeploymentId::new(), ServiceProtocolVersion::V1);
+    let (original_id_tx, original_id_rx) = tokio::sync::oneshot::channel();
+    let (mirrored_tx, mirrored_rx) = tokio::sync::oneshot::channel();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(move |invocation_request| {
+            assert!(invocation_request.header.pinned_deployment.is_none());
+            assert!(!invocation_request.header.dry_run);
+            original_id_tx
+                .send(invocation_request.invocation_id())
+                .unwrap();
+
+            ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: IngressResponseResult::Success(
+                    invocation_request.header.target,
+                    serde_json::to_vec(&GreetingResponse {
+                        greeting: "Igal".to_string(),
+                    })
+                    .unwrap()
+                    .into(),
+                ),
+            }))
+            .boxed()
+        });
+    mock_dispatcher
+        .expect_send()
+        .return_once(move |invocation_request| {
+            mirrored_tx.send(invocation_request.clone()).unwrap();
+
+            ready(Ok(SubmittedInvocationNotification {
+                request_id: Default::default(),
+                is_new_invocation: true,
+            }))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        MockSchemas::default().with_service_and_target(
+            "greeter.Greeter",
+            "greet",
+            InvocationTargetMetadata {
+                mirroring: Some(InvocationTargetMirroring {
+                    pinned_deployment: pinned_deployment.clone(),
+                    fraction: 1.0,
+                }),
+                ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
+            },
+        ),
+        mock_dispatcher,
+    )
+    .await;
+
+    // The response of the original request is returned
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let response_value: GreetingResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value.greeting, "Igal");
+
+    // The mirrored request is a new invocation pinned to the shadow deployment
+    let original_id = original_id_rx.await.unwrap();
+    let mirrored_request = mirrored_rx.await.unwrap();
+    assert_ne!(mirrored_request.invocation_id(), original_id);
+    assert_eq!(
+        mirrored_request.header.pinned_deployment,
+        Some(pinned_deployment)
+    );
+    assert!(mirrored_request.header.dry_run);
+    assert_eq!(
+        mirrored_request.header.target,
+        InvocationTarget::service("greeter.Greeter", "greet")
+    );
+    let greeting_req: GreetingRequest = serde_json::from_slice(&mirrored_request.body).unwrap();
+    assert_eq!(&greeting_req.person, "Francesco");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn call_service_with_routing_header() {
+    let greeting_req = GreetingRequest {
+        person: "Francesco".to_string(),
+    };
+
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .header("x-canary", "true")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&greeting_req).unwrap(),
+        )))
+        .unwrap();
+
+    let pinned_deployment = PinnedDeployment::new(DeploymentId::new(), ServiceProtocolVersion::V1);
+    let expected_pinned_deployment = pinned_deployment.clone();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(move |invocation_request| {
+            // The request matches the header, so it is pinned to the canary deployment
+            assert_eq!(
+                invocation_request.header.pinned_deployment,
+                Some(expected_pinned_deployment)
+            );
+            assert!(!invocation_request.header.dry_run);
+
+            ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: IngressResponseResult::Success(
+                    invocation_request.header.target,
+                    serde_json::to_vec(&GreetingResponse {
+                        greeting: "Igal".to_string(),
+                    })
+                    .unwrap()
+                    .into(),
+                ),
+            }))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        MockSchemas::default().with_service_and_target(
+            "greeter.Greeter",
+            "greet",
+            InvocationTargetMetadata {
+                routing: Some(InvocationTargetRouting {
+                    pinned_deployment,
+                    percentage: 0.0,
+                    header: Some(RoutingHeaderMatch {
+                        name: "x-canary".to_owned(),
+                        value: "true".to_owned(),
+                    }),
+                }),
+                ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
+            },
+        ),
+        mock_dispatcher,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn call_virtual_object() {
@@ -312,6 +499,82 @@ async fn send_with_delay_service() {
     let _: SendResponse = serde_json::from_slice(&response_bytes).unwrap();
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn send_with_cron_service() {
+    let greeting_req = GreetingRequest {
+        person: "Francesco".to_string(),
+    };
+
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet/send")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .header("x-restate-cron", "0 9 * * MON-FRI[Europe/Berlin]")
+        .header("x-restate-cron-misfire", "skip")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&greeting_req).unwrap(),
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_create_schedule()
+        .return_once(|schedule| {
+            assert_eq!(schedule.cron.to_string(), "0 9 * * MON-FRI[Europe/Berlin]");
+            assert_eq!(schedule.misfire_policy, MisfirePolicy::Skip);
+            assert_eq!(
+                schedule.invocation.header.target.service_name(),
+                "greeter.Greeter"
+            );
+            assert_eq!(schedule.invocation.header.target.handler_name(), "greet");
+            assert!(schedule
+                .invocation
+                .header
+                .headers
+                .iter()
+                .all(|header| !header.name.starts_with("x-restate-cron")));
+
+            let greeting_req: GreetingRequest =
+                serde_json::from_slice(&schedule.invocation.body).unwrap();
+            assert_eq!(&greeting_req.person, "Francesco");
+
+            ready(Ok(())).boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let (parts, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let schedule_response: ScheduleResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(
+        parts.headers.get("x-restate-schedule-id").unwrap(),
+        schedule_response.schedule_id.to_string().as_str()
+    );
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn send_with_cron_and_delay_is_rejected() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet/send?delay=PT1M")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .header("x-restate-cron", "@hourly")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&GreetingRequest {
+                person: "Francesco".to_string(),
+            })
+            .unwrap(),
+        )))
+        .unwrap();
+
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn send_virtual_object() {
@@ -713,6 +976,86 @@ async fn attach_with_idempotency_id_to_keyed_service() {
     assert_eq!(response_value.greeting, "Igal");
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn attach_with_event_stream() {
+    let invocation_id = InvocationId::mock_random();
+
+    let mock_schemas = MockSchemas::default().with_service_and_target(
+        "greeter.Greeter",
+        "greet",
+        InvocationTargetMetadata::mock(InvocationTargetType::Service),
+    );
+
+    let req = hyper::Request::builder()
+        .uri(format!(
+            "http://localhost/restate/invocation/{}/attach",
+            invocation_id
+        ))
+        .method(Method::GET)
+        .header(http::header::ACCEPT, "text/event-stream")
+        .body(Empty::<Bytes>::new())
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_get_invocation_progress()
+        .returning(move |actual_invocation_query| {
+            assert_eq!(
+                InvocationQuery::Invocation(invocation_id),
+                actual_invocation_query
+            );
+
+            ready(Ok(GetInvocationProgressResponse::Progress(
+                InvocationProgress {
+                    invocation_id,
+                    status: InvocationProgressStatus::Invoked,
+                    journal_length: 2,
+                    invocation_target: None,
+                },
+            )))
+            .boxed()
+        });
+    mock_dispatcher
+        .expect_attach_invocation()
+        .return_once(move |_| {
+            ready(Ok(AttachInvocationResponse::Ready(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_id),
+                completion_expiry_time: None,
+                response: IngressResponseResult::Success(
+                    InvocationTarget::service("greeter.Greeter", "greet"),
+                    serde_json::to_vec(&GreetingResponse {
+                        greeting: "Igal".to_string(),
+                    })
+                    .unwrap()
+                    .into(),
+                ),
+            })))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(req, mock_schemas, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(
+        response.headers().get(X_RESTATE_ID).unwrap(),
+        invocation_id.to_string().as_str()
+    );
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(
+        response_bytes,
+        Bytes::from_static(
+            b"event: progress\ndata: {\"status\":\"running\",\"journalLength\":2}\n\n\
+              event: output\ndata: {\"greeting\":\"Igal\"}\n\n"
+        )
+    );
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn get_output_with_invocation_id() {
@@ -960,6 +1303,7 @@ async fn invalid_input() {
                             "json".into(),
                         ),
                     }],
+                    protobuf_message: None,
                 },
                 ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
             },
@@ -984,6 +1328,7 @@ async fn set_custom_content_type_on_response() {
                     has_json_schema: false,
                 },
                 json_schema: None,
+                protobuf_message: None,
             },
             ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
         },
@@ -1032,6 +1377,7 @@ async fn set_custom_content_type_on_empty_response() {
                     has_json_schema: false,
                 },
                 json_schema: None,
+                protobuf_message: None,
             },
             ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
         },
@@ -1086,6 +1432,469 @@ async fn health() {
     let _: HealthResponse = serde_json::from_slice(&response_bytes).unwrap();
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn slo_without_objectives() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/slo")
+        .method(Method::GET)
+        .body(Empty::<Bytes>::default())
+        .unwrap();
+
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let slo_response: SloResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert!(slo_response.objectives.is_empty());
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn authentication() {
+    let _env = TestCoreEnv::create_with_single_node(1, 1).await;
+    let options: IngressAuthOptions = serde_json::from_value(serde_json::json!({
+        "api-keys": [
+            {"name": "frontend", "key": "frontend-secret"},
+            {"name": "backend", "key": "backend-secret"},
+        ],
+        "services": {"greeter.Greeter": {"api-keys": ["frontend"]}}
+    }))
+    .unwrap();
+    let authenticator = Arc::new(Authenticator::new(&options));
+
+    let handle = |path: &str, token: Option<&str>, dispatcher: MockRequestDispatcher| {
+        let mut req = hyper::Request::get(format!("http://localhost{path}"));
+        if let Some(token) = token {
+            req = req.header(http::header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let mut req = req.body(Empty::<Bytes>::default()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo::new("0.0.0.0:0".parse().unwrap()));
+        req.extensions_mut().insert(opentelemetry::Context::new());
+
+        Handler::new(Live::from_value(mock_schemas()), Arc::new(dispatcher))
+            .with_authenticator(Some(Arc::clone(&authenticator)))
+            .oneshot(req)
+    };
+
+    let response = handle(
+        "/greeter.Greeter/greet",
+        None,
+        MockRequestDispatcher::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response
+            .headers()
+            .get(http::header::WWW_AUTHENTICATE)
+            .unwrap(),
+        "Bearer"
+    );
+
+    let response = handle(
+        "/greeter.Greeter/greet",
+        Some("unknown-secret"),
+        MockRequestDispatcher::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = handle(
+        "/greeter.Greeter/greet",
+        Some("backend-secret"),
+        MockRequestDispatcher::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = handle(
+        "/greeter.Greeter/greet",
+        Some("frontend-secret"),
+        expect_invocation_and_reply_with_empty(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Services without rules only require authentication
+    let response = handle(
+        "/greeter.GreeterObject/my-key/greet",
+        Some("backend-secret"),
+        expect_invocation_and_reply_with_empty(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Invocations by id are authorized against the rule of their target service
+    let invocation_id = InvocationId::mock_random();
+    let output_path = format!("/restate/invocation/{invocation_id}/output");
+    let expect_progress = || {
+        let mut mock_dispatcher = MockRequestDispatcher::default();
+        mock_dispatcher
+            .expect_get_invocation_progress()
+            .return_once(move |_| {
+                ready(Ok(GetInvocationProgressResponse::Progress(
+                    InvocationProgress {
+                        invocation_id,
+                        status: InvocationProgressStatus::Invoked,
+                        journal_length: 1,
+                        invocation_target: Some(InvocationTarget::service(
+                            "greeter.Greeter",
+                            "greet",
+                        )),
+                    },
+                )))
+                .boxed()
+            });
+        mock_dispatcher
+    };
+
+    let response = handle(&output_path, Some("backend-secret"), expect_progress())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let mut mock_dispatcher = expect_progress();
+    mock_dispatcher
+        .expect_get_invocation_output()
+        .return_once(|_| ready(Ok(GetInvocationOutputResponse::NotReady)).boxed());
+    let response = handle(&output_path, Some("frontend-secret"), mock_dispatcher)
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 470);
+
+    // Health checks don't require authentication
+    let response = handle("/restate/health", None, MockRequestDispatcher::default())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn rate_limited() {
+    let _env = TestCoreEnv::create_with_single_node(1, 1).await;
+    let options: IngressRateLimitOptions = serde_json::from_value(serde_json::json!({
+        "services": {"greeter.Greeter": {"requests-per-second": 1}}
+    }))
+    .unwrap();
+    let rate_limiter = Arc::new(RateLimiter::new(Live::from_value(options).boxed()));
+
+    let handle = |dispatcher: MockRequestDispatcher| {
+        let mut req = hyper::Request::get("http://localhost/greeter.Greeter/greet")
+            .body(Empty::<Bytes>::default())
+            .unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo::new("0.0.0.0:0".parse().unwrap()));
+        req.extensions_mut().insert(opentelemetry::Context::new());
+
+        Handler::new(Live::from_value(mock_schemas()), Arc::new(dispatcher))
+            .with_rate_limiter(Some(Arc::clone(&rate_limiter)))
+            .oneshot(req)
+    };
+
+    let response = handle(expect_invocation_and_reply_with_empty())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = handle(MockRequestDispatcher::default()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        response.headers().get(http::header::RETRY_AFTER).unwrap(),
+        "1"
+    );
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send() {
+    let req = hyper::Request::post("http://localhost/restate/batch/send")
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&serde_json::json!({
+                "invocations": [
+                    {
+                        "service": "greeter.Greeter",
+                        "handler": "greet",
+                        "body": {"person": "Francesco"}
+                    },
+                    {"service": "greeter.Greeter", "handler": "unknown"},
+                    {"service": "greeter.Greeter", "handler": "greet", "key": "my-key"},
+                    {
+                        "service": "greeter.GreeterObject",
+                        "handler": "greet",
+                        "key": "my-key",
+                        "idempotencyKey": "123"
+                    }
+                ]
+            }))
+            .unwrap(),
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send_batch()
+        .return_once(|invocation_requests| {
+            assert_eq!(invocation_requests.len(), 2);
+            assert_eq!(
+                invocation_requests[0].header.target,
+                InvocationTarget::service("greeter.Greeter", "greet")
+            );
+            let greeting_req: GreetingRequest =
+                serde_json::from_slice(&invocation_requests[0].body).unwrap();
+            assert_eq!(&greeting_req.person, "Francesco");
+            assert_eq!(
+                invocation_requests[1].header.target,
+                InvocationTarget::virtual_object(
+                    "greeter.GreeterObject",
+                    "my-key",
+                    "greet",
+                    VirtualObjectHandlerType::Exclusive
+                )
+            );
+            assert_eq!(
+                invocation_requests[1].header.idempotency_key,
+                Some(ByteString::from_static("123"))
+            );
+
+            ready(vec![Ok(()), Ok(())]).boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let response_value: BatchSendResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value.invocations.len(), 4);
+    assert!(matches!(
+        response_value.invocations[0],
+        BatchSendResult::Accepted { .. }
+    ));
+    assert!(matches!(
+        response_value.invocations[1],
+        BatchSendResult::Failed { .. }
+    ));
+    assert!(matches!(
+        response_value.invocations[2],
+        BatchSendResult::Failed { .. }
+    ));
+    let BatchSendResult::Accepted { invocation_id } = &response_value.invocations[3] else {
+        panic!("expected the invocation to be accepted");
+    };
+    assert_eq!(
+        *invocation_id,
+        InvocationId::generate(
+            &InvocationTarget::virtual_object(
+                "greeter.GreeterObject",
+                "my-key",
+                "greet",
+                VirtualObjectHandlerType::Exclusive
+            ),
+            Some("123")
+        )
+    );
+}
+
+fn github_webhook_subscription() -> Subscription {
+    Subscription::new(
+        SubscriptionId::new(),
+        Source::Webhook {
+            provider: WebhookProvider::Github,
+        },
+        Sink::Invocation {
+            event_invocation_target_template: EventInvocationTargetTemplate::Service {
+                name: "greeter.Greeter".to_owned(),
+                handler: "greet".to_owned(),
+            },
+        },
+        [(WEBHOOK_SECRET_OPTION.to_owned(), "my-secret".to_owned())].into(),
+    )
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn webhook() {
+    let subscription = github_webhook_subscription();
+    let body = Bytes::from_static(br#"{"person": "Francesco"}"#);
+
+    let req = hyper::Request::post(format!(
+        "http://localhost/restate/webhooks/{}",
+        subscription.id()
+    ))
+    .header("content-type", "application/json")
+    .header("x-github-delivery", "delivery-1")
+    .header(
+        "x-hub-signature-256",
+        format!("sha256={}", sign("my-secret", &body)),
+    )
+    .body(Full::new(body))
+    .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send()
+        .return_once(|invocation_request| {
+            assert_eq!(
+                invocation_request.header.target,
+                InvocationTarget::service("greeter.Greeter", "greet")
+            );
+            // The delivery id protects from replays
+            assert_eq!(
+                invocation_request.header.idempotency_key,
+                Some(ByteString::from_static("delivery-1"))
+            );
+            let greeting_req: GreetingRequest =
+                serde_json::from_slice(&invocation_request.body).unwrap();
+            assert_eq!(&greeting_req.person, "Francesco");
+
+            ready(Ok(SubmittedInvocationNotification {
+                request_id: Default::default(),
+                is_new_invocation: false,
+            }))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        mock_schemas().with_subscription(subscription),
+        mock_dispatcher,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let response_value: SendResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert!(matches!(
+        response_value.status,
+        SendStatus::PreviouslyAccepted
+    ));
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn webhook_bad_signature() {
+    let subscription = github_webhook_subscription();
+    let body = Bytes::from_static(br#"{"person": "Francesco"}"#);
+
+    let req = hyper::Request::post(format!(
+        "http://localhost/restate/webhooks/{}",
+        subscription.id()
+    ))
+    .header("x-github-delivery", "delivery-1")
+    .header(
+        "x-hub-signature-256",
+        format!("sha256={}", sign("another-secret", &body)),
+    )
+    .body(Full::new(body))
+    .unwrap();
+
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        mock_schemas().with_subscription(subscription),
+        MockRequestDispatcher::default(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn hmac_webhook_signed_timestamp() {
+    let subscription = Subscription::new(
+        SubscriptionId::new(),
+        Source::Webhook {
+            provider: WebhookProvider::Hmac,
+        },
+        Sink::Invocation {
+            event_invocation_target_template: EventInvocationTargetTemplate::Service {
+                name: "greeter.Greeter".to_owned(),
+                handler: "greet".to_owned(),
+            },
+        },
+        [(WEBHOOK_SECRET_OPTION.to_owned(), "my-secret".to_owned())].into(),
+    );
+    let body = Bytes::from_static(br#"{"person": "Francesco"}"#);
+    let request = |timestamp: u64| {
+        let mut signed_payload = format!("{timestamp}.").into_bytes();
+        signed_payload.extend_from_slice(&body);
+        hyper::Request::post(format!(
+            "http://localhost/restate/webhooks/{}",
+            subscription.id()
+        ))
+        .header("x-timestamp", timestamp.to_string())
+        .header("x-signature", sign("my-secret", &signed_payload))
+        .body(Full::new(body.clone()))
+        .unwrap()
+    };
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher.expect_send().return_once(|_| {
+        ready(Ok(SubmittedInvocationNotification {
+            request_id: Default::default(),
+            is_new_invocation: true,
+        }))
+        .boxed()
+    });
+    let response = handle_with_schemas_and_dispatcher(
+        request(now),
+        mock_schemas().with_subscription(subscription.clone()),
+        mock_dispatcher,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    // Replaying a request signed an hour ago is rejected
+    let response = handle_with_schemas_and_dispatcher(
+        request(now - 60 * 60),
+        mock_schemas().with_subscription(subscription),
+        MockRequestDispatcher::default(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn webhook_too_large() {
+    let subscription = github_webhook_subscription();
+    let body = Bytes::from(vec![b' '; 26 * 1024 * 1024]);
+
+    let req = hyper::Request::post(format!(
+        "http://localhost/restate/webhooks/{}",
+        subscription.id()
+    ))
+    .header("x-github-delivery", "delivery-1")
+    .header("x-hub-signature-256", "sha256=00")
+    .body(Full::new(body))
+    .unwrap();
+
+    // The body is rejected before verifying its signature
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        mock_schemas().with_subscription(subscription),
+        MockRequestDispatcher::default(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
 fn expect_invocation_and_reply_with_empty() -> MockRequestDispatcher {
     let mut mock_dispatcher = MockRequestDispatcher::new();
     mock_dispatcher
@@ -1130,7 +1939,7 @@ pub async fn handle_with_schemas_and_dispatcher<B: http_body::Body + Send + 'sta
     mut req: Request<B>,
     schemas: MockSchemas,
     dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -1149,7 +1958,7 @@ where
 pub async fn handle<B: http_body::Body + Send + 'static>(
     req: Request<B>,
     mock_request_dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -0,0 +1,574 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Transcoding between the content types used by the clients and the ones declared by the handlers.
+//!
+//! Requests to handlers accepting JSON can be sent with any JSON content type (`application/json`
+//! or `<type>/<subtype>+json`), or as `application/x-www-form-urlencoded` forms. Forms are
+//! converted to a JSON object, using the types of the properties of the handler input schema to
+//! convert the fields to numbers, booleans and arrays. The request reaches the handler with the
+//! content type it declared.
+//!
+//! Requests to handlers accepting protobuf can be sent as JSON too, if the deployment registered
+//! the descriptor of the input message at discovery. The JSON body is converted to the message
+//! following the [protobuf JSON mapping](https://protobuf.dev/programming-guides/json/), and
+//! reaches the handler with the content type it declared.
+//!
+//! Responses of handlers returning JSON are sent as `application/json` if the client doesn't
+//! accept the content type declared by the handler, but accepts `application/json`. The same
+//! applies to handlers returning protobuf, whose responses are converted to JSON using the
+//! descriptor of the output message registered at discovery.
+
+use bytes::Bytes;
+use http::HeaderValue;
+use prost::Message;
+use prost_reflect::DynamicMessage;
+use serde_json::{Map, Number, Value};
+use tracing::warn;
+
+use restate_types::schema::invocation_target::{
+    InputContentType, InputRules, InputValidationRule, OutputRules,
+};
+
+use super::error::HandlerError;
+use super::APPLICATION_JSON;
+
+const APPLICATION_FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+
+/// Transcodes the request body to the JSON or protobuf input declared by the handler, returning
+/// the new content type and body. Returns `None` if the request needs no transcoding, or can't be
+/// transcoded, in which case it's validated as is.
+pub(crate) fn transcode_request(
+    input_rules: &InputRules,
+    content_type: Option<&str>,
+    body: &Bytes,
+) -> Result<Option<(HeaderValue, Bytes)>, HandlerError> {
+    let Some(content_type) = content_type else {
+        return Ok(None);
+    };
+    if input_rules.validate(Some(content_type), body).is_ok() {
+        return Ok(None);
+    }
+    let mime_type = essence(content_type);
+
+    if let Some(protobuf_message) = &input_rules.protobuf_message {
+        if !is_json(mime_type) {
+            return Ok(None);
+        }
+        let Some(declared_content_type) =
+            input_rules
+                .input_validation_rules
+                .iter()
+                .find_map(|rule| match rule {
+                    InputValidationRule::ContentType { content_type } => {
+                        declared_content_type(content_type)
+                    }
+                    _ => None,
+                })
+        else {
+            return Ok(None);
+        };
+
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        let message =
+            DynamicMessage::deserialize(protobuf_message.descriptor().clone(), &mut deserializer)
+                .and_then(|message| deserializer.end().map(|_| message))
+                .map_err(|e| {
+                    HandlerError::BadProtobufInput(protobuf_message.name().to_owned(), e)
+                })?;
+        return Ok(Some((
+            declared_content_type,
+            Bytes::from(message.encode_to_vec()),
+        )));
+    }
+
+    let Some((declared_content_type, schema)) =
+        input_rules
+            .input_validation_rules
+            .iter()
+            .find_map(|rule| match rule {
+                InputValidationRule::JsonValue {
+                    content_type,
+                    schema,
+                } => Some((content_type, schema)),
+                _ => None,
+            })
+    else {
+        return Ok(None);
+    };
+    let Some(declared_content_type) = declared_content_type(declared_content_type) else {
+        return Ok(None);
+    };
+
+    Ok(if is_json(mime_type) {
+        Some((declared_content_type, body.clone()))
+    } else if mime_type.eq_ignore_ascii_case(APPLICATION_FORM_URLENCODED) {
+        let value = form_to_json(body, schema);
+        Some((
+            declared_content_type,
+            Bytes::from(serde_json::to_vec(&value).expect("Serializing JSON must not fail")),
+        ))
+    } else {
+        None
+    })
+}
+
+/// Returns the content type the handler receives for the given declared input content type.
+fn declared_content_type(content_type: &InputContentType) -> Option<HeaderValue> {
+    match content_type {
+        InputContentType::Any => Some(APPLICATION_JSON),
+        InputContentType::MimeTypeAndSubtype(ty, sub_ty) => {
+            HeaderValue::try_from(format!("{ty}/{sub_ty}")).ok()
+        }
+        // We can't pick a subtype for the handler
+        InputContentType::MimeType(_) => None,
+    }
+}
+
+/// Transcodes the response body, given the content type declared by the handler and the `Accept`
+/// header of the request, returning the content type and body of the response.
+pub(crate) fn transcode_response(
+    output_rules: &OutputRules,
+    declared_content_type: HeaderValue,
+    accept: Option<&HeaderValue>,
+    body: Bytes,
+) -> (HeaderValue, Bytes) {
+    let Some(protobuf_message) = &output_rules.protobuf_message else {
+        return (
+            negotiate_response_content_type(declared_content_type, accept),
+            body,
+        );
+    };
+    let (Some(accept), Ok(declared)) = (
+        accept.and_then(|accept| accept.to_str().ok()),
+        declared_content_type.to_str(),
+    ) else {
+        return (declared_content_type, body);
+    };
+    if accepts(accept, essence(declared)) || !accepts(accept, "application/json") {
+        return (declared_content_type, body);
+    }
+
+    match DynamicMessage::decode(protobuf_message.descriptor().clone(), body.clone())
+        .map_err(|e| e.to_string())
+        .and_then(|message| serde_json::to_vec(&message).map_err(|e| e.to_string()))
+    {
+        Ok(json) => (APPLICATION_JSON, Bytes::from(json)),
+        Err(error) => {
+            warn!(
+                "Cannot transcode the response to JSON using the protobuf message '{}', sending it as is: {}",
+                protobuf_message.name(),
+                error
+            );
+            (declared_content_type, body)
+        }
+    }
+}
+
+/// Returns the content type of the response, given the one declared by the handler and the
+/// `Accept` header of the request.
+fn negotiate_response_content_type(
+    declared_content_type: HeaderValue,
+    accept: Option<&HeaderValue>,
+) -> HeaderValue {
+    let (Some(accept), Ok(declared)) = (
+        accept.and_then(|accept| accept.to_str().ok()),
+        declared_content_type.to_str(),
+    ) else {
+        return declared_content_type;
+    };
+    let declared = essence(declared);
+
+    if !is_json(declared) || accepts(accept, declared) || !accepts(accept, "application/json") {
+        return declared_content_type;
+    }
+    APPLICATION_JSON
+}
+
+/// Returns the content type without its parameters.
+fn essence(content_type: &str) -> &str {
+    content_type
+        .split_once(';')
+        .map(|(essence, _)| essence)
+        .unwrap_or(content_type)
+        .trim()
+}
+
+fn is_json(mime_type: &str) -> bool {
+    let Some((ty, sub_ty)) = mime_type.split_once('/') else {
+        return false;
+    };
+    ty.eq_ignore_ascii_case("application")
+        && (sub_ty.eq_ignore_ascii_case("json")
+            || sub_ty
+                .rsplit_once('+')
+                .is_some_and(|(_, suffix)| suffix.eq_ignore_ascii_case("json")))
+}
+
+/// Returns true if the `Accept` header accepts the given media type, either explicitly or
+/// through a wildcard. As per [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.1),
+/// the most specific media range matching the media type determines whether it's acceptable,
+/// hence a media range with `q=0` excludes the media type.
+fn accepts(accept: &str, mime_type: &str) -> bool {
+    let Some((ty, _)) = mime_type.split_once('/') else {
+        return false;
+    };
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let specificity = match essence(range) {
+                "*/*" => 0,
+                range if range.eq_ignore_ascii_case(mime_type) => 2,
+                range
+                    if range
+                        .strip_suffix("/*")
+                        .is_some_and(|range_ty| range_ty.eq_ignore_ascii_case(ty)) =>
+                {
+                    1
+                }
+                _ => return None,
+            };
+            Some((specificity, quality(range)))
+        })
+        // On ties, the first matching range wins
+        .rev()
+        .max_by_key(|(specificity, _)| *specificity)
+        .is_some_and(|(_, quality)| quality > 0.0)
+}
+
+/// Returns the quality value of the media range, which defaults to 1.
+fn quality(range: &str) -> f32 {
+    range
+        .split(';')
+        .skip(1)
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+        .map(|(_, value)| value.trim().parse().unwrap_or(1.0))
+        .unwrap_or(1.0)
+}
+
+/// Converts the fields of the form into a JSON object. Repeated fields, and fields of array
+/// properties, are converted to arrays.
+fn form_to_json(body: &Bytes, schema: &Value) -> Value {
+    let mut object = Map::new();
+    for (name, value) in url::form_urlencoded::parse(body) {
+        let property_schema = schema
+            .get("properties")
+            .and_then(|properties| properties.get(name.as_ref()));
+        let is_array = property_schema
+            .and_then(|property_schema| property_schema.get("type"))
+            .is_some_and(|ty| ty == "array");
+        let value = coerce(
+            &value,
+            if is_array {
+                property_schema.and_then(|property_schema| property_schema.get("items"))
+            } else {
+                property_schema
+            },
+        );
+
+        match object.get_mut(name.as_ref()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(previous) => {
+                let previous = previous.take();
+                object.insert(name.into_owned(), Value::Array(vec![previous, value]));
+            }
+            None if is_array => {
+                object.insert(name.into_owned(), Value::Array(vec![value]));
+            }
+            None => {
+                object.insert(name.into_owned(), value);
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+/// Converts the value of a form field to the type of the given schema. Values which can't be
+/// converted are kept as strings, and rejected by the handler input validation if needed.
+fn coerce(value: &str, schema: Option<&Value>) -> Value {
+    let ty = schema
+        .and_then(|schema| schema.get("type"))
+        .and_then(Value::as_str);
+    match ty {
+        Some("integer") => value.parse::<i64>().ok().map(Value::from),
+        Some("number") => value
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        Some("boolean") => value.parse::<bool>().ok().map(Value::Bool),
+        _ => None,
+    }
+    .unwrap_or_else(|| Value::String(value.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+    use restate_types::schema::invocation_target::{OutputContentTypeRule, ProtobufMessage};
+    use serde_json::json;
+
+    const APPLICATION_PROTO: HeaderValue = HeaderValue::from_static("application/proto");
+    // person: "Francesco", count: 2
+    const GREETING: &[u8] = b"\x0a\x09Francesco\x10\x02";
+
+    fn json_input_rules(content_type: InputContentType, schema: Value) -> InputRules {
+        InputRules {
+            input_validation_rules: vec![InputValidationRule::JsonValue {
+                content_type,
+                schema,
+            }],
+            protobuf_message: None,
+        }
+    }
+
+    #[test]
+    fn json_request_to_declared_content_type() {
+        let input_rules = json_input_rules(
+            InputContentType::MimeTypeAndSubtype("application".into(), "vnd.greeting+json".into()),
+            json!({}),
+        );
+        let body = Bytes::from_static(b"{\"person\":\"Francesco\"}");
+
+        assert_eq!(
+            transcode_request(&input_rules, Some("application/json"), &body).unwrap(),
+            Some((
+                HeaderValue::from_static("application/vnd.greeting+json"),
+                body.clone()
+            ))
+        );
+        assert_eq!(
+            transcode_request(&input_rules, Some("application/vnd.greeting+json"), &body).unwrap(),
+            None
+        );
+        assert_eq!(
+            transcode_request(&input_rules, Some("text/plain"), &body).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn form_request_to_json() {
+        let input_rules = json_input_rules(
+            InputContentType::MimeTypeAndSubtype("application".into(), "json".into()),
+            json!({
+                "type": "object",
+                "properties": {
+                    "person": {"type": "string"},
+                    "age": {"type": "integer"},
+                    "vip": {"type": "boolean"},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                }
+            }),
+        );
+        let body =
+            Bytes::from_static(b"person=Francesco&age=30&vip=true&tags=a&repeated=1&repeated=2");
+
+        let (content_type, body) = transcode_request(
+            &input_rules,
+            Some("application/x-www-form-urlencoded"),
+            &body,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(content_type, APPLICATION_JSON);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({
+                "person": "Francesco",
+                "age": 30,
+                "vip": true,
+                "tags": ["a"],
+                "repeated": ["1", "2"]
+            })
+        );
+    }
+
+    fn greeting_message() -> ProtobufMessage {
+        let field = |name: &str, number, ty: Type| FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            json_name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(ty as i32),
+            ..Default::default()
+        };
+        let descriptor_set = FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("greeter.proto".to_owned()),
+                package: Some("greeter".to_owned()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Greeting".to_owned()),
+                    field: vec![
+                        field("person", 1, Type::String),
+                        field("count", 2, Type::Int32),
+                    ],
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".to_owned()),
+                ..Default::default()
+            }],
+        };
+
+        ProtobufMessage::new("greeter.Greeting", descriptor_set.encode_to_vec().into()).unwrap()
+    }
+
+    #[test]
+    fn json_request_to_protobuf() {
+        let input_rules = InputRules {
+            input_validation_rules: vec![InputValidationRule::ContentType {
+                content_type: InputContentType::MimeTypeAndSubtype(
+                    "application".into(),
+                    "proto".into(),
+                ),
+            }],
+            protobuf_message: Some(greeting_message()),
+        };
+
+        assert_eq!(
+            transcode_request(
+                &input_rules,
+                Some("application/json"),
+                &Bytes::from_static(b"{\"person\":\"Francesco\",\"count\":2}")
+            )
+            .unwrap(),
+            Some((APPLICATION_PROTO, Bytes::from_static(GREETING)))
+        );
+        assert_eq!(
+            transcode_request(
+                &input_rules,
+                Some("application/proto"),
+                &Bytes::from_static(GREETING)
+            )
+            .unwrap(),
+            None
+        );
+        assert!(matches!(
+            transcode_request(
+                &input_rules,
+                Some("application/json"),
+                &Bytes::from_static(b"{\"person\":3}")
+            ),
+            Err(HandlerError::BadProtobufInput(name, _)) if name == "greeter.Greeting"
+        ));
+    }
+
+    #[test]
+    fn protobuf_response_to_json() {
+        let output_rules = OutputRules {
+            content_type_rule: OutputContentTypeRule::Set {
+                content_type: APPLICATION_PROTO,
+                set_content_type_if_empty: true,
+                has_json_schema: false,
+            },
+            json_schema: None,
+            protobuf_message: Some(greeting_message()),
+        };
+        let body = Bytes::from_static(GREETING);
+
+        let (content_type, json) = transcode_response(
+            &output_rules,
+            APPLICATION_PROTO,
+            Some(&HeaderValue::from_static("application/json")),
+            body.clone(),
+        );
+        assert_eq!(content_type, APPLICATION_JSON);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&json).unwrap(),
+            json!({"person": "Francesco", "count": 2})
+        );
+
+        assert_eq!(
+            transcode_response(
+                &output_rules,
+                APPLICATION_PROTO,
+                Some(&HeaderValue::from_static(
+                    "application/proto, application/json"
+                )),
+                body.clone(),
+            ),
+            (APPLICATION_PROTO, body.clone())
+        );
+        assert_eq!(
+            transcode_response(&output_rules, APPLICATION_PROTO, None, body.clone()),
+            (APPLICATION_PROTO, body)
+        );
+    }
+
+    #[test]
+    fn response_content_type() {
+        let declared = HeaderValue::from_static("application/vnd.greeting+json");
+
+        assert_eq!(
+            negotiate_response_content_type(declared.clone(), None),
+            declared
+        );
+        assert_eq!(
+            negotiate_response_content_type(
+                declared.clone(),
+                Some(&HeaderValue::from_static("application/json"))
+            ),
+            APPLICATION_JSON
+        );
+        assert_eq!(
+            negotiate_response_content_type(
+                declared.clone(),
+                Some(&HeaderValue::from_static("application/*, text/plain"))
+            ),
+            declared
+        );
+        assert_eq!(
+            negotiate_response_content_type(
+                HeaderValue::from_static("text/plain"),
+                Some(&HeaderValue::from_static("application/json"))
+            ),
+            HeaderValue::from_static("text/plain")
+        );
+        // The declared content type is excluded
+        assert_eq!(
+            negotiate_response_content_type(
+                declared.clone(),
+                Some(&HeaderValue::from_static(
+                    "application/vnd.greeting+json;q=0, application/json"
+                ))
+            ),
+            APPLICATION_JSON
+        );
+        // Both are excluded, hence there's nothing better than the declared content type
+        assert_eq!(
+            negotiate_response_content_type(
+                declared.clone(),
+                Some(&HeaderValue::from_static("application/*;q=0"))
+            ),
+            declared
+        );
+    }
+
+    #[test]
+    fn accepts_with_quality() {
+        assert!(accepts("application/json", "application/json"));
+        assert!(accepts("*/*;q=0.1", "application/json"));
+        assert!(!accepts("application/json;q=0", "application/json"));
+        assert!(!accepts("application/json; q=0.0, */*", "application/json"));
+        assert!(accepts(
+            "application/*;q=0, application/json",
+            "application/json"
+        ));
+        assert!(!accepts("*/*, application/*;q=0", "application/json"));
+        assert!(!accepts("text/plain", "application/json"));
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use hmac::{Hmac, Mac};
+use http::{HeaderMap, HeaderName, Method, Request, Response};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use metrics::counter;
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::{info, Instrument};
+
+use restate_types::identifiers::{InvocationId, SubscriptionId};
+use restate_types::invocation::{
+    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
+    SpanRelation, WorkflowHandlerType,
+};
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::schema::subscriptions::{
+    Source, Subscription, SubscriptionResolver, WebhookProvider,
+};
+
+use super::service_handler::parse_headers;
+use super::tracing::prepare_tracing_span;
+use super::{Handler, HandlerError};
+use crate::metric_definitions::INGRESS_WEBHOOK_EVENTS;
+use crate::RequestDispatcher;
+
+const STRIPE_SIGNATURE: HeaderName = HeaderName::from_static("stripe-signature");
+const GITHUB_SIGNATURE: HeaderName = HeaderName::from_static("x-hub-signature-256");
+const GITHUB_DELIVERY: HeaderName = HeaderName::from_static("x-github-delivery");
+const SHA256_PREFIX: &str = "sha256=";
+/// Max size of the body of the webhook requests, matching the max payload size of GitHub.
+const MAX_WEBHOOK_BODY_SIZE: usize = 25 * 1024 * 1024;
+
+/// Body of the Stripe events, only the fields used by the ingress.
+#[derive(Deserialize)]
+struct StripeEvent {
+    id: String,
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: InvocationTargetResolver + SubscriptionResolver + Clone + Send + Sync + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    /// Ingests an event posted by the provider of a webhook subscription. The event id becomes
+    /// the idempotency key of the invocation, so that the requests replayed by the provider, or
+    /// by an attacker, don't invoke the sink again.
+    pub(crate) async fn handle_webhook<B: http_body::Body>(
+        self,
+        req: Request<B>,
+        subscription_id: SubscriptionId,
+    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    where
+        <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let subscription = self
+            .schemas
+            .pinned()
+            .get_subscription(subscription_id)
+            .ok_or(HandlerError::NotFound)?;
+        let Source::Webhook { provider } = *subscription.source() else {
+            return Err(HandlerError::NotFound);
+        };
+
+        if req.method() != Method::POST {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+
+        // Collect body, up to the limit since the requests are not authenticated yet
+        let (parts, body) = req.into_parts();
+        let body = Limited::new(body, MAX_WEBHOOK_BODY_SIZE)
+            .collect()
+            .await
+            .map_err(|e| {
+                if e.is::<LengthLimitError>() {
+                    HandlerError::WebhookEventTooLarge(MAX_WEBHOOK_BODY_SIZE)
+                } else {
+                    HandlerError::Body(anyhow::anyhow!(e))
+                }
+            })?
+            .to_bytes();
+        let req = Request::from_parts(parts, ());
+
+        let event_id = verify_event(provider, &subscription, req.headers(), &body)?;
+
+        let invocation_target = invocation_target(&subscription, req.headers())?;
+        let invocation_target_meta = self
+            .schemas
+            .pinned()
+            .resolve_latest_invocation_target(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+            )
+            .ok_or_else(|| {
+                HandlerError::ServiceHandlerNotFound(
+                    invocation_target.service_name().to_string(),
+                    invocation_target.handler_name().to_string(),
+                )
+            })?;
+
+        // Workflow runs are already deduplicated by the workflow key
+        let idempotency_key = (invocation_target_meta.target_ty
+            != InvocationTargetType::Workflow(WorkflowHandlerType::Workflow))
+        .then(|| ByteString::from(event_id));
+        let invocation_id = InvocationId::generate(&invocation_target, idempotency_key.as_deref());
+
+        let runtime_span = tracing::info_span!(
+            "ingress_webhook",
+            restate.invocation.id = %invocation_id,
+            restate.invocation.target = %invocation_target.short(),
+            restate.subscription.id = %subscription_id,
+        );
+
+        async move {
+            let ingress_span_context =
+                prepare_tracing_span(&invocation_id, &invocation_target, &req);
+
+            info!("Processing webhook request");
+
+            let mut headers = parse_headers(req.into_parts().0)?;
+            headers.push(Header::new(
+                "restate.subscription.id",
+                subscription_id.to_string(),
+            ));
+
+            let mut invocation_request_header =
+                InvocationRequestHeader::initialize(invocation_id, invocation_target);
+            invocation_request_header.with_related_span(SpanRelation::Parent(ingress_span_context));
+            invocation_request_header.completion_retention_duration =
+                invocation_target_meta.compute_retention(idempotency_key.is_some());
+            invocation_request_header.idempotency_key = idempotency_key;
+            invocation_request_header.headers = headers;
+
+            counter!(INGRESS_WEBHOOK_EVENTS, "provider" => provider.to_string()).increment(1);
+
+            Self::handle_service_send(
+                InvocationRequest::new(invocation_request_header, body),
+                self.dispatcher,
+            )
+            .await
+        }
+        .instrument(runtime_span)
+        .await
+    }
+}
+
+/// Verifies the signature of the request, returning the id of the event.
+fn verify_event(
+    provider: WebhookProvider,
+    subscription: &Subscription,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<String, HandlerError> {
+    let secret = subscription
+        .webhook_secret()
+        .expect("webhook subscriptions have a secret")
+        .as_bytes();
+
+    match provider {
+        WebhookProvider::Stripe => {
+            // Format: t=<timestamp>,v1=<signature>[,v1=<signature>...]
+            let mut timestamp = None;
+            let mut signatures = vec![];
+            for item in signature_header(headers, &STRIPE_SIGNATURE)?.split(',') {
+                match item.trim().split_once('=') {
+                    Some(("t", value)) => timestamp = value.parse::<u64>().ok(),
+                    Some(("v1", value)) => signatures.push(value),
+                    _ => {}
+                }
+            }
+            let timestamp = timestamp.ok_or(HandlerError::BadWebhookSignature(
+                "missing timestamp in the stripe-signature header",
+            ))?;
+            verify_timestamp(subscription, timestamp)?;
+
+            let signed_payload = timestamped_payload(timestamp, body);
+            if !signatures
+                .into_iter()
+                .any(|signature| verify_hmac(secret, &signed_payload, signature))
+            {
+                return Err(HandlerError::BadWebhookSignature("signature mismatch"));
+            }
+
+            serde_json::from_slice::<StripeEvent>(body)
+                .map(|event| event.id)
+                .map_err(|e| HandlerError::BadWebhookEvent(format!("invalid Stripe event: {e}")))
+        }
+        WebhookProvider::Github => {
+            let signature = signature_header(headers, &GITHUB_SIGNATURE)?;
+            if !verify_hmac(secret, body, strip_sha256_prefix(signature)) {
+                return Err(HandlerError::BadWebhookSignature("signature mismatch"));
+            }
+
+            Ok(event_id_header(headers, &GITHUB_DELIVERY)?.to_owned())
+        }
+        WebhookProvider::Hmac => {
+            let signature_header_name =
+                HeaderName::try_from(subscription.webhook_signature_header())
+                    .expect("header name is validated when creating the subscription");
+            let signature = strip_sha256_prefix(signature_header(headers, &signature_header_name)?);
+            let timestamp_header_name =
+                HeaderName::try_from(subscription.webhook_timestamp_header())
+                    .expect("header name is validated when creating the subscription");
+            let timestamp = header_str(headers, &timestamp_header_name)?
+                .and_then(|timestamp| timestamp.parse::<u64>().ok())
+                .ok_or(HandlerError::BadWebhookSignature(
+                    "missing or invalid timestamp header",
+                ))?;
+            verify_timestamp(subscription, timestamp)?;
+
+            // The timestamp is signed together with the body, so that it can't be replaced
+            if !verify_hmac(secret, &timestamped_payload(timestamp, body), signature) {
+                return Err(HandlerError::BadWebhookSignature("signature mismatch"));
+            }
+
+            match subscription.webhook_id_header() {
+                Some(id_header) => {
+                    let id_header = HeaderName::try_from(id_header)
+                        .expect("header name is validated when creating the subscription");
+                    Ok(event_id_header(headers, &id_header)?.to_owned())
+                }
+                // Replays of the same body share the signature
+                None => Ok(signature.to_ascii_lowercase()),
+            }
+        }
+    }
+}
+
+fn invocation_target(
+    subscription: &Subscription,
+    headers: &HeaderMap,
+) -> Result<InvocationTarget, HandlerError> {
+    let target_key = || -> Result<String, HandlerError> {
+        let key = subscription
+            .webhook_key_header()
+            .and_then(|key_header| headers.get(key_header))
+            .ok_or_else(|| {
+                HandlerError::BadWebhookEvent(format!(
+                    "the request has no key, which is required to invoke {}",
+                    subscription.sink()
+                ))
+            })?;
+        key.to_str()
+            .map(str::to_owned)
+            .map_err(|e| HandlerError::BadWebhookEvent(format!("the key is not valid: {e}")))
+    };
+
+    subscription.sink().invocation_target(target_key)
+}
+
+fn signature_header<'a>(
+    headers: &'a HeaderMap,
+    name: &HeaderName,
+) -> Result<&'a str, HandlerError> {
+    header_str(headers, name)?.ok_or(HandlerError::BadWebhookSignature(
+        "missing signature header",
+    ))
+}
+
+fn event_id_header<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Result<&'a str, HandlerError> {
+    header_str(headers, name)?
+        .ok_or_else(|| HandlerError::BadWebhookEvent(format!("missing event id header {name}")))
+}
+
+fn header_str<'a>(
+    headers: &'a HeaderMap,
+    name: &HeaderName,
+) -> Result<Option<&'a str>, HandlerError> {
+    headers
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|e| HandlerError::BadHeader(name.clone(), e))
+        })
+        .transpose()
+}
+
+/// Rejects the requests signed too long ago, which might be replayed by an attacker after the
+/// idempotency key of the original event has expired.
+fn verify_timestamp(subscription: &Subscription, timestamp: u64) -> Result<(), HandlerError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("duration since Unix epoch should be well-defined")
+        .as_secs();
+    if now.abs_diff(timestamp) > subscription.webhook_timestamp_tolerance().as_secs() {
+        return Err(HandlerError::BadWebhookSignature(
+            "the timestamp is outside of the tolerance",
+        ));
+    }
+    Ok(())
+}
+
+fn timestamped_payload(timestamp: u64, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{timestamp}.").into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+fn strip_sha256_prefix(signature: &str) -> &str {
+    signature.strip_prefix(SHA256_PREFIX).unwrap_or(signature)
+}
+
+/// Compares the hex encoded signature with the HMAC-SHA256 of the payload, in constant time.
+fn verify_hmac(secret: &[u8], payload: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+pub(super) fn sign(secret: &str, payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
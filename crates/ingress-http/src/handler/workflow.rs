@@ -9,7 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use bytes::Bytes;
-use http::{Method, Request, Response};
+use http::{header, Method, Request, Response};
 use http_body_util::Full;
 use tracing::{info, warn};
 
@@ -83,15 +83,19 @@ where
             AttachInvocationResponse::Ready(response) => response,
         };
 
-        Self::reply_with_invocation_response(response, move |invocation_target| {
-            self.schemas
-                .pinned()
-                .resolve_latest_invocation_target(
-                    invocation_target.service_name(),
-                    invocation_target.handler_name(),
-                )
-                .ok_or(HandlerError::NotFound)
-        })
+        Self::reply_with_invocation_response(
+            response,
+            req.headers().get(header::ACCEPT),
+            move |invocation_target| {
+                self.schemas
+                    .pinned()
+                    .resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                    .ok_or(HandlerError::NotFound)
+            },
+        )
     }
 
     pub(crate) async fn handle_workflow_get_output<B: http_body::Body>(
@@ -130,14 +134,18 @@ where
             }
         };
 
-        Self::reply_with_invocation_response(response, move |invocation_target| {
-            self.schemas
-                .pinned()
-                .resolve_latest_invocation_target(
-                    invocation_target.service_name(),
-                    invocation_target.handler_name(),
-                )
-                .ok_or(HandlerError::NotFound)
-        })
+        Self::reply_with_invocation_response(
+            response,
+            req.headers().get(header::ACCEPT),
+            move |invocation_target| {
+                self.schemas
+                    .pinned()
+                    .resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                    .ok_or(HandlerError::NotFound)
+            },
+        )
     }
 }
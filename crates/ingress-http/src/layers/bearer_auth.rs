@@ -0,0 +1,145 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use futures::future::{self, Either, Ready};
+use http::{header, Request, Response, StatusCode};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::debug;
+
+use crate::auth::constant_time_eq;
+
+/// Rejects requests which don't carry the expected `Authorization: Bearer <token>` header.
+/// If no token is configured, all requests are let through.
+#[derive(Clone)]
+pub struct BearerAuthLayer {
+    token: Option<Arc<str>>,
+}
+
+impl BearerAuthLayer {
+    pub fn new(token: Option<&str>) -> Self {
+        Self {
+            token: token.map(Arc::from),
+        }
+    }
+}
+
+impl<S> Layer<S> for BearerAuthLayer {
+    type Service = BearerAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerAuth {
+            inner,
+            token: self.token.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BearerAuth<S> {
+    inner: S,
+    token: Option<Arc<str>>,
+}
+
+impl<S> BearerAuth<S> {
+    fn is_authorized<B>(&self, req: &Request<B>) -> bool {
+        let Some(expected_token) = &self.token else {
+            return true;
+        };
+
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| {
+                constant_time_eq(token.trim().as_bytes(), expected_token.as_bytes())
+            })
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for BearerAuth<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: Default,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Response<ResBody>, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        if self.is_authorized(&req) {
+            return Either::Left(self.inner.call(req));
+        }
+
+        debug!("Rejecting request with missing or invalid bearer token");
+        Either::Right(future::ready(Ok(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header(header::WWW_AUTHENTICATE, "Bearer")
+            .body(Default::default())
+            .unwrap())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    async fn call(token: Option<&str>, authorization: Option<&str>) -> StatusCode {
+        let service = BearerAuthLayer::new(token).layer(service_fn(|_: Request<()>| async {
+            Ok::<_, Infallible>(Response::new(()))
+        }));
+
+        let mut request = Request::builder();
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        service
+            .oneshot(request.body(()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn accepts_any_request_without_token() {
+        assert_eq!(call(None, None).await, StatusCode::OK);
+        assert_eq!(call(None, Some("Bearer foo")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn accepts_only_the_configured_token() {
+        assert_eq!(
+            call(Some("secret"), Some("Bearer secret")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(Some("secret"), Some("Bearer other")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call(Some("secret"), Some("Bearer secret-suffix")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call(Some("secret"), Some("Basic secret")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(call(Some("secret"), None).await, StatusCode::UNAUTHORIZED);
+    }
+}
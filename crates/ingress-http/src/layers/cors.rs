@@ -0,0 +1,156 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use http::{HeaderName, HeaderValue, Method};
+use restate_types::config::{IngressCorsOptions, CORS_WILDCARD};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
+use tracing::warn;
+
+/// Builds the CORS layer from the configured policy. Empty lists of origins, methods and headers
+/// mirror the request, allowing any of them, while `*` allows any of them with the wildcard.
+/// Invalid entries are ignored.
+pub fn cors_layer(options: &IngressCorsOptions) -> CorsLayer {
+    let mut layer = CorsLayer::new().allow_credentials(options.allow_credentials);
+
+    layer = if options.allowed_origins.is_empty() {
+        layer.allow_origin(AllowOrigin::mirror_request())
+    } else if is_wildcard(&options.allowed_origins) {
+        layer.allow_origin(AllowOrigin::any())
+    } else {
+        layer.allow_origin(AllowOrigin::list(parse_all::<HeaderValue>(
+            &options.allowed_origins,
+            "origin",
+        )))
+    };
+    layer = if options.allowed_methods.is_empty() {
+        layer.allow_methods(AllowMethods::mirror_request())
+    } else if is_wildcard(&options.allowed_methods) {
+        layer.allow_methods(AllowMethods::any())
+    } else {
+        layer.allow_methods(AllowMethods::list(parse_all::<Method>(
+            &options.allowed_methods,
+            "method",
+        )))
+    };
+    layer = if options.allowed_headers.is_empty() {
+        layer.allow_headers(AllowHeaders::mirror_request())
+    } else if is_wildcard(&options.allowed_headers) {
+        layer.allow_headers(AllowHeaders::any())
+    } else {
+        layer.allow_headers(AllowHeaders::list(parse_all::<HeaderName>(
+            &options.allowed_headers,
+            "header",
+        )))
+    };
+    if is_wildcard(&options.exposed_headers) {
+        layer = layer.expose_headers(ExposeHeaders::any());
+    } else if !options.exposed_headers.is_empty() {
+        layer = layer.expose_headers(ExposeHeaders::list(parse_all::<HeaderName>(
+            &options.exposed_headers,
+            "exposed header",
+        )));
+    }
+    if let Some(max_age) = options.max_age() {
+        layer = layer.max_age(max_age);
+    }
+
+    layer
+}
+
+/// The wildcard allows any value, which makes the other values of the list redundant.
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|value| value == CORS_WILDCARD)
+}
+
+fn parse_all<T>(values: &[String], kind: &str) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    values
+        .iter()
+        .filter_map(|value| match value.parse() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("Ignoring invalid CORS {} '{}': {}", kind, value, err);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use http::{header, Request, Response};
+    use tower::{service_fn, Layer, ServiceExt};
+
+    fn cors_options(options: serde_json::Value) -> Result<IngressCorsOptions, serde_json::Error> {
+        serde_json::from_value(options)
+    }
+
+    #[tokio::test]
+    async fn wildcard_allows_any_origin_and_header() {
+        let options = cors_options(serde_json::json!({
+            "allowed-origins": ["*"],
+            "allowed-headers": ["*"],
+            "allow-credentials": false,
+        }))
+        .unwrap();
+        let service = cors_layer(&options).layer(service_fn(|_| async {
+            Ok::<_, Infallible>(Response::new(()))
+        }));
+
+        let response = service
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .header(header::ORIGIN, "https://app.example.com")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "x-custom")
+                    .body(())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            CORS_WILDCARD
+        );
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            CORS_WILDCARD
+        );
+    }
+
+    #[test]
+    fn reject_wildcard_with_credentials() {
+        assert!(cors_options(serde_json::json!({
+            "allowed-origins": ["*"],
+        }))
+        .is_err());
+        assert!(cors_options(serde_json::json!({
+            "allowed-headers": ["*"],
+            "allow-credentials": true,
+        }))
+        .is_err());
+        assert!(cors_options(serde_json::json!({
+            "allowed-origins": ["https://app.example.com"],
+        }))
+        .is_ok());
+    }
+}
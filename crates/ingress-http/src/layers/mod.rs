@@ -8,5 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod bearer_auth;
+pub mod cors;
 pub mod load_shed;
+pub mod security_headers;
 pub mod tracing_context_extractor;
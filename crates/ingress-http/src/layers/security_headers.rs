@@ -0,0 +1,175 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use http::{header, HeaderName, HeaderValue, Request, Response};
+use pin_project_lite::pin_project;
+use restate_types::config::IngressSecurityHeadersOptions;
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// Adds the configured security headers to the responses, unless they are already set.
+#[derive(Clone)]
+pub struct SecurityHeadersLayer {
+    headers: Arc<[(HeaderName, HeaderValue)]>,
+}
+
+impl SecurityHeadersLayer {
+    pub fn new(options: &IngressSecurityHeadersOptions) -> Self {
+        let mut headers = Vec::new();
+        if options.enabled {
+            headers.push((
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            ));
+            headers.push((header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")));
+            headers.push((
+                header::REFERRER_POLICY,
+                HeaderValue::from_static("no-referrer"),
+            ));
+        }
+        if let Some(max_age) = options.hsts_max_age() {
+            headers.push((
+                header::STRICT_TRANSPORT_SECURITY,
+                HeaderValue::from_str(&format!("max-age={}", max_age.as_secs()))
+                    .expect("max-age is a valid header value"),
+            ));
+        }
+        if let Some(policy) = &options.content_security_policy {
+            match HeaderValue::from_str(policy) {
+                Ok(value) => headers.push((header::CONTENT_SECURITY_POLICY, value)),
+                Err(err) => warn!("Ignoring invalid content security policy: {}", err),
+            }
+        }
+
+        Self {
+            headers: headers.into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for SecurityHeadersLayer {
+    type Service = SecurityHeaders<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SecurityHeaders {
+            inner,
+            headers: Arc::clone(&self.headers),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SecurityHeaders<S> {
+    inner: S,
+    headers: Arc<[(HeaderName, HeaderValue)]>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SecurityHeaders<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        ResponseFuture {
+            fut: self.inner.call(req),
+            headers: Arc::clone(&self.headers),
+        }
+    }
+}
+
+pin_project! {
+    pub struct ResponseFuture<F> {
+        #[pin]
+        fut: F,
+        headers: Arc<[(HeaderName, HeaderValue)]>,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut response = ready!(this.fut.poll(cx))?;
+        for (name, value) in this.headers.iter() {
+            if !response.headers().contains_key(name) {
+                response.headers_mut().insert(name.clone(), value.clone());
+            }
+        }
+        Poll::Ready(Ok(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    #[tokio::test]
+    async fn adds_missing_headers() {
+        let options: IngressSecurityHeadersOptions = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "hsts-max-age": "1d"
+        }))
+        .unwrap();
+        let service = SecurityHeadersLayer::new(&options).layer(service_fn(|_| async {
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .header(header::X_FRAME_OPTIONS, "SAMEORIGIN")
+                    .body(())
+                    .unwrap(),
+            )
+        }));
+
+        let response = service.oneshot(Request::new(())).await.unwrap();
+
+        assert_eq!(
+            response.headers()[header::X_CONTENT_TYPE_OPTIONS],
+            "nosniff"
+        );
+        assert_eq!(response.headers()[header::X_FRAME_OPTIONS], "SAMEORIGIN");
+        assert_eq!(
+            response.headers()[header::STRICT_TRANSPORT_SECURITY],
+            "max-age=86400"
+        );
+        assert!(!response
+            .headers()
+            .contains_key(header::CONTENT_SECURITY_POLICY));
+    }
+
+    #[tokio::test]
+    async fn disabled_by_default() {
+        let service = SecurityHeadersLayer::new(&IngressSecurityHeadersOptions::default()).layer(
+            service_fn(|_| async { Ok::<_, Infallible>(Response::new(())) }),
+        );
+
+        let response = service.oneshot(Request::new(())).await.unwrap();
+
+        assert!(response.headers().is_empty());
+    }
+}
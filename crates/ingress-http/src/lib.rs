@@ -8,11 +8,15 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod auth;
 mod handler;
 mod layers;
 mod metric_definitions;
+mod rate_limit;
 pub mod rpc_request_dispatcher;
 mod server;
+mod slo;
+mod tls;
 
 pub use server::{HyperServerIngress, IngressServerError, StartSignal};
 
@@ -21,10 +25,12 @@ use std::future::Future;
 use std::net::{IpAddr, SocketAddr};
 
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
 };
+use restate_types::identifiers::PartitionId;
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::net::partition_processor::{InvocationOutput, SubmittedInvocationNotification};
+use restate_types::schedule::Schedule;
 
 /// Client connection information for a given RPC request
 #[derive(Clone, Copy, Debug)]
@@ -46,6 +52,8 @@ impl ConnectInfo {
 
 #[derive(Debug, thiserror::Error)]
 pub enum RequestDispatcherError {
+    #[error("partition '{0}' is overloaded, retry later")]
+    Overloaded(PartitionId),
     #[error(transparent)]
     Internal(#[from] anyhow::Error),
 }
@@ -59,6 +67,12 @@ pub trait RequestDispatcher {
         invocation_request: InvocationRequest,
     ) -> impl Future<Output = Result<SubmittedInvocationNotification, RequestDispatcherError>> + Send;
 
+    /// Send batch: append the invocations, returning the result of each of them in the same order
+    fn send_batch(
+        &self,
+        invocation_requests: Vec<InvocationRequest>,
+    ) -> impl Future<Output = Vec<Result<(), RequestDispatcherError>>> + Send;
+
     /// Call: append invocation and wait for its response
     fn call(
         &self,
@@ -77,17 +91,29 @@ pub trait RequestDispatcher {
         invocation_query: InvocationQuery,
     ) -> impl Future<Output = Result<GetInvocationOutputResponse, RequestDispatcherError>> + Send;
 
+    /// Get invocation progress, without blocking.
+    fn get_invocation_progress(
+        &self,
+        invocation_query: InvocationQuery,
+    ) -> impl Future<Output = Result<GetInvocationProgressResponse, RequestDispatcherError>> + Send;
+
     /// Send invocation response (for awakeables).
     fn send_invocation_response(
         &self,
         invocation_response: InvocationResponse,
     ) -> impl Future<Output = Result<(), RequestDispatcherError>> + Send;
+
+    /// Create schedule: append the recurring schedule, returning once it's appended.
+    fn create_schedule(
+        &self,
+        schedule: Schedule,
+    ) -> impl Future<Output = Result<(), RequestDispatcherError>> + Send;
 }
 
 // Contains some mocks we use in unit tests in this crate
 #[cfg(test)]
 mod mocks {
-    use restate_types::identifiers::DeploymentId;
+    use restate_types::identifiers::{DeploymentId, SubscriptionId};
     use restate_types::invocation::{
         InvocationQuery, InvocationTargetType, ServiceType, VirtualObjectHandlerType,
     };
@@ -100,8 +126,12 @@ mod mocks {
     use restate_types::schema::service::{
         HandlerMetadata, ServiceMetadata, ServiceMetadataResolver,
     };
+    use restate_types::schema::subscriptions::{
+        ListSubscriptionFilter, Subscription, SubscriptionResolver,
+    };
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use super::*;
@@ -120,6 +150,7 @@ mod mocks {
     pub(crate) struct MockSchemas(
         pub(crate) MockServiceMetadataResolver,
         pub(crate) MockInvocationTargetResolver,
+        pub(crate) HashMap<SubscriptionId, Subscription>,
     );
 
     impl MockSchemas {
@@ -140,6 +171,13 @@ mod mocks {
                     output_description: "any".to_string(),
                     input_json_schema: None,
                     output_json_schema: None,
+                    idempotency_retention: None,
+                    completion_retention: None,
+                    retry_policy: None,
+                    execution_timeout: None,
+                    inactivity_timeout: None,
+                    abort_timeout: None,
+                    public: invocation_target_metadata.public,
                 }],
                 ty: invocation_target_metadata.target_ty.into(),
                 documentation: None,
@@ -147,10 +185,19 @@ mod mocks {
                 deployment_id: DeploymentId::default(),
                 revision: 0,
                 public: invocation_target_metadata.public,
+                allowed_callers: invocation_target_metadata.allowed_callers.clone(),
+                paused: false,
                 idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION.into(),
                 workflow_completion_retention: None,
+                completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                mirroring: None,
+                routing: None,
+                shared_handler_concurrency: None,
+                concurrency_limit: None,
+                retry_policy: None,
+                execution_timeout: None,
             });
             self.1
                 .add(service_name, [(handler_name, invocation_target_metadata)]);
@@ -165,6 +212,11 @@ mod mocks {
             self.add_service_and_target(service_name, handler_name, invocation_target_metadata);
             self
         }
+
+        pub fn with_subscription(mut self, subscription: Subscription) -> Self {
+            self.2.insert(subscription.id(), subscription);
+            self
+        }
     }
 
     impl ServiceMetadataResolver for MockSchemas {
@@ -176,6 +228,10 @@ mod mocks {
             todo!()
         }
 
+        fn resolve_latest_services_openapi(&self) -> Value {
+            todo!()
+        }
+
         fn resolve_latest_service_type(
             &self,
             service_name: impl AsRef<str>,
@@ -199,6 +255,20 @@ mod mocks {
         }
     }
 
+    impl SubscriptionResolver for MockSchemas {
+        fn get_subscription(&self, id: SubscriptionId) -> Option<Subscription> {
+            self.2.get(&id).cloned()
+        }
+
+        fn list_subscriptions(&self, filters: &[ListSubscriptionFilter]) -> Vec<Subscription> {
+            self.2
+                .values()
+                .filter(|sub| filters.iter().all(|f| f.matches(sub)))
+                .cloned()
+                .collect()
+        }
+    }
+
     pub(super) fn mock_schemas() -> MockSchemas {
         let mut mock_schemas = MockSchemas::default();
 
@@ -227,6 +297,13 @@ mod mocks {
             MockRequestDispatcher::send(self, invocation_request)
         }
 
+        fn send_batch(
+            &self,
+            invocation_requests: Vec<InvocationRequest>,
+        ) -> impl Future<Output = Vec<Result<(), RequestDispatcherError>>> + Send {
+            MockRequestDispatcher::send_batch(self, invocation_requests)
+        }
+
         fn call(
             &self,
             invocation_request: InvocationRequest,
@@ -250,11 +327,26 @@ mod mocks {
             MockRequestDispatcher::get_invocation_output(self, invocation_query)
         }
 
+        fn get_invocation_progress(
+            &self,
+            invocation_query: InvocationQuery,
+        ) -> impl Future<Output = Result<GetInvocationProgressResponse, RequestDispatcherError>> + Send
+        {
+            MockRequestDispatcher::get_invocation_progress(self, invocation_query)
+        }
+
         fn send_invocation_response(
             &self,
             invocation_response: InvocationResponse,
         ) -> impl Future<Output = Result<(), RequestDispatcherError>> + Send {
             MockRequestDispatcher::send_invocation_response(self, invocation_response)
         }
+
+        fn create_schedule(
+            &self,
+            schedule: Schedule,
+        ) -> impl Future<Output = Result<(), RequestDispatcherError>> + Send {
+            MockRequestDispatcher::create_schedule(self, schedule)
+        }
     }
 }
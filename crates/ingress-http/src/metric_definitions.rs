@@ -10,7 +10,7 @@
 
 /// Optional to have but adds description/help message to the metrics emitted to
 /// the metrics' sink.
-use metrics::{describe_counter, describe_histogram, Unit};
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
 
 pub const INGRESS_REQUESTS: &str = "restate.ingress.requests.total";
 // values of label `status` in INGRESS_REQUEST
@@ -20,6 +20,12 @@ pub const REQUEST_DENIED_THROTTLE: &str = "throttled";
 
 pub const INGRESS_REQUEST_DURATION: &str = "restate.ingress.request_duration.seconds";
 
+pub const INGRESS_MIRRORED_REQUESTS: &str = "restate.ingress.mirrored_requests.total";
+
+pub const INGRESS_SLO_BURN_RATE: &str = "restate.ingress.slo.burn_rate";
+
+pub const INGRESS_WEBHOOK_EVENTS: &str = "restate.ingress.webhook_events.total";
+
 pub(crate) fn describe_metrics() {
     describe_counter!(
         INGRESS_REQUESTS,
@@ -31,4 +37,19 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Total latency of Ingress request processing in seconds"
     );
+    describe_counter!(
+        INGRESS_MIRRORED_REQUESTS,
+        Unit::Count,
+        "Number of ingress requests mirrored to a shadow deployment"
+    );
+    describe_counter!(
+        INGRESS_WEBHOOK_EVENTS,
+        Unit::Count,
+        "Number of webhook events with a valid signature, see label provider"
+    );
+    describe_gauge!(
+        INGRESS_SLO_BURN_RATE,
+        Unit::Count,
+        "Rate at which the error budget of a service level objective is consumed, see labels objective and window"
+    );
 }
@@ -0,0 +1,272 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Admission control of the ingress requests, see [`IngressRateLimitOptions`].
+//!
+//! The limits are read from the live configuration on every admission, so that changes to them
+//! are applied without restarting the ingress.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use restate_types::config::{IngressRateLimitOptions, RateLimitOptions};
+use restate_types::live::BoxedLiveLoad;
+
+/// Suggested delay before retrying a request rejected because of the in-flight caps.
+const IN_FLIGHT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+pub(crate) struct RateLimiter {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    options: BoxedLiveLoad<IngressRateLimitOptions>,
+    global: LimiterState,
+    services: HashMap<String, LimiterState>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    bucket: Option<TokenBucket>,
+    in_flight: usize,
+}
+
+impl LimiterState {
+    /// Checks whether a request can be admitted, without admitting it. Returns the delay after
+    /// which the request should be retried otherwise.
+    fn check(&mut self, limits: &RateLimitOptions, now: Instant) -> Result<(), Duration> {
+        if limits
+            .max_in_flight_requests
+            .is_some_and(|max| self.in_flight >= max.get())
+        {
+            return Err(IN_FLIGHT_RETRY_AFTER);
+        }
+
+        match limits.token_bucket() {
+            Some((rate, burst)) => self
+                .bucket
+                .get_or_insert_with(|| TokenBucket::new(burst.get(), now))
+                .check(rate.get(), burst.get(), now),
+            None => {
+                self.bucket = None;
+                Ok(())
+            }
+        }
+    }
+
+    fn admit(&mut self) {
+        if let Some(bucket) = &mut self.bucket {
+            bucket.tokens -= 1.0;
+        }
+        self.in_flight += 1;
+    }
+
+    fn release(&mut self) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(burst: u32, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(burst),
+            last_refill: now,
+        }
+    }
+
+    fn check(&mut self, rate: u32, burst: u32, now: Instant) -> Result<(), Duration> {
+        let rate = f64::from(rate);
+        self.tokens = (self.tokens
+            + now
+                .saturating_duration_since(self.last_refill)
+                .as_secs_f64()
+                * rate)
+            .min(f64::from(burst));
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn new(options: BoxedLiveLoad<IngressRateLimitOptions>) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                options,
+                global: LimiterState::default(),
+                services: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Admits a request to the given service, if any. The returned permit counts as in-flight
+    /// request until dropped. If the request is rejected, returns the delay after which it should
+    /// be retried.
+    pub(crate) fn try_admit(
+        self: &Arc<Self>,
+        service_name: Option<&str>,
+    ) -> Result<RateLimitPermit, Duration> {
+        let now = Instant::now();
+        let mut guard = self
+            .inner
+            .lock()
+            .expect("rate limiter lock is not poisoned");
+        let Inner {
+            options,
+            global,
+            services,
+        } = &mut *guard;
+        let options = options.live_load();
+
+        // Only services with limits are tracked, to keep the state bounded
+        let service_limits = service_name.and_then(|name| {
+            options
+                .services
+                .get_key_value(name)
+                .map(|(name, limits)| (name.as_str(), limits))
+        });
+
+        global.check(&options.global, now)?;
+        if let Some((name, limits)) = service_limits {
+            services
+                .entry(name.to_owned())
+                .or_default()
+                .check(limits, now)?;
+        }
+
+        global.admit();
+        let service_name = service_limits.map(|(name, _)| {
+            services
+                .get_mut(name)
+                .expect("service state was created above")
+                .admit();
+            name.to_owned()
+        });
+
+        Ok(RateLimitPermit {
+            rate_limiter: Arc::clone(self),
+            service_name,
+        })
+    }
+
+    fn release(&self, service_name: Option<&str>) {
+        let mut inner = self
+            .inner
+            .lock()
+            .expect("rate limiter lock is not poisoned");
+        inner.global.release();
+        if let Some(state) = service_name.and_then(|name| inner.services.get_mut(name)) {
+            state.release();
+        }
+    }
+}
+
+/// An admitted request, counting as in-flight until dropped.
+pub(crate) struct RateLimitPermit {
+    rate_limiter: Arc<RateLimiter>,
+    service_name: Option<String>,
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        self.rate_limiter.release(self.service_name.as_deref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::live::Live;
+
+    fn rate_limiter(options: serde_json::Value) -> Arc<RateLimiter> {
+        let options: IngressRateLimitOptions = serde_json::from_value(options).unwrap();
+        Arc::new(RateLimiter::new(Live::from_value(options).boxed()))
+    }
+
+    #[test]
+    fn token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2, now);
+
+        assert!(bucket.check(1, 2, now).is_ok());
+        bucket.tokens -= 1.0;
+        assert!(bucket.check(1, 2, now).is_ok());
+        bucket.tokens -= 1.0;
+        assert_eq!(bucket.check(1, 2, now), Err(Duration::from_secs(1)));
+
+        // Refills at the configured rate, up to the burst
+        assert!(bucket
+            .check(1, 2, now + Duration::from_millis(500))
+            .is_err());
+        assert!(bucket.check(1, 2, now + Duration::from_secs(1)).is_ok());
+        assert!(bucket.check(1, 2, now + Duration::from_secs(10)).is_ok());
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn global_rate_limit() {
+        let rate_limiter = rate_limiter(serde_json::json!({
+            "global": {"requests-per-second": 1, "burst": 2}
+        }));
+
+        let _permits = [
+            rate_limiter.try_admit(None).unwrap(),
+            rate_limiter.try_admit(Some("Greeter")).unwrap(),
+        ];
+        assert!(rate_limiter.try_admit(None).is_err());
+    }
+
+    #[test]
+    fn service_rate_limit() {
+        let rate_limiter = rate_limiter(serde_json::json!({
+            "services": {"Greeter": {"requests-per-second": 1}}
+        }));
+
+        let _permit = rate_limiter.try_admit(Some("Greeter")).unwrap();
+        assert!(rate_limiter.try_admit(Some("Greeter")).is_err());
+
+        // Other services and the global limits are not affected
+        assert!(rate_limiter.try_admit(Some("Other")).is_ok());
+        assert!(rate_limiter.try_admit(None).is_ok());
+    }
+
+    #[test]
+    fn max_in_flight_requests() {
+        let rate_limiter = rate_limiter(serde_json::json!({
+            "global": {"max-in-flight-requests": 3},
+            "services": {"Greeter": {"max-in-flight-requests": 1}}
+        }));
+
+        let permit = rate_limiter.try_admit(Some("Greeter")).unwrap();
+        assert_eq!(
+            rate_limiter.try_admit(Some("Greeter")).err(),
+            Some(IN_FLIGHT_RETRY_AFTER)
+        );
+        drop(permit);
+        let _greeter = rate_limiter.try_admit(Some("Greeter")).unwrap();
+
+        let _others = [
+            rate_limiter.try_admit(Some("Other")).unwrap(),
+            rate_limiter.try_admit(None).unwrap(),
+        ];
+        assert!(rate_limiter.try_admit(None).is_err());
+    }
+}
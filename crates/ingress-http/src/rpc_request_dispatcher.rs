@@ -11,16 +11,20 @@
 use crate::{RequestDispatcher, RequestDispatcherError};
 use anyhow::anyhow;
 use restate_core::network::partition_processor_rpc_client::{
-    AttachInvocationResponse, GetInvocationOutputResponse,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
 };
 use restate_core::network::partition_processor_rpc_client::{
     PartitionProcessorRpcClient, PartitionProcessorRpcClientError,
 };
 use restate_core::network::TransportConnect;
-use restate_types::identifiers::{PartitionProcessorRpcRequestId, WithInvocationId};
+use restate_types::identifiers::{
+    PartitionId, PartitionProcessorRpcRequestId, WithInvocationId, WithPartitionKey,
+};
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::net::partition_processor::{InvocationOutput, SubmittedInvocationNotification};
 use restate_types::retries::RetryPolicy;
+use restate_types::schedule::Schedule;
+use std::collections::HashMap;
 use std::future::Future;
 use std::time::Duration;
 use tracing::{debug_span, trace, Instrument};
@@ -57,11 +61,12 @@ impl<C> RpcRequestDispatcher<C> {
         Fn: FnMut() -> Fut,
         Fut: Future<Output = Result<T, PartitionProcessorRpcClientError>>,
     {
-        Ok(self
-            .retry_policy
+        self.retry_policy
             .clone()
             .retry_if(operation, |e| {
-                let retry = is_idempotent || e.is_safe_to_retry();
+                // Overloaded partitions are reported back to the client, which should back off
+                let retry = !matches!(e, PartitionProcessorRpcClientError::Overloaded(_))
+                    && (is_idempotent || e.is_safe_to_retry());
 
                 if retry {
                     trace!("Retrying rpc because of error: {e}.");
@@ -72,7 +77,12 @@ impl<C> RpcRequestDispatcher<C> {
                 retry
             })
             .await
-            .map_err(|e| anyhow!("Error when trying to route the request internally: {e}"))?)
+            .map_err(|e| match e {
+                PartitionProcessorRpcClientError::Overloaded(partition_id) => {
+                    RequestDispatcherError::Overloaded(partition_id)
+                }
+                e => anyhow!("Error when trying to route the request internally: {e}").into(),
+            })
     }
 }
 
@@ -97,6 +107,70 @@ where
         .await
     }
 
+    async fn send_batch(
+        &self,
+        invocation_requests: Vec<InvocationRequest>,
+    ) -> Vec<Result<(), RequestDispatcherError>> {
+        let mut results: Vec<_> = invocation_requests.iter().map(|_| None).collect();
+
+        // Group the invocations by partition, to append each group with a single rpc
+        let mut batches: HashMap<PartitionId, (Vec<usize>, Vec<InvocationRequest>)> =
+            HashMap::new();
+        for (idx, invocation_request) in invocation_requests.into_iter().enumerate() {
+            match self
+                .partition_processor_rpc_client
+                .find_partition_id(invocation_request.partition_key())
+            {
+                Ok(partition_id) => {
+                    let (indexes, batch) = batches.entry(partition_id).or_default();
+                    indexes.push(idx);
+                    batch.push(invocation_request);
+                }
+                Err(e) => {
+                    results[idx] = Some(Err(anyhow!(
+                        "Error when trying to route the request internally: {e}"
+                    )
+                    .into()))
+                }
+            }
+        }
+
+        let batch_results = futures::future::join_all(batches.into_values().map(
+            |(indexes, invocation_requests)| async move {
+                let request_id = PartitionProcessorRpcRequestId::default();
+                let is_idempotent = invocation_requests
+                    .iter()
+                    .all(InvocationRequest::is_idempotent);
+                let result = self
+                    .execute_rpc(is_idempotent, || {
+                        self.partition_processor_rpc_client
+                            .append_invocations(request_id, invocation_requests.clone())
+                    })
+                    .instrument(debug_span!("send invocations batch", %request_id, batch_size = invocation_requests.len()))
+                    .await;
+                (indexes, result)
+            },
+        ))
+        .await;
+
+        for (indexes, result) in batch_results {
+            for idx in indexes {
+                results[idx] = Some(match &result {
+                    Ok(()) => Ok(()),
+                    Err(RequestDispatcherError::Overloaded(partition_id)) => {
+                        Err(RequestDispatcherError::Overloaded(*partition_id))
+                    }
+                    Err(RequestDispatcherError::Internal(e)) => Err(anyhow!("{e}").into()),
+                });
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("every invocation has a result"))
+            .collect()
+    }
+
     async fn call(
         &self,
         invocation_request: InvocationRequest,
@@ -137,6 +211,19 @@ where
         .await
     }
 
+    async fn get_invocation_progress(
+        &self,
+        invocation_query: InvocationQuery,
+    ) -> Result<GetInvocationProgressResponse, RequestDispatcherError> {
+        let request_id = PartitionProcessorRpcRequestId::default();
+        self.execute_rpc(true, || {
+            self.partition_processor_rpc_client
+                .get_invocation_progress(request_id, invocation_query.clone())
+        })
+        .instrument(debug_span!("get invocation progress", %request_id, invocation_id = %invocation_query.to_invocation_id()))
+        .await
+    }
+
     async fn send_invocation_response(
         &self,
         invocation_response: InvocationResponse,
@@ -149,4 +236,15 @@ where
         .instrument(debug_span!("send invocation response", %request_id, invocation_id = %invocation_response.id))
         .await
     }
+
+    async fn create_schedule(&self, schedule: Schedule) -> Result<(), RequestDispatcherError> {
+        let request_id = PartitionProcessorRpcRequestId::default();
+        // Upserting the same schedule twice is idempotent
+        self.execute_rpc(true, || {
+            self.partition_processor_rpc_client
+                .append_schedule(request_id, schedule.clone())
+        })
+        .instrument(debug_span!("create schedule", %request_id, schedule_id = %schedule.id))
+        .await
+    }
 }
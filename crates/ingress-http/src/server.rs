@@ -10,32 +10,52 @@
 
 use super::*;
 
-use crate::handler::Handler;
+use crate::auth::{self, Authenticator};
+use crate::handler::{Handler, ResponseBody};
+use crate::layers::bearer_auth::BearerAuthLayer;
+use crate::layers::security_headers::SecurityHeadersLayer;
+use crate::rate_limit::RateLimiter;
+use crate::slo::{self, SloTracker};
+use crate::tls::{self, TlsError};
 use codederror::CodedError;
 use http::{Request, Response};
-use http_body_util::Full;
 use hyper::body::Incoming;
 use hyper_util::rt::TokioIo;
 use hyper_util::server::conn::auto;
 use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
-use restate_types::config::IngressOptions;
+use restate_types::config::{
+    IngressAuthOptions, IngressCorsOptions, IngressListenerOptions, IngressOptions,
+    IngressRateLimitOptions, IngressSecurityHeadersOptions, SloOptions,
+};
 use restate_types::health::HealthStatus;
-use restate_types::live::Live;
+use restate_types::live::{BoxedLiveLoad, Live};
+use restate_types::net::BindAddress;
 use restate_types::protobuf::common::IngressStatus;
 use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::schema::subscriptions::SubscriptionResolver;
 use std::convert::Infallible;
 use std::future::Future;
-use std::net::SocketAddr;
-use tokio::net::{TcpListener, TcpStream};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, UnixListener};
 use tokio::sync::oneshot;
-use tower::{ServiceBuilder, ServiceExt};
-use tower_http::cors::CorsLayer;
+use tokio_rustls::TlsAcceptor;
+use tower::{Layer, ServiceBuilder, ServiceExt};
 use tower_http::normalize_path::NormalizePathLayer;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 pub type StartSignal = oneshot::Receiver<SocketAddr>;
 
+/// How long an additional listener waits before accepting connections again after it failed
+/// accepting a connection.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// How long clients of TLS listeners may take to complete the handshake.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum IngressServerError {
     #[error(
@@ -47,6 +67,29 @@ pub enum IngressServerError {
         #[source]
         source: std::io::Error,
     },
+    #[error(
+        "failed binding to address '{address}' specified in 'worker.ingress_http.additional_listeners'"
+    )]
+    #[code(restate_errors::RT0004)]
+    ListenerBinding {
+        address: BindAddress,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(
+        "failed loading the TLS certificate of the listener '{address}' specified in 'worker.ingress_http.additional_listeners': {source}"
+    )]
+    #[code(unknown)]
+    ListenerTls {
+        address: BindAddress,
+        #[source]
+        source: TlsError,
+    },
+    #[error(
+        "the listener '{address}' specified in 'worker.ingress_http.additional_listeners' sets a 'bearer-token' while 'auth' is enabled; configure the token as an API key in 'auth' instead"
+    )]
+    #[code(unknown)]
+    ListenerBearerTokenWithAuth { address: BindAddress },
     #[error("error while running ingress http server: {0}")]
     #[code(unknown)]
     Running(#[from] hyper::Error),
@@ -54,7 +97,13 @@ pub enum IngressServerError {
 
 pub struct HyperServerIngress<Schemas, Dispatcher> {
     listening_addr: SocketAddr,
+    additional_listeners: Vec<IngressListenerOptions>,
     concurrency_limit: usize,
+    slo_options: SloOptions,
+    auth_options: IngressAuthOptions,
+    rate_limits: Option<BoxedLiveLoad<IngressRateLimitOptions>>,
+    cors_options: IngressCorsOptions,
+    security_headers_options: IngressSecurityHeadersOptions,
 
     // Parameters to build the layers
     schemas: Live<Schemas>,
@@ -67,7 +116,13 @@ pub struct HyperServerIngress<Schemas, Dispatcher> {
 
 impl<Schemas, Dispatcher> HyperServerIngress<Schemas, Dispatcher>
 where
-    Schemas: ServiceMetadataResolver + InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
 {
     pub fn from_options(
@@ -86,12 +141,23 @@ where
         );
 
         hyper_ingress_server
+            .with_additional_listeners(ingress_options.additional_listeners.clone())
+            .with_slo_options(ingress_options.slo.clone())
+            .with_auth_options(ingress_options.auth.clone())
+            .with_cors_options(ingress_options.cors.clone())
+            .with_security_headers_options(ingress_options.security_headers.clone())
     }
 }
 
 impl<Schemas, Dispatcher> HyperServerIngress<Schemas, Dispatcher>
 where
-    Schemas: ServiceMetadataResolver + InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Schemas: ServiceMetadataResolver
+        + InvocationTargetResolver
+        + SubscriptionResolver
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
 {
     pub(crate) fn new(
@@ -106,7 +172,13 @@ where
 
         let ingress = Self {
             listening_addr,
+            additional_listeners: Vec::new(),
             concurrency_limit,
+            slo_options: SloOptions::default(),
+            auth_options: IngressAuthOptions::default(),
+            rate_limits: None,
+            cors_options: IngressCorsOptions::default(),
+            security_headers_options: IngressSecurityHeadersOptions::default(),
             schemas,
             dispatcher,
             health,
@@ -116,16 +188,72 @@ where
         (ingress, start_signal_rx)
     }
 
+    pub(crate) fn with_additional_listeners(
+        mut self,
+        additional_listeners: Vec<IngressListenerOptions>,
+    ) -> Self {
+        self.additional_listeners = additional_listeners;
+        self
+    }
+
+    pub(crate) fn with_slo_options(mut self, slo_options: SloOptions) -> Self {
+        self.slo_options = slo_options;
+        self
+    }
+
+    pub(crate) fn with_auth_options(mut self, auth_options: IngressAuthOptions) -> Self {
+        self.auth_options = auth_options;
+        self
+    }
+
+    pub(crate) fn with_cors_options(mut self, cors_options: IngressCorsOptions) -> Self {
+        self.cors_options = cors_options;
+        self
+    }
+
+    pub(crate) fn with_security_headers_options(
+        mut self,
+        security_headers_options: IngressSecurityHeadersOptions,
+    ) -> Self {
+        self.security_headers_options = security_headers_options;
+        self
+    }
+
+    /// Enforces the rate limits read from the given live configuration.
+    pub fn with_rate_limits(mut self, rate_limits: BoxedLiveLoad<IngressRateLimitOptions>) -> Self {
+        self.rate_limits = Some(rate_limits);
+        self
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         let HyperServerIngress {
             listening_addr,
+            additional_listeners,
             concurrency_limit,
+            slo_options,
+            auth_options,
+            rate_limits,
+            cors_options,
+            security_headers_options,
             schemas,
             dispatcher,
             health,
             start_signal_tx,
         } = self;
 
+        // Both would authenticate the requests of the listener, each with its own credentials
+        if auth_options.is_enabled() {
+            if let Some(listener_options) = additional_listeners
+                .iter()
+                .find(|listener_options| listener_options.bearer_token.is_some())
+            {
+                return Err(IngressServerError::ListenerBearerTokenWithAuth {
+                    address: listener_options.bind_address.clone(),
+                }
+                .into());
+            }
+        }
+
         // We create a TcpListener and bind it
         let listener =
             TcpListener::bind(listening_addr)
@@ -141,13 +269,69 @@ where
                 source: err,
             })?;
 
+        let mut listeners = Vec::with_capacity(additional_listeners.len());
+        for listener_options in additional_listeners {
+            let tls_acceptor = listener_options
+                .tls
+                .as_ref()
+                .map(tls::acceptor)
+                .transpose()
+                .map_err(|err| IngressServerError::ListenerTls {
+                    address: listener_options.bind_address.clone(),
+                    source: err,
+                })?;
+            let listener = IngressListener::bind(&listener_options.bind_address, tls_acceptor)
+                .await
+                .map_err(|err| IngressServerError::ListenerBinding {
+                    address: listener_options.bind_address.clone(),
+                    source: err,
+                })?;
+            listeners.push((listener, listener_options));
+        }
+
+        // Track the service level objectives, if any is configured
+        let slo_tracker = if slo_options.is_enabled() {
+            let slo_tracker = Arc::new(SloTracker::new(slo_options, Instant::now()));
+            TaskCenter::spawn_child(
+                TaskKind::Ingress,
+                "ingress-slo-alerts",
+                slo::run_alerts(Arc::clone(&slo_tracker)),
+            )?;
+            Some(slo_tracker)
+        } else {
+            None
+        };
+
+        // Authenticate the requests, if any credential is configured
+        let authenticator = if auth_options.is_enabled() {
+            let authenticator = Arc::new(Authenticator::new(&auth_options));
+            if authenticator.has_jwt_validation() {
+                TaskCenter::spawn_child(
+                    TaskKind::Ingress,
+                    "ingress-jwks-refresh",
+                    auth::run_jwks_refresh(Arc::clone(&authenticator)),
+                )?;
+            }
+            Some(authenticator)
+        } else {
+            None
+        };
+
         // Prepare the handler
         let service = ServiceBuilder::new()
             .layer(NormalizePathLayer::trim_trailing_slash())
+            .layer(SecurityHeadersLayer::new(&security_headers_options))
             .layer(layers::load_shed::LoadShedLayer::new(concurrency_limit))
-            .layer(CorsLayer::very_permissive())
+            .layer(layers::cors::cors_layer(&cors_options))
             .layer(layers::tracing_context_extractor::HttpTraceContextExtractorLayer)
-            .service(Handler::new(schemas, dispatcher));
+            .service(
+                Handler::new(schemas, dispatcher)
+                    .with_slo_tracker(slo_tracker)
+                    .with_authenticator(authenticator)
+                    .with_rate_limiter(
+                        rate_limits.map(|rate_limits| Arc::new(RateLimiter::new(rate_limits))),
+                    ),
+            );
 
         info!(
             net.host.addr = %local_addr.ip(),
@@ -158,16 +342,47 @@ where
         let shutdown = cancellation_watcher();
         tokio::pin!(shutdown);
 
+        // Each additional listener accepts connections in its own task
+        for (listener, listener_options) in listeners {
+            let bind_address = listener_options.bind_address;
+            info!(
+                %bind_address,
+                authenticated = listener_options.bearer_token.is_some(),
+                tls = listener_options.tls.is_some(),
+                "Ingress HTTP listening"
+            );
+            let service = BearerAuthLayer::new(listener_options.bearer_token.as_deref())
+                .layer(service.clone());
+            TaskCenter::spawn_child(TaskKind::Ingress, "ingress-listener", async move {
+                let shutdown = cancellation_watcher();
+                tokio::pin!(shutdown);
+                loop {
+                    tokio::select! {
+                        res = listener.accept_and_serve(service.clone()) => {
+                            if let Err(err) = res {
+                                warn!(%bind_address, "Failed accepting ingress connection: {err}");
+                                // e.g. the process ran out of file descriptors, give it some time
+                                // to recover
+                                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                            }
+                        }
+                        _ = &mut shutdown => return Ok(()),
+                    }
+                }
+            })?;
+        }
+
         // Send start signal
         let _ = start_signal_tx.send(local_addr);
         health.update(IngressStatus::Ready);
 
+        let service = BearerAuthLayer::new(None).layer(service);
         // We start a loop to continuously accept incoming connections
         loop {
             tokio::select! {
                 res = listener.accept() => {
                     let (stream, remote_peer) = res?;
-                    Self::handle_connection(stream, remote_peer, service.clone())?;
+                    handle_connection(stream, ConnectInfo::new(remote_peer), None, service.clone())?;
                 }
                   _ = &mut shutdown => {
                     return Ok(());
@@ -175,50 +390,139 @@ where
             }
         }
     }
+}
 
-    fn handle_connection<T, F>(
-        stream: TcpStream,
-        remote_peer: SocketAddr,
-        handler: T,
-    ) -> anyhow::Result<()>
+/// Listener of an additional ingress bind address.
+struct IngressListener {
+    socket: ListenerSocket,
+    tls_acceptor: Option<TlsAcceptor>,
+}
+
+enum ListenerSocket {
+    Tcp(TcpListener),
+    Uds(UnixListener),
+}
+
+impl IngressListener {
+    async fn bind(
+        bind_address: &BindAddress,
+        tls_acceptor: Option<TlsAcceptor>,
+    ) -> std::io::Result<Self> {
+        let socket = match bind_address {
+            BindAddress::Socket(socket_addr) => {
+                ListenerSocket::Tcp(TcpListener::bind(socket_addr).await?)
+            }
+            BindAddress::Uds(uds_path) => {
+                if uds_path.exists() {
+                    // if this fails, the following bind will fail, so its safe to ignore this error
+                    _ = std::fs::remove_file(uds_path);
+                }
+                ListenerSocket::Uds(UnixListener::bind(uds_path)?)
+            }
+        };
+
+        Ok(Self {
+            socket,
+            tls_acceptor,
+        })
+    }
+
+    async fn accept_and_serve<T, F>(&self, handler: T) -> anyhow::Result<()>
     where
         F: Send,
         T: tower::Service<
                 Request<Incoming>,
-                Response = Response<Full<Bytes>>,
+                Response = Response<ResponseBody>,
                 Error = Infallible,
                 Future = F,
             > + Clone
             + Send
             + 'static,
     {
-        let connect_info = ConnectInfo::new(remote_peer);
-        let io = TokioIo::new(stream);
-        let handler = hyper_util::service::TowerToHyperService::new(handler.map_request(
-            move |mut req: Request<Incoming>| {
-                req.extensions_mut().insert(connect_info);
-                req
-            },
-        ));
-
-        // Spawn a tokio task to serve the connection
-        TaskCenter::spawn(TaskKind::Ingress, "ingress", async move {
-            let shutdown = cancellation_watcher();
-            let auto_connection = auto::Builder::new(TaskCenterExecutor);
-            let serve_connection_fut = auto_connection.serve_connection(io, handler);
-
-            tokio::select! {
-                res = serve_connection_fut => {
-                    if let Err(err) = res {
-                        warn!("Error when serving the connection: {:?}", err);
-                    }
-                }
-                _ = shutdown => {}
+        let tls_acceptor = self.tls_acceptor.clone();
+        match &self.socket {
+            ListenerSocket::Tcp(listener) => {
+                let (stream, remote_peer) = listener.accept().await?;
+                handle_connection(stream, ConnectInfo::new(remote_peer), tls_acceptor, handler)
             }
-            Ok(())
-        })?;
+            ListenerSocket::Uds(listener) => {
+                let (stream, _) = listener.accept().await?;
+                // Unix domain socket peers don't have a socket address, they are always local
+                let connect_info = ConnectInfo::new(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+                handle_connection(stream, connect_info, tls_acceptor, handler)
+            }
+        }
+    }
+}
 
+fn handle_connection<S, T, F>(
+    stream: S,
+    connect_info: ConnectInfo,
+    tls_acceptor: Option<TlsAcceptor>,
+    handler: T,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    F: Send,
+    T: tower::Service<
+            Request<Incoming>,
+            Response = Response<ResponseBody>,
+            Error = Infallible,
+            Future = F,
+        > + Clone
+        + Send
+        + 'static,
+{
+    // Spawn a tokio task to serve the connection
+    TaskCenter::spawn(TaskKind::Ingress, "ingress", async move {
+        let Some(tls_acceptor) = tls_acceptor else {
+            serve_connection(stream, connect_info, handler).await;
+            return Ok(());
+        };
+
+        match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, tls_acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => serve_connection(stream, connect_info, handler).await,
+            Ok(Err(err)) => debug!("TLS handshake with ingress client failed: {err}"),
+            Err(_) => debug!("TLS handshake with ingress client timed out"),
+        }
         Ok(())
+    })?;
+
+    Ok(())
+}
+
+async fn serve_connection<S, T, F>(stream: S, connect_info: ConnectInfo, handler: T)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    F: Send,
+    T: tower::Service<
+            Request<Incoming>,
+            Response = Response<ResponseBody>,
+            Error = Infallible,
+            Future = F,
+        > + Clone
+        + Send
+        + 'static,
+{
+    let io = TokioIo::new(stream);
+    let handler = hyper_util::service::TowerToHyperService::new(handler.map_request(
+        move |mut req: Request<Incoming>| {
+            req.extensions_mut().insert(connect_info);
+            req
+        },
+    ));
+
+    let shutdown = cancellation_watcher();
+    let auto_connection = auto::Builder::new(TaskCenterExecutor);
+    let serve_connection_fut = auto_connection.serve_connection(io, handler);
+
+    tokio::select! {
+        res = serve_connection_fut => {
+            if let Err(err) = res {
+                warn!("Error when serving the connection: {:?}", err);
+            }
+        }
+        _ = shutdown => {}
     }
 }
 
@@ -243,6 +547,7 @@ mod tests {
     use super::mocks::*;
     use super::*;
 
+    use http::StatusCode;
     use http_body_util::BodyExt;
     use http_body_util::Full;
     use hyper_util::client::legacy::Client;
@@ -250,6 +555,7 @@ mod tests {
     use restate_core::TestCoreEnv;
     use restate_core::{TaskCenter, TaskKind};
     use restate_test_util::assert_eq;
+    use restate_types::config::IngressApiKey;
     use restate_types::health::Health;
     use restate_types::identifiers::WithInvocationId;
     use restate_types::invocation::InvocationTarget;
@@ -334,7 +640,101 @@ mod tests {
         restate_test_util::assert_eq!(response_value.greeting, "Igal");
     }
 
+    #[restate_core::test]
+    #[traced_test]
+    async fn test_uds_listener_with_bearer_token() {
+        let socket_dir = tempfile::tempdir().unwrap();
+        let socket_path = socket_dir.path().join("ingress.sock");
+        bootstrap_test_with_listeners(
+            MockRequestDispatcher::default(),
+            vec![IngressListenerOptions {
+                bind_address: BindAddress::Uds(socket_path.clone()),
+                bearer_token: Some("secret".to_owned()),
+                tls: None,
+            }],
+        )
+        .await;
+
+        let health_request = |authorization: Option<&str>| {
+            let mut request = http::Request::get("http://localhost/restate/health");
+            if let Some(authorization) = authorization {
+                request = request.header(http::header::AUTHORIZATION, authorization);
+            }
+            request.body(Full::<Bytes>::default()).unwrap()
+        };
+
+        let stream = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        TaskCenter::spawn(TaskKind::Disposable, "uds-connection", async move {
+            let _ = connection.await;
+            Ok(())
+        })
+        .unwrap();
+
+        let response = sender.send_request(health_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = sender
+            .send_request(health_request(Some("Bearer secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[restate_core::test]
+    async fn test_listener_bearer_token_conflicts_with_auth() {
+        let (ingress, _) = HyperServerIngress::new(
+            "0.0.0.0:0".parse().unwrap(),
+            Semaphore::MAX_PERMITS,
+            Live::from_value(mock_schemas()),
+            Arc::new(MockRequestDispatcher::default()),
+            Health::default().ingress_status(),
+        );
+        let ingress = ingress
+            .with_additional_listeners(vec![IngressListenerOptions {
+                bind_address: BindAddress::Socket("127.0.0.1:0".parse().unwrap()),
+                bearer_token: Some("secret".to_owned()),
+                tls: None,
+            }])
+            .with_auth_options(IngressAuthOptions {
+                api_keys: vec![IngressApiKey {
+                    name: "client".to_owned(),
+                    key: "other-secret".to_owned(),
+                }],
+                ..IngressAuthOptions::default()
+            });
+
+        let err = ingress.run().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<IngressServerError>(),
+            Some(IngressServerError::ListenerBearerTokenWithAuth { .. })
+        ));
+    }
+
+    #[test]
+    fn listener_bearer_token_is_redacted() {
+        let listener_options = IngressListenerOptions {
+            bind_address: BindAddress::Socket("127.0.0.1:8081".parse().unwrap()),
+            bearer_token: Some("secret".to_owned()),
+            tls: None,
+        };
+
+        assert!(!format!("{listener_options:?}").contains("secret"));
+        assert!(!serde_json::to_string(&listener_options)
+            .unwrap()
+            .contains("secret"));
+    }
+
     async fn bootstrap_test(mock_request_dispatcher: MockRequestDispatcher) -> SocketAddr {
+        bootstrap_test_with_listeners(mock_request_dispatcher, Vec::new()).await
+    }
+
+    async fn bootstrap_test_with_listeners(
+        mock_request_dispatcher: MockRequestDispatcher,
+        additional_listeners: Vec<IngressListenerOptions>,
+    ) -> SocketAddr {
         let _env = TestCoreEnv::create_with_single_node(1, 1).await;
         let health = Health::default();
 
@@ -346,6 +746,7 @@ mod tests {
             Arc::new(mock_request_dispatcher),
             health.ingress_status(),
         );
+        let ingress = ingress.with_additional_listeners(additional_listeners);
         TaskCenter::spawn(TaskKind::SystemService, "ingress", ingress.run()).unwrap();
 
         // Wait server to start
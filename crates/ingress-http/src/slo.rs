@@ -0,0 +1,407 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! In-process tracking of the service level objectives configured in [`SloOptions`].
+//!
+//! Request outcomes are aggregated in fixed-width time buckets spanning the long window. The
+//! burn rate of an objective is the observed ratio of bad requests divided by its error budget,
+//! so a burn rate of 1 consumes exactly the error budget. An objective alerts once the burn rate
+//! exceeds the configured threshold over both the long and the short window.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use metrics::gauge;
+use serde::Serialize;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+use restate_core::cancellation_watcher;
+use restate_types::config::{ServiceLevelObjective, SloOptions};
+
+use crate::metric_definitions::INGRESS_SLO_BURN_RATE;
+
+/// Number of buckets covering the short window.
+const BUCKETS_PER_SHORT_WINDOW: u32 = 10;
+const MIN_BUCKET_WIDTH: Duration = Duration::from_secs(1);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ObjectiveKind {
+    Availability,
+    Latency,
+}
+
+impl ObjectiveKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ObjectiveKind::Availability => "availability",
+            ObjectiveKind::Latency => "latency",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SloStatus {
+    pub(crate) service: String,
+    pub(crate) objective: ObjectiveKind,
+    pub(crate) target: f64,
+    pub(crate) long_window_burn_rate: f64,
+    pub(crate) short_window_burn_rate: f64,
+    pub(crate) alerting: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SloAlert<'a> {
+    status: &'static str,
+    #[serde(flatten)]
+    slo: &'a SloStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    index: u64,
+    total: u64,
+    errors: u64,
+    slow: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    total: u64,
+    errors: u64,
+    slow: u64,
+}
+
+pub(crate) struct SloTracker {
+    options: SloOptions,
+    bucket_width: Duration,
+    long_window_buckets: u64,
+    short_window_buckets: u64,
+    started_at: Instant,
+    services: Mutex<HashMap<String, VecDeque<Bucket>>>,
+}
+
+impl SloTracker {
+    pub(crate) fn new(options: SloOptions, now: Instant) -> Self {
+        let bucket_width = std::cmp::max(
+            options.short_window() / BUCKETS_PER_SHORT_WINDOW,
+            MIN_BUCKET_WIDTH,
+        );
+        let buckets_for = |window: Duration| {
+            std::cmp::max(window.as_millis() / bucket_width.as_millis(), 1) as u64
+        };
+        let long_window_buckets = buckets_for(options.long_window());
+        let short_window_buckets = buckets_for(options.short_window());
+        let services = options
+            .objectives
+            .keys()
+            .map(|service| (service.clone(), VecDeque::new()))
+            .collect();
+
+        Self {
+            options,
+            bucket_width,
+            long_window_buckets,
+            short_window_buckets,
+            started_at: now,
+            services: Mutex::new(services),
+        }
+    }
+
+    /// Records the outcome of a request to the given service. Requests to services without
+    /// objectives are ignored.
+    pub(crate) fn record(
+        &self,
+        service: &str,
+        server_error: bool,
+        latency: Duration,
+        now: Instant,
+    ) {
+        let Some(objective) = self.options.objectives.get(service) else {
+            return;
+        };
+        let slow = objective
+            .latency
+            .as_ref()
+            .is_some_and(|latency_objective| latency > *latency_objective.threshold);
+        let index = self.bucket_index(now);
+
+        let mut services = self.services.lock().unwrap();
+        let Some(buckets) = services.get_mut(service) else {
+            return;
+        };
+        if buckets.back().is_none_or(|bucket| bucket.index != index) {
+            buckets.push_back(Bucket {
+                index,
+                ..Default::default()
+            });
+        }
+        let bucket = buckets.back_mut().expect("bucket was just pushed");
+        bucket.total += 1;
+        bucket.errors += u64::from(server_error);
+        bucket.slow += u64::from(slow);
+
+        self.prune(buckets, index);
+    }
+
+    /// Computes the burn rates of all the configured objectives.
+    pub(crate) fn evaluate(&self, now: Instant) -> Vec<SloStatus> {
+        let index = self.bucket_index(now);
+        let mut services = self.services.lock().unwrap();
+
+        let mut statuses = Vec::new();
+        for (service, buckets) in services.iter_mut() {
+            self.prune(buckets, index);
+            let long = Self::counts(buckets, index, self.long_window_buckets);
+            let short = Self::counts(buckets, index, self.short_window_buckets);
+            let objective = &self.options.objectives[service];
+
+            for (kind, target) in Self::targets(objective) {
+                let bad = |counts: &Counts| match kind {
+                    ObjectiveKind::Availability => counts.errors,
+                    ObjectiveKind::Latency => counts.slow,
+                };
+                let long_window_burn_rate = burn_rate(bad(&long), long.total, target);
+                let short_window_burn_rate = burn_rate(bad(&short), short.total, target);
+                statuses.push(SloStatus {
+                    service: service.clone(),
+                    objective: kind,
+                    target,
+                    long_window_burn_rate,
+                    short_window_burn_rate,
+                    alerting: long_window_burn_rate >= self.options.burn_rate_threshold
+                        && short_window_burn_rate >= self.options.burn_rate_threshold,
+                });
+            }
+        }
+
+        statuses.sort_by(|a, b| {
+            (&a.service, a.objective.as_str()).cmp(&(&b.service, b.objective.as_str()))
+        });
+        statuses
+    }
+
+    fn targets(objective: &ServiceLevelObjective) -> impl Iterator<Item = (ObjectiveKind, f64)> {
+        objective
+            .availability
+            .map(|target| (ObjectiveKind::Availability, target))
+            .into_iter()
+            .chain(
+                objective
+                    .latency
+                    .as_ref()
+                    .map(|latency| (ObjectiveKind::Latency, latency.target)),
+            )
+    }
+
+    fn bucket_index(&self, now: Instant) -> u64 {
+        (now.saturating_duration_since(self.started_at).as_millis() / self.bucket_width.as_millis())
+            as u64
+    }
+
+    fn prune(&self, buckets: &mut VecDeque<Bucket>, index: u64) {
+        while buckets
+            .front()
+            .is_some_and(|bucket| bucket.index + self.long_window_buckets <= index)
+        {
+            buckets.pop_front();
+        }
+    }
+
+    fn counts(buckets: &VecDeque<Bucket>, index: u64, window_buckets: u64) -> Counts {
+        buckets
+            .iter()
+            .rev()
+            .take_while(|bucket| bucket.index + window_buckets > index)
+            .fold(Counts::default(), |mut counts, bucket| {
+                counts.total += bucket.total;
+                counts.errors += bucket.errors;
+                counts.slow += bucket.slow;
+                counts
+            })
+    }
+}
+
+fn burn_rate(bad: u64, total: u64, target: f64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let error_budget = (1.0 - target).max(f64::EPSILON);
+    (bad as f64 / total as f64) / error_budget
+}
+
+/// Periodically evaluates the objectives, exporting the burn rates as metrics, and notifies
+/// the alert webhook whenever an objective starts or stops alerting.
+pub(crate) async fn run_alerts(tracker: Arc<SloTracker>) -> anyhow::Result<()> {
+    let webhook = tracker
+        .options
+        .alert_webhook
+        .as_ref()
+        .map(|uri| uri.to_string());
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?;
+
+    let mut interval = tokio::time::interval(tracker.options.evaluation_interval());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut alerting: HashSet<(String, ObjectiveKind)> = HashSet::new();
+
+    let shutdown = cancellation_watcher();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = &mut shutdown => return Ok(()),
+        }
+
+        for status in tracker.evaluate(Instant::now()) {
+            gauge!(
+                INGRESS_SLO_BURN_RATE,
+                "rpc.service" => status.service.clone(),
+                "objective" => status.objective.as_str(),
+                "window" => "long",
+            )
+            .set(status.long_window_burn_rate);
+            gauge!(
+                INGRESS_SLO_BURN_RATE,
+                "rpc.service" => status.service.clone(),
+                "objective" => status.objective.as_str(),
+                "window" => "short",
+            )
+            .set(status.short_window_burn_rate);
+
+            let key = (status.service.clone(), status.objective);
+            let alert_status = if status.alerting && alerting.insert(key.clone()) {
+                warn!(
+                    rpc.service = %status.service,
+                    "The {} objective of service '{}' is burning its error budget {:.1}x faster than sustainable",
+                    status.objective.as_str(),
+                    status.service,
+                    status.long_window_burn_rate
+                );
+                "firing"
+            } else if !status.alerting && alerting.remove(&key) {
+                info!(
+                    rpc.service = %status.service,
+                    "The {} objective of service '{}' stopped alerting",
+                    status.objective.as_str(),
+                    status.service
+                );
+                "resolved"
+            } else {
+                continue;
+            };
+
+            if let Some(webhook) = &webhook {
+                let alert = SloAlert {
+                    status: alert_status,
+                    slo: &status,
+                };
+                if let Err(err) = client
+                    .post(webhook)
+                    .json(&alert)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                {
+                    warn!("Failed notifying the SLO alert webhook: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::config::{LatencyObjective, SloOptionsBuilder};
+
+    fn tracker(now: Instant) -> SloTracker {
+        let options = SloOptionsBuilder::default()
+            .objectives(HashMap::from([(
+                "greeter".to_owned(),
+                ServiceLevelObjective {
+                    availability: Some(0.99),
+                    latency: Some(LatencyObjective {
+                        threshold: Duration::from_millis(100).into(),
+                        target: 0.9,
+                    }),
+                },
+            )]))
+            .burn_rate_threshold(10.0)
+            .build()
+            .unwrap();
+        SloTracker::new(options, now)
+    }
+
+    fn status(statuses: &[SloStatus], kind: ObjectiveKind) -> &SloStatus {
+        statuses.iter().find(|s| s.objective == kind).unwrap()
+    }
+
+    #[test]
+    fn alerts_when_burning_budget_in_both_windows() {
+        let start = Instant::now();
+        let tracker = tracker(start);
+
+        for i in 0..100 {
+            tracker.record("greeter", i < 20, Duration::from_millis(10), start);
+        }
+        // Not tracked
+        tracker.record("other", true, Duration::from_millis(10), start);
+
+        let statuses = tracker.evaluate(start);
+        assert_eq!(statuses.len(), 2);
+
+        let availability = status(&statuses, ObjectiveKind::Availability);
+        assert!((availability.long_window_burn_rate - 20.0).abs() < 1e-6);
+        assert!((availability.short_window_burn_rate - 20.0).abs() < 1e-6);
+        assert!(availability.alerting);
+
+        let latency = status(&statuses, ObjectiveKind::Latency);
+        assert_eq!(latency.long_window_burn_rate, 0.0);
+        assert!(!latency.alerting);
+    }
+
+    #[test]
+    fn resolves_once_short_window_recovers() {
+        let start = Instant::now();
+        let tracker = tracker(start);
+
+        for _ in 0..100 {
+            tracker.record("greeter", true, Duration::from_millis(10), start);
+        }
+
+        // After the short window, only successful requests
+        let later = start + Duration::from_secs(10 * 60);
+        for _ in 0..100 {
+            tracker.record("greeter", false, Duration::from_millis(10), later);
+        }
+
+        let availability = status(&tracker.evaluate(later), ObjectiveKind::Availability).clone();
+        assert!(availability.long_window_burn_rate >= 10.0);
+        assert_eq!(availability.short_window_burn_rate, 0.0);
+        assert!(!availability.alerting);
+
+        // After the long window everything expired
+        let much_later = start + Duration::from_secs(2 * 60 * 60);
+        let availability =
+            status(&tracker.evaluate(much_later), ObjectiveKind::Availability).clone();
+        assert_eq!(availability.long_window_burn_rate, 0.0);
+    }
+}
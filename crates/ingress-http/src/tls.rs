@@ -0,0 +1,40 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! TLS of the additional ingress listeners, see [`IngressListenerTlsOptions`].
+
+use std::sync::Arc;
+
+use rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use restate_tls_util::{read_certificates, read_private_key, PemError};
+use restate_types::config::IngressListenerTlsOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error(transparent)]
+    Pem(#[from] PemError),
+    #[error("invalid certificate: {0}")]
+    InvalidCertificate(#[from] rustls::Error),
+}
+
+/// Creates the acceptor performing the server side of the TLS handshakes of a listener.
+pub fn acceptor(options: &IngressListenerTlsOptions) -> Result<TlsAcceptor, TlsError> {
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(
+            read_certificates(&options.certificate_file)?,
+            read_private_key(&options.private_key_file)?,
+        )?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
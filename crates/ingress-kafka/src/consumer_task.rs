@@ -12,6 +12,7 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 use base64::Engine;
 use bytes::Bytes;
@@ -23,14 +24,18 @@ use rdkafka::error::KafkaError;
 use rdkafka::message::BorrowedMessage;
 use rdkafka::{ClientConfig, Message};
 use tokio::sync::oneshot;
-use tracing::{debug, info, info_span, Instrument};
+use tracing::{debug, info, info_span, warn, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
-use crate::dispatcher::{DispatchKafkaEvent, KafkaIngressDispatcher, KafkaIngressEvent};
-use crate::metric_definitions::KAFKA_INGRESS_REQUESTS;
+use crate::dead_letter::DeadLetterQueue;
+use crate::dispatcher::{
+    DispatchKafkaEvent, IngressDispatchError, KafkaIngressDispatcher, KafkaIngressEvent,
+};
+use crate::metric_definitions::{KAFKA_INGRESS_DEAD_LETTERS, KAFKA_INGRESS_REQUESTS};
 use restate_core::{cancellation_watcher, TaskCenter, TaskId, TaskKind};
 use restate_types::invocation::{Header, SpanRelation};
 use restate_types::message::MessageIndex;
+use restate_types::retries::{RetryIter, RetryPolicy};
 use restate_types::schema::subscriptions::{
     EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
 };
@@ -49,8 +54,8 @@ pub enum Error {
         #[source]
         cause: anyhow::Error,
     },
-    #[error("ingress dispatcher channel is closed")]
-    IngressDispatcherClosed,
+    #[error("failed to dispatch the event: {0}")]
+    Dispatch(#[from] IngressDispatchError),
     #[error("topic {0} partition {1} queue split didn't succeed")]
     TopicPartitionSplit(String, i32),
 }
@@ -89,14 +94,48 @@ impl KafkaDeduplicationId {
     }
 }
 
+impl Error {
+    /// Events which cannot be converted to an invocation will fail again on retry.
+    fn is_retryable(&self) -> bool {
+        !matches!(self, Error::Event { .. })
+    }
+}
+
+/// How to handle a failed ingestion of an event.
+#[derive(Debug, PartialEq)]
+enum FailureAction {
+    /// Try again after the given interval
+    Retry(Duration),
+    /// Publish the event to the dead letter topic
+    DeadLetter,
+    /// Return the error, restarting the consumer
+    Fail,
+}
+
+impl FailureAction {
+    fn of(err: &Error, has_dead_letter_queue: bool, retry_iter: &mut RetryIter<'_>) -> Self {
+        if err.is_retryable() {
+            // The retry policy is unlimited, fall back to its max interval just in case
+            FailureAction::Retry(retry_iter.next().unwrap_or(Duration::from_secs(5)))
+        } else if has_dead_letter_queue {
+            FailureAction::DeadLetter
+        } else {
+            FailureAction::Fail
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MessageSender {
     subscription: Subscription,
     dispatcher: KafkaIngressDispatcher,
     experimental_feature_kafka_ingress_next: bool,
+    dead_letter_queue: Option<Arc<DeadLetterQueue>>,
+    retry_policy: RetryPolicy,
 
     subscription_id: String,
     ingress_request_counter: metrics::Counter,
+    dead_letter_counter: metrics::Counter,
 }
 
 impl MessageSender {
@@ -104,6 +143,7 @@ impl MessageSender {
         subscription: Subscription,
         dispatcher: KafkaIngressDispatcher,
         experimental_feature_kafka_ingress_next: bool,
+        dead_letter_queue: Option<DeadLetterQueue>,
     ) -> Self {
         Self {
             subscription_id: subscription.id().to_string(),
@@ -111,13 +151,72 @@ impl MessageSender {
                 KAFKA_INGRESS_REQUESTS,
                 "subscription" => subscription.id().to_string()
             ),
+            dead_letter_counter: counter!(
+                KAFKA_INGRESS_DEAD_LETTERS,
+                "subscription" => subscription.id().to_string()
+            ),
+            retry_policy: RetryPolicy::exponential(
+                Duration::from_millis(100),
+                2.0,
+                None,
+                Some(Duration::from_secs(5)),
+            ),
             subscription,
             dispatcher,
             experimental_feature_kafka_ingress_next,
+            dead_letter_queue: dead_letter_queue.map(Arc::new),
         }
     }
 
+    /// Sends the message to the dispatcher. Failures to dispatch are retried indefinitely, as they
+    /// don't depend on the event. Events which cannot be converted to an invocation fail the same
+    /// way on every attempt: if the subscription has a dead letter topic they're published to it,
+    /// otherwise the error is returned and the consumer is restarted.
     async fn send(&self, consumer_group_id: &str, msg: BorrowedMessage<'_>) -> Result<(), Error> {
+        let mut retry_iter = self.retry_policy.iter();
+        loop {
+            let err = match self.try_send(consumer_group_id, &msg).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+
+            match FailureAction::of(&err, self.dead_letter_queue.is_some(), &mut retry_iter) {
+                FailureAction::Retry(next_retry_interval) => {
+                    debug!(
+                        restate.subscription.id = %self.subscription.id(),
+                        "Error when ingesting Kafka event, retrying in {next_retry_interval:?}: {err}"
+                    );
+                    tokio::time::sleep(next_retry_interval).await;
+                }
+                FailureAction::DeadLetter => {
+                    let dead_letter_queue = self
+                        .dead_letter_queue
+                        .as_ref()
+                        .expect("dead lettering requires a dead letter queue");
+                    warn!(
+                        restate.subscription.id = %self.subscription.id(),
+                        "Publishing the event at topic {} partition {} offset {} to the dead letter topic {}: {err}",
+                        msg.topic(),
+                        msg.partition(),
+                        msg.offset(),
+                        dead_letter_queue.topic()
+                    );
+                    dead_letter_queue
+                        .publish(&msg, &self.subscription_id, &err)
+                        .await?;
+                    self.dead_letter_counter.increment(1);
+                    return Ok(());
+                }
+                FailureAction::Fail => return Err(err),
+            }
+        }
+    }
+
+    async fn try_send(
+        &self,
+        consumer_group_id: &str,
+        msg: &BorrowedMessage<'_>,
+    ) -> Result<(), Error> {
         // Prepare ingress span
         let ingress_span = info_span!(
             "kafka_ingress_consume",
@@ -142,10 +241,10 @@ impl MessageSender {
         } else {
             Bytes::default()
         };
-        let headers = Self::generate_events_attributes(&msg, &self.subscription_id);
+        let headers = Self::generate_events_attributes(msg, &self.subscription_id);
 
         let (deduplication_id, deduplication_index) =
-            Self::generate_deduplication_id(consumer_group_id, &msg);
+            Self::generate_deduplication_id(consumer_group_id, msg);
         let req = KafkaIngressEvent::new(
             &self.subscription,
             key,
@@ -168,8 +267,7 @@ impl MessageSender {
         self.dispatcher
             .dispatch_kafka_event(req)
             .instrument(ingress_span)
-            .await
-            .map_err(|_| Error::IngressDispatcherClosed)?;
+            .await?;
         Ok(())
     }
 
@@ -287,8 +385,13 @@ impl ConsumerTask {
                         }
                     }
 
-                    // We got this message, let's send it through
-                    if let Err(e) = self.sender.send(&consumer_group_id, msg).await {
+                    // We got this message, let's send it through. Sending retries indefinitely, so
+                    // it must be interrupted when the consumer is stopped
+                    let sent = tokio::select! {
+                        sent = self.sender.send(&consumer_group_id, msg) => sent,
+                        _ = &mut rx => break Ok(()),
+                    };
+                    if let Err(e) = sent {
                         break Err(e)
                     }
 
@@ -327,7 +430,10 @@ async fn topic_partition_queue_consumption_loop(
             res = topic_partition_consumer.recv() => {
                 let msg = res?;
                 let offset = msg.offset();
-                sender.send(&consumer_group_id, msg).await?;
+                tokio::select! {
+                    sent = sender.send(&consumer_group_id, msg) => sent?,
+                    _ = &mut shutdown => return Ok(()),
+                }
                 consumer.store_offset(&topic, partition, offset)?;
             }
             _ = &mut shutdown => {
@@ -336,3 +442,62 @@ async fn topic_partition_queue_consumption_loop(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transient_error() -> Error {
+        Error::TopicPartitionSplit("my-topic".to_owned(), 0)
+    }
+
+    fn event_error() -> Error {
+        Error::Event {
+            topic: "my-topic".to_owned(),
+            partition: 0,
+            offset: 42,
+            cause: anyhow::anyhow!("The event has no key"),
+        }
+    }
+
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy::exponential(
+            Duration::from_millis(100),
+            2.0,
+            None,
+            Some(Duration::from_secs(5)),
+        )
+    }
+
+    #[test]
+    fn retry_transient_failures_indefinitely() {
+        let retry_policy = retry_policy();
+        let mut retry_iter = retry_policy.iter();
+
+        for _ in 0..100 {
+            assert!(matches!(
+                FailureAction::of(&transient_error(), true, &mut retry_iter),
+                FailureAction::Retry(_)
+            ));
+        }
+        assert_eq!(
+            FailureAction::of(&transient_error(), false, &mut retry_iter),
+            FailureAction::Retry(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn dead_letter_only_events_which_cannot_be_ingested() {
+        let retry_policy = retry_policy();
+        let mut retry_iter = retry_policy.iter();
+
+        assert_eq!(
+            FailureAction::of(&event_error(), true, &mut retry_iter),
+            FailureAction::DeadLetter
+        );
+        assert_eq!(
+            FailureAction::of(&event_error(), false, &mut retry_iter),
+            FailureAction::Fail
+        );
+    }
+}
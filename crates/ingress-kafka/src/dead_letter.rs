@@ -0,0 +1,87 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Duration;
+
+use rdkafka::error::KafkaError;
+use rdkafka::message::{BorrowedMessage, Headers, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use rdkafka::{ClientConfig, Message};
+
+use crate::consumer_task::Error;
+
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publishes the events which cannot be ingested to the dead letter topic of the subscription,
+/// with the original key, payload and headers. The error and the coordinates of the original
+/// event are added as headers.
+pub(crate) struct DeadLetterQueue {
+    topic: String,
+    producer: FutureProducer,
+}
+
+impl DeadLetterQueue {
+    pub(crate) fn new(client_config: &ClientConfig, topic: String) -> Result<Self, KafkaError> {
+        Ok(Self {
+            topic,
+            producer: client_config.create()?,
+        })
+    }
+
+    pub(crate) fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub(crate) async fn publish(
+        &self,
+        msg: &BorrowedMessage<'_>,
+        subscription_id: &str,
+        error: &Error,
+    ) -> Result<(), KafkaError> {
+        let offset = msg.offset().to_string();
+        let partition = msg.partition().to_string();
+        let error = error.to_string();
+
+        let mut headers = OwnedHeaders::new();
+        if let Some(original_headers) = msg.headers() {
+            for header in original_headers.iter() {
+                headers = headers.insert(header);
+            }
+        }
+        headers = headers
+            .insert(header("restate.subscription.id", subscription_id))
+            .insert(header("restate.error", &error))
+            .insert(header("kafka.topic", msg.topic()))
+            .insert(header("kafka.partition", &partition))
+            .insert(header("kafka.offset", &offset));
+
+        let mut record = FutureRecord::<[u8], [u8]>::to(&self.topic).headers(headers);
+        if let Some(key) = msg.key() {
+            record = record.key(key);
+        }
+        if let Some(payload) = msg.payload() {
+            record = record.payload(payload);
+        }
+
+        self.producer
+            .send(record, Timeout::After(PUBLISH_TIMEOUT))
+            .await
+            .map(|_| ())
+            .map_err(|(err, _)| err)
+    }
+}
+
+fn header<'a>(key: &'a str, value: &'a str) -> rdkafka::message::Header<'a, &'a str> {
+    rdkafka::message::Header {
+        key,
+        value: Some(value),
+    }
+}
@@ -10,21 +10,20 @@
 
 use crate::consumer_task::KafkaDeduplicationId;
 use bytes::Bytes;
+use bytestring::ByteString;
 use restate_bifrost::Bifrost;
 use restate_core::{my_node_id, Metadata};
 use restate_storage_api::deduplication_table::DedupInformation;
 use restate_types::identifiers::{
     partitioner, InvocationId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
 };
-use restate_types::invocation::{
-    InvocationTarget, ServiceInvocation, SpanRelation, VirtualObjectHandlerType,
-    WorkflowHandlerType,
-};
+use restate_types::invocation::{ServiceInvocation, SpanRelation};
 use restate_types::message::MessageIndex;
 use restate_types::partition_table::PartitionTableError;
-use restate_types::schema::subscriptions::{
-    EventInvocationTargetTemplate, EventReceiverServiceType, Sink, Subscription,
+use restate_types::schema::invocation_target::{
+    InvocationTargetResolver, DEFAULT_IDEMPOTENCY_RETENTION,
 };
+use restate_types::schema::subscriptions::Subscription;
 use restate_types::GenerationalNodeId;
 use restate_wal_protocol::{
     append_envelope_to_bifrost, Command, Destination, Envelope, Header, Source,
@@ -61,81 +60,15 @@ impl KafkaIngressEvent {
             None
         };
 
-        let invocation_target = match subscription.sink() {
-            Sink::DeprecatedService {
-                ref name,
-                ref handler,
-                ty,
-            } => match ty {
-                EventReceiverServiceType::VirtualObject => InvocationTarget::virtual_object(
-                    &**name,
-                    std::str::from_utf8(&key)
-                        .map_err(|e| anyhow::anyhow!("The key must be valid UTF-8: {e}"))?
-                        .to_owned(),
-                    &**handler,
-                    VirtualObjectHandlerType::Exclusive,
-                ),
-                EventReceiverServiceType::Workflow => InvocationTarget::workflow(
-                    &**name,
-                    std::str::from_utf8(&key)
-                        .map_err(|e| anyhow::anyhow!("The key must be valid UTF-8: {e}"))?
-                        .to_owned(),
-                    &**handler,
-                    WorkflowHandlerType::Workflow,
-                ),
-                EventReceiverServiceType::Service => InvocationTarget::service(&**name, &**handler),
-            },
-            Sink::Invocation {
-                event_invocation_target_template,
-            } => match event_invocation_target_template {
-                EventInvocationTargetTemplate::Service { name, handler } => {
-                    InvocationTarget::service(name.clone(), handler.clone())
-                }
-                EventInvocationTargetTemplate::VirtualObject {
-                    name,
-                    handler,
-                    handler_ty,
-                } => InvocationTarget::virtual_object(
-                    name.clone(),
-                    std::str::from_utf8(&key)
-                        .map_err(|e| {
-                            anyhow::anyhow!("The Kafka record key must be valid UTF-8: {e}")
-                        })?
-                        .to_owned(),
-                    handler.clone(),
-                    *handler_ty,
-                ),
-                EventInvocationTargetTemplate::Workflow {
-                    name,
-                    handler,
-                    handler_ty,
-                } => InvocationTarget::workflow(
-                    name.clone(),
-                    std::str::from_utf8(&key)
-                        .map_err(|e| {
-                            anyhow::anyhow!("The Kafka record key must be valid UTF-8: {e}")
-                        })?
-                        .to_owned(),
-                    handler.clone(),
-                    *handler_ty,
-                ),
-            },
-        };
-
-        // Generate service invocation
-        let invocation_id = InvocationId::generate(&invocation_target, None);
-        let mut service_invocation = ServiceInvocation::initialize(
-            invocation_id,
-            invocation_target,
-            if experimental_feature_kafka_ingress_next {
-                restate_types::invocation::Source::Subscription(subscription.id())
-            } else {
-                restate_types::invocation::Source::Ingress(PartitionProcessorRpcRequestId::new())
-            },
-        );
-        service_invocation.with_related_span(related_span);
-        service_invocation.argument = payload;
-        service_invocation.headers = headers;
+        let service_invocation = event_service_invocation(
+            subscription,
+            Some(&key[..]),
+            None,
+            payload,
+            related_span,
+            headers,
+            experimental_feature_kafka_ingress_next,
+        )?;
 
         Ok(KafkaIngressEvent {
             service_invocation,
@@ -146,6 +79,65 @@ impl KafkaIngressEvent {
     }
 }
 
+/// Creates the invocation of the sink of the subscription for the given event. The key is
+/// required to invoke virtual objects and workflows. The idempotency key, if any, lets the
+/// partition processor discard the redeliveries of the same event.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn event_service_invocation(
+    subscription: &Subscription,
+    key: Option<&[u8]>,
+    idempotency_key: Option<&str>,
+    payload: Bytes,
+    related_span: SpanRelation,
+    headers: Vec<restate_types::invocation::Header>,
+    experimental_feature_kafka_ingress_next: bool,
+) -> Result<ServiceInvocation, anyhow::Error> {
+    let target_key = || -> Result<String, anyhow::Error> {
+        let key = key.ok_or_else(|| {
+            anyhow::anyhow!(
+                "The event has no key, which is required to invoke {}",
+                subscription.sink()
+            )
+        })?;
+        Ok(std::str::from_utf8(key)
+            .map_err(|e| anyhow::anyhow!("The key must be valid UTF-8: {e}"))?
+            .to_owned())
+    };
+
+    let invocation_target = subscription.sink().invocation_target(target_key)?;
+
+    let completion_retention_duration = idempotency_key.and_then(|_| {
+        Metadata::with_current(|m| {
+            m.schema_ref().resolve_latest_invocation_target(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+            )
+        })
+        .map_or(Some(DEFAULT_IDEMPOTENCY_RETENTION), |metadata| {
+            metadata.compute_retention(true)
+        })
+    });
+
+    // Generate service invocation
+    let invocation_id = InvocationId::generate(&invocation_target, idempotency_key);
+    let mut service_invocation = ServiceInvocation::initialize(
+        invocation_id,
+        invocation_target,
+        if experimental_feature_kafka_ingress_next {
+            restate_types::invocation::Source::Subscription(subscription.id())
+        } else {
+            restate_types::invocation::Source::Ingress(PartitionProcessorRpcRequestId::new())
+        },
+    );
+    service_invocation.with_related_span(related_span);
+    service_invocation.argument = payload;
+    service_invocation.headers = headers;
+    service_invocation.idempotency_key = idempotency_key.map(ByteString::from);
+    service_invocation.completion_retention_duration = completion_retention_duration;
+
+    Ok(service_invocation)
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum IngressDispatchError {
     #[error("bifrost error: {0}")]
@@ -171,6 +163,36 @@ impl KafkaIngressDispatcher {
     pub(crate) fn new(bifrost: Bifrost) -> Self {
         Self { bifrost }
     }
+
+    /// Appends the invocation to bifrost, without deduplication by the sequence of its source.
+    /// Used by the sources which deduplicate the events through the idempotency key of the
+    /// invocation, such as NATS.
+    pub(crate) async fn dispatch_invocation(
+        &self,
+        service_invocation: ServiceInvocation,
+    ) -> Result<(), IngressDispatchError> {
+        let envelope = Envelope::new(
+            Header {
+                source: Source::Ingress {
+                    node_id: my_node_id(),
+                    nodes_config_version: Metadata::with_current(|m| m.nodes_config_version()),
+                },
+                dest: Destination::Processor {
+                    partition_key: service_invocation.partition_key(),
+                    dedup: None,
+                },
+            },
+            Command::Invoke(service_invocation),
+        );
+        let (log_id, lsn) = append_envelope_to_bifrost(&self.bifrost, Arc::new(envelope)).await?;
+
+        debug!(
+            log_id = %log_id,
+            lsn = %lsn,
+            "Ingress request written to bifrost"
+        );
+        Ok(())
+    }
 }
 
 impl DispatchKafkaEvent for KafkaIngressDispatcher {
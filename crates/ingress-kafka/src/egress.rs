@@ -0,0 +1,438 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use metrics::counter;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::util::Timeout;
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use tracing::{debug, warn};
+
+use crate::metric_definitions::KAFKA_EGRESS_EVENTS;
+use restate_storage_api::outbox_table::KafkaEgressEvent;
+use restate_types::config::Configuration;
+use restate_types::identifiers::{LeaderEpoch, PartitionId};
+use restate_types::message::MessageIndex;
+use restate_types::PlainNodeId;
+
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+/// Attempts to commit a transaction whose outcome is unknown, before giving up on the producer.
+const COMMIT_ATTEMPTS: usize = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("the Kafka cluster '{0}' is not configured")]
+    UnknownCluster(String),
+    #[error(transparent)]
+    Kafka(#[from] KafkaError),
+    #[error("the delivery of the event was canceled")]
+    DeliveryCanceled,
+    #[error("the Kafka transaction task panicked: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+/// Publishes the events of the outbox sent to Kafka egress targets, see
+/// [`KAFKA_EGRESS_SERVICE_PREFIX`](restate_types::invocation::KAFKA_EGRESS_SERVICE_PREFIX).
+///
+/// Events are published with exactly-once semantics, in batches: each batch is published in a
+/// Kafka transaction, which also commits the offsets of the published records for the consumer
+/// group `restate-egress-<cluster name>-<partition id>`. The outbox sequence number following the
+/// batch is stored as metadata of the committed offsets. Events whose sequence number is lower
+/// than the committed one were already published, and are skipped.
+///
+/// The transactional id of the producer is derived from the partition and the node, so that a
+/// producer which gave up on a transaction is fenced, and its transaction completed, by the next
+/// producer of the same leader. The producers of previous leaders on other nodes stop publishing
+/// once their shuffle is stopped.
+pub struct KafkaEgress {
+    partition_id: PartitionId,
+    leader_epoch: LeaderEpoch,
+    node_id: PlainNodeId,
+    producers: Mutex<HashMap<String, Arc<EgressProducer>>>,
+}
+
+impl KafkaEgress {
+    pub fn new(partition_id: PartitionId, leader_epoch: LeaderEpoch, node_id: PlainNodeId) -> Self {
+        Self {
+            partition_id,
+            leader_epoch,
+            node_id,
+            producers: Mutex::default(),
+        }
+    }
+
+    /// Publishes the events in a single transaction. The events must be sent to the same cluster,
+    /// and be ordered by sequence number.
+    pub async fn publish(
+        &self,
+        events: Vec<(MessageIndex, Arc<KafkaEgressEvent>)>,
+    ) -> Result<(), Error> {
+        let Some((_, first_event)) = events.first() else {
+            return Ok(());
+        };
+        let cluster = first_event.cluster.clone();
+        debug_assert!(events.iter().all(|(_, event)| event.cluster == cluster));
+        let producer = self.producer(&cluster).await?;
+
+        let result = producer
+            .publish(self.partition_id, self.leader_epoch, events)
+            .await;
+        match result {
+            Ok(published) => {
+                counter!(KAFKA_EGRESS_EVENTS, "cluster" => cluster).increment(published as u64);
+                Ok(())
+            }
+            Err(err) => {
+                // The producer can't be used anymore, e.g. because it was fenced or the outcome of
+                // its transaction is unknown. The next attempt creates a new one, completing the
+                // pending transaction and reading again the committed offsets.
+                if !matches!(&err, Error::Kafka(err) if is_abortable(err)) {
+                    self.producers
+                        .lock()
+                        .expect("lock not poisoned")
+                        .remove(&cluster);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn producer(&self, cluster: &str) -> Result<Arc<EgressProducer>, Error> {
+        if let Some(producer) = self
+            .producers
+            .lock()
+            .expect("lock not poisoned")
+            .get(cluster)
+        {
+            return Ok(Arc::clone(producer));
+        }
+
+        let (client_config, cluster_name) = {
+            let configuration = Configuration::pinned();
+            let cluster_options = configuration
+                .ingress
+                .get_kafka_cluster(cluster)
+                .ok_or_else(|| Error::UnknownCluster(cluster.to_owned()))?;
+
+            let mut client_config = ClientConfig::new();
+            client_config.set("metadata.broker.list", cluster_options.brokers.join(","));
+            for (k, v) in &cluster_options.additional_options {
+                client_config.set(k, v);
+            }
+            (
+                client_config,
+                configuration.common.cluster_name().to_owned(),
+            )
+        };
+
+        // Restate clusters sharing the Kafka cluster have the same partition ids
+        let group_id = format!("restate-egress-{cluster_name}-{}", self.partition_id);
+        let transactional_id = format!("{group_id}-{}", self.node_id);
+        debug!(
+            restate.partition.id = %self.partition_id,
+            "Initializing the transactional producer '{transactional_id}' for Kafka cluster '{cluster}'"
+        );
+        let producer = Arc::new(
+            tokio::task::spawn_blocking(move || {
+                EgressProducer::new(client_config, &group_id, &transactional_id)
+            })
+            .await??,
+        );
+
+        self.producers
+            .lock()
+            .expect("lock not poisoned")
+            .insert(cluster.to_owned(), Arc::clone(&producer));
+        Ok(producer)
+    }
+}
+
+struct EgressProducer {
+    producer: FutureProducer,
+    consumer: BaseConsumer,
+    committed: Mutex<CommittedSeqNumber>,
+}
+
+/// Next sequence number to publish, according to the committed offsets of the topics read so far.
+/// Since batches are committed in order, all the events before the highest committed sequence
+/// number of any topic were published.
+#[derive(Default)]
+struct CommittedSeqNumber {
+    next_seq_number: MessageIndex,
+    topics: HashSet<String>,
+}
+
+impl EgressProducer {
+    fn new(
+        mut client_config: ClientConfig,
+        group_id: &str,
+        transactional_id: &str,
+    ) -> Result<Self, KafkaError> {
+        let consumer: BaseConsumer = client_config
+            .clone()
+            .set("group.id", group_id)
+            .set("enable.auto.commit", "false")
+            .set("isolation.level", "read_committed")
+            .create()?;
+
+        let producer: FutureProducer = client_config
+            .set("transactional.id", transactional_id)
+            .set("enable.idempotence", "true")
+            .create()?;
+        // Fences the previous producers with the same transactional id, and completes their
+        // pending transactions
+        producer.init_transactions(Timeout::After(TRANSACTION_TIMEOUT))?;
+
+        Ok(Self {
+            producer,
+            consumer,
+            committed: Mutex::default(),
+        })
+    }
+
+    /// Publishes the events which weren't published yet in a transaction. Returns the number of
+    /// published events.
+    async fn publish(
+        self: &Arc<Self>,
+        partition_id: PartitionId,
+        leader_epoch: LeaderEpoch,
+        events: Vec<(MessageIndex, Arc<KafkaEgressEvent>)>,
+    ) -> Result<usize, Error> {
+        let next_seq_number = {
+            let this = Arc::clone(self);
+            let topics: Vec<_> = events.iter().map(|(_, e)| e.topic.clone()).collect();
+            tokio::task::spawn_blocking(move || this.committed_seq_number(&topics)).await??
+        };
+        let events: Vec<_> = events
+            .into_iter()
+            .skip_while(|(seq_number, event)| {
+                let published = *seq_number < next_seq_number;
+                if published {
+                    debug!(
+                        restate.outbox.seq = seq_number,
+                        "Skipping the event for Kafka topic '{}', it was already published",
+                        event.topic
+                    );
+                }
+                published
+            })
+            .collect();
+        let Some((last_seq_number, _)) = events.last() else {
+            return Ok(0);
+        };
+        let last_seq_number = *last_seq_number;
+
+        {
+            let this = Arc::clone(self);
+            tokio::task::spawn_blocking(move || this.producer.begin_transaction()).await??;
+        }
+        let offsets = match self.send(partition_id, leader_epoch, &events).await {
+            Ok(offsets) => offsets,
+            Err(err) => {
+                // Nothing was committed yet, the transaction can be aborted safely
+                let this = Arc::clone(self);
+                let _ = tokio::task::spawn_blocking(move || {
+                    this.producer
+                        .abort_transaction(Timeout::After(TRANSACTION_TIMEOUT))
+                })
+                .await;
+                return Err(err);
+            }
+        };
+
+        {
+            let this = Arc::clone(self);
+            tokio::task::spawn_blocking(move || this.commit(offsets)).await??;
+        }
+        let mut committed = self.committed.lock().expect("lock not poisoned");
+        committed.next_seq_number = committed.next_seq_number.max(last_seq_number + 1);
+        Ok(events.len())
+    }
+
+    /// Sends the events, returning the offsets of the delivered records with the next sequence
+    /// number as metadata.
+    async fn send(
+        &self,
+        partition_id: PartitionId,
+        leader_epoch: LeaderEpoch,
+        events: &[(MessageIndex, Arc<KafkaEgressEvent>)],
+    ) -> Result<TopicPartitionList, Error> {
+        let partition_id = partition_id.to_string();
+        let leader_epoch = leader_epoch.to_string();
+
+        let mut deliveries = Vec::with_capacity(events.len());
+        for (seq_number, event) in events {
+            let sequence_number = seq_number.to_string();
+            let invocation_id = event.source_invocation_id.to_string();
+
+            let mut headers = OwnedHeaders::new_with_capacity(event.headers.len() + 4);
+            for header in &event.headers {
+                headers = headers.insert(rdkafka::message::Header {
+                    key: &header.name,
+                    value: Some(header.value.as_bytes()),
+                });
+            }
+            headers = headers
+                .insert(header("restate.egress.partition_id", &partition_id))
+                .insert(header("restate.egress.leader_epoch", &leader_epoch))
+                .insert(header("restate.egress.sequence_number", &sequence_number))
+                .insert(header("restate.invocation.id", &invocation_id));
+
+            let mut record = FutureRecord::<[u8], [u8]>::to(&event.topic)
+                .payload(&event.payload[..])
+                .headers(headers);
+            if let Some(key) = &event.key {
+                record = record.key(&key[..]);
+            }
+            let delivery = self
+                .producer
+                .send_result(record)
+                .map_err(|(err, _)| Error::Kafka(err))?;
+            deliveries.push((event.topic.as_str(), delivery));
+        }
+
+        // The committed offset of each partition follows its last delivered record
+        let mut offsets: HashMap<(&str, i32), i64> = HashMap::new();
+        for (topic, delivery) in deliveries {
+            let (partition, offset) = delivery
+                .await
+                .map_err(|_| Error::DeliveryCanceled)?
+                .map_err(|(err, _)| Error::Kafka(err))?;
+            let next_offset = offsets.entry((topic, partition)).or_default();
+            *next_offset = (*next_offset).max(offset + 1);
+        }
+
+        let next_seq_number = events
+            .last()
+            .map(|(seq_number, _)| seq_number + 1)
+            .unwrap_or_default()
+            .to_string();
+        let mut list = TopicPartitionList::with_capacity(offsets.len());
+        for ((topic, partition), offset) in offsets {
+            let mut elem = list.add_partition(topic, partition);
+            elem.set_offset(Offset::Offset(offset))?;
+            elem.set_metadata(&next_seq_number);
+        }
+        Ok(list)
+    }
+
+    /// Commits the transaction together with the offsets of the delivered records. A commit whose
+    /// outcome is unknown is retried, and never aborted since the transaction might have been
+    /// committed already.
+    fn commit(&self, offsets: TopicPartitionList) -> Result<(), KafkaError> {
+        let group_metadata = self
+            .consumer
+            .group_metadata()
+            .expect("consumer has a group id");
+        if let Err(err) = self.producer.send_offsets_to_transaction(
+            &offsets,
+            &group_metadata,
+            Timeout::After(TRANSACTION_TIMEOUT),
+        ) {
+            if is_abortable(&err) {
+                let _ = self
+                    .producer
+                    .abort_transaction(Timeout::After(TRANSACTION_TIMEOUT));
+            }
+            return Err(err);
+        }
+
+        let mut attempt = 1;
+        loop {
+            match self
+                .producer
+                .commit_transaction(Timeout::After(TRANSACTION_TIMEOUT))
+            {
+                Ok(()) => return Ok(()),
+                Err(err) if is_abortable(&err) => {
+                    let _ = self
+                        .producer
+                        .abort_transaction(Timeout::After(TRANSACTION_TIMEOUT));
+                    return Err(err);
+                }
+                Err(err) if is_retriable(&err) && attempt < COMMIT_ATTEMPTS => {
+                    warn!(
+                        "Retrying the commit of the Kafka transaction with unknown outcome: {err}"
+                    );
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the next sequence number to publish, reading the committed offsets of the topics
+    /// which weren't read yet.
+    fn committed_seq_number(&self, topics: &[String]) -> Result<MessageIndex, KafkaError> {
+        for topic in topics {
+            if self
+                .committed
+                .lock()
+                .expect("lock not poisoned")
+                .topics
+                .contains(topic)
+            {
+                continue;
+            }
+
+            let metadata = self
+                .consumer
+                .fetch_metadata(Some(topic), Timeout::After(TRANSACTION_TIMEOUT))?;
+            let mut partitions = TopicPartitionList::new();
+            for topic_metadata in metadata.topics() {
+                for partition in topic_metadata.partitions() {
+                    partitions.add_partition(topic, partition.id());
+                }
+            }
+            let committed = self
+                .consumer
+                .committed_offsets(partitions, Timeout::After(TRANSACTION_TIMEOUT))?;
+            let seq_number = committed
+                .elements()
+                .iter()
+                .filter_map(|elem| elem.metadata().parse::<MessageIndex>().ok())
+                .max()
+                .unwrap_or_default();
+
+            let mut committed = self.committed.lock().expect("lock not poisoned");
+            committed.next_seq_number = committed.next_seq_number.max(seq_number);
+            committed.topics.insert(topic.clone());
+        }
+
+        Ok(self
+            .committed
+            .lock()
+            .expect("lock not poisoned")
+            .next_seq_number)
+    }
+}
+
+/// The transaction failed without being committed, and the producer can be used again after
+/// aborting it.
+fn is_abortable(err: &KafkaError) -> bool {
+    matches!(err, KafkaError::Transaction(err) if err.txn_requires_abort() && !err.is_fatal())
+}
+
+fn is_retriable(err: &KafkaError) -> bool {
+    matches!(err, KafkaError::Transaction(err) if err.is_retriable())
+}
+
+fn header<'a>(key: &'a str, value: &'a str) -> rdkafka::message::Header<'a, &'a str> {
+    rdkafka::message::Header {
+        key,
+        value: Some(value),
+    }
+}
@@ -9,12 +9,16 @@
 // by the Apache License, Version 2.0.
 
 mod consumer_task;
+mod dead_letter;
 mod dispatcher;
+mod egress;
 mod metric_definitions;
+mod nats;
 mod subscription_controller;
 
 use tokio::sync::mpsc;
 
+pub use egress::{Error as KafkaEgressError, KafkaEgress};
 pub use subscription_controller::{Command, Error, Service};
 
 pub type SubscriptionCommandSender = mpsc::Sender<Command>;
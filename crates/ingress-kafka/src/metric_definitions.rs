@@ -11,6 +11,9 @@
 use metrics::{describe_counter, Unit};
 
 pub const KAFKA_INGRESS_REQUESTS: &str = "restate.kafka_ingress.requests.total";
+pub const KAFKA_INGRESS_DEAD_LETTERS: &str = "restate.kafka_ingress.dead_letters.total";
+pub const NATS_INGRESS_REQUESTS: &str = "restate.nats_ingress.requests.total";
+pub const KAFKA_EGRESS_EVENTS: &str = "restate.kafka_egress.events.total";
 
 pub(crate) fn describe_metrics() {
     describe_counter!(
@@ -18,4 +21,19 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of Kafka ingress requests"
     );
+    describe_counter!(
+        KAFKA_INGRESS_DEAD_LETTERS,
+        Unit::Count,
+        "Number of Kafka events published to the dead letter topic"
+    );
+    describe_counter!(
+        NATS_INGRESS_REQUESTS,
+        Unit::Count,
+        "Number of NATS ingress requests"
+    );
+    describe_counter!(
+        KAFKA_EGRESS_EVENTS,
+        Unit::Count,
+        "Number of events published to Kafka egress targets"
+    );
 }
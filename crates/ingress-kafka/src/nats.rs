@@ -0,0 +1,251 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use async_nats::jetstream;
+use async_nats::jetstream::consumer::{pull, AckPolicy, DeliverPolicy};
+use async_nats::jetstream::AckKind;
+use futures::StreamExt;
+use metrics::counter;
+use opentelemetry::trace::TraceContextExt;
+use tokio::sync::oneshot;
+use tracing::{debug, info, info_span, warn, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::dispatcher::{event_service_invocation, IngressDispatchError, KafkaIngressDispatcher};
+use crate::metric_definitions::NATS_INGRESS_REQUESTS;
+use restate_types::errors::GenericError;
+use restate_types::invocation::{Header, SpanRelation};
+use restate_types::schema::subscriptions::{
+    Subscription, NATS_CREDENTIALS_FILE_OPTION, NATS_PASSWORD_OPTION, NATS_TLS_CA_FILE_OPTION,
+    NATS_TOKEN_OPTION, NATS_USER_OPTION,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("NATS error: {0}")]
+    Nats(GenericError),
+    #[error("error processing message {sequence} of stream {stream}: {cause}")]
+    Event {
+        stream: String,
+        sequence: u64,
+        #[source]
+        cause: anyhow::Error,
+    },
+    #[error("failed to dispatch the event: {0}")]
+    Dispatch(#[from] IngressDispatchError),
+}
+
+/// Consumes a NATS JetStream stream through a durable pull consumer named after the
+/// subscription id, and ingests the received messages.
+///
+/// Messages are acknowledged only after the invocation has been appended to bifrost, and failed
+/// dispatches are retried indefinitely by receiving the messages again. The redeliveries are
+/// deduplicated through the idempotency key of the invocations, made of the subscription id and
+/// the stream sequence of the message. Messages which cannot be converted to an invocation are
+/// terminated, to stop their redelivery.
+#[derive(Clone)]
+pub struct NatsConsumerTask {
+    subscription: Subscription,
+    server: String,
+    stream: String,
+    dispatcher: KafkaIngressDispatcher,
+    experimental_feature_kafka_ingress_next: bool,
+
+    subscription_id: String,
+    ingress_request_counter: metrics::Counter,
+}
+
+impl NatsConsumerTask {
+    pub fn new(
+        subscription: Subscription,
+        server: String,
+        stream: String,
+        dispatcher: KafkaIngressDispatcher,
+        experimental_feature_kafka_ingress_next: bool,
+    ) -> Self {
+        Self {
+            subscription_id: subscription.id().to_string(),
+            ingress_request_counter: counter!(
+                NATS_INGRESS_REQUESTS,
+                "subscription" => subscription.id().to_string()
+            ),
+            subscription,
+            server,
+            stream,
+            dispatcher,
+            experimental_feature_kafka_ingress_next,
+        }
+    }
+
+    pub async fn run(self, mut rx: oneshot::Receiver<()>) -> Result<(), Error> {
+        debug!(
+            restate.subscription.id = %self.subscription.id(),
+            "Starting NATS consumer for stream {} of server {}",
+            self.stream, self.server
+        );
+
+        let client = self
+            .connect_options()
+            .await?
+            .connect(format!("nats://{}", self.server))
+            .await
+            .map_err(|e| Error::Nats(e.into()))?;
+        let stream = jetstream::new(client)
+            .get_stream(&self.stream)
+            .await
+            .map_err(|e| Error::Nats(e.into()))?;
+        let consumer = stream
+            .get_or_create_consumer(&self.subscription_id, self.consumer_config())
+            .await
+            .map_err(|e| Error::Nats(e.into()))?;
+        let mut messages = consumer
+            .messages()
+            .await
+            .map_err(|e| Error::Nats(e.into()))?;
+
+        loop {
+            let message = tokio::select! {
+                message = messages.next() => match message {
+                    Some(message) => message.map_err(|e| Error::Nats(e.into()))?,
+                    None => return Ok(()),
+                },
+                _ = &mut rx => {
+                    return Ok(());
+                }
+            };
+
+            match self.send(&message).await {
+                Ok(()) => {
+                    // The invocation is durably appended to the log, the message can be acked
+                    message.ack().await.map_err(Error::Nats)?;
+                }
+                Err(err @ Error::Event { .. }) => {
+                    warn!(
+                        restate.subscription.id = %self.subscription.id(),
+                        "Terminating the delivery of the NATS message: {err}"
+                    );
+                    message.ack_with(AckKind::Term).await.map_err(Error::Nats)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn connect_options(&self) -> Result<async_nats::ConnectOptions, Error> {
+        let metadata = self.subscription.metadata();
+        let mut options = if let (Some(user), Some(password)) = (
+            metadata.get(NATS_USER_OPTION),
+            metadata.get(NATS_PASSWORD_OPTION),
+        ) {
+            async_nats::ConnectOptions::with_user_and_password(user.clone(), password.clone())
+        } else if let Some(token) = metadata.get(NATS_TOKEN_OPTION) {
+            async_nats::ConnectOptions::with_token(token.clone())
+        } else if let Some(credentials_file) = metadata.get(NATS_CREDENTIALS_FILE_OPTION) {
+            async_nats::ConnectOptions::with_credentials_file(credentials_file)
+                .await
+                .map_err(|e| Error::Nats(e.into()))?
+        } else {
+            async_nats::ConnectOptions::new()
+        };
+
+        if self.subscription.nats_tls() {
+            options = options.require_tls(true);
+        }
+        if let Some(ca_file) = metadata.get(NATS_TLS_CA_FILE_OPTION) {
+            options = options.add_root_certificates(ca_file.into());
+        }
+
+        Ok(options)
+    }
+
+    fn consumer_config(&self) -> pull::Config {
+        pull::Config {
+            durable_name: Some(self.subscription_id.clone()),
+            deliver_policy: DeliverPolicy::All,
+            ack_policy: AckPolicy::Explicit,
+            max_ack_pending: self.subscription.nats_max_ack_pending(),
+            // Failed dispatches must not count as deliveries towards a limit, they're retried
+            // until they succeed
+            max_deliver: -1,
+            filter_subject: self
+                .subscription
+                .nats_filter_subject()
+                .unwrap_or_default()
+                .to_owned(),
+            ..Default::default()
+        }
+    }
+
+    async fn send(&self, message: &jetstream::Message) -> Result<(), Error> {
+        let info = message.info().map_err(Error::Nats)?;
+        let sequence = info.stream_sequence;
+
+        // Prepare ingress span
+        let ingress_span = info_span!(
+            "nats_ingress_consume",
+            otel.name = "nats_ingress_consume",
+            messaging.system = "nats",
+            messaging.operation = "receive",
+            messaging.source.name = message.subject.as_str(),
+            messaging.destination.name = %self.subscription.sink(),
+            restate.subscription.id = %self.subscription.id(),
+            messaging.consumer.group.name = self.subscription_id.as_str(),
+        );
+        info!(parent: &ingress_span, "Processing NATS ingress request");
+        let ingress_span_context = ingress_span.context().span().span_context().clone();
+
+        let key = match self.subscription.nats_key_header() {
+            Some(key_header) => message
+                .headers
+                .as_ref()
+                .and_then(|headers| headers.get(key_header))
+                .map(|value| value.as_str()),
+            None => Some(message.subject.as_str()),
+        };
+        let headers = self.generate_events_attributes(message, sequence);
+        let idempotency_key = format!("{}-{}", self.subscription_id, sequence);
+
+        let service_invocation = event_service_invocation(
+            &self.subscription,
+            key.map(str::as_bytes),
+            Some(&idempotency_key),
+            message.payload.clone(),
+            SpanRelation::Parent(ingress_span_context),
+            headers,
+            self.experimental_feature_kafka_ingress_next,
+        )
+        .map_err(|cause| Error::Event {
+            stream: self.stream.clone(),
+            sequence,
+            cause,
+        })?;
+
+        self.ingress_request_counter.increment(1);
+
+        self.dispatcher
+            .dispatch_invocation(service_invocation)
+            .instrument(ingress_span)
+            .await?;
+        Ok(())
+    }
+
+    fn generate_events_attributes(
+        &self,
+        message: &jetstream::Message,
+        sequence: u64,
+    ) -> Vec<Header> {
+        vec![
+            Header::new("nats.subject", message.subject.as_str()),
+            Header::new("nats.stream", &*self.stream),
+            Header::new("nats.sequence", sequence.to_string()),
+            Header::new("restate.subscription.id", &*self.subscription_id),
+        ]
+    }
+}
@@ -12,8 +12,10 @@ use super::consumer_task::MessageSender;
 use super::*;
 use std::collections::HashSet;
 
+use crate::dead_letter::DeadLetterQueue;
 use crate::dispatcher::KafkaIngressDispatcher;
-use crate::subscription_controller::task_orchestrator::TaskOrchestrator;
+use crate::nats::NatsConsumerTask;
+use crate::subscription_controller::task_orchestrator::{SubscriptionTask, TaskOrchestrator};
 use anyhow::Context;
 use rdkafka::error::KafkaError;
 use restate_bifrost::Bifrost;
@@ -116,9 +118,27 @@ impl Service {
         subscription: Subscription,
         task_orchestrator: &mut TaskOrchestrator,
     ) -> anyhow::Result<()> {
-        let mut client_config = rdkafka::ClientConfig::new();
+        let (cluster, topic) = match subscription.source() {
+            Source::Kafka { cluster, topic } => (cluster, topic),
+            Source::Nats { server, stream } => {
+                let (server, stream) = (server.clone(), stream.clone());
+                let subscription_id = subscription.id();
+                let consumer_task = NatsConsumerTask::new(
+                    subscription,
+                    server,
+                    stream,
+                    self.dispatcher.clone(),
+                    options.experimental_feature_kafka_ingress_next(),
+                );
+                task_orchestrator.start(subscription_id, SubscriptionTask::Nats(consumer_task));
+                return Ok(());
+            }
+            // SQS queues are polled by the SQS ingress, and webhook events are pushed to the
+            // HTTP ingress, there is nothing to consume
+            Source::Sqs { .. } | Source::Webhook { .. } => return Ok(()),
+        };
 
-        let Source::Kafka { cluster, topic, .. } = subscription.source();
+        let mut client_config = rdkafka::ClientConfig::new();
 
         // Copy cluster options and subscription metadata into client_config
         let cluster_options = options
@@ -129,7 +149,7 @@ impl Service {
         for (k, v) in cluster_options.additional_options.clone() {
             client_config.set(k, v);
         }
-        for (k, v) in subscription.metadata() {
+        for (k, v) in subscription.client_options() {
             client_config.set(k, v);
         }
 
@@ -140,6 +160,11 @@ impl Service {
 
         let subscription_id = subscription.id();
 
+        let dead_letter_queue = subscription
+            .dead_letter_topic()
+            .map(|topic| DeadLetterQueue::new(&client_config, topic.to_owned()))
+            .transpose()?;
+
         // Create the consumer task
         let consumer_task = consumer_task::ConsumerTask::new(
             client_config,
@@ -148,10 +173,11 @@ impl Service {
                 subscription,
                 self.dispatcher.clone(),
                 options.experimental_feature_kafka_ingress_next(),
+                dead_letter_queue,
             ),
         );
 
-        task_orchestrator.start(subscription_id, consumer_task);
+        task_orchestrator.start(subscription_id, SubscriptionTask::Kafka(consumer_task));
 
         Ok(())
     }
@@ -189,7 +215,7 @@ impl Service {
 }
 
 mod task_orchestrator {
-    use crate::consumer_task;
+    use crate::{consumer_task, nats};
     use restate_core::{TaskCenterFutureExt, TaskKind};
     use restate_timer_queue::TimerQueue;
     use restate_types::identifiers::SubscriptionId;
@@ -201,9 +227,40 @@ mod task_orchestrator {
     use tokio::task::{JoinError, JoinSet};
     use tracing::{debug, warn};
 
+    /// Task consuming the source of a subscription.
+    #[derive(Clone)]
+    pub(super) enum SubscriptionTask {
+        Kafka(consumer_task::ConsumerTask),
+        Nats(nats::NatsConsumerTask),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub(super) enum SubscriptionTaskError {
+        #[error(transparent)]
+        Kafka(#[from] consumer_task::Error),
+        #[error(transparent)]
+        Nats(#[from] nats::Error),
+    }
+
+    impl SubscriptionTask {
+        async fn run(self, rx: oneshot::Receiver<()>) -> Result<(), SubscriptionTaskError> {
+            match self {
+                SubscriptionTask::Kafka(consumer_task) => Ok(consumer_task.run(rx).await?),
+                SubscriptionTask::Nats(consumer_task) => Ok(consumer_task.run(rx).await?),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            match self {
+                SubscriptionTask::Kafka(_) => "kafka-consumer-task",
+                SubscriptionTask::Nats(_) => "nats-consumer-task",
+            }
+        }
+    }
+
     struct TaskState {
         // We use this to restart the consumer task in case of a failure
-        consumer_task_clone: consumer_task::ConsumerTask,
+        consumer_task_clone: SubscriptionTask,
         task_state_inner: TaskStateInner,
         retry_iter: RetryIter<'static>,
     }
@@ -220,7 +277,7 @@ mod task_orchestrator {
         retry_policy: RetryPolicy,
         running_tasks_to_subscriptions: HashMap<task::Id, SubscriptionId>,
         subscription_id_to_task_state: HashMap<SubscriptionId, TaskState>,
-        tasks: JoinSet<Result<(), consumer_task::Error>>,
+        tasks: JoinSet<Result<(), SubscriptionTaskError>>,
         timer_queue: TimerQueue<SubscriptionId>,
     }
 
@@ -252,7 +309,7 @@ mod task_orchestrator {
 
         fn handle_task_closed(
             &mut self,
-            result: Result<(task::Id, Result<(), consumer_task::Error>), JoinError>,
+            result: Result<(task::Id, Result<(), SubscriptionTaskError>), JoinError>,
         ) {
             match result {
                 Ok((id, Ok(_))) => {
@@ -323,7 +380,7 @@ mod task_orchestrator {
         pub(super) fn start(
             &mut self,
             subscription_id: SubscriptionId,
-            consumer_task_clone: consumer_task::ConsumerTask,
+            consumer_task_clone: SubscriptionTask,
         ) {
             // Shutdown old task, if any
             if let Some(task_state) = self.subscription_id_to_task_state.remove(&subscription_id) {
@@ -344,9 +401,10 @@ mod task_orchestrator {
                 .tasks
                 .spawn({
                     let consumer_task_clone = consumer_task_clone.clone();
+                    let name = consumer_task_clone.name();
                     consumer_task_clone
                         .run(rx)
-                        .in_current_tc_as_task(TaskKind::Kafka, "kafka-consumer-task")
+                        .in_current_tc_as_task(TaskKind::Kafka, name)
                 })
                 .id();
 
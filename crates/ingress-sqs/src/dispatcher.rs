@@ -0,0 +1,124 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use tracing::debug;
+
+use restate_bifrost::Bifrost;
+use restate_core::{my_node_id, Metadata};
+use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId, WithPartitionKey};
+use restate_types::invocation::{Header, ServiceInvocation, SpanRelation};
+use restate_types::schema::invocation_target::{
+    InvocationTargetResolver, DEFAULT_IDEMPOTENCY_RETENTION,
+};
+use restate_types::schema::subscriptions::Subscription;
+use restate_wal_protocol::{append_envelope_to_bifrost, Command, Destination, Envelope, Source};
+
+#[derive(Debug, thiserror::Error)]
+#[error("bifrost error: {0}")]
+pub struct IngressDispatchError(#[from] restate_wal_protocol::Error);
+
+/// Creates the invocation of the sink of the subscription for an SQS message.
+///
+/// The message id is used as idempotency key: SQS delivers the messages at least once, and the
+/// same message is received again if it could not be deleted after being ingested, for example
+/// because its visibility timeout expired in the meantime. Such redeliveries are deduplicated by
+/// the partition processor, as long as the idempotency retention of the target handler.
+pub(crate) fn sqs_service_invocation(
+    subscription: &Subscription,
+    message_id: &str,
+    key: Option<&str>,
+    payload: Bytes,
+    related_span: SpanRelation,
+    headers: Vec<Header>,
+    experimental_feature_kafka_ingress_next: bool,
+) -> Result<ServiceInvocation, anyhow::Error> {
+    let invocation_target = subscription.sink().invocation_target(|| {
+        key.map(str::to_owned).ok_or_else(|| {
+            anyhow::anyhow!(
+                "The message has no key, which is required to invoke {}",
+                subscription.sink()
+            )
+        })
+    })?;
+
+    let idempotency_key = ByteString::from(message_id);
+    let completion_retention_duration = Metadata::with_current(|m| {
+        m.schema_ref().resolve_latest_invocation_target(
+            invocation_target.service_name(),
+            invocation_target.handler_name(),
+        )
+    })
+    .map(|metadata| metadata.compute_retention(true))
+    .unwrap_or(Some(DEFAULT_IDEMPOTENCY_RETENTION));
+
+    let invocation_id = InvocationId::generate(&invocation_target, Some(message_id));
+    let mut service_invocation = ServiceInvocation::initialize(
+        invocation_id,
+        invocation_target,
+        if experimental_feature_kafka_ingress_next {
+            restate_types::invocation::Source::Subscription(subscription.id())
+        } else {
+            restate_types::invocation::Source::Ingress(PartitionProcessorRpcRequestId::new())
+        },
+    );
+    service_invocation.with_related_span(related_span);
+    service_invocation.argument = payload;
+    service_invocation.headers = headers;
+    service_invocation.idempotency_key = Some(idempotency_key);
+    service_invocation.completion_retention_duration = completion_retention_duration;
+
+    Ok(service_invocation)
+}
+
+/// Dispatches the invocations of the SQS ingress to bifrost
+#[derive(Clone)]
+pub(crate) struct SqsIngressDispatcher {
+    bifrost: Bifrost,
+}
+
+impl SqsIngressDispatcher {
+    pub(crate) fn new(bifrost: Bifrost) -> Self {
+        Self { bifrost }
+    }
+
+    /// Appends the invocation to the log of its partition. Duplicates are discarded by the
+    /// partition processor through the idempotency key of the invocation, see
+    /// [`sqs_service_invocation`].
+    pub(crate) async fn dispatch(
+        &self,
+        service_invocation: ServiceInvocation,
+    ) -> Result<(), IngressDispatchError> {
+        let envelope = Envelope::new(
+            restate_wal_protocol::Header {
+                source: Source::Ingress {
+                    node_id: my_node_id(),
+                    nodes_config_version: Metadata::with_current(|m| m.nodes_config_version()),
+                },
+                dest: Destination::Processor {
+                    partition_key: service_invocation.partition_key(),
+                    dedup: None,
+                },
+            },
+            Command::Invoke(service_invocation),
+        );
+        let (log_id, lsn) = append_envelope_to_bifrost(&self.bifrost, Arc::new(envelope)).await?;
+
+        debug!(
+            log_id = %log_id,
+            lsn = %lsn,
+            "SQS ingress request written to bifrost"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Ingestion of the messages of AWS SQS queues. Every subscription with an SQS source is served
+//! by a poller task, which appends the invocations of the received messages to bifrost.
+
+mod dispatcher;
+mod metric_definitions;
+mod poller;
+mod service;
+
+use tokio::sync::mpsc;
+
+pub use service::{Command, Service};
+
+pub type SubscriptionCommandSender = mpsc::Sender<Command>;
+pub type SubscriptionCommandReceiver = mpsc::Receiver<Command>;
@@ -0,0 +1,335 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashSet;
+
+use aws_config::BehaviorVersion;
+use aws_sdk_sqs::config::Region;
+use aws_sdk_sqs::types::{Message, MessageSystemAttributeName, QueueAttributeName};
+use bytes::Bytes;
+use metrics::counter;
+use opentelemetry::trace::TraceContextExt;
+use serde::Deserialize;
+use tracing::{debug, info, info_span, warn, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use restate_core::cancellation_watcher;
+use restate_types::identifiers::SubscriptionId;
+use restate_types::invocation::{Header, ServiceInvocation, SpanRelation};
+use restate_types::schema::subscriptions::Subscription;
+
+use crate::dispatcher::{sqs_service_invocation, IngressDispatchError, SqsIngressDispatcher};
+use crate::metric_definitions::{SQS_INGRESS_DROPPED_MESSAGES, SQS_INGRESS_REQUESTS};
+
+/// Max number of messages returned by a single receive, as allowed by SQS.
+const MAX_NUMBER_OF_MESSAGES: i32 = 10;
+/// Long polling wait time, as allowed by SQS.
+const WAIT_TIME_SECONDS: i32 = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Sqs(#[from] aws_sdk_sqs::Error),
+    #[error("failed to dispatch the event: {0}")]
+    Dispatch(#[from] IngressDispatchError),
+}
+
+/// Body of the SQS messages delivered by an SNS subscription, when raw message delivery is
+/// disabled.
+#[derive(Deserialize)]
+struct SnsNotification {
+    #[serde(rename = "Message")]
+    message: String,
+}
+
+/// Long polls the SQS queue of a subscription, and ingests the received messages.
+///
+/// Messages are deleted from the queue only after the invocation has been appended to bifrost,
+/// so failures to dispatch them are retried by receiving them again. Messages which can never be
+/// converted to an invocation, e.g. because they miss the key required by the sink, are made
+/// visible again right away if the queue has a redrive policy, so that SQS moves them to the dead
+/// letter queue of the queue after its max receive count. Without a redrive policy they're
+/// deleted, so that they don't block the following messages of their group in FIFO queues.
+#[derive(Clone)]
+pub(crate) struct SqsPoller {
+    region: String,
+    queue_url: String,
+    subscription: Subscription,
+    dispatcher: SqsIngressDispatcher,
+    experimental_feature_kafka_ingress_next: bool,
+
+    subscription_id: String,
+    ingress_request_counter: metrics::Counter,
+    dropped_messages_counter: metrics::Counter,
+}
+
+impl SqsPoller {
+    pub(crate) fn new(
+        region: String,
+        queue_url: String,
+        subscription: Subscription,
+        dispatcher: SqsIngressDispatcher,
+        experimental_feature_kafka_ingress_next: bool,
+    ) -> Self {
+        Self {
+            subscription_id: subscription.id().to_string(),
+            ingress_request_counter: counter!(
+                SQS_INGRESS_REQUESTS,
+                "subscription" => subscription.id().to_string()
+            ),
+            dropped_messages_counter: counter!(
+                SQS_INGRESS_DROPPED_MESSAGES,
+                "subscription" => subscription.id().to_string()
+            ),
+            region,
+            queue_url,
+            subscription,
+            dispatcher,
+            experimental_feature_kafka_ingress_next,
+        }
+    }
+
+    pub(crate) fn subscription_id(&self) -> SubscriptionId {
+        self.subscription.id()
+    }
+
+    /// Polls the queue until the task is cancelled.
+    pub(crate) async fn run(self) -> Result<(), Error> {
+        debug!(
+            restate.subscription.id = %self.subscription.id(),
+            "Starting SQS poller for queue {}",
+            self.queue_url
+        );
+
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(self.region.clone()))
+            .load()
+            .await;
+        let mut sqs_config = aws_sdk_sqs::config::Builder::from(&config);
+        if let Some(endpoint_url) = self.subscription.sqs_endpoint_url() {
+            sqs_config = sqs_config.endpoint_url(endpoint_url);
+        }
+        let client = aws_sdk_sqs::Client::from_conf(sqs_config.build());
+
+        let has_redrive_policy = self.has_redrive_policy(&client).await;
+        let shutdown = cancellation_watcher();
+        tokio::pin!(shutdown);
+
+        loop {
+            let output = tokio::select! {
+                res = client
+                    .receive_message()
+                    .queue_url(&self.queue_url)
+                    .max_number_of_messages(MAX_NUMBER_OF_MESSAGES)
+                    .wait_time_seconds(WAIT_TIME_SECONDS)
+                    .message_system_attribute_names(MessageSystemAttributeName::All)
+                    .message_attribute_names("All")
+                    .send() => res.map_err(aws_sdk_sqs::Error::from)?,
+                _ = &mut shutdown => {
+                    return Ok(());
+                }
+            };
+
+            self.process_messages(
+                &client,
+                output.messages.unwrap_or_default(),
+                has_redrive_policy,
+            )
+            .await?;
+        }
+    }
+
+    /// Whether the queue moves the messages received too many times to a dead letter queue. If
+    /// the policy can't be read, e.g. for lack of permissions, the queue is assumed to have one,
+    /// so that no message is deleted without being ingested.
+    async fn has_redrive_policy(&self, client: &aws_sdk_sqs::Client) -> bool {
+        match client
+            .get_queue_attributes()
+            .queue_url(&self.queue_url)
+            .attribute_names(QueueAttributeName::RedrivePolicy)
+            .send()
+            .await
+        {
+            Ok(output) => output.attributes().is_some_and(|attributes| {
+                attributes.contains_key(&QueueAttributeName::RedrivePolicy)
+            }),
+            Err(err) => {
+                warn!(
+                    restate.subscription.id = %self.subscription.id(),
+                    "Cannot read the redrive policy of the SQS queue {}, assuming it has one: {}",
+                    self.queue_url,
+                    aws_sdk_sqs::Error::from(err)
+                );
+                true
+            }
+        }
+    }
+
+    async fn process_messages(
+        &self,
+        client: &aws_sdk_sqs::Client,
+        messages: Vec<Message>,
+        has_redrive_policy: bool,
+    ) -> Result<(), Error> {
+        // Messages of FIFO queues must be ingested in order within their group. Once a message
+        // is left in the queue, the following messages of its group are skipped, so they're
+        // received again after the left one.
+        let mut blocked_groups = HashSet::new();
+
+        for message in messages {
+            let group_id =
+                message_system_attribute(&message, &MessageSystemAttributeName::MessageGroupId);
+            if group_id.is_some_and(|group_id| blocked_groups.contains(group_id)) {
+                continue;
+            }
+            let Some(receipt_handle) = message.receipt_handle() else {
+                continue;
+            };
+
+            let ingress_span = self.ingress_span(&message);
+            match self.service_invocation(&message, group_id, &ingress_span) {
+                Ok(service_invocation) => {
+                    self.ingress_request_counter.increment(1);
+                    self.dispatcher
+                        .dispatch(service_invocation)
+                        .instrument(ingress_span)
+                        .await?;
+                }
+                Err(cause) if has_redrive_policy => {
+                    warn!(
+                        restate.subscription.id = %self.subscription.id(),
+                        "Cannot ingest the SQS message {}, leaving it to the redrive policy of the queue: {cause}",
+                        message.message_id().unwrap_or_default()
+                    );
+                    client
+                        .change_message_visibility()
+                        .queue_url(&self.queue_url)
+                        .receipt_handle(receipt_handle)
+                        .visibility_timeout(0)
+                        .send()
+                        .await
+                        .map_err(aws_sdk_sqs::Error::from)?;
+                    if let Some(group_id) = group_id {
+                        blocked_groups.insert(group_id.to_owned());
+                    }
+                    continue;
+                }
+                Err(cause) => {
+                    warn!(
+                        restate.subscription.id = %self.subscription.id(),
+                        "Cannot ingest the SQS message {}, deleting it as the queue has no redrive policy: {cause}",
+                        message.message_id().unwrap_or_default()
+                    );
+                    self.dropped_messages_counter.increment(1);
+                }
+            }
+
+            // The invocation is durably appended to the log, or it can never be, the message
+            // can be deleted
+            client
+                .delete_message()
+                .queue_url(&self.queue_url)
+                .receipt_handle(receipt_handle)
+                .send()
+                .await
+                .map_err(aws_sdk_sqs::Error::from)?;
+        }
+
+        Ok(())
+    }
+
+    fn ingress_span(&self, message: &Message) -> Span {
+        let ingress_span = info_span!(
+            "sqs_ingress_consume",
+            otel.name = "sqs_ingress_consume",
+            messaging.system = "aws_sqs",
+            messaging.operation = "receive",
+            messaging.source.name = %self.subscription.source(),
+            messaging.destination.name = %self.subscription.sink(),
+            messaging.message.id = message.message_id().unwrap_or_default(),
+            restate.subscription.id = %self.subscription.id(),
+        );
+        info!(parent: &ingress_span, "Processing SQS ingress request");
+        ingress_span
+    }
+
+    /// Converts the message to an invocation. Failures are deterministic, receiving the message
+    /// again would fail the same way.
+    fn service_invocation(
+        &self,
+        message: &Message,
+        group_id: Option<&str>,
+        ingress_span: &Span,
+    ) -> Result<ServiceInvocation, anyhow::Error> {
+        let message_id = message
+            .message_id()
+            .ok_or_else(|| anyhow::anyhow!("The message has no id"))?;
+        let key = match self.subscription.sqs_key_attribute() {
+            Some(key_attribute) => message
+                .message_attributes()
+                .and_then(|attributes| attributes.get(key_attribute))
+                .and_then(|attribute| attribute.string_value()),
+            None => group_id,
+        };
+
+        sqs_service_invocation(
+            &self.subscription,
+            message_id,
+            key,
+            self.payload(message)?,
+            SpanRelation::Parent(ingress_span.context().span().span_context().clone()),
+            self.generate_events_attributes(message, group_id),
+            self.experimental_feature_kafka_ingress_next,
+        )
+    }
+
+    fn payload(&self, message: &Message) -> Result<Bytes, anyhow::Error> {
+        let body = message.body().unwrap_or_default();
+        if self.subscription.sqs_sns_envelope() {
+            let notification: SnsNotification = serde_json::from_str(body)
+                .map_err(|e| anyhow::anyhow!("The message is not a valid SNS notification: {e}"))?;
+            Ok(Bytes::from(notification.message))
+        } else {
+            Ok(Bytes::copy_from_slice(body.as_bytes()))
+        }
+    }
+
+    fn generate_events_attributes(&self, message: &Message, group_id: Option<&str>) -> Vec<Header> {
+        let mut headers = Vec::with_capacity(5);
+        headers.push(Header::new("sqs.queue_url", &*self.queue_url));
+        if let Some(message_id) = message.message_id() {
+            headers.push(Header::new("sqs.message_id", message_id));
+        }
+        if let Some(group_id) = group_id {
+            headers.push(Header::new("sqs.message_group_id", group_id));
+        }
+        if let Some(sent_timestamp) =
+            message_system_attribute(message, &MessageSystemAttributeName::SentTimestamp)
+        {
+            headers.push(Header::new("sqs.sent_timestamp", sent_timestamp));
+        }
+        headers.push(Header::new(
+            "restate.subscription.id".to_string(),
+            &*self.subscription_id,
+        ));
+
+        headers
+    }
+}
+
+fn message_system_attribute<'a>(
+    message: &'a Message,
+    name: &MessageSystemAttributeName,
+) -> Option<&'a str> {
+    message
+        .attributes()
+        .and_then(|attributes| attributes.get(name))
+        .map(String::as_str)
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use restate_bifrost::Bifrost;
+use restate_core::{cancellation_watcher, TaskCenter, TaskId, TaskKind};
+use restate_types::config::IngressOptions;
+use restate_types::identifiers::SubscriptionId;
+use restate_types::live::LiveLoad;
+use restate_types::retries::RetryPolicy;
+use restate_types::schema::subscriptions::{Source, Subscription};
+
+use crate::dispatcher::SqsIngressDispatcher;
+use crate::metric_definitions;
+use crate::poller::SqsPoller;
+use crate::{SubscriptionCommandReceiver, SubscriptionCommandSender};
+
+#[derive(Debug)]
+pub enum Command {
+    StartSubscription(Subscription),
+    StopSubscription(SubscriptionId),
+    /// Replaces the running subscriptions with the SQS subscriptions of the list
+    UpdateSubscriptions(Vec<Subscription>),
+}
+
+/// Runs a poller task for every subscription with an SQS source. Failed pollers are restarted
+/// with an exponential backoff, until their subscription is stopped.
+pub struct Service {
+    dispatcher: SqsIngressDispatcher,
+
+    commands_tx: SubscriptionCommandSender,
+    commands_rx: SubscriptionCommandReceiver,
+}
+
+impl Service {
+    pub fn new(bifrost: Bifrost) -> Service {
+        metric_definitions::describe_metrics();
+        let (commands_tx, commands_rx) = mpsc::channel(10);
+
+        Service {
+            dispatcher: SqsIngressDispatcher::new(bifrost),
+            commands_tx,
+            commands_rx,
+        }
+    }
+
+    pub fn create_command_sender(&self) -> SubscriptionCommandSender {
+        self.commands_tx.clone()
+    }
+
+    pub async fn run(
+        mut self,
+        mut updateable_config: impl LiveLoad<IngressOptions> + Send + 'static,
+    ) -> anyhow::Result<()> {
+        let shutdown = cancellation_watcher();
+        tokio::pin!(shutdown);
+
+        // NOTE: Configuration is pinned to a certain snapshot until we support adding/removing
+        // subscriptions dynamically from config
+        let experimental_feature_kafka_ingress_next = updateable_config
+            .live_load()
+            .experimental_feature_kafka_ingress_next();
+
+        let mut pollers = HashMap::new();
+
+        loop {
+            tokio::select! {
+                Some(cmd) = self.commands_rx.recv() => {
+                    match cmd {
+                        Command::StartSubscription(subscription) => {
+                            self.start_poller(&mut pollers, subscription, experimental_feature_kafka_ingress_next)
+                        }
+                        Command::StopSubscription(subscription_id) => {
+                            stop_poller(&mut pollers, subscription_id)
+                        }
+                        Command::UpdateSubscriptions(subscriptions) => {
+                            let mut stopped: Vec<_> = pollers.keys().copied().collect();
+                            for subscription in subscriptions {
+                                stopped.retain(|id| *id != subscription.id());
+                                if !pollers.contains_key(&subscription.id()) {
+                                    self.start_poller(&mut pollers, subscription, experimental_feature_kafka_ingress_next);
+                                }
+                            }
+                            for subscription_id in stopped {
+                                stop_poller(&mut pollers, subscription_id);
+                            }
+                        }
+                    }
+                }
+                _ = &mut shutdown => {
+                    break;
+                }
+            }
+        }
+
+        for (_, task_id) in pollers.drain() {
+            if let Some(handle) = TaskCenter::cancel_task(task_id) {
+                let _ = handle.await;
+            }
+        }
+        Ok(())
+    }
+
+    fn start_poller(
+        &self,
+        pollers: &mut HashMap<SubscriptionId, TaskId>,
+        subscription: Subscription,
+        experimental_feature_kafka_ingress_next: bool,
+    ) {
+        let Source::Sqs { region, .. } = subscription.source() else {
+            // Only SQS sources are polled by this service
+            return;
+        };
+        let subscription_id = subscription.id();
+        stop_poller(pollers, subscription_id);
+
+        let queue_url = subscription
+            .source()
+            .sqs_queue_url(subscription.sqs_endpoint_url())
+            .expect("source is an SQS queue");
+        let poller = SqsPoller::new(
+            region.clone(),
+            queue_url,
+            subscription,
+            self.dispatcher.clone(),
+            experimental_feature_kafka_ingress_next,
+        );
+
+        debug!("Spawning the SQS poller for subscription id {subscription_id}");
+        match TaskCenter::spawn_child(TaskKind::SqsIngress, "sqs-poller", run_with_retries(poller))
+        {
+            Ok(task_id) => {
+                pollers.insert(subscription_id, task_id);
+            }
+            Err(_) => {
+                debug!("Not starting the SQS poller of subscription {subscription_id}, the node is shutting down");
+            }
+        }
+    }
+}
+
+fn stop_poller(pollers: &mut HashMap<SubscriptionId, TaskId>, subscription_id: SubscriptionId) {
+    if let Some(task_id) = pollers.remove(&subscription_id) {
+        // Dropping the handle doesn't wait for the task, it's cancelled in the background
+        TaskCenter::cancel_task(task_id);
+    }
+}
+
+/// Runs the poller until its task is cancelled, restarting it after failures. The poller is
+/// restarted indefinitely, as the failures are caused by the queue or the log being unavailable.
+async fn run_with_retries(poller: SqsPoller) -> anyhow::Result<()> {
+    let mut retry_iter = RetryPolicy::exponential(
+        Duration::from_millis(200),
+        2.0,
+        None,
+        Some(Duration::from_secs(10)),
+    )
+    .into_iter();
+
+    loop {
+        match poller.clone().run().await {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                let delay = retry_iter.next().unwrap_or(Duration::from_secs(10));
+                warn!(
+                    restate.subscription.id = %poller.subscription_id(),
+                    "SQS poller failed, restarting in {delay:?}: {err}"
+                );
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancellation_watcher() => return Ok(()),
+                }
+            }
+        }
+    }
+}
@@ -12,6 +12,8 @@ use restate_types::deployment::PinnedDeployment;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::EntryIndex;
 use restate_types::identifiers::InvocationId;
+use restate_types::identifiers::LeaderEpoch;
+use restate_types::invocation::InvocationEpoch;
 use restate_types::journal::enriched::EnrichedRawEntry;
 use std::collections::HashSet;
 
@@ -19,6 +21,15 @@ use std::collections::HashSet;
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Effect {
     pub invocation_id: InvocationId,
+    /// Leader epoch of the partition processor which started the invocation. It fences the
+    /// effect, so that effects of a deposed leader are dropped when applied. Effects written by
+    /// older versions don't carry it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leader_epoch: Option<LeaderEpoch>,
+    /// Epoch of the invocation attempt which produced the effect. Effects of discarded attempts
+    /// are dropped when applied. Effects written by older versions carry the initial epoch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub invocation_epoch: InvocationEpoch,
     pub kind: EffectKind,
 }
 
@@ -38,4 +49,7 @@ pub enum EffectKind {
     End,
     /// This is sent when the invoker exhausted all its attempts to make progress on the specific invocation.
     Failed(InvocationError),
+    /// This is sent when attempts to make progress on the specific invocation failed, and the invoker scheduled retries.
+    /// The retries scheduled in a short interval are coalesced in a single effect.
+    RetryScheduled { retries: u32 },
 }
@@ -76,6 +76,7 @@ pub mod test_util {
                                 invocation_id: InvocationId::mock_random(),
                                 invocation_target: InvocationTarget::service("", ""),
                                 completion_retention_time: None,
+                                shared_concurrency_limit: None,
                                 span_context: current_invocation_span_context.clone(),
                             }),
                         }
@@ -92,6 +93,7 @@ pub mod test_util {
                         invocation_id: InvocationId::mock_random(),
                         invocation_target: InvocationTarget::service("", ""),
                         completion_retention_time: None,
+                        shared_concurrency_limit: None,
                         span_context: current_invocation_span_context.clone(),
                     },
                 },
@@ -14,9 +14,10 @@ use super::JournalMetadata;
 use restate_errors::NotRunningError;
 use restate_types::identifiers::PartitionKey;
 use restate_types::identifiers::{EntryIndex, InvocationId, PartitionLeaderEpoch};
-use restate_types::invocation::InvocationTarget;
+use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::raw::PlainRawEntry;
 use restate_types::journal::Completion;
+use restate_types::time::MillisSinceEpoch;
 use std::future::Future;
 use std::ops::RangeInclusive;
 use tokio::sync::mpsc;
@@ -34,6 +35,8 @@ pub trait InvokerHandle<SR> {
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
+        execution_deadline: Option<MillisSinceEpoch>,
         journal: InvokeInputJournal,
     ) -> impl Future<Output = Result<(), NotRunningError>> + Send;
 
@@ -28,6 +28,8 @@ pub struct JournalMetadata {
     /// The upper bound for the total clock skew is the clock skew of the different machines
     /// and the max time difference between two replicas applying the journal append command.
     pub last_modification_date: MillisSinceEpoch,
+    /// If true, the deployment is asked to suppress external side effects of the invocation.
+    pub dry_run: bool,
 }
 
 impl JournalMetadata {
@@ -36,12 +38,14 @@ impl JournalMetadata {
         span_context: ServiceInvocationSpanContext,
         pinned_deployment: Option<PinnedDeployment>,
         last_modification_date: MillisSinceEpoch,
+        dry_run: bool,
     ) -> Self {
         Self {
             pinned_deployment,
             span_context,
             length,
             last_modification_date,
+            dry_run,
         }
     }
 }
@@ -50,8 +54,10 @@ pub trait JournalReader {
     type JournalStream: Stream<Item = PlainRawEntry>;
     type Error: std::error::Error + Send + Sync + 'static;
 
+    /// Reads the journal metadata and the journal entries starting from `from_index`.
     fn read_journal<'a>(
         &'a mut self,
         fid: &'a InvocationId,
+        from_index: EntryIndex,
     ) -> impl Future<Output = Result<(JournalMetadata, Self::JournalStream), Self::Error>> + Send;
 }
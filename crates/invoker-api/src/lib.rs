@@ -30,7 +30,9 @@ pub mod test_util {
     use restate_types::identifiers::{
         EntryIndex, InvocationId, PartitionKey, PartitionLeaderEpoch, ServiceId,
     };
-    use restate_types::invocation::{InvocationTarget, ServiceInvocationSpanContext};
+    use restate_types::invocation::{
+        InvocationEpoch, InvocationTarget, ServiceInvocationSpanContext,
+    };
     use restate_types::journal::raw::PlainRawEntry;
     use restate_types::journal::Completion;
     use restate_types::time::MillisSinceEpoch;
@@ -50,6 +52,7 @@ pub mod test_util {
         async fn read_journal<'a>(
             &'a mut self,
             _sid: &'a InvocationId,
+            _from_index: EntryIndex,
         ) -> Result<(JournalMetadata, Self::JournalStream), Self::Error> {
             Ok((
                 JournalMetadata::new(
@@ -57,6 +60,7 @@ pub mod test_util {
                     ServiceInvocationSpanContext::empty(),
                     None,
                     MillisSinceEpoch::UNIX_EPOCH,
+                    false,
                 ),
                 futures::stream::empty(),
             ))
@@ -94,6 +98,8 @@ pub mod test_util {
             _partition: PartitionLeaderEpoch,
             _invocation_id: InvocationId,
             _invocation_target: InvocationTarget,
+            _invocation_epoch: InvocationEpoch,
+            _execution_deadline: Option<MillisSinceEpoch>,
             _journal: InvokeInputJournal,
         ) -> Result<(), NotRunningError> {
             Ok(())
@@ -0,0 +1,464 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroU32;
+use std::time::Instant;
+
+use bytestring::ByteString;
+use metrics::{gauge, histogram};
+
+use restate_types::identifiers::{DeploymentId, InvocationId, PartitionLeaderEpoch};
+
+use crate::input_command::InvokeCommand;
+use crate::metric_definitions::{INVOKER_CONCURRENCY_QUEUED, INVOKER_CONCURRENCY_QUEUE_DURATION};
+
+/// Target whose number of concurrently running invocations can be limited.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum ConcurrencyKey {
+    Service(ByteString),
+    Deployment(DeploymentId),
+}
+
+pub(super) type ConcurrencyLimits = Vec<(ConcurrencyKey, NonZeroU32)>;
+
+#[derive(Debug)]
+struct ParkedInvocation {
+    invoke: InvokeCommand,
+    limits: ConcurrencyLimits,
+    parked_at: Instant,
+}
+
+#[derive(Debug)]
+struct KeyState {
+    limit: NonZeroU32,
+    running: u32,
+    // Partitions with parked invocations, in the order in which they get to start the next one
+    partitions: VecDeque<PartitionLeaderEpoch>,
+    parked: HashMap<PartitionLeaderEpoch, VecDeque<ParkedInvocation>>,
+}
+
+impl KeyState {
+    fn new(limit: NonZeroU32) -> Self {
+        Self {
+            limit,
+            running: 0,
+            partitions: VecDeque::new(),
+            parked: HashMap::new(),
+        }
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.running < self.limit.get()
+    }
+
+    fn has_parked(&self) -> bool {
+        !self.partitions.is_empty()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.running == 0 && !self.has_parked()
+    }
+
+    fn push(&mut self, parked: ParkedInvocation) {
+        let partition = parked.invoke.partition;
+        let queue = self.parked.entry(partition).or_default();
+        if queue.is_empty() {
+            self.partitions.push_back(partition);
+        }
+        queue.push_back(parked);
+    }
+
+    /// Pops the oldest parked invocation of the next partition, rotating the partitions so that
+    /// a partition with many parked invocations cannot starve the others.
+    fn pop(&mut self) -> Option<ParkedInvocation> {
+        let partition = self.partitions.pop_front()?;
+        let queue = self
+            .parked
+            .get_mut(&partition)
+            .expect("partitions with parked invocations have a queue");
+        let parked = queue.pop_front().expect("queues are not empty");
+        if queue.is_empty() {
+            self.parked.remove(&partition);
+        } else {
+            self.partitions.push_back(partition);
+        }
+        Some(parked)
+    }
+
+    fn remove(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: Option<&InvocationId>,
+    ) -> usize {
+        let Some(queue) = self.parked.get_mut(&partition) else {
+            return 0;
+        };
+        let len_before = queue.len();
+        queue.retain(|parked| {
+            invocation_id.is_some_and(|invocation_id| parked.invoke.invocation_id != *invocation_id)
+        });
+        let removed = len_before - queue.len();
+        if queue.is_empty() {
+            self.parked.remove(&partition);
+            self.partitions.retain(|p| *p != partition);
+        }
+        removed
+    }
+}
+
+/// Enforces the concurrency limits of services and deployments.
+///
+/// Invocations exceeding a limit are parked in the queue of the limited key. Whenever a running
+/// invocation releases its slots, the parked invocations of the different partitions are started
+/// in a round-robin fashion.
+#[derive(Debug, Default)]
+pub(super) struct ConcurrencyLimiter {
+    keys: HashMap<ConcurrencyKey, KeyState>,
+    acquired: HashMap<(PartitionLeaderEpoch, InvocationId), Vec<ConcurrencyKey>>,
+    parked: usize,
+}
+
+impl ConcurrencyLimiter {
+    /// Acquires a slot of every limited key, if all of them have capacity and no parked
+    /// invocations which would be overtaken.
+    pub(super) fn try_acquire(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+        limits: &ConcurrencyLimits,
+    ) -> bool {
+        if limits.is_empty() {
+            return true;
+        }
+        if self.blocking_key(limits, true).is_some() {
+            return false;
+        }
+        self.acquire(partition, invocation_id, limits);
+        true
+    }
+
+    /// Parks an invocation for which [`Self::try_acquire`] failed.
+    pub(super) fn park(&mut self, invoke: InvokeCommand, limits: ConcurrencyLimits) {
+        let key = self
+            .blocking_key(&limits, true)
+            .expect("invocation is blocked by one of its limits")
+            .clone();
+        self.keys
+            .get_mut(&key)
+            .expect("blocking key exists")
+            .push(ParkedInvocation {
+                invoke,
+                limits,
+                parked_at: Instant::now(),
+            });
+        self.parked += 1;
+        gauge!(INVOKER_CONCURRENCY_QUEUED).set(self.parked as f64);
+    }
+
+    /// Returns a parked invocation which can be started now, after having acquired its slots.
+    pub(super) fn next_runnable(&mut self) -> Option<InvokeCommand> {
+        loop {
+            let key = self
+                .keys
+                .iter()
+                .find(|(_, state)| state.has_parked() && state.has_capacity())
+                .map(|(key, _)| key.clone())?;
+            let state = self.keys.get_mut(&key).expect("key exists");
+            let parked = state.pop().expect("key has parked invocations");
+            if state.is_idle() {
+                self.keys.remove(&key);
+            }
+
+            // The invocation might still be blocked by another of its keys, in which case it
+            // waits in the queue of that key. Those keys have no capacity, hence the loop ends.
+            if let Some(other_key) = self.blocking_key(&parked.limits, false).cloned() {
+                self.keys
+                    .get_mut(&other_key)
+                    .expect("blocking key exists")
+                    .push(parked);
+                continue;
+            }
+
+            self.parked -= 1;
+            gauge!(INVOKER_CONCURRENCY_QUEUED).set(self.parked as f64);
+            histogram!(INVOKER_CONCURRENCY_QUEUE_DURATION).record(parked.parked_at.elapsed());
+
+            let invoke = parked.invoke;
+            self.acquire(invoke.partition, invoke.invocation_id, &parked.limits);
+            return Some(invoke);
+        }
+    }
+
+    pub(super) fn has_parked(&self) -> bool {
+        self.parked > 0
+    }
+
+    /// Releases the slots acquired by the given invocation.
+    pub(super) fn release(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: &InvocationId,
+    ) {
+        let Some(keys) = self.acquired.remove(&(partition, *invocation_id)) else {
+            return;
+        };
+        for key in keys {
+            if let Some(state) = self.keys.get_mut(&key) {
+                state.running -= 1;
+                if state.is_idle() {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Removes the given invocation if it is parked. Returns true if it was parked.
+    pub(super) fn remove_parked(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: &InvocationId,
+    ) -> bool {
+        self.remove(partition, Some(invocation_id)) > 0
+    }
+
+    /// Removes all the parked invocations of the given partition.
+    pub(super) fn remove_partition(&mut self, partition: PartitionLeaderEpoch) {
+        self.remove(partition, None);
+    }
+
+    fn remove(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: Option<&InvocationId>,
+    ) -> usize {
+        let mut removed = 0;
+        self.keys.retain(|_, state| {
+            removed += state.remove(partition, invocation_id);
+            !state.is_idle()
+        });
+        if removed > 0 {
+            self.parked -= removed;
+            gauge!(INVOKER_CONCURRENCY_QUEUED).set(self.parked as f64);
+        }
+        removed
+    }
+
+    fn acquire(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+        limits: &ConcurrencyLimits,
+    ) {
+        for (key, limit) in limits {
+            let state = self
+                .keys
+                .entry(key.clone())
+                .or_insert_with(|| KeyState::new(*limit));
+            state.limit = *limit;
+            state.running += 1;
+        }
+        self.acquired.insert(
+            (partition, invocation_id),
+            limits.iter().map(|(key, _)| key.clone()).collect(),
+        );
+    }
+
+    /// First key preventing the invocation from running. Newly arriving invocations are also
+    /// blocked by keys with parked invocations, so that they don't overtake them.
+    fn blocking_key<'a>(
+        &self,
+        limits: &'a ConcurrencyLimits,
+        respect_parked: bool,
+    ) -> Option<&'a ConcurrencyKey> {
+        limits.iter().find_map(|(key, limit)| {
+            let state = self.keys.get(key)?;
+            let blocked = state.running >= limit.get() || (respect_parked && state.has_parked());
+            blocked.then_some(key)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::identifiers::{LeaderEpoch, PartitionId};
+    use restate_types::invocation::InvocationTarget;
+
+    use restate_invoker_api::InvokeInputJournal;
+
+    fn service_limit(limit: u32) -> ConcurrencyLimits {
+        vec![(
+            ConcurrencyKey::Service(ByteString::from_static("Greeter")),
+            NonZeroU32::new(limit).unwrap(),
+        )]
+    }
+
+    fn invoke(partition: PartitionLeaderEpoch) -> InvokeCommand {
+        InvokeCommand {
+            partition,
+            invocation_id: InvocationId::mock_random(),
+            invocation_target: InvocationTarget::mock_service(),
+            invocation_epoch: 0,
+            execution_deadline: None,
+            journal: InvokeInputJournal::NoCachedJournal,
+        }
+    }
+
+    fn acquire_or_park(
+        limiter: &mut ConcurrencyLimiter,
+        invoke: InvokeCommand,
+        limits: ConcurrencyLimits,
+    ) -> bool {
+        if limiter.try_acquire(invoke.partition, invoke.invocation_id, &limits) {
+            true
+        } else {
+            limiter.park(invoke, limits);
+            false
+        }
+    }
+
+    #[test]
+    fn parks_invocations_exceeding_the_limit() {
+        let mut limiter = ConcurrencyLimiter::default();
+        let partition = (PartitionId::MIN, LeaderEpoch::INITIAL);
+
+        let running = invoke(partition);
+        let running_id = running.invocation_id;
+        let parked = invoke(partition);
+        let parked_id = parked.invocation_id;
+
+        assert!(acquire_or_park(&mut limiter, running, service_limit(1)));
+        assert!(!acquire_or_park(&mut limiter, parked, service_limit(1)));
+        assert!(limiter.next_runnable().is_none());
+
+        limiter.release(partition, &running_id);
+        assert_eq!(limiter.next_runnable().unwrap().invocation_id, parked_id);
+        assert!(!limiter.has_parked());
+
+        limiter.release(partition, &parked_id);
+        assert!(limiter.keys.is_empty());
+    }
+
+    #[test]
+    fn new_invocations_do_not_overtake_parked_ones() {
+        let mut limiter = ConcurrencyLimiter::default();
+        let partition = (PartitionId::MIN, LeaderEpoch::INITIAL);
+
+        let running = invoke(partition);
+        let running_id = running.invocation_id;
+        assert!(acquire_or_park(&mut limiter, running, service_limit(1)));
+        assert!(!acquire_or_park(
+            &mut limiter,
+            invoke(partition),
+            service_limit(1)
+        ));
+
+        limiter.release(partition, &running_id);
+        assert!(!acquire_or_park(
+            &mut limiter,
+            invoke(partition),
+            service_limit(1)
+        ));
+    }
+
+    #[test]
+    fn parked_invocations_start_round_robin_across_partitions() {
+        let mut limiter = ConcurrencyLimiter::default();
+        let partition_1 = (PartitionId::from(1), LeaderEpoch::INITIAL);
+        let partition_2 = (PartitionId::from(2), LeaderEpoch::INITIAL);
+
+        let running = invoke(partition_1);
+        let running_id = running.invocation_id;
+        assert!(acquire_or_park(&mut limiter, running, service_limit(1)));
+        for _ in 0..3 {
+            assert!(!acquire_or_park(
+                &mut limiter,
+                invoke(partition_1),
+                service_limit(1)
+            ));
+        }
+        assert!(!acquire_or_park(
+            &mut limiter,
+            invoke(partition_2),
+            service_limit(1)
+        ));
+
+        let mut started_partitions = vec![];
+        let mut to_release = (partition_1, running_id);
+        while limiter.has_parked() {
+            limiter.release(to_release.0, &to_release.1);
+            let next = limiter.next_runnable().unwrap();
+            started_partitions.push(next.partition);
+            to_release = (next.partition, next.invocation_id);
+        }
+
+        assert_eq!(
+            started_partitions,
+            vec![partition_1, partition_2, partition_1, partition_1]
+        );
+    }
+
+    #[test]
+    fn invocations_wait_for_all_their_limits() {
+        let mut limiter = ConcurrencyLimiter::default();
+        let partition = (PartitionId::MIN, LeaderEpoch::INITIAL);
+        let deployment_limit = (
+            ConcurrencyKey::Deployment(DeploymentId::new()),
+            NonZeroU32::new(1).unwrap(),
+        );
+        let mut both_limits = service_limit(1);
+        both_limits.push(deployment_limit.clone());
+
+        // Hold the only slot of the deployment
+        let deployment_holder = invoke(partition);
+        let deployment_holder_id = deployment_holder.invocation_id;
+        assert!(acquire_or_park(
+            &mut limiter,
+            deployment_holder,
+            vec![deployment_limit]
+        ));
+
+        let parked = invoke(partition);
+        let parked_id = parked.invocation_id;
+        assert!(!acquire_or_park(&mut limiter, parked, both_limits));
+        assert!(limiter.next_runnable().is_none());
+
+        limiter.release(partition, &deployment_holder_id);
+        assert_eq!(limiter.next_runnable().unwrap().invocation_id, parked_id);
+    }
+
+    #[test]
+    fn remove_parked_invocations() {
+        let mut limiter = ConcurrencyLimiter::default();
+        let partition = (PartitionId::MIN, LeaderEpoch::INITIAL);
+
+        let running = invoke(partition);
+        let running_id = running.invocation_id;
+        let parked = invoke(partition);
+        let parked_id = parked.invocation_id;
+        assert!(acquire_or_park(&mut limiter, running, service_limit(1)));
+        assert!(!acquire_or_park(&mut limiter, parked, service_limit(1)));
+        assert!(!acquire_or_park(
+            &mut limiter,
+            invoke(partition),
+            service_limit(1)
+        ));
+
+        assert!(limiter.remove_parked(partition, &parked_id));
+        assert!(!limiter.remove_parked(partition, &parked_id));
+        limiter.remove_partition(partition);
+        assert!(!limiter.has_parked());
+
+        limiter.release(partition, &running_id);
+        assert!(limiter.keys.is_empty());
+    }
+}
@@ -11,8 +11,9 @@
 use restate_errors::NotRunningError;
 use restate_invoker_api::{Effect, InvocationStatusReport, InvokeInputJournal, StatusHandle};
 use restate_types::identifiers::{EntryIndex, InvocationId, PartitionKey, PartitionLeaderEpoch};
-use restate_types::invocation::InvocationTarget;
+use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::Completion;
+use restate_types::time::MillisSinceEpoch;
 use std::ops::RangeInclusive;
 use tokio::sync::mpsc;
 
@@ -23,6 +24,10 @@ pub(crate) struct InvokeCommand {
     pub(super) partition: PartitionLeaderEpoch,
     pub(super) invocation_id: InvocationId,
     pub(super) invocation_target: InvocationTarget,
+    #[serde(default)]
+    pub(super) invocation_epoch: InvocationEpoch,
+    #[serde(default)]
+    pub(super) execution_deadline: Option<MillisSinceEpoch>,
     #[serde(skip)]
     pub(super) journal: InvokeInputJournal,
 }
@@ -74,6 +79,8 @@ impl<SR: Send> restate_invoker_api::InvokerHandle<SR> for InvokerHandle<SR> {
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
+        execution_deadline: Option<MillisSinceEpoch>,
         journal: InvokeInputJournal,
     ) -> Result<(), NotRunningError> {
         self.input
@@ -81,6 +88,8 @@ impl<SR: Send> restate_invoker_api::InvokerHandle<SR> for InvokerHandle<SR> {
                 partition,
                 invocation_id,
                 invocation_target,
+                invocation_epoch,
+                execution_deadline,
                 journal,
             }))
             .map_err(|_| NotRunningError)
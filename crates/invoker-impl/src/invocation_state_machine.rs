@@ -10,10 +10,14 @@
 
 use super::*;
 
+use crate::invocation_task::ErrorClass;
+use restate_types::invocation::InvocationEpoch;
 use restate_types::journal::Completion;
 use restate_types::retries;
+use restate_types::schema::service::RetryPolicyOverrides;
+use restate_types::time::MillisSinceEpoch;
 use std::fmt;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
 
@@ -21,13 +25,26 @@ use tokio::task::AbortHandle;
 #[derive(Debug)]
 pub(super) struct InvocationStateMachine {
     pub(super) invocation_target: InvocationTarget,
+    /// Epoch of the invocation attempt, with which the effects of this attempt are stamped.
+    pub(super) invocation_epoch: InvocationEpoch,
     invocation_state: InvocationState,
-    retry_iter: retries::RetryIter<'static>,
+    retry_iters: RetryIters,
     /// This retry count is passed in the StartMessage.
     /// For more details of when we bump it, see [`InvocationTaskError::should_bump_start_message_retry_count_since_last_stored_entry`].
     pub(super) start_message_retry_count_since_last_stored_entry: u32,
+    /// Once reached, the invocation is aborted regardless of its state, see [`InvocationTaskError::ExecutionTimeout`].
+    /// Stored in the invocation status, hence it's the same for all the attempts.
+    execution_deadline: Option<MillisSinceEpoch>,
+    /// Retries scheduled since the last time they were reported to the partition processor.
+    unreported_retries: u32,
+    last_retries_report: Option<Instant>,
 }
 
+/// Minimum interval between two reports of the scheduled retries of the same invocation.
+/// Retries scheduled in between are coalesced in the next report, so that hot retry loops
+/// don't cost a log append each.
+const RETRIES_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
 /// This struct tracks which entries the invocation task generates,
 /// and which ones have been already stored and acked by the partition processor.
 /// This information is used to decide when it's safe to retry.
@@ -74,6 +91,49 @@ impl JournalTracker {
     }
 }
 
+/// Retry iterators of the different [`ErrorClass`]es. Classes without an override share the
+/// iterator of the invoker retry policy.
+#[derive(Debug)]
+struct RetryIters {
+    default: retries::RetryIter<'static>,
+    transport_errors: Option<retries::RetryIter<'static>>,
+    retryable_errors: Option<retries::RetryIter<'static>>,
+    terminal_errors: Option<retries::RetryIter<'static>>,
+    max_duration: Option<Duration>,
+    first_failure_at: Option<Instant>,
+}
+
+impl RetryIters {
+    fn new(retry_policy: RetryPolicy, overrides: Option<RetryPolicyOverrides>) -> Self {
+        let overrides = overrides.unwrap_or_default();
+        Self {
+            default: retry_policy.into_iter(),
+            transport_errors: overrides.transport_errors.map(IntoIterator::into_iter),
+            retryable_errors: overrides.retryable_errors.map(IntoIterator::into_iter),
+            terminal_errors: overrides.terminal_errors.map(IntoIterator::into_iter),
+            max_duration: overrides.max_duration.map(Into::into),
+            first_failure_at: None,
+        }
+    }
+
+    /// Returns true if the invocation has been retried for longer than the max duration.
+    fn notify_failure(&mut self) -> bool {
+        let first_failure_at = *self.first_failure_at.get_or_insert_with(Instant::now);
+        self.max_duration
+            .is_some_and(|max_duration| first_failure_at.elapsed() >= max_duration)
+    }
+
+    fn next(&mut self, error_class: ErrorClass) -> Option<Duration> {
+        match error_class {
+            ErrorClass::Transport => self.transport_errors.as_mut(),
+            ErrorClass::Retryable => self.retryable_errors.as_mut(),
+            ErrorClass::Terminal => self.terminal_errors.as_mut(),
+        }
+        .unwrap_or(&mut self.default)
+        .next()
+    }
+}
+
 enum InvocationState {
     New,
 
@@ -132,16 +192,48 @@ impl fmt::Debug for InvocationState {
 impl InvocationStateMachine {
     pub(super) fn create(
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
         retry_policy: RetryPolicy,
+        retry_policy_overrides: Option<RetryPolicyOverrides>,
+        execution_deadline: Option<MillisSinceEpoch>,
     ) -> InvocationStateMachine {
         Self {
             invocation_target,
+            invocation_epoch,
             invocation_state: InvocationState::New,
-            retry_iter: retry_policy.into_iter(),
+            retry_iters: RetryIters::new(retry_policy, retry_policy_overrides),
             start_message_retry_count_since_last_stored_entry: 0,
+            execution_deadline,
+            unreported_retries: 0,
+            last_retries_report: None,
         }
     }
 
+    /// Notes that a retry was scheduled, returning the number of retries to report to the
+    /// partition processor, if it's time to report them.
+    pub(super) fn notify_retry_scheduled(&mut self) -> Option<u32> {
+        self.unreported_retries += 1;
+        if self
+            .last_retries_report
+            .is_some_and(|last_report| last_report.elapsed() < RETRIES_REPORT_INTERVAL)
+        {
+            return None;
+        }
+        self.last_retries_report = Some(Instant::now());
+        Some(std::mem::take(&mut self.unreported_retries))
+    }
+
+    /// Retries scheduled but not yet reported, to report before the invocation leaves the invoker.
+    pub(super) fn unreported_retries(&self) -> u32 {
+        self.unreported_retries
+    }
+
+    /// Returns true if the invocation has been executing for longer than its execution timeout.
+    pub(super) fn is_execution_timed_out(&self) -> bool {
+        self.execution_deadline
+            .is_some_and(|deadline| deadline <= MillisSinceEpoch::now())
+    }
+
     pub(super) fn start(
         &mut self,
         abort_handle: AbortHandle,
@@ -279,6 +371,7 @@ impl InvocationStateMachine {
     /// Returns Some() with the timer for the next retry, otherwise None if retry limit exhausted
     pub(super) fn handle_task_error(
         &mut self,
+        error_class: ErrorClass,
         next_retry_interval_override: Option<Duration>,
         should_bump_start_message_retry_count_since_last_stored_entry: bool,
     ) -> Option<Duration> {
@@ -300,7 +393,11 @@ impl InvocationStateMachine {
             }
         };
 
-        let next_timer = next_retry_interval_override.or_else(|| self.retry_iter.next());
+        if self.retry_iters.notify_failure() {
+            return None;
+        }
+        let next_timer =
+            next_retry_interval_override.or_else(|| self.retry_iters.next(error_class));
         if next_timer.is_some() {
             if should_bump_start_message_retry_count_since_last_stored_entry {
                 self.start_message_retry_count_since_last_stored_entry += 1;
@@ -349,11 +446,14 @@ mod tests {
     fn handle_error_when_waiting_for_retry() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
+            0,
             RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            None,
         );
 
         assert!(invocation_state_machine
-            .handle_task_error(None, true)
+            .handle_task_error(ErrorClass::Retryable, None, true)
             .is_some());
         check!(let InvocationState::WaitingRetry { .. } = invocation_state_machine.invocation_state);
 
@@ -361,16 +461,44 @@ mod tests {
 
         // We stay in `WaitingForRetry`
         assert!(invocation_state_machine
-            .handle_task_error(None, true)
+            .handle_task_error(ErrorClass::Retryable, None, true)
             .is_some());
         check!(let InvocationState::WaitingRetry { .. } = invocation_state_machine.invocation_state);
     }
 
+    #[test]
+    fn coalesce_scheduled_retries() {
+        let mut invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            None,
+        );
+
+        // The first retry is reported right away
+        assert_eq!(invocation_state_machine.notify_retry_scheduled(), Some(1));
+        assert_eq!(invocation_state_machine.unreported_retries(), 0);
+
+        // The following ones are coalesced until the report interval elapses
+        assert_eq!(invocation_state_machine.notify_retry_scheduled(), None);
+        assert_eq!(invocation_state_machine.notify_retry_scheduled(), None);
+        assert_eq!(invocation_state_machine.unreported_retries(), 2);
+
+        invocation_state_machine.last_retries_report =
+            Some(Instant::now() - RETRIES_REPORT_INTERVAL);
+        assert_eq!(invocation_state_machine.notify_retry_scheduled(), Some(3));
+        assert_eq!(invocation_state_machine.unreported_retries(), 0);
+    }
+
     #[test(tokio::test)]
     async fn handle_error_counts_attempts_on_same_entry() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
+            0,
             RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            None,
         );
 
         // Start invocation
@@ -381,7 +509,7 @@ mod tests {
 
         // Notify error
         assert!(invocation_state_machine
-            .handle_task_error(None, true)
+            .handle_task_error(ErrorClass::Retryable, None, true)
             .is_some());
         assert_eq!(
             invocation_state_machine.start_message_retry_count_since_last_stored_entry,
@@ -396,7 +524,7 @@ mod tests {
 
         // Get error again
         assert!(invocation_state_machine
-            .handle_task_error(None, true)
+            .handle_task_error(ErrorClass::Retryable, None, true)
             .is_some());
         assert_eq!(
             invocation_state_machine.start_message_retry_count_since_last_stored_entry,
@@ -421,11 +549,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_error_uses_retry_policy_of_error_class() {
+        let mut invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            Some(RetryPolicyOverrides {
+                transport_errors: Some(RetryPolicy::fixed_delay(Duration::from_secs(5), Some(2))),
+                terminal_errors: Some(RetryPolicy::None),
+                ..Default::default()
+            }),
+            None,
+        );
+
+        assert_eq!(
+            invocation_state_machine.handle_task_error(ErrorClass::Transport, None, true),
+            Some(Duration::from_secs(5))
+        );
+        invocation_state_machine.notify_retry_timer_fired();
+        assert_eq!(
+            invocation_state_machine.handle_task_error(ErrorClass::Retryable, None, true),
+            Some(Duration::from_secs(1))
+        );
+        invocation_state_machine.notify_retry_timer_fired();
+
+        // The transport errors policy allows a single retry
+        assert_eq!(
+            invocation_state_machine.handle_task_error(ErrorClass::Transport, None, true),
+            None
+        );
+        assert_eq!(
+            invocation_state_machine.handle_task_error(ErrorClass::Terminal, None, true),
+            None
+        );
+    }
+
+    #[test]
+    fn handle_error_gives_up_after_max_duration() {
+        let mut invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), None),
+            Some(RetryPolicyOverrides {
+                max_duration: Some(Duration::ZERO.into()),
+                ..Default::default()
+            }),
+            None,
+        );
+
+        assert_eq!(
+            invocation_state_machine.handle_task_error(
+                ErrorClass::Retryable,
+                Some(Duration::from_secs(1)),
+                true
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn execution_timeout() {
+        let invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            Some(MillisSinceEpoch::now()),
+        );
+        assert!(invocation_state_machine.is_execution_timed_out());
+
+        let invocation_state_machine = InvocationStateMachine::create(
+            InvocationTarget::mock_virtual_object(),
+            0,
+            RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            Some(MillisSinceEpoch::now() + Duration::from_secs(60 * 60)),
+        );
+        assert!(!invocation_state_machine.is_execution_timed_out());
+    }
+
     #[test(tokio::test)]
     async fn handle_requires_ack() {
         let mut invocation_state_machine = InvocationStateMachine::create(
             InvocationTarget::mock_virtual_object(),
+            0,
             RetryPolicy::fixed_delay(Duration::from_secs(1), Some(10)),
+            None,
+            None,
         );
 
         let abort_handle = tokio::spawn(async {}).abort_handle();
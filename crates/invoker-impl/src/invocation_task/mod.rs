@@ -13,6 +13,7 @@ mod service_protocol_runner;
 use super::Notification;
 
 use crate::invocation_task::service_protocol_runner::ServiceProtocolRunner;
+use crate::journal_cache::JournalReplayCache;
 use crate::metric_definitions::INVOKER_TASK_DURATION;
 use bytes::Bytes;
 use futures::{future, stream, FutureExt};
@@ -27,16 +28,18 @@ use restate_invoker_api::{
 use restate_service_client::{Request, ResponseBody, ServiceClient, ServiceClientError};
 use restate_service_protocol::message::{EncodingError, MessageType};
 use restate_types::deployment::PinnedDeployment;
-use restate_types::errors::InvocationError;
+use restate_types::errors::{codes, InvocationError};
 use restate_types::identifiers::{DeploymentId, EntryIndex, InvocationId, PartitionLeaderEpoch};
-use restate_types::invocation::InvocationTarget;
+use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::enriched::EnrichedRawEntry;
 use restate_types::journal::EntryType;
 use restate_types::live::Live;
 use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
 use restate_types::service_protocol::ServiceProtocolVersion;
-use restate_types::service_protocol::{MAX_SERVICE_PROTOCOL_VERSION, MIN_SERVICE_PROTOCOL_VERSION};
+use restate_types::service_protocol::{
+    ProtocolExtension, MAX_SERVICE_PROTOCOL_VERSION, MIN_SERVICE_PROTOCOL_VERSION,
+};
 use std::collections::HashSet;
 use std::convert::Infallible;
 use std::future::Future;
@@ -157,6 +160,29 @@ pub(crate) enum InvocationTaskError {
     #[error("service is temporary unavailable '{0}'")]
     #[code(restate_errors::RT0010)]
     ServiceUnavailable(http::StatusCode),
+
+    #[error("cannot send message '{0:?}' to the service because it hits the request message size limit: {1} >= {2}")]
+    #[code(restate_errors::RT0016)]
+    RequestMessageSizeLimit(MessageType, usize, usize),
+
+    #[error("the invocation exceeded its execution timeout")]
+    #[code(restate_errors::RT0017)]
+    ExecutionTimeout,
+
+    #[error("cannot run the dry-run invocation because the deployment '{0}' didn't accept the dry-run protocol extension when it was discovered")]
+    #[code(restate_errors::RT0018)]
+    DryRunNotSupported(DeploymentId),
+}
+
+/// Class of an [`InvocationTaskError`], determining which retry policy applies to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorClass {
+    /// Errors occurring while talking to the deployment.
+    Transport,
+    /// Errors the SDK declares as not recoverable by retrying the same code.
+    Terminal,
+    /// All other errors.
+    Retryable,
 }
 
 #[derive(Debug, Default)]
@@ -168,7 +194,15 @@ pub struct InvocationErrorRelatedEntry {
 
 impl InvocationTaskError {
     pub(crate) fn is_transient(&self) -> bool {
-        true
+        // Retrying cannot make oversized messages any smaller, nor give back the execution time,
+        // nor make the deployment honor the dry-run header
+        !matches!(
+            self,
+            InvocationTaskError::Encoding(EncodingError::MessageSizeLimit(_, _))
+                | InvocationTaskError::RequestMessageSizeLimit(_, _, _)
+                | InvocationTaskError::ExecutionTimeout
+                | InvocationTaskError::DryRunNotSupported(_)
+        )
     }
 
     pub(crate) fn should_bump_start_message_retry_count_since_last_stored_entry(&self) -> bool {
@@ -181,9 +215,29 @@ impl InvocationTaskError {
                 | InvocationTaskError::UnknownDeployment(_)
                 | InvocationTaskError::ResumeWithWrongServiceProtocolVersion(_)
                 | InvocationTaskError::IncompatibleServiceEndpoint(_, _)
+                | InvocationTaskError::DryRunNotSupported(_)
         )
     }
 
+    pub(crate) fn error_class(&self) -> ErrorClass {
+        match self {
+            InvocationTaskError::Client(_)
+            | InvocationTaskError::ClientBody(_)
+            | InvocationTaskError::UnexpectedJoinError(_)
+            | InvocationTaskError::UnexpectedClosedRequestStream
+            | InvocationTaskError::ResponseTimeout
+            | InvocationTaskError::ServiceUnavailable(_)
+            | InvocationTaskError::UnexpectedResponse(_) => ErrorClass::Transport,
+            InvocationTaskError::ErrorMessageReceived { error, .. }
+                if error.code() == codes::JOURNAL_MISMATCH
+                    || error.code() == codes::PROTOCOL_VIOLATION =>
+            {
+                ErrorClass::Terminal
+            }
+            _ => ErrorClass::Retryable,
+        }
+    }
+
     pub(crate) fn next_retry_interval_override(&self) -> Option<Duration> {
         match self {
             InvocationTaskError::ErrorMessageReceived {
@@ -210,6 +264,12 @@ impl InvocationTaskError {
                 }
                 err
             }
+            e @ InvocationTaskError::ExecutionTimeout => {
+                InvocationError::new(codes::TIMEOUT, e.to_string())
+            }
+            e @ InvocationTaskError::DryRunNotSupported(_) => {
+                InvocationError::new(codes::BAD_REQUEST, e.to_string())
+            }
             e => InvocationError::internal(e),
         }
     }
@@ -281,11 +341,14 @@ pub(super) struct InvocationTask<SR, JR, EE, DMR> {
     partition: PartitionLeaderEpoch,
     invocation_id: InvocationId,
     invocation_target: InvocationTarget,
+    invocation_epoch: InvocationEpoch,
     inactivity_timeout: Duration,
     abort_timeout: Duration,
     disable_eager_state: bool,
     message_size_warning: usize,
     message_size_limit: Option<usize>,
+    request_message_size_limit: Option<usize>,
+    message_chunk_size: usize,
     retry_count_since_last_stored_entry: u32,
 
     // Invoker tx/rx
@@ -293,6 +356,7 @@ pub(super) struct InvocationTask<SR, JR, EE, DMR> {
     journal_reader: JR,
     entry_enricher: EE,
     deployment_metadata_resolver: Live<DMR>,
+    journal_replay_cache: JournalReplayCache,
     invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
     invoker_rx: mpsc::UnboundedReceiver<Notification>,
 }
@@ -342,16 +406,20 @@ where
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
         default_inactivity_timeout: Duration,
         default_abort_timeout: Duration,
         disable_eager_state: bool,
         message_size_warning: usize,
         message_size_limit: Option<usize>,
+        request_message_size_limit: Option<usize>,
+        message_chunk_size: usize,
         retry_count_since_last_stored_entry: u32,
         state_reader: SR,
         journal_reader: JR,
         entry_enricher: EE,
         deployment_metadata_resolver: Live<Schemas>,
+        journal_replay_cache: JournalReplayCache,
         invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
         invoker_rx: mpsc::UnboundedReceiver<Notification>,
     ) -> Self {
@@ -360,6 +428,7 @@ where
             partition,
             invocation_id,
             invocation_target,
+            invocation_epoch,
             inactivity_timeout: default_inactivity_timeout,
             abort_timeout: default_abort_timeout,
             disable_eager_state,
@@ -367,9 +436,12 @@ where
             journal_reader,
             entry_enricher,
             deployment_metadata_resolver,
+            journal_replay_cache,
             invoker_tx,
             invoker_rx,
             message_size_limit,
+            request_message_size_limit,
+            message_chunk_size,
             message_size_warning,
             retry_count_since_last_stored_entry,
         }
@@ -393,6 +465,17 @@ where
         // Execute the task
         let terminal_state = self.select_protocol_version_and_run(input_journal).await;
 
+        // The journal of an invocation which won't be resumed anymore doesn't need to stay cached
+        match &terminal_state {
+            TerminalLoopState::Closed => self
+                .journal_replay_cache
+                .invalidate(self.invocation_id, self.invocation_epoch),
+            TerminalLoopState::Failed(e) if !e.is_transient() => self
+                .journal_replay_cache
+                .invalidate(self.invocation_id, self.invocation_epoch),
+            _ => {}
+        }
+
         // Sanity check of the final state
         let inner = match terminal_state {
             TerminalLoopState::Continue(_) => {
@@ -415,15 +498,36 @@ where
         let read_journal_future = async {
             Ok(match input_journal {
                 InvokeInputJournal::NoCachedJournal => {
-                    let (journal_meta, journal_stream) = self
+                    // Only read the entries following the already encoded journal prefix
+                    let mut journal_prefix = self
+                        .journal_replay_cache
+                        .get(self.invocation_id, self.invocation_epoch);
+                    let from_index = journal_prefix.as_ref().map_or(0, |prefix| prefix.len());
+                    let (mut journal_meta, mut journal_stream) = self
                         .journal_reader
-                        .read_journal(&self.invocation_id)
+                        .read_journal(&self.invocation_id, from_index)
                         .await
                         .map_err(|e| InvocationTaskError::JournalReader(e.into()))?;
-                    (journal_meta, future::Either::Left(journal_stream))
+                    if journal_meta.length < from_index {
+                        // The cached prefix doesn't belong to this journal, read it in full
+                        self.journal_replay_cache
+                            .invalidate(self.invocation_id, self.invocation_epoch);
+                        journal_prefix = None;
+                        (journal_meta, journal_stream) = self
+                            .journal_reader
+                            .read_journal(&self.invocation_id, 0)
+                            .await
+                            .map_err(|e| InvocationTaskError::JournalReader(e.into()))?;
+                    }
+                    (
+                        journal_meta,
+                        journal_prefix,
+                        future::Either::Left(journal_stream),
+                    )
                 }
                 InvokeInputJournal::CachedJournal(journal_meta, journal_items) => (
                     journal_meta,
+                    None,
                     future::Either::Right(stream::iter(journal_items)),
                 ),
             })
@@ -443,7 +547,7 @@ where
         };
 
         // We execute those concurrently
-        let ((journal_metadata, journal_stream), state_iter) =
+        let ((journal_metadata, journal_prefix, journal_stream), state_iter) =
             shortcircuit!(tokio::try_join!(read_journal_future, read_state_future));
 
         // Resolve the deployment metadata
@@ -475,9 +579,13 @@ where
                 )
             } else {
                 // We can choose the freshest deployment for the latest revision
-                // of the registered service.
+                // of the registered service, unless the routing rules of the service
+                // send this invocation to a canary deployment.
                 let deployment = shortcircuit!(schemas
-                    .resolve_latest_deployment_for_service(self.invocation_target.service_name())
+                    .resolve_deployment_for_invocation(
+                        self.invocation_target.service_name(),
+                        &self.invocation_id
+                    )
                     .ok_or(InvocationTaskError::NoDeploymentForService));
 
                 let chosen_service_protocol_version =
@@ -500,28 +608,58 @@ where
         if let Some(service_metadata) =
             schemas.resolve_latest_service(self.invocation_target.service_name())
         {
-            // Override the inactivity timeout and abort timeout, if available
-            if let Some(inactivity_timeout) = service_metadata.inactivity_timeout {
+            // Override the inactivity timeout and abort timeout, if available.
+            // The timeouts declared on the handler take precedence over the service ones.
+            let handler_metadata = service_metadata
+                .handlers
+                .iter()
+                .find(|h| *self.invocation_target.handler_name() == h.name);
+            if let Some(inactivity_timeout) = handler_metadata
+                .and_then(|h| h.inactivity_timeout)
+                .or(service_metadata.inactivity_timeout)
+            {
                 self.inactivity_timeout = inactivity_timeout.into();
             }
-            if let Some(abort_timeout) = service_metadata.abort_timeout {
+            if let Some(abort_timeout) = handler_metadata
+                .and_then(|h| h.abort_timeout)
+                .or(service_metadata.abort_timeout)
+            {
                 self.abort_timeout = abort_timeout.into();
             }
         } else {
             warn!("Unexpected service not found, after resolving correctly the deployment.");
         }
 
+        // SDKs which didn't accept the extension ignore the dry-run header, and would execute
+        // the side effects
+        if journal_metadata.dry_run
+            && !deployment
+                .metadata
+                .supports_protocol_extension(ProtocolExtension::DryRun)
+        {
+            shortcircuit!(Err(InvocationTaskError::DryRunNotSupported(deployment.id)));
+        }
+
         self.send_invoker_tx(InvocationTaskOutputInner::PinnedDeployment(
             PinnedDeployment::new(deployment.id, chosen_service_protocol_version),
             deployment_changed,
         ));
 
         // create a correctly versioned service protocol runner
+        let chunked_messages = deployment
+            .metadata
+            .supports_protocol_extension(ProtocolExtension::ChunkedMessages);
         let service_protocol_runner =
-            ServiceProtocolRunner::new(self, chosen_service_protocol_version);
+            ServiceProtocolRunner::new(self, chosen_service_protocol_version, chunked_messages);
 
         service_protocol_runner
-            .run(journal_metadata, deployment, journal_stream, state_iter)
+            .run(
+                journal_metadata,
+                deployment,
+                journal_prefix,
+                journal_stream,
+                state_iter,
+            )
             .await
     }
 }
@@ -14,9 +14,11 @@ use crate::invocation_task::{
     InvokerBodyStream, InvokerRequestStreamSender, ResponseChunk, ResponseStreamState,
     TerminalLoopState, X_RESTATE_SERVER,
 };
+use crate::journal_cache::EncodedJournalPrefix;
 use crate::Notification;
 use bytes::Bytes;
 use futures::future::FusedFuture;
+use futures::stream::FusedStream;
 use futures::{FutureExt, Stream, StreamExt};
 use http::uri::PathAndQuery;
 use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
@@ -24,9 +26,9 @@ use http_body::Frame;
 use opentelemetry::trace::TraceFlags;
 use restate_errors::warn_it;
 use restate_invoker_api::{EagerState, EntryEnricher, JournalMetadata};
-use restate_service_client::{Endpoint, Method, Parts, Request};
+use restate_service_client::{Endpoint, LambdaInvokeOptions, Method, Parts, Request};
 use restate_service_protocol::message::{
-    Decoder, Encoder, MessageHeader, MessageType, ProtocolMessage,
+    Decoder, EncodedMessage, Encoder, MessageHeader, MessageType, ProtocolMessage,
 };
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::{EntryIndex, InvocationId};
@@ -36,9 +38,10 @@ use restate_types::journal::EntryType;
 use restate_types::schema::deployment::{
     Deployment, DeploymentMetadata, DeploymentType, ProtocolType,
 };
-use restate_types::service_protocol::ServiceProtocolVersion;
-use std::collections::HashSet;
+use restate_types::service_protocol::{ServiceProtocolVersion, DRY_RUN_HEADER};
+use std::collections::{HashSet, VecDeque};
 use std::future::poll_fn;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
@@ -47,12 +50,18 @@ use tracing::{debug, info, trace, warn};
 ///  Provides the value of the invocation id
 const INVOCATION_ID_HEADER_NAME: HeaderName = HeaderName::from_static("x-restate-invocation-id");
 
+/// Marks the invocation as a dry-run, see [`restate_types::service_protocol::ProtocolExtension`]
+const DRY_RUN_HEADER_NAME: HeaderName = HeaderName::from_static(DRY_RUN_HEADER);
+
 const GATEWAY_ERRORS_CODES: [http::StatusCode; 3] = [
     http::StatusCode::BAD_GATEWAY,
     http::StatusCode::SERVICE_UNAVAILABLE,
     http::StatusCode::GATEWAY_TIMEOUT,
 ];
 
+/// Number of journal entries read ahead while replaying, when the request stream is busy
+const JOURNAL_PREFETCH_BUFFER_SIZE: usize = 32;
+
 /// Runs the interaction between the server and the service endpoint.
 pub struct ServiceProtocolRunner<'a, SR, JR, EE, DMR> {
     invocation_task: &'a mut InvocationTask<SR, JR, EE, DMR>,
@@ -74,13 +83,20 @@ where
     pub fn new(
         invocation_task: &'a mut InvocationTask<SR, JR, EE, DMR>,
         service_protocol_version: ServiceProtocolVersion,
+        chunked_messages: bool,
     ) -> Self {
-        let encoder = Encoder::new(service_protocol_version);
-        let decoder = Decoder::new(
+        let mut encoder = Encoder::new(service_protocol_version);
+        let mut decoder = Decoder::new(
             service_protocol_version,
             invocation_task.message_size_warning,
             invocation_task.message_size_limit,
         );
+        // Chunks are not part of the service protocol, use them only with deployments which
+        // accepted the protocol extension
+        if chunked_messages {
+            encoder = encoder.with_chunk_size(invocation_task.message_chunk_size);
+            decoder = decoder.with_chunks();
+        }
 
         Self {
             invocation_task,
@@ -95,6 +111,7 @@ where
         mut self,
         journal_metadata: JournalMetadata,
         deployment: Deployment,
+        journal_prefix: Option<Arc<EncodedJournalPrefix>>,
         journal_stream: JournalStream,
         state_iter: EagerState<StateIter>,
     ) -> TerminalLoopState<()>
@@ -143,28 +160,43 @@ where
             self.service_protocol_version,
             &self.invocation_task.invocation_id,
             &service_invocation_span_context,
+            journal_metadata.dry_run,
         );
 
+        // Initialize the response stream state first, so the connection to the deployment
+        // is established while we encode the start message and the replayed journal
+        let mut http_stream_rx =
+            ResponseStreamState::initialize(&self.invocation_task.client, request);
+
         crate::shortcircuit!(
             self.write_start(
                 &mut http_stream_tx,
                 journal_size,
                 state_iter,
                 self.invocation_task.retry_count_since_last_stored_entry,
-                journal_metadata.last_modification_date.elapsed()
+                journal_metadata.last_modification_date.elapsed(),
             )
             .await
         );
 
-        // Initialize the response stream state
-        let mut http_stream_rx =
-            ResponseStreamState::initialize(&self.invocation_task.client, request);
-
         // Execute the replay
-        crate::shortcircuit!(
-            self.replay_loop(&mut http_stream_tx, &mut http_stream_rx, journal_stream)
-                .await
+        let cached_prefix_len = journal_prefix.as_ref().map_or(0, |prefix| prefix.len());
+        let journal_prefix = crate::shortcircuit!(
+            self.replay_loop(
+                &mut http_stream_tx,
+                &mut http_stream_rx,
+                journal_prefix,
+                journal_stream
+            )
+            .await
         );
+        if journal_prefix.len() > cached_prefix_len {
+            self.invocation_task.journal_replay_cache.insert(
+                self.invocation_task.invocation_id,
+                self.invocation_task.invocation_epoch,
+                journal_prefix,
+            );
+        }
 
         // Check all the entries have been replayed
         debug_assert_eq!(self.next_journal_index, journal_size);
@@ -211,6 +243,7 @@ where
         service_protocol_version: ServiceProtocolVersion,
         invocation_id: &InvocationId,
         parent_span_context: &ServiceInvocationSpanContext,
+        dry_run: bool,
     ) -> (InvokerRequestStreamSender, Request<InvokerBodyStream>) {
         // Just an arbitrary buffering size
         let (http_stream_tx, http_stream_rx) = mpsc::channel(10);
@@ -259,7 +292,14 @@ where
             DeploymentType::Lambda {
                 arn,
                 assume_role_arn,
-            } => Endpoint::Lambda(arn, assume_role_arn),
+                region,
+            } => Endpoint::Lambda(
+                arn,
+                LambdaInvokeOptions {
+                    assume_role_arn,
+                    region,
+                },
+            ),
             DeploymentType::Http {
                 address,
                 http_version,
@@ -268,6 +308,11 @@ where
         };
 
         headers.extend(deployment_metadata.delivery_options.additional_headers);
+        // Set after the additional headers, which must not override it. The invocation task
+        // checked the deployment accepted the dry-run protocol extension.
+        if dry_run {
+            headers.insert(DRY_RUN_HEADER_NAME, HeaderValue::from_static("true"));
+        }
 
         (
             http_stream_tx,
@@ -278,20 +323,46 @@ where
     // --- Loops
 
     /// This loop concurrently pushes journal entries and waits for the response headers and end of replay.
+    ///
+    /// Journal entries are prefetched into a bounded buffer while the request stream is not
+    /// yet able to accept more data (e.g. because the connection is still being established),
+    /// so that replaying long journals is not bound to a sequential read-then-send.
+    ///
+    /// The already encoded journal prefix, if any, is sent before the entries of the journal
+    /// stream, which contains only the entries following the prefix. Returns the encoded journal
+    /// prefix extended with the replayed entries.
     async fn replay_loop<JournalStream>(
         &mut self,
         http_stream_tx: &mut InvokerRequestStreamSender,
         http_stream_rx: &mut ResponseStreamState,
+        journal_prefix: Option<Arc<EncodedJournalPrefix>>,
         journal_stream: JournalStream,
-    ) -> TerminalLoopState<()>
+    ) -> TerminalLoopState<EncodedJournalPrefix>
     where
         JournalStream: Stream<Item = PlainRawEntry> + Unpin,
     {
+        let mut cached_frames = journal_prefix
+            .as_deref()
+            .map(EncodedJournalPrefix::frames)
+            .unwrap_or_default()
+            .iter()
+            .peekable();
+        let mut new_journal_prefix = journal_prefix.as_deref().cloned().unwrap_or_default();
         let mut journal_stream = journal_stream.fuse();
+        let mut prefetched_entries = VecDeque::with_capacity(JOURNAL_PREFETCH_BUFFER_SIZE);
         let got_headers_future = poll_fn(|cx| http_stream_rx.poll_only_headers(cx)).fuse();
         tokio::pin!(got_headers_future);
 
         loop {
+            if cached_frames.peek().is_none()
+                && journal_stream.is_terminated()
+                && prefetched_entries.is_empty()
+            {
+                // No need to wait for the headers to continue
+                trace!("Finished to replay the journal");
+                return TerminalLoopState::Continue(new_journal_prefix);
+            }
+
             tokio::select! {
                 got_headers_res = got_headers_future.as_mut(), if !got_headers_future.is_terminated() => {
                     // The reason we want to poll headers in this function is
@@ -299,18 +370,34 @@ where
                     let headers = crate::shortcircuit!(got_headers_res);
                     crate::shortcircuit!(self.handle_response_headers(headers));
                 },
-                opt_je = journal_stream.next() => {
-                    match opt_je {
-                        Some(je) => {
-                            crate::shortcircuit!(self.write(http_stream_tx, ProtocolMessage::UnparsedEntry(je)).await);
-                            self.next_journal_index += 1;
-                        },
-                        None => {
-                            // No need to wait for the headers to continue
-                            trace!("Finished to replay the journal");
-                            return TerminalLoopState::Continue(())
+                opt_je = journal_stream.next(), if !journal_stream.is_terminated() && prefetched_entries.len() < JOURNAL_PREFETCH_BUFFER_SIZE => {
+                    if let Some(je) = opt_je {
+                        prefetched_entries.push_back(je);
+                    }
+                },
+                permit = http_stream_tx.reserve(), if cached_frames.peek().is_some() || !prefetched_entries.is_empty() => {
+                    let Ok(permit) = permit else {
+                        return TerminalLoopState::Failed(InvocationTaskError::UnexpectedClosedRequestStream);
+                    };
+                    let encoded_message = if let Some(frame) = cached_frames.next() {
+                        trace!(restate.journal.index = self.next_journal_index, "Sending cached entry");
+                        frame.clone()
+                    } else {
+                        let je = prefetched_entries.pop_front().expect("buffer must be non empty");
+                        let msg = ProtocolMessage::UnparsedEntry(je.clone());
+                        trace!(restate.protocol.message = ?msg, "Sending message");
+                        let encoded_message = crate::shortcircuit!(self.encode(msg));
+                        new_journal_prefix.push(&je, &encoded_message);
+                        encoded_message
+                    };
+                    let mut chunks = self.encoder.chunk(&encoded_message).into_iter();
+                    permit.send(Ok(Frame::data(chunks.next().expect("at least one buffer"))));
+                    for chunk in chunks {
+                        if http_stream_tx.send(Ok(Frame::data(chunk))).await.is_err() {
+                            return TerminalLoopState::Failed(InvocationTaskError::UnexpectedClosedRequestStream);
                         }
                     }
+                    self.next_journal_index += 1;
                 }
             }
         }
@@ -431,14 +518,31 @@ where
         msg: ProtocolMessage,
     ) -> Result<(), InvocationTaskError> {
         trace!(restate.protocol.message = ?msg, "Sending message");
-        let buf = self.encoder.encode(msg);
+        let encoded_message = self.encode(msg)?;
 
-        if http_stream_tx.send(Ok(Frame::data(buf))).await.is_err() {
-            return Err(InvocationTaskError::UnexpectedClosedRequestStream);
-        };
+        for chunk in self.encoder.chunk(&encoded_message) {
+            if http_stream_tx.send(Ok(Frame::data(chunk))).await.is_err() {
+                return Err(InvocationTaskError::UnexpectedClosedRequestStream);
+            };
+        }
         Ok(())
     }
 
+    /// Encodes the given message, failing if it hits the request message size limit.
+    fn encode(&self, msg: ProtocolMessage) -> Result<EncodedMessage, InvocationTaskError> {
+        if let Some(limit) = self.invocation_task.request_message_size_limit {
+            let message_length = self.encoder.encoded_len(&msg);
+            if message_length >= limit {
+                return Err(InvocationTaskError::RequestMessageSizeLimit(
+                    msg.message_type(),
+                    message_length,
+                    limit,
+                ));
+            }
+        }
+        Ok(self.encoder.encode_message(msg))
+    }
+
     fn handle_response_headers(
         &mut self,
         mut parts: http::response::Parts,
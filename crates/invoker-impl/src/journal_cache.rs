@@ -0,0 +1,231 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use metrics::counter;
+use moka::notification::RemovalCause;
+use moka::policy::EvictionPolicy;
+use moka::sync::{Cache, CacheBuilder};
+
+use restate_service_protocol::message::EncodedMessage;
+use restate_types::identifiers::{EntryIndex, InvocationId};
+use restate_types::invocation::InvocationEpoch;
+use restate_types::journal::raw::PlainRawEntry;
+
+use crate::metric_definitions::{
+    INVOKER_JOURNAL_CACHE_EVICTIONS, INVOKER_JOURNAL_CACHE_HITS, INVOKER_JOURNAL_CACHE_MISSES,
+};
+
+/// Encoded entries of a prefix of an invocation journal, ready to be replayed.
+///
+/// The prefix only contains entries that can't change anymore, that is entries which are not
+/// waiting for a completion. It ends at the first entry that is still waiting for a completion.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EncodedJournalPrefix {
+    frames: Vec<EncodedMessage>,
+    size: usize,
+    sealed: bool,
+}
+
+impl EncodedJournalPrefix {
+    pub(crate) fn len(&self) -> EntryIndex {
+        self.frames.len() as EntryIndex
+    }
+
+    pub(crate) fn frames(&self) -> &[EncodedMessage] {
+        &self.frames
+    }
+
+    /// Appends the encoded frame of the next journal entry. Once an entry waiting for a
+    /// completion has been pushed, the prefix doesn't grow anymore.
+    pub(crate) fn push(&mut self, entry: &PlainRawEntry, frame: &EncodedMessage) {
+        if self.sealed {
+            return;
+        }
+        if entry.header().is_completed() == Some(false) {
+            self.sealed = true;
+            return;
+        }
+        self.size += frame.encoded_len();
+        self.frames.push(frame.clone());
+    }
+}
+
+/// LRU cache of the encoded journal prefixes of recently replayed invocations, keyed by
+/// invocation id and invocation epoch.
+///
+/// Restarting an invocation bumps its epoch and replaces its journal, so the prefix cached for
+/// a previous epoch is never replayed to the new attempt.
+///
+/// This can be safely shared between all invocation tasks of the invoker.
+#[derive(Clone)]
+pub(crate) struct JournalReplayCache {
+    inner: Option<Cache<(InvocationId, InvocationEpoch), Arc<EncodedJournalPrefix>>>,
+}
+
+impl JournalReplayCache {
+    /// Creates a new instance of JournalReplayCache. If memory budget is 0
+    /// cache will be disabled
+    pub(crate) fn new(memory_budget_bytes: usize) -> Self {
+        let inner = if memory_budget_bytes > 0 {
+            Some(
+                CacheBuilder::default()
+                    .name("InvokerJournalReplayCache")
+                    .weigher(|_, prefix: &Arc<EncodedJournalPrefix>| {
+                        (size_of::<(InvocationId, InvocationEpoch)>() + prefix.size)
+                            .try_into()
+                            .unwrap_or(u32::MAX)
+                    })
+                    .max_capacity(memory_budget_bytes.try_into().unwrap_or(u64::MAX))
+                    .eviction_policy(EvictionPolicy::lru())
+                    .eviction_listener(|_, _, cause| {
+                        if cause == RemovalCause::Size {
+                            counter!(INVOKER_JOURNAL_CACHE_EVICTIONS).increment(1);
+                        }
+                    })
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        Self { inner }
+    }
+
+    /// Get the cached journal prefix of the given invocation epoch.
+    pub(crate) fn get(
+        &self,
+        invocation_id: InvocationId,
+        invocation_epoch: InvocationEpoch,
+    ) -> Option<Arc<EncodedJournalPrefix>> {
+        let inner = self.inner.as_ref()?;
+
+        let prefix = inner.get(&(invocation_id, invocation_epoch));
+        if prefix.is_some() {
+            counter!(INVOKER_JOURNAL_CACHE_HITS).increment(1);
+        } else {
+            counter!(INVOKER_JOURNAL_CACHE_MISSES).increment(1);
+        }
+        prefix
+    }
+
+    /// Caches the journal prefix of the given invocation, unless it's empty.
+    pub(crate) fn insert(
+        &self,
+        invocation_id: InvocationId,
+        invocation_epoch: InvocationEpoch,
+        prefix: EncodedJournalPrefix,
+    ) {
+        let Some(ref inner) = self.inner else {
+            return;
+        };
+
+        if prefix.frames.is_empty() {
+            return;
+        }
+        // The entry ending the prefix might have been completed by the next replay
+        let prefix = EncodedJournalPrefix {
+            sealed: false,
+            ..prefix
+        };
+        inner.insert((invocation_id, invocation_epoch), Arc::new(prefix));
+    }
+
+    /// Removes the journal prefix of the given invocation, e.g. because the invocation completed.
+    pub(crate) fn invalidate(
+        &self,
+        invocation_id: InvocationId,
+        invocation_epoch: InvocationEpoch,
+    ) {
+        if let Some(ref inner) = self.inner {
+            inner.invalidate(&(invocation_id, invocation_epoch));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use restate_service_protocol::message::{Encoder, ProtocolMessage};
+    use restate_types::journal::raw::{PlainEntryHeader, RawEntry};
+    use restate_types::service_protocol::ServiceProtocolVersion;
+
+    fn entry(completed: Option<bool>) -> PlainRawEntry {
+        let header = match completed {
+            None => PlainEntryHeader::SetState {},
+            Some(is_completed) => PlainEntryHeader::GetState { is_completed },
+        };
+        RawEntry::new(header, Bytes::from_static(b"entry"))
+    }
+
+    fn frame(entry: &PlainRawEntry) -> EncodedMessage {
+        Encoder::new(ServiceProtocolVersion::V3)
+            .encode_message(ProtocolMessage::UnparsedEntry(entry.clone()))
+    }
+
+    #[test]
+    fn prefix_stops_at_first_entry_waiting_for_completion() {
+        let mut prefix = EncodedJournalPrefix::default();
+
+        for entry in [
+            entry(None),
+            entry(Some(true)),
+            entry(Some(false)),
+            entry(None),
+        ] {
+            prefix.push(&entry, &frame(&entry));
+        }
+
+        assert_eq!(prefix.len(), 2);
+        assert_eq!(prefix.size, 2 * frame(&entry(None)).encoded_len());
+    }
+
+    #[test]
+    fn disabled_cache() {
+        let cache = JournalReplayCache::new(0);
+        let invocation_id = InvocationId::mock_random();
+
+        let mut prefix = EncodedJournalPrefix::default();
+        prefix.push(&entry(None), &frame(&entry(None)));
+        cache.insert(invocation_id, 0, prefix);
+
+        assert!(cache.get(invocation_id, 0).is_none());
+    }
+
+    #[test]
+    fn insert_and_invalidate() {
+        let cache = JournalReplayCache::new(1024);
+        let invocation_id = InvocationId::mock_random();
+
+        let mut prefix = EncodedJournalPrefix::default();
+        prefix.push(&entry(None), &frame(&entry(None)));
+        cache.insert(invocation_id, 0, prefix);
+        assert_eq!(cache.get(invocation_id, 0).unwrap().len(), 1);
+
+        cache.invalidate(invocation_id, 0);
+        assert!(cache.get(invocation_id, 0).is_none());
+    }
+
+    #[test]
+    fn prefix_of_previous_epoch_is_not_served() {
+        let cache = JournalReplayCache::new(1024);
+        let invocation_id = InvocationId::mock_random();
+
+        let mut prefix = EncodedJournalPrefix::default();
+        prefix.push(&entry(None), &frame(&entry(None)));
+        cache.insert(invocation_id, 0, prefix);
+
+        assert!(cache.get(invocation_id, 1).is_none());
+        assert_eq!(cache.get(invocation_id, 0).unwrap().len(), 1);
+    }
+}
@@ -8,9 +8,11 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod concurrency_limiter;
 mod input_command;
 mod invocation_state_machine;
 mod invocation_task;
+mod journal_cache;
 mod metric_definitions;
 mod quota;
 mod state_machine_manager;
@@ -22,7 +24,7 @@ use invocation_state_machine::InvocationStateMachine;
 use invocation_task::InvocationTask;
 use invocation_task::{InvocationTaskOutput, InvocationTaskOutputInner};
 use metrics::counter;
-use restate_core::cancellation_watcher;
+use restate_core::{cancellation_watcher, Metadata};
 use restate_errors::warn_it;
 use restate_invoker_api::{
     Effect, EffectKind, EntryEnricher, InvocationErrorReport, InvocationStatusReport,
@@ -52,7 +54,9 @@ use tokio::task::{AbortHandle, JoinSet};
 use tracing::instrument;
 use tracing::{debug, trace};
 
+use crate::concurrency_limiter::{ConcurrencyKey, ConcurrencyLimiter, ConcurrencyLimits};
 use crate::invocation_task::InvocationTaskError;
+use crate::journal_cache::JournalReplayCache;
 use crate::metric_definitions::{
     INVOKER_ENQUEUE, INVOKER_INVOCATION_TASK, TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED,
     TASK_OP_SUSPENDED,
@@ -61,8 +65,10 @@ pub use input_command::ChannelStatusReader;
 pub use input_command::InvokerHandle;
 use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
 use restate_types::deployment::PinnedDeployment;
-use restate_types::invocation::InvocationTarget;
-use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::invocation::{InvocationEpoch, InvocationTarget};
+use restate_types::nodes_config::ClusterVersion;
+use restate_types::schema::service::{RetryPolicyOverrides, ServiceMetadataResolver};
+use restate_types::time::MillisSinceEpoch;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Notification {
@@ -80,6 +86,7 @@ trait InvocationTaskRunner<SR> {
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
         retry_count_since_last_stored_entry: u32,
         storage_reader: SR,
         invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
@@ -87,12 +94,26 @@ trait InvocationTaskRunner<SR> {
         input_journal: InvokeInputJournal,
         task_pool: &mut JoinSet<()>,
     ) -> AbortHandle;
+
+    /// Concurrency limits an invocation of the given target is subject to.
+    fn concurrency_limits(&self, _invocation_target: &InvocationTarget) -> ConcurrencyLimits {
+        Vec::new()
+    }
+
+    /// Retry policies overriding the invoker retry policy for invocations of the given target.
+    fn retry_policy_overrides(
+        &self,
+        _invocation_target: &InvocationTarget,
+    ) -> Option<RetryPolicyOverrides> {
+        None
+    }
 }
 
 struct DefaultInvocationTaskRunner<EE, Schemas> {
     client: ServiceClient,
     entry_enricher: EE,
     schemas: Live<Schemas>,
+    journal_replay_cache: JournalReplayCache,
 }
 
 impl<SR, EE, Schemas> InvocationTaskRunner<SR> for DefaultInvocationTaskRunner<EE, Schemas>
@@ -109,6 +130,7 @@ where
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
         retry_count_since_last_stored_entry: u32,
         storage_reader: SR,
         invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
@@ -122,22 +144,66 @@ where
                 partition,
                 invocation_id,
                 invocation_target,
+                invocation_epoch,
                 opts.inactivity_timeout.into(),
                 opts.abort_timeout.into(),
                 opts.disable_eager_state,
                 opts.message_size_warning.get(),
                 opts.message_size_limit(),
+                opts.request_message_size_limit(),
+                opts.message_chunk_size.get(),
                 retry_count_since_last_stored_entry,
                 storage_reader.clone(),
                 storage_reader,
                 self.entry_enricher.clone(),
                 self.schemas.clone(),
+                self.journal_replay_cache.clone(),
                 invoker_tx,
                 invoker_rx,
             )
             .run(input_journal),
         )
     }
+
+    fn concurrency_limits(&self, invocation_target: &InvocationTarget) -> ConcurrencyLimits {
+        let schemas = self.schemas.pinned();
+        let service_name = invocation_target.service_name();
+        let mut limits = Vec::new();
+
+        if let Some(limit) = schemas
+            .resolve_latest_service(service_name)
+            .and_then(|service| service.concurrency_limit)
+        {
+            limits.push((ConcurrencyKey::Service(service_name.clone()), limit));
+        }
+        // Invocations are accounted to the latest deployment of their service, even if they
+        // are pinned to an older deployment.
+        if let Some(deployment) = schemas.resolve_latest_deployment_for_service(service_name) {
+            if let Some(limit) = deployment.metadata.delivery_options.concurrency_limit {
+                limits.push((ConcurrencyKey::Deployment(deployment.id), limit));
+            }
+        }
+
+        limits
+    }
+
+    fn retry_policy_overrides(
+        &self,
+        invocation_target: &InvocationTarget,
+    ) -> Option<RetryPolicyOverrides> {
+        let service = self
+            .schemas
+            .pinned()
+            .resolve_latest_service(invocation_target.service_name())?;
+        match service
+            .handlers
+            .iter()
+            .find(|handler| handler.name == invocation_target.handler_name().as_ref())
+        {
+            Some(handler) => handler.retry_policy.clone(),
+            None => service.retry_policy,
+        }
+    }
 }
 
 // -- Service implementation
@@ -188,10 +254,15 @@ impl<SR, EE, Schemas> Service<SR, EE, Schemas> {
                     client,
                     entry_enricher,
                     schemas: deployment_metadata_resolver,
+                    journal_replay_cache: JournalReplayCache::new(
+                        options.journal_replay_cache_memory_size.as_usize(),
+                    ),
                 },
                 invocation_tasks: Default::default(),
                 retry_timers: Default::default(),
+                execution_timers: Default::default(),
                 quota: quota::InvokerConcurrencyQuota::new(options.concurrent_invocations_limit()),
+                concurrency_limiter: Default::default(),
                 status_store: Default::default(),
                 invocation_state_machine_manager: Default::default(),
             },
@@ -305,7 +376,9 @@ struct ServiceInner<InvocationTaskRunner, SR> {
     // Invoker state machine
     invocation_tasks: JoinSet<()>,
     retry_timers: TimerQueue<(PartitionLeaderEpoch, InvocationId)>,
+    execution_timers: TimerQueue<(PartitionLeaderEpoch, InvocationId)>,
     quota: quota::InvokerConcurrencyQuota,
+    concurrency_limiter: ConcurrencyLimiter,
     status_store: InvocationStatusStore,
     invocation_state_machine_manager: state_machine_manager::InvocationStateMachineManager<SR>,
 }
@@ -367,7 +440,7 @@ where
             },
 
             Some(invoke_input_command) = segmented_input_queue.dequeue(), if !segmented_input_queue.is_empty() && self.quota.is_slot_available() => {
-                self.handle_invoke(options, invoke_input_command.partition, invoke_input_command.invocation_id, invoke_input_command.invocation_target, invoke_input_command.journal);
+                self.handle_invoke(options, invoke_input_command.partition, invoke_input_command.invocation_id, invoke_input_command.invocation_target, invoke_input_command.invocation_epoch, invoke_input_command.execution_deadline, invoke_input_command.journal);
             },
 
             Some(invocation_task_msg) = self.invocation_tasks_rx.recv() => {
@@ -416,6 +489,10 @@ where
                 let (partition, fid) = timer.into_inner();
                 self.handle_retry_timer_fired(options, partition, fid);
             },
+            timer = self.execution_timers.await_timer() => {
+                let (partition, fid) = timer.into_inner();
+                self.handle_execution_timer_fired(partition, fid).await;
+            },
             Some(invocation_task_result) = self.invocation_tasks.join_next() => {
                 if let Err(err) = invocation_task_result {
                     // Propagate panics coming from invocation tasks.
@@ -432,6 +509,7 @@ where
                 return false;
             }
         }
+        self.start_unblocked_invocations(options);
         // Execute next loop
         true
     }
@@ -471,12 +549,15 @@ where
             restate.invoker.partition_leader_epoch = ?partition,
         )
     )]
+    #[allow(clippy::too_many_arguments)]
     fn handle_invoke(
         &mut self,
         options: &InvokerOptions,
         partition: PartitionLeaderEpoch,
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
+        execution_deadline: Option<MillisSinceEpoch>,
         journal: InvokeInputJournal,
     ) {
         debug_assert!(self
@@ -487,10 +568,68 @@ where
             .resolve_invocation(partition, &invocation_id)
             .is_none());
 
+        let limits = self
+            .invocation_task_runner
+            .concurrency_limits(&invocation_target);
+        if !self
+            .concurrency_limiter
+            .try_acquire(partition, invocation_id, &limits)
+        {
+            trace!("Concurrency limit reached, queueing the invocation");
+            self.concurrency_limiter.park(
+                InvokeCommand {
+                    partition,
+                    invocation_id,
+                    invocation_target,
+                    invocation_epoch,
+                    execution_deadline,
+                    journal,
+                },
+                limits,
+            );
+            return;
+        }
+
+        self.start_new_invocation(
+            options,
+            partition,
+            invocation_id,
+            invocation_target,
+            invocation_epoch,
+            execution_deadline,
+            journal,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn start_new_invocation(
+        &mut self,
+        options: &InvokerOptions,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+        invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
+        execution_deadline: Option<MillisSinceEpoch>,
+        journal: InvokeInputJournal,
+    ) {
         let storage_reader = self
             .invocation_state_machine_manager
             .partition_storage_reader(partition)
             .expect("partition is registered");
+        let retry_policy_overrides = self
+            .invocation_task_runner
+            .retry_policy_overrides(&invocation_target);
+        let ism = InvocationStateMachine::create(
+            invocation_target,
+            invocation_epoch,
+            options.retry_policy.clone(),
+            retry_policy_overrides,
+            execution_deadline,
+        );
+        if let Some(execution_deadline) = execution_deadline {
+            self.execution_timers
+                .sleep_until(execution_deadline.into(), (partition, invocation_id));
+        }
         self.quota.reserve_slot();
         self.start_invocation_task(
             options,
@@ -498,10 +637,29 @@ where
             storage_reader.clone(),
             invocation_id,
             journal,
-            InvocationStateMachine::create(invocation_target, options.retry_policy.clone()),
+            ism,
         )
     }
 
+    /// Starts the queued invocations whose concurrency limits have been freed up, as long as the
+    /// invoker has slots available.
+    fn start_unblocked_invocations(&mut self, options: &InvokerOptions) {
+        while self.concurrency_limiter.has_parked() && self.quota.is_slot_available() {
+            let Some(invoke) = self.concurrency_limiter.next_runnable() else {
+                break;
+            };
+            self.start_new_invocation(
+                options,
+                invoke.partition,
+                invoke.invocation_id,
+                invoke.invocation_target,
+                invoke.invocation_epoch,
+                invoke.execution_deadline,
+                invoke.journal,
+            );
+        }
+    }
+
     #[instrument(
         level = "trace",
         skip_all,
@@ -522,6 +680,44 @@ where
         });
     }
 
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            restate.invocation.id = %invocation_id,
+            restate.invoker.partition_leader_epoch = ?partition,
+        )
+    )]
+    async fn handle_execution_timer_fired(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+    ) {
+        // The timer might belong to an invocation which already ended, or to a previous run of an
+        // invocation which has been resumed in the meantime.
+        if !self
+            .invocation_state_machine_manager
+            .resolve_invocation(partition, &invocation_id)
+            .is_some_and(|(_, ism)| ism.is_execution_timed_out())
+        {
+            trace!("Ignoring execution timeout because there is no matching timed out invocation");
+            return;
+        }
+        let (_, _, mut ism) = self
+            .invocation_state_machine_manager
+            .remove_invocation(partition, &invocation_id)
+            .expect("invocation state machine was resolved above");
+
+        ism.abort();
+        self.fail_invocation(
+            partition,
+            invocation_id,
+            &ism,
+            InvocationTaskError::ExecutionTimeout,
+        )
+        .await;
+    }
+
     #[instrument(
         level = "trace",
         skip_all,
@@ -655,6 +851,8 @@ where
                 let _ = output_tx
                     .send(Effect {
                         invocation_id,
+                        leader_epoch: Some(partition.1),
+                        invocation_epoch: ism.invocation_epoch,
                         kind: EffectKind::PinnedDeployment(pinned_deployment),
                     })
                     .await;
@@ -662,6 +860,8 @@ where
             let _ = output_tx
                 .send(Effect {
                     invocation_id,
+                    leader_epoch: Some(partition.1),
+                    invocation_epoch: ism.invocation_epoch,
                     kind: EffectKind::JournalEntry { entry_index, entry },
                 })
                 .await;
@@ -723,10 +923,21 @@ where
                 restate.invocation.target = %ism.invocation_target,
                 "Invocation task closed correctly");
             self.quota.unreserve_slot();
+            self.concurrency_limiter.release(partition, &invocation_id);
             self.status_store.on_end(&partition, &invocation_id);
+            report_retries(
+                sender,
+                partition,
+                invocation_id,
+                &ism,
+                ism.unreported_retries(),
+            )
+            .await;
             let _ = sender
                 .send(Effect {
                     invocation_id,
+                    leader_epoch: Some(partition.1),
+                    invocation_epoch: ism.invocation_epoch,
                     kind: EffectKind::End,
                 })
                 .await;
@@ -759,10 +970,21 @@ where
                 restate.invocation.target = %ism.invocation_target,
                 "Suspending invocation");
             self.quota.unreserve_slot();
+            self.concurrency_limiter.release(partition, &invocation_id);
             self.status_store.on_end(&partition, &invocation_id);
+            report_retries(
+                sender,
+                partition,
+                invocation_id,
+                &ism,
+                ism.unreported_retries(),
+            )
+            .await;
             let _ = sender
                 .send(Effect {
                     invocation_id,
+                    leader_epoch: Some(partition.1),
+                    invocation_epoch: ism.invocation_epoch,
                     kind: EffectKind::Suspended {
                         waiting_for_completed_entries: entry_indexes,
                     },
@@ -822,7 +1044,13 @@ where
                 "Aborting invocation");
             ism.abort();
             self.quota.unreserve_slot();
+            self.concurrency_limiter.release(partition, &invocation_id);
             self.status_store.on_end(&partition, &invocation_id);
+        } else if self
+            .concurrency_limiter
+            .remove_parked(partition, &invocation_id)
+        {
+            trace!("Removed queued invocation");
         } else {
             trace!("Ignoring Abort command because there is no matching partition/invocation");
         }
@@ -836,6 +1064,7 @@ where
         )
     )]
     fn handle_abort_partition(&mut self, partition: PartitionLeaderEpoch) {
+        self.concurrency_limiter.remove_partition(partition);
         if let Some(invocation_state_machines) = self
             .invocation_state_machine_manager
             .remove_partition(partition)
@@ -848,6 +1077,7 @@ where
                 );
                 ism.abort();
                 self.quota.unreserve_slot();
+                self.concurrency_limiter.release(partition, &fid);
                 self.status_store.on_end(&partition, &fid);
             }
         } else {
@@ -875,6 +1105,7 @@ where
         mut ism: InvocationStateMachine,
     ) {
         match ism.handle_task_error(
+            error.error_class(),
             error.next_retry_interval_override(),
             error.should_bump_start_message_retry_count_since_last_stored_entry(),
         ) {
@@ -899,6 +1130,20 @@ where
                     error.into_invocation_error_report(),
                     Some(next_retry_at),
                 );
+                // Let the partition processor record the retries, for the invocation history
+                if let Some(retries) = ism.notify_retry_scheduled() {
+                    report_retries(
+                        self.invocation_state_machine_manager
+                            .resolve_partition_sender(partition)
+                            .expect("Partition should be registered"),
+                        partition,
+                        invocation_id,
+                        &ism,
+                        retries,
+                    )
+                    .await;
+                }
+
                 self.invocation_state_machine_manager.register_invocation(
                     partition,
                     invocation_id,
@@ -908,32 +1153,56 @@ where
                     .sleep_until(next_retry_at, (partition, invocation_id));
             }
             _ => {
-                counter!(INVOKER_INVOCATION_TASK,
-                    "status" => TASK_OP_FAILED,
-                    "transient" => "false"
-                )
-                .increment(1);
-                warn_it!(
-                    error,
-                    restate.invocation.id = %invocation_id,
-                    restate.invocation.target = %ism.invocation_target,
-                    "Error when executing the invocation, not going to retry.");
-                self.quota.unreserve_slot();
-                self.status_store.on_end(&partition, &invocation_id);
-
-                let _ = self
-                    .invocation_state_machine_manager
-                    .resolve_partition_sender(partition)
-                    .expect("Partition should be registered")
-                    .send(Effect {
-                        invocation_id,
-                        kind: EffectKind::Failed(error.into_invocation_error()),
-                    })
+                self.fail_invocation(partition, invocation_id, &ism, error)
                     .await;
             }
         }
     }
 
+    /// Fails the invocation without retrying, recording the error in its completion.
+    async fn fail_invocation(
+        &mut self,
+        partition: PartitionLeaderEpoch,
+        invocation_id: InvocationId,
+        ism: &InvocationStateMachine,
+        error: InvocationTaskError,
+    ) {
+        counter!(INVOKER_INVOCATION_TASK,
+            "status" => TASK_OP_FAILED,
+            "transient" => "false"
+        )
+        .increment(1);
+        warn_it!(
+            error,
+            restate.invocation.id = %invocation_id,
+            restate.invocation.target = %ism.invocation_target,
+            "Error when executing the invocation, not going to retry.");
+        self.quota.unreserve_slot();
+        self.concurrency_limiter.release(partition, &invocation_id);
+        self.status_store.on_end(&partition, &invocation_id);
+
+        let sender = self
+            .invocation_state_machine_manager
+            .resolve_partition_sender(partition)
+            .expect("Partition should be registered");
+        report_retries(
+            sender,
+            partition,
+            invocation_id,
+            ism,
+            ism.unreported_retries(),
+        )
+        .await;
+        let _ = sender
+            .send(Effect {
+                invocation_id,
+                leader_epoch: Some(partition.1),
+                invocation_epoch: ism.invocation_epoch,
+                kind: EffectKind::Failed(error.into_invocation_error()),
+            })
+            .await;
+    }
+
     fn start_invocation_task(
         &mut self,
         options: &InvokerOptions,
@@ -950,6 +1219,7 @@ where
             partition,
             invocation_id,
             ism.invocation_target.clone(),
+            ism.invocation_epoch,
             ism.start_message_retry_count_since_last_stored_entry,
             storage_reader,
             self.invocation_tasks_tx.clone(),
@@ -1018,6 +1288,34 @@ where
     }
 }
 
+/// Reports the given number of scheduled retries of the invocation to the partition processor.
+///
+/// Older nodes can't decode [`EffectKind::RetryScheduled`], hence the retries are reported only
+/// once all the nodes of the cluster understand it.
+async fn report_retries(
+    sender: &mpsc::Sender<Effect>,
+    partition: PartitionLeaderEpoch,
+    invocation_id: InvocationId,
+    ism: &InvocationStateMachine,
+    retries: u32,
+) {
+    if retries == 0
+        || Metadata::try_with_current(|m| m.nodes_config_ref().cluster_version())
+            .unwrap_or(ClusterVersion::UNKNOWN)
+            < ClusterVersion::V2
+    {
+        return;
+    }
+    let _ = sender
+        .send(Effect {
+            invocation_id,
+            leader_epoch: Some(partition.1),
+            invocation_epoch: ism.invocation_epoch,
+            kind: EffectKind::RetryScheduled { retries },
+        })
+        .await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1037,8 +1335,10 @@ mod tests {
     use restate_invoker_api::entry_enricher;
     use restate_invoker_api::test_util::EmptyStorageReader;
     use restate_invoker_api::InvokerHandle;
+    use restate_service_protocol::message::EncodingError;
     use restate_test_util::{check, let_assert};
     use restate_types::config::InvokerOptionsBuilder;
+    use restate_types::errors::codes;
     use restate_types::identifiers::{LeaderEpoch, PartitionId, ServiceRevision};
     use restate_types::invocation::ServiceType;
     use restate_types::journal::enriched::EnrichedEntryHeader;
@@ -1087,7 +1387,9 @@ mod tests {
                 invocation_task_runner,
                 invocation_tasks: Default::default(),
                 retry_timers: Default::default(),
+                execution_timers: Default::default(),
                 quota: InvokerConcurrencyQuota::new(concurrency_limit),
+                concurrency_limiter: Default::default(),
                 status_store: Default::default(),
                 invocation_state_machine_manager: Default::default(),
             };
@@ -1131,6 +1433,7 @@ mod tests {
             partition: PartitionLeaderEpoch,
             invocation_id: InvocationId,
             invocation_target: InvocationTarget,
+            _invocation_epoch: InvocationEpoch,
             _retry_count_since_last_stored_entry: u32,
             storage_reader: SR,
             invoker_tx: mpsc::UnboundedSender<InvocationTaskOutput>,
@@ -1162,6 +1465,10 @@ mod tests {
             todo!()
         }
 
+        fn resolve_latest_services_openapi(&self) -> Value {
+            todo!()
+        }
+
         fn resolve_latest_service_type(&self, _: impl AsRef<str>) -> Option<ServiceType> {
             None
         }
@@ -1245,6 +1552,7 @@ mod tests {
                 partition_leader_epoch,
                 invocation_id,
                 invocation_target,
+                0,
                 InvokeInputJournal::NoCachedJournal,
             )
             .await
@@ -1292,6 +1600,8 @@ mod tests {
                 partition: MOCK_PARTITION,
                 invocation_id: invocation_id_1,
                 invocation_target: InvocationTarget::mock_virtual_object(),
+                invocation_epoch: 0,
+                execution_deadline: None,
                 journal: InvokeInputJournal::NoCachedJournal,
             })
             .await;
@@ -1300,6 +1610,8 @@ mod tests {
                 partition: MOCK_PARTITION,
                 invocation_id: invocation_id_2,
                 invocation_target: InvocationTarget::mock_virtual_object(),
+                invocation_epoch: 0,
+                execution_deadline: None,
                 journal: InvokeInputJournal::NoCachedJournal,
             })
             .await;
@@ -1400,6 +1712,8 @@ mod tests {
             MOCK_PARTITION,
             invocation_id,
             InvocationTarget::mock_virtual_object(),
+            0,
+            None,
             InvokeInputJournal::NoCachedJournal,
         );
 
@@ -1432,4 +1746,92 @@ mod tests {
         let_assert!(InvokerConcurrencyQuota::Limited { available_slots } = &service_inner.quota);
         assert_eq!(*available_slots, 2);
     }
+
+    #[test(restate_core::test)]
+    async fn fail_without_retrying_when_hitting_message_size_limit() {
+        let invoker_options = InvokerOptionsBuilder::default()
+            .inactivity_timeout(Duration::ZERO.into())
+            .abort_timeout(Duration::ZERO.into())
+            .disable_eager_state(false)
+            .message_size_warning(NonZeroUsize::new(1024).unwrap())
+            .message_size_limit(NonZeroUsize::new(2048))
+            .build()
+            .unwrap();
+        let invocation_id = InvocationId::mock_random();
+
+        let (_, _status_tx, mut service_inner) =
+            ServiceInner::mock(|_, _, _, _, _, _, _| pending(), None);
+        let mut partition_rx = service_inner.register_mock_partition(EmptyStorageReader);
+
+        service_inner.handle_invoke(
+            &invoker_options,
+            MOCK_PARTITION,
+            invocation_id,
+            InvocationTarget::mock_virtual_object(),
+            0,
+            None,
+            InvokeInputJournal::NoCachedJournal,
+        );
+
+        service_inner
+            .handle_invocation_task_failed(
+                MOCK_PARTITION,
+                invocation_id,
+                InvocationTaskError::Encoding(EncodingError::MessageSizeLimit(4096, 2048)),
+            )
+            .await;
+
+        // The invocation is failed right away, even though the retry policy is unlimited
+        let effect = partition_rx.recv().await.unwrap();
+        assert_eq!(effect.invocation_id, invocation_id);
+        check!(let EffectKind::Failed(_) = effect.kind);
+        assert!(service_inner
+            .status_store
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .is_none());
+    }
+
+    #[test(restate_core::test)]
+    async fn fail_when_exceeding_execution_timeout() {
+        let invoker_options = InvokerOptionsBuilder::default()
+            .inactivity_timeout(Duration::ZERO.into())
+            .abort_timeout(Duration::ZERO.into())
+            .disable_eager_state(false)
+            .build()
+            .unwrap();
+        let invocation_id = InvocationId::mock_random();
+
+        let (_, _status_tx, mut service_inner) =
+            ServiceInner::mock(|_, _, _, _, _, _, _| pending(), None);
+        let mut partition_rx = service_inner.register_mock_partition(EmptyStorageReader);
+
+        service_inner.handle_invoke(
+            &invoker_options,
+            MOCK_PARTITION,
+            invocation_id,
+            InvocationTarget::mock_virtual_object(),
+            0,
+            Some(MillisSinceEpoch::now()),
+            InvokeInputJournal::NoCachedJournal,
+        );
+        assert!(!service_inner.execution_timers.is_empty());
+
+        service_inner
+            .handle_execution_timer_fired(MOCK_PARTITION, invocation_id)
+            .await;
+
+        // The invocation is aborted and failed with a timeout error, without retrying
+        let effect = partition_rx.recv().await.unwrap();
+        assert_eq!(effect.invocation_id, invocation_id);
+        let_assert!(EffectKind::Failed(error) = effect.kind);
+        assert_eq!(error.code(), codes::TIMEOUT);
+        assert!(service_inner
+            .invocation_state_machine_manager
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .is_none());
+        assert!(service_inner
+            .status_store
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .is_none());
+    }
 }
@@ -16,6 +16,13 @@ pub const INVOKER_ENQUEUE: &str = "restate.invoker.enqueue.total";
 pub const INVOKER_INVOCATION_TASK: &str = "restate.invoker.invocation_task.total";
 pub const INVOKER_AVAILABLE_SLOTS: &str = "restate.invoker.available_slots";
 pub const INVOKER_TASK_DURATION: &str = "restate.invoker.task_duration.seconds";
+pub const INVOKER_CONCURRENCY_QUEUED: &str = "restate.invoker.concurrency_limit.queued";
+pub const INVOKER_CONCURRENCY_QUEUE_DURATION: &str =
+    "restate.invoker.concurrency_limit.queue_duration.seconds";
+pub const INVOKER_JOURNAL_CACHE_HITS: &str = "restate.invoker.journal_replay_cache.hits.total";
+pub const INVOKER_JOURNAL_CACHE_MISSES: &str = "restate.invoker.journal_replay_cache.misses.total";
+pub const INVOKER_JOURNAL_CACHE_EVICTIONS: &str =
+    "restate.invoker.journal_replay_cache.evictions.total";
 
 pub const TASK_OP_STARTED: &str = "started";
 pub const TASK_OP_SUSPENDED: &str = "suspended";
@@ -45,5 +52,35 @@ pub(crate) fn describe_metrics() {
         INVOKER_TASK_DURATION,
         Unit::Seconds,
         "Time taken to complete an invoker task"
-    )
+    );
+
+    describe_gauge!(
+        INVOKER_CONCURRENCY_QUEUED,
+        Unit::Count,
+        "Number of invocations waiting for the concurrency limit of their service or deployment"
+    );
+
+    describe_histogram!(
+        INVOKER_CONCURRENCY_QUEUE_DURATION,
+        Unit::Seconds,
+        "Time invocations waited for the concurrency limit of their service or deployment"
+    );
+
+    describe_counter!(
+        INVOKER_JOURNAL_CACHE_HITS,
+        Unit::Count,
+        "Number of journal replays which found the journal prefix in the replay cache"
+    );
+
+    describe_counter!(
+        INVOKER_JOURNAL_CACHE_MISSES,
+        Unit::Count,
+        "Number of journal replays which had to read the full journal from storage"
+    );
+
+    describe_counter!(
+        INVOKER_JOURNAL_CACHE_EVICTIONS,
+        Unit::Count,
+        "Number of journal prefixes evicted from the replay cache to stay within its memory limit"
+    );
 }
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod grpc;
+mod snapshots;
 mod store;
 
 mod service;
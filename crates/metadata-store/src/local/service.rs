@@ -19,7 +19,6 @@ use tower_http::classify::{GrpcCode, GrpcErrorsAsFailures, SharedClassifier};
 
 use restate_core::network::net_util;
 use restate_core::{ShutdownError, TaskCenter, TaskKind};
-use restate_rocksdb::RocksError;
 use restate_types::config::{MetadataStoreOptions, RocksDbOptions};
 use restate_types::live::BoxedLiveLoad;
 use restate_types::protobuf::common::MetadataServerStatus;
@@ -43,8 +42,8 @@ pub enum Error {
     GrpcReflection(#[from] tonic_reflection::server::Error),
     #[error("system is shutting down")]
     Shutdown(#[from] ShutdownError),
-    #[error("rocksdb error: {0}")]
-    RocksDB(#[from] RocksError),
+    #[error("failed to start metadata store: {0}")]
+    Store(#[from] crate::local::store::Error),
 }
 
 impl LocalMetadataStoreService {
@@ -111,6 +110,7 @@ impl LocalMetadataStoreService {
                 &bind_address,
                 service,
                 "metadata-store-grpc",
+                restate_core::network::tls::current(),
                 || health_status.update(MetadataServerStatus::Ready),
                 || health_status.update(MetadataServerStatus::Unknown),
             )
@@ -0,0 +1,191 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::BytesMut;
+use bytestring::ByteString;
+use futures::TryStreamExt;
+use object_store::PutPayload;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use restate_core::metadata_store::VersionedValue;
+use restate_object_store_util::ObjectStoreDestination;
+use restate_types::config::MetadataStoreSnapshotsOptions;
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::storage::{StorageCodec, StorageDecodeError, StorageEncodeError};
+
+const LATEST_SNAPSHOT_KEY: &str = "latest.json";
+const SNAPSHOT_KEY_PREFIX: &str = "metadata-snapshot-";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[error(transparent)]
+    Destination(#[from] restate_object_store_util::Error),
+    #[error("encode error: {0}")]
+    Encode(#[from] StorageEncodeError),
+    #[error("decode error: {0}")]
+    Decode(#[from] StorageDecodeError),
+    #[error("invalid latest snapshot pointer: {0}")]
+    InvalidPointer(#[from] serde_json::Error),
+}
+
+/// Consistent point-in-time copy of all key-value pairs stored in the metadata store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataSnapshot {
+    /// Milliseconds since the unix epoch at which the snapshot was taken.
+    pub created_at_millis: u64,
+    pub kv_pairs: Vec<(ByteString, VersionedValue)>,
+}
+
+flexbuffers_storage_encode_decode!(MetadataSnapshot);
+
+impl MetadataSnapshot {
+    pub fn new(kv_pairs: Vec<(ByteString, VersionedValue)>) -> Self {
+        let created_at_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is after unix epoch")
+            .as_millis() as u64;
+        Self {
+            created_at_millis,
+            kv_pairs,
+        }
+    }
+
+    fn object_key(&self) -> String {
+        format!("{SNAPSHOT_KEY_PREFIX}{:020}.bin", self.created_at_millis)
+    }
+}
+
+/// Pointer to the most recently uploaded snapshot. Stored as json so that operators can inspect
+/// it easily. Concurrent uploaders can race on it, hence readers find the latest snapshot by
+/// listing the snapshot objects instead.
+#[derive(Debug, Serialize, Deserialize)]
+struct LatestSnapshot {
+    key: String,
+    created_at_millis: u64,
+}
+
+/// Stores metadata snapshots in an object store. Each snapshot is written as an immutable
+/// object whose key sorts by creation time, after which the `latest.json` pointer is updated to
+/// reference it unless it already references a newer snapshot.
+#[derive(Clone, Debug)]
+pub struct MetadataSnapshotRepository {
+    destination: ObjectStoreDestination,
+}
+
+impl MetadataSnapshotRepository {
+    /// Returns `None` if no snapshot destination is configured.
+    pub fn create_if_configured(
+        options: &MetadataStoreSnapshotsOptions,
+    ) -> Result<Option<Self>, SnapshotError> {
+        let Some(destination) = options.destination.as_ref() else {
+            return Ok(None);
+        };
+
+        let destination = ObjectStoreDestination::create(destination, &options.object_store)?;
+        Ok(Some(Self { destination }))
+    }
+
+    pub async fn put(&self, snapshot: &MetadataSnapshot) -> Result<(), SnapshotError> {
+        let mut buf = BytesMut::new();
+        StorageCodec::encode(snapshot, &mut buf)?;
+
+        let key = snapshot.object_key();
+        self.destination
+            .object_store
+            .put(
+                &self.destination.path(&key),
+                PutPayload::from_bytes(buf.freeze()),
+            )
+            .await?;
+
+        match self.get_latest_pointer().await? {
+            Some(latest) if latest.created_at_millis >= snapshot.created_at_millis => {
+                debug!(
+                    "Not pointing '{LATEST_SNAPSHOT_KEY}' to '{key}' since it points to the newer snapshot '{}'",
+                    latest.key
+                );
+            }
+            _ => {
+                let latest = LatestSnapshot {
+                    key: key.clone(),
+                    created_at_millis: snapshot.created_at_millis,
+                };
+                let latest = serde_json::to_vec_pretty(&latest)?;
+                self.destination
+                    .object_store
+                    .put(
+                        &self.destination.path(LATEST_SNAPSHOT_KEY),
+                        PutPayload::from(latest),
+                    )
+                    .await?;
+            }
+        }
+
+        debug!(
+            "Uploaded metadata snapshot '{}' containing {} key-value pairs",
+            key,
+            snapshot.kv_pairs.len()
+        );
+        Ok(())
+    }
+
+    async fn get_latest_pointer(&self) -> Result<Option<LatestSnapshot>, SnapshotError> {
+        match self
+            .destination
+            .object_store
+            .get(&self.destination.path(LATEST_SNAPSHOT_KEY))
+            .await
+        {
+            Ok(result) => Ok(Some(serde_json::from_slice(&result.bytes().await?)?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Fetches the most recently created snapshot, if any.
+    pub async fn get_latest(&self) -> Result<Option<MetadataSnapshot>, SnapshotError> {
+        // the keys of the snapshots sort by creation time
+        let latest = self
+            .destination
+            .object_store
+            .list(Some(&self.destination.prefix))
+            .try_filter(|object| {
+                futures::future::ready(object.location.filename().is_some_and(|name| {
+                    name.starts_with(SNAPSHOT_KEY_PREFIX) && name.ends_with(".bin")
+                }))
+            })
+            .try_fold(None, |latest: Option<object_store::path::Path>, object| {
+                futures::future::ready(Ok(match latest {
+                    Some(latest) if latest.filename() >= object.location.filename() => Some(latest),
+                    _ => Some(object.location),
+                }))
+            })
+            .await?;
+        let Some(latest) = latest else {
+            return Ok(None);
+        };
+
+        let snapshot = self
+            .destination
+            .object_store
+            .get(&latest)
+            .await?
+            .bytes()
+            .await?;
+        let snapshot = StorageCodec::decode::<MetadataSnapshot, _>(&mut snapshot.as_ref())?;
+
+        Ok(Some(snapshot))
+    }
+}
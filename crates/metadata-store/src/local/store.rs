@@ -10,8 +10,8 @@
 
 use bytes::BytesMut;
 use bytestring::ByteString;
-use restate_core::cancellation_watcher;
 use restate_core::metadata_store::{Precondition, VersionedValue};
+use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
 use restate_rocksdb::{
     CfName, CfPrefixPattern, DbName, DbSpecBuilder, IoMode, Priority, RocksDb, RocksDbManager,
     RocksError,
@@ -22,10 +22,14 @@ use restate_types::storage::{
     StorageCodec, StorageDecode, StorageDecodeError, StorageEncode, StorageEncodeError,
 };
 use restate_types::Version;
-use rocksdb::{BoundColumnFamily, DBCompressionType, WriteBatch, WriteOptions, DB};
+use rocksdb::{BoundColumnFamily, DBCompressionType, IteratorMode, WriteBatch, WriteOptions, DB};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
-use tracing::{debug, trace};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, trace, warn};
+
+use crate::local::snapshots::{MetadataSnapshot, MetadataSnapshotRepository, SnapshotError};
 
 pub type RequestSender = mpsc::Sender<MetadataStoreRequest>;
 pub type RequestReceiver = mpsc::Receiver<MetadataStoreRequest>;
@@ -72,6 +76,8 @@ pub enum Error {
     Encode(#[from] StorageEncodeError),
     #[error("decode error: {0}")]
     Decode(#[from] StorageDecodeError),
+    #[error("snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
 }
 
 impl Error {
@@ -98,6 +104,13 @@ pub struct LocalMetadataStore {
     request_rx: RequestReceiver,
     buffer: BytesMut,
 
+    snapshot_repository: Option<MetadataSnapshotRepository>,
+    snapshot_interval: Duration,
+    // whether the store content changed since the last snapshot was uploaded
+    dirty: bool,
+    // completes with whether the snapshot upload in flight succeeded
+    snapshot_upload: Option<oneshot::Receiver<bool>>,
+
     // for creating other senders
     request_tx: RequestSender,
 }
@@ -106,7 +119,7 @@ impl LocalMetadataStore {
     pub async fn create(
         options: &MetadataStoreOptions,
         updateable_rocksdb_options: BoxedLiveLoad<RocksDbOptions>,
-    ) -> std::result::Result<Self, RocksError> {
+    ) -> Result<Self> {
         let (request_tx, request_rx) = mpsc::channel(options.request_queue_length());
 
         let db_name = DbName::new(DB_NAME);
@@ -128,14 +141,62 @@ impl LocalMetadataStore {
             .get_db(db_name)
             .expect("metadata store db is open");
 
-        Ok(Self {
+        let snapshot_repository =
+            MetadataSnapshotRepository::create_if_configured(&options.snapshots)?;
+
+        let mut store = Self {
             db,
             rocksdb,
             rocksdb_options: updateable_rocksdb_options,
             buffer: BytesMut::default(),
             request_rx,
             request_tx,
-        })
+            snapshot_interval: options.snapshots.interval.into(),
+            // take an initial snapshot after startup
+            dirty: true,
+            snapshot_upload: None,
+            snapshot_repository,
+        };
+
+        if options.snapshots.bootstrap_from_snapshot {
+            store.bootstrap_from_snapshot().await?;
+        }
+
+        Ok(store)
+    }
+
+    /// Restores the latest snapshot from the snapshot repository if the local database is empty.
+    async fn bootstrap_from_snapshot(&mut self) -> Result<()> {
+        let Some(repository) = self.snapshot_repository.as_ref() else {
+            warn!("Bootstrapping from snapshot is enabled but no snapshot destination is configured; starting with the local state");
+            return Ok(());
+        };
+
+        if self
+            .db
+            .iterator_cf(&self.kv_cf_handle(), IteratorMode::Start)
+            .next()
+            .is_some()
+        {
+            debug!("Metadata store is not empty; skipping bootstrap from snapshot");
+            return Ok(());
+        }
+
+        let Some(snapshot) = repository.get_latest().await? else {
+            info!("No metadata snapshot found; starting with an empty metadata store");
+            return Ok(());
+        };
+
+        info!(
+            "Bootstrapping metadata store from snapshot taken at {}ms with {} key-value pairs",
+            snapshot.created_at_millis,
+            snapshot.kv_pairs.len()
+        );
+        for (key, value) in snapshot.kv_pairs {
+            self.write_versioned_kv_pair(&key, &value).await?;
+        }
+
+        Ok(())
     }
 
     fn write_options(&mut self) -> WriteOptions {
@@ -159,12 +220,31 @@ impl LocalMetadataStore {
     pub async fn run(mut self) {
         debug!("Running LocalMetadataStore");
 
+        // only needed if snapshots are uploaded somewhere
+        let mut snapshot_interval = self.snapshot_repository.is_some().then(|| {
+            let mut snapshot_interval = tokio::time::interval(self.snapshot_interval);
+            snapshot_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            snapshot_interval
+        });
+
         loop {
             tokio::select! {
                 request = self.request_rx.recv() => {
                     let request = request.expect("receiver should not be closed since we own one clone.");
                     self.handle_request(request).await;
                 }
+                _ = async {
+                    snapshot_interval.as_mut().expect("snapshot interval is started").tick().await
+                }, if snapshot_interval.is_some() => {
+                    self.upload_snapshot();
+                }
+                uploaded = async {
+                    self.snapshot_upload.as_mut().expect("upload in flight").await
+                }, if self.snapshot_upload.is_some() => {
+                    self.snapshot_upload = None;
+                    // retry with the next snapshot, unless the upload succeeded
+                    self.dirty |= !uploaded.unwrap_or(false);
+                }
                 _ = cancellation_watcher() => {
                     break;
                 },
@@ -174,6 +254,66 @@ impl LocalMetadataStore {
         debug!("Stopped LocalMetadataStore");
     }
 
+    /// Takes a consistent snapshot of all key-value pairs and uploads it in the background.
+    /// Snapshots are only taken if the store content changed since the last uploaded snapshot,
+    /// and one at a time so that an older snapshot never overwrites a newer one.
+    fn upload_snapshot(&mut self) {
+        if !self.dirty || self.snapshot_upload.is_some() {
+            return;
+        }
+        let Some(repository) = self.snapshot_repository.clone() else {
+            return;
+        };
+
+        // The store is single threaded, hence iterating over the kv pairs yields a consistent view.
+        let snapshot = match self.create_snapshot() {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                warn!("Failed to create metadata snapshot: {err}");
+                return;
+            }
+        };
+        // Writes from now on need a new snapshot. If the upload fails, the store is marked dirty
+        // again once the upload completes.
+        self.dirty = false;
+
+        let (uploaded_tx, uploaded_rx) = oneshot::channel();
+        match TaskCenter::spawn_child(
+            TaskKind::Disposable,
+            "metadata-snapshot-upload",
+            async move {
+                let result = repository.put(&snapshot).await;
+                if let Err(err) = &result {
+                    warn!("Failed to upload metadata snapshot: {err}");
+                }
+                let _ = uploaded_tx.send(result.is_ok());
+                Ok(())
+            },
+        ) {
+            Ok(_) => self.snapshot_upload = Some(uploaded_rx),
+            Err(err) => {
+                debug!("Failed to spawn metadata snapshot upload task: {err}");
+                self.dirty = true;
+            }
+        }
+    }
+
+    fn create_snapshot(&self) -> Result<MetadataSnapshot> {
+        let mut kv_pairs = Vec::new();
+        for item in self
+            .db
+            .iterator_cf(&self.kv_cf_handle(), IteratorMode::Start)
+        {
+            let (key, value) = item?;
+            let key = ByteString::try_from(key.to_vec()).map_err(|err| {
+                Error::InvalidArgument(format!("metadata key is not valid utf-8: {err}"))
+            })?;
+            kv_pairs.push((key, Self::decode(value)?));
+        }
+
+        Ok(MetadataSnapshot::new(kv_pairs))
+    }
+
     fn kv_cf_handle(&self) -> Arc<BoundColumnFamily> {
         self.db
             .cf_handle(KV_PAIRS)
@@ -201,6 +341,7 @@ impl LocalMetadataStore {
                 result_tx,
             } => {
                 let result = self.put(&key, &value, precondition).await;
+                self.dirty |= result.is_ok();
                 Self::log_error(&result, "Put");
                 let _ = result_tx.send(result);
             }
@@ -210,6 +351,7 @@ impl LocalMetadataStore {
                 result_tx,
             } => {
                 let result = self.delete(&key, precondition);
+                self.dirty |= result.is_ok();
                 Self::log_error(&result, "Delete");
                 let _ = result_tx.send(result);
             }
@@ -8,18 +8,23 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
+
+use bytes::Bytes;
 use bytestring::ByteString;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use test_log::test;
 
+use restate_core::metadata_store::VersionedValue;
 use restate_core::network::FailingConnector;
 use restate_core::{TaskCenter, TaskKind, TestCoreEnv, TestCoreEnvBuilder};
 use restate_rocksdb::RocksDbManager;
 use restate_types::config::{
-    self, reset_base_temp_dir_and_retain, Configuration, MetadataStoreClientOptions,
-    MetadataStoreClientOptionsBuilder, MetadataStoreOptions, RocksDbOptions,
+    self, reset_base_temp_dir, reset_base_temp_dir_and_retain, Configuration,
+    MetadataStoreClientOptions, MetadataStoreClientOptionsBuilder, MetadataStoreOptions,
+    RocksDbOptions,
 };
 use restate_types::health::HealthStatus;
 use restate_types::live::{BoxedLiveLoad, Live};
@@ -29,6 +34,7 @@ use restate_types::{flexbuffers_storage_encode_decode, Version, Versioned};
 
 use crate::local::grpc::client::LocalMetadataStoreClient;
 use crate::local::service::LocalMetadataStoreService;
+use crate::local::snapshots::{MetadataSnapshot, MetadataSnapshotRepository};
 use crate::{MetadataStoreClient, Precondition, WriteError};
 
 #[derive(Debug, Clone, PartialOrd, PartialEq, Serialize, Deserialize)]
@@ -276,6 +282,112 @@ async fn durable_storage() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the latest snapshot is found even if an older snapshot finished uploading last.
+#[test(restate_core::test)]
+async fn latest_snapshot_wins_over_late_uploads() -> anyhow::Result<()> {
+    let snapshot_dir = tempfile::tempdir()?;
+    let mut opts = MetadataStoreOptions::default();
+    opts.snapshots.destination = Some(format!("file://{}", snapshot_dir.path().display()));
+    let repository = MetadataSnapshotRepository::create_if_configured(&opts.snapshots)?
+        .expect("snapshot destination is configured");
+
+    assert!(repository.get_latest().await?.is_none());
+
+    let mut newer = MetadataSnapshot::new(vec![]);
+    newer.created_at_millis = 2000;
+    let mut older = MetadataSnapshot::new(vec![(
+        ByteString::from_static("key"),
+        VersionedValue::new(Version::MIN, Bytes::from_static(b"value")),
+    )]);
+    older.created_at_millis = 1000;
+
+    repository.put(&newer).await?;
+    repository.put(&older).await?;
+
+    let latest = repository.get_latest().await?.expect("snapshot exists");
+    assert_eq!(latest.created_at_millis, 2000);
+    assert!(latest.kv_pairs.is_empty());
+
+    let pointer = std::fs::read_to_string(snapshot_dir.path().join("latest.json"))?;
+    assert!(pointer.contains("metadata-snapshot-00000000000000002000.bin"));
+    Ok(())
+}
+
+/// Tests that a fresh metadata store can be bootstrapped from an uploaded snapshot.
+#[test(restate_core::test(flavor = "multi_thread", worker_threads = 2))]
+async fn bootstrap_from_snapshot() -> anyhow::Result<()> {
+    let snapshot_dir = tempfile::tempdir()?;
+    let mut opts = MetadataStoreOptions::default();
+    opts.snapshots.destination = Some(format!("file://{}", snapshot_dir.path().display()));
+    opts.snapshots.interval = Duration::from_millis(10).into();
+    opts.snapshots.bootstrap_from_snapshot = true;
+
+    let (client, _env) = create_test_environment(&opts).await?;
+
+    for key in 1u32..=10 {
+        let value = key.to_string();
+        client
+            .put(
+                ByteString::from(value.clone()),
+                &Value {
+                    version: Version::from(key),
+                    value,
+                },
+                Precondition::DoesNotExist,
+            )
+            .await?;
+    }
+
+    // wait until a snapshot containing all writes has been uploaded
+    let repository = MetadataSnapshotRepository::create_if_configured(&opts.snapshots)?
+        .expect("snapshot destination is configured");
+    loop {
+        if repository
+            .get_latest()
+            .await?
+            .is_some_and(|snapshot| snapshot.kv_pairs.len() == 10)
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // start a new metadata store with an empty data directory
+    TaskCenter::cancel_tasks(Some(TaskKind::MetadataStore), None).await;
+    RocksDbManager::get().reset().await?;
+    reset_base_temp_dir();
+
+    let uds_path = tempfile::tempdir()?.into_path().join("grpc-server");
+    let metadata_store_client_opts = MetadataStoreClientOptionsBuilder::default()
+        .metadata_store_client(restate_types::config::MetadataStoreClient::Embedded {
+            address: AdvertisedAddress::Uds(uds_path.clone()),
+        })
+        .build()
+        .expect("valid metadata store client options");
+    let mut metadata_store_opts = opts.clone();
+    metadata_store_opts.bind_address = BindAddress::Uds(uds_path);
+    let metadata_store_opts = Live::from_value(metadata_store_opts);
+    let client = start_metadata_store(
+        metadata_store_client_opts,
+        metadata_store_opts.clone().boxed(),
+        metadata_store_opts.map(|c| &c.rocksdb).boxed(),
+    )
+    .await?;
+
+    for key in 1u32..=10 {
+        let value = key.to_string();
+        assert_eq!(
+            client.get(ByteString::from(value.clone())).await?,
+            Some(Value {
+                version: Version::from(key),
+                value
+            })
+        );
+    }
+
+    Ok(())
+}
+
 /// Creates a test environment with the [`RocksDBMetadataStore`] and a [`MetadataStoreClient`]
 /// connected to it.
 async fn create_test_environment(
@@ -0,0 +1,206 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Discovery of the nodes of the cluster through a DNS name resolving to the addresses of all of
+//! them, like the headless service of a Kubernetes StatefulSet.
+
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::pin::pin;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use restate_core::metadata_store::MetadataStoreClient;
+use restate_core::{cancellation_watcher, Metadata, MetadataWriter, ShutdownError};
+use restate_types::config::DnsDiscoveryOptions;
+use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
+use restate_types::net::AdvertisedAddress;
+use restate_types::nodes_config::NodesConfiguration;
+
+async fn resolve(options: &DnsDiscoveryOptions) -> std::io::Result<BTreeSet<SocketAddr>> {
+    Ok(
+        tokio::net::lookup_host((options.name.as_str(), options.port))
+            .await?
+            .collect(),
+    )
+}
+
+/// Finds the address of this node among the resolved ones. It's the address of the local
+/// interface used to reach the resolved nodes.
+fn find_own_address(addresses: &BTreeSet<SocketAddr>) -> Option<SocketAddr> {
+    addresses.iter().find_map(|address| {
+        let unspecified = match address.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+        // Connecting an UDP socket only selects the route, it doesn't send any packet
+        let socket = UdpSocket::bind(SocketAddr::new(unspecified, 0)).ok()?;
+        socket.connect(address).ok()?;
+        let local_ip = socket.local_addr().ok()?.ip();
+        addresses
+            .iter()
+            .find(|address| address.ip() == local_ip)
+            .copied()
+    })
+}
+
+fn to_advertised_address(address: SocketAddr) -> AdvertisedAddress {
+    AdvertisedAddress::from_str(&format!("http://{address}/"))
+        .expect("socket address is a valid advertised address")
+}
+
+/// Resolves the DNS name until its addresses include the one of this node, which is returned as
+/// its advertised address.
+pub async fn discover_advertised_address(
+    options: &DnsDiscoveryOptions,
+) -> Result<AdvertisedAddress, ShutdownError> {
+    let mut cancel = pin!(cancellation_watcher());
+    loop {
+        match resolve(options).await {
+            Ok(addresses) => match find_own_address(&addresses) {
+                Some(address) => {
+                    info!(
+                        "Discovered the address {address} of this node through '{}'",
+                        options.name
+                    );
+                    return Ok(to_advertised_address(address));
+                }
+                None => debug!(
+                    "The addresses {addresses:?} resolved from '{}' don't include this node yet",
+                    options.name
+                ),
+            },
+            Err(err) => warn!("Failed resolving '{}': {err}", options.name),
+        }
+
+        tokio::select! {
+            _ = &mut cancel => return Err(ShutdownError),
+            _ = tokio::time::sleep(options.refresh_interval.into()) => {}
+        }
+    }
+}
+
+/// Periodically resolves the DNS name, updating the address of this node in the nodes
+/// configuration if it changes.
+pub struct DnsDiscovery {
+    options: DnsDiscoveryOptions,
+    advertised_address: AdvertisedAddress,
+    metadata_store_client: MetadataStoreClient,
+    metadata_writer: MetadataWriter,
+}
+
+impl DnsDiscovery {
+    pub fn new(
+        options: DnsDiscoveryOptions,
+        advertised_address: AdvertisedAddress,
+        metadata_store_client: MetadataStoreClient,
+        metadata_writer: MetadataWriter,
+    ) -> Self {
+        Self {
+            options,
+            advertised_address,
+            metadata_store_client,
+            metadata_writer,
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let mut refresh_interval = tokio::time::interval(self.options.refresh_interval.into());
+        refresh_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut cancel = pin!(cancellation_watcher());
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel => return Ok(()),
+                _ = refresh_interval.tick() => {
+                    if let Err(err) = self.refresh().await {
+                        warn!(
+                            "Failed refreshing the nodes discovered through '{}': {err}",
+                            self.options.name
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    async fn refresh(&mut self) -> anyhow::Result<()> {
+        let addresses = resolve(&self.options).await?;
+
+        let nodes_config = Metadata::with_current(|m| m.nodes_config_snapshot());
+        let unregistered: Vec<_> = addresses
+            .iter()
+            .map(|address| to_advertised_address(*address))
+            .filter(|address| {
+                !nodes_config
+                    .iter()
+                    .any(|(_, node_config)| node_config.address == *address)
+            })
+            .collect();
+        if !unregistered.is_empty() {
+            debug!("Discovered nodes which didn't join the cluster yet: {unregistered:?}");
+        }
+
+        let Some(own_address) = find_own_address(&addresses).map(to_advertised_address) else {
+            return Ok(());
+        };
+        if own_address == self.advertised_address {
+            return Ok(());
+        }
+
+        info!(
+            "The address of this node changed from {} to {own_address}",
+            self.advertised_address
+        );
+        let my_node_id = Metadata::with_current(|m| m.my_node_id());
+        let nodes_config = self
+            .metadata_store_client
+            .read_modify_write(
+                NODES_CONFIG_KEY.clone(),
+                |nodes_config: Option<NodesConfiguration>| {
+                    let mut nodes_config =
+                        nodes_config.ok_or(anyhow::anyhow!("missing nodes configuration"))?;
+                    let mut node_config = nodes_config.find_node_by_id(my_node_id)?.clone();
+                    node_config.address = own_address.clone();
+                    nodes_config.upsert_node(node_config);
+                    nodes_config.increment_version();
+                    Ok::<_, anyhow::Error>(nodes_config)
+                },
+            )
+            .await
+            .map_err(|err| err.transpose())?;
+
+        self.metadata_writer.update(Arc::new(nodes_config)).await?;
+        self.advertised_address = own_address;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_own_loopback_address() {
+        // the loopback address can only be reached through the loopback interface
+        let addresses = BTreeSet::from([
+            SocketAddr::from(([127, 0, 0, 1], 5122)),
+            SocketAddr::from(([192, 0, 2, 1], 5122)),
+        ]);
+
+        assert_eq!(
+            find_own_address(&addresses),
+            Some(SocketAddr::from(([127, 0, 0, 1], 5122)))
+        );
+    }
+}
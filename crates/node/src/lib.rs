@@ -9,7 +9,9 @@
 // by the Apache License, Version 2.0.
 
 mod cluster_marker;
+mod dns_discovery;
 mod network_server;
+mod provision;
 mod roles;
 
 use std::sync::Arc;
@@ -19,6 +21,7 @@ use tracing::{debug, error, info, trace};
 use codederror::CodedError;
 use restate_bifrost::BifrostService;
 use restate_core::metadata_store::{retry_on_network_error, ReadWriteError};
+use restate_core::network::failure_detector::{spawn_failure_detector, FailureDetector};
 use restate_core::network::{
     GrpcConnector, MessageRouterBuilder, NetworkServerBuilder, Networking,
 };
@@ -39,16 +42,22 @@ use restate_types::live::Live;
 #[cfg(feature = "replicated-loglet")]
 use restate_types::logs::RecordCache;
 use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
-use restate_types::nodes_config::{LogServerConfig, NodeConfig, NodesConfiguration, Role};
+use restate_types::net::AdvertisedAddress;
+use restate_types::nodes_config::{
+    ClusterVersion, LogServerConfig, NodeConfig, NodesConfiguration, Role,
+};
 use restate_types::protobuf::common::{
     AdminStatus, IngressStatus, LogServerStatus, MetadataServerStatus, NodeStatus, WorkerStatus,
 };
 use restate_types::Version;
 
 use crate::cluster_marker::ClusterValidationError;
+use crate::dns_discovery::DnsDiscovery;
 use crate::network_server::NetworkServer;
 use crate::roles::{AdminRole, BaseRole, IngressRole, WorkerRole};
 
+pub use provision::{provision_cluster, ClusterProvisioning, ProvisionError};
+
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum Error {
     #[error("node failed to start due to failed safety check: {0}")]
@@ -100,6 +109,9 @@ pub enum BuildError {
     #[error("failed to initialize metadata store client: {0}")]
     #[code(unknown)]
     MetadataStoreClient(GenericError),
+    #[error("failed to initialize the network TLS: {0}")]
+    #[code(unknown)]
+    NetworkTls(#[from] restate_core::network::tls::TlsError),
 }
 
 pub struct Node {
@@ -112,6 +124,7 @@ pub struct Node {
     bifrost: BifrostService,
     metadata_store_role: Option<LocalMetadataStoreService>,
     base_role: BaseRole,
+    failure_detector: FailureDetector<GrpcConnector>,
     admin_role: Option<AdminRole<GrpcConnector>>,
     worker_role: Option<WorkerRole>,
     ingress_role: Option<IngressRole<GrpcConnector>>,
@@ -129,6 +142,9 @@ impl Node {
 
         cluster_marker::validate_and_update_cluster_marker(config.common.cluster_name())?;
 
+        // Before serving or connecting to any other node, including the metadata store
+        restate_core::network::tls::init(&config.networking)?;
+
         // todo(asoli) move local metadata store to use NetworkServer
         let metadata_store_role = if config.has_role(Role::MetadataStore) {
             Some(LocalMetadataStoreService::from_options(
@@ -158,6 +174,7 @@ impl Node {
         metadata_manager.register_in_message_router(&mut router_builder);
         let partition_routing_refresher =
             PartitionRoutingRefresher::new(metadata_store_client.clone());
+        let failure_detector = FailureDetector::new(networking.clone(), &mut router_builder);
 
         #[cfg(feature = "replicated-loglet")]
         let record_cache = RecordCache::new(
@@ -239,6 +256,10 @@ impl Node {
                     .clone()
                     .map(|config| &config.ingress)
                     .boxed(),
+                updateable_config
+                    .clone()
+                    .map(|config| &config.ingress.rate_limits)
+                    .boxed(),
                 health.ingress_status(),
                 networking.clone(),
                 metadata.updateable_schema(),
@@ -258,6 +279,7 @@ impl Node {
                     updateable_config.clone(),
                     partition_routing_refresher.partition_routing(),
                     networking.clone(),
+                    failure_detector.cluster_liveness(),
                     metadata,
                     metadata_manager.writer(),
                     &mut server_builder,
@@ -296,6 +318,7 @@ impl Node {
             metadata_store_role,
             metadata_store_client,
             base_role,
+            failure_detector,
             admin_role,
             ingress_role,
             worker_role,
@@ -331,8 +354,30 @@ impl Node {
         // Start partition routing information refresher
         spawn_partition_routing_refresher(self.partition_routing_refresher)?;
 
-        let nodes_config =
-            Self::upsert_node_config(&self.metadata_store_client, &config.common).await?;
+        let advertised_address = match &config.common.dns_discovery {
+            Some(dns_discovery) => {
+                dns_discovery::discover_advertised_address(dns_discovery).await?
+            }
+            None => config.common.advertised_address.clone(),
+        };
+
+        let nodes_config = loop {
+            let result = Self::upsert_node_config(
+                &self.metadata_store_client,
+                &config.common,
+                &advertised_address,
+            )
+            .await;
+            match (result, &config.common.dns_discovery) {
+                // Nodes discovered through DNS are started together, join the cluster once
+                // another node bootstrapped it.
+                (Err(Error::MissingNodesConfiguration), Some(dns_discovery)) => {
+                    info!("Waiting for the cluster to be bootstrapped before joining it");
+                    tokio::time::sleep(dns_discovery.refresh_interval.into()).await;
+                }
+                (result, _) => break result?,
+            }
+        };
         metadata_writer.update(Arc::new(nodes_config)).await?;
 
         if config.common.allow_bootstrap {
@@ -384,8 +429,23 @@ impl Node {
         info!(
             roles = %my_node_config.roles,
             address = %my_node_config.address,
+            location = %my_node_config.location,
             "My Node ID is {}", my_node_config.current_generation);
 
+        if let Some(dns_discovery) = config.common.dns_discovery.clone() {
+            TaskCenter::spawn(
+                TaskKind::SystemService,
+                "dns-discovery",
+                DnsDiscovery::new(
+                    dns_discovery,
+                    my_node_config.address.clone(),
+                    self.metadata_store_client.clone(),
+                    metadata_writer.clone(),
+                )
+                .run(),
+            )?;
+        }
+
         // todo this is a temporary solution to announce the updated NodesConfiguration to the
         //  configured admin nodes. It should be removed once we have a gossip-based node status
         //  protocol. Notifying the admin nodes is done on a best effort basis in case one admin
@@ -411,6 +471,7 @@ impl Node {
 
         // Ensures bifrost has initial metadata synced up before starting the worker.
         // Need to run start in new tc scope to have access to metadata()
+        let bifrost = self.bifrost.handle();
         self.bifrost.start().await?;
 
         #[cfg(feature = "replicated-loglet")]
@@ -424,6 +485,14 @@ impl Node {
             TaskCenter::spawn(TaskKind::SystemBoot, "admin-init", admin_role.start())?;
         }
 
+        let processors_manager = self
+            .worker_role
+            .as_ref()
+            .map(|role| role.partition_processor_manager_handle());
+        let partition_store_manager = self
+            .worker_role
+            .as_ref()
+            .map(|role| role.partition_store_manager());
         if let Some(worker_role) = self.worker_role {
             TaskCenter::spawn(TaskKind::SystemBoot, "worker-init", worker_role.start())?;
         }
@@ -442,6 +511,9 @@ impl Node {
                     connection_manager,
                     self.server_builder,
                     common_options,
+                    processors_manager,
+                    bifrost,
+                    partition_store_manager,
                 )
                 .await?;
                 Ok(())
@@ -450,6 +522,8 @@ impl Node {
 
         self.base_role.start()?;
 
+        spawn_failure_detector(self.failure_detector)?;
+
         let my_roles = my_node_config.roles;
         // Report that the node is running when all roles are ready
         let _ = TaskCenter::spawn(TaskKind::Disposable, "status-report", async move {
@@ -508,6 +582,7 @@ impl Node {
     async fn upsert_node_config(
         metadata_store_client: &MetadataStoreClient,
         common_opts: &CommonOptions,
+        advertised_address: &AdvertisedAddress,
     ) -> Result<NodesConfiguration, Error> {
         retry_on_network_error(common_opts.network_error_retry_policy.clone(), || {
             let mut previous_node_generation = None;
@@ -553,7 +628,9 @@ impl Node {
 
                     // update node_config
                     node_config.roles = common_opts.roles;
-                    node_config.address = common_opts.advertised_address.clone();
+                    node_config.address = advertised_address.clone();
+                    node_config.location = common_opts.location.clone();
+                    node_config.cluster_version = ClusterVersion::CURRENT;
                     node_config.current_generation.bump_generation();
 
                     node_config
@@ -573,13 +650,15 @@ impl Node {
 
                     let my_node_id = plain_node_id.with_generation(1);
 
-                    NodeConfig::new(
+                    let mut node_config = NodeConfig::new(
                         common_opts.node_name().to_owned(),
                         my_node_id,
-                        common_opts.advertised_address.clone(),
+                        advertised_address.clone(),
                         common_opts.roles,
                         LogServerConfig::default(),
-                    )
+                    );
+                    node_config.location = common_opts.location.clone();
+                    node_config
                 };
 
                 nodes_config.upsert_node(my_node_config);
@@ -0,0 +1,219 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{BTreeMap, HashMap};
+
+use tonic::{Request, Response, Status};
+
+use restate_bifrost::Bifrost;
+use restate_core::network::protobuf::node_svc::node_debug_svc_server::NodeDebugSvc;
+use restate_core::network::protobuf::node_svc::{
+    ColumnFamilyStats, GetBifrostStateRequest, GetBifrostStateResponse, GetRocksDbStatsRequest,
+    GetRocksDbStatsResponse, ListPartitionStoresResponse, LogState, PartitionStoreState,
+    RocksDbStats,
+};
+use restate_core::worker_api::ProcessorsManagerHandle;
+use restate_core::Metadata;
+use restate_partition_store::PartitionStoreManager;
+use restate_rocksdb::{CfName, RocksDb, RocksDbManager};
+use restate_types::logs::LogId;
+use restate_types::Versioned;
+
+use crate::network_server::metrics::{ROCKSDB_CF_PROPERTIES, ROCKSDB_DB_PROPERTIES};
+
+/// Read-only access to storage and bifrost internals of this node for debugging purposes.
+pub struct NodeDebugSvcHandler {
+    bifrost: Bifrost,
+    partition_store_manager: Option<PartitionStoreManager>,
+    processors_manager: Option<ProcessorsManagerHandle>,
+}
+
+impl NodeDebugSvcHandler {
+    pub fn new(
+        bifrost: Bifrost,
+        partition_store_manager: Option<PartitionStoreManager>,
+        processors_manager: Option<ProcessorsManagerHandle>,
+    ) -> Self {
+        Self {
+            bifrost,
+            partition_store_manager,
+            processors_manager,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeDebugSvc for NodeDebugSvcHandler {
+    async fn get_rocks_db_stats(
+        &self,
+        request: Request<GetRocksDbStatsRequest>,
+    ) -> Result<Response<GetRocksDbStatsResponse>, Status> {
+        let request = request.into_inner();
+        let manager = RocksDbManager::get();
+
+        let dbs: Vec<_> = manager
+            .get_all_dbs()
+            .iter()
+            .filter(|db| match &request.db {
+                Some(name) => db.name.as_str() == name,
+                None => true,
+            })
+            .map(|db| rocksdb_stats(db))
+            .collect();
+
+        if let Some(name) = request.db {
+            if dbs.is_empty() {
+                return Err(Status::not_found(format!("Unknown database '{name}'")));
+            }
+        }
+
+        Ok(Response::new(GetRocksDbStatsResponse {
+            write_buffer_manager_capacity: manager.get_total_write_buffer_capacity(),
+            write_buffer_manager_usage: manager.get_total_write_buffer_usage(),
+            dbs,
+        }))
+    }
+
+    async fn get_bifrost_state(
+        &self,
+        request: Request<GetBifrostStateRequest>,
+    ) -> Result<Response<GetBifrostStateResponse>, Status> {
+        let request = request.into_inner();
+        let logs = Metadata::with_current(|m| m.logs_snapshot());
+
+        let log_ids: Vec<LogId> = if request.log_ids.is_empty() {
+            logs.iter().map(|(log_id, _)| *log_id).collect()
+        } else {
+            request.log_ids.into_iter().map(LogId::from).collect()
+        };
+
+        let mut log_states = Vec::with_capacity(log_ids.len());
+        for log_id in log_ids {
+            let chain = logs
+                .chain(&log_id)
+                .ok_or_else(|| Status::not_found(format!("Unknown log id {log_id}")))?;
+            let tail_segment = chain.tail();
+
+            let mut log_state = LogState {
+                log_id: log_id.into(),
+                num_segments: u32::try_from(chain.num_segments()).unwrap_or(u32::MAX),
+                tail_segment_provider: tail_segment.config.kind.to_string(),
+                tail_segment_base_lsn: tail_segment.base_lsn.into(),
+                ..Default::default()
+            };
+
+            match self.bifrost.get_trim_point(log_id).await {
+                Ok(trim_point) => log_state.trim_point = Some(trim_point.into()),
+                Err(err) => log_state.error = Some(err.to_string()),
+            }
+
+            if request.find_tail && log_state.error.is_none() {
+                match self.bifrost.find_tail(log_id).await {
+                    Ok(tail) => {
+                        log_state.tail_lsn = Some(tail.offset().into());
+                        log_state.sealed = Some(tail.is_sealed());
+                    }
+                    Err(err) => log_state.error = Some(err.to_string()),
+                }
+            }
+
+            log_states.push(log_state);
+        }
+
+        Ok(Response::new(GetBifrostStateResponse {
+            enabled_providers: self
+                .bifrost
+                .enabled_providers()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            logs_version: logs.version().into(),
+            logs: log_states,
+        }))
+    }
+
+    async fn list_partition_stores(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<ListPartitionStoresResponse>, Status> {
+        let Some(partition_store_manager) = &self.partition_store_manager else {
+            return Err(Status::failed_precondition(
+                "Partition stores are only available on nodes running the worker role",
+            ));
+        };
+
+        let processor_states = match &self.processors_manager {
+            Some(processors_manager) => processors_manager
+                .get_state()
+                .await
+                .map_err(|_| Status::unavailable("Node is shutting down"))?,
+            None => BTreeMap::default(),
+        };
+
+        let mut partition_stores = partition_store_manager.get_all_partition_stores().await;
+        partition_stores.sort_by_key(|partition_store| partition_store.partition_id());
+
+        let partition_stores = partition_stores
+            .iter()
+            .map(|partition_store| {
+                let status = processor_states.get(&partition_store.partition_id());
+                PartitionStoreState {
+                    partition_id: partition_store.partition_id().into(),
+                    start_key: *partition_store.partition_key_range().start(),
+                    end_key: *partition_store.partition_key_range().end(),
+                    cf_name: partition_store.data_cf_name().to_string(),
+                    last_applied_lsn: status
+                        .and_then(|status| status.last_applied_log_lsn)
+                        .map(Into::into),
+                    last_persisted_lsn: status
+                        .and_then(|status| status.last_persisted_log_lsn)
+                        .map(Into::into),
+                    last_archived_lsn: status
+                        .and_then(|status| status.last_archived_log_lsn)
+                        .map(Into::into),
+                }
+            })
+            .collect();
+
+        Ok(Response::new(ListPartitionStoresResponse {
+            partition_stores,
+        }))
+    }
+}
+
+fn rocksdb_stats(db: &RocksDb) -> RocksDbStats {
+    let default_cf = CfName::new("default");
+
+    RocksDbStats {
+        name: db.name.to_string(),
+        properties: properties(db, &default_cf, ROCKSDB_DB_PROPERTIES),
+        cfs: db
+            .cfs()
+            .iter()
+            .map(|cf| ColumnFamilyStats {
+                name: cf.to_string(),
+                properties: properties(db, cf, ROCKSDB_CF_PROPERTIES),
+            })
+            .collect(),
+    }
+}
+
+fn properties<T>(db: &RocksDb, cf: &CfName, properties: &[(&str, T)]) -> HashMap<String, u64> {
+    properties
+        .iter()
+        .filter_map(|(property, _)| {
+            db.inner()
+                .get_property_int_cf(cf, property)
+                .ok()
+                .flatten()
+                .map(|value| (property.to_string(), value))
+        })
+        .collect()
+}
@@ -0,0 +1,277 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use metrics::{
+    Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+use metrics_exporter_prometheus::formatting;
+use metrics_util::layers::Layer;
+use opentelemetry::trace::{TraceContextExt, TraceId};
+use parking_lot::Mutex;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Histograms recording exemplars, which link their observations to the trace of the span in which
+/// they were recorded. These histograms are rendered as prometheus histograms rather than
+/// summaries, as the exemplars are attached to their buckets.
+pub(crate) static EXEMPLAR_HISTOGRAMS: &[&str] = &[
+    "restate.bifrost.append_duration.seconds",
+    "restate.ingress.request_duration.seconds",
+    "restate.invoker.task_duration.seconds",
+];
+
+pub(crate) const EXEMPLAR_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Exemplar {
+    trace_id: TraceId,
+    value: f64,
+    timestamp: SystemTime,
+}
+
+/// The latest exemplar of each time series of the [`EXEMPLAR_HISTOGRAMS`].
+#[derive(Debug, Clone, Default)]
+pub struct Exemplars(Arc<Mutex<HashMap<Key, Exemplar>>>);
+
+impl Exemplars {
+    /// Converts the metrics rendered in the prometheus text format to the OpenMetrics text
+    /// format, the only one supporting exemplars, adding the exemplars to the histogram buckets.
+    pub(crate) fn render_openmetrics(&self, prometheus_text: &str) -> String {
+        // Counters have the _total suffix in the samples, but not in the metric family name
+        let counters: HashSet<&str> = prometheus_text
+            .lines()
+            .filter_map(|line| line.strip_prefix("# TYPE "))
+            .filter_map(|line| line.strip_suffix(" counter"))
+            .collect();
+
+        // Buckets of each time series, with the exemplar to add to the first bucket containing it
+        let mut bucket_prefixes: Vec<(String, Exemplar)> = self
+            .0
+            .lock()
+            .iter()
+            .map(|(key, exemplar)| {
+                let mut prefix =
+                    format!("{}_bucket{{", formatting::sanitize_metric_name(key.name()));
+                for label in key.labels() {
+                    let _ = write!(
+                        prefix,
+                        "{}=\"{}\",",
+                        formatting::sanitize_label_key(label.key()),
+                        formatting::sanitize_label_value(label.value())
+                    );
+                }
+                prefix.push_str("le=\"");
+                (prefix, *exemplar)
+            })
+            .collect();
+
+        let mut out = String::with_capacity(prometheus_text.len());
+        for line in prometheus_text.lines() {
+            // Empty lines are not allowed
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(comment) = line
+                .strip_prefix("# TYPE ")
+                .map(|rest| ("# TYPE ", rest))
+                .or_else(|| line.strip_prefix("# HELP ").map(|rest| ("# HELP ", rest)))
+            {
+                let (kind, rest) = comment;
+                let (name, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+                if counters.contains(name) {
+                    let family = name.strip_suffix("_total").unwrap_or(name);
+                    let _ = writeln!(out, "{kind}{family} {tail}");
+                    continue;
+                }
+            } else if !line.starts_with('#') {
+                let name_end = line.find(['{', ' ']).unwrap_or(line.len());
+                let name = &line[..name_end];
+                if counters.contains(name) && !name.ends_with("_total") {
+                    let _ = writeln!(out, "{name}_total{}", &line[name_end..]);
+                    continue;
+                }
+
+                if let Some(position) = bucket_prefixes
+                    .iter()
+                    .position(|(prefix, _)| line.starts_with(prefix.as_str()))
+                {
+                    let (prefix, exemplar) = &bucket_prefixes[position];
+                    let le = line[prefix.len()..].split('"').next().unwrap_or_default();
+                    let le = if le == "+Inf" {
+                        f64::INFINITY
+                    } else {
+                        le.parse().unwrap_or(f64::INFINITY)
+                    };
+                    if exemplar.value <= le {
+                        let timestamp = exemplar
+                            .timestamp
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs_f64();
+                        let _ = writeln!(
+                            out,
+                            "{line} # {{trace_id=\"{}\"}} {} {timestamp:.3}",
+                            exemplar.trace_id, exemplar.value
+                        );
+                        bucket_prefixes.swap_remove(position);
+                        continue;
+                    }
+                }
+            }
+
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// Layer recording the exemplars of the [`EXEMPLAR_HISTOGRAMS`].
+pub(crate) struct ExemplarLayer(pub(crate) Exemplars);
+
+impl<R> Layer<R> for ExemplarLayer {
+    type Output = ExemplarRecorder<R>;
+
+    fn layer(&self, inner: R) -> Self::Output {
+        ExemplarRecorder {
+            inner,
+            exemplars: self.0.clone(),
+        }
+    }
+}
+
+pub(crate) struct ExemplarRecorder<R> {
+    inner: R,
+    exemplars: Exemplars,
+}
+
+impl<R: Recorder> Recorder for ExemplarRecorder<R> {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_counter(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_gauge(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_histogram(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key, metadata: &Metadata<'_>) -> Counter {
+        self.inner.register_counter(key, metadata)
+    }
+
+    fn register_gauge(&self, key: &Key, metadata: &Metadata<'_>) -> Gauge {
+        self.inner.register_gauge(key, metadata)
+    }
+
+    fn register_histogram(&self, key: &Key, metadata: &Metadata<'_>) -> Histogram {
+        let histogram = self.inner.register_histogram(key, metadata);
+        if !EXEMPLAR_HISTOGRAMS.contains(&key.name()) {
+            return histogram;
+        }
+
+        Histogram::from_arc(Arc::new(ExemplarHistogram {
+            inner: histogram,
+            key: key.clone(),
+            exemplars: self.exemplars.clone(),
+        }))
+    }
+}
+
+struct ExemplarHistogram {
+    inner: Histogram,
+    key: Key,
+    exemplars: Exemplars,
+}
+
+impl HistogramFn for ExemplarHistogram {
+    fn record(&self, value: f64) {
+        self.inner.record(value);
+
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() && span_context.is_sampled() {
+            self.exemplars.0.lock().insert(
+                self.key.clone(),
+                Exemplar {
+                    trace_id: span_context.trace_id(),
+                    value,
+                    timestamp: SystemTime::now(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_openmetrics_with_exemplars() {
+        let exemplars = Exemplars::default();
+        exemplars.0.lock().insert(
+            Key::from_parts(
+                "restate.bifrost.append_duration.seconds",
+                vec![metrics::Label::new("db", "bifrost")],
+            ),
+            Exemplar {
+                trace_id: TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+                value: 0.02,
+                timestamp: UNIX_EPOCH + std::time::Duration::from_secs(10),
+            },
+        );
+
+        let prometheus_text = "\
+# HELP restate_invoker_enqueue_total Number of invocations
+# TYPE restate_invoker_enqueue_total counter
+restate_invoker_enqueue_total 3
+
+# TYPE restate_sequencer_committed_records_bytes counter
+restate_sequencer_committed_records_bytes{partition=\"1\"} 10
+
+# TYPE restate_bifrost_append_duration_seconds histogram
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"0.01\"} 0
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"0.025\"} 1
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"+Inf\"} 1
+restate_bifrost_append_duration_seconds_sum{db=\"bifrost\"} 0.02
+restate_bifrost_append_duration_seconds_count{db=\"bifrost\"} 1
+";
+
+        assert_eq!(
+            exemplars.render_openmetrics(prometheus_text),
+            "\
+# HELP restate_invoker_enqueue Number of invocations
+# TYPE restate_invoker_enqueue counter
+restate_invoker_enqueue_total 3
+# TYPE restate_sequencer_committed_records_bytes counter
+restate_sequencer_committed_records_bytes_total{partition=\"1\"} 10
+# TYPE restate_bifrost_append_duration_seconds histogram
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"0.01\"} 0
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"0.025\"} 1 # {trace_id=\"4bf92f3577b34da6a3ce929d0e0e4736\"} 0.02 10.000
+restate_bifrost_append_duration_seconds_bucket{db=\"bifrost\",le=\"+Inf\"} 1
+restate_bifrost_append_duration_seconds_sum{db=\"bifrost\"} 0.02
+restate_bifrost_append_duration_seconds_count{db=\"bifrost\"} 1
+# EOF
+"
+        );
+    }
+}
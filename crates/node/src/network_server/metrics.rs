@@ -11,8 +11,11 @@
 use std::fmt::Write;
 
 use axum::extract::State;
+use axum::response::IntoResponse;
+use http::header::{ACCEPT, CONTENT_TYPE};
+use http::HeaderMap;
 use metrics_exporter_prometheus::formatting;
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use metrics_tracing_context::TracingContextLayer;
 use metrics_util::layers::Layer;
 use metrics_util::MetricKindMask;
@@ -22,6 +25,9 @@ use restate_core::task_center::TaskCenterMonitoring;
 use restate_rocksdb::{CfName, RocksDbManager};
 use restate_types::config::CommonOptions;
 
+use crate::network_server::exemplars::{
+    ExemplarLayer, Exemplars, EXEMPLAR_BUCKETS, EXEMPLAR_HISTOGRAMS,
+};
 use crate::network_server::prometheus_helpers::{
     format_rocksdb_histogram_for_prometheus, format_rocksdb_property_for_prometheus,
     format_rocksdb_stat_ticker_for_prometheus, MetricUnit,
@@ -141,7 +147,7 @@ const ROCKSDB_HISTOGRAMS: &[(Histogram, &str, MetricUnit)] = &[
 ];
 
 // Per database properties
-const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
+pub(crate) const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.block-cache-capacity", MetricUnit::Bytes),
     ("rocksdb.block-cache-usage", MetricUnit::Bytes),
     ("rocksdb.block-cache-pinned-usage", MetricUnit::Bytes),
@@ -149,7 +155,7 @@ const ROCKSDB_DB_PROPERTIES: &[(&str, MetricUnit)] = &[
 ];
 
 // Per column-family properties
-const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
+pub(crate) const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-immutable-mem-table", MetricUnit::Count),
     ("rocksdb.mem-table-flush-pending", MetricUnit::Count),
     ("rocksdb.is-write-stopped", MetricUnit::Count),
@@ -184,33 +190,68 @@ const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-files-at-level6", MetricUnit::Count),
 ];
 
-pub(crate) fn install_global_prometheus_recorder(opts: &CommonOptions) -> PrometheusHandle {
-    let builder = PrometheusBuilder::default()
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+pub(crate) fn install_global_prometheus_recorder(
+    opts: &CommonOptions,
+) -> (PrometheusHandle, Exemplars) {
+    let mut builder = PrometheusBuilder::default()
         // Remove a metric from registry if it was not updated for that duration
         .idle_timeout(
             MetricKindMask::HISTOGRAM,
             opts.histogram_inactivity_timeout.map(Into::into),
         );
+    // Exemplars are attached to the buckets, hence these are rendered as histograms
+    for name in EXEMPLAR_HISTOGRAMS {
+        builder = builder
+            .set_buckets_for_metric(Matcher::Full(name.to_string()), EXEMPLAR_BUCKETS)
+            .expect("buckets are not empty");
+    }
     let recorder = builder.build_recorder();
     let prometheus_handle = recorder.handle();
-    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(recorder);
+    let exemplars = Exemplars::default();
+    // The exemplar layer is innermost, so that it sees the labels added from the tracing context
+    let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS)
+        .layer(ExemplarLayer(exemplars.clone()).layer(recorder));
 
     // We do not expect this to fail except due to atomic CAS failure
     // which should never happen in practice.
     metrics::set_global_recorder(recorder).expect("no global metrics recorder should be installed");
-    prometheus_handle
+    (prometheus_handle, exemplars)
 }
 
 // -- Direct HTTP Handlers --
-pub async fn render_metrics(State(state): State<NodeCtrlHandlerState>) -> String {
+/// Renders the metrics in the prometheus text format, or in the OpenMetrics text format including
+/// the exemplars if the client accepts it.
+pub async fn render_metrics(
+    State(state): State<NodeCtrlHandlerState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let openmetrics = headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains("application/openmetrics-text"));
+
+    let out = render_prometheus_text(&state);
+    match state.exemplars {
+        Some(exemplars) if openmetrics => (
+            [(CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+            exemplars.render_openmetrics(&out),
+        ),
+        _ => ([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], out),
+    }
+}
+
+fn render_prometheus_text(state: &NodeCtrlHandlerState) -> String {
     let default_cf = CfName::new("default");
     let mut out = String::new();
 
     // Default tokio runtime metrics
     state.task_center.submit_metrics();
 
-    // Response content type is plain/text and that's expected.
-    if let Some(prometheus_handle) = state.prometheus_handle {
+    if let Some(prometheus_handle) = &state.prometheus_handle {
         // Internal system metrics
         let _ = write!(&mut out, "{}", prometheus_handle.render());
     }
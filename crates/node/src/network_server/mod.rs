@@ -8,10 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod debug_svc_handler;
+mod exemplars;
 mod grpc_svc_handler;
 mod metrics;
 mod prometheus_helpers;
+mod readiness;
 mod service;
 mod state;
+mod tasks;
 
 pub use service::NetworkServer;
@@ -0,0 +1,70 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use axum::extract::State;
+use http::StatusCode;
+
+use restate_types::cluster::cluster_state::ReplayStatus;
+use restate_types::nodes_config::Role;
+use restate_types::protobuf::common::{
+    AdminStatus, IngressStatus, LogServerStatus, MetadataServerStatus, NodeStatus, WorkerStatus,
+};
+
+use crate::network_server::state::NodeCtrlHandlerState;
+
+/// Readiness of the node, e.g. for the readiness probe of a Kubernetes pod. The node is ready
+/// once all of its roles are ready and all partition processors running on it have caught up
+/// with their logs.
+pub async fn render_readiness(State(state): State<NodeCtrlHandlerState>) -> (StatusCode, String) {
+    match check_readiness(&state).await {
+        Ok(()) => (StatusCode::OK, "ready".to_owned()),
+        Err(reason) => (StatusCode::SERVICE_UNAVAILABLE, reason),
+    }
+}
+
+async fn check_readiness(state: &NodeCtrlHandlerState) -> Result<(), String> {
+    let health = &state.health;
+    let node_status = health.current_node_status();
+    if node_status != NodeStatus::Alive {
+        return Err(format!("node is {}", node_status.as_str_name()));
+    }
+
+    for role in state.roles {
+        let ready = match role {
+            Role::Worker => health.current_worker_status() == WorkerStatus::Ready,
+            Role::Admin => health.current_admin_status() == AdminStatus::Ready,
+            Role::MetadataStore => {
+                health.current_metadata_server_status() == MetadataServerStatus::Ready
+            }
+            Role::LogServer => health.current_log_server_status() == LogServerStatus::Ready,
+            Role::HttpIngress => *health.ingress_status().get() == IngressStatus::Ready,
+        };
+        if !ready {
+            return Err(format!("{role} role is not ready"));
+        }
+    }
+
+    if let Some(processors_manager) = &state.processors_manager {
+        let processors = processors_manager
+            .get_state()
+            .await
+            .map_err(|_| "node is shutting down".to_owned())?;
+        if let Some((partition_id, _)) = processors
+            .iter()
+            .find(|(_, status)| status.replay_status != ReplayStatus::Active)
+        {
+            return Err(format!(
+                "partition {partition_id} is catching up with its log"
+            ));
+        }
+    }
+
+    Ok(())
+}
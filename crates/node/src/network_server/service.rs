@@ -11,32 +11,50 @@
 use axum::routing::get;
 use tonic::codec::CompressionEncoding;
 
+use restate_bifrost::Bifrost;
+use restate_core::network::protobuf::node_svc::node_debug_svc_server::NodeDebugSvcServer;
 use restate_core::network::protobuf::node_svc::node_svc_server::NodeSvcServer;
 use restate_core::network::{ConnectionManager, NetworkServerBuilder, TransportConnect};
+use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::TaskCenter;
-use restate_types::config::CommonOptions;
+use restate_partition_store::PartitionStoreManager;
+use restate_types::config::{CommonOptions, Configuration};
 use restate_types::health::Health;
 
 use crate::network_server::metrics::{install_global_prometheus_recorder, render_metrics};
+use crate::network_server::readiness::render_readiness;
 use crate::network_server::state::NodeCtrlHandlerStateBuilder;
+use crate::network_server::tasks::render_task_dump;
 
+use super::debug_svc_handler::NodeDebugSvcHandler;
 use super::grpc_svc_handler::NodeSvcHandler;
 
 pub struct NetworkServer {}
 
 impl NetworkServer {
+    #[allow(clippy::too_many_arguments)]
     pub async fn run<T: TransportConnect>(
         health: Health,
         connection_manager: ConnectionManager<T>,
         mut server_builder: NetworkServerBuilder,
         options: CommonOptions,
+        processors_manager: Option<ProcessorsManagerHandle>,
+        bifrost: Bifrost,
+        partition_store_manager: Option<PartitionStoreManager>,
     ) -> Result<(), anyhow::Error> {
         // Configure Metric Exporter
         let mut state_builder = NodeCtrlHandlerStateBuilder::default();
-        state_builder.task_center(TaskCenter::current());
+        state_builder
+            .task_center(TaskCenter::current())
+            .health(health.clone())
+            .roles(options.roles)
+            .processors_manager(processors_manager.clone());
 
         if !options.disable_prometheus {
-            state_builder.prometheus_handle(Some(install_global_prometheus_recorder(&options)));
+            let (prometheus_handle, exemplars) = install_global_prometheus_recorder(&options);
+            state_builder
+                .prometheus_handle(Some(prometheus_handle))
+                .exemplars(Some(exemplars));
         }
 
         let shared_state = state_builder.build().expect("should be infallible");
@@ -44,9 +62,12 @@ impl NetworkServer {
         // -- HTTP service (for prometheus et al.)
         let axum_router = axum::Router::new()
             .route("/metrics", get(render_metrics))
+            .route("/ready", get(render_readiness))
+            .route("/debug/tasks", get(render_task_dump))
             .with_state(shared_state);
 
         let node_health = health.node_status();
+        let max_message_size = Configuration::pinned().networking.grpc_max_message_size();
 
         server_builder.register_grpc_service(
             NodeSvcServer::new(NodeSvcHandler::new(
@@ -57,6 +78,19 @@ impl NetworkServer {
                 connection_manager,
             ))
             .accept_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Gzip)
+            .max_decoding_message_size(max_message_size)
+            .max_encoding_message_size(max_message_size),
+            restate_types::protobuf::FILE_DESCRIPTOR_SET,
+        );
+
+        server_builder.register_grpc_service(
+            NodeDebugSvcServer::new(NodeDebugSvcHandler::new(
+                bifrost,
+                partition_store_manager,
+                processors_manager,
+            ))
+            .accept_compressed(CompressionEncoding::Gzip)
             .send_compressed(CompressionEncoding::Gzip),
             restate_types::protobuf::FILE_DESCRIPTOR_SET,
         );
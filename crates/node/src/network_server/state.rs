@@ -8,12 +8,25 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use enumset::EnumSet;
 use metrics_exporter_prometheus::PrometheusHandle;
+
 use restate_core::task_center;
+use restate_core::worker_api::ProcessorsManagerHandle;
+use restate_types::health::Health;
+use restate_types::nodes_config::Role;
+
+use crate::network_server::exemplars::Exemplars;
 
 #[derive(Clone, derive_builder::Builder)]
 pub struct NodeCtrlHandlerState {
     #[builder(default)]
     pub prometheus_handle: Option<PrometheusHandle>,
+    #[builder(default)]
+    pub exemplars: Option<Exemplars>,
     pub task_center: task_center::Handle,
+    pub health: Health,
+    pub roles: EnumSet<Role>,
+    #[builder(default)]
+    pub processors_manager: Option<ProcessorsManagerHandle>,
 }
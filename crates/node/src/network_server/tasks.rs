@@ -0,0 +1,21 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use axum::extract::State;
+use axum::Json;
+
+use restate_core::task_center::TaskDump;
+
+use crate::network_server::state::NodeCtrlHandlerState;
+
+/// Dump of the tasks running on the node, e.g. to diagnose a hung shutdown.
+pub async fn render_task_dump(State(state): State<NodeCtrlHandlerState>) -> Json<Vec<TaskDump>> {
+    Json(state.task_center.dump_tasks().await)
+}
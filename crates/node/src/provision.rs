@@ -0,0 +1,302 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Provisioning of the metadata of a new cluster: the nodes configuration, the partition table,
+//! the scheduling plan and the logs metadata.
+
+use bytestring::ByteString;
+use tracing::info;
+
+use restate_core::metadata_store::{retry_on_network_error, ReadWriteError};
+use restate_metadata_store::MetadataStoreClient;
+use restate_types::cluster_controller::{ReplicationStrategy, SchedulingPlan};
+use restate_types::config::Configuration;
+use restate_types::logs::metadata::{Logs, LogsConfiguration, ProviderKind};
+use restate_types::metadata_store::keys::{
+    BIFROST_CONFIG_KEY, NODES_CONFIG_KEY, PARTITION_TABLE_KEY, SCHEDULING_PLAN_KEY,
+};
+use restate_types::nodes_config::NodesConfiguration;
+use restate_types::partition_table::PartitionTable;
+use restate_types::retries::RetryPolicy;
+use restate_types::storage::StorageDecode;
+use restate_types::{Version, Versioned};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProvisionError {
+    #[error("the cluster is already provisioned with an incompatible {metadata}: {reason}")]
+    Incompatible {
+        metadata: &'static str,
+        reason: String,
+    },
+    #[error("could not read/write from/to metadata store: {0}")]
+    MetadataStore(#[from] ReadWriteError),
+}
+
+/// The metadata a cluster is provisioned with.
+#[derive(Debug, Clone)]
+pub struct ClusterProvisioning {
+    pub cluster_name: String,
+    pub num_partitions: u16,
+    pub replication_strategy: ReplicationStrategy,
+    pub log_provider: ProviderKind,
+}
+
+impl ClusterProvisioning {
+    pub fn from_configuration(config: &Configuration) -> Self {
+        Self {
+            cluster_name: config.common.cluster_name().to_owned(),
+            num_partitions: config.common.bootstrap_num_partitions(),
+            replication_strategy: config.admin.default_replication_strategy,
+            log_provider: config.bifrost.default_provider,
+        }
+    }
+
+    fn nodes_configuration(&self) -> NodesConfiguration {
+        NodesConfiguration::new(Version::MIN, self.cluster_name.clone())
+    }
+
+    fn partition_table(&self) -> PartitionTable {
+        PartitionTable::with_equally_sized_partitions(Version::MIN, self.num_partitions)
+    }
+
+    fn scheduling_plan(&self, partition_table: &PartitionTable) -> SchedulingPlan {
+        SchedulingPlan::from(partition_table, self.replication_strategy)
+    }
+
+    fn logs(&self) -> Logs {
+        let mut builder = Logs::default().into_builder();
+        builder.set_configuration(LogsConfiguration {
+            default_provider: Some(self.log_provider),
+            providers: Default::default(),
+        });
+        builder.build()
+    }
+
+    fn check_nodes_configuration(&self, nodes_config: &NodesConfiguration) -> Result<(), String> {
+        if nodes_config.cluster_name() != self.cluster_name {
+            return Err(format!(
+                "cluster name is '{}' instead of '{}'",
+                nodes_config.cluster_name(),
+                self.cluster_name
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_partition_table(&self, partition_table: &PartitionTable) -> Result<(), String> {
+        // Splits add partitions to the table, hence only the partitions which haven't been split
+        // off from another one are compared against the configured number of partitions.
+        let num_unsplit_partitions = partition_table
+            .partitions()
+            .filter(|(_, partition)| partition.split_from.is_none())
+            .count();
+        if num_unsplit_partitions != usize::from(self.num_partitions) {
+            return Err(format!(
+                "it has {} partitions instead of {}, not counting split partitions",
+                num_unsplit_partitions, self.num_partitions
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_scheduling_plan(&self, scheduling_plan: &SchedulingPlan) -> Result<(), String> {
+        // The plan is filled in by the cluster controller if it started before the provisioning
+        match scheduling_plan.iter().find(|(_, target_state)| {
+            target_state.replication_strategy != self.replication_strategy
+        }) {
+            Some((partition_id, target_state)) => Err(format!(
+                "partition {partition_id} is replicated with {:?} instead of {:?}",
+                target_state.replication_strategy, self.replication_strategy
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_logs(&self, logs: &Logs) -> Result<(), String> {
+        match logs.configuration().default_provider {
+            Some(provider) if provider != self.log_provider => Err(format!(
+                "the default log provider is '{provider}' instead of '{}'",
+                self.log_provider
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Provisions the metadata of the cluster which doesn't exist yet. It is safe to provision an
+/// already provisioned cluster again, as long as the existing metadata is compatible with the
+/// requested one. The existing metadata is checked before writing anything, so that an
+/// incompatible cluster is left untouched.
+pub async fn provision_cluster(
+    metadata_store_client: &MetadataStoreClient,
+    provisioning: &ClusterProvisioning,
+    retry_policy: RetryPolicy,
+) -> Result<(), ProvisionError> {
+    let nodes_config =
+        get::<NodesConfiguration>(metadata_store_client, &NODES_CONFIG_KEY, &retry_policy).await?;
+    let partition_table =
+        get::<PartitionTable>(metadata_store_client, &PARTITION_TABLE_KEY, &retry_policy).await?;
+    let scheduling_plan =
+        get::<SchedulingPlan>(metadata_store_client, &SCHEDULING_PLAN_KEY, &retry_policy).await?;
+    let logs = get::<Logs>(metadata_store_client, &BIFROST_CONFIG_KEY, &retry_policy).await?;
+
+    check("nodes configuration", nodes_config.as_ref(), |v| {
+        provisioning.check_nodes_configuration(v)
+    })?;
+    check("partition table", partition_table.as_ref(), |v| {
+        provisioning.check_partition_table(v)
+    })?;
+    check("scheduling plan", scheduling_plan.as_ref(), |v| {
+        provisioning.check_scheduling_plan(v)
+    })?;
+    check("logs metadata", logs.as_ref(), |v| {
+        provisioning.check_logs(v)
+    })?;
+
+    // Another node might provision the cluster concurrently, hence the values are checked again
+    let nodes_config = retry_on_network_error(retry_policy.clone(), || {
+        metadata_store_client.get_or_insert(NODES_CONFIG_KEY.clone(), || {
+            provisioning.nodes_configuration()
+        })
+    })
+    .await?;
+    check("nodes configuration", Some(&nodes_config), |v| {
+        provisioning.check_nodes_configuration(v)
+    })?;
+
+    let partition_table = retry_on_network_error(retry_policy.clone(), || {
+        metadata_store_client.get_or_insert(PARTITION_TABLE_KEY.clone(), || {
+            provisioning.partition_table()
+        })
+    })
+    .await?;
+    check("partition table", Some(&partition_table), |v| {
+        provisioning.check_partition_table(v)
+    })?;
+
+    let scheduling_plan = retry_on_network_error(retry_policy.clone(), || {
+        metadata_store_client.get_or_insert(SCHEDULING_PLAN_KEY.clone(), || {
+            provisioning.scheduling_plan(&partition_table)
+        })
+    })
+    .await?;
+    check("scheduling plan", Some(&scheduling_plan), |v| {
+        provisioning.check_scheduling_plan(v)
+    })?;
+
+    let logs = retry_on_network_error(retry_policy, || {
+        metadata_store_client.get_or_insert(BIFROST_CONFIG_KEY.clone(), || provisioning.logs())
+    })
+    .await?;
+    check("logs metadata", Some(&logs), |v| provisioning.check_logs(v))?;
+
+    info!(
+        "Cluster '{}' is provisioned with {} partitions",
+        provisioning.cluster_name,
+        partition_table.num_partitions()
+    );
+    Ok(())
+}
+
+async fn get<T: Versioned + StorageDecode>(
+    metadata_store_client: &MetadataStoreClient,
+    key: &ByteString,
+    retry_policy: &RetryPolicy,
+) -> Result<Option<T>, ReadWriteError> {
+    retry_on_network_error(retry_policy.clone(), || async {
+        metadata_store_client
+            .get::<T>(key.clone())
+            .await
+            .map_err(ReadWriteError::from)
+    })
+    .await
+}
+
+fn check<T>(
+    metadata: &'static str,
+    value: Option<&T>,
+    check: impl FnOnce(&T) -> Result<(), String>,
+) -> Result<(), ProvisionError> {
+    match value {
+        Some(value) => {
+            check(value).map_err(|reason| ProvisionError::Incompatible { metadata, reason })
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroU32;
+
+    use restate_types::identifiers::PartitionId;
+    use restate_types::partition_table::PartitionTableBuilder;
+
+    fn provisioning() -> ClusterProvisioning {
+        ClusterProvisioning {
+            cluster_name: "test-cluster".to_owned(),
+            num_partitions: 4,
+            replication_strategy: ReplicationStrategy::OnAllNodes,
+            log_provider: ProviderKind::Local,
+        }
+    }
+
+    #[test]
+    fn provisioned_metadata_is_compatible() {
+        let provisioning = provisioning();
+
+        assert!(provisioning
+            .check_nodes_configuration(&provisioning.nodes_configuration())
+            .is_ok());
+        assert!(provisioning
+            .check_partition_table(&provisioning.partition_table())
+            .is_ok());
+        assert!(provisioning
+            .check_scheduling_plan(&provisioning.scheduling_plan(&provisioning.partition_table()))
+            .is_ok());
+        assert!(provisioning.check_logs(&provisioning.logs()).is_ok());
+    }
+
+    #[test]
+    fn detect_incompatible_metadata() {
+        let provisioning = provisioning();
+        let other = ClusterProvisioning {
+            cluster_name: "other-cluster".to_owned(),
+            num_partitions: 8,
+            replication_strategy: ReplicationStrategy::Factor(NonZeroU32::new(2).unwrap()),
+            log_provider: ProviderKind::Replicated,
+        };
+
+        assert!(provisioning
+            .check_nodes_configuration(&other.nodes_configuration())
+            .is_err());
+        assert!(provisioning
+            .check_partition_table(&other.partition_table())
+            .is_err());
+        assert!(provisioning
+            .check_scheduling_plan(&other.scheduling_plan(&other.partition_table()))
+            .is_err());
+        assert!(provisioning.check_logs(&other.logs()).is_err());
+    }
+
+    #[test]
+    fn split_partitions_are_compatible() {
+        let provisioning = provisioning();
+        let mut builder = PartitionTableBuilder::from(provisioning.partition_table());
+        let child = builder.allocate_partition_id().unwrap();
+        builder
+            .split_partition(PartitionId::MIN, 1024, child)
+            .unwrap();
+
+        assert!(provisioning.check_partition_table(&builder.build()).is_ok());
+    }
+}
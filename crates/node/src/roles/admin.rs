@@ -15,6 +15,7 @@ use restate_admin::cluster_controller;
 use restate_admin::service::AdminService;
 use restate_bifrost::Bifrost;
 use restate_core::metadata_store::MetadataStoreClient;
+use restate_core::network::failure_detector::ClusterLiveness;
 use restate_core::network::MessageRouterBuilder;
 use restate_core::network::NetworkServerBuilder;
 use restate_core::network::Networking;
@@ -66,6 +67,7 @@ impl<T: TransportConnect> AdminRole<T> {
         updateable_config: Live<Configuration>,
         partition_routing: PartitionRouting,
         networking: Networking<T>,
+        cluster_liveness: ClusterLiveness,
         metadata: Metadata,
         metadata_writer: MetadataWriter,
         server_builder: &mut NetworkServerBuilder,
@@ -87,7 +89,7 @@ impl<T: TransportConnect> AdminRole<T> {
         } else {
             let remote_scanner_manager = RemoteScannerManager::new(
                 create_remote_scanner_service(networking.clone(), router_builder),
-                create_partition_locator(partition_routing, metadata.clone()),
+                create_partition_locator(partition_routing, metadata.clone(), None),
             );
 
             // need to create a remote query context since we are not co-located with a worker role
@@ -118,6 +120,7 @@ impl<T: TransportConnect> AdminRole<T> {
                 health_status,
                 bifrost,
                 networking,
+                cluster_liveness,
                 router_builder,
                 server_builder,
                 metadata_writer,
@@ -8,6 +8,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Instant;
+
 use anyhow::Context;
 use futures::StreamExt;
 
@@ -17,11 +19,14 @@ use restate_core::{
     worker_api::ProcessorsManagerHandle,
     ShutdownError, TaskCenter, TaskKind,
 };
+use restate_types::cluster::cluster_state::NodeLoad;
+use restate_types::config::Configuration;
 use restate_types::net::node::{GetNodeState, NodeStateResponse};
 
 pub struct BaseRole {
     processor_manager_handle: Option<ProcessorsManagerHandle>,
     incoming_node_state: MessageStream<GetNodeState>,
+    cpu_usage: CpuUsage,
 }
 
 impl BaseRole {
@@ -34,6 +39,7 @@ impl BaseRole {
         Self {
             processor_manager_handle,
             incoming_node_state,
+            cpu_usage: CpuUsage::default(),
         }
     }
 
@@ -65,7 +71,7 @@ impl BaseRole {
     }
 
     async fn handle_get_node_state(
-        &self,
+        &mut self,
         msg: Incoming<GetNodeState>,
     ) -> Result<(), ShutdownError> {
         let partition_state = if let Some(ref handle) = self.processor_manager_handle {
@@ -78,6 +84,10 @@ impl BaseRole {
         if let Err(NetworkError::Shutdown(err)) = msg
             .to_rpc_response(NodeStateResponse {
                 partition_processor_state: partition_state,
+                load: NodeLoad {
+                    capacity: Configuration::pinned().worker.capacity(),
+                    cpu_usage: self.cpu_usage.sample(),
+                },
             })
             .try_send()
             .map_err(|err| err.source)
@@ -88,3 +98,46 @@ impl BaseRole {
         Ok(())
     }
 }
+
+/// CPU usage of the cgroup of this process, computed from the CPU time consumed between two
+/// samples. Unlike the load average, it only accounts for the CPUs this node is allowed to use,
+/// which matters when running in containers.
+#[derive(Default)]
+struct CpuUsage {
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl CpuUsage {
+    /// Returns the fraction of the available CPUs which was busy since the previous sample.
+    fn sample(&mut self) -> Option<f32> {
+        let now = Instant::now();
+        let usage_usec = cgroup_cpu_usage_usec()?;
+        let (last_sampled_at, last_usage_usec) = self.last_sample.replace((now, usage_usec))?;
+
+        let elapsed_usec = now.duration_since(last_sampled_at).as_micros() as f64;
+        // std takes the cgroup CPU quota into account
+        let cpus = std::thread::available_parallelism().ok()?.get() as f64;
+        if elapsed_usec == 0.0 {
+            return None;
+        }
+        let busy_usec = usage_usec.saturating_sub(last_usage_usec) as f64;
+        Some((busy_usec / (elapsed_usec * cpus)) as f32)
+    }
+}
+
+/// CPU time consumed by the cgroup of this process, read from cgroup v2 and falling back to
+/// cgroup v1.
+fn cgroup_cpu_usage_usec() -> Option<u64> {
+    if let Ok(cpu_stat) = std::fs::read_to_string("/sys/fs/cgroup/cpu.stat") {
+        return cpu_stat
+            .lines()
+            .find_map(|line| line.strip_prefix("usage_usec "))
+            .and_then(|usage| usage.trim().parse().ok());
+    }
+    let usage_nsec: u64 = std::fs::read_to_string("/sys/fs/cgroup/cpuacct/cpuacct.usage")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(usage_nsec / 1_000)
+}
@@ -14,7 +14,7 @@ use restate_core::network::{MessageRouterBuilder, Networking, TransportConnect};
 use restate_core::partitions::PartitionRouting;
 use restate_ingress_http::rpc_request_dispatcher::RpcRequestDispatcher;
 use restate_ingress_http::HyperServerIngress;
-use restate_types::config::IngressOptions;
+use restate_types::config::{IngressOptions, IngressRateLimitOptions};
 use restate_types::health::HealthStatus;
 use restate_types::live::{BoxedLiveLoad, Live};
 use restate_types::partition_table::PartitionTable;
@@ -30,6 +30,7 @@ pub struct IngressRole<T> {
 impl<T: TransportConnect> IngressRole<T> {
     pub fn create(
         mut ingress_options: BoxedLiveLoad<IngressOptions>,
+        rate_limits: BoxedLiveLoad<IngressRateLimitOptions>,
         health: HealthStatus<IngressStatus>,
         networking: Networking<T>,
         schema: Live<Schema>,
@@ -50,7 +51,8 @@ impl<T: TransportConnect> IngressRole<T> {
             dispatcher,
             schema,
             health,
-        );
+        )
+        .with_rate_limits(rate_limits);
 
         Self { ingress_http }
     }
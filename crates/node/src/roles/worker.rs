@@ -20,6 +20,7 @@ use restate_core::TaskCenter;
 use restate_core::{cancellation_watcher, Metadata, MetadataKind};
 use restate_core::{ShutdownError, TaskKind};
 use restate_metadata_store::MetadataStoreClient;
+use restate_partition_store::PartitionStoreManager;
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::config::Configuration;
 use restate_types::health::HealthStatus;
@@ -99,6 +100,10 @@ impl WorkerRole {
         self.worker.partition_processor_manager_handle()
     }
 
+    pub fn partition_store_manager(&self) -> PartitionStoreManager {
+        self.worker.partition_store_manager()
+    }
+
     pub fn storage_query_context(&self) -> &QueryContext {
         self.worker.storage_query_context()
     }
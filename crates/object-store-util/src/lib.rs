@@ -0,0 +1,101 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! This crate contains helpers to create object store clients from Restate configuration.
+
+use std::sync::Arc;
+
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use url::Url;
+
+use restate_types::config::ObjectStoreOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid object store destination '{0}': {1}")]
+    InvalidDestination(String, String),
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+}
+
+/// An object store client together with the key prefix under which all objects of a particular
+/// destination live.
+#[derive(Clone, Debug)]
+pub struct ObjectStoreDestination {
+    pub object_store: Arc<dyn ObjectStore>,
+    pub prefix: Path,
+}
+
+impl ObjectStoreDestination {
+    /// Creates an object store client for the given destination URL. Supported schemes are `s3://`,
+    /// `gs://` and `file://`. The path component of the URL is used as key prefix.
+    pub fn create(destination: &str, options: &ObjectStoreOptions) -> Result<Self, Error> {
+        let url = Url::parse(destination)
+            .map_err(|e| Error::InvalidDestination(destination.to_owned(), e.to_string()))?;
+
+        let object_store: Arc<dyn ObjectStore> = match url.scheme() {
+            "s3" => {
+                let mut builder = AmazonS3Builder::from_env().with_url(url.as_str());
+                if let Some(region) = &options.aws_region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(access_key_id) = &options.aws_access_key_id {
+                    builder = builder.with_access_key_id(access_key_id);
+                }
+                if let Some(secret_access_key) = &options.aws_secret_access_key {
+                    builder = builder.with_secret_access_key(secret_access_key);
+                }
+                if let Some(session_token) = &options.aws_session_token {
+                    builder = builder.with_token(session_token);
+                }
+                if let Some(endpoint_url) = &options.aws_endpoint_url {
+                    builder = builder.with_endpoint(endpoint_url);
+                }
+                if let Some(allow_http) = options.aws_allow_http {
+                    builder = builder.with_allow_http(allow_http);
+                }
+                Arc::new(builder.build()?)
+            }
+            "gs" => {
+                let mut builder = GoogleCloudStorageBuilder::from_env().with_url(url.as_str());
+                if let Some(service_account_path) = &options.gcs_service_account_path {
+                    builder = builder.with_service_account_path(service_account_path);
+                }
+                Arc::new(builder.build()?)
+            }
+            "file" => {
+                let (object_store, prefix) = object_store::parse_url(&url)?;
+                return Ok(Self {
+                    object_store: Arc::from(object_store),
+                    prefix,
+                });
+            }
+            scheme => {
+                return Err(Error::InvalidDestination(
+                    destination.to_owned(),
+                    format!("unsupported scheme '{}'", scheme),
+                ))
+            }
+        };
+
+        Ok(Self {
+            object_store,
+            prefix: Path::from(url.path()),
+        })
+    }
+
+    /// Returns the full object path for the given key relative to the destination prefix.
+    pub fn path(&self, key: &str) -> Path {
+        Path::from(format!("{}/{}", self.prefix, key))
+    }
+}
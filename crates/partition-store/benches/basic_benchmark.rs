@@ -10,19 +10,28 @@
 
 use std::ops::RangeInclusive;
 
+use bytes::Bytes;
 use criterion::{criterion_group, criterion_main, Criterion};
 use restate_core::TaskCenterBuilder;
 use restate_partition_store::{OpenMode, PartitionStore, PartitionStoreManager};
-use restate_rocksdb::RocksDbManager;
+use restate_rocksdb::{DbName, RocksDbManager};
 use restate_storage_api::deduplication_table::{
     DedupSequenceNumber, DeduplicationTable, ProducerId,
 };
+use restate_storage_api::journal_table::{JournalEntry, JournalTable, ReadOnlyJournalTable};
+use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
 use restate_storage_api::Transaction;
 use restate_types::config::{CommonOptions, WorkerOptions};
-use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::identifiers::{InvocationId, PartitionId, PartitionKey, ServiceId};
+use restate_types::journal::enriched::{EnrichedEntryHeader, EnrichedRawEntry};
 use restate_types::live::Constant;
+use rocksdb::statistics::Ticker;
 use tokio::runtime::Builder;
 
+const NUM_SERVICE_KEYS: usize = 10_000;
+const NUM_STATE_ENTRIES: usize = 4;
+const NUM_JOURNAL_ENTRIES: u32 = 4;
+
 async fn writing_to_rocksdb(mut rocksdb: PartitionStore) {
     //
     // write
@@ -38,6 +47,75 @@ async fn writing_to_rocksdb(mut rocksdb: PartitionStore) {
     txn.commit().await.unwrap();
 }
 
+fn service_id(i: usize) -> ServiceId {
+    ServiceId::new("Counter", format!("key-{i}"))
+}
+
+fn journal_entry() -> JournalEntry {
+    JournalEntry::Entry(EnrichedRawEntry::new(
+        EnrichedEntryHeader::ClearState {},
+        Bytes::from_static(b"payload"),
+    ))
+}
+
+/// Writes state and journals spread over many partition keys and flushes them, so that lookups
+/// have to go through the SST files rather than the memtables.
+async fn populate_state_and_journals(mut rocksdb: PartitionStore, invocation_ids: &[InvocationId]) {
+    let mut txn = rocksdb.transaction();
+    for i in 0..NUM_SERVICE_KEYS {
+        let service_id = service_id(i);
+        for j in 0..NUM_STATE_ENTRIES {
+            txn.put_user_state(&service_id, format!("state-{j}"), b"value")
+                .await;
+        }
+    }
+    for invocation_id in invocation_ids {
+        for index in 0..NUM_JOURNAL_ENTRIES {
+            txn.put_journal_entry(invocation_id, index, &journal_entry())
+                .await;
+        }
+    }
+    txn.commit().await.unwrap();
+    rocksdb.flush_memtables(true).await.unwrap();
+}
+
+async fn state_point_lookups(mut rocksdb: PartitionStore, existing: bool) {
+    let state_key = if existing { "state-0" } else { "absent" };
+    for i in (0..NUM_SERVICE_KEYS).step_by(10) {
+        let state = rocksdb
+            .get_user_state(&service_id(i), state_key)
+            .await
+            .unwrap();
+        assert_eq!(existing, state.is_some());
+    }
+}
+
+async fn journal_lookups(mut rocksdb: PartitionStore, invocation_ids: &[InvocationId]) {
+    for invocation_id in invocation_ids.iter().step_by(10) {
+        let entry = rocksdb
+            .get_journal_entry(invocation_id, NUM_JOURNAL_ENTRIES - 1)
+            .await
+            .unwrap();
+        assert!(entry.is_some());
+    }
+}
+
+fn report_filter_statistics(name: &str) {
+    // statistics are shared by all column families of the partition store database
+    let db = RocksDbManager::get()
+        .get_db(DbName::new("db"))
+        .expect("partition store db exists");
+    println!(
+        "{name}: bloom filter useful={}, prefix checked={}, prefix useful={}, full positive={}, \
+         block cache data misses={}",
+        db.get_ticker_count(Ticker::BloomFilterUseful),
+        db.get_ticker_count(Ticker::BloomFilterPrefixChecked),
+        db.get_ticker_count(Ticker::BloomFilterPrefixUseful),
+        db.get_ticker_count(Ticker::BloomFilterFullPositive),
+        db.get_ticker_count(Ticker::BlockCacheDataMiss),
+    );
+}
+
 fn basic_writing_reading_benchmark(c: &mut Criterion) {
     let rt = Builder::new_multi_thread().enable_all().build().unwrap();
 
@@ -79,6 +157,41 @@ fn basic_writing_reading_benchmark(c: &mut Criterion) {
             .iter(|| writing_to_rocksdb(rocksdb.clone()));
     });
 
+    let invocation_ids: Vec<_> = (0..NUM_SERVICE_KEYS)
+        .map(|_| InvocationId::mock_random())
+        .collect();
+    tc.block_on(populate_state_and_journals(
+        rocksdb.clone(),
+        &invocation_ids,
+    ));
+
+    group
+        .sample_size(10)
+        .bench_function("state-lookup-hit", |bencher| {
+            bencher
+                .to_async(&rt)
+                .iter(|| state_point_lookups(rocksdb.clone(), true));
+        });
+    report_filter_statistics("state-lookup-hit");
+
+    group
+        .sample_size(10)
+        .bench_function("state-lookup-miss", |bencher| {
+            bencher
+                .to_async(&rt)
+                .iter(|| state_point_lookups(rocksdb.clone(), false));
+        });
+    report_filter_statistics("state-lookup-miss");
+
+    group
+        .sample_size(10)
+        .bench_function("journal-lookup", |bencher| {
+            bencher
+                .to_async(&rt)
+                .iter(|| journal_lookups(rocksdb.clone(), &invocation_ids));
+        });
+    report_filter_statistics("journal-lookup");
+
     group.finish();
     rt.block_on(tc.shutdown_node("completed", 0));
     rt.block_on(RocksDbManager::get().shutdown());
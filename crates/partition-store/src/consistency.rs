@@ -0,0 +1,295 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Cross-validation of the invariants between the tables of a partition, which the partition
+//! processor relies on but never checks, e.g. that every inbox entry points at an inboxed
+//! invocation.
+
+use std::ops::RangeInclusive;
+
+use futures::{future, TryStreamExt};
+
+use restate_storage_api::consistency::ConsistencyViolation;
+use restate_storage_api::idempotency_table::ReadOnlyIdempotencyTable;
+use restate_storage_api::inbox_table::{InboxEntry, ReadOnlyInboxTable};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatusKind, JournalVersion, ReadOnlyInvocationStatusTable,
+};
+use restate_storage_api::journal_table_v2::ReadOnlyJournalTableV2;
+use restate_storage_api::service_status_table::{
+    ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus,
+};
+use restate_storage_api::Result;
+use restate_types::identifiers::{EntryIndex, InvocationId, PartitionKey, WithPartitionKey};
+use restate_types::invocation::{
+    InvocationTargetType, VirtualObjectHandlerType, WorkflowHandlerType,
+};
+
+use crate::journal_table::JournalKey;
+use crate::journal_table_v2::{JournalPendingCompletionKey, JournalV2Key};
+use crate::keys::TableRecord;
+use crate::scan::{ScanDirection, TableScan};
+use crate::PartitionStore;
+
+impl PartitionStore {
+    /// Checks the invariants between the tables within the given key range:
+    ///
+    /// * The journal of every invoked or suspended invocation has the length declared by its
+    ///   status.
+    /// * Every entry of the pending completions index of the journal v2 table points at a journal
+    ///   entry awaiting the completion.
+    /// * Every inbox entry points at an inboxed invocation.
+    /// * Every idempotency entry points at an existing invocation.
+    /// * Every locked virtual object or workflow is locked by an invocation running on it, and
+    ///   every running invocation of an exclusive handler holds the lock of its virtual object.
+    ///
+    /// The check reads the tables one after the other without a consistent view, hence it can
+    /// report spurious violations while the partition processor is applying commands. Violations
+    /// should therefore be confirmed by checking again.
+    pub async fn check_consistency(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> Result<Vec<ConsistencyViolation>> {
+        let mut violations = Vec::new();
+        self.check_running_invocations(range.clone(), &mut violations)
+            .await?;
+        self.check_pending_completions(range.clone(), &mut violations)
+            .await?;
+        self.check_inboxes(range.clone(), &mut violations).await?;
+        self.check_idempotency_keys(range.clone(), &mut violations)
+            .await?;
+        self.check_service_locks(range, &mut violations).await?;
+        Ok(violations)
+    }
+
+    async fn check_running_invocations(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        violations: &mut Vec<ConsistencyViolation>,
+    ) -> Result<()> {
+        let mut lookup = self.clone();
+        let mut statuses = std::pin::pin!(self.all_invocation_statuses(range));
+        while let Some((invocation_id, status)) = statuses.try_next().await? {
+            let Some(metadata) = status.get_invocation_metadata() else {
+                continue;
+            };
+
+            let expected_length = metadata.journal_metadata.length;
+            let actual_length = match metadata.journal_metadata.version {
+                JournalVersion::V1 => self.journal_length::<JournalKey>(&invocation_id).await?,
+                JournalVersion::V2 => self.journal_length::<JournalV2Key>(&invocation_id).await?,
+            };
+            if actual_length != expected_length {
+                violations.push(ConsistencyViolation::JournalLengthMismatch {
+                    invocation_id,
+                    expected_length,
+                    actual_length,
+                });
+            }
+
+            if metadata.invocation_target.invocation_target_ty()
+                == InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Exclusive)
+            {
+                let service_id = metadata
+                    .invocation_target
+                    .as_keyed_service_id()
+                    .expect("Virtual object invocations must have a keyed service id");
+                let locked_by = match lookup.get_virtual_object_status(&service_id).await? {
+                    VirtualObjectStatus::Locked(locked_by) => Some(locked_by),
+                    VirtualObjectStatus::Unlocked => None,
+                };
+                if locked_by != Some(invocation_id) {
+                    violations.push(ConsistencyViolation::MissingServiceLock {
+                        service_id,
+                        invocation_id,
+                        locked_by,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of consecutive journal entries of the invocation, starting from the
+    /// first one.
+    async fn journal_length<K: JournalIndexKey>(
+        &self,
+        invocation_id: &InvocationId,
+    ) -> Result<EntryIndex> {
+        let entries = self.scan_table(
+            TableScan::SinglePartitionKeyPrefix(
+                invocation_id.partition_key(),
+                K::invocation_prefix(invocation_id),
+            ),
+            ScanDirection::Forward,
+            None,
+        );
+        let mut entries = std::pin::pin!(entries);
+
+        let mut length = 0;
+        while let Some((key, _)) = entries.try_next().await? {
+            if key.journal_index() != Some(length) {
+                break;
+            }
+            length += 1;
+        }
+        Ok(length)
+    }
+
+    async fn check_pending_completions(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        violations: &mut Vec<ConsistencyViolation>,
+    ) -> Result<()> {
+        let mut lookup = self.clone();
+        let mut pending_completions = std::pin::pin!(self.scan_table(
+            TableScan::FullScanPartitionKeyRange::<JournalPendingCompletionKey>(range),
+            ScanDirection::Forward,
+            None,
+        ));
+        while let Some((key, pending_completion)) = pending_completions.try_next().await? {
+            let (partition_key, invocation_uuid, completion_id) = key.into_inner_ok_or()?;
+            let invocation_id = InvocationId::from_parts(partition_key, invocation_uuid);
+            let journal_index = pending_completion.journal_index;
+            let awaits_completion = lookup
+                .get_journal_entry(&invocation_id, journal_index)
+                .await?
+                .is_some_and(|entry| {
+                    entry.completion_id == Some(completion_id) && entry.is_pending()
+                });
+            if !awaits_completion {
+                violations.push(ConsistencyViolation::DanglingPendingCompletion {
+                    invocation_id,
+                    completion_id,
+                    journal_index,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_inboxes(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        violations: &mut Vec<ConsistencyViolation>,
+    ) -> Result<()> {
+        let mut lookup = self.clone();
+        let mut entries = std::pin::pin!(self.all_inboxes(range));
+        while let Some(entry) = entries.try_next().await? {
+            let InboxEntry::Invocation(service_id, invocation_id, _) = entry.inbox_entry else {
+                continue;
+            };
+            if lookup
+                .get_invocation_status_view(&invocation_id)
+                .await?
+                .kind
+                != InvocationStatusKind::Inboxed
+            {
+                violations.push(ConsistencyViolation::OrphanedInboxEntry {
+                    service_id,
+                    inbox_sequence_number: entry.inbox_sequence_number,
+                    invocation_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_idempotency_keys(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        violations: &mut Vec<ConsistencyViolation>,
+    ) -> Result<()> {
+        let mut lookup = self.clone();
+        let mut entries = std::pin::pin!(self.all_idempotency_metadata(range));
+        while let Some((idempotency_id, metadata)) = entries.try_next().await? {
+            if lookup
+                .get_invocation_status_view(&metadata.invocation_id)
+                .await?
+                .is_free()
+            {
+                violations.push(ConsistencyViolation::DanglingIdempotencyKey {
+                    idempotency_id,
+                    invocation_id: metadata.invocation_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_service_locks(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+        violations: &mut Vec<ConsistencyViolation>,
+    ) -> Result<()> {
+        let mut lookup = self.clone();
+        let mut locks = std::pin::pin!(self.all_virtual_object_statuses(range).try_filter_map(
+            |(service_id, status)| {
+                future::ready(Ok(match status {
+                    VirtualObjectStatus::Locked(invocation_id) => Some((service_id, invocation_id)),
+                    VirtualObjectStatus::Unlocked => None,
+                }))
+            }
+        ));
+        while let Some((service_id, invocation_id)) = locks.try_next().await? {
+            let view = lookup.get_invocation_status_view(&invocation_id).await?;
+            let holds_lock = match &view.invocation_target {
+                Some(target) if target.as_keyed_service_id().as_ref() == Some(&service_id) => {
+                    // Workflow runs keep the lock until they are purged
+                    target.invocation_target_ty()
+                        == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+                        || matches!(
+                            view.kind,
+                            InvocationStatusKind::Invoked | InvocationStatusKind::Suspended
+                        )
+                }
+                _ => false,
+            };
+            if !holds_lock {
+                violations.push(ConsistencyViolation::DanglingServiceLock {
+                    service_id,
+                    invocation_id,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keys of the journal tables, which share the layout of their invocation prefix.
+trait JournalIndexKey: TableRecord + Send {
+    fn invocation_prefix(invocation_id: &InvocationId) -> Self;
+
+    fn journal_index(&self) -> Option<EntryIndex>;
+}
+
+impl JournalIndexKey for JournalKey {
+    fn invocation_prefix(invocation_id: &InvocationId) -> Self {
+        JournalKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid())
+    }
+
+    fn journal_index(&self) -> Option<EntryIndex> {
+        self.journal_index
+    }
+}
+
+impl JournalIndexKey for JournalV2Key {
+    fn invocation_prefix(invocation_id: &InvocationId) -> Self {
+        JournalV2Key::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid())
+    }
+
+    fn journal_index(&self) -> Option<EntryIndex> {
+        self.journal_index
+    }
+}
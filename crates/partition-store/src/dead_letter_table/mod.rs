@@ -0,0 +1,126 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future;
+use std::ops::RangeInclusive;
+
+use futures::{Stream, TryStreamExt};
+
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::dead_letter_table::{
+    DeadLetter, DeadLetterTable, ReadOnlyDeadLetterTable,
+};
+use restate_storage_api::Result;
+use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::logs::Lsn;
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection};
+use crate::TableKind;
+use crate::TableScan;
+use crate::{PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess};
+
+define_table_key!(
+    TableKind::DeadLetter,
+    KeyKind::DeadLetter,
+    DeadLetterKey(partition_id: PaddedPartitionId, lsn: u64)
+);
+impl_table_record!(DeadLetterKey, DeadLetter);
+
+fn dead_letter_key(partition_id: PartitionId, lsn: Lsn) -> DeadLetterKey {
+    DeadLetterKey::default()
+        .partition_id(partition_id.into())
+        .lsn(lsn.into())
+}
+
+fn put_dead_letter<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    dead_letter: &DeadLetter,
+) {
+    storage.put_kv(dead_letter_key(partition_id, dead_letter.lsn), dead_letter);
+}
+
+fn get_dead_letter<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    lsn: Lsn,
+) -> Result<Option<DeadLetter>> {
+    let _x = RocksDbPerfGuard::new("get-dead-letter");
+    storage.get_value(dead_letter_key(partition_id, lsn))
+}
+
+fn delete_dead_letter<S: StorageAccess>(storage: &mut S, partition_id: PartitionId, lsn: Lsn) {
+    storage.delete_key(&dead_letter_key(partition_id, lsn));
+}
+
+fn all_dead_letters<S: StorageAccess>(
+    storage: &S,
+    partition_id: PartitionId,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<DeadLetter>> + Send + '_ {
+    scan_table(
+        storage,
+        TableScan::SinglePartition::<DeadLetterKey>(partition_id),
+        ScanDirection::Forward,
+        None,
+    )
+    .try_filter_map(move |(_, dead_letter)| {
+        future::ready(Ok(range
+            .contains(&dead_letter.partition_key)
+            .then_some(dead_letter)))
+    })
+}
+
+impl ReadOnlyDeadLetterTable for PartitionStore {
+    async fn get_dead_letter(&mut self, lsn: Lsn) -> Result<Option<DeadLetter>> {
+        get_dead_letter(self, self.partition_id(), lsn)
+    }
+
+    fn all_dead_letters(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<DeadLetter>> + Send {
+        all_dead_letters(self, self.partition_id(), range)
+    }
+}
+
+impl DeadLetterTable for PartitionStore {
+    async fn put_dead_letter(&mut self, dead_letter: &DeadLetter) {
+        put_dead_letter(self, self.partition_id(), dead_letter)
+    }
+
+    async fn delete_dead_letter(&mut self, lsn: Lsn) {
+        delete_dead_letter(self, self.partition_id(), lsn)
+    }
+}
+
+impl<'a> ReadOnlyDeadLetterTable for PartitionStoreTransaction<'a> {
+    async fn get_dead_letter(&mut self, lsn: Lsn) -> Result<Option<DeadLetter>> {
+        get_dead_letter(self, self.partition_id(), lsn)
+    }
+
+    fn all_dead_letters(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<DeadLetter>> + Send {
+        all_dead_letters(self, self.partition_id(), range)
+    }
+}
+
+impl<'a> DeadLetterTable for PartitionStoreTransaction<'a> {
+    async fn put_dead_letter(&mut self, dead_letter: &DeadLetter) {
+        put_dead_letter(self, self.partition_id(), dead_letter)
+    }
+
+    async fn delete_dead_letter(&mut self, lsn: Lsn) {
+        delete_dead_letter(self, self.partition_id(), lsn)
+    }
+}
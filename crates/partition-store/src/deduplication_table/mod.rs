@@ -22,7 +22,7 @@ use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::PartitionId;
 use restate_types::storage::StorageCodec;
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
 use crate::TableKind::Deduplication;
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
@@ -34,6 +34,7 @@ define_table_key!(
     KeyKind::Deduplication,
     DeduplicationKey(partition_id: PaddedPartitionId, producer_id: ProducerId)
 );
+impl_table_record!(DeduplicationKey, DedupSequenceNumber);
 
 fn get_dedup_sequence_number<S: StorageAccess>(
     storage: &mut S,
@@ -8,21 +8,18 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
-use crate::owned_iter::OwnedIterator;
-use crate::scan::TableScan;
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection, TableScan};
 use crate::{PartitionStore, TableKind};
 use crate::{PartitionStoreTransaction, StorageAccess};
 use bytes::Bytes;
 use bytestring::ByteString;
-use futures::Stream;
-use futures_util::stream;
+use futures::{Stream, StreamExt};
 use restate_storage_api::idempotency_table::{
     IdempotencyMetadata, IdempotencyTable, ReadOnlyIdempotencyTable,
 };
 use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{IdempotencyId, PartitionKey, WithPartitionKey};
-use restate_types::storage::StorageCodec;
 use std::ops::RangeInclusive;
 
 define_table_key!(
@@ -36,6 +33,7 @@ define_table_key!(
         idempotency_key: ByteString
     )
 );
+impl_table_record!(IdempotencyKey, IdempotencyMetadata);
 
 fn create_key(idempotency_id: &IdempotencyId) -> IdempotencyKey {
     IdempotencyKey::default()
@@ -64,13 +62,14 @@ fn all_idempotency_metadata<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(IdempotencyId, IdempotencyMetadata)>> + Send + '_ {
-    let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<IdempotencyKey>(
-        range,
-    ));
-    stream::iter(OwnedIterator::new(iter).map(|(mut k, mut v)| {
-        let key = IdempotencyKey::deserialize_from(&mut k)?;
-        let idempotency_metadata = StorageCodec::decode::<IdempotencyMetadata, _>(&mut v)
-            .map_err(|err| StorageError::Generic(err.into()))?;
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<IdempotencyKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (key, idempotency_metadata) = row?;
 
         Ok((
             IdempotencyId::new(
@@ -84,7 +83,7 @@ fn all_idempotency_metadata<S: StorageAccess>(
             ),
             idempotency_metadata,
         ))
-    }))
+    })
 }
 
 fn put_idempotency_metadata<S: StorageAccess>(
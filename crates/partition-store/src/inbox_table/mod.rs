@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
 use crate::TableKind::Inbox;
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess};
 use crate::{TableScan, TableScanIterationDecision};
@@ -21,6 +21,7 @@ use restate_storage_api::inbox_table::{
 };
 use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{PartitionKey, ServiceId, WithPartitionKey};
+use restate_types::invocation::InvocationPriority;
 use restate_types::message::MessageIndex;
 use restate_types::storage::StorageCodec;
 use std::future::Future;
@@ -37,18 +38,95 @@ define_table_key!(
         sequence_number: u64
     )
 );
+impl_table_record!(InboxKey, InboxEntry);
 
-fn peek_inbox<S: StorageAccess>(
+define_table_key!(
+    Inbox,
+    KeyKind::InboxOrder,
+    InboxOrderKey(
+        partition_key: PartitionKey,
+        service_name: ByteString,
+        service_key: ByteString,
+        rank: u64,
+        deadline: u64,
+        sequence_number: u64
+    )
+);
+
+define_table_key!(
+    Inbox,
+    KeyKind::PausedInbox,
+    PausedInboxKey(
+        partition_key: PartitionKey,
+        service_name: ByteString,
+        service_key: ByteString,
+        sequence_number: u64
+    )
+);
+impl_table_record!(PausedInboxKey, InboxEntry);
+
+fn paused_inbox_key(service_id: &ServiceId, sequence_number: MessageIndex) -> PausedInboxKey {
+    PausedInboxKey::default()
+        .partition_key(service_id.partition_key())
+        .service_name(service_id.service_name.clone())
+        .service_key(service_id.key.clone())
+        .sequence_number(sequence_number)
+}
+
+/// Returns the key under which the given entry is ordered within the inbox of its service.
+///
+/// State mutations are ranked before all invocations, ordered by sequence number, so that the
+/// first order key of an inbox tells the oldest state mutation. Invocations are ranked by
+/// [`InvocationPriority`] and sequence number.
+fn inbox_order_key(sequence_number: MessageIndex, inbox_entry: &InboxEntry) -> InboxOrderKey {
+    let service_id = inbox_entry.service_id();
+    let (rank, deadline) = match inbox_entry {
+        InboxEntry::StateMutation(_) => (0, 0),
+        InboxEntry::Invocation(_, _, priority) => (
+            // higher priorities rank first
+            1 + (i64::from(i32::MAX) - i64::from(priority.priority)) as u64,
+            priority
+                .deadline
+                .map_or(u64::MAX, |deadline| deadline.as_u64()),
+        ),
+    };
+
+    InboxOrderKey::default()
+        .partition_key(service_id.partition_key())
+        .service_name(service_id.service_name.clone())
+        .service_key(service_id.key.clone())
+        .rank(rank)
+        .deadline(deadline)
+        .sequence_number(sequence_number)
+}
+
+fn inbox_key(service_id: &ServiceId) -> InboxKey {
+    InboxKey::default()
+        .partition_key(service_id.partition_key())
+        .service_name(service_id.service_name.clone())
+        .service_key(service_id.key.clone())
+}
+
+fn get_inbox_entry<S: StorageAccess>(
     storage: &mut S,
     service_id: &ServiceId,
+    sequence_number: MessageIndex,
 ) -> Result<Option<SequenceNumberInboxEntry>> {
-    let key = InboxKey::default()
-        .partition_key(service_id.partition_key())
-        .service_name(service_id.service_name.clone())
-        .service_key(service_id.key.clone());
+    storage
+        .get_value::<_, InboxEntry>(inbox_key(service_id).sequence_number(sequence_number))
+        .map(|inbox_entry| {
+            inbox_entry
+                .map(|inbox_entry| SequenceNumberInboxEntry::new(sequence_number, inbox_entry))
+        })
+}
 
+/// Returns the oldest entry of the inbox.
+fn first_inbox_entry<S: StorageAccess>(
+    storage: &mut S,
+    service_id: &ServiceId,
+) -> Result<Option<SequenceNumberInboxEntry>> {
     storage.get_first_blocking(
-        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), key),
+        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), inbox_key(service_id)),
         |kv| match kv {
             Some((k, v)) => {
                 let entry = decode_inbox_key_value(k, v)?;
@@ -59,17 +137,102 @@ fn peek_inbox<S: StorageAccess>(
     )
 }
 
-fn inbox<S: StorageAccess>(
+/// Returns the entry of the inbox to process next. This is the oldest entry if it is a state
+/// mutation, otherwise the invocation with the highest [`InvocationPriority`] among the ones
+/// inboxed before the oldest state mutation. Invocations with equal priority are processed in
+/// order.
+///
+/// Without state mutations in the inbox, this takes two point lookups and two seeks. Only if the
+/// oldest entry is an invocation inboxed before a state mutation, the invocations up to the state
+/// mutation are scanned.
+fn next_inbox_entry<S: StorageAccess>(
     storage: &mut S,
     service_id: &ServiceId,
-) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
-    let key = InboxKey::default()
+) -> Result<Option<SequenceNumberInboxEntry>> {
+    let Some(first) = first_inbox_entry(storage, service_id)? else {
+        return Ok(None);
+    };
+    if let InboxEntry::StateMutation(_) = first.inbox_entry {
+        // invocations must not overtake state mutations and vice versa
+        return Ok(Some(first));
+    }
+
+    // Entries inboxed before the order keys were introduced are processed in order
+    let is_ordered = storage.get_kv_raw(
+        inbox_order_key(first.inbox_sequence_number, &first.inbox_entry),
+        |_, v| Ok(v.is_some()),
+    )?;
+    if !is_ordered {
+        return Ok(Some(first));
+    }
+
+    let order_key = InboxOrderKey::default()
         .partition_key(service_id.partition_key())
         .service_name(service_id.service_name.clone())
         .service_key(service_id.key.clone());
+    let next_ordered = storage.get_first_blocking(
+        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), order_key),
+        |kv| match kv {
+            Some((k, _)) => {
+                let key = InboxOrderKey::deserialize_from(&mut Cursor::new(k))?;
+                Ok(Some((*key.rank_ok_or()?, *key.sequence_number_ok_or()?)))
+            }
+            None => Ok(None),
+        },
+    )?;
+
+    match next_ordered {
+        Some((0, state_mutation_sequence_number)) => {
+            // The first entry is an invocation, hence it was inboxed before the oldest state
+            // mutation. Pick among the invocations inboxed before the state mutation.
+            next_invocation_before(storage, service_id, state_mutation_sequence_number)
+        }
+        Some((_, sequence_number)) => get_inbox_entry(storage, service_id, sequence_number),
+        None => Ok(Some(first)),
+    }
+}
+
+/// Returns the invocation with the highest [`InvocationPriority`] among the ones inboxed before
+/// the given sequence number.
+fn next_invocation_before<S: StorageAccess>(
+    storage: &S,
+    service_id: &ServiceId,
+    sequence_number: MessageIndex,
+) -> Result<Option<SequenceNumberInboxEntry>> {
+    let mut next: Option<(InvocationPriority, SequenceNumberInboxEntry)> = None;
+    let result = storage.for_each_key_value_in_place(
+        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), inbox_key(service_id)),
+        |k, v| {
+            let entry = match decode_inbox_key_value(k, v) {
+                Ok(entry) => entry,
+                Err(err) => return TableScanIterationDecision::BreakWith(Err(err)),
+            };
+            let InboxEntry::Invocation(_, _, priority) = entry.inbox_entry else {
+                return TableScanIterationDecision::Break;
+            };
+            if entry.inbox_sequence_number >= sequence_number {
+                return TableScanIterationDecision::Break;
+            }
+
+            if next.as_ref().map_or(true, |(next_priority, _)| {
+                priority.sort_key() < next_priority.sort_key()
+            }) {
+                next = Some((priority, entry));
+            }
+            TableScanIterationDecision::Continue
+        },
+    );
+    result.into_iter().next().transpose()?;
+
+    Ok(next.map(|(_, entry)| entry))
+}
 
+fn inbox<S: StorageAccess>(
+    storage: &mut S,
+    service_id: &ServiceId,
+) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
     stream::iter(storage.for_each_key_value_in_place(
-        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), key),
+        TableScan::SinglePartitionKeyPrefix(service_id.partition_key(), inbox_key(service_id)),
         |k, v| {
             let inbox_entry = decode_inbox_key_value(k, v);
             TableScanIterationDecision::Emit(inbox_entry)
@@ -81,13 +244,70 @@ fn all_inboxes<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
-    stream::iter(storage.for_each_key_value_in_place(
-        TableScan::FullScanPartitionKeyRange::<InboxKey>(range),
+    let inbox_entries = storage.for_each_key_value_in_place(
+        TableScan::FullScanPartitionKeyRange::<InboxKey>(range.clone()),
         |k, v| {
             let inbox_entry = decode_inbox_key_value(k, v);
             TableScanIterationDecision::Emit(inbox_entry)
         },
-    ))
+    );
+    let paused_invocations = storage.for_each_key_value_in_place(
+        TableScan::FullScanPartitionKeyRange::<PausedInboxKey>(range),
+        |k, v| {
+            let inbox_entry = decode_paused_inbox_key_value(k, v);
+            TableScanIterationDecision::Emit(inbox_entry)
+        },
+    );
+
+    stream::iter(inbox_entries.into_iter().chain(paused_invocations))
+}
+
+fn paused_invocations<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+    service_name: &str,
+) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
+    let mut paused_invocations = storage.for_each_key_value_in_place(
+        TableScan::FullScanPartitionKeyRange::<PausedInboxKey>(range),
+        |k, v| {
+            let key = match PausedInboxKey::deserialize_from(&mut Cursor::new(k)) {
+                Ok(key) => key,
+                Err(err) => return TableScanIterationDecision::Emit(Err(err)),
+            };
+            if key
+                .service_name
+                .as_ref()
+                .is_some_and(|name| name != service_name)
+            {
+                return TableScanIterationDecision::Continue;
+            }
+            TableScanIterationDecision::Emit(decode_paused_inbox_key_value(k, v))
+        },
+    );
+    // The paused invocations are scanned in the order of their partition keys
+    paused_invocations.sort_by_key(|entry| {
+        entry
+            .as_ref()
+            .map_or(0, |entry| entry.inbox_sequence_number)
+    });
+
+    stream::iter(paused_invocations)
+}
+
+impl PartitionStore {
+    /// Counts the inbox entries of this partition without decoding them.
+    pub fn count_inbox_entries(&self) -> u64 {
+        let _x = RocksDbPerfGuard::new("count-inbox-entries");
+        let mut count = 0;
+        self.for_each_key_value_in_place(
+            TableScan::FullScanPartitionKeyRange::<InboxKey>(self.partition_key_range().clone()),
+            |_, _| {
+                count += 1;
+                TableScanIterationDecision::<()>::Continue
+            },
+        );
+        count
+    }
 }
 
 impl ReadOnlyInboxTable for PartitionStore {
@@ -96,7 +316,7 @@ impl ReadOnlyInboxTable for PartitionStore {
         service_id: &ServiceId,
     ) -> impl Future<Output = Result<Option<SequenceNumberInboxEntry>>> + Send {
         self.assert_partition_key(service_id);
-        futures::future::ready(peek_inbox(self, service_id))
+        futures::future::ready(next_inbox_entry(self, service_id))
     }
 
     fn inbox(
@@ -113,6 +333,14 @@ impl ReadOnlyInboxTable for PartitionStore {
     ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
         all_inboxes(self, range)
     }
+
+    fn paused_invocations(
+        &mut self,
+        service_name: &str,
+    ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
+        let range = self.partition_key_range().clone();
+        paused_invocations(self, range, service_name)
+    }
 }
 
 impl<'a> ReadOnlyInboxTable for PartitionStoreTransaction<'a> {
@@ -121,7 +349,7 @@ impl<'a> ReadOnlyInboxTable for PartitionStoreTransaction<'a> {
         service_id: &ServiceId,
     ) -> impl Future<Output = Result<Option<SequenceNumberInboxEntry>>> + Send {
         self.assert_partition_key(service_id);
-        futures::future::ready(peek_inbox(self, service_id))
+        futures::future::ready(next_inbox_entry(self, service_id))
     }
 
     fn inbox(
@@ -138,6 +366,14 @@ impl<'a> ReadOnlyInboxTable for PartitionStoreTransaction<'a> {
     ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
         all_inboxes(self, range)
     }
+
+    fn paused_invocations(
+        &mut self,
+        service_name: &str,
+    ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send {
+        let range = self.partition_key_range().clone();
+        paused_invocations(self, range, service_name)
+    }
 }
 
 impl<'a> InboxTable for PartitionStoreTransaction<'a> {
@@ -149,18 +385,46 @@ impl<'a> InboxTable for PartitionStoreTransaction<'a> {
         let service_id = inbox_entry.service_id();
         self.assert_partition_key(service_id);
 
-        let key = InboxKey::default()
-            .partition_key(service_id.partition_key())
-            .service_name(service_id.service_name.clone())
-            .service_key(service_id.key.clone())
-            .sequence_number(inbox_sequence_number);
+        self.put_kv_raw(inbox_order_key(inbox_sequence_number, inbox_entry), b"");
+        self.put_kv(
+            inbox_key(service_id).sequence_number(inbox_sequence_number),
+            inbox_entry,
+        );
+    }
 
-        self.put_kv(key, inbox_entry);
+    async fn delete_inbox_entry(
+        &mut self,
+        service_id: &ServiceId,
+        sequence_number: u64,
+    ) -> Result<()> {
+        self.assert_partition_key(service_id);
+        if let Some(inbox_entry) = get_inbox_entry(self, service_id, sequence_number)? {
+            delete_inbox_entry(self, &inbox_entry);
+        }
+        Ok(())
     }
 
-    async fn delete_inbox_entry(&mut self, service_id: &ServiceId, sequence_number: u64) {
+    async fn put_paused_invocation(
+        &mut self,
+        inbox_sequence_number: MessageIndex,
+        inbox_entry: &InboxEntry,
+    ) {
+        let service_id = inbox_entry.service_id();
         self.assert_partition_key(service_id);
-        delete_inbox_entry(self, service_id, sequence_number);
+
+        self.put_kv(
+            paused_inbox_key(service_id, inbox_sequence_number),
+            inbox_entry,
+        );
+    }
+
+    async fn delete_paused_invocation(
+        &mut self,
+        service_id: &ServiceId,
+        sequence_number: MessageIndex,
+    ) {
+        self.assert_partition_key(service_id);
+        self.delete_key(&paused_inbox_key(service_id, sequence_number));
     }
 
     async fn pop_inbox(
@@ -169,28 +433,34 @@ impl<'a> InboxTable for PartitionStoreTransaction<'a> {
     ) -> Result<Option<SequenceNumberInboxEntry>> {
         self.assert_partition_key(service_id);
         let _x = RocksDbPerfGuard::new("pop-inbox");
-        let result = peek_inbox(self, service_id);
+        let result = next_inbox_entry(self, service_id);
 
         if let Ok(Some(ref inbox_entry)) = result {
-            delete_inbox_entry(self, service_id, inbox_entry.inbox_sequence_number)
+            delete_inbox_entry(self, inbox_entry)
         }
 
         result
     }
 }
 
-fn delete_inbox_entry(
-    txn: &mut PartitionStoreTransaction,
-    service_id: &ServiceId,
-    sequence_number: u64,
-) {
-    let key = InboxKey::default()
-        .partition_key(service_id.partition_key())
-        .service_name(service_id.service_name.clone())
-        .service_key(service_id.key.clone())
-        .sequence_number(sequence_number);
+fn delete_inbox_entry(txn: &mut PartitionStoreTransaction, inbox_entry: &SequenceNumberInboxEntry) {
+    txn.delete_key(&inbox_order_key(
+        inbox_entry.inbox_sequence_number,
+        &inbox_entry.inbox_entry,
+    ));
+    txn.delete_key(
+        &inbox_key(inbox_entry.service_id()).sequence_number(inbox_entry.inbox_sequence_number),
+    );
+}
+
+fn decode_paused_inbox_key_value(k: &[u8], mut v: &[u8]) -> Result<SequenceNumberInboxEntry> {
+    let key = PausedInboxKey::deserialize_from(&mut Cursor::new(k))?;
+    let sequence_number = *key.sequence_number_ok_or()?;
 
-    txn.delete_key(&key);
+    let inbox_entry = StorageCodec::decode::<InboxEntry, _>(&mut v)
+        .map_err(|error| StorageError::Generic(error.into()))?;
+
+    Ok(SequenceNumberInboxEntry::new(sequence_number, inbox_entry))
 }
 
 fn decode_inbox_key_value(k: &[u8], mut v: &[u8]) -> Result<SequenceNumberInboxEntry> {
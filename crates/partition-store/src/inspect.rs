@@ -0,0 +1,635 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Read-only access to the partition store database of a node, decoding the raw records into
+//! their storage types. Meant for offline inspection, e.g. after a node failed to start, and for
+//! inspecting the state of a partition as of one of its snapshots.
+
+use std::fmt::Debug;
+use std::path::Path;
+
+use rocksdb::{properties, ExportImportFilesMetaData, ImportColumnFamilyOptions, ReadOptions};
+use strum::VariantArray;
+
+use restate_storage_api::dead_letter_table::DeadLetter;
+use restate_storage_api::deduplication_table::DedupSequenceNumber;
+use restate_storage_api::fsm_table::{fsm_variable, ApplyFailure, PausedServices, SequenceNumber};
+use restate_storage_api::idempotency_table::IdempotencyMetadata;
+use restate_storage_api::inbox_table::InboxEntry;
+use restate_storage_api::invocation_event_table::InvocationEvent;
+use restate_storage_api::invocation_index_table::InvocationIndexEntry;
+use restate_storage_api::invocation_status_table::{
+    ArchivedInvocationStatus, InvocationStatus, InvocationStatusV1,
+};
+use restate_storage_api::journal_table::JournalEntry;
+use restate_storage_api::journal_table_v2::{PendingCompletion, StoredEntry};
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_storage_api::promise_table::Promise;
+use restate_storage_api::schedule_table::ScheduleStatus;
+use restate_storage_api::service_status_table::{SharedHandlerExecutions, VirtualObjectStatus};
+use restate_storage_api::timer_table::Timer;
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{InvocationId, PartitionId, WithPartitionKey};
+use restate_types::storage::{StorageCodec, StorageCodecKind, StorageDecode};
+
+use crate::dead_letter_table::DeadLetterKey;
+use crate::deduplication_table::DeduplicationKey;
+use crate::fsm_table::PartitionStateMachineKey;
+use crate::idempotency_table::IdempotencyKey;
+use crate::inbox_table::{InboxKey, InboxOrderKey, PausedInboxKey};
+use crate::invocation_event_table::InvocationEventKey;
+use crate::invocation_index_table::InvocationIndexKey;
+use crate::invocation_status_table::{
+    InvocationStatusArchiveKey, InvocationStatusKey, InvocationStatusKeyV1,
+};
+use crate::journal_table::JournalKey;
+use crate::journal_table_v2::{JournalPendingCompletionKey, JournalV2Key};
+use crate::keys::{KeyKind, TableKey};
+use crate::outbox_table::OutboxKey;
+use crate::prepared_message_table::PreparedMessageKey;
+use crate::promise_table::PromiseKey;
+use crate::schedule_table::ScheduleKey;
+use crate::service_status_table::{ServiceStatusKey, SharedHandlerExecutionsKey};
+use crate::snapshots::LocalPartitionSnapshot;
+use crate::state_table::StateKey;
+use crate::timer_table::TimersKey;
+use crate::{ARCHIVE_CF_PREFIX, DB, DB_NAME, PARTITION_CF_PREFIX};
+
+/// A record of the partition store, with its key and value decoded into their storage types.
+#[derive(Debug, Clone)]
+pub struct DecodedRecord {
+    pub key_kind: KeyKind,
+    pub key: String,
+    pub value: String,
+}
+
+/// Returns the key kind with the given name, e.g. `InvocationStatus`, ignoring the case.
+pub fn key_kind_by_name(name: &str) -> Option<KeyKind> {
+    KeyKind::VARIANTS
+        .iter()
+        .find(|key_kind| key_kind.to_string().eq_ignore_ascii_case(name))
+        .copied()
+}
+
+/// Decodes a raw record of a partition column family.
+pub fn decode_record(mut key: &[u8], mut value: &[u8]) -> Result<DecodedRecord> {
+    let key_kind = KeyKind::deserialize(&mut &key[..])?;
+
+    let (key, value) = match key_kind {
+        KeyKind::Deduplication => {
+            decode::<DeduplicationKey, DedupSequenceNumber>(&mut key, &mut value)?
+        }
+        KeyKind::Fsm => {
+            let fsm_key = PartitionStateMachineKey::deserialize_from(&mut key)?;
+            let value = match fsm_key.state_id {
+                Some(fsm_variable::APPLY_FAILURE) => decode_value::<ApplyFailure>(&mut value)?,
+                Some(fsm_variable::PAUSED_SERVICES) => decode_value::<PausedServices>(&mut value)?,
+                _ => decode_value::<SequenceNumber>(&mut value)?,
+            };
+            (format!("{fsm_key:?}"), value)
+        }
+        KeyKind::Idempotency => {
+            decode::<IdempotencyKey, IdempotencyMetadata>(&mut key, &mut value)?
+        }
+        KeyKind::Inbox => decode::<InboxKey, InboxEntry>(&mut key, &mut value)?,
+        KeyKind::PausedInbox => decode::<PausedInboxKey, InboxEntry>(&mut key, &mut value)?,
+        // inbox order keys have no value
+        KeyKind::InboxOrder => (
+            format!("{:?}", InboxOrderKey::deserialize_from(&mut key)?),
+            String::new(),
+        ),
+        KeyKind::InvocationStatusV1 => {
+            decode::<InvocationStatusKeyV1, InvocationStatusV1>(&mut key, &mut value)?
+        }
+        KeyKind::InvocationStatus => {
+            decode::<InvocationStatusKey, InvocationStatus>(&mut key, &mut value)?
+        }
+        KeyKind::InvocationStatusArchive => {
+            decode::<InvocationStatusArchiveKey, ArchivedInvocationStatus>(&mut key, &mut value)?
+        }
+        KeyKind::Journal => decode::<JournalKey, JournalEntry>(&mut key, &mut value)?,
+        KeyKind::JournalV2 => decode::<JournalV2Key, StoredEntry>(&mut key, &mut value)?,
+        KeyKind::JournalPendingCompletion => {
+            decode::<JournalPendingCompletionKey, PendingCompletion>(&mut key, &mut value)?
+        }
+        KeyKind::Outbox => decode::<OutboxKey, OutboxMessage>(&mut key, &mut value)?,
+        KeyKind::ServiceStatus => {
+            decode::<ServiceStatusKey, VirtualObjectStatus>(&mut key, &mut value)?
+        }
+        // user state is stored as raw bytes
+        KeyKind::State => (
+            format!("{:?}", StateKey::deserialize_from(&mut key)?),
+            format!("{:?}", bytes::Bytes::copy_from_slice(value)),
+        ),
+        KeyKind::Timers => decode::<TimersKey, Timer>(&mut key, &mut value)?,
+        KeyKind::Promise => decode::<PromiseKey, Promise>(&mut key, &mut value)?,
+        KeyKind::DeadLetter => decode::<DeadLetterKey, DeadLetter>(&mut key, &mut value)?,
+        KeyKind::InvocationIndex => {
+            decode::<InvocationIndexKey, InvocationIndexEntry>(&mut key, &mut value)?
+        }
+        KeyKind::SharedHandlerExecutions => {
+            decode::<SharedHandlerExecutionsKey, SharedHandlerExecutions>(&mut key, &mut value)?
+        }
+        KeyKind::Schedule => decode::<ScheduleKey, ScheduleStatus>(&mut key, &mut value)?,
+        KeyKind::InvocationEvent => {
+            decode::<InvocationEventKey, InvocationEvent>(&mut key, &mut value)?
+        }
+        KeyKind::PreparedMessage => {
+            decode::<PreparedMessageKey, OutboxMessage>(&mut key, &mut value)?
+        }
+    };
+
+    Ok(DecodedRecord {
+        key_kind,
+        key,
+        value,
+    })
+}
+
+fn decode<K: TableKey, V: StorageDecode + Debug>(
+    key: &mut &[u8],
+    value: &mut &[u8],
+) -> Result<(String, String)> {
+    let key = K::deserialize_from(key)?;
+    Ok((format!("{key:?}"), decode_value::<V>(value)?))
+}
+
+fn decode_value<V: StorageDecode + Debug>(value: &mut &[u8]) -> Result<String> {
+    let value =
+        StorageCodec::decode::<V, _>(value).map_err(|err| StorageError::Conversion(err.into()))?;
+    Ok(format!("{value:?}"))
+}
+
+/// A column family of the partition store database.
+#[derive(Debug, Clone)]
+pub struct ColumnFamilyInfo {
+    pub name: String,
+    /// The partition whose data the column family holds, if any
+    pub partition_id: Option<PartitionId>,
+    pub estimated_num_keys: Option<u64>,
+    pub live_sst_files_size: Option<u64>,
+}
+
+/// Number of records of a key kind, and the codecs their values are encoded with.
+#[derive(Debug, Clone)]
+pub struct KeyKindStats {
+    pub key_kind: KeyKind,
+    pub records: u64,
+    pub codecs: Vec<StorageCodecKind>,
+    /// The key kind the records are migrated to, if the key kind is an older version of it
+    pub superseded_by: Option<KeyKind>,
+}
+
+fn superseded_by(key_kind: KeyKind) -> Option<KeyKind> {
+    match key_kind {
+        KeyKind::InvocationStatusV1 => Some(KeyKind::InvocationStatus),
+        _ => None,
+    }
+}
+
+/// The partition store database of a node opened in read-only mode. It doesn't take the lock of
+/// the database, and never writes to it.
+pub struct ReadOnlyPartitionStoreDb {
+    db: DB,
+    cf_names: Vec<String>,
+}
+
+impl ReadOnlyPartitionStoreDb {
+    /// Opens the partition store database within the data dir of a node.
+    pub fn open(node_dir: &Path) -> std::result::Result<Self, rocksdb::Error> {
+        let path = node_dir.join(DB_NAME);
+        let opts = rocksdb::Options::default();
+        let cf_names = DB::list_cf(&opts, &path)?;
+        let db = DB::open_cf_for_read_only(&opts, &path, &cf_names, false)?;
+        Ok(Self { db, cf_names })
+    }
+
+    /// Imports a partition snapshot into a new database within `dir`, and opens it read-only.
+    /// This allows inspecting the state of the partition as of the snapshot, independently of
+    /// the partition store of the node. Fails if `dir` already contains a database.
+    pub fn import_snapshot(
+        dir: &Path,
+        partition_id: PartitionId,
+        snapshot: &LocalPartitionSnapshot,
+    ) -> std::result::Result<Self, rocksdb::Error> {
+        let mut opts = rocksdb::Options::default();
+        opts.create_if_missing(true);
+        opts.set_error_if_exists(true);
+        {
+            let db = DB::open(&opts, dir.join(DB_NAME))?;
+
+            let (archive_files, data_files): (Vec<_>, Vec<_>) = snapshot
+                .files
+                .iter()
+                .cloned()
+                .partition(|file| file.column_family_name.starts_with(ARCHIVE_CF_PREFIX));
+            let mut import_opts = ImportColumnFamilyOptions::default();
+            // keep the downloaded snapshot files intact
+            import_opts.set_move_files(false);
+            for (name, files) in [
+                (cf_name(partition_id), data_files),
+                (archive_cf_name(partition_id), archive_files),
+            ] {
+                if files.is_empty() {
+                    db.create_cf(name, &rocksdb::Options::default())?;
+                    continue;
+                }
+                let mut metadata = ExportImportFilesMetaData::default();
+                metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
+                metadata.set_files(&files);
+                db.create_column_family_with_import(
+                    &rocksdb::Options::default(),
+                    name,
+                    &import_opts,
+                    &metadata,
+                )?;
+            }
+        }
+        Self::open(dir)
+    }
+
+    pub fn column_families(&self) -> Result<Vec<ColumnFamilyInfo>> {
+        self.cf_names
+            .iter()
+            .map(|name| {
+                let cf = self.cf_handle(name)?;
+                let property = |property| {
+                    self.db
+                        .property_int_value_cf(&cf, property)
+                        .map_err(|err| StorageError::Generic(err.into()))
+                };
+                Ok(ColumnFamilyInfo {
+                    name: name.clone(),
+                    partition_id: partition_id_of_cf(name),
+                    estimated_num_keys: property(properties::ESTIMATE_NUM_KEYS)?,
+                    live_sst_files_size: property(properties::LIVE_SST_FILES_SIZE)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn partition_ids(&self) -> Vec<PartitionId> {
+        let mut partition_ids: Vec<_> = self
+            .cf_names
+            .iter()
+            .filter_map(|name| partition_id_of_cf(name))
+            .collect();
+        partition_ids.sort();
+        // the data and the archive column family of a partition
+        partition_ids.dedup();
+        partition_ids
+    }
+
+    /// Decodes up to `limit` records of the partition, starting at the key `from` and ending
+    /// before the key `to`.
+    pub fn scan(
+        &self,
+        partition_id: PartitionId,
+        from: Option<&[u8]>,
+        to: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Result<DecodedRecord>>> {
+        self.scan_while(partition_id, from, to, limit, |_| true)
+    }
+
+    fn scan_while(
+        &self,
+        partition_id: PartitionId,
+        from: Option<&[u8]>,
+        to: Option<&[u8]>,
+        limit: usize,
+        mut predicate: impl FnMut(&[u8]) -> bool,
+    ) -> Result<Vec<Result<DecodedRecord>>> {
+        let cf = self.cf_handle(&cf_name_of_key(partition_id, from.unwrap_or_default()))?;
+        let mut opts = ReadOptions::default();
+        // the prefix extractor of the partition column families isn't configured when opening
+        // the database read-only, hence the scans must not rely on it
+        opts.set_total_order_seek(true);
+        if let Some(to) = to {
+            opts.set_iterate_upper_bound(to);
+        }
+
+        let mut iterator = self.db.raw_iterator_cf_opt(&cf, opts);
+        match from {
+            Some(from) => iterator.seek(from),
+            None => iterator.seek_to_first(),
+        }
+
+        let mut records = Vec::new();
+        while records.len() < limit {
+            let Some((key, value)) = iterator.item().filter(|(key, _)| predicate(key)) else {
+                break;
+            };
+            records.push(decode_record(key, value));
+            iterator.next();
+        }
+        iterator
+            .status()
+            .map_err(|err| StorageError::Generic(err.into()))?;
+        Ok(records)
+    }
+
+    /// Decodes up to `limit` records of the partition whose keys start with `prefix`, e.g. the
+    /// bytes of a [`KeyKind`], continuing after the key `after` of a previous scan. Also returns
+    /// the key of the last decoded record if the limit was reached, to continue the scan from.
+    pub fn scan_prefix(
+        &self,
+        partition_id: PartitionId,
+        prefix: &[u8],
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<(Vec<Result<DecodedRecord>>, Option<Vec<u8>>)> {
+        let from = match after {
+            Some(after) if after.starts_with(prefix) => {
+                // the smallest key which sorts after `after`
+                let mut from = after.to_vec();
+                from.push(0);
+                from
+            }
+            Some(_) => {
+                return Err(StorageError::Generic(anyhow::anyhow!(
+                    "the scan can't continue after a key without the scanned prefix"
+                )))
+            }
+            None => prefix.to_vec(),
+        };
+        let to = prefix_upper_bound(prefix);
+
+        let mut last_key = None;
+        let records = self.scan_while(partition_id, Some(&from), to.as_deref(), limit, |key| {
+            last_key = Some(key.to_vec());
+            true
+        })?;
+        let next = last_key.filter(|_| records.len() == limit);
+        Ok((records, next))
+    }
+
+    /// Decodes the invocation status and the journal entries of the invocation. Returns the
+    /// partition which holds them, if any.
+    pub fn invocation(
+        &self,
+        invocation_id: &InvocationId,
+    ) -> Result<Option<(PartitionId, Vec<Result<DecodedRecord>>)>> {
+        let status_key = InvocationStatusKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid());
+        let status_v1_key = InvocationStatusKeyV1::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid());
+        let archive_key = InvocationStatusArchiveKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid());
+        let journal_prefix = JournalKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid())
+            .serialize();
+
+        for partition_id in self.partition_ids() {
+            let mut records = Vec::new();
+            for key in [
+                status_key.serialize(),
+                status_v1_key.serialize(),
+                archive_key.serialize(),
+            ] {
+                if let Some(record) = self.get(partition_id, &key)? {
+                    records.push(record);
+                }
+            }
+            records.extend(self.scan_while(
+                partition_id,
+                Some(&journal_prefix[..]),
+                None,
+                usize::MAX,
+                |key| key.starts_with(&journal_prefix),
+            )?);
+
+            if !records.is_empty() {
+                return Ok(Some((partition_id, records)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Counts the records of each key kind of the partition, and the codecs of their values.
+    pub fn key_kind_stats(&self, partition_id: PartitionId) -> Result<Vec<KeyKindStats>> {
+        let mut stats: Vec<KeyKindStats> = Vec::new();
+        for name in [cf_name(partition_id), archive_cf_name(partition_id)] {
+            self.collect_key_kind_stats(&name, &mut stats)?;
+        }
+        Ok(stats)
+    }
+
+    fn collect_key_kind_stats(&self, name: &str, stats: &mut Vec<KeyKindStats>) -> Result<()> {
+        let cf = self.cf_handle(name)?;
+        let mut opts = ReadOptions::default();
+        opts.set_total_order_seek(true);
+        let mut iterator = self.db.raw_iterator_cf_opt(&cf, opts);
+        iterator.seek_to_first();
+
+        while let Some((key, value)) = iterator.item() {
+            let key_kind = KeyKind::deserialize(&mut &key[..])?;
+            let codec = match key_kind {
+                KeyKind::State => None,
+                _ => value
+                    .first()
+                    .and_then(|codec| StorageCodecKind::try_from(*codec).ok()),
+            };
+
+            let entry = match stats.iter().position(|entry| entry.key_kind == key_kind) {
+                Some(idx) => &mut stats[idx],
+                None => {
+                    stats.push(KeyKindStats {
+                        key_kind,
+                        records: 0,
+                        codecs: Vec::new(),
+                        superseded_by: superseded_by(key_kind),
+                    });
+                    stats.last_mut().unwrap()
+                }
+            };
+            entry.records += 1;
+            if let Some(codec) = codec.filter(|codec| !entry.codecs.contains(codec)) {
+                entry.codecs.push(codec);
+            }
+            iterator.next();
+        }
+        iterator
+            .status()
+            .map_err(|err| StorageError::Generic(err.into()))?;
+        Ok(())
+    }
+
+    fn get(&self, partition_id: PartitionId, key: &[u8]) -> Result<Option<Result<DecodedRecord>>> {
+        let cf = self.cf_handle(&cf_name_of_key(partition_id, key))?;
+        let value = self
+            .db
+            .get_pinned_cf(&cf, key)
+            .map_err(|err| StorageError::Generic(err.into()))?;
+        Ok(value.map(|value| decode_record(key, &value)))
+    }
+
+    fn cf_handle(&self, name: &str) -> Result<std::sync::Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.db.cf_handle(name).ok_or_else(|| {
+            StorageError::Generic(anyhow::anyhow!("column family '{name}' doesn't exist"))
+        })
+    }
+}
+
+/// Returns the smallest key which sorts after all keys starting with `prefix`, or `None` if there
+/// is no such key.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last) = upper_bound.pop() {
+        if last < u8::MAX {
+            upper_bound.push(last + 1);
+            return Some(upper_bound);
+        }
+    }
+    None
+}
+
+fn cf_name(partition_id: PartitionId) -> String {
+    format!("{PARTITION_CF_PREFIX}{partition_id}")
+}
+
+fn archive_cf_name(partition_id: PartitionId) -> String {
+    format!("{ARCHIVE_CF_PREFIX}{partition_id}")
+}
+
+/// The archived invocation statuses are kept in the archive column family of the partition.
+fn cf_name_of_key(partition_id: PartitionId, key: &[u8]) -> String {
+    if key.starts_with(KeyKind::InvocationStatusArchive.as_bytes()) {
+        archive_cf_name(partition_id)
+    } else {
+        cf_name(partition_id)
+    }
+}
+
+fn partition_id_of_cf(name: &str) -> Option<PartitionId> {
+    name.strip_prefix(PARTITION_CF_PREFIX)
+        .or_else(|| name.strip_prefix(ARCHIVE_CF_PREFIX))?
+        .parse::<u16>()
+        .ok()
+        .map(PartitionId::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_journal_record() {
+        let invocation_id = InvocationId::mock_random();
+        let key = JournalKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid())
+            .journal_index(3)
+            .serialize();
+        let mut value = bytes::BytesMut::new();
+        StorageCodec::encode(&SequenceNumber::from(42), &mut value).expect("value encodes");
+
+        // the value isn't a journal entry
+        assert!(decode_record(&key, &value).is_err());
+
+        let key = PartitionStateMachineKey::default()
+            .partition_id(PartitionId::from(7).into())
+            .state_id(fsm_variable::APPLIED_LSN)
+            .serialize();
+        let record = decode_record(&key, &value).expect("record decodes");
+        assert_eq!(record.key_kind, KeyKind::Fsm);
+        assert!(record.value.contains("42"));
+    }
+
+    #[test]
+    fn scan_prefix_continues_after_cursor() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let partition_id = PartitionId::from(7);
+        {
+            let mut opts = rocksdb::Options::default();
+            opts.create_if_missing(true);
+            opts.create_missing_column_families(true);
+            let db = DB::open_cf(&opts, dir.path().join(DB_NAME), [cf_name(partition_id)])
+                .expect("db opens");
+            let cf = db.cf_handle(&cf_name(partition_id)).expect("cf exists");
+
+            let mut value = bytes::BytesMut::new();
+            StorageCodec::encode(&SequenceNumber::from(42), &mut value).expect("value encodes");
+            for state_id in 0..5 {
+                let key = PartitionStateMachineKey::default()
+                    .partition_id(partition_id.into())
+                    .state_id(state_id)
+                    .serialize();
+                db.put_cf(&cf, key, &value).expect("record is written");
+            }
+            // sorts after the fsm records, and must not be scanned
+            let invocation_id = InvocationId::mock_random();
+            let key = JournalKey::default()
+                .partition_key(invocation_id.partition_key())
+                .invocation_uuid(invocation_id.invocation_uuid())
+                .journal_index(0)
+                .serialize();
+            db.put_cf(&cf, key, &value).expect("record is written");
+        }
+
+        let db = ReadOnlyPartitionStoreDb::open(dir.path()).expect("db opens");
+        let prefix = KeyKind::Fsm.as_bytes();
+        let mut after = None;
+        let mut pages = Vec::new();
+        loop {
+            let (records, next) = db
+                .scan_prefix(partition_id, prefix, after.as_deref(), 2)
+                .expect("scan succeeds");
+            assert!(records
+                .iter()
+                .all(|record| record.as_ref().expect("record decodes").key_kind == KeyKind::Fsm));
+            pages.push(records.len());
+            match next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![2, 2, 1]);
+
+        let other_prefix = KeyKind::Journal.as_bytes();
+        assert!(db
+            .scan_prefix(partition_id, other_prefix, after.as_deref(), 2)
+            .is_err());
+    }
+
+    #[test]
+    fn key_kinds_by_name() {
+        assert_eq!(
+            key_kind_by_name("invocationstatus"),
+            Some(KeyKind::InvocationStatus)
+        );
+        assert_eq!(key_kind_by_name("Fsm"), Some(KeyKind::Fsm));
+        assert_eq!(key_kind_by_name("unknown"), None);
+    }
+
+    #[test]
+    fn prefix_upper_bounds() {
+        assert_eq!(prefix_upper_bound(b"fs"), Some(b"ft".to_vec()));
+        assert_eq!(prefix_upper_bound(&[1, u8::MAX]), Some(vec![2]));
+        assert_eq!(prefix_upper_bound(&[u8::MAX, u8::MAX]), None);
+    }
+
+    #[test]
+    fn partition_cf_names() {
+        assert_eq!(
+            partition_id_of_cf(&cf_name(PartitionId::from(12))),
+            Some(PartitionId::from(12))
+        );
+        assert_eq!(
+            partition_id_of_cf(&archive_cf_name(PartitionId::from(12))),
+            Some(PartitionId::from(12))
+        );
+        assert_eq!(partition_id_of_cf("effect-digests"), None);
+    }
+}
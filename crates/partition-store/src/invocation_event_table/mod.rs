@@ -0,0 +1,173 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+
+use futures::{Stream, StreamExt};
+
+use restate_storage_api::invocation_event_table::{
+    InvocationEvent, InvocationEventKind, InvocationEventTable, ReadOnlyInvocationEventTable,
+    MAX_EVENTS_PER_INVOCATION,
+};
+use restate_storage_api::Result;
+use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
+use restate_types::time::MillisSinceEpoch;
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
+use crate::scan::{scan_table, ScanDirection};
+use crate::TableKind;
+use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess};
+use crate::{TableScan, TableScanIterationDecision};
+
+// The events of an invocation are stored next to each other, ordered by their sequence number
+define_table_key!(
+    TableKind::InvocationEvent,
+    KeyKind::InvocationEvent,
+    InvocationEventKey(
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid,
+        seq_number: u64
+    )
+);
+impl_table_record!(InvocationEventKey, InvocationEvent);
+
+fn invocation_events_prefix(invocation_id: &InvocationId) -> InvocationEventKey {
+    InvocationEventKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+}
+
+/// Sequence numbers of the stored events of the invocation, at most
+/// [`MAX_EVENTS_PER_INVOCATION`] of them.
+fn seq_numbers<S: StorageAccess>(storage: &S, invocation_id: &InvocationId) -> Result<Vec<u64>> {
+    storage
+        .for_each_key_value_in_place(
+            TableScan::SinglePartitionKeyPrefix(
+                invocation_id.partition_key(),
+                invocation_events_prefix(invocation_id),
+            ),
+            |k, _| {
+                TableScanIterationDecision::Emit(
+                    InvocationEventKey::deserialize_from(&mut Cursor::new(k)).map(|key| {
+                        key.seq_number
+                            .expect("The sequence number must be part of the key.")
+                    }),
+                )
+            },
+        )
+        .into_iter()
+        .collect()
+}
+
+fn append_invocation_events<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    timestamp: MillisSinceEpoch,
+    kinds: Vec<InvocationEventKind>,
+) -> Result<()> {
+    let mut seq_numbers = seq_numbers(storage, invocation_id)?;
+    let mut next_seq_number = seq_numbers.last().map_or(0, |last| last + 1);
+
+    for kind in kinds {
+        let key = invocation_events_prefix(invocation_id).seq_number(next_seq_number);
+        storage.put_kv(
+            key,
+            &InvocationEvent {
+                invocation_id: *invocation_id,
+                seq_number: next_seq_number,
+                timestamp,
+                kind,
+            },
+        );
+        seq_numbers.push(next_seq_number);
+        next_seq_number += 1;
+    }
+
+    let excess = seq_numbers
+        .len()
+        .saturating_sub(MAX_EVENTS_PER_INVOCATION as usize);
+    for seq_number in &seq_numbers[..excess] {
+        storage.delete_key(&invocation_events_prefix(invocation_id).seq_number(*seq_number));
+    }
+
+    Ok(())
+}
+
+fn delete_invocation_events<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<()> {
+    for seq_number in seq_numbers(storage, invocation_id)? {
+        storage.delete_key(&invocation_events_prefix(invocation_id).seq_number(seq_number));
+    }
+    Ok(())
+}
+
+fn all_invocation_events<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<InvocationEvent>> + Send + '_ {
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<InvocationEventKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| row.map(|(_, event)| event))
+}
+
+impl ReadOnlyInvocationEventTable for PartitionStore {
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<InvocationEvent>> + Send {
+        all_invocation_events(self, range)
+    }
+}
+
+impl InvocationEventTable for PartitionStore {
+    async fn append_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+        timestamp: MillisSinceEpoch,
+        kinds: Vec<InvocationEventKind>,
+    ) -> Result<()> {
+        append_invocation_events(self, invocation_id, timestamp, kinds)
+    }
+
+    async fn delete_invocation_events(&mut self, invocation_id: &InvocationId) -> Result<()> {
+        delete_invocation_events(self, invocation_id)
+    }
+}
+
+impl<'a> ReadOnlyInvocationEventTable for PartitionStoreTransaction<'a> {
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<InvocationEvent>> + Send {
+        all_invocation_events(self, range)
+    }
+}
+
+impl<'a> InvocationEventTable for PartitionStoreTransaction<'a> {
+    async fn append_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+        timestamp: MillisSinceEpoch,
+        kinds: Vec<InvocationEventKind>,
+    ) -> Result<()> {
+        append_invocation_events(self, invocation_id, timestamp, kinds)
+    }
+
+    async fn delete_invocation_events(&mut self, invocation_id: &InvocationId) -> Result<()> {
+        delete_invocation_events(self, invocation_id)
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use futures::{Stream, StreamExt};
+
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::invocation_index_table::{
+    InvocationIndexEntry, InvocationIndexTable, InvocationLookupKey, ReadOnlyInvocationIndexTable,
+};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{IdempotencyId, PartitionKey, ServiceId, WithPartitionKey};
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection, TableScan};
+use crate::{PartitionStore, TableKind};
+use crate::{PartitionStoreTransaction, StorageAccess};
+
+// Workflow lookup keys are stored with an empty handler and idempotency key. This cannot clash
+// with idempotency lookup keys, since those always carry the name of the invoked handler.
+define_table_key!(
+    TableKind::InvocationIndex,
+    KeyKind::InvocationIndex,
+    InvocationIndexKey(
+        partition_key: PartitionKey,
+        service_name: ByteString,
+        service_key: Bytes,
+        service_handler: ByteString,
+        idempotency_key: ByteString
+    )
+);
+impl_table_record!(InvocationIndexKey, InvocationIndexEntry);
+
+fn create_key(lookup_key: &InvocationLookupKey) -> InvocationIndexKey {
+    let key = InvocationIndexKey::default().partition_key(lookup_key.partition_key());
+    match lookup_key {
+        InvocationLookupKey::Idempotency(idempotency_id) => key
+            .service_name(idempotency_id.service_name.clone())
+            .service_key(
+                idempotency_id
+                    .service_key
+                    .as_ref()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_bytes(),
+            )
+            .service_handler(idempotency_id.service_handler.clone())
+            .idempotency_key(idempotency_id.idempotency_key.clone()),
+        InvocationLookupKey::Workflow(service_id) => key
+            .service_name(service_id.service_name.clone())
+            .service_key(service_id.key.clone().into_bytes())
+            .service_handler(ByteString::new())
+            .idempotency_key(ByteString::new()),
+    }
+}
+
+fn lookup_key_from_parts(key: InvocationIndexKey) -> Result<InvocationLookupKey> {
+    let (partition_key, service_name, service_key, service_handler, idempotency_key) =
+        key.into_inner_ok_or()?;
+    let service_key =
+        ByteString::try_from(service_key).map_err(|e| StorageError::Generic(e.into()))?;
+
+    if service_handler.is_empty() {
+        Ok(InvocationLookupKey::Workflow(ServiceId::from_parts(
+            partition_key,
+            service_name,
+            service_key,
+        )))
+    } else {
+        Ok(InvocationLookupKey::Idempotency(IdempotencyId::new(
+            service_name,
+            (!service_key.is_empty()).then_some(service_key),
+            service_handler,
+            idempotency_key,
+        )))
+    }
+}
+
+fn get_invocation_index_entry<S: StorageAccess>(
+    storage: &mut S,
+    lookup_key: &InvocationLookupKey,
+) -> Result<Option<InvocationIndexEntry>> {
+    let _x = RocksDbPerfGuard::new("get-invocation-index-entry");
+    storage.get_value(create_key(lookup_key))
+}
+
+fn all_invocation_index_entries<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<(InvocationLookupKey, InvocationIndexEntry)>> + Send + '_ {
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<InvocationIndexKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (key, entry) = row?;
+        Ok((lookup_key_from_parts(key)?, entry))
+    })
+}
+
+fn put_invocation_index_entry<S: StorageAccess>(
+    storage: &mut S,
+    lookup_key: &InvocationLookupKey,
+    entry: &InvocationIndexEntry,
+) {
+    storage.put_kv(create_key(lookup_key), entry);
+}
+
+fn delete_invocation_index_entry<S: StorageAccess>(
+    storage: &mut S,
+    lookup_key: &InvocationLookupKey,
+) {
+    storage.delete_key(&create_key(lookup_key));
+}
+
+impl ReadOnlyInvocationIndexTable for PartitionStore {
+    async fn get_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+    ) -> Result<Option<InvocationIndexEntry>> {
+        self.assert_partition_key(lookup_key);
+        get_invocation_index_entry(self, lookup_key)
+    }
+
+    fn all_invocation_index_entries(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(InvocationLookupKey, InvocationIndexEntry)>> + Send {
+        all_invocation_index_entries(self, range)
+    }
+}
+
+impl<'a> ReadOnlyInvocationIndexTable for PartitionStoreTransaction<'a> {
+    async fn get_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+    ) -> Result<Option<InvocationIndexEntry>> {
+        self.assert_partition_key(lookup_key);
+        get_invocation_index_entry(self, lookup_key)
+    }
+
+    fn all_invocation_index_entries(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(InvocationLookupKey, InvocationIndexEntry)>> + Send {
+        all_invocation_index_entries(self, range)
+    }
+}
+
+impl<'a> InvocationIndexTable for PartitionStoreTransaction<'a> {
+    async fn put_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+        entry: &InvocationIndexEntry,
+    ) {
+        self.assert_partition_key(lookup_key);
+        put_invocation_index_entry(self, lookup_key, entry)
+    }
+
+    async fn delete_invocation_index_entry(&mut self, lookup_key: &InvocationLookupKey) {
+        self.assert_partition_key(lookup_key);
+        delete_invocation_index_entry(self, lookup_key)
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use metrics::counter;
+use moka::notification::RemovalCause;
+use moka::policy::EvictionPolicy;
+use moka::sync::{Cache, CacheBuilder};
+
+use restate_storage_api::invocation_status_table::{InvocationStatus, InvocationStatusView};
+use restate_types::identifiers::InvocationId;
+
+use crate::metric_definitions::{
+    INVOCATION_STATUS_CACHE_EVICTIONS, INVOCATION_STATUS_CACHE_HITS, INVOCATION_STATUS_CACHE_MISSES,
+};
+
+#[derive(Debug, Clone)]
+struct CachedInvocationStatus {
+    status: InvocationStatus,
+    encoded_size: usize,
+}
+
+/// LRU cache of the committed invocation statuses of a partition, keyed by invocation id.
+///
+/// The cache is only populated and updated by the transactions of the partition store: a
+/// transaction caches the statuses it reads from the committed state, and applies the statuses
+/// it writes once it has been committed. It therefore relies on the partition store having a
+/// single writer, the partition processor.
+#[derive(Clone)]
+pub(crate) struct InvocationStatusCache {
+    inner: Option<Cache<InvocationId, Arc<CachedInvocationStatus>>>,
+}
+
+impl InvocationStatusCache {
+    /// Creates a new instance of InvocationStatusCache. If memory budget is 0
+    /// cache will be disabled
+    pub(crate) fn new(memory_budget_bytes: usize) -> Self {
+        let inner = if memory_budget_bytes > 0 {
+            Some(
+                CacheBuilder::default()
+                    .name("PartitionStoreInvocationStatusCache")
+                    .weigher(|_, cached: &Arc<CachedInvocationStatus>| {
+                        (size_of::<InvocationId>() + cached.encoded_size)
+                            .try_into()
+                            .unwrap_or(u32::MAX)
+                    })
+                    .max_capacity(memory_budget_bytes.try_into().unwrap_or(u64::MAX))
+                    .eviction_policy(EvictionPolicy::lru())
+                    .eviction_listener(|_, _, cause| {
+                        if cause == RemovalCause::Size {
+                            counter!(INVOCATION_STATUS_CACHE_EVICTIONS).increment(1);
+                        }
+                    })
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        Self { inner }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Get the cached status of the given invocation.
+    pub(crate) fn get(&self, invocation_id: &InvocationId) -> Option<InvocationStatus> {
+        self.get_cached(invocation_id)
+            .map(|cached| cached.status.clone())
+    }
+
+    /// Get the view of the cached status of the given invocation.
+    pub(crate) fn get_view(&self, invocation_id: &InvocationId) -> Option<InvocationStatusView> {
+        self.get_cached(invocation_id)
+            .map(|cached| InvocationStatusView::from(&cached.status))
+    }
+
+    fn get_cached(&self, invocation_id: &InvocationId) -> Option<Arc<CachedInvocationStatus>> {
+        let inner = self.inner.as_ref()?;
+
+        let cached = inner.get(invocation_id);
+        if cached.is_some() {
+            counter!(INVOCATION_STATUS_CACHE_HITS).increment(1);
+        } else {
+            counter!(INVOCATION_STATUS_CACHE_MISSES).increment(1);
+        }
+        cached
+    }
+
+    /// Caches the committed status of the given invocation, unless it's free.
+    pub(crate) fn insert(
+        &self,
+        invocation_id: InvocationId,
+        status: InvocationStatus,
+        encoded_size: usize,
+    ) {
+        let Some(ref inner) = self.inner else {
+            return;
+        };
+
+        if status == InvocationStatus::Free {
+            return;
+        }
+        inner.insert(
+            invocation_id,
+            Arc::new(CachedInvocationStatus {
+                status,
+                encoded_size,
+            }),
+        );
+    }
+
+    /// Applies the statuses written by a committed transaction, caching the written statuses and
+    /// removing the deleted ones.
+    pub(crate) fn apply_writes(&self, writes: InvocationStatusWrites) {
+        let Some(ref inner) = self.inner else {
+            return;
+        };
+
+        for (invocation_id, written) in writes.0 {
+            match written {
+                Some(written) => {
+                    self.insert(invocation_id, written.status, written.encoded_size);
+                }
+                None => inner.invalidate(&invocation_id),
+            }
+        }
+    }
+}
+
+/// Invocation statuses written by a transaction, which are applied to the
+/// [`InvocationStatusCache`] once the transaction has been committed. Tracking them exactly keeps
+/// the cache valid without invalidating it as a whole on every commit.
+#[derive(Debug, Default)]
+pub(crate) struct InvocationStatusWrites(HashMap<InvocationId, Option<CachedInvocationStatus>>);
+
+impl InvocationStatusWrites {
+    /// Returns the status last written for the given invocation, if any.
+    pub(crate) fn get(&self, invocation_id: &InvocationId) -> Option<InvocationStatus> {
+        self.0.get(invocation_id).map(|written| {
+            written
+                .as_ref()
+                .map(|written| written.status.clone())
+                .unwrap_or(InvocationStatus::Free)
+        })
+    }
+
+    /// Returns the view of the status last written for the given invocation, if any.
+    pub(crate) fn get_view(&self, invocation_id: &InvocationId) -> Option<InvocationStatusView> {
+        self.0.get(invocation_id).map(|written| {
+            written
+                .as_ref()
+                .map(|written| InvocationStatusView::from(&written.status))
+                .unwrap_or(InvocationStatusView::FREE)
+        })
+    }
+
+    pub(crate) fn put(
+        &mut self,
+        invocation_id: InvocationId,
+        status: &InvocationStatus,
+        encoded_size: usize,
+    ) {
+        let written = (*status != InvocationStatus::Free).then(|| CachedInvocationStatus {
+            status: status.clone(),
+            encoded_size,
+        });
+        self.0.insert(invocation_id, written);
+    }
+
+    pub(crate) fn delete(&mut self, invocation_id: InvocationId) {
+        self.0.insert(invocation_id, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_storage_api::invocation_status_table::InFlightInvocationMetadata;
+
+    fn invoked() -> InvocationStatus {
+        InvocationStatus::Invoked(InFlightInvocationMetadata::mock())
+    }
+
+    #[test]
+    fn disabled_cache() {
+        let cache = InvocationStatusCache::new(0);
+        let invocation_id = InvocationId::mock_random();
+
+        cache.insert(invocation_id, invoked(), 100);
+
+        assert!(cache.get(&invocation_id).is_none());
+    }
+
+    #[test]
+    fn apply_committed_writes() {
+        let cache = InvocationStatusCache::new(1024);
+        let updated_id = InvocationId::mock_random();
+        let deleted_id = InvocationId::mock_random();
+        let status = invoked();
+        cache.insert(updated_id, InvocationStatus::Free, 100);
+        assert!(cache.get(&updated_id).is_none());
+        cache.insert(deleted_id, status.clone(), 100);
+
+        let mut writes = InvocationStatusWrites::default();
+        writes.put(updated_id, &status, 100);
+        writes.delete(deleted_id);
+        assert_eq!(writes.get(&updated_id), Some(status.clone()));
+        assert_eq!(writes.get(&deleted_id), Some(InvocationStatus::Free));
+
+        cache.apply_writes(writes);
+        assert_eq!(cache.get(&updated_id), Some(status));
+        assert!(cache.get(&deleted_id).is_none());
+    }
+}
@@ -8,23 +8,30 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
-use crate::owned_iter::OwnedIterator;
+mod cache;
+
+pub(crate) use cache::{InvocationStatusCache, InvocationStatusWrites};
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
+use crate::migration::{self, Migration};
+use crate::scan::{scan_table, ScanDirection};
 use crate::TableScan::FullScanPartitionKeyRange;
 use crate::{PartitionStore, TableKind, TableScanIterationDecision};
 use crate::{PartitionStoreTransaction, StorageAccess};
-use futures::Stream;
+use bytes::BytesMut;
+use futures::{Stream, StreamExt};
 use futures_util::stream;
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::invocation_status_table::{
-    InvocationStatus, InvocationStatusTable, InvocationStatusV1, ReadOnlyInvocationStatusTable,
+    ArchivedInvocationStatus, InvocationStatus, InvocationStatusTable, InvocationStatusV1,
+    InvocationStatusView, InvokedInvocation, ReadOnlyInvocationStatusTable,
 };
-use restate_storage_api::{Result, StorageError};
+use restate_storage_api::{Result, StorageError, Transaction};
 use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
-use restate_types::invocation::InvocationTarget;
 use restate_types::storage::StorageCodec;
+use restate_types::time::MillisSinceEpoch;
 use std::ops::RangeInclusive;
-use tracing::trace;
+use tracing::debug;
 
 // TODO remove this once we remove the old InvocationStatus
 define_table_key!(
@@ -35,13 +42,7 @@ define_table_key!(
         invocation_uuid: InvocationUuid
     )
 );
-
-// TODO remove this once we remove the old InvocationStatus
-fn create_invocation_status_key_v1(invocation_id: &InvocationId) -> InvocationStatusKeyV1 {
-    InvocationStatusKeyV1::default()
-        .partition_key(invocation_id.partition_key())
-        .invocation_uuid(invocation_id.invocation_uuid())
-}
+impl_table_record!(InvocationStatusKeyV1, InvocationStatusV1);
 
 define_table_key!(
     TableKind::InvocationStatus,
@@ -51,6 +52,7 @@ define_table_key!(
         invocation_uuid: InvocationUuid
     )
 );
+impl_table_record!(InvocationStatusKey, InvocationStatus);
 
 fn create_invocation_status_key(invocation_id: &InvocationId) -> InvocationStatusKey {
     InvocationStatusKey::default()
@@ -58,6 +60,57 @@ fn create_invocation_status_key(invocation_id: &InvocationId) -> InvocationStatu
         .invocation_uuid(invocation_id.invocation_uuid())
 }
 
+// Completed invocation statuses which have been moved out of the invocation status table, see
+// PartitionStore::archive_completed_invocation_statuses. Stored in the archive column family of
+// the partition.
+define_table_key!(
+    TableKind::InvocationStatusArchive,
+    KeyKind::InvocationStatusArchive,
+    InvocationStatusArchiveKey(
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid
+    )
+);
+impl_table_record!(InvocationStatusArchiveKey, ArchivedInvocationStatus);
+
+fn create_invocation_status_archive_key(
+    invocation_id: &InvocationId,
+) -> InvocationStatusArchiveKey {
+    InvocationStatusArchiveKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+}
+
+// TODO remove this once we remove the old InvocationStatus
+/// Moves the invocation statuses from the v1 to the v2 invocation status table.
+pub(crate) struct InvocationStatusV1Migration;
+
+impl Migration for InvocationStatusV1Migration {
+    const NAME: &'static str = "invocation-status-v1";
+
+    type SourceKey = InvocationStatusKeyV1;
+    type SourceValue = InvocationStatusV1;
+    type TargetKey = InvocationStatusKey;
+    type TargetValue = InvocationStatus;
+
+    fn source_key(target_key: &InvocationStatusKey) -> InvocationStatusKeyV1 {
+        InvocationStatusKeyV1 {
+            partition_key: target_key.partition_key,
+            invocation_uuid: target_key.invocation_uuid,
+        }
+    }
+
+    fn target_key(source_key: &InvocationStatusKeyV1) -> Result<InvocationStatusKey> {
+        Ok(InvocationStatusKey::default()
+            .partition_key(*source_key.partition_key_ok_or()?)
+            .invocation_uuid(*source_key.invocation_uuid_ok_or()?))
+    }
+
+    fn convert(source_value: InvocationStatusV1) -> Result<InvocationStatus> {
+        Ok(source_value.0)
+    }
+}
+
 // TODO remove this once we remove the old InvocationStatus
 fn invocation_id_from_v1_key_bytes<B: bytes::Buf>(bytes: &mut B) -> crate::Result<InvocationId> {
     let mut key = InvocationStatusKeyV1::deserialize_from(bytes)?;
@@ -87,41 +140,87 @@ fn invocation_id_from_key_bytes<B: bytes::Buf>(bytes: &mut B) -> crate::Result<I
     ))
 }
 
+/// Returns the size of the encoded status, or 0 if the status is free.
 fn put_invocation_status<S: StorageAccess>(
     storage: &mut S,
     invocation_id: &InvocationId,
     status: &InvocationStatus,
-) {
+) -> usize {
     match status {
         InvocationStatus::Free => {
             storage.delete_key(&create_invocation_status_key(invocation_id));
+            storage.delete_key(&create_invocation_status_archive_key(invocation_id));
+            0
         }
         _ => {
-            storage.put_kv(create_invocation_status_key(invocation_id), status);
+            let value = encode_invocation_status(storage, status);
+            let encoded_size = value.len();
+            storage.put_kv_raw(create_invocation_status_key(invocation_id), value);
+            encoded_size
         }
     }
 }
 
+fn encode_invocation_status<S: StorageAccess>(
+    storage: &mut S,
+    status: &InvocationStatus,
+) -> BytesMut {
+    let value_buffer = storage.cleared_value_buffer_mut(0);
+    StorageCodec::encode(status, value_buffer).unwrap();
+    value_buffer.split()
+}
+
 fn get_invocation_status<S: StorageAccess>(
     storage: &mut S,
     invocation_id: &InvocationId,
 ) -> Result<InvocationStatus> {
     let _x = RocksDbPerfGuard::new("get-invocation-status");
 
-    if let Some(s) =
-        storage.get_value::<_, InvocationStatus>(create_invocation_status_key(invocation_id))?
-    {
+    // The underlying assumption is that an invocation status will never exist in both old and new
+    // invocation status table.
+    if let Some(s) = migration::read::<InvocationStatusV1Migration, _>(
+        storage,
+        &create_invocation_status_key(invocation_id),
+    )? {
         return Ok(s);
     }
 
-    // todo: Remove this once we remove the old InvocationStatus
-    // The underlying assumption is that an invocation status will never exist in both old and new
-    // invocation status table.
+    get_archived_invocation_status(storage, invocation_id)
+}
+
+/// Reads the view of the status, decoding only its fields needed by the view.
+fn get_invocation_status_view<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<InvocationStatusView> {
+    let _x = RocksDbPerfGuard::new("get-invocation-status-view");
+
+    let key = create_invocation_status_key(invocation_id);
+    if let Some(view) = storage.get_value::<_, InvocationStatusView>(key.clone())? {
+        return Ok(view);
+    }
+
+    // Not yet migrated and archived statuses are rarely accessed, they're decoded fully
+    if let Some(InvocationStatusV1(status)) =
+        storage.get_value::<_, InvocationStatusV1>(InvocationStatusV1Migration::source_key(&key))?
+    {
+        return Ok(InvocationStatusView::from(&status));
+    }
+    get_archived_invocation_status(storage, invocation_id)
+        .map(|status| InvocationStatusView::from(&status))
+}
+
+fn get_archived_invocation_status<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Result<InvocationStatus> {
     storage
-        .get_value::<_, InvocationStatusV1>(create_invocation_status_key_v1(invocation_id))
+        .get_value::<_, ArchivedInvocationStatus>(create_invocation_status_archive_key(
+            invocation_id,
+        ))
         .map(|value| {
-            if let Some(invocation_status) = value {
-                invocation_status.0
+            if let Some(archived) = value {
+                InvocationStatus::Completed(archived.0)
             } else {
                 InvocationStatus::Free
             }
@@ -134,42 +233,82 @@ fn try_migrate_and_get_invocation_status<S: StorageAccess>(
 ) -> Result<InvocationStatus> {
     let _x = RocksDbPerfGuard::new("try-migrate-and-get-invocation-status");
 
-    let v1_key = create_invocation_status_key_v1(invocation_id);
-    if let Some(status_v1) = storage.get_value::<_, InvocationStatusV1>(v1_key.clone())? {
-        trace!("Migrating invocation {invocation_id} from InvocationStatus V1");
-        put_invocation_status(
-            storage,
-            &InvocationId::from_parts(
-                *v1_key.partition_key_ok_or()?,
-                *v1_key.invocation_uuid_ok_or()?,
-            ),
-            &status_v1.0,
-        );
-        storage.delete_key(&v1_key);
-        return Ok(status_v1.0);
+    if let Some(s) = migration::read_and_migrate::<InvocationStatusV1Migration, _>(
+        storage,
+        &create_invocation_status_key(invocation_id),
+    )? {
+        return Ok(s);
     }
 
-    storage
-        .get_value::<_, InvocationStatus>(create_invocation_status_key(invocation_id))
-        .map(|value| {
-            if let Some(invocation_status) = value {
-                invocation_status
-            } else {
-                InvocationStatus::Free
-            }
-        })
+    get_archived_invocation_status(storage, invocation_id)
 }
 
 fn delete_invocation_status<S: StorageAccess>(storage: &mut S, invocation_id: &InvocationId) {
-    // TODO remove this once we remove the old InvocationStatus
-    storage.delete_key(&create_invocation_status_key_v1(invocation_id));
-    storage.delete_key(&create_invocation_status_key(invocation_id));
+    migration::delete::<InvocationStatusV1Migration, _>(
+        storage,
+        &create_invocation_status_key(invocation_id),
+    );
+    storage.delete_key(&create_invocation_status_archive_key(invocation_id));
+}
+
+/// Collects up to `limit` completed invocations which completed before `completed_before`.
+fn completed_invocations_to_archive<S: StorageAccess>(
+    storage: &mut S,
+    partition_key_range: RangeInclusive<PartitionKey>,
+    completed_before: MillisSinceEpoch,
+    limit: usize,
+) -> Result<Vec<(InvocationId, ArchivedInvocationStatus)>> {
+    let _x = RocksDbPerfGuard::new("completed-invocations-to-archive");
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+    let mut collected = 0;
+    storage
+        .for_each_key_value_in_place(
+            FullScanPartitionKeyRange::<InvocationStatusKey>(partition_key_range),
+            |mut k, mut v| match read_completed_before(&mut k, &mut v, completed_before).transpose()
+            {
+                Some(res) => {
+                    collected += 1;
+                    if collected >= limit {
+                        TableScanIterationDecision::BreakWith(res)
+                    } else {
+                        TableScanIterationDecision::Emit(res)
+                    }
+                }
+                None => TableScanIterationDecision::Continue,
+            },
+        )
+        .into_iter()
+        .collect()
+}
+
+fn read_completed_before(
+    mut k: &mut &[u8],
+    v: &mut &[u8],
+    completed_before: MillisSinceEpoch,
+) -> Result<Option<(InvocationId, ArchivedInvocationStatus)>> {
+    let invocation_id = invocation_id_from_key_bytes(&mut k)?;
+    let invocation_status = StorageCodec::decode::<InvocationStatus, _>(v)
+        .map_err(|err| StorageError::Generic(err.into()))?;
+    if let InvocationStatus::Completed(completed) = invocation_status {
+        // SAFETY: the completion time is wall-clock time, hence replicas archive a status at
+        // different points in time. Archiving drops the source and span context of the status,
+        // which the state machine never reads for completed invocations, so its decisions don't
+        // depend on whether a status has been archived yet. Queries do observe the difference,
+        // see PartitionStore::archive_completed_invocation_statuses.
+        let completed_transition_time = unsafe { completed.timestamps.completed_transition_time() };
+        if completed_transition_time.is_some_and(|t| t < completed_before) {
+            return Ok(Some((invocation_id, ArchivedInvocationStatus(completed))));
+        }
+    }
+    Ok(None)
 }
 
 fn invoked_invocations<S: StorageAccess>(
     storage: &mut S,
     partition_key_range: RangeInclusive<PartitionKey>,
-) -> Vec<Result<(InvocationId, InvocationTarget)>> {
+) -> Vec<Result<InvokedInvocation>> {
     let _x = RocksDbPerfGuard::new("invoked-invocations");
     let mut invocations = storage.for_each_key_value_in_place(
         FullScanPartitionKeyRange::<InvocationStatusKeyV1>(partition_key_range.clone()),
@@ -201,37 +340,51 @@ fn all_invocation_status<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(InvocationId, InvocationStatus)>> + Send + '_ {
-    stream::iter(
-        OwnedIterator::new(storage.iterator_from(
-            FullScanPartitionKeyRange::<InvocationStatusKeyV1>(range.clone()),
+    scan_table(
+        storage,
+        FullScanPartitionKeyRange::<InvocationStatusKeyV1>(range.clone()),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (state_key, state_value) = row?;
+        let (partition_key, invocation_uuid) = state_key.into_inner_ok_or()?;
+        Ok((
+            InvocationId::from_parts(partition_key, invocation_uuid),
+            state_value.0,
         ))
-        .map(|(mut key, mut value)| {
-            let state_key = InvocationStatusKeyV1::deserialize_from(&mut key)?;
-            let state_value = StorageCodec::decode::<InvocationStatusV1, _>(&mut value)
-                .map_err(|err| StorageError::Conversion(err.into()))?;
-
+    })
+    .chain(
+        scan_table(
+            storage,
+            FullScanPartitionKeyRange::<InvocationStatusKey>(range.clone()),
+            ScanDirection::Forward,
+            None,
+        )
+        .map(|row| {
+            let (state_key, state_value) = row?;
             let (partition_key, invocation_uuid) = state_key.into_inner_ok_or()?;
             Ok((
                 InvocationId::from_parts(partition_key, invocation_uuid),
-                state_value.0,
+                state_value,
             ))
-        })
-        .chain(
-            OwnedIterator::new(storage.iterator_from(FullScanPartitionKeyRange::<
-                InvocationStatusKey,
-            >(range.clone())))
-            .map(|(mut key, mut value)| {
-                let state_key = InvocationStatusKey::deserialize_from(&mut key)?;
-                let state_value = StorageCodec::decode::<InvocationStatus, _>(&mut value)
-                    .map_err(|err| StorageError::Conversion(err.into()))?;
-
-                let (partition_key, invocation_uuid) = state_key.into_inner_ok_or()?;
-                Ok((
-                    InvocationId::from_parts(partition_key, invocation_uuid),
-                    state_value,
-                ))
-            }),
-        ),
+        }),
+    )
+    .chain(
+        scan_table(
+            storage,
+            FullScanPartitionKeyRange::<InvocationStatusArchiveKey>(range),
+            ScanDirection::Forward,
+            None,
+        )
+        .map(|row| {
+            let (state_key, state_value) = row?;
+            let (partition_key, invocation_uuid) = state_key.into_inner_ok_or()?;
+            Ok((
+                InvocationId::from_parts(partition_key, invocation_uuid),
+                InvocationStatus::Completed(state_value.0),
+            ))
+        }),
     )
 }
 
@@ -239,12 +392,17 @@ fn all_invocation_status<S: StorageAccess>(
 fn read_invoked_v1_full_invocation_id(
     mut k: &mut &[u8],
     v: &mut &[u8],
-) -> Result<Option<(InvocationId, InvocationTarget)>> {
+) -> Result<Option<InvokedInvocation>> {
     let invocation_id = invocation_id_from_v1_key_bytes(&mut k)?;
     let invocation_status = StorageCodec::decode::<InvocationStatusV1, _>(v)
         .map_err(|err| StorageError::Generic(err.into()))?;
     if let InvocationStatus::Invoked(invocation_meta) = invocation_status.0 {
-        Ok(Some((invocation_id, invocation_meta.invocation_target)))
+        Ok(Some(InvokedInvocation {
+            invocation_id,
+            invocation_target: invocation_meta.invocation_target,
+            invocation_epoch: invocation_meta.invocation_epoch,
+            execution_deadline: invocation_meta.execution_deadline,
+        }))
     } else {
         Ok(None)
     }
@@ -253,18 +411,70 @@ fn read_invoked_v1_full_invocation_id(
 fn read_invoked_full_invocation_id(
     mut k: &mut &[u8],
     v: &mut &[u8],
-) -> Result<Option<(InvocationId, InvocationTarget)>> {
+) -> Result<Option<InvokedInvocation>> {
     // TODO this can be improved by simply parsing InvocationTarget and the Status enum
     let invocation_id = invocation_id_from_key_bytes(&mut k)?;
     let invocation_status = StorageCodec::decode::<InvocationStatus, _>(v)
         .map_err(|err| StorageError::Generic(err.into()))?;
     if let InvocationStatus::Invoked(invocation_meta) = invocation_status {
-        Ok(Some((invocation_id, invocation_meta.invocation_target)))
+        Ok(Some(InvokedInvocation {
+            invocation_id,
+            invocation_target: invocation_meta.invocation_target,
+            invocation_epoch: invocation_meta.invocation_epoch,
+            execution_deadline: invocation_meta.execution_deadline,
+        }))
     } else {
         Ok(None)
     }
 }
 
+impl PartitionStore {
+    /// Moves up to `limit` completed invocation statuses which completed before
+    /// `completed_before` to the invocation status archive, which stores them with a slimmer
+    /// schema in a column family of its own. Archived statuses are transparently returned by the
+    /// invocation status table. Returns the number of archived statuses.
+    ///
+    /// The archive drops the source and span context of the statuses, which is visible to
+    /// queries: once archived, an invocation shows up in `sys_invocation_status` as
+    /// `invoked_by = 'restate'` without a trace id. Since `completed_before` is derived from
+    /// wall-clock time, this happens at different times on different replicas.
+    ///
+    /// Must not be called concurrently with transactions modifying the invocation status table.
+    pub async fn archive_completed_invocation_statuses(
+        &mut self,
+        completed_before: MillisSinceEpoch,
+        limit: usize,
+    ) -> Result<usize> {
+        let to_archive = completed_invocations_to_archive(
+            self,
+            self.partition_key_range().clone(),
+            completed_before,
+            limit,
+        )?;
+        if to_archive.is_empty() {
+            return Ok(0);
+        }
+
+        let archived = to_archive.len();
+        let mut transaction = self.transaction();
+        for (invocation_id, archived_status) in to_archive {
+            transaction.put_kv(
+                create_invocation_status_archive_key(&invocation_id),
+                &archived_status,
+            );
+            transaction.delete_key(&create_invocation_status_key(&invocation_id));
+            // The archived status doesn't retain all the fields of the cached one
+            transaction
+                .invocation_status_writes_mut()
+                .delete(invocation_id);
+        }
+        transaction.commit().await?;
+
+        debug!("Archived {archived} completed invocation statuses");
+        Ok(archived)
+    }
+}
+
 impl ReadOnlyInvocationStatusTable for PartitionStore {
     async fn get_invocation_status(
         &mut self,
@@ -274,9 +484,15 @@ impl ReadOnlyInvocationStatusTable for PartitionStore {
         get_invocation_status(self, invocation_id)
     }
 
-    fn all_invoked_invocations(
+    async fn get_invocation_status_view(
         &mut self,
-    ) -> impl Stream<Item = Result<(InvocationId, InvocationTarget)>> + Send {
+        invocation_id: &InvocationId,
+    ) -> Result<InvocationStatusView> {
+        self.assert_partition_key(invocation_id);
+        get_invocation_status_view(self, invocation_id)
+    }
+
+    fn all_invoked_invocations(&mut self) -> impl Stream<Item = Result<InvokedInvocation>> + Send {
         stream::iter(invoked_invocations(
             self,
             self.partition_key_range().clone(),
@@ -297,12 +513,38 @@ impl<'a> ReadOnlyInvocationStatusTable for PartitionStoreTransaction<'a> {
         invocation_id: &InvocationId,
     ) -> Result<InvocationStatus> {
         self.assert_partition_key(invocation_id);
-        try_migrate_and_get_invocation_status(self, invocation_id)
+        if let Some(status) = self.invocation_status_writes().get(invocation_id) {
+            return Ok(status);
+        }
+        let cache = self.invocation_status_cache();
+        if let Some(status) = cache.get(invocation_id) {
+            return Ok(status);
+        }
+
+        let status = try_migrate_and_get_invocation_status(self, invocation_id)?;
+        if cache.is_enabled() && status != InvocationStatus::Free {
+            // Not written by this transaction, hence this is the committed status
+            let encoded_size = encode_invocation_status(self, &status).len();
+            cache.insert(*invocation_id, status.clone(), encoded_size);
+        }
+        Ok(status)
     }
 
-    fn all_invoked_invocations(
+    async fn get_invocation_status_view(
         &mut self,
-    ) -> impl Stream<Item = Result<(InvocationId, InvocationTarget)>> + Send {
+        invocation_id: &InvocationId,
+    ) -> Result<InvocationStatusView> {
+        self.assert_partition_key(invocation_id);
+        if let Some(view) = self.invocation_status_writes().get_view(invocation_id) {
+            return Ok(view);
+        }
+        if let Some(view) = self.invocation_status_cache().get_view(invocation_id) {
+            return Ok(view);
+        }
+        get_invocation_status_view(self, invocation_id)
+    }
+
+    fn all_invoked_invocations(&mut self) -> impl Stream<Item = Result<InvokedInvocation>> + Send {
         stream::iter(invoked_invocations(
             self,
             self.partition_key_range().clone(),
@@ -324,12 +566,19 @@ impl<'a> InvocationStatusTable for PartitionStoreTransaction<'a> {
         status: &InvocationStatus,
     ) {
         self.assert_partition_key(invocation_id);
-        put_invocation_status(self, invocation_id, status)
+        let encoded_size = put_invocation_status(self, invocation_id, status);
+        if self.invocation_status_cache().is_enabled() {
+            self.invocation_status_writes_mut()
+                .put(*invocation_id, status, encoded_size);
+        }
     }
 
     async fn delete_invocation_status(&mut self, invocation_id: &InvocationId) {
         self.assert_partition_key(invocation_id);
-        delete_invocation_status(self, invocation_id)
+        delete_invocation_status(self, invocation_id);
+        if self.invocation_status_cache().is_enabled() {
+            self.invocation_status_writes_mut().delete(*invocation_id);
+        }
     }
 }
 
@@ -9,13 +9,13 @@
 // by the Apache License, Version 2.0.
 
 use crate::keys::TableKey;
-use crate::keys::{define_table_key, KeyKind};
-use crate::owned_iter::OwnedIterator;
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
 use crate::scan::TableScan::FullScanPartitionKeyRange;
+use crate::scan::{scan_table, ScanDirection};
 use crate::TableKind::Journal;
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess};
 use crate::{TableScan, TableScanIterationDecision};
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use futures_util::stream;
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::journal_table::{JournalEntry, JournalTable, ReadOnlyJournalTable};
@@ -36,6 +36,7 @@ define_table_key!(
         journal_index: u32
     )
 );
+impl_table_record!(JournalKey, JournalEntry);
 
 fn write_journal_entry_key(invocation_id: &InvocationId, journal_index: u32) -> JournalKey {
     JournalKey::default()
@@ -103,11 +104,14 @@ fn all_journals<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(JournalEntryId, JournalEntry)>> + Send + '_ {
-    let iter = storage.iterator_from(FullScanPartitionKeyRange::<JournalKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, mut value)| {
-        let journal_key = JournalKey::deserialize_from(&mut key)?;
-        let journal_entry = StorageCodec::decode::<JournalEntry, _>(&mut value)
-            .map_err(|err| StorageError::Conversion(err.into()))?;
+    scan_table(
+        storage,
+        FullScanPartitionKeyRange::<JournalKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (journal_key, journal_entry) = row?;
 
         let (partition_key, invocation_uuid, entry_index) = journal_key.into_inner_ok_or()?;
 
@@ -118,7 +122,7 @@ fn all_journals<S: StorageAccess>(
             ),
             journal_entry,
         ))
-    }))
+    })
 }
 
 fn delete_journal<S: StorageAccess>(
@@ -0,0 +1,381 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::journal_table::JournalKey;
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
+use crate::scan::TableScan::FullScanPartitionKeyRange;
+use crate::scan::{scan_table, ScanDirection};
+use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess, TableKind};
+use crate::{TableScan, TableScanIterationDecision};
+use futures::{Stream, StreamExt};
+use futures_util::stream;
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::journal_table::JournalEntry;
+use restate_storage_api::journal_table_v2::{
+    CompletionId, JournalTableV2, PendingCompletion, ReadOnlyJournalTableV2, StoredEntry,
+};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{
+    EntryIndex, InvocationId, InvocationUuid, JournalEntryId, PartitionKey, WithPartitionKey,
+};
+use restate_types::storage::StorageCodec;
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+
+define_table_key!(
+    TableKind::Journal,
+    KeyKind::JournalV2,
+    JournalV2Key(
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid,
+        journal_index: u32
+    )
+);
+impl_table_record!(JournalV2Key, StoredEntry);
+
+// Index of the completions awaited by the entries of the journal v2 table.
+define_table_key!(
+    TableKind::Journal,
+    KeyKind::JournalPendingCompletion,
+    JournalPendingCompletionKey(
+        partition_key: PartitionKey,
+        invocation_uuid: InvocationUuid,
+        completion_id: u32
+    )
+);
+impl_table_record!(JournalPendingCompletionKey, PendingCompletion);
+
+fn write_journal_entry_key(invocation_id: &InvocationId, journal_index: u32) -> JournalV2Key {
+    JournalV2Key::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+        .journal_index(journal_index)
+}
+
+fn pending_completion_key(
+    invocation_id: &InvocationId,
+    completion_id: CompletionId,
+) -> JournalPendingCompletionKey {
+    JournalPendingCompletionKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+        .completion_id(completion_id)
+}
+
+fn pending_completions_prefix(invocation_id: &InvocationId) -> JournalPendingCompletionKey {
+    JournalPendingCompletionKey::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid())
+}
+
+fn put_journal_entry<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    journal_index: u32,
+    journal_entry: &StoredEntry,
+) {
+    storage.put_kv(
+        write_journal_entry_key(invocation_id, journal_index),
+        journal_entry,
+    );
+
+    if let Some(completion_id) = journal_entry.completion_id {
+        let key = pending_completion_key(invocation_id, completion_id);
+        if journal_entry.is_pending() {
+            storage.put_kv(key, &PendingCompletion { journal_index });
+        } else {
+            storage.delete_key(&key);
+        }
+    }
+}
+
+fn get_journal_entry<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    journal_index: u32,
+) -> Result<Option<StoredEntry>> {
+    storage.get_value(write_journal_entry_key(invocation_id, journal_index))
+}
+
+fn get_journal<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    journal_length: EntryIndex,
+) -> Vec<Result<(EntryIndex, StoredEntry)>> {
+    let _x = RocksDbPerfGuard::new("get-journal-v2");
+    if journal_length == 0 {
+        return Vec::new();
+    }
+    let key = JournalV2Key::default()
+        .partition_key(invocation_id.partition_key())
+        .invocation_uuid(invocation_id.invocation_uuid());
+
+    let mut n = 0;
+    storage.for_each_key_value_in_place(
+        TableScan::SinglePartitionKeyPrefix(invocation_id.partition_key(), key),
+        move |k, mut v| {
+            let key = JournalV2Key::deserialize_from(&mut Cursor::new(k)).map(|journal_key| {
+                journal_key
+                    .journal_index
+                    .expect("The journal index must be part of the journal key.")
+            });
+            let entry = StorageCodec::decode::<StoredEntry, _>(&mut v)
+                .map_err(|error| StorageError::Generic(error.into()));
+
+            let result = key.and_then(|key| entry.map(|entry| (key, entry)));
+
+            n += 1;
+            if n < journal_length {
+                TableScanIterationDecision::Emit(result)
+            } else {
+                TableScanIterationDecision::BreakWith(result)
+            }
+        },
+    )
+}
+
+fn get_pending_completion<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    completion_id: CompletionId,
+) -> Result<Option<EntryIndex>> {
+    Ok(storage
+        .get_value::<_, PendingCompletion>(pending_completion_key(invocation_id, completion_id))?
+        .map(|pending_completion| pending_completion.journal_index))
+}
+
+fn get_pending_completions<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+) -> Vec<Result<(CompletionId, EntryIndex)>> {
+    let _x = RocksDbPerfGuard::new("get-pending-completions");
+    storage.for_each_key_value_in_place(
+        TableScan::SinglePartitionKeyPrefix(
+            invocation_id.partition_key(),
+            pending_completions_prefix(invocation_id),
+        ),
+        |k, mut v| {
+            let completion_id = JournalPendingCompletionKey::deserialize_from(&mut Cursor::new(k))
+                .and_then(|key| key.completion_id.ok_or(StorageError::DataIntegrityError));
+            let pending_completion = StorageCodec::decode::<PendingCompletion, _>(&mut v)
+                .map_err(|error| StorageError::Generic(error.into()));
+
+            TableScanIterationDecision::Emit(completion_id.and_then(|completion_id| {
+                pending_completion
+                    .map(|pending_completion| (completion_id, pending_completion.journal_index))
+            }))
+        },
+    )
+}
+
+fn all_journals<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<(JournalEntryId, StoredEntry)>> + Send + '_ {
+    scan_table(
+        storage,
+        FullScanPartitionKeyRange::<JournalV2Key>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (journal_key, journal_entry) = row?;
+
+        let (partition_key, invocation_uuid, entry_index) = journal_key.into_inner_ok_or()?;
+
+        Ok((
+            JournalEntryId::from_parts(
+                InvocationId::from_parts(partition_key, invocation_uuid),
+                entry_index,
+            ),
+            journal_entry,
+        ))
+    })
+}
+
+fn delete_journal<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    journal_length: EntryIndex,
+) -> Result<()> {
+    let mut key = write_journal_entry_key(invocation_id, 0);
+    let k = &mut key;
+    for journal_index in 0..journal_length {
+        k.journal_index = Some(journal_index);
+        storage.delete_key(k);
+    }
+
+    let pending_completions = get_pending_completions(storage, invocation_id)
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    for (completion_id, _) in pending_completions {
+        storage.delete_key(&pending_completion_key(invocation_id, completion_id));
+    }
+
+    Ok(())
+}
+
+fn migrate_journal<S: StorageAccess>(
+    storage: &mut S,
+    invocation_id: &InvocationId,
+    journal_length: EntryIndex,
+) -> Result<()> {
+    for journal_index in 0..journal_length {
+        let source_key = JournalKey::default()
+            .partition_key(invocation_id.partition_key())
+            .invocation_uuid(invocation_id.invocation_uuid())
+            .journal_index(journal_index);
+        let Some(entry) = storage.get_value::<_, JournalEntry>(source_key.clone())? else {
+            // already migrated
+            continue;
+        };
+
+        // The journal table completes entries by their journal index
+        let completion_id = match &entry {
+            JournalEntry::Entry(entry) => entry.header().is_completed().map(|_| journal_index),
+            JournalEntry::Completion(_) => None,
+        };
+        put_journal_entry(
+            storage,
+            invocation_id,
+            journal_index,
+            &StoredEntry {
+                entry,
+                completion_id,
+                append_time: None,
+            },
+        );
+        storage.delete_key(&source_key);
+    }
+
+    Ok(())
+}
+
+impl ReadOnlyJournalTableV2 for PartitionStore {
+    async fn get_journal_entry(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_index: u32,
+    ) -> Result<Option<StoredEntry>> {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("get-journal-entry-v2");
+        get_journal_entry(self, invocation_id, journal_index)
+    }
+
+    fn get_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, StoredEntry)>> + Send {
+        self.assert_partition_key(invocation_id);
+        stream::iter(get_journal(self, invocation_id, journal_length))
+    }
+
+    async fn get_pending_completion(
+        &mut self,
+        invocation_id: &InvocationId,
+        completion_id: CompletionId,
+    ) -> Result<Option<EntryIndex>> {
+        self.assert_partition_key(invocation_id);
+        get_pending_completion(self, invocation_id, completion_id)
+    }
+
+    fn get_pending_completions(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Stream<Item = Result<(CompletionId, EntryIndex)>> + Send {
+        self.assert_partition_key(invocation_id);
+        stream::iter(get_pending_completions(self, invocation_id))
+    }
+
+    fn all_journals(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(JournalEntryId, StoredEntry)>> + Send {
+        all_journals(self, range)
+    }
+}
+
+impl<'a> ReadOnlyJournalTableV2 for PartitionStoreTransaction<'a> {
+    async fn get_journal_entry(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_index: u32,
+    ) -> Result<Option<StoredEntry>> {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("get-journal-entry-v2");
+        get_journal_entry(self, invocation_id, journal_index)
+    }
+
+    fn get_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, StoredEntry)>> + Send {
+        self.assert_partition_key(invocation_id);
+        stream::iter(get_journal(self, invocation_id, journal_length))
+    }
+
+    async fn get_pending_completion(
+        &mut self,
+        invocation_id: &InvocationId,
+        completion_id: CompletionId,
+    ) -> Result<Option<EntryIndex>> {
+        self.assert_partition_key(invocation_id);
+        get_pending_completion(self, invocation_id, completion_id)
+    }
+
+    fn get_pending_completions(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Stream<Item = Result<(CompletionId, EntryIndex)>> + Send {
+        self.assert_partition_key(invocation_id);
+        stream::iter(get_pending_completions(self, invocation_id))
+    }
+
+    fn all_journals(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(JournalEntryId, StoredEntry)>> + Send {
+        all_journals(self, range)
+    }
+}
+
+impl<'a> JournalTableV2 for PartitionStoreTransaction<'a> {
+    async fn put_journal_entry(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_index: u32,
+        journal_entry: &StoredEntry,
+    ) {
+        self.assert_partition_key(invocation_id);
+        put_journal_entry(self, invocation_id, journal_index, journal_entry)
+    }
+
+    async fn delete_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> Result<()> {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("delete-journal-v2");
+        delete_journal(self, invocation_id, journal_length)
+    }
+
+    async fn migrate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> Result<()> {
+        self.assert_partition_key(invocation_id);
+        let _x = RocksDbPerfGuard::new("migrate-journal-v2");
+        migrate_journal(self, invocation_id, journal_length)
+    }
+}
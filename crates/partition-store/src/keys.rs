@@ -30,14 +30,25 @@ pub enum KeyKind {
     Fsm,
     Idempotency,
     Inbox,
+    InboxOrder,
+    PausedInbox,
     InvocationStatusV1,
     InvocationStatus,
+    InvocationStatusArchive,
     Journal,
+    JournalV2,
+    JournalPendingCompletion,
     Outbox,
     ServiceStatus,
     State,
     Timers,
     Promise,
+    DeadLetter,
+    InvocationIndex,
+    SharedHandlerExecutions,
+    Schedule,
+    InvocationEvent,
+    PreparedMessage,
 }
 
 impl KeyKind {
@@ -70,14 +81,25 @@ impl KeyKind {
             KeyKind::Fsm => b"fs",
             KeyKind::Idempotency => b"ip",
             KeyKind::Inbox => b"ib",
+            KeyKind::InboxOrder => b"io",
+            KeyKind::PausedInbox => b"pi",
             KeyKind::InvocationStatusV1 => b"is",
             KeyKind::InvocationStatus => b"iS",
+            KeyKind::InvocationStatusArchive => b"ia",
             KeyKind::Journal => b"jo",
+            KeyKind::JournalV2 => b"jn",
+            KeyKind::JournalPendingCompletion => b"jc",
             KeyKind::Outbox => b"ob",
             KeyKind::ServiceStatus => b"ss",
             KeyKind::State => b"st",
             KeyKind::Timers => b"ti",
             KeyKind::Promise => b"pr",
+            KeyKind::DeadLetter => b"dl",
+            KeyKind::InvocationIndex => b"ix",
+            KeyKind::SharedHandlerExecutions => b"sx",
+            KeyKind::Schedule => b"sc",
+            KeyKind::InvocationEvent => b"ie",
+            KeyKind::PreparedMessage => b"pm",
         }
     }
 
@@ -95,14 +117,25 @@ impl KeyKind {
             b"fs" => Some(KeyKind::Fsm),
             b"ip" => Some(KeyKind::Idempotency),
             b"ib" => Some(KeyKind::Inbox),
+            b"io" => Some(KeyKind::InboxOrder),
+            b"pi" => Some(KeyKind::PausedInbox),
             b"is" => Some(KeyKind::InvocationStatusV1),
             b"iS" => Some(KeyKind::InvocationStatus),
+            b"ia" => Some(KeyKind::InvocationStatusArchive),
             b"jo" => Some(KeyKind::Journal),
+            b"jn" => Some(KeyKind::JournalV2),
+            b"jc" => Some(KeyKind::JournalPendingCompletion),
             b"ob" => Some(KeyKind::Outbox),
             b"ss" => Some(KeyKind::ServiceStatus),
             b"st" => Some(KeyKind::State),
             b"ti" => Some(KeyKind::Timers),
             b"pr" => Some(KeyKind::Promise),
+            b"dl" => Some(KeyKind::DeadLetter),
+            b"ix" => Some(KeyKind::InvocationIndex),
+            b"sx" => Some(KeyKind::SharedHandlerExecutions),
+            b"sc" => Some(KeyKind::Schedule),
+            b"ie" => Some(KeyKind::InvocationEvent),
+            b"pm" => Some(KeyKind::PreparedMessage),
             _ => None,
         }
     }
@@ -141,6 +174,29 @@ pub trait TableKey: Sized + std::fmt::Debug + Send + 'static {
     fn serialized_length(&self) -> usize;
 }
 
+/// Ties the key of a table to the type of its values, so that the records of the table can be
+/// scanned generically with [`crate::PartitionStore::scan_table`]. The fsm table stores values of
+/// different types depending on the key, hence it doesn't implement this trait.
+pub trait TableRecord: TableKey {
+    type Value: Send + 'static;
+
+    fn decode_value(value: &mut Bytes) -> crate::Result<Self::Value>;
+}
+
+/// Implements [`TableRecord`] for a table whose values are encoded with the storage codec.
+macro_rules! impl_table_record {
+    ($key_name:ident, $value:ty) => {
+        impl $crate::keys::TableRecord for $key_name {
+            type Value = $value;
+
+            fn decode_value(value: &mut ::bytes::Bytes) -> $crate::Result<Self::Value> {
+                ::restate_types::storage::StorageCodec::decode::<$value, _>(value)
+                    .map_err(|err| ::restate_storage_api::StorageError::Conversion(err.into()))
+            }
+        }
+    };
+}
+
 /// The following macro defines an ordered, named key tuple, that is used as a rocksdb key.
 ///
 /// Given the following definition
@@ -317,6 +373,7 @@ macro_rules! define_table_key {
 use crate::PaddedPartitionId;
 use crate::TableKind;
 pub(crate) use define_table_key;
+pub(crate) use impl_table_record;
 use restate_storage_api::deduplication_table::ProducerId;
 use restate_storage_api::timer_table::TimerKeyKind;
 use restate_storage_api::StorageError;
@@ -531,6 +588,10 @@ impl KeyCodec for TimerKeyKind {
                 target.put_u8(3);
                 invocation_uuid.encode(target);
             }
+            TimerKeyKind::FireSchedule { schedule_uuid } => {
+                target.put_u8(4);
+                schedule_uuid.encode(target);
+            }
         }
     }
 
@@ -562,6 +623,10 @@ impl KeyCodec for TimerKeyKind {
                 let invocation_uuid = InvocationUuid::decode(source)?;
                 TimerKeyKind::NeoInvoke { invocation_uuid }
             }
+            4 => {
+                let schedule_uuid = InvocationUuid::decode(source)?;
+                TimerKeyKind::FireSchedule { schedule_uuid }
+            }
             i => {
                 return Err(StorageError::Generic(anyhow!(
                     "Unknown discriminator for TimerKind: '{}'",
@@ -589,6 +654,9 @@ impl KeyCodec for TimerKeyKind {
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => {
                 KeyCodec::serialized_length(invocation_uuid)
             }
+            TimerKeyKind::FireSchedule { schedule_uuid } => {
+                KeyCodec::serialized_length(schedule_uuid)
+            }
         }
     }
 }
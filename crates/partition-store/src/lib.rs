@@ -8,19 +8,29 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod consistency;
+pub mod dead_letter_table;
 pub mod deduplication_table;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
+pub mod inspect;
+pub mod invocation_event_table;
+pub mod invocation_index_table;
 pub mod invocation_status_table;
 pub mod journal_table;
+pub mod journal_table_v2;
 pub mod keys;
+mod metric_definitions;
+pub mod migration;
 pub mod outbox_table;
 mod owned_iter;
 mod partition_store;
 mod partition_store_manager;
+pub mod prepared_message_table;
 pub mod promise_table;
 pub mod scan;
+pub mod schedule_table;
 pub mod service_status_table;
 pub mod snapshots;
 pub mod state_table;
@@ -0,0 +1,57 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+/// Optional to have but adds description/help message to the metrics emitted to
+/// the metrics' sink.
+use metrics::{describe_counter, describe_gauge, Unit};
+
+pub const MIGRATION_RECORDS: &str = "restate.partition_store.migration.records.total";
+pub const MIGRATION_COMPLETED: &str = "restate.partition_store.migration.completed";
+
+pub const INVOCATION_STATUS_CACHE_HITS: &str =
+    "restate.partition_store.invocation_status_cache.hits.total";
+pub const INVOCATION_STATUS_CACHE_MISSES: &str =
+    "restate.partition_store.invocation_status_cache.misses.total";
+pub const INVOCATION_STATUS_CACHE_EVICTIONS: &str =
+    "restate.partition_store.invocation_status_cache.evictions.total";
+
+pub const MIGRATION_LABEL: &str = "migration";
+pub const MIGRATION_MODE_LABEL: &str = "mode";
+pub const MIGRATION_MODE_LAZY: &str = "lazy";
+pub const MIGRATION_MODE_BACKGROUND: &str = "background";
+pub const PARTITION_LABEL: &str = "partition";
+
+pub(crate) fn describe_metrics() {
+    describe_counter!(
+        MIGRATION_RECORDS,
+        Unit::Count,
+        "Number of stored values migrated, either lazily when accessed or by the background rewrite"
+    );
+    describe_gauge!(
+        MIGRATION_COMPLETED,
+        Unit::Count,
+        "1 if the background rewrite of the migration completed for the partition, 0 otherwise"
+    );
+    describe_counter!(
+        INVOCATION_STATUS_CACHE_HITS,
+        Unit::Count,
+        "Number of invocation status reads of the partition processor served by the cache"
+    );
+    describe_counter!(
+        INVOCATION_STATUS_CACHE_MISSES,
+        Unit::Count,
+        "Number of invocation status reads of the partition processor which missed the cache"
+    );
+    describe_counter!(
+        INVOCATION_STATUS_CACHE_EVICTIONS,
+        Unit::Count,
+        "Number of invocation statuses evicted from the cache to stay within its memory limit"
+    );
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Migration of stored types whose key or encoding changes, such as the invocation status v1 to
+//! v2 migration.
+//!
+//! A [`Migration`] declares the source and the target key and value types, and how to convert
+//! from one to the other. The tables use the functions of this module to transparently read
+//! values which haven't been migrated yet, and to migrate them lazily when they are accessed
+//! within a transaction. The values which are never accessed are rewritten in batches by
+//! [`PartitionStore::run_migrations`].
+
+use std::ops::RangeInclusive;
+
+use metrics::{counter, gauge};
+use tracing::debug;
+
+use restate_storage_api::{Result, StorageError, Transaction};
+use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::storage::{StorageCodec, StorageDecode, StorageEncode};
+
+use crate::invocation_status_table::InvocationStatusV1Migration;
+use crate::keys::TableKey;
+use crate::metric_definitions::{
+    MIGRATION_COMPLETED, MIGRATION_LABEL, MIGRATION_MODE_BACKGROUND, MIGRATION_MODE_LABEL,
+    MIGRATION_MODE_LAZY, MIGRATION_RECORDS, PARTITION_LABEL,
+};
+use crate::scan::TableScan;
+use crate::{PartitionStore, StorageAccess, TableScanIterationDecision};
+
+pub trait Migration {
+    /// Name of the migration, used to label its metrics.
+    const NAME: &'static str;
+
+    type SourceKey: TableKey + Clone;
+    type SourceValue: StorageDecode;
+    type TargetKey: TableKey + Clone;
+    type TargetValue: StorageEncode + StorageDecode;
+
+    fn source_key(target_key: &Self::TargetKey) -> Self::SourceKey;
+
+    fn target_key(source_key: &Self::SourceKey) -> Result<Self::TargetKey>;
+
+    fn convert(source_value: Self::SourceValue) -> Result<Self::TargetValue>;
+
+    /// Scan over the source values of a partition, used by the background rewrite.
+    fn source_scan(
+        _partition_id: PartitionId,
+        partition_key_range: RangeInclusive<PartitionKey>,
+    ) -> TableScan<Self::SourceKey> {
+        TableScan::FullScanPartitionKeyRange(partition_key_range)
+    }
+}
+
+/// Reads the value from the target table, falling back to the source table if the value hasn't
+/// been migrated yet. Doesn't migrate the value.
+pub(crate) fn read<M: Migration, S: StorageAccess>(
+    storage: &mut S,
+    target_key: &M::TargetKey,
+) -> Result<Option<M::TargetValue>> {
+    if let Some(value) = storage.get_value::<_, M::TargetValue>(target_key.clone())? {
+        return Ok(Some(value));
+    }
+
+    storage
+        .get_value::<_, M::SourceValue>(M::source_key(target_key))?
+        .map(M::convert)
+        .transpose()
+}
+
+/// Reads the value, moving it from the source to the target table if it hasn't been migrated
+/// yet. Must be used within a transaction.
+pub(crate) fn read_and_migrate<M: Migration, S: StorageAccess>(
+    storage: &mut S,
+    target_key: &M::TargetKey,
+) -> Result<Option<M::TargetValue>> {
+    let source_key = M::source_key(target_key);
+    if let Some(source_value) = storage.get_value::<_, M::SourceValue>(source_key.clone())? {
+        let value = M::convert(source_value)?;
+        storage.put_kv(target_key.clone(), &value);
+        storage.delete_key(&source_key);
+        counter!(MIGRATION_RECORDS, MIGRATION_LABEL => M::NAME, MIGRATION_MODE_LABEL => MIGRATION_MODE_LAZY)
+            .increment(1);
+        return Ok(Some(value));
+    }
+
+    storage.get_value::<_, M::TargetValue>(target_key.clone())
+}
+
+/// Deletes the value from both the source and the target table.
+pub(crate) fn delete<M: Migration, S: StorageAccess>(storage: &mut S, target_key: &M::TargetKey) {
+    storage.delete_key(&M::source_key(target_key));
+    storage.delete_key(target_key);
+}
+
+impl PartitionStore {
+    /// Rewrites up to `limit` values of each pending migration. Returns true once all the
+    /// migrations of this partition completed.
+    ///
+    /// Must not be called concurrently with transactions modifying the migrated tables.
+    pub async fn run_migrations(&mut self, limit: usize) -> Result<bool> {
+        let mut completed = true;
+        completed &= self
+            .run_migration::<InvocationStatusV1Migration>(limit)
+            .await?;
+        Ok(completed)
+    }
+
+    /// Rewrites up to `limit` values from the source to the target table of the migration.
+    /// Returns true if no value was left to migrate.
+    async fn run_migration<M: Migration>(&mut self, limit: usize) -> Result<bool> {
+        if limit == 0 {
+            return Ok(false);
+        }
+
+        let mut collected = 0;
+        let to_migrate = self
+            .for_each_key_value_in_place(
+                M::source_scan(self.partition_id(), self.partition_key_range().clone()),
+                |mut k, mut v| {
+                    collected += 1;
+                    let res = decode_source::<M>(&mut k, &mut v);
+                    if collected >= limit {
+                        TableScanIterationDecision::BreakWith(res)
+                    } else {
+                        TableScanIterationDecision::Emit(res)
+                    }
+                },
+            )
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let migrated = to_migrate.len();
+        if migrated > 0 {
+            let mut transaction = self.transaction();
+            for (source_key, target_key, value) in to_migrate {
+                transaction.put_kv(target_key, &value);
+                transaction.delete_key(&source_key);
+            }
+            transaction.commit().await?;
+
+            counter!(MIGRATION_RECORDS, MIGRATION_LABEL => M::NAME, MIGRATION_MODE_LABEL => MIGRATION_MODE_BACKGROUND)
+                .increment(migrated as u64);
+            debug!("Migrated {migrated} values of migration '{}'", M::NAME);
+        }
+
+        let completed = migrated < limit;
+        gauge!(MIGRATION_COMPLETED, MIGRATION_LABEL => M::NAME, PARTITION_LABEL => self.partition_id().to_string())
+            .set(if completed { 1.0 } else { 0.0 });
+        Ok(completed)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn decode_source<M: Migration>(
+    k: &mut &[u8],
+    v: &mut &[u8],
+) -> Result<(M::SourceKey, M::TargetKey, M::TargetValue)> {
+    let source_key = M::SourceKey::deserialize_from(k)?;
+    let target_key = M::target_key(&source_key)?;
+    let source_value = StorageCodec::decode::<M::SourceValue, _>(v)
+        .map_err(|err| StorageError::Conversion(err.into()))?;
+    Ok((source_key, target_key, M::convert(source_value)?))
+}
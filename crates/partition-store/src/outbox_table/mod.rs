@@ -17,7 +17,7 @@ use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::PartitionId;
 use restate_types::storage::StorageCodec;
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
 use crate::TableKind::Outbox;
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
@@ -28,6 +28,7 @@ define_table_key!(
     KeyKind::Outbox,
     OutboxKey(partition_id: PaddedPartitionId, message_index: u64)
 );
+impl_table_record!(OutboxKey, OutboxMessage);
 
 fn add_message<S: StorageAccess>(
     storage: &mut S,
@@ -14,6 +14,7 @@ use rocksdb::{DBAccess, DBRawIteratorWithThreadMode};
 pub struct OwnedIterator<'a, DB: DBAccess> {
     iter: DBRawIteratorWithThreadMode<'a, DB>,
     arena: BytesMut,
+    reverse: bool,
 }
 
 impl<'a, DB: DBAccess> OwnedIterator<'a, DB> {
@@ -21,6 +22,16 @@ impl<'a, DB: DBAccess> OwnedIterator<'a, DB> {
         Self {
             iter,
             arena: BytesMut::with_capacity(8196),
+            reverse: false,
+        }
+    }
+
+    /// Iterates backwards from the current position of the iterator, see
+    /// [`crate::StorageAccess::reverse_iterator_from`].
+    pub(crate) fn new_reverse(iter: DBRawIteratorWithThreadMode<'a, DB>) -> Self {
+        Self {
+            reverse: true,
+            ..Self::new(iter)
         }
     }
 }
@@ -37,7 +48,11 @@ impl<'a, DB: DBAccess> Iterator for OwnedIterator<'a, DB> {
             let key = self.arena.split().freeze();
             self.arena.put_slice(v);
             let value = self.arena.split().freeze();
-            self.iter.next();
+            if self.reverse {
+                self.iter.prev();
+            } else {
+                self.iter.next();
+            }
             Some((key, value))
         } else {
             None
@@ -10,13 +10,13 @@
 
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
-use std::slice;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use bytes::BytesMut;
 use codederror::CodedError;
 use enum_map::Enum;
+use futures::Stream;
 use restate_rocksdb::CfName;
 use restate_rocksdb::IoMode;
 use restate_rocksdb::Priority;
@@ -27,21 +27,28 @@ use rocksdb::DBPinnableSlice;
 use rocksdb::DBRawIteratorWithThreadMode;
 use rocksdb::PrefixRange;
 use rocksdb::ReadOptions;
-use rocksdb::{BoundColumnFamily, SliceTransform};
+use rocksdb::{BlockBasedOptions, BoundColumnFamily, SliceTransform};
 use static_assertions::const_assert_eq;
+use tokio::sync::RwLock;
 use tracing::trace;
 
 use restate_core::ShutdownError;
-use restate_rocksdb::{RocksDb, RocksError};
+use restate_rocksdb::{RocksDb, RocksDbManager, RocksError};
 use restate_storage_api::{Storage, StorageError, Transaction};
 
+use crate::invocation_status_table::{InvocationStatusCache, InvocationStatusWrites};
 use crate::keys::KeyKind;
 use crate::keys::TableKey;
+use crate::keys::TableRecord;
+use crate::scan::try_increment;
 use crate::scan::PhysicalScan;
+use crate::scan::ScanDirection;
 use crate::scan::TableScan;
 use crate::snapshots::LocalPartitionSnapshot;
 use restate_types::identifiers::{PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::logs::Lsn;
 use restate_types::storage::{StorageCodec, StorageDecode, StorageEncode};
+use xxhash_rust::xxh3::Xxh3;
 
 pub type DB = rocksdb::DB;
 
@@ -54,6 +61,10 @@ const DB_PREFIX_LENGTH: usize = KeyKind::SERIALIZED_LENGTH + std::mem::size_of::
 // If this changes, we need to know.
 const_assert_eq!(DB_PREFIX_LENGTH, 10);
 
+/// Column family holding the effect digests of all partitions. The digests are kept apart from
+/// the partition column families so that they survive restoring a partition from a snapshot.
+pub(crate) const EFFECT_DIGESTS_CF: &str = "effect-digests";
+
 /// An internal representation of PartitionId that pads the underlying u16 into u64 to align with
 /// partition-key length. This should only be used as a replacement to PartitionId when
 /// compatibility with old u64-sized PartitionId is needed. Additionally. This must be aligned with
@@ -121,14 +132,20 @@ pub enum TableKind {
     Deduplication,
     Outbox,
     Timers,
+    DeadLetter,
+    InvocationEvent,
+    PreparedMessage,
     // By Partition Key
     State,
     InvocationStatus,
+    InvocationStatusArchive,
     ServiceStatus,
     Idempotency,
     Inbox,
     Journal,
     Promise,
+    InvocationIndex,
+    Schedule,
 }
 
 impl TableKind {
@@ -136,15 +153,25 @@ impl TableKind {
         match self {
             Self::State => &[KeyKind::State],
             Self::InvocationStatus => &[KeyKind::InvocationStatusV1, KeyKind::InvocationStatus],
-            Self::ServiceStatus => &[KeyKind::ServiceStatus],
+            Self::InvocationStatusArchive => &[KeyKind::InvocationStatusArchive],
+            Self::ServiceStatus => &[KeyKind::ServiceStatus, KeyKind::SharedHandlerExecutions],
             Self::Idempotency => &[KeyKind::Idempotency],
-            Self::Inbox => &[KeyKind::Inbox],
+            Self::Inbox => &[KeyKind::Inbox, KeyKind::InboxOrder, KeyKind::PausedInbox],
             Self::Outbox => &[KeyKind::Outbox],
             Self::Deduplication => &[KeyKind::Deduplication],
             Self::PartitionStateMachine => &[KeyKind::Fsm],
             Self::Timers => &[KeyKind::Timers],
-            Self::Journal => &[KeyKind::Journal],
+            Self::Journal => &[
+                KeyKind::Journal,
+                KeyKind::JournalV2,
+                KeyKind::JournalPendingCompletion,
+            ],
             Self::Promise => &[KeyKind::Promise],
+            Self::DeadLetter => &[KeyKind::DeadLetter],
+            Self::InvocationEvent => &[KeyKind::InvocationEvent],
+            Self::PreparedMessage => &[KeyKind::PreparedMessage],
+            Self::InvocationIndex => &[KeyKind::InvocationIndex],
+            Self::Schedule => &[KeyKind::Schedule],
         }
     }
 
@@ -189,7 +216,13 @@ pub struct PartitionStore {
     rocksdb: Arc<RocksDb>,
     partition_id: PartitionId,
     data_cf_name: CfName,
+    archive_cf_name: CfName,
     key_range: RangeInclusive<PartitionKey>,
+    invocation_status_cache: InvocationStatusCache,
+    // Snapshots export the column families of the partition one after the other. Commits hold
+    // the lock shared and snapshots exclusively, so that a snapshot observes all column families
+    // at the same point in time.
+    snapshot_lock: Arc<RwLock<()>>,
     key_buffer: BytesMut,
     value_buffer: BytesMut,
 }
@@ -200,6 +233,7 @@ impl std::fmt::Debug for PartitionStore {
             .field("db", &self.raw_db)
             .field("partition_id", &self.partition_id)
             .field("cf", &self.data_cf_name)
+            .field("archive_cf", &self.archive_cf_name)
             .field("key_buffer", &self.key_buffer.len())
             .field("value_buffer", &self.value_buffer.len())
             .finish()
@@ -213,7 +247,10 @@ impl Clone for PartitionStore {
             rocksdb: self.rocksdb.clone(),
             partition_id: self.partition_id,
             data_cf_name: self.data_cf_name.clone(),
+            archive_cf_name: self.archive_cf_name.clone(),
             key_range: self.key_range.clone(),
+            invocation_status_cache: self.invocation_status_cache.clone(),
+            snapshot_lock: self.snapshot_lock.clone(),
             key_buffer: BytesMut::default(),
             value_buffer: BytesMut::default(),
         }
@@ -230,6 +267,10 @@ pub(crate) fn cf_options(
         cf_options.set_prefix_extractor(SliceTransform::create_fixed_prefix(DB_PREFIX_LENGTH));
         cf_options.set_memtable_prefix_bloom_ratio(0.2);
         cf_options.set_memtable_whole_key_filtering(true);
+        set_filter_opts(
+            &mut cf_options,
+            RocksDbManager::get().default_block_based_options(),
+        );
         // Most of the changes are highly temporal, we try to delay flushing
         // As much as we can to increase the chances to observe a deletion.
         //
@@ -248,6 +289,46 @@ pub(crate) fn cf_options(
     }
 }
 
+/// Options of the column family holding the archived invocation statuses of a partition, see
+/// `PartitionStore::archive_completed_invocation_statuses`. The archive grows with the retention
+/// of completed invocations and is rarely read, hence it trades CPU for space: larger blocks
+/// which are compressed with zstd on all levels, and a small share of the memory budget.
+pub(crate) fn archive_cf_options(
+    memory_budget: usize,
+) -> impl Fn(rocksdb::Options) -> rocksdb::Options + Send + Sync + 'static {
+    move |mut cf_options| {
+        set_memory_related_opts(&mut cf_options, memory_budget);
+        // Same key layout as the data column family, the same prefix scans apply.
+        cf_options.set_prefix_extractor(SliceTransform::create_fixed_prefix(DB_PREFIX_LENGTH));
+        cf_options.set_memtable_prefix_bloom_ratio(0.2);
+        cf_options.set_memtable_whole_key_filtering(true);
+        let mut block_opts = RocksDbManager::get().default_block_based_options();
+        block_opts.set_block_size(64 * 1024);
+        set_filter_opts(&mut cf_options, block_opts);
+        cf_options.set_num_levels(7);
+        cf_options.set_compression_type(DBCompressionType::Zstd);
+        cf_options.set_compression_per_level(&[DBCompressionType::Zstd; 7]);
+
+        cf_options
+    }
+}
+
+/// All keys of the partition store start with `KeyKind(2) + PartitionKey(8)`. The partition key is
+/// derived from the service key for virtual object and workflow state, and from the invocation id
+/// for journals and invocation statuses. The fixed-length prefix filter thus lets scans over the
+/// state of a single service key or the journal of a single invocation skip all SST files which
+/// don't contain that partition key, while whole-key filters serve point lookups (e.g. single
+/// state entries, invocation statuses or idempotency ids) which frequently miss.
+fn set_filter_opts(cf_options: &mut rocksdb::Options, mut block_opts: BlockBasedOptions) {
+    // full (not block based) filters holding both the prefixes and the whole keys.
+    block_opts.set_bloom_filter(10.0, false);
+    block_opts.set_whole_key_filtering(true);
+    cf_options.set_block_based_table_factory(&block_opts);
+    // Lookups of absent keys are common (e.g. idempotency ids, promises or unset state), keep
+    // filters on the last level so that those don't need to read data blocks.
+    cf_options.set_optimize_filters_for_hits(false);
+}
+
 fn set_memory_related_opts(opts: &mut rocksdb::Options, memtables_budget: usize) {
     // We set the budget to allow 1 mutable + 3 immutable.
     opts.set_write_buffer_size(memtables_budget / 4);
@@ -276,15 +357,20 @@ impl PartitionStore {
         raw_db: Arc<DB>,
         rocksdb: Arc<RocksDb>,
         data_cf_name: CfName,
+        archive_cf_name: CfName,
         partition_id: PartitionId,
         key_range: RangeInclusive<PartitionKey>,
+        invocation_status_cache: InvocationStatusCache,
     ) -> Self {
         Self {
             raw_db,
             rocksdb,
             partition_id,
             data_cf_name,
+            archive_cf_name,
             key_range,
+            invocation_status_cache,
+            snapshot_lock: Arc::default(),
             key_buffer: BytesMut::new(),
             value_buffer: BytesMut::new(),
         }
@@ -299,6 +385,11 @@ impl PartitionStore {
         &self.key_range
     }
 
+    /// The column family which holds the data of this partition.
+    pub fn data_cf_name(&self) -> &CfName {
+        &self.data_cf_name
+    }
+
     #[inline]
     pub fn assert_partition_key(&self, partition_key: &impl WithPartitionKey) {
         assert_partition_key(&self.key_range, partition_key);
@@ -308,8 +399,24 @@ impl PartitionStore {
         self.key_range.contains(&key)
     }
 
+    /// Scans the records of a table, decoding their keys and values. Returns at most `limit`
+    /// records, if given.
+    pub fn scan_table<K: TableRecord>(
+        &self,
+        scan: TableScan<K>,
+        direction: ScanDirection,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<(K, K::Value)>> + Send + '_ {
+        crate::scan::scan_table(self, scan, direction, limit)
+    }
+
     fn table_handle(&self, table_kind: TableKind) -> Arc<BoundColumnFamily> {
-        find_cf_handle(&self.rocksdb, &self.data_cf_name, table_kind)
+        find_cf_handle(
+            &self.rocksdb,
+            &self.data_cf_name,
+            &self.archive_cf_name,
+            table_kind,
+        )
     }
 
     fn prefix_iterator(&self, table: TableKind, _key_kind: KeyKind, prefix: Bytes) -> DBIterator {
@@ -385,37 +492,52 @@ impl PartitionStore {
         }
     }
 
+    fn reverse_iterator_from<K: TableKey>(
+        &self,
+        scan: TableScan<K>,
+    ) -> DBRawIteratorWithThreadMode<'_, DB> {
+        let (table, key_kind, from, to) = total_order_range(scan);
+        let mut it = self.range_iterator(table, key_kind, ScanMode::TotalOrder, from, to);
+        it.seek_to_last();
+        it
+    }
+
     #[allow(clippy::needless_lifetimes)]
     pub fn transaction(&mut self) -> PartitionStoreTransaction {
         let rocksdb = self.rocksdb.clone();
-        // An optimization to avoid looking up the cf handle everytime, if we split into more
-        // column families, we will need to cache those cfs here as well.
-        let data_cf_handle = self
-            .rocksdb
-            .inner()
-            .cf_handle(&self.data_cf_name)
-            .unwrap_or_else(|| {
-                panic!(
-                    "Access a column family that must exist: {}",
-                    &self.data_cf_name
-                )
-            });
+        // An optimization to avoid looking up the cf handles everytime.
+        let cf_handle = |cf_name: &CfName| {
+            self.rocksdb
+                .inner()
+                .cf_handle(cf_name)
+                .unwrap_or_else(|| panic!("Access a column family that must exist: {}", cf_name))
+        };
+        let data_cf_handle = cf_handle(&self.data_cf_name);
+        let archive_cf_handle = cf_handle(&self.archive_cf_name);
 
         PartitionStoreTransaction {
             write_batch_with_index: rocksdb::WriteBatchWithIndex::new(0, true),
             raw_db: self.raw_db.as_ref(),
             data_cf_handle,
+            archive_cf_handle,
             rocksdb,
+            snapshot_lock: &self.snapshot_lock,
             key_buffer: &mut self.key_buffer,
             value_buffer: &mut self.value_buffer,
             partition_id: self.partition_id,
             partition_key_range: &self.key_range,
+            invocation_status_cache: &self.invocation_status_cache,
+            invocation_status_writes: InvocationStatusWrites::default(),
+            effect_digest: None,
         }
     }
 
     pub async fn flush_memtables(&self, wait: bool) -> Result<()> {
         self.rocksdb
-            .flush_memtables(slice::from_ref(&self.data_cf_name), wait)
+            .flush_memtables(
+                &[self.data_cf_name.clone(), self.archive_cf_name.clone()],
+                wait,
+            )
             .await
             .map_err(|err| StorageError::Generic(err.into()))?;
         Ok(())
@@ -427,7 +549,10 @@ impl PartitionStore {
     /// snapshot was actually created. The actual snapshot applied LSN will always be equal to, or
     /// greater than, the reported applied LSN.
     ///
-    /// *NB:* Creating a snapshot causes an implicit flush of the column family!
+    /// The snapshot contains the files of both the data and the archive column family of the
+    /// partition, which are told apart by their column family name.
+    ///
+    /// *NB:* Creating a snapshot causes an implicit flush of the column families!
     ///
     /// See [rocksdb::checkpoint::Checkpoint::export_column_family] for additional implementation details.
     pub async fn create_snapshot(
@@ -439,14 +564,38 @@ impl PartitionStore {
             .await?
             .ok_or(StorageError::DataIntegrityError)?;
 
+        let _snapshot_guard = self.snapshot_lock.write().await;
         let metadata = self
             .rocksdb
             .export_cf(self.data_cf_name.clone(), snapshot_dir.clone())
             .await
             .map_err(|err| StorageError::Generic(err.into()))?;
+        let mut files = metadata.get_files();
+
+        // The export directory must not exist, hence the archive is exported next to the data
+        // files first. SST file numbers are unique within the database, so its files can be
+        // moved into the snapshot directory.
+        let archive_dir = snapshot_dir.join(self.archive_cf_name.as_str());
+        let archive_metadata = self
+            .rocksdb
+            .export_cf(self.archive_cf_name.clone(), archive_dir.clone())
+            .await
+            .map_err(|err| StorageError::Generic(err.into()))?;
+        for mut file in archive_metadata.get_files() {
+            let file_name = file.name.trim_start_matches('/');
+            tokio::fs::rename(archive_dir.join(file_name), snapshot_dir.join(file_name))
+                .await
+                .map_err(|err| StorageError::Generic(err.into()))?;
+            file.directory = snapshot_dir.to_string_lossy().into_owned();
+            files.push(file);
+        }
+        tokio::fs::remove_dir_all(&archive_dir)
+            .await
+            .map_err(|err| StorageError::Generic(err.into()))?;
 
         trace!(
             cf_name = ?self.data_cf_name,
+            archive_cf_name = ?self.archive_cf_name,
             %applied_lsn,
             "Exported column family snapshot to {:?}",
             snapshot_dir
@@ -454,7 +603,7 @@ impl PartitionStore {
 
         Ok(LocalPartitionSnapshot {
             base_dir: snapshot_dir,
-            files: metadata.get_files(),
+            files,
             db_comparator_name: metadata.get_db_comparator_name(),
             min_applied_lsn: applied_lsn,
             key_range: self.key_range.clone(),
@@ -462,15 +611,53 @@ impl PartitionStore {
     }
 }
 
+/// Bounds of the scan as a total order range. Prefix iterators don't support seeking to the last
+/// key, hence the reverse scans iterate total order ranges bound by `iterate_upper_bound`.
+fn total_order_range<K: TableKey>(scan: TableScan<K>) -> (TableKind, KeyKind, Bytes, Bytes) {
+    match scan.into() {
+        PhysicalScan::Prefix(table, key_kind, prefix) => {
+            let mut end = prefix.clone();
+            // not allowed to fail since we guarantee that KeyKind is always incrementable.
+            assert!(
+                try_increment(&mut end),
+                "Key prefix overflowed, prefix {:x?}",
+                &prefix
+            );
+            (table, key_kind, prefix.freeze(), end.freeze())
+        }
+        PhysicalScan::RangeExclusive(table, key_kind, _scan_mode, start, end) => {
+            (table, key_kind, start.freeze(), end.freeze())
+        }
+        PhysicalScan::RangeOpen(table, key_kind, start) => {
+            // Same upper bound as the forward scans, see `PartitionStore::iterator_from`
+            let mut end = BytesMut::zeroed(DB_PREFIX_LENGTH);
+            let kind_upper_bound = K::KEY_KIND.exclusive_upper_bound();
+            end[..kind_upper_bound.len()].copy_from_slice(&kind_upper_bound);
+            (table, key_kind, start.freeze(), end.freeze())
+        }
+    }
+}
+
+/// The archived invocation statuses are kept in a column family of their own, all other tables
+/// are in the data column family of the partition.
+#[inline]
+fn table_cf<T>(table_kind: TableKind, data_cf: T, archive_cf: T) -> T {
+    match table_kind {
+        TableKind::InvocationStatusArchive => archive_cf,
+        _ => data_cf,
+    }
+}
+
 fn find_cf_handle<'a>(
     db: &'a Arc<RocksDb>,
     data_cf_name: &CfName,
-    _table_kind: TableKind,
+    archive_cf_name: &CfName,
+    table_kind: TableKind,
 ) -> Arc<BoundColumnFamily<'a>> {
-    // At the moment, everything is in one cf
+    let cf_name = table_cf(table_kind, data_cf_name, archive_cf_name);
     db.inner()
-        .cf_handle(data_cf_name)
-        .unwrap_or_else(|| panic!("Access a column family that must exist: {}", data_cf_name))
+        .cf_handle(cf_name)
+        .unwrap_or_else(|| panic!("Access a column family that must exist: {}", cf_name))
 }
 
 impl Storage for PartitionStore {
@@ -494,6 +681,13 @@ impl StorageAccess for PartitionStore {
         self.iterator_from(scan)
     }
 
+    fn reverse_iterator_from<K: TableKey>(
+        &self,
+        scan: TableScan<K>,
+    ) -> DBRawIteratorWithThreadMode<'_, Self::DBAccess<'_>> {
+        self.reverse_iterator_from(scan)
+    }
+
     #[inline]
     fn cleared_key_buffer_mut(&mut self, min_size: usize) -> &mut BytesMut {
         self.key_buffer.clear();
@@ -545,9 +739,14 @@ pub struct PartitionStoreTransaction<'a> {
     write_batch_with_index: rocksdb::WriteBatchWithIndex,
     raw_db: &'a DB,
     rocksdb: Arc<RocksDb>,
+    snapshot_lock: &'a RwLock<()>,
     data_cf_handle: Arc<BoundColumnFamily<'a>>,
+    archive_cf_handle: Arc<BoundColumnFamily<'a>>,
     key_buffer: &'a mut BytesMut,
     value_buffer: &'a mut BytesMut,
+    invocation_status_cache: &'a InvocationStatusCache,
+    invocation_status_writes: InvocationStatusWrites,
+    effect_digest: Option<Xxh3>,
 }
 
 impl<'a> PartitionStoreTransaction<'a> {
@@ -590,9 +789,8 @@ impl<'a> PartitionStoreTransaction<'a> {
         it
     }
 
-    pub(crate) fn table_handle(&self, _table_kind: TableKind) -> &Arc<BoundColumnFamily> {
-        // Right now, everything is in one cf, return a reference and save CPU.
-        &self.data_cf_handle
+    pub(crate) fn table_handle(&self, table_kind: TableKind) -> &Arc<BoundColumnFamily> {
+        table_cf(table_kind, &self.data_cf_handle, &self.archive_cf_handle)
     }
 
     #[inline]
@@ -604,10 +802,103 @@ impl<'a> PartitionStoreTransaction<'a> {
         self.partition_key_range
     }
 
+    pub(crate) fn invocation_status_cache(&self) -> &'a InvocationStatusCache {
+        self.invocation_status_cache
+    }
+
+    pub(crate) fn invocation_status_writes(&self) -> &InvocationStatusWrites {
+        &self.invocation_status_writes
+    }
+
+    pub(crate) fn invocation_status_writes_mut(&mut self) -> &mut InvocationStatusWrites {
+        &mut self.invocation_status_writes
+    }
+
+    /// Scans the records of a table including the writes of this transaction, see
+    /// [`PartitionStore::scan_table`].
+    pub fn scan_table<K: TableRecord>(
+        &self,
+        scan: TableScan<K>,
+        direction: ScanDirection,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<(K, K::Value)>> + Send + '_ {
+        crate::scan::scan_table(self, scan, direction, limit)
+    }
+
     #[inline]
     pub(crate) fn assert_partition_key(&self, partition_key: &impl WithPartitionKey) {
         assert_partition_key(self.partition_key_range, partition_key);
     }
+
+    /// Starts digesting the writes of this transaction. The digest covers the keys and the values,
+    /// hence stored values must be encoded deterministically.
+    pub fn start_effect_digest(&mut self) {
+        self.effect_digest = Some(Xxh3::new());
+    }
+
+    /// Returns the digest of the writes since the last call to [`Self::start_effect_digest`] and
+    /// stops digesting.
+    pub fn take_effect_digest(&mut self) -> Option<u64> {
+        self.effect_digest.take().map(|digest| digest.digest())
+    }
+
+    /// Returns the recorded effect digest of the record at `lsn` of this partition.
+    pub fn get_effect_digest(&self, lsn: Lsn) -> Result<Option<u64>> {
+        let cf = self.effect_digests_cf_handle();
+        let value = self
+            .raw_db
+            .get_pinned_cf(&cf, effect_digest_key(self.partition_id, lsn))
+            .map_err(|error| StorageError::Generic(error.into()))?;
+
+        value
+            .map(|value| {
+                <[u8; 8]>::try_from(value.as_ref())
+                    .map(u64::from_be_bytes)
+                    .map_err(|err| StorageError::Conversion(err.into()))
+            })
+            .transpose()
+    }
+
+    /// Records the effect digest of the record at `lsn` of this partition as part of this
+    /// transaction.
+    pub fn put_effect_digest(&mut self, lsn: Lsn, digest: u64) {
+        let cf = self.effect_digests_cf_handle();
+        self.write_batch_with_index.put_cf(
+            &cf,
+            effect_digest_key(self.partition_id, lsn),
+            digest.to_be_bytes(),
+        );
+    }
+
+    pub fn delete_effect_digest(&mut self, lsn: Lsn) {
+        let cf = self.effect_digests_cf_handle();
+        self.write_batch_with_index
+            .delete_cf(&cf, effect_digest_key(self.partition_id, lsn));
+    }
+
+    fn effect_digests_cf_handle(&self) -> Arc<BoundColumnFamily<'a>> {
+        self.raw_db.cf_handle(EFFECT_DIGESTS_CF).unwrap_or_else(|| {
+            panic!("Access a column family that must exist: {EFFECT_DIGESTS_CF}")
+        })
+    }
+
+    #[inline]
+    fn digest_write(&mut self, op: u8, key: &[u8], value: &[u8]) {
+        if let Some(digest) = &mut self.effect_digest {
+            digest.update(&[op]);
+            digest.update(&(key.len() as u64).to_be_bytes());
+            digest.update(key);
+            digest.update(&(value.len() as u64).to_be_bytes());
+            digest.update(value);
+        }
+    }
+}
+
+fn effect_digest_key(partition_id: PartitionId, lsn: Lsn) -> [u8; 16] {
+    let mut key = [0; 16];
+    key[..8].copy_from_slice(&u64::from(PaddedPartitionId::from(partition_id)).to_be_bytes());
+    key[8..].copy_from_slice(&u64::from(lsn).to_be_bytes());
+    key
 }
 
 #[inline]
@@ -642,6 +933,7 @@ impl<'a> Transaction for PartitionStoreTransaction<'a> {
         let mut opts = rocksdb::WriteOptions::default();
         // We disable WAL since bifrost is our durable distributed log.
         opts.disable_wal(true);
+        let _snapshot_guard = self.snapshot_lock.read().await;
         self.rocksdb
             .write_batch_with_index(
                 "partition-store-txn-commit",
@@ -651,7 +943,11 @@ impl<'a> Transaction for PartitionStoreTransaction<'a> {
                 self.write_batch_with_index,
             )
             .await
-            .map_err(|error| StorageError::Generic(error.into()))
+            .map_err(|error| StorageError::Generic(error.into()))?;
+        // The cache only holds committed statuses, hence it's updated once the writes are durable
+        self.invocation_status_cache
+            .apply_writes(self.invocation_status_writes);
+        Ok(())
     }
 }
 
@@ -698,6 +994,16 @@ impl<'a> StorageAccess for PartitionStoreTransaction<'a> {
         }
     }
 
+    fn reverse_iterator_from<K: TableKey>(
+        &self,
+        scan: TableScan<K>,
+    ) -> DBRawIteratorWithThreadMode<'_, Self::DBAccess<'_>> {
+        let (table, key_kind, from, to) = total_order_range(scan);
+        let mut it = self.range_iterator(table, key_kind, ScanMode::TotalOrder, from, to);
+        it.seek_to_last();
+        it
+    }
+
     #[inline]
     fn cleared_key_buffer_mut(&mut self, min_size: usize) -> &mut BytesMut {
         self.key_buffer.clear();
@@ -726,15 +1032,22 @@ impl<'a> StorageAccess for PartitionStoreTransaction<'a> {
     }
 
     #[inline]
-    fn put_cf(&mut self, _table: TableKind, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) {
-        self.write_batch_with_index
-            .put_cf(&self.data_cf_handle, key, value);
+    fn put_cf(&mut self, table: TableKind, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) {
+        self.digest_write(0, key.as_ref(), value.as_ref());
+        self.write_batch_with_index.put_cf(
+            table_cf(table, &self.data_cf_handle, &self.archive_cf_handle),
+            key,
+            value,
+        );
     }
 
     #[inline]
-    fn delete_cf(&mut self, _table: TableKind, key: impl AsRef<[u8]>) {
-        self.write_batch_with_index
-            .delete_cf(&self.data_cf_handle, key);
+    fn delete_cf(&mut self, table: TableKind, key: impl AsRef<[u8]>) {
+        self.digest_write(1, key.as_ref(), &[]);
+        self.write_batch_with_index.delete_cf(
+            table_cf(table, &self.data_cf_handle, &self.archive_cf_handle),
+            key,
+        );
     }
 }
 
@@ -748,6 +1061,12 @@ pub(crate) trait StorageAccess {
         scan: TableScan<K>,
     ) -> DBRawIteratorWithThreadMode<'_, Self::DBAccess<'_>>;
 
+    /// Returns an iterator positioned at the last key of the scan, to iterate it backwards.
+    fn reverse_iterator_from<K: TableKey>(
+        &self,
+        scan: TableScan<K>,
+    ) -> DBRawIteratorWithThreadMode<'_, Self::DBAccess<'_>>;
+
     fn cleared_key_buffer_mut(&mut self, min_size: usize) -> &mut BytesMut;
 
     fn cleared_value_buffer_mut(&mut self, min_size: usize) -> &mut BytesMut;
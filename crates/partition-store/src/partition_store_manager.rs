@@ -13,24 +13,36 @@ use std::ops::RangeInclusive;
 use std::path::Path;
 use std::sync::Arc;
 
-use rocksdb::ExportImportFilesMetaData;
+use futures::TryStreamExt;
+use rocksdb::{ExportImportFilesMetaData, LiveFile};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
-use crate::cf_options;
+use crate::invocation_status_table::InvocationStatusCache;
+use crate::metric_definitions;
+use crate::partition_store::EFFECT_DIGESTS_CF;
+use crate::partition_store::{archive_cf_options, cf_options};
 use crate::snapshots::LocalPartitionSnapshot;
 use crate::PartitionStore;
 use crate::DB;
-use restate_core::worker_api::SnapshotError;
+use restate_core::worker_api::{SnapshotError, SplitPartitionError};
 use restate_rocksdb::{
-    CfName, CfPrefixPattern, DbName, DbSpecBuilder, RocksDb, RocksDbManager, RocksError,
+    CfExactPattern, CfName, CfPrefixPattern, DbName, DbSpecBuilder, RocksDb, RocksDbManager,
+    RocksError,
 };
+use restate_storage_api::deduplication_table::{DeduplicationTable, ReadOnlyDeduplicationTable};
+use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
+use restate_storage_api::timer_table::TimerTable;
+use restate_storage_api::Transaction;
 use restate_types::config::{RocksDbOptions, StorageOptions};
-use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
+use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId, WithPartitionKey};
 use restate_types::live::{BoxedLiveLoad, LiveLoad};
+use restate_types::logs::{Lsn, SequenceNumber};
 
-const DB_NAME: &str = "db";
-const PARTITION_CF_PREFIX: &str = "data-";
+pub(crate) const DB_NAME: &str = "db";
+pub(crate) const PARTITION_CF_PREFIX: &str = "data-";
+/// Prefix of the column families holding the archived invocation statuses of the partitions.
+pub(crate) const ARCHIVE_CF_PREFIX: &str = "archive-";
 
 /// Controls how a partition store is opened
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -44,6 +56,7 @@ pub struct PartitionStoreManager {
     lookup: Arc<Mutex<PartitionLookup>>,
     rocksdb: Arc<RocksDb>,
     raw_db: Arc<DB>,
+    per_partition_invocation_status_cache_size: usize,
 }
 
 #[derive(Default, Debug)]
@@ -57,17 +70,41 @@ impl PartitionStoreManager {
         updateable_opts: BoxedLiveLoad<RocksDbOptions>,
         initial_partition_set: &[(PartitionId, RangeInclusive<PartitionKey>)],
     ) -> Result<Self, RocksError> {
+        metric_definitions::describe_metrics();
+
         let options = storage_opts.live_load();
 
         let per_partition_memory_budget = options.rocksdb_memory_budget()
             / options.num_partitions_to_share_memory_budget() as usize;
+        // The archive is written in batches and rarely read, it gets a small share of the budget.
+        let per_partition_archive_memory_budget = per_partition_memory_budget / 8;
+        let per_partition_invocation_status_cache_size =
+            options.invocation_status_cache_memory_size.as_usize()
+                / options.num_partitions_to_share_memory_budget() as usize;
 
         let db_spec = DbSpecBuilder::new(DbName::new(DB_NAME), options.data_dir(), db_options())
             .add_cf_pattern(
                 CfPrefixPattern::new(PARTITION_CF_PREFIX),
-                cf_options(per_partition_memory_budget),
+                cf_options(per_partition_memory_budget - per_partition_archive_memory_budget),
+            )
+            .add_cf_pattern(
+                CfPrefixPattern::new(ARCHIVE_CF_PREFIX),
+                archive_cf_options(per_partition_archive_memory_budget),
+            )
+            .add_cf_pattern(CfExactPattern::new(EFFECT_DIGESTS_CF), |cf_options| {
+                cf_options
+            })
+            .ensure_column_families(
+                partition_ids_to_cfs(initial_partition_set)
+                    .into_iter()
+                    .chain(
+                        initial_partition_set
+                            .iter()
+                            .map(|(partition_id, _)| archive_cf_for_partition(*partition_id)),
+                    )
+                    .chain(std::iter::once(CfName::new(EFFECT_DIGESTS_CF)))
+                    .collect(),
             )
-            .ensure_column_families(partition_ids_to_cfs(initial_partition_set))
             // This is added as an experiment. We might make this configurable to let users decide
             // on the trade-off between shutdown time and startup catchup time.
             .add_to_flush_on_shutdown(CfPrefixPattern::ANY)
@@ -83,6 +120,7 @@ impl PartitionStoreManager {
             raw_db,
             rocksdb,
             lookup: Arc::default(),
+            per_partition_invocation_status_cache_size,
         })
     }
 
@@ -91,6 +129,15 @@ impl PartitionStoreManager {
         guard.live.contains_key(&partition_id)
     }
 
+    /// Returns true if the database contains data for the partition, regardless of whether its
+    /// partition store is open.
+    pub fn has_partition_store(&self, partition_id: PartitionId) -> bool {
+        self.rocksdb
+            .inner()
+            .cf_handle(&cf_for_partition(partition_id))
+            .is_some()
+    }
+
     pub async fn get_partition_store(&self, partition_id: PartitionId) -> Option<PartitionStore> {
         self.lookup.lock().await.live.get(&partition_id).cloned()
     }
@@ -108,7 +155,10 @@ impl PartitionStoreManager {
     ) -> Result<PartitionStore, RocksError> {
         let mut guard = self.lookup.lock().await;
         if let Some(store) = guard.live.get(&partition_id) {
-            return Ok(store.clone());
+            // the key range of a partition shrinks when it is split
+            if store.partition_key_range() == &partition_key_range {
+                return Ok(store.clone());
+            }
         }
         let cf_name = cf_for_partition(partition_id);
         let already_exists = self.rocksdb.inner().cf_handle(&cf_name).is_some();
@@ -121,13 +171,19 @@ impl PartitionStoreManager {
                 return Err(RocksError::AlreadyOpen);
             }
         }
+        let archive_cf_name = archive_cf_for_partition(partition_id);
+        if self.rocksdb.inner().cf_handle(&archive_cf_name).is_none() {
+            self.rocksdb.open_cf(archive_cf_name.clone(), opts).await?;
+        }
 
         let partition_store = PartitionStore::new(
             self.raw_db.clone(),
             self.rocksdb.clone(),
             cf_name,
+            archive_cf_name,
             partition_id,
             partition_key_range,
+            InvocationStatusCache::new(self.per_partition_invocation_status_cache_size),
         );
         guard.live.insert(partition_id, partition_store.clone());
 
@@ -166,9 +222,16 @@ impl PartitionStoreManager {
             return Err(RocksError::ColumnFamilyExists);
         }
 
-        let mut import_metadata = ExportImportFilesMetaData::default();
-        import_metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
-        import_metadata.set_files(&snapshot.files);
+        let (archive_files, data_files): (Vec<_>, Vec<_>) = snapshot
+            .files
+            .into_iter()
+            .partition(|file| file.column_family_name.starts_with(ARCHIVE_CF_PREFIX));
+        let import_metadata = |files: &[LiveFile]| {
+            let mut import_metadata = ExportImportFilesMetaData::default();
+            import_metadata.set_db_comparator_name(snapshot.db_comparator_name.as_str());
+            import_metadata.set_files(files);
+            import_metadata
+        };
 
         info!(
             ?partition_id,
@@ -176,22 +239,43 @@ impl PartitionStoreManager {
             "Initializing partition store from snapshot"
         );
 
+        // The archive is imported last, a data column family without an archive is completed
+        // when the partition store is opened.
+        let archive_cf_name = archive_cf_for_partition(partition_id);
         if let Err(e) = self
             .rocksdb
-            .import_cf(cf_name.clone(), opts, import_metadata)
+            .import_cf(cf_name.clone(), opts, import_metadata(&data_files))
             .await
         {
             error!(?partition_id, "Failed to import snapshot");
             return Err(e);
         }
+        let archive_import = if archive_files.is_empty() {
+            // snapshot of a partition without archived invocation statuses
+            self.rocksdb.open_cf(archive_cf_name.clone(), opts).await
+        } else {
+            self.rocksdb
+                .import_cf(
+                    archive_cf_name.clone(),
+                    opts,
+                    import_metadata(&archive_files),
+                )
+                .await
+        };
+        if let Err(e) = archive_import {
+            error!(?partition_id, "Failed to import snapshot");
+            return Err(e);
+        }
 
         assert!(self.rocksdb.inner().cf_handle(&cf_name).is_some());
         let partition_store = PartitionStore::new(
             self.raw_db.clone(),
             self.rocksdb.clone(),
             cf_name,
+            archive_cf_name,
             partition_id,
             partition_key_range,
+            InvocationStatusCache::new(self.per_partition_invocation_status_cache_size),
         );
         guard.live.insert(partition_id, partition_store.clone());
 
@@ -221,8 +305,140 @@ impl PartitionStoreManager {
             .map_err(|e| SnapshotError::SnapshotExportError(partition_id, e.into()))
     }
 
+    /// Creates the partition store of a partition which is split off from the given parent
+    /// partition. The new store is created from a checkpoint of the parent's store, which must
+    /// contain the state of the parent right before the split. The partition-scoped state
+    /// (timers, deduplication information, the inbox sequence number and the paused services)
+    /// covering the child key range is copied to the child partition, which starts reading its
+    /// own log from the beginning.
+    ///
+    /// An existing partition store of the child which is not open is the remainder of an
+    /// interrupted split and is replaced. Data of the parent partition outside the child key
+    /// range remains in the new store but is never read by the child partition processor.
+    pub async fn split_partition_store(
+        &self,
+        partition_id: PartitionId,
+        child_partition_id: PartitionId,
+        child_key_range: RangeInclusive<PartitionKey>,
+        snapshot_base_path: &Path,
+        opts: &RocksDbOptions,
+    ) -> Result<(), SplitPartitionError> {
+        if self.has_partition(child_partition_id).await {
+            debug!(
+                %partition_id,
+                %child_partition_id,
+                "Partition store for split partition exists already"
+            );
+            return Ok(());
+        }
+        if self.has_partition_store(child_partition_id) {
+            debug!(
+                %partition_id,
+                %child_partition_id,
+                "Replacing partition store of interrupted split"
+            );
+            self.drop_partition(child_partition_id).await;
+        }
+
+        let snapshot = self
+            .export_partition_snapshot(partition_id, SnapshotId::new(), snapshot_base_path)
+            .await?;
+        let snapshot_dir = snapshot.base_dir.clone();
+
+        let child_store = self
+            .open_partition_store_from_snapshot(
+                child_partition_id,
+                child_key_range.clone(),
+                snapshot,
+                opts,
+            )
+            .await
+            .map_err(|err| SplitPartitionError::Storage(child_partition_id, err.into()));
+
+        if let Err(err) = tokio::fs::remove_dir_all(&snapshot_dir).await {
+            warn!(
+                ?snapshot_dir,
+                "Failed to remove local split snapshot: {err}"
+            );
+        }
+        let mut child_store = child_store?;
+
+        if let Err(err) = self
+            .hand_over_split_state(partition_id, &mut child_store)
+            .await
+        {
+            // don't leave an incomplete partition store behind
+            self.drop_partition(child_partition_id).await;
+            return Err(SplitPartitionError::Storage(child_partition_id, err.into()));
+        }
+
+        info!(
+            %partition_id,
+            %child_partition_id,
+            ?child_key_range,
+            "Created partition store for split partition"
+        );
+
+        Ok(())
+    }
+
+    async fn hand_over_split_state(
+        &self,
+        partition_id: PartitionId,
+        child_store: &mut PartitionStore,
+    ) -> restate_storage_api::Result<()> {
+        let child_key_range = child_store.partition_key_range().clone();
+
+        // The imported column family still contains the partition-scoped state of the parent
+        // partition, which we can read through a store bound to the parent's partition id.
+        let mut parent_view = PartitionStore::new(
+            self.raw_db.clone(),
+            self.rocksdb.clone(),
+            cf_for_partition(child_store.partition_id()),
+            archive_cf_for_partition(child_store.partition_id()),
+            partition_id,
+            child_key_range.clone(),
+            InvocationStatusCache::new(0),
+        );
+        let inbox_seq_number = parent_view.get_inbox_seq_number().await?;
+        let paused_services = parent_view.get_paused_services().await?;
+        let dedup_information: Vec<_> =
+            parent_view.get_all_sequence_numbers().try_collect().await?;
+        let timers: Vec<_> = parent_view
+            .next_timers_greater_than(None, usize::MAX)
+            .try_filter(|(_, timer)| {
+                std::future::ready(child_key_range.contains(&timer.partition_key()))
+            })
+            .try_collect()
+            .await?;
+
+        // The parent removes the timers of the child key range when applying the split.
+        let mut txn = child_store.transaction();
+        txn.put_applied_lsn(Lsn::INVALID).await;
+        txn.put_inbox_seq_number(inbox_seq_number).await;
+        // The invocations of paused services are keyed by partition key, so the child partition
+        // has those of its key range already.
+        txn.put_paused_services(&paused_services).await;
+        for dedup in dedup_information {
+            txn.put_dedup_seq_number(dedup.producer_id, &dedup.sequence_number)
+                .await;
+        }
+        for (timer_key, timer) in &timers {
+            txn.put_timer(timer_key, timer).await;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
     pub async fn drop_partition(&self, partition_id: PartitionId) {
         let mut guard = self.lookup.lock().await;
+        // The archive is dropped first, a data column family without an archive is completed
+        // when the partition store is opened.
+        let archive_cf_name = archive_cf_for_partition(partition_id);
+        if self.raw_db.cf_handle(&archive_cf_name).is_some() {
+            self.raw_db.drop_cf(&archive_cf_name).unwrap();
+        }
         self.raw_db
             .drop_cf(&cf_for_partition(partition_id))
             .unwrap();
@@ -235,6 +451,10 @@ fn cf_for_partition(partition_id: PartitionId) -> CfName {
     CfName::from(format!("{}{}", PARTITION_CF_PREFIX, partition_id))
 }
 
+fn archive_cf_for_partition(partition_id: PartitionId) -> CfName {
+    CfName::from(format!("{}{}", ARCHIVE_CF_PREFIX, partition_id))
+}
+
 #[inline]
 fn partition_ids_to_cfs<T>(partition_ids: &[(PartitionId, T)]) -> Vec<CfName> {
     partition_ids
@@ -247,6 +467,10 @@ fn db_options() -> rocksdb::Options {
     let mut db_options = rocksdb::Options::default();
     // we always enable manual wal flushing in case that the user enables wal at runtime
     db_options.set_manual_wal_flush(true);
+    // The partition store writes without WAL, and a transaction may span the data, archive and
+    // effect digests column families. Atomic flushes make sure that the flushed state of those
+    // column families is consistent after a crash.
+    db_options.set_atomic_flush(true);
 
     db_options
 }
@@ -0,0 +1,142 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use futures::{Stream, StreamExt};
+
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_storage_api::prepared_message_table::{
+    PreparedMessageTable, ReadOnlyPreparedMessageTable,
+};
+use restate_storage_api::Result;
+use restate_types::identifiers::PartitionId;
+use restate_types::message::MessageIndex;
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection};
+use crate::TableKind;
+use crate::TableScan;
+use crate::{PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess};
+
+define_table_key!(
+    TableKind::PreparedMessage,
+    KeyKind::PreparedMessage,
+    PreparedMessageKey(partition_id: PaddedPartitionId, sequence_number: u64)
+);
+impl_table_record!(PreparedMessageKey, OutboxMessage);
+
+fn prepared_message_key(
+    partition_id: PartitionId,
+    sequence_number: MessageIndex,
+) -> PreparedMessageKey {
+    PreparedMessageKey::default()
+        .partition_id(partition_id.into())
+        .sequence_number(sequence_number)
+}
+
+fn get_prepared_message<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    sequence_number: MessageIndex,
+) -> Result<Option<OutboxMessage>> {
+    let _x = RocksDbPerfGuard::new("get-prepared-message");
+    storage.get_value(prepared_message_key(partition_id, sequence_number))
+}
+
+fn get_prepared_messages<S: StorageAccess>(
+    storage: &S,
+    partition_id: PartitionId,
+) -> impl Stream<Item = Result<(MessageIndex, OutboxMessage)>> + Send + '_ {
+    scan_table(
+        storage,
+        TableScan::SinglePartition::<PreparedMessageKey>(partition_id),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (key, message) = row?;
+        Ok((*key.sequence_number_ok_or()?, message))
+    })
+}
+
+fn put_prepared_message<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    sequence_number: MessageIndex,
+    message: &OutboxMessage,
+) {
+    storage.put_kv(prepared_message_key(partition_id, sequence_number), message);
+}
+
+fn delete_prepared_message<S: StorageAccess>(
+    storage: &mut S,
+    partition_id: PartitionId,
+    sequence_number: MessageIndex,
+) {
+    storage.delete_key(&prepared_message_key(partition_id, sequence_number));
+}
+
+impl ReadOnlyPreparedMessageTable for PartitionStore {
+    async fn get_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+    ) -> Result<Option<OutboxMessage>> {
+        get_prepared_message(self, self.partition_id(), sequence_number)
+    }
+
+    fn get_prepared_messages(
+        &self,
+    ) -> impl Stream<Item = Result<(MessageIndex, OutboxMessage)>> + Send {
+        get_prepared_messages(self, self.partition_id())
+    }
+}
+
+impl PreparedMessageTable for PartitionStore {
+    async fn put_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+        message: &OutboxMessage,
+    ) {
+        put_prepared_message(self, self.partition_id(), sequence_number, message)
+    }
+
+    async fn delete_prepared_message(&mut self, sequence_number: MessageIndex) {
+        delete_prepared_message(self, self.partition_id(), sequence_number)
+    }
+}
+
+impl<'a> ReadOnlyPreparedMessageTable for PartitionStoreTransaction<'a> {
+    async fn get_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+    ) -> Result<Option<OutboxMessage>> {
+        get_prepared_message(self, self.partition_id(), sequence_number)
+    }
+
+    fn get_prepared_messages(
+        &self,
+    ) -> impl Stream<Item = Result<(MessageIndex, OutboxMessage)>> + Send {
+        get_prepared_messages(self, self.partition_id())
+    }
+}
+
+impl<'a> PreparedMessageTable for PartitionStoreTransaction<'a> {
+    async fn put_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+        message: &OutboxMessage,
+    ) {
+        put_prepared_message(self, self.partition_id(), sequence_number, message)
+    }
+
+    async fn delete_prepared_message(&mut self, sequence_number: MessageIndex) {
+        delete_prepared_message(self, self.partition_id(), sequence_number)
+    }
+}
@@ -8,23 +8,20 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
-use crate::owned_iter::OwnedIterator;
-use crate::scan::TableScan;
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection, TableScan};
 use crate::{PartitionStore, TableKind, TableScanIterationDecision};
 use crate::{PartitionStoreTransaction, StorageAccess};
 use anyhow::anyhow;
 use bytes::Bytes;
 use bytestring::ByteString;
-use futures::Stream;
-use futures_util::stream;
+use futures::{Stream, StreamExt};
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::promise_table::{
     OwnedPromiseRow, Promise, PromiseTable, ReadOnlyPromiseTable,
 };
-use restate_storage_api::{Result, StorageError};
+use restate_storage_api::Result;
 use restate_types::identifiers::{PartitionKey, ServiceId, WithPartitionKey};
-use restate_types::storage::StorageCodec;
 use std::ops::RangeInclusive;
 
 define_table_key!(
@@ -37,6 +34,7 @@ define_table_key!(
         key: ByteString
     )
 );
+impl_table_record!(PromiseKey, Promise);
 
 fn create_key(service_id: &ServiceId, key: &ByteString) -> PromiseKey {
     PromiseKey::default()
@@ -59,12 +57,14 @@ fn all_promise<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<OwnedPromiseRow>> + Send + '_ {
-    let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<PromiseKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut k, mut v)| {
-        let key = PromiseKey::deserialize_from(&mut k)?;
-        let metadata = StorageCodec::decode::<Promise, _>(&mut v)
-            .map_err(|err| StorageError::Generic(err.into()))?;
-
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<PromiseKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (key, metadata) = row?;
         let (partition_key, service_name, service_key, promise_key) = key.into_inner_ok_or()?;
 
         Ok(OwnedPromiseRow {
@@ -77,7 +77,7 @@ fn all_promise<S: StorageAccess>(
             key: promise_key,
             metadata,
         })
-    }))
+    })
 }
 
 fn put_promise<S: StorageAccess>(
@@ -8,13 +8,16 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{KeyCodec, KeyKind, TableKey};
+use crate::keys::{KeyCodec, KeyKind, TableKey, TableRecord};
+use crate::owned_iter::OwnedIterator;
 use crate::scan::TableScan::{
     FullScanPartitionKeyRange, KeyRangeInclusiveInSinglePartition, SinglePartition,
     SinglePartitionKeyPrefix,
 };
-use crate::{PaddedPartitionId, ScanMode, TableKind};
+use crate::{PaddedPartitionId, Result, ScanMode, StorageAccess, TableKind};
 use bytes::BytesMut;
+use futures::Stream;
+use futures_util::stream;
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use std::ops::RangeInclusive;
 
@@ -107,6 +110,38 @@ impl<K: TableKey> TableScan<K> {
     }
 }
 
+/// The order in which [`crate::PartitionStore::scan_table`] returns the records.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ScanDirection {
+    /// In ascending order of the keys.
+    #[default]
+    Forward,
+    /// In descending order of the keys.
+    Reverse,
+}
+
+/// Scans the records of a table, decoding their keys and values. Returns at most `limit`
+/// records, if given.
+pub(crate) fn scan_table<S: StorageAccess, K: TableRecord>(
+    storage: &S,
+    scan: TableScan<K>,
+    direction: ScanDirection,
+    limit: Option<usize>,
+) -> impl Stream<Item = Result<(K, K::Value)>> + Send + '_ {
+    let iter = match direction {
+        ScanDirection::Forward => OwnedIterator::new(storage.iterator_from(scan)),
+        ScanDirection::Reverse => OwnedIterator::new_reverse(storage.reverse_iterator_from(scan)),
+    };
+    stream::iter(
+        iter.take(limit.unwrap_or(usize::MAX))
+            .map(|(mut key, mut value)| {
+                let key = K::deserialize_from(&mut key)?;
+                let value = K::decode_value(&mut value)?;
+                Ok((key, value))
+            }),
+    )
+}
+
 /// Binary increment the number represented by the given bytes.
 /// This function computes the next lexicographical byte string
 /// that comes after this string.
@@ -125,7 +160,7 @@ impl<K: TableKey> TableScan<K> {
 ///```
 /// returns true iff the successor doesn't generate a carry.
 #[inline]
-fn try_increment(bytes: &mut BytesMut) -> bool {
+pub(crate) fn try_increment(bytes: &mut BytesMut) -> bool {
     for byte in bytes.iter_mut().rev() {
         if let Some(incremented) = byte.checked_add(1) {
             *byte = incremented;
@@ -0,0 +1,117 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+
+use futures::{Stream, TryStreamExt};
+
+use restate_rocksdb::RocksDbPerfGuard;
+use restate_storage_api::schedule_table::{ReadOnlyScheduleTable, ScheduleStatus, ScheduleTable};
+use restate_storage_api::Result;
+use restate_types::identifiers::{InvocationUuid, PartitionKey, ScheduleId, WithPartitionKey};
+
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection, TableScan};
+use crate::TableKind;
+use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess};
+
+define_table_key!(
+    TableKind::Schedule,
+    KeyKind::Schedule,
+    ScheduleKey(partition_key: PartitionKey, schedule_uuid: InvocationUuid)
+);
+impl_table_record!(ScheduleKey, ScheduleStatus);
+
+fn schedule_key(schedule_id: &ScheduleId) -> ScheduleKey {
+    ScheduleKey::default()
+        .partition_key(schedule_id.partition_key())
+        .schedule_uuid(schedule_id.schedule_uuid())
+}
+
+fn put_schedule<S: StorageAccess>(storage: &mut S, schedule: &ScheduleStatus) {
+    storage.put_kv(schedule_key(&schedule.schedule.id), schedule);
+}
+
+fn get_schedule<S: StorageAccess>(
+    storage: &mut S,
+    schedule_id: &ScheduleId,
+) -> Result<Option<ScheduleStatus>> {
+    let _x = RocksDbPerfGuard::new("get-schedule");
+    storage.get_value(schedule_key(schedule_id))
+}
+
+fn delete_schedule<S: StorageAccess>(storage: &mut S, schedule_id: &ScheduleId) {
+    storage.delete_key(&schedule_key(schedule_id));
+}
+
+fn all_schedules<S: StorageAccess>(
+    storage: &S,
+    range: RangeInclusive<PartitionKey>,
+) -> impl Stream<Item = Result<ScheduleStatus>> + Send + '_ {
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<ScheduleKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map_ok(|(_, schedule)| schedule)
+}
+
+impl ReadOnlyScheduleTable for PartitionStore {
+    async fn get_schedule(&mut self, schedule_id: &ScheduleId) -> Result<Option<ScheduleStatus>> {
+        self.assert_partition_key(schedule_id);
+        get_schedule(self, schedule_id)
+    }
+
+    fn all_schedules(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ScheduleStatus>> + Send {
+        all_schedules(self, range)
+    }
+}
+
+impl ScheduleTable for PartitionStore {
+    async fn put_schedule(&mut self, schedule: &ScheduleStatus) {
+        self.assert_partition_key(&schedule.schedule.id);
+        put_schedule(self, schedule)
+    }
+
+    async fn delete_schedule(&mut self, schedule_id: &ScheduleId) {
+        self.assert_partition_key(schedule_id);
+        delete_schedule(self, schedule_id)
+    }
+}
+
+impl<'a> ReadOnlyScheduleTable for PartitionStoreTransaction<'a> {
+    async fn get_schedule(&mut self, schedule_id: &ScheduleId) -> Result<Option<ScheduleStatus>> {
+        self.assert_partition_key(schedule_id);
+        get_schedule(self, schedule_id)
+    }
+
+    fn all_schedules(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ScheduleStatus>> + Send {
+        all_schedules(self, range)
+    }
+}
+
+impl<'a> ScheduleTable for PartitionStoreTransaction<'a> {
+    async fn put_schedule(&mut self, schedule: &ScheduleStatus) {
+        self.assert_partition_key(&schedule.schedule.id);
+        put_schedule(self, schedule)
+    }
+
+    async fn delete_schedule(&mut self, schedule_id: &ScheduleId) {
+        self.assert_partition_key(schedule_id);
+        delete_schedule(self, schedule_id)
+    }
+}
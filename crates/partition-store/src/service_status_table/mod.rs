@@ -8,22 +8,21 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
-use crate::owned_iter::OwnedIterator;
+use crate::keys::{define_table_key, impl_table_record, KeyKind};
+use crate::scan::{scan_table, ScanDirection};
 use crate::TableScan::FullScanPartitionKeyRange;
 use crate::{PartitionStore, TableKind};
 use crate::{PartitionStoreTransaction, StorageAccess};
 use bytestring::ByteString;
-use futures::Stream;
-use futures_util::stream;
+use futures::{Stream, StreamExt};
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::service_status_table::{
-    ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
+    ReadOnlyVirtualObjectStatusTable, SharedHandlerExecutions, VirtualObjectStatus,
+    VirtualObjectStatusTable,
 };
-use restate_storage_api::{Result, StorageError};
+use restate_storage_api::Result;
 use restate_types::identifiers::WithPartitionKey;
 use restate_types::identifiers::{PartitionKey, ServiceId};
-use restate_types::storage::StorageCodec;
 use std::ops::RangeInclusive;
 
 define_table_key!(
@@ -35,6 +34,18 @@ define_table_key!(
         service_key: ByteString
     )
 );
+impl_table_record!(ServiceStatusKey, VirtualObjectStatus);
+
+define_table_key!(
+    TableKind::ServiceStatus,
+    KeyKind::SharedHandlerExecutions,
+    SharedHandlerExecutionsKey(
+        partition_key: PartitionKey,
+        service_name: ByteString,
+        service_key: ByteString
+    )
+);
+impl_table_record!(SharedHandlerExecutionsKey, SharedHandlerExecutions);
 
 fn write_status_key(service_id: &ServiceId) -> ServiceStatusKey {
     ServiceStatusKey::default()
@@ -78,11 +89,14 @@ fn all_virtual_object_status<S: StorageAccess>(
     storage: &S,
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(ServiceId, VirtualObjectStatus)>> + Send + '_ {
-    let iter = storage.iterator_from(FullScanPartitionKeyRange::<ServiceStatusKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, mut value)| {
-        let state_key = ServiceStatusKey::deserialize_from(&mut key)?;
-        let state_value = StorageCodec::decode::<VirtualObjectStatus, _>(&mut value)
-            .map_err(|err| StorageError::Conversion(err.into()))?;
+    scan_table(
+        storage,
+        FullScanPartitionKeyRange::<ServiceStatusKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (state_key, state_value) = row?;
 
         let (partition_key, service_name, service_key) = state_key.into_inner_ok_or()?;
 
@@ -90,7 +104,7 @@ fn all_virtual_object_status<S: StorageAccess>(
             ServiceId::from_parts(partition_key, service_name, service_key),
             state_value,
         ))
-    }))
+    })
 }
 
 fn delete_virtual_object_status<S: StorageAccess>(storage: &mut S, service_id: &ServiceId) {
@@ -98,6 +112,36 @@ fn delete_virtual_object_status<S: StorageAccess>(storage: &mut S, service_id: &
     storage.delete_key(&key);
 }
 
+fn shared_handler_executions_key(service_id: &ServiceId) -> SharedHandlerExecutionsKey {
+    SharedHandlerExecutionsKey::default()
+        .partition_key(service_id.partition_key())
+        .service_name(service_id.service_name.clone())
+        .service_key(service_id.key.clone())
+}
+
+fn get_shared_handler_executions<S: StorageAccess>(
+    storage: &mut S,
+    service_id: &ServiceId,
+) -> Result<SharedHandlerExecutions> {
+    let _x = RocksDbPerfGuard::new("get-shared-handler-executions");
+    storage
+        .get_value(shared_handler_executions_key(service_id))
+        .map(Option::unwrap_or_default)
+}
+
+fn put_shared_handler_executions<S: StorageAccess>(
+    storage: &mut S,
+    service_id: &ServiceId,
+    executions: &SharedHandlerExecutions,
+) {
+    let key = shared_handler_executions_key(service_id);
+    if executions.is_empty() {
+        storage.delete_key(&key);
+    } else {
+        storage.put_kv(key, executions);
+    }
+}
+
 impl ReadOnlyVirtualObjectStatusTable for PartitionStore {
     async fn get_virtual_object_status(
         &mut self,
@@ -113,6 +157,14 @@ impl ReadOnlyVirtualObjectStatusTable for PartitionStore {
     ) -> impl Stream<Item = Result<(ServiceId, VirtualObjectStatus)>> + Send {
         all_virtual_object_status(self, range)
     }
+
+    async fn get_shared_handler_executions(
+        &mut self,
+        service_id: &ServiceId,
+    ) -> Result<SharedHandlerExecutions> {
+        self.assert_partition_key(service_id);
+        get_shared_handler_executions(self, service_id)
+    }
 }
 
 impl<'a> ReadOnlyVirtualObjectStatusTable for PartitionStoreTransaction<'a> {
@@ -130,6 +182,14 @@ impl<'a> ReadOnlyVirtualObjectStatusTable for PartitionStoreTransaction<'a> {
     ) -> impl Stream<Item = Result<(ServiceId, VirtualObjectStatus)>> + Send {
         all_virtual_object_status(self, range)
     }
+
+    async fn get_shared_handler_executions(
+        &mut self,
+        service_id: &ServiceId,
+    ) -> Result<SharedHandlerExecutions> {
+        self.assert_partition_key(service_id);
+        get_shared_handler_executions(self, service_id)
+    }
 }
 
 impl<'a> VirtualObjectStatusTable for PartitionStoreTransaction<'a> {
@@ -146,4 +206,13 @@ impl<'a> VirtualObjectStatusTable for PartitionStoreTransaction<'a> {
         self.assert_partition_key(service_id);
         delete_virtual_object_status(self, service_id)
     }
+
+    async fn put_shared_handler_executions(
+        &mut self,
+        service_id: &ServiceId,
+        executions: &SharedHandlerExecutions,
+    ) {
+        self.assert_partition_key(service_id);
+        put_shared_handler_executions(self, service_id, executions)
+    }
 }
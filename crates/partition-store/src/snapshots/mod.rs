@@ -19,6 +19,10 @@ use serde_with::{serde_as, DeserializeAs, SerializeAs};
 use restate_types::identifiers::{PartitionId, PartitionKey, SnapshotId};
 use restate_types::logs::Lsn;
 
+mod repository;
+
+pub use repository::{SnapshotRepository, SnapshotRepositoryError, StoredSnapshot};
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SnapshotFormatVersion {
     #[default]
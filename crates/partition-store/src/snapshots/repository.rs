@@ -0,0 +1,544 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use futures::TryStreamExt;
+use object_store::buffered::BufWriter;
+use object_store::path::Path as ObjectPath;
+use object_store::PutPayload;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, trace};
+
+use crate::snapshots::{LocalPartitionSnapshot, PartitionSnapshotMetadata};
+use restate_object_store_util::ObjectStoreDestination;
+use restate_types::config::SnapshotsOptions;
+use restate_types::identifiers::{PartitionId, SnapshotId};
+use restate_types::logs::Lsn;
+
+const METADATA_KEY: &str = "metadata.json";
+const LATEST_SNAPSHOT_KEY: &str = "latest.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotRepositoryError {
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[error(transparent)]
+    Destination(#[from] restate_object_store_util::Error),
+    #[error("cannot read snapshot file '{}': {1}", .0.display())]
+    ReadFile(PathBuf, #[source] std::io::Error),
+    #[error("cannot write snapshot file '{}': {1}", .0.display())]
+    WriteFile(PathBuf, #[source] std::io::Error),
+    #[error("cannot upload snapshot file '{0}': {1}")]
+    UploadFile(ObjectPath, #[source] std::io::Error),
+    #[error("invalid snapshot metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+    #[error("snapshot '{0}' belongs to partition {1}")]
+    PartitionMismatch(String, PartitionId),
+}
+
+/// Pointer to the most recently uploaded snapshot of a partition. Stored as json next to the
+/// snapshots so that operators and bootstrapping nodes can find it without listing the bucket.
+#[derive(Debug, Serialize, Deserialize)]
+struct LatestSnapshot {
+    key: String,
+    snapshot_id: SnapshotId,
+    min_applied_lsn: Lsn,
+}
+
+/// A complete snapshot of a partition stored in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredSnapshot {
+    pub snapshot_id: SnapshotId,
+    pub min_applied_lsn: Lsn,
+}
+
+/// Stores partition snapshots in an object store. The layout of the destination is:
+///
+/// ```text
+/// <destination>/<partition-id>/latest.json
+/// <destination>/<partition-id>/<lsn>_<snapshot-id>/metadata.json
+/// <destination>/<partition-id>/<lsn>_<snapshot-id>/<sst-file>
+/// ```
+///
+/// The SST files of a snapshot are uploaded before its `metadata.json`, so a snapshot is only
+/// complete once its metadata exists. Snapshot keys start with the zero-padded applied LSN, hence
+/// they sort in the order in which the snapshots were taken.
+#[derive(Clone, Debug)]
+pub struct SnapshotRepository {
+    destination: ObjectStoreDestination,
+    num_retained: NonZeroUsize,
+}
+
+impl SnapshotRepository {
+    /// Returns `None` if no snapshot destination is configured.
+    pub fn create_if_configured(
+        options: &SnapshotsOptions,
+    ) -> Result<Option<Self>, SnapshotRepositoryError> {
+        let Some(destination) = options.destination.as_ref() else {
+            return Ok(None);
+        };
+
+        let destination = ObjectStoreDestination::create(destination, &options.object_store)?;
+        Ok(Some(Self {
+            destination,
+            num_retained: options.num_retained,
+        }))
+    }
+
+    /// Uploads the snapshot whose files are stored in `local_snapshot_path` and deletes the
+    /// snapshots of the partition which exceed the retention.
+    pub async fn put(
+        &self,
+        metadata: &PartitionSnapshotMetadata,
+        local_snapshot_path: &Path,
+    ) -> Result<(), SnapshotRepositoryError> {
+        let key = snapshot_key(metadata.min_applied_lsn, metadata.snapshot_id);
+        let snapshot_prefix = format!("{}/{}", metadata.partition_id, key);
+
+        for file in &metadata.files {
+            let file_name = file.name.trim_start_matches('/');
+            let local_path = local_snapshot_path.join(file_name);
+            let object_path = self
+                .destination
+                .path(&format!("{}/{}", snapshot_prefix, file_name));
+            self.upload_file(&local_path, object_path).await?;
+        }
+
+        let metadata_json = serde_json::to_vec_pretty(metadata)?;
+        self.destination
+            .object_store
+            .put(
+                &self
+                    .destination
+                    .path(&format!("{}/{}", snapshot_prefix, METADATA_KEY)),
+                PutPayload::from(metadata_json),
+            )
+            .await?;
+
+        let latest = LatestSnapshot {
+            key: key.clone(),
+            snapshot_id: metadata.snapshot_id,
+            min_applied_lsn: metadata.min_applied_lsn,
+        };
+        self.destination
+            .object_store
+            .put(
+                &self.destination.path(&format!(
+                    "{}/{}",
+                    metadata.partition_id, LATEST_SNAPSHOT_KEY
+                )),
+                PutPayload::from(serde_json::to_vec_pretty(&latest)?),
+            )
+            .await?;
+
+        debug!(
+            partition_id = %metadata.partition_id,
+            "Uploaded partition snapshot '{}' with {} files",
+            key,
+            metadata.files.len()
+        );
+
+        self.delete_expired_snapshots(metadata.partition_id).await
+    }
+
+    /// Downloads the most recent snapshot of the partition into `staging_dir`, from where it can
+    /// be imported into the partition store. Returns `None` if the partition has no snapshot.
+    pub async fn get_latest(
+        &self,
+        partition_id: PartitionId,
+        staging_dir: &Path,
+    ) -> Result<Option<LocalPartitionSnapshot>, SnapshotRepositoryError> {
+        let latest = match self
+            .destination
+            .object_store
+            .get(
+                &self
+                    .destination
+                    .path(&format!("{}/{}", partition_id, LATEST_SNAPSHOT_KEY)),
+            )
+            .await
+        {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let latest: LatestSnapshot = serde_json::from_slice(&latest)?;
+
+        self.download(partition_id, latest.key, staging_dir)
+            .await
+            .map(Some)
+    }
+
+    /// Downloads the snapshot of the partition with the given id into `staging_dir`. Returns
+    /// `None` if the partition has no complete snapshot with this id.
+    pub async fn get(
+        &self,
+        partition_id: PartitionId,
+        snapshot_id: SnapshotId,
+        staging_dir: &Path,
+    ) -> Result<Option<LocalPartitionSnapshot>, SnapshotRepositoryError> {
+        let Some(snapshot) = self
+            .list(partition_id)
+            .await?
+            .into_iter()
+            .find(|snapshot| snapshot.snapshot_id == snapshot_id)
+        else {
+            return Ok(None);
+        };
+
+        self.download(
+            partition_id,
+            snapshot_key(snapshot.min_applied_lsn, snapshot.snapshot_id),
+            staging_dir,
+        )
+        .await
+        .map(Some)
+    }
+
+    /// Lists the complete snapshots of the partition, from the oldest to the most recent one.
+    pub async fn list(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<Vec<StoredSnapshot>, SnapshotRepositoryError> {
+        let partition_prefix = self.destination.path(&partition_id.to_string());
+        let snapshot_keys: BTreeSet<_> = self
+            .destination
+            .object_store
+            .list(Some(&partition_prefix))
+            .try_filter_map(|object| {
+                let key = snapshot_key_of(&partition_prefix, &object.location)
+                    .filter(|(_, name)| name == METADATA_KEY)
+                    .map(|(key, _)| key);
+                futures::future::ready(Ok(key))
+            })
+            .try_collect()
+            .await?;
+
+        Ok(snapshot_keys
+            .iter()
+            .filter_map(|key| parse_snapshot_key(key))
+            .collect())
+    }
+
+    async fn download(
+        &self,
+        partition_id: PartitionId,
+        key: String,
+        staging_dir: &Path,
+    ) -> Result<LocalPartitionSnapshot, SnapshotRepositoryError> {
+        let snapshot_prefix = format!("{}/{}", partition_id, key);
+
+        let metadata = self
+            .destination
+            .object_store
+            .get(
+                &self
+                    .destination
+                    .path(&format!("{}/{}", snapshot_prefix, METADATA_KEY)),
+            )
+            .await?
+            .bytes()
+            .await?;
+        let mut metadata: PartitionSnapshotMetadata = serde_json::from_slice(&metadata)?;
+        if metadata.partition_id != partition_id {
+            return Err(SnapshotRepositoryError::PartitionMismatch(
+                key,
+                metadata.partition_id,
+            ));
+        }
+
+        let local_dir = staging_dir.join(metadata.snapshot_id.to_string());
+        tokio::fs::create_dir_all(&local_dir)
+            .await
+            .map_err(|err| SnapshotRepositoryError::WriteFile(local_dir.clone(), err))?;
+
+        for file in &mut metadata.files {
+            let file_name = file.name.trim_start_matches('/').to_owned();
+            let object_path = self
+                .destination
+                .path(&format!("{}/{}", snapshot_prefix, file_name));
+            self.download_file(object_path, &local_dir.join(&file_name))
+                .await?;
+            // the files are imported from where they were downloaded to
+            file.directory = local_dir.to_string_lossy().into_owned();
+        }
+
+        debug!(
+            %partition_id,
+            "Downloaded partition snapshot '{}' with {} files",
+            key,
+            metadata.files.len()
+        );
+
+        Ok(LocalPartitionSnapshot {
+            base_dir: local_dir,
+            min_applied_lsn: metadata.min_applied_lsn,
+            db_comparator_name: metadata.db_comparator_name,
+            files: metadata.files,
+            key_range: metadata.key_range,
+        })
+    }
+
+    async fn download_file(
+        &self,
+        object_path: ObjectPath,
+        local_path: &Path,
+    ) -> Result<(), SnapshotRepositoryError> {
+        let mut stream = self
+            .destination
+            .object_store
+            .get(&object_path)
+            .await?
+            .into_stream();
+        let mut file = tokio::fs::File::create(local_path)
+            .await
+            .map_err(|err| SnapshotRepositoryError::WriteFile(local_path.to_owned(), err))?;
+
+        while let Some(chunk) = stream.try_next().await? {
+            file.write_all(&chunk)
+                .await
+                .map_err(|err| SnapshotRepositoryError::WriteFile(local_path.to_owned(), err))?;
+        }
+        file.sync_all()
+            .await
+            .map_err(|err| SnapshotRepositoryError::WriteFile(local_path.to_owned(), err))?;
+
+        trace!("Downloaded snapshot file '{}'", object_path);
+        Ok(())
+    }
+
+    async fn upload_file(
+        &self,
+        local_path: &Path,
+        object_path: ObjectPath,
+    ) -> Result<(), SnapshotRepositoryError> {
+        let mut file = tokio::fs::File::open(local_path)
+            .await
+            .map_err(|err| SnapshotRepositoryError::ReadFile(local_path.to_owned(), err))?;
+
+        // SST files can be large, the buffered writer switches to a multipart upload if needed
+        let mut writer = BufWriter::new(self.destination.object_store.clone(), object_path.clone());
+        let upload = async {
+            tokio::io::copy(&mut file, &mut writer).await?;
+            writer.shutdown().await
+        };
+        let result = upload.await;
+        if let Err(err) = result {
+            let _ = writer.abort().await;
+            return Err(SnapshotRepositoryError::UploadFile(object_path, err));
+        }
+
+        trace!("Uploaded snapshot file '{}'", object_path);
+        Ok(())
+    }
+
+    /// Deletes all objects of the snapshots older than the `num_retained` most recent complete
+    /// snapshots of the partition. Objects of snapshots which sort after the oldest retained
+    /// snapshot are left untouched, since they might belong to an upload which is in progress.
+    async fn delete_expired_snapshots(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<(), SnapshotRepositoryError> {
+        let partition_prefix = self.destination.path(&partition_id.to_string());
+        let objects: Vec<_> = self
+            .destination
+            .object_store
+            .list(Some(&partition_prefix))
+            .map_ok(|object| object.location)
+            .try_collect()
+            .await?;
+
+        let complete_snapshots: BTreeSet<_> = objects
+            .iter()
+            .filter_map(|location| snapshot_key_of(&partition_prefix, location))
+            .filter(|(_, name)| name == METADATA_KEY)
+            .map(|(key, _)| key)
+            .collect();
+
+        let Some(oldest_retained) = complete_snapshots
+            .iter()
+            .rev()
+            .nth(self.num_retained.get() - 1)
+        else {
+            return Ok(());
+        };
+
+        let mut expired_snapshots = BTreeSet::new();
+        for location in &objects {
+            let Some((key, _)) = snapshot_key_of(&partition_prefix, location) else {
+                continue;
+            };
+            if key < *oldest_retained {
+                self.destination.object_store.delete(location).await?;
+                expired_snapshots.insert(key);
+            }
+        }
+
+        if !expired_snapshots.is_empty() {
+            debug!(
+                %partition_id,
+                "Deleted {} expired partition snapshots: {:?}",
+                expired_snapshots.len(),
+                expired_snapshots
+            );
+        }
+        Ok(())
+    }
+}
+
+fn snapshot_key(min_applied_lsn: Lsn, snapshot_id: SnapshotId) -> String {
+    format!("{:020}_{}", u64::from(min_applied_lsn), snapshot_id)
+}
+
+fn parse_snapshot_key(key: &str) -> Option<StoredSnapshot> {
+    let (lsn, snapshot_id) = key.split_once('_')?;
+    Some(StoredSnapshot {
+        snapshot_id: snapshot_id.parse().ok()?,
+        min_applied_lsn: Lsn::from(lsn.parse::<u64>().ok()?),
+    })
+}
+
+/// Splits the location of an object of a snapshot into the snapshot key and the object name
+/// within the snapshot. Returns `None` for objects which don't belong to a snapshot.
+fn snapshot_key_of(
+    partition_prefix: &ObjectPath,
+    location: &ObjectPath,
+) -> Option<(String, String)> {
+    let mut parts = location.prefix_match(partition_prefix)?;
+    let key = parts.next()?;
+    let name = parts.next()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((key.as_ref().to_owned(), name.as_ref().to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use crate::snapshots::SnapshotFormatVersion;
+    use restate_types::config::SnapshotsOptionsBuilder;
+
+    use super::*;
+
+    fn snapshot_metadata(partition_id: PartitionId, lsn: u64) -> PartitionSnapshotMetadata {
+        PartitionSnapshotMetadata {
+            version: SnapshotFormatVersion::V1,
+            cluster_name: "test-cluster".to_owned(),
+            partition_id,
+            node_name: "test-node".to_owned(),
+            created_at: humantime::Timestamp::from(SystemTime::now()),
+            snapshot_id: SnapshotId::new(),
+            key_range: 0..=u64::MAX,
+            min_applied_lsn: Lsn::from(lsn),
+            db_comparator_name: "leveldb.BytewiseComparator".to_owned(),
+            files: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn retains_most_recent_snapshots() -> anyhow::Result<()> {
+        let destination = tempfile::tempdir()?;
+        let options = SnapshotsOptionsBuilder::default()
+            .destination(Some(format!("file://{}", destination.path().display())))
+            .num_retained(NonZeroUsize::new(2).unwrap())
+            .build()?;
+        let repository =
+            SnapshotRepository::create_if_configured(&options)?.expect("destination is configured");
+        let local_snapshot = tempfile::tempdir()?;
+
+        let partition_id = PartitionId::from(1);
+        let snapshots: Vec<_> = [10, 20, 30]
+            .into_iter()
+            .map(|lsn| snapshot_metadata(partition_id, lsn))
+            .collect();
+        for snapshot in &snapshots {
+            repository.put(snapshot, local_snapshot.path()).await?;
+        }
+
+        let partition_prefix = repository.destination.path(&partition_id.to_string());
+        let remaining: BTreeSet<_> = repository
+            .destination
+            .object_store
+            .list(Some(&partition_prefix))
+            .map_ok(|object| object.location)
+            .try_collect::<Vec<_>>()
+            .await?
+            .iter()
+            .filter_map(|location| snapshot_key_of(&partition_prefix, location))
+            .map(|(key, _)| key)
+            .collect();
+
+        let expected: BTreeSet<_> = snapshots[1..]
+            .iter()
+            .map(|snapshot| snapshot_key(snapshot.min_applied_lsn, snapshot.snapshot_id))
+            .collect();
+        assert_eq!(remaining, expected);
+
+        let latest = repository
+            .destination
+            .object_store
+            .get(
+                &repository
+                    .destination
+                    .path(&format!("{}/{}", partition_id, LATEST_SNAPSHOT_KEY)),
+            )
+            .await?
+            .bytes()
+            .await?;
+        let latest: LatestSnapshot = serde_json::from_slice(&latest)?;
+        assert_eq!(latest.snapshot_id, snapshots[2].snapshot_id);
+        assert_eq!(latest.min_applied_lsn, Lsn::from(30));
+
+        let staging_dir = tempfile::tempdir()?;
+        let downloaded = repository
+            .get_latest(partition_id, staging_dir.path())
+            .await?
+            .expect("snapshot exists");
+        assert_eq!(downloaded.min_applied_lsn, Lsn::from(30));
+        assert_eq!(
+            downloaded.base_dir,
+            staging_dir
+                .path()
+                .join(snapshots[2].snapshot_id.to_string())
+        );
+        assert!(repository
+            .get_latest(PartitionId::from(2), staging_dir.path())
+            .await?
+            .is_none());
+
+        let stored: Vec<_> = snapshots[1..]
+            .iter()
+            .map(|snapshot| StoredSnapshot {
+                snapshot_id: snapshot.snapshot_id,
+                min_applied_lsn: snapshot.min_applied_lsn,
+            })
+            .collect();
+        assert_eq!(repository.list(partition_id).await?, stored);
+
+        let downloaded = repository
+            .get(partition_id, snapshots[1].snapshot_id, staging_dir.path())
+            .await?
+            .expect("snapshot exists");
+        assert_eq!(downloaded.min_applied_lsn, Lsn::from(20));
+        // the oldest snapshot has expired
+        assert!(repository
+            .get(partition_id, snapshots[0].snapshot_id, staging_dir.path())
+            .await?
+            .is_none());
+
+        Ok(())
+    }
+}
@@ -8,14 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
-use crate::owned_iter::OwnedIterator;
+use crate::keys::{define_table_key, KeyKind, TableKey, TableRecord};
+use crate::scan::{scan_table, ScanDirection};
 use crate::TableKind::State;
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess};
 use crate::{TableScan, TableScanIterationDecision};
 use bytes::Bytes;
 use bytestring::ByteString;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use futures_util::stream;
 use restate_rocksdb::RocksDbPerfGuard;
 use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
@@ -36,6 +36,15 @@ define_table_key!(
     )
 );
 
+impl TableRecord for StateKey {
+    // user state is stored as raw bytes
+    type Value = Bytes;
+
+    fn decode_value(value: &mut Bytes) -> Result<Self::Value> {
+        Ok(std::mem::take(value))
+    }
+}
+
 #[inline]
 fn write_state_entry_key(service_id: &ServiceId, state_key: impl AsRef<[u8]>) -> StateKey {
     StateKey::default()
@@ -123,9 +132,14 @@ fn get_all_user_states<S: StorageAccess>(
     range: RangeInclusive<PartitionKey>,
 ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send + '_ {
     let _x = RocksDbPerfGuard::new("get-all-user-state");
-    let iter = storage.iterator_from(TableScan::FullScanPartitionKeyRange::<StateKey>(range));
-    stream::iter(OwnedIterator::new(iter).map(|(mut key, value)| {
-        let row_key = StateKey::deserialize_from(&mut key)?;
+    scan_table(
+        storage,
+        TableScan::FullScanPartitionKeyRange::<StateKey>(range),
+        ScanDirection::Forward,
+        None,
+    )
+    .map(|row| {
+        let (row_key, value) = row?;
         let (partition_key, service_name, service_key, state_key) = row_key.into_inner_ok_or()?;
 
         Ok((
@@ -133,7 +147,7 @@ fn get_all_user_states<S: StorageAccess>(
             state_key,
             value,
         ))
-    }))
+    })
 }
 
 impl ReadOnlyStateTable for PartitionStore {
@@ -154,8 +168,11 @@ impl ReadOnlyStateTable for PartitionStore {
         stream::iter(get_all_user_states_for_service(self, service_id))
     }
 
-    fn get_all_user_states(&self) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
-        get_all_user_states(self, self.partition_key_range().clone())
+    fn get_all_user_states(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
+        get_all_user_states(self, range)
     }
 }
 
@@ -177,8 +194,11 @@ impl<'a> ReadOnlyStateTable for PartitionStoreTransaction<'a> {
         stream::iter(get_all_user_states_for_service(self, service_id))
     }
 
-    fn get_all_user_states(&self) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
-        get_all_user_states(self, self.partition_key_range().clone())
+    fn get_all_user_states(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send {
+        get_all_user_states(self, range)
     }
 }
 
@@ -0,0 +1,271 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use bytes::Bytes;
+use googletest::prelude::*;
+use restate_storage_api::consistency::ConsistencyViolation;
+use restate_storage_api::idempotency_table::{IdempotencyMetadata, IdempotencyTable};
+use restate_storage_api::inbox_table::{InboxEntry, InboxTable};
+use restate_storage_api::invocation_status_table::{
+    InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable, JournalMetadata,
+    JournalVersion, StatusTimestamps,
+};
+use restate_storage_api::journal_table::{JournalEntry, JournalTable};
+use restate_storage_api::journal_table_v2::{self, StoredEntry};
+use restate_storage_api::service_status_table::{VirtualObjectStatus, VirtualObjectStatusTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{
+    IdempotencyId, InvocationId, InvocationUuid, PartitionKey, PartitionProcessorRpcRequestId,
+    ServiceId,
+};
+use restate_types::invocation::{
+    InvocationTarget, ServiceInvocationSpanContext, Source, VirtualObjectHandlerType,
+};
+use restate_types::journal::enriched::{EnrichedEntryHeader, EnrichedRawEntry};
+use restate_types::time::MillisSinceEpoch;
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn invoked_status(invocation_target: InvocationTarget, journal_length: u32) -> InvocationStatus {
+    InvocationStatus::Invoked(InFlightInvocationMetadata {
+        invocation_target,
+        journal_metadata: JournalMetadata::new(
+            journal_length,
+            ServiceInvocationSpanContext::empty(),
+        ),
+        pinned_deployment: None,
+        response_sinks: HashSet::new(),
+        timestamps: StatusTimestamps::init(MillisSinceEpoch::new(0)),
+        source: Source::Ingress(PartitionProcessorRpcRequestId::new()),
+        completion_retention_duration: Duration::ZERO,
+        idempotency_key: None,
+        dry_run: false,
+        retry_count: 0,
+        invocation_epoch: 0,
+        execution_deadline: None,
+    })
+}
+
+fn exclusive_handler(key: &str) -> InvocationTarget {
+    InvocationTarget::virtual_object("counter", key, "add", VirtualObjectHandlerType::Exclusive)
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn check_consistency() {
+    let mut rocksdb = storage_test_environment().await;
+
+    // consistent: invoked with its full journal, holding the lock of the object
+    let consistent_target = exclusive_handler("0");
+    let consistent_id = InvocationId::mock_generate(&consistent_target);
+    // the journal misses its second entry
+    let truncated_journal_target = exclusive_handler("1");
+    let truncated_journal_id = InvocationId::mock_generate(&truncated_journal_target);
+    // the object is not locked
+    let unlocked_target = exclusive_handler("2");
+    let unlocked_id = InvocationId::mock_generate(&unlocked_target);
+    // neither the invocation of the inbox entry nor the one locking the object exist
+    let inboxed_id = InvocationId::mock_generate(&exclusive_handler("3"));
+    let locking_id = InvocationId::mock_generate(&exclusive_handler("4"));
+    // the invocation of the idempotency key doesn't exist
+    let idempotency_id = IdempotencyId::unkeyed(10, "greeter", "greet", "my-key");
+    let idempotent_id = InvocationId::from_parts(10, InvocationUuid::from_u128(12345678900001));
+
+    let journal_entry = JournalEntry::Entry(EnrichedRawEntry::new(
+        EnrichedEntryHeader::ClearState {},
+        Bytes::new(),
+    ));
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &consistent_id,
+        &invoked_status(consistent_target.clone(), 1),
+    )
+    .await;
+    txn.put_journal_entry(&consistent_id, 0, &journal_entry)
+        .await;
+    txn.put_virtual_object_status(
+        &consistent_target.as_keyed_service_id().unwrap(),
+        &VirtualObjectStatus::Locked(consistent_id),
+    )
+    .await;
+    txn.put_invocation_status(
+        &truncated_journal_id,
+        &invoked_status(truncated_journal_target.clone(), 2),
+    )
+    .await;
+    txn.put_journal_entry(&truncated_journal_id, 0, &journal_entry)
+        .await;
+    txn.put_virtual_object_status(
+        &truncated_journal_target.as_keyed_service_id().unwrap(),
+        &VirtualObjectStatus::Locked(truncated_journal_id),
+    )
+    .await;
+    txn.put_invocation_status(&unlocked_id, &invoked_status(unlocked_target.clone(), 0))
+        .await;
+    txn.put_inbox_entry(
+        1,
+        &InboxEntry::Invocation(
+            ServiceId::new("counter", "3"),
+            inboxed_id,
+            Default::default(),
+        ),
+    )
+    .await;
+    txn.put_virtual_object_status(
+        &ServiceId::new("counter", "4"),
+        &VirtualObjectStatus::Locked(locking_id),
+    )
+    .await;
+    txn.put_idempotency_metadata(
+        &idempotency_id,
+        &IdempotencyMetadata {
+            invocation_id: idempotent_id,
+        },
+    )
+    .await;
+    txn.commit().await.unwrap();
+
+    let violations = rocksdb
+        .check_consistency(0..=PartitionKey::MAX - 1)
+        .await
+        .unwrap();
+
+    assert_that!(
+        violations,
+        unordered_elements_are![
+            eq(ConsistencyViolation::JournalLengthMismatch {
+                invocation_id: truncated_journal_id,
+                expected_length: 2,
+                actual_length: 1,
+            }),
+            eq(ConsistencyViolation::MissingServiceLock {
+                service_id: unlocked_target.as_keyed_service_id().unwrap(),
+                invocation_id: unlocked_id,
+                locked_by: None,
+            }),
+            eq(ConsistencyViolation::OrphanedInboxEntry {
+                service_id: ServiceId::new("counter", "3"),
+                inbox_sequence_number: 1,
+                invocation_id: inboxed_id,
+            }),
+            eq(ConsistencyViolation::DanglingIdempotencyKey {
+                idempotency_id,
+                invocation_id: idempotent_id,
+            }),
+            eq(ConsistencyViolation::DanglingServiceLock {
+                service_id: ServiceId::new("counter", "4"),
+                invocation_id: locking_id,
+            }),
+        ]
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn check_consistency_journal_v2() {
+    let mut rocksdb = storage_test_environment().await;
+
+    // the journal misses its second entry
+    let truncated_journal_target = exclusive_handler("0");
+    let truncated_journal_id = InvocationId::mock_generate(&truncated_journal_target);
+    // the pending completion points at an entry which doesn't await it anymore
+    let dangling_completion_target = exclusive_handler("1");
+    let dangling_completion_id = InvocationId::mock_generate(&dangling_completion_target);
+
+    let completed_entry = StoredEntry::new(
+        JournalEntry::Entry(EnrichedRawEntry::new(
+            EnrichedEntryHeader::ClearState {},
+            Bytes::new(),
+        )),
+        None,
+        MillisSinceEpoch::new(0),
+    );
+    let pending_entry = StoredEntry::new(
+        JournalEntry::Entry(EnrichedRawEntry::new(
+            EnrichedEntryHeader::Sleep {
+                is_completed: false,
+            },
+            Bytes::new(),
+        )),
+        Some(1),
+        MillisSinceEpoch::new(0),
+    );
+
+    let mut txn = rocksdb.transaction();
+    for (invocation_id, invocation_target, journal_length) in [
+        (truncated_journal_id, truncated_journal_target.clone(), 2),
+        (
+            dangling_completion_id,
+            dangling_completion_target.clone(),
+            2,
+        ),
+    ] {
+        let mut status = invoked_status(invocation_target.clone(), journal_length);
+        status.get_journal_metadata_mut().unwrap().version = JournalVersion::V2;
+        txn.put_invocation_status(&invocation_id, &status).await;
+        txn.put_virtual_object_status(
+            &invocation_target.as_keyed_service_id().unwrap(),
+            &VirtualObjectStatus::Locked(invocation_id),
+        )
+        .await;
+    }
+    journal_table_v2::JournalTableV2::put_journal_entry(
+        &mut txn,
+        &truncated_journal_id,
+        0,
+        &completed_entry,
+    )
+    .await;
+    journal_table_v2::JournalTableV2::put_journal_entry(
+        &mut txn,
+        &dangling_completion_id,
+        0,
+        &completed_entry,
+    )
+    .await;
+    journal_table_v2::JournalTableV2::put_journal_entry(
+        &mut txn,
+        &dangling_completion_id,
+        1,
+        &pending_entry,
+    )
+    .await;
+    // overwriting the entry without its completion id keeps the index entry
+    journal_table_v2::JournalTableV2::put_journal_entry(
+        &mut txn,
+        &dangling_completion_id,
+        1,
+        &completed_entry,
+    )
+    .await;
+    txn.commit().await.unwrap();
+
+    let violations = rocksdb
+        .check_consistency(0..=PartitionKey::MAX - 1)
+        .await
+        .unwrap();
+
+    assert_that!(
+        violations,
+        unordered_elements_are![
+            eq(ConsistencyViolation::JournalLengthMismatch {
+                invocation_id: truncated_journal_id,
+                expected_length: 2,
+                actual_length: 1,
+            }),
+            eq(ConsistencyViolation::DanglingPendingCompletion {
+                invocation_id: dangling_completion_id,
+                completion_id: 1,
+                journal_index: 1,
+            }),
+        ]
+    );
+}
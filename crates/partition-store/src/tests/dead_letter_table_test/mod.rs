@@ -0,0 +1,77 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+
+use crate::PartitionStore;
+use restate_storage_api::dead_letter_table::{
+    DeadLetter, DeadLetterTable, ReadOnlyDeadLetterTable,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::logs::Lsn;
+use restate_types::time::MillisSinceEpoch;
+
+fn mock_dead_letter(lsn: u64, partition_key: PartitionKey) -> DeadLetter {
+    DeadLetter {
+        partition_id: PartitionId::MIN,
+        lsn: Lsn::from(lsn),
+        partition_key,
+        command: "Invoke".to_owned(),
+        error: "failed to deserialize entry".to_owned(),
+        attempts: 3,
+        dead_lettered_at: MillisSinceEpoch::now(),
+        envelope: Bytes::from_static(b"envelope"),
+    }
+}
+
+pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
+    let mut txn = rocksdb.transaction();
+    txn.put_dead_letter(&mock_dead_letter(10, 1)).await;
+    txn.put_dead_letter(&mock_dead_letter(20, 1000)).await;
+    txn.commit().await.expect("should not fail");
+
+    let dead_letter = rocksdb
+        .get_dead_letter(Lsn::from(10))
+        .await
+        .expect("should not fail")
+        .expect("dead letter should exist");
+    assert_eq!(dead_letter.partition_key, 1);
+    assert_eq!(dead_letter.envelope, Bytes::from_static(b"envelope"));
+
+    let all: Vec<_> = rocksdb
+        .all_dead_letters(0..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(
+        all.iter().map(|dl| dl.lsn).collect::<Vec<_>>(),
+        vec![Lsn::from(10), Lsn::from(20)]
+    );
+
+    let filtered: Vec<_> = rocksdb
+        .all_dead_letters(500..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].lsn, Lsn::from(20));
+
+    let mut txn = rocksdb.transaction();
+    txn.delete_dead_letter(Lsn::from(10)).await;
+    txn.commit().await.expect("should not fail");
+
+    assert!(rocksdb
+        .get_dead_letter(Lsn::from(10))
+        .await
+        .expect("should not fail")
+        .is_none());
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::PartitionStore;
+use restate_storage_api::fsm_table::FsmTable;
+use restate_storage_api::Transaction;
+use restate_types::logs::Lsn;
+
+async fn digest_of_writes(rocksdb: &mut PartitionStore, inbox_seq_number: u64) -> u64 {
+    let mut txn = rocksdb.transaction();
+    txn.start_effect_digest();
+    txn.put_applied_lsn(Lsn::from(1)).await;
+    txn.put_inbox_seq_number(inbox_seq_number).await;
+    txn.take_effect_digest().expect("digest was started")
+}
+
+pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
+    // Writes are only digested once started
+    let mut txn = rocksdb.transaction();
+    txn.put_applied_lsn(Lsn::from(1)).await;
+    assert_eq!(txn.take_effect_digest(), None);
+    drop(txn);
+
+    let digest = digest_of_writes(&mut rocksdb, 1).await;
+    assert_eq!(digest, digest_of_writes(&mut rocksdb, 1).await);
+    // Values of the same length are told apart
+    assert_ne!(digest, digest_of_writes(&mut rocksdb, 2).await);
+    assert_ne!(digest, digest_of_writes(&mut rocksdb, u64::MAX).await);
+
+    // Recorded digests are read back
+    let mut txn = rocksdb.transaction();
+    txn.put_effect_digest(Lsn::from(10), digest);
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    assert_eq!(txn.get_effect_digest(Lsn::from(10)).unwrap(), Some(digest));
+    assert_eq!(txn.get_effect_digest(Lsn::from(11)).unwrap(), None);
+    txn.delete_effect_digest(Lsn::from(10));
+    txn.commit().await.expect("should not fail");
+
+    let txn = rocksdb.transaction();
+    assert_eq!(txn.get_effect_digest(Lsn::from(10)).unwrap(), None);
+}
@@ -11,12 +11,15 @@
 use super::{assert_stream_eq, mock_state_mutation};
 
 use crate::PartitionStore;
+use futures_util::TryStreamExt;
 use once_cell::sync::Lazy;
 use restate_storage_api::inbox_table::{
     InboxEntry, InboxTable, ReadOnlyInboxTable, SequenceNumberInboxEntry,
 };
 use restate_storage_api::Transaction;
 use restate_types::identifiers::{InvocationId, ServiceId};
+use restate_types::invocation::InvocationPriority;
+use restate_types::time::MillisSinceEpoch;
 
 static INBOX_ENTRIES: Lazy<Vec<SequenceNumberInboxEntry>> = Lazy::new(|| {
     vec![
@@ -25,6 +28,7 @@ static INBOX_ENTRIES: Lazy<Vec<SequenceNumberInboxEntry>> = Lazy::new(|| {
             InboxEntry::Invocation(
                 ServiceId::new("svc-1", "key-1"),
                 InvocationId::mock_random(),
+                InvocationPriority::default(),
             ),
         ),
         SequenceNumberInboxEntry::new(
@@ -36,6 +40,7 @@ static INBOX_ENTRIES: Lazy<Vec<SequenceNumberInboxEntry>> = Lazy::new(|| {
             InboxEntry::Invocation(
                 ServiceId::new("svc-2", "key-1"),
                 InvocationId::mock_random(),
+                InvocationPriority::default(),
             ),
         ),
         SequenceNumberInboxEntry::new(
@@ -43,6 +48,7 @@ static INBOX_ENTRIES: Lazy<Vec<SequenceNumberInboxEntry>> = Lazy::new(|| {
             InboxEntry::Invocation(
                 ServiceId::new("svc-1", "key-1"),
                 InvocationId::mock_random(),
+                InvocationPriority::default(),
             ),
         ),
     ]
@@ -81,7 +87,8 @@ async fn get_svc_inbox<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
 async fn delete_entry<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
     table
         .delete_inbox_entry(INBOX_ENTRIES[0].service_id(), 7)
-        .await;
+        .await
+        .unwrap();
 }
 
 async fn peek_after_delete<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
@@ -90,6 +97,106 @@ async fn peek_after_delete<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
     assert_eq!(result.unwrap(), Some(INBOX_ENTRIES[1].clone()));
 }
 
+async fn pop_by_priority<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
+    let service_id = ServiceId::new("svc-3", "key-1");
+    let invocation = |priority, deadline: Option<u64>| {
+        InboxEntry::Invocation(
+            service_id.clone(),
+            InvocationId::mock_random(),
+            InvocationPriority::new(priority, deadline.map(MillisSinceEpoch::new)),
+        )
+    };
+    let entries = vec![
+        SequenceNumberInboxEntry::new(20, invocation(0, None)),
+        SequenceNumberInboxEntry::new(21, invocation(5, None)),
+        SequenceNumberInboxEntry::new(22, invocation(5, Some(10))),
+        SequenceNumberInboxEntry::new(
+            23,
+            InboxEntry::StateMutation(mock_state_mutation(service_id.clone())),
+        ),
+        SequenceNumberInboxEntry::new(24, invocation(10, None)),
+    ];
+    for entry in &entries {
+        table
+            .put_inbox_entry(entry.inbox_sequence_number, &entry.inbox_entry)
+            .await;
+    }
+
+    // the state mutation is not overtaken by the invocation with the highest priority
+    for expected in [22, 21, 20, 23, 24] {
+        let peeked = table.peek_inbox(&service_id).await.unwrap().unwrap();
+        let popped = table.pop_inbox(&service_id).await.unwrap().unwrap();
+        assert_eq!(popped.inbox_sequence_number, expected);
+        assert_eq!(peeked, popped);
+    }
+    assert_eq!(table.peek_inbox(&service_id).await.unwrap(), None);
+    assert_eq!(table.pop_inbox(&service_id).await.unwrap(), None);
+}
+
+async fn delete_by_priority<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
+    let service_id = ServiceId::new("svc-4", "key-1");
+    let invocation = |priority| {
+        InboxEntry::Invocation(
+            service_id.clone(),
+            InvocationId::mock_random(),
+            InvocationPriority::new(priority, None),
+        )
+    };
+    table.put_inbox_entry(30, &invocation(0)).await;
+    table.put_inbox_entry(31, &invocation(5)).await;
+    table.put_inbox_entry(32, &invocation(1)).await;
+
+    // deleting an entry also removes it from the priority order
+    table.delete_inbox_entry(&service_id, 31).await.unwrap();
+    for expected in [32, 30] {
+        let popped = table.pop_inbox(&service_id).await.unwrap().unwrap();
+        assert_eq!(popped.inbox_sequence_number, expected);
+    }
+    assert_eq!(table.pop_inbox(&service_id).await.unwrap(), None);
+}
+
+async fn paused_invocations<T: InboxTable + ReadOnlyInboxTable>(table: &mut T) {
+    let invocation = |service_name: &'static str, key: &'static str| {
+        InboxEntry::Invocation(
+            ServiceId::new(service_name, key),
+            InvocationId::mock_random(),
+            InvocationPriority::default(),
+        )
+    };
+    let entries = vec![
+        SequenceNumberInboxEntry::new(42, invocation("svc-5", "key-2")),
+        SequenceNumberInboxEntry::new(40, invocation("svc-5", "key-1")),
+        SequenceNumberInboxEntry::new(41, invocation("svc-6", "key-1")),
+    ];
+    for entry in &entries {
+        table
+            .put_paused_invocation(entry.inbox_sequence_number, &entry.inbox_entry)
+            .await;
+    }
+
+    // paused invocations are returned in the order they were enqueued, and never popped
+    let paused: Vec<_> = table
+        .paused_invocations("svc-5")
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(paused, vec![entries[1].clone(), entries[0].clone()]);
+    assert_eq!(
+        table.pop_inbox(entries[0].service_id()).await.unwrap(),
+        None
+    );
+
+    table
+        .delete_paused_invocation(entries[1].service_id(), 40)
+        .await;
+    let paused: Vec<_> = table
+        .paused_invocations("svc-5")
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(paused, vec![entries[0].clone()]);
+}
+
 pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     let mut txn = rocksdb.transaction();
     populate_data(&mut txn).await;
@@ -102,4 +209,7 @@ pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
 
     let mut txn = rocksdb.transaction();
     peek_after_delete(&mut txn).await;
+    pop_by_priority(&mut txn).await;
+    delete_by_priority(&mut txn).await;
+    paused_invocations(&mut txn).await;
 }
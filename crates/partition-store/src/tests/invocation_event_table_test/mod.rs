@@ -0,0 +1,115 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use futures_util::TryStreamExt;
+
+use crate::PartitionStore;
+use restate_storage_api::invocation_event_table::{
+    InvocationEvent, InvocationEventKind, InvocationEventTable, ReadOnlyInvocationEventTable,
+    MAX_EVENTS_PER_INVOCATION,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey};
+use restate_types::time::MillisSinceEpoch;
+
+async fn all_events(rocksdb: &PartitionStore) -> Vec<InvocationEvent> {
+    rocksdb
+        .all_invocation_events(0..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail")
+}
+
+pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
+    let invocation_1 = InvocationId::from_parts(1, InvocationUuid::mock_random());
+    let invocation_2 = InvocationId::from_parts(1000, InvocationUuid::mock_random());
+
+    let mut txn = rocksdb.transaction();
+    txn.append_invocation_events(
+        &invocation_1,
+        MillisSinceEpoch::now(),
+        vec![InvocationEventKind::Created, InvocationEventKind::Inboxed],
+    )
+    .await
+    .expect("should not fail");
+    txn.append_invocation_events(
+        &invocation_2,
+        MillisSinceEpoch::now(),
+        vec![InvocationEventKind::Created],
+    )
+    .await
+    .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    // Sequence numbers continue from the stored events
+    let mut txn = rocksdb.transaction();
+    txn.append_invocation_events(
+        &invocation_1,
+        MillisSinceEpoch::now(),
+        vec![InvocationEventKind::Resumed],
+    )
+    .await
+    .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    let all = all_events(&rocksdb).await;
+    assert_eq!(
+        all.iter()
+            .map(|event| (event.invocation_id, event.seq_number, event.kind.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (invocation_1, 0, InvocationEventKind::Created),
+            (invocation_1, 1, InvocationEventKind::Inboxed),
+            (invocation_1, 2, InvocationEventKind::Resumed),
+            (invocation_2, 0, InvocationEventKind::Created),
+        ]
+    );
+
+    let filtered: Vec<_> = rocksdb
+        .all_invocation_events(500..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].invocation_id, invocation_2);
+
+    // Drops the oldest events of the invocation once the cap is reached
+    let mut txn = rocksdb.transaction();
+    txn.append_invocation_events(
+        &invocation_1,
+        MillisSinceEpoch::now(),
+        vec![InvocationEventKind::Resumed; MAX_EVENTS_PER_INVOCATION as usize],
+    )
+    .await
+    .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    let events_1: Vec<_> = all_events(&rocksdb)
+        .await
+        .into_iter()
+        .filter(|event| event.invocation_id == invocation_1)
+        .map(|event| event.seq_number)
+        .collect();
+    assert_eq!(
+        events_1,
+        (3..3 + MAX_EVENTS_PER_INVOCATION).collect::<Vec<_>>()
+    );
+
+    // Deletes all the events of the invocation
+    let mut txn = rocksdb.transaction();
+    txn.delete_invocation_events(&invocation_1)
+        .await
+        .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    let all = all_events(&rocksdb).await;
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].invocation_id, invocation_2);
+}
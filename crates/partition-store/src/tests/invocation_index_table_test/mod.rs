@@ -0,0 +1,107 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+// Unfortunately we need this because of https://github.com/rust-lang/rust-clippy/issues/9801
+#![allow(clippy::borrow_interior_mutable_const)]
+#![allow(clippy::declare_interior_mutable_const)]
+
+use super::storage_test_environment;
+
+use futures_util::TryStreamExt;
+use restate_storage_api::invocation_index_table::{
+    InvocationIndexEntry, InvocationIndexTable, InvocationLookupKey, ReadOnlyInvocationIndexTable,
+};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{IdempotencyId, InvocationId, InvocationUuid, ServiceId};
+
+const FIXTURE_INVOCATION_1: InvocationUuid = InvocationUuid::from_u128(12345678900001);
+const FIXTURE_INVOCATION_2: InvocationUuid = InvocationUuid::from_u128(12345678900002);
+
+const IDEMPOTENCY_ID: IdempotencyId =
+    IdempotencyId::unkeyed(10, "my-component", "my-handler", "my-key");
+
+fn workflow_lookup_key() -> InvocationLookupKey {
+    InvocationLookupKey::Workflow(ServiceId::with_partition_key(10, "my-workflow", "my-key"))
+}
+
+fn entry(invocation_uuid: InvocationUuid) -> InvocationIndexEntry {
+    InvocationIndexEntry {
+        invocation_id: InvocationId::from_parts(10, invocation_uuid),
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_invocation_index() {
+    let mut rocksdb = storage_test_environment().await;
+    let idempotency_lookup_key = InvocationLookupKey::Idempotency(IDEMPOTENCY_ID);
+
+    // Fill in some data
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_index_entry(&idempotency_lookup_key, &entry(FIXTURE_INVOCATION_1))
+        .await;
+    txn.put_invocation_index_entry(&workflow_lookup_key(), &entry(FIXTURE_INVOCATION_2))
+        .await;
+    txn.commit().await.unwrap();
+
+    // Query
+    assert_eq!(
+        rocksdb
+            .get_invocation_index_entry(&idempotency_lookup_key)
+            .await
+            .unwrap(),
+        Some(entry(FIXTURE_INVOCATION_1))
+    );
+    assert_eq!(
+        rocksdb
+            .get_invocation_index_entry(&workflow_lookup_key())
+            .await
+            .unwrap(),
+        Some(entry(FIXTURE_INVOCATION_2))
+    );
+    assert_eq!(
+        rocksdb
+            .get_invocation_index_entry(&InvocationLookupKey::Workflow(
+                ServiceId::with_partition_key(10, "my-workflow", "another-key")
+            ))
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Workflow and idempotency lookup keys are told apart when scanning
+    let mut entries: Vec<_> = rocksdb
+        .all_invocation_index_entries(0..=u64::MAX)
+        .try_collect()
+        .await
+        .unwrap();
+    entries.sort_by_key(|(_, entry)| entry.invocation_id);
+    assert_eq!(entries.len(), 2);
+    assert!(matches!(
+        &entries[0].0,
+        InvocationLookupKey::Idempotency(idempotency_id) if idempotency_id.idempotency_key == "my-key"
+    ));
+    assert_eq!(
+        entries[1],
+        (workflow_lookup_key(), entry(FIXTURE_INVOCATION_2))
+    );
+
+    // Delete and query afterwards
+    let mut txn = rocksdb.transaction();
+    txn.delete_invocation_index_entry(&idempotency_lookup_key)
+        .await;
+    txn.commit().await.unwrap();
+    assert_eq!(
+        rocksdb
+            .get_invocation_index_entry(&idempotency_lookup_key)
+            .await
+            .unwrap(),
+        None
+    );
+}
@@ -14,15 +14,18 @@
 
 use super::storage_test_environment;
 
-use crate::invocation_status_table::{InvocationStatusKey, InvocationStatusKeyV1};
+use crate::invocation_status_table::{
+    InvocationStatusArchiveKey, InvocationStatusKey, InvocationStatusKeyV1,
+};
 use crate::partition_store::StorageAccess;
 use bytestring::ByteString;
 use futures_util::TryStreamExt;
 use googletest::prelude::*;
 use once_cell::sync::Lazy;
 use restate_storage_api::invocation_status_table::{
-    InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable, InvocationStatusV1,
-    JournalMetadata, ReadOnlyInvocationStatusTable, StatusTimestamps,
+    CompletedInvocation, InFlightInvocationMetadata, InvocationStatus, InvocationStatusKind,
+    InvocationStatusTable, InvocationStatusV1, InvocationStatusView, InvokedInvocation,
+    JournalMetadata, JournalVersion, ReadOnlyInvocationStatusTable, StatusTimestamps,
 };
 use restate_storage_api::Transaction;
 use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId, WithPartitionKey};
@@ -81,6 +84,8 @@ static INVOCATION_ID_5: Lazy<InvocationId> =
 static RPC_REQUEST_ID: Lazy<PartitionProcessorRpcRequestId> =
     Lazy::new(PartitionProcessorRpcRequestId::new);
 
+const EXECUTION_DEADLINE: MillisSinceEpoch = MillisSinceEpoch::new(60_000);
+
 fn invoked_status(invocation_target: InvocationTarget) -> InvocationStatus {
     InvocationStatus::Invoked(InFlightInvocationMetadata {
         invocation_target,
@@ -91,6 +96,10 @@ fn invoked_status(invocation_target: InvocationTarget) -> InvocationStatus {
         source: Source::Ingress(*RPC_REQUEST_ID),
         completion_retention_duration: Duration::ZERO,
         idempotency_key: None,
+        dry_run: false,
+        retry_count: 0,
+        invocation_epoch: 0,
+        execution_deadline: Some(EXECUTION_DEADLINE),
     })
 }
 
@@ -105,6 +114,10 @@ fn suspended_status(invocation_target: InvocationTarget) -> InvocationStatus {
             source: Source::Ingress(*RPC_REQUEST_ID),
             completion_retention_duration: Duration::ZERO,
             idempotency_key: None,
+            dry_run: false,
+            retry_count: 0,
+            invocation_epoch: 0,
+            execution_deadline: None,
         },
         waiting_for_completed_entries: HashSet::default(),
     }
@@ -158,6 +171,18 @@ async fn verify_point_lookups<T: InvocationStatusTable>(txn: &mut T) {
     );
 }
 
+fn invoked_invocation(
+    invocation_id: InvocationId,
+    invocation_target: InvocationTarget,
+) -> InvokedInvocation {
+    InvokedInvocation {
+        invocation_id,
+        invocation_target,
+        invocation_epoch: 0,
+        execution_deadline: Some(EXECUTION_DEADLINE),
+    }
+}
+
 async fn verify_all_svc_with_status_invoked<T: InvocationStatusTable>(txn: &mut T) {
     let actual = txn
         .all_invoked_invocations()
@@ -167,9 +192,18 @@ async fn verify_all_svc_with_status_invoked<T: InvocationStatusTable>(txn: &mut
     assert_that!(
         actual,
         unordered_elements_are![
-            eq((*INVOCATION_ID_1, INVOCATION_TARGET_1.clone())),
-            eq((*INVOCATION_ID_2, INVOCATION_TARGET_2.clone())),
-            eq((*INVOCATION_ID_4, INVOCATION_TARGET_4.clone()))
+            eq(invoked_invocation(
+                *INVOCATION_ID_1,
+                INVOCATION_TARGET_1.clone()
+            )),
+            eq(invoked_invocation(
+                *INVOCATION_ID_2,
+                INVOCATION_TARGET_2.clone()
+            )),
+            eq(invoked_invocation(
+                *INVOCATION_ID_4,
+                INVOCATION_TARGET_4.clone()
+            ))
         ]
     );
 }
@@ -240,3 +274,262 @@ async fn test_migration() {
         rocksdb.get_invocation_status(&invocation_id).await.unwrap()
     );
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_background_migration() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let status = InvocationStatus::Invoked(InFlightInvocationMetadata::mock());
+    let invocation_ids: Vec<_> = (0..3).map(|_| InvocationId::mock_random()).collect();
+
+    let mut txn = rocksdb.transaction();
+    for invocation_id in &invocation_ids {
+        txn.put_kv(
+            InvocationStatusKeyV1::default()
+                .partition_key(invocation_id.partition_key())
+                .invocation_uuid(invocation_id.invocation_uuid()),
+            &InvocationStatusV1(status.clone()),
+        );
+    }
+    txn.commit().await.unwrap();
+
+    // Rewrites in batches until nothing is left
+    assert!(!rocksdb.run_migrations(2).await.unwrap());
+    assert!(rocksdb.run_migrations(2).await.unwrap());
+    assert!(rocksdb.run_migrations(2).await.unwrap());
+
+    for invocation_id in &invocation_ids {
+        assert!(rocksdb
+            .get_kv_raw(
+                InvocationStatusKeyV1::default()
+                    .partition_key(invocation_id.partition_key())
+                    .invocation_uuid(invocation_id.invocation_uuid()),
+                |_, v| Ok(v.is_none())
+            )
+            .unwrap());
+        assert_eq!(
+            status,
+            rocksdb.get_invocation_status(invocation_id).await.unwrap()
+        );
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_archive_completed_invocations() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let completed = CompletedInvocation::mock_neo();
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(completed.clone()),
+    )
+    .await;
+    txn.commit().await.unwrap();
+
+    // Nothing completed before the invocation
+    assert_eq!(
+        0,
+        rocksdb
+            .archive_completed_invocation_statuses(MillisSinceEpoch::UNIX_EPOCH, 10)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        rocksdb
+            .archive_completed_invocation_statuses(MillisSinceEpoch::MAX, 10)
+            .await
+            .unwrap()
+    );
+
+    // The status moved from the hot key space to the archive
+    assert!(rocksdb
+        .get_kv_raw(
+            InvocationStatusKey::default()
+                .partition_key(invocation_id.partition_key())
+                .invocation_uuid(invocation_id.invocation_uuid()),
+            |_, v| Ok(v.is_none())
+        )
+        .unwrap());
+    assert!(rocksdb
+        .get_kv_raw(
+            InvocationStatusArchiveKey::default()
+                .partition_key(invocation_id.partition_key())
+                .invocation_uuid(invocation_id.invocation_uuid()),
+            |_, v| Ok(v.is_some())
+        )
+        .unwrap());
+
+    // Reads transparently fall back to the archive, which doesn't retain span context and source
+    assert_eq!(
+        InvocationStatus::Completed(CompletedInvocation {
+            span_context: ServiceInvocationSpanContext::empty(),
+            source: Source::Internal,
+            ..completed
+        }),
+        rocksdb.get_invocation_status(&invocation_id).await.unwrap()
+    );
+
+    let mut txn = rocksdb.transaction();
+    txn.delete_invocation_status(&invocation_id).await;
+    txn.commit().await.unwrap();
+
+    assert_eq!(
+        InvocationStatus::Free,
+        rocksdb.get_invocation_status(&invocation_id).await.unwrap()
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_invocation_status_cache() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let invocation_id = InvocationId::mock_generate(&INVOCATION_TARGET_1);
+    let invoked = invoked_status(INVOCATION_TARGET_1.clone());
+    let suspended = suspended_status(INVOCATION_TARGET_1.clone());
+
+    let mut txn = rocksdb.transaction();
+    txn.put_invocation_status(&invocation_id, &invoked).await;
+    txn.commit().await.unwrap();
+
+    // Writes are visible within the transaction, but don't reach the cache before the commit
+    let mut txn = rocksdb.transaction();
+    assert_eq!(
+        invoked,
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+    txn.put_invocation_status(&invocation_id, &suspended).await;
+    assert_eq!(
+        suspended,
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+    drop(txn);
+
+    let mut txn = rocksdb.transaction();
+    assert_eq!(
+        invoked,
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+    txn.put_invocation_status(&invocation_id, &suspended).await;
+    txn.commit().await.unwrap();
+
+    let mut txn = rocksdb.transaction();
+    assert_eq!(
+        suspended,
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+    txn.delete_invocation_status(&invocation_id).await;
+    txn.commit().await.unwrap();
+
+    let mut txn = rocksdb.transaction();
+    assert_eq!(
+        InvocationStatus::Free,
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+
+    // Archiving replaces the cached status with the slimmer archived one
+    let completed = CompletedInvocation::mock_neo();
+    txn.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(completed.clone()),
+    )
+    .await;
+    txn.commit().await.unwrap();
+    assert_eq!(
+        1,
+        rocksdb
+            .archive_completed_invocation_statuses(MillisSinceEpoch::MAX, 10)
+            .await
+            .unwrap()
+    );
+
+    let mut txn = rocksdb.transaction();
+    assert_eq!(
+        InvocationStatus::Completed(CompletedInvocation {
+            span_context: ServiceInvocationSpanContext::empty(),
+            source: Source::Internal,
+            ..completed
+        }),
+        txn.get_invocation_status(&invocation_id).await.unwrap()
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_invocation_status_view() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let suspended = suspended_status(INVOCATION_TARGET_3.clone());
+    let v1_invocation_id = InvocationId::mock_random();
+    let v1_status = InvocationStatus::Invoked(InFlightInvocationMetadata::mock());
+    let completed_invocation_id = InvocationId::mock_random();
+    let completed = CompletedInvocation::mock_neo();
+
+    let mut txn = rocksdb.transaction();
+    populate_data(&mut txn).await;
+    txn.put_kv(
+        InvocationStatusKeyV1::default()
+            .partition_key(v1_invocation_id.partition_key())
+            .invocation_uuid(v1_invocation_id.invocation_uuid()),
+        &InvocationStatusV1(v1_status.clone()),
+    );
+    txn.put_invocation_status(
+        &completed_invocation_id,
+        &InvocationStatus::Completed(completed.clone()),
+    )
+    .await;
+
+    // The view of the writes of the transaction
+    assert_eq!(
+        InvocationStatusView::from(&suspended),
+        txn.get_invocation_status_view(&INVOCATION_ID_3)
+            .await
+            .unwrap()
+    );
+    txn.commit().await.unwrap();
+
+    // The view decoded from the stored status
+    let view = rocksdb
+        .get_invocation_status_view(&INVOCATION_ID_3)
+        .await
+        .unwrap();
+    assert_eq!(
+        InvocationStatusView {
+            kind: InvocationStatusKind::Suspended,
+            invocation_target: Some(INVOCATION_TARGET_3.clone()),
+            idempotency_key: None,
+            journal_length: Some(0),
+            journal_version: Some(JournalVersion::V1),
+        },
+        view
+    );
+    assert_eq!(InvocationStatusView::from(&suspended), view);
+
+    assert_eq!(
+        InvocationStatusView::from(&v1_status),
+        rocksdb
+            .get_invocation_status_view(&v1_invocation_id)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        rocksdb
+            .archive_completed_invocation_statuses(MillisSinceEpoch::MAX, 10)
+            .await
+            .unwrap()
+    );
+    let view = rocksdb
+        .get_invocation_status_view(&completed_invocation_id)
+        .await
+        .unwrap();
+    assert!(view.is_completed());
+    assert_eq!(Some(completed.invocation_target), view.invocation_target);
+    assert!(rocksdb
+        .get_invocation_status_view(&InvocationId::mock_random())
+        .await
+        .unwrap()
+        .is_free());
+}
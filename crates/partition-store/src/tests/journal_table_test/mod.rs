@@ -44,6 +44,7 @@ static MOCK_INVOKE_JOURNAL_ENTRY: Lazy<JournalEntry> = Lazy::new(|| {
                     handler: ByteString::from_static("MyHandler"),
                 },
                 completion_retention_time: Some(Duration::from_secs(10)),
+                shared_concurrency_limit: None,
                 span_context: ServiceInvocationSpanContext::empty(),
             }),
         },
@@ -0,0 +1,198 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::storage_test_environment;
+
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+use restate_storage_api::journal_table::JournalEntry;
+use restate_storage_api::journal_table_v2::{JournalTableV2, ReadOnlyJournalTableV2, StoredEntry};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, InvocationUuid};
+use restate_types::journal::enriched::{EnrichedEntryHeader, EnrichedRawEntry};
+use restate_types::journal::CompletionResult;
+use restate_types::time::MillisSinceEpoch;
+
+const MOCK_INVOCATION_ID_1: InvocationId =
+    InvocationId::from_parts(1, InvocationUuid::from_u128(12345678900001));
+const MOCK_INVOCATION_ID_2: InvocationId =
+    InvocationId::from_parts(1, InvocationUuid::from_u128(12345678900002));
+
+fn clear_state_entry() -> JournalEntry {
+    JournalEntry::Entry(EnrichedRawEntry::new(
+        EnrichedEntryHeader::ClearState {},
+        Bytes::new(),
+    ))
+}
+
+fn sleep_entry(is_completed: bool) -> JournalEntry {
+    JournalEntry::Entry(EnrichedRawEntry::new(
+        EnrichedEntryHeader::Sleep { is_completed },
+        Bytes::new(),
+    ))
+}
+
+fn get_state_entry(is_completed: bool) -> JournalEntry {
+    JournalEntry::Entry(EnrichedRawEntry::new(
+        EnrichedEntryHeader::GetState { is_completed },
+        Bytes::new(),
+    ))
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn journal_v2_tests() {
+    let mut rocksdb = storage_test_environment().await;
+    let append_time = MillisSinceEpoch::new(1000);
+
+    let journal = vec![
+        StoredEntry::new(clear_state_entry(), None, append_time),
+        StoredEntry::new(sleep_entry(false), Some(1), append_time),
+        StoredEntry::new(get_state_entry(false), Some(2), append_time),
+    ];
+
+    let mut txn = rocksdb.transaction();
+    for (journal_index, entry) in journal.iter().enumerate() {
+        txn.put_journal_entry(&MOCK_INVOCATION_ID_1, journal_index as u32, entry)
+            .await;
+    }
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        journal.iter().cloned().enumerate().collect::<Vec<_>>(),
+        rocksdb
+            .get_journal(&MOCK_INVOCATION_ID_1, 3)
+            .map_ok(|(journal_index, entry)| (journal_index as usize, entry))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        vec![(1, 1), (2, 2)],
+        rocksdb
+            .get_pending_completions(&MOCK_INVOCATION_ID_1)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    );
+
+    // the notification of the completion 1 completes the sleep entry
+    let mut txn = rocksdb.transaction();
+    txn.put_journal_entry(
+        &MOCK_INVOCATION_ID_1,
+        3,
+        &StoredEntry::new(
+            JournalEntry::Completion(CompletionResult::Empty),
+            Some(1),
+            append_time,
+        ),
+    )
+    .await;
+    assert_eq!(
+        None,
+        txn.get_pending_completion(&MOCK_INVOCATION_ID_1, 1)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        Some(2),
+        txn.get_pending_completion(&MOCK_INVOCATION_ID_1, 2)
+            .await
+            .unwrap()
+    );
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    txn.delete_journal(&MOCK_INVOCATION_ID_1, 4)
+        .await
+        .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    for journal_index in 0..4 {
+        assert!(rocksdb
+            .get_journal_entry(&MOCK_INVOCATION_ID_1, journal_index)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert!(rocksdb
+        .get_pending_completions(&MOCK_INVOCATION_ID_1)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn migrate_journal() {
+    use restate_storage_api::journal_table::{JournalTable, ReadOnlyJournalTable};
+
+    let mut rocksdb = storage_test_environment().await;
+
+    let mut txn = rocksdb.transaction();
+    JournalTable::put_journal_entry(&mut txn, &MOCK_INVOCATION_ID_2, 0, &clear_state_entry()).await;
+    JournalTable::put_journal_entry(&mut txn, &MOCK_INVOCATION_ID_2, 1, &sleep_entry(true)).await;
+    JournalTable::put_journal_entry(&mut txn, &MOCK_INVOCATION_ID_2, 2, &get_state_entry(false))
+        .await;
+    txn.commit().await.expect("should not fail");
+
+    let mut txn = rocksdb.transaction();
+    txn.migrate_journal(&MOCK_INVOCATION_ID_2, 3)
+        .await
+        .expect("should not fail");
+    txn.commit().await.expect("should not fail");
+
+    assert!(
+        ReadOnlyJournalTable::get_journal(&mut rocksdb, &MOCK_INVOCATION_ID_2, 3)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        vec![
+            (
+                0,
+                StoredEntry {
+                    entry: clear_state_entry(),
+                    completion_id: None,
+                    append_time: None,
+                }
+            ),
+            (
+                1,
+                StoredEntry {
+                    entry: sleep_entry(true),
+                    completion_id: Some(1),
+                    append_time: None,
+                }
+            ),
+            (
+                2,
+                StoredEntry {
+                    entry: get_state_entry(false),
+                    completion_id: Some(2),
+                    append_time: None,
+                }
+            ),
+        ],
+        ReadOnlyJournalTableV2::get_journal(&mut rocksdb, &MOCK_INVOCATION_ID_2, 3)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        vec![(2, 2)],
+        rocksdb
+            .get_pending_completions(&MOCK_INVOCATION_ID_2)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    );
+}
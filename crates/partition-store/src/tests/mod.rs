@@ -27,12 +27,20 @@ use restate_types::invocation::{InvocationTarget, ServiceInvocation, Source};
 use restate_types::live::{Constant, Live};
 use restate_types::state_mut::ExternalStateMutation;
 
+mod consistency_test;
+mod dead_letter_table_test;
+mod effect_digest_test;
 mod idempotency_table_test;
 mod inbox_table_test;
+mod invocation_event_table_test;
+mod invocation_index_table_test;
 mod invocation_status_table_test;
 mod journal_table_test;
+mod journal_table_v2_test;
 mod outbox_table_test;
+mod prepared_message_table_test;
 mod promise_table_test;
+mod schedule_table_test;
 mod snapshots_test;
 mod state_table_test;
 mod timer_table_test;
@@ -81,6 +89,11 @@ async fn test_read_write() {
     state_table_test::run_tests(store.clone()).await;
     virtual_object_status_table_test::run_tests(store.clone()).await;
     timer_table_test::run_tests(store.clone()).await;
+    dead_letter_table_test::run_tests(store.clone()).await;
+    invocation_event_table_test::run_tests(store.clone()).await;
+    prepared_message_table_test::run_tests(store.clone()).await;
+    schedule_table_test::run_tests(store.clone()).await;
+    effect_digest_test::run_tests(store.clone()).await;
     snapshots_test::run_tests(manager.clone(), store.clone()).await;
 }
 
@@ -95,8 +108,13 @@ pub(crate) fn mock_service_invocation(service_id: ServiceId) -> ServiceInvocatio
         span_context: Default::default(),
         headers: vec![],
         execution_time: None,
+        execution_wall_clock_time: None,
         completion_retention_duration: None,
         idempotency_key: None,
+        pinned_deployment: None,
+        dry_run: false,
+        priority: Default::default(),
+        shared_concurrency_limit: None,
         submit_notification_sink: None,
     }
 }
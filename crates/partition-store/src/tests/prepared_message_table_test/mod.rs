@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use futures_util::TryStreamExt;
+
+use super::mock_random_service_invocation;
+
+use crate::PartitionStore;
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_storage_api::prepared_message_table::{
+    PreparedMessageTable, ReadOnlyPreparedMessageTable,
+};
+use restate_storage_api::Transaction;
+
+pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
+    let message = OutboxMessage::ServiceInvocation(mock_random_service_invocation());
+
+    let mut txn = rocksdb.transaction();
+    for sequence_number in [2, 0, 1] {
+        txn.put_prepared_message(sequence_number, &message).await;
+    }
+    txn.commit().await.expect("should not fail");
+
+    let prepared: Vec<_> = rocksdb
+        .get_prepared_messages()
+        .map_ok(|(sequence_number, _)| sequence_number)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(prepared, vec![0, 1, 2]);
+    assert_eq!(
+        rocksdb
+            .get_prepared_message(1)
+            .await
+            .expect("should not fail"),
+        Some(message.clone())
+    );
+
+    // Acknowledged messages are deleted
+    let mut txn = rocksdb.transaction();
+    txn.delete_prepared_message(0).await;
+    txn.delete_prepared_message(1).await;
+    txn.commit().await.expect("should not fail");
+
+    let prepared: Vec<_> = rocksdb
+        .get_prepared_messages()
+        .map_ok(|(sequence_number, _)| sequence_number)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(prepared, vec![2]);
+    assert_eq!(
+        rocksdb
+            .get_prepared_message(1)
+            .await
+            .expect("should not fail"),
+        None
+    );
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use futures_util::TryStreamExt;
+
+use crate::PartitionStore;
+use restate_storage_api::schedule_table::{ReadOnlyScheduleTable, ScheduleStatus, ScheduleTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationUuid, PartitionKey, ScheduleId};
+use restate_types::invocation::{InvocationRequest, InvocationRequestHeader, InvocationTarget};
+use restate_types::schedule::Schedule;
+use restate_types::time::MillisSinceEpoch;
+
+fn mock_schedule(partition_key: PartitionKey, uuid: u128) -> ScheduleStatus {
+    let id = ScheduleId::from_parts(partition_key, InvocationUuid::from(uuid));
+    let next_fire_time = MillisSinceEpoch::new(60_000);
+    ScheduleStatus {
+        schedule: Schedule {
+            id,
+            cron: "* * * * *".parse().unwrap(),
+            misfire_policy: Default::default(),
+            invocation: InvocationRequest::new(
+                InvocationRequestHeader::initialize(
+                    id.invocation_id_at(next_fire_time),
+                    InvocationTarget::mock_service(),
+                ),
+                Default::default(),
+            ),
+            created_at: MillisSinceEpoch::UNIX_EPOCH,
+        },
+        next_fire_time,
+        last_fire_time: None,
+        last_invocation_id: None,
+    }
+}
+
+pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
+    let first = mock_schedule(1, 1);
+    let second = mock_schedule(1000, 2);
+
+    let mut txn = rocksdb.transaction();
+    txn.put_schedule(&first).await;
+    txn.put_schedule(&second).await;
+    txn.commit().await.expect("should not fail");
+
+    assert_eq!(
+        rocksdb
+            .get_schedule(&first.schedule.id)
+            .await
+            .expect("should not fail"),
+        Some(first.clone())
+    );
+
+    let all: Vec<_> = rocksdb
+        .all_schedules(0..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(all, vec![first.clone(), second.clone()]);
+
+    let filtered: Vec<_> = rocksdb
+        .all_schedules(500..=PartitionKey::MAX)
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(filtered, vec![second]);
+
+    let mut txn = rocksdb.transaction();
+    txn.delete_schedule(&first.schedule.id).await;
+    txn.commit().await.expect("should not fail");
+
+    assert!(rocksdb
+        .get_schedule(&first.schedule.id)
+        .await
+        .expect("should not fail")
+        .is_none());
+}
@@ -15,15 +15,23 @@ use tempfile::tempdir;
 use crate::snapshots::{LocalPartitionSnapshot, PartitionSnapshotMetadata, SnapshotFormatVersion};
 use crate::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InvocationStatus, InvocationStatusTable, ReadOnlyInvocationStatusTable,
+};
 use restate_storage_api::Transaction;
 use restate_types::config::WorkerOptions;
-use restate_types::identifiers::{PartitionKey, SnapshotId};
+use restate_types::identifiers::{InvocationId, PartitionKey, SnapshotId};
 use restate_types::live::Live;
 use restate_types::logs::Lsn;
 use restate_types::time::MillisSinceEpoch;
 
 pub(crate) async fn run_tests(manager: PartitionStoreManager, mut partition_store: PartitionStore) {
-    insert_test_data(&mut partition_store).await;
+    let archived_invocation_id = InvocationId::mock_random();
+    insert_test_data(&mut partition_store, &archived_invocation_id).await;
+    let archived_status = partition_store
+        .get_invocation_status(&archived_invocation_id)
+        .await
+        .unwrap();
 
     let snapshots_dir = tempdir().unwrap();
 
@@ -74,18 +82,49 @@ pub(crate) async fn run_tests(manager: PartitionStoreManager, mut partition_stor
         .await
         .unwrap();
 
-    verify_restored_data(&mut new_partition_store).await;
+    verify_restored_data(
+        &mut new_partition_store,
+        &archived_invocation_id,
+        archived_status,
+    )
+    .await;
 }
 
-async fn insert_test_data(partition: &mut PartitionStore) {
+async fn insert_test_data(partition: &mut PartitionStore, archived_invocation_id: &InvocationId) {
     let mut txn = partition.transaction();
     txn.put_applied_lsn(Lsn::new(100)).await;
+    txn.put_invocation_status(
+        archived_invocation_id,
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .await;
     txn.commit().await.expect("commit succeeds");
+
+    // other tests may have left completed invocations behind
+    assert!(
+        partition
+            .archive_completed_invocation_statuses(MillisSinceEpoch::MAX, usize::MAX)
+            .await
+            .expect("archiving succeeds")
+            >= 1
+    );
 }
 
-async fn verify_restored_data(partition: &mut PartitionStore) {
+async fn verify_restored_data(
+    partition: &mut PartitionStore,
+    archived_invocation_id: &InvocationId,
+    archived_status: InvocationStatus,
+) {
     assert_eq!(
         Lsn::new(100),
         partition.get_applied_lsn().await.unwrap().unwrap()
     );
+    // the snapshot contains the archive column family
+    assert_eq!(
+        archived_status,
+        partition
+            .get_invocation_status(archived_invocation_id)
+            .await
+            .unwrap()
+    );
 }
@@ -10,10 +10,13 @@
 
 use super::{assert_stream_eq, storage_test_environment};
 
+use crate::scan::{ScanDirection, TableScan};
+use crate::state_table::StateKey;
 use crate::PartitionStore;
 use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
 use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
-use restate_storage_api::Transaction;
+use restate_storage_api::{Result, Transaction};
 use restate_types::identifiers::ServiceId;
 
 async fn populate_data<T: StateTable>(table: &mut T) {
@@ -145,3 +148,135 @@ async fn test_delete_all() {
         .expect("should not fail")
         .is_some());
 }
+
+fn service_prefix(service_key: &'static str) -> TableScan<StateKey> {
+    TableScan::SinglePartitionKeyPrefix(
+        1337,
+        StateKey::default()
+            .partition_key(1337)
+            .service_name("svc-1".into())
+            .service_key(service_key.into()),
+    )
+}
+
+async fn collect_state_keys(records: impl Stream<Item = Result<(StateKey, Bytes)>>) -> Vec<Bytes> {
+    records
+        .map_ok(|(key, _)| key.state_key.expect("state key is set"))
+        .try_collect()
+        .await
+        .expect("should not fail")
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reverse_prefix_scan() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let mut txn = rocksdb.transaction();
+    populate_data(&mut txn).await;
+    // Same service key in the next partition key, which the prefix scan must skip
+    txn.put_user_state(
+        &ServiceId::with_partition_key(1338, "svc-1", "key-1"),
+        &Bytes::from_static(b"k3"),
+        &Bytes::from_static(b"v3"),
+    )
+    .await;
+
+    // Includes the writes of the transaction
+    assert_eq!(
+        collect_state_keys(txn.scan_table(service_prefix("key-1"), ScanDirection::Reverse, None))
+            .await,
+        vec![Bytes::from_static(b"k2"), Bytes::from_static(b"k1")]
+    );
+    txn.commit().await.expect("should not fail");
+
+    // Stops at the end of the prefix, even if the next service key shares the partition key
+    assert_eq!(
+        collect_state_keys(rocksdb.scan_table(
+            service_prefix("key-1"),
+            ScanDirection::Reverse,
+            None
+        ))
+        .await,
+        vec![Bytes::from_static(b"k2"), Bytes::from_static(b"k1")]
+    );
+    assert_eq!(
+        collect_state_keys(rocksdb.scan_table(
+            service_prefix("key-1"),
+            ScanDirection::Reverse,
+            Some(1)
+        ))
+        .await,
+        vec![Bytes::from_static(b"k2")]
+    );
+    assert_eq!(
+        collect_state_keys(rocksdb.scan_table(
+            service_prefix("key-2"),
+            ScanDirection::Reverse,
+            None
+        ))
+        .await,
+        vec![Bytes::from_static(b"k2")]
+    );
+    assert_eq!(
+        collect_state_keys(rocksdb.scan_table(
+            service_prefix("key-0"),
+            ScanDirection::Reverse,
+            None
+        ))
+        .await,
+        vec![]
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reverse_partition_key_range_scan() {
+    let mut rocksdb = storage_test_environment().await;
+
+    let mut txn = rocksdb.transaction();
+    populate_data(&mut txn).await;
+    for partition_key in [1336, 1338] {
+        txn.put_user_state(
+            &ServiceId::with_partition_key(partition_key, "svc-1", "key-1"),
+            &Bytes::from_static(b"k3"),
+            &Bytes::from_static(b"v3"),
+        )
+        .await;
+    }
+    txn.commit().await.expect("should not fail");
+
+    let records: Vec<_> = rocksdb
+        .scan_table(
+            TableScan::FullScanPartitionKeyRange::<StateKey>(1337..=1338),
+            ScanDirection::Reverse,
+            None,
+        )
+        .map_ok(|(key, _)| (key.partition_key, key.service_key, key.state_key))
+        .try_collect()
+        .await
+        .expect("should not fail");
+    assert_eq!(
+        records,
+        vec![
+            (
+                Some(1338),
+                Some("key-1".into()),
+                Some(Bytes::from_static(b"k3"))
+            ),
+            (
+                Some(1337),
+                Some("key-2".into()),
+                Some(Bytes::from_static(b"k2"))
+            ),
+            (
+                Some(1337),
+                Some("key-1".into()),
+                Some(Bytes::from_static(b"k2"))
+            ),
+            (
+                Some(1337),
+                Some("key-1".into()),
+                Some(Bytes::from_static(b"k1"))
+            ),
+        ]
+    );
+}
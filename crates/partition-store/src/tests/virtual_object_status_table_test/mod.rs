@@ -9,7 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use crate::PartitionStore;
-use restate_storage_api::service_status_table::{VirtualObjectStatus, VirtualObjectStatusTable};
+use restate_storage_api::service_status_table::{
+    SharedHandlerExecutions, VirtualObjectStatus, VirtualObjectStatusTable,
+};
 use restate_types::identifiers::{InvocationId, InvocationUuid, ServiceId};
 
 const FIXTURE_INVOCATION: InvocationUuid = InvocationUuid::from_u128(12345678900001);
@@ -40,9 +42,44 @@ async fn verify_point_lookups<T: VirtualObjectStatusTable>(txn: &mut T) {
     );
 }
 
+async fn verify_shared_handler_executions<T: VirtualObjectStatusTable>(txn: &mut T) {
+    let service_id = ServiceId::with_partition_key(1337, "svc-1", "key-1");
+    let executions = SharedHandlerExecutions {
+        running: vec![InvocationId::from_parts(1337, FIXTURE_INVOCATION)],
+        pending: [InvocationId::from_parts(1337, InvocationUuid::from_u128(2))].into(),
+    };
+
+    txn.put_shared_handler_executions(&service_id, &executions)
+        .await;
+    assert_eq!(
+        txn.get_shared_handler_executions(&service_id)
+            .await
+            .expect("should not fail"),
+        executions
+    );
+
+    // The executions are stored next to the status, without affecting it
+    assert_eq!(
+        txn.get_virtual_object_status(&service_id)
+            .await
+            .expect("should not fail"),
+        VirtualObjectStatus::Locked(InvocationId::from_parts(1337, FIXTURE_INVOCATION))
+    );
+
+    // Empty executions are deleted
+    txn.put_shared_handler_executions(&service_id, &SharedHandlerExecutions::default())
+        .await;
+    assert!(txn
+        .get_shared_handler_executions(&service_id)
+        .await
+        .expect("should not fail")
+        .is_empty());
+}
+
 pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     let mut txn = rocksdb.transaction();
     populate_data(&mut txn).await;
 
     verify_point_lookups(&mut txn).await;
+    verify_shared_handler_executions(&mut txn).await;
 }
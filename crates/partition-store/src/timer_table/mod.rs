@@ -17,7 +17,7 @@ use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::{InvocationUuid, PartitionId};
 use restate_types::storage::StorageCodec;
 
-use crate::keys::{define_table_key, KeyKind, TableKey};
+use crate::keys::{define_table_key, impl_table_record, KeyKind, TableKey};
 use crate::TableKind::Timers;
 use crate::TableScanIterationDecision::Emit;
 use crate::{PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess};
@@ -32,6 +32,7 @@ define_table_key!(
         kind: TimerKeyKind,
     )
 );
+impl_table_record!(TimersKey, Timer);
 
 #[inline]
 fn write_timer_key(partition_id: PartitionId, timer_key: &TimerKey) -> TimersKey {
@@ -111,6 +112,12 @@ fn exclusive_start_key_range(
                     },
                 }
             }
+            TimerKeyKind::FireSchedule { schedule_uuid } => TimerKey {
+                timestamp: timer_key.timestamp,
+                kind: TimerKeyKind::FireSchedule {
+                    schedule_uuid: increment_invocation_uuid(schedule_uuid),
+                },
+            },
         };
 
         let lower_bound = write_timer_key(partition_id, &next_timer_key);
@@ -288,6 +295,21 @@ mod tests {
         assert_eq!(got, key);
     }
 
+    #[test]
+    fn round_trip_fire_schedule() {
+        let key = TimerKey {
+            kind: TimerKeyKind::FireSchedule {
+                schedule_uuid: FIXTURE_INVOCATION,
+            },
+            timestamp: 87654321,
+        };
+
+        let key_bytes = write_timer_key(PartitionId::from(1337), &key).serialize();
+        let got = timer_key_from_key_slice(&key_bytes).expect("should not fail");
+
+        assert_eq!(got, key);
+    }
+
     #[test]
     fn test_lexicographical_sorting_by_timestamp() {
         let kinds = [
@@ -304,6 +326,9 @@ mod tests {
             TimerKeyKind::NeoInvoke {
                 invocation_uuid: FIXTURE_INVOCATION,
             },
+            TimerKeyKind::FireSchedule {
+                schedule_uuid: FIXTURE_INVOCATION,
+            },
         ];
 
         for first_kind in &kinds {
@@ -516,6 +541,9 @@ mod tests {
                         invocation_uuid: InvocationUuid::mock_random(),
                     }
                 }
+                TimerKeyKindDiscriminants::FireSchedule => TimerKeyKind::FireSchedule {
+                    schedule_uuid: InvocationUuid::mock_random(),
+                },
             }
         };
 
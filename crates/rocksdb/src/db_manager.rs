@@ -338,6 +338,14 @@ impl RocksDbManager {
         cf_options.set_avoid_unnecessary_blocking_io(true);
 
         cf_options.set_optimize_filters_for_hits(true);
+        cf_options.set_block_based_table_factory(&self.default_block_based_options());
+
+        cf_options
+    }
+
+    /// Block based table options using the shared block cache. Column families which need to tune
+    /// their filters can start from these options and override the table factory.
+    pub fn default_block_based_options(&self) -> BlockBasedOptions {
         // bloom filters and block cache.
         //
         let mut block_opts = BlockBasedOptions::default();
@@ -352,9 +360,7 @@ impl RocksDbManager {
         block_opts.set_block_size(32 * 1024);
 
         block_opts.set_block_cache(&self.cache);
-        cf_options.set_block_based_table_factory(&block_opts);
-
-        cf_options
+        block_opts
     }
 
     /// Spawn a rocksdb blocking operation in the background
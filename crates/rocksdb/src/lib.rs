@@ -212,7 +212,7 @@ impl RocksDb {
                 )
                 .increment(1);
 
-                return Ok(race_against_stall_detector(self.manager, task).await??);
+                return Ok(race_against_stall_detector(self.manager, &self.name, task).await??);
             }
             IoMode::OnlyIfNonBlocking => {
                 let _x = RocksDbPerfGuard::new(name);
@@ -270,7 +270,7 @@ impl RocksDb {
                     .build()
                     .unwrap();
 
-                Ok(race_against_stall_detector(self.manager, task).await??)
+                Ok(race_against_stall_detector(self.manager, &self.name, task).await??)
             }
             Err(e) => {
                 counter!(STORAGE_IO_OP,
@@ -478,6 +478,7 @@ fn is_retryable_error(error_kind: rocksdb::ErrorKind) -> bool {
 
 async fn race_against_stall_detector<OP, R>(
     manager: &RocksDbManager,
+    db_name: &DbName,
     task: ReadyStorageTask<OP>,
 ) -> Result<R, ShutdownError>
 where
@@ -495,7 +496,7 @@ where
                     // reset the flare guage
                     gauge!(ROCKSDB_STALL_FLARE).decrement(1);
                     let elapsed = stalled_since.elapsed();
-                    histogram!(ROCKSDB_STALL_DURATION).record(elapsed);
+                    histogram!(ROCKSDB_STALL_DURATION, DB => db_name.to_string()).record(elapsed);
                     info!("[Stall Detector] Rocksdb write operation completed after a stall time of {:?}!", elapsed);
                 }
                 return result;
@@ -504,6 +505,7 @@ where
                 stalled = true;
                 stalled_since = Instant::now();
                 gauge!(ROCKSDB_STALL_FLARE).increment(1);
+                counter!(ROCKSDB_STALLS, DB => db_name.to_string()).increment(1);
                 warn!("[Stall Detector] Rocksdb write operation exceeded rocksdb-write-stall-threshold, will continue waiting");
             }
 
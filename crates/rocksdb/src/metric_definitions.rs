@@ -41,10 +41,12 @@ pub const WRITE_ARTIFICIAL_DELAY_DURATION: &str =
 
 pub const ROCKSDB_STALL_FLARE: &str = "restate.rocksdb_stall_flare";
 pub const ROCKSDB_STALL_DURATION: &str = "restate.rocksdb_stall_duration.seconds";
+pub const ROCKSDB_STALLS: &str = "restate.rocksdb_stall.total";
 
 pub const OP_TYPE: &str = "operation";
 pub const OP_NAME: &str = "name";
 pub const PRIORITY: &str = "priority";
+pub const DB: &str = "db";
 
 pub const DISPOSITION: &str = "disposition";
 
@@ -85,10 +87,16 @@ pub fn describe_metrics() {
         "Number of next() issued on memtables"
     );
 
+    describe_counter!(
+        ROCKSDB_STALLS,
+        Unit::Count,
+        "Number of writes considered as stalled by the stall detector, with 'db' label"
+    );
+
     describe_histogram!(
         ROCKSDB_STALL_DURATION,
         Unit::Seconds,
-        "Time spent after a write is considered as stalled by the stall detector, note that this is only updated when the write is unstalled"
+        "Time spent after a write is considered as stalled by the stall detector, note that this is only updated when the write is unstalled, with 'db' label"
     );
 
     describe_histogram!(
@@ -52,6 +52,35 @@ impl SerializeAs<std::time::Duration> for DurationString {
     }
 }
 
+/// Humantime duration to use with serde_with, which rejects zero durations when deserializing.
+///
+/// Use this for intervals of timers, which can't have a zero period.
+pub struct NonZeroDurationString;
+
+impl<'de> DeserializeAs<'de, humantime::Duration> for NonZeroDurationString {
+    fn deserialize_as<D>(deserializer: D) -> Result<humantime::Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let duration: humantime::Duration = String::deserialize(deserializer)?
+            .parse()
+            .map_err(Error::custom)?;
+        if duration.is_zero() {
+            return Err(Error::custom("duration must not be zero"));
+        }
+        Ok(duration)
+    }
+}
+
+impl SerializeAs<humantime::Duration> for NonZeroDurationString {
+    fn serialize_as<S>(source: &humantime::Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(source)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +130,23 @@ mod tests {
             d
         );
     }
+
+    #[serde_as]
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    struct MyNonZeroDuration(#[serde_as(as = "NonZeroDurationString")] humantime::Duration);
+
+    #[test]
+    fn deserialize_non_zero_rejects_zero() {
+        assert!(
+            serde_json::from_value::<MyNonZeroDuration>(serde_json::Value::String("0s".to_owned()))
+                .is_err()
+        );
+        assert_eq!(
+            serde_json::from_value::<MyNonZeroDuration>(serde_json::Value::String("1s".to_owned()))
+                .unwrap()
+                .0,
+            humantime::Duration::from(std::time::Duration::from_secs(1))
+        );
+    }
 }
@@ -12,6 +12,7 @@ mod byte_count;
 mod header_map;
 #[cfg(feature = "proto")]
 mod proto;
+mod redacted;
 
 pub mod authority;
 pub mod default;
@@ -20,9 +21,10 @@ pub mod header_value;
 mod version;
 
 pub use byte_count::*;
-pub use duration::DurationString;
+pub use duration::{DurationString, NonZeroDurationString};
 pub use header_map::SerdeableHeaderHashMap;
 pub use header_value::HeaderValueSerde;
 #[cfg(feature = "proto")]
 pub use proto::ProtobufEncoded;
+pub use redacted::{RedactedSerde, REDACTED};
 pub use version::VersionSerde;
@@ -0,0 +1,38 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::Deserialize;
+use serde_with::{DeserializeAs, SerializeAs};
+
+/// Placeholder of secret values when they are serialized.
+pub const REDACTED: &str = "***";
+
+/// SerializeAs/DeserializeAs for secret strings, which are replaced by [`REDACTED`] when
+/// serialized so that they don't end up in configuration dumps or logs.
+/// Use it with `#[serde(with = "serde_with::As::<RedactedSerde>")]`.
+pub struct RedactedSerde;
+
+impl SerializeAs<String> for RedactedSerde {
+    fn serialize_as<S>(_source: &String, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> DeserializeAs<'de, String> for RedactedSerde {
+    fn deserialize_as<D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)
+    }
+}
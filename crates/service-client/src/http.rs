@@ -8,8 +8,11 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use super::proxy::ProxyConnector;
+use super::metered_connector::MeteredConnector;
+use super::proxy::{ProxyConnector, TunnelConnector};
+use super::tls::{HostTlsConnector, TlsClientConfigs, TlsConfigError};
 
+use crate::metric_definitions;
 use crate::utils::ErrorExt;
 
 use bytes::Bytes;
@@ -22,7 +25,7 @@ use hyper::body::Body;
 use hyper::http::uri::PathAndQuery;
 use hyper::http::HeaderValue;
 use hyper::{HeaderMap, Method, Request, Response, Uri};
-use hyper_rustls::{ConfigBuilderExt, HttpsConnector};
+use hyper_rustls::HttpsConnector;
 use hyper_util::client::legacy::connect::HttpConnector;
 use restate_types::config::HttpOptions;
 use rustls::ClientConfig;
@@ -30,17 +33,9 @@ use std::error::Error;
 use std::fmt::Debug;
 use std::future;
 use std::future::Future;
-use std::sync::LazyLock;
 
-type ProxiedHttpsConnector = ProxyConnector<HttpsConnector<HttpConnector>>;
-type ProxiedHttpConnector = ProxyConnector<HttpConnector>;
-
-static TLS_CLIENT_CONFIG: LazyLock<ClientConfig> = LazyLock::new(|| {
-    ClientConfig::builder()
-        .with_native_roots()
-        .expect("Can load native certificates")
-        .with_no_client_auth()
-});
+type ProxiedHttpsConnector = MeteredConnector<ProxyConnector<HostTlsConnector<TunnelConnector>>>;
+type ProxiedHttpConnector = MeteredConnector<ProxyConnector<HttpConnector>>;
 
 // TODO
 //  for the time being we use BoxBody here to simplify the migration to hyper 1.0.
@@ -64,7 +59,9 @@ pub struct HttpClient {
 }
 
 impl HttpClient {
-    pub fn from_options(options: &HttpOptions) -> HttpClient {
+    pub fn from_options(options: &HttpOptions) -> Result<HttpClient, TlsConfigError> {
+        metric_definitions::describe_metrics();
+
         let mut builder =
             hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::default());
         builder.timer(hyper_util::rt::TokioTimer::default());
@@ -78,28 +75,48 @@ impl HttpClient {
         http_connector.set_nodelay(true);
         http_connector.set_connect_timeout(Some(options.connect_timeout.into()));
 
-        let https_connector = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_tls_config(TLS_CLIENT_CONFIG.clone())
-            .https_or_http()
-            .enable_http1()
-            .enable_http2()
-            .wrap_connector(http_connector.clone());
-
-        HttpClient {
-            client: builder.clone().build::<_, BoxBody>(ProxyConnector::new(
-                options.http_proxy.clone(),
-                options.no_proxy.clone(),
-                https_connector,
+        // Connections to HTTPS endpoints are tunneled through the proxy, the TLS session is
+        // established with the endpoint itself.
+        let tunnel_connector = TunnelConnector::new(
+            options.http_proxy.clone(),
+            options.no_proxy.clone(),
+            http_connector.clone(),
+        );
+        let https_connector = |tls_config: ClientConfig| {
+            hyper_rustls::HttpsConnectorBuilder::new()
+                .with_tls_config(tls_config)
+                .https_or_http()
+                .enable_http1()
+                .enable_http2()
+                .wrap_connector(tunnel_connector.clone())
+        };
+        let tls_configs = TlsClientConfigs::from_options(options)?;
+        let tls_connector = HostTlsConnector::new(
+            https_connector(tls_configs.default),
+            tls_configs
+                .by_host
+                .into_iter()
+                .map(|(host, tls_config)| (host, https_connector(tls_config)))
+                .collect(),
+        );
+
+        Ok(HttpClient {
+            client: builder.clone().build::<_, BoxBody>(MeteredConnector::new(
+                ProxyConnector::new(
+                    options.http_proxy.clone(),
+                    options.no_proxy.clone(),
+                    tls_connector,
+                ),
             )),
             h2c_prior_knowledge_client: {
                 builder.http2_only(true);
-                builder.build::<_, BoxBody>(ProxyConnector::new(
+                builder.build::<_, BoxBody>(MeteredConnector::new(ProxyConnector::new(
                     options.http_proxy.clone(),
                     options.no_proxy.clone(),
                     http_connector,
-                ))
+                )))
             },
-        }
+        })
     }
 
     fn build_request<B>(
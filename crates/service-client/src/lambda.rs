@@ -13,6 +13,7 @@
 
 use crate::aws_hyper_client::{CryptoMode, HyperClientBuilder};
 use crate::utils::ErrorExt;
+use crate::LambdaInvokeOptions;
 use arc_swap::ArcSwap;
 use assume_role::AssumeRoleProvider;
 use aws_config::BehaviorVersion;
@@ -23,7 +24,6 @@ use aws_sdk_lambda::primitives::Blob;
 use base64::display::Base64Display;
 use base64::Engine;
 use bytes::Bytes;
-use bytestring::ByteString;
 use futures::future::{BoxFuture, Shared};
 use futures::{FutureExt, TryFutureExt};
 use http::uri::PathAndQuery;
@@ -70,27 +70,27 @@ struct LambdaClientInner {
     /// Lambda client config builder
     /// Keep track of how to create a lambda client with shared http connector etc with the main one
     lambda_client_builder: aws_sdk_lambda::config::Builder,
-    /// Map of Role -> Client
-    /// We use this map to cache pre-configured clients per role.
+    /// Map of (Role, Region) -> Client
+    /// We use this map to cache pre-configured clients per role. Every client caches the
+    /// credentials obtained from STS until they expire.
     /// If not set, do not cache; the discovery client for example should not cache because there is
     /// a DoS vector
     /// https://github.com/restatedev/restate/issues/878
-    role_to_lambda_clients: Option<ArcSwap<HashMap<String, aws_sdk_lambda::Client>>>,
-    /// External id to set on assume role requests
+    role_to_lambda_clients: Option<ArcSwap<HashMap<LambdaInvokeOptions, aws_sdk_lambda::Client>>>,
+    /// External id to set on assume role requests. It is configured for the whole server rather
+    /// than per deployment, so that whoever registers a deployment can't pick the external id
+    /// the roles trust Restate with (confused deputy).
     assume_role_external_id: Option<String>,
 }
 
 impl LambdaClient {
-    pub fn new(
-        profile_name: Option<String>,
-        assume_role_external_id: Option<String>,
-        assume_role_cache_mode: AssumeRoleCacheMode,
-    ) -> Self {
+    pub fn from_options(options: &AwsOptions, assume_role_cache_mode: AssumeRoleCacheMode) -> Self {
         // create client for a default region, region can be overridden per request
         let mut config = aws_config::defaults(BehaviorVersion::latest());
-        if let Some(profile_name) = profile_name {
+        if let Some(profile_name) = &options.aws_profile {
             config = config.profile_name(profile_name);
         };
+        let assume_role_external_id = options.aws_assume_role_external_id.clone();
         config = config.http_client(
             HyperClientBuilder::new()
                 .crypto_mode(CryptoMode::Ring)
@@ -127,22 +127,11 @@ impl LambdaClient {
         Self { inner }
     }
 
-    pub fn from_options(
-        options: &AwsOptions,
-        assume_role_cache_mode: AssumeRoleCacheMode,
-    ) -> LambdaClient {
-        LambdaClient::new(
-            options.aws_profile.clone(),
-            options.aws_assume_role_external_id.clone(),
-            assume_role_cache_mode,
-        )
-    }
-
     pub fn invoke<B>(
         &self,
         arn: LambdaARN,
         method: Method,
-        assume_role_arn: Option<ByteString>,
+        invoke_options: LambdaInvokeOptions,
         body: B,
         path: PathAndQuery,
         headers: HeaderMap<HeaderValue>,
@@ -153,7 +142,13 @@ impl LambdaClient {
         <B as Body>::Error: Error + Send + Sync + 'static,
     {
         let function_name = arn.to_string();
-        let region = Region::new(arn.region().to_string());
+        let region = Region::new(
+            invoke_options
+                .region
+                .as_deref()
+                .unwrap_or(arn.region())
+                .to_string(),
+        );
         let inner = self.inner.clone();
         let body = body
             .map_err(|e| LambdaError::Body(Box::new(e)))
@@ -173,7 +168,7 @@ impl LambdaClient {
             };
 
             let res = inner
-                .build_invoke(assume_role_arn)
+                .build_invoke(invoke_options)
                 .function_name(function_name)
                 .payload(Blob::new(
                     serde_json::to_vec(&payload).map_err(LambdaError::SerializationError)?,
@@ -207,10 +202,10 @@ impl LambdaClient {
 impl LambdaClientInner {
     fn build_invoke(
         &self,
-        assume_role_arn: Option<ByteString>,
+        invoke_options: LambdaInvokeOptions,
     ) -> aws_sdk_lambda::operation::invoke::builders::InvokeFluentBuilder {
-        let assume_role_arn = if let Some(assume_role_arn) = assume_role_arn {
-            assume_role_arn
+        let assume_role_arn = if let Some(assume_role_arn) = &invoke_options.assume_role_arn {
+            assume_role_arn.to_string()
         } else {
             // fastest path; no assumed role, don't bother with the shared hashmap
             return self.no_role_lambda_client.invoke();
@@ -218,7 +213,7 @@ impl LambdaClientInner {
 
         if let Some(invoke) = self.role_to_lambda_clients.as_ref().and_then(|rlc| {
             rlc.load()
-                .get(&*assume_role_arn)
+                .get(&invoke_options)
                 .map(|client| client.invoke())
         }) {
             // fast-ish path; we've seen this assumed role before
@@ -227,12 +222,23 @@ impl LambdaClientInner {
 
         // slow path; create the client for this assumed role
 
+        // use the regional STS endpoint of the region override, if any
+        let sts_client = match &invoke_options.region {
+            Some(region) => aws_sdk_sts::Client::from_conf(
+                self.sts_client
+                    .config()
+                    .to_builder()
+                    .region(Region::new(region.to_string()))
+                    .build(),
+            ),
+            None => self.sts_client.clone(),
+        };
         let conf = self
             .lambda_client_builder
             .clone()
             .credentials_provider(AssumeRoleProvider::new(
-                self.sts_client.clone(),
-                assume_role_arn.to_string(),
+                sts_client,
+                assume_role_arn,
                 self.assume_role_external_id.clone(),
             ))
             .build();
@@ -242,14 +248,14 @@ impl LambdaClientInner {
         if let Some(rlc) = &self.role_to_lambda_clients {
             // repeatedly try to clone the hashmap and compare and swap in a map with the new arn
             rlc.rcu(|cache| {
-                if let Some(existing_client) = cache.get(&*assume_role_arn) {
+                if let Some(existing_client) = cache.get(&invoke_options) {
                     // someone else got there first; instead of cloning the whole hashmap, just keep track
                     // of the winning client and write the existing hashmap back into the ArcSwap
                     client = existing_client.clone();
                     return Arc::clone(cache);
                 }
                 let mut cache = HashMap::clone(cache);
-                cache.insert(assume_role_arn.to_string(), client.clone());
+                cache.insert(invoke_options.clone(), client.clone());
                 cache.into()
             });
         }
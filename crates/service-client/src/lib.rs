@@ -14,6 +14,7 @@ use crate::lambda::LambdaClient;
 pub use crate::http::HttpError;
 pub use crate::lambda::AssumeRoleCacheMode;
 use crate::request_identity::SignRequest;
+pub use crate::tls::TlsConfigError;
 use ::http::Version;
 use arc_swap::ArcSwapOption;
 use bytes::Bytes;
@@ -36,8 +37,11 @@ use std::sync::Arc;
 mod aws_hyper_client;
 mod http;
 mod lambda;
+mod metered_connector;
+mod metric_definitions;
 mod proxy;
 mod request_identity;
+mod tls;
 mod utils;
 
 pub type ResponseBody = http_body_util::Either<hyper::body::Incoming, Full<Bytes>>;
@@ -82,7 +86,7 @@ impl ServiceClient {
         };
 
         Ok(Self::new(
-            HttpClient::from_options(&options.http),
+            HttpClient::from_options(&options.http)?,
             LambdaClient::from_options(&options.lambda, assume_role_cache_mode),
             request_identity_key,
         ))
@@ -93,6 +97,8 @@ impl ServiceClient {
 pub enum BuildError {
     #[error("Failed to read request identity private key: {0}")]
     SigningPrivateKeyReadError(#[from] request_identity::v1::SigningPrivateKeyReadError),
+    #[error("Failed to configure TLS: {0}")]
+    Tls(#[from] TlsConfigError),
 }
 
 impl ServiceClient {
@@ -134,11 +140,11 @@ impl ServiceClient {
                 );
                 async move { Ok(fut.await?.map(http_body_util::Either::Left)) }.left_future()
             }
-            Endpoint::Lambda(arn, assume_role_arn) => {
+            Endpoint::Lambda(arn, invoke_options) => {
                 let fut = self.lambda.invoke(
                     arn,
                     parts.method.into(),
-                    assume_role_arn,
+                    invoke_options,
                     body,
                     parts.path,
                     parts.headers,
@@ -244,7 +250,16 @@ impl Parts {
 #[derive(Clone, Debug)]
 pub enum Endpoint {
     Http(Uri, Option<Version>),
-    Lambda(LambdaARN, Option<ByteString>),
+    Lambda(LambdaARN, LambdaInvokeOptions),
+}
+
+/// Options of the AWS requests issued to invoke a Lambda function.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LambdaInvokeOptions {
+    /// ARN of a role to assume to invoke the function, to support role chaining.
+    pub assume_role_arn: Option<ByteString>,
+    /// Region of the AWS endpoints, overriding the region of the function ARN.
+    pub region: Option<ByteString>,
 }
 
 impl fmt::Display for Endpoint {
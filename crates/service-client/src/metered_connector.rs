@@ -0,0 +1,128 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper::Uri;
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use metrics::{counter, gauge, histogram};
+use tower_service::Service;
+
+use crate::metric_definitions::{
+    CONNECTIONS_OPEN, CONNECTIONS_OPENED, CONNECT_DURATION, CONNECT_FAILURES,
+};
+
+/// Connector recording the metrics of the connections opened by the pool of the HTTP client.
+#[derive(Clone, Debug)]
+pub struct MeteredConnector<C> {
+    connector: C,
+}
+
+impl<C> MeteredConnector<C> {
+    pub fn new(connector: C) -> Self {
+        Self { connector }
+    }
+}
+
+impl<C> Service<Uri> for MeteredConnector<C>
+where
+    C: Service<Uri>,
+    C::Future: Send + 'static,
+{
+    type Response = MeteredConnection<C::Response>;
+    type Error = C::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.connector.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let connecting = self.connector.call(dst);
+        Box::pin(async move {
+            let start = Instant::now();
+            match connecting.await {
+                Ok(io) => {
+                    histogram!(CONNECT_DURATION).record(start.elapsed());
+                    counter!(CONNECTIONS_OPENED).increment(1);
+                    gauge!(CONNECTIONS_OPEN).increment(1);
+                    Ok(MeteredConnection { io })
+                }
+                Err(err) => {
+                    counter!(CONNECT_FAILURES).increment(1);
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+/// Connection which is accounted as open until it's dropped by the pool.
+#[derive(Debug)]
+pub struct MeteredConnection<T> {
+    io: T,
+}
+
+impl<T> Drop for MeteredConnection<T> {
+    fn drop(&mut self) {
+        gauge!(CONNECTIONS_OPEN).decrement(1);
+    }
+}
+
+impl<T: Connection> Connection for MeteredConnection<T> {
+    fn connected(&self) -> Connected {
+        self.io.connected()
+    }
+}
+
+impl<T: Read + Unpin> Read for MeteredConnection<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_read(cx, buf)
+    }
+}
+
+impl<T: Write + Unpin> Write for MeteredConnection<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write_vectored(cx, bufs)
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+/// Optional to have but adds description/help message to the metrics emitted to
+/// the metrics' sink.
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+
+pub(crate) const CONNECTIONS_OPENED: &str = "restate.service_client.connections_opened.total";
+pub(crate) const CONNECTIONS_OPEN: &str = "restate.service_client.connections_open";
+pub(crate) const CONNECT_FAILURES: &str = "restate.service_client.connect_failures.total";
+pub(crate) const CONNECT_DURATION: &str = "restate.service_client.connect_duration.seconds";
+
+pub(crate) fn describe_metrics() {
+    describe_counter!(
+        CONNECTIONS_OPENED,
+        Unit::Count,
+        "Number of connections opened to the deployments by the pool of the HTTP client"
+    );
+
+    describe_gauge!(
+        CONNECTIONS_OPEN,
+        Unit::Count,
+        "Number of connections to the deployments currently held by the pool of the HTTP client, idle or in use"
+    );
+
+    describe_counter!(
+        CONNECT_FAILURES,
+        Unit::Count,
+        "Number of failed attempts to open a connection to a deployment"
+    );
+
+    describe_histogram!(
+        CONNECT_DURATION,
+        Unit::Seconds,
+        "Time taken to open a connection to a deployment, including the TLS handshake"
+    );
+}
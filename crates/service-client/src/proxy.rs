@@ -8,46 +8,48 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::uri::Scheme;
 use hyper::Uri;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioIo;
 use restate_types::config::ProxyUri;
 use rustls::pki_types::IpAddr;
-use std::{
-    collections::HashSet,
-    task::{Context, Poll},
-};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tower_service::Service;
 
-#[derive(Clone, Debug)]
-pub struct ProxyConnector<C> {
-    proxy: Option<ProxyUri>,
-    no_proxy_ips: HashSet<IpAddr>,
-    no_proxy_domains: Vec<String>,
-    connector: C,
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Authorities which should not be proxied.
+#[derive(Clone, Debug, Default)]
+struct NoProxy {
+    ips: HashSet<IpAddr>,
+    domains: Vec<String>,
 }
 
-impl<C> ProxyConnector<C> {
-    pub fn new(proxy: Option<ProxyUri>, no_proxy: Vec<http::uri::Authority>, connector: C) -> Self {
-        let mut no_proxy_ips = HashSet::new();
-        let mut no_proxy_domains = Vec::new();
+impl NoProxy {
+    fn new(no_proxy: Vec<http::uri::Authority>) -> Self {
+        let mut ips = HashSet::new();
+        let mut domains = Vec::new();
 
         for no_proxy_authority in no_proxy {
             match IpAddr::try_from(no_proxy_authority.as_str()) {
                 Ok(ip) => {
-                    no_proxy_ips.insert(ip);
+                    ips.insert(ip);
                 }
-                Err(_) => no_proxy_domains.push(no_proxy_authority.host().to_owned()),
+                Err(_) => domains.push(no_proxy_authority.host().to_owned()),
             }
         }
 
-        Self {
-            proxy,
-            no_proxy_ips,
-            no_proxy_domains,
-            connector,
-        }
+        Self { ips, domains }
     }
 
-    fn no_proxy(&self, host: &str) -> bool {
+    fn matches(&self, host: &str) -> bool {
         // According to RFC3986, raw IPv6 hosts will be wrapped in []. So we need to strip those off
         // the end in order to parse correctly
         let authority = if host.starts_with('[') && host.ends_with(']') {
@@ -57,22 +59,22 @@ impl<C> ProxyConnector<C> {
         };
         match IpAddr::try_from(authority) {
             // If we can parse an IP addr, then use it, otherwise, assume it is a domain
-            Ok(ip) => self.no_proxy_ip(ip),
-            Err(_) => self.no_proxy_domain(authority),
+            Ok(ip) => self.matches_ip(ip),
+            Err(_) => self.matches_domain(authority),
         }
     }
 
-    fn no_proxy_ip(&self, ip: IpAddr) -> bool {
-        self.no_proxy_ips.contains(&ip)
+    fn matches_ip(&self, ip: IpAddr) -> bool {
+        self.ips.contains(&ip)
     }
 
     // Copied from reqwest: https://github.com/seanmonstar/reqwest/blob/master/src/proxy.rs <dual-licensed Apache and MIT>
     // The following links may be useful to understand the origin of these rules:
     // * https://curl.se/libcurl/c/CURLOPT_NOPROXY.html
     // * https://github.com/curl/curl/issues/1208
-    fn no_proxy_domain(&self, domain: &str) -> bool {
+    fn matches_domain(&self, domain: &str) -> bool {
         let domain_len = domain.len();
-        for d in &self.no_proxy_domains {
+        for d in &self.domains {
             let d = d.as_str();
             if d == domain || d.strip_prefix('.') == Some(domain) {
                 return true;
@@ -83,7 +85,7 @@ impl<C> ProxyConnector<C> {
                     return true;
                 } else if domain.as_bytes().get(domain_len - d.len() - 1) == Some(&b'.') {
                     // Given that d is a prefix of domain, if the prior character in domain is a dot
-                    // then that means we must be matching a subdomain of d, and that matches
+                    // then that means we must be matching a subdomain of d and that matches
                     return true;
                 }
             } else if d == "*" {
@@ -94,6 +96,23 @@ impl<C> ProxyConnector<C> {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ProxyConnector<C> {
+    proxy: Option<ProxyUri>,
+    no_proxy: NoProxy,
+    connector: C,
+}
+
+impl<C> ProxyConnector<C> {
+    pub fn new(proxy: Option<ProxyUri>, no_proxy: Vec<http::uri::Authority>, connector: C) -> Self {
+        Self {
+            proxy,
+            no_proxy: NoProxy::new(no_proxy),
+            connector,
+        }
+    }
+}
+
 impl<C> Service<Uri> for ProxyConnector<C>
 where
     C: Service<Uri>,
@@ -108,7 +127,7 @@ where
 
     fn call(&mut self, uri: Uri) -> Self::Future {
         if let Some(host) = uri.host() {
-            if self.no_proxy(host) {
+            if self.no_proxy.matches(host) {
                 return self.connector.call(uri);
             }
         }
@@ -118,3 +137,192 @@ where
         })
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum TunnelError {
+    #[error("the proxy closed the connection while establishing the tunnel")]
+    UnexpectedEof,
+    #[error("the response of the proxy to the CONNECT request is too long")]
+    ResponseTooLong,
+    #[error("the proxy refused to establish the tunnel: {0}")]
+    Refused(String),
+}
+
+/// Connector tunneling the connections to HTTPS endpoints through the HTTP proxy, using `CONNECT`.
+///
+/// The TLS handshake with the endpoint is performed over the tunnel by the wrapping
+/// [`hyper_rustls::HttpsConnector`], hence the proxy never sees the plain traffic. Connections to
+/// HTTP endpoints are proxied by the [`ProxyConnector`] instead.
+#[derive(Clone, Debug)]
+pub struct TunnelConnector {
+    proxy: Option<ProxyUri>,
+    no_proxy: NoProxy,
+    connector: HttpConnector,
+}
+
+impl TunnelConnector {
+    pub fn new(
+        proxy: Option<ProxyUri>,
+        no_proxy: Vec<http::uri::Authority>,
+        connector: HttpConnector,
+    ) -> Self {
+        // Tunneling through HTTPS proxies would require nesting TLS sessions
+        let proxy = proxy.filter(|proxy| proxy.as_uri().scheme() == Some(&Scheme::HTTP));
+        Self {
+            proxy,
+            no_proxy: NoProxy::new(no_proxy),
+            connector,
+        }
+    }
+
+    fn tunnel_proxy(&self, dst: &Uri) -> Option<&ProxyUri> {
+        let proxy = self.proxy.as_ref()?;
+        if dst.scheme() != Some(&Scheme::HTTPS)
+            || dst.authority() == proxy.as_uri().authority()
+            || dst.host().is_none_or(|host| self.no_proxy.matches(host))
+        {
+            return None;
+        }
+        Some(proxy)
+    }
+}
+
+impl Service<Uri> for TunnelConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.connector.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let Some(proxy) = self.tunnel_proxy(&dst) else {
+            let connecting = self.connector.call(dst);
+            return Box::pin(async move { Ok(connecting.await?) });
+        };
+
+        let connecting = self.connector.call(proxy.as_uri().clone());
+        let host = dst.host().expect("checked by tunnel_proxy").to_owned();
+        let port = dst.port_u16().unwrap_or(443);
+        Box::pin(async move {
+            let stream = connecting.await?.into_inner();
+            Ok(TokioIo::new(
+                establish_tunnel(stream, &format!("{host}:{port}")).await?,
+            ))
+        })
+    }
+}
+
+async fn establish_tunnel(mut stream: TcpStream, authority: &str) -> Result<TcpStream, BoxError> {
+    stream
+        .write_all(format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n\r\n").as_bytes())
+        .await?;
+
+    let mut buf = [0; 8192];
+    let mut pos = 0;
+    loop {
+        let n = stream.read(&mut buf[pos..]).await?;
+        if n == 0 {
+            return Err(TunnelError::UnexpectedEof.into());
+        }
+        pos += n;
+
+        let response = &buf[..pos];
+        if response.starts_with(b"HTTP/1.1 200") || response.starts_with(b"HTTP/1.0 200") {
+            if response.ends_with(b"\r\n\r\n") {
+                return Ok(stream);
+            }
+            if pos == buf.len() {
+                return Err(TunnelError::ResponseTooLong.into());
+            }
+        } else if response.len() >= 12 {
+            let status_line = response.split(|b| *b == b'\r').next().unwrap_or(response);
+            return Err(
+                TunnelError::Refused(String::from_utf8_lossy(status_line).into_owned()).into(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn no_proxy_matches_subdomains_and_ips() {
+        let no_proxy = NoProxy::new(vec![
+            "restate.dev".parse().unwrap(),
+            "127.0.0.1".parse().unwrap(),
+        ]);
+
+        assert!(no_proxy.matches("restate.dev"));
+        assert!(no_proxy.matches("api.restate.dev"));
+        assert!(!no_proxy.matches("notrestate.dev"));
+        assert!(no_proxy.matches("127.0.0.1"));
+        assert!(!no_proxy.matches("[::1]"));
+    }
+
+    #[tokio::test]
+    async fn tunnel_https_through_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+        let proxy = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        let mut connector = TunnelConnector::new(
+            Some(format!("http://{proxy_address}/").parse().unwrap()),
+            vec![],
+            HttpConnector::new(),
+        );
+        connector
+            .call("https://greeter.internal:9080/".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            proxy.await.unwrap(),
+            "CONNECT greeter.internal:9080 HTTP/1.1\r\nHost: greeter.internal:9080\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn tunnel_refused_by_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let mut connector = TunnelConnector::new(
+            Some(format!("http://{proxy_address}/").parse().unwrap()),
+            vec![],
+            HttpConnector::new(),
+        );
+        let err = connector
+            .call("https://greeter.internal/".parse().unwrap())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "the proxy refused to establish the tunnel: HTTP/1.1 403 Forbidden"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::Uri;
+use hyper_rustls::HttpsConnector;
+use restate_tls_util::{read_certificates, read_private_key, PemError};
+use restate_types::config::{HttpOptions, TlsClientCertificate};
+use rustls::{ClientConfig, RootCertStore};
+use tower_service::Service;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error("failed to load the native root certificates: {0}")]
+    NativeRootCertificates(#[source] io::Error),
+    #[error(transparent)]
+    Pem(#[from] PemError),
+    #[error("invalid root certificate in '{}': {source}", path.display())]
+    InvalidRootCertificate {
+        path: PathBuf,
+        #[source]
+        source: rustls::Error,
+    },
+    #[error("invalid TLS client certificate for host '{host}': {source}")]
+    InvalidClientCertificate {
+        host: String,
+        #[source]
+        source: rustls::Error,
+    },
+}
+
+/// TLS client configurations of the [`HttpClient`](crate::http::HttpClient).
+pub(crate) struct TlsClientConfigs {
+    /// Used for the hosts without a client certificate.
+    pub(crate) default: ClientConfig,
+    /// Presents the client certificate configured for the host.
+    pub(crate) by_host: HashMap<String, ClientConfig>,
+}
+
+impl TlsClientConfigs {
+    pub(crate) fn from_options(options: &HttpOptions) -> Result<Self, TlsConfigError> {
+        let root_certificates = Arc::new(root_cert_store(&options.additional_root_certificates)?);
+
+        let default = ClientConfig::builder()
+            .with_root_certificates(Arc::clone(&root_certificates))
+            .with_no_client_auth();
+        let by_host = options
+            .tls_client_certificates
+            .iter()
+            .map(|(host, client_certificate)| {
+                let config =
+                    client_auth_config(host, client_certificate, Arc::clone(&root_certificates))?;
+                Ok((host.clone(), config))
+            })
+            .collect::<Result<_, TlsConfigError>>()?;
+
+        Ok(Self { default, by_host })
+    }
+}
+
+fn root_cert_store(
+    additional_root_certificates: &[PathBuf],
+) -> Result<RootCertStore, TlsConfigError> {
+    let mut root_cert_store = RootCertStore::empty();
+    root_cert_store.add_parsable_certificates(
+        rustls_native_certs::load_native_certs().map_err(TlsConfigError::NativeRootCertificates)?,
+    );
+
+    for path in additional_root_certificates {
+        for certificate in read_certificates(path)? {
+            root_cert_store.add(certificate).map_err(|source| {
+                TlsConfigError::InvalidRootCertificate {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+        }
+    }
+
+    Ok(root_cert_store)
+}
+
+fn client_auth_config(
+    host: &str,
+    client_certificate: &TlsClientCertificate,
+    root_certificates: Arc<RootCertStore>,
+) -> Result<ClientConfig, TlsConfigError> {
+    let certificate_chain = read_certificates(&client_certificate.certificate_file)?;
+    let private_key = read_private_key(&client_certificate.private_key_file)?;
+
+    ClientConfig::builder()
+        .with_root_certificates(root_certificates)
+        .with_client_auth_cert(certificate_chain, private_key)
+        .map_err(|source| TlsConfigError::InvalidClientCertificate {
+            host: host.to_owned(),
+            source,
+        })
+}
+
+/// Connector performing the TLS handshake with the client configuration of the destination host.
+#[derive(Clone, Debug)]
+pub(crate) struct HostTlsConnector<C> {
+    default: HttpsConnector<C>,
+    by_host: Arc<HashMap<String, HttpsConnector<C>>>,
+}
+
+impl<C> HostTlsConnector<C> {
+    pub(crate) fn new(
+        default: HttpsConnector<C>,
+        by_host: HashMap<String, HttpsConnector<C>>,
+    ) -> Self {
+        Self {
+            default,
+            by_host: Arc::new(by_host),
+        }
+    }
+}
+
+impl<C> Service<Uri> for HostTlsConnector<C>
+where
+    C: Clone,
+    HttpsConnector<C>: Service<Uri>,
+{
+    type Response = <HttpsConnector<C> as Service<Uri>>::Response;
+    type Error = <HttpsConnector<C> as Service<Uri>>::Error;
+    type Future = <HttpsConnector<C> as Service<Uri>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // All connectors wrap the same inner connector
+        self.default.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        match dst.host().and_then(|host| self.by_host.get(host)) {
+            Some(connector) => connector.clone().call(dst),
+            None => self.default.call(dst),
+        }
+    }
+}
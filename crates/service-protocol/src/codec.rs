@@ -265,6 +265,7 @@ mod test_util {
                                     handler_ty: VirtualObjectHandlerType::Exclusive,
                                 },
                                 completion_retention_time: None,
+                                shared_concurrency_limit: None,
                                 span_context: Default::default(),
                             }),
                         },
@@ -302,6 +303,7 @@ mod test_util {
                                     handler_ty: VirtualObjectHandlerType::Exclusive,
                                 },
                                 completion_retention_time: None,
+                                shared_concurrency_limit: None,
                                 span_context: Default::default(),
                             },
                         },
@@ -9,8 +9,8 @@
 // by the Apache License, Version 2.0.
 
 use bytes::Bytes;
-use bytestring::ByteString;
 use codederror::CodedError;
+use enumset::EnumSet;
 use http::header::{ACCEPT, CONTENT_TYPE};
 use http::response::Parts as ResponseParts;
 use http::uri::PathAndQuery;
@@ -20,7 +20,9 @@ use http_body_util::Empty;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use restate_errors::{META0003, META0012, META0013, META0014, META0015};
-use restate_service_client::{Endpoint, Method, Parts, Request, ServiceClient, ServiceClientError};
+use restate_service_client::{
+    Endpoint, LambdaInvokeOptions, Method, Parts, Request, ServiceClient, ServiceClientError,
+};
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
 use restate_types::identifiers::LambdaARN;
@@ -31,8 +33,8 @@ use restate_types::service_discovery::{
     MIN_SERVICE_DISCOVERY_PROTOCOL_VERSION,
 };
 use restate_types::service_protocol::{
-    ServiceProtocolVersion, MAX_SERVICE_PROTOCOL_VERSION, MAX_SERVICE_PROTOCOL_VERSION_VALUE,
-    MIN_SERVICE_PROTOCOL_VERSION,
+    ProtocolExtension, ServiceProtocolVersion, MAX_SERVICE_PROTOCOL_VERSION,
+    MAX_SERVICE_PROTOCOL_VERSION_VALUE, MIN_SERVICE_PROTOCOL_VERSION, PROTOCOL_EXTENSIONS_HEADER,
 };
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -48,6 +50,8 @@ const SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE: &str =
     "application/vnd.restate.endpointmanifest.v1+json";
 const SERVICE_DISCOVERY_PROTOCOL_V2_HEADER_VALUE: &str =
     "application/vnd.restate.endpointmanifest.v2+json";
+const SERVICE_DISCOVERY_PROTOCOL_V3_HEADER_VALUE: &str =
+    "application/vnd.restate.endpointmanifest.v3+json";
 static SUPPORTED_SERVICE_DISCOVERY_PROTOCOL_VERSIONS: Lazy<HeaderValue> = Lazy::new(|| {
     let supported_versions = ServiceDiscoveryProtocolVersion::iter()
         .skip_while(|version| version < &MIN_SERVICE_DISCOVERY_PROTOCOL_VERSION)
@@ -58,6 +62,13 @@ static SUPPORTED_SERVICE_DISCOVERY_PROTOCOL_VERSIONS: Lazy<HeaderValue> = Lazy::
         .expect("header value to contain only valid characters")
 });
 
+static SUPPORTED_PROTOCOL_EXTENSIONS: Lazy<HeaderValue> = Lazy::new(|| {
+    HeaderValue::from_str(&ProtocolExtension::to_header_value(
+        ProtocolExtension::supported(),
+    ))
+    .expect("header value to contain only valid characters")
+});
+
 const DISCOVER_PATH: &str = "/discover";
 
 fn service_discovery_protocol_to_content_type(
@@ -69,6 +80,7 @@ fn service_discovery_protocol_to_content_type(
         }
         ServiceDiscoveryProtocolVersion::V1 => SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE,
         ServiceDiscoveryProtocolVersion::V2 => SERVICE_DISCOVERY_PROTOCOL_V2_HEADER_VALUE,
+        ServiceDiscoveryProtocolVersion::V3 => SERVICE_DISCOVERY_PROTOCOL_V3_HEADER_VALUE,
     }
 }
 
@@ -78,10 +90,30 @@ fn parse_service_discovery_protocol_version_from_content_type(
     match content_type {
         SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE => Some(ServiceDiscoveryProtocolVersion::V1),
         SERVICE_DISCOVERY_PROTOCOL_V2_HEADER_VALUE => Some(ServiceDiscoveryProtocolVersion::V2),
+        SERVICE_DISCOVERY_PROTOCOL_V3_HEADER_VALUE => Some(ServiceDiscoveryProtocolVersion::V3),
         _ => None,
     }
 }
 
+/// Clears the timeouts, retention and ingress visibility options of the services and handlers.
+fn clear_v3_options(endpoint: &mut endpoint_manifest::Endpoint) {
+    for service in &mut endpoint.services {
+        service.abort_timeout = None;
+        service.idempotency_retention = None;
+        service.inactivity_timeout = None;
+        service.ingress_private = None;
+        service.journal_retention = None;
+        for handler in &mut service.handlers {
+            handler.abort_timeout = None;
+            handler.idempotency_retention = None;
+            handler.inactivity_timeout = None;
+            handler.ingress_private = None;
+            handler.journal_retention = None;
+            handler.workflow_completion_retention = None;
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DiscoverEndpoint(Endpoint, HashMap<HeaderName, HeaderValue>);
 
@@ -99,12 +131,18 @@ impl DiscoverEndpoint {
     }
 
     fn request(&self) -> Request<Empty<Bytes>> {
-        let mut headers = HeaderMap::from_iter([(
-            ACCEPT,
-            SUPPORTED_SERVICE_DISCOVERY_PROTOCOL_VERSIONS
-                .deref()
-                .clone(),
-        )]);
+        let mut headers = HeaderMap::from_iter([
+            (
+                ACCEPT,
+                SUPPORTED_SERVICE_DISCOVERY_PROTOCOL_VERSIONS
+                    .deref()
+                    .clone(),
+            ),
+            (
+                HeaderName::from_static(PROTOCOL_EXTENSIONS_HEADER),
+                SUPPORTED_PROTOCOL_EXTENSIONS.deref().clone(),
+            ),
+        ]);
         headers.extend(self.1.clone());
         let path = PathAndQuery::from_static(DISCOVER_PATH);
         Request::new(
@@ -117,7 +155,7 @@ impl DiscoverEndpoint {
 #[derive(Clone, Debug)]
 pub enum DiscoveredEndpoint {
     Http(Uri, Version),
-    Lambda(LambdaARN, Option<ByteString>),
+    Lambda(LambdaARN, LambdaInvokeOptions),
 }
 
 #[derive(Debug)]
@@ -129,6 +167,7 @@ pub struct DiscoveredMetadata {
     // type is i32 because the generated ServiceProtocolVersion enum uses this as its representation
     // and we need to represent unknown later versions
     pub supported_protocol_versions: RangeInclusive<i32>,
+    pub protocol_extensions: EnumSet<ProtocolExtension>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -233,12 +272,21 @@ impl ServiceDiscovery {
             Self::retrieve_service_discovery_protocol_version(content_type)?;
 
         let x_restate_server = parts.headers.remove(X_RESTATE_SERVER);
+        let protocol_extensions =
+            Self::retrieve_protocol_extensions(parts.headers.remove(PROTOCOL_EXTENSIONS_HEADER));
 
         let response = match service_discovery_protocol_version {
             ServiceDiscoveryProtocolVersion::Unspecified => {
                 unreachable!("unspecified service discovery protocol should not be chosen")
             }
             ServiceDiscoveryProtocolVersion::V1 | ServiceDiscoveryProtocolVersion::V2 => {
+                let mut response: endpoint_manifest::Endpoint =
+                    serde_json::from_slice(&body).map_err(|e| DiscoveryError::Decode(e, body))?;
+                // The options were introduced with V3, older manifests can't declare them
+                clear_v3_options(&mut response);
+                response
+            }
+            ServiceDiscoveryProtocolVersion::V3 => {
                 serde_json::from_slice(&body).map_err(|e| DiscoveryError::Decode(e, body))?
             }
         };
@@ -252,6 +300,30 @@ impl ServiceDiscovery {
             response,
             x_restate_server,
         )
+        .map(|metadata| DiscoveredMetadata {
+            protocol_extensions,
+            ..metadata
+        })
+    }
+
+    /// Protocol extensions accepted by the deployment. Deployments which don't know about the
+    /// extensions don't send the header, hence they don't accept any.
+    fn retrieve_protocol_extensions(
+        protocol_extensions: Option<HeaderValue>,
+    ) -> EnumSet<ProtocolExtension> {
+        let Some(protocol_extensions) = protocol_extensions else {
+            return EnumSet::empty();
+        };
+        match protocol_extensions.to_str() {
+            Ok(value) => {
+                // Ignore the extensions we didn't offer
+                ProtocolExtension::from_header_value(value) & ProtocolExtension::supported()
+            }
+            Err(e) => {
+                warn!("Ignoring the protocol extensions header of the discovery response: {e}");
+                EnumSet::empty()
+            }
+        }
     }
 
     fn retrieve_service_discovery_protocol_version(
@@ -382,8 +454,8 @@ impl ServiceDiscovery {
         Ok(DiscoveredMetadata {
             endpoint: match endpoint {
                 Endpoint::Http(uri, _) => DiscoveredEndpoint::Http(uri, response_http_version),
-                Endpoint::Lambda(arn, assume_role_arn) => {
-                    DiscoveredEndpoint::Lambda(arn, assume_role_arn)
+                Endpoint::Lambda(arn, invoke_options) => {
+                    DiscoveredEndpoint::Lambda(arn, invoke_options)
                 }
             },
             headers,
@@ -392,6 +464,7 @@ impl ServiceDiscovery {
             // we need to store the raw representation since the runtime might not know the latest
             // version yet.
             supported_protocol_versions: min_version..=max_version,
+            protocol_extensions: EnumSet::empty(),
         })
     }
 
@@ -456,11 +529,12 @@ mod tests {
         parse_service_discovery_protocol_version_from_content_type, DiscoveryError,
         ServiceDiscovery, SERVICE_DISCOVERY_PROTOCOL_V1_HEADER_VALUE,
     };
-    use http::{Uri, Version};
+    use enumset::EnumSet;
+    use http::{HeaderValue, Uri, Version};
     use restate_service_client::Endpoint;
     use restate_types::endpoint_manifest;
     use restate_types::service_discovery::ServiceDiscoveryProtocolVersion;
-    use restate_types::service_protocol::MAX_SERVICE_PROTOCOL_VERSION;
+    use restate_types::service_protocol::{ProtocolExtension, MAX_SERVICE_PROTOCOL_VERSION};
     use std::collections::HashMap;
 
     #[test]
@@ -499,7 +573,7 @@ mod tests {
                     "arn:partition:lambda:region:account_id:function:name:version"
                         .parse()
                         .unwrap(),
-                    None
+                    LambdaInvokeOptions::default()
                 ),
                 HashMap::default(),
                 Version::HTTP_11,
@@ -573,6 +647,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn retrieve_protocol_extensions() {
+        assert!(ServiceDiscovery::retrieve_protocol_extensions(None).is_empty());
+        assert!(
+            ServiceDiscovery::retrieve_protocol_extensions(Some(HeaderValue::from_static(
+                "unknown-extension"
+            )))
+            .is_empty()
+        );
+        assert_eq!(
+            ServiceDiscovery::retrieve_protocol_extensions(Some(HeaderValue::from_static(
+                "unknown-extension, dry-run"
+            ))),
+            EnumSet::only(ProtocolExtension::DryRun)
+        );
+        assert_eq!(
+            ServiceDiscovery::retrieve_protocol_extensions(Some(HeaderValue::from_static(
+                "chunked-messages,dry-run"
+            ))),
+            ProtocolExtension::DryRun | ProtocolExtension::ChunkedMessages
+        );
+    }
+
     #[test]
     fn parse_service_discovery_protocol_version() {
         assert_eq!(
@@ -582,6 +679,13 @@ mod tests {
             Some(ServiceDiscoveryProtocolVersion::V1)
         );
 
+        assert_eq!(
+            parse_service_discovery_protocol_version_from_content_type(
+                SERVICE_DISCOVERY_PROTOCOL_V3_HEADER_VALUE
+            ),
+            Some(ServiceDiscoveryProtocolVersion::V3)
+        );
+
         assert_eq!(
             parse_service_discovery_protocol_version_from_content_type(
                 "application/vnd.restate.endpointmanifest.v1+protobuf"
@@ -30,11 +30,29 @@ pub enum EncodingError {
     #[error("hit message size limit: {0} >= {1}")]
     #[code(restate_errors::RT0003)]
     MessageSizeLimit(usize, usize),
+    #[error("received an unexpected chunk message. Chunks are supported only by deployments which accepted the chunked-messages protocol extension, and cannot be nested. This looks like a bug of the SDK")]
+    UnexpectedChunk,
 }
 
 // --- Input message encoder
 
-pub struct Encoder {}
+/// Message encoded by the [`Encoder`], made of the message header and the message body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedMessage {
+    header: MessageHeader,
+    body: Bytes,
+}
+
+impl EncodedMessage {
+    /// Includes header len
+    pub fn encoded_len(&self) -> usize {
+        8 + self.body.len()
+    }
+}
+
+pub struct Encoder {
+    chunk_size: Option<usize>,
+}
 
 impl Encoder {
     pub fn new(service_protocol_version: ServiceProtocolVersion) -> Self {
@@ -43,7 +61,77 @@ impl Encoder {
             ServiceProtocolVersion::Unspecified,
             "A protocol version should be specified"
         );
-        Self {}
+        Self { chunk_size: None }
+    }
+
+    /// Split messages larger than `chunk_size` in chunks when calling [`Self::chunk`]. Use it only
+    /// if the deployment accepted the chunked-messages protocol extension.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    /// Splits an encoded message in chunk messages, if it's larger than the chunk size.
+    /// Returns the buffers to write in order. The buffers are slices of the message header and
+    /// body, hence this doesn't copy the message.
+    pub fn chunk(&self, encoded_message: &EncodedMessage) -> Vec<Bytes> {
+        let header =
+            Bytes::copy_from_slice(&u64::from(encoded_message.header.clone()).to_be_bytes());
+        let mut segments = [header, encoded_message.body.clone()]
+            .into_iter()
+            .filter(|segment| !segment.is_empty());
+
+        let message_len = encoded_message.encoded_len();
+        let Some(chunk_size) = self
+            .chunk_size
+            .filter(|chunk_size| message_len > *chunk_size)
+        else {
+            return segments.collect();
+        };
+
+        let mut buffers = Vec::with_capacity(3 * message_len.div_ceil(chunk_size));
+        let mut segment = Bytes::new();
+        let mut remaining = message_len;
+        while remaining > 0 {
+            let len = chunk_size.min(remaining);
+            let header = MessageHeader::new(
+                MessageType::Chunk,
+                u32::try_from(len).expect("chunk size must fit in u32"),
+            );
+            buffers.push(Bytes::copy_from_slice(&u64::from(header).to_be_bytes()));
+
+            let mut chunk_remaining = len;
+            while chunk_remaining > 0 {
+                if segment.is_empty() {
+                    segment = segments
+                        .next()
+                        .expect("segments must cover the encoded message");
+                }
+                let slice = segment.split_to(chunk_remaining.min(segment.len()));
+                chunk_remaining -= slice.len();
+                buffers.push(slice);
+            }
+            remaining -= len;
+        }
+        buffers
+    }
+
+    /// Encodes a message without concatenating its header and body. The body of journal entries
+    /// is the serialized entry itself, hence this doesn't copy entries.
+    pub fn encode_message(&self, msg: ProtocolMessage) -> EncodedMessage {
+        let header = generate_header(&msg);
+        let body = if let ProtocolMessage::UnparsedEntry(entry) = &msg {
+            entry.serialized_entry().clone()
+        } else {
+            let mut buf = BytesMut::with_capacity(msg.encoded_len());
+            encode_msg(&msg, &mut buf).expect(
+                "Encoding messages should be infallible, \
+                this error indicates a bug in the invoker code. \
+                Please contact the Restate developers.",
+            );
+            buf.freeze()
+        };
+        EncodedMessage { header, body }
     }
 
     /// Encodes a message to bytes
@@ -124,6 +212,8 @@ pub struct Decoder {
     state: DecoderState,
     message_size_warning: usize,
     message_size_limit: usize,
+    // Only set if the deployment accepted the chunked-messages protocol extension
+    dechunker: Option<Dechunker>,
 }
 
 impl Decoder {
@@ -142,22 +232,42 @@ impl Decoder {
             state: DecoderState::WaitingHeader,
             message_size_warning,
             message_size_limit: message_size_limit.unwrap_or(usize::MAX),
+            dechunker: None,
         }
     }
 
+    /// Reassemble chunked messages. Use it only if the deployment accepted the chunked-messages
+    /// protocol extension.
+    pub fn with_chunks(mut self) -> Self {
+        self.dechunker = Some(Dechunker::default());
+        self
+    }
+
     pub fn has_remaining(&self) -> bool {
         self.buf.has_remaining()
+            || self
+                .dechunker
+                .as_ref()
+                .is_some_and(|dechunker| dechunker.raw.has_remaining())
     }
 
     /// Concatenate a new chunk in the internal buffer.
     pub fn push(&mut self, buf: Bytes) {
-        self.buf.push(buf)
+        if let Some(dechunker) = &mut self.dechunker {
+            dechunker.raw.push(buf)
+        } else {
+            self.buf.push(buf)
+        }
     }
 
     /// Try to consume the next message in the internal buffer.
     pub fn consume_next(
         &mut self,
     ) -> Result<Option<(MessageHeader, ProtocolMessage)>, EncodingError> {
+        if let Some(dechunker) = &mut self.dechunker {
+            dechunker.dechunk(&mut self.buf)?;
+        }
+
         loop {
             let remaining = self.buf.remaining();
 
@@ -176,6 +286,55 @@ impl Decoder {
     }
 }
 
+/// Removes the framing of chunk messages, reassembling the chunked messages.
+///
+/// Messages which are not chunked are forwarded as they are, while for chunk messages only the
+/// payload, that is a slice of the chunked message, is forwarded. The bytes are forwarded as soon
+/// as they're received, without waiting for the complete chunks.
+#[derive(Default)]
+struct Dechunker {
+    raw: SegmentedBuf<Bytes>,
+    state: DechunkerState,
+}
+
+#[derive(Default)]
+enum DechunkerState {
+    #[default]
+    WaitingHeader,
+    Forwarding(usize),
+}
+
+impl Dechunker {
+    fn dechunk(&mut self, out: &mut SegmentedBuf<Bytes>) -> Result<(), EncodingError> {
+        loop {
+            match self.state {
+                DechunkerState::WaitingHeader => {
+                    if self.raw.remaining() < 8 {
+                        return Ok(());
+                    }
+                    let header_bytes = self.raw.copy_to_bytes(8);
+                    let header: MessageHeader = (&header_bytes[..]).get_u64().try_into()?;
+                    if header.message_type() != MessageType::Chunk {
+                        out.push(header_bytes);
+                    }
+                    self.state = DechunkerState::Forwarding(header.frame_length() as usize);
+                }
+                DechunkerState::Forwarding(0) => {
+                    self.state = DechunkerState::WaitingHeader;
+                }
+                DechunkerState::Forwarding(remaining) => {
+                    if !self.raw.has_remaining() {
+                        return Ok(());
+                    }
+                    let len = remaining.min(self.raw.chunk().len());
+                    out.push(self.raw.copy_to_bytes(len));
+                    self.state = DechunkerState::Forwarding(remaining - len);
+                }
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 enum DecoderState {
     #[default]
@@ -202,6 +361,9 @@ impl DecoderState {
         *self = match mem::take(self) {
             DecoderState::WaitingHeader => {
                 let header: MessageHeader = buf.get_u64().try_into()?;
+                if header.message_type() == MessageType::Chunk {
+                    return Err(EncodingError::UnexpectedChunk);
+                }
                 let message_length =
                     usize::try_from(header.frame_length()).expect("u32 must convert into usize");
 
@@ -278,6 +440,7 @@ fn message_header_to_raw_header(message_header: &MessageHeader) -> PlainEntryHea
                 | MessageType::Completion
                 | MessageType::Suspension
                 | MessageType::EntryAck
+                | MessageType::Chunk
                 | MessageType::Error
                 | MessageType::End
         ),
@@ -290,6 +453,7 @@ fn message_header_to_raw_header(message_header: &MessageHeader) -> PlainEntryHea
         MessageType::Error => unreachable!(),
         MessageType::End => unreachable!(),
         MessageType::EntryAck => unreachable!(),
+        MessageType::Chunk => unreachable!(),
 
         MessageType::InputEntry => PlainEntryHeader::Input {},
         MessageType::OutputEntry => PlainEntryHeader::Output {},
@@ -342,7 +506,7 @@ fn message_header_to_raw_header(message_header: &MessageHeader) -> PlainEntryHea
     }
 }
 
-fn raw_header_to_message_type(entry_header: &PlainEntryHeader) -> MessageType {
+pub(super) fn raw_header_to_message_type(entry_header: &PlainEntryHeader) -> MessageType {
     match entry_header {
         PlainEntryHeader::Input { .. } => MessageType::InputEntry,
         PlainEntryHeader::Output { .. } => MessageType::OutputEntry,
@@ -485,4 +649,127 @@ mod tests {
         assert_eq!(msg_size, expected_msg_size);
         assert_eq!(limit, u8::MAX as usize)
     }
+
+    #[test]
+    fn chunked_message_roundtrip() {
+        let encoder = Encoder::new(ServiceProtocolVersion::V3).with_chunk_size(16);
+        let mut decoder = Decoder::new(ServiceProtocolVersion::V3, usize::MAX, None).with_chunks();
+
+        let large_msg: ProtocolMessage = ProtobufRawEntryCodec::serialize_as_input_entry(
+            vec![],
+            (0..=u8::MAX).collect::<Vec<_>>().into(),
+        )
+        .erase_enrichment()
+        .into();
+        let small_msg: ProtocolMessage = Completion {
+            entry_index: 1,
+            result: CompletionResult::Empty,
+        }
+        .into();
+
+        let large_msg_buffers = encoder.chunk(&encoder.encode_message(large_msg.clone()));
+        assert!(large_msg_buffers.len() > 3);
+        // Small messages are written as they are
+        let small_msg_buffers = encoder.chunk(&encoder.encode_message(small_msg.clone()));
+        assert_eq!(
+            small_msg_buffers.concat(),
+            encoder.encode(small_msg.clone())
+        );
+
+        // Push the bytes in small pieces, not aligned with the chunks
+        let bytes: Vec<u8> = large_msg_buffers
+            .into_iter()
+            .chain(small_msg_buffers)
+            .flat_map(|b| b.to_vec())
+            .collect();
+        for piece in bytes.chunks(7) {
+            decoder.push(Bytes::copy_from_slice(piece));
+        }
+
+        let (actual_header, actual_msg) = decoder.consume_next().unwrap().unwrap();
+        assert_eq!(actual_header.message_type(), MessageType::InputEntry);
+        assert_eq!(actual_msg, large_msg);
+
+        let (actual_header, actual_msg) = decoder.consume_next().unwrap().unwrap();
+        assert_eq!(actual_header.message_type(), MessageType::Completion);
+        assert_eq!(actual_msg, small_msg);
+
+        assert!(decoder.consume_next().unwrap().is_none());
+        assert!(!decoder.has_remaining());
+    }
+
+    #[test]
+    fn chunks_are_slices_of_the_entry() {
+        let encoder = Encoder::new(ServiceProtocolVersion::V3).with_chunk_size(16);
+        let entry = ProtobufRawEntryCodec::serialize_as_input_entry(
+            vec![],
+            (0..=u8::MAX).collect::<Vec<_>>().into(),
+        )
+        .erase_enrichment();
+        let serialized_entry = entry.serialized_entry().clone();
+
+        let buffers = encoder.chunk(&encoder.encode_message(entry.into()));
+
+        // Apart from the chunk and message headers, the buffers point into the serialized entry
+        let entry_range = serialized_entry.as_ptr_range();
+        let body_len: usize = buffers
+            .iter()
+            .filter(|buf| entry_range.contains(&buf.as_ptr()))
+            .map(Bytes::len)
+            .sum();
+        assert_eq!(body_len, serialized_entry.len());
+    }
+
+    #[test]
+    fn no_chunks_without_the_extension() {
+        let encoder = Encoder::new(ServiceProtocolVersion::V3);
+        let msg: ProtocolMessage = ProtobufRawEntryCodec::serialize_as_input_entry(
+            vec![],
+            (0..=u8::MAX).collect::<Vec<_>>().into(),
+        )
+        .erase_enrichment()
+        .into();
+        assert_eq!(
+            encoder.chunk(&encoder.encode_message(msg.clone())).concat(),
+            encoder.encode(msg.clone())
+        );
+
+        let chunking_encoder = Encoder::new(ServiceProtocolVersion::V3).with_chunk_size(16);
+        let chunked = chunking_encoder.chunk(&chunking_encoder.encode_message(msg));
+        let mut decoder = Decoder::new(ServiceProtocolVersion::V3, usize::MAX, None);
+        for buf in chunked {
+            decoder.push(buf);
+        }
+        let_assert!(EncodingError::UnexpectedChunk = decoder.consume_next().unwrap_err());
+    }
+
+    #[test]
+    fn hit_message_size_limit_with_chunks() {
+        let encoder = Encoder::new(ServiceProtocolVersion::V3).with_chunk_size(16);
+        let mut decoder = Decoder::new(
+            ServiceProtocolVersion::V3,
+            (u8::MAX / 2) as usize,
+            Some(u8::MAX as usize),
+        )
+        .with_chunks();
+
+        let msg = encoder.encode_message(
+            ProtobufRawEntryCodec::serialize_as_input_entry(
+                vec![],
+                (0..=u8::MAX).collect::<Vec<_>>().into(),
+            )
+            .erase_enrichment()
+            .into(),
+        );
+        // Only the first chunk, containing the chunk header, the header of the chunked message
+        // and the beginning of its body
+        for buf in encoder.chunk(&msg).into_iter().take(3) {
+            decoder.push(buf);
+        }
+
+        let_assert!(
+            EncodingError::MessageSizeLimit(_, limit) = decoder.consume_next().unwrap_err()
+        );
+        assert_eq!(limit, u8::MAX as usize)
+    }
 }
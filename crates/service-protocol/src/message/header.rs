@@ -33,6 +33,7 @@ pub enum MessageType {
     Error,
     End,
     EntryAck,
+    Chunk,
     InputEntry,
     OutputEntry,
     GetStateEntry,
@@ -65,6 +66,7 @@ impl MessageType {
             MessageType::Error => MessageKind::Core,
             MessageType::End => MessageKind::Core,
             MessageType::EntryAck => MessageKind::Core,
+            MessageType::Chunk => MessageKind::Core,
             MessageType::InputEntry => MessageKind::IO,
             MessageType::OutputEntry => MessageKind::IO,
             MessageType::GetStateEntry => MessageKind::State,
@@ -120,6 +122,8 @@ const SUSPENSION_MESSAGE_TYPE: u16 = 0x0002;
 const ERROR_MESSAGE_TYPE: u16 = 0x0003;
 const ENTRY_ACK_MESSAGE_TYPE: u16 = 0x0004;
 const END_MESSAGE_TYPE: u16 = 0x0005;
+// Not part of the service protocol specification, see the chunked-messages protocol extension
+const CHUNK_MESSAGE_TYPE: u16 = 0x0006;
 const INPUT_ENTRY_MESSAGE_TYPE: u16 = 0x0400;
 const OUTPUT_ENTRY_MESSAGE_TYPE: u16 = 0x0401;
 const GET_STATE_ENTRY_MESSAGE_TYPE: u16 = 0x0800;
@@ -150,6 +154,7 @@ impl From<MessageType> for MessageTypeId {
             MessageType::Error => ERROR_MESSAGE_TYPE,
             MessageType::End => END_MESSAGE_TYPE,
             MessageType::EntryAck => ENTRY_ACK_MESSAGE_TYPE,
+            MessageType::Chunk => CHUNK_MESSAGE_TYPE,
             MessageType::InputEntry => INPUT_ENTRY_MESSAGE_TYPE,
             MessageType::OutputEntry => OUTPUT_ENTRY_MESSAGE_TYPE,
             MessageType::GetStateEntry => GET_STATE_ENTRY_MESSAGE_TYPE,
@@ -190,6 +195,7 @@ impl TryFrom<MessageTypeId> for MessageType {
             ERROR_MESSAGE_TYPE => Ok(MessageType::Error),
             END_MESSAGE_TYPE => Ok(MessageType::End),
             ENTRY_ACK_MESSAGE_TYPE => Ok(MessageType::EntryAck),
+            CHUNK_MESSAGE_TYPE => Ok(MessageType::Chunk),
             INPUT_ENTRY_MESSAGE_TYPE => Ok(MessageType::InputEntry),
             OUTPUT_ENTRY_MESSAGE_TYPE => Ok(MessageType::OutputEntry),
             GET_STATE_ENTRY_MESSAGE_TYPE => Ok(MessageType::GetStateEntry),
@@ -247,7 +253,8 @@ impl TryFrom<MessageType> for EntryType {
             | MessageType::Suspension
             | MessageType::Error
             | MessageType::End
-            | MessageType::EntryAck => Err(value),
+            | MessageType::EntryAck
+            | MessageType::Chunk => Err(value),
         }
     }
 }
@@ -486,6 +493,8 @@ mod tests {
         22
     );
 
+    roundtrip_test!(chunk, MessageHeader::new(Chunk, 1024), Chunk, Core, 1024);
+
     roundtrip_test!(
         completed_get_state,
         MessageHeader::new_completable_entry(GetStateEntry, true, 0),
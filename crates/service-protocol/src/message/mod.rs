@@ -22,7 +22,7 @@ use std::time::Duration;
 mod encoding;
 mod header;
 
-pub use encoding::{Decoder, Encoder, EncodingError};
+pub use encoding::{Decoder, EncodedMessage, Encoder, EncodingError};
 pub use header::{MessageHeader, MessageKind, MessageType};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -72,6 +72,20 @@ impl ProtocolMessage {
         Self::EntryAck(service_protocol::EntryAckMessage { entry_index })
     }
 
+    pub fn message_type(&self) -> MessageType {
+        match self {
+            ProtocolMessage::Start(_) => MessageType::Start,
+            ProtocolMessage::Completion(_) => MessageType::Completion,
+            ProtocolMessage::Suspension(_) => MessageType::Suspension,
+            ProtocolMessage::Error(_) => MessageType::Error,
+            ProtocolMessage::End(_) => MessageType::End,
+            ProtocolMessage::EntryAck(_) => MessageType::EntryAck,
+            ProtocolMessage::UnparsedEntry(entry) => {
+                encoding::raw_header_to_message_type(entry.header())
+            }
+        }
+    }
+
     pub(crate) fn encoded_len(&self) -> usize {
         match self {
             ProtocolMessage::Start(m) => m.encoded_len(),
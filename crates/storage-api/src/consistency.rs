@@ -0,0 +1,225 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt;
+
+use restate_types::identifiers::{
+    EntryIndex, IdempotencyId, InvocationId, PartitionKey, ServiceId, WithPartitionKey,
+};
+use restate_types::message::MessageIndex;
+
+use crate::journal_table_v2::CompletionId;
+
+/// Invariant between the tables of a partition which doesn't hold.
+#[derive(Debug, Clone, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ConsistencyViolation {
+    /// The journal of an invoked or suspended invocation doesn't have the length declared by its
+    /// invocation status.
+    JournalLengthMismatch {
+        invocation_id: InvocationId,
+        expected_length: EntryIndex,
+        actual_length: EntryIndex,
+    },
+    /// An entry of the pending completions index of the journal v2 table points at a journal
+    /// entry which doesn't await the completion.
+    DanglingPendingCompletion {
+        invocation_id: InvocationId,
+        completion_id: CompletionId,
+        journal_index: EntryIndex,
+    },
+    /// An inbox entry points at an invocation which is not inboxed. The partition processor
+    /// fails when popping such an entry from the inbox.
+    OrphanedInboxEntry {
+        service_id: ServiceId,
+        inbox_sequence_number: MessageIndex,
+        invocation_id: InvocationId,
+    },
+    /// An idempotency entry points at an invocation which doesn't exist.
+    DanglingIdempotencyKey {
+        idempotency_id: IdempotencyId,
+        invocation_id: InvocationId,
+    },
+    /// A virtual object or workflow is locked by an invocation which doesn't run on it.
+    DanglingServiceLock {
+        service_id: ServiceId,
+        invocation_id: InvocationId,
+    },
+    /// An invoked or suspended invocation of an exclusive handler doesn't hold the lock of its
+    /// virtual object.
+    MissingServiceLock {
+        service_id: ServiceId,
+        invocation_id: InvocationId,
+        locked_by: Option<InvocationId>,
+    },
+}
+
+impl ConsistencyViolation {
+    pub fn kind(&self) -> &'static str {
+        self.into()
+    }
+
+    /// The invocation the violating record refers to.
+    pub fn invocation_id(&self) -> InvocationId {
+        match self {
+            ConsistencyViolation::JournalLengthMismatch { invocation_id, .. }
+            | ConsistencyViolation::DanglingPendingCompletion { invocation_id, .. }
+            | ConsistencyViolation::OrphanedInboxEntry { invocation_id, .. }
+            | ConsistencyViolation::DanglingIdempotencyKey { invocation_id, .. }
+            | ConsistencyViolation::DanglingServiceLock { invocation_id, .. }
+            | ConsistencyViolation::MissingServiceLock { invocation_id, .. } => *invocation_id,
+        }
+    }
+
+    /// Returns the repair of the violation, if it is known to be benign. Deleting an orphaned
+    /// inbox entry or a dangling idempotency key doesn't lose anything, since the invocation
+    /// they point at is gone already.
+    pub fn repair(&self) -> Option<ConsistencyRepair> {
+        match self {
+            ConsistencyViolation::OrphanedInboxEntry {
+                service_id,
+                inbox_sequence_number,
+                invocation_id,
+            } => Some(ConsistencyRepair::DeleteInboxEntry {
+                service_id: service_id.clone(),
+                inbox_sequence_number: *inbox_sequence_number,
+                invocation_id: *invocation_id,
+            }),
+            ConsistencyViolation::DanglingIdempotencyKey {
+                idempotency_id,
+                invocation_id,
+            } => Some(ConsistencyRepair::DeleteIdempotencyKey {
+                idempotency_id: idempotency_id.clone(),
+                invocation_id: *invocation_id,
+            }),
+            ConsistencyViolation::JournalLengthMismatch { .. }
+            | ConsistencyViolation::DanglingPendingCompletion { .. }
+            | ConsistencyViolation::DanglingServiceLock { .. }
+            | ConsistencyViolation::MissingServiceLock { .. } => None,
+        }
+    }
+}
+
+impl WithPartitionKey for ConsistencyViolation {
+    fn partition_key(&self) -> PartitionKey {
+        match self {
+            ConsistencyViolation::OrphanedInboxEntry { service_id, .. }
+            | ConsistencyViolation::DanglingServiceLock { service_id, .. }
+            | ConsistencyViolation::MissingServiceLock { service_id, .. } => {
+                service_id.partition_key()
+            }
+            ConsistencyViolation::DanglingIdempotencyKey { idempotency_id, .. } => {
+                idempotency_id.partition_key()
+            }
+            ConsistencyViolation::JournalLengthMismatch { invocation_id, .. }
+            | ConsistencyViolation::DanglingPendingCompletion { invocation_id, .. } => {
+                invocation_id.partition_key()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsistencyViolation::JournalLengthMismatch {
+                invocation_id,
+                expected_length,
+                actual_length,
+            } => write!(
+                f,
+                "the journal of invocation {invocation_id} has {actual_length} entries, but its \
+                status declares {expected_length}"
+            ),
+            ConsistencyViolation::DanglingPendingCompletion {
+                invocation_id,
+                completion_id,
+                journal_index,
+            } => write!(
+                f,
+                "completion {completion_id} of invocation {invocation_id} is indexed as awaited \
+                by journal entry {journal_index}, which doesn't await it"
+            ),
+            ConsistencyViolation::OrphanedInboxEntry {
+                service_id,
+                inbox_sequence_number,
+                invocation_id,
+            } => write!(
+                f,
+                "inbox entry {inbox_sequence_number} of {service_id} points at invocation \
+                {invocation_id}, which is not inboxed"
+            ),
+            ConsistencyViolation::DanglingIdempotencyKey {
+                idempotency_id,
+                invocation_id,
+            } => write!(
+                f,
+                "idempotency key '{}' of {}/{} points at invocation {invocation_id}, which \
+                doesn't exist",
+                idempotency_id.idempotency_key,
+                idempotency_id.service_name,
+                idempotency_id.service_handler
+            ),
+            ConsistencyViolation::DanglingServiceLock {
+                service_id,
+                invocation_id,
+            } => write!(
+                f,
+                "{service_id} is locked by invocation {invocation_id}, which doesn't run on it"
+            ),
+            ConsistencyViolation::MissingServiceLock {
+                service_id,
+                invocation_id,
+                locked_by: Some(locked_by),
+            } => write!(
+                f,
+                "invocation {invocation_id} runs on {service_id}, which is locked by invocation \
+                {locked_by}"
+            ),
+            ConsistencyViolation::MissingServiceLock {
+                service_id,
+                invocation_id,
+                locked_by: None,
+            } => write!(
+                f,
+                "invocation {invocation_id} runs on {service_id}, which is not locked"
+            ),
+        }
+    }
+}
+
+/// Repair of a benign [`ConsistencyViolation`]. The partition processor applies it only if the
+/// violation still holds, since the tables might have changed since the violation was found.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ConsistencyRepair {
+    /// Delete the inbox entry, if the invocation it points at is not inboxed.
+    DeleteInboxEntry {
+        service_id: ServiceId,
+        inbox_sequence_number: MessageIndex,
+        invocation_id: InvocationId,
+    },
+    /// Delete the idempotency key, if it still points at the invocation and the invocation
+    /// doesn't exist.
+    DeleteIdempotencyKey {
+        idempotency_id: IdempotencyId,
+        invocation_id: InvocationId,
+    },
+}
+
+impl WithPartitionKey for ConsistencyRepair {
+    fn partition_key(&self) -> PartitionKey {
+        match self {
+            ConsistencyRepair::DeleteInboxEntry { service_id, .. } => service_id.partition_key(),
+            ConsistencyRepair::DeleteIdempotencyKey { idempotency_id, .. } => {
+                idempotency_id.partition_key()
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use bytes::Bytes;
+use futures_util::Stream;
+
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::identifiers::{PartitionId, PartitionKey};
+use restate_types::logs::Lsn;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::Result;
+
+/// A log record which the partition processor repeatedly failed to apply and therefore skipped.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeadLetter {
+    pub partition_id: PartitionId,
+    /// Lsn of the skipped record in the partition's log.
+    pub lsn: Lsn,
+    /// Partition key the skipped record was addressed to.
+    pub partition_key: PartitionKey,
+    /// Name of the command contained in the skipped record.
+    pub command: String,
+    /// Error of the last failed attempt to apply the record.
+    pub error: String,
+    /// Number of failed attempts to apply the record.
+    pub attempts: u32,
+    pub dead_lettered_at: MillisSinceEpoch,
+    /// The encoded envelope of the skipped record.
+    pub envelope: Bytes,
+}
+
+flexbuffers_storage_encode_decode!(DeadLetter);
+
+/// Decision to move a record, which the partition processor repeatedly failed to apply, to the
+/// dead-letter table. The decision is written to the partition's log so that every replica
+/// dead-letters the same record in the same way, instead of each replica deciding on its own.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeadLetterDecision {
+    /// Lsn of the record to dead-letter.
+    pub lsn: Lsn,
+    /// Error of the last failed attempt to apply the record.
+    pub error: String,
+    /// Number of failed attempts to apply the record.
+    pub attempts: u32,
+    pub dead_lettered_at: MillisSinceEpoch,
+}
+
+pub trait ReadOnlyDeadLetterTable {
+    fn get_dead_letter(
+        &mut self,
+        lsn: Lsn,
+    ) -> impl Future<Output = Result<Option<DeadLetter>>> + Send;
+
+    fn all_dead_letters(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<DeadLetter>> + Send;
+}
+
+pub trait DeadLetterTable: ReadOnlyDeadLetterTable {
+    fn put_dead_letter(&mut self, dead_letter: &DeadLetter) -> impl Future<Output = ()> + Send;
+
+    fn delete_dead_letter(&mut self, lsn: Lsn) -> impl Future<Output = ()> + Send;
+}
@@ -37,6 +37,15 @@ impl DedupInformation {
         }
     }
 
+    /// Dedup information of a message prepared by the partition `producer_id`, see
+    /// [`crate::prepared_message_table`].
+    pub fn prepared_message(producer_id: PartitionId, sequence_number: MessageIndex) -> Self {
+        DedupInformation {
+            producer_id: ProducerId::prepared_messages(producer_id),
+            sequence_number: DedupSequenceNumber::Sn(sequence_number),
+        }
+    }
+
     pub fn ingress(producer_id: impl Into<ByteString>, sequence_number: MessageIndex) -> Self {
         DedupInformation {
             producer_id: ProducerId::Other(producer_id.into()),
@@ -57,6 +66,12 @@ impl ProducerId {
     pub fn self_producer() -> Self {
         ProducerId::Other(SELF_PRODUCER.clone())
     }
+
+    /// Producer of the prepared messages of the given partition. Their sequence numbers are
+    /// independent of the outbox of the partition, hence they need a producer of their own.
+    pub fn prepared_messages(partition_id: PartitionId) -> Self {
+        ProducerId::Other(format!("prepared-{partition_id}").into())
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
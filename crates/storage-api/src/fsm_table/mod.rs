@@ -9,10 +9,14 @@
 // by the Apache License, Version 2.0.
 
 use crate::{protobuf_storage_encode_decode, Result};
+use bytestring::ByteString;
 use futures_util::FutureExt;
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::identifiers::PartitionId;
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use restate_types::storage::{StorageDecode, StorageEncode};
+use std::collections::BTreeSet;
 use std::future::Future;
 
 #[derive(Debug, Clone, Copy, derive_more::From, derive_more::Into)]
@@ -20,11 +24,50 @@ pub struct SequenceNumber(u64);
 
 protobuf_storage_encode_decode!(SequenceNumber);
 
-mod fsm_variable {
-    pub(crate) const INBOX_SEQ_NUMBER: u64 = 0;
-    pub(crate) const OUTBOX_SEQ_NUMBER: u64 = 1;
+/// Failed attempts to apply the record at `lsn`. It is kept across restarts of the partition
+/// processor so that records which fail deterministically can be moved to the dead-letter table.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ApplyFailure {
+    pub lsn: Lsn,
+    pub attempts: u32,
+    pub error: String,
+}
+
+flexbuffers_storage_encode_decode!(ApplyFailure);
+
+/// Services paused on this partition. The invocations waiting for a paused service to be resumed
+/// are stored in the inbox table, see [`crate::inbox_table::InboxTable::put_paused_invocation`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PausedServices {
+    pub services: BTreeSet<ByteString>,
+}
+
+flexbuffers_storage_encode_decode!(PausedServices);
+
+/// The latest split of this partition, which moved a part of its key range to a new partition.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartitionSplit {
+    pub child_partition_id: PartitionId,
+    /// Lsn of the record which split the partition.
+    pub lsn: Lsn,
+}
+
+flexbuffers_storage_encode_decode!(PartitionSplit);
+
+/// Ids of the variables of the partition state machine, the keys of the fsm table.
+pub mod fsm_variable {
+    pub const INBOX_SEQ_NUMBER: u64 = 0;
+    pub const OUTBOX_SEQ_NUMBER: u64 = 1;
+
+    pub const APPLIED_LSN: u64 = 2;
+
+    pub const APPLY_FAILURE: u64 = 3;
+
+    pub const PAUSED_SERVICES: u64 = 4;
 
-    pub(crate) const APPLIED_LSN: u64 = 2;
+    pub const PREPARED_MESSAGE_SEQ_NUMBER: u64 = 6;
+
+    pub const PARTITION_SPLIT: u64 = 7;
 }
 
 pub trait ReadOnlyFsmTable {
@@ -49,6 +92,30 @@ pub trait ReadOnlyFsmTable {
                     .map(|seq_number| seq_number.map(|seq_number| Lsn::from(u64::from(seq_number))))
             })
     }
+
+    fn get_apply_failure(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<ApplyFailure>>> + Send + '_ {
+        self.get::<ApplyFailure>(fsm_variable::APPLY_FAILURE)
+    }
+
+    fn get_prepared_message_seq_number(
+        &mut self,
+    ) -> impl Future<Output = Result<MessageIndex>> + Send + '_ {
+        self.get::<SequenceNumber>(fsm_variable::PREPARED_MESSAGE_SEQ_NUMBER)
+            .map(|result| result.map(|seq_number| seq_number.map(Into::into).unwrap_or_default()))
+    }
+
+    fn get_paused_services(&mut self) -> impl Future<Output = Result<PausedServices>> + Send + '_ {
+        self.get::<PausedServices>(fsm_variable::PAUSED_SERVICES)
+            .map(|result| result.map(Option::unwrap_or_default))
+    }
+
+    fn get_partition_split(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<PartitionSplit>>> + Send + '_ {
+        self.get::<PartitionSplit>(fsm_variable::PARTITION_SPLIT)
+    }
 }
 
 pub trait FsmTable: ReadOnlyFsmTable {
@@ -67,6 +134,41 @@ pub trait FsmTable: ReadOnlyFsmTable {
         )
     }
 
+    fn put_apply_failure(
+        &mut self,
+        apply_failure: &ApplyFailure,
+    ) -> impl Future<Output = ()> + Send {
+        self.put(fsm_variable::APPLY_FAILURE, apply_failure.clone())
+    }
+
+    fn clear_apply_failure(&mut self) -> impl Future<Output = ()> + Send {
+        self.clear(fsm_variable::APPLY_FAILURE)
+    }
+
+    fn put_paused_services(
+        &mut self,
+        paused_services: &PausedServices,
+    ) -> impl Future<Output = ()> + Send {
+        self.put(fsm_variable::PAUSED_SERVICES, paused_services.clone())
+    }
+
+    fn put_partition_split(
+        &mut self,
+        partition_split: &PartitionSplit,
+    ) -> impl Future<Output = ()> + Send {
+        self.put(fsm_variable::PARTITION_SPLIT, partition_split.clone())
+    }
+
+    fn put_prepared_message_seq_number(
+        &mut self,
+        seq_number: MessageIndex,
+    ) -> impl Future<Output = ()> + Send {
+        self.put(
+            fsm_variable::PREPARED_MESSAGE_SEQ_NUMBER,
+            SequenceNumber::from(seq_number),
+        )
+    }
+
     fn put_inbox_seq_number(
         &mut self,
         seq_number: MessageIndex,
@@ -12,6 +12,7 @@ use crate::promise_table::ReadOnlyPromiseTable;
 use crate::{protobuf_storage_encode_decode, Result};
 use futures_util::Stream;
 use restate_types::identifiers::{InvocationId, PartitionKey, ServiceId, WithPartitionKey};
+use restate_types::invocation::InvocationPriority;
 use restate_types::message::MessageIndex;
 use restate_types::state_mut::ExternalStateMutation;
 use std::future::Future;
@@ -19,14 +20,14 @@ use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InboxEntry {
-    Invocation(ServiceId, InvocationId),
+    Invocation(ServiceId, InvocationId, InvocationPriority),
     StateMutation(ExternalStateMutation),
 }
 
 impl InboxEntry {
     pub fn service_id(&self) -> &ServiceId {
         match self {
-            InboxEntry::Invocation(service_id, _) => service_id,
+            InboxEntry::Invocation(service_id, _, _) => service_id,
             InboxEntry::StateMutation(state_mutation) => &state_mutation.service_id,
         }
     }
@@ -58,10 +59,11 @@ impl SequenceNumberInboxEntry {
         inbox_sequence_number: MessageIndex,
         service_id: ServiceId,
         invocation_id: InvocationId,
+        priority: InvocationPriority,
     ) -> Self {
         Self {
             inbox_sequence_number,
-            inbox_entry: InboxEntry::Invocation(service_id, invocation_id),
+            inbox_entry: InboxEntry::Invocation(service_id, invocation_id, priority),
         }
     }
 
@@ -81,6 +83,7 @@ impl SequenceNumberInboxEntry {
 }
 
 pub trait ReadOnlyInboxTable {
+    /// Returns the next entry of the inbox to process, see [`InboxTable::pop_inbox`].
     fn peek_inbox(
         &mut self,
         service_id: &ServiceId,
@@ -91,10 +94,18 @@ pub trait ReadOnlyInboxTable {
         service_id: &ServiceId,
     ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send;
 
+    /// Returns the inbox entries of all services, including the invocations of paused services.
     fn all_inboxes(
         &self,
         range: RangeInclusive<PartitionKey>,
     ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send;
+
+    /// Returns the invocations of the given service enqueued while the service is paused, in the
+    /// order they were enqueued.
+    fn paused_invocations(
+        &mut self,
+        service_name: &str,
+    ) -> impl Stream<Item = Result<SequenceNumberInboxEntry>> + Send;
 }
 
 pub trait InboxTable: ReadOnlyPromiseTable {
@@ -108,8 +119,24 @@ pub trait InboxTable: ReadOnlyPromiseTable {
         &mut self,
         service_id: &ServiceId,
         sequence_number: u64,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Enqueues an invocation of a paused service. The invocations of a paused service are kept
+    /// apart from the inboxes, and they are never popped.
+    fn put_paused_invocation(
+        &mut self,
+        sequence_number: MessageIndex,
+        inbox_entry: &InboxEntry,
+    ) -> impl Future<Output = ()> + Send;
+
+    fn delete_paused_invocation(
+        &mut self,
+        service_id: &ServiceId,
+        sequence_number: MessageIndex,
     ) -> impl Future<Output = ()> + Send;
 
+    /// Removes and returns the next entry of the inbox to process. Invocations are started by
+    /// [`InvocationPriority`], but never before a state mutation which was inboxed earlier.
     fn pop_inbox(
         &mut self,
         service_id: &ServiceId,
@@ -0,0 +1,95 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use futures_util::Stream;
+
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::identifiers::{DeploymentId, EntryIndex, InvocationId, PartitionKey};
+use restate_types::time::MillisSinceEpoch;
+
+use crate::Result;
+
+/// Number of events retained for each invocation. Once reached, the oldest events of the
+/// invocation are dropped in favour of the new ones.
+pub const MAX_EVENTS_PER_INVOCATION: u64 = 64;
+
+/// Significant event in the lifecycle of an invocation, recorded in the timeline of the
+/// invocation.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationEvent {
+    pub invocation_id: InvocationId,
+    /// Sequence number of the event within the invocation, which orders the events.
+    pub seq_number: u64,
+    /// Creation time of the log record whose application produced the event, hence it is the
+    /// same on all replicas of the partition.
+    pub timestamp: MillisSinceEpoch,
+    pub kind: InvocationEventKind,
+}
+
+flexbuffers_storage_encode_decode!(InvocationEvent);
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum InvocationEventKind {
+    Created,
+    Scheduled {
+        execution_time: MillisSinceEpoch,
+    },
+    Inboxed,
+    /// The partition processor asked the invoker to start the given attempt.
+    Started {
+        attempt: u32,
+        deployment_id: Option<DeploymentId>,
+    },
+    DeploymentPinned {
+        deployment_id: DeploymentId,
+    },
+    /// The given attempt failed, and the invoker scheduled the next one.
+    RetryScheduled {
+        attempt: u32,
+    },
+    Suspended {
+        waiting_for_entries: Vec<EntryIndex>,
+    },
+    Resumed,
+    Completed {
+        failure: Option<String>,
+    },
+}
+
+pub trait ReadOnlyInvocationEventTable {
+    /// Events of the invocations in the given range, ordered by invocation and then by sequence
+    /// number.
+    fn all_invocation_events(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<InvocationEvent>> + Send;
+}
+
+pub trait InvocationEventTable: ReadOnlyInvocationEventTable {
+    /// Appends the events to the timeline of the invocation, dropping its oldest events beyond
+    /// [`MAX_EVENTS_PER_INVOCATION`].
+    fn append_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+        timestamp: MillisSinceEpoch,
+        kinds: Vec<InvocationEventKind>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn delete_invocation_events(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use bytestring::ByteString;
+use futures_util::Stream;
+
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::identifiers::{
+    IdempotencyId, InvocationId, PartitionKey, ServiceId, WithPartitionKey,
+};
+use restate_types::invocation::{
+    InvocationQuery, InvocationTarget, InvocationTargetType, WorkflowHandlerType,
+};
+
+use crate::Result;
+
+/// Secondary key under which an invocation can be looked up without knowing its invocation id.
+///
+/// Index entries are stored in the partition owning the partition key of the lookup key, hence
+/// every partition processor can resolve the lookup keys routed to it with a single point lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InvocationLookupKey {
+    /// An invocation submitted with an idempotency key.
+    Idempotency(IdempotencyId),
+    /// The workflow run of the given workflow key.
+    Workflow(ServiceId),
+}
+
+impl InvocationLookupKey {
+    /// Returns the lookup key of the given query, if the query is not by invocation id.
+    pub fn from_query(query: &InvocationQuery) -> Option<Self> {
+        match query {
+            InvocationQuery::Invocation(_) => None,
+            InvocationQuery::Workflow(service_id) => {
+                Some(InvocationLookupKey::Workflow(service_id.clone()))
+            }
+            InvocationQuery::IdempotencyId(idempotency_id) => {
+                Some(InvocationLookupKey::Idempotency(idempotency_id.clone()))
+            }
+        }
+    }
+
+    /// Returns the lookup keys under which an invocation to the given target should be indexed.
+    pub fn for_invocation(
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        idempotency_key: Option<&ByteString>,
+    ) -> Vec<Self> {
+        // The idempotency key is ignored for workflow runs
+        if invocation_target.invocation_target_ty()
+            == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+        {
+            return invocation_target
+                .as_keyed_service_id()
+                .map(InvocationLookupKey::Workflow)
+                .into_iter()
+                .collect();
+        }
+
+        idempotency_key
+            .map(|idempotency_key| {
+                InvocationLookupKey::Idempotency(IdempotencyId::combine(
+                    invocation_id,
+                    invocation_target,
+                    idempotency_key.clone(),
+                ))
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+impl WithPartitionKey for InvocationLookupKey {
+    fn partition_key(&self) -> PartitionKey {
+        match self {
+            InvocationLookupKey::Idempotency(idempotency_id) => idempotency_id.partition_key(),
+            InvocationLookupKey::Workflow(service_id) => service_id.partition_key(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationIndexEntry {
+    pub invocation_id: InvocationId,
+}
+
+flexbuffers_storage_encode_decode!(InvocationIndexEntry);
+
+pub trait ReadOnlyInvocationIndexTable {
+    fn get_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+    ) -> impl Future<Output = Result<Option<InvocationIndexEntry>>> + Send;
+
+    fn all_invocation_index_entries(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(InvocationLookupKey, InvocationIndexEntry)>> + Send;
+}
+
+pub trait InvocationIndexTable: ReadOnlyInvocationIndexTable {
+    fn put_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+        entry: &InvocationIndexEntry,
+    ) -> impl Future<Output = ()> + Send;
+
+    fn delete_invocation_index_entry(
+        &mut self,
+        lookup_key: &InvocationLookupKey,
+    ) -> impl Future<Output = ()> + Send;
+}
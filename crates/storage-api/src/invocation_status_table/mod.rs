@@ -8,21 +8,22 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::{protobuf_storage_encode_decode, Result};
+use crate::{protobuf_storage_decode, protobuf_storage_encode_decode, Result};
 use bytes::Bytes;
 use bytestring::ByteString;
 use futures_util::Stream;
 use restate_types::deployment::PinnedDeployment;
 use restate_types::identifiers::{EntryIndex, InvocationId, PartitionKey};
 use restate_types::invocation::{
-    Header, InvocationInput, InvocationTarget, ResponseResult, ServiceInvocation,
-    ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source,
+    Header, InvocationEpoch, InvocationInput, InvocationPriority, InvocationTarget, ResponseResult,
+    ServiceInvocation, ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source,
 };
 use restate_types::time::MillisSinceEpoch;
 use std::collections::HashSet;
 use std::future::Future;
+use std::num::NonZeroU32;
 use std::ops::RangeInclusive;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 /// Holds timestamps of the [`InvocationStatus`].
 #[derive(Debug, Clone, PartialEq)]
@@ -196,6 +197,18 @@ pub enum InvocationStatus {
 }
 
 impl InvocationStatus {
+    #[inline]
+    pub fn kind(&self) -> InvocationStatusKind {
+        match self {
+            InvocationStatus::Scheduled(_) => InvocationStatusKind::Scheduled,
+            InvocationStatus::Inboxed(_) => InvocationStatusKind::Inboxed,
+            InvocationStatus::Invoked(_) => InvocationStatusKind::Invoked,
+            InvocationStatus::Suspended { .. } => InvocationStatusKind::Suspended,
+            InvocationStatus::Completed(_) => InvocationStatusKind::Completed,
+            InvocationStatus::Free => InvocationStatusKind::Free,
+        }
+    }
+
     #[inline]
     pub fn invocation_target(&self) -> Option<&InvocationTarget> {
         match self {
@@ -232,6 +245,19 @@ impl InvocationStatus {
         }
     }
 
+    #[inline]
+    pub fn span_context(&self) -> Option<&ServiceInvocationSpanContext> {
+        match self {
+            InvocationStatus::Scheduled(metadata) => Some(&metadata.metadata.span_context),
+            InvocationStatus::Inboxed(metadata) => Some(&metadata.metadata.span_context),
+            InvocationStatus::Invoked(metadata) => Some(&metadata.journal_metadata.span_context),
+            InvocationStatus::Suspended { metadata, .. } => {
+                Some(&metadata.journal_metadata.span_context)
+            }
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn into_journal_metadata(self) -> Option<JournalMetadata> {
         match self {
@@ -337,17 +363,103 @@ impl InvocationStatus {
 
 protobuf_storage_encode_decode!(InvocationStatus, crate::storage::v1::InvocationStatusV2);
 
+/// Kind of an [`InvocationStatus`], without its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvocationStatusKind {
+    Scheduled,
+    Inboxed,
+    Invoked,
+    Suspended,
+    Completed,
+    Free,
+}
+
+/// View of an [`InvocationStatus`] on the fields needed by cheap queries, e.g. whether the
+/// invocation completed or the length of its journal. Reading the view doesn't decode the other
+/// fields of the stored status, which can be large since they include the argument, the headers
+/// and the result of the invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationStatusView {
+    pub kind: InvocationStatusKind,
+    pub invocation_target: Option<InvocationTarget>,
+    pub idempotency_key: Option<ByteString>,
+    /// Length of the journal of invoked and suspended invocations.
+    pub journal_length: Option<EntryIndex>,
+    /// Version of the journal of invoked and suspended invocations.
+    pub journal_version: Option<JournalVersion>,
+}
+
+impl InvocationStatusView {
+    pub const FREE: InvocationStatusView = InvocationStatusView {
+        kind: InvocationStatusKind::Free,
+        invocation_target: None,
+        idempotency_key: None,
+        journal_length: None,
+        journal_version: None,
+    };
+
+    #[inline]
+    pub fn is_free(&self) -> bool {
+        self.kind == InvocationStatusKind::Free
+    }
+
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.kind == InvocationStatusKind::Completed
+    }
+}
+
+impl From<&InvocationStatus> for InvocationStatusView {
+    fn from(status: &InvocationStatus) -> Self {
+        InvocationStatusView {
+            kind: status.kind(),
+            invocation_target: status.invocation_target().cloned(),
+            idempotency_key: status.idempotency_key().cloned(),
+            journal_length: status
+                .get_journal_metadata()
+                .map(|metadata| metadata.length),
+            journal_version: status
+                .get_journal_metadata()
+                .map(|metadata| metadata.version),
+        }
+    }
+}
+
+protobuf_storage_decode!(
+    InvocationStatusView,
+    crate::storage::v1::InvocationStatusV2View
+);
+
 /// Wrapper used by the table implementation only for the migration, don't use it!
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct InvocationStatusV1(pub InvocationStatus);
 
 protobuf_storage_encode_decode!(InvocationStatusV1, crate::storage::v1::InvocationStatus);
 
+/// Wrapper used by the table implementation to store a [`CompletedInvocation`] in the invocation
+/// status archive. The archive only retains the target, the result and the timestamps of the
+/// invocation, its source and span context are lost when archiving.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedInvocationStatus(pub CompletedInvocation);
+
+protobuf_storage_encode_decode!(ArchivedInvocationStatus);
+
+/// Table in which the journal of an invocation is stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalVersion {
+    /// The [journal table](crate::journal_table).
+    #[default]
+    V1,
+    /// The [journal v2 table](crate::journal_table_v2).
+    V2,
+}
+
 /// Metadata associated with a journal
 #[derive(Debug, Clone, PartialEq)]
 pub struct JournalMetadata {
     pub length: EntryIndex,
     pub span_context: ServiceInvocationSpanContext,
+    pub version: JournalVersion,
 }
 
 impl JournalMetadata {
@@ -355,6 +467,7 @@ impl JournalMetadata {
         Self {
             span_context,
             length,
+            version: JournalVersion::default(),
         }
     }
 
@@ -382,6 +495,13 @@ pub struct PreFlightInvocationMetadata {
     /// If zero, the invocation completion will not be retained.
     pub completion_retention_duration: Duration,
     pub idempotency_key: Option<ByteString>,
+    /// Deployment the invocation must run on, if requested by the caller.
+    pub pinned_deployment: Option<PinnedDeployment>,
+    pub dry_run: bool,
+    /// Priority of the invocation in the inbox of a virtual object.
+    pub priority: InvocationPriority,
+    /// Per key concurrency limit of shared virtual object handlers, if any.
+    pub shared_concurrency_limit: Option<NonZeroU32>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -412,6 +532,10 @@ impl PreFlightInvocationMetadata {
                 .completion_retention_duration
                 .unwrap_or_default(),
             idempotency_key: service_invocation.idempotency_key,
+            pinned_deployment: service_invocation.pinned_deployment,
+            dry_run: service_invocation.dry_run,
+            priority: service_invocation.priority,
+            shared_concurrency_limit: service_invocation.shared_concurrency_limit,
         }
     }
 }
@@ -459,6 +583,20 @@ pub struct InFlightInvocationMetadata {
     /// If zero, the invocation completion will not be retained.
     pub completion_retention_duration: Duration,
     pub idempotency_key: Option<ByteString>,
+    /// If true, the deployment is asked to suppress external side effects of the invocation.
+    pub dry_run: bool,
+    /// Number of times the invoker retried the invocation after a failed attempt.
+    pub retry_count: u32,
+    /// Incremented whenever the current attempt of the invocation is discarded, e.g. when it's
+    /// retried now or restarted. Effects of the invoker carry the epoch of the attempt producing
+    /// them, so that the effects of discarded attempts are dropped.
+    pub invocation_epoch: InvocationEpoch,
+    /// Once reached, the invoker aborts the invocation and fails it with a timeout error. It's
+    /// set when the invocation starts running, hence suspending, retrying or resuming the
+    /// invocation on another leader doesn't extend it.
+    ///
+    /// Like the [`StatusTimestamps`], this value is not agreed among partition processor replicas.
+    pub execution_deadline: Option<MillisSinceEpoch>,
 }
 
 impl InFlightInvocationMetadata {
@@ -475,13 +613,17 @@ impl InFlightInvocationMetadata {
                 journal_metadata: JournalMetadata::initialize(
                     pre_flight_invocation_metadata.span_context,
                 ),
-                pinned_deployment: None,
+                pinned_deployment: pre_flight_invocation_metadata.pinned_deployment,
                 response_sinks: pre_flight_invocation_metadata.response_sinks,
                 timestamps: pre_flight_invocation_metadata.timestamps,
                 source: pre_flight_invocation_metadata.source,
                 completion_retention_duration: pre_flight_invocation_metadata
                     .completion_retention_duration,
                 idempotency_key: pre_flight_invocation_metadata.idempotency_key,
+                dry_run: pre_flight_invocation_metadata.dry_run,
+                retry_count: 0,
+                invocation_epoch: 0,
+                execution_deadline: None,
             },
             InvocationInput {
                 argument: pre_flight_invocation_metadata.argument,
@@ -504,6 +646,25 @@ impl InFlightInvocationMetadata {
         self.pinned_deployment = Some(pinned_deployment);
         self.timestamps.update();
     }
+
+    pub fn record_retries(&mut self, retries: u32) {
+        self.retry_count = self.retry_count.saturating_add(retries);
+        self.timestamps.update();
+    }
+
+    /// Starts the execution timeout of the invocation, if any, see [`Self::execution_deadline`].
+    pub fn start_execution_timeout(&mut self, execution_timeout: Option<Duration>) {
+        // a deadline too far in the future to be represented is no deadline
+        self.execution_deadline = execution_timeout
+            .and_then(|timeout| SystemTime::now().checked_add(timeout))
+            .map(MillisSinceEpoch::from);
+    }
+
+    /// Discards the current attempt of the invocation, fencing its pending effects.
+    pub fn bump_invocation_epoch(&mut self) {
+        self.invocation_epoch = self.invocation_epoch.wrapping_add(1);
+        self.timestamps.update();
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -515,6 +676,10 @@ pub struct CompletedInvocation {
     pub timestamps: StatusTimestamps,
     pub response_result: ResponseResult,
     pub completion_retention_duration: Duration,
+    /// The deployment which executed the invocation, if any.
+    pub pinned_deployment: Option<PinnedDeployment>,
+    /// Number of times the invoker retried the invocation after a failed attempt.
+    pub retry_count: u32,
 }
 
 impl CompletedInvocation {
@@ -535,6 +700,8 @@ impl CompletedInvocation {
             response_result,
             completion_retention_duration: in_flight_invocation_metadata
                 .completion_retention_duration,
+            pinned_deployment: in_flight_invocation_metadata.pinned_deployment,
+            retry_count: in_flight_invocation_metadata.retry_count,
         }
     }
 
@@ -550,15 +717,29 @@ impl CompletedInvocation {
     }
 }
 
+/// An invocation which is invoked, see [`ReadOnlyInvocationStatusTable::all_invoked_invocations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvokedInvocation {
+    pub invocation_id: InvocationId,
+    pub invocation_target: InvocationTarget,
+    pub invocation_epoch: InvocationEpoch,
+    pub execution_deadline: Option<MillisSinceEpoch>,
+}
+
 pub trait ReadOnlyInvocationStatusTable {
     fn get_invocation_status(
         &mut self,
         invocation_id: &InvocationId,
     ) -> impl Future<Output = Result<InvocationStatus>> + Send;
 
-    fn all_invoked_invocations(
+    /// Returns the [`InvocationStatusView`] of the invocation, which is cheaper to read than the
+    /// full status.
+    fn get_invocation_status_view(
         &mut self,
-    ) -> impl Stream<Item = Result<(InvocationId, InvocationTarget)>> + Send;
+        invocation_id: &InvocationId,
+    ) -> impl Future<Output = Result<InvocationStatusView>> + Send;
+
+    fn all_invoked_invocations(&mut self) -> impl Stream<Item = Result<InvokedInvocation>> + Send;
 
     fn all_invocation_statuses(
         &self,
@@ -602,6 +783,10 @@ mod test_util {
                 source: Source::Ingress(PartitionProcessorRpcRequestId::default()),
                 completion_retention_duration: Duration::ZERO,
                 idempotency_key: None,
+                dry_run: false,
+                retry_count: 0,
+                invocation_epoch: 0,
+                execution_deadline: None,
             }
         }
     }
@@ -625,6 +810,8 @@ mod test_util {
                 timestamps,
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
                 completion_retention_duration: Duration::from_secs(60 * 60),
+                pinned_deployment: None,
+                retry_count: 0,
             }
         }
 
@@ -642,6 +829,8 @@ mod test_util {
                 timestamps: StatusTimestamps::now(),
                 response_result: ResponseResult::Success(Bytes::from_static(b"123")),
                 completion_retention_duration: Duration::from_secs(60 * 60),
+                pinned_deployment: None,
+                retry_count: 0,
             }
         }
     }
@@ -0,0 +1,152 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Journal table of the invocations using the notification based journal of the newer service
+//! protocol versions. Entries are keyed by invocation id and journal index, and carry the id of
+//! the completion they await, which is indexed so that notifications can be matched with the
+//! entry they complete without scanning the journal.
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use futures_util::Stream;
+
+use restate_types::identifiers::{EntryIndex, InvocationId, JournalEntryId, PartitionKey};
+use restate_types::time::MillisSinceEpoch;
+
+use crate::journal_table::{JournalEntry, JournalEntryType};
+use crate::{protobuf_storage_encode_decode, Result};
+
+/// Id with which a completable journal entry is completed by a notification.
+pub type CompletionId = u32;
+
+/// Journal entry along with its metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredEntry {
+    pub entry: JournalEntry,
+    /// Id of the completion of the entry, if the entry can be completed.
+    pub completion_id: Option<CompletionId>,
+    /// Time at which the entry was appended to the journal. Unknown for the entries migrated
+    /// from the journal table.
+    pub append_time: Option<MillisSinceEpoch>,
+}
+
+impl StoredEntry {
+    pub fn new(
+        entry: JournalEntry,
+        completion_id: Option<CompletionId>,
+        append_time: MillisSinceEpoch,
+    ) -> Self {
+        Self {
+            entry,
+            completion_id,
+            append_time: Some(append_time),
+        }
+    }
+
+    /// Replaces the entry, e.g. to complete it, keeping the time it was appended at.
+    pub fn with_entry(self, entry: JournalEntry, completion_id: Option<CompletionId>) -> Self {
+        Self {
+            entry,
+            completion_id,
+            append_time: self.append_time,
+        }
+    }
+
+    pub fn entry_type(&self) -> JournalEntryType {
+        self.entry.entry_type()
+    }
+
+    /// Returns true if the entry awaits its completion.
+    pub fn is_pending(&self) -> bool {
+        self.completion_id.is_some()
+            && match &self.entry {
+                JournalEntry::Entry(entry) => entry.header().is_completed() == Some(false),
+                JournalEntry::Completion(_) => false,
+            }
+    }
+}
+
+protobuf_storage_encode_decode!(StoredEntry, crate::storage::v1::StoredJournalEntry);
+
+/// Journal index of the entry awaiting a completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingCompletion {
+    pub journal_index: EntryIndex,
+}
+
+protobuf_storage_encode_decode!(
+    PendingCompletion,
+    crate::storage::v1::JournalPendingCompletion
+);
+
+pub trait ReadOnlyJournalTableV2 {
+    fn get_journal_entry(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_index: EntryIndex,
+    ) -> impl Future<Output = Result<Option<StoredEntry>>> + Send;
+
+    fn get_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> impl Stream<Item = Result<(EntryIndex, StoredEntry)>> + Send;
+
+    /// Returns the journal index of the entry awaiting the given completion, if any.
+    fn get_pending_completion(
+        &mut self,
+        invocation_id: &InvocationId,
+        completion_id: CompletionId,
+    ) -> impl Future<Output = Result<Option<EntryIndex>>> + Send;
+
+    /// Returns the completions awaited by the journal of the given invocation, along with the
+    /// journal index of the entries awaiting them, ordered by completion id.
+    fn get_pending_completions(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> impl Stream<Item = Result<(CompletionId, EntryIndex)>> + Send;
+
+    fn all_journals(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(JournalEntryId, StoredEntry)>> + Send;
+}
+
+pub trait JournalTableV2: ReadOnlyJournalTableV2 {
+    /// Stores the entry, indexing its completion while the entry is pending.
+    fn put_journal_entry(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_index: EntryIndex,
+        journal_entry: &StoredEntry,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// Deletes the journal of the given invocation along with its pending completions.
+    fn delete_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Moves the journal of the given invocation from the journal table to this table. The
+    /// completable entries of the journal table are completed by journal index, hence their
+    /// completion id is their journal index.
+    ///
+    /// Journals are migrated one invocation at a time. The caller must set the journal version
+    /// of the invocation status to
+    /// [`JournalVersion::V2`](crate::invocation_status_table::JournalVersion::V2) in the same
+    /// transaction.
+    fn migrate_journal(
+        &mut self,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
@@ -25,14 +25,21 @@ pub enum StorageError {
 
 pub type Result<T> = std::result::Result<T, StorageError>;
 
+pub mod consistency;
+pub mod dead_letter_table;
 pub mod deduplication_table;
 pub mod fsm_table;
 pub mod idempotency_table;
 pub mod inbox_table;
+pub mod invocation_event_table;
+pub mod invocation_index_table;
 pub mod invocation_status_table;
 pub mod journal_table;
+pub mod journal_table_v2;
 pub mod outbox_table;
+pub mod prepared_message_table;
 pub mod promise_table;
+pub mod schedule_table;
 pub mod service_status_table;
 pub mod state_table;
 mod storage;
@@ -54,10 +61,16 @@ pub trait Transaction:
     + outbox_table::OutboxTable
     + deduplication_table::DeduplicationTable
     + journal_table::JournalTable
+    + journal_table_v2::JournalTableV2
     + fsm_table::FsmTable
     + timer_table::TimerTable
     + idempotency_table::IdempotencyTable
     + promise_table::PromiseTable
+    + dead_letter_table::DeadLetterTable
+    + schedule_table::ScheduleTable
+    + invocation_index_table::InvocationIndexTable
+    + invocation_event_table::InvocationEventTable
+    + prepared_message_table::PreparedMessageTable
     + Send
 {
     fn commit(self) -> impl Future<Output = Result<()>> + Send;
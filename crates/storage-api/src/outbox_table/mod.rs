@@ -8,9 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 use crate::{protobuf_storage_encode_decode, Result};
-use restate_types::identifiers::{PartitionKey, WithPartitionKey};
+use bytes::Bytes;
+use restate_types::identifiers::{InvocationId, PartitionKey, WithPartitionKey};
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationResponse, InvocationTermination, ServiceInvocation,
+    AttachInvocationRequest, Header, InvocationResponse, InvocationTermination, ServiceInvocation,
 };
 use std::future::Future;
 use std::ops::RangeInclusive;
@@ -29,6 +30,22 @@ pub enum OutboxMessage {
 
     /// Attach invocation
     AttachInvocation(AttachInvocationRequest),
+
+    /// Event to publish to a Kafka topic
+    KafkaEvent(KafkaEgressEvent),
+}
+
+/// Event sent by an invocation to a Kafka egress target, see
+/// [`KAFKA_EGRESS_SERVICE_PREFIX`](restate_types::invocation::KAFKA_EGRESS_SERVICE_PREFIX).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KafkaEgressEvent {
+    pub cluster: String,
+    pub topic: String,
+    pub key: Option<Bytes>,
+    pub payload: Bytes,
+    pub headers: Vec<Header>,
+    /// Invocation which sent the event
+    pub source_invocation_id: InvocationId,
 }
 
 protobuf_storage_encode_decode!(OutboxMessage);
@@ -40,6 +57,8 @@ impl WithPartitionKey for OutboxMessage {
             OutboxMessage::ServiceResponse(sr) => sr.id.partition_key(),
             OutboxMessage::InvocationTermination(it) => it.invocation_id.partition_key(),
             OutboxMessage::AttachInvocation(ai) => ai.invocation_query.partition_key(),
+            // Kafka events are published by the partition of the sending invocation
+            OutboxMessage::KafkaEvent(ke) => ke.source_invocation_id.partition_key(),
         }
     }
 }
@@ -0,0 +1,65 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Messages which this partition hands off to the partition owning their partition key, e.g. an
+//! invocation whose target hashes to another partition after re-keying. A message is prepared
+//! in the same transaction as the command producing it, and is deleted once the destination
+//! partition acknowledged having applied it. Until then, the leader re-delivers the message,
+//! and the destination deduplicates it by its sequence number.
+
+use std::future::Future;
+
+use futures_util::Stream;
+
+use restate_types::identifiers::{PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::message::MessageIndex;
+
+use crate::outbox_table::OutboxMessage;
+use crate::Result;
+
+/// Prepared message as delivered to the partition owning its partition key.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PreparedMessageDelivery {
+    /// Partition which prepared the message, and to which its application is acknowledged.
+    pub source: PartitionId,
+    pub sequence_number: MessageIndex,
+    pub message: OutboxMessage,
+}
+
+impl WithPartitionKey for PreparedMessageDelivery {
+    fn partition_key(&self) -> PartitionKey {
+        self.message.partition_key()
+    }
+}
+
+pub trait ReadOnlyPreparedMessageTable {
+    fn get_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+    ) -> impl Future<Output = Result<Option<OutboxMessage>>> + Send;
+
+    /// Returns the messages which are not acknowledged yet, ordered by sequence number.
+    fn get_prepared_messages(
+        &self,
+    ) -> impl Stream<Item = Result<(MessageIndex, OutboxMessage)>> + Send;
+}
+
+pub trait PreparedMessageTable: ReadOnlyPreparedMessageTable {
+    fn put_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+        message: &OutboxMessage,
+    ) -> impl Future<Output = ()> + Send;
+
+    fn delete_prepared_message(
+        &mut self,
+        sequence_number: MessageIndex,
+    ) -> impl Future<Output = ()> + Send;
+}
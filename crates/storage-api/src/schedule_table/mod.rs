@@ -0,0 +1,53 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use futures_util::Stream;
+
+use restate_types::flexbuffers_storage_encode_decode;
+use restate_types::identifiers::{InvocationId, PartitionKey, ScheduleId};
+use restate_types::schedule::Schedule;
+use restate_types::time::MillisSinceEpoch;
+
+use crate::Result;
+
+/// A [`Schedule`] together with the state of its occurrences.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ScheduleStatus {
+    pub schedule: Schedule,
+    /// Next occurrence of the schedule. A timer is registered to fire it.
+    pub next_fire_time: MillisSinceEpoch,
+    /// Time of the last occurrence, if the schedule fired already.
+    pub last_fire_time: Option<MillisSinceEpoch>,
+    /// Invocation fired by the last occurrence, if the schedule fired already.
+    pub last_invocation_id: Option<InvocationId>,
+}
+
+flexbuffers_storage_encode_decode!(ScheduleStatus);
+
+pub trait ReadOnlyScheduleTable {
+    fn get_schedule(
+        &mut self,
+        schedule_id: &ScheduleId,
+    ) -> impl Future<Output = Result<Option<ScheduleStatus>>> + Send;
+
+    fn all_schedules(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<ScheduleStatus>> + Send;
+}
+
+pub trait ScheduleTable: ReadOnlyScheduleTable {
+    fn put_schedule(&mut self, schedule: &ScheduleStatus) -> impl Future<Output = ()> + Send;
+
+    fn delete_schedule(&mut self, schedule_id: &ScheduleId) -> impl Future<Output = ()> + Send;
+}
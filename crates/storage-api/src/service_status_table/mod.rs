@@ -10,7 +10,9 @@
 
 use crate::{protobuf_storage_encode_decode, Result};
 use futures_util::Stream;
+use restate_types::flexbuffers_storage_encode_decode;
 use restate_types::identifiers::{InvocationId, PartitionKey, ServiceId};
+use std::collections::VecDeque;
 use std::future::Future;
 use std::ops::RangeInclusive;
 
@@ -23,6 +25,25 @@ pub enum VirtualObjectStatus {
 
 protobuf_storage_encode_decode!(VirtualObjectStatus);
 
+/// Executions of the shared handlers of a virtual object key. Only invocations submitted with a
+/// shared concurrency limit are tracked, see
+/// [`restate_types::invocation::ServiceInvocation::shared_concurrency_limit`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SharedHandlerExecutions {
+    /// Running invocations of shared handlers.
+    pub running: Vec<InvocationId>,
+    /// Invocations waiting for a running invocation to complete, in arrival order.
+    pub pending: VecDeque<InvocationId>,
+}
+
+impl SharedHandlerExecutions {
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty() && self.pending.is_empty()
+    }
+}
+
+flexbuffers_storage_encode_decode!(SharedHandlerExecutions);
+
 pub trait ReadOnlyVirtualObjectStatusTable {
     fn get_virtual_object_status(
         &mut self,
@@ -33,6 +54,11 @@ pub trait ReadOnlyVirtualObjectStatusTable {
         &self,
         range: RangeInclusive<PartitionKey>,
     ) -> impl Stream<Item = Result<(ServiceId, VirtualObjectStatus)>> + Send;
+
+    fn get_shared_handler_executions(
+        &mut self,
+        service_id: &ServiceId,
+    ) -> impl Future<Output = Result<SharedHandlerExecutions>> + Send;
 }
 
 pub trait VirtualObjectStatusTable: ReadOnlyVirtualObjectStatusTable {
@@ -46,4 +72,11 @@ pub trait VirtualObjectStatusTable: ReadOnlyVirtualObjectStatusTable {
         &mut self,
         service_id: &ServiceId,
     ) -> impl Future<Output = ()> + Send;
+
+    /// Empty executions are deleted.
+    fn put_shared_handler_executions(
+        &mut self,
+        service_id: &ServiceId,
+        executions: &SharedHandlerExecutions,
+    ) -> impl Future<Output = ()> + Send;
 }
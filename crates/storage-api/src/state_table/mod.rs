@@ -11,8 +11,9 @@
 use crate::Result;
 use bytes::Bytes;
 use futures_util::Stream;
-use restate_types::identifiers::ServiceId;
+use restate_types::identifiers::{PartitionKey, ServiceId};
 use std::future::Future;
+use std::ops::RangeInclusive;
 
 pub trait ReadOnlyStateTable {
     fn get_user_state(
@@ -26,7 +27,10 @@ pub trait ReadOnlyStateTable {
         service_id: &ServiceId,
     ) -> impl Stream<Item = Result<(Bytes, Bytes)>> + Send;
 
-    fn get_all_user_states(&self) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send;
+    fn get_all_user_states(
+        &self,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = Result<(ServiceId, Bytes, Bytes)>> + Send;
 }
 
 pub trait StateTable: ReadOnlyStateTable {
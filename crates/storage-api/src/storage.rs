@@ -33,6 +33,16 @@ macro_rules! protobuf_storage_encode_decode {
             }
         }
 
+        $crate::protobuf_storage_decode!($ty, $protobuf_ty);
+    };
+}
+
+/// Implement [`restate_types::storage::StorageDecode`] using the protobuf codec for the given
+/// type, which needs to implement TryFrom the protobuf type. Used on its own for read-only views
+/// of stored types.
+#[macro_export]
+macro_rules! protobuf_storage_decode {
+    ($ty:ident, $protobuf_ty:path) => {
         impl restate_types::storage::StorageDecode for $ty {
             fn decode<B: bytes::Buf>(
                 buf: &mut B,
@@ -72,6 +82,7 @@ pub mod v1 {
 
     pub mod pb_conversion {
         use std::collections::HashSet;
+        use std::num::NonZeroU32;
         use std::str::FromStr;
 
         use anyhow::anyhow;
@@ -91,7 +102,8 @@ pub mod v1 {
         use crate::storage::v1::journal_entry::completion_result::{Empty, Failure, Success};
         use crate::storage::v1::journal_entry::{completion_result, CompletionResult, Entry, Kind};
         use crate::storage::v1::outbox_message::{
-            OutboxCancel, OutboxKill, OutboxServiceInvocation, OutboxServiceInvocationResponse,
+            OutboxCancel, OutboxKafkaEvent, OutboxKill, OutboxServiceInvocation,
+            OutboxServiceInvocationResponse,
         };
         use crate::storage::v1::service_invocation_response_sink::{
             Ingress, PartitionProcessor, ResponseSink,
@@ -100,13 +112,15 @@ pub mod v1 {
             enriched_entry_header, entry_result, inbox_entry, invocation_resolution_result,
             invocation_status, invocation_status_v2, invocation_target, outbox_message, promise,
             response_result, source, span_relation, submit_notification_sink, timer,
-            virtual_object_status, BackgroundCallResolutionResult, DedupSequenceNumber, Duration,
-            EnrichedEntryHeader, EntryResult, EpochSequenceNumber, Header, IdempotencyId,
-            IdempotencyMetadata, InboxEntry, InvocationId, InvocationResolutionResult,
-            InvocationStatus, InvocationStatusV2, InvocationTarget, JournalEntry, JournalEntryId,
-            JournalMeta, KvPair, OutboxMessage, Promise, ResponseResult, SequenceNumber, ServiceId,
-            ServiceInvocation, ServiceInvocationResponseSink, Source, SpanContext, SpanRelation,
-            StateMutation, SubmitNotificationSink, Timer, VirtualObjectStatus,
+            virtual_object_status, ArchivedInvocationStatus, BackgroundCallResolutionResult,
+            DedupSequenceNumber, Duration, EnrichedEntryHeader, EntryResult, EpochSequenceNumber,
+            Header, IdempotencyId, IdempotencyMetadata, InboxEntry, InvocationId,
+            InvocationResolutionResult, InvocationStatus, InvocationStatusV2,
+            InvocationStatusV2View, InvocationTarget, JournalEntry, JournalEntryId, JournalMeta,
+            JournalPendingCompletion, KvPair, OutboxMessage, Promise, ResponseResult,
+            SequenceNumber, ServiceId, ServiceInvocation, ServiceInvocationResponseSink, Source,
+            SpanContext, SpanRelation, StateMutation, StoredJournalEntry, SubmitNotificationSink,
+            Timer, VirtualObjectStatus,
         };
         use crate::StorageError;
         use restate_types::errors::{IdDecodeError, InvocationError};
@@ -119,7 +133,7 @@ pub mod v1 {
         use restate_types::storage::{
             StorageCodecKind, StorageDecode, StorageDecodeError, StorageEncode, StorageEncodeError,
         };
-        use restate_types::time::MillisSinceEpoch;
+        use restate_types::time::{MillisSinceEpoch, ZonedDateTime};
         use restate_types::GenerationalNodeId;
 
         /// Error type for conversion related problems (e.g. Rust <-> Protobuf)
@@ -150,6 +164,28 @@ pub mod v1 {
             }
         }
 
+        /// Encodes the response sinks in a deterministic order, so that every replica writes the
+        /// same bytes for the same invocation status.
+        fn response_sinks_to_repr(
+            response_sinks: HashSet<restate_types::invocation::ServiceInvocationResponseSink>,
+        ) -> Vec<ServiceInvocationResponseSink> {
+            let mut response_sinks: Vec<_> = response_sinks
+                .into_iter()
+                .map(|s| ServiceInvocationResponseSink::from(Some(s)))
+                .collect();
+            response_sinks.sort_by_cached_key(prost::Message::encode_to_vec);
+            response_sinks
+        }
+
+        /// Encodes the entry indexes in ascending order, see [`response_sinks_to_repr`].
+        fn entry_indexes_to_repr(
+            entry_indexes: HashSet<restate_types::identifiers::EntryIndex>,
+        ) -> Vec<u32> {
+            let mut entry_indexes: Vec<_> = entry_indexes.into_iter().collect();
+            entry_indexes.sort_unstable();
+            entry_indexes
+        }
+
         impl From<IdDecodeError> for ConversionError {
             fn from(value: IdDecodeError) -> Self {
                 ConversionError::invalid_data(value)
@@ -345,10 +381,18 @@ pub mod v1 {
                     execution_time,
                     completion_retention_duration,
                     idempotency_key,
+                    dry_run,
                     inbox_sequence_number,
+                    priority,
+                    priority_deadline,
+                    shared_concurrency_limit,
                     journal_length,
                     deployment_id,
                     service_protocol_version,
+                    retry_count,
+                    invocation_epoch,
+                    execution_deadline,
+                    journal_version,
                     waiting_for_completed_entries,
                     result,
                 } = value;
@@ -377,6 +421,10 @@ pub mod v1 {
                     .into_iter()
                     .map(|h| restate_types::invocation::Header::try_from(h))
                     .collect::<Result<Vec<_>, ConversionError>>()?;
+                let priority = restate_types::invocation::InvocationPriority::new(
+                    priority,
+                    priority_deadline.map(MillisSinceEpoch::new),
+                );
 
                 match status.try_into().unwrap_or_default() {
                     invocation_status_v2::Status::Scheduled => {
@@ -397,6 +445,14 @@ pub mod v1 {
                                                 .unwrap_or_default()
                                                 .try_into()?,
                                         idempotency_key: idempotency_key.map(ByteString::from),
+                                        dry_run,
+                                        pinned_deployment: derive_pinned_deployment(
+                                            deployment_id,
+                                            service_protocol_version,
+                                        )?,
+                                        priority,
+                                        shared_concurrency_limit: shared_concurrency_limit
+                                            .and_then(NonZeroU32::new),
                                     },
                             },
                         ))
@@ -420,6 +476,14 @@ pub mod v1 {
                                                 .unwrap_or_default()
                                                 .try_into()?,
                                         idempotency_key: idempotency_key.map(ByteString::from),
+                                        dry_run,
+                                        pinned_deployment: derive_pinned_deployment(
+                                            deployment_id,
+                                            service_protocol_version,
+                                        )?,
+                                        priority,
+                                        shared_concurrency_limit: shared_concurrency_limit
+                                            .and_then(NonZeroU32::new),
                                     },
                             },
                         ))
@@ -433,6 +497,7 @@ pub mod v1 {
                                 journal_metadata: crate::invocation_status_table::JournalMetadata {
                                     length: journal_length,
                                     span_context: expect_or_fail!(span_context)?.try_into()?,
+                                    version: journal_version_from_repr(journal_version)?,
                                 },
                                 pinned_deployment: derive_pinned_deployment(
                                     deployment_id,
@@ -443,6 +508,10 @@ pub mod v1 {
                                     .unwrap_or_default()
                                     .try_into()?,
                                 idempotency_key: idempotency_key.map(ByteString::from),
+                                dry_run,
+                                retry_count,
+                                invocation_epoch,
+                                execution_deadline: execution_deadline.map(MillisSinceEpoch::new),
                             },
                         ))
                     }
@@ -455,6 +524,7 @@ pub mod v1 {
                                 journal_metadata: crate::invocation_status_table::JournalMetadata {
                                     length: journal_length,
                                     span_context: expect_or_fail!(span_context)?.try_into()?,
+                                    version: journal_version_from_repr(journal_version)?,
                                 },
                                 pinned_deployment: derive_pinned_deployment(
                                     deployment_id,
@@ -465,6 +535,10 @@ pub mod v1 {
                                     .unwrap_or_default()
                                     .try_into()?,
                                 idempotency_key: idempotency_key.map(ByteString::from),
+                                dry_run,
+                                retry_count,
+                                invocation_epoch,
+                                execution_deadline: execution_deadline.map(MillisSinceEpoch::new),
                             },
                             waiting_for_completed_entries: waiting_for_completed_entries
                                 .into_iter()
@@ -483,6 +557,11 @@ pub mod v1 {
                                 completion_retention_duration: completion_retention_duration
                                     .unwrap_or_default()
                                     .try_into()?,
+                                pinned_deployment: derive_pinned_deployment(
+                                    deployment_id,
+                                    service_protocol_version,
+                                )?,
+                                retry_count,
                             },
                         ))
                     }
@@ -494,6 +573,57 @@ pub mod v1 {
             }
         }
 
+        impl TryFrom<InvocationStatusV2View> for crate::invocation_status_table::InvocationStatusView {
+            type Error = ConversionError;
+
+            fn try_from(value: InvocationStatusV2View) -> Result<Self, Self::Error> {
+                let InvocationStatusV2View {
+                    status,
+                    invocation_target,
+                    idempotency_key,
+                    journal_length,
+                    journal_version,
+                } = value;
+
+                let kind = match status.try_into().unwrap_or_default() {
+                    invocation_status_v2::Status::Scheduled => {
+                        crate::invocation_status_table::InvocationStatusKind::Scheduled
+                    }
+                    invocation_status_v2::Status::Inboxed => {
+                        crate::invocation_status_table::InvocationStatusKind::Inboxed
+                    }
+                    invocation_status_v2::Status::Invoked => {
+                        crate::invocation_status_table::InvocationStatusKind::Invoked
+                    }
+                    invocation_status_v2::Status::Suspended => {
+                        crate::invocation_status_table::InvocationStatusKind::Suspended
+                    }
+                    invocation_status_v2::Status::Completed => {
+                        crate::invocation_status_table::InvocationStatusKind::Completed
+                    }
+                    _ => return Err(ConversionError::unexpected_enum_variant("status", status)),
+                };
+                let has_journal = matches!(
+                    kind,
+                    crate::invocation_status_table::InvocationStatusKind::Invoked
+                        | crate::invocation_status_table::InvocationStatusKind::Suspended
+                );
+                let journal_version = if has_journal {
+                    Some(journal_version_from_repr(journal_version)?)
+                } else {
+                    None
+                };
+
+                Ok(crate::invocation_status_table::InvocationStatusView {
+                    kind,
+                    invocation_target: Some(expect_or_fail!(invocation_target)?.try_into()?),
+                    idempotency_key: idempotency_key.map(ByteString::from),
+                    journal_length: has_journal.then_some(journal_length),
+                    journal_version,
+                })
+            }
+        }
+
         impl From<crate::invocation_status_table::InvocationStatus> for InvocationStatusV2 {
             fn from(value: crate::invocation_status_table::InvocationStatus) -> Self {
                 match value {
@@ -511,6 +641,10 @@ pub mod v1 {
                                     execution_time,
                                     completion_retention_duration,
                                     idempotency_key,
+                                    dry_run,
+                                    pinned_deployment,
+                                    priority,
+                                    shared_concurrency_limit,
                                 },
                         },
                     ) => InvocationStatusV2 {
@@ -532,19 +666,27 @@ pub mod v1 {
                             timestamps.completed_transition_time()
                         }
                         .map(|t| t.as_u64()),
-                        response_sinks: response_sinks
-                            .into_iter()
-                            .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                            .collect(),
+                        response_sinks: response_sinks_to_repr(response_sinks),
                         argument: Some(argument),
                         headers: headers.into_iter().map(Into::into).collect(),
                         execution_time: execution_time.map(|t| t.as_u64()),
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
+                        dry_run,
                         inbox_sequence_number: None,
+                        priority: priority.priority,
+                        priority_deadline: priority.deadline.map(|t| t.as_u64()),
+                        shared_concurrency_limit: shared_concurrency_limit.map(NonZeroU32::get),
                         journal_length: 0,
-                        deployment_id: None,
-                        service_protocol_version: None,
+                        deployment_id: pinned_deployment
+                            .as_ref()
+                            .map(|p| p.deployment_id.to_string()),
+                        service_protocol_version: pinned_deployment
+                            .map(|p| p.service_protocol_version.as_repr()),
+                        retry_count: 0,
+                        invocation_epoch: 0,
+                        execution_deadline: None,
+                        journal_version: 0,
                         waiting_for_completed_entries: vec![],
                         result: None,
                     },
@@ -562,6 +704,10 @@ pub mod v1 {
                                     execution_time,
                                     completion_retention_duration,
                                     idempotency_key,
+                                    dry_run,
+                                    pinned_deployment,
+                                    priority,
+                                    shared_concurrency_limit,
                                 },
                             inbox_sequence_number,
                         },
@@ -584,19 +730,27 @@ pub mod v1 {
                             timestamps.completed_transition_time()
                         }
                         .map(|t| t.as_u64()),
-                        response_sinks: response_sinks
-                            .into_iter()
-                            .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                            .collect(),
+                        response_sinks: response_sinks_to_repr(response_sinks),
                         argument: Some(argument),
                         headers: headers.into_iter().map(Into::into).collect(),
                         execution_time: execution_time.map(|t| t.as_u64()),
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
+                        dry_run,
                         inbox_sequence_number: Some(inbox_sequence_number),
+                        priority: priority.priority,
+                        priority_deadline: priority.deadline.map(|t| t.as_u64()),
+                        shared_concurrency_limit: shared_concurrency_limit.map(NonZeroU32::get),
                         journal_length: 0,
-                        deployment_id: None,
-                        service_protocol_version: None,
+                        deployment_id: pinned_deployment
+                            .as_ref()
+                            .map(|p| p.deployment_id.to_string()),
+                        service_protocol_version: pinned_deployment
+                            .map(|p| p.service_protocol_version.as_repr()),
+                        retry_count: 0,
+                        invocation_epoch: 0,
+                        execution_deadline: None,
+                        journal_version: 0,
                         waiting_for_completed_entries: vec![],
                         result: None,
                     },
@@ -610,6 +764,10 @@ pub mod v1 {
                             source,
                             completion_retention_duration,
                             idempotency_key,
+                            dry_run,
+                            retry_count,
+                            invocation_epoch,
+                            execution_deadline,
                         },
                     ) => {
                         let (deployment_id, service_protocol_version) = match pinned_deployment {
@@ -643,10 +801,7 @@ pub mod v1 {
                                 timestamps.completed_transition_time()
                             }
                             .map(|t| t.as_u64()),
-                            response_sinks: response_sinks
-                                .into_iter()
-                                .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                                .collect(),
+                            response_sinks: response_sinks_to_repr(response_sinks),
                             argument: None,
                             headers: vec![],
                             execution_time: None,
@@ -654,10 +809,19 @@ pub mod v1 {
                                 completion_retention_duration.into(),
                             ),
                             idempotency_key: idempotency_key.map(|key| key.to_string()),
+                            dry_run,
                             inbox_sequence_number: None,
+                            priority: 0,
+                            priority_deadline: None,
+                            shared_concurrency_limit: None,
                             journal_length: journal_metadata.length,
                             deployment_id,
                             service_protocol_version,
+                            retry_count,
+                            invocation_epoch,
+                            execution_deadline: execution_deadline
+                                .map(|deadline| deadline.as_u64()),
+                            journal_version: journal_version_to_repr(journal_metadata.version),
                             waiting_for_completed_entries: vec![],
                             result: None,
                         }
@@ -673,6 +837,10 @@ pub mod v1 {
                                 source,
                                 completion_retention_duration,
                                 idempotency_key,
+                                dry_run,
+                                retry_count,
+                                invocation_epoch,
+                                execution_deadline,
                             },
                         waiting_for_completed_entries,
                     } => {
@@ -707,10 +875,7 @@ pub mod v1 {
                                 timestamps.completed_transition_time()
                             }
                             .map(|t| t.as_u64()),
-                            response_sinks: response_sinks
-                                .into_iter()
-                                .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                                .collect(),
+                            response_sinks: response_sinks_to_repr(response_sinks),
                             argument: None,
                             headers: vec![],
                             execution_time: None,
@@ -718,13 +883,22 @@ pub mod v1 {
                                 completion_retention_duration.into(),
                             ),
                             idempotency_key: idempotency_key.map(|key| key.to_string()),
+                            dry_run,
                             inbox_sequence_number: None,
+                            priority: 0,
+                            priority_deadline: None,
+                            shared_concurrency_limit: None,
                             journal_length: journal_metadata.length,
                             deployment_id,
                             service_protocol_version,
-                            waiting_for_completed_entries: waiting_for_completed_entries
-                                .into_iter()
-                                .collect(),
+                            retry_count,
+                            invocation_epoch,
+                            execution_deadline: execution_deadline
+                                .map(|deadline| deadline.as_u64()),
+                            journal_version: journal_version_to_repr(journal_metadata.version),
+                            waiting_for_completed_entries: entry_indexes_to_repr(
+                                waiting_for_completed_entries,
+                            ),
                             result: None,
                         }
                     }
@@ -737,6 +911,8 @@ pub mod v1 {
                             timestamps,
                             response_result,
                             completion_retention_duration,
+                            pinned_deployment,
+                            retry_count,
                         },
                     ) => InvocationStatusV2 {
                         status: invocation_status_v2::Status::Completed.into(),
@@ -763,10 +939,21 @@ pub mod v1 {
                         execution_time: None,
                         completion_retention_duration: Some(completion_retention_duration.into()),
                         idempotency_key: idempotency_key.map(|key| key.to_string()),
+                        dry_run: false,
                         inbox_sequence_number: None,
+                        priority: 0,
+                        priority_deadline: None,
+                        shared_concurrency_limit: None,
                         journal_length: 0,
-                        deployment_id: None,
-                        service_protocol_version: None,
+                        deployment_id: pinned_deployment
+                            .as_ref()
+                            .map(|p| p.deployment_id.to_string()),
+                        service_protocol_version: pinned_deployment
+                            .map(|p| p.service_protocol_version.as_repr()),
+                        retry_count,
+                        invocation_epoch: 0,
+                        execution_deadline: None,
+                        journal_version: 0,
                         waiting_for_completed_entries: vec![],
                         result: Some(response_result.into()),
                     },
@@ -777,6 +964,97 @@ pub mod v1 {
             }
         }
 
+        impl TryFrom<ArchivedInvocationStatus>
+            for crate::invocation_status_table::ArchivedInvocationStatus
+        {
+            type Error = ConversionError;
+
+            fn try_from(value: ArchivedInvocationStatus) -> Result<Self, Self::Error> {
+                let ArchivedInvocationStatus {
+                    invocation_target,
+                    idempotency_key,
+                    completion_retention_duration,
+                    creation_time,
+                    modification_time,
+                    inboxed_transition_time,
+                    scheduled_transition_time,
+                    running_transition_time,
+                    completed_transition_time,
+                    result,
+                    deployment_id,
+                    service_protocol_version,
+                    retry_count,
+                } = value;
+
+                Ok(crate::invocation_status_table::ArchivedInvocationStatus(
+                    crate::invocation_status_table::CompletedInvocation {
+                        invocation_target: expect_or_fail!(invocation_target)?.try_into()?,
+                        // not retained by the archive
+                        span_context:
+                            restate_types::invocation::ServiceInvocationSpanContext::empty(),
+                        source: restate_types::invocation::Source::Internal,
+                        idempotency_key: idempotency_key.map(ByteString::from),
+                        timestamps: crate::invocation_status_table::StatusTimestamps::new(
+                            MillisSinceEpoch::new(creation_time),
+                            MillisSinceEpoch::new(modification_time),
+                            inboxed_transition_time.map(MillisSinceEpoch::new),
+                            scheduled_transition_time.map(MillisSinceEpoch::new),
+                            running_transition_time.map(MillisSinceEpoch::new),
+                            completed_transition_time.map(MillisSinceEpoch::new),
+                        ),
+                        response_result: expect_or_fail!(result)?.try_into()?,
+                        completion_retention_duration: completion_retention_duration
+                            .unwrap_or_default()
+                            .try_into()?,
+                        pinned_deployment: derive_pinned_deployment(
+                            deployment_id,
+                            service_protocol_version,
+                        )?,
+                        retry_count,
+                    },
+                ))
+            }
+        }
+
+        impl From<crate::invocation_status_table::ArchivedInvocationStatus> for ArchivedInvocationStatus {
+            fn from(value: crate::invocation_status_table::ArchivedInvocationStatus) -> Self {
+                let crate::invocation_status_table::CompletedInvocation {
+                    invocation_target,
+                    span_context: _,
+                    source: _,
+                    idempotency_key,
+                    timestamps,
+                    response_result,
+                    completion_retention_duration,
+                    pinned_deployment,
+                    retry_count,
+                } = value.0;
+
+                ArchivedInvocationStatus {
+                    invocation_target: Some(invocation_target.into()),
+                    idempotency_key: idempotency_key.map(|key| key.to_string()),
+                    completion_retention_duration: Some(completion_retention_duration.into()),
+                    creation_time: unsafe { timestamps.creation_time() }.as_u64(),
+                    modification_time: unsafe { timestamps.modification_time() }.as_u64(),
+                    inboxed_transition_time: unsafe { timestamps.inboxed_transition_time() }
+                        .map(|t| t.as_u64()),
+                    scheduled_transition_time: unsafe { timestamps.scheduled_transition_time() }
+                        .map(|t| t.as_u64()),
+                    running_transition_time: unsafe { timestamps.running_transition_time() }
+                        .map(|t| t.as_u64()),
+                    completed_transition_time: unsafe { timestamps.completed_transition_time() }
+                        .map(|t| t.as_u64()),
+                    result: Some(response_result.into()),
+                    deployment_id: pinned_deployment
+                        .as_ref()
+                        .map(|p| p.deployment_id.to_string()),
+                    service_protocol_version: pinned_deployment
+                        .map(|p| p.service_protocol_version.as_repr()),
+                    retry_count,
+                }
+            }
+        }
+
         impl TryFrom<InvocationStatus> for crate::invocation_status_table::InvocationStatusV1 {
             type Error = ConversionError;
 
@@ -951,6 +1229,10 @@ pub mod v1 {
                     source,
                     completion_retention_duration: completion_retention_time,
                     idempotency_key,
+                    dry_run: false,
+                    retry_count: 0,
+                    invocation_epoch: 0,
+                    execution_deadline: None,
                 })
             }
         }
@@ -966,6 +1248,11 @@ pub mod v1 {
                     source,
                     completion_retention_duration: completion_retention_time,
                     idempotency_key,
+                    // not supported by the legacy invocation status format
+                    dry_run: _,
+                    retry_count: _,
+                    invocation_epoch: _,
+                    execution_deadline: _,
                 } = value;
 
                 let (deployment_id, service_protocol_version) = match pinned_deployment {
@@ -978,10 +1265,7 @@ pub mod v1 {
 
                 Invoked {
                     invocation_target: Some(invocation_target.into()),
-                    response_sinks: response_sinks
-                        .into_iter()
-                        .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                        .collect(),
+                    response_sinks: response_sinks_to_repr(response_sinks),
                     deployment_id,
                     service_protocol_version,
                     journal_meta: Some(JournalMeta::from(journal_metadata)),
@@ -1061,6 +1345,10 @@ pub mod v1 {
                         source: caller,
                         completion_retention_duration: completion_retention_time,
                         idempotency_key,
+                        dry_run: false,
+                        retry_count: 0,
+                        invocation_epoch: 0,
+                        execution_deadline: None,
                     },
                     waiting_for_completed_entries,
                 ))
@@ -1081,7 +1369,7 @@ pub mod v1 {
             ) -> Self {
                 let journal_meta = JournalMeta::from(metadata.journal_metadata);
                 let waiting_for_completed_entries =
-                    waiting_for_completed_entries.into_iter().collect();
+                    entry_indexes_to_repr(waiting_for_completed_entries);
 
                 let (deployment_id, service_protocol_version) = match metadata.pinned_deployment {
                     None => (None, None),
@@ -1183,8 +1471,12 @@ pub mod v1 {
                         argument: value.argument,
                         execution_time,
                         idempotency_key,
+                        dry_run: false,
                         completion_retention_duration: completion_retention_time,
                         invocation_target,
+                        pinned_deployment: None,
+                        priority: Default::default(),
+                        shared_concurrency_limit: None,
                     },
                 })
             }
@@ -1205,6 +1497,11 @@ pub mod v1 {
                             execution_time,
                             completion_retention_duration: completion_retention_time,
                             idempotency_key,
+                            // not supported by the legacy invocation status format
+                            pinned_deployment: _,
+                            dry_run: _,
+                            priority: _,
+                            shared_concurrency_limit: _,
                         },
                     inbox_sequence_number,
                 } = value;
@@ -1214,10 +1511,7 @@ pub mod v1 {
                 Inboxed {
                     invocation_target: Some(invocation_target.into()),
                     inbox_sequence_number,
-                    response_sinks: response_sinks
-                        .into_iter()
-                        .map(|s| ServiceInvocationResponseSink::from(Some(s)))
-                        .collect(),
+                    response_sinks: response_sinks_to_repr(response_sinks),
                     creation_time: unsafe { timestamps.creation_time() }.as_u64(),
                     modification_time: unsafe { timestamps.modification_time() }.as_u64(),
                     source: Some(Source::from(source)),
@@ -1269,6 +1563,8 @@ pub mod v1 {
                     // The value Duration::MAX here disables the new cleaner task business logic.
                     // Look at crates/worker/src/partition/cleaner.rs for more details.
                     completion_retention_duration: std::time::Duration::MAX,
+                    pinned_deployment: None,
+                    retry_count: 0,
                 })
             }
         }
@@ -1285,6 +1581,9 @@ pub mod v1 {
                     completion_retention_duration: _,
                     // The old invocation status table doesn't support span context on Completed
                     span_context: _,
+                    // Not supported by the old invocation status table
+                    pinned_deployment: _,
+                    retry_count: _,
                 } = value;
 
                 Completed {
@@ -1298,6 +1597,27 @@ pub mod v1 {
             }
         }
 
+        fn journal_version_from_repr(
+            version: u32,
+        ) -> Result<crate::invocation_status_table::JournalVersion, ConversionError> {
+            match version {
+                // unset by the versions which only supported the journal table
+                0 | 1 => Ok(crate::invocation_status_table::JournalVersion::V1),
+                2 => Ok(crate::invocation_status_table::JournalVersion::V2),
+                _ => Err(ConversionError::unexpected_enum_variant(
+                    "journal_version",
+                    i32::try_from(version).unwrap_or(i32::MAX),
+                )),
+            }
+        }
+
+        fn journal_version_to_repr(version: crate::invocation_status_table::JournalVersion) -> u32 {
+            match version {
+                crate::invocation_status_table::JournalVersion::V1 => 1,
+                crate::invocation_status_table::JournalVersion::V2 => 2,
+            }
+        }
+
         impl TryFrom<JournalMeta> for crate::invocation_status_table::JournalMetadata {
             type Error = ConversionError;
 
@@ -1312,6 +1632,7 @@ pub mod v1 {
                 Ok(crate::invocation_status_table::JournalMetadata {
                     length,
                     span_context,
+                    version: journal_version_from_repr(value.version)?,
                 })
             }
         }
@@ -1321,11 +1642,13 @@ pub mod v1 {
                 let crate::invocation_status_table::JournalMetadata {
                     span_context,
                     length,
+                    version,
                 } = value;
 
                 JournalMeta {
                     length,
                     span_context: Some(SpanContext::from(span_context)),
+                    version: journal_version_to_repr(version),
                 }
             }
         }
@@ -1417,6 +1740,10 @@ pub mod v1 {
                                         .invocation_id
                                         .ok_or(ConversionError::missing_field("invocation_id"))?,
                                 )?,
+                                restate_types::invocation::InvocationPriority::new(
+                                    invocation.priority,
+                                    invocation.priority_deadline.map(MillisSinceEpoch::new),
+                                ),
                             )
                         }
                         inbox_entry::Entry::StateMutation(state_mutation) => {
@@ -1434,12 +1761,16 @@ pub mod v1 {
         impl From<crate::inbox_table::InboxEntry> for InboxEntry {
             fn from(inbox_entry: crate::inbox_table::InboxEntry) -> Self {
                 let inbox_entry = match inbox_entry {
-                    crate::inbox_table::InboxEntry::Invocation(service_id, invocation_id) => {
-                        inbox_entry::Entry::Invocation(inbox_entry::Invocation {
-                            service_id: Some(service_id.into()),
-                            invocation_id: Some(InvocationId::from(invocation_id)),
-                        })
-                    }
+                    crate::inbox_table::InboxEntry::Invocation(
+                        service_id,
+                        invocation_id,
+                        priority,
+                    ) => inbox_entry::Entry::Invocation(inbox_entry::Invocation {
+                        service_id: Some(service_id.into()),
+                        invocation_id: Some(InvocationId::from(invocation_id)),
+                        priority: priority.priority,
+                        priority_deadline: priority.deadline.map(|t| t.as_u64()),
+                    }),
                     crate::inbox_table::InboxEntry::StateMutation(state_mutation) => {
                         inbox_entry::Entry::StateMutation(StateMutation::from(state_mutation))
                     }
@@ -1464,9 +1795,16 @@ pub mod v1 {
                     source,
                     headers,
                     execution_time,
+                    execution_wall_clock_time,
                     idempotency_key,
                     completion_retention_time,
                     submit_notification_sink,
+                    deployment_id,
+                    service_protocol_version,
+                    dry_run,
+                    priority,
+                    priority_deadline,
+                    shared_concurrency_limit,
                 } = value;
 
                 let invocation_id = restate_types::identifiers::InvocationId::try_from(
@@ -1502,6 +1840,11 @@ pub mod v1 {
                     Some(MillisSinceEpoch::new(execution_time))
                 };
 
+                let execution_wall_clock_time = execution_wall_clock_time
+                    .map(|t| t.parse::<ZonedDateTime>())
+                    .transpose()
+                    .map_err(ConversionError::invalid_data)?;
+
                 let completion_retention_time = completion_retention_time
                     .map(std::time::Duration::try_from)
                     .transpose()?;
@@ -1512,6 +1855,14 @@ pub mod v1 {
                     .map(TryInto::try_into)
                     .transpose()?;
 
+                let pinned_deployment =
+                    derive_pinned_deployment(deployment_id, service_protocol_version)?;
+
+                let priority = restate_types::invocation::InvocationPriority::new(
+                    priority,
+                    priority_deadline.map(MillisSinceEpoch::new),
+                );
+
                 Ok(restate_types::invocation::ServiceInvocation {
                     invocation_id,
                     invocation_target,
@@ -1521,8 +1872,13 @@ pub mod v1 {
                     span_context,
                     headers,
                     execution_time,
+                    execution_wall_clock_time,
                     completion_retention_duration: completion_retention_time,
                     idempotency_key,
+                    pinned_deployment,
+                    dry_run,
+                    priority,
+                    shared_concurrency_limit: shared_concurrency_limit.and_then(NonZeroU32::new),
                     submit_notification_sink: submit_notification_sink,
                 })
             }
@@ -1545,11 +1901,25 @@ pub mod v1 {
                     source: Some(source),
                     headers,
                     execution_time: value.execution_time.map(|m| m.as_u64()).unwrap_or_default(),
+                    execution_wall_clock_time: value
+                        .execution_wall_clock_time
+                        .map(|t| t.to_string()),
                     completion_retention_time: value
                         .completion_retention_duration
                         .map(Duration::from),
                     idempotency_key: value.idempotency_key.map(|s| s.to_string()),
                     submit_notification_sink: value.submit_notification_sink.map(Into::into),
+                    deployment_id: value
+                        .pinned_deployment
+                        .as_ref()
+                        .map(|p| p.deployment_id.to_string()),
+                    service_protocol_version: value
+                        .pinned_deployment
+                        .map(|p| p.service_protocol_version.as_repr()),
+                    dry_run: value.dry_run,
+                    priority: value.priority.priority,
+                    priority_deadline: value.priority.deadline.map(|t| t.as_u64()),
+                    shared_concurrency_limit: value.shared_concurrency_limit.map(NonZeroU32::get),
                 }
             }
         }
@@ -2026,6 +2396,50 @@ pub mod v1 {
             }
         }
 
+        impl TryFrom<StoredJournalEntry> for crate::journal_table_v2::StoredEntry {
+            type Error = ConversionError;
+
+            fn try_from(value: StoredJournalEntry) -> Result<Self, Self::Error> {
+                let StoredJournalEntry {
+                    entry,
+                    completion_id,
+                    append_time,
+                } = value;
+
+                Ok(crate::journal_table_v2::StoredEntry {
+                    entry: expect_or_fail!(entry)?.try_into()?,
+                    completion_id,
+                    append_time: append_time.map(MillisSinceEpoch::new),
+                })
+            }
+        }
+
+        impl From<crate::journal_table_v2::StoredEntry> for StoredJournalEntry {
+            fn from(value: crate::journal_table_v2::StoredEntry) -> Self {
+                StoredJournalEntry {
+                    entry: Some(JournalEntry::from(value.entry)),
+                    completion_id: value.completion_id,
+                    append_time: value.append_time.map(|time| time.as_u64()),
+                }
+            }
+        }
+
+        impl From<JournalPendingCompletion> for crate::journal_table_v2::PendingCompletion {
+            fn from(value: JournalPendingCompletion) -> Self {
+                crate::journal_table_v2::PendingCompletion {
+                    journal_index: value.journal_index,
+                }
+            }
+        }
+
+        impl From<crate::journal_table_v2::PendingCompletion> for JournalPendingCompletion {
+            fn from(value: crate::journal_table_v2::PendingCompletion) -> Self {
+                JournalPendingCompletion {
+                    journal_index: value.journal_index,
+                }
+            }
+        }
+
         impl From<restate_types::journal::enriched::EnrichedRawEntry> for JournalEntry {
             fn from(value: restate_types::journal::enriched::EnrichedRawEntry) -> Self {
                 let entry = Entry::from(value);
@@ -2406,6 +2820,9 @@ pub mod v1 {
                             invocation_target,
                             span_context,
                             completion_retention_time,
+                            shared_concurrency_limit: success
+                                .shared_concurrency_limit
+                                .and_then(NonZeroU32::new),
                         })
                     }
                 };
@@ -2426,6 +2843,7 @@ pub mod v1 {
                             invocation_target,
                             span_context,
                             completion_retention_time,
+                            shared_concurrency_limit,
                         } => invocation_resolution_result::Result::Success(
                             invocation_resolution_result::Success {
                                 invocation_id: Some(InvocationId::from(invocation_id)),
@@ -2434,6 +2852,8 @@ pub mod v1 {
                                 completion_retention_time: Some(Duration::from(
                                     completion_retention_time.unwrap_or_default(),
                                 )),
+                                shared_concurrency_limit: shared_concurrency_limit
+                                    .map(NonZeroU32::get),
                             },
                         ),
                     },
@@ -2478,6 +2898,9 @@ pub mod v1 {
                     span_context,
                     invocation_target,
                     completion_retention_time,
+                    shared_concurrency_limit: value
+                        .shared_concurrency_limit
+                        .and_then(NonZeroU32::new),
                 })
             }
         }
@@ -2493,6 +2916,7 @@ pub mod v1 {
                     completion_retention_time: Some(Duration::from(
                         value.completion_retention_time.unwrap_or_default(),
                     )),
+                    shared_concurrency_limit: value.shared_concurrency_limit.map(NonZeroU32::get),
                 }
             }
         }
@@ -2591,6 +3015,31 @@ pub mod v1 {
                             .ok_or(ConversionError::missing_field("response_sink"))??,
                         },
                     ),
+                    outbox_message::OutboxMessage::KafkaEvent(OutboxKafkaEvent {
+                        cluster,
+                        topic,
+                        key,
+                        payload,
+                        headers,
+                        source_invocation_id,
+                    }) => crate::outbox_table::OutboxMessage::KafkaEvent(
+                        crate::outbox_table::KafkaEgressEvent {
+                            cluster,
+                            topic,
+                            key,
+                            payload,
+                            headers: headers
+                                .into_iter()
+                                .map(restate_types::invocation::Header::try_from)
+                                .collect::<Result<Vec<_>, ConversionError>>()?,
+                            source_invocation_id:
+                                restate_types::identifiers::InvocationId::try_from(
+                                    source_invocation_id.ok_or(ConversionError::missing_field(
+                                        "source_invocation_id",
+                                    ))?,
+                                )?,
+                        },
+                    ),
                 };
 
                 Ok(result)
@@ -2667,6 +3116,23 @@ pub mod v1 {
                             response_sink: Some(Some(response_sink).into()),
                         },
                     ),
+                    crate::outbox_table::OutboxMessage::KafkaEvent(
+                        crate::outbox_table::KafkaEgressEvent {
+                            cluster,
+                            topic,
+                            key,
+                            payload,
+                            headers,
+                            source_invocation_id,
+                        },
+                    ) => outbox_message::OutboxMessage::KafkaEvent(OutboxKafkaEvent {
+                        cluster,
+                        topic,
+                        key,
+                        payload,
+                        headers: headers.into_iter().map(Into::into).collect(),
+                        source_invocation_id: Some(InvocationId::from(source_invocation_id)),
+                    }),
                 };
 
                 OutboxMessage {
@@ -2744,6 +3210,27 @@ pub mod v1 {
                         timer::Value::ScheduledInvoke(id) => crate::timer_table::Timer::NeoInvoke(
                             restate_types::identifiers::InvocationId::try_from(id)?,
                         ),
+                        timer::Value::ScheduledInvokeAtLocalTime(scheduled_invoke) => {
+                            crate::timer_table::Timer::NeoInvokeAtLocalTime(
+                                restate_types::identifiers::InvocationId::try_from(
+                                    scheduled_invoke
+                                        .invocation_id
+                                        .ok_or(ConversionError::missing_field("invocation_id"))?,
+                                )?,
+                                scheduled_invoke
+                                    .local_time
+                                    .parse()
+                                    .map_err(ConversionError::invalid_data)?,
+                            )
+                        }
+                        timer::Value::FireSchedule(fire_schedule) => {
+                            crate::timer_table::Timer::FireSchedule(
+                                fire_schedule
+                                    .schedule_id
+                                    .parse()
+                                    .map_err(ConversionError::invalid_data)?,
+                            )
+                        }
                         timer::Value::CleanInvocationStatus(clean_invocation_status) => {
                             crate::timer_table::Timer::CleanInvocationStatus(
                                 restate_types::identifiers::InvocationId::try_from(
@@ -2772,6 +3259,15 @@ pub mod v1 {
                         crate::timer_table::Timer::NeoInvoke(invocation_id) => {
                             timer::Value::ScheduledInvoke(InvocationId::from(invocation_id))
                         }
+                        crate::timer_table::Timer::NeoInvokeAtLocalTime(
+                            invocation_id,
+                            local_time,
+                        ) => timer::Value::ScheduledInvokeAtLocalTime(
+                            timer::ScheduledInvokeAtLocalTime {
+                                invocation_id: Some(InvocationId::from(invocation_id)),
+                                local_time: local_time.to_string(),
+                            },
+                        ),
                         crate::timer_table::Timer::Invoke(si) => {
                             timer::Value::Invoke(ServiceInvocation::from(si))
                         }
@@ -2780,6 +3276,11 @@ pub mod v1 {
                                 invocation_id: Some(InvocationId::from(invocation_id)),
                             })
                         }
+                        crate::timer_table::Timer::FireSchedule(schedule_id) => {
+                            timer::Value::FireSchedule(timer::FireSchedule {
+                                schedule_id: schedule_id.to_string(),
+                            })
+                        }
                     }),
                 }
             }
@@ -10,9 +10,11 @@
 
 use crate::{protobuf_storage_encode_decode, Result};
 use futures_util::Stream;
-use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
+use restate_types::identifiers::{
+    InvocationId, InvocationUuid, PartitionKey, ScheduleId, WithPartitionKey,
+};
 use restate_types::invocation::ServiceInvocation;
-use restate_types::time::MillisSinceEpoch;
+use restate_types::time::{MillisSinceEpoch, ZonedDateTime};
 use std::cmp::Ordering;
 use std::future::Future;
 
@@ -62,6 +64,13 @@ impl TimerKey {
             kind: TimerKeyKind::CleanInvocationStatus { invocation_uuid },
         }
     }
+
+    fn fire_schedule(timestamp: u64, schedule_uuid: InvocationUuid) -> Self {
+        TimerKey {
+            timestamp,
+            kind: TimerKeyKind::FireSchedule { schedule_uuid },
+        }
+    }
 }
 
 impl PartialOrd for TimerKey {
@@ -101,17 +110,21 @@ pub enum TimerKeyKind {
     },
     /// Cleaning of invocation status
     CleanInvocationStatus { invocation_uuid: InvocationUuid },
+    /// Next occurrence of a schedule
+    FireSchedule { schedule_uuid: InvocationUuid },
 }
 
 impl TimerKeyKind {
-    pub fn invocation_uuid(&self) -> InvocationUuid {
-        *match self {
-            TimerKeyKind::Invoke { invocation_uuid } => invocation_uuid,
+    /// The invocation this timer is about, if any.
+    pub fn invocation_uuid(&self) -> Option<InvocationUuid> {
+        match self {
+            TimerKeyKind::Invoke { invocation_uuid } => Some(*invocation_uuid),
             TimerKeyKind::CompleteJournalEntry {
                 invocation_uuid, ..
-            } => invocation_uuid,
-            TimerKeyKind::CleanInvocationStatus { invocation_uuid } => invocation_uuid,
-            TimerKeyKind::NeoInvoke { invocation_uuid } => invocation_uuid,
+            } => Some(*invocation_uuid),
+            TimerKeyKind::CleanInvocationStatus { invocation_uuid } => Some(*invocation_uuid),
+            TimerKeyKind::NeoInvoke { invocation_uuid } => Some(*invocation_uuid),
+            TimerKeyKind::FireSchedule { .. } => None,
         }
     }
 }
@@ -131,7 +144,8 @@ impl Ord for TimerKeyKind {
                 } => invocation_uuid.cmp(other_invocation_uuid),
                 TimerKeyKind::CompleteJournalEntry { .. }
                 | TimerKeyKind::CleanInvocationStatus { .. }
-                | TimerKeyKind::NeoInvoke { .. } => Ordering::Less,
+                | TimerKeyKind::NeoInvoke { .. }
+                | TimerKeyKind::FireSchedule { .. } => Ordering::Less,
             },
             TimerKeyKind::CompleteJournalEntry {
                 invocation_uuid,
@@ -144,9 +158,9 @@ impl Ord for TimerKeyKind {
                 } => invocation_uuid
                     .cmp(other_invocation_uuid)
                     .then_with(|| journal_index.cmp(other_journal_index)),
-                TimerKeyKind::CleanInvocationStatus { .. } | TimerKeyKind::NeoInvoke { .. } => {
-                    Ordering::Less
-                }
+                TimerKeyKind::CleanInvocationStatus { .. }
+                | TimerKeyKind::NeoInvoke { .. }
+                | TimerKeyKind::FireSchedule { .. } => Ordering::Less,
             },
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => match other {
                 TimerKeyKind::Invoke { .. } | TimerKeyKind::CompleteJournalEntry { .. } => {
@@ -155,7 +169,9 @@ impl Ord for TimerKeyKind {
                 TimerKeyKind::CleanInvocationStatus {
                     invocation_uuid: other_invocation_uuid,
                 } => invocation_uuid.cmp(other_invocation_uuid),
-                TimerKeyKind::NeoInvoke { .. } => Ordering::Less,
+                TimerKeyKind::NeoInvoke { .. } | TimerKeyKind::FireSchedule { .. } => {
+                    Ordering::Less
+                }
             },
             TimerKeyKind::NeoInvoke { invocation_uuid } => match other {
                 TimerKeyKind::Invoke { .. }
@@ -164,6 +180,16 @@ impl Ord for TimerKeyKind {
                 TimerKeyKind::NeoInvoke {
                     invocation_uuid: other_invocation_uuid,
                 } => invocation_uuid.cmp(other_invocation_uuid),
+                TimerKeyKind::FireSchedule { .. } => Ordering::Less,
+            },
+            TimerKeyKind::FireSchedule { schedule_uuid } => match other {
+                TimerKeyKind::Invoke { .. }
+                | TimerKeyKind::CompleteJournalEntry { .. }
+                | TimerKeyKind::CleanInvocationStatus { .. }
+                | TimerKeyKind::NeoInvoke { .. } => Ordering::Greater,
+                TimerKeyKind::FireSchedule {
+                    schedule_uuid: other_schedule_uuid,
+                } => schedule_uuid.cmp(other_schedule_uuid),
             },
         }
     }
@@ -183,6 +209,10 @@ pub enum Timer {
     // TODO remove this variant when removing the old invocation status table
     CleanInvocationStatus(InvocationId),
     NeoInvoke(InvocationId),
+    /// Scheduled invocation whose wake up time was resolved from a wall-clock time in a timezone.
+    NeoInvokeAtLocalTime(InvocationId, ZonedDateTime),
+    /// Next occurrence of a schedule, see [`crate::schedule_table`].
+    FireSchedule(ScheduleId),
 }
 
 impl Timer {
@@ -218,6 +248,17 @@ impl Timer {
         )
     }
 
+    pub fn neo_invoke_at_local_time(
+        timestamp: u64,
+        invocation_id: InvocationId,
+        local_time: ZonedDateTime,
+    ) -> (TimerKey, Self) {
+        (
+            TimerKey::neo_invoke(timestamp, invocation_id.invocation_uuid()),
+            Timer::NeoInvokeAtLocalTime(invocation_id, local_time),
+        )
+    }
+
     pub fn clean_invocation_status(
         timestamp: u64,
         invocation_id: InvocationId,
@@ -228,12 +269,22 @@ impl Timer {
         )
     }
 
-    pub fn invocation_id(&self) -> InvocationId {
+    pub fn fire_schedule(timestamp: u64, schedule_id: ScheduleId) -> (TimerKey, Self) {
+        (
+            TimerKey::fire_schedule(timestamp, schedule_id.schedule_uuid()),
+            Timer::FireSchedule(schedule_id),
+        )
+    }
+
+    /// The invocation this timer is about, if any.
+    pub fn invocation_id(&self) -> Option<InvocationId> {
         match self {
-            Timer::Invoke(service_invocation) => service_invocation.invocation_id,
-            Timer::CompleteJournalEntry(invocation_id, _) => *invocation_id,
-            Timer::CleanInvocationStatus(invocation_id) => *invocation_id,
-            Timer::NeoInvoke(invocation_id) => *invocation_id,
+            Timer::Invoke(service_invocation) => Some(service_invocation.invocation_id),
+            Timer::CompleteJournalEntry(invocation_id, _) => Some(*invocation_id),
+            Timer::CleanInvocationStatus(invocation_id) => Some(*invocation_id),
+            Timer::NeoInvoke(invocation_id) => Some(*invocation_id),
+            Timer::NeoInvokeAtLocalTime(invocation_id, _) => Some(*invocation_id),
+            Timer::FireSchedule(_) => None,
         }
     }
 }
@@ -245,6 +296,8 @@ impl WithPartitionKey for Timer {
             Timer::Invoke(service_invocation) => service_invocation.partition_key(),
             Timer::CleanInvocationStatus(invocation_id) => invocation_id.partition_key(),
             Timer::NeoInvoke(invocation_id) => invocation_id.partition_key(),
+            Timer::NeoInvokeAtLocalTime(invocation_id, _) => invocation_id.partition_key(),
+            Timer::FireSchedule(schedule_id) => schedule_id.partition_key(),
         }
     }
 }
@@ -0,0 +1,86 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::consistency_violation::schema::SysConsistencyViolationBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::consistency::ConsistencyViolation;
+use restate_types::identifiers::WithPartitionKey;
+
+#[inline]
+pub(crate) fn append_consistency_violation_row(
+    builder: &mut SysConsistencyViolationBuilder,
+    output: &mut String,
+    violation: ConsistencyViolation,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(violation.partition_key());
+    row.kind(violation.kind());
+    if row.is_invocation_id_defined() {
+        row.invocation_id(format_using(output, &violation.invocation_id()));
+    }
+    if row.is_description_defined() {
+        row.description(format_using(output, &violation));
+    }
+    row.repairable(violation.repair().is_some());
+
+    match violation {
+        ConsistencyViolation::JournalLengthMismatch {
+            expected_length,
+            actual_length,
+            ..
+        } => {
+            row.expected_journal_length(expected_length);
+            row.actual_journal_length(actual_length);
+        }
+        ConsistencyViolation::DanglingPendingCompletion {
+            completion_id,
+            journal_index,
+            ..
+        } => {
+            row.completion_id(completion_id);
+            row.journal_index(journal_index);
+        }
+        ConsistencyViolation::OrphanedInboxEntry {
+            service_id,
+            inbox_sequence_number,
+            ..
+        } => {
+            row.service_name(&service_id.service_name);
+            row.service_key(&service_id.key);
+            row.inbox_sequence_number(inbox_sequence_number);
+        }
+        ConsistencyViolation::DanglingIdempotencyKey { idempotency_id, .. } => {
+            row.service_name(&idempotency_id.service_name);
+            if let Some(service_key) = &idempotency_id.service_key {
+                row.service_key(service_key);
+            }
+            row.service_handler(&idempotency_id.service_handler);
+            row.idempotency_key(&idempotency_id.idempotency_key);
+        }
+        ConsistencyViolation::DanglingServiceLock { service_id, .. } => {
+            row.service_name(&service_id.service_name);
+            row.service_key(&service_id.key);
+        }
+        ConsistencyViolation::MissingServiceLock {
+            service_id,
+            locked_by,
+            ..
+        } => {
+            row.service_name(&service_id.service_name);
+            row.service_key(&service_id.key);
+            if let Some(locked_by) = locked_by {
+                if row.is_locked_by_defined() {
+                    row.locked_by(format_using(output, &locked_by));
+                }
+            }
+        }
+    }
+}
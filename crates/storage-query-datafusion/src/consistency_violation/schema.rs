@@ -0,0 +1,68 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_consistency_violation(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// The kind of violation. Either `journal_length_mismatch`, `dangling_pending_completion`,
+    /// `orphaned_inbox_entry`, `dangling_idempotency_key`, `dangling_service_lock` or
+    /// `missing_service_lock`.
+    kind: DataType::LargeUtf8,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation the violating
+    /// record refers to.
+    invocation_id: DataType::LargeUtf8,
+
+    /// For all the kinds but `journal_length_mismatch` and `dangling_pending_completion`, the name
+    /// of the service of the violating record.
+    service_name: DataType::LargeUtf8,
+
+    /// The key of the virtual object or workflow of the violating record, if any.
+    service_key: DataType::LargeUtf8,
+
+    /// For `dangling_idempotency_key`, the handler of the idempotency key.
+    service_handler: DataType::LargeUtf8,
+
+    /// For `dangling_idempotency_key`, the idempotency key.
+    idempotency_key: DataType::LargeUtf8,
+
+    /// For `orphaned_inbox_entry`, the sequence number of the inbox entry.
+    inbox_sequence_number: DataType::UInt64,
+
+    /// For `missing_service_lock`, the invocation locking the virtual object instead, if any.
+    locked_by: DataType::LargeUtf8,
+
+    /// For `journal_length_mismatch`, the journal length declared by the invocation status.
+    expected_journal_length: DataType::UInt32,
+
+    /// For `journal_length_mismatch`, the number of consecutive journal entries of the
+    /// invocation.
+    actual_journal_length: DataType::UInt32,
+
+    /// For `dangling_pending_completion`, the id of the completion.
+    completion_id: DataType::UInt32,
+
+    /// For `dangling_pending_completion`, the journal index the completion is indexed with.
+    journal_index: DataType::UInt32,
+
+    /// Human-readable description of the violation.
+    description: DataType::LargeUtf8,
+
+    /// If true, the violation is known to be benign and can be repaired through the
+    /// `/consistency/repair` endpoint of the admin API.
+    repairable: DataType::Boolean,
+));
@@ -0,0 +1,67 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::consistency::ConsistencyViolation;
+use restate_types::identifiers::PartitionKey;
+
+use crate::consistency_violation::row::append_consistency_violation_row;
+use crate::consistency_violation::schema::SysConsistencyViolationBuilder;
+use crate::context::{QueryContext, SelectPartitions};
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_consistency_violation";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    local_partition_store_manager: Option<PartitionStoreManager>,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = local_partition_store_manager.map(|partition_store_manager| {
+        Arc::new(LocalPartitionsScanner::new(
+            partition_store_manager,
+            ConsistencyViolationScanner,
+        )) as Arc<dyn ScanPartition>
+    });
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysConsistencyViolationBuilder::schema(),
+        ctx.create_distributed_scanner(NAME, local_partition_scanner),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct ConsistencyViolationScanner;
+
+impl ScanLocalPartition for ConsistencyViolationScanner {
+    type Builder = SysConsistencyViolationBuilder;
+    type Item = ConsistencyViolation;
+
+    fn scan_partition_store(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
+        // the violations are only known once all the tables were checked
+        stream::once(partition_store.check_consistency(range))
+            .map_ok(|violations| stream::iter(violations).map(Ok))
+            .try_flatten()
+    }
+
+    fn append_row(row_builder: &mut Self::Builder, string_buffer: &mut String, value: Self::Item) {
+        append_consistency_violation_row(row_builder, string_buffer, value);
+    }
+}
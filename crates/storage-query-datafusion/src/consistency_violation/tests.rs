@@ -0,0 +1,60 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{BooleanArray, LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::inbox_table::{InboxEntry, InboxTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, ServiceId, WithPartitionKey};
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_orphaned_inbox_entry() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let service_id = ServiceId::mock_random();
+    let invocation_id = InvocationId::mock_random();
+    let mut tx = engine.partition_store().transaction();
+    tx.put_inbox_entry(
+        7,
+        &InboxEntry::Invocation(service_id.clone(), invocation_id, Default::default()),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_consistency_violation")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "partition_key" => UInt64Array: eq(service_id.partition_key()),
+                "kind" => LargeStringArray: eq("orphaned_inbox_entry".to_owned()),
+                "invocation_id" => LargeStringArray: eq(invocation_id.to_string()),
+                "service_name" => LargeStringArray: eq(service_id.service_name.to_string()),
+                "service_key" => LargeStringArray: eq(service_id.key.to_string()),
+                "inbox_sequence_number" => UInt64Array: eq(7),
+                "repairable" => BooleanArray: eq(true),
+            }
+        ))
+    );
+}
@@ -10,11 +10,13 @@
 
 use std::cmp::max;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use codederror::CodedError;
 use datafusion::catalog::TableProvider;
+use datafusion::dataframe::DataFrame;
 use datafusion::error::DataFusionError;
 use datafusion::execution::context::SQLOptions;
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
@@ -29,7 +31,7 @@ use restate_invoker_api::StatusHandle;
 use restate_partition_store::PartitionStoreManager;
 use restate_types::config::QueryEngineOptions;
 use restate_types::errors::GenericError;
-use restate_types::identifiers::PartitionId;
+use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::live::Live;
 use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
@@ -95,7 +97,27 @@ pub enum BuildError {
 
 #[async_trait]
 pub trait SelectPartitions: Send + Sync + Debug + 'static {
-    async fn get_live_partitions(&self) -> Result<Vec<PartitionId>, GenericError>;
+    /// Returns the live partitions, together with their range of partition keys.
+    async fn get_live_partitions(
+        &self,
+    ) -> Result<Vec<(PartitionId, RangeInclusive<PartitionKey>)>, GenericError>;
+}
+
+/// Warnings collected while executing a query with partial results, see
+/// [`QueryContext::execute_with_partial_results`].
+#[derive(Debug, Clone, Default)]
+pub struct QueryWarnings(Arc<Mutex<Vec<String>>>);
+
+impl QueryWarnings {
+    pub(crate) fn push(&self, warning: String) {
+        self.0.lock().expect("lock not poisoned").push(warning);
+    }
+
+    /// Returns the warnings collected so far. All the warnings are collected once the result
+    /// stream of the query is exhausted.
+    pub fn get(&self) -> Vec<String> {
+        self.0.lock().expect("lock not poisoned").clone()
+    }
 }
 
 #[derive(Clone)]
@@ -138,6 +160,11 @@ impl QueryContext {
             partition_selector.clone(),
             local_partition_store_manager.clone(),
         )?;
+        crate::invocation_history::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
         crate::keyed_service_status::register_self(
             &ctx,
             partition_selector.clone(),
@@ -164,6 +191,26 @@ impl QueryContext {
             local_partition_store_manager.clone(),
         )?;
         crate::promise::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::dead_letter::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::invocation_timeline::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::schedule::register_self(
+            &ctx,
+            partition_selector.clone(),
+            local_partition_store_manager.clone(),
+        )?;
+        crate::consistency_violation::register_self(
             &ctx,
             partition_selector.clone(),
             local_partition_store_manager,
@@ -313,6 +360,25 @@ impl QueryContext {
         let df = self.datafusion_context.execute_logical_plan(plan).await?;
         df.execute_stream().await
     }
+
+    /// Executes the query over the partitions of all the nodes, like [`Self::execute`], but
+    /// tolerating the failures of scanning single partitions, e.g. because the node hosting them
+    /// is unreachable. The rows of the failed partitions are left out of the result, and a warning
+    /// is recorded for each of them.
+    pub async fn execute_with_partial_results(
+        &self,
+        sql: &str,
+    ) -> datafusion::common::Result<(SendableRecordBatchStream, QueryWarnings)> {
+        let warnings = QueryWarnings::default();
+        let mut state = self.datafusion_context.state();
+        state.config_mut().set_extension(Arc::new(warnings.clone()));
+
+        let statement = state.sql_to_statement(sql, "postgres")?;
+        let plan = state.statement_to_plan(statement).await?;
+        self.sql_options.verify_plan(&plan)?;
+        let stream = DataFrame::new(state, plan).execute_stream().await?;
+        Ok((stream, warnings))
+    }
 }
 
 impl AsRef<SessionContext> for QueryContext {
@@ -327,9 +393,14 @@ pub struct SelectPartitionsFromMetadata;
 
 #[async_trait]
 impl SelectPartitions for SelectPartitionsFromMetadata {
-    async fn get_live_partitions(&self) -> Result<Vec<PartitionId>, GenericError> {
+    async fn get_live_partitions(
+        &self,
+    ) -> Result<Vec<(PartitionId, RangeInclusive<PartitionKey>)>, GenericError> {
         Ok(Metadata::with_current(|m| {
-            m.partition_table_ref().partition_ids().cloned().collect()
+            m.partition_table_ref()
+                .partitions()
+                .map(|(partition_id, partition)| (*partition_id, partition.key_range.clone()))
+                .collect()
         }))
     }
 }
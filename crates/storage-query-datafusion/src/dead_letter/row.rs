@@ -0,0 +1,29 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysDeadLetterBuilder;
+use restate_storage_api::dead_letter_table::DeadLetter;
+
+#[inline]
+pub(crate) fn append_dead_letter_row(
+    builder: &mut SysDeadLetterBuilder,
+    _output: &mut String,
+    dead_letter: DeadLetter,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(dead_letter.partition_key);
+    row.partition_id(u32::from(dead_letter.partition_id));
+    row.lsn(dead_letter.lsn.into());
+    row.command(&dead_letter.command);
+    row.error(&dead_letter.error);
+    row.attempts(dead_letter.attempts);
+    row.dead_lettered_at(dead_letter.dead_lettered_at.as_u64() as i64);
+}
@@ -0,0 +1,39 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_dead_letter(
+    /// Internal column that is used for partitioning. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// The partition whose log contains the dead-lettered record.
+    partition_id: DataType::UInt32,
+
+    /// The LSN of the dead-lettered record in the partition's log. Use it to re-inject the record
+    /// via the admin API.
+    lsn: DataType::UInt64,
+
+    /// The name of the command contained in the dead-lettered record.
+    command: DataType::LargeUtf8,
+
+    /// The error of the last failed attempt to apply the record.
+    error: DataType::LargeUtf8,
+
+    /// The number of failed attempts to apply the record.
+    attempts: DataType::UInt32,
+
+    /// Timestamp indicating when the record was moved to the dead-letter table.
+    dead_lettered_at: DataType::Date64,
+));
@@ -0,0 +1,65 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use bytes::Bytes;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::dead_letter_table::{DeadLetter, DeadLetterTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::PartitionId;
+use restate_types::logs::Lsn;
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_dead_letters() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    tx.put_dead_letter(&DeadLetter {
+        partition_id: PartitionId::MIN,
+        lsn: Lsn::from(42),
+        partition_key: 1337,
+        command: "Invoke".to_owned(),
+        error: "failed to deserialize entry".to_owned(),
+        attempts: 3,
+        dead_lettered_at: MillisSinceEpoch::now(),
+        envelope: Bytes::from_static(b"envelope"),
+    })
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_dead_letter")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "partition_key" => UInt64Array: eq(1337),
+                "lsn" => UInt64Array: eq(42),
+                "command" => LargeStringArray: eq("Invoke".to_owned()),
+                "error" => LargeStringArray: eq("failed to deserialize entry".to_owned()),
+                "attempts" => UInt32Array: eq(3),
+            }
+        ))
+    );
+}
@@ -26,7 +26,7 @@ pub(crate) fn append_inbox_row(
         inbox_entry,
     } = inbox_entry;
 
-    if let InboxEntry::Invocation(service_id, invocation_id) = inbox_entry {
+    if let InboxEntry::Invocation(service_id, invocation_id, priority) = inbox_entry {
         row.partition_key(invocation_id.partition_key());
         row.service_name(&service_id.service_name);
         row.service_key(&service_id.key);
@@ -36,6 +36,10 @@ pub(crate) fn append_inbox_row(
         }
 
         row.sequence_number(inbox_sequence_number);
+        row.priority(priority.priority);
+        if let Some(deadline) = priority.deadline {
+            row.priority_deadline(deadline.as_u64() as i64);
+        }
     } else {
         // todo think about how to present other inbox entries via datafusion: https://github.com/restatedev/restate/issues/1101
     }
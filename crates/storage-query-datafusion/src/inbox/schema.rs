@@ -30,6 +30,13 @@ define_table!(sys_inbox(
     /// Sequence number in the inbox.
     sequence_number: DataType::UInt64,
 
+    /// Priority of the invocation in the inbox. Invocations with higher priority start first.
+    priority: DataType::Int32,
+
+    /// Time by which the invocation should have started, if any. Among invocations with the same
+    /// priority, the ones with the earliest deadline start first.
+    priority_deadline: DataType::Date64,
+
     /// Timestamp indicating the start of this invocation.
     /// DEPRECATED: you should not use this field anymore, but join with the sys_invocation table
     created_at: DataType::Date64,
@@ -30,13 +30,13 @@ async fn get_inbox() {
     let invocation_id_1 = InvocationId::mock_generate(&invocation_target);
     tx.put_inbox_entry(
         0,
-        &InboxEntry::Invocation(service_id.clone(), invocation_id_1),
+        &InboxEntry::Invocation(service_id.clone(), invocation_id_1, Default::default()),
     )
     .await;
     let invocation_id_2 = InvocationId::mock_generate(&invocation_target);
     tx.put_inbox_entry(
         1,
-        &InboxEntry::Invocation(service_id.clone(), invocation_id_2),
+        &InboxEntry::Invocation(service_id.clone(), invocation_id_2, Default::default()),
     )
     .await;
     tx.commit().await.unwrap();
@@ -0,0 +1,109 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::invocation_history::schema::SysInvocationHistoryBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::invocation_status_table::CompletedInvocation;
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::invocation::{ResponseResult, ServiceType};
+
+#[inline]
+pub(crate) fn append_invocation_history_row(
+    builder: &mut SysInvocationHistoryBuilder,
+    output: &mut String,
+    invocation_id: InvocationId,
+    completed: CompletedInvocation,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(invocation_id.partition_key());
+    if row.is_id_defined() {
+        row.id(format_using(output, &invocation_id));
+    }
+
+    let invocation_target = &completed.invocation_target;
+    row.target_service_name(invocation_target.service_name());
+    if let Some(key) = invocation_target.key() {
+        row.target_service_key(key);
+    }
+    row.target_handler_name(invocation_target.handler_name());
+    if row.is_target_defined() {
+        row.target(format_using(output, invocation_target));
+    }
+    row.target_service_ty(match invocation_target.service_ty() {
+        ServiceType::Service => "service",
+        ServiceType::VirtualObject => "virtual_object",
+        ServiceType::Workflow => "workflow",
+    });
+
+    if let Some(key) = &completed.idempotency_key {
+        row.idempotency_key(key);
+    }
+
+    match &completed.response_result {
+        ResponseResult::Success(_) => {
+            row.completion_result("success");
+        }
+        ResponseResult::Failure(failure) => {
+            row.completion_result("failure");
+            if row.is_completion_failure_defined() {
+                row.completion_failure(format_using(output, failure));
+            }
+        }
+    }
+
+    if let Some(pinned_deployment) = &completed.pinned_deployment {
+        if row.is_pinned_deployment_id_defined() {
+            row.pinned_deployment_id(format_using(output, &pinned_deployment.deployment_id));
+        }
+        row.pinned_service_protocol_version(
+            pinned_deployment
+                .service_protocol_version
+                .as_repr()
+                .unsigned_abs(),
+        );
+    }
+    row.retry_count(completed.retry_count);
+
+    // SAFETY: All these unsafe usages to get timestamps are ok,
+    //  as we don't use them as part of the PP state machine business logic.
+    let timestamps = &completed.timestamps;
+    let created_at = unsafe { timestamps.creation_time() }.as_u64();
+    let inboxed_at = unsafe { timestamps.inboxed_transition_time() }.map(|t| t.as_u64());
+    let running_at = unsafe { timestamps.running_transition_time() }.map(|t| t.as_u64());
+    let completed_at = unsafe { timestamps.completed_transition_time() }.map(|t| t.as_u64());
+
+    row.created_at(created_at as i64);
+    if let Some(inboxed_at) = inboxed_at {
+        row.inboxed_at(inboxed_at as i64);
+    }
+    if let Some(scheduled_at) = unsafe { timestamps.scheduled_transition_time() } {
+        row.scheduled_at(scheduled_at.as_u64() as i64);
+    }
+    if let Some(running_at) = running_at {
+        row.running_at(running_at as i64);
+    }
+    if let Some(completed_at) = completed_at {
+        row.completed_at(completed_at as i64);
+        row.total_duration_ms(completed_at.saturating_sub(created_at));
+    }
+
+    // The inboxed invocation leaves the inbox when it starts running
+    if let (Some(inboxed_at), Some(running_at)) = (inboxed_at, running_at) {
+        row.inboxed_duration_ms(running_at.saturating_sub(inboxed_at));
+    }
+    if let (Some(running_at), Some(completed_at)) = (running_at, completed_at) {
+        row.running_duration_ms(completed_at.saturating_sub(running_at));
+    }
+
+    if let Some(expires_at) = unsafe { completed.completion_expiry_time() } {
+        row.expires_at(expires_at.as_u64() as i64);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_invocation_history(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier).
+    id: DataType::LargeUtf8,
+
+    /// Invocation Target. Format for plain services: `ServiceName/HandlerName`, e.g.
+    /// `Greeter/greet`. Format for virtual objects/workflows: `VirtualObjectName/Key/HandlerName`,
+    /// e.g. `Greeter/Francesco/greet`.
+    target: DataType::LargeUtf8,
+
+    /// The name of the invoked service.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the virtual object or the workflow ID. Null for regular services.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The invoked handler.
+    target_handler_name: DataType::LargeUtf8,
+
+    /// The service type. Either `service` or `virtual_object` or `workflow`.
+    target_service_ty: DataType::LargeUtf8,
+
+    /// Idempotency key, if any.
+    idempotency_key: DataType::LargeUtf8,
+
+    /// Either `success` or `failure`.
+    completion_result: DataType::LargeUtf8,
+
+    /// If `completion_result = 'failure'`, this contains the error cause.
+    completion_failure: DataType::LargeUtf8,
+
+    /// The ID of the service deployment that executed this invocation, if any.
+    pinned_deployment_id: DataType::LargeUtf8,
+
+    /// The negotiated protocol version used for this invocation, if any.
+    pinned_service_protocol_version: DataType::UInt32,
+
+    /// The number of times the invocation was retried after a failed attempt.
+    retry_count: DataType::UInt32,
+
+    /// Timestamp indicating the start of this invocation.
+    created_at: DataType::Date64,
+
+    /// Timestamp indicating when the invocation was inboxed, if ever.
+    inboxed_at: DataType::Date64,
+
+    /// Timestamp indicating when the invocation was scheduled, if ever.
+    scheduled_at: DataType::Date64,
+
+    /// Timestamp indicating when the invocation first transitioned to running.
+    running_at: DataType::Date64,
+
+    /// Timestamp indicating when the invocation was completed.
+    completed_at: DataType::Date64,
+
+    /// Milliseconds the invocation spent in the inbox, waiting for the virtual object to be
+    /// unlocked. Null if it was never inboxed.
+    inboxed_duration_ms: DataType::UInt64,
+
+    /// Milliseconds between the first transition to running and the completion, including the
+    /// retries and the time spent suspended.
+    running_duration_ms: DataType::UInt64,
+
+    /// Milliseconds between the creation and the completion of the invocation.
+    total_duration_ms: DataType::UInt64,
+
+    /// Timestamp after which the completed invocation is removed from the history, according to
+    /// its completion retention.
+    expires_at: DataType::Date64,
+));
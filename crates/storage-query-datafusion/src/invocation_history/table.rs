@@ -0,0 +1,81 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::future::ready;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{Stream, TryStreamExt};
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InvocationStatus, ReadOnlyInvocationStatusTable,
+};
+use restate_types::identifiers::{InvocationId, PartitionKey};
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::invocation_history::row::append_invocation_history_row;
+use crate::invocation_history::schema::SysInvocationHistoryBuilder;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_invocation_history";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    local_partition_store_manager: Option<PartitionStoreManager>,
+) -> datafusion::common::Result<()> {
+    let local_scanner = local_partition_store_manager.map(|partition_store_manager| {
+        Arc::new(LocalPartitionsScanner::new(
+            partition_store_manager,
+            HistoryScanner,
+        )) as Arc<dyn ScanPartition>
+    });
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysInvocationHistoryBuilder::schema(),
+        ctx.create_distributed_scanner(NAME, local_scanner),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+/// Scans the completed invocations, both the ones still in the invocation status table and the
+/// archived ones. They're retained until their completion retention expires.
+#[derive(Debug, Clone)]
+struct HistoryScanner;
+
+impl ScanLocalPartition for HistoryScanner {
+    type Builder = SysInvocationHistoryBuilder;
+    type Item = (InvocationId, CompletedInvocation);
+
+    fn scan_partition_store(
+        partition_store: &PartitionStore,
+        range: RangeInclusive<PartitionKey>,
+    ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
+        partition_store
+            .all_invocation_statuses(range)
+            .try_filter_map(|(invocation_id, invocation_status)| {
+                ready(Ok(match invocation_status {
+                    InvocationStatus::Completed(completed) => Some((invocation_id, completed)),
+                    _ => None,
+                }))
+            })
+    }
+
+    fn append_row(
+        row_builder: &mut Self::Builder,
+        string_buffer: &mut String,
+        (invocation_id, completed): Self::Item,
+    ) {
+        append_invocation_history_row(row_builder, string_buffer, invocation_id, completed)
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
+    StatusTimestamps,
+};
+use restate_storage_api::Transaction;
+use restate_types::deployment::PinnedDeployment;
+use restate_types::identifiers::{DeploymentId, InvocationId};
+use restate_types::invocation::InvocationTarget;
+use restate_types::service_protocol::ServiceProtocolVersion;
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_invocation_history() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let completed_invocation_id = InvocationId::mock_generate(&invocation_target);
+    let deployment_id = DeploymentId::new();
+
+    let mut tx = engine.partition_store().transaction();
+    tx.put_invocation_status(
+        &completed_invocation_id,
+        &InvocationStatus::Completed(CompletedInvocation {
+            invocation_target: invocation_target.clone(),
+            timestamps: StatusTimestamps::new(
+                MillisSinceEpoch::new(1_000),
+                MillisSinceEpoch::new(4_000),
+                Some(MillisSinceEpoch::new(1_000)),
+                None,
+                Some(MillisSinceEpoch::new(1_500)),
+                Some(MillisSinceEpoch::new(4_000)),
+            ),
+            pinned_deployment: Some(PinnedDeployment::new(
+                deployment_id,
+                ServiceProtocolVersion::V3,
+            )),
+            retry_count: 2,
+            ..CompletedInvocation::mock_neo()
+        }),
+    )
+    .await;
+    // In-flight invocations are not part of the history
+    tx.put_invocation_status(
+        &InvocationId::mock_generate(&invocation_target),
+        &InvocationStatus::Invoked(InFlightInvocationMetadata {
+            invocation_target: invocation_target.clone(),
+            ..InFlightInvocationMetadata::mock()
+        }),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_invocation_history")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 1);
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "id" => LargeStringArray: eq(completed_invocation_id.to_string()),
+                "target" => LargeStringArray: eq(invocation_target.to_string()),
+                "completion_result" => LargeStringArray: eq("success"),
+                "pinned_deployment_id" => LargeStringArray: eq(deployment_id.to_string()),
+                "pinned_service_protocol_version" => UInt32Array: eq(3),
+                "retry_count" => UInt32Array: eq(2),
+                "inboxed_duration_ms" => UInt64Array: eq(500),
+                "running_duration_ms" => UInt64Array: eq(2_500),
+                "total_duration_ms" => UInt64Array: eq(3_000),
+            }
+        ))
+    );
+}
@@ -54,6 +54,8 @@ define_table!(sys_invocation_status(
     /// * `ingress` if the invocation was created externally.
     /// * `service` if the invocation was created by another Restate service.
     /// * `subscription` if the invocation was created by a subscription (e.g. Kafka).
+    /// * `restate` if the invocation was created by Restate itself, or if it's a completed
+    ///   invocation which has been archived, see `worker.archive-completed-invocations-after`.
     invoked_by: DataType::LargeUtf8,
 
     /// The caller [Invocation ID](/operate/invocation#invocation-identifier) if `invoked_by = 'service'`.
@@ -78,7 +80,7 @@ define_table!(sys_invocation_status(
     pinned_service_protocol_version: DataType::UInt32,
 
     /// The ID of the trace that is assigned to this invocation. Only relevant when tracing is
-    /// enabled.
+    /// enabled. Not retained by archived invocations.
     trace_id: DataType::LargeUtf8,
 
     /// The number of journal entries durably logged for this invocation.
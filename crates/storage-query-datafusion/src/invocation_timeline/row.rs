@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Write;
+
+use crate::invocation_timeline::schema::SysInvocationTimelineBuilder;
+use crate::table_util::format_using;
+use restate_storage_api::invocation_event_table::{InvocationEvent, InvocationEventKind};
+use restate_types::identifiers::WithPartitionKey;
+
+#[inline]
+pub(crate) fn append_invocation_event_row(
+    builder: &mut SysInvocationTimelineBuilder,
+    output: &mut String,
+    event: InvocationEvent,
+) {
+    let mut row = builder.row();
+
+    row.partition_key(event.invocation_id.partition_key());
+    if row.is_id_defined() {
+        row.id(format_using(output, &event.invocation_id));
+    }
+    row.seq(event.seq_number);
+    row.timestamp(event.timestamp.as_u64() as i64);
+    row.kind(<&'static str>::from(&event.kind));
+
+    match event.kind {
+        InvocationEventKind::Scheduled { execution_time } => {
+            row.execution_time(execution_time.as_u64() as i64);
+        }
+        InvocationEventKind::Started {
+            attempt,
+            deployment_id,
+        } => {
+            row.attempt(attempt);
+            if let Some(deployment_id) = deployment_id {
+                if row.is_deployment_id_defined() {
+                    row.deployment_id(format_using(output, &deployment_id));
+                }
+            }
+        }
+        InvocationEventKind::DeploymentPinned { deployment_id } => {
+            if row.is_deployment_id_defined() {
+                row.deployment_id(format_using(output, &deployment_id));
+            }
+        }
+        InvocationEventKind::RetryScheduled { attempt } => {
+            row.attempt(attempt);
+        }
+        InvocationEventKind::Suspended {
+            waiting_for_entries,
+        } => {
+            if row.is_waiting_for_entries_defined() {
+                output.clear();
+                for (i, entry_index) in waiting_for_entries.iter().enumerate() {
+                    if i > 0 {
+                        output.push(',');
+                    }
+                    let _ = write!(output, "{entry_index}");
+                }
+                row.waiting_for_entries(output);
+            }
+        }
+        InvocationEventKind::Completed { failure } => {
+            if let Some(failure) = failure {
+                row.failure(failure);
+            }
+        }
+        InvocationEventKind::Created
+        | InvocationEventKind::Inboxed
+        | InvocationEventKind::Resumed => {}
+    }
+}
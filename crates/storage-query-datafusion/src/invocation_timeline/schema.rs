@@ -0,0 +1,52 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_invocation_timeline(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier).
+    id: DataType::LargeUtf8,
+
+    /// Sequence number of the event within the invocation. Order the events of an invocation by
+    /// this column. Only the 64 most recent events of each invocation are retained.
+    seq: DataType::UInt64,
+
+    /// Timestamp indicating when the log record which produced the event was created.
+    timestamp: DataType::Date64,
+
+    /// The kind of event. Either `created`, `scheduled`, `inboxed`, `started`,
+    /// `deployment_pinned`, `retry_scheduled`, `suspended`, `resumed` or `completed`.
+    kind: DataType::LargeUtf8,
+
+    /// For `started`, the attempt being started. For `retry_scheduled`, the attempt which
+    /// failed.
+    attempt: DataType::UInt32,
+
+    /// For `started`, the deployment the invocation is pinned to, if any. For
+    /// `deployment_pinned`, the chosen deployment.
+    deployment_id: DataType::LargeUtf8,
+
+    /// For `scheduled`, the time at which the invocation will start.
+    execution_time: DataType::Date64,
+
+    /// For `suspended`, the comma separated indexes of the journal entries the invocation is
+    /// waiting for.
+    waiting_for_entries: DataType::LargeUtf8,
+
+    /// For `completed`, the failure of the invocation, if it failed.
+    failure: DataType::LargeUtf8,
+));
@@ -0,0 +1,86 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::invocation_event_table::{InvocationEventKind, InvocationEventTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_invocation_timeline() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let mut tx = engine.partition_store().transaction();
+    tx.append_invocation_events(
+        &invocation_id,
+        MillisSinceEpoch::now(),
+        vec![
+            InvocationEventKind::Created,
+            InvocationEventKind::Started {
+                attempt: 1,
+                deployment_id: None,
+            },
+            InvocationEventKind::Suspended {
+                waiting_for_entries: vec![1, 3],
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_invocation_timeline ORDER BY seq")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "partition_key" => UInt64Array: eq(invocation_id.partition_key()),
+                    "id" => LargeStringArray: eq(invocation_id.to_string()),
+                    "seq" => UInt64Array: eq(0),
+                    "kind" => LargeStringArray: eq("created".to_owned()),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "seq" => UInt64Array: eq(1),
+                    "kind" => LargeStringArray: eq("started".to_owned()),
+                    "attempt" => UInt32Array: eq(1),
+                }
+            ),
+            row!(
+                2,
+                {
+                    "seq" => UInt64Array: eq(2),
+                    "kind" => LargeStringArray: eq("suspended".to_owned()),
+                    "waiting_for_entries" => LargeStringArray: eq("1,3".to_owned()),
+                }
+            )
+        )
+    );
+}
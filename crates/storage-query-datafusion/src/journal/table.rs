@@ -8,13 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use futures::Stream;
+use futures::{Stream, StreamExt, TryStreamExt};
 use std::fmt::Debug;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
 use restate_storage_api::journal_table::{JournalEntry, ReadOnlyJournalTable};
+use restate_storage_api::journal_table_v2::ReadOnlyJournalTableV2;
 use restate_types::identifiers::{JournalEntryId, PartitionKey};
 
 use crate::context::{QueryContext, SelectPartitions};
@@ -55,7 +56,10 @@ impl ScanLocalPartition for JournalScanner {
         partition_store: &PartitionStore,
         range: RangeInclusive<PartitionKey>,
     ) -> impl Stream<Item = restate_storage_api::Result<Self::Item>> + Send {
-        partition_store.all_journals(range)
+        ReadOnlyJournalTable::all_journals(partition_store, range.clone()).chain(
+            ReadOnlyJournalTableV2::all_journals(partition_store, range)
+                .map_ok(|(journal_entry_id, stored_entry)| (journal_entry_id, stored_entry.entry)),
+        )
     }
 
     fn append_row(row_builder: &mut Self::Builder, string_buffer: &mut String, value: Self::Item) {
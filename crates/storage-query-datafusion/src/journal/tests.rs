@@ -56,6 +56,7 @@ async fn get_entries() {
                     invocation_id: invoked_invocation_id,
                     invocation_target: invoked_invocation_target.clone(),
                     completion_retention_time: None,
+                    shared_concurrency_limit: None,
                     span_context: Default::default(),
                 }),
             },
@@ -150,6 +151,7 @@ async fn select_count_star() {
                     invocation_id: InvocationId::mock_random(),
                     invocation_target: InvocationTarget::mock_virtual_object(),
                     completion_retention_time: None,
+                    shared_concurrency_limit: None,
                     span_context: Default::default(),
                 }),
             },
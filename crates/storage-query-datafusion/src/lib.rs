@@ -9,20 +9,26 @@
 // by the Apache License, Version 2.0.
 
 mod analyzer;
+mod consistency_violation;
 pub mod context;
 
 pub mod remote_query_scanner_server;
 
+mod dead_letter;
 mod deployment;
 mod idempotency;
 mod inbox;
+mod invocation_history;
 mod invocation_state;
 mod invocation_status;
+mod invocation_timeline;
 mod journal;
 mod keyed_service_status;
+mod partition_filter;
 mod partition_store_scanner;
 mod physical_optimizer;
 mod promise;
+mod schedule;
 mod service;
 mod state;
 #[cfg(feature = "table_docs")]
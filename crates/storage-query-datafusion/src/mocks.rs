@@ -61,6 +61,10 @@ impl ServiceMetadataResolver for MockSchemas {
         todo!()
     }
 
+    fn resolve_latest_services_openapi(&self) -> Value {
+        todo!()
+    }
+
     fn resolve_latest_service_type(&self, service_name: impl AsRef<str>) -> Option<ServiceType> {
         self.0.resolve_latest_service_type(service_name)
     }
@@ -99,8 +103,10 @@ struct MockPartitionSelector;
 
 #[async_trait]
 impl SelectPartitions for MockPartitionSelector {
-    async fn get_live_partitions(&self) -> Result<Vec<PartitionId>, GenericError> {
-        Ok(vec![PartitionId::MIN])
+    async fn get_live_partitions(
+        &self,
+    ) -> Result<Vec<(PartitionId, RangeInclusive<PartitionKey>)>, GenericError> {
+        Ok(vec![(PartitionId::MIN, 0..=PartitionKey::MAX)])
     }
 }
 
@@ -210,6 +216,10 @@ impl MockQueryEngine {
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
         self.2.execute(sql).await
     }
+
+    pub fn context(&self) -> &QueryContext {
+        &self.2
+    }
 }
 
 // --- Matchers for rows
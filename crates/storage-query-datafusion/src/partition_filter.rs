@@ -0,0 +1,205 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::cmp::{max, min};
+use std::ops::RangeInclusive;
+
+use datafusion::logical_expr::expr::InList;
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Operator};
+use datafusion::scalar::ScalarValue;
+
+use restate_types::identifiers::PartitionKey;
+
+const PARTITION_KEY: &str = "partition_key";
+const FULL_RANGE: RangeInclusive<PartitionKey> = 0..=PartitionKey::MAX;
+
+/// Computes the range of partition keys which can satisfy all the filters pushed down to a
+/// partitioned table, using the predicates on the `partition_key` column which compare it with
+/// literals. Returns `None` if no partition key can satisfy the filters.
+///
+/// The range is an over-approximation, the filters are still evaluated on the scanned rows.
+pub(crate) fn partition_key_range(filters: &[Expr]) -> Option<RangeInclusive<PartitionKey>> {
+    filters.iter().try_fold(FULL_RANGE, |range, filter| {
+        intersect(&range, &filter_range(filter)?)
+    })
+}
+
+pub(crate) fn intersect(
+    a: &RangeInclusive<PartitionKey>,
+    b: &RangeInclusive<PartitionKey>,
+) -> Option<RangeInclusive<PartitionKey>> {
+    let start = max(*a.start(), *b.start());
+    let end = min(*a.end(), *b.end());
+    (start <= end).then_some(start..=end)
+}
+
+fn filter_range(expr: &Expr) -> Option<RangeInclusive<PartitionKey>> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => intersect(&filter_range(left)?, &filter_range(right)?),
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        }) => match (filter_range(left), filter_range(right)) {
+            (Some(left), Some(right)) => {
+                Some(min(*left.start(), *right.start())..=max(*left.end(), *right.end()))
+            }
+            (left, right) => left.or(right),
+        },
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            let (op, value) = if is_partition_key(left) {
+                (*op, partition_key_literal(right))
+            } else if is_partition_key(right) {
+                // literal <op> partition_key
+                (op.swap().unwrap_or(*op), partition_key_literal(left))
+            } else {
+                return Some(FULL_RANGE);
+            };
+            let Some(value) = value else {
+                return Some(FULL_RANGE);
+            };
+
+            match op {
+                Operator::Eq => Some(value..=value),
+                Operator::Lt => Some(0..=value.checked_sub(1)?),
+                Operator::LtEq => Some(0..=value),
+                Operator::Gt => Some(value.checked_add(1)?..=PartitionKey::MAX),
+                Operator::GtEq => Some(value..=PartitionKey::MAX),
+                _ => Some(FULL_RANGE),
+            }
+        }
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) if is_partition_key(expr) => {
+            match (partition_key_literal(low), partition_key_literal(high)) {
+                (Some(low), Some(high)) => (low <= high).then_some(low..=high),
+                _ => Some(FULL_RANGE),
+            }
+        }
+        Expr::InList(InList {
+            expr,
+            list,
+            negated: false,
+        }) if is_partition_key(expr) => {
+            let Some(values) = list
+                .iter()
+                .map(partition_key_literal)
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Some(FULL_RANGE);
+            };
+            Some(*values.iter().min()?..=*values.iter().max()?)
+        }
+        _ => Some(FULL_RANGE),
+    }
+}
+
+fn is_partition_key(expr: &Expr) -> bool {
+    matches!(expr, Expr::Column(column) if column.name == PARTITION_KEY)
+}
+
+fn partition_key_literal(expr: &Expr) -> Option<PartitionKey> {
+    match expr {
+        Expr::Literal(ScalarValue::UInt64(Some(value))) => Some(*value),
+        Expr::Literal(ScalarValue::Int64(Some(value))) => PartitionKey::try_from(*value).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::prelude::{col, lit};
+
+    #[test]
+    fn no_partition_key_predicates() {
+        assert_eq!(partition_key_range(&[]), Some(FULL_RANGE));
+        assert_eq!(
+            partition_key_range(&[col("service_name").eq(lit("greeter"))]),
+            Some(FULL_RANGE)
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(
+            partition_key_range(&[col("partition_key").eq(lit(42u64))]),
+            Some(42..=42)
+        );
+        assert_eq!(
+            partition_key_range(&[col("partition_key").gt(lit(10u64))]),
+            Some(11..=PartitionKey::MAX)
+        );
+        assert_eq!(
+            partition_key_range(&[lit(10u64).gt_eq(col("partition_key"))]),
+            Some(0..=10)
+        );
+        assert_eq!(
+            partition_key_range(&[col("partition_key").lt(lit(0u64))]),
+            None
+        );
+    }
+
+    #[test]
+    fn conjunctions_and_disjunctions() {
+        assert_eq!(
+            partition_key_range(&[
+                col("partition_key").gt_eq(lit(10u64)),
+                col("partition_key").lt(lit(20u64))
+            ]),
+            Some(10..=19)
+        );
+        assert_eq!(
+            partition_key_range(&[col("partition_key")
+                .eq(lit(10u64))
+                .or(col("partition_key").eq(lit(20u64)))]),
+            Some(10..=20)
+        );
+        assert_eq!(
+            partition_key_range(&[col("partition_key")
+                .eq(lit(10u64))
+                .or(col("service_name").eq(lit("greeter")))]),
+            Some(FULL_RANGE)
+        );
+        assert_eq!(
+            partition_key_range(&[
+                col("partition_key").eq(lit(10u64)),
+                col("partition_key").eq(lit(20u64))
+            ]),
+            None
+        );
+    }
+
+    #[test]
+    fn between_and_in_list() {
+        assert_eq!(
+            partition_key_range(&[col("partition_key").between(lit(5u64), lit(7u64))]),
+            Some(5..=7)
+        );
+        assert_eq!(
+            partition_key_range(&[
+                col("partition_key").in_list(vec![lit(30u64), lit(3u64), lit(12u64)], false)
+            ]),
+            Some(3..=30)
+        );
+        assert_eq!(
+            partition_key_range(&[col("partition_key").in_list(vec![lit(3u64)], true)]),
+            Some(FULL_RANGE)
+        );
+    }
+}
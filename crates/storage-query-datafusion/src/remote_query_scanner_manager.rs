@@ -18,6 +18,7 @@ use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::execution::SendableRecordBatchStream;
 use restate_core::partitions::PartitionRouting;
 use restate_core::Metadata;
+use restate_partition_store::PartitionStoreManager;
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::NodeId;
 
@@ -76,19 +77,24 @@ pub trait PartitionLocator: Send + Sync + 'static {
     ) -> anyhow::Result<PartitionLocation>;
 }
 
+/// Locates the partitions on this node if it hosts a replica of them, be it the leader or a
+/// follower, and otherwise on the node of the leader.
 #[derive(Clone)]
 struct MetadataAwarePartitionLocator {
     partition_routing: PartitionRouting,
     metadata: Metadata,
+    local_partition_store_manager: Option<PartitionStoreManager>,
 }
 
 pub fn create_partition_locator(
     partition_routing: PartitionRouting,
     metadata: Metadata,
+    local_partition_store_manager: Option<PartitionStoreManager>,
 ) -> Arc<dyn PartitionLocator> {
     Arc::new(MetadataAwarePartitionLocator {
         partition_routing,
         metadata,
+        local_partition_store_manager,
     })
 }
 
@@ -97,6 +103,14 @@ impl PartitionLocator for MetadataAwarePartitionLocator {
         &self,
         partition_id: PartitionId,
     ) -> anyhow::Result<PartitionLocation> {
+        if self
+            .local_partition_store_manager
+            .as_ref()
+            .is_some_and(|manager| manager.has_partition_store(partition_id))
+        {
+            return Ok(PartitionLocation::Local);
+        }
+
         let my_node_id = self.metadata.my_node_id();
         match self.partition_routing.get_node_by_partition(partition_id) {
             None => {
@@ -0,0 +1,58 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::schema::SysScheduleBuilder;
+
+use crate::table_util::format_using;
+use restate_storage_api::schedule_table::ScheduleStatus;
+use restate_types::identifiers::WithPartitionKey;
+
+#[inline]
+pub(crate) fn append_schedule_row(
+    builder: &mut SysScheduleBuilder,
+    output: &mut String,
+    schedule_status: ScheduleStatus,
+) {
+    let ScheduleStatus {
+        schedule,
+        next_fire_time,
+        last_fire_time,
+        last_invocation_id,
+    } = schedule_status;
+    let target = &schedule.invocation.header.target;
+
+    let mut row = builder.row();
+    row.partition_key(schedule.partition_key());
+    if row.is_id_defined() {
+        row.id(format_using(output, &schedule.id));
+    }
+    if row.is_cron_defined() {
+        row.cron(format_using(output, &schedule.cron));
+    }
+    row.misfire_policy(<&'static str>::from(schedule.misfire_policy));
+    if row.is_target_defined() {
+        row.target(format_using(output, target));
+    }
+    row.target_service_name(target.service_name());
+    if let Some(key) = target.key() {
+        row.target_service_key(key);
+    }
+    row.target_handler_name(target.handler_name());
+    row.created_at(schedule.created_at.as_u64() as i64);
+    row.next_fire_time(next_fire_time.as_u64() as i64);
+    if let Some(last_fire_time) = last_fire_time {
+        row.last_fire_time(last_fire_time.as_u64() as i64);
+    }
+    if let Some(last_invocation_id) = last_invocation_id {
+        if row.is_last_invocation_id_defined() {
+            row.last_invocation_id(format_using(output, &last_invocation_id));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![allow(dead_code)]
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_schedule(
+    /// Internal column that is used for partitioning. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// Schedule ID. Use it to delete the schedule via the admin API.
+    id: DataType::LargeUtf8,
+
+    /// The cron expression of the schedule, including its timezone if not UTC.
+    cron: DataType::LargeUtf8,
+
+    /// What to do with the occurrences missed e.g. while the partition was unavailable, either
+    /// `skip`, `fire-once` or `fire-all`.
+    misfire_policy: DataType::LargeUtf8,
+
+    /// Invocation Target. Format for plain services: `ServiceName/HandlerName`, e.g.
+    /// `Greeter/greet`. Format for virtual objects/workflows: `VirtualObjectName/Key/HandlerName`,
+    /// e.g. `Greeter/Francesco/greet`.
+    target: DataType::LargeUtf8,
+
+    /// The name of the invoked service.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the virtual object or the workflow ID. Null for regular services.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The invoked handler.
+    target_handler_name: DataType::LargeUtf8,
+
+    /// Timestamp indicating when the schedule was created, or last replaced.
+    created_at: DataType::Date64,
+
+    /// Timestamp of the next invocation of the schedule.
+    next_fire_time: DataType::Date64,
+
+    /// Timestamp of the last invocation of the schedule. Null if the schedule didn't fire yet.
+    last_fire_time: DataType::Date64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the last invocation of the
+    /// schedule. Null if the schedule didn't fire yet.
+    last_invocation_id: DataType::LargeUtf8,
+));
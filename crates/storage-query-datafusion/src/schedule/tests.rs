@@ -0,0 +1,79 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use bytes::Bytes;
+use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::schedule_table::{ScheduleStatus, ScheduleTable};
+use restate_storage_api::Transaction;
+use restate_types::identifiers::{InvocationId, ScheduleId, WithPartitionKey};
+use restate_types::invocation::{InvocationRequest, InvocationRequestHeader, InvocationTarget};
+use restate_types::schedule::{MisfirePolicy, Schedule};
+use restate_types::time::MillisSinceEpoch;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_schedules() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let schedule = Schedule {
+        id: ScheduleId::generate(&invocation_target),
+        cron: "@hourly".parse().unwrap(),
+        misfire_policy: MisfirePolicy::Skip,
+        invocation: InvocationRequest::new(
+            InvocationRequestHeader::initialize(
+                InvocationId::mock_random(),
+                invocation_target.clone(),
+            ),
+            Bytes::new(),
+        ),
+        created_at: MillisSinceEpoch::new(0),
+    };
+    let schedule_id = schedule.id;
+
+    let mut tx = engine.partition_store().transaction();
+    tx.put_schedule(&ScheduleStatus {
+        schedule,
+        next_fire_time: MillisSinceEpoch::new(3_600_000),
+        last_fire_time: None,
+        last_invocation_id: None,
+    })
+    .await;
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT * FROM sys_schedule")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "partition_key" => UInt64Array: eq(schedule_id.partition_key()),
+                "id" => LargeStringArray: eq(schedule_id.to_string()),
+                "cron" => LargeStringArray: eq("@hourly".to_owned()),
+                "misfire_policy" => LargeStringArray: eq("skip".to_owned()),
+                "target_service_name" => LargeStringArray: eq(invocation_target.service_name().to_string()),
+                "target_handler_name" => LargeStringArray: eq(invocation_target.handler_name().to_string()),
+            }
+        ))
+    );
+}
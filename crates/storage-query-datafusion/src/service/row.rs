@@ -24,6 +24,7 @@ pub(crate) fn append_service_row(
     row.name(service_metadata.name);
     row.revision(service_metadata.revision as u64);
     row.public(service_metadata.public);
+    row.paused(service_metadata.paused);
     row.deployment_id(format_using(output, &service_metadata.deployment_id));
     row.ty(match service_metadata.ty {
         ServiceType::Service => "service",
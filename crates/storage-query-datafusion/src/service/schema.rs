@@ -24,6 +24,9 @@ define_table!(sys_service(
     /// Whether the service is accessible through the ingress endpoint or not.
     public: DataType::Boolean,
 
+    /// Whether the service is paused. New invocations of a paused service are enqueued without being executed, until the service is resumed.
+    paused: DataType::Boolean,
+
     /// The service type. Either `service` or `virtual_object` or `workflow`.
     ty: DataType::LargeUtf8,
 
@@ -9,8 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use crate::{
-    deployment, idempotency, inbox, invocation_state, invocation_status, journal,
-    keyed_service_status, promise, service, state,
+    consistency_violation, dead_letter, deployment, idempotency, inbox, invocation_history,
+    invocation_state, invocation_status, invocation_timeline, journal, keyed_service_status,
+    promise, schedule, service, state,
 };
 use std::borrow::Cow;
 
@@ -24,6 +25,11 @@ pub const ALL_TABLE_DOCS: &[StaticTableDocs] = &[
     inbox::schema::TABLE_DOCS,
     idempotency::schema::TABLE_DOCS,
     promise::schema::TABLE_DOCS,
+    dead_letter::schema::TABLE_DOCS,
+    invocation_history::schema::TABLE_DOCS,
+    invocation_timeline::schema::TABLE_DOCS,
+    schedule::schema::TABLE_DOCS,
+    consistency_violation::schema::TABLE_DOCS,
     service::schema::TABLE_DOCS,
     deployment::schema::TABLE_DOCS,
 ];
@@ -20,13 +20,18 @@ use datafusion::datasource::{TableProvider, TableType};
 use datafusion::execution::context::TaskContext;
 use datafusion::logical_expr::{Expr, TableProviderFilterPushDown};
 use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionMode, ExecutionPlan, Partitioning, PlanProperties,
     SendableRecordBatchStream,
 };
+use futures::{StreamExt, TryStreamExt};
 use restate_types::identifiers::{PartitionId, PartitionKey};
+use tracing::warn;
 
-use crate::context::SelectPartitions;
+use crate::context::{QueryWarnings, SelectPartitions};
+use crate::partition_filter::{intersect, partition_key_range};
 use crate::table_util::compute_ordering;
 
 pub trait ScanPartition: Send + Sync + Debug + 'static {
@@ -76,18 +81,31 @@ where
         &self,
         _state: &(dyn datafusion::catalog::Session),
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
+        filters: &[Expr],
         _limit: Option<usize>,
     ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
         let projected_schema = match projection {
             Some(p) => SchemaRef::new(self.schema.project(p)?),
             None => self.schema.clone(),
         };
-        let live_partitions = self
+
+        // prune the partitions which can't contain the partition keys matched by the filters
+        let Some(range) = partition_key_range(filters) else {
+            return Ok(Arc::new(EmptyExec::new(projected_schema)));
+        };
+        let live_partitions: Vec<_> = self
             .partition_selector
             .get_live_partitions()
             .await
-            .map_err(DataFusionError::External)?;
+            .map_err(DataFusionError::External)?
+            .into_iter()
+            .filter_map(|(partition_id, partition_range)| {
+                intersect(&partition_range, &range).map(|range| (partition_id, range))
+            })
+            .collect();
+        if live_partitions.is_empty() {
+            return Ok(Arc::new(EmptyExec::new(projected_schema)));
+        }
 
         let eq_properties = if let Some(ordering) = compute_ordering(projected_schema.clone()) {
             EquivalenceProperties::new_with_orderings(projected_schema.clone(), &[ordering])
@@ -124,7 +142,8 @@ where
 
 #[derive(Debug, Clone)]
 struct PartitionedExecutionPlan<T> {
-    live_partitions: Vec<PartitionId>,
+    /// The partitions to scan, each with the range of partition keys to scan
+    live_partitions: Vec<(PartitionId, RangeInclusive<PartitionKey>)>,
     projected_schema: SchemaRef,
     scanner: T,
     plan: PlanProperties,
@@ -170,23 +189,65 @@ where
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
-        // fake range until we can handle filters
-        let range = 0..=PartitionKey::MAX;
         // map df partitions to our partition ids by index.
-        let partition_id = self
+        let (partition_id, range) = self
             .live_partitions
             .get(partition)
             .expect("num_partitions within bounds");
-        let stream = self
-            .scanner
-            .scan_partition(*partition_id, range, self.projected_schema.clone())
-            .map_err(|e| DataFusionError::External(e.into()))?;
-        Ok(stream)
+        let partition_id = *partition_id;
+        let result =
+            self.scanner
+                .scan_partition(partition_id, range.clone(), self.projected_schema.clone());
+
+        let Some(warnings) = context.session_config().get_extension::<QueryWarnings>() else {
+            return result.map_err(|e| DataFusionError::External(e.into()));
+        };
+
+        // the query tolerates partial results, the failed partitions are skipped with a warning
+        let stream = match result {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn_partition_failure(&warnings, partition_id, err);
+                return Ok(Box::pin(RecordBatchStreamAdapter::new(
+                    self.projected_schema.clone(),
+                    futures::stream::empty(),
+                )));
+            }
+        };
+        // A partition is either part of the results as a whole or not at all, so its batches
+        // are buffered until it has been scanned completely. This trades memory for not
+        // returning the rows of a partition that failed midway.
+        let schema = stream.schema();
+        let stream = futures::stream::once(async move {
+            match stream.try_collect::<Vec<_>>().await {
+                Ok(batches) => batches,
+                Err(err) => {
+                    warn_partition_failure(&warnings, partition_id, err);
+                    vec![]
+                }
+            }
+        })
+        .flat_map(|batches| futures::stream::iter(batches.into_iter().map(Ok)));
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
     }
 }
 
+fn warn_partition_failure(
+    warnings: &QueryWarnings,
+    partition_id: PartitionId,
+    err: impl std::fmt::Display,
+) {
+    warn!(
+        restate.partition.id = %partition_id,
+        "Leaving the partition out of the query results, as scanning it failed: {err}"
+    );
+    warnings.push(format!(
+        "the results are partial, partition {partition_id} could not be scanned: {err}"
+    ));
+}
+
 impl<T> DisplayAs for PartitionedExecutionPlan<T>
 where
     T: std::fmt::Debug,
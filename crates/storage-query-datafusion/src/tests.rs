@@ -8,11 +8,18 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
+use async_trait::async_trait;
 use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
-use futures::StreamExt;
+use datafusion::common::DataFusionError;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::{StreamExt, TryStreamExt};
 use googletest::all;
 use googletest::prelude::{assert_that, eq};
 
@@ -23,15 +30,17 @@ use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
 };
 use restate_storage_api::Transaction;
-use restate_types::errors::InvocationError;
+use restate_types::errors::{GenericError, InvocationError};
 use restate_types::identifiers::LeaderEpoch;
-use restate_types::identifiers::PartitionId;
 use restate_types::identifiers::{DeploymentId, InvocationId};
+use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::invocation::InvocationTarget;
 use restate_types::journal::EntryType;
 
+use crate::context::SelectPartitions;
 use crate::mocks::*;
 use crate::row;
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
 
 #[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
 async fn query_sys_invocation() {
@@ -113,3 +122,103 @@ async fn query_sys_invocation() {
         ))
     );
 }
+
+#[derive(Clone, Debug)]
+struct TwoPartitionsSelector;
+
+#[async_trait]
+impl SelectPartitions for TwoPartitionsSelector {
+    async fn get_live_partitions(
+        &self,
+    ) -> Result<Vec<(PartitionId, RangeInclusive<PartitionKey>)>, GenericError> {
+        Ok(vec![
+            (PartitionId::from(0), 0..=99),
+            (PartitionId::from(1), 100..=PartitionKey::MAX),
+        ])
+    }
+}
+
+/// Yields one row per partition, and fails partition 1 after its first row.
+#[derive(Clone, Debug)]
+struct FailingSecondPartitionScanner;
+
+impl ScanPartition for FailingSecondPartitionScanner {
+    fn scan_partition(
+        &self,
+        partition_id: PartitionId,
+        _range: RangeInclusive<PartitionKey>,
+        projection: SchemaRef,
+    ) -> anyhow::Result<SendableRecordBatchStream> {
+        let batch = RecordBatch::try_new(
+            projection.clone(),
+            vec![Arc::new(UInt64Array::from(vec![u64::from(u16::from(
+                partition_id,
+            ))]))],
+        )?;
+        let mut batches = vec![Ok(batch)];
+        if partition_id == PartitionId::from(1) {
+            batches.push(Err(DataFusionError::Execution(
+                "partition unavailable".to_owned(),
+            )));
+        }
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            projection,
+            futures::stream::iter(batches),
+        )))
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn partial_results_leave_out_failed_partitions() {
+    let engine = MockQueryEngine::create().await;
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "partition",
+        DataType::UInt64,
+        false,
+    )]));
+    engine
+        .context()
+        .register_partitioned_table(
+            "partitions",
+            Arc::new(PartitionedTableProvider::new(
+                TwoPartitionsSelector,
+                schema,
+                FailingSecondPartitionScanner,
+            )),
+        )
+        .unwrap();
+
+    let (stream, warnings) = engine
+        .context()
+        .execute_with_partial_results("SELECT partition FROM partitions")
+        .await
+        .unwrap();
+    let batches = stream.try_collect::<Vec<_>>().await.unwrap();
+
+    let partitions: Vec<u64> = batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap()
+                .values()
+                .to_vec()
+        })
+        .collect();
+    assert_eq!(partitions, vec![0]);
+
+    let warnings = warnings.get();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("partition 1"), "{warnings:?}");
+
+    // without partial results, the failure fails the query
+    let result = engine
+        .execute("SELECT partition FROM partitions")
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await;
+    assert!(result.is_err());
+}
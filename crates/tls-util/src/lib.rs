@@ -0,0 +1,66 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! This crate contains utils to load the PEM encoded certificates and private keys configured for
+//! TLS.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PemError {
+    #[error("failed to read '{}': {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("no certificate found in '{}'", .0.display())]
+    NoCertificate(PathBuf),
+    #[error("no private key found in '{}'", .0.display())]
+    NoPrivateKey(PathBuf),
+}
+
+/// Reads all certificates of a PEM file, failing if it contains none.
+pub fn read_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, PemError> {
+    let certificates = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| PemError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+
+    if certificates.is_empty() {
+        return Err(PemError::NoCertificate(path.to_owned()));
+    }
+    Ok(certificates)
+}
+
+/// Reads the first private key of a PEM file.
+pub fn read_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, PemError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| PemError::Read {
+            path: path.to_owned(),
+            source,
+        })?
+        .ok_or_else(|| PemError::NoPrivateKey(path.to_owned()))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, PemError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| PemError::Read {
+            path: path.to_owned(),
+            source,
+        })
+}
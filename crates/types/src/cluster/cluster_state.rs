@@ -9,7 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::collections::BTreeMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use prost_dto::IntoProto;
 use serde::{Deserialize, Serialize};
@@ -74,6 +74,10 @@ fn instant_to_proto(t: Instant) -> prost_types::Duration {
     t.elapsed().try_into().unwrap()
 }
 
+fn duration_to_proto(d: Duration) -> prost_types::Duration {
+    d.try_into().unwrap_or_default()
+}
+
 #[derive(Debug, Clone, IntoProto)]
 #[proto(target = "crate::protobuf::cluster::NodeState", oneof = "state")]
 pub enum NodeState {
@@ -90,6 +94,29 @@ pub struct AliveNode {
     #[proto(required)]
     pub generational_node_id: GenerationalNodeId,
     pub partitions: BTreeMap<PartitionId, PartitionProcessorStatus>,
+    #[proto(required)]
+    pub load: NodeLoad,
+}
+
+/// Load of a node, as reported by the node itself.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, IntoProto)]
+#[proto(target = "crate::protobuf::cluster::NodeLoad")]
+pub struct NodeLoad {
+    /// Relative capacity of the node for running partition processor leaders, see
+    /// [`WorkerOptions::capacity`](crate::config::WorkerOptions::capacity).
+    pub capacity: u16,
+    /// Fraction of the CPUs available to the node's cgroup which is busy, averaged since the
+    /// previous report. Not set if the platform doesn't report it.
+    pub cpu_usage: Option<f32>,
+}
+
+impl Default for NodeLoad {
+    fn default() -> Self {
+        Self {
+            capacity: 1,
+            cpu_usage: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, IntoProto)]
@@ -143,6 +170,22 @@ pub struct PartitionProcessorStatus {
     pub last_archived_log_lsn: Option<Lsn>,
     // Set if replay_status is CatchingUp
     pub target_tail_lsn: Option<Lsn>,
+    /// Last observed tail of the partition's log, refreshed periodically
+    #[serde(default)]
+    pub log_tail_lsn: Option<Lsn>,
+    /// Creation time of the last applied record
+    #[serde(default)]
+    pub last_record_timestamp: Option<MillisSinceEpoch>,
+    /// How far behind the log tail the partition processor is, measured as the age of the last
+    /// applied record. Zero if the processor has applied all the records up to the log tail.
+    #[serde(default)]
+    #[into_proto(map = "duration_to_proto")]
+    pub apply_lag: Option<Duration>,
+    #[serde(default)]
+    pub num_inboxed_invocations: u64,
+    /// Number of invocations currently held by the invoker of this partition
+    #[serde(default)]
+    pub invoker_queue_depth: u64,
 }
 
 impl Default for PartitionProcessorStatus {
@@ -160,6 +203,11 @@ impl Default for PartitionProcessorStatus {
             last_persisted_log_lsn: None,
             last_archived_log_lsn: None,
             target_tail_lsn: None,
+            log_tail_lsn: None,
+            last_record_timestamp: None,
+            apply_lag: None,
+            num_inboxed_invocations: 0,
+            invoker_queue_depth: 0,
         }
     }
 }
@@ -192,6 +192,10 @@ impl SchedulingPlanBuilder {
         self.inner.partition_ids()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&PartitionId, &TargetPartitionState)> {
+        self.inner.iter()
+    }
+
     pub fn contains_partition(&self, partition_id: &PartitionId) -> bool {
         self.inner.partitions.contains_key(partition_id)
     }
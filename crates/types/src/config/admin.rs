@@ -10,6 +10,7 @@
 
 use super::QueryEngineOptions;
 use crate::cluster_controller::ReplicationStrategy;
+use restate_serde_util::NonZeroByteCount;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::net::SocketAddr;
@@ -59,6 +60,14 @@ pub struct AdminOptions {
     /// operations.
     pub log_trim_threshold: u64,
 
+    /// # Log trim retention
+    ///
+    /// Number of log entries to retain below the safe trim point. The safe trim point of a
+    /// partition's log is the latest LSN covered by a snapshot in the snapshot repository or, if
+    /// no snapshot is available, the minimum durably applied LSN reported by all nodes running the
+    /// partition processor. Retaining additional entries gives lagging readers some slack.
+    pub log_trim_retention: u64,
+
     /// # Log Tail Update interval
     ///
     /// Controls the interval at which cluster controller tries to refind the tails of logs. This
@@ -73,6 +82,71 @@ pub struct AdminOptions {
     /// processors.
     pub default_replication_strategy: ReplicationStrategy,
 
+    /// # Leadership rebalancing
+    ///
+    /// Enables moving partition leaderships between the alive worker nodes, so that every node
+    /// leads a number of partitions proportional to its capacity. Leaderships are only moved to
+    /// caught up followers on nodes which aren't overloaded. Default: false.
+    pub leadership_rebalancing: bool,
+
+    /// # Leadership rebalancing interval
+    ///
+    /// Minimum interval between two rounds of leadership rebalancing.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub leadership_rebalancing_interval: humantime::Duration,
+
+    /// # Leadership rebalancing max moves
+    ///
+    /// Maximum number of leaderships moved in a single round of leadership rebalancing. Every
+    /// move makes the partition briefly unavailable while the new leader takes over.
+    pub leadership_rebalancing_max_moves: NonZeroUsize,
+
+    /// # Debug endpoints
+    ///
+    /// Enables admin API endpoints which bypass the regular validation, such as injecting
+    /// synthetic invocations directly into a partition's log. Only meant for testing.
+    pub enable_debug_endpoints: bool,
+
+    /// # Static deployment descriptor
+    ///
+    /// Path to a YAML file declaring deployments, service options and subscriptions. On startup,
+    /// the admin service registers every declared deployment and subscription which doesn't exist
+    /// yet, updates the ones whose options differ from the file, and applies the service options.
+    /// Existing deployments and subscriptions which are not declared in the file are left
+    /// untouched.
+    pub static_descriptor: Option<PathBuf>,
+
+    /// # Audit log
+    ///
+    /// Records every mutation done through the Admin APIs, with the identity of the caller and a
+    /// summary of the state before and after the change, as JSON lines in the `audit` directory
+    /// of the node's base directory.
+    pub audit_log_enabled: bool,
+
+    /// # Audit log caller header
+    ///
+    /// Request header carrying the identity of the caller, recorded in the audit log. It's meant
+    /// to be set by the authenticating proxy in front of the Admin APIs, as the Admin APIs don't
+    /// authenticate the callers themselves.
+    ///
+    /// The header is recorded as sent, hence it identifies the caller only if the Admin APIs are
+    /// reachable exclusively through a trusted proxy which overwrites it. Otherwise any caller can
+    /// set it to impersonate someone else.
+    pub audit_log_caller_header: String,
+
+    /// # Audit log max file size
+    ///
+    /// Size after which the audit log file is rotated.
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    #[serde_as(as = "NonZeroByteCount")]
+    pub audit_log_max_file_size: NonZeroUsize,
+
+    /// # Audit log retained files
+    ///
+    /// Number of rotated audit log files to retain, besides the one being written.
+    pub audit_log_max_files: usize,
+
     #[cfg(any(test, feature = "test-util"))]
     pub disable_cluster_controller: bool,
 }
@@ -82,6 +156,15 @@ impl AdminOptions {
         super::data_dir("registry")
     }
 
+    pub fn audit_log_dir(&self) -> PathBuf {
+        super::data_dir("audit")
+    }
+
+    /// Directory the partition snapshots inspected through the admin api are imported into.
+    pub fn snapshot_queries_dir(&self) -> PathBuf {
+        super::data_dir("snapshot-queries")
+    }
+
     pub fn concurrent_api_requests_limit(&self) -> usize {
         std::cmp::min(
             self.concurrent_api_requests_limit
@@ -110,7 +193,17 @@ impl Default for AdminOptions {
             // try to trim the log every hour
             log_trim_interval: Some(Duration::from_secs(60 * 60).into()),
             log_trim_threshold: 1000,
+            log_trim_retention: 0,
             default_replication_strategy: ReplicationStrategy::OnAllNodes,
+            leadership_rebalancing: false,
+            leadership_rebalancing_interval: Duration::from_secs(10).into(),
+            leadership_rebalancing_max_moves: NonZeroUsize::new(2).unwrap(),
+            enable_debug_endpoints: false,
+            static_descriptor: None,
+            audit_log_enabled: true,
+            audit_log_caller_header: "x-forwarded-user".to_owned(),
+            audit_log_max_file_size: NonZeroUsize::new(64 * 1024 * 1024).unwrap(), // 64MiB
+            audit_log_max_files: 10,
             #[cfg(any(test, feature = "test-util"))]
             disable_cluster_controller: false,
             log_tail_update_interval: Duration::from_secs(5 * 60).into(),
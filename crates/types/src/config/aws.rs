@@ -29,6 +29,9 @@ pub struct AwsOptions {
     ///
     /// An external ID to apply to any AssumeRole operations taken by this client.
     /// https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles_create_for-user_externalid.html
+    /// The external ID is the same for all the deployments on purpose: whoever registers a Lambda
+    /// deployment can choose the role to assume, but not the external ID the role trusts, which
+    /// protects against the confused deputy problem.
     /// Can be overridden by the `AWS_EXTERNAL_ID` environment variable.
     pub aws_assume_role_external_id: Option<String>,
 }
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -15,13 +16,14 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use restate_serde_util::{ByteCount, NonZeroByteCount};
+use restate_serde_util::{ByteCount, NonZeroByteCount, NonZeroDurationString};
 use tracing::warn;
 
 use crate::logs::metadata::ProviderKind;
+use crate::logs::LogId;
 use crate::retries::RetryPolicy;
 
-use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use super::{CommonOptions, ObjectStoreOptions, RocksDbOptions, RocksDbOptionsBuilder};
 
 /// # Bifrost options
 #[serde_as]
@@ -32,6 +34,10 @@ use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
 #[builder(default)]
 pub struct BifrostOptions {
     /// # The default kind of loglet to be used
+    ///
+    /// Used when provisioning new logs, unless the logs configuration in the metadata store
+    /// specifies a provider. Existing logs are only moved to a different provider through the
+    /// logs configuration, e.g. by setting the provider of a log through the admin API.
     pub default_provider: ProviderKind,
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     /// Configuration of local loglet provider
@@ -72,6 +78,12 @@ pub struct BifrostOptions {
     /// Defaults: 20M
     #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
     pub record_cache_memory_size: ByteCount,
+
+    /// # Read-ahead records
+    ///
+    /// Number of records that readers replaying a log, like partition processors, read and
+    /// decode ahead of processing them.
+    pub read_ahead_records: NonZeroUsize,
 }
 
 impl BifrostOptions {
@@ -102,6 +114,7 @@ impl Default for BifrostOptions {
             append_retry_max_interval: Duration::from_secs(1).into(),
             seal_retry_interval: Duration::from_secs(2).into(),
             record_cache_memory_size: 20_000_000u64.into(), // 20MB
+            read_ahead_records: NonZeroUsize::new(512).unwrap(),
         }
     }
 }
@@ -131,8 +144,36 @@ pub struct LocalLogletOptions {
     rocksdb_memory_ratio: f32,
 
     /// Disable fsync of WAL on every batch
+    ///
+    /// Deprecated, use `fsync-policy = "os-buffered"` instead. If set, logs without a
+    /// `fsync-policy-overrides` entry use the `os-buffered` policy.
     rocksdb_disable_wal_fsync: bool,
 
+    /// # Fsync policy
+    ///
+    /// Durability guarantee of acknowledged appends. See [`FsyncPolicy`] for the trade-offs of
+    /// the available policies. Appends of logs with different policies committed together are
+    /// synced according to the strictest policy among them.
+    pub fsync_policy: FsyncPolicy,
+
+    /// # Fsync policy overrides
+    ///
+    /// Overrides of `fsync-policy` for individual logs, keyed by log id.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde_as(as = "HashMap<serde_with::DisplayFromStr, _>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "HashMap<String, FsyncPolicy>"))]
+    pub fsync_policy_overrides: HashMap<u32, FsyncPolicy>,
+
+    /// # Fsync interval
+    ///
+    /// Interval at which appends of logs using the `interval` fsync policy are synced to disk.
+    /// This bounds the window of acknowledged appends which can be lost if the machine crashes.
+    ///
+    /// Must not be zero. Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "NonZeroDurationString")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub fsync_interval: humantime::Duration,
+
     /// Whether to perform commits in background IO thread pools eagerly or not
     #[cfg_attr(feature = "schemars", schemars(skip))]
     #[serde(skip_serializing_if = "std::ops::Not::not", default)]
@@ -143,10 +184,50 @@ pub struct LocalLogletOptions {
     /// Set to 0 or 1 to commit the write batch on every command.
     pub writer_batch_commit_count: usize,
 
-    /// Trigger a commit when the time since the last commit exceeds this threshold.
+    /// Trigger a commit when the size of the write batch exceeds this threshold.
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    #[serde_as(as = "NonZeroByteCount")]
+    pub writer_batch_commit_size: NonZeroUsize,
+
+    /// # Group commit window
+    ///
+    /// Appends of all logs which arrive within this window after the first append of a batch are
+    /// committed together with a single write and WAL sync, unless the batch reaches
+    /// `writer-batch-commit-count` or `writer-batch-commit-size` earlier. Larger windows increase
+    /// throughput under load at the cost of append latency.
+    ///
+    /// Set to 0 to only group appends which are already waiting to be committed.
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub writer_batch_commit_duration: humantime::Duration,
+
+    /// # Object store tiering
+    ///
+    /// Offloading of trimmed records to an object store.
+    pub tiering: LocalLogletTieringOptions,
+
+    /// # Record checksums
+    ///
+    /// Store a CRC32C checksum alongside every appended record, which is verified whenever the
+    /// record is read. Records written with a checksum can't be read by Restate versions which
+    /// predate this option, hence enable it only once all nodes of the cluster have been upgraded.
+    pub record_checksums: bool,
+
+    /// # Corruption policy
+    ///
+    /// How readers react to records which fail checksum verification or can't be decoded.
+    pub corruption_policy: CorruptionPolicy,
+
+    /// # Corruption policy overrides
+    ///
+    /// Overrides of `corruption-policy` for individual logs, keyed by log id.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde_as(as = "HashMap<serde_with::DisplayFromStr, _>")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "HashMap<String, CorruptionPolicy>")
+    )]
+    pub corruption_policy_overrides: HashMap<u32, CorruptionPolicy>,
 }
 
 impl LocalLogletOptions {
@@ -183,6 +264,23 @@ impl LocalLogletOptions {
     pub fn data_dir(&self) -> PathBuf {
         super::data_dir("local-loglet")
     }
+
+    pub fn fsync_policy(&self, log_id: LogId) -> FsyncPolicy {
+        if let Some(policy) = self.fsync_policy_overrides.get(&u32::from(log_id)) {
+            *policy
+        } else if self.rocksdb_disable_wal_fsync {
+            FsyncPolicy::OsBuffered
+        } else {
+            self.fsync_policy
+        }
+    }
+
+    pub fn corruption_policy(&self, log_id: LogId) -> CorruptionPolicy {
+        self.corruption_policy_overrides
+            .get(&u32::from(log_id))
+            .copied()
+            .unwrap_or(self.corruption_policy)
+    }
 }
 
 impl Default for LocalLogletOptions {
@@ -197,9 +295,102 @@ impl Default for LocalLogletOptions {
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.5,
             writer_batch_commit_count: 5000,
+            writer_batch_commit_size: NonZeroUsize::new(4 * 1024 * 1024).unwrap(),
             writer_batch_commit_duration: Duration::ZERO.into(),
             rocksdb_disable_wal_fsync: false,
+            fsync_policy: FsyncPolicy::default(),
+            fsync_policy_overrides: HashMap::default(),
+            fsync_interval: Duration::from_secs(1).into(),
             always_commit_in_background: false,
+            tiering: LocalLogletTieringOptions::default(),
+            record_checksums: false,
+            corruption_policy: CorruptionPolicy::default(),
+            corruption_policy_overrides: HashMap::default(),
+        }
+    }
+}
+
+/// # Fsync policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum FsyncPolicy {
+    /// # Always
+    ///
+    /// Sync the WAL to disk before acknowledging every batch of appends. Acknowledged appends
+    /// survive process and machine crashes.
+    #[default]
+    Always,
+    /// # Interval
+    ///
+    /// Acknowledge appends once they are written to the OS, and sync the WAL to disk every
+    /// `fsync-interval`. Acknowledged appends survive process crashes, but a machine crash can
+    /// lose the appends of the last interval.
+    Interval,
+    /// # OS buffered
+    ///
+    /// Never sync the WAL explicitly and leave flushing to the OS. Acknowledged appends survive
+    /// process crashes, but a machine crash can lose an unbounded amount of appends. Only meant
+    /// for development and environments where losing data is acceptable.
+    OsBuffered,
+}
+
+/// # Corruption policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum CorruptionPolicy {
+    /// # Halt
+    ///
+    /// Fail the reader on the first corrupted record.
+    #[default]
+    Halt,
+    /// # Skip
+    ///
+    /// Skip corrupted records and report them in the logs and metrics. Readers won't observe the
+    /// skipped records at all, use this only to recover logs whose lost records can be tolerated.
+    Skip,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "LocalLogletTiering", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct LocalLogletTieringOptions {
+    /// # Tiering destination
+    ///
+    /// Base URL under which records are stored before they are trimmed from the local loglet,
+    /// e.g. `s3://bucket/bifrost`. Supported schemes are `s3://`, `gs://` and `file://`. Readers
+    /// requesting offsets below the local trim point are transparently served from this
+    /// destination.
+    ///
+    /// Default: `None` - trimmed records are discarded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// # Chunk size
+    ///
+    /// Upper bound of the size of a single object uploaded to the destination. Trimmed ranges
+    /// which exceed it are split into multiple objects.
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    #[serde_as(as = "NonZeroByteCount")]
+    pub chunk_size: NonZeroUsize,
+
+    /// # Object store options
+    ///
+    /// Credentials and connection settings for the tiering destination.
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+}
+
+impl Default for LocalLogletTieringOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            chunk_size: NonZeroUsize::new(32 * 1024 * 1024).unwrap(),
+            object_store: ObjectStoreOptions::default(),
         }
     }
 }
@@ -18,9 +18,10 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use restate_serde_util::{NonZeroByteCount, SerdeableHeaderHashMap};
+use restate_serde_util::{NonZeroByteCount, NonZeroDurationString, SerdeableHeaderHashMap};
 
 use super::{AwsOptions, HttpOptions, PerfStatsLevel, RocksDbOptions};
+use crate::locality::NodeLocation;
 use crate::net::{AdvertisedAddress, BindAddress};
 use crate::nodes_config::Role;
 use crate::retries::RetryPolicy;
@@ -43,8 +44,21 @@ pub struct CommonOptions {
     ///
     /// Unique name for this node in the cluster. The node must not change unless
     /// it's started with empty local store. It defaults to the node's hostname.
+    ///
+    /// Can reference environment variables as `${NAME}`, e.g. `${POD_NAME}` when exposing the
+    /// pod name through the Kubernetes downward API.
     node_name: Option<String>,
 
+    /// # Node location
+    ///
+    /// Failure domain of this node in the form of `region[.zone]`, e.g. `us-east-1.us-east-1a`.
+    /// Leave it empty if the location of the node is unknown.
+    ///
+    /// Can reference environment variables as `${NAME}`, e.g. `${REGION}.${ZONE}`.
+    #[serde(default, skip_serializing_if = "NodeLocation::is_empty")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub location: NodeLocation,
+
     /// If set, the node insists on acquiring this node ID.
     pub force_node_id: Option<PlainNodeId>,
 
@@ -73,9 +87,21 @@ pub struct CommonOptions {
     pub bind_address: Option<BindAddress>,
 
     /// Address that other nodes will use to connect to this node. Default is `http://127.0.0.1:5122/`
+    ///
+    /// Can reference environment variables as `${NAME}`, e.g.
+    /// `http://${POD_NAME}.restate-cluster:5122/` for the pods of a StatefulSet.
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub advertised_address: AdvertisedAddress,
 
+    /// # DNS discovery
+    ///
+    /// Discovers the nodes of the cluster by resolving a DNS name to the addresses of all of
+    /// them, e.g. the headless service of a Kubernetes StatefulSet. When set, the node advertises
+    /// its own address among the resolved ones instead of `advertised-address`, and a node which
+    /// is not allowed to bootstrap waits for the cluster to be bootstrapped before joining it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_discovery: Option<DnsDiscoveryOptions>,
+
     /// # Partitions
     ///
     /// Number of partitions that will be provisioned during cluster bootstrap,
@@ -90,12 +116,32 @@ pub struct CommonOptions {
     /// # Shutdown grace timeout
     ///
     /// This timeout is used when shutting down the various Restate components to drain all the internal queues.
+    /// It bounds the last shutdown phase, which stops the components that don't belong to any of
+    /// the phases configured in `shutdown-phases`, like the networking and the metadata store.
     ///
     /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub shutdown_timeout: humantime::Duration,
 
+    /// # Shutdown phases
+    ///
+    /// Deadlines of the phases in which a node drains its components on shutdown.
+    pub shutdown_phases: ShutdownPhasesOptions,
+
+    /// # Runtime shutdown timeout
+    ///
+    /// Maximum time a dedicated runtime, like the one of a partition processor, may take to stop
+    /// after it has been asked to shut down. Once exceeded, the runtime is forcefully torn down so
+    /// that a single stuck partition cannot hold up the shutdown of the whole node. This is the
+    /// default for runtimes which are not started with their own shutdown timeout. It should be
+    /// lower than `shutdown-timeout`.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub runtime_shutdown_timeout: humantime::Duration,
+
     /// # Default async runtime thread pool
     ///
     /// Size of the default thread pool used to perform internal tasks.
@@ -103,6 +149,13 @@ pub struct CommonOptions {
     #[builder(setter(strip_option))]
     default_thread_pool_size: Option<usize>,
 
+    /// # Dedicated runtimes
+    ///
+    /// Resource budgets of the runtimes running the tasks of the partition processors, invokers,
+    /// ingress and metadata store, so that a busy role cannot starve the others. Changes are
+    /// applied on restart.
+    pub runtimes: TaskRuntimesOptions,
+
     #[serde(flatten)]
     pub tracing: TracingOptions,
 
@@ -347,6 +400,7 @@ impl Default for CommonOptions {
             //   see "roles_compat_test" test below.
             roles: EnumSet::all() - Role::LogServer - Role::HttpIngress,
             node_name: None,
+            location: NodeLocation::default(),
             force_node_id: None,
             cluster_name: "localcluster".to_owned(),
             // boot strap the cluster by default. This is very likely to change in the future to be
@@ -357,11 +411,15 @@ impl Default for CommonOptions {
             metadata_store_client: MetadataStoreClientOptions::default(),
             bind_address: None,
             advertised_address: AdvertisedAddress::from_str("http://127.0.0.1:5122/").unwrap(),
+            dns_discovery: None,
+            runtimes: TaskRuntimesOptions::default(),
             bootstrap_num_partitions: NonZeroU16::new(24).unwrap(),
             histogram_inactivity_timeout: None,
             disable_prometheus: false,
             service_client: Default::default(),
             shutdown_timeout: Duration::from_secs(60).into(),
+            shutdown_phases: ShutdownPhasesOptions::default(),
+            runtime_shutdown_timeout: Duration::from_secs(30).into(),
             tracing: TracingOptions::default(),
             log_filter: "warn,restate=info".to_string(),
             log_format: Default::default(),
@@ -440,6 +498,185 @@ pub enum LogFormat {
     Json,
 }
 
+/// # DNS discovery options
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct DnsDiscoveryOptions {
+    /// # Name
+    ///
+    /// DNS name resolving to the addresses of all the nodes, e.g.
+    /// `restate-cluster.restate.svc.cluster.local`. For Kubernetes, the headless service must
+    /// publish the addresses of the pods which are not ready yet.
+    pub name: String,
+
+    /// # Port
+    ///
+    /// Port of the node server of the resolved nodes.
+    #[serde(default = "DnsDiscoveryOptions::default_port")]
+    pub port: u16,
+
+    /// # Refresh interval
+    ///
+    /// Interval at which the DNS name is resolved again. Must not be zero.
+    #[serde(default = "DnsDiscoveryOptions::default_refresh_interval")]
+    #[serde_as(as = "NonZeroDurationString")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub refresh_interval: humantime::Duration,
+}
+
+impl DnsDiscoveryOptions {
+    fn default_port() -> u16 {
+        5122
+    }
+
+    fn default_refresh_interval() -> humantime::Duration {
+        Duration::from_secs(10).into()
+    }
+}
+
+/// # Dedicated runtimes options
+///
+/// Roles without a configured runtime run their tasks as before: the ingress on its own runtime
+/// sized like the default one, partition processors on a single-threaded runtime per partition,
+/// and invokers and the metadata store on the runtime they are spawned from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct TaskRuntimesOptions {
+    /// # Partition processors
+    ///
+    /// Applies to the single-threaded runtime of every partition processor, whose number of
+    /// worker threads can't be changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_processor: Option<RuntimeOptions>,
+
+    /// # Invokers
+    ///
+    /// Runs the invokers of all partitions on a shared dedicated runtime, instead of the runtime
+    /// of their partition processor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoker: Option<RuntimeOptions>,
+
+    /// # Ingress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingress: Option<RuntimeOptions>,
+
+    /// # Metadata store
+    ///
+    /// Runs the metadata store on a dedicated runtime, instead of the runtime of the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_store: Option<RuntimeOptions>,
+}
+
+/// # Runtime options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct RuntimeOptions {
+    /// # Worker threads
+    ///
+    /// Number of threads running the async tasks of the runtime. If not set, it defaults to the
+    /// number of CPU cores the runtime is pinned to or, if not pinned, to the number of CPU cores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_threads: Option<NonZeroUsize>,
+
+    /// # Max blocking threads
+    ///
+    /// Maximum number of threads running the blocking operations of the runtime, like file
+    /// system accesses. If not set, tokio's default of 512 applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_blocking_threads: Option<NonZeroUsize>,
+
+    /// # CPU cores
+    ///
+    /// Ids of the CPU cores the threads of the runtime are pinned to. The threads are not pinned
+    /// if empty. Only supported on Linux.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_cores: Vec<usize>,
+}
+
+impl RuntimeOptions {
+    pub fn worker_threads(&self) -> usize {
+        self.worker_threads
+            .map(NonZeroUsize::get)
+            .unwrap_or_else(|| {
+                if self.cpu_cores.is_empty() {
+                    std::thread::available_parallelism()
+                        // Shouldn't really fail, but just in case.
+                        .unwrap_or(NonZeroUsize::new(4).unwrap())
+                        .get()
+                } else {
+                    self.cpu_cores.len()
+                }
+            })
+    }
+}
+
+/// # Shutdown phases options
+///
+/// A node drains its components in phases: first the ingress, then the invokers, the partition
+/// processors, bifrost, the remaining components and finally RocksDB. Once the deadline of a
+/// phase is exceeded, the shutdown moves on to the next phase without waiting for the lingering
+/// tasks, which are logged.
+///
+/// The deadlines can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+#[serde(default)]
+pub struct ShutdownPhasesOptions {
+    /// # Ingress timeout
+    ///
+    /// Time to stop accepting requests through the HTTP and Kafka ingress.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub ingress_timeout: humantime::Duration,
+
+    /// # Invoker timeout
+    ///
+    /// Time to stop the invokers and their in-flight invocations.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub invoker_timeout: humantime::Duration,
+
+    /// # Partition processors timeout
+    ///
+    /// Time to stop the partition processors. A single partition processor is forcefully torn
+    /// down after `runtime-shutdown-timeout`.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub partition_processors_timeout: humantime::Duration,
+
+    /// # Bifrost timeout
+    ///
+    /// Time to flush the pending appends of bifrost and stop its loglets.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub bifrost_timeout: humantime::Duration,
+
+    /// # RocksDB timeout
+    ///
+    /// Time to flush the memtables of the RocksDB databases and close them.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub rocksdb_timeout: humantime::Duration,
+}
+
+impl Default for ShutdownPhasesOptions {
+    fn default() -> Self {
+        Self {
+            ingress_timeout: Duration::from_secs(10).into(),
+            invoker_timeout: Duration::from_secs(10).into(),
+            partition_processors_timeout: Duration::from_secs(35).into(),
+            bifrost_timeout: Duration::from_secs(20).into(),
+            rocksdb_timeout: Duration::from_secs(20).into(),
+        }
+    }
+}
+
 /// # Service Client options
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
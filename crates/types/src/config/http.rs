@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -35,7 +37,8 @@ pub struct HttpOptions {
     /// # Proxy URI
     ///
     /// A URI, such as `http://127.0.0.1:10001`, of a server to which all invocations should be sent, with the `Host` header set to the deployment URI.
-    /// HTTPS proxy URIs are supported, but only HTTP endpoint traffic will be proxied currently.
+    /// Traffic to HTTPS endpoints is tunneled through the proxy using `CONNECT`, hence the deployment certificates are still verified end to end.
+    /// HTTPS proxy URIs are supported, but only HTTP endpoint traffic will be proxied through them currently.
     /// Can be overridden by the `HTTP_PROXY` environment variable.
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub http_proxy: Option<ProxyUri>,
@@ -56,6 +59,20 @@ pub struct HttpOptions {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub connect_timeout: humantime::Duration,
+
+    /// # Additional root certificates
+    ///
+    /// Paths to PEM files containing CA certificates to trust when connecting to deployments
+    /// over TLS, in addition to the native root certificates of the system. Use this to reach
+    /// deployments whose certificates are issued by a private CA.
+    pub additional_root_certificates: Vec<PathBuf>,
+
+    /// # TLS client certificates
+    ///
+    /// Client certificates to present to deployments requiring mutual TLS, keyed by the host
+    /// name of the deployment, e.g. `greeter.internal`. Deployments on other hosts are
+    /// contacted without a client certificate.
+    pub tls_client_certificates: HashMap<String, TlsClientCertificate>,
 }
 
 impl Default for HttpOptions {
@@ -65,6 +82,8 @@ impl Default for HttpOptions {
             http_proxy: None,
             no_proxy: Vec::new(),
             connect_timeout: HttpOptions::default_connect_timeout(),
+            additional_root_certificates: Vec::new(),
+            tls_client_certificates: HashMap::new(),
         }
     }
 }
@@ -76,6 +95,25 @@ impl HttpOptions {
     }
 }
 
+/// # TLS client certificate
+///
+/// Certificate and private key used to authenticate with a deployment requiring mutual TLS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsClientCertificate {
+    /// # Certificate file
+    ///
+    /// Path to a PEM file containing the client certificate, optionally followed by its
+    /// intermediate certificates.
+    pub certificate_file: PathBuf,
+
+    /// # Private key file
+    ///
+    /// Path to a PEM file containing the private key of the client certificate.
+    pub private_key_file: PathBuf,
+}
+
 /// # HTTP/2 Keep alive options
 ///
 /// Configuration for the HTTP/2 keep-alive mechanism, using PING frames.
@@ -189,6 +227,10 @@ impl ProxyUri {
         }
     }
 
+    pub fn as_uri(&self) -> &Uri {
+        &self.uri
+    }
+
     pub fn dst(&self, dst: Uri) -> Uri {
         // only proxy non TLS traffic, otherwise just pass through directly to underlying connector
         if dst.scheme() != Some(&Scheme::HTTPS) {
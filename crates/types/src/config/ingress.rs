@@ -8,13 +8,19 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
+use std::fmt;
 use std::net::SocketAddr;
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use tokio::sync::Semaphore;
 
 use super::KafkaClusterOptions;
+use crate::net::BindAddress;
 
 /// # Ingress options
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
@@ -29,6 +35,14 @@ pub struct IngressOptions {
     /// The address to bind for the ingress.
     pub bind_address: SocketAddr,
 
+    /// # Additional listeners
+    ///
+    /// Additional addresses the ingress listens on, next to `bind-address`. Listeners can either
+    /// be TCP socket addresses or Unix domain sockets in the form `unix:/path/to/socket`, which is
+    /// useful to serve sidecar-local traffic. Unless `auth` is enabled, each listener can require
+    /// its own bearer token.
+    pub additional_listeners: Vec<IngressListenerOptions>,
+
     /// # Concurrency limit
     ///
     /// Local concurrency limit to use to limit the amount of concurrent requests. If exceeded,
@@ -37,6 +51,36 @@ pub struct IngressOptions {
 
     kafka_clusters: Vec<KafkaClusterOptions>,
 
+    /// # Service level objectives
+    ///
+    /// In-process tracking of service level objectives for the requests served by this ingress.
+    pub slo: SloOptions,
+
+    /// # Authentication
+    ///
+    /// Authentication and authorization of the requests received by the ingress, using static API
+    /// keys and/or JWT bearer tokens. Disabled unless API keys or JWT validation are configured.
+    /// If enabled, it applies to all the listeners, which then can't set their own bearer token.
+    pub auth: IngressAuthOptions,
+
+    /// # Rate limits
+    ///
+    /// Admission control of the requests received by the ingress. Requests exceeding the limits
+    /// are rejected with `429 Too Many Requests` and a `Retry-After` header. Changes to the limits
+    /// are applied without restarting the ingress.
+    pub rate_limits: IngressRateLimitOptions,
+
+    /// # CORS
+    ///
+    /// Cross-Origin Resource Sharing policy of the ingress, which lets browser-based clients call
+    /// Restate directly.
+    pub cors: IngressCorsOptions,
+
+    /// # Security headers
+    ///
+    /// Standard security headers added to the responses of the ingress.
+    pub security_headers: IngressSecurityHeadersOptions,
+
     /// # Experimental feature to run the ingress independent of the worker role
     ///
     /// This feature is experimental and should be used with caution. It allows to run the ingress
@@ -89,11 +133,653 @@ impl Default for IngressOptions {
     fn default() -> Self {
         Self {
             bind_address: "0.0.0.0:8080".parse().unwrap(),
+            additional_listeners: Vec::new(),
             // max is limited by Tower's LoadShedLayer.
             concurrent_api_requests_limit: None,
             kafka_clusters: Default::default(),
+            slo: SloOptions::default(),
+            auth: IngressAuthOptions::default(),
+            rate_limits: IngressRateLimitOptions::default(),
+            cors: IngressCorsOptions::default(),
+            security_headers: IngressSecurityHeadersOptions::default(),
             experimental_feature_enable_separate_ingress_role: false,
             experimental_feature_kafka_ingress_next: false,
         }
     }
 }
+
+/// # Ingress listener options
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressListenerOptions {
+    /// # Bind address
+    ///
+    /// The address to bind this listener to. Either a socket address like `127.0.0.1:8081`
+    /// or a Unix domain socket like `unix:/run/restate/ingress.sock`.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub bind_address: BindAddress,
+
+    /// # Bearer token
+    ///
+    /// If set, requests received on this listener must carry an `Authorization: Bearer <token>`
+    /// header with this token, otherwise they are rejected with `401 Unauthorized`. The token is
+    /// redacted when the configuration is printed.
+    ///
+    /// Cannot be set if `auth` is enabled, in which case the token must be configured as one of
+    /// its API keys instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<restate_serde_util::RedactedSerde>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub bearer_token: Option<String>,
+
+    /// # TLS
+    ///
+    /// If set, the listener only accepts TLS connections, using the given certificate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<IngressListenerTlsOptions>,
+}
+
+impl fmt::Debug for IngressListenerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IngressListenerOptions")
+            .field("bind_address", &self.bind_address)
+            .field(
+                "bearer_token",
+                &self
+                    .bearer_token
+                    .as_ref()
+                    .map(|_| restate_serde_util::REDACTED),
+            )
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
+
+/// # Ingress listener TLS options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressListenerTlsOptions {
+    /// # Certificate file
+    ///
+    /// Path to the PEM encoded certificate chain presented to the clients.
+    pub certificate_file: PathBuf,
+
+    /// # Private key file
+    ///
+    /// Path to the PEM encoded private key of the certificate.
+    pub private_key_file: PathBuf,
+}
+
+/// # Ingress authentication options
+///
+/// Requests must carry an `Authorization: Bearer <credential>` header, where the credential is
+/// either one of the configured API keys or a JWT signed by the configured issuer. Requests to
+/// `/restate/health` are always accepted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "IngressAuthOptions", default))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressAuthOptions {
+    /// # API keys
+    ///
+    /// Static API keys accepted by the ingress.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<IngressApiKey>,
+
+    /// # JWT validation
+    ///
+    /// If set, the ingress accepts JWT bearer tokens signed by one of the keys of the issuer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt: Option<JwtValidationOptions>,
+
+    /// # Service access rules
+    ///
+    /// Principals allowed to invoke each service. Services without a rule can be invoked by any
+    /// authenticated principal. Attaching to or getting the output of an invocation by id is
+    /// authorized against the rule of its target service. Other requests which don't name a
+    /// service, like completing an awakeable, only require authentication.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub services: HashMap<String, ServiceAccessRule>,
+}
+
+impl IngressAuthOptions {
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty() || self.jwt.is_some()
+    }
+}
+
+/// # Ingress API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressApiKey {
+    /// # Name
+    ///
+    /// Name identifying the key in the service access rules and in the logs.
+    pub name: String,
+
+    /// # Key
+    ///
+    /// The secret value of the key.
+    pub key: String,
+}
+
+/// # JWT validation options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct JwtValidationOptions {
+    /// # Issuer
+    ///
+    /// Expected `iss` claim of the tokens, for example `https://accounts.example.com`.
+    pub issuer: String,
+
+    /// # Audience
+    ///
+    /// Accepted values of the `aud` claim of the tokens. If empty, the audience is not validated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audience: Vec<String>,
+
+    /// # Algorithms
+    ///
+    /// Signature algorithms accepted for the tokens. Tokens declaring any other algorithm in
+    /// their header are rejected. Default: `["RS256"]`.
+    #[serde(default = "JwtValidationOptions::default_algorithms")]
+    pub algorithms: Vec<JwtAlgorithm>,
+
+    /// # JWKS URI
+    ///
+    /// URI of the JSON Web Key Set containing the keys which sign the tokens. If unset, it's
+    /// discovered from the OpenID Connect configuration of the issuer, at
+    /// `<issuer>/.well-known/openid-configuration`.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub jwks_uri: Option<http::Uri>,
+
+    /// # JWKS refresh interval
+    ///
+    /// Interval at which the JSON Web Key Set is fetched again, to pick up rotated keys.
+    /// Default: 10 minutes.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    jwks_refresh_interval: Option<humantime::Duration>,
+}
+
+impl JwtValidationOptions {
+    fn default_algorithms() -> Vec<JwtAlgorithm> {
+        vec![JwtAlgorithm::RS256]
+    }
+
+    pub fn jwks_refresh_interval(&self) -> Duration {
+        self.jwks_refresh_interval
+            .map(Into::into)
+            .unwrap_or(Duration::from_secs(10 * 60))
+    }
+}
+
+/// # JWT signature algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum JwtAlgorithm {
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    RS384,
+    RS512,
+    PS256,
+    PS384,
+    PS512,
+    ES256,
+    ES384,
+    EdDSA,
+}
+
+/// # Service access rule
+///
+/// A request is allowed if its principal matches any of the entries of the rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct ServiceAccessRule {
+    /// # API keys
+    ///
+    /// Names of the API keys allowed to invoke the service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<String>,
+
+    /// # Subjects
+    ///
+    /// Values of the `sub` claim of the JWTs allowed to invoke the service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subjects: Vec<String>,
+
+    /// # Scopes
+    ///
+    /// JWTs carrying any of these scopes in their space separated `scope` claim are allowed to
+    /// invoke the service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+}
+
+/// # Ingress rate limit options
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "IngressRateLimitOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+pub struct IngressRateLimitOptions {
+    /// # Global limits
+    ///
+    /// Limits shared by all the requests received by this ingress, except health checks.
+    #[serde(default)]
+    pub global: RateLimitOptions,
+
+    /// # Per-service limits
+    ///
+    /// Limits of the requests to each service, applied on top of the global limits. Requests which
+    /// don't name a service, like attaching to an invocation by id, are only subject to the global
+    /// limits.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub services: HashMap<String, RateLimitOptions>,
+}
+
+/// # Rate limit options
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitOptions {
+    /// # Requests per second
+    ///
+    /// Sustained rate of admitted requests, enforced with a token bucket. Default is unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<NonZeroU32>,
+
+    /// # Burst
+    ///
+    /// Number of requests which can be admitted at once, after a period of inactivity. Defaults to
+    /// `requests-per-second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<NonZeroU32>,
+
+    /// # Max in-flight requests
+    ///
+    /// Maximum number of requests being processed at the same time. Default is unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_in_flight_requests: Option<NonZeroUsize>,
+}
+
+impl RateLimitOptions {
+    /// Returns the rate and the burst of the token bucket, if a rate is configured.
+    pub fn token_bucket(&self) -> Option<(NonZeroU32, NonZeroU32)> {
+        self.requests_per_second
+            .map(|rate| (rate, self.burst.unwrap_or(rate)))
+    }
+}
+
+/// Allows any origin, method or header in the CORS options.
+pub const CORS_WILDCARD: &str = "*";
+
+/// # Ingress CORS options
+///
+/// By default, requests from any origin are allowed, with any method and headers, by mirroring
+/// the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "IngressCorsOptions", default))]
+#[serde(
+    rename_all = "kebab-case",
+    default,
+    try_from = "IngressCorsOptionsShadow"
+)]
+pub struct IngressCorsOptions {
+    /// # Allowed origins
+    ///
+    /// Origins allowed to call the ingress, like `https://app.example.com`. If empty, any origin
+    /// is allowed by mirroring the request. `*` allows any origin, but cannot be used together
+    /// with credentials.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+
+    /// # Allowed methods
+    ///
+    /// Methods allowed in cross-origin requests. If empty, any method is allowed by mirroring the
+    /// request. `*` allows any method, but cannot be used together with credentials.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_methods: Vec<String>,
+
+    /// # Allowed headers
+    ///
+    /// Request headers allowed in cross-origin requests. If empty, any header is allowed by
+    /// mirroring the request. `*` allows any header, but cannot be used together with
+    /// credentials.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_headers: Vec<String>,
+
+    /// # Exposed headers
+    ///
+    /// Response headers readable by cross-origin clients, in addition to the CORS-safelisted
+    /// ones. For example, `x-restate-id` exposes the id of the invocations. `*` exposes any
+    /// header, but cannot be used together with credentials.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exposed_headers: Vec<String>,
+
+    /// # Max age
+    ///
+    /// How long browsers can cache the response to preflight requests. If unset, browsers use
+    /// their own default.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    max_age: Option<humantime::Duration>,
+
+    /// # Allow credentials
+    ///
+    /// Whether cross-origin requests can carry credentials, like cookies or the `Authorization`
+    /// header. Default: true.
+    pub allow_credentials: bool,
+}
+
+impl IngressCorsOptions {
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age.map(Into::into)
+    }
+
+    /// Browsers reject the wildcard in the responses to requests with credentials.
+    fn validate(&self) -> Result<(), InvalidCorsOptions> {
+        if !self.allow_credentials {
+            return Ok(());
+        }
+        for (option, values) in [
+            ("allowed-origins", &self.allowed_origins),
+            ("allowed-methods", &self.allowed_methods),
+            ("allowed-headers", &self.allowed_headers),
+            ("exposed-headers", &self.exposed_headers),
+        ] {
+            if values.iter().any(|value| value == CORS_WILDCARD) {
+                return Err(InvalidCorsOptions(option));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "the CORS option '{0}' cannot contain '*' while credentials are allowed, either list the \
+    allowed values or set 'allow-credentials' to false"
+)]
+pub struct InvalidCorsOptions(&'static str);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+struct IngressCorsOptionsShadow {
+    allowed_origins: Vec<String>,
+    allowed_methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    max_age: Option<humantime::Duration>,
+    allow_credentials: bool,
+}
+
+impl Default for IngressCorsOptionsShadow {
+    fn default() -> Self {
+        let IngressCorsOptions {
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+            exposed_headers,
+            max_age,
+            allow_credentials,
+        } = IngressCorsOptions::default();
+        Self {
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+            exposed_headers,
+            max_age,
+            allow_credentials,
+        }
+    }
+}
+
+impl TryFrom<IngressCorsOptionsShadow> for IngressCorsOptions {
+    type Error = InvalidCorsOptions;
+
+    fn try_from(value: IngressCorsOptionsShadow) -> Result<Self, Self::Error> {
+        let options = Self {
+            allowed_origins: value.allowed_origins,
+            allowed_methods: value.allowed_methods,
+            allowed_headers: value.allowed_headers,
+            exposed_headers: value.exposed_headers,
+            max_age: value.max_age,
+            allow_credentials: value.allow_credentials,
+        };
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+impl Default for IngressCorsOptions {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: Vec::new(),
+            allowed_headers: Vec::new(),
+            exposed_headers: Vec::new(),
+            max_age: None,
+            allow_credentials: true,
+        }
+    }
+}
+
+/// # Ingress security headers options
+///
+/// Headers already set on a response are never overridden.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "IngressSecurityHeadersOptions", default)
+)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct IngressSecurityHeadersOptions {
+    /// # Enabled
+    ///
+    /// If true, responses carry the `X-Content-Type-Options: nosniff`, `X-Frame-Options: DENY`
+    /// and `Referrer-Policy: no-referrer` headers. Default: false, as they can break existing
+    /// clients, e.g. embedding the responses in frames.
+    pub enabled: bool,
+
+    /// # HSTS max age
+    ///
+    /// If set, responses carry a `Strict-Transport-Security` header with this max age. Only set
+    /// it if clients reach the ingress over HTTPS, for example through a TLS-terminating load
+    /// balancer.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    hsts_max_age: Option<humantime::Duration>,
+
+    /// # Content security policy
+    ///
+    /// If set, value of the `Content-Security-Policy` header of the responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_security_policy: Option<String>,
+}
+
+impl IngressSecurityHeadersOptions {
+    pub fn hsts_max_age(&self) -> Option<Duration> {
+        self.hsts_max_age.map(Into::into)
+    }
+}
+
+impl Default for IngressSecurityHeadersOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hsts_max_age: None,
+            content_security_policy: None,
+        }
+    }
+}
+
+/// # SLO options
+///
+/// Service level objectives are tracked in-process by every ingress, using multi-window
+/// burn-rate alerting: an objective alerts when the error budget is consumed faster than
+/// `burn-rate-threshold` times the sustainable rate over both the long and the short window.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "SloOptions", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct SloOptions {
+    /// # Objectives
+    ///
+    /// Objectives per service name. Services without objectives are not tracked.
+    pub objectives: HashMap<String, ServiceLevelObjective>,
+
+    /// # Alert webhook
+    ///
+    /// If set, the ingress sends a `POST` request with a JSON body to this URL whenever an
+    /// objective starts or stops alerting. Alerts are also logged, and can be inspected on the
+    /// `/restate/slo` endpoint of the ingress.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub alert_webhook: Option<http::Uri>,
+
+    /// # Evaluation interval
+    ///
+    /// Interval at which the burn rates are evaluated. Default: 30 seconds.
+    ///
+    /// Must not be zero. Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "restate_serde_util::NonZeroDurationString")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    evaluation_interval: humantime::Duration,
+
+    /// # Long window
+    ///
+    /// Window over which the burn rate must exceed the threshold to alert. Default: 1 hour.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    long_window: humantime::Duration,
+
+    /// # Short window
+    ///
+    /// Window over which the burn rate must exceed the threshold as well, so that alerts
+    /// resolve quickly once the error rate drops. Default: 5 minutes.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    short_window: humantime::Duration,
+
+    /// # Burn rate threshold
+    ///
+    /// Burn rate above which an objective alerts. A burn rate of 1 consumes exactly the error
+    /// budget over the objective period. Default: 14.4, which consumes 2% of a 30 days budget
+    /// within one hour.
+    pub burn_rate_threshold: f64,
+}
+
+impl SloOptions {
+    pub fn is_enabled(&self) -> bool {
+        !self.objectives.is_empty()
+    }
+
+    pub fn evaluation_interval(&self) -> Duration {
+        self.evaluation_interval.into()
+    }
+
+    pub fn long_window(&self) -> Duration {
+        self.long_window.into()
+    }
+
+    pub fn short_window(&self) -> Duration {
+        std::cmp::min(self.short_window.into(), self.long_window())
+    }
+}
+
+impl Default for SloOptions {
+    fn default() -> Self {
+        Self {
+            objectives: HashMap::new(),
+            alert_webhook: None,
+            evaluation_interval: Duration::from_secs(30).into(),
+            long_window: Duration::from_secs(60 * 60).into(),
+            short_window: Duration::from_secs(5 * 60).into(),
+            burn_rate_threshold: 14.4,
+        }
+    }
+}
+
+/// # Service level objective
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct ServiceLevelObjective {
+    /// # Availability
+    ///
+    /// Target ratio of requests which don't fail with a server error, for example `0.999`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<f64>,
+
+    /// # Latency
+    ///
+    /// Target ratio of requests which complete within the latency threshold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyObjective>,
+}
+
+/// # Latency objective
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct LatencyObjective {
+    /// # Threshold
+    ///
+    /// Requests taking longer than this duration count against the objective.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub threshold: humantime::Duration,
+
+    /// # Target
+    ///
+    /// Target ratio of requests completing within the threshold, for example `0.99`.
+    pub target: f64,
+}
@@ -10,14 +10,15 @@
 
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use restate_serde_util::NonZeroByteCount;
+use restate_serde_util::{NonZeroByteCount, NonZeroDurationString};
 use tracing::warn;
 
-use super::{data_dir, CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use super::{data_dir, CommonOptions, ObjectStoreOptions, RocksDbOptions, RocksDbOptionsBuilder};
 use crate::net::BindAddress;
 
 /// # Metadata store options
@@ -60,6 +61,11 @@ pub struct MetadataStoreOptions {
     ///
     /// The RocksDB options which will be used to configure the metadata store's RocksDB instance.
     pub rocksdb: RocksDbOptions,
+
+    /// # Snapshots
+    ///
+    /// Periodic upload of metadata store snapshots to an object store.
+    pub snapshots: MetadataStoreSnapshotsOptions,
 }
 
 impl MetadataStoreOptions {
@@ -112,6 +118,62 @@ impl Default for MetadataStoreOptions {
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.01,
             rocksdb,
+            snapshots: MetadataStoreSnapshotsOptions::default(),
+        }
+    }
+}
+
+/// # Metadata store snapshot options
+///
+/// Configures the periodic upload of consistent metadata store snapshots to an object store. This
+/// provides disaster recovery for single-node metadata store deployments.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "MetadataStoreSnapshotsOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct MetadataStoreSnapshotsOptions {
+    /// # Snapshot destination
+    ///
+    /// Base URL under which metadata snapshots are stored, e.g. `s3://bucket/metadata` or
+    /// `gs://bucket/metadata`. Supported schemes are `s3://`, `gs://` and `file://`.
+    ///
+    /// Default: `None` - metadata snapshots are disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// # Snapshot interval
+    ///
+    /// Interval at which a new snapshot is uploaded. A snapshot is only uploaded if the store
+    /// content changed since the last upload. Must not be zero.
+    #[serde_as(as = "NonZeroDurationString")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub interval: humantime::Duration,
+
+    /// # Bootstrap from snapshot
+    ///
+    /// If set, a metadata store which starts with an empty database restores its content from
+    /// the latest snapshot found at the destination before serving requests.
+    pub bootstrap_from_snapshot: bool,
+
+    /// # Object store options
+    ///
+    /// Credentials and connection settings for the snapshot destination.
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+}
+
+impl Default for MetadataStoreSnapshotsOptions {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            interval: Duration::from_secs(10 * 60).into(),
+            bootstrap_from_snapshot: false,
+            object_store: ObjectStoreOptions::default(),
         }
     }
 }
@@ -23,6 +23,7 @@ mod kafka;
 mod log_server;
 mod metadata_store;
 mod networking;
+mod object_store;
 mod query_engine;
 mod rocksdb;
 mod worker;
@@ -39,6 +40,7 @@ pub use kafka::*;
 pub use log_server::*;
 pub use metadata_store::*;
 pub use networking::*;
+pub use object_store::*;
 pub use query_engine::*;
 pub use rocksdb::*;
 pub use worker::*;
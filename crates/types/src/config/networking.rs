@@ -9,9 +9,11 @@
 // by the Apache License, Version 2.0.
 
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::retries::RetryPolicy;
+use restate_serde_util::{NonZeroByteCount, NonZeroDurationString};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -63,6 +65,56 @@ pub struct NetworkingOptions {
     /// The number of messages that can be queued on the outbound stream of a single
     /// connection.
     pub outbound_queue_length: NonZeroUsize,
+
+    /// # Message compression
+    ///
+    /// Compression of the payloads of the messages exchanged with other nodes. Compression is
+    /// used on a connection only if it's enabled on both nodes, with the algorithm of the node
+    /// accepting the connection.
+    pub message_compression: NetworkMessageCompression,
+
+    /// # Message compression threshold
+    ///
+    /// Payloads smaller than this size are sent uncompressed, as compressing them isn't worth
+    /// the cost.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub message_compression_threshold: NonZeroUsize,
+
+    /// # Max message size
+    ///
+    /// Maximum size of the uncompressed payload of the messages exchanged with other nodes.
+    /// Larger messages are rejected both when sending and when receiving them.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub max_message_size: NonZeroUsize,
+
+    /// # Gossip interval
+    ///
+    /// Interval of the gossip rounds, at every round the node sends the heartbeats it knows
+    /// about to a few random peers. Must not be zero.
+    #[serde_as(as = "NonZeroDurationString")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub gossip_interval: humantime::Duration,
+
+    /// # Gossip fanout
+    ///
+    /// Number of peers the heartbeats are sent to at every gossip round.
+    pub gossip_fanout: NonZeroUsize,
+
+    /// # Gossip failure timeout
+    ///
+    /// A node is considered dead if its heartbeat didn't advance within this timeout. It should
+    /// be a few times the gossip interval, to tolerate lost or delayed gossip messages.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub gossip_failure_timeout: humantime::Duration,
+
+    /// # TLS
+    ///
+    /// Mutual TLS for the connections between the nodes, and to the metadata store. When unset,
+    /// the nodes communicate in plaintext.
+    pub tls: Option<NetworkTlsOptions>,
 }
 
 impl Default for NetworkingOptions {
@@ -80,6 +132,72 @@ impl Default for NetworkingOptions {
             http2_keep_alive_interval: Duration::from_secs(40).into(),
             http2_keep_alive_timeout: Duration::from_secs(20).into(),
             http2_adaptive_window: true,
+            message_compression: NetworkMessageCompression::None,
+            message_compression_threshold: NonZeroUsize::new(64 * 1024).expect("Non zero number"),
+            max_message_size: NonZeroUsize::new(32 * 1024 * 1024).expect("Non zero number"),
+            gossip_interval: Duration::from_secs(1).into(),
+            gossip_fanout: NonZeroUsize::new(3).expect("Non zero number"),
+            gossip_failure_timeout: Duration::from_secs(5).into(),
+            tls: None,
         }
     }
 }
+
+impl NetworkingOptions {
+    /// Max size of the gRPC messages exchanged with other nodes. It leaves room for the header of
+    /// the messages on top of the max payload size.
+    pub fn grpc_max_message_size(&self) -> usize {
+        self.max_message_size.get().saturating_add(1024 * 1024)
+    }
+}
+
+/// # Network message compression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkMessageCompression {
+    /// Messages are sent uncompressed.
+    #[default]
+    None,
+    /// Better compression ratio, for bandwidth constrained networks, e.g. across availability
+    /// zones.
+    Zstd,
+    /// Faster compression, with a lower ratio.
+    Lz4,
+}
+
+/// # Network TLS options
+///
+/// Certificates used to authenticate the nodes with each other. Every node presents its own
+/// certificate both as server and as client, and accepts only peers whose certificate is signed by
+/// the configured certificate authority. The files are watched, and the certificates reloaded
+/// when they change, so they can be rotated without restarting the node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct NetworkTlsOptions {
+    /// # Certificate file
+    ///
+    /// Path to a PEM file containing the certificate of the node, optionally followed by its
+    /// intermediate certificates.
+    pub certificate_file: PathBuf,
+
+    /// # Private key file
+    ///
+    /// Path to a PEM file containing the private key of the node certificate.
+    pub private_key_file: PathBuf,
+
+    /// # CA certificate file
+    ///
+    /// Path to a PEM file containing the certificates of the authorities signing the
+    /// certificates of the nodes.
+    pub ca_certificate_file: PathBuf,
+
+    /// # SPIFFE trust domain
+    ///
+    /// When set, peers are authenticated by their [SPIFFE ID](https://spiffe.io/docs/latest/spiffe-about/spiffe-concepts/#spiffe-id)
+    /// rather than by their host name: their certificate must contain a URI SAN
+    /// `spiffe://<trust-domain>/...`. Use this with workload identity providers issuing
+    /// certificates without DNS names, such as SPIRE.
+    pub spiffe_trust_domain: Option<String>,
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// # Object store options
+///
+/// Credentials and connection settings for an S3-compatible or GCS object store. Any setting
+/// which is not explicitly configured falls back to the standard environment variables of the
+/// respective provider (e.g. `AWS_REGION`, `AWS_ACCESS_KEY_ID`, `GOOGLE_SERVICE_ACCOUNT`).
+#[serde_as]
+#[derive(Default, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "ObjectStoreOptions", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct ObjectStoreOptions {
+    /// # AWS region
+    ///
+    /// AWS region to use with S3 object store destinations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_region: Option<String>,
+
+    /// # AWS access key
+    ///
+    /// Username for S3 object store access.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_access_key_id: Option<String>,
+
+    /// # AWS secret key
+    ///
+    /// Password for S3 object store access. It is redacted when the configuration is printed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<restate_serde_util::RedactedSerde>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub aws_secret_access_key: Option<String>,
+
+    /// # AWS session token
+    ///
+    /// Session token for temporary S3 object store credentials. It is redacted when the
+    /// configuration is printed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<restate_serde_util::RedactedSerde>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub aws_session_token: Option<String>,
+
+    /// # Object store API endpoint URL override
+    ///
+    /// When you use an S3-compatible object store such as MinIO, set this to the API endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_endpoint_url: Option<String>,
+
+    /// # Allow plain HTTP
+    ///
+    /// Allow plain HTTP to be used with the object store endpoint. Only meant for local testing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_allow_http: Option<bool>,
+
+    /// # GCS service account key
+    ///
+    /// Path to the service account key file for GCS object store access.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcs_service_account_path: Option<String>,
+}
+
+impl fmt::Debug for ObjectStoreOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectStoreOptions")
+            .field("aws_region", &self.aws_region)
+            .field("aws_access_key_id", &self.aws_access_key_id)
+            .field(
+                "aws_secret_access_key",
+                &self
+                    .aws_secret_access_key
+                    .as_ref()
+                    .map(|_| restate_serde_util::REDACTED),
+            )
+            .field(
+                "aws_session_token",
+                &self
+                    .aws_session_token
+                    .as_ref()
+                    .map(|_| restate_serde_util::REDACTED),
+            )
+            .field("aws_endpoint_url", &self.aws_endpoint_url)
+            .field("aws_allow_http", &self.aws_allow_http)
+            .field("gcs_service_account_path", &self.gcs_service_account_path)
+            .finish()
+    }
+}
@@ -10,14 +10,14 @@
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::num::{NonZeroU16, NonZeroU64, NonZeroUsize};
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 use tracing::warn;
 
-use restate_serde_util::NonZeroByteCount;
+use restate_serde_util::{ByteCount, NonZeroByteCount};
 
-use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use super::{CommonOptions, ObjectStoreOptions, RocksDbOptions, RocksDbOptionsBuilder};
 use crate::identifiers::PartitionId;
 use crate::retries::RetryPolicy;
 
@@ -48,9 +48,35 @@ pub struct WorkerOptions {
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     cleanup_interval: humantime::Duration,
 
+    /// # Archive completed invocations after
+    ///
+    /// Completed invocations which are retained for longer than this duration are moved to a
+    /// compact archive on every cleanup interval. Archived invocations remain readable, but only
+    /// retain their target, result and timestamps: the `sys_invocation_status` table reports them
+    /// as `invoked_by = 'restate'` without a trace id. As archiving depends on the wall clock of
+    /// each node, replicas of a partition may report an invocation differently for a short time.
+    /// Unset to disable archiving. Default: unset.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    archive_completed_invocations_after: Option<humantime::Duration>,
+
     #[cfg_attr(feature = "schemars", schemars(skip))]
     experimental_feature_disable_idempotency_table: bool,
 
+    /// # Journal table v2
+    ///
+    /// When enabled, partition processors store the journals of new invocations in the journal
+    /// table v2, which indexes pending completions. Invocations which have been started before
+    /// keep using the journal table they were started with. Default: false.
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    experimental_feature_journal_table_v2: bool,
+
     pub storage: StorageOptions,
 
     pub invoker: InvokerOptions,
@@ -61,11 +87,60 @@ pub struct WorkerOptions {
     /// value is, the higher the throughput and latency are.
     max_command_batch_size: NonZeroUsize,
 
+    /// # Dead-letter after failed attempts
+    ///
+    /// The number of times applying a log record may fail to decode before the leader of the
+    /// partition appends the decision to move the record to the dead-letter table to the log. All
+    /// replicas apply this decision and continue with the next record. Storage errors never lead
+    /// to dead-lettering. Dead-lettered records can be inspected via the `sys_dead_letter` table
+    /// and re-injected via the admin API. Unset to disable dead-lettering, in which case the
+    /// partition processor keeps failing on such a record. Default: unset.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    dead_letter_after_failed_attempts: Option<NonZeroU32>,
+
+    /// # Effect digests
+    ///
+    /// When set to `record`, partition processors record a digest of the storage writes of every
+    /// applied log record. When set to `verify`, partition processors additionally compare the
+    /// digests of records which are applied again, e.g. after restoring a partition store from a
+    /// snapshot, with the recorded ones and report divergences. Since the digests are kept outside
+    /// of the partition stores, this validates that changes to the state machine are deterministic.
+    /// Default: `disabled`.
+    effect_digests: EffectDigestMode,
+
+    /// # Effect digest retention
+    ///
+    /// The number of most recently applied log records per partition for which effect digests
+    /// are retained.
+    effect_digest_retention: NonZeroU64,
+
+    /// # Command tracing
+    ///
+    /// When enabled, leading partition processors emit a span for every applied command which
+    /// relates to an invocation. The span is a child of the invocation's span and covers the time
+    /// from appending the command to the log until its effects have been committed, which shows
+    /// how long commands were queued versus applied. Default: false.
+    command_tracing: bool,
+
+    /// # Capacity
+    ///
+    /// Relative capacity of this node for running partition processor leaders, compared to the
+    /// other worker nodes of the cluster. When rebalancing leaderships, the cluster controller
+    /// assigns a node of capacity 2 twice as many leaders as a node of capacity 1.
+    capacity: NonZeroU16,
+
     /// # Snapshots
     ///
     /// Snapshots provide a mechanism for safely trimming the log and efficient bootstrapping of new
     /// worker nodes.
     pub snapshots: SnapshotsOptions,
+
+    /// # Ingress admission control
+    ///
+    /// Limits the rate at which new invocations are appended to the log of each partition.
+    /// Invocations exceeding the limits are rejected with a retriable overloaded error.
+    /// Changes to these limits are applied at runtime.
+    pub ingress_admission: IngressAdmissionOptions,
 }
 
 impl WorkerOptions {
@@ -77,6 +152,26 @@ impl WorkerOptions {
         self.max_command_batch_size.into()
     }
 
+    pub fn dead_letter_after_failed_attempts(&self) -> Option<NonZeroU32> {
+        self.dead_letter_after_failed_attempts
+    }
+
+    pub fn effect_digests(&self) -> EffectDigestMode {
+        self.effect_digests
+    }
+
+    pub fn effect_digest_retention(&self) -> u64 {
+        self.effect_digest_retention.get()
+    }
+
+    pub fn command_tracing(&self) -> bool {
+        self.command_tracing
+    }
+
+    pub fn capacity(&self) -> u16 {
+        self.capacity.get()
+    }
+
     pub fn num_timers_in_memory_limit(&self) -> Option<usize> {
         self.num_timers_in_memory_limit.map(Into::into)
     }
@@ -85,9 +180,17 @@ impl WorkerOptions {
         self.cleanup_interval.into()
     }
 
+    pub fn archive_completed_invocations_after(&self) -> Option<Duration> {
+        self.archive_completed_invocations_after.map(Into::into)
+    }
+
     pub fn experimental_feature_disable_idempotency_table(&self) -> bool {
         self.experimental_feature_disable_idempotency_table
     }
+
+    pub fn experimental_feature_journal_table_v2(&self) -> bool {
+        self.experimental_feature_journal_table_v2
+    }
 }
 
 impl Default for WorkerOptions {
@@ -96,15 +199,69 @@ impl Default for WorkerOptions {
             internal_queue_length: NonZeroUsize::new(1000).expect("Non zero number"),
             num_timers_in_memory_limit: None,
             cleanup_interval: Duration::from_secs(60 * 60).into(),
+            archive_completed_invocations_after: None,
             experimental_feature_disable_idempotency_table: false,
+            experimental_feature_journal_table_v2: false,
             storage: StorageOptions::default(),
             invoker: Default::default(),
             max_command_batch_size: NonZeroUsize::new(4).expect("Non zero number"),
+            dead_letter_after_failed_attempts: None,
+            effect_digests: EffectDigestMode::default(),
+            effect_digest_retention: NonZeroU64::new(1_000_000).expect("Non zero number"),
+            command_tracing: false,
+            capacity: NonZeroU16::new(1).expect("Non zero number"),
             snapshots: SnapshotsOptions::default(),
+            ingress_admission: IngressAdmissionOptions::default(),
         }
     }
 }
 
+/// # Effect digest mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum EffectDigestMode {
+    /// Don't compute effect digests.
+    #[default]
+    Disabled,
+    /// Record the effect digest of every applied log record.
+    Record,
+    /// Record the effect digest of every applied log record and compare it with the previously
+    /// recorded digest, if any.
+    Verify,
+}
+
+/// # Ingress admission control options
+///
+/// The limits apply to each partition leader individually. Bursts of up to one second worth of
+/// the configured rate are admitted.
+#[serde_as]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(rename = "IngressAdmissionOptions", default)
+)]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct IngressAdmissionOptions {
+    /// # Invocations per second
+    ///
+    /// The maximum number of new invocations per second which are appended to the log of a
+    /// partition. Unset means unlimited. Default: unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invocations_per_second: Option<NonZeroU32>,
+
+    /// # Bytes per second
+    ///
+    /// The maximum number of invocation payload bytes per second which are appended to the log
+    /// of a partition. Unset means unlimited. Default: unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    pub bytes_per_second: Option<NonZeroUsize>,
+}
+
 /// # Invoker options
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
@@ -157,10 +314,38 @@ pub struct InvokerOptions {
     /// # Message size limit
     ///
     /// Threshold to fail the invocation in case protocol messages coming from a service are larger than the specified amount.
+    /// The invocation is failed without being retried.
     #[serde_as(as = "Option<NonZeroByteCount>")]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
     message_size_limit: Option<NonZeroUsize>,
 
+    /// # Request message size limit
+    ///
+    /// Threshold to fail the invocation in case protocol messages sent to a service, such as the
+    /// replayed journal entries or the completions, are larger than the specified amount.
+    /// The invocation is failed without being retried.
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    request_message_size_limit: Option<NonZeroUsize>,
+
+    /// # Message chunk size
+    ///
+    /// Messages sent to services larger than the specified amount are split in chunks of this
+    /// size, if the deployment accepted the chunked-messages protocol extension when it was
+    /// discovered.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub message_chunk_size: NonZeroUsize,
+
+    /// # Journal replay cache memory limit
+    ///
+    /// Size in bytes of the cache holding the already encoded journal entries of recently
+    /// suspended or retried invocations, so that resuming them doesn't need to read and
+    /// encode the full journal again. The least recently used journals are evicted first.
+    /// If set to 0, the cache will be disabled.
+    #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
+    pub journal_replay_cache_memory_size: ByteCount,
+
     /// # Temporary directory
     ///
     /// Temporary directory to use for the invoker temporary files.
@@ -203,6 +388,10 @@ impl InvokerOptions {
     pub fn message_size_limit(&self) -> Option<usize> {
         self.message_size_limit.map(Into::into)
     }
+
+    pub fn request_message_size_limit(&self) -> Option<usize> {
+        self.request_message_size_limit.map(Into::into)
+    }
 }
 
 impl Default for InvokerOptions {
@@ -220,6 +409,9 @@ impl Default for InvokerOptions {
             abort_timeout: Duration::from_secs(60).into(),
             message_size_warning: NonZeroUsize::new(10_000_000).unwrap(), // 10MB
             message_size_limit: None,
+            request_message_size_limit: None,
+            message_chunk_size: NonZeroUsize::new(1_048_576).unwrap(), // 1MiB
+            journal_replay_cache_memory_size: 20_000_000u64.into(),    // 20MB
             tmp_dir: None,
             concurrent_invocations_limit: Some(NonZeroUsize::new(100).unwrap()),
             disable_eager_state: false,
@@ -262,6 +454,15 @@ pub struct StorageOptions {
     /// partitions. The divisor is defined in `num-partitions-to-share-memory-budget`
     rocksdb_memory_ratio: f32,
 
+    /// # Invocation status cache memory limit
+    ///
+    /// Size in bytes of the cache holding the recently accessed invocation statuses, which the
+    /// partition processor reads whenever it applies a command of an invocation. The total is
+    /// divided evenly across partitions, like the rocksdb memory budget. The least recently used
+    /// statuses are evicted first. If set to 0, the cache will be disabled.
+    #[cfg_attr(feature = "schemars", schemars(with = "ByteCount"))]
+    pub invocation_status_cache_memory_size: ByteCount,
+
     /// # Persist lsn interval
     ///
     /// Controls the interval at which worker tries to persist the last applied lsn. Lsn persisting
@@ -342,6 +543,7 @@ impl Default for StorageOptions {
             // set by apply_common in runtime
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.49,
+            invocation_status_cache_memory_size: 20_000_000u64.into(), // 20MB
             // persist the lsn every hour
             persist_lsn_interval: Some(Duration::from_secs(60 * 60).into()),
             persist_lsn_threshold: 1000,
@@ -353,7 +555,7 @@ impl Default for StorageOptions {
 /// # Snapshot options.
 /// Configures the worker store partition snapshot mechanism.
 #[serde_as]
-#[derive(Default, Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "schemars", schemars(rename = "SnapshotsOptions", default))]
 #[serde(rename_all = "kebab-case")]
@@ -372,6 +574,39 @@ pub struct SnapshotsOptions {
     ///
     /// Default: `None` - automatic snapshots are disabled by default
     pub snapshot_interval_num_records: Option<NonZeroU64>,
+
+    /// # Snapshot destination
+    ///
+    /// Base URL under which partition snapshots are uploaded, e.g. `s3://bucket/snapshots` or
+    /// `gs://bucket/snapshots`. Supported schemes are `s3://`, `gs://` and `file://`. Snapshots of
+    /// a partition are stored under `<destination>/<partition-id>/`.
+    ///
+    /// Default: `None` - snapshots are only kept in the local node's data directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// # Number of retained snapshots
+    ///
+    /// Number of most recent snapshots per partition which are kept at the snapshot destination.
+    /// Older snapshots are deleted after a new snapshot was uploaded successfully.
+    pub num_retained: NonZeroUsize,
+
+    /// # Object store options
+    ///
+    /// Credentials and connection settings for the snapshot destination.
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+}
+
+impl Default for SnapshotsOptions {
+    fn default() -> Self {
+        Self {
+            snapshot_interval_num_records: None,
+            destination: None,
+            num_retained: NonZeroUsize::new(3).expect("Non zero number"),
+            object_store: ObjectStoreOptions::default(),
+        }
+    }
 }
 
 impl SnapshotsOptions {
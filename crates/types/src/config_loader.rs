@@ -24,8 +24,19 @@ use tracing::{error, info, warn};
 pub enum ConfigLoadError {
     #[error("configuration loading error: {0}")]
     Figment(#[from] figment::Error),
+    #[error("cannot expand option '{key}': environment variable '{var}' is not set")]
+    MissingEnvVar { key: &'static str, var: String },
+    #[error(
+        "cannot expand option '{key}': unterminated environment variable reference in '{value}'"
+    )]
+    UnterminatedEnvVar { key: &'static str, value: String },
 }
 
+/// Options which identify a node and which may reference environment variables as `${NAME}`.
+/// This allows deriving them from the environment of the node, e.g. from the pod name and
+/// topology which the Kubernetes downward API exposes as environment variables.
+const ENV_EXPANDED_OPTIONS: &[&str] = &["node-name", "location", "advertised-address"];
+
 #[derive(Debug, Default, derive_builder::Builder)]
 #[builder(default)]
 pub struct ConfigLoader {
@@ -60,6 +71,15 @@ impl ConfigLoader {
             figment = figment.merge(Figment::from(Serialized::defaults(cli_overrides)))
         }
 
+        for key in ENV_EXPANDED_OPTIONS {
+            if let Ok(value) = figment.extract_inner::<String>(key) {
+                if value.contains("${") {
+                    let expanded = expand_env_vars(key, &value, |var| std::env::var(var).ok())?;
+                    figment = figment.merge((*key, expanded));
+                }
+            }
+        }
+
         let mut config: Configuration = figment.extract()?;
 
         config.common.set_derived_values();
@@ -179,3 +199,75 @@ impl ConfigLoader {
         }
     }
 }
+
+/// Replaces all `${NAME}` references in `value` by the value of the environment variable `NAME`.
+fn expand_env_vars(
+    key: &'static str,
+    value: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, ConfigLoadError> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find('}') else {
+            return Err(ConfigLoadError::UnterminatedEnvVar {
+                key,
+                value: value.to_owned(),
+            });
+        };
+        let var = &rest[start + 2..start + 2 + len];
+        let var_value = lookup(var).ok_or_else(|| ConfigLoadError::MissingEnvVar {
+            key,
+            var: var.to_owned(),
+        })?;
+        expanded.push_str(&var_value);
+        rest = &rest[start + 3 + len..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(var: &str) -> Option<String> {
+        match var {
+            "POD_NAME" => Some("restate-0".to_owned()),
+            "ZONE" => Some("eu-central-1a".to_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_env_var_references() {
+        assert_eq!(
+            expand_env_vars(
+                "advertised-address",
+                "http://${POD_NAME}.restate-cluster:5122/",
+                lookup
+            )
+            .unwrap(),
+            "http://restate-0.restate-cluster:5122/"
+        );
+        assert_eq!(
+            expand_env_vars("location", "eu-central-1.${ZONE}", lookup).unwrap(),
+            "eu-central-1.eu-central-1a"
+        );
+        assert_eq!(
+            expand_env_vars("node-name", "node-1", lookup).unwrap(),
+            "node-1"
+        );
+
+        assert!(matches!(
+            expand_env_vars("node-name", "${NODE_NAME}", lookup),
+            Err(ConfigLoadError::MissingEnvVar { var, .. }) if var == "NODE_NAME"
+        ));
+        assert!(matches!(
+            expand_env_vars("node-name", "${POD_NAME", lookup),
+            Err(ConfigLoadError::UnterminatedEnvVar { .. })
+        ));
+    }
+}
@@ -8,6 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::ops::RangeInclusive;
+
+use bytestring::ByteString;
+
 use crate::identifiers::DeploymentId;
 use crate::service_protocol::ServiceProtocolVersion;
 
@@ -30,6 +34,38 @@ impl PinnedDeployment {
     }
 }
 
+/// Message to re-pin the invocations of a deployment to other deployments of their services,
+/// before the deployment is deleted.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeploymentMigration {
+    pub deployment_id: DeploymentId,
+    /// Deployment to re-pin the invocations of each service to. Invocations of services without
+    /// a target are left pinned to the deployment.
+    pub targets: Vec<MigrationTarget>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MigrationTarget {
+    pub service_name: ByteString,
+    pub deployment_id: DeploymentId,
+    pub supported_protocol_versions: RangeInclusive<i32>,
+}
+
+impl MigrationTarget {
+    /// Returns the deployment to re-pin the given invocation to, if the target deployment
+    /// supports the service protocol version the invocation started with.
+    pub fn repin(&self, pinned_deployment: &PinnedDeployment) -> Option<PinnedDeployment> {
+        self.supported_protocol_versions
+            .contains(&(pinned_deployment.service_protocol_version as i32))
+            .then(|| {
+                PinnedDeployment::new(
+                    self.deployment_id,
+                    pinned_deployment.service_protocol_version,
+                )
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
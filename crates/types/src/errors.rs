@@ -140,12 +140,14 @@ pub mod codes {
     use super::InvocationErrorCode;
 
     pub const BAD_REQUEST: InvocationErrorCode = InvocationErrorCode(400);
+    pub const FORBIDDEN: InvocationErrorCode = InvocationErrorCode(403);
     pub const NOT_FOUND: InvocationErrorCode = InvocationErrorCode(404);
     pub const INTERNAL: InvocationErrorCode = InvocationErrorCode(500);
     pub const UNKNOWN: InvocationErrorCode = INTERNAL;
     pub const ABORTED: InvocationErrorCode = InvocationErrorCode(409);
     pub const KILLED: InvocationErrorCode = ABORTED;
     pub const GONE: InvocationErrorCode = InvocationErrorCode(410);
+    pub const TIMEOUT: InvocationErrorCode = InvocationErrorCode(408);
     pub const JOURNAL_MISMATCH: InvocationErrorCode = InvocationErrorCode(570);
     pub const PROTOCOL_VIOLATION: InvocationErrorCode = InvocationErrorCode(571);
     pub const CONFLICT: InvocationErrorCode = InvocationErrorCode(409);
@@ -226,6 +228,17 @@ impl InvocationError {
         }
     }
 
+    pub fn service_call_forbidden(
+        service: impl fmt::Display,
+        caller_service: impl fmt::Display,
+    ) -> Self {
+        Self {
+            code: codes::FORBIDDEN,
+            message: Cow::Owned(format!("Service '{}' is not allowed to call service '{}'. Check the allowed callers of the service.", caller_service, service)),
+            description: None,
+        }
+    }
+
     pub fn with_static_message(mut self, message: &'static str) -> InvocationError {
         self.message = Cow::Borrowed(message);
         self
@@ -292,6 +305,11 @@ pub const WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR: InvocationError =
 pub const NOT_READY_INVOCATION_ERROR: InvocationError =
     InvocationError::new_static(codes::NOT_READY, "the response is not ready yet");
 
+pub const DRY_RUN_CALL_INVOCATION_ERROR: InvocationError = InvocationError::new_static(
+    codes::BAD_REQUEST,
+    "dry-run invocations cannot call other services",
+);
+
 /// Error parsing/decoding a resource ID.
 #[derive(Debug, thiserror::Error, Clone, Eq, PartialEq)]
 pub enum IdDecodeError {
@@ -46,6 +46,7 @@ prefixed_ids! {
         Subscription("sub"),
         Awakeable("prom"),
         Snapshot("snap"),
+        Schedule("sch"),
     }
 }
 
@@ -131,7 +131,7 @@ pub type EntryIndex = u32;
 pub type PartitionKey = u64;
 
 /// Returns the partition key computed from either the service_key, or idempotency_key, if possible
-fn deterministic_partition_key(
+pub(crate) fn deterministic_partition_key(
     service_key: Option<&str>,
     idempotency_key: Option<&str>,
 ) -> Option<PartitionKey> {
@@ -619,6 +619,113 @@ impl WithPartitionKey for IdempotencyId {
     }
 }
 
+/// Identifies a recurring schedule of invocations, see [`crate::schedule::Schedule`].
+///
+/// Like [`InvocationId`], it includes the partition key of the partition owning the schedule.
+/// All the invocations fired by the schedule belong to this partition as well.
+#[derive(
+    Eq,
+    Hash,
+    PartialEq,
+    Clone,
+    Copy,
+    Debug,
+    PartialOrd,
+    Ord,
+    serde_with::SerializeDisplay,
+    serde_with::DeserializeFromStr,
+)]
+pub struct ScheduleId {
+    partition_key: PartitionKey,
+    inner: InvocationUuid,
+}
+
+impl ScheduleId {
+    pub fn generate(invocation_target: &InvocationTarget) -> Self {
+        // Invocations of virtual objects and workflows must be owned by the partition of their key
+        let partition_key =
+            deterministic_partition_key(invocation_target.key().map(|bs| bs.as_ref()), None)
+                .unwrap_or_else(|| rand::thread_rng().next_u64());
+
+        Self::from_parts(partition_key, InvocationUuid::from(u128::from(Ulid::new())))
+    }
+
+    pub const fn from_parts(partition_key: PartitionKey, schedule_uuid: InvocationUuid) -> Self {
+        Self {
+            partition_key,
+            inner: schedule_uuid,
+        }
+    }
+
+    pub fn schedule_uuid(&self) -> InvocationUuid {
+        self.inner
+    }
+
+    /// Returns the id of the invocation fired by this schedule at the given time. The id is
+    /// deterministic, so that every replica of the partition fires the same invocation.
+    pub fn invocation_id_at(&self, fire_time: MillisSinceEpoch) -> InvocationId {
+        let mut hasher = Sha256::new();
+        hasher.update(b"sch");
+        hasher.update(self.inner.to_bytes());
+        hasher.update(fire_time.as_u64().to_be_bytes());
+        let result = hasher.finalize();
+        let (int_bytes, _) = result.split_at(size_of::<u128>());
+        let uuid = u128::from_be_bytes(
+            int_bytes
+                .try_into()
+                .expect("Conversion after split can't fail"),
+        );
+
+        InvocationId::from_parts(self.partition_key, InvocationUuid::from(uuid))
+    }
+}
+
+impl WithPartitionKey for ScheduleId {
+    fn partition_key(&self) -> PartitionKey {
+        self.partition_key
+    }
+}
+
+impl ResourceId for ScheduleId {
+    const SIZE_IN_BYTES: usize = size_of::<PartitionKey>() + InvocationUuid::SIZE_IN_BYTES;
+    const RESOURCE_TYPE: IdResourceType = IdResourceType::Schedule;
+    const STRING_CAPACITY_HINT: usize =
+        base62_max_length_for_type::<PartitionKey>() + base62_max_length_for_type::<u128>();
+
+    fn push_contents_to_encoder(&self, encoder: &mut IdEncoder<Self>) {
+        encoder.encode_fixed_width(self.partition_key);
+        let uuid_raw: u128 = self.inner.0;
+        encoder.encode_fixed_width(uuid_raw);
+    }
+}
+
+impl fmt::Display for ScheduleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut encoder = IdEncoder::<Self>::new();
+        self.push_contents_to_encoder(&mut encoder);
+        fmt::Display::fmt(&encoder.finalize(), f)
+    }
+}
+
+impl FromStr for ScheduleId {
+    type Err = IdDecodeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut decoder = IdDecoder::new(input)?;
+        // Ensure we are decoding the right type
+        if decoder.resource_type != Self::RESOURCE_TYPE {
+            return Err(IdDecodeError::TypeMismatch);
+        }
+
+        let partition_key: PartitionKey = decoder.cursor.decode_next()?;
+        let raw_uuid: u128 = decoder.cursor.decode_next()?;
+        Ok(Self {
+            partition_key,
+            inner: InvocationUuid::from(raw_uuid),
+        })
+    }
+}
+
 /// Incremental id defining the service revision.
 pub type ServiceRevision = u32;
 
@@ -1078,6 +1185,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn roundtrip_schedule_id_str() {
+        let expected = ScheduleId::generate(&InvocationTarget::virtual_object(
+            "Greeter",
+            "slinkydeveloper",
+            "greet",
+            VirtualObjectHandlerType::Exclusive,
+        ));
+        let serialized = expected.to_string();
+        assert!(serialized.starts_with("sch_1"), "{}", serialized);
+        assert_eq!(expected, ScheduleId::from_str(&serialized).unwrap());
+        assert_eq!(
+            expected.partition_key(),
+            partitioner::HashPartitioner::compute_partition_key("slinkydeveloper")
+        );
+    }
+
+    #[test]
+    fn schedule_invocation_ids_are_deterministic() {
+        let schedule_id = ScheduleId::generate(&InvocationTarget::mock_service());
+        let first = schedule_id.invocation_id_at(MillisSinceEpoch::new(60_000));
+
+        assert_eq!(
+            first,
+            schedule_id.invocation_id_at(MillisSinceEpoch::new(60_000))
+        );
+        assert_eq!(first.partition_key(), schedule_id.partition_key());
+        assert_ne!(
+            first,
+            schedule_id.invocation_id_at(MillisSinceEpoch::new(120_000))
+        );
+    }
+
     #[test]
     fn bad_invocation_id_str() {
         let bad_strs = [
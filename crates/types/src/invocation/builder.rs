@@ -0,0 +1,379 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Validated builders for [`InvocationTarget`] and [`ServiceInvocation`].
+
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use bytes::Bytes;
+use bytestring::ByteString;
+
+use super::{
+    Header, InvocationPriority, InvocationTarget, InvocationTargetType, ServiceInvocation,
+    ServiceInvocationResponseSink, ServiceInvocationSpanContext, ServiceType, Source, SpanRelation,
+    SubmitNotificationSink, VirtualObjectHandlerType, WorkflowHandlerType,
+};
+use crate::deployment::PinnedDeployment;
+use crate::identifiers::{
+    deterministic_partition_key, EntryIndex, InvocationId, PartitionProcessorRpcRequestId,
+    WithPartitionKey,
+};
+use crate::time::{MillisSinceEpoch, ZonedDateTime};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvocationTargetBuildError {
+    #[error("the service name must not be empty")]
+    EmptyServiceName,
+    #[error("the handler name must not be empty")]
+    EmptyHandlerName,
+    #[error("targets of a {0} require a key")]
+    MissingKey(ServiceType),
+    #[error("targets of a {0} must not have a key")]
+    UnexpectedKey(ServiceType),
+}
+
+/// Builder for [`InvocationTarget`], which ensures that keyed targets have a key and unkeyed
+/// targets don't.
+#[derive(Debug, Clone)]
+pub struct InvocationTargetBuilder {
+    ty: InvocationTargetType,
+    name: ByteString,
+    key: Option<ByteString>,
+    handler: ByteString,
+}
+
+impl InvocationTargetBuilder {
+    pub fn new(ty: InvocationTargetType, name: impl Into<ByteString>) -> Self {
+        Self {
+            ty,
+            name: name.into(),
+            key: None,
+            handler: ByteString::new(),
+        }
+    }
+
+    pub fn service(name: impl Into<ByteString>) -> Self {
+        Self::new(InvocationTargetType::Service, name)
+    }
+
+    pub fn virtual_object(
+        name: impl Into<ByteString>,
+        handler_ty: VirtualObjectHandlerType,
+    ) -> Self {
+        Self::new(InvocationTargetType::VirtualObject(handler_ty), name)
+    }
+
+    pub fn workflow(name: impl Into<ByteString>, handler_ty: WorkflowHandlerType) -> Self {
+        Self::new(InvocationTargetType::Workflow(handler_ty), name)
+    }
+
+    pub fn key(mut self, key: impl Into<ByteString>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn handler(mut self, handler: impl Into<ByteString>) -> Self {
+        self.handler = handler.into();
+        self
+    }
+
+    pub fn build(self) -> Result<InvocationTarget, InvocationTargetBuildError> {
+        if self.name.is_empty() {
+            return Err(InvocationTargetBuildError::EmptyServiceName);
+        }
+        if self.handler.is_empty() {
+            return Err(InvocationTargetBuildError::EmptyHandlerName);
+        }
+
+        let service_ty = ServiceType::from(self.ty);
+        match (self.ty, self.key) {
+            (InvocationTargetType::Service, None) => Ok(InvocationTarget::Service {
+                name: self.name,
+                handler: self.handler,
+            }),
+            (InvocationTargetType::VirtualObject(handler_ty), Some(key)) => {
+                Ok(InvocationTarget::VirtualObject {
+                    name: self.name,
+                    key,
+                    handler: self.handler,
+                    handler_ty,
+                })
+            }
+            (InvocationTargetType::Workflow(handler_ty), Some(key)) => {
+                Ok(InvocationTarget::Workflow {
+                    name: self.name,
+                    key,
+                    handler: self.handler,
+                    handler_ty,
+                })
+            }
+            (InvocationTargetType::Service, Some(_)) => {
+                Err(InvocationTargetBuildError::UnexpectedKey(service_ty))
+            }
+            (_, None) => Err(InvocationTargetBuildError::MissingKey(service_ty)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ServiceInvocationBuildError {
+    #[error("the partition key of invocation id '{0}' doesn't match its target or idempotency key, use InvocationId::generate to create the id")]
+    PartitionKeyMismatch(InvocationId),
+    #[error("invocation '{0}' cannot send its response to itself")]
+    ResponseSinkIsSelf(InvocationId),
+    #[error("the idempotency key must not be empty")]
+    EmptyIdempotencyKey,
+}
+
+/// Builder for [`ServiceInvocation`].
+///
+/// Compared to constructing the struct directly, the builder checks on [`Self::build`] that the
+/// invocation id was generated for the given target and idempotency key, so that the invocation is
+/// routed to the partition owning its state and deduplication entries.
+#[derive(Debug, Clone)]
+pub struct ServiceInvocationBuilder {
+    inner: ServiceInvocation,
+}
+
+impl ServiceInvocation {
+    pub fn builder(
+        invocation_id: InvocationId,
+        invocation_target: InvocationTarget,
+        source: Source,
+    ) -> ServiceInvocationBuilder {
+        ServiceInvocationBuilder {
+            inner: ServiceInvocation::initialize(invocation_id, invocation_target, source),
+        }
+    }
+}
+
+impl ServiceInvocationBuilder {
+    pub fn argument(mut self, argument: impl Into<Bytes>) -> Self {
+        self.inner.argument = argument.into();
+        self
+    }
+
+    pub fn header(mut self, header: Header) -> Self {
+        self.inner.headers.push(header);
+        self
+    }
+
+    pub fn headers(mut self, headers: impl IntoIterator<Item = Header>) -> Self {
+        self.inner.headers.extend(headers);
+        self
+    }
+
+    pub fn span_context(mut self, span_context: ServiceInvocationSpanContext) -> Self {
+        self.inner.span_context = span_context;
+        self
+    }
+
+    /// Starts a new span context for the invocation, related to the given span.
+    pub fn related_span(mut self, span_relation: SpanRelation) -> Self {
+        self.inner.with_related_span(span_relation);
+        self
+    }
+
+    pub fn execution_time(mut self, execution_time: MillisSinceEpoch) -> Self {
+        self.inner.execution_time = Some(execution_time);
+        self
+    }
+
+    /// Schedules the invocation at the instant the wall clock in the given timezone shows the
+    /// local date time.
+    pub fn execution_wall_clock_time(mut self, execution_wall_clock_time: ZonedDateTime) -> Self {
+        self.inner.execution_time = Some(execution_wall_clock_time.resolve());
+        self.inner.execution_wall_clock_time = Some(execution_wall_clock_time);
+        self
+    }
+
+    pub fn completion_retention_duration(mut self, duration: Duration) -> Self {
+        self.inner.completion_retention_duration = Some(duration);
+        self
+    }
+
+    pub fn idempotency_key(mut self, idempotency_key: impl Into<ByteString>) -> Self {
+        self.inner.idempotency_key = Some(idempotency_key.into());
+        self
+    }
+
+    pub fn pinned_deployment(mut self, pinned_deployment: PinnedDeployment) -> Self {
+        self.inner.pinned_deployment = Some(pinned_deployment);
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.inner.dry_run = dry_run;
+        self
+    }
+
+    pub fn priority(mut self, priority: InvocationPriority) -> Self {
+        self.inner.priority = priority;
+        self
+    }
+
+    pub fn shared_concurrency_limit(mut self, shared_concurrency_limit: NonZeroU32) -> Self {
+        self.inner.shared_concurrency_limit = Some(shared_concurrency_limit);
+        self
+    }
+
+    pub fn response_sink(mut self, response_sink: ServiceInvocationResponseSink) -> Self {
+        self.inner.response_sink = Some(response_sink);
+        self
+    }
+
+    /// Sends the response to the journal entry `entry_index` of the `caller` invocation.
+    pub fn respond_to_invocation(self, caller: InvocationId, entry_index: EntryIndex) -> Self {
+        self.response_sink(ServiceInvocationResponseSink::partition_processor(
+            caller,
+            entry_index,
+        ))
+    }
+
+    /// Sends the response to the ingress request `request_id`.
+    pub fn respond_to_ingress(self, request_id: PartitionProcessorRpcRequestId) -> Self {
+        self.response_sink(ServiceInvocationResponseSink::ingress(request_id))
+    }
+
+    /// Notifies the ingress request `request_id` once the invocation has been submitted.
+    pub fn notify_submit_to_ingress(mut self, request_id: PartitionProcessorRpcRequestId) -> Self {
+        self.inner.submit_notification_sink = Some(SubmitNotificationSink::Ingress { request_id });
+        self
+    }
+
+    pub fn build(self) -> Result<ServiceInvocation, ServiceInvocationBuildError> {
+        let invocation = self.inner;
+
+        if invocation
+            .idempotency_key
+            .as_ref()
+            .is_some_and(|key| key.is_empty())
+        {
+            return Err(ServiceInvocationBuildError::EmptyIdempotencyKey);
+        }
+
+        if let Some(expected_partition_key) = deterministic_partition_key(
+            invocation.invocation_target.key().map(|key| key.as_ref()),
+            invocation.idempotency_key.as_deref(),
+        ) {
+            if invocation.invocation_id.partition_key() != expected_partition_key {
+                return Err(ServiceInvocationBuildError::PartitionKeyMismatch(
+                    invocation.invocation_id,
+                ));
+            }
+        }
+
+        if let Some(ServiceInvocationResponseSink::PartitionProcessor { caller, .. }) =
+            &invocation.response_sink
+        {
+            if *caller == invocation.invocation_id {
+                return Err(ServiceInvocationBuildError::ResponseSinkIsSelf(
+                    invocation.invocation_id,
+                ));
+            }
+        }
+
+        Ok(invocation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_invocation_targets() {
+        assert_eq!(
+            InvocationTargetBuilder::service("Greeter")
+                .handler("greet")
+                .build(),
+            Ok(InvocationTarget::service("Greeter", "greet"))
+        );
+        assert_eq!(
+            InvocationTargetBuilder::virtual_object("Counter", VirtualObjectHandlerType::Shared)
+                .key("my-key")
+                .handler("get")
+                .build(),
+            Ok(InvocationTarget::virtual_object(
+                "Counter",
+                "my-key",
+                "get",
+                VirtualObjectHandlerType::Shared
+            ))
+        );
+
+        assert_eq!(
+            InvocationTargetBuilder::workflow("Signup", WorkflowHandlerType::Workflow)
+                .handler("run")
+                .build(),
+            Err(InvocationTargetBuildError::MissingKey(
+                ServiceType::Workflow
+            ))
+        );
+        assert_eq!(
+            InvocationTargetBuilder::service("Greeter")
+                .key("my-key")
+                .handler("greet")
+                .build(),
+            Err(InvocationTargetBuildError::UnexpectedKey(
+                ServiceType::Service
+            ))
+        );
+        assert_eq!(
+            InvocationTargetBuilder::service("Greeter").build(),
+            Err(InvocationTargetBuildError::EmptyHandlerName)
+        );
+    }
+
+    #[test]
+    fn build_service_invocation() {
+        let target = InvocationTarget::mock_virtual_object();
+        let invocation_id = InvocationId::generate(&target, Some("my-key"));
+        let caller = InvocationId::mock_random();
+
+        let invocation =
+            ServiceInvocation::builder(invocation_id, target.clone(), Source::Internal)
+                .argument(Bytes::from_static(b"input"))
+                .idempotency_key("my-key")
+                .respond_to_invocation(caller, 1)
+                .build()
+                .unwrap();
+
+        assert_eq!(invocation.invocation_id, invocation_id);
+        assert_eq!(invocation.argument, Bytes::from_static(b"input"));
+        assert_eq!(
+            invocation.response_sink,
+            Some(ServiceInvocationResponseSink::partition_processor(
+                caller, 1
+            ))
+        );
+
+        // the response of an invocation cannot be sent to itself
+        assert_eq!(
+            ServiceInvocation::builder(invocation_id, target.clone(), Source::Internal)
+                .respond_to_invocation(invocation_id, 1)
+                .build(),
+            Err(ServiceInvocationBuildError::ResponseSinkIsSelf(
+                invocation_id
+            ))
+        );
+
+        // the id must be generated for the target
+        let other_invocation_id =
+            InvocationId::generate(&InvocationTarget::mock_virtual_object(), None);
+        assert_eq!(
+            ServiceInvocation::builder(other_invocation_id, target, Source::Internal).build(),
+            Err(ServiceInvocationBuildError::PartitionKeyMismatch(
+                other_invocation_id
+            ))
+        );
+    }
+}
@@ -10,18 +10,28 @@
 
 //! This module contains all the core types representing a service invocation.
 
+mod builder;
+
+pub use builder::{
+    InvocationTargetBuildError, InvocationTargetBuilder, ServiceInvocationBuildError,
+    ServiceInvocationBuilder,
+};
+
+use crate::deployment::PinnedDeployment;
 use crate::errors::InvocationError;
 use crate::identifiers::{
     EntryIndex, IdempotencyId, InvocationId, PartitionKey, PartitionProcessorRpcRequestId,
     ServiceId, SubscriptionId, WithInvocationId, WithPartitionKey,
 };
-use crate::time::MillisSinceEpoch;
+use crate::time::{MillisSinceEpoch, ZonedDateTime};
 use bytes::Bytes;
 use bytestring::ByteString;
 use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceState};
 use serde_with::{serde_as, FromInto};
+use std::cmp::Reverse;
 use std::fmt;
 use std::hash::Hash;
+use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::str::FromStr;
 use std::time::Duration;
@@ -29,6 +39,11 @@ use std::time::Duration;
 // Re-exporting opentelemetry [`TraceId`] to avoid having to import opentelemetry in all crates.
 pub use opentelemetry::trace::TraceId;
 
+/// Prefix of the service names of the Kafka egress targets. A one-way call to the handler
+/// `<topic>` of the virtual object `restate.kafka.<cluster>` publishes its argument to the given
+/// topic of the Kafka cluster, using the virtual object key as record key.
+pub const KAFKA_EGRESS_SERVICE_PREFIX: &str = "restate.kafka.";
+
 #[derive(Eq, Hash, PartialEq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ServiceType {
@@ -260,6 +275,19 @@ impl InvocationTarget {
             }
         }
     }
+
+    /// Kafka cluster of the egress target, or `None` if this is not a Kafka egress target.
+    /// See [`KAFKA_EGRESS_SERVICE_PREFIX`].
+    pub fn kafka_egress_cluster(&self) -> Option<&str> {
+        kafka_egress_cluster(self.service_name())
+    }
+}
+
+/// Kafka cluster of the egress service name, or `None` if this is not a Kafka egress service.
+pub fn kafka_egress_cluster(service_name: &str) -> Option<&str> {
+    service_name
+        .strip_prefix(KAFKA_EGRESS_SERVICE_PREFIX)
+        .filter(|cluster| !cluster.is_empty())
 }
 
 impl fmt::Display for InvocationTarget {
@@ -286,8 +314,28 @@ pub struct InvocationRequestHeader {
     /// Time when the request should be executed. If none, it's executed immediately.
     pub execution_time: Option<MillisSinceEpoch>,
 
+    /// Wall-clock time in a timezone the `execution_time` was resolved from, if any.
+    #[serde(default)]
+    pub execution_wall_clock_time: Option<ZonedDateTime>,
+
     /// Retention duration of the completed status. If none, the completed status is not retained.
     pub completion_retention_duration: Option<Duration>,
+
+    /// Deployment to run this invocation on. If none, the latest deployment of the service is chosen.
+    #[serde(default)]
+    pub pinned_deployment: Option<PinnedDeployment>,
+
+    /// If true, the invocation runs in dry-run mode. See [`ServiceInvocation::dry_run`].
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Priority of the invocation in the inbox of a virtual object. See [`InvocationPriority`].
+    #[serde(default)]
+    pub priority: InvocationPriority,
+
+    /// Per key concurrency limit of shared virtual object handlers. See [`ServiceInvocation::shared_concurrency_limit`].
+    #[serde(default)]
+    pub shared_concurrency_limit: Option<NonZeroU32>,
 }
 
 impl InvocationRequestHeader {
@@ -299,7 +347,12 @@ impl InvocationRequestHeader {
             span_context: ServiceInvocationSpanContext::empty(),
             idempotency_key: None,
             execution_time: None,
+            execution_wall_clock_time: None,
             completion_retention_duration: None,
+            pinned_deployment: None,
+            dry_run: false,
+            priority: InvocationPriority::default(),
+            shared_concurrency_limit: None,
         }
     }
 
@@ -343,6 +396,10 @@ impl WithInvocationId for InvocationRequest {
     }
 }
 
+/// Epoch of the current attempt of an invocation, which is incremented whenever the attempt is
+/// discarded, e.g. because the invocation is retried now or restarted from its input.
+pub type InvocationEpoch = u32;
+
 /// Struct representing an invocation to a service. This struct is processed by Restate to execute the invocation.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ServiceInvocation {
@@ -354,8 +411,29 @@ pub struct ServiceInvocation {
     pub headers: Vec<Header>,
     /// Time when the request should be executed
     pub execution_time: Option<MillisSinceEpoch>,
+    /// Wall-clock time in a timezone the `execution_time` was resolved from, if any
+    #[serde(default)]
+    pub execution_wall_clock_time: Option<ZonedDateTime>,
     pub completion_retention_duration: Option<Duration>,
     pub idempotency_key: Option<ByteString>,
+    /// Deployment to run this invocation on. If none, the latest deployment of the service is
+    /// chosen when the invocation starts.
+    #[serde(default)]
+    pub pinned_deployment: Option<PinnedDeployment>,
+    /// If true, the deployment is asked to suppress external side effects of the invocation, such
+    /// as the execution of `ctx.run` closures, while still running the handler logic. The
+    /// partition processor suppresses the messages to other invocations, and the invoker refuses
+    /// to run it on deployments which didn't accept the dry-run protocol extension.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Priority of the invocation in the inbox of a virtual object.
+    #[serde(default)]
+    pub priority: InvocationPriority,
+    /// If set, this invocation of a shared virtual object handler starts only if fewer than this
+    /// many invocations of shared handlers are running for the same key, otherwise it waits
+    /// until one of them completes.
+    #[serde(default)]
+    pub shared_concurrency_limit: Option<NonZeroU32>,
 
     // Where to send the response, if any
     pub response_sink: Option<ServiceInvocationResponseSink>,
@@ -365,6 +443,44 @@ pub struct ServiceInvocation {
     pub submit_notification_sink: Option<SubmitNotificationSink>,
 }
 
+/// Priority of an invocation waiting in the inbox of a virtual object. When the virtual object
+/// is unlocked, the inboxed invocation with the highest priority starts first. Among invocations
+/// with the same priority, those with the earliest deadline start first, followed by those
+/// without a deadline. Remaining ties are broken by arrival order.
+///
+/// The deadline only affects the order in which inboxed invocations start, invocations which
+/// didn't start before their deadline are not failed.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct InvocationPriority {
+    /// Invocations with higher priority start first. Defaults to 0.
+    #[serde(default)]
+    pub priority: i32,
+    /// Time by which the invocation should have started, if any.
+    #[serde(default)]
+    pub deadline: Option<MillisSinceEpoch>,
+}
+
+impl InvocationPriority {
+    pub const fn new(priority: i32, deadline: Option<MillisSinceEpoch>) -> Self {
+        Self { priority, deadline }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the key to sort inboxed invocations by, smaller keys start first.
+    pub fn sort_key(&self) -> impl Ord {
+        (
+            Reverse(self.priority),
+            self.deadline.is_none(),
+            self.deadline,
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SubmitNotificationSink {
     Ingress {
@@ -382,8 +498,13 @@ impl ServiceInvocation {
             span_context: request.header.span_context,
             headers: request.header.headers,
             execution_time: request.header.execution_time,
+            execution_wall_clock_time: request.header.execution_wall_clock_time,
             completion_retention_duration: request.header.completion_retention_duration,
             idempotency_key: request.header.idempotency_key,
+            pinned_deployment: request.header.pinned_deployment,
+            dry_run: request.header.dry_run,
+            priority: request.header.priority,
+            shared_concurrency_limit: request.header.shared_concurrency_limit,
             response_sink: None,
             submit_notification_sink: None,
         }
@@ -403,8 +524,13 @@ impl ServiceInvocation {
             span_context: ServiceInvocationSpanContext::empty(),
             headers: vec![],
             execution_time: None,
+            execution_wall_clock_time: None,
             completion_retention_duration: None,
             idempotency_key: None,
+            pinned_deployment: None,
+            dry_run: false,
+            priority: InvocationPriority::default(),
+            shared_concurrency_limit: None,
             submit_notification_sink: None,
         }
     }
@@ -784,12 +910,98 @@ pub enum TerminationFlavor {
     Cancel,
 }
 
+/// Message to terminate all invocations of a partition whose target matches the filter. The
+/// matching invocations are terminated as part of applying a single command.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BulkInvocationTermination {
+    pub filter: InvocationTargetFilter,
+    pub flavor: TerminationFlavor,
+}
+
+/// Selects invocations by their target.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationTargetFilter {
+    pub service_name: ByteString,
+    /// If set, only invocations of this handler match.
+    #[serde(default)]
+    pub handler_name: Option<ByteString>,
+    /// If set, only invocations of keyed services whose key starts with this prefix match.
+    #[serde(default)]
+    pub key_prefix: Option<ByteString>,
+}
+
+impl InvocationTargetFilter {
+    pub fn service(service_name: impl Into<ByteString>) -> Self {
+        Self {
+            service_name: service_name.into(),
+            handler_name: None,
+            key_prefix: None,
+        }
+    }
+
+    pub fn with_handler_name(mut self, handler_name: impl Into<ByteString>) -> Self {
+        self.handler_name = Some(handler_name.into());
+        self
+    }
+
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<ByteString>) -> Self {
+        self.key_prefix = Some(key_prefix.into());
+        self
+    }
+
+    pub fn matches(&self, invocation_target: &InvocationTarget) -> bool {
+        if invocation_target.service_name() != &self.service_name {
+            return false;
+        }
+        if self
+            .handler_name
+            .as_ref()
+            .is_some_and(|handler_name| invocation_target.handler_name() != handler_name)
+        {
+            return false;
+        }
+        match &self.key_prefix {
+            None => true,
+            Some(key_prefix) => invocation_target
+                .key()
+                .is_some_and(|key| key.starts_with(key_prefix.as_ref())),
+        }
+    }
+}
+
 /// Message to purge an invocation.
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct PurgeInvocationRequest {
     pub invocation_id: InvocationId,
 }
 
+/// Message to retry an invocation right away, without waiting for its retry timer or for the
+/// completions it's suspended on.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RetryInvocationRequest {
+    pub invocation_id: InvocationId,
+    /// If true, the journal is truncated to the input entry before retrying, so the invocation
+    /// restarts from scratch.
+    pub restart: bool,
+}
+
+/// Message to cancel, kill or purge the given invocations of a partition. The invocations are
+/// processed as part of applying a single command.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BulkInvocationOperation {
+    pub invocation_ids: Vec<InvocationId>,
+    pub operation: InvocationOperation,
+}
+
+/// Operation to apply to each invocation of a [`BulkInvocationOperation`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum InvocationOperation {
+    /// Terminate the invocation, if it's not completed yet
+    Terminate(TerminationFlavor),
+    /// Purge the invocation, if it's completed
+    Purge,
+}
+
 // A hack to allow spancontext to be serialized.
 // Details in https://github.com/open-telemetry/opentelemetry-rust/issues/576#issuecomment-1253396100
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -995,8 +1207,13 @@ mod mocks {
                 span_context: Default::default(),
                 headers: vec![],
                 execution_time: None,
+                execution_wall_clock_time: None,
                 completion_retention_duration: None,
                 idempotency_key: None,
+                pinned_deployment: None,
+                dry_run: false,
+                priority: Default::default(),
+                shared_concurrency_limit: None,
                 submit_notification_sink: None,
             }
         }
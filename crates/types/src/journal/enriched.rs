@@ -13,6 +13,7 @@ use super::*;
 
 use crate::identifiers::InvocationId;
 use crate::invocation::{InvocationTarget, ServiceInvocationSpanContext};
+use std::num::NonZeroU32;
 use std::time::Duration;
 
 pub type EnrichedEntryHeader = EntryHeader<CallEnrichmentResult, AwakeableEnrichmentResult>;
@@ -24,6 +25,9 @@ pub struct CallEnrichmentResult {
     pub invocation_id: InvocationId,
     pub invocation_target: InvocationTarget,
     pub completion_retention_time: Option<Duration>,
+    /// Per key concurrency limit of the invoked shared virtual object handler, if any.
+    #[serde(default)]
+    pub shared_concurrency_limit: Option<NonZeroU32>,
 
     // When resolving the service and generating its id, we also generate the associated span
     pub span_context: ServiceInvocationSpanContext,
@@ -31,6 +31,7 @@ pub mod identifiers;
 pub mod invocation;
 pub mod journal;
 pub mod live;
+pub mod locality;
 pub mod logs;
 pub mod message;
 pub mod metadata_store;
@@ -40,6 +41,7 @@ pub mod partition_table;
 pub mod protobuf;
 pub mod replicated_loglet;
 pub mod retries;
+pub mod schedule;
 pub mod schema;
 pub mod service_discovery;
 pub mod service_protocol;
@@ -0,0 +1,157 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Maximum length of a location label, same as a Kubernetes label value.
+const MAX_LABEL_LEN: usize = 63;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum InvalidNodeLocation {
+    #[error("location '{0}' has more than two labels, expected 'region[.zone]'")]
+    TooManyLabels(String),
+    #[error("location '{0}' has an empty region")]
+    EmptyRegion(String),
+    #[error("location label '{0}' is longer than {MAX_LABEL_LEN} characters")]
+    LabelTooLong(String),
+    #[error("location label '{0}' must only contain alphanumeric characters, '-' and '_'")]
+    InvalidLabel(String),
+}
+
+/// The failure domain of a node in the form of `region[.zone]`, e.g. `eu-central-1.eu-central-1a`.
+/// An empty location means that the location of the node is unknown.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    serde_with::SerializeDisplay,
+    serde_with::DeserializeFromStr,
+)]
+pub struct NodeLocation {
+    region: String,
+    zone: String,
+}
+
+impl NodeLocation {
+    pub fn new(
+        region: impl Into<String>,
+        zone: impl Into<String>,
+    ) -> Result<Self, InvalidNodeLocation> {
+        let location = Self {
+            region: region.into(),
+            zone: zone.into(),
+        };
+        if location.region.is_empty() && !location.zone.is_empty() {
+            return Err(InvalidNodeLocation::EmptyRegion(location.to_string()));
+        }
+        validate_label(&location.region)?;
+        validate_label(&location.zone)?;
+
+        Ok(location)
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn zone(&self) -> &str {
+        &self.zone
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.region.is_empty()
+    }
+}
+
+fn validate_label(label: &str) -> Result<(), InvalidNodeLocation> {
+    if label.len() > MAX_LABEL_LEN {
+        return Err(InvalidNodeLocation::LabelTooLong(label.to_owned()));
+    }
+    if !label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(InvalidNodeLocation::InvalidLabel(label.to_owned()));
+    }
+    Ok(())
+}
+
+impl fmt::Display for NodeLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.zone.is_empty() {
+            write!(f, "{}", self.region)
+        } else {
+            write!(f, "{}.{}", self.region, self.zone)
+        }
+    }
+}
+
+impl FromStr for NodeLocation {
+    type Err = InvalidNodeLocation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut labels = s.split('.');
+        let region = labels.next().unwrap_or_default();
+        let zone = labels.next().unwrap_or_default();
+        if labels.next().is_some() {
+            return Err(InvalidNodeLocation::TooManyLabels(s.to_owned()));
+        }
+        if region.is_empty() && s.contains('.') {
+            return Err(InvalidNodeLocation::EmptyRegion(s.to_owned()));
+        }
+
+        NodeLocation::new(region, zone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_node_location() {
+        let location: NodeLocation = "eu-central-1.eu-central-1a".parse().unwrap();
+        assert_eq!(location.region(), "eu-central-1");
+        assert_eq!(location.zone(), "eu-central-1a");
+        assert_eq!(location.to_string(), "eu-central-1.eu-central-1a");
+
+        let location: NodeLocation = "us-east-1".parse().unwrap();
+        assert_eq!(location.region(), "us-east-1");
+        assert_eq!(location.zone(), "");
+        assert_eq!(location.to_string(), "us-east-1");
+
+        assert!("".parse::<NodeLocation>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_node_location() {
+        assert_eq!(
+            "a.b.c".parse::<NodeLocation>(),
+            Err(InvalidNodeLocation::TooManyLabels("a.b.c".to_owned()))
+        );
+        assert_eq!(
+            ".zone".parse::<NodeLocation>(),
+            Err(InvalidNodeLocation::EmptyRegion(".zone".to_owned()))
+        );
+        assert_eq!(
+            "eu central".parse::<NodeLocation>(),
+            Err(InvalidNodeLocation::InvalidLabel("eu central".to_owned()))
+        );
+        assert!(matches!(
+            "a".repeat(64).parse::<NodeLocation>(),
+            Err(InvalidNodeLocation::LabelTooLong(_))
+        ));
+    }
+}
@@ -12,7 +12,8 @@ use std::num::NonZeroU32;
 use std::ops::Deref;
 
 use super::metadata::{
-    Chain, LogletConfig, LogletParams, Logs, LookupIndex, MaybeSegment, ProviderKind, SegmentIndex,
+    Chain, LogletConfig, LogletParams, Logs, LogsConfiguration, LookupIndex, MaybeSegment,
+    ProviderKind, SegmentIndex,
 };
 use super::{LogId, Lsn};
 use crate::replicated_loglet::ReplicatedLogletParams;
@@ -71,12 +72,31 @@ impl LogsBuilder {
         })
     }
 
+    /// Sets the provider that is used for new segments of the given log. Passing `None`
+    /// removes a previous override.
+    pub fn set_provider(&mut self, log_id: LogId, provider: Option<ProviderKind>) {
+        let previous = match provider {
+            Some(provider) => self.inner.config.providers.insert(log_id, provider),
+            None => self.inner.config.providers.remove(&log_id),
+        };
+        self.modified |= previous != provider;
+    }
+
+    /// Replaces the logs configuration.
+    pub fn set_configuration(&mut self, config: LogsConfiguration) {
+        if self.inner.config != config {
+            self.inner.config = config;
+            self.modified = true;
+        }
+    }
+
     /// Bumps the version and returns the constructed log metadata.
     pub fn build(self) -> Logs {
         Logs {
             version: self.inner.version.next(),
             logs: self.inner.logs,
             lookup_index: self.inner.lookup_index,
+            config: self.inner.config,
         }
     }
 
@@ -92,6 +112,7 @@ impl LogsBuilder {
                 version: self.inner.version.next(),
                 logs: self.inner.logs,
                 lookup_index: self.inner.lookup_index,
+                config: self.inner.config,
             })
         } else {
             None
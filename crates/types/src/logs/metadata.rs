@@ -117,6 +117,7 @@ pub struct Logs {
     pub(super) version: Version,
     pub(super) logs: HashMap<LogId, Chain, Xxh3Builder>,
     pub(super) lookup_index: LookupIndex,
+    pub(super) config: LogsConfiguration,
 }
 
 impl Default for Logs {
@@ -125,6 +126,7 @@ impl Default for Logs {
             version: Version::INVALID,
             logs: Default::default(),
             lookup_index: Default::default(),
+            config: Default::default(),
         }
     }
 }
@@ -134,6 +136,7 @@ impl From<Logs> for LogsSerde {
         Self {
             version: value.version,
             logs: value.logs.into_iter().collect(),
+            config: value.config,
         }
     }
 }
@@ -159,6 +162,7 @@ impl TryFrom<LogsSerde> for Logs {
             version: value.version,
             logs,
             lookup_index,
+            config: value.config,
         })
     }
 }
@@ -172,6 +176,38 @@ struct LogsSerde {
     version: Version,
     // flexbuffers only supports string-keyed maps :-( --> so we store it as vector of kv pairs
     logs: Vec<(LogId, Chain)>,
+    // serde(default) for compatibility with logs metadata written before the configuration was
+    // introduced.
+    #[serde(default)]
+    config: LogsConfiguration,
+}
+
+/// Cluster-wide configuration of logs that is stored alongside the log chains in the metadata
+/// store. It determines which loglet provider is used when the logs controller provisions new
+/// logs or extends the chain of an existing log.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsConfiguration {
+    /// Provider used for logs without an explicit override. If unset, the node's configured
+    /// `bifrost.default-provider` is used.
+    #[serde(default)]
+    pub default_provider: Option<ProviderKind>,
+    /// Per-log provider overrides.
+    // flexbuffers only supports string-keyed maps :-( --> so we store it as vector of kv pairs
+    #[serde(default)]
+    #[serde_as(as = "serde_with::Seq<(_, _)>")]
+    pub providers: BTreeMap<LogId, ProviderKind>,
+}
+
+impl LogsConfiguration {
+    /// Returns the provider that should be used for new segments of the given log, if one is
+    /// configured.
+    pub fn provider_for(&self, log_id: LogId) -> Option<ProviderKind> {
+        self.providers
+            .get(&log_id)
+            .copied()
+            .or(self.default_provider)
+    }
 }
 
 /// the chain is a list of segments in (from Lsn) order.
@@ -327,6 +363,10 @@ impl Logs {
         self.into()
     }
 
+    pub fn configuration(&self) -> &LogsConfiguration {
+        &self.config
+    }
+
     pub fn get_replicated_loglet(
         &self,
         loglet_id: &ReplicatedLogletId,
@@ -521,4 +561,23 @@ mod tests {
         assert_eq!(ProviderKind::Local, config.kind);
         assert_eq!("test".to_string(), config.params.0.to_string());
     }
+
+    #[test]
+    fn test_logs_configuration_provider_for() {
+        let mut config = LogsConfiguration::default();
+        assert_eq!(None, config.provider_for(LogId::from(1u32)));
+
+        config.default_provider = Some(ProviderKind::Local);
+        config
+            .providers
+            .insert(LogId::from(1u32), ProviderKind::Replicated);
+        assert_eq!(
+            Some(ProviderKind::Replicated),
+            config.provider_for(LogId::from(1u32))
+        );
+        assert_eq!(
+            Some(ProviderKind::Local),
+            config.provider_for(LogId::from(2u32))
+        );
+    }
 }
@@ -12,6 +12,7 @@ pub mod keys {
     //! Keys of values stored in the metadata store
 
     use crate::identifiers::PartitionId;
+    use crate::Version;
     use bytestring::ByteString;
 
     pub static NODES_CONFIG_KEY: ByteString = ByteString::from_static("nodes_config");
@@ -20,10 +21,15 @@ pub mod keys {
     pub static PARTITION_PROCESSOR_EPOCH_PREFIX: &str = "pp_epoch";
 
     pub static SCHEMA_INFORMATION_KEY: ByteString = ByteString::from_static("schema_registry");
+    pub static SCHEMA_VERSION_PREFIX: &str = "schema_registry_version";
 
     pub static SCHEDULING_PLAN_KEY: ByteString = ByteString::from_static("scheduling_plan");
 
     pub fn partition_processor_epoch_key(partition_id: PartitionId) -> ByteString {
         ByteString::from(format!("{PARTITION_PROCESSOR_EPOCH_PREFIX}_{partition_id}"))
     }
+
+    pub fn schema_version_key(version: Version) -> ByteString {
+        ByteString::from(format!("{SCHEMA_VERSION_PREFIX}_{}", u32::from(version)))
+    }
 }
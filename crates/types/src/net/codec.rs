@@ -10,15 +10,17 @@
 
 use std::sync::Arc;
 
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use crate::config::{NetworkMessageCompression, NetworkingOptions};
 use crate::net::CodecError;
 use crate::protobuf::common::ProtocolVersion;
 use crate::protobuf::common::TargetName;
 use crate::protobuf::node::message;
 use crate::protobuf::node::message::BinaryMessage;
+use crate::protobuf::node::MessageCompression;
 use crate::storage::{decode_from_flexbuffers, encode_as_flexbuffers};
 
 pub trait Targeted {
@@ -130,9 +132,109 @@ pub fn serialize_message<M: WireEncode + Targeted>(
     Ok(message::Body::Encoded(BinaryMessage {
         target,
         payload: payload.freeze(),
+        compression: MessageCompression::None.into(),
     }))
 }
 
+/// Compression and size limits of the payloads of the messages exchanged on a connection.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadCodec {
+    compression: MessageCompression,
+    compression_threshold: usize,
+    max_message_size: usize,
+}
+
+impl PayloadCodec {
+    /// Uses the compression negotiated with the peer, if any.
+    pub fn new(compression: MessageCompression, options: &NetworkingOptions) -> Self {
+        Self {
+            compression,
+            compression_threshold: options.message_compression_threshold.get(),
+            max_message_size: options.max_message_size.get(),
+        }
+    }
+
+    pub fn compression(&self) -> MessageCompression {
+        self.compression
+    }
+
+    /// Compresses the payload if it's larger than the compression threshold.
+    pub fn encode(&self, message: &mut BinaryMessage) -> Result<(), CodecError> {
+        let size = message.payload.len();
+        if size > self.max_message_size {
+            return Err(CodecError::MessageTooLarge {
+                size,
+                limit: self.max_message_size,
+            });
+        }
+        if size < self.compression_threshold {
+            return Ok(());
+        }
+
+        let compressed = match self.compression {
+            MessageCompression::None => return Ok(()),
+            MessageCompression::Zstd => zstd::bulk::compress(&message.payload, 0)
+                .map_err(|err| CodecError::Encode(err.into()))?,
+            MessageCompression::Lz4 => lz4_flex::compress_prepend_size(&message.payload),
+        };
+        message.payload = Bytes::from(compressed);
+        message.set_compression(self.compression);
+        Ok(())
+    }
+
+    /// Decompresses the payload with the compression of the message, which is not necessarily the
+    /// negotiated one.
+    pub fn decode(&self, message: &mut BinaryMessage) -> Result<(), CodecError> {
+        let compression = MessageCompression::try_from(message.compression).map_err(|_| {
+            CodecError::Decode(
+                format!("unknown message compression {}", message.compression).into(),
+            )
+        })?;
+
+        let size = match compression {
+            MessageCompression::None => message.payload.len(),
+            MessageCompression::Zstd => zstd::zstd_safe::get_frame_content_size(&message.payload)
+                .ok()
+                .flatten()
+                .ok_or_else(|| CodecError::Decode("missing zstd frame content size".into()))?
+                as usize,
+            MessageCompression::Lz4 => {
+                lz4_flex::block::uncompressed_size(&message.payload)
+                    .map_err(|err| CodecError::Decode(err.into()))?
+                    .0
+            }
+        };
+        // Checked before decompressing, to not allocate the buffer of a too large message
+        if size > self.max_message_size {
+            return Err(CodecError::MessageTooLarge {
+                size,
+                limit: self.max_message_size,
+            });
+        }
+
+        let decompressed = match compression {
+            MessageCompression::None => return Ok(()),
+            MessageCompression::Zstd => zstd::bulk::decompress(&message.payload, size)
+                .map_err(|err| CodecError::Decode(err.into()))?,
+            MessageCompression::Lz4 => lz4_flex::decompress_size_prepended(&message.payload)
+                .map_err(|err| CodecError::Decode(err.into()))?,
+        };
+        message.payload = Bytes::from(decompressed);
+        message.set_compression(MessageCompression::None);
+        Ok(())
+    }
+}
+
+impl From<NetworkMessageCompression> for MessageCompression {
+    fn from(value: NetworkMessageCompression) -> Self {
+        match value {
+            NetworkMessageCompression::None => MessageCompression::None,
+            NetworkMessageCompression::Zstd => MessageCompression::Zstd,
+            NetworkMessageCompression::Lz4 => MessageCompression::Lz4,
+        }
+    }
+}
+
 /// Helper function for default encoding of values.
 pub fn encode_default<T: Serialize, B: BufMut>(
     value: T,
@@ -190,3 +292,84 @@ impl MessageBodyExt for crate::protobuf::node::message::Body {
         <T as WireDecode>::decode(&mut binary_message.payload, protocol_version)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroUsize;
+
+    use crate::protobuf::common::TargetName;
+
+    fn codec(compression: MessageCompression, max_message_size: usize) -> PayloadCodec {
+        PayloadCodec::new(
+            compression,
+            &NetworkingOptions {
+                message_compression_threshold: NonZeroUsize::new(1024).unwrap(),
+                max_message_size: NonZeroUsize::new(max_message_size).unwrap(),
+                ..NetworkingOptions::default()
+            },
+        )
+    }
+
+    fn binary_message(payload: Bytes) -> BinaryMessage {
+        BinaryMessage {
+            target: TargetName::Unknown.into(),
+            payload,
+            compression: MessageCompression::None.into(),
+        }
+    }
+
+    #[test]
+    fn compress_large_payloads() {
+        let payload = Bytes::from(vec![42; 16 * 1024]);
+        for compression in [MessageCompression::Zstd, MessageCompression::Lz4] {
+            let codec = codec(compression, 1024 * 1024);
+            let mut message = binary_message(payload.clone());
+
+            codec.encode(&mut message).unwrap();
+            assert_eq!(message.compression(), compression);
+            assert!(message.payload.len() < payload.len());
+
+            codec.decode(&mut message).unwrap();
+            assert_eq!(message.compression(), MessageCompression::None);
+            assert_eq!(message.payload, payload);
+        }
+    }
+
+    #[test]
+    fn skip_compression_of_small_payloads() {
+        let codec = codec(MessageCompression::Zstd, 1024 * 1024);
+        let mut message = binary_message(Bytes::from_static(b"small"));
+
+        codec.encode(&mut message).unwrap();
+        assert_eq!(message.compression(), MessageCompression::None);
+        assert_eq!(message.payload, Bytes::from_static(b"small"));
+    }
+
+    #[test]
+    fn reject_too_large_messages() {
+        let payload = Bytes::from(vec![42; 16 * 1024]);
+        let codec_with_limit = codec(MessageCompression::Zstd, 8 * 1024);
+        assert!(matches!(
+            codec_with_limit.encode(&mut binary_message(payload.clone())),
+            Err(CodecError::MessageTooLarge {
+                size: 16384,
+                limit: 8192
+            })
+        ));
+
+        // the limit applies to the decompressed payload
+        let mut message = binary_message(payload);
+        codec(MessageCompression::Zstd, 1024 * 1024)
+            .encode(&mut message)
+            .unwrap();
+        assert!(matches!(
+            codec_with_limit.decode(&mut message),
+            Err(CodecError::MessageTooLarge {
+                size: 16384,
+                limit: 8192
+            })
+        ));
+    }
+}
@@ -16,4 +16,6 @@ pub enum CodecError {
     Encode(GenericError),
     #[error("decode error: {0}")]
     Decode(GenericError),
+    #[error("message of {size} bytes exceeds the max message size of {limit} bytes")]
+    MessageTooLarge { size: usize, limit: usize },
 }
@@ -14,7 +14,9 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::TargetName;
-use crate::{cluster::cluster_state::PartitionProcessorStatus, identifiers::PartitionId};
+use crate::cluster::cluster_state::{NodeLoad, PartitionProcessorStatus};
+use crate::identifiers::PartitionId;
+use crate::GenerationalNodeId;
 
 super::define_rpc! {
     @request=GetNodeState,
@@ -32,4 +34,25 @@ pub struct NodeStateResponse {
     /// State of paritions processor per parition. Is set to None if this node is not a `Worker` node
     #[serde_as(as = "Option<serde_with::Seq<(_, _)>>")]
     pub partition_processor_state: Option<BTreeMap<PartitionId, PartitionProcessorStatus>>,
+    #[serde(default)]
+    pub load: NodeLoad,
+}
+
+super::define_message! {
+    @message = Gossip,
+    @target = TargetName::NodeGossip,
+}
+
+/// Heartbeats of the nodes known to the sender, including its own one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Gossip {
+    pub heartbeats: Vec<NodeHeartbeat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeHeartbeat {
+    pub node_id: GenerationalNodeId,
+    /// Incremented by the node at every gossip round. It restarts from zero with every new
+    /// generation of the node.
+    pub counter: u64,
 }
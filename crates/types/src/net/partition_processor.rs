@@ -10,11 +10,13 @@
 
 use crate::errors::InvocationError;
 use crate::identifiers::{
-    InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
+    EntryIndex, InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
+    WithPartitionKey,
 };
 use crate::invocation::{InvocationQuery, InvocationRequest, InvocationResponse, InvocationTarget};
 use crate::net::define_rpc;
 use crate::net::TargetName;
+use crate::schedule::Schedule;
 use crate::time::MillisSinceEpoch;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
@@ -57,6 +59,14 @@ pub enum PartitionProcessorRpcRequestInner {
     AppendInvocation(InvocationRequest, AppendInvocationReplyOn),
     GetInvocationOutput(InvocationQuery, GetInvocationOutputResponseMode),
     AppendInvocationResponse(InvocationResponse),
+    /// Appends a batch of invocations, replying with [`PartitionProcessorRpcResponse::Appended`]
+    /// once all of them are appended. All the invocations must belong to the same partition.
+    AppendInvocations(Vec<InvocationRequest>),
+    /// Reads the [`InvocationProgress`] of an invocation, replying immediately.
+    GetInvocationProgress(InvocationQuery),
+    /// Creates or replaces a recurring [`Schedule`], replying with
+    /// [`PartitionProcessorRpcResponse::Appended`] once appended.
+    AppendSchedule(Schedule),
 }
 
 impl WithPartitionKey for PartitionProcessorRpcRequestInner {
@@ -65,6 +75,12 @@ impl WithPartitionKey for PartitionProcessorRpcRequestInner {
             PartitionProcessorRpcRequestInner::AppendInvocation(si, _) => si.partition_key(),
             PartitionProcessorRpcRequestInner::GetInvocationOutput(iq, _) => iq.partition_key(),
             PartitionProcessorRpcRequestInner::AppendInvocationResponse(ir) => ir.partition_key(),
+            PartitionProcessorRpcRequestInner::AppendInvocations(invocations) => invocations
+                .first()
+                .map(WithPartitionKey::partition_key)
+                .unwrap_or_default(),
+            PartitionProcessorRpcRequestInner::GetInvocationProgress(iq) => iq.partition_key(),
+            PartitionProcessorRpcRequestInner::AppendSchedule(schedule) => schedule.partition_key(),
         }
     }
 }
@@ -83,6 +99,8 @@ pub enum PartitionProcessorRpcError {
     Starting,
     #[error("partition processor stopping")]
     Stopping,
+    #[error("partition '{0}' is overloaded, rejecting new invocations")]
+    Overloaded(PartitionId),
 }
 
 impl PartitionProcessorRpcError {
@@ -94,6 +112,7 @@ impl PartitionProcessorRpcError {
             PartitionProcessorRpcError::Internal(_) => false,
             PartitionProcessorRpcError::Starting => false,
             PartitionProcessorRpcError::Stopping => false,
+            PartitionProcessorRpcError::Overloaded(_) => false,
         }
     }
 }
@@ -106,6 +125,7 @@ pub enum PartitionProcessorRpcResponse {
     NotSupported,
     Submitted(SubmittedInvocationNotification),
     Output(InvocationOutput),
+    Progress(InvocationProgress),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -124,6 +144,28 @@ pub struct InvocationOutput {
     pub response: IngressResponseResult,
 }
 
+/// Snapshot of the progress of a not yet completed invocation.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationProgress {
+    pub invocation_id: InvocationId,
+    pub status: InvocationProgressStatus,
+    /// Number of entries of the journal, 0 if the invocation isn't running.
+    pub journal_length: EntryIndex,
+    /// Target of the invocation, used to authorize access to the invocation by id. Not sent by
+    /// older nodes.
+    #[serde(default)]
+    pub invocation_target: Option<InvocationTarget>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InvocationProgressStatus {
+    Scheduled,
+    Inboxed,
+    Invoked,
+    Suspended,
+    Completed,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum IngressResponseResult {
     Success(InvocationTarget, Bytes),
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::cluster::cluster_state::RunMode;
 use crate::identifiers::{PartitionId, SnapshotId};
+use crate::logs::Lsn;
 use crate::net::define_rpc;
 use crate::net::{define_message, TargetName};
 use crate::Version;
@@ -81,3 +82,34 @@ pub struct CreateSnapshotResponse {
 pub enum SnapshotError {
     SnapshotCreationFailed(String),
 }
+
+define_rpc! {
+    @request = SplitPartitionRequest,
+    @response = SplitPartitionResponse,
+    @request_target = TargetName::PartitionSplitRequest,
+    @response_target = TargetName::PartitionSplitResponse,
+}
+
+/// Asks the node running the leader of a partition to create the partition store of a partition
+/// which is being split off from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitPartitionRequest {
+    pub partition_id: PartitionId,
+    pub child_partition_id: PartitionId,
+    /// The partition table version which contains the split.
+    pub min_partition_table_version: Version,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitPartitionResponse {
+    /// The applied LSN of the parent partition at which the split was performed.
+    pub result: Result<Lsn, SplitPartitionError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SplitPartitionError {
+    /// The split failed before it was started.
+    SplitFailed(String),
+    /// The split was started but did not complete. Retrying the split completes it.
+    Incomplete(String),
+}
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use enumset::{EnumSet, EnumSetType};
 use serde_with::serde_as;
 
+use crate::locality::NodeLocation;
 use crate::net::AdvertisedAddress;
 use crate::{flexbuffers_storage_encode_decode, GenerationalNodeId, NodeId, PlainNodeId};
 use crate::{Version, Versioned};
@@ -80,6 +81,37 @@ enum MaybeNode {
     Node(NodeConfig),
 }
 
+/// Version of the cluster-wide data formats, e.g. the commands written to the partitions' logs,
+/// which a node understands. New formats may only be written once every node of the cluster
+/// understands them, see [`NodesConfiguration::cluster_version`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    derive_more::Display,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct ClusterVersion(u32);
+
+impl ClusterVersion {
+    /// Version of the nodes which don't advertise a version.
+    pub const UNKNOWN: ClusterVersion = ClusterVersion(0);
+    /// Adds the commands to dead-letter records and to re-inject them, and to split partitions.
+    pub const V1: ClusterVersion = ClusterVersion(1);
+    /// Adds the invoker effect recording the scheduled retries of an invocation.
+    pub const V2: ClusterVersion = ClusterVersion(2);
+
+    /// Version understood by this node.
+    pub const CURRENT: ClusterVersion = ClusterVersion::V2;
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct NodeConfig {
     pub name: String,
@@ -88,6 +120,11 @@ pub struct NodeConfig {
     pub roles: EnumSet<Role>,
     #[serde(default)]
     pub log_server_config: LogServerConfig,
+    #[serde(default, skip_serializing_if = "NodeLocation::is_empty")]
+    pub location: NodeLocation,
+    /// Cluster version understood by the node, updated whenever the node starts.
+    #[serde(default)]
+    pub cluster_version: ClusterVersion,
 }
 
 impl NodeConfig {
@@ -104,6 +141,8 @@ impl NodeConfig {
             address,
             roles,
             log_server_config,
+            location: NodeLocation::default(),
+            cluster_version: ClusterVersion::CURRENT,
         }
     }
 
@@ -236,6 +275,14 @@ impl NodesConfiguration {
         })
     }
 
+    /// Returns the cluster version understood by all nodes of the cluster.
+    pub fn cluster_version(&self) -> ClusterVersion {
+        self.iter()
+            .map(|(_, node)| node.cluster_version)
+            .min()
+            .unwrap_or(ClusterVersion::CURRENT)
+    }
+
     /// Returns the maximum known plain node id.
     pub fn max_plain_node_id(&self) -> Option<PlainNodeId> {
         self.nodes.keys().max().cloned()
@@ -47,6 +47,9 @@ pub struct PartitionTable {
     // verify that the start partition key is smaller or equal than the given key, because holes
     // are not visible from this index structure.
     partition_key_index: BTreeMap<PartitionKey, PartitionId>,
+    // The id of the next partition to allocate. Partition ids are never reused since the log and
+    // the partition stores of a removed partition might outlive it.
+    next_partition_id: u32,
 }
 
 impl Default for PartitionTable {
@@ -55,6 +58,7 @@ impl Default for PartitionTable {
             version: Version::INVALID,
             partitions: BTreeMap::default(),
             partition_key_index: BTreeMap::default(),
+            next_partition_id: 0,
         }
     }
 }
@@ -109,6 +113,16 @@ impl PartitionTable {
     pub fn contains_partition(&self, partition_id: &PartitionId) -> bool {
         self.partitions.contains_key(partition_id)
     }
+
+    /// Returns the partition which is being split off from the given partition, if any.
+    pub fn splitting_child(&self, partition_id: &PartitionId) -> Option<PartitionId> {
+        self.partitions
+            .iter()
+            .find(|(_, partition)| {
+                partition.splitting && partition.split_from == Some(*partition_id)
+            })
+            .map(|(child_partition_id, _)| *child_partition_id)
+    }
 }
 
 impl Versioned for PartitionTable {
@@ -150,11 +164,22 @@ impl FindPartition for PartitionTable {
 #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Partition {
     pub key_range: RangeInclusive<PartitionKey>,
+    /// The partition from which this partition has been split off, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_from: Option<PartitionId>,
+    /// Set while the split which creates this partition is in progress. Such a partition must not
+    /// be run before its partition store has been created from the parent partition.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub splitting: bool,
 }
 
 impl Partition {
     pub fn new(key_range: RangeInclusive<PartitionKey>) -> Self {
-        Self { key_range }
+        Self {
+            key_range,
+            split_from: None,
+            splitting: false,
+        }
     }
 }
 
@@ -167,6 +192,16 @@ pub enum BuilderError {
     Duplicate(PartitionId),
     #[error("partition table has reached its limits")]
     LimitReached,
+    #[error("partition '{0}' does not exist")]
+    UnknownPartition(PartitionId),
+    #[error(
+        "split key '{0}' must be within the key range of partition '{1}' and larger than its start"
+    )]
+    InvalidSplitKey(PartitionKey, PartitionId),
+    #[error("partition '{0}' is not being split off")]
+    NotSplitting(PartitionId),
+    #[error("partition '{1}' is being split off from partition '{0}' already")]
+    SplitInProgress(PartitionId, PartitionId),
 }
 
 #[derive(Debug, Default)]
@@ -198,6 +233,13 @@ impl PartitionTableBuilder {
             return Err(BuilderError::LimitReached);
         }
 
+        if let Some(splitting_child_id) = self.inner.splitting_child(&partition_id) {
+            return Err(BuilderError::SplitInProgress(
+                partition_id,
+                splitting_child_id,
+            ));
+        }
+
         let start = *partition.key_range.start();
         let end = *partition.key_range.end();
 
@@ -218,10 +260,28 @@ impl PartitionTableBuilder {
 
         self.inner.partitions.insert(partition_id, partition);
         self.inner.partition_key_index.insert(end, partition_id);
+        self.reserve_partition_id(partition_id);
 
         Ok(())
     }
 
+    /// Allocates the id of a new partition. Ids are allocated monotonically, so the id of a
+    /// removed partition is not handed out again.
+    pub fn allocate_partition_id(&mut self) -> Result<PartitionId, BuilderError> {
+        let partition_id = u16::try_from(self.inner.next_partition_id)
+            .map(PartitionId::from)
+            .map_err(|_| BuilderError::LimitReached)?;
+        self.inner.next_partition_id += 1;
+        Ok(partition_id)
+    }
+
+    fn reserve_partition_id(&mut self, partition_id: PartitionId) {
+        self.inner.next_partition_id = self
+            .inner
+            .next_partition_id
+            .max(u32::from(*partition_id) + 1);
+    }
+
     pub fn remove_partition(&mut self, partition_id: &PartitionId) {
         if let Some(partition) = self.inner.partitions.remove(partition_id) {
             self.inner
@@ -230,6 +290,106 @@ impl PartitionTableBuilder {
         }
     }
 
+    /// Splits the partition at the given split key. The partition retains the keys before the
+    /// split key whereas the keys starting at the split key are moved to a new partition with the
+    /// given child partition id. The new partition is marked as splitting until
+    /// [`Self::complete_split`] is called.
+    pub fn split_partition(
+        &mut self,
+        partition_id: PartitionId,
+        split_key: PartitionKey,
+        child_partition_id: PartitionId,
+    ) -> Result<(), BuilderError> {
+        if self.inner.partitions.contains_key(&child_partition_id) {
+            return Err(BuilderError::Duplicate(child_partition_id));
+        }
+
+        if self.inner.partitions.len() > usize::from(*PartitionId::MAX) {
+            return Err(BuilderError::LimitReached);
+        }
+
+        let partition = self
+            .inner
+            .partitions
+            .get_mut(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+        let start = *partition.key_range.start();
+        let end = *partition.key_range.end();
+
+        if split_key <= start || split_key > end {
+            return Err(BuilderError::InvalidSplitKey(split_key, partition_id));
+        }
+
+        partition.key_range = start..=(split_key - 1);
+        self.inner.partition_key_index.remove(&end);
+        self.inner
+            .partition_key_index
+            .insert(split_key - 1, partition_id);
+
+        self.inner.partitions.insert(
+            child_partition_id,
+            Partition {
+                key_range: split_key..=end,
+                split_from: Some(partition_id),
+                splitting: true,
+            },
+        );
+        self.inner
+            .partition_key_index
+            .insert(end, child_partition_id);
+        self.reserve_partition_id(child_partition_id);
+
+        Ok(())
+    }
+
+    /// Marks the split which created the given partition as completed.
+    pub fn complete_split(&mut self, partition_id: PartitionId) -> Result<(), BuilderError> {
+        let partition = self
+            .inner
+            .partitions
+            .get_mut(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+        if !partition.splitting {
+            return Err(BuilderError::NotSplitting(partition_id));
+        }
+
+        partition.splitting = false;
+        Ok(())
+    }
+
+    /// Reverts an in-progress split by removing the given partition and handing its key range
+    /// back to the partition it was split off from.
+    pub fn abort_split(&mut self, partition_id: PartitionId) -> Result<(), BuilderError> {
+        let partition = self
+            .inner
+            .partitions
+            .get(&partition_id)
+            .ok_or(BuilderError::UnknownPartition(partition_id))?;
+
+        let parent_id = match partition.split_from {
+            Some(parent_id) if partition.splitting => parent_id,
+            _ => return Err(BuilderError::NotSplitting(partition_id)),
+        };
+        let end = *partition.key_range.end();
+
+        let parent = self
+            .inner
+            .partitions
+            .get_mut(&parent_id)
+            .ok_or(BuilderError::UnknownPartition(parent_id))?;
+        self.inner
+            .partition_key_index
+            .remove(parent.key_range.end());
+        parent.key_range = *parent.key_range.start()..=end;
+
+        self.inner.partitions.remove(&partition_id);
+        self.inner.partition_key_index.insert(end, parent_id);
+
+        Ok(())
+    }
+
     /// Builds the new [`PartitionTable`] with an incremented version.
     pub fn build(mut self) -> PartitionTable {
         self.inner.version = Version::MIN.max(self.inner.version.next());
@@ -261,6 +421,9 @@ struct PartitionTableShadow {
     // flexbuffers only supports string-keyed maps :-( --> so we store it as vector of kv pairs
     #[serde_as(as = "Option<serde_with::Seq<(_, _)>>")]
     partitions: Option<BTreeMap<PartitionId, Partition>>,
+    // unset if the data has been written before partitions could be split
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_partition_id: Option<u32>,
 }
 
 impl From<PartitionTable> for PartitionTableShadow {
@@ -270,6 +433,7 @@ impl From<PartitionTable> for PartitionTableShadow {
             version: value.version,
             num_partitions,
             partitions: Some(value.partitions),
+            next_partition_id: Some(value.next_partition_id),
         }
     }
 }
@@ -286,6 +450,10 @@ impl TryFrom<PartitionTableShadow> for PartitionTable {
                 for (partition_id, partition) in partitions {
                     builder.add_partition(partition_id, partition)?;
                 }
+                builder.inner.next_partition_id = builder
+                    .inner
+                    .next_partition_id
+                    .max(value.next_partition_id.unwrap_or_default());
 
                 Ok(builder.build_with_same_version())
             }
@@ -477,4 +645,60 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn split_partition() -> googletest::Result<()> {
+        let partition_table = PartitionTable::with_equally_sized_partitions(Version::MIN, 1);
+        let parent = PartitionId::MIN;
+        let child = PartitionId::from(1);
+        let split_key = PartitionKey::MAX / 2;
+
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        assert_eq!(builder.allocate_partition_id()?, child);
+        assert!(builder.split_partition(parent, 0, child).is_err());
+        builder.split_partition(parent, split_key, child)?;
+        let partition_table = builder.build();
+
+        assert_eq!(partition_table.num_partitions(), 2);
+        assert_eq!(partition_table.find_partition_id(split_key - 1)?, parent);
+        assert_eq!(partition_table.find_partition_id(split_key)?, child);
+        assert_eq!(partition_table.find_partition_id(PartitionKey::MAX)?, child);
+
+        let child_partition = partition_table.get_partition(&child).unwrap();
+        assert_eq!(child_partition.split_from, Some(parent));
+        assert!(child_partition.splitting);
+        assert_eq!(partition_table.splitting_child(&parent), Some(child));
+
+        // a partition is split once at a time
+        let mut builder = PartitionTableBuilder::from(partition_table.clone());
+        assert!(matches!(
+            builder.split_partition(parent, split_key / 2, PartitionId::from(2)),
+            Err(BuilderError::SplitInProgress(_, splitting_child)) if splitting_child == child
+        ));
+
+        let mut builder = PartitionTableBuilder::from(partition_table.clone());
+        builder.complete_split(child)?;
+        let completed = builder.build();
+        assert!(!completed.get_partition(&child).unwrap().splitting);
+        assert_eq!(completed.splitting_child(&parent), None);
+
+        let mut builder = PartitionTableBuilder::from(partition_table);
+        builder.abort_split(child)?;
+        let aborted = builder.build();
+        assert_eq!(aborted.num_partitions(), 1);
+        assert_eq!(aborted.find_partition_id(PartitionKey::MAX)?, parent);
+        assert_eq!(
+            aborted.get_partition(&parent).unwrap().key_range,
+            0..=PartitionKey::MAX
+        );
+
+        // the id of the aborted split is not reused, also not after a round-trip through storage
+        let mut buf = BytesMut::default();
+        StorageCodec::encode(&aborted, &mut buf)?;
+        let aborted = StorageCodec::decode::<PartitionTable, _>(&mut buf)?;
+        let mut builder = PartitionTableBuilder::from(aborted);
+        assert_eq!(builder.allocate_partition_id()?, PartitionId::from(2));
+
+        Ok(())
+    }
 }
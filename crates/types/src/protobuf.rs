@@ -80,8 +80,18 @@ pub mod node {
                 max_protocol_version: CURRENT_PROTOCOL_VERSION.into(),
                 my_node_id: Some(my_node_id.into()),
                 cluster_name,
+                accepted_compressions: Vec::new(),
             }
         }
+
+        pub fn with_accepted_compressions(
+            mut self,
+            accepted_compressions: impl IntoIterator<Item = MessageCompression>,
+        ) -> Self {
+            self.accepted_compressions =
+                accepted_compressions.into_iter().map(Into::into).collect();
+            self
+        }
     }
 
     impl Injector for SpanContext {
@@ -128,10 +138,15 @@ pub mod node {
     }
 
     impl Welcome {
-        pub fn new(my_node_id: GenerationalNodeId, protocol_version: ProtocolVersion) -> Self {
+        pub fn new(
+            my_node_id: GenerationalNodeId,
+            protocol_version: ProtocolVersion,
+            compression: MessageCompression,
+        ) -> Self {
             Self {
                 my_node_id: Some(my_node_id.into()),
                 protocol_version: protocol_version.into(),
+                compression: compression.into(),
             }
         }
     }
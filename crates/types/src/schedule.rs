@@ -0,0 +1,492 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Recurring invocations, fired at the occurrences of a cron expression.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
+use chrono_tz::Tz;
+
+use crate::identifiers::{PartitionKey, ScheduleId, WithPartitionKey};
+use crate::invocation::{
+    InvocationRequest, ServiceInvocation, ServiceInvocationSpanContext, Source,
+};
+use crate::time::{MillisSinceEpoch, ZonedDateTime};
+
+/// Upper bound of the days searched for the next occurrence of a schedule. Enough to find the
+/// next 29th of February, which can be up to 8 years apart.
+const MAX_LOOKAHEAD_DAYS: usize = 8 * 366;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_OF_WEEK_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A cron expression in the timezone its occurrences are computed in.
+///
+/// The expression has the five standard fields `<minute> <hour> <day of month> <month> <day of
+/// week>`, each being `*`, a value, a range `a-b` or a list of them separated by `,`, optionally
+/// followed by a step `/n`. Months and days of the week can also be referred by their three
+/// letters English name, and both `0` and `7` are Sunday. The macros `@yearly`, `@monthly`,
+/// `@weekly`, `@daily` and `@hourly` are supported as well.
+///
+/// Like in cron, if both the day of month and the day of week are restricted, a day matches if
+/// either of them matches.
+///
+/// The timezone is UTC, unless the expression is followed by a timezone in brackets, e.g.
+/// `0 9 * * MON-FRI[Europe/Berlin]`. Occurrences falling into a daylight saving time transition
+/// are resolved as in [`ZonedDateTime::resolve`].
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde_with::SerializeDisplay, serde_with::DeserializeFromStr,
+)]
+pub struct CronSchedule {
+    expression: String,
+    timezone: Tz,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CronParseError {
+    #[error(
+        "expected the fields '<minute> <hour> <day of month> <month> <day of week>', optionally followed by a timezone, e.g. '0 9 * * MON-FRI[Europe/Berlin]'"
+    )]
+    Format,
+    #[error("invalid {field} field '{value}'")]
+    Field { field: &'static str, value: String },
+    #[error("unknown timezone '{0}'")]
+    Timezone(String),
+}
+
+impl CronSchedule {
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Returns the first occurrence strictly after `after`, or `None` if the schedule has no
+    /// occurrence in the following years.
+    pub fn next_after(&self, after: MillisSinceEpoch) -> Option<MillisSinceEpoch> {
+        let after_local = DateTime::from_timestamp_millis(i64::try_from(after.as_u64()).ok()?)?
+            .with_timezone(&self.timezone)
+            .naive_local();
+        // Occurrences have a minute granularity
+        let start = after_local.with_second(0)?.with_nanosecond(0)?;
+
+        let mut day = start.date();
+        for _ in 0..MAX_LOOKAHEAD_DAYS {
+            if self.matches_day(day) {
+                if let Some(fire_time) = self.next_in_day(day, start, after) {
+                    return Some(fire_time);
+                }
+            }
+            day = day.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn next_in_day(
+        &self,
+        day: NaiveDate,
+        start: NaiveDateTime,
+        after: MillisSinceEpoch,
+    ) -> Option<MillisSinceEpoch> {
+        for hour in (0..24).filter(|hour| self.hours & (1 << hour) != 0) {
+            for minute in (0..60).filter(|minute| self.minutes & (1 << minute) != 0) {
+                let local = day.and_hms_opt(hour, minute, 0)?;
+                if local < start {
+                    continue;
+                }
+                // Local times occurring twice resolve to their first occurrence, which might
+                // precede `after`
+                let fire_time = ZonedDateTime::new(local, self.timezone).resolve();
+                if fire_time > after {
+                    return Some(fire_time);
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, day: NaiveDate) -> bool {
+        if self.months & (1 << day.month()) == 0 {
+            return false;
+        }
+        let day_of_month = self.days_of_month & (1 << day.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << day.weekday().num_days_from_sunday()) != 0;
+
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.timezone == Tz::UTC {
+            write!(f, "{}", self.expression)
+        } else {
+            write!(f, "{}[{}]", self.expression, self.timezone.name())
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (expression, timezone) = match s.trim().strip_suffix(']') {
+            Some(s) => {
+                let (expression, timezone) = s.split_once('[').ok_or(CronParseError::Format)?;
+                let timezone = Tz::from_str(timezone)
+                    .map_err(|_| CronParseError::Timezone(timezone.to_owned()))?;
+                (expression.trim(), timezone)
+            }
+            None => (s.trim(), Tz::UTC),
+        };
+
+        let expanded = match expression {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            expression => expression,
+        };
+        let fields: Vec<_> = expanded.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(CronParseError::Format);
+        };
+
+        // Sunday is both 0 and 7
+        let days_of_week_bits = parse_field("day of week", days_of_week, 0, 7, &DAY_OF_WEEK_NAMES)?;
+        let days_of_week_bits = (days_of_week_bits | (days_of_week_bits >> 7)) & 0x7f;
+
+        Ok(Self {
+            expression: if expression.starts_with('@') {
+                expression.to_owned()
+            } else {
+                fields.join(" ")
+            },
+            timezone,
+            minutes: parse_field("minute", minutes, 0, 59, &[])?,
+            hours: u32::try_from(parse_field("hour", hours, 0, 23, &[])?)
+                .expect("hours fit into u32"),
+            days_of_month: u32::try_from(parse_field("day of month", days_of_month, 1, 31, &[])?)
+                .expect("days of month fit into u32"),
+            months: u16::try_from(parse_field("month", months, 1, 12, &MONTH_NAMES)?)
+                .expect("months fit into u16"),
+            days_of_week: u8::try_from(days_of_week_bits).expect("days of week fit into u8"),
+            days_of_month_restricted: !days_of_month.starts_with('*'),
+            days_of_week_restricted: !days_of_week.starts_with('*'),
+        })
+    }
+}
+
+/// Parses a field into a bit set of the matching values. `names` are the names of the values
+/// starting from `min`.
+fn parse_field(
+    field: &'static str,
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, CronParseError> {
+    let error = || CronParseError::Field {
+        field,
+        value: value.to_owned(),
+    };
+    let parse_value = |s: &str| -> Result<u32, CronParseError> {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(idx) => min + u32::try_from(idx).expect("names are few"),
+            None => s.parse().map_err(|_| error())?,
+        };
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(error())
+        }
+    };
+
+    let mut bits = 0;
+    for part in value.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| error())?)),
+            None => (part, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+            // A single value with a step, e.g. '5/15', runs until the end of the range
+            None if step.is_some() => (parse_value(range)?, max),
+            None => {
+                let value = parse_value(range)?;
+                (value, value)
+            }
+        };
+        let step = step.unwrap_or(1);
+        if start > end || step == 0 {
+            return Err(error());
+        }
+        for value in (start..=end).step_by(usize::try_from(step).map_err(|_| error())?) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+/// Occurrences are considered missed if they fire later than this after their time, e.g. because
+/// the partition was unavailable.
+pub const MISFIRE_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// What to do with the occurrences of a schedule which were missed, see [`MISFIRE_THRESHOLD`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum MisfirePolicy {
+    /// Don't fire the missed occurrences, the schedule fires again at the next occurrence.
+    Skip,
+    /// Fire a single invocation for all the missed occurrences.
+    #[default]
+    FireOnce,
+    /// Fire an invocation for every missed occurrence, one after the other.
+    FireAll,
+}
+
+/// A recurring invocation, fired at every occurrence of its [`CronSchedule`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Schedule {
+    pub id: ScheduleId,
+    pub cron: CronSchedule,
+    /// Schedules created before the policy was introduced fire once for the missed occurrences.
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+    /// Template of the fired invocations. Its id, idempotency key and execution time are ignored.
+    pub invocation: InvocationRequest,
+    /// Time the schedule was created, or last replaced. The schedule fires at the occurrences
+    /// after this time.
+    pub created_at: MillisSinceEpoch,
+}
+
+impl Schedule {
+    /// Returns the first occurrence of the schedule, if any.
+    pub fn first_fire_time(&self) -> Option<MillisSinceEpoch> {
+        self.cron.next_after(self.created_at)
+    }
+
+    /// Returns whether the occurrence at `fire_time` fires an invocation when its timer fired at
+    /// `fired_at`, and the time after which the next occurrence is looked up. The schedule is
+    /// over if there is no next occurrence.
+    pub fn on_fire(
+        &self,
+        fire_time: MillisSinceEpoch,
+        fired_at: MillisSinceEpoch,
+    ) -> (bool, MillisSinceEpoch) {
+        let missed = fired_at.as_u64()
+            > fire_time
+                .as_u64()
+                .saturating_add(u64::try_from(MISFIRE_THRESHOLD.as_millis()).unwrap_or(u64::MAX));
+        match self.misfire_policy {
+            _ if !missed => (true, fire_time),
+            MisfirePolicy::FireAll => (true, fire_time),
+            // The following missed occurrences are folded into this one
+            MisfirePolicy::FireOnce => (true, fired_at.max(fire_time)),
+            MisfirePolicy::Skip => (false, fired_at.max(fire_time)),
+        }
+    }
+
+    /// Returns the invocation fired at the given occurrence of the schedule, see
+    /// [`ScheduleId::invocation_id_at`].
+    pub fn invocation_at(&self, fire_time: MillisSinceEpoch) -> ServiceInvocation {
+        let mut service_invocation =
+            ServiceInvocation::from_request(self.invocation.clone(), Source::Internal);
+        service_invocation.invocation_id = self.id.invocation_id_at(fire_time);
+        service_invocation.span_context = ServiceInvocationSpanContext::empty();
+        service_invocation.idempotency_key = None;
+        service_invocation.execution_time = None;
+        service_invocation.execution_wall_clock_time = None;
+        service_invocation
+    }
+}
+
+impl WithPartitionKey for Schedule {
+    fn partition_key(&self) -> PartitionKey {
+        self.id.partition_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::identifiers::InvocationId;
+    use crate::invocation::{InvocationRequestHeader, InvocationTarget};
+
+    fn cron(s: &str) -> CronSchedule {
+        s.parse().unwrap()
+    }
+
+    fn millis(s: &str) -> MillisSinceEpoch {
+        s.parse::<ZonedDateTime>().unwrap().resolve()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(
+            cron("*/15 9-17 * * MON-FRI").to_string(),
+            "*/15 9-17 * * MON-FRI"
+        );
+        assert_eq!(
+            cron(" 0 9 * * 1 [Europe/Berlin] ").to_string(),
+            "0 9 * * 1[Europe/Berlin]"
+        );
+        assert_eq!(cron("@daily").to_string(), "@daily");
+
+        for bad in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "* * * * MON[Mars/Olympus]",
+        ] {
+            assert!(bad.parse::<CronSchedule>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn next_occurrence() {
+        let every_15_minutes = cron("*/15 * * * *");
+        assert_eq!(
+            every_15_minutes.next_after(millis("2025-01-01T10:00:00[UTC]")),
+            Some(millis("2025-01-01T10:15:00[UTC]"))
+        );
+        assert_eq!(
+            every_15_minutes.next_after(millis("2025-01-01T10:14:59[UTC]")),
+            Some(millis("2025-01-01T10:15:00[UTC]"))
+        );
+
+        // 2025-01-03 is a Friday
+        assert_eq!(
+            cron("0 9 * * MON-FRI").next_after(millis("2025-01-03T09:00:00[UTC]")),
+            Some(millis("2025-01-06T09:00:00[UTC]"))
+        );
+        assert_eq!(
+            cron("0 0 29 FEB *").next_after(millis("2025-01-01T00:00:00[UTC]")),
+            Some(millis("2028-02-29T00:00:00[UTC]"))
+        );
+        assert_eq!(
+            cron("0 0 30 2 *").next_after(millis("2025-01-01T00:00:00[UTC]")),
+            None
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // Either the 1st of the month or a Sunday, 2025-02-02 is a Sunday
+        let schedule = cron("0 0 1 * SUN");
+        assert_eq!(
+            schedule.next_after(millis("2025-01-01T00:00:00[UTC]")),
+            Some(millis("2025-01-05T00:00:00[UTC]"))
+        );
+        assert_eq!(
+            schedule.next_after(millis("2025-01-26T00:00:00[UTC]")),
+            Some(millis("2025-02-01T00:00:00[UTC]"))
+        );
+    }
+
+    #[test]
+    fn misfire_policy() {
+        let fire_time = millis("2025-01-01T10:00:00[UTC]");
+        let on_time = millis("2025-01-01T10:00:30[UTC]");
+        let late = millis("2025-01-01T12:00:00[UTC]");
+        let schedule = |misfire_policy| Schedule {
+            id: ScheduleId::generate(&InvocationTarget::mock_service()),
+            cron: cron("*/15 * * * *"),
+            misfire_policy,
+            invocation: InvocationRequest::new(
+                InvocationRequestHeader::initialize(
+                    InvocationId::mock_random(),
+                    InvocationTarget::mock_service(),
+                ),
+                Default::default(),
+            ),
+            created_at: millis("2025-01-01T00:00:00[UTC]"),
+        };
+
+        for misfire_policy in [
+            MisfirePolicy::Skip,
+            MisfirePolicy::FireOnce,
+            MisfirePolicy::FireAll,
+        ] {
+            assert_eq!(
+                schedule(misfire_policy).on_fire(fire_time, on_time),
+                (true, fire_time)
+            );
+        }
+        assert_eq!(
+            schedule(MisfirePolicy::Skip).on_fire(fire_time, late),
+            (false, late)
+        );
+        assert_eq!(
+            schedule(MisfirePolicy::FireOnce).on_fire(fire_time, late),
+            (true, late)
+        );
+        assert_eq!(
+            schedule(MisfirePolicy::FireAll).on_fire(fire_time, late),
+            (true, fire_time)
+        );
+        assert_eq!(MisfirePolicy::default(), MisfirePolicy::FireOnce);
+        assert_eq!(
+            "fire-all".parse::<MisfirePolicy>().unwrap(),
+            MisfirePolicy::FireAll
+        );
+    }
+
+    #[test]
+    fn next_occurrence_in_timezone() {
+        let schedule = cron("30 2 * * *[Europe/Berlin]");
+        assert_eq!(
+            schedule.next_after(millis("2025-03-29T12:00:00[UTC]")),
+            // 02:30 doesn't exist on 2025-03-30 in Berlin, it's moved forward to 03:30
+            Some(millis("2025-03-30T03:30:00[Europe/Berlin]"))
+        );
+        assert_eq!(
+            schedule.next_after(millis("2025-03-30T03:30:00[Europe/Berlin]")),
+            Some(millis("2025-03-31T02:30:00[Europe/Berlin]"))
+        );
+    }
+}
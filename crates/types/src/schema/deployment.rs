@@ -10,17 +10,20 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::num::NonZeroU32;
 use std::ops::RangeInclusive;
 
 use bytestring::ByteString;
+use enumset::EnumSet;
 use http::header::{HeaderName, HeaderValue};
 use http::Uri;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision};
+use crate::identifiers::{DeploymentId, InvocationId, LambdaARN, ServiceRevision};
 use crate::schema::service::ServiceMetadata;
 use crate::schema::Schema;
+use crate::service_protocol::ProtocolExtension;
 use crate::time::MillisSinceEpoch;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -30,7 +33,7 @@ pub enum ProtocolType {
     BidiStream,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DeliveryOptions {
     #[serde(
@@ -38,11 +41,24 @@ pub struct DeliveryOptions {
     )]
     #[cfg_attr(feature = "schemars", schemars(with = "HashMap<String, String>"))]
     pub additional_headers: HashMap<HeaderName, HeaderValue>,
+    /// Maximum number of invocations the invoker of each node executes concurrently on this
+    /// deployment. If unset, the deployment is only bound by the invoker concurrency limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u32>"))]
+    pub concurrency_limit: Option<NonZeroU32>,
 }
 
 impl DeliveryOptions {
     pub fn new(additional_headers: HashMap<HeaderName, HeaderValue>) -> Self {
-        Self { additional_headers }
+        Self {
+            additional_headers,
+            concurrency_limit: None,
+        }
+    }
+
+    pub fn with_concurrency_limit(mut self, concurrency_limit: Option<NonZeroU32>) -> Self {
+        self.concurrency_limit = concurrency_limit;
+        self
     }
 }
 
@@ -60,7 +76,15 @@ pub struct DeploymentMetadata {
     pub ty: DeploymentType,
     pub delivery_options: DeliveryOptions,
     pub supported_protocol_versions: RangeInclusive<i32>,
+    /// Protocol extensions the deployment accepted when it was discovered.
+    #[serde(default, skip_serializing_if = "EnumSet::is_empty")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<ProtocolExtension>"))]
+    pub protocol_extensions: EnumSet<ProtocolExtension>,
     pub created_at: MillisSinceEpoch,
+    /// If true, no new invocations are started on this deployment, while the invocations
+    /// already pinned to it keep running on it.
+    #[serde(default)]
+    pub draining: bool,
 }
 
 #[serde_as]
@@ -81,6 +105,11 @@ pub enum DeploymentType {
         arn: LambdaARN,
         #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
         assume_role_arn: Option<ByteString>,
+        /// Region of the AWS endpoints used to invoke the function, overriding the region of
+        /// the function ARN.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+        region: Option<ByteString>,
     },
 }
 
@@ -100,6 +129,8 @@ enum DeploymentTypeShadow {
     Lambda {
         arn: LambdaARN,
         assume_role_arn: Option<ByteString>,
+        #[serde(default)]
+        region: Option<ByteString>,
     },
 }
 
@@ -121,9 +152,11 @@ impl From<DeploymentTypeShadow> for DeploymentType {
             DeploymentTypeShadow::Lambda {
                 arn,
                 assume_role_arn,
+                region,
             } => Self::Lambda {
                 arn,
                 assume_role_arn,
+                region,
             },
         }
     }
@@ -242,12 +275,15 @@ impl DeploymentMetadata {
             delivery_options,
             created_at: MillisSinceEpoch::now(),
             supported_protocol_versions,
+            protocol_extensions: EnumSet::empty(),
+            draining: false,
         }
     }
 
     pub fn new_lambda(
         arn: LambdaARN,
         assume_role_arn: Option<ByteString>,
+        region: Option<ByteString>,
         delivery_options: DeliveryOptions,
         supported_protocol_versions: RangeInclusive<i32>,
     ) -> Self {
@@ -255,13 +291,28 @@ impl DeploymentMetadata {
             ty: DeploymentType::Lambda {
                 arn,
                 assume_role_arn,
+                region,
             },
             delivery_options,
             created_at: MillisSinceEpoch::now(),
             supported_protocol_versions,
+            protocol_extensions: EnumSet::empty(),
+            draining: false,
         }
     }
 
+    pub fn with_protocol_extensions(
+        mut self,
+        protocol_extensions: EnumSet<ProtocolExtension>,
+    ) -> Self {
+        self.protocol_extensions = protocol_extensions;
+        self
+    }
+
+    pub fn supports_protocol_extension(&self, protocol_extension: ProtocolExtension) -> bool {
+        self.protocol_extensions.contains(protocol_extension)
+    }
+
     // address_display returns a Displayable identifier for the endpoint; for http endpoints this is a URI,
     // and for Lambda deployments its the ARN
     pub fn address_display(&self) -> impl Display + '_ {
@@ -288,6 +339,16 @@ pub trait DeploymentResolver {
         service_name: impl AsRef<str>,
     ) -> Option<Deployment>;
 
+    /// Resolves the deployment of a new invocation, taking into account the routing rules of
+    /// the service. Invocations already pinned to a deployment must not be resolved again.
+    fn resolve_deployment_for_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        _invocation_id: &InvocationId,
+    ) -> Option<Deployment> {
+        self.resolve_latest_deployment_for_service(service_name)
+    }
+
     fn get_deployment(&self, deployment_id: &DeploymentId) -> Option<Deployment>;
 
     fn get_deployment_and_services(
@@ -422,14 +483,46 @@ impl DeploymentResolver for Schema {
         service_name: impl AsRef<str>,
     ) -> Option<Deployment> {
         let service = self.services.get(service_name.as_ref())?;
-        self.deployments
-            .get(&service.location.latest_deployment)
-            .map(|schemas| Deployment {
+        let latest = self.deployments.get(&service.location.latest_deployment)?;
+        if !latest.metadata.draining {
+            return Some(Deployment {
                 id: service.location.latest_deployment,
+                metadata: latest.metadata.clone(),
+            });
+        }
+
+        // Fall back to the most recent deployment of the service which is not draining
+        self.deployments
+            .iter()
+            .filter(|(_, schemas)| {
+                !schemas.metadata.draining
+                    && schemas
+                        .services
+                        .iter()
+                        .any(|s| s.name == service_name.as_ref())
+            })
+            .max_by_key(|(_, schemas)| schemas.metadata.created_at)
+            .map(|(deployment_id, schemas)| Deployment {
+                id: *deployment_id,
                 metadata: schemas.metadata.clone(),
             })
     }
 
+    fn resolve_deployment_for_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        invocation_id: &InvocationId,
+    ) -> Option<Deployment> {
+        let canary = self
+            .services
+            .get(service_name.as_ref())
+            .and_then(|service| service.routing.as_ref())
+            .filter(|routing| routing.routes_invocation(invocation_id))
+            .and_then(|routing| self.get_deployment(&routing.deployment_id))
+            .filter(|deployment| !deployment.metadata.draining);
+        canary.or_else(|| self.resolve_latest_deployment_for_service(service_name))
+    }
+
     fn get_deployment(&self, deployment_id: &DeploymentId) -> Option<Deployment> {
         self.deployments
             .get(deployment_id)
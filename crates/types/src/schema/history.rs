@@ -0,0 +1,42 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use super::Schema;
+use crate::time::MillisSinceEpoch;
+use crate::{flexbuffers_storage_encode_decode, Version, Versioned};
+
+/// Snapshot of a version of the [`Schema`] which was superseded by a newer version. Each snapshot
+/// is stored in the metadata store under its own
+/// [`schema_version_key`](crate::metadata_store::keys::schema_version_key), and is written before
+/// the version it holds gets overwritten, so that every superseded version can be rolled back to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaVersionSnapshot {
+    pub superseded_at: MillisSinceEpoch,
+    pub schema: Schema,
+}
+
+impl SchemaVersionSnapshot {
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            superseded_at: MillisSinceEpoch::now(),
+            schema,
+        }
+    }
+}
+
+impl Versioned for SchemaVersionSnapshot {
+    fn version(&self) -> Version {
+        self.schema.version
+    }
+}
+
+flexbuffers_storage_encode_decode!(SchemaVersionSnapshot);
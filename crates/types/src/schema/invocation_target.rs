@@ -8,12 +8,16 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::num::NonZeroU32;
 use std::str::FromStr;
 use std::time::Duration;
 use std::{cmp, fmt};
 
 use super::Schema;
-use crate::invocation::InvocationTargetType;
+use crate::deployment::PinnedDeployment;
+use crate::identifiers::InvocationId;
+use crate::invocation::{Header, InvocationTargetType};
+use crate::schema::service::{is_in_percentage, RoutingHeaderMatch};
 use bytes::Bytes;
 use bytestring::ByteString;
 use itertools::Itertools;
@@ -32,9 +36,32 @@ pub struct InvocationTargetMetadata {
     pub target_ty: InvocationTargetType,
     pub input_rules: InputRules,
     pub output_rules: OutputRules,
+    /// Shadow deployment receiving a copy of the ingress requests. See [`InvocationTargetMirroring`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirroring: Option<InvocationTargetMirroring>,
+    /// Canary deployment of the new invocations. See [`InvocationTargetRouting`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<InvocationTargetRouting>,
+    /// Per key limit of the concurrent executions of shared virtual object handlers, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_concurrency_limit: Option<NonZeroU32>,
+    /// Services allowed to call this target, any service if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_callers: Option<Vec<String>>,
 }
 
 impl InvocationTargetMetadata {
+    /// Returns true if the given service is allowed to call this target.
+    pub fn is_caller_allowed(&self, caller_service_name: &str) -> bool {
+        self.allowed_callers
+            .as_ref()
+            .map_or(true, |allowed_callers| {
+                allowed_callers
+                    .iter()
+                    .any(|allowed_caller| allowed_caller == caller_service_name)
+            })
+    }
+
     pub fn compute_retention(&self, has_idempotency_key: bool) -> Option<Duration> {
         if has_idempotency_key {
             Some(cmp::max(
@@ -47,6 +74,41 @@ impl InvocationTargetMetadata {
     }
 }
 
+/// Mirroring of ingress requests to a shadow deployment, see [`crate::schema::service::ServiceMirroring`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvocationTargetMirroring {
+    pub pinned_deployment: PinnedDeployment,
+    /// Fraction of the ingress requests to mirror, between 0 and 1.
+    pub fraction: f64,
+}
+
+impl InvocationTargetMirroring {
+    /// Samples whether the next request should be mirrored.
+    pub fn should_mirror(&self) -> bool {
+        self.fraction > 0.0 && rand::random::<f64>() < self.fraction
+    }
+}
+
+/// Routing of new invocations to a canary deployment, see [`crate::schema::service::ServiceRouting`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvocationTargetRouting {
+    pub pinned_deployment: PinnedDeployment,
+    /// Percentage of the new invocations to route, between 0 and 100.
+    pub percentage: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<RoutingHeaderMatch>,
+}
+
+impl InvocationTargetRouting {
+    /// Returns true if the invocation should be pinned to the canary deployment.
+    pub fn should_route(&self, invocation_id: &InvocationId, headers: &[Header]) -> bool {
+        self.header
+            .as_ref()
+            .is_some_and(|header| header.matches(headers))
+            || is_in_percentage(invocation_id, self.percentage)
+    }
+}
+
 /// This API resolves invocation targets.
 pub trait InvocationTargetResolver {
     /// Returns None if the service handler doesn't exist, Some(basic_service_metadata) otherwise.
@@ -93,6 +155,9 @@ pub enum InputValidationError {
 pub struct InputRules {
     /// Input validation will try each of these rules. Validation passes if at least one rule matches.
     pub input_validation_rules: Vec<InputValidationRule>,
+    /// Protobuf message of the input, used to transcode JSON requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protobuf_message: Option<ProtobufMessage>,
 }
 
 impl InputRules {
@@ -132,6 +197,7 @@ impl Default for InputRules {
                     content_type: InputContentType::Any,
                 },
             ],
+            protobuf_message: None,
         }
     }
 }
@@ -356,6 +422,9 @@ pub struct OutputRules {
     pub content_type_rule: OutputContentTypeRule,
     // Json schema, if present
     pub json_schema: Option<serde_json::Value>,
+    /// Protobuf message of the output, used to transcode responses to JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protobuf_message: Option<ProtobufMessage>,
 }
 
 impl OutputRules {
@@ -437,6 +506,76 @@ impl fmt::Display for OutputContentTypeRule {
     }
 }
 
+// --- Protobuf messages
+
+#[derive(Debug, thiserror::Error)]
+pub enum BadProtobufMessage {
+    #[error("cannot decode the descriptor set: {0}")]
+    DescriptorSet(#[from] prost_reflect::DescriptorError),
+    #[error("the descriptor set doesn't contain the message '{0}'")]
+    MessageNotFound(String),
+}
+
+/// Protobuf message of a handler input or output, resolved from the descriptor set registered at discovery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "ProtobufMessageShadow", into = "ProtobufMessageShadow")]
+pub struct ProtobufMessage {
+    descriptor_set: Bytes,
+    descriptor: prost_reflect::MessageDescriptor,
+}
+
+impl ProtobufMessage {
+    /// Resolves the message with the given fully qualified name from the encoded `FileDescriptorSet`.
+    pub fn new(name: &str, descriptor_set: Bytes) -> Result<Self, BadProtobufMessage> {
+        let descriptor = prost_reflect::DescriptorPool::decode(descriptor_set.clone())?
+            .get_message_by_name(name)
+            .ok_or_else(|| BadProtobufMessage::MessageNotFound(name.to_owned()))?;
+        Ok(Self {
+            descriptor_set,
+            descriptor,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.descriptor.full_name()
+    }
+
+    pub fn descriptor(&self) -> &prost_reflect::MessageDescriptor {
+        &self.descriptor
+    }
+}
+
+impl PartialEq for ProtobufMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name() && self.descriptor_set == other.descriptor_set
+    }
+}
+
+impl Eq for ProtobufMessage {}
+
+#[derive(Serialize, Deserialize)]
+struct ProtobufMessageShadow {
+    name: String,
+    descriptor_set: Bytes,
+}
+
+impl TryFrom<ProtobufMessageShadow> for ProtobufMessage {
+    type Error = BadProtobufMessage;
+
+    fn try_from(value: ProtobufMessageShadow) -> Result<Self, Self::Error> {
+        ProtobufMessage::new(&value.name, value.descriptor_set)
+    }
+}
+
+impl From<ProtobufMessage> for ProtobufMessageShadow {
+    fn from(value: ProtobufMessage) -> Self {
+        ProtobufMessageShadow {
+            name: value.name().to_owned(),
+            descriptor_set: value.descriptor_set,
+        }
+    }
+}
+
 fn try_display_json_detailed_info<D: fmt::Display>(
     f: &mut fmt::Formatter<'_>,
     schema: &serde_json::Value,
@@ -503,6 +642,10 @@ pub mod test_util {
                 target_ty: invocation_target_type,
                 input_rules: Default::default(),
                 output_rules: Default::default(),
+                mirroring: None,
+                routing: None,
+                shared_concurrency_limit: None,
+                allowed_callers: None,
             }
         }
     }
@@ -557,6 +700,7 @@ mod tests {
     fn validate_empty_only() {
         let input_rules = InputRules {
             input_validation_rules: vec![InputValidationRule::NoBodyAndContentType],
+            protobuf_message: None,
         };
 
         assert_input_valid!(input_rules, None, Bytes::new());
@@ -570,6 +714,7 @@ mod tests {
             input_validation_rules: vec![InputValidationRule::ContentType {
                 content_type: InputContentType::Any,
             }],
+            protobuf_message: None,
         };
 
         assert_input_valid!(
@@ -588,6 +733,7 @@ mod tests {
             input_validation_rules: vec![InputValidationRule::ContentType {
                 content_type: InputContentType::MimeType("application".into()),
             }],
+            protobuf_message: None,
         };
 
         assert_input_valid!(input_rules, Some("application/restate+json"), Bytes::new());
@@ -608,6 +754,7 @@ mod tests {
                     "json".into(),
                 ),
             }],
+            protobuf_message: None,
         };
 
         assert_input_valid!(input_rules, Some("application/json"), Bytes::new());
@@ -629,6 +776,7 @@ mod tests {
                     schema: Default::default(),
                 },
             ],
+            protobuf_message: None,
         };
 
         assert_input_valid!(input_rules, None, Bytes::new());
@@ -650,6 +798,7 @@ mod tests {
                 ),
                 schema: Default::default(),
             }],
+            protobuf_message: None,
         };
 
         assert_input_valid!(
@@ -687,6 +836,7 @@ mod tests {
                 has_json_schema: false,
             },
             json_schema: None,
+            protobuf_message: None,
         };
 
         assert_eq!(input_rules.infer_content_type(false), Some(ct.clone()));
@@ -698,9 +848,45 @@ mod tests {
         let input_rules = OutputRules {
             content_type_rule: OutputContentTypeRule::None,
             json_schema: None,
+            protobuf_message: None,
         };
 
         assert_eq!(input_rules.infer_content_type(false), None);
         assert_eq!(input_rules.infer_content_type(true), None);
     }
+
+    #[test]
+    fn protobuf_message_serde_roundtrip() {
+        use prost::Message;
+        use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+
+        let descriptor_set = Bytes::from(
+            FileDescriptorSet {
+                file: vec![FileDescriptorProto {
+                    name: Some("greeter.proto".to_owned()),
+                    package: Some("greeter".to_owned()),
+                    message_type: vec![DescriptorProto {
+                        name: Some("Greeting".to_owned()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+            }
+            .encode_to_vec(),
+        );
+
+        let protobuf_message =
+            ProtobufMessage::new("greeter.Greeting", descriptor_set.clone()).unwrap();
+        assert_eq!(protobuf_message.name(), "greeter.Greeting");
+        assert!(matches!(
+            ProtobufMessage::new("greeter.Farewell", descriptor_set),
+            Err(BadProtobufMessage::MessageNotFound(_))
+        ));
+
+        let serialized = flexbuffers::to_vec(&protobuf_message).unwrap();
+        assert_eq!(
+            flexbuffers::from_slice::<ProtobufMessage>(&serialized).unwrap(),
+            protobuf_message
+        );
+    }
 }
@@ -9,7 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::identifiers::ServiceRevision;
-use crate::invocation::ServiceType;
+use crate::invocation::{InvocationTargetType, ServiceType, WorkflowHandlerType};
 use crate::schema::invocation_target::{InputValidationRule, OutputContentTypeRule};
 use crate::schema::service::HandlerSchemas;
 use restate_utoipa::openapi::path::{Operation, Parameter, ParameterIn};
@@ -46,6 +46,11 @@ impl ServiceOpenAPI {
         if service_type != ServiceType::Workflow {
             parameters.push(parameters_ref(IDEMPOTENCY_KEY_PARAMETER_REF_NAME).into());
         }
+        let mut send_parameters = parameters.clone();
+        if service_type != ServiceType::Workflow {
+            send_parameters.push(parameters_ref(CRON_PARAMETER_REF_NAME).into());
+            send_parameters.push(parameters_ref(CRON_MISFIRE_PARAMETER_REF_NAME).into());
+        }
 
         let mut paths = Paths::builder();
         for (handler_name, handler_schemas) in handlers {
@@ -79,6 +84,8 @@ impl ServiceOpenAPI {
                                 }),
                         ))
                         .parameters(Some(parameters.clone()))
+                        .parameter(parameters_ref(PRIORITY_PARAMETER_REF_NAME))
+                        .parameter(parameters_ref(DEADLINE_PARAMETER_REF_NAME))
                         .tag(service_name.to_string())
                         .request_body(request_body.clone())
                         .response("200", response)
@@ -103,8 +110,11 @@ impl ServiceOpenAPI {
                                     format!("Send request to {service_name} handler {handler_name}")
                                 }),
                         ))
-                        .parameters(Some(parameters.clone()))
+                        .parameters(Some(send_parameters.clone()))
                         .parameter(parameters_ref(DELAY_PARAMETER_REF_NAME))
+                        .parameter(parameters_ref(AT_PARAMETER_REF_NAME))
+                        .parameter(parameters_ref(PRIORITY_PARAMETER_REF_NAME))
+                        .parameter(parameters_ref(DEADLINE_PARAMETER_REF_NAME))
                         .tag(service_name.to_string())
                         .request_body(request_body)
                         .response("200", responses_ref(SEND_RESPONSE_REF_NAME))
@@ -166,6 +176,215 @@ impl ServiceOpenAPI {
     }
 }
 
+/// Service to include in the OpenAPI contract of all the services, see [`infer_services_openapi_contract`].
+pub(crate) struct ServiceContract<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) ty: ServiceType,
+    pub(crate) documentation: Option<&'a str>,
+    pub(crate) handlers: &'a HashMap<String, HandlerSchemas>,
+    pub(crate) openapi: &'a ServiceOpenAPI,
+}
+
+/// Returns the OpenAPI contract of all the given services, including the endpoints to attach to
+/// their invocations and to get their output.
+///
+/// Operation ids and schema names of each service are prefixed with the service name, to make
+/// them unique across services.
+pub(crate) fn infer_services_openapi_contract<'a>(
+    services: impl IntoIterator<Item = ServiceContract<'a>>,
+) -> Value {
+    let mut paths = serde_json::Map::new();
+    let mut schemas = serde_json::Map::new();
+    let mut tags = vec![];
+
+    for service in services {
+        let prefix = format!("{}.", service.name);
+        let mut service_paths = serde_json::to_value(&service.openapi.paths)
+            .expect("Mapping OpenAPI to JSON should never fail");
+        prefix_names(&prefix, &mut service_paths);
+        let mut service_schemas = serde_json::to_value(&service.openapi.components.schemas)
+            .expect("Mapping OpenAPI to JSON should never fail");
+        prefix_names(&prefix, &mut service_schemas);
+        if let Value::Object(service_schemas) = service_schemas {
+            schemas.extend(
+                service_schemas
+                    .into_iter()
+                    .map(|(name, schema)| (format!("{prefix}{name}"), schema)),
+            );
+        }
+
+        let root_path = if service.ty.is_keyed() {
+            format!("/{}/{{key}}/", service.name)
+        } else {
+            format!("/{}/", service.name)
+        };
+        for (handler_name, handler_schemas) in service.handlers {
+            if !handler_schemas.target_meta.public {
+                continue;
+            }
+            // The output of attach and get output is the same of the call
+            let call_path = format!("{root_path}{handler_name}")
+                .replace('~', "~0")
+                .replace('/', "~1");
+            let output_response = service_paths
+                .pointer(&format!("/{call_path}/post/responses/200"))
+                .cloned()
+                .unwrap_or_else(|| json!({"description": "Output of the handler"}));
+
+            let (path, parameters) = match handler_schemas.target_meta.target_ty {
+                InvocationTargetType::Workflow(WorkflowHandlerType::Workflow) => (
+                    format!("/restate/workflow/{}/{{key}}", service.name),
+                    vec![
+                        json!({"$ref": format!("#/components/parameters/{KEY_PARAMETER_REF_NAME}")}),
+                    ],
+                ),
+                // The invocations of the other workflow handlers can't be retrieved
+                InvocationTargetType::Workflow(WorkflowHandlerType::Shared) => continue,
+                _ => {
+                    let mut parameters = vec![];
+                    if service.ty.is_keyed() {
+                        parameters.push(json!({"$ref": format!("#/components/parameters/{KEY_PARAMETER_REF_NAME}")}));
+                    }
+                    parameters.push(idempotency_key_path_parameter());
+                    (
+                        format!("/restate/invocation{root_path}{handler_name}/{{idempotencyKey}}"),
+                        parameters,
+                    )
+                }
+            };
+            let operation_id = format!("{prefix}{handler_name}");
+            let (attach, output) = attach_and_output_path_items(
+                service.name,
+                &operation_id,
+                &parameters,
+                &output_response,
+            );
+            paths.insert(format!("{path}/attach"), attach);
+            paths.insert(format!("{path}/output"), output);
+        }
+
+        if let Value::Object(service_paths) = service_paths {
+            paths.extend(service_paths);
+        }
+        tags.push(json!({
+            "name": service.name,
+            "description": service.documentation,
+        }));
+    }
+
+    let (attach, output) = attach_and_output_path_items(
+        "invocation",
+        "invocation",
+        &[json!({
+            "name": "invocationId",
+            "in": "path",
+            "required": true,
+            "schema": {"type": "string"},
+            "description": "Invocation identifier."
+        })],
+        &json!({
+            "description": "Output of the invocation",
+            "content": {"*/*": {}}
+        }),
+    );
+    paths.insert(
+        "/restate/invocation/{invocationId}/attach".to_owned(),
+        attach,
+    );
+    paths.insert(
+        "/restate/invocation/{invocationId}/output".to_owned(),
+        output,
+    );
+
+    let mut components = serde_json::to_value(restate_components())
+        .expect("Mapping OpenAPI to JSON should never fail");
+    components["schemas"] = Value::Object(schemas);
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Restate services",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "tags": tags,
+        "paths": paths,
+        "components": components,
+    })
+}
+
+fn attach_and_output_path_items(
+    tag: &str,
+    operation_id: &str,
+    parameters: &[Value],
+    output_response: &Value,
+) -> (Value, Value) {
+    (
+        json!({
+            "get": {
+                "operationId": format!("{operation_id}Attach"),
+                "description": "Attach to the invocation, waiting for its output. Clients accepting text/event-stream receive the progress of the invocation as server-sent events.",
+                "tags": [tag],
+                "parameters": parameters,
+                "responses": {
+                    "200": output_response,
+                    "default": {"$ref": format!("#/components/responses/{ERROR_RESPONSE_REF_NAME}")}
+                }
+            }
+        }),
+        json!({
+            "get": {
+                "operationId": format!("{operation_id}Output"),
+                "description": "Get the output of the invocation, without waiting for it to complete.",
+                "tags": [tag],
+                "parameters": parameters,
+                "responses": {
+                    "200": output_response,
+                    "470": {"description": "The invocation is not completed yet"},
+                    "default": {"$ref": format!("#/components/responses/{ERROR_RESPONSE_REF_NAME}")}
+                }
+            }
+        }),
+    )
+}
+
+fn idempotency_key_path_parameter() -> Value {
+    json!({
+        "name": "idempotencyKey",
+        "in": "path",
+        "required": true,
+        "schema": {"type": "string"},
+        "description": "Idempotency key used to send the request, see the idempotency-key header."
+    })
+}
+
+// Prefixes the operation ids, and the names of the schemas in the $refs
+fn prefix_names(prefix: &str, value: &mut Value) {
+    const SCHEMAS_REF_PREFIX: &str = "#/components/schemas/";
+
+    match value {
+        Value::Array(array_value) => {
+            for val in array_value.iter_mut() {
+                prefix_names(prefix, val)
+            }
+        }
+        Value::Object(obj_value) => {
+            if let Some(Value::String(ref_value)) = obj_value.get_mut("$ref") {
+                if let Some(schema_name) = ref_value.strip_prefix(SCHEMAS_REF_PREFIX) {
+                    *ref_value = format!("{SCHEMAS_REF_PREFIX}{prefix}{schema_name}");
+                }
+            }
+            if let Some(Value::String(operation_id)) = obj_value.get_mut("operationId") {
+                *operation_id = format!("{prefix}{operation_id}");
+            }
+
+            for val in obj_value.values_mut() {
+                prefix_names(prefix, val)
+            }
+        }
+        _ => {}
+    }
+}
+
 fn request_schema_name(operation_id: &str) -> String {
     format!("{operation_id}Request")
 }
@@ -297,11 +516,16 @@ fn infer_handler_response(
 fn restate_components() -> Components {
     Components::builder()
         .parameter(DELAY_PARAMETER_REF_NAME, delay_parameter())
+        .parameter(AT_PARAMETER_REF_NAME, at_parameter())
+        .parameter(PRIORITY_PARAMETER_REF_NAME, priority_parameter())
+        .parameter(DEADLINE_PARAMETER_REF_NAME, deadline_parameter())
         .parameter(KEY_PARAMETER_REF_NAME, key_parameter())
         .parameter(
             IDEMPOTENCY_KEY_PARAMETER_REF_NAME,
             idempotency_key_parameter(),
         )
+        .parameter(CRON_PARAMETER_REF_NAME, cron_parameter())
+        .parameter(CRON_MISFIRE_PARAMETER_REF_NAME, cron_misfire_parameter())
         .response(ERROR_RESPONSE_REF_NAME, error_response())
         .response(SEND_RESPONSE_REF_NAME, send_response())
         .build()
@@ -326,6 +550,51 @@ fn delay_parameter() -> Parameter {
         .build()
 }
 
+const AT_PARAMETER_REF_NAME: &str = "at";
+
+fn at_parameter() -> Parameter {
+    Parameter::builder()
+        .name("at")
+        .parameter_in(ParameterIn::Query)
+        .schema(Some(
+            string_json_schema()
+        ))
+        .example(Some(Value::String("2025-03-30T09:00:00[Europe/Berlin]".to_string())))
+        .required(Required::False)
+        .description(Some("Specify the local date time and the IANA timezone to execute the operation at. Daylight saving time transitions are taken into account. Cannot be combined with delay."))
+        .build()
+}
+
+const PRIORITY_PARAMETER_REF_NAME: &str = "priority";
+
+fn priority_parameter() -> Parameter {
+    Parameter::builder()
+        .name("priority")
+        .parameter_in(ParameterIn::Query)
+        .schema(Some(Schema::new(
+            json!({"type": "integer", "format": "int32"}),
+        )))
+        .example(Some(Value::Number(10.into())))
+        .required(Required::False)
+        .description(Some("Specify the priority of the invocation within the inbox of a virtual object. Invocations with a higher priority are started first. Defaults to 0."))
+        .build()
+}
+
+const DEADLINE_PARAMETER_REF_NAME: &str = "deadline";
+
+fn deadline_parameter() -> Parameter {
+    Parameter::builder()
+        .name("deadline")
+        .parameter_in(ParameterIn::Query)
+        .schema(Some(
+            string_json_schema()
+        ))
+        .example(Some(Value::String("2025-03-30T09:00:00[Europe/Berlin]".to_string())))
+        .required(Required::False)
+        .description(Some("Specify the local date time and the IANA timezone by which the invocation should be started. Among invocations with the same priority within the inbox of a virtual object, the earliest deadline is started first. The deadline is not enforced."))
+        .build()
+}
+
 const KEY_PARAMETER_REF_NAME: &str = "key";
 
 fn key_parameter() -> Parameter {
@@ -351,6 +620,36 @@ fn idempotency_key_parameter() -> Parameter {
         .build()
 }
 
+const CRON_PARAMETER_REF_NAME: &str = "cron";
+
+fn cron_parameter() -> Parameter {
+    Parameter::builder()
+        .name("x-restate-cron")
+        .parameter_in(ParameterIn::Header)
+        .schema(Some(
+            string_json_schema()
+        ))
+        .example(Some(Value::String("0 9 * * MON-FRI[Europe/Berlin]".to_string())))
+        .required(Required::False)
+        .description(Some("Cron expression, optionally followed by an IANA timezone, to send the request repeatedly. Creates a schedule which can be deleted through the admin API. Cannot be combined with delay, at and the idempotency key."))
+        .build()
+}
+
+const CRON_MISFIRE_PARAMETER_REF_NAME: &str = "cron-misfire";
+
+fn cron_misfire_parameter() -> Parameter {
+    Parameter::builder()
+        .name("x-restate-cron-misfire")
+        .parameter_in(ParameterIn::Header)
+        .schema(Some(
+            string_json_schema()
+        ))
+        .example(Some(Value::String("fire-once".to_string())))
+        .required(Required::False)
+        .description(Some("What to do with the occurrences of the schedule created with x-restate-cron which were missed, e.g. while the partition was unavailable: 'skip' them, 'fire-once' for all of them, or 'fire-all' of them one after the other. Defaults to 'fire-once'."))
+        .build()
+}
+
 fn responses_ref(name: &str) -> Ref {
     Ref::new(format!("#/components/responses/{name}"))
 }
@@ -434,10 +733,22 @@ fn send_response_json_schema() -> Value {
             "executionTime": {
                 "type": "string",
                 "format": "date-time",
-                "description": "Time when the invocation will be executed, in case 'delay' is used"
+                "description": "Time when the invocation will be executed, in case 'delay' or 'at' is used"
+            },
+            "scheduleId": {
+                "type": "string",
+                "description": "Id of the created schedule, in case 'x-restate-cron' is used"
+            },
+            "nextFireTime": {
+                "type": "string",
+                "format": "date-time",
+                "description": "Time of the first invocation of the created schedule, in case 'x-restate-cron' is used"
             }
         },
-        "required": ["invocationId", "status"],
+        "anyOf": [
+            {"required": ["invocationId", "status"]},
+            {"required": ["scheduleId", "nextFireTime"]}
+        ],
         "additionalProperties": false
     })
 }
@@ -10,16 +10,18 @@
 
 use super::invocation_target::InvocationTargetMetadata;
 use super::Schema;
-use crate::identifiers::{DeploymentId, ServiceRevision};
+use crate::identifiers::{DeploymentId, InvocationId, ServiceRevision};
 use crate::invocation::{
-    InvocationTargetType, ServiceType, VirtualObjectHandlerType, WorkflowHandlerType,
+    Header, InvocationTargetType, ServiceType, VirtualObjectHandlerType, WorkflowHandlerType,
 };
-use crate::schema::openapi::ServiceOpenAPI;
+use crate::retries::RetryPolicy;
+use crate::schema::openapi::{infer_services_openapi_contract, ServiceContract, ServiceOpenAPI};
 use arc_swap::ArcSwapOption;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -64,6 +66,20 @@ pub struct ServiceMetadata {
     /// If false, the service can be invoked only from another Restate service.
     pub public: bool,
 
+    /// # Allowed callers
+    ///
+    /// If set, the service can be invoked from another Restate service only if the caller is
+    /// listed here. Invocations through the ingress are governed by `public` instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_callers: Option<Vec<String>>,
+
+    /// # Paused
+    ///
+    /// If true, new invocations of the service are enqueued without being executed, until the
+    /// service is resumed. Invocations already running are not affected.
+    #[serde(default)]
+    pub paused: bool,
+
     /// # Idempotency retention
     ///
     /// The retention duration of idempotent requests for this service.
@@ -82,6 +98,19 @@ pub struct ServiceMetadata {
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub workflow_completion_retention: Option<humantime::Duration>,
 
+    /// # Completion retention
+    ///
+    /// The retention duration of completed invocations of this service, regardless of whether
+    /// they were submitted with an idempotency key. If unset, only idempotent requests and
+    /// workflows are retained.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub completion_retention: Option<humantime::Duration>,
+
     /// # Inactivity timeout
     ///
     /// This timer guards against stalled service/handler invocations. Once it expires,
@@ -122,6 +151,213 @@ pub struct ServiceMetadata {
     )]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub abort_timeout: Option<humantime::Duration>,
+
+    /// # Mirroring
+    ///
+    /// If set, a fraction of the ingress requests to this service is duplicated to a shadow deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirroring: Option<ServiceMirroring>,
+
+    /// # Routing
+    ///
+    /// If set, part of the new invocations of this service is routed to a canary deployment,
+    /// instead of the latest deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<ServiceRouting>,
+
+    /// # Shared handler concurrency
+    ///
+    /// Maximum number of concurrent executions of the shared handlers of this virtual object,
+    /// per key. Invocations exceeding the limit wait until an execution of a shared handler of the
+    /// same key completes. If unset, shared handlers are not limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u32>"))]
+    pub shared_handler_concurrency: Option<NonZeroU32>,
+
+    /// # Concurrency limit
+    ///
+    /// Maximum number of invocations of this service the invoker of each node executes
+    /// concurrently. Invocations exceeding the limit are queued by the invoker, and the queued
+    /// invocations of the different partitions are started in a round-robin fashion.
+    /// If unset, the service is only bound by the limit of its deployment and of the invoker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u32>"))]
+    pub concurrency_limit: Option<NonZeroU32>,
+
+    /// # Retry policy
+    ///
+    /// Retry policies of the invocations of this service, overriding the retry policy of the
+    /// invoker. Handlers can further override them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+
+    /// # Execution timeout
+    ///
+    /// Maximum wall-clock time an invocation of this service can execute for, from the moment
+    /// it starts running until it completes, including the time spent waiting for retries or
+    /// while suspended. Once it expires, the invocation is aborted and fails with a timeout error.
+    /// Unlike the 'inactivity timeout', this timer is not reset when the service makes progress.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    ///
+    /// Handlers can override it. If unset, invocations are not bound by an execution timeout.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub execution_timeout: Option<humantime::Duration>,
+}
+
+/// Retry policies overriding the invoker retry policy for the different classes of errors an
+/// invocation can fail with. Unset policies fall back to the invoker retry policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RetryPolicyOverrides {
+    /// # Transport errors
+    ///
+    /// Retry policy of the errors occurring while talking to the deployment, such as connection
+    /// errors, timeouts and unexpected HTTP responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport_errors: Option<RetryPolicy>,
+
+    /// # Retryable errors
+    ///
+    /// Retry policy of the errors reported by the SDK, and of all the errors which don't belong
+    /// to another class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retryable_errors: Option<RetryPolicy>,
+
+    /// # Terminal errors
+    ///
+    /// Retry policy of the errors the SDK declares as not recoverable by retrying the same code,
+    /// that is journal mismatches and protocol violations. Retrying these is useful only once a
+    /// fixed version of the service has been deployed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal_errors: Option<RetryPolicy>,
+
+    /// # Max duration
+    ///
+    /// Maximum time an invocation is retried for since its first failure, across all the
+    /// classes of errors. Once exceeded, the invocation fails.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub max_duration: Option<humantime::Duration>,
+}
+
+impl RetryPolicyOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.transport_errors.is_none()
+            && self.retryable_errors.is_none()
+            && self.terminal_errors.is_none()
+            && self.max_duration.is_none()
+    }
+
+    /// Overrides set in `self`, falling back to the ones of `fallback`.
+    pub fn or(&self, fallback: &RetryPolicyOverrides) -> RetryPolicyOverrides {
+        RetryPolicyOverrides {
+            transport_errors: self
+                .transport_errors
+                .clone()
+                .or_else(|| fallback.transport_errors.clone()),
+            retryable_errors: self
+                .retryable_errors
+                .clone()
+                .or_else(|| fallback.retryable_errors.clone()),
+            terminal_errors: self
+                .terminal_errors
+                .clone()
+                .or_else(|| fallback.terminal_errors.clone()),
+            max_duration: self.max_duration.or(fallback.max_duration),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServiceMirroring {
+    /// # Deployment Id
+    ///
+    /// Shadow deployment receiving the mirrored requests. The deployment must expose the service,
+    /// and have accepted the dry-run protocol extension when it was discovered, as mirrored
+    /// requests run as dry-runs.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub deployment_id: DeploymentId,
+
+    /// # Fraction
+    ///
+    /// Fraction of the ingress requests to mirror, between 0 and 1.
+    /// Responses of mirrored requests are discarded.
+    pub fraction: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServiceRouting {
+    /// # Deployment Id
+    ///
+    /// Canary deployment receiving the routed invocations. The deployment must expose the service.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub deployment_id: DeploymentId,
+
+    /// # Percentage
+    ///
+    /// Percentage of the new invocations to route to the canary deployment, between 0 and 100.
+    /// The other invocations run on the latest deployment of the service.
+    #[serde(default)]
+    pub percentage: f64,
+
+    /// # Header match
+    ///
+    /// Ingress requests carrying this header are always routed to the canary deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<RoutingHeaderMatch>,
+}
+
+impl ServiceRouting {
+    /// Returns true if the invocation falls in the percentage of the canary deployment.
+    pub fn routes_invocation(&self, invocation_id: &InvocationId) -> bool {
+        is_in_percentage(invocation_id, self.percentage)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RoutingHeaderMatch {
+    /// # Name
+    ///
+    /// Name of the header, matched case-insensitively.
+    pub name: String,
+
+    /// # Value
+    ///
+    /// Expected value of the header.
+    pub value: String,
+}
+
+impl RoutingHeaderMatch {
+    pub fn matches(&self, headers: &[Header]) -> bool {
+        headers
+            .iter()
+            .any(|h| h.name.eq_ignore_ascii_case(&self.name) && *h.value == *self.value)
+    }
+}
+
+/// The split is computed on the invocation uuid, so that all the nodes and all the attempts of
+/// an invocation agree on the same deployment.
+pub(crate) fn is_in_percentage(invocation_id: &InvocationId, percentage: f64) -> bool {
+    if percentage <= 0.0 {
+        return false;
+    }
+    let bucket = (u128::from(invocation_id.invocation_uuid()) % 10_000) as f64;
+    bucket < percentage * 100.0
 }
 
 // This type is used only for exposing the handler metadata, and not internally. See [ServiceAndHandlerType].
@@ -189,6 +425,79 @@ pub struct HandlerMetadata {
     /// JSON Schema of the handler output
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_json_schema: Option<serde_json::Value>,
+
+    /// # Idempotency retention
+    ///
+    /// The retention duration of idempotent requests for this handler.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub idempotency_retention: Option<humantime::Duration>,
+
+    /// # Completion retention
+    ///
+    /// The retention duration of completed invocations of this handler. For workflow handlers,
+    /// this is the workflow completion retention.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub completion_retention: Option<humantime::Duration>,
+
+    /// # Retry policy
+    ///
+    /// Retry policies of the invocations of this handler, combining the overrides of the
+    /// handler with the ones of the service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+
+    /// # Execution timeout
+    ///
+    /// Maximum wall-clock time an invocation of this handler can execute for, before being
+    /// aborted with a timeout error. If unset, it falls back to the one of the service.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub execution_timeout: Option<humantime::Duration>,
+
+    /// # Inactivity timeout
+    ///
+    /// Inactivity timeout of the invocations of this handler, as declared by the SDK.
+    /// If unset, it falls back to the one of the service.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub inactivity_timeout: Option<humantime::Duration>,
+
+    /// # Abort timeout
+    ///
+    /// Abort timeout of the invocations of this handler, as declared by the SDK.
+    /// If unset, it falls back to the one of the service.
+    #[serde(
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub abort_timeout: Option<humantime::Duration>,
+
+    /// # Public
+    ///
+    /// If false, this handler cannot be invoked through the ingress, either because the
+    /// service is private or because the handler was declared ingress private by the SDK.
+    #[serde(default = "restate_serde_util::default::bool::<true>")]
+    pub public: bool,
 }
 
 /// This API will return services registered by the user.
@@ -200,6 +509,9 @@ pub trait ServiceMetadataResolver {
         service_name: impl AsRef<str>,
     ) -> Option<serde_json::Value>;
 
+    /// Returns the OpenAPI contract of all the public services.
+    fn resolve_latest_services_openapi(&self) -> serde_json::Value;
+
     fn resolve_latest_service_type(&self, service_name: impl AsRef<str>) -> Option<ServiceType>;
 
     fn list_services(&self) -> Vec<ServiceMetadata>;
@@ -208,6 +520,28 @@ pub trait ServiceMetadataResolver {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HandlerSchemas {
     pub target_meta: InvocationTargetMetadata,
+    /// Overrides the idempotency retention of the service, see [`ServiceSchemas::apply_retention_policies`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_retention: Option<Duration>,
+    /// Overrides the completion retention of the service, see [`ServiceSchemas::apply_retention_policies`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_retention: Option<Duration>,
+    /// Overrides the retry policies of the service, see [`ServiceSchemas::handler_retry_policy`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+    /// Overrides the execution timeout of the service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_timeout: Option<Duration>,
+    /// Overrides the inactivity timeout of the service, as declared by the SDK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactivity_timeout: Option<Duration>,
+    /// Overrides the abort timeout of the service, as declared by the SDK.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_timeout: Option<Duration>,
+    /// If true, the handler is not exposed through the ingress, even if the service is public.
+    /// See [`ServiceSchemas::apply_visibility`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ingress_private: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub documentation: Option<String>,
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
@@ -222,12 +556,39 @@ pub struct ServiceSchemas {
     pub location: ServiceLocation,
     pub idempotency_retention: Duration,
     pub workflow_completion_retention: Option<Duration>,
+    /// Retention of the completed invocations of the non-workflow handlers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_retention: Option<Duration>,
     pub inactivity_timeout: Option<Duration>,
     pub abort_timeout: Option<Duration>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub documentation: Option<String>,
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirroring: Option<ServiceMirroring>,
+    /// Canary deployment of the new invocations, see [`ServiceMetadata::routing`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<ServiceRouting>,
+    /// Per key limit of the concurrent executions of shared handlers, see [`ServiceSchemas::apply_shared_handler_concurrency`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_handler_concurrency: Option<NonZeroU32>,
+    /// Limit of the concurrent invocations of this service enforced by the invoker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<NonZeroU32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicyOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_timeout: Option<Duration>,
+    /// Paused services don't execute new invocations, see [`ServiceMetadata::paused`].
+    #[serde(default)]
+    pub paused: bool,
+    /// Services allowed to call this service, see [`ServiceSchemas::apply_visibility`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_callers: Option<Vec<String>>,
+    /// Options set through the admin API, see [`ServiceSchemas::apply_admin_overrides`].
+    #[serde(default, skip_serializing_if = "AdminOverrides::is_empty")]
+    pub admin_overrides: AdminOverrides,
 
     /// This is a cache for the computed value of ServiceOpenAPI
     #[serde(skip)]
@@ -250,6 +611,25 @@ impl ServiceSchemas {
                     output_description: h_schemas.target_meta.output_rules.to_string(),
                     input_json_schema: h_schemas.target_meta.input_rules.json_schema(),
                     output_json_schema: h_schemas.target_meta.output_rules.json_schema(),
+                    idempotency_retention: Some(h_schemas.target_meta.idempotency_retention.into()),
+                    completion_retention: h_schemas
+                        .target_meta
+                        .completion_retention
+                        .map(Into::into),
+                    retry_policy: self.handler_retry_policy(h_schemas),
+                    execution_timeout: h_schemas
+                        .execution_timeout
+                        .or(self.execution_timeout)
+                        .map(Into::into),
+                    inactivity_timeout: h_schemas
+                        .inactivity_timeout
+                        .or(self.inactivity_timeout)
+                        .map(Into::into),
+                    abort_timeout: h_schemas
+                        .abort_timeout
+                        .or(self.abort_timeout)
+                        .map(Into::into),
+                    public: h_schemas.target_meta.public,
                 })
                 .collect(),
             ty: self.ty,
@@ -258,30 +638,160 @@ impl ServiceSchemas {
             deployment_id: self.location.latest_deployment,
             revision: self.revision,
             public: self.location.public,
+            allowed_callers: self.allowed_callers.clone(),
+            paused: self.paused,
             idempotency_retention: self.idempotency_retention.into(),
             workflow_completion_retention: self.workflow_completion_retention.map(Into::into),
+            completion_retention: self.completion_retention.map(Into::into),
             inactivity_timeout: self.inactivity_timeout.map(Into::into),
             abort_timeout: self.abort_timeout.map(Into::into),
+            mirroring: self.mirroring.clone(),
+            routing: self.routing.clone(),
+            shared_handler_concurrency: self.shared_handler_concurrency,
+            concurrency_limit: self.concurrency_limit,
+            retry_policy: self.retry_policy.clone(),
+            execution_timeout: self.execution_timeout.map(Into::into),
         }
     }
 
-    pub fn openapi_spec(&self, name: &str) -> serde_json::Value {
-        let service_openapi = {
-            let cached_openapi = self.service_openapi_cache.load();
-            if let Some(result) = cached_openapi.as_ref() {
-                Arc::clone(result)
+    /// Retry policies of the given handler. Handler overrides take precedence over the ones of
+    /// the service.
+    pub fn handler_retry_policy(&self, handler: &HandlerSchemas) -> Option<RetryPolicyOverrides> {
+        match (&handler.retry_policy, &self.retry_policy) {
+            (Some(handler_policy), Some(service_policy)) => Some(handler_policy.or(service_policy)),
+            (handler_policy, service_policy) => handler_policy.clone().or(service_policy.clone()),
+        }
+    }
+
+    /// Propagates the shared handler concurrency limit of the service to its shared handlers.
+    pub fn apply_shared_handler_concurrency(&mut self) {
+        for handler in self.handlers.values_mut() {
+            handler.target_meta.shared_concurrency_limit = if handler.target_meta.target_ty
+                == InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Shared)
+            {
+                self.shared_handler_concurrency
             } else {
-                // Not present, compute it!
-                let computed = Arc::new(ServiceOpenAPI::infer(name, self.ty, &self.handlers));
-                self.service_openapi_cache.store(Some(computed.clone()));
-                computed
+                None
+            };
+        }
+    }
+
+    /// Computes the visibility of each handler: a handler is public only if the service is public
+    /// and the handler wasn't declared ingress private, and it can be called only by the allowed
+    /// callers of the service, if any.
+    pub fn apply_visibility(&mut self) {
+        for handler in self.handlers.values_mut() {
+            handler.target_meta.public = self.location.public && !handler.ingress_private;
+            handler.target_meta.allowed_callers = self.allowed_callers.clone();
+        }
+    }
+
+    /// Re-applies the options set through the admin API, which take precedence over the options
+    /// declared by the SDK when registering a new revision of the service.
+    pub fn apply_admin_overrides(&mut self) {
+        let overrides = &self.admin_overrides;
+        if let Some(public) = overrides.public {
+            self.location.public = public;
+        }
+        if let Some(idempotency_retention) = overrides.idempotency_retention {
+            self.idempotency_retention = idempotency_retention;
+        }
+        if let Some(completion_retention) = overrides.completion_retention {
+            self.completion_retention =
+                (!completion_retention.is_zero()).then_some(completion_retention);
+        }
+        if let Some(inactivity_timeout) = overrides.inactivity_timeout {
+            self.inactivity_timeout = Some(inactivity_timeout);
+        }
+        if let Some(abort_timeout) = overrides.abort_timeout {
+            self.abort_timeout = Some(abort_timeout);
+        }
+        for (name, handler_overrides) in &overrides.handlers {
+            if let Some(handler) = self.handlers.get_mut(name) {
+                if handler_overrides.idempotency_retention.is_some() {
+                    handler.idempotency_retention = handler_overrides.idempotency_retention;
+                }
+                if handler_overrides.completion_retention.is_some() {
+                    handler.completion_retention = handler_overrides.completion_retention;
+                }
             }
-        };
+        }
+    }
+
+    /// Computes the retention of each handler. Handler overrides take precedence over the
+    /// service policy, which is the workflow completion retention for workflow handlers and the
+    /// completion retention for all the other handlers.
+    pub fn apply_retention_policies(&mut self) {
+        for handler in self.handlers.values_mut() {
+            handler.target_meta.idempotency_retention = handler
+                .idempotency_retention
+                .unwrap_or(self.idempotency_retention);
+            handler.target_meta.completion_retention = handler.completion_retention.or(
+                if handler.target_meta.target_ty
+                    == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+                {
+                    self.workflow_completion_retention
+                } else {
+                    self.completion_retention
+                },
+            );
+        }
+    }
+
+    pub fn openapi_spec(&self, name: &str) -> serde_json::Value {
+        self.service_openapi(name).to_openapi_contract(
+            name,
+            self.documentation.as_deref(),
+            self.revision,
+        )
+    }
 
-        service_openapi.to_openapi_contract(name, self.documentation.as_deref(), self.revision)
+    fn service_openapi(&self, name: &str) -> Arc<ServiceOpenAPI> {
+        let cached_openapi = self.service_openapi_cache.load();
+        if let Some(result) = cached_openapi.as_ref() {
+            Arc::clone(result)
+        } else {
+            // Not present, compute it!
+            let computed = Arc::new(ServiceOpenAPI::infer(name, self.ty, &self.handlers));
+            self.service_openapi_cache.store(Some(computed.clone()));
+            computed
+        }
     }
 }
 
+/// Service options set through the admin API. Unlike the other options of [`ServiceSchemas`],
+/// which are overwritten by the options declared by the SDK, they are kept across revisions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AdminOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_retention: Option<Duration>,
+    /// A zero retention disables it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_retention: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactivity_timeout: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_timeout: Option<Duration>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub handlers: HashMap<String, HandlerAdminOverrides>,
+}
+
+impl AdminOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HandlerAdminOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_retention: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_retention: Option<Duration>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ServiceLocation {
     pub latest_deployment: DeploymentId,
@@ -304,6 +814,33 @@ impl ServiceMetadataResolver for Schema {
         self.use_service_schema(name, |service_schemas| service_schemas.openapi_spec(name))
     }
 
+    fn resolve_latest_services_openapi(&self) -> serde_json::Value {
+        let mut services: Vec<_> = self
+            .services
+            .iter()
+            .filter(|(_, service_schemas)| service_schemas.location.public)
+            .map(|(service_name, service_schemas)| {
+                (
+                    service_name,
+                    service_schemas,
+                    service_schemas.service_openapi(service_name),
+                )
+            })
+            .collect();
+        // Stable order of the tags
+        services.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        infer_services_openapi_contract(services.iter().map(
+            |(service_name, service_schemas, service_openapi)| ServiceContract {
+                name: service_name,
+                ty: service_schemas.ty,
+                documentation: service_schemas.documentation.as_deref(),
+                handlers: &service_schemas.handlers,
+                openapi: service_openapi,
+            },
+        ))
+    }
+
     fn resolve_latest_service_type(&self, service_name: impl AsRef<str>) -> Option<ServiceType> {
         self.use_service_schema(service_name.as_ref(), |service_schemas| service_schemas.ty)
     }
@@ -318,6 +855,24 @@ impl ServiceMetadataResolver for Schema {
     }
 }
 
+impl Schema {
+    /// Returns the execution timeout of the handler, which falls back to the one of the service.
+    pub fn resolve_execution_timeout(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+    ) -> Option<Duration> {
+        self.use_service_schema(service_name.as_ref(), |service_schemas| {
+            service_schemas
+                .handlers
+                .get(handler_name.as_ref())
+                .and_then(|handler_schemas| handler_schemas.execution_timeout)
+                .or(service_schemas.execution_timeout)
+        })
+        .flatten()
+    }
+}
+
 #[cfg(feature = "test-util")]
 #[allow(dead_code)]
 pub mod test_util {
@@ -345,6 +900,10 @@ pub mod test_util {
             Some(Value::Null)
         }
 
+        fn resolve_latest_services_openapi(&self) -> Value {
+            Value::Null
+        }
+
         fn resolve_latest_service_type(
             &self,
             service_name: impl AsRef<str>,
@@ -375,6 +934,13 @@ pub mod test_util {
                         output_description: "any".to_string(),
                         input_json_schema: None,
                         output_json_schema: None,
+                        idempotency_retention: None,
+                        completion_retention: None,
+                        retry_policy: None,
+                        execution_timeout: None,
+                        inactivity_timeout: None,
+                        abort_timeout: None,
+                        public: true,
                     })
                     .collect(),
                 ty: ServiceType::Service,
@@ -383,10 +949,18 @@ pub mod test_util {
                 deployment_id: Default::default(),
                 revision: 0,
                 public: true,
+                allowed_callers: None,
                 idempotency_retention: Duration::from_secs(60).into(),
                 workflow_completion_retention: None,
+                completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                mirroring: None,
+                routing: None,
+                shared_handler_concurrency: None,
+                concurrency_limit: None,
+                retry_policy: None,
+                execution_timeout: None,
             }
         }
 
@@ -407,6 +981,13 @@ pub mod test_util {
                         output_description: "any".to_string(),
                         input_json_schema: None,
                         output_json_schema: None,
+                        idempotency_retention: None,
+                        completion_retention: None,
+                        retry_policy: None,
+                        execution_timeout: None,
+                        inactivity_timeout: None,
+                        abort_timeout: None,
+                        public: true,
                     })
                     .collect(),
                 ty: ServiceType::VirtualObject,
@@ -415,10 +996,18 @@ pub mod test_util {
                 deployment_id: Default::default(),
                 revision: 0,
                 public: true,
+                allowed_callers: None,
                 idempotency_retention: Duration::from_secs(60).into(),
                 workflow_completion_retention: None,
+                completion_retention: None,
                 inactivity_timeout: None,
                 abort_timeout: None,
+                mirroring: None,
+                routing: None,
+                shared_handler_concurrency: None,
+                concurrency_limit: None,
+                retry_policy: None,
+                execution_timeout: None,
             }
         }
     }
@@ -10,6 +10,8 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -19,12 +21,141 @@ use super::Schema;
 use crate::config::IngressOptions;
 use crate::errors::GenericError;
 use crate::identifiers::SubscriptionId;
-use crate::invocation::{VirtualObjectHandlerType, WorkflowHandlerType};
+use crate::invocation::{InvocationTarget, VirtualObjectHandlerType, WorkflowHandlerType};
+
+/// Subscription options starting with this prefix are interpreted by Restate, all the other
+/// options are passed to the Kafka client.
+pub const RESTATE_OPTIONS_PREFIX: &str = "restate.";
+/// Topic, in the same cluster as the source, where the events which cannot be converted to an
+/// invocation are published to, together with the error.
+pub const DEAD_LETTER_TOPIC_OPTION: &str = "restate.dead-letter-topic";
+/// Name of the SQS message attribute to use as key of the event. When unset, the message group
+/// id of FIFO queues is used.
+pub const SQS_KEY_ATTRIBUTE_OPTION: &str = "restate.key-attribute";
+/// If `true`, the body of the SQS messages is an SNS notification, which is unwrapped before
+/// ingesting the event.
+pub const SQS_SNS_ENVELOPE_OPTION: &str = "restate.sns-envelope";
+/// Endpoint to use instead of the public AWS SQS endpoint of the region, e.g. for local testing.
+pub const SQS_ENDPOINT_URL_OPTION: &str = "restate.endpoint-url";
+/// Subject filter of the JetStream consumer. When unset, all the messages of the stream are
+/// ingested.
+pub const NATS_FILTER_SUBJECT_OPTION: &str = "restate.filter-subject";
+/// Name of the NATS message header to use as key of the event. When unset, the subject of the
+/// message is used.
+pub const NATS_KEY_HEADER_OPTION: &str = "restate.key-header";
+/// Max number of messages delivered to the JetStream consumer and not acknowledged yet. Setting
+/// it to 1 ingests the messages in the order of the stream also across redeliveries, at the cost
+/// of waiting for the acknowledgement of every message before receiving the next one.
+pub const NATS_MAX_ACK_PENDING_OPTION: &str = "restate.max-ack-pending";
+pub const DEFAULT_NATS_MAX_ACK_PENDING: i64 = 1000;
+/// User and password to authenticate to the NATS server.
+pub const NATS_USER_OPTION: &str = "restate.user";
+pub const NATS_PASSWORD_OPTION: &str = "restate.password";
+/// Token to authenticate to the NATS server.
+pub const NATS_TOKEN_OPTION: &str = "restate.token";
+/// Path of the credentials file, containing the JWT and NKey seed to authenticate to the NATS
+/// server.
+pub const NATS_CREDENTIALS_FILE_OPTION: &str = "restate.credentials-file";
+/// If `true`, the connection to the NATS server must use TLS.
+pub const NATS_TLS_OPTION: &str = "restate.tls";
+/// Path of the PEM file with the root certificates used to verify the NATS server, in addition
+/// to the system ones.
+pub const NATS_TLS_CA_FILE_OPTION: &str = "restate.tls-ca-file";
+/// Secret shared with the webhook provider, used to verify the signature of the requests.
+pub const WEBHOOK_SECRET_OPTION: &str = "restate.secret";
+/// Name of the request header to use as key of the event, required by virtual object and
+/// workflow sinks.
+pub const WEBHOOK_KEY_HEADER_OPTION: &str = "restate.key-header";
+/// Name of the request header carrying the signature of generic HMAC webhooks.
+pub const WEBHOOK_SIGNATURE_HEADER_OPTION: &str = "restate.signature-header";
+pub const DEFAULT_WEBHOOK_SIGNATURE_HEADER: &str = "x-signature";
+/// Name of the request header carrying the unique id of the events of generic HMAC webhooks,
+/// used to discard the replayed requests. When unset, the signature is used as id.
+pub const WEBHOOK_ID_HEADER_OPTION: &str = "restate.id-header";
+/// Name of the request header carrying the signed timestamp, in seconds since the Unix epoch,
+/// of generic HMAC webhooks.
+pub const WEBHOOK_TIMESTAMP_HEADER_OPTION: &str = "restate.timestamp-header";
+pub const DEFAULT_WEBHOOK_TIMESTAMP_HEADER: &str = "x-timestamp";
+/// Max difference between the signed timestamp of a Stripe or generic HMAC request and the
+/// current time.
+pub const WEBHOOK_TIMESTAMP_TOLERANCE_OPTION: &str = "restate.timestamp-tolerance";
+pub const DEFAULT_WEBHOOK_TIMESTAMP_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// Provider of a webhook source, defining how the requests are signed and how the events are
+/// identified.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WebhookProvider {
+    Stripe,
+    Github,
+    Hmac,
+}
+
+impl fmt::Display for WebhookProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookProvider::Stripe => write!(f, "stripe"),
+            WebhookProvider::Github => write!(f, "github"),
+            WebhookProvider::Hmac => write!(f, "hmac"),
+        }
+    }
+}
+
+impl FromStr for WebhookProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stripe" => Ok(WebhookProvider::Stripe),
+            "github" => Ok(WebhookProvider::Github),
+            "hmac" => Ok(WebhookProvider::Hmac),
+            _ => Err(format!("unknown webhook provider '{s}'")),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Source {
-    Kafka { cluster: String, topic: String },
+    Kafka {
+        cluster: String,
+        topic: String,
+    },
+    Sqs {
+        region: String,
+        account_id: String,
+        queue: String,
+    },
+    Nats {
+        server: String,
+        stream: String,
+    },
+    /// Events pushed to the ingress, at `/restate/webhooks/<subscription_id>`
+    Webhook {
+        provider: WebhookProvider,
+    },
+}
+
+impl Source {
+    /// Url of the SQS queue, or `None` if this is not an SQS source.
+    pub fn sqs_queue_url(&self, endpoint_url: Option<&str>) -> Option<String> {
+        match self {
+            Source::Kafka { .. } | Source::Nats { .. } | Source::Webhook { .. } => None,
+            Source::Sqs {
+                region,
+                account_id,
+                queue,
+            } => Some(match endpoint_url {
+                Some(endpoint_url) => format!(
+                    "{}/{}/{}",
+                    endpoint_url.trim_end_matches('/'),
+                    account_id,
+                    queue
+                ),
+                None => format!("https://sqs.{region}.amazonaws.com/{account_id}/{queue}"),
+            }),
+        }
+    }
 }
 
 impl fmt::Display for Source {
@@ -33,6 +164,19 @@ impl fmt::Display for Source {
             Source::Kafka { cluster, topic, .. } => {
                 write!(f, "kafka://{}/{}", cluster, topic)
             }
+            Source::Sqs {
+                region,
+                account_id,
+                queue,
+            } => {
+                write!(f, "sqs://{}/{}/{}", region, account_id, queue)
+            }
+            Source::Nats { server, stream } => {
+                write!(f, "nats://{}/{}", server, stream)
+            }
+            Source::Webhook { provider } => {
+                write!(f, "webhook://{}", provider)
+            }
         }
     }
 }
@@ -86,6 +230,55 @@ pub enum EventInvocationTargetTemplate {
     },
 }
 
+impl Sink {
+    /// Target of the invocation of this sink for an event. The key of the event is resolved only
+    /// when it's required, that is to invoke virtual objects and workflows.
+    pub fn invocation_target<E>(
+        &self,
+        key: impl FnOnce() -> Result<String, E>,
+    ) -> Result<InvocationTarget, E> {
+        Ok(match self {
+            Sink::DeprecatedService { name, handler, ty } => match ty {
+                EventReceiverServiceType::VirtualObject => InvocationTarget::virtual_object(
+                    &**name,
+                    key()?,
+                    &**handler,
+                    VirtualObjectHandlerType::Exclusive,
+                ),
+                EventReceiverServiceType::Workflow => InvocationTarget::workflow(
+                    &**name,
+                    key()?,
+                    &**handler,
+                    WorkflowHandlerType::Workflow,
+                ),
+                EventReceiverServiceType::Service => InvocationTarget::service(&**name, &**handler),
+            },
+            Sink::Invocation {
+                event_invocation_target_template,
+            } => match event_invocation_target_template {
+                EventInvocationTargetTemplate::Service { name, handler } => {
+                    InvocationTarget::service(name.clone(), handler.clone())
+                }
+                EventInvocationTargetTemplate::VirtualObject {
+                    name,
+                    handler,
+                    handler_ty,
+                } => InvocationTarget::virtual_object(
+                    name.clone(),
+                    key()?,
+                    handler.clone(),
+                    *handler_ty,
+                ),
+                EventInvocationTargetTemplate::Workflow {
+                    name,
+                    handler,
+                    handler_ty,
+                } => InvocationTarget::workflow(name.clone(), key()?, handler.clone(), *handler_ty),
+            },
+        })
+    }
+}
+
 impl fmt::Display for Sink {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -159,6 +352,117 @@ impl Subscription {
     pub fn metadata_mut(&mut self) -> &mut HashMap<String, String> {
         &mut self.metadata
     }
+
+    /// Options to pass to the Kafka client, excluding the ones interpreted by Restate.
+    pub fn client_options(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.metadata
+            .iter()
+            .filter(|(k, _)| !k.starts_with(RESTATE_OPTIONS_PREFIX))
+    }
+
+    pub fn dead_letter_topic(&self) -> Option<&str> {
+        self.metadata
+            .get(DEAD_LETTER_TOPIC_OPTION)
+            .map(String::as_str)
+    }
+
+    pub fn sqs_key_attribute(&self) -> Option<&str> {
+        self.metadata
+            .get(SQS_KEY_ATTRIBUTE_OPTION)
+            .map(String::as_str)
+    }
+
+    /// Whether the SQS messages wrap SNS notifications. The value is checked by the
+    /// [`SubscriptionValidator`] when the subscription is created.
+    pub fn sqs_sns_envelope(&self) -> bool {
+        self.metadata
+            .get(SQS_SNS_ENVELOPE_OPTION)
+            .and_then(|sns_envelope| sns_envelope.parse().ok())
+            .unwrap_or(false)
+    }
+
+    pub fn sqs_endpoint_url(&self) -> Option<&str> {
+        self.metadata
+            .get(SQS_ENDPOINT_URL_OPTION)
+            .map(String::as_str)
+    }
+
+    pub fn nats_filter_subject(&self) -> Option<&str> {
+        self.metadata
+            .get(NATS_FILTER_SUBJECT_OPTION)
+            .map(String::as_str)
+    }
+
+    pub fn nats_key_header(&self) -> Option<&str> {
+        self.metadata
+            .get(NATS_KEY_HEADER_OPTION)
+            .map(String::as_str)
+    }
+
+    /// The value is checked by the [`SubscriptionValidator`] when the subscription is created.
+    pub fn nats_max_ack_pending(&self) -> i64 {
+        self.metadata
+            .get(NATS_MAX_ACK_PENDING_OPTION)
+            .and_then(|max_ack_pending| max_ack_pending.parse().ok())
+            .unwrap_or(DEFAULT_NATS_MAX_ACK_PENDING)
+    }
+
+    /// Whether the connection to the NATS server must use TLS. The value is checked by the
+    /// [`SubscriptionValidator`] when the subscription is created.
+    pub fn nats_tls(&self) -> bool {
+        self.metadata
+            .get(NATS_TLS_OPTION)
+            .and_then(|tls| tls.parse().ok())
+            .unwrap_or(false)
+    }
+
+    pub fn webhook_secret(&self) -> Option<&str> {
+        self.metadata.get(WEBHOOK_SECRET_OPTION).map(String::as_str)
+    }
+
+    pub fn webhook_key_header(&self) -> Option<&str> {
+        self.metadata
+            .get(WEBHOOK_KEY_HEADER_OPTION)
+            .map(String::as_str)
+    }
+
+    pub fn webhook_signature_header(&self) -> &str {
+        self.metadata
+            .get(WEBHOOK_SIGNATURE_HEADER_OPTION)
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_WEBHOOK_SIGNATURE_HEADER)
+    }
+
+    pub fn webhook_timestamp_header(&self) -> &str {
+        self.metadata
+            .get(WEBHOOK_TIMESTAMP_HEADER_OPTION)
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_WEBHOOK_TIMESTAMP_HEADER)
+    }
+
+    pub fn webhook_id_header(&self) -> Option<&str> {
+        self.metadata
+            .get(WEBHOOK_ID_HEADER_OPTION)
+            .map(String::as_str)
+    }
+
+    /// The value is checked by the [`SubscriptionValidator`] when the subscription is created.
+    pub fn webhook_timestamp_tolerance(&self) -> Duration {
+        self.metadata
+            .get(WEBHOOK_TIMESTAMP_TOLERANCE_OPTION)
+            .and_then(|tolerance| humantime::parse_duration(tolerance).ok())
+            .unwrap_or(DEFAULT_WEBHOOK_TIMESTAMP_TOLERANCE)
+    }
+}
+
+/// Whether the subscription option holds a secret, like the signing secret of webhooks or the
+/// SASL password of Kafka clusters.
+pub fn is_secret_option(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == WEBHOOK_SECRET_OPTION
+        || ["password", "secret", "token", "credentials"]
+            .iter()
+            .any(|secret| key.contains(secret))
 }
 
 pub enum ListSubscriptionFilter {
@@ -219,8 +523,17 @@ impl SubscriptionValidator for IngressOptions {
     type Error = ValidationError;
 
     fn validate(&self, mut subscription: Subscription) -> Result<Subscription, Self::Error> {
+        let cluster = match subscription.source() {
+            Source::Kafka { cluster, .. } => cluster,
+            Source::Sqs { .. } => return validate_sqs_subscription(subscription),
+            Source::Nats { .. } => return validate_nats_subscription(subscription),
+            Source::Webhook { provider } => {
+                let provider = *provider;
+                return validate_webhook_subscription(provider, subscription);
+            }
+        };
+
         // Retrieve the cluster option and merge them with subscription metadata
-        let Source::Kafka { cluster, .. } = subscription.source();
         let cluster_options = &self.get_kafka_cluster(cluster).ok_or(ValidationError {
             name: "source",
             reason: "specified cluster in the source URI does not exist. Make sure it is defined in the KafkaOptions",
@@ -239,6 +552,17 @@ impl SubscriptionValidator for IngressOptions {
             warn!("The configuration option enable.auto.offset.store should not be set and it will be ignored.");
         }
 
+        if let Some(dead_letter_topic) = subscription.dead_letter_topic() {
+            if dead_letter_topic.is_empty()
+                || matches!(subscription.source(), Source::Kafka { topic, .. } if dead_letter_topic == topic)
+            {
+                return Err(ValidationError {
+                    name: DEAD_LETTER_TOPIC_OPTION,
+                    reason: "must not be empty, nor be the source topic",
+                });
+            }
+        }
+
         // Set the group.id if unset
         if !(cluster_options.contains_key("group.id")
             || subscription.metadata().contains_key("group.id"))
@@ -263,6 +587,167 @@ impl SubscriptionValidator for IngressOptions {
     }
 }
 
+fn validate_sqs_subscription(subscription: Subscription) -> Result<Subscription, ValidationError> {
+    // Retries and dead lettering are configured on the queue itself, through its redrive policy
+    if subscription.dead_letter_topic().is_some() {
+        return Err(ValidationError {
+            name: DEAD_LETTER_TOPIC_OPTION,
+            reason:
+                "is not supported by SQS sources, configure a redrive policy on the queue instead",
+        });
+    }
+    if subscription
+        .sqs_key_attribute()
+        .is_some_and(|key_attribute| key_attribute.is_empty())
+    {
+        return Err(ValidationError {
+            name: SQS_KEY_ATTRIBUTE_OPTION,
+            reason: "must not be empty",
+        });
+    }
+    if let Some(sns_envelope) = subscription.metadata().get(SQS_SNS_ENVELOPE_OPTION) {
+        if sns_envelope.parse::<bool>().is_err() {
+            return Err(ValidationError {
+                name: SQS_SNS_ENVELOPE_OPTION,
+                reason: "must be either true or false",
+            });
+        }
+    }
+
+    Ok(subscription)
+}
+
+fn validate_nats_subscription(subscription: Subscription) -> Result<Subscription, ValidationError> {
+    if subscription.dead_letter_topic().is_some() {
+        return Err(ValidationError {
+            name: DEAD_LETTER_TOPIC_OPTION,
+            reason: "is not supported by NATS sources",
+        });
+    }
+    if let Some(max_ack_pending) = subscription.metadata().get(NATS_MAX_ACK_PENDING_OPTION) {
+        if !max_ack_pending
+            .parse::<i64>()
+            .is_ok_and(|max_ack_pending| max_ack_pending > 0)
+        {
+            return Err(ValidationError {
+                name: NATS_MAX_ACK_PENDING_OPTION,
+                reason: "must be a positive integer",
+            });
+        }
+    }
+    if let Some(tls) = subscription.metadata().get(NATS_TLS_OPTION) {
+        if tls.parse::<bool>().is_err() {
+            return Err(ValidationError {
+                name: NATS_TLS_OPTION,
+                reason: "must be either true or false",
+            });
+        }
+    }
+    if subscription.metadata().contains_key(NATS_USER_OPTION)
+        != subscription.metadata().contains_key(NATS_PASSWORD_OPTION)
+    {
+        return Err(ValidationError {
+            name: NATS_PASSWORD_OPTION,
+            reason: "must be set together with the user",
+        });
+    }
+    let auth_methods = [
+        NATS_USER_OPTION,
+        NATS_TOKEN_OPTION,
+        NATS_CREDENTIALS_FILE_OPTION,
+    ]
+    .into_iter()
+    .filter(|option| subscription.metadata().contains_key(*option))
+    .count();
+    if auth_methods > 1 {
+        return Err(ValidationError {
+            name: NATS_TOKEN_OPTION,
+            reason: "only one of user and password, token or credentials file can be set",
+        });
+    }
+    if subscription
+        .nats_key_header()
+        .is_some_and(|key_header| key_header.is_empty())
+    {
+        return Err(ValidationError {
+            name: NATS_KEY_HEADER_OPTION,
+            reason: "must not be empty",
+        });
+    }
+
+    Ok(subscription)
+}
+
+fn validate_webhook_subscription(
+    provider: WebhookProvider,
+    subscription: Subscription,
+) -> Result<Subscription, ValidationError> {
+    // Providers retry the requests which are not accepted
+    if subscription.dead_letter_topic().is_some() {
+        return Err(ValidationError {
+            name: DEAD_LETTER_TOPIC_OPTION,
+            reason: "is not supported by webhook sources",
+        });
+    }
+    if subscription
+        .webhook_secret()
+        .is_none_or(|secret| secret.is_empty())
+    {
+        return Err(ValidationError {
+            name: WEBHOOK_SECRET_OPTION,
+            reason: "must be set to the signing secret of the webhook",
+        });
+    }
+    if subscription
+        .webhook_key_header()
+        .is_some_and(|key_header| key_header.is_empty())
+    {
+        return Err(ValidationError {
+            name: WEBHOOK_KEY_HEADER_OPTION,
+            reason: "must not be empty",
+        });
+    }
+    for name in [
+        WEBHOOK_SIGNATURE_HEADER_OPTION,
+        WEBHOOK_TIMESTAMP_HEADER_OPTION,
+        WEBHOOK_ID_HEADER_OPTION,
+    ] {
+        if let Some(header) = subscription.metadata().get(name) {
+            if provider != WebhookProvider::Hmac {
+                return Err(ValidationError {
+                    name,
+                    reason: "is supported only by hmac webhook sources",
+                });
+            }
+            if http::HeaderName::from_str(header).is_err() {
+                return Err(ValidationError {
+                    name,
+                    reason: "must be a valid header name",
+                });
+            }
+        }
+    }
+    if let Some(tolerance) = subscription
+        .metadata()
+        .get(WEBHOOK_TIMESTAMP_TOLERANCE_OPTION)
+    {
+        if provider == WebhookProvider::Github {
+            return Err(ValidationError {
+                name: WEBHOOK_TIMESTAMP_TOLERANCE_OPTION,
+                reason: "is supported only by stripe and hmac webhook sources",
+            });
+        }
+        if humantime::parse_duration(tolerance).is_err() {
+            return Err(ValidationError {
+                name: WEBHOOK_TIMESTAMP_TOLERANCE_OPTION,
+                reason: "must be a duration, e.g. 5m",
+            });
+        }
+    }
+
+    Ok(subscription)
+}
+
 #[cfg(feature = "test-util")]
 pub mod mocks {
     use std::str::FromStr;
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use crate::errors::InvocationError;
+use enumset::{EnumSet, EnumSetType};
 use std::ops::RangeInclusive;
 
 // Range of supported service protocol versions by this server
@@ -17,6 +18,64 @@ pub const MAX_SERVICE_PROTOCOL_VERSION: ServiceProtocolVersion = ServiceProtocol
 
 pub const MAX_SERVICE_PROTOCOL_VERSION_VALUE: i32 = i32::MAX;
 
+/// Header the runtime uses to offer its [`ProtocolExtension`]s in the discovery request, and the
+/// deployment uses to accept them in the discovery response.
+pub const PROTOCOL_EXTENSIONS_HEADER: &str = "x-restate-protocol-extensions";
+
+/// Header of the invocation request marking the invocation as a dry-run, sent only to deployments
+/// which accepted the [`ProtocolExtension::DryRun`] extension.
+pub const DRY_RUN_HEADER: &str = "x-restate-dry-run";
+
+/// Extensions of the service protocol which are not part of the service protocol specification.
+/// The runtime offers them when discovering a deployment, and uses only those the deployment
+/// accepted, independently of the negotiated service protocol version.
+// PartialEq+Eq+Clone+Copy are implemented by EnumSetType
+#[derive(
+    Debug,
+    Hash,
+    EnumSetType,
+    strum::Display,
+    strum::EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[enumset(serialize_repr = "list")]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ProtocolExtension {
+    /// The deployment skips the side effects of invocations sent with the [`DRY_RUN_HEADER`].
+    DryRun,
+    /// The runtime and the deployment may split messages in chunk messages of type `0x0006`,
+    /// whose payload is the next slice of the bytes of the chunked message, including its header.
+    /// Chunks of different messages are not interleaved, and chunk messages are not chunked.
+    ChunkedMessages,
+}
+
+impl ProtocolExtension {
+    /// Extensions offered by this runtime.
+    pub fn supported() -> EnumSet<ProtocolExtension> {
+        EnumSet::all()
+    }
+
+    /// Formats the extensions as the value of the [`PROTOCOL_EXTENSIONS_HEADER`].
+    pub fn to_header_value(extensions: EnumSet<ProtocolExtension>) -> String {
+        extensions
+            .iter()
+            .map(|extension| extension.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Parses the value of the [`PROTOCOL_EXTENSIONS_HEADER`], ignoring unknown extensions.
+    pub fn from_header_value(value: &str) -> EnumSet<ProtocolExtension> {
+        value
+            .split(',')
+            .filter_map(|extension| extension.trim().parse().ok())
+            .collect()
+    }
+}
+
 include!(concat!(env!("OUT_DIR"), "/dev.restate.service.protocol.rs"));
 
 impl ServiceProtocolVersion {
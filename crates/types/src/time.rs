@@ -11,8 +11,12 @@
 use std::fmt;
 use std::fmt::Display;
 use std::ops::Add;
+use std::str::FromStr;
 use std::time::{Duration, SystemTime};
 
+use chrono::{LocalResult, NaiveDateTime, Offset, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+
 /// Milliseconds since the unix epoch
 #[derive(
     Debug,
@@ -122,6 +126,103 @@ impl From<MillisSinceEpoch> for SystemTime {
     }
 }
 
+/// Wall-clock date and time in a named IANA timezone, e.g. `2025-03-30T09:00:00[Europe/Berlin]`.
+///
+/// [`ZonedDateTime::resolve`] takes daylight saving time transitions into account: local times
+/// which are skipped by a transition are moved forward by the length of the transition, local
+/// times which occur twice resolve to their first occurrence.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde_with::SerializeDisplay,
+    serde_with::DeserializeFromStr,
+)]
+pub struct ZonedDateTime {
+    local: NaiveDateTime,
+    timezone: Tz,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ZonedDateTimeParseError {
+    #[error(
+        "expected format '<date>T<time>[<timezone>]', e.g. '2025-03-30T09:00:00[Europe/Berlin]'"
+    )]
+    Format,
+    #[error("invalid local date time: {0}")]
+    LocalDateTime(#[from] chrono::ParseError),
+    #[error("unknown timezone '{0}'")]
+    Timezone(String),
+}
+
+impl ZonedDateTime {
+    const LOCAL_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S";
+
+    pub fn new(local: NaiveDateTime, timezone: Tz) -> Self {
+        Self { local, timezone }
+    }
+
+    pub fn local(&self) -> NaiveDateTime {
+        self.local
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Returns the instant at which the wall clock in the timezone shows the local date time.
+    pub fn resolve(&self) -> MillisSinceEpoch {
+        let utc = match self.timezone.from_local_datetime(&self.local) {
+            LocalResult::Single(date_time) | LocalResult::Ambiguous(date_time, _) => {
+                date_time.naive_utc()
+            }
+            LocalResult::None => {
+                // The local time falls into a gap. Interpreting it with the offset which was in
+                // effect before the transition moves it forward by the length of the gap.
+                let offset_before = self
+                    .timezone
+                    .offset_from_utc_datetime(&(self.local - TimeDelta::days(1)))
+                    .fix();
+                self.local - TimeDelta::seconds(i64::from(offset_before.local_minus_utc()))
+            }
+        };
+
+        MillisSinceEpoch::new(u64::try_from(utc.and_utc().timestamp_millis()).unwrap_or(0))
+    }
+}
+
+impl Display for ZonedDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]",
+            self.local.format(Self::LOCAL_FORMAT),
+            self.timezone.name()
+        )
+    }
+}
+
+impl FromStr for ZonedDateTime {
+    type Err = ZonedDateTimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (local, timezone) = s
+            .strip_suffix(']')
+            .and_then(|s| s.split_once('['))
+            .ok_or(ZonedDateTimeParseError::Format)?;
+
+        let local = NaiveDateTime::parse_from_str(local, Self::LOCAL_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M"))?;
+        let timezone = Tz::from_str(timezone)
+            .map_err(|_| ZonedDateTimeParseError::Timezone(timezone.to_owned()))?;
+
+        Ok(Self { local, timezone })
+    }
+}
+
 /// Nanos since the unix epoch. Used internally to get rough latency measurements across nodes.
 /// It's vulnerable to clock skews and sync issues, so use with care. That said, it's fairly
 /// accurate when used on the same node. This roughly maps to std::time::Instant except that the
@@ -176,6 +277,12 @@ impl From<SystemTime> for NanosSinceEpoch {
     }
 }
 
+impl From<NanosSinceEpoch> for MillisSinceEpoch {
+    fn from(value: NanosSinceEpoch) -> Self {
+        MillisSinceEpoch::new(value.0 / 1_000_000)
+    }
+}
+
 /// # Panics
 /// If timestamp is out of range (e.g. older than UNIX_EPOCH) this conversion will panic.
 impl From<prost_types::Timestamp> for NanosSinceEpoch {
@@ -216,4 +323,32 @@ mod tests {
         assert_eq!(ms_epoch.elapsed(), Duration::ZERO);
         assert_eq!(ns_epoch.elapsed(), Duration::ZERO);
     }
+
+    fn resolve(s: &str) -> u64 {
+        s.parse::<ZonedDateTime>().unwrap().resolve().as_u64()
+    }
+
+    #[test]
+    fn zoned_date_time_round_trip() {
+        let zdt: ZonedDateTime = "2025-03-30T09:00[Europe/Berlin]".parse().unwrap();
+        assert_eq!(zdt.to_string(), "2025-03-30T09:00:00[Europe/Berlin]");
+        assert_eq!(zdt, zdt.to_string().parse().unwrap());
+
+        assert!("2025-03-30T09:00:00".parse::<ZonedDateTime>().is_err());
+        assert!("2025-03-30T09:00:00[Mars/Olympus]"
+            .parse::<ZonedDateTime>()
+            .is_err());
+    }
+
+    #[test]
+    fn zoned_date_time_resolves_daylight_saving_time() {
+        // 2025-03-30T07:00:00Z, CEST
+        assert_eq!(resolve("2025-03-30T09:00:00[Europe/Berlin]"), 1743318000000);
+        // 2025-03-29T08:00:00Z, CET
+        assert_eq!(resolve("2025-03-29T09:00:00[Europe/Berlin]"), 1743235200000);
+        // 02:30 doesn't exist on 2025-03-30, moved forward to 03:30 CEST = 01:30Z
+        assert_eq!(resolve("2025-03-30T02:30:00[Europe/Berlin]"), 1743298200000);
+        // 02:30 occurs twice on 2025-10-26, the first occurrence (CEST) = 00:30Z
+        assert_eq!(resolve("2025-10-26T02:30:00[Europe/Berlin]"), 1761438600000);
+    }
 }
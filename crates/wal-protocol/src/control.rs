@@ -10,7 +10,7 @@
 
 use std::ops::RangeInclusive;
 
-use restate_types::identifiers::{LeaderEpoch, PartitionKey};
+use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
 use restate_types::GenerationalNodeId;
 
 /// Announcing a new leader. This message can be written by any component to make the specified
@@ -27,6 +27,16 @@ pub struct AnnounceLeader {
     pub partition_key_range: Option<RangeInclusive<PartitionKey>>,
 }
 
+/// Splits off the given key range into a new partition. Every replica of the partition creates
+/// the partition store of the new partition from its state right before this command, and stops
+/// serving the key range afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitPartition {
+    pub child_partition_id: PartitionId,
+    pub child_key_range: RangeInclusive<PartitionKey>,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::control::AnnounceLeader;
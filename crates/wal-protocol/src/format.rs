@@ -0,0 +1,203 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt;
+
+use once_cell::sync::Lazy;
+use xxhash_rust::xxh3::Xxh3;
+
+use restate_types::storage::StorageCodecKind;
+
+/// Version of the [`Envelope`](crate::Envelope) schema. It needs to be bumped whenever the
+/// envelope, or any of the types it contains, changes in a way that readers of the previous
+/// version can no longer decode.
+pub const ENVELOPE_SCHEMA_VERSION: u16 = 1;
+
+static CURRENT_FORMAT: Lazy<EnvelopeFormat> = Lazy::new(|| {
+    let mut hasher = Xxh3::new();
+    hasher.update(&ENVELOPE_SCHEMA_VERSION.to_le_bytes());
+    hasher.update(restate_types::protobuf::FILE_DESCRIPTOR_SET);
+    EnvelopeFormat {
+        codec: StorageCodecKind::FlexbuffersSerde as u8,
+        schema_version: ENVELOPE_SCHEMA_VERSION,
+        schema_fingerprint: hasher.digest(),
+    }
+});
+
+/// Self-description of the format an [`Envelope`](crate::Envelope) was written with. It allows
+/// readers, including external tools, to verify that they decode a record with a compatible
+/// schema, and to fail with an actionable error otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnvelopeFormat {
+    /// The [`StorageCodecKind`] that was used to encode the envelope.
+    pub codec: u8,
+    /// Schema version of the writer, see [`ENVELOPE_SCHEMA_VERSION`].
+    pub schema_version: u16,
+    /// Fingerprint of the writer's schema version and generated protobuf definitions.
+    pub schema_fingerprint: u64,
+}
+
+impl EnvelopeFormat {
+    /// The format used by this binary when writing envelopes.
+    pub fn current() -> Self {
+        *CURRENT_FORMAT
+    }
+
+    pub fn codec_kind(&self) -> Option<StorageCodecKind> {
+        StorageCodecKind::try_from(self.codec).ok()
+    }
+
+    /// Returns true if envelopes in this format can be decoded by this binary.
+    pub fn is_supported(&self) -> bool {
+        self.schema_version <= ENVELOPE_SCHEMA_VERSION
+            && matches!(self.codec_kind(), Some(StorageCodecKind::FlexbuffersSerde))
+    }
+
+    /// Returns true if the writer had the same schema fingerprint as this binary. Binaries with
+    /// the same schema version can still differ in their generated protobuf definitions.
+    pub fn has_current_fingerprint(&self) -> bool {
+        self.schema_fingerprint == CURRENT_FORMAT.schema_fingerprint
+    }
+
+    /// Verifies that envelopes in this format can be decoded by this binary, before using a
+    /// decoded envelope.
+    pub fn verify(&self) -> Result<(), UnsupportedEnvelopeFormat> {
+        if self.is_supported() {
+            Ok(())
+        } else {
+            Err(UnsupportedEnvelopeFormat {
+                written: *self,
+                supported: EnvelopeFormat::current(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for EnvelopeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "schema version {} (fingerprint {:016x}, codec {})",
+            self.schema_version,
+            self.schema_fingerprint,
+            self.codec_kind()
+                .map(|codec| codec.to_string())
+                .unwrap_or_else(|| format!("unknown({})", self.codec))
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "record was written with envelope {written} but this binary supports envelope {supported}. \
+    Upgrade to a Restate version which supports the newer envelope format to read this record."
+)]
+pub struct UnsupportedEnvelopeFormat {
+    pub written: EnvelopeFormat,
+    pub supported: EnvelopeFormat,
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+
+    use restate_types::storage::{
+        encode_as_flexbuffers, StorageCodec, StorageCodecKind, StorageDecodeError,
+    };
+
+    use super::*;
+    use crate::Envelope;
+
+    #[derive(serde::Serialize)]
+    struct FutureEnvelope {
+        header: String,
+        command: String,
+        format: Option<EnvelopeFormat>,
+    }
+
+    #[test]
+    fn current_format_is_supported() {
+        let format = EnvelopeFormat::current();
+        assert!(format.is_supported());
+        assert_eq!(format, EnvelopeFormat::current());
+    }
+
+    #[test]
+    fn verify_format() {
+        assert!(EnvelopeFormat::current().verify().is_ok());
+
+        let other_fingerprint = EnvelopeFormat {
+            schema_fingerprint: !EnvelopeFormat::current().schema_fingerprint,
+            ..EnvelopeFormat::current()
+        };
+        assert!(other_fingerprint.verify().is_ok());
+        assert!(!other_fingerprint.has_current_fingerprint());
+
+        let unknown_codec = EnvelopeFormat {
+            codec: u8::MAX,
+            ..EnvelopeFormat::current()
+        };
+        assert_eq!(unknown_codec.verify().unwrap_err().written, unknown_codec);
+    }
+
+    #[test]
+    fn decoding_newer_format_fails_with_actionable_error() {
+        let written = EnvelopeFormat {
+            schema_version: ENVELOPE_SCHEMA_VERSION + 1,
+            ..EnvelopeFormat::current()
+        };
+        let mut buf = BytesMut::new();
+        buf.put_u8(StorageCodecKind::FlexbuffersSerde.into());
+        encode_as_flexbuffers(
+            FutureEnvelope {
+                header: "unknown".to_owned(),
+                command: "unknown".to_owned(),
+                format: Some(written),
+            },
+            &mut buf,
+        )
+        .unwrap();
+
+        let err = StorageCodec::decode::<Envelope, _>(&mut buf.freeze()).unwrap_err();
+        let StorageDecodeError::DecodeValue(err) = err else {
+            panic!("unexpected error: {err}");
+        };
+        let err = err
+            .downcast_ref::<UnsupportedEnvelopeFormat>()
+            .expect("unsupported envelope format error");
+        assert_eq!(written, err.written);
+    }
+
+    #[test]
+    fn decoding_error_with_other_fingerprint_is_reported_as_is() {
+        let written = EnvelopeFormat {
+            schema_fingerprint: !EnvelopeFormat::current().schema_fingerprint,
+            ..EnvelopeFormat::current()
+        };
+        let mut buf = BytesMut::new();
+        buf.put_u8(StorageCodecKind::FlexbuffersSerde.into());
+        encode_as_flexbuffers(
+            FutureEnvelope {
+                header: "unknown".to_owned(),
+                command: "unknown".to_owned(),
+                format: Some(written),
+            },
+            &mut buf,
+        )
+        .unwrap();
+
+        let err = StorageCodec::decode::<Envelope, _>(&mut buf.freeze()).unwrap_err();
+        let StorageDecodeError::DecodeValue(err) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(err.downcast_ref::<UnsupportedEnvelopeFormat>().is_none());
+    }
+}
@@ -10,40 +10,63 @@
 
 use std::sync::Arc;
 
-use bytes::{Bytes, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
+use bytestring::ByteString;
 use restate_bifrost::Bifrost;
 use restate_core::{Metadata, ShutdownError};
+use restate_storage_api::consistency::ConsistencyRepair;
+use restate_storage_api::dead_letter_table::DeadLetterDecision;
 use restate_storage_api::deduplication_table::DedupInformation;
-use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
+use restate_storage_api::prepared_message_table::PreparedMessageDelivery;
+use restate_types::deployment::DeploymentMigration;
+use restate_types::identifiers::{
+    InvocationId, LeaderEpoch, PartitionId, PartitionKey, ScheduleId, WithPartitionKey,
+};
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationResponse, InvocationTermination, PurgeInvocationRequest,
+    AttachInvocationRequest, BulkInvocationOperation, BulkInvocationTermination, InvocationQuery,
+    InvocationResponse, InvocationTermination, PurgeInvocationRequest, RetryInvocationRequest,
     ServiceInvocation,
 };
 use restate_types::message::MessageIndex;
+use restate_types::schedule::Schedule;
 use restate_types::state_mut::ExternalStateMutation;
-use restate_types::{flexbuffers_storage_encode_decode, logs, PlainNodeId, Version};
+use restate_types::{logs, PlainNodeId, Version};
 
-use crate::control::AnnounceLeader;
-use crate::timer::TimerKeyValue;
-use restate_types::logs::{HasRecordKeys, Keys, LogId, Lsn, MatchKeyQuery};
+use crate::control::{AnnounceLeader, SplitPartition};
+use crate::timer::{FiredScheduleTimer, TimerKeyValue};
+use restate_types::logs::{HasRecordKeys, Keys, LogId, Lsn, MatchKeyQuery, Record};
 use restate_types::partition_table::{FindPartition, PartitionTableError};
-use restate_types::storage::{StorageCodec, StorageDecodeError, StorageEncodeError};
+use restate_types::storage::{
+    decode_from_flexbuffers, encode_as_flexbuffers, PolyBytes, StorageCodec, StorageCodecKind,
+    StorageDecode, StorageDecodeError, StorageEncode, StorageEncodeError,
+};
 use restate_types::GenerationalNodeId;
 
 pub mod control;
+mod format;
 pub mod timer;
 
+pub use format::{EnvelopeFormat, UnsupportedEnvelopeFormat, ENVELOPE_SCHEMA_VERSION};
+
 /// The primary envelope for all messages in the system.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Envelope {
     pub header: Header,
     pub command: Command,
+    /// Format this envelope was written with. Envelopes written before the format was introduced
+    /// don't carry it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: Option<EnvelopeFormat>,
 }
 
 impl Envelope {
     pub fn new(header: Header, command: Command) -> Self {
-        Self { header, command }
+        Self {
+            header,
+            command,
+            format: Some(EnvelopeFormat::current()),
+        }
     }
 
     pub fn to_bytes(&self) -> Result<Bytes, StorageEncodeError> {
@@ -56,9 +79,162 @@ impl Envelope {
         let mut bytes = bytes.as_ref();
         StorageCodec::decode::<Self, _>(&mut bytes)
     }
+
+    /// Returns the kind of the command of the envelope stored in `record` without decoding the
+    /// envelope. Returns `None` if the record doesn't hold an envelope or the kind is unknown.
+    pub fn peek_command_kind(record: &Record) -> Option<CommandDiscriminants> {
+        match record.body() {
+            PolyBytes::Typed(value) => value
+                .downcast_ref::<Envelope>()
+                .map(|envelope| CommandDiscriminants::from(&envelope.command)),
+            PolyBytes::Bytes(bytes) => {
+                let mut buf = bytes.clone();
+                if !buf.has_remaining()
+                    || !matches!(
+                        StorageCodecKind::try_from(buf.get_u8()),
+                        Ok(StorageCodecKind::FlexbuffersSerde)
+                    )
+                {
+                    return None;
+                }
+                let probe = decode_from_flexbuffers::<EnvelopeCommandProbe, _>(&mut buf).ok()?;
+                probe.command.0.parse().ok()
+            }
+        }
+    }
+}
+
+/// Returns a filter for [`restate_bifrost::LogReadStream::with_record_filter`] which only keeps
+/// envelopes whose command is of one of the given `kinds`. Records whose command kind can't be
+/// determined are kept, so that the reader still observes them when decoding.
+pub fn command_kind_filter(
+    kinds: impl IntoIterator<Item = CommandDiscriminants>,
+) -> impl Fn(&Record) -> bool + Send + Sync + 'static {
+    let kinds: Vec<_> = kinds.into_iter().collect();
+    move |record| Envelope::peek_command_kind(record).map_or(true, |kind| kinds.contains(&kind))
+}
+
+impl StorageEncode for Envelope {
+    fn default_codec(&self) -> StorageCodecKind {
+        StorageCodecKind::FlexbuffersSerde
+    }
+
+    fn encode(&self, buf: &mut BytesMut) -> Result<(), StorageEncodeError> {
+        encode_as_flexbuffers(self, buf).map_err(|err| StorageEncodeError::EncodeValue(err.into()))
+    }
+}
+
+/// Only used to read the [`EnvelopeFormat`] of envelopes that can't be fully decoded.
+#[derive(serde::Deserialize)]
+struct EnvelopeFormatProbe {
+    #[serde(default)]
+    format: Option<EnvelopeFormat>,
+}
+
+/// Only used to read the command kind of envelopes, see [`Envelope::peek_command_kind`].
+#[derive(serde::Deserialize)]
+struct EnvelopeCommandProbe {
+    command: CommandVariantName,
+}
+
+/// Reads the variant name of an externally tagged [`Command`] without deserializing its content.
+struct CommandVariantName(String);
+
+impl<'de> serde::Deserialize<'de> for CommandVariantName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VariantNameVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for VariantNameVisitor {
+            type Value = CommandVariantName;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an externally tagged command")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let name = map
+                    .next_key::<String>()?
+                    .ok_or_else(|| serde::de::Error::custom("empty command"))?;
+                Ok(CommandVariantName(name))
+            }
+        }
+
+        deserializer.deserialize_map(VariantNameVisitor)
+    }
+}
+
+impl StorageDecode for Envelope {
+    fn decode<B: Buf>(buf: &mut B, kind: StorageCodecKind) -> Result<Self, StorageDecodeError>
+    where
+        Self: Sized,
+    {
+        match kind {
+            StorageCodecKind::FlexbuffersSerde => {
+                if buf.chunk().len() >= buf.remaining() {
+                    let (envelope, consumed) = decode_flexbuffers_envelope(buf.chunk())?;
+                    buf.advance(consumed);
+                    Ok(envelope)
+                } else {
+                    // need a contiguous buffer to probe the format in case decoding fails
+                    let bytes = buf.copy_to_bytes(buf.remaining());
+                    decode_flexbuffers_envelope(&bytes).map(|(envelope, _)| envelope)
+                }
+            }
+            codec => Err(StorageDecodeError::UnsupportedCodecKind(codec)),
+        }
+    }
+}
+
+/// Decodes the envelope at the start of `bytes`, returning it and the number of bytes read.
+fn decode_flexbuffers_envelope(bytes: &[u8]) -> Result<(Envelope, usize), StorageDecodeError> {
+    let mut view = bytes;
+    match decode_from_flexbuffers::<Envelope, _>(&mut view) {
+        Ok(envelope) => {
+            if let Some(written) = envelope.format {
+                written
+                    .verify()
+                    .map_err(|err| StorageDecodeError::DecodeValue(err.into()))?;
+                if !written.has_current_fingerprint() {
+                    warn_fingerprint_mismatch(written);
+                }
+            }
+            Ok((envelope, bytes.len() - view.len()))
+        }
+        Err(err) => {
+            // If the envelope was written in a newer format, report this instead of the decoding
+            // error since it is the likely root cause. A different fingerprint alone doesn't
+            // explain the error, as the schema version is bumped on breaking changes.
+            match decode_from_flexbuffers::<EnvelopeFormatProbe, _>(&mut &bytes[..]) {
+                Ok(EnvelopeFormatProbe {
+                    format: Some(written),
+                }) if !written.is_supported() => Err(StorageDecodeError::DecodeValue(
+                    UnsupportedEnvelopeFormat {
+                        written,
+                        supported: EnvelopeFormat::current(),
+                    }
+                    .into(),
+                )),
+                _ => Err(StorageDecodeError::DecodeValue(err.into())),
+            }
+        }
+    }
 }
 
-flexbuffers_storage_encode_decode!(Envelope);
+/// Envelopes of the same schema version but a different fingerprint decode fine as long as the
+/// schema version is bumped on breaking changes, hence the mismatch is only reported once.
+fn warn_fingerprint_mismatch(written: EnvelopeFormat) {
+    static REPORTED: std::sync::Once = std::sync::Once::new();
+    REPORTED.call_once(|| {
+        tracing::warn!(
+            "Read a record written with envelope {written}, while this binary writes envelope {}. \
+            The nodes of the cluster might run different Restate versions.",
+            EnvelopeFormat::current()
+        );
+    });
+}
 
 /// Header is set on every message
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -129,11 +305,13 @@ pub enum Destination {
 
 /// State machine input commands
 #[derive(Debug, Clone, PartialEq, Eq, strum::EnumDiscriminants, strum::VariantNames)]
-#[strum_discriminants(derive(strum::IntoStaticStr))]
+#[strum_discriminants(derive(strum::IntoStaticStr, strum::EnumString))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Command {
     // -- Control-plane related events
     AnnounceLeader(AnnounceLeader),
+    /// Split off a part of the key range of this partition into a new partition
+    SplitPartition(SplitPartition),
 
     // -- Partition processor commands
     /// Manual patching of storage state
@@ -142,6 +320,8 @@ pub enum Command {
     TerminateInvocation(InvocationTermination),
     /// Purge a completed invocation
     PurgeInvocation(PurgeInvocationRequest),
+    /// Retry an invocation now, optionally restarting it from its input
+    RetryInvocation(RetryInvocationRequest),
     /// Start an invocation on this partition
     Invoke(ServiceInvocation),
     /// Truncate the message outbox up to, and including, the specified index.
@@ -150,22 +330,85 @@ pub enum Command {
     ProxyThrough(ServiceInvocation),
     /// Attach to an existing invocation
     AttachInvocation(AttachInvocationRequest),
+    /// Terminate all invocations of this partition whose target matches the filter
+    BulkTerminateInvocations(BulkInvocationTermination),
+    /// Cancel, kill or purge the given invocations of this partition
+    BulkInvocationOperation(BulkInvocationOperation),
+    /// Create a schedule, or replace the existing schedule with the same id
+    UpsertSchedule(Schedule),
+    /// Delete a schedule, without affecting the invocations it already fired
+    DeleteSchedule(ScheduleId),
+    /// Stop executing new invocations of the service, enqueueing them instead
+    PauseService(ByteString),
+    /// Execute the enqueued invocations of the paused service, and the new ones
+    ResumeService(ByteString),
+    /// Re-pin the invocations of this partition running on a deployment to other deployments
+    MigrateDeployment(DeploymentMigration),
+    /// Repair a benign inconsistency between the tables of this partition
+    RepairConsistency(ConsistencyRepair),
+    /// Apply a message prepared by another partition, unless it was applied before
+    ApplyPreparedMessage(PreparedMessageDelivery),
+    /// The destination applied the prepared message with the given sequence number
+    AcknowledgePreparedMessage(MessageIndex),
 
     // -- Partition processor events for PP
     /// Invoker is reporting effect(s) from an ongoing invocation.
     InvokerEffect(restate_invoker_api::Effect),
     /// Timer has fired
     Timer(TimerKeyValue),
+    /// Timer of a schedule has fired
+    FireSchedule(FiredScheduleTimer),
     /// Schedule timer
     ScheduleTimer(TimerKeyValue),
     /// Another partition processor is reporting a response of an invocation we requested.
     InvocationResponse(InvocationResponse),
+
+    // -- Dead-letter related commands
+    /// Apply the dead-lettered record which was originally written at the given lsn again.
+    ReinjectDeadLetter(Lsn),
+    /// Move the record at the given lsn, which failed to apply, to the dead-letter table. Every
+    /// replica applies the decision once it reaches the failing record.
+    DeadLetterRecord(DeadLetterDecision),
 }
 
 impl Command {
     pub fn name(&self) -> &'static str {
         CommandDiscriminants::from(self).into()
     }
+
+    /// The invocation this command is about, if any.
+    pub fn invocation_id(&self) -> Option<InvocationId> {
+        match self {
+            Command::TerminateInvocation(terminate) => Some(terminate.invocation_id),
+            Command::PurgeInvocation(purge) => Some(purge.invocation_id),
+            Command::RetryInvocation(retry) => Some(retry.invocation_id),
+            Command::Invoke(invoke) | Command::ProxyThrough(invoke) => Some(invoke.invocation_id),
+            Command::AttachInvocation(attach) => match attach.invocation_query {
+                InvocationQuery::Invocation(invocation_id) => Some(invocation_id),
+                InvocationQuery::Workflow(_) | InvocationQuery::IdempotencyId(_) => None,
+            },
+            Command::InvokerEffect(effect) => Some(effect.invocation_id),
+            Command::Timer(timer) | Command::ScheduleTimer(timer) => timer.invocation_id(),
+            Command::FireSchedule(_) => None,
+            Command::InvocationResponse(response) => Some(response.id),
+            Command::AnnounceLeader(_)
+            | Command::SplitPartition(_)
+            | Command::BulkTerminateInvocations(_)
+            | Command::BulkInvocationOperation(_)
+            | Command::PatchState(_)
+            | Command::TruncateOutbox(_)
+            | Command::UpsertSchedule(_)
+            | Command::DeleteSchedule(_)
+            | Command::PauseService(_)
+            | Command::ResumeService(_)
+            | Command::MigrateDeployment(_)
+            | Command::RepairConsistency(_)
+            | Command::ApplyPreparedMessage(_)
+            | Command::AcknowledgePreparedMessage(_)
+            | Command::ReinjectDeadLetter(_)
+            | Command::DeadLetterRecord(_) => None,
+        }
+    }
 }
 
 impl WithPartitionKey for Envelope {
@@ -188,22 +431,37 @@ impl HasRecordKeys for Envelope {
                     Keys::Single(self.partition_key())
                 }
             }
+            Command::SplitPartition(split) => Keys::RangeInclusive(split.child_key_range.clone()),
             Command::PatchState(mutation) => Keys::Single(mutation.service_id.partition_key()),
             Command::TerminateInvocation(terminate) => {
                 Keys::Single(terminate.invocation_id.partition_key())
             }
             Command::PurgeInvocation(purge) => Keys::Single(purge.invocation_id.partition_key()),
+            Command::RetryInvocation(retry) => Keys::Single(retry.invocation_id.partition_key()),
             Command::Invoke(invoke) => Keys::Single(invoke.partition_key()),
             // todo: Remove this, or pass the partition key range but filter based on partition-id
             // on read if needed.
             Command::TruncateOutbox(_) => Keys::Single(self.partition_key()),
             Command::ProxyThrough(_) => Keys::Single(self.partition_key()),
             Command::AttachInvocation(_) => Keys::Single(self.partition_key()),
+            Command::BulkTerminateInvocations(_) => Keys::Single(self.partition_key()),
+            Command::BulkInvocationOperation(_) => Keys::Single(self.partition_key()),
+            Command::UpsertSchedule(schedule) => Keys::Single(schedule.partition_key()),
+            Command::DeleteSchedule(schedule_id) => Keys::Single(schedule_id.partition_key()),
+            Command::PauseService(_) => Keys::Single(self.partition_key()),
+            Command::ResumeService(_) => Keys::Single(self.partition_key()),
+            Command::MigrateDeployment(_) => Keys::Single(self.partition_key()),
+            Command::RepairConsistency(repair) => Keys::Single(repair.partition_key()),
+            Command::ApplyPreparedMessage(delivery) => Keys::Single(delivery.partition_key()),
+            Command::AcknowledgePreparedMessage(_) => Keys::Single(self.partition_key()),
             // todo: Handle journal entries that request cross-partition invocations
             Command::InvokerEffect(effect) => Keys::Single(effect.invocation_id.partition_key()),
-            Command::Timer(timer) => Keys::Single(timer.invocation_id().partition_key()),
-            Command::ScheduleTimer(timer) => Keys::Single(timer.invocation_id().partition_key()),
+            Command::Timer(timer) => Keys::Single(timer.value().partition_key()),
+            Command::FireSchedule(fired) => Keys::Single(fired.timer.value().partition_key()),
+            Command::ScheduleTimer(timer) => Keys::Single(timer.value().partition_key()),
             Command::InvocationResponse(response) => Keys::Single(response.partition_key()),
+            Command::ReinjectDeadLetter(_) => Keys::Single(self.partition_key()),
+            Command::DeadLetterRecord(_) => Keys::Single(self.partition_key()),
         }
     }
 }
@@ -250,3 +508,49 @@ pub async fn append_envelope_to_bifrost(
 
     Ok((log_id, lsn))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::time::NanosSinceEpoch;
+
+    #[test]
+    fn peek_command_kind() {
+        let envelope = Arc::new(Envelope::new(
+            Header {
+                source: Source::ControlPlane {},
+                dest: Destination::Processor {
+                    partition_key: 42,
+                    dedup: None,
+                },
+            },
+            Command::TruncateOutbox(5),
+        ));
+
+        let encoded = Record::from_parts(
+            NanosSinceEpoch::now(),
+            Keys::Single(42),
+            PolyBytes::Bytes(envelope.to_bytes().unwrap()),
+        );
+        assert_eq!(
+            Some(CommandDiscriminants::TruncateOutbox),
+            Envelope::peek_command_kind(&encoded)
+        );
+
+        let typed = Record::from_parts(
+            NanosSinceEpoch::now(),
+            Keys::Single(42),
+            PolyBytes::Typed(envelope),
+        );
+        assert_eq!(
+            Some(CommandDiscriminants::TruncateOutbox),
+            Envelope::peek_command_kind(&typed)
+        );
+
+        let filter = command_kind_filter([CommandDiscriminants::Invoke]);
+        assert!(!filter(&encoded));
+        assert!(!filter(&typed));
+        assert!(filter(&Record::from("not an envelope".to_owned())));
+    }
+}
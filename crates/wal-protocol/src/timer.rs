@@ -9,9 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use restate_storage_api::timer_table::{Timer, TimerKey, TimerKeyKind};
-use restate_types::identifiers::{EntryIndex, InvocationId};
+use restate_types::identifiers::{EntryIndex, InvocationId, ScheduleId};
 use restate_types::invocation::ServiceInvocation;
-use restate_types::time::MillisSinceEpoch;
+use restate_types::time::{MillisSinceEpoch, ZonedDateTime};
 use std::borrow::Borrow;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -51,6 +51,17 @@ impl TimerKeyValue {
         Self { timer_key, value }
     }
 
+    pub fn neo_invoke_at_local_time(
+        wake_up_time: MillisSinceEpoch,
+        invocation_id: InvocationId,
+        local_time: ZonedDateTime,
+    ) -> Self {
+        let (timer_key, value) =
+            Timer::neo_invoke_at_local_time(wake_up_time.as_u64(), invocation_id, local_time);
+
+        Self { timer_key, value }
+    }
+
     pub fn clean_invocation_status(
         wake_up_time: MillisSinceEpoch,
         invocation_id: InvocationId,
@@ -60,6 +71,11 @@ impl TimerKeyValue {
         Self { timer_key, value }
     }
 
+    pub fn fire_schedule(wake_up_time: MillisSinceEpoch, schedule_id: ScheduleId) -> Self {
+        let (timer_key, value) = Timer::fire_schedule(wake_up_time.as_u64(), schedule_id);
+        Self { timer_key, value }
+    }
+
     pub fn into_inner(self) -> (TimerKey, Timer) {
         (self.timer_key, self.value)
     }
@@ -72,7 +88,7 @@ impl TimerKeyValue {
         &self.value
     }
 
-    pub fn invocation_id(&self) -> InvocationId {
+    pub fn invocation_id(&self) -> Option<InvocationId> {
         self.value.invocation_id()
     }
 
@@ -102,6 +118,15 @@ impl Borrow<TimerKey> for TimerKeyValue {
     }
 }
 
+/// A fired timer of a schedule, together with the time it fired at. The replicas need to agree
+/// on this time to tell whether the occurrence was missed, hence it's decided by the leader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FiredScheduleTimer {
+    pub timer: TimerKeyValue,
+    pub fired_at: MillisSinceEpoch,
+}
+
 impl restate_types::timer::Timer for TimerKeyValue {
     type TimerKey = TimerKey;
 
@@ -134,6 +159,9 @@ impl<'a> fmt::Display for TimerKeyDisplay<'a> {
             TimerKeyKind::CleanInvocationStatus { invocation_uuid } => {
                 write!(f, "Clean invocation status '{}'", invocation_uuid)
             }
+            TimerKeyKind::FireSchedule { schedule_uuid } => {
+                write!(f, "Fire schedule '{}'", schedule_uuid)
+            }
         }
     }
 }
@@ -18,10 +18,12 @@ use bytes::Bytes;
 use bytestring::ByteString;
 
 use restate_service_protocol::awakeable_id::AwakeableIdentifier;
+use restate_types::config::Configuration;
 use restate_types::errors::{codes, InvocationError};
 use restate_types::identifiers::InvocationId;
 use restate_types::invocation::{
-    InvocationTarget, InvocationTargetType, ServiceInvocationSpanContext, ServiceType, SpanRelation,
+    kafka_egress_cluster, InvocationTarget, InvocationTargetType, ServiceInvocationSpanContext,
+    ServiceType, SpanRelation, VirtualObjectHandlerType,
 };
 use restate_types::journal::enriched::{
     AwakeableEnrichmentResult, CallEnrichmentResult, EnrichedEntryHeader, EnrichedRawEntry,
@@ -61,12 +63,57 @@ where
         entry_type: EntryType,
         serialized_entry: &Bytes,
         request_extractor: impl Fn(Entry) -> InvokeRequest,
+        caller_invocation_target: &InvocationTarget,
         span_relation: SpanRelation,
     ) -> Result<CallEnrichmentResult, InvocationError> {
         let entry = Codec::deserialize(entry_type, serialized_entry.clone())
             .map_err(InvocationError::internal)?;
+        let is_delayed = matches!(
+            &entry,
+            Entry::OneWayCall(OneWayCallEntry { invoke_time, .. }) if *invoke_time != 0
+        );
         let request = request_extractor(entry);
 
+        if let Some(cluster) = kafka_egress_cluster(&request.service_name) {
+            if entry_type != EntryType::OneWayCall || is_delayed {
+                return Err(InvocationError::new(
+                    codes::BAD_REQUEST,
+                    format!(
+                        "The Kafka egress target '{}' accepts only one-way calls without delay",
+                        request.service_name
+                    ),
+                ));
+            }
+            if Configuration::pinned()
+                .ingress
+                .get_kafka_cluster(cluster)
+                .is_none()
+            {
+                return Err(InvocationError::new(
+                    codes::NOT_FOUND,
+                    format!("The Kafka cluster '{cluster}' is not configured"),
+                ));
+            }
+
+            // The key of the virtual object is the record key
+            let invocation_target = InvocationTarget::virtual_object(
+                request.service_name,
+                request.key,
+                request.handler_name,
+                VirtualObjectHandlerType::Shared,
+            );
+            let invocation_id = InvocationId::generate(&invocation_target, None);
+            let span_context = ServiceInvocationSpanContext::start(&invocation_id, span_relation);
+
+            return Ok(CallEnrichmentResult {
+                invocation_id,
+                invocation_target,
+                completion_retention_time: None,
+                shared_concurrency_limit: None,
+                span_context,
+            });
+        }
+
         let meta = self
             .schemas
             .live_load()
@@ -78,6 +125,13 @@ where
                 )
             })?;
 
+        if !meta.is_caller_allowed(caller_invocation_target.service_name()) {
+            return Err(InvocationError::service_call_forbidden(
+                &request.service_name,
+                caller_invocation_target.service_name(),
+            ));
+        }
+
         let invocation_target = match meta.target_ty {
             InvocationTargetType::Service => {
                 InvocationTarget::service(request.service_name, request.handler_name)
@@ -123,6 +177,7 @@ where
             invocation_id,
             invocation_target,
             completion_retention_time: meta.compute_retention(false),
+            shared_concurrency_limit: meta.shared_concurrency_limit,
             span_context,
         })
     }
@@ -210,6 +265,7 @@ where
                             let_assert!(Entry::Call(InvokeEntry { request, .. }) = entry);
                             request
                         },
+                        current_invocation_target,
                         current_invocation_span_context.as_parent(),
                     )?;
 
@@ -233,6 +289,7 @@ where
                         let_assert!(Entry::OneWayCall(OneWayCallEntry { request, .. }) = entry);
                         request
                     },
+                    current_invocation_target,
                     current_invocation_span_context.as_linked(),
                 )?;
 
@@ -31,6 +31,7 @@ use restate_core::partitions::PartitionRouting;
 use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{Metadata, TaskKind};
 use restate_ingress_kafka::Service as IngressKafkaService;
+use restate_ingress_sqs::Service as IngressSqsService;
 use restate_invoker_impl::InvokerHandle as InvokerChannelServiceHandle;
 use restate_metadata_store::MetadataStoreClient;
 use restate_partition_store::{PartitionStore, PartitionStoreManager};
@@ -47,6 +48,7 @@ use restate_types::live::Live;
 use restate_types::protobuf::common::WorkerStatus;
 
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
+use crate::partition::snapshots::{SnapshotRepository, SnapshotRepositoryError};
 use crate::partition_processor_manager::PartitionProcessorManager;
 
 pub use self::error::*;
@@ -80,6 +82,9 @@ pub enum BuildError {
     ),
     #[code(unknown)]
     Invoker(#[from] restate_invoker_impl::BuildError),
+    #[error("failed creating the snapshot repository: {0}")]
+    #[code(unknown)]
+    SnapshotRepository(#[from] SnapshotRepositoryError),
 }
 
 #[derive(Debug, thiserror::Error, CodedError)]
@@ -98,8 +103,10 @@ pub struct Worker {
     storage_query_postgres: PostgresQueryService,
     datafusion_remote_scanner: RemoteQueryScannerServer,
     ingress_kafka: IngressKafkaService,
+    ingress_sqs: IngressSqsService,
     subscription_controller_handle: SubscriptionControllerHandle,
     partition_processor_manager: PartitionProcessorManager,
+    partition_store_manager: PartitionStoreManager,
 }
 
 impl Worker {
@@ -119,11 +126,13 @@ impl Worker {
 
         let config = updateable_config.pinned();
 
-        // ingress_kafka
+        // ingress_kafka and ingress_sqs
         let ingress_kafka = IngressKafkaService::new(bifrost.clone());
+        let ingress_sqs = IngressSqsService::new(bifrost.clone());
         let subscription_controller_handle = SubscriptionControllerHandle::new(
             config.ingress.clone(),
             ingress_kafka.create_command_sender(),
+            ingress_sqs.create_command_sender(),
         );
 
         let partition_store_manager = PartitionStoreManager::create(
@@ -136,6 +145,9 @@ impl Worker {
         )
         .await?;
 
+        let snapshot_repository =
+            SnapshotRepository::create_if_configured(&config.worker.snapshots)?;
+
         let partition_processor_manager = PartitionProcessorManager::new(
             health_status,
             updateable_config.clone(),
@@ -143,6 +155,7 @@ impl Worker {
             partition_store_manager.clone(),
             router_builder,
             bifrost,
+            snapshot_repository,
         );
 
         // handle RPCs
@@ -150,7 +163,11 @@ impl Worker {
 
         let remote_scanner_manager = RemoteScannerManager::new(
             create_remote_scanner_service(networking, router_builder),
-            create_partition_locator(partition_routing, metadata.clone()),
+            create_partition_locator(
+                partition_routing,
+                metadata.clone(),
+                Some(partition_store_manager.clone()),
+            ),
         );
         let schema = metadata.updateable_schema();
         let storage_query_context = QueryContext::create(
@@ -180,8 +197,10 @@ impl Worker {
             storage_query_postgres,
             datafusion_remote_scanner,
             ingress_kafka,
+            ingress_sqs,
             subscription_controller_handle,
             partition_processor_manager,
+            partition_store_manager,
         })
     }
 
@@ -197,6 +216,10 @@ impl Worker {
         self.partition_processor_manager.handle()
     }
 
+    pub fn partition_store_manager(&self) -> PartitionStoreManager {
+        self.partition_store_manager.clone()
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         // Postgres external server
         TaskCenter::spawn_child(
@@ -220,6 +243,14 @@ impl Worker {
                 .run(self.updateable_config.clone().map(|c| &c.ingress)),
         )?;
 
+        // SQS Ingress
+        TaskCenter::spawn_child(
+            TaskKind::SystemService,
+            "sqs-ingress",
+            self.ingress_sqs
+                .run(self.updateable_config.clone().map(|c| &c.ingress)),
+        )?;
+
         TaskCenter::spawn_child(
             TaskKind::SystemService,
             "partition-processor-manager",
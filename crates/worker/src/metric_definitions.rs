@@ -26,13 +26,24 @@ pub const PARTITION_LAST_APPLIED_LOG_LSN: &str = "restate.partition.last_applied
 pub const PARTITION_LAST_PERSISTED_LOG_LSN: &str = "restate.partition.last_persisted_lsn";
 pub const PARTITION_IS_EFFECTIVE_LEADER: &str = "restate.partition.is_effective_leader";
 pub const PARTITION_IS_ACTIVE: &str = "restate.partition.is_active";
+pub const PARTITION_LOG_TAIL_LSN: &str = "restate.partition.log_tail_lsn";
+pub const PARTITION_APPLY_LAG: &str = "restate.partition.apply_lag.seconds";
+pub const PARTITION_INBOXED_INVOCATIONS: &str = "restate.partition.inboxed_invocations";
+pub const PARTITION_INVOKER_QUEUE_DEPTH: &str = "restate.partition.invoker_queue_depth";
 
 pub const PP_APPLY_COMMAND_DURATION: &str = "restate.partition.apply_command_duration.seconds";
 pub const PP_APPLY_COMMAND_BATCH_SIZE: &str = "restate.partition.apply_command_batch_size";
+pub const PP_DECODE_RECORD_DURATION: &str = "restate.partition.decode_record_duration.seconds";
+pub const PP_COMMIT_BATCH_DURATION: &str = "restate.partition.commit_batch_duration.seconds";
 pub const PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION: &str =
     "restate.partition.handle_action_batch_duration.seconds";
 pub const PARTITION_HANDLE_INVOKER_EFFECT_COMMAND: &str =
     "restate.partition.handle_invoker_effect.seconds";
+pub const PARTITION_DEAD_LETTERED_RECORDS: &str = "restate.partition.dead_lettered_records.total";
+pub const PARTITION_INGRESS_ADMISSION_REJECTED: &str =
+    "restate.partition.ingress_admission_rejected.total";
+pub const PARTITION_EFFECT_DIGEST_DIVERGENCES: &str =
+    "restate.partition.effect_digest_divergences.total";
 
 pub const PARTITION_LABEL: &str = "partition";
 
@@ -47,6 +58,21 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of actuator operation outputs processed"
     );
+    describe_counter!(
+        PARTITION_DEAD_LETTERED_RECORDS,
+        Unit::Count,
+        "Number of log records moved to the dead-letter table because applying them failed repeatedly"
+    );
+    describe_counter!(
+        PARTITION_EFFECT_DIGEST_DIVERGENCES,
+        Unit::Count,
+        "Number of applied log records whose effect digest diverged from the recorded one"
+    );
+    describe_counter!(
+        PARTITION_INGRESS_ADMISSION_REJECTED,
+        Unit::Count,
+        "Number of new invocations rejected because the partition exceeded its ingress limits"
+    );
     describe_counter!(
         PARTITION_STORAGE_TX_CREATED,
         Unit::Count,
@@ -67,6 +93,16 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Size of the applied command batch"
     );
+    describe_histogram!(
+        PP_DECODE_RECORD_DURATION,
+        Unit::Seconds,
+        "Time spent decoding a single bifrost record on the read-ahead task"
+    );
+    describe_histogram!(
+        PP_COMMIT_BATCH_DURATION,
+        Unit::Seconds,
+        "Time spent committing the storage transaction of an applied command batch"
+    );
     describe_histogram!(
         PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION,
         Unit::Seconds,
@@ -124,4 +160,28 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Number of seconds since the last record was applied"
     );
+
+    describe_gauge!(
+        PARTITION_LOG_TAIL_LSN,
+        Unit::Count,
+        "Raw value of the last observed log tail LSN"
+    );
+
+    describe_gauge!(
+        PARTITION_APPLY_LAG,
+        Unit::Seconds,
+        "Age of the last applied record, zero if the partition applied all records up to the log tail"
+    );
+
+    describe_gauge!(
+        PARTITION_INBOXED_INVOCATIONS,
+        Unit::Count,
+        "Number of entries waiting in the inboxes of the partition"
+    );
+
+    describe_gauge!(
+        PARTITION_INVOKER_QUEUE_DEPTH,
+        Unit::Count,
+        "Number of invocations held by the invoker of the partition"
+    );
 }
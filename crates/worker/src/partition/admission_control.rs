@@ -0,0 +1,181 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Instant;
+
+use restate_types::config::IngressAdmissionOptions;
+
+/// Bounds the rate and the bytes of new invocations which the partition leader appends to its log.
+///
+/// The limits are passed in on every admission so that configuration changes take effect
+/// immediately.
+#[derive(Debug, Default)]
+pub(super) struct AdmissionController {
+    invocations: TokenBucket,
+    bytes: TokenBucket,
+}
+
+impl AdmissionController {
+    /// Returns true if an invocation with a payload of `payload_size` bytes may be appended to
+    /// the log. Admitted invocations consume their tokens from both buckets.
+    pub fn try_admit(
+        &mut self,
+        options: &IngressAdmissionOptions,
+        payload_size: usize,
+        now: Instant,
+    ) -> bool {
+        self.try_admit_batch(options, 1, payload_size, now)
+    }
+
+    /// Like [`Self::try_admit`], for a batch of `invocations` with `payload_size` bytes in total.
+    /// The batch is admitted or rejected as a whole.
+    pub fn try_admit_batch(
+        &mut self,
+        options: &IngressAdmissionOptions,
+        invocations: usize,
+        payload_size: usize,
+        now: Instant,
+    ) -> bool {
+        let invocations_limit = options
+            .invocations_per_second
+            .map(|limit| f64::from(limit.get()));
+        let bytes_limit = options.bytes_per_second.map(|limit| limit.get() as f64);
+
+        if !self.invocations.has_capacity(invocations_limit, now)
+            || !self.bytes.has_capacity(bytes_limit, now)
+        {
+            return false;
+        }
+
+        self.invocations.consume(invocations as f64);
+        self.bytes.consume(payload_size as f64);
+        true
+    }
+}
+
+/// Token bucket holding up to one second worth of its rate. An admission may drive the bucket
+/// into debt so that a single request larger than the capacity can still make progress. Following
+/// admissions are delayed until the debt has been paid off.
+#[derive(Debug, Default)]
+struct TokenBucket {
+    /// The rate the bucket was last refilled with. `None` if the bucket is unlimited.
+    rate: Option<f64>,
+    tokens: f64,
+    last_refill: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn has_capacity(&mut self, rate: Option<f64>, now: Instant) -> bool {
+        let Some(rate) = rate else {
+            self.rate = None;
+            return true;
+        };
+
+        if self.rate != Some(rate) {
+            // the limit was enabled or changed; start over with a full bucket
+            self.rate = Some(rate);
+            self.tokens = rate;
+        } else if let Some(last_refill) = self.last_refill {
+            let elapsed = now.saturating_duration_since(last_refill).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * rate).min(rate);
+        }
+        self.last_refill = Some(now);
+
+        self.tokens > 0.0
+    }
+
+    fn consume(&mut self, tokens: f64) {
+        if self.rate.is_some() {
+            self.tokens -= tokens;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::{NonZeroU32, NonZeroUsize};
+    use std::time::Duration;
+
+    #[test]
+    fn unlimited_admits_everything() {
+        let mut controller = AdmissionController::default();
+        let options = IngressAdmissionOptions::default();
+        let now = Instant::now();
+
+        for _ in 0..1000 {
+            assert!(controller.try_admit(&options, 1024 * 1024, now));
+        }
+    }
+
+    #[test]
+    fn limits_invocations_per_second() {
+        let mut controller = AdmissionController::default();
+        let options = IngressAdmissionOptions {
+            invocations_per_second: NonZeroU32::new(10),
+            ..Default::default()
+        };
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(controller.try_admit(&options, 0, now));
+        }
+        assert!(!controller.try_admit(&options, 0, now));
+
+        // refills proportionally to the elapsed time
+        let now = now + Duration::from_millis(100);
+        assert!(controller.try_admit(&options, 0, now));
+        assert!(!controller.try_admit(&options, 0, now));
+    }
+
+    #[test]
+    fn limits_bytes_per_second() {
+        let mut controller = AdmissionController::default();
+        let options = IngressAdmissionOptions {
+            bytes_per_second: NonZeroUsize::new(1000),
+            ..Default::default()
+        };
+        let now = Instant::now();
+
+        // a payload larger than the capacity is admitted, but puts the bucket into debt
+        assert!(controller.try_admit(&options, 1500, now));
+        assert!(!controller.try_admit(&options, 1, now + Duration::from_millis(500)));
+        assert!(controller.try_admit(&options, 1, now + Duration::from_millis(501)));
+    }
+
+    #[test]
+    fn batches_consume_one_token_per_invocation() {
+        let mut controller = AdmissionController::default();
+        let options = IngressAdmissionOptions {
+            invocations_per_second: NonZeroU32::new(10),
+            ..Default::default()
+        };
+        let now = Instant::now();
+
+        assert!(controller.try_admit_batch(&options, 10, 0, now));
+        assert!(!controller.try_admit(&options, 0, now));
+        assert!(controller.try_admit(&options, 0, now + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn disabling_limits_admits_again() {
+        let mut controller = AdmissionController::default();
+        let limited = IngressAdmissionOptions {
+            invocations_per_second: NonZeroU32::new(1),
+            ..Default::default()
+        };
+        let now = Instant::now();
+
+        assert!(controller.try_admit(&limited, 0, now));
+        assert!(!controller.try_admit(&limited, 0, now));
+        assert!(controller.try_admit(&IngressAdmissionOptions::default(), 0, now));
+    }
+}
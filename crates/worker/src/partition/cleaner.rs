@@ -25,6 +25,7 @@ use restate_storage_api::invocation_status_table::{
 use restate_types::identifiers::WithPartitionKey;
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
 use restate_types::invocation::PurgeInvocationRequest;
+use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_wal_protocol::{
     append_envelope_to_bifrost, Command, Destination, Envelope, Header, Source,
 };
@@ -110,6 +111,8 @@ where
     ) -> anyhow::Result<()> {
         debug!("Executing completed invocations cleanup");
 
+        let schema = Metadata::with_current(|m| m.schema());
+
         let invocations_stream = storage.all_invocation_statuses(partition_key_range);
         tokio::pin!(invocations_stream);
 
@@ -132,8 +135,23 @@ where
                 //  thus it will be cleaned up with the old timer.
                 continue;
             };
-            let Some(expiration_time) = SystemTime::from(completed_time)
-                .checked_add(completed_invocation.completion_retention_duration)
+
+            // Enforce the current retention policy of the handler, which might have been
+            // modified after the invocation completed.
+            let completion_retention_duration = schema
+                .resolve_latest_invocation_target(
+                    completed_invocation.invocation_target.service_name(),
+                    completed_invocation.invocation_target.handler_name(),
+                )
+                .map(|target| {
+                    target
+                        .compute_retention(completed_invocation.idempotency_key.is_some())
+                        .unwrap_or_default()
+                })
+                .unwrap_or(completed_invocation.completion_retention_duration);
+
+            let Some(expiration_time) =
+                SystemTime::from(completed_time).checked_add(completion_retention_duration)
             else {
                 // If sum overflow, then the cleanup time lies far enough in the future
                 continue;
@@ -142,16 +160,16 @@ where
             if SystemTime::now() >= expiration_time {
                 append_envelope_to_bifrost(
                     bifrost,
-                    Arc::new(Envelope {
-                        header: Header {
+                    Arc::new(Envelope::new(
+                        Header {
                             source: bifrost_envelope_source.clone(),
                             dest: Destination::Processor {
                                 partition_key: invocation_id.partition_key(),
                                 dedup: None,
                             },
                         },
-                        command: Command::PurgeInvocation(PurgeInvocationRequest { invocation_id }),
-                    }),
+                        Command::PurgeInvocation(PurgeInvocationRequest { invocation_id }),
+                    )),
                 )
                 .await
                 .context("Cannot append to bifrost")?;
@@ -170,10 +188,10 @@ mod tests {
     use googletest::prelude::*;
     use restate_core::{Metadata, TaskCenter, TaskKind, TestCoreEnvBuilder};
     use restate_storage_api::invocation_status_table::{
-        CompletedInvocation, InFlightInvocationMetadata, InvocationStatus,
+        CompletedInvocation, InFlightInvocationMetadata, InvocationStatus, InvocationStatusView,
+        InvokedInvocation,
     };
     use restate_types::identifiers::{InvocationId, InvocationUuid};
-    use restate_types::invocation::InvocationTarget;
     use restate_types::partition_table::{FindPartition, PartitionTable};
     use restate_types::Version;
     use std::future::Future;
@@ -192,10 +210,19 @@ mod tests {
             std::future::pending()
         }
 
-        fn all_invoked_invocations(
+        fn get_invocation_status_view(
             &mut self,
-        ) -> impl Stream<Item = restate_storage_api::Result<(InvocationId, InvocationTarget)>> + Send
+            _: &InvocationId,
+        ) -> impl Future<Output = restate_storage_api::Result<InvocationStatusView>> + Send
         {
+            todo!();
+            #[allow(unreachable_code)]
+            std::future::pending()
+        }
+
+        fn all_invoked_invocations(
+            &mut self,
+        ) -> impl Stream<Item = restate_storage_api::Result<InvokedInvocation>> + Send {
             todo!();
             #[allow(unreachable_code)]
             stream::empty()
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::global::BoxedSpan;
+use opentelemetry::trace::Span;
+use opentelemetry::KeyValue;
+
+use restate_storage_api::invocation_status_table::ReadOnlyInvocationStatusTable;
+use restate_storage_api::Result as StorageResult;
+use restate_tracing_instrumentation as instrumentation;
+use restate_types::identifiers::InvocationId;
+use restate_types::time::NanosSinceEpoch;
+
+/// Spans of the commands applied in the current batch.
+///
+/// Every span is a child of the span of the invocation the command relates to. It starts when the
+/// command was appended to the log and ends once the storage transaction of the batch has been
+/// committed. The `applying` event marks the point in time at which the partition processor started
+/// applying the command, which separates the time the command was queued from the time it took to
+/// apply it.
+#[derive(Default)]
+pub(super) struct CommandSpans {
+    spans: Vec<BoxedSpan>,
+}
+
+impl CommandSpans {
+    /// Starts the span of an applied command. Commands of invocations whose status no longer
+    /// carries a span context, e.g. because they have completed, are not traced.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn start<S: ReadOnlyInvocationStatusTable>(
+        &mut self,
+        storage: &mut S,
+        invocation_id: InvocationId,
+        command_name: &'static str,
+        created_at: NanosSinceEpoch,
+        apply_started_at: SystemTime,
+        apply_duration: Duration,
+        num_effects: usize,
+    ) -> StorageResult<()> {
+        let invocation_status = storage.get_invocation_status(&invocation_id).await?;
+        let Some(span_context) = invocation_status.span_context() else {
+            return Ok(());
+        };
+
+        let mut span = instrumentation::invocation_span!(
+            level = ::tracing::Level::INFO,
+            relation = span_context.as_parent(),
+            id = invocation_id,
+            name = format!("apply-command: {command_name}"),
+            tags = (
+                restate.state_machine.command = command_name,
+                restate.state_machine.effects = num_effects as i64,
+                restate.state_machine.apply_duration_ms = apply_duration.as_millis() as i64
+            ),
+            fields = (with_start_time =
+                SystemTime::UNIX_EPOCH + Duration::from_nanos(created_at.as_u64()))
+        );
+        span.add_event_with_timestamp("applying", apply_started_at, Vec::default());
+
+        self.spans.push(span);
+        Ok(())
+    }
+
+    /// Ends the spans of all commands of the batch whose storage transaction took `commit_duration`
+    /// to commit.
+    pub(super) fn end_committed(&mut self, commit_duration: Duration) {
+        for mut span in self.spans.drain(..) {
+            span.set_attribute(KeyValue::new(
+                "restate.state_machine.commit_duration_ms",
+                commit_duration.as_millis() as i64,
+            ));
+            span.end();
+        }
+    }
+}
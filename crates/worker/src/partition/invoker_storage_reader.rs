@@ -9,15 +9,16 @@
 // by the Apache License, Version 2.0.
 
 use bytes::Bytes;
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{stream, TryStreamExt};
 use restate_invoker_api::{EagerState, JournalMetadata};
 use restate_storage_api::invocation_status_table::{
-    InvocationStatus, ReadOnlyInvocationStatusTable,
+    InvocationStatus, JournalVersion, ReadOnlyInvocationStatusTable,
 };
 use restate_storage_api::journal_table::{JournalEntry, ReadOnlyJournalTable};
+use restate_storage_api::journal_table_v2::ReadOnlyJournalTableV2;
 use restate_storage_api::state_table::ReadOnlyStateTable;
-use restate_types::identifiers::InvocationId;
 use restate_types::identifiers::ServiceId;
+use restate_types::identifiers::{EntryIndex, InvocationId};
 use restate_types::journal::raw::PlainRawEntry;
 use std::vec::IntoIter;
 
@@ -25,6 +26,10 @@ use std::vec::IntoIter;
 pub enum InvokerStorageReaderError {
     #[error("not invoked")]
     NotInvoked,
+    #[error("journal entry {0} not found")]
+    MissingJournalEntry(EntryIndex),
+    #[error("expected journal entry {0} but found a completion")]
+    UnexpectedCompletion(EntryIndex),
     #[error(transparent)]
     Storage(#[from] restate_storage_api::StorageError),
 }
@@ -38,9 +43,58 @@ impl<Storage> InvokerStorageReader<Storage> {
     }
 }
 
+impl<Storage> InvokerStorageReader<Storage>
+where
+    Storage: ReadOnlyJournalTable + ReadOnlyJournalTableV2,
+{
+    async fn read_journal_entry(
+        &mut self,
+        journal_version: JournalVersion,
+        invocation_id: &InvocationId,
+        journal_index: EntryIndex,
+    ) -> Result<Option<JournalEntry>, InvokerStorageReaderError> {
+        Ok(match journal_version {
+            JournalVersion::V1 => {
+                ReadOnlyJournalTable::get_journal_entry(&mut self.0, invocation_id, journal_index)
+                    .await?
+            }
+            JournalVersion::V2 => {
+                ReadOnlyJournalTableV2::get_journal_entry(&mut self.0, invocation_id, journal_index)
+                    .await?
+                    .map(|stored_entry| stored_entry.entry)
+            }
+        })
+    }
+
+    async fn read_full_journal(
+        &mut self,
+        journal_version: JournalVersion,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> Result<Vec<JournalEntry>, InvokerStorageReaderError> {
+        // TODO: Update invoker to maintain transaction while reading the journal stream: See https://github.com/restatedev/restate/issues/275
+        // collecting the stream because we cannot keep the transaction open
+        Ok(match journal_version {
+            JournalVersion::V1 => {
+                ReadOnlyJournalTable::get_journal(&mut self.0, invocation_id, journal_length)
+                    .map_ok(|(_, journal_entry)| journal_entry)
+                    .try_collect()
+                    .await?
+            }
+            JournalVersion::V2 => {
+                ReadOnlyJournalTableV2::get_journal(&mut self.0, invocation_id, journal_length)
+                    .map_ok(|(_, stored_entry)| stored_entry.entry)
+                    .try_collect()
+                    .await?
+            }
+        })
+    }
+}
+
 impl<Storage> restate_invoker_api::JournalReader for InvokerStorageReader<Storage>
 where
-    for<'a> Storage: ReadOnlyJournalTable + ReadOnlyInvocationStatusTable + Send + 'a,
+    for<'a> Storage:
+        ReadOnlyJournalTable + ReadOnlyJournalTableV2 + ReadOnlyInvocationStatusTable + Send + 'a,
 {
     type JournalStream = stream::Iter<IntoIter<PlainRawEntry>>;
     type Error = InvokerStorageReaderError;
@@ -48,34 +102,57 @@ where
     async fn read_journal<'a>(
         &'a mut self,
         invocation_id: &'a InvocationId,
+        from_index: EntryIndex,
     ) -> Result<(JournalMetadata, Self::JournalStream), Self::Error> {
         let invocation_status = self.0.get_invocation_status(invocation_id).await?;
 
         if let InvocationStatus::Invoked(invoked_status) = invocation_status {
+            let journal_version = invoked_status.journal_metadata.version;
             let journal_metadata = JournalMetadata::new(
                 invoked_status.journal_metadata.length,
                 invoked_status.journal_metadata.span_context,
                 invoked_status.pinned_deployment,
                 // SAFETY: this value is used by the invoker, it's ok if it's not in sync
                 unsafe { invoked_status.timestamps.modification_time() },
+                invoked_status.dry_run,
             );
+            if from_index > 0 {
+                // The invoker already has the prefix of the journal, only look up the tail
+                let mut journal =
+                    Vec::with_capacity(journal_metadata.length.saturating_sub(from_index) as usize);
+                for journal_index in from_index..journal_metadata.length {
+                    match self
+                        .read_journal_entry(journal_version, invocation_id, journal_index)
+                        .await?
+                    {
+                        Some(JournalEntry::Entry(entry)) => journal.push(entry.erase_enrichment()),
+                        Some(JournalEntry::Completion(_)) => {
+                            return Err(InvokerStorageReaderError::UnexpectedCompletion(
+                                journal_index,
+                            ))
+                        }
+                        None => {
+                            return Err(InvokerStorageReaderError::MissingJournalEntry(
+                                journal_index,
+                            ))
+                        }
+                    }
+                }
+                return Ok((journal_metadata, stream::iter(journal)));
+            }
+
             let journal_stream = self
-                .0
-                .get_journal(invocation_id, journal_metadata.length)
-                .map(|entry| {
-                    entry
-                        .map_err(InvokerStorageReaderError::Storage)
-                        .map(|(_, journal_entry)| match journal_entry {
-                            JournalEntry::Entry(entry) => entry.erase_enrichment(),
-                            JournalEntry::Completion(_) => {
-                                panic!("should only read entries when reading the journal")
-                            }
-                        })
+                .read_full_journal(journal_version, invocation_id, journal_metadata.length)
+                .await?
+                .into_iter()
+                .zip(0..)
+                .map(|(journal_entry, journal_index)| match journal_entry {
+                    JournalEntry::Entry(entry) => Ok(entry.erase_enrichment()),
+                    JournalEntry::Completion(_) => Err(
+                        InvokerStorageReaderError::UnexpectedCompletion(journal_index),
+                    ),
                 })
-                // TODO: Update invoker to maintain transaction while reading the journal stream: See https://github.com/restatedev/restate/issues/275
-                // collecting the stream because we cannot keep the transaction open
-                .try_collect::<Vec<_>>()
-                .await?;
+                .collect::<Result<Vec<_>, _>>()?;
 
             Ok((journal_metadata, stream::iter(journal_stream)))
         } else {
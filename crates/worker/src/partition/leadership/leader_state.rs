@@ -12,6 +12,7 @@ use crate::metric_definitions::{PARTITION_ACTUATOR_HANDLED, PARTITION_HANDLE_LEA
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::self_proposer::SelfProposer;
 use crate::partition::leadership::{ActionEffect, Error, TimerService};
+use crate::partition::prepared_messages::PreparedMessagesHandle;
 use crate::partition::shuffle::HintSender;
 use crate::partition::state_machine::Action;
 use crate::partition::{respond_to_rpc, shuffle};
@@ -23,6 +24,7 @@ use restate_bifrost::CommitToken;
 use restate_core::network::Reciprocal;
 use restate_core::{TaskCenter, TaskId};
 use restate_partition_store::PartitionStore;
+use restate_storage_api::timer_table::Timer;
 use restate_types::identifiers::{
     InvocationId, LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
     WithPartitionKey,
@@ -32,7 +34,7 @@ use restate_types::net::partition_processor::{
     SubmittedInvocationNotification,
 };
 use restate_types::time::MillisSinceEpoch;
-use restate_wal_protocol::timer::TimerKeyValue;
+use restate_wal_protocol::timer::{FiredScheduleTimer, TimerKeyValue};
 use restate_wal_protocol::Command;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
@@ -68,6 +70,8 @@ pub struct LeaderState {
     shuffle_stream: ReceiverStream<shuffle::OutboxTruncation>,
     pub pending_cleanup_timers_to_schedule: VecDeque<(InvocationId, Duration)>,
     cleaner_task_id: TaskId,
+    prepared_messages: PreparedMessagesHandle,
+    reconciler_task_id: TaskId,
 }
 
 impl LeaderState {
@@ -78,7 +82,9 @@ impl LeaderState {
         own_partition_key: PartitionKey,
         shuffle_task_id: TaskId,
         cleaner_task_id: TaskId,
+        reconciler_task_id: TaskId,
         shuffle_hint_tx: HintSender,
+        prepared_messages: PreparedMessagesHandle,
         timer_service: TimerService,
         self_proposer: SelfProposer,
         invoker_rx: tokio::sync::mpsc::Receiver<restate_invoker_api::Effect>,
@@ -91,7 +97,9 @@ impl LeaderState {
             action_effects_counter: counter!(PARTITION_ACTUATOR_HANDLED),
             shuffle_task_id,
             cleaner_task_id,
+            reconciler_task_id,
             shuffle_hint_tx,
+            prepared_messages,
             timer_service: Box::pin(timer_service),
             self_proposer,
             awaiting_rpc_actions: Default::default(),
@@ -164,13 +172,16 @@ impl LeaderState {
             OptionFuture::from(TaskCenter::current().cancel_task(self.shuffle_task_id));
         let cleaner_handle =
             OptionFuture::from(TaskCenter::current().cancel_task(self.cleaner_task_id));
+        let reconciler_handle =
+            OptionFuture::from(TaskCenter::current().cancel_task(self.reconciler_task_id));
 
         // It's ok to not check the abort_result because either it succeeded or the invoker
         // is not running. If the invoker is not running, and we are not shutting down, then
         // we will fail the next time we try to invoke.
-        let (shuffle_result, cleaner_result, _abort_result) = tokio::join!(
+        let (shuffle_result, cleaner_result, reconciler_result, _abort_result) = tokio::join!(
             shuffle_handle,
             cleaner_handle,
+            reconciler_handle,
             invoker_handle.abort_all_partition((self.partition_id, self.leader_epoch)),
         );
 
@@ -180,6 +191,9 @@ impl LeaderState {
         if let Some(cleaner_result) = cleaner_result {
             cleaner_result.expect("graceful termination of cleaner task");
         }
+        if let Some(reconciler_result) = reconciler_result {
+            reconciler_result.expect("graceful termination of prepared message reconciler task");
+        }
 
         // Reply to all RPCs with not a leader
         for (request_id, reciprocal) in self.awaiting_rpc_actions.drain() {
@@ -225,9 +239,17 @@ impl LeaderState {
                         .await?;
                 }
                 ActionEffect::Timer(timer) => {
-                    self.self_proposer
-                        .propose(timer.invocation_id().partition_key(), Command::Timer(timer))
-                        .await?;
+                    let partition_key = timer.value().partition_key();
+                    let command = if matches!(timer.value(), Timer::FireSchedule(_)) {
+                        // Whether the occurrence was missed depends on the time it fired at
+                        Command::FireSchedule(FiredScheduleTimer {
+                            timer,
+                            fired_at: MillisSinceEpoch::now(),
+                        })
+                    } else {
+                        Command::Timer(timer)
+                    };
+                    self.self_proposer.propose(partition_key, command).await?;
                 }
                 ActionEffect::ScheduleCleanupTimer(invocation_id, duration) => {
                     self.self_proposer
@@ -306,6 +328,33 @@ impl LeaderState {
         }
     }
 
+    /// Self proposes the commands in order, responding once the last one is committed. The
+    /// background appender batches the proposed commands into as few log appends as possible.
+    pub async fn self_propose_batch_and_respond_asynchronously(
+        &mut self,
+        commands: Vec<(PartitionKey, Command)>,
+        reciprocal: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    ) {
+        let mut commands = commands.into_iter();
+        let Some((last_partition_key, last_cmd)) = commands.next_back() else {
+            respond_to_rpc(reciprocal.prepare(Ok(PartitionProcessorRpcResponse::Appended)));
+            return;
+        };
+
+        for (partition_key, cmd) in commands {
+            if let Err(e) = self.self_proposer.propose(partition_key, cmd).await {
+                respond_to_rpc(
+                    reciprocal.prepare(Err(PartitionProcessorRpcError::Internal(e.to_string()))),
+                );
+                return;
+            }
+        }
+
+        // Records are committed in order, so all the commands are committed with the last one
+        self.self_propose_and_respond_asynchronously(last_partition_key, last_cmd, reciprocal)
+            .await;
+    }
+
     pub async fn handle_actions(
         &mut self,
         invoker_tx: &mut impl restate_invoker_api::InvokerHandle<InvokerStorageReader<PartitionStore>>,
@@ -332,12 +381,16 @@ impl LeaderState {
             Action::Invoke {
                 invocation_id,
                 invocation_target,
+                invocation_epoch,
+                execution_deadline,
                 invoke_input_journal,
             } => invoker_tx
                 .invoke(
                     partition_leader_epoch,
                     invocation_id,
                     invocation_target,
+                    invocation_epoch,
+                    execution_deadline,
                     invoke_input_journal,
                 )
                 .await
@@ -417,6 +470,11 @@ impl LeaderState {
                 self.pending_cleanup_timers_to_schedule
                     .push_back((invocation_id, retention));
             }
+            Action::DeliverPreparedMessages => self.prepared_messages.deliver(),
+            Action::AcknowledgePreparedMessage {
+                source,
+                sequence_number,
+            } => self.prepared_messages.acknowledge(source, sequence_number),
         }
 
         Ok(())
@@ -28,7 +28,9 @@ use restate_errors::NotRunningError;
 use restate_invoker_api::InvokeInputJournal;
 use restate_partition_store::PartitionStore;
 use restate_storage_api::deduplication_table::EpochSequenceNumber;
-use restate_storage_api::invocation_status_table::ReadOnlyInvocationStatusTable;
+use restate_storage_api::invocation_status_table::{
+    InvokedInvocation, ReadOnlyInvocationStatusTable,
+};
 use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
 use restate_storage_api::timer_table::{TimerKey, TimerTable};
 use restate_timer::TokioClock;
@@ -48,6 +50,7 @@ use crate::partition::cleaner::Cleaner;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::leader_state::LeaderState;
 use crate::partition::leadership::self_proposer::SelfProposer;
+use crate::partition::prepared_messages::PreparedMessageReconciler;
 use crate::partition::shuffle::{OutboxReaderError, Shuffle, ShuffleMetadata};
 use crate::partition::state_machine::Action;
 use crate::partition::{respond_to_rpc, shuffle};
@@ -247,6 +250,28 @@ where
         Ok(())
     }
 
+    /// Proposes the command to the partition's log if this processor runs for leadership, e.g.
+    /// while it cannot apply its own leadership announcement. Returns whether the command was
+    /// proposed.
+    pub async fn propose_as_candidate(&mut self, cmd: Command) -> Result<bool, Error> {
+        let State::Candidate { self_proposer, .. } = &mut self.state else {
+            return Ok(false);
+        };
+
+        self_proposer
+            .as_mut()
+            .expect("must be present")
+            .propose(
+                *self
+                    .partition_processor_metadata
+                    .partition_key_range
+                    .start(),
+                cmd,
+            )
+            .await?;
+        Ok(true)
+    }
+
     pub async fn step_down(&mut self) {
         debug!("Stepping down. Being a role model for Joe.");
         self.become_follower().await
@@ -360,6 +385,21 @@ where
             let cleaner_task_id =
                 TaskCenter::spawn_child(TaskKind::Cleaner, "cleaner", cleaner.run())?;
 
+            let reconciler = PreparedMessageReconciler::new(
+                self.partition_processor_metadata.partition_id,
+                *leader_epoch,
+                partition_store.clone(),
+                self.bifrost.clone(),
+                self.channel_size,
+            );
+            let prepared_messages = reconciler.create_handle();
+
+            let reconciler_task_id = TaskCenter::spawn_child(
+                TaskKind::PreparedMessageReconciler,
+                "prepared-message-reconciler",
+                reconciler.run(),
+            )?;
+
             self.state = State::Leader(LeaderState::new(
                 self.partition_processor_metadata.partition_id,
                 *leader_epoch,
@@ -369,7 +409,9 @@ where
                     .start(),
                 shuffle_task_id,
                 cleaner_task_id,
+                reconciler_task_id,
                 shuffle_hint_tx,
+                prepared_messages,
                 timer_service,
                 self_proposer.take().expect("must be present"),
                 invoker_rx,
@@ -406,13 +448,20 @@ where
             tokio::pin!(invoked_invocations);
 
             let mut count = 0;
-            while let Some(invocation_id_and_target) = invoked_invocations.next().await {
-                let (invocation_id, invocation_target) = invocation_id_and_target?;
+            while let Some(invoked_invocation) = invoked_invocations.next().await {
+                let InvokedInvocation {
+                    invocation_id,
+                    invocation_target,
+                    invocation_epoch,
+                    execution_deadline,
+                } = invoked_invocation?;
                 invoker_handle
                     .invoke(
                         partition_leader_epoch,
                         invocation_id,
                         invocation_target,
+                        invocation_epoch,
+                        execution_deadline,
                         InvokeInputJournal::NoCachedJournal,
                     )
                     .await
@@ -537,6 +586,27 @@ where
             }
         }
     }
+
+    /// Self propose a batch of commands to this partition, and register the reciprocal to
+    /// respond asynchronously once all of them are appended.
+    pub async fn self_propose_batch_and_respond_asynchronously(
+        &mut self,
+        commands: Vec<(PartitionKey, Command)>,
+        reciprocal: Reciprocal<Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>>,
+    ) {
+        match &mut self.state {
+            State::Follower | State::Candidate { .. } => respond_to_rpc(reciprocal.prepare(Err(
+                PartitionProcessorRpcError::NotLeader(
+                    self.partition_processor_metadata.partition_id,
+                ),
+            ))),
+            State::Leader(leader_state) => {
+                leader_state
+                    .self_propose_batch_and_respond_asynchronously(commands, reciprocal)
+                    .await;
+            }
+        }
+    }
 }
 #[derive(Debug, derive_more::From)]
 struct TimerReader(PartitionStore);
@@ -11,38 +11,39 @@
 use std::fmt::Debug;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Context;
 use assert2::let_assert;
 use futures::{FutureExt, Stream, StreamExt, TryStreamExt as _};
-use metrics::histogram;
+use metrics::{counter, histogram};
 use tokio::sync::{mpsc, watch};
 use tokio::time::MissedTickBehavior;
 use tracing::{debug, error, info, instrument, trace, warn, Span};
 
 use restate_bifrost::Bifrost;
 use restate_core::network::{HasConnection, Incoming, Outgoing};
-use restate_core::{cancellation_watcher, TaskCenter, TaskKind};
-use restate_partition_store::{PartitionStore, PartitionStoreTransaction};
+use restate_core::{cancellation_watcher, Metadata, TaskCenter, TaskKind};
+use restate_invoker_api::StatusHandle;
+use restate_invoker_impl::ChannelStatusReader;
+use restate_partition_store::{PartitionStore, PartitionStoreManager, PartitionStoreTransaction};
+use restate_storage_api::dead_letter_table::{DeadLetter, DeadLetterDecision, DeadLetterTable};
 use restate_storage_api::deduplication_table::{
     DedupInformation, DedupSequenceNumber, DeduplicationTable, ProducerId,
     ReadOnlyDeduplicationTable,
 };
-use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
-use restate_storage_api::idempotency_table::ReadOnlyIdempotencyTable;
+use restate_storage_api::fsm_table::{ApplyFailure, FsmTable, PartitionSplit, ReadOnlyFsmTable};
 use restate_storage_api::invocation_status_table::{
-    InvocationStatus, ReadOnlyInvocationStatusTable,
+    InvocationStatus, InvocationStatusKind, InvocationStatusView, JournalVersion,
+    ReadOnlyInvocationStatusTable,
 };
 use restate_storage_api::outbox_table::ReadOnlyOutboxTable;
-use restate_storage_api::service_status_table::{
-    ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus,
-};
 use restate_storage_api::{StorageError, Transaction};
 use restate_types::cluster::cluster_state::{PartitionProcessorStatus, ReplayStatus, RunMode};
-use restate_types::config::WorkerOptions;
+use restate_types::config::{Configuration, EffectDigestMode, WorkerOptions};
 use restate_types::identifiers::{
-    LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithPartitionKey,
+    LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, WithInvocationId,
+    WithPartitionKey,
 };
 use restate_types::invocation;
 use restate_types::invocation::{
@@ -55,25 +56,34 @@ use restate_types::logs::MatchKeyQuery;
 use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
 use restate_types::net::partition_processor::{
     AppendInvocationReplyOn, GetInvocationOutputResponseMode, IngressResponseResult,
-    InvocationOutput, PartitionProcessorRpcError, PartitionProcessorRpcRequest,
-    PartitionProcessorRpcRequestInner, PartitionProcessorRpcResponse,
+    InvocationOutput, InvocationProgress, InvocationProgressStatus, PartitionProcessorRpcError,
+    PartitionProcessorRpcRequest, PartitionProcessorRpcRequestInner, PartitionProcessorRpcResponse,
 };
-use restate_types::time::MillisSinceEpoch;
-use restate_wal_protocol::control::AnnounceLeader;
+use restate_types::nodes_config::ClusterVersion;
+use restate_types::time::{MillisSinceEpoch, NanosSinceEpoch};
+use restate_wal_protocol::control::{AnnounceLeader, SplitPartition};
 use restate_wal_protocol::{Command, Destination, Envelope, Header, Source};
 
 use crate::metric_definitions::{
-    PARTITION_LABEL, PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION, PP_APPLY_COMMAND_BATCH_SIZE,
-    PP_APPLY_COMMAND_DURATION,
+    PARTITION_DEAD_LETTERED_RECORDS, PARTITION_EFFECT_DIGEST_DIVERGENCES,
+    PARTITION_INGRESS_ADMISSION_REJECTED, PARTITION_LABEL,
+    PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION, PP_APPLY_COMMAND_BATCH_SIZE,
+    PP_APPLY_COMMAND_DURATION, PP_COMMIT_BATCH_DURATION, PP_DECODE_RECORD_DURATION,
 };
+use crate::partition::admission_control::AdmissionController;
+use crate::partition::command_tracing::CommandSpans;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::{LeadershipState, PartitionProcessorMetadata};
-use crate::partition::state_machine::{ActionCollector, StateMachine};
+use crate::partition::state_machine::{resolve_invocation_query, ActionCollector, StateMachine};
 
+mod admission_control;
 mod cleaner;
+mod command_tracing;
 pub mod invoker_storage_reader;
 mod leadership;
+mod prepared_messages;
 pub mod shuffle;
+pub mod snapshots;
 mod state_machine;
 pub mod types;
 
@@ -83,6 +93,27 @@ pub enum PartitionProcessorControlCommand {
     StepDown,
 }
 
+/// Maximum number of completed invocation statuses archived per cleanup interval.
+const ARCHIVE_BATCH_SIZE: usize = 1000;
+
+/// Interval at which the log tail, inbox size and invoker queue depth of the status are refreshed.
+const STATUS_DETAILS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum number of stored values rewritten by each pending migration per cleanup interval.
+const MIGRATION_BATCH_SIZE: usize = 1000;
+
+/// Interval at which a partition processor waiting for the decision to dead-letter a failing
+/// record looks for the decision in the log.
+const DEAD_LETTER_DECISION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The records following the last applied LSN of the partition processor were trimmed from its
+/// log. The partition processor can only continue from a snapshot which covers the trim gap.
+#[derive(Debug, thiserror::Error)]
+#[error("encountered a trim gap in the log up to lsn {trim_gap_end}")]
+pub struct TrimGapEncountered {
+    pub trim_gap_end: Lsn,
+}
+
 #[derive(Debug)]
 pub(super) struct PartitionProcessorBuilder<InvokerInputSender> {
     pub partition_id: PartitionId,
@@ -90,12 +121,15 @@ pub(super) struct PartitionProcessorBuilder<InvokerInputSender> {
 
     num_timers_in_memory_limit: Option<usize>,
     disable_idempotency_table: bool,
+    journal_version: JournalVersion,
     cleanup_interval: Duration,
+    archive_completed_invocations_after: Option<Duration>,
     channel_size: usize,
     max_command_batch_size: usize,
 
     status: PartitionProcessorStatus,
     invoker_tx: InvokerInputSender,
+    invoker_status_reader: ChannelStatusReader,
     control_rx: mpsc::Receiver<PartitionProcessorControlCommand>,
     rpc_rx: mpsc::Receiver<Incoming<PartitionProcessorRpcRequest>>,
     status_watch_tx: watch::Sender<PartitionProcessorStatus>,
@@ -116,6 +150,7 @@ where
         rpc_rx: mpsc::Receiver<Incoming<PartitionProcessorRpcRequest>>,
         status_watch_tx: watch::Sender<PartitionProcessorStatus>,
         invoker_tx: InvokerInputSender,
+        invoker_status_reader: ChannelStatusReader,
     ) -> Self {
         Self {
             partition_id,
@@ -123,10 +158,17 @@ where
             status,
             num_timers_in_memory_limit: options.num_timers_in_memory_limit(),
             disable_idempotency_table: options.experimental_feature_disable_idempotency_table(),
+            journal_version: if options.experimental_feature_journal_table_v2() {
+                JournalVersion::V2
+            } else {
+                JournalVersion::V1
+            },
             cleanup_interval: options.cleanup_interval(),
+            archive_completed_invocations_after: options.archive_completed_invocations_after(),
             channel_size: options.internal_queue_length(),
             max_command_batch_size: options.max_command_batch_size(),
             invoker_tx,
+            invoker_status_reader,
             control_rx,
             rpc_rx,
             status_watch_tx,
@@ -137,16 +179,20 @@ where
         self,
         bifrost: Bifrost,
         mut partition_store: PartitionStore,
+        partition_store_manager: PartitionStoreManager,
     ) -> Result<PartitionProcessor<Codec, InvokerInputSender>, StorageError> {
         let PartitionProcessorBuilder {
             partition_id,
             partition_key_range,
             num_timers_in_memory_limit,
             cleanup_interval,
+            archive_completed_invocations_after,
             disable_idempotency_table,
+            journal_version,
             channel_size,
             max_command_batch_size,
             invoker_tx,
+            invoker_status_reader,
             control_rx,
             rpc_rx,
             status_watch_tx,
@@ -158,6 +204,7 @@ where
             &mut partition_store,
             partition_key_range.clone(),
             disable_idempotency_table,
+            journal_version,
         )
         .await?;
 
@@ -187,9 +234,14 @@ where
             partition_key_range,
             leadership_state,
             state_machine,
+            admission_controller: AdmissionController::default(),
             max_command_batch_size,
+            cleanup_interval,
+            archive_completed_invocations_after,
             partition_store,
+            partition_store_manager,
             bifrost,
+            invoker_status_reader,
             control_rx,
             rpc_rx,
             status_watch_tx,
@@ -201,6 +253,7 @@ where
         partition_store: &mut PartitionStore,
         partition_key_range: RangeInclusive<PartitionKey>,
         disable_idempotency_table: bool,
+        journal_version: JournalVersion,
     ) -> Result<StateMachine<Codec>, StorageError>
     where
         Codec: RawEntryCodec + Default + Debug,
@@ -215,6 +268,7 @@ where
             outbox_head_seq_number,
             partition_key_range,
             disable_idempotency_table,
+            journal_version,
         );
 
         Ok(state_machine)
@@ -226,14 +280,19 @@ pub struct PartitionProcessor<Codec, InvokerSender> {
     partition_key_range: RangeInclusive<PartitionKey>,
     leadership_state: LeadershipState<InvokerSender>,
     state_machine: StateMachine<Codec>,
+    admission_controller: AdmissionController,
     bifrost: Bifrost,
+    invoker_status_reader: ChannelStatusReader,
     control_rx: mpsc::Receiver<PartitionProcessorControlCommand>,
     rpc_rx: mpsc::Receiver<Incoming<PartitionProcessorRpcRequest>>,
     status_watch_tx: watch::Sender<PartitionProcessorStatus>,
     status: PartitionProcessorStatus,
 
     max_command_batch_size: usize,
+    cleanup_interval: Duration,
+    archive_completed_invocations_after: Option<Duration>,
     partition_store: PartitionStore,
+    partition_store_manager: PartitionStoreManager,
 }
 
 impl<Codec, InvokerSender> PartitionProcessor<Codec, InvokerSender>
@@ -248,7 +307,7 @@ where
         let res = tokio::select! {
             res = self.run_inner() => {
                 match res.as_ref() {
-                    Ok(_) => warn!("Shutting partition processor down because it stopped unexpectedly."),
+                    Ok(_) => info!("Shutting partition processor down to restart it with the key range remaining after a split."),
                     Err(err) => warn!("Shutting partition processor down because it failed: {err}"),
                 }
                 res
@@ -281,6 +340,16 @@ where
         let mut partition_store = self.partition_store.clone();
         let last_applied_lsn = partition_store.get_applied_lsn().await?;
         let last_applied_lsn = last_applied_lsn.unwrap_or(Lsn::INVALID);
+        // a record which failed to apply in a previous run
+        let mut apply_failure = partition_store.get_apply_failure().await?;
+        // the decision to dead-letter this record instead of applying it again
+        let mut dead_letter_decision = match &apply_failure {
+            Some(failure) => {
+                self.await_dead_letter_decision(failure, &mut partition_store)
+                    .await?
+            }
+            None => None,
+        };
 
         self.status.last_applied_log_lsn = Some(last_applied_lsn);
 
@@ -289,6 +358,7 @@ where
             .bifrost
             .find_tail(LogId::from(self.partition_id))
             .await?;
+        self.status.log_tail_lsn = Some(current_tail.offset());
 
         debug!(
             last_applied_lsn = %last_applied_lsn,
@@ -326,6 +396,10 @@ where
             );
         }
 
+        let partition_id_str: &'static str = Box::leak(Box::new(self.partition_id.to_string()));
+        let decode_record_latency =
+            histogram!(PP_DECODE_RECORD_DURATION, PARTITION_LABEL => partition_id_str);
+
         // Start reading after the last applied lsn
         let key_query = KeyFilter::Within(self.partition_key_range.clone());
         let mut log_reader = self
@@ -336,24 +410,39 @@ where
                 last_applied_lsn.next(),
                 Lsn::MAX,
             )?
-            .map_ok(|entry| {
-                trace!(?entry, "Read entry");
-                let lsn = entry.sequence_number();
-                let Some(envelope) = entry.try_decode_arc::<Envelope>() else {
-                    // trim-gap
-                    unimplemented!("Handling trim gap is currently not supported")
-                };
-                anyhow::Ok((lsn, envelope?))
-            })
+            // read and decode records on a background task so that replaying a long backlog isn't
+            // bottlenecked on reading one record at a time
+            .read_ahead(
+                Configuration::pinned().bifrost.read_ahead_records,
+                move |entry| {
+                    trace!(?entry, "Read entry");
+                    let decode_start = Instant::now();
+                    let lsn = entry.sequence_number();
+                    let created_at = entry
+                        .as_record()
+                        .map(|record| record.created_at())
+                        .unwrap_or_default();
+                    if let Some(trim_gap_end) = entry.trim_gap_to_sequence_number() {
+                        // the partition processor manager restarts the partition processor from a
+                        // snapshot covering the trim gap
+                        return Err(TrimGapEncountered { trim_gap_end }.into());
+                    }
+                    let envelope = entry.try_decode_arc::<Envelope>().expect("not a trim gap");
+                    decode_record_latency.record(decode_start.elapsed());
+                    anyhow::Ok((lsn, created_at, envelope?))
+                },
+            )?
             .try_take_while(|entry| {
                 // a catch-all safety net if all lower layers didn't filter this record out. This
                 // could happen for old records that didn't store `Keys` in the log store.
                 //
                 // At some point, we should remove this and trust that stored records have Keys
                 // stored correctly.
-                std::future::ready(Ok(entry
-                    .as_ref()
-                    .is_ok_and(|(_, envelope)| envelope.matches_key_query(&key_query))))
+                //
+                // Errors are passed on so that the partition processor fails on them.
+                std::future::ready(Ok(entry.as_ref().map_or(true, |(_, _, envelope)| {
+                    envelope.matches_key_query(&key_query)
+                })))
             });
 
         // avoid synchronized timers. We pick a randomised timer between 500 and 1023 millis.
@@ -361,15 +450,27 @@ where
             tokio::time::interval(Duration::from_millis(500 + rand::random::<u64>() % 524));
         status_update_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
-        let partition_id_str: &'static str = Box::leak(Box::new(self.partition_id.to_string()));
+        let mut status_details_timer = tokio::time::interval(STATUS_DETAILS_REFRESH_INTERVAL);
+        status_details_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut archive_timer = tokio::time::interval(self.cleanup_interval);
+        archive_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut migration_timer = tokio::time::interval(self.cleanup_interval);
+        migration_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut migrations_completed = false;
+
         // Telemetry setup
         let apply_command_latency =
             histogram!(PP_APPLY_COMMAND_DURATION, PARTITION_LABEL => partition_id_str);
+        let commit_batch_latency =
+            histogram!(PP_COMMIT_BATCH_DURATION, PARTITION_LABEL => partition_id_str);
         let record_actions_latency = histogram!(PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION);
         let command_batch_size =
             histogram!(PP_APPLY_COMMAND_BATCH_SIZE, PARTITION_LABEL => partition_id_str);
 
         let mut action_collector = ActionCollector::default();
+        let mut command_spans = CommandSpans::default();
         let mut command_buffer = Vec::with_capacity(self.max_command_batch_size);
 
         info!("PartitionProcessor starting event loop.");
@@ -385,11 +486,24 @@ where
                     self.on_rpc(rpc, &mut partition_store).await;
                 }
                 _ = status_update_timer.tick() => {
+                    self.update_apply_lag();
                     self.status_watch_tx.send_modify(|old| {
                         old.clone_from(&self.status);
                         old.updated_at = MillisSinceEpoch::now();
                     });
                 }
+                _ = status_details_timer.tick() => {
+                    self.refresh_status_details(&partition_store).await;
+                }
+                _ = archive_timer.tick(), if self.archive_completed_invocations_after.is_some() => {
+                    self.archive_completed_invocations(&mut partition_store).await;
+                }
+                _ = migration_timer.tick(), if !migrations_completed => {
+                    match partition_store.run_migrations(MIGRATION_BATCH_SIZE).await {
+                        Ok(completed) => migrations_completed = completed,
+                        Err(err) => warn!("Failed running partition store migrations: {err}"),
+                    }
+                }
                 operation = Self::read_commands(&mut log_reader, self.max_command_batch_size, &mut command_buffer) => {
                     // check that reading has succeeded
                     operation?;
@@ -397,20 +511,80 @@ where
                     command_batch_size.record(command_buffer.len() as f64);
 
                     let mut transaction = partition_store.transaction();
+                    let effect_digests = Configuration::pinned().worker.effect_digests();
+                    let command_tracing = Configuration::pinned().worker.command_tracing();
 
                     // clear buffers used when applying the next record
                     action_collector.clear();
+                    let mut split_off = None;
 
-                    for (lsn, envelope) in command_buffer.drain(..) {
+                    for (lsn, created_at, envelope) in command_buffer.drain(..) {
                         let command_start = Instant::now();
+                        self.status.last_record_timestamp = Some(created_at.into());
 
                         trace!(%lsn, "Processing bifrost record for '{}': {:?}", envelope.command.name(), envelope.header);
 
-                        let leadership_change = self.apply_record(
+                        if let Some(decision) = dead_letter_decision.take_if(|decision| decision.lsn == lsn) {
+                            apply_failure = None;
+                            self.dead_letter_record(&envelope, decision, &mut transaction).await?;
+                            continue;
+                        }
+
+                        let split = self.split_of(&envelope).cloned();
+                        if let Some(split) = &split {
+                            // the partition store of the new partition is created from the state
+                            // right before the split
+                            transaction.commit().await?;
+                            self.split_partition_store(split).await?;
+                            transaction = partition_store.transaction();
+                        }
+
+                        let command_name = envelope.command.name();
+                        let invocation_id = envelope.command.invocation_id();
+                        let apply_started_at = SystemTime::now();
+                        let num_actions = action_collector.len();
+                        if effect_digests != EffectDigestMode::Disabled {
+                            transaction.start_effect_digest();
+                        }
+
+                        let leadership_change = match self.apply_record(
                             lsn,
+                            created_at.into(),
                             envelope,
                             &mut transaction,
-                            &mut action_collector).await?;
+                            &mut action_collector).await {
+                            Ok(leadership_change) => leadership_change,
+                            Err(err) => {
+                                if err.is_deterministic() {
+                                    // discard the partially applied batch before recording the failure
+                                    drop(transaction);
+                                    Self::record_apply_failure(lsn, &err, apply_failure.take(), &mut partition_store).await?;
+                                }
+                                return Err(err.into());
+                            }
+                        };
+
+                        if let Some(digest) = transaction.take_effect_digest() {
+                            self.record_effect_digest(lsn, command_name, digest, effect_digests, &mut transaction)?;
+                        }
+
+                        if let Some(invocation_id) = invocation_id.filter(|_| command_tracing && self.leadership_state.is_leader()) {
+                            command_spans.start(
+                                &mut transaction,
+                                invocation_id,
+                                command_name,
+                                created_at,
+                                apply_started_at,
+                                command_start.elapsed(),
+                                action_collector.len().saturating_sub(num_actions),
+                            ).await?;
+                        }
+
+                        if apply_failure.as_ref().is_some_and(|failure| failure.lsn <= lsn) {
+                            // the previously failing record has been applied by now
+                            apply_failure = None;
+                            transaction.clear_apply_failure().await;
+                        }
 
                         apply_command_latency.record(command_start.elapsed());
 
@@ -445,13 +619,37 @@ where
 
                             transaction = partition_store.transaction();
                         }
+
+                        if let Some(split) = split {
+                            transaction.put_partition_split(&PartitionSplit {
+                                child_partition_id: split.child_partition_id,
+                                lsn,
+                            }).await;
+                            split_off = Some((lsn, split));
+                            // the remaining records are read again with the reduced key range
+                            break;
+                        }
                     }
 
                     // Commit our changes and notify actuators about actions if we are the leader
+                    let commit_start = Instant::now();
                     transaction.commit().await?;
+                    let commit_duration = commit_start.elapsed();
+                    commit_batch_latency.record(commit_duration);
+                    command_spans.end_committed(commit_duration);
                     let actions_start = Instant::now();
                     self.leadership_state.handle_actions(action_collector.drain(..)).await?;
                     record_actions_latency.record(actions_start.elapsed());
+
+                    if let Some((lsn, split)) = split_off {
+                        info!(
+                            child_partition_id = %split.child_partition_id,
+                            %lsn,
+                            "Split off key range {:?}",
+                            split.child_key_range
+                        );
+                        return Ok(());
+                    }
                 },
                 result = self.leadership_state.run() => {
                     let action_effects = result?;
@@ -489,6 +687,60 @@ where
         Ok(())
     }
 
+    /// Refreshes the parts of the status which are too expensive to keep up to date on every
+    /// applied record.
+    async fn refresh_status_details(&mut self, partition_store: &PartitionStore) {
+        match self.bifrost.find_tail(LogId::from(self.partition_id)).await {
+            Ok(tail) => {
+                self.status.log_tail_lsn =
+                    std::cmp::max(self.status.log_tail_lsn, Some(tail.offset()));
+            }
+            Err(err) => debug!("Failed refreshing the log tail: {err}"),
+        }
+        self.status.num_inboxed_invocations = partition_store.count_inbox_entries();
+        self.status.invoker_queue_depth = self
+            .invoker_status_reader
+            .read_status(self.partition_key_range.clone())
+            .await
+            .count() as u64;
+        self.update_apply_lag();
+    }
+
+    fn update_apply_lag(&mut self) {
+        let caught_up = match (self.status.last_applied_log_lsn, self.status.log_tail_lsn) {
+            (Some(last_applied), Some(tail)) => last_applied.next() >= tail,
+            _ => false,
+        };
+        self.status.apply_lag = if caught_up {
+            Some(Duration::ZERO)
+        } else {
+            self.status
+                .last_record_timestamp
+                .map(|timestamp| timestamp.elapsed())
+        };
+    }
+
+    /// Moves completed invocation statuses which are older than the configured window into the
+    /// archive of the partition store. Runs on the event loop so that it never races with the
+    /// application of commands.
+    async fn archive_completed_invocations(&self, partition_store: &mut PartitionStore) {
+        let Some(archive_after) = self.archive_completed_invocations_after else {
+            return;
+        };
+        let completed_before = MillisSinceEpoch::from(
+            SystemTime::now()
+                .checked_sub(archive_after)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        );
+
+        if let Err(err) = partition_store
+            .archive_completed_invocation_statuses(completed_before, ARCHIVE_BATCH_SIZE)
+            .await
+        {
+            warn!("Failed archiving completed invocation statuses: {err}");
+        }
+    }
+
     // --- RPC Handling
 
     async fn on_rpc(
@@ -502,6 +754,49 @@ where
                 request_id, inner, ..
             },
         ) = rpc.split();
+
+        if let PartitionProcessorRpcRequestInner::AppendInvocation(invocation_request, _) = &inner {
+            // only the leader appends to the log, followers reject the request anyway
+            if self.leadership_state.is_leader()
+                && !self.admission_controller.try_admit(
+                    &Configuration::pinned().worker.ingress_admission,
+                    invocation_request.body.len(),
+                    Instant::now(),
+                )
+            {
+                counter!(PARTITION_INGRESS_ADMISSION_REJECTED, PARTITION_LABEL => self.partition_id.to_string())
+                    .increment(1);
+                respond_to_rpc(
+                    response_tx.prepare(Err(PartitionProcessorRpcError::Overloaded(
+                        self.partition_id,
+                    ))),
+                );
+                return;
+            }
+        }
+        if let PartitionProcessorRpcRequestInner::AppendInvocations(invocation_requests) = &inner {
+            if self.leadership_state.is_leader()
+                && !self.admission_controller.try_admit_batch(
+                    &Configuration::pinned().worker.ingress_admission,
+                    invocation_requests.len(),
+                    invocation_requests
+                        .iter()
+                        .map(|invocation_request| invocation_request.body.len())
+                        .sum(),
+                    Instant::now(),
+                )
+            {
+                counter!(PARTITION_INGRESS_ADMISSION_REJECTED, PARTITION_LABEL => self.partition_id.to_string())
+                    .increment(invocation_requests.len() as u64);
+                respond_to_rpc(
+                    response_tx.prepare(Err(PartitionProcessorRpcError::Overloaded(
+                        self.partition_id,
+                    ))),
+                );
+                return;
+            }
+        }
+
         match inner {
             PartitionProcessorRpcRequestInner::AppendInvocation(
                 invocation_request,
@@ -615,6 +910,57 @@ where
                     )
                     .await;
             }
+            PartitionProcessorRpcRequestInner::AppendInvocations(invocation_requests) => {
+                if let Some(invocation_request) = invocation_requests
+                    .iter()
+                    .find(|request| !self.partition_key_range.contains(&request.partition_key()))
+                {
+                    respond_to_rpc(
+                        response_tx.prepare(Err(PartitionProcessorRpcError::Internal(format!(
+                            "invocation '{}' does not belong to partition '{}'",
+                            invocation_request.invocation_id(),
+                            self.partition_id
+                        )))),
+                    );
+                    return;
+                }
+
+                let commands = invocation_requests
+                    .into_iter()
+                    .map(|invocation_request| {
+                        let service_invocation = ServiceInvocation::from_request(
+                            invocation_request,
+                            invocation::Source::ingress(request_id),
+                        );
+                        (
+                            service_invocation.partition_key(),
+                            Command::Invoke(service_invocation),
+                        )
+                    })
+                    .collect();
+
+                self.leadership_state
+                    .self_propose_batch_and_respond_asynchronously(commands, response_tx)
+                    .await;
+            }
+            PartitionProcessorRpcRequestInner::AppendSchedule(schedule) => {
+                self.leadership_state
+                    .self_propose_and_respond_asynchronously(
+                        schedule.partition_key(),
+                        Command::UpsertSchedule(schedule),
+                        response_tx,
+                    )
+                    .await;
+            }
+            PartitionProcessorRpcRequestInner::GetInvocationProgress(invocation_query) => {
+                respond_to_rpc(
+                    response_tx.prepare(
+                        self.handle_rpc_get_invocation_progress(invocation_query, partition_store)
+                            .await
+                            .map_err(|err| PartitionProcessorRpcError::Internal(err.to_string())),
+                    ),
+                );
+            }
         };
     }
 
@@ -625,46 +971,24 @@ where
         partition_store: &mut PartitionStore,
     ) -> Result<PartitionProcessorRpcResponse, StorageError> {
         // We can handle this immediately by querying the partition store, no need to go through proposals
-        let invocation_id = match invocation_query {
-            InvocationQuery::Invocation(iid) => iid,
-            ref q @ InvocationQuery::Workflow(ref sid) => {
-                // TODO We need this query for backward compatibility, remove when we remove the idempotency table
-                match partition_store.get_virtual_object_status(sid).await? {
-                    VirtualObjectStatus::Locked(iid) => iid,
-                    VirtualObjectStatus::Unlocked => {
-                        // Try the deterministic id
-                        q.to_invocation_id()
-                    }
-                }
-            }
-            ref q @ InvocationQuery::IdempotencyId(ref iid) => {
-                // TODO We need this query for backward compatibility, remove when we remove the idempotency table
-                match partition_store.get_idempotency_metadata(iid).await? {
-                    Some(idempotency_metadata) => idempotency_metadata.invocation_id,
-                    None => {
-                        // Try the deterministic id
-                        q.to_invocation_id()
-                    }
-                }
-            }
-        };
+        let invocation_id = resolve_invocation_query(partition_store, &invocation_query).await?;
 
-        let invocation_status = partition_store
-            .get_invocation_status(&invocation_id)
+        let view = partition_store
+            .get_invocation_status_view(&invocation_id)
             .await?;
 
-        match invocation_status {
-            InvocationStatus::Free => Ok(PartitionProcessorRpcResponse::NotFound),
-            is if is.idempotency_key().is_none()
-                && is
-                    .invocation_target()
-                    .map(InvocationTarget::invocation_target_ty)
-                    != Some(InvocationTargetType::Workflow(
-                        WorkflowHandlerType::Workflow,
-                    )) =>
-            {
-                Ok(PartitionProcessorRpcResponse::NotSupported)
-            }
+        match view.kind {
+            InvocationStatusKind::Free => return Ok(PartitionProcessorRpcResponse::NotFound),
+            _ if !retains_output(&view) => return Ok(PartitionProcessorRpcResponse::NotSupported),
+            InvocationStatusKind::Completed => {}
+            _ => return Ok(PartitionProcessorRpcResponse::NotReady),
+        }
+
+        // Only the output of completed invocations needs the full status
+        match partition_store
+            .get_invocation_status(&invocation_id)
+            .await?
+        {
             InvocationStatus::Completed(completed) => {
                 // SAFETY: We use this field to send back the notification to ingress, and not as part of the PP deterministic logic.
                 let completion_expiry_time = unsafe { completed.completion_expiry_time() };
@@ -684,11 +1008,44 @@ where
         }
     }
 
+    async fn handle_rpc_get_invocation_progress(
+        &self,
+        invocation_query: InvocationQuery,
+        partition_store: &mut PartitionStore,
+    ) -> Result<PartitionProcessorRpcResponse, StorageError> {
+        // Like the output, the progress is read from the partition store without proposals
+        let invocation_id = resolve_invocation_query(partition_store, &invocation_query).await?;
+
+        let view = partition_store
+            .get_invocation_status_view(&invocation_id)
+            .await?;
+
+        let status = match view.kind {
+            InvocationStatusKind::Free => return Ok(PartitionProcessorRpcResponse::NotFound),
+            _ if !retains_output(&view) => return Ok(PartitionProcessorRpcResponse::NotSupported),
+            InvocationStatusKind::Scheduled => InvocationProgressStatus::Scheduled,
+            InvocationStatusKind::Inboxed => InvocationProgressStatus::Inboxed,
+            InvocationStatusKind::Invoked => InvocationProgressStatus::Invoked,
+            InvocationStatusKind::Suspended => InvocationProgressStatus::Suspended,
+            InvocationStatusKind::Completed => InvocationProgressStatus::Completed,
+        };
+
+        Ok(PartitionProcessorRpcResponse::Progress(
+            InvocationProgress {
+                invocation_id,
+                status,
+                journal_length: view.journal_length.unwrap_or_default(),
+                invocation_target: view.invocation_target,
+            },
+        ))
+    }
+
     // --- Apply new commands/records
 
     async fn apply_record<'a, 'b: 'a>(
         &mut self,
         lsn: Lsn,
+        created_at: MillisSinceEpoch,
         envelope: Arc<Envelope>,
         transaction: &mut PartitionStoreTransaction<'b>,
         action_collector: &mut ActionCollector,
@@ -698,6 +1055,8 @@ where
         // Update replay status
         self.status.last_applied_log_lsn = Some(lsn);
         self.status.last_record_applied_at = Some(MillisSinceEpoch::now());
+        // the log tail is at least after the applied record
+        self.status.log_tail_lsn = std::cmp::max(self.status.log_tail_lsn, Some(lsn.next()));
         match self.status.replay_status {
             ReplayStatus::CatchingUp
                 if self
@@ -739,6 +1098,7 @@ where
                 self.state_machine
                     .apply(
                         envelope.command,
+                        created_at,
                         transaction,
                         action_collector,
                         self.leadership_state.is_leader(),
@@ -756,6 +1116,243 @@ where
         Ok(None)
     }
 
+    /// Records the effect digest of the record at `lsn`. In verify mode, the digest is first
+    /// compared with the digest recorded when the record was applied before, if any.
+    fn record_effect_digest(
+        &self,
+        lsn: Lsn,
+        command_name: &'static str,
+        digest: u64,
+        mode: EffectDigestMode,
+        transaction: &mut PartitionStoreTransaction<'_>,
+    ) -> Result<(), StorageError> {
+        if mode == EffectDigestMode::Verify {
+            if let Some(recorded_digest) = transaction.get_effect_digest(lsn)? {
+                if recorded_digest != digest {
+                    warn!(
+                        %lsn,
+                        "Effects of applying record '{command_name}' diverged from the recorded effects: expected digest {recorded_digest:016x} but got {digest:016x}"
+                    );
+                    counter!(PARTITION_EFFECT_DIGEST_DIVERGENCES, PARTITION_LABEL => self.partition_id.to_string())
+                        .increment(1);
+                }
+            }
+        }
+
+        transaction.put_effect_digest(lsn, digest);
+        let retention = Configuration::pinned().worker.effect_digest_retention();
+        if let Some(expired) = u64::from(lsn).checked_sub(retention) {
+            transaction.delete_effect_digest(Lsn::from(expired));
+        }
+
+        Ok(())
+    }
+
+    fn should_dead_letter(apply_failure: &ApplyFailure) -> bool {
+        Configuration::pinned()
+            .worker
+            .dead_letter_after_failed_attempts()
+            .is_some_and(|max_attempts| apply_failure.attempts >= max_attempts.get())
+    }
+
+    /// Looks for the decision to dead-letter the record which failed to apply in a previous run.
+    /// If the record failed too often to be retried, waits for the decision instead, which this
+    /// processor proposes once it is asked to run for leadership. Returns `None` if the record
+    /// should be applied again.
+    async fn await_dead_letter_decision(
+        &mut self,
+        apply_failure: &ApplyFailure,
+        partition_store: &mut PartitionStore,
+    ) -> anyhow::Result<Option<DeadLetterDecision>> {
+        let mut next_lsn = apply_failure.lsn.next();
+        let mut proposed = false;
+
+        loop {
+            if let Some(decision) = self
+                .find_dead_letter_decision(apply_failure.lsn, &mut next_lsn)
+                .await?
+            {
+                return Ok(Some(decision));
+            }
+            if !Self::should_dead_letter(apply_failure) {
+                return Ok(None);
+            }
+            let cluster_version =
+                Metadata::with_current(|m| m.nodes_config_ref().cluster_version());
+            if cluster_version < ClusterVersion::V1 {
+                warn!(
+                    lsn = %apply_failure.lsn,
+                    "Cannot dead-letter the failing record before all nodes are upgraded to the cluster version {}",
+                    ClusterVersion::V1
+                );
+                return Ok(None);
+            }
+
+            tokio::select! {
+                Some(command) = self.control_rx.recv() => {
+                    if let Err(err) = self.on_command(command).await {
+                        warn!("Failed executing command: {err}");
+                    }
+                    if !proposed {
+                        proposed = self.leadership_state.propose_as_candidate(
+                            Command::DeadLetterRecord(DeadLetterDecision {
+                                lsn: apply_failure.lsn,
+                                error: apply_failure.error.clone(),
+                                attempts: apply_failure.attempts,
+                                dead_lettered_at: MillisSinceEpoch::now(),
+                            }),
+                        ).await?;
+                    }
+                }
+                Some(rpc) = self.rpc_rx.recv() => {
+                    self.on_rpc(rpc, partition_store).await;
+                }
+                _ = tokio::time::sleep(DEAD_LETTER_DECISION_POLL_INTERVAL) => {
+                    if !proposed {
+                        debug!(
+                            lsn = %apply_failure.lsn,
+                            "Waiting for leadership to dead-letter the failing record"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads the log from `next_lsn` up to its current tail, looking for the decision to
+    /// dead-letter the record at `lsn`. Advances `next_lsn` to the first record not read.
+    async fn find_dead_letter_decision(
+        &self,
+        lsn: Lsn,
+        next_lsn: &mut Lsn,
+    ) -> anyhow::Result<Option<DeadLetterDecision>> {
+        let log_id = LogId::from(self.partition_id);
+        let tail = self.bifrost.find_tail(log_id).await?.offset();
+        if *next_lsn >= tail {
+            return Ok(None);
+        }
+
+        let mut log_reader = self.bifrost.create_reader(
+            log_id,
+            KeyFilter::Within(self.partition_key_range.clone()),
+            *next_lsn,
+            tail.prev(),
+        )?;
+        *next_lsn = tail;
+        while let Some(entry) = log_reader.next().await {
+            // trim gaps and undecodable records cannot contain the decision
+            let Some(Ok(envelope)) = entry?.try_decode::<Envelope>() else {
+                continue;
+            };
+            if let Command::DeadLetterRecord(decision) = envelope.command {
+                if decision.lsn == lsn {
+                    return Ok(Some(decision));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Persists a deterministic failure to apply the record at `lsn` so that it is taken into
+    /// account by the next run of the partition processor.
+    async fn record_apply_failure(
+        lsn: Lsn,
+        err: &state_machine::Error,
+        previous_failure: Option<ApplyFailure>,
+        partition_store: &mut PartitionStore,
+    ) -> Result<(), StorageError> {
+        let attempts = previous_failure
+            .filter(|failure| failure.lsn == lsn)
+            .map_or(0, |failure| failure.attempts)
+            + 1;
+        warn!(%lsn, %attempts, "Failed applying record: {err}");
+
+        let mut transaction = partition_store.transaction();
+        transaction
+            .put_apply_failure(&ApplyFailure {
+                lsn,
+                attempts,
+                error: err.to_string(),
+            })
+            .await;
+        transaction.commit().await
+    }
+
+    /// Moves the record to the dead-letter table instead of applying it, as decided by the
+    /// leader. The dead letter only depends on the decision, so that all replicas store the same.
+    async fn dead_letter_record(
+        &mut self,
+        envelope: &Envelope,
+        decision: DeadLetterDecision,
+        transaction: &mut PartitionStoreTransaction<'_>,
+    ) -> anyhow::Result<()> {
+        let lsn = decision.lsn;
+        warn!(
+            %lsn,
+            attempts = %decision.attempts,
+            "Moving record '{}' to the dead-letter table because applying it failed repeatedly: {}",
+            envelope.command.name(),
+            decision.error
+        );
+
+        transaction
+            .put_dead_letter(&DeadLetter {
+                partition_id: self.partition_id,
+                lsn,
+                partition_key: envelope.partition_key(),
+                command: envelope.command.name().to_owned(),
+                error: decision.error,
+                attempts: decision.attempts,
+                dead_lettered_at: decision.dead_lettered_at,
+                envelope: envelope.to_bytes()?,
+            })
+            .await;
+        transaction.clear_apply_failure().await;
+        transaction.put_applied_lsn(lsn).await;
+        self.status.last_applied_log_lsn = Some(lsn);
+        counter!(PARTITION_DEAD_LETTERED_RECORDS, PARTITION_LABEL => self.partition_id.to_string())
+            .increment(1);
+
+        Ok(())
+    }
+
+    /// Returns the split of the record if it splits off a key range of this partition.
+    fn split_of<'a>(&self, envelope: &'a Envelope) -> Option<&'a SplitPartition> {
+        match &envelope.command {
+            Command::SplitPartition(split)
+                if split.child_partition_id != self.partition_id
+                    && self
+                        .partition_key_range
+                        .contains(split.child_key_range.start())
+                    && self
+                        .partition_key_range
+                        .contains(split.child_key_range.end()) =>
+            {
+                Some(split)
+            }
+            _ => None,
+        }
+    }
+
+    async fn split_partition_store(&self, split: &SplitPartition) -> anyhow::Result<()> {
+        let config = Configuration::pinned();
+        self.partition_store_manager
+            .split_partition_store(
+                self.partition_id,
+                split.child_partition_id,
+                split.child_key_range.clone(),
+                config
+                    .worker
+                    .snapshots
+                    .snapshots_dir(self.partition_id)
+                    .as_path(),
+                &config.worker.storage.rocksdb,
+            )
+            .await?;
+        Ok(())
+    }
+
     fn is_targeted_to_me<'a>(&self, header: &'a Header) -> Option<&'a Option<DedupInformation>> {
         match &header.dest {
             Destination::Processor {
@@ -793,11 +1390,15 @@ where
     async fn read_commands<S>(
         log_reader: &mut S,
         max_batching_size: usize,
-        record_buffer: &mut Vec<(Lsn, Arc<Envelope>)>,
+        record_buffer: &mut Vec<(Lsn, NanosSinceEpoch, Arc<Envelope>)>,
     ) -> anyhow::Result<()>
     where
-        S: Stream<Item = Result<anyhow::Result<(Lsn, Arc<Envelope>)>, restate_bifrost::Error>>
-            + Unpin,
+        S: Stream<
+                Item = Result<
+                    anyhow::Result<(Lsn, NanosSinceEpoch, Arc<Envelope>)>,
+                    restate_bifrost::Error,
+                >,
+            > + Unpin,
     {
         // beyond this point we must not await; otherwise we are no longer cancellation safe
         let first_record = log_reader.next().await;
@@ -829,6 +1430,19 @@ where
     }
 }
 
+/// Only the invocations with an idempotency key and the workflow runs retain their output, and
+/// can therefore be attached to.
+fn retains_output(view: &InvocationStatusView) -> bool {
+    view.idempotency_key.is_some()
+        || view
+            .invocation_target
+            .as_ref()
+            .map(InvocationTarget::invocation_target_ty)
+            == Some(InvocationTargetType::Workflow(
+                WorkflowHandlerType::Workflow,
+            ))
+}
+
 fn respond_to_rpc(
     outgoing: Outgoing<
         Result<PartitionProcessorRpcResponse, PartitionProcessorRpcError>,
@@ -0,0 +1,246 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use futures::StreamExt;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, instrument, warn};
+
+use restate_bifrost::Bifrost;
+use restate_core::{cancellation_watcher, Metadata};
+use restate_storage_api::prepared_message_table::{
+    PreparedMessageDelivery, ReadOnlyPreparedMessageTable,
+};
+use restate_types::identifiers::{LeaderEpoch, PartitionId, WithPartitionKey};
+use restate_types::message::MessageIndex;
+use restate_wal_protocol::{
+    append_envelope_to_bifrost, Command, Destination, Envelope, Header, Source,
+};
+
+/// Interval at which the prepared messages which are not acknowledged yet are delivered again.
+const REDELIVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Handle of the leader to the [`PreparedMessageReconciler`].
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedMessagesHandle {
+    new_messages: Arc<Notify>,
+    acknowledgements_tx: mpsc::Sender<(PartitionId, MessageIndex)>,
+}
+
+impl PreparedMessagesHandle {
+    /// Triggers the delivery of the messages which were prepared since the last delivery.
+    pub(crate) fn deliver(&self) {
+        self.new_messages.notify_one();
+    }
+
+    /// Acknowledges to the `source` partition that its prepared message was applied. If the
+    /// reconciler is lagging behind, the acknowledgement is dropped; the source delivers the
+    /// message again in this case, which is then acknowledged again.
+    pub(crate) fn acknowledge(&self, source: PartitionId, sequence_number: MessageIndex) {
+        let _ = self.acknowledgements_tx.try_send((source, sequence_number));
+    }
+}
+
+/// Delivers the prepared messages of the partition to the partitions owning their partition
+/// keys until they are acknowledged, and acknowledges the prepared messages applied by this
+/// partition to their source partitions.
+///
+/// Destinations only remember the highest applied sequence number of each source partition, see
+/// [`restate_storage_api::deduplication_table::DedupInformation::prepared_message`]. Messages
+/// are therefore delivered in sequence number order, and a failed delivery stops delivering the
+/// following messages until the next reconciliation.
+pub(super) struct PreparedMessageReconciler<Storage> {
+    partition_id: PartitionId,
+    leader_epoch: LeaderEpoch,
+    storage: Storage,
+    bifrost: Bifrost,
+    new_messages: Arc<Notify>,
+    acknowledgements_rx: mpsc::Receiver<(PartitionId, MessageIndex)>,
+    acknowledgements_tx: mpsc::Sender<(PartitionId, MessageIndex)>,
+}
+
+impl<Storage> PreparedMessageReconciler<Storage>
+where
+    Storage: ReadOnlyPreparedMessageTable + Send + Sync + 'static,
+{
+    pub(super) fn new(
+        partition_id: PartitionId,
+        leader_epoch: LeaderEpoch,
+        storage: Storage,
+        bifrost: Bifrost,
+        channel_size: usize,
+    ) -> Self {
+        let (acknowledgements_tx, acknowledgements_rx) = mpsc::channel(channel_size);
+        Self {
+            partition_id,
+            leader_epoch,
+            storage,
+            bifrost,
+            new_messages: Arc::new(Notify::new()),
+            acknowledgements_rx,
+            acknowledgements_tx,
+        }
+    }
+
+    pub(super) fn create_handle(&self) -> PreparedMessagesHandle {
+        PreparedMessagesHandle {
+            new_messages: Arc::clone(&self.new_messages),
+            acknowledgements_tx: self.acknowledgements_tx.clone(),
+        }
+    }
+
+    #[instrument(skip_all, fields(restate.partition.id = %self.partition_id))]
+    pub(super) async fn run(self) -> anyhow::Result<()> {
+        let Self {
+            partition_id,
+            leader_epoch,
+            storage,
+            bifrost,
+            new_messages,
+            mut acknowledgements_rx,
+            ..
+        } = self;
+        debug!("Running prepared message reconciler");
+
+        let my_node_id = Metadata::with_current(|m| m.my_node_id());
+        let bifrost_envelope_source = Source::Processor {
+            partition_id,
+            partition_key: None,
+            leader_epoch,
+            node_id: my_node_id.as_plain(),
+            generational_node_id: Some(my_node_id),
+        };
+
+        // time of the last delivery of the messages which are not acknowledged yet
+        let mut deliveries = HashMap::new();
+
+        let mut interval = tokio::time::interval(REDELIVERY_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = Self::reconcile(&storage, &bifrost, partition_id, &bifrost_envelope_source, &mut deliveries).await {
+                        warn!("Error when trying to deliver prepared messages: {e:?}");
+                    }
+                },
+                _ = new_messages.notified() => {
+                    if let Err(e) = Self::reconcile(&storage, &bifrost, partition_id, &bifrost_envelope_source, &mut deliveries).await {
+                        warn!("Error when trying to deliver prepared messages: {e:?}");
+                    }
+                },
+                Some((source, sequence_number)) = acknowledgements_rx.recv() => {
+                    if let Err(e) = Self::acknowledge(&bifrost, source, sequence_number, &bifrost_envelope_source).await {
+                        warn!("Error when trying to acknowledge prepared message {sequence_number} of partition {source}: {e:?}");
+                    }
+                },
+                _ = cancellation_watcher() => {
+                    break;
+                }
+            }
+        }
+
+        debug!("Stopping prepared message reconciler");
+
+        Ok(())
+    }
+
+    /// Delivers the prepared messages which were not delivered within the last
+    /// [`REDELIVERY_INTERVAL`].
+    async fn reconcile(
+        storage: &Storage,
+        bifrost: &Bifrost,
+        partition_id: PartitionId,
+        bifrost_envelope_source: &Source,
+        deliveries: &mut HashMap<MessageIndex, Instant>,
+    ) -> anyhow::Result<()> {
+        let prepared_messages = storage.get_prepared_messages();
+        tokio::pin!(prepared_messages);
+
+        let mut pending_deliveries = HashMap::with_capacity(deliveries.len());
+        while let Some((sequence_number, message)) = prepared_messages
+            .next()
+            .await
+            .transpose()
+            .context("Cannot read the next prepared message")?
+        {
+            if let Some(delivery_time) = deliveries.get(&sequence_number) {
+                if delivery_time.elapsed() < REDELIVERY_INTERVAL {
+                    pending_deliveries.insert(sequence_number, *delivery_time);
+                    continue;
+                }
+            }
+
+            let delivery = PreparedMessageDelivery {
+                source: partition_id,
+                sequence_number,
+                message,
+            };
+            append_envelope_to_bifrost(
+                bifrost,
+                Arc::new(Envelope::new(
+                    Header {
+                        source: bifrost_envelope_source.clone(),
+                        dest: Destination::Processor {
+                            partition_key: delivery.partition_key(),
+                            // deduplicated by the state machine, which acknowledges duplicates
+                            dedup: None,
+                        },
+                    },
+                    Command::ApplyPreparedMessage(delivery),
+                )),
+            )
+            .await
+            .context("Cannot append to bifrost")?;
+            pending_deliveries.insert(sequence_number, Instant::now());
+        }
+
+        // forget the acknowledged messages
+        *deliveries = pending_deliveries;
+        Ok(())
+    }
+
+    async fn acknowledge(
+        bifrost: &Bifrost,
+        source: PartitionId,
+        sequence_number: MessageIndex,
+        bifrost_envelope_source: &Source,
+    ) -> anyhow::Result<()> {
+        let partition_key = Metadata::with_current(|m| {
+            m.partition_table_ref()
+                .get_partition(&source)
+                .map(|partition| *partition.key_range.start())
+        })
+        .with_context(|| format!("Unknown partition {source}"))?;
+
+        append_envelope_to_bifrost(
+            bifrost,
+            Arc::new(Envelope::new(
+                Header {
+                    source: bifrost_envelope_source.clone(),
+                    dest: Destination::Processor {
+                        partition_key,
+                        dedup: None,
+                    },
+                },
+                Command::AcknowledgePreparedMessage(sequence_number),
+            )),
+        )
+        .await
+        .context("Cannot append to bifrost")?;
+
+        Ok(())
+    }
+}
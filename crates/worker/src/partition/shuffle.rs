@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::future::Future;
+use std::sync::Arc;
 
 use async_channel::{TryRecvError, TrySendError};
 use tokio::sync::mpsc;
@@ -16,8 +17,9 @@ use tracing::debug;
 
 use restate_bifrost::Bifrost;
 use restate_core::{cancellation_watcher, Metadata};
+use restate_ingress_kafka::KafkaEgress;
 use restate_storage_api::deduplication_table::DedupInformation;
-use restate_storage_api::outbox_table::OutboxMessage;
+use restate_storage_api::outbox_table::{KafkaEgressEvent, OutboxMessage};
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
 use restate_types::message::MessageIndex;
 use restate_wal_protocol::{append_envelope_to_bifrost, Destination, Envelope, Header, Source};
@@ -53,7 +55,74 @@ impl OutboxTruncation {
     }
 }
 
-pub(crate) fn wrap_outbox_message_in_envelope(
+/// Maximum number of events to Kafka egress targets published in a single transaction.
+const MAX_KAFKA_EVENTS_BATCH: usize = 100;
+
+/// Outbox messages ready to be shuffled. Events sent to Kafka egress targets are published to
+/// Kafka in batches, all the other messages are appended to bifrost one by one.
+#[derive(Debug, Clone)]
+pub(crate) enum ShuffledMessage {
+    Envelope(Arc<Envelope>),
+    KafkaEvents(Vec<(MessageIndex, Arc<KafkaEgressEvent>)>),
+}
+
+/// Prepares the messages read by [`read_next_messages`].
+pub(crate) fn prepare_outbox_messages(
+    messages: Vec<(MessageIndex, OutboxMessage)>,
+    shuffle_metadata: &ShuffleMetadata,
+) -> ShuffledMessage {
+    let mut messages = messages.into_iter().peekable();
+    match messages.peek() {
+        Some((_, OutboxMessage::KafkaEvent(_))) => ShuffledMessage::KafkaEvents(
+            messages
+                .filter_map(|(seq_number, message)| match message {
+                    OutboxMessage::KafkaEvent(event) => Some((seq_number, Arc::new(event))),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => {
+            let (seq_number, message) = messages.next().expect("at least one message");
+            ShuffledMessage::Envelope(Arc::new(wrap_outbox_message_in_envelope(
+                message,
+                seq_number,
+                shuffle_metadata,
+            )))
+        }
+    }
+}
+
+/// Reads the next message of the outbox, starting from `next_sequence_number`. If it's an event
+/// to a Kafka egress target, the following events to the same Kafka cluster are read as well, to
+/// publish them in the same transaction.
+async fn read_next_messages<OR: OutboxReader>(
+    outbox_reader: &mut OR,
+    next_sequence_number: MessageIndex,
+) -> Result<Vec<(MessageIndex, OutboxMessage)>, OutboxReaderError> {
+    let Some((seq_number, message)) = outbox_reader.get_next_message(next_sequence_number).await?
+    else {
+        return Ok(Vec::new());
+    };
+    let cluster = match &message {
+        OutboxMessage::KafkaEvent(event) => event.cluster.clone(),
+        _ => return Ok(vec![(seq_number, message)]),
+    };
+
+    let mut messages = vec![(seq_number, message)];
+    let mut last_seq_number = seq_number;
+    while messages.len() < MAX_KAFKA_EVENTS_BATCH {
+        match outbox_reader.get_next_message(last_seq_number + 1).await? {
+            Some((seq_number, OutboxMessage::KafkaEvent(event))) if event.cluster == cluster => {
+                last_seq_number = seq_number;
+                messages.push((seq_number, OutboxMessage::KafkaEvent(event)));
+            }
+            _ => break,
+        }
+    }
+    Ok(messages)
+}
+
+fn wrap_outbox_message_in_envelope(
     message: OutboxMessage,
     seq_number: MessageIndex,
     shuffle_metadata: &ShuffleMetadata,
@@ -219,13 +288,27 @@ where
         let node_id = Metadata::with_current(|m| m.my_node_id());
         debug!(restate.node = %node_id, restate.partition.id = %metadata.partition_id, "Running shuffle");
 
+        let kafka_egress = Arc::new(KafkaEgress::new(
+            metadata.partition_id,
+            metadata.leader_epoch,
+            node_id.as_plain(),
+        ));
+
         let state_machine = StateMachine::new(
             metadata,
             outbox_reader,
             move |msg| {
                 let bifrost = bifrost.clone();
+                let kafka_egress = Arc::clone(&kafka_egress);
                 async move {
-                    append_envelope_to_bifrost(&bifrost, msg).await?;
+                    match msg {
+                        ShuffledMessage::Envelope(envelope) => {
+                            append_envelope_to_bifrost(&bifrost, envelope).await?;
+                        }
+                        ShuffledMessage::KafkaEvents(events) => {
+                            kafka_egress.publish(events).await?;
+                        }
+                    }
                     Ok(())
                 }
             },
@@ -259,24 +342,23 @@ mod state_machine {
     use std::cmp::Ordering;
     use std::future::Future;
     use std::pin::Pin;
-    use std::sync::Arc;
     use std::time::Duration;
     use tokio_util::sync::ReusableBoxFuture;
     use tracing::{debug, trace};
 
     use restate_storage_api::outbox_table::OutboxMessage;
     use restate_types::message::MessageIndex;
-    use restate_wal_protocol::Envelope;
 
     use crate::partition::shuffle;
     use crate::partition::shuffle::{
-        wrap_outbox_message_in_envelope, NewOutboxMessage, OutboxReaderError, ShuffleMetadata,
+        prepare_outbox_messages, read_next_messages, NewOutboxMessage, OutboxReaderError,
+        ShuffleMetadata, ShuffledMessage,
     };
 
     type ReadFuture<OutboxReader> = ReusableBoxFuture<
         'static,
         (
-            Result<Option<(MessageIndex, OutboxMessage)>, OutboxReaderError>,
+            Result<Vec<(MessageIndex, OutboxMessage)>, OutboxReaderError>,
             OutboxReader,
         ),
     >;
@@ -285,7 +367,7 @@ mod state_machine {
     enum State<SendFuture> {
         Idle,
         ReadingOutbox,
-        Sending(#[pin] SendFuture, Arc<Envelope>),
+        Sending(#[pin] SendFuture, ShuffledMessage),
     }
 
     #[pin_project]
@@ -304,17 +386,17 @@ mod state_machine {
         mut outbox_reader: OutboxReader,
         sequence_number: MessageIndex,
     ) -> (
-        Result<Option<(MessageIndex, OutboxMessage)>, OutboxReaderError>,
+        Result<Vec<(MessageIndex, OutboxMessage)>, OutboxReaderError>,
         OutboxReader,
     ) {
-        let result = outbox_reader.get_next_message(sequence_number).await;
+        let result = read_next_messages(&mut outbox_reader, sequence_number).await;
         (result, outbox_reader)
     }
 
     impl<'a, OutboxReader, SendOp, SendFuture> StateMachine<'a, OutboxReader, SendOp, SendFuture>
     where
         SendFuture: Future<Output = Result<(), anyhow::Error>>,
-        SendOp: Fn(Arc<Envelope>) -> SendFuture,
+        SendOp: Fn(ShuffledMessage) -> SendFuture,
         OutboxReader: shuffle::OutboxReader + Send + Sync + 'static,
     {
         pub(super) fn new(
@@ -358,18 +440,20 @@ mod state_machine {
                                 .expect("shuffle is owning the hint sender");
 
                             match seq_number.cmp(this.current_sequence_number) {
-                                Ordering::Equal => {
-                                    let envelope = Arc::new(wrap_outbox_message_in_envelope(
-                                        message.clone(),
-                                        seq_number,
+                                Ordering::Equal
+                                    if !matches!(message, OutboxMessage::KafkaEvent(_)) =>
+                                {
+                                    let message = prepare_outbox_messages(
+                                        vec![(seq_number, message.clone())],
                                         this.metadata,
-                                    ));
-                                    let send_future = (this.send_operation)(Arc::clone(&envelope));
-                                    this.state.set(State::Sending(send_future, envelope));
+                                    );
+                                    let send_future = (this.send_operation)(message.clone());
+                                    this.state.set(State::Sending(send_future, message));
                                     break;
                                 }
-                                Ordering::Greater => {
-                                    // we might have missed some hints, so try again reading the next available outbox message (scan)
+                                Ordering::Equal | Ordering::Greater => {
+                                    // we might have missed some hints, so try again reading the next available outbox message (scan).
+                                    // Events to Kafka egress targets are read from the outbox too, to batch them with the following ones
                                     this.read_future.set(get_next_message(
                                         this.outbox_reader
                                             .take()
@@ -389,33 +473,32 @@ mod state_machine {
                         let (reading_result, outbox_reader) = this.read_future.get_pin().await;
                         *this.outbox_reader = Some(outbox_reader);
 
-                        if let Some((seq_number, message)) = reading_result? {
+                        let messages = reading_result?;
+                        if let Some((seq_number, _)) = messages.first() {
                             assert!(
-                                seq_number >= *this.current_sequence_number,
+                                *seq_number >= *this.current_sequence_number,
                                 "message sequence numbers must not decrease"
                             );
 
-                            *this.current_sequence_number = seq_number;
+                            // Messages read together are sent together
+                            *this.current_sequence_number =
+                                messages.last().expect("at least one message").0;
 
-                            let envelope = Arc::new(wrap_outbox_message_in_envelope(
-                                message,
-                                seq_number,
-                                this.metadata,
-                            ));
-                            let send_future = (this.send_operation)(Arc::clone(&envelope));
+                            let message = prepare_outbox_messages(messages, this.metadata);
+                            let send_future = (this.send_operation)(message.clone());
 
-                            this.state.set(State::Sending(send_future, envelope));
+                            this.state.set(State::Sending(send_future, message));
                         } else {
                             this.state.set(State::Idle);
                         }
                     }
-                    StateProj::Sending(send_future, envelope) => {
+                    StateProj::Sending(send_future, message) => {
                         if let Err(err) = send_future.await {
                             debug!("Retrying failed shuffle attempt: {err}");
 
-                            let send_future = (this.send_operation)(Arc::clone(envelope));
-                            let envelope = Arc::clone(envelope);
-                            this.state.set(State::Sending(send_future, envelope));
+                            let send_future = (this.send_operation)(message.clone());
+                            let message = message.clone();
+                            this.state.set(State::Sending(send_future, message));
 
                             tokio::time::sleep(Duration::from_secs(1)).await;
                         } else {
@@ -455,7 +538,7 @@ mod tests {
     use restate_bifrost::{Bifrost, LogEntry};
     use restate_core::network::FailingConnector;
     use restate_core::{TaskCenter, TaskKind, TestCoreEnv, TestCoreEnvBuilder};
-    use restate_storage_api::outbox_table::OutboxMessage;
+    use restate_storage_api::outbox_table::{KafkaEgressEvent, OutboxMessage};
     use restate_storage_api::StorageError;
     use restate_types::identifiers::{InvocationId, LeaderEpoch, PartitionId};
     use restate_types::invocation::ServiceInvocation;
@@ -465,7 +548,9 @@ mod tests {
     use restate_types::Version;
     use restate_wal_protocol::{Command, Envelope};
 
-    use crate::partition::shuffle::{OutboxReader, OutboxReaderError, Shuffle, ShuffleMetadata};
+    use crate::partition::shuffle::{
+        read_next_messages, OutboxReader, OutboxReaderError, Shuffle, ShuffleMetadata,
+    };
 
     struct MockOutboxReader {
         base_offset: MessageIndex,
@@ -807,4 +892,52 @@ mod tests {
 
         Ok(())
     }
+
+    /// Outbox reader over consecutive messages starting from 0.
+    struct VecOutboxReader(Vec<OutboxMessage>);
+
+    impl OutboxReader for VecOutboxReader {
+        async fn get_next_message(
+            &mut self,
+            next_sequence_number: MessageIndex,
+        ) -> Result<Option<(MessageIndex, OutboxMessage)>, OutboxReaderError> {
+            Ok(self
+                .0
+                .get(next_sequence_number as usize)
+                .map(|message| (next_sequence_number, message.clone())))
+        }
+    }
+
+    fn kafka_event(cluster: &str) -> OutboxMessage {
+        OutboxMessage::KafkaEvent(KafkaEgressEvent {
+            cluster: cluster.to_owned(),
+            topic: "topic".to_owned(),
+            key: None,
+            payload: Default::default(),
+            headers: Vec::new(),
+            source_invocation_id: InvocationId::mock_random(),
+        })
+    }
+
+    #[test(tokio::test)]
+    async fn read_kafka_events_in_batches() -> anyhow::Result<()> {
+        let mut outbox_reader = VecOutboxReader(vec![
+            kafka_event("a"),
+            kafka_event("a"),
+            kafka_event("b"),
+            OutboxMessage::ServiceInvocation(ServiceInvocation::mock()),
+            kafka_event("b"),
+        ]);
+
+        let batches = [vec![0, 1], vec![2], vec![3], vec![4], vec![]];
+        let mut next_sequence_number = 0;
+        for batch in batches {
+            let messages = read_next_messages(&mut outbox_reader, next_sequence_number).await?;
+            let seq_numbers: Vec<_> = messages.iter().map(|(seq, _)| *seq).collect();
+            assert_eq!(seq_numbers, batch);
+            next_sequence_number += messages.len() as MessageIndex;
+        }
+
+        Ok(())
+    }
 }
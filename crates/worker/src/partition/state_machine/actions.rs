@@ -11,8 +11,10 @@
 use restate_invoker_api::InvokeInputJournal;
 use restate_storage_api::outbox_table::OutboxMessage;
 use restate_storage_api::timer_table::TimerKey;
-use restate_types::identifiers::{EntryIndex, InvocationId, PartitionProcessorRpcRequestId};
-use restate_types::invocation::InvocationTarget;
+use restate_types::identifiers::{
+    EntryIndex, InvocationId, PartitionId, PartitionProcessorRpcRequestId,
+};
+use restate_types::invocation::{InvocationEpoch, InvocationTarget};
 use restate_types::journal::Completion;
 use restate_types::message::MessageIndex;
 use restate_types::net::partition_processor::IngressResponseResult;
@@ -27,6 +29,8 @@ pub enum Action {
     Invoke {
         invocation_id: InvocationId,
         invocation_target: InvocationTarget,
+        invocation_epoch: InvocationEpoch,
+        execution_deadline: Option<MillisSinceEpoch>,
         invoke_input_journal: InvokeInputJournal,
     },
     NewOutboxMessage {
@@ -64,6 +68,13 @@ pub enum Action {
         invocation_id: InvocationId,
         retention: Duration,
     },
+    /// Deliver the messages which were prepared for other partitions.
+    DeliverPreparedMessages,
+    /// Acknowledge to the source partition that its prepared message was applied.
+    AcknowledgePreparedMessage {
+        source: PartitionId,
+        sequence_number: MessageIndex,
+    },
 }
 
 impl Action {
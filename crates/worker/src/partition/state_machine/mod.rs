@@ -20,21 +20,38 @@ use bytes::Bytes;
 use bytestring::ByteString;
 use futures::{StreamExt, TryStreamExt};
 use metrics::{histogram, Histogram};
+use restate_core::Metadata;
 use restate_invoker_api::InvokeInputJournal;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_storage_api::consistency::ConsistencyRepair;
+use restate_storage_api::dead_letter_table::DeadLetterTable;
+use restate_storage_api::deduplication_table::{
+    DedupInformation, DedupSequenceNumber, DeduplicationTable, ProducerId,
+    ReadOnlyDeduplicationTable,
+};
 use restate_storage_api::fsm_table::FsmTable;
 use restate_storage_api::idempotency_table::IdempotencyMetadata;
 use restate_storage_api::idempotency_table::{IdempotencyTable, ReadOnlyIdempotencyTable};
 use restate_storage_api::inbox_table::{InboxEntry, InboxTable};
+use restate_storage_api::invocation_event_table::{InvocationEventKind, InvocationEventTable};
+use restate_storage_api::invocation_index_table::{
+    InvocationIndexEntry, InvocationIndexTable, InvocationLookupKey, ReadOnlyInvocationIndexTable,
+};
 use restate_storage_api::invocation_status_table::{
     CompletedInvocation, InFlightInvocationMetadata, InboxedInvocation, InvocationStatusTable,
-    PreFlightInvocationMetadata, ReadOnlyInvocationStatusTable,
+    JournalMetadata, JournalVersion, PreFlightInvocationMetadata, ReadOnlyInvocationStatusTable,
+};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, InvocationStatusKind, InvocationStatusView, ScheduledInvocation,
 };
-use restate_storage_api::invocation_status_table::{InvocationStatus, ScheduledInvocation};
 use restate_storage_api::journal_table::ReadOnlyJournalTable;
 use restate_storage_api::journal_table::{JournalEntry, JournalTable};
-use restate_storage_api::outbox_table::{OutboxMessage, OutboxTable};
+use restate_storage_api::journal_table_v2;
+use restate_storage_api::journal_table_v2::StoredEntry;
+use restate_storage_api::outbox_table::{KafkaEgressEvent, OutboxMessage, OutboxTable};
+use restate_storage_api::prepared_message_table::{PreparedMessageDelivery, PreparedMessageTable};
 use restate_storage_api::promise_table::{Promise, PromiseState, PromiseTable};
+use restate_storage_api::schedule_table::{ScheduleStatus, ScheduleTable};
 use restate_storage_api::service_status_table::{
     ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
 };
@@ -42,25 +59,27 @@ use restate_storage_api::state_table::StateTable;
 use restate_storage_api::timer_table::TimerKey;
 use restate_storage_api::timer_table::{Timer, TimerTable};
 use restate_storage_api::Result as StorageResult;
+use restate_storage_api::StorageError;
 use restate_tracing_instrumentation as instrumentation;
-use restate_types::deployment::PinnedDeployment;
+use restate_types::deployment::{DeploymentMigration, PinnedDeployment};
 use restate_types::errors::{
     InvocationError, InvocationErrorCode, ALREADY_COMPLETED_INVOCATION_ERROR,
-    ATTACH_NOT_SUPPORTED_INVOCATION_ERROR, CANCELED_INVOCATION_ERROR, KILLED_INVOCATION_ERROR,
-    NOT_FOUND_INVOCATION_ERROR, NOT_READY_INVOCATION_ERROR,
-    WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR,
+    ATTACH_NOT_SUPPORTED_INVOCATION_ERROR, CANCELED_INVOCATION_ERROR,
+    DRY_RUN_CALL_INVOCATION_ERROR, KILLED_INVOCATION_ERROR, NOT_FOUND_INVOCATION_ERROR,
+    NOT_READY_INVOCATION_ERROR, WORKFLOW_ALREADY_INVOKED_INVOCATION_ERROR,
 };
 use restate_types::identifiers::{
-    EntryIndex, InvocationId, PartitionKey, PartitionProcessorRpcRequestId, ServiceId,
+    EntryIndex, InvocationId, PartitionKey, PartitionProcessorRpcRequestId, ScheduleId, ServiceId,
 };
 use restate_types::identifiers::{
     IdempotencyId, JournalEntryId, WithInvocationId, WithPartitionKey,
 };
 use restate_types::invocation::{
-    AttachInvocationRequest, InvocationQuery, InvocationResponse, InvocationTarget,
-    InvocationTargetType, InvocationTermination, ResponseResult, ServiceInvocation,
-    ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source, SubmitNotificationSink,
-    TerminationFlavor, VirtualObjectHandlerType, WorkflowHandlerType,
+    AttachInvocationRequest, BulkInvocationOperation, BulkInvocationTermination,
+    InvocationOperation, InvocationQuery, InvocationResponse, InvocationTarget,
+    InvocationTargetType, InvocationTermination, ResponseResult, RetryInvocationRequest,
+    ServiceInvocation, ServiceInvocationResponseSink, ServiceInvocationSpanContext, Source,
+    SubmitNotificationSink, TerminationFlavor, VirtualObjectHandlerType, WorkflowHandlerType,
 };
 use restate_types::invocation::{InvocationInput, SpanRelation};
 use restate_types::journal::enriched::EnrichedRawEntry;
@@ -72,14 +91,18 @@ use restate_types::journal::Completion;
 use restate_types::journal::CompletionResult;
 use restate_types::journal::EntryType;
 use restate_types::journal::*;
+use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use restate_types::net::partition_processor::IngressResponseResult;
+use restate_types::schedule::Schedule;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_types::state_mut::StateMutationVersion;
-use restate_types::time::MillisSinceEpoch;
+use restate_types::time::{MillisSinceEpoch, ZonedDateTime};
+use restate_wal_protocol::control::SplitPartition;
+use restate_wal_protocol::timer::FiredScheduleTimer;
 use restate_wal_protocol::timer::TimerKeyDisplay;
 use restate_wal_protocol::timer::TimerKeyValue;
-use restate_wal_protocol::Command;
+use restate_wal_protocol::{Command, Envelope};
 use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fmt;
@@ -103,6 +126,9 @@ pub struct StateMachine<Codec> {
     /// This is used to disable writing to idempotency table/virtual object status table for idempotent invocations/workflow invocations.
     /// From Restate 1.2 invocation ids are generated deterministically, so this additional index is not needed.
     disable_idempotency_table: bool,
+    /// Journal version of the invocations started by this state machine. Invocations keep the
+    /// journal version they were started with.
+    journal_version: JournalVersion,
 
     _codec: PhantomData<Codec>,
 }
@@ -125,6 +151,15 @@ pub enum Error {
     Storage(#[from] restate_storage_api::StorageError),
 }
 
+impl Error {
+    /// Returns true if applying the same command to the same state is expected to fail again.
+    pub fn is_deterministic(&self) -> bool {
+        // Storage errors, including conversion and integrity errors, indicate a problem of the
+        // local storage rather than of the record. Skipping the record would diverge the replicas.
+        matches!(self, Error::Codec(_))
+    }
+}
+
 macro_rules! debug_if_leader {
     ($i_am_leader:expr, $($args:tt)*) => {{
         use ::tracing::Level;
@@ -161,6 +196,7 @@ impl<Codec> StateMachine<Codec> {
         outbox_head_seq_number: Option<MessageIndex>,
         partition_key_range: RangeInclusive<PartitionKey>,
         disable_idempotency_table: bool,
+        journal_version: JournalVersion,
     ) -> Self {
         let latency =
             histogram!(crate::metric_definitions::PARTITION_HANDLE_INVOKER_EFFECT_COMMAND);
@@ -171,6 +207,7 @@ impl<Codec> StateMachine<Codec> {
             partition_key_range,
             latency,
             disable_idempotency_table,
+            journal_version,
             _codec: PhantomData,
         }
     }
@@ -179,10 +216,20 @@ impl<Codec> StateMachine<Codec> {
 pub(crate) struct StateMachineApplyContext<'a, S> {
     storage: &'a mut S,
     action_collector: &'a mut ActionCollector,
+    /// Lifecycle events of the invocations, stored once the command is applied.
+    invocation_events: &'a mut Vec<(InvocationId, InvocationEventKind)>,
+    /// Creation time of the applied record. Unlike the wall clock, it is the same on all
+    /// replicas, hence it can be written to the partition store.
+    record_created_at: MillisSinceEpoch,
+    journal_version: JournalVersion,
     is_leader: bool,
 }
 
 impl<'a, S> StateMachineApplyContext<'a, S> {
+    fn record_invocation_event(&mut self, invocation_id: InvocationId, kind: InvocationEventKind) {
+        self.invocation_events.push((invocation_id, kind));
+    }
+
     async fn get_invocation_status(
         &mut self,
         invocation_id: &InvocationId,
@@ -198,12 +245,32 @@ impl<'a, S> StateMachineApplyContext<'a, S> {
         }
         Ok(status)
     }
+
+    async fn get_invocation_status_view(
+        &mut self,
+        invocation_id: &InvocationId,
+    ) -> Result<InvocationStatusView, Error>
+    where
+        S: ReadOnlyInvocationStatusTable,
+    {
+        Span::current().record_invocation_id(invocation_id);
+        let view = self
+            .storage
+            .get_invocation_status_view(invocation_id)
+            .await?;
+
+        if let Some(invocation_target) = &view.invocation_target {
+            Span::current().record_invocation_target(invocation_target);
+        }
+        Ok(view)
+    }
 }
 
 impl<Codec: RawEntryCodec> StateMachine<Codec> {
     pub async fn apply<TransactionType: restate_storage_api::Transaction + Send>(
         &mut self,
         command: Command,
+        record_created_at: MillisSinceEpoch,
         transaction: &mut TransactionType,
         action_collector: &mut ActionCollector,
         is_leader: bool,
@@ -213,16 +280,28 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             let start = Instant::now();
             // Apply the command
             let command_type = command.name();
-            let res = self
+            let mut invocation_events = Vec::new();
+            let mut res = self
                 .on_apply(
                     StateMachineApplyContext {
                         storage: transaction,
                         action_collector,
+                        invocation_events: &mut invocation_events,
+                        record_created_at,
+                        journal_version: self.journal_version,
                         is_leader,
                     },
                     command,
                 )
                 .await;
+            if res.is_ok() {
+                res = Self::store_invocation_events(
+                    transaction,
+                    record_created_at,
+                    invocation_events,
+                )
+                .await;
+            }
             histogram!(PARTITION_APPLY_COMMAND, "command" => command_type).record(start.elapsed());
             res
         }
@@ -230,23 +309,138 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         .await
     }
 
+    /// Stores the lifecycle events recorded while applying a command, in the order they were
+    /// recorded. The events are timestamped with the creation time of the applied record.
+    async fn store_invocation_events<State: InvocationEventTable>(
+        storage: &mut State,
+        timestamp: MillisSinceEpoch,
+        invocation_events: Vec<(InvocationId, InvocationEventKind)>,
+    ) -> Result<(), Error> {
+        let mut invocation_events = invocation_events.into_iter().peekable();
+        while let Some((invocation_id, kind)) = invocation_events.next() {
+            // Consecutive events of the same invocation are appended together
+            let mut kinds = vec![kind];
+            while let Some((_, kind)) = invocation_events
+                .next_if(|(next_invocation_id, _)| *next_invocation_id == invocation_id)
+            {
+                kinds.push(kind);
+            }
+            storage
+                .append_invocation_events(&invocation_id, timestamp, kinds)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the command of the dead letter at `lsn` and removes the dead letter. Returns
+    /// `None` if the dead letter does not exist.
+    async fn resolve_dead_letter<State: DeadLetterTable>(
+        storage: &mut State,
+        lsn: Lsn,
+    ) -> Result<Option<Command>, Error> {
+        let Some(dead_letter) = storage.get_dead_letter(lsn).await? else {
+            debug!(%lsn, "Ignoring re-injection of unknown dead letter");
+            return Ok(None);
+        };
+        storage.delete_dead_letter(lsn).await;
+
+        let command = Envelope::from_bytes(&dead_letter.envelope)
+            .map_err(|err| StorageError::Conversion(err.into()))?
+            .command;
+        debug!(%lsn, "Re-injecting dead-lettered command '{}'", command.name());
+
+        Ok(Some(command))
+    }
+
+    /// Returns the command of the prepared message, or `None` if the message was applied before.
+    /// Either way, the application of the message is acknowledged to the partition which
+    /// prepared it.
+    async fn resolve_prepared_message<State: DeduplicationTable>(
+        storage: &mut State,
+        action_collector: &mut ActionCollector,
+        PreparedMessageDelivery {
+            source,
+            sequence_number,
+            message,
+        }: PreparedMessageDelivery,
+    ) -> Result<Option<Command>, Error> {
+        action_collector.push(Action::AcknowledgePreparedMessage {
+            source,
+            sequence_number,
+        });
+
+        // prepared messages are delivered in sequence number order, hence it suffices to
+        // remember the highest applied sequence number of each source partition
+        let dedup_information = DedupInformation::prepared_message(source, sequence_number);
+        if let Some(DedupSequenceNumber::Sn(last_sequence_number)) = storage
+            .get_dedup_sequence_number(&dedup_information.producer_id)
+            .await?
+        {
+            if last_sequence_number >= sequence_number {
+                debug!(
+                    "Ignoring prepared message {sequence_number} of partition {source}, which was applied before"
+                );
+                return Ok(None);
+            }
+        }
+        storage
+            .put_dedup_seq_number(
+                dedup_information.producer_id,
+                &dedup_information.sequence_number,
+            )
+            .await;
+
+        Ok(Some(message.to_command()))
+    }
+
     async fn on_apply<
         State: IdempotencyTable
+            + InvocationIndexTable
             + PromiseTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + InvocationStatusTable
             + OutboxTable
             + FsmTable
             + TimerTable
             + VirtualObjectStatusTable
             + InboxTable
-            + StateTable,
+            + StateTable
+            + ScheduleTable
+            + DeduplicationTable
+            + DeadLetterTable
+            + PreparedMessageTable
+            + InvocationEventTable,
     >(
         &mut self,
         mut ctx: StateMachineApplyContext<'_, State>,
         command: Command,
     ) -> Result<(), Error> {
         match command {
+            // invocations owned by another partition, e.g. after re-keying, are handed off to it
+            Command::Invoke(service_invocation)
+                if !self
+                    .partition_key_range
+                    .contains(&service_invocation.partition_key()) =>
+            {
+                Self::prepare_message(
+                    &mut ctx,
+                    OutboxMessage::ServiceInvocation(service_invocation),
+                )
+                .await
+            }
+            Command::AttachInvocation(attach_invocation_request)
+                if !self
+                    .partition_key_range
+                    .contains(&attach_invocation_request.partition_key()) =>
+            {
+                Self::prepare_message(
+                    &mut ctx,
+                    OutboxMessage::AttachInvocation(attach_invocation_request),
+                )
+                .await
+            }
             Command::Invoke(service_invocation) => {
                 self.on_service_invocation(&mut ctx, service_invocation)
                     .await
@@ -286,14 +480,51 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 Ok(())
             }
             Command::Timer(timer) => self.on_timer(&mut ctx, timer).await,
+            Command::FireSchedule(FiredScheduleTimer { timer, fired_at }) => {
+                let wake_up_time = timer.wake_up_time();
+                let (key, value) = timer.into_inner();
+                Self::do_delete_timer(&mut ctx, key).await?;
+                let Timer::FireSchedule(schedule_id) = value else {
+                    warn!("Ignoring fired schedule with a timer of another kind: {value:?}");
+                    return Ok(());
+                };
+                self.on_fire_schedule(&mut ctx, schedule_id, wake_up_time, fired_at)
+                    .await
+            }
             Command::TerminateInvocation(invocation_termination) => {
                 self.try_terminate_invocation(&mut ctx, invocation_termination)
                     .await
             }
+            Command::BulkTerminateInvocations(bulk_termination) => {
+                self.terminate_invocations_by_target(&mut ctx, bulk_termination)
+                    .await
+            }
+            Command::BulkInvocationOperation(bulk_operation) => {
+                self.apply_bulk_invocation_operation(&mut ctx, bulk_operation)
+                    .await
+            }
+            Command::PauseService(service_name) => {
+                Self::on_pause_service(&mut ctx, service_name).await
+            }
+            Command::ResumeService(service_name) => {
+                self.on_resume_service(&mut ctx, service_name).await
+            }
+            Command::MigrateDeployment(migration) => {
+                self.on_migrate_deployment(&mut ctx, migration).await
+            }
+            Command::RepairConsistency(repair) => {
+                Self::on_repair_consistency(&mut ctx, repair).await
+            }
+            Command::AcknowledgePreparedMessage(sequence_number) => {
+                Self::on_acknowledge_prepared_message(&mut ctx, sequence_number).await
+            }
             Command::PurgeInvocation(purge_invocation_request) => {
                 self.try_purge_invocation(&mut ctx, purge_invocation_request.invocation_id)
                     .await
             }
+            Command::RetryInvocation(retry_invocation_request) => {
+                Self::try_retry_invocation(&mut ctx, retry_invocation_request).await
+            }
             Command::PatchState(mutation) => {
                 self.handle_external_state_mutation(&mut ctx, mutation)
                     .await
@@ -302,15 +533,50 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 // no-op :-)
                 Ok(())
             }
+            Command::SplitPartition(split) => self.on_split_partition(&mut ctx, split).await,
             Command::ScheduleTimer(timer) => {
                 Self::register_timer(&mut ctx, timer, Default::default()).await?;
                 Ok(())
             }
+            Command::UpsertSchedule(schedule) => Self::on_upsert_schedule(&mut ctx, schedule).await,
+            Command::DeleteSchedule(schedule_id) => {
+                Self::on_delete_schedule(&mut ctx, schedule_id).await
+            }
+            Command::ReinjectDeadLetter(lsn) => {
+                match Self::resolve_dead_letter(&mut *ctx.storage, lsn).await? {
+                    Some(command) => Box::pin(self.on_apply(ctx, command)).await,
+                    None => Ok(()),
+                }
+            }
+            Command::ApplyPreparedMessage(delivery) => {
+                match Self::resolve_prepared_message(
+                    &mut *ctx.storage,
+                    &mut *ctx.action_collector,
+                    delivery,
+                )
+                .await?
+                {
+                    Some(command) => Box::pin(self.on_apply(ctx, command)).await,
+                    None => Ok(()),
+                }
+            }
+            Command::DeadLetterRecord(decision) => {
+                // the partition processor dead-letters the record when reaching it, see
+                // PartitionProcessor::dead_letter_record
+                debug_if_leader!(
+                    ctx.is_leader,
+                    "Record {} was moved to the dead-letter table: {}",
+                    decision.lsn,
+                    decision.error
+                );
+                Ok(())
+            }
         }
     }
 
     async fn on_service_invocation<
         State: IdempotencyTable
+            + InvocationIndexTable
             + InvocationStatusTable
             + OutboxTable
             + FsmTable
@@ -318,7 +584,8 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + TimerTable
             + InboxTable
             + FsmTable
-            + JournalTable,
+            + JournalTable
+            + journal_table_v2::JournalTableV2,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -336,7 +603,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         // Phases of an invocation
         // 1. Try deduplicate it first
         // 2. Check if we need to schedule it
-        // 3. Check if we need to inbox it (only for exclusive handlers of virtual objects services)
+        // 3. Check if we need to inbox it (paused services, and virtual objects services)
         // 4. Execute it
 
         // 1. Try deduplicate it first
@@ -347,9 +614,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             // Invocation was deduplicated, nothing else to do here
             return Ok(());
         };
+        ctx.record_invocation_event(invocation_id, InvocationEventKind::Created);
 
         // Prepare PreFlightInvocationMetadata structure
         let submit_notification_sink = service_invocation.submit_notification_sink.take();
+        let execution_wall_clock_time = service_invocation.execution_wall_clock_time.take();
         let pre_flight_invocation_metadata =
             PreFlightInvocationMetadata::from_service_invocation(service_invocation);
 
@@ -359,6 +628,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 ctx,
                 invocation_id,
                 pre_flight_invocation_metadata,
+                execution_wall_clock_time,
             )
             .await?
         else {
@@ -372,9 +642,26 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             return Ok(());
         };
 
-        // 3. Check if we need to inbox it (only for exclusive methods of virtual objects)
+        // 3. Check if we need to inbox it (paused services, and virtual objects)
+        let Some(pre_flight_invocation_metadata) = self
+            .handle_service_invocation_paused_service(
+                ctx,
+                invocation_id,
+                pre_flight_invocation_metadata,
+            )
+            .await?
+        else {
+            // Invocation was inboxed, send back the ingress attach notification and return
+            Self::send_submit_notification_if_needed(
+                ctx,
+                invocation_id,
+                true,
+                submit_notification_sink,
+            );
+            return Ok(());
+        };
         let Some(pre_flight_invocation_metadata) = self
-            .handle_service_invocation_exclusive_handler(
+            .handle_service_invocation_virtual_object_handler(
                 ctx,
                 invocation_id,
                 pre_flight_invocation_metadata,
@@ -417,6 +704,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     /// Returns the invocation in case the invocation is not a duplicate
     async fn handle_duplicated_requests<
         State: IdempotencyTable
+            + InvocationIndexTable
             + InvocationStatusTable
             + VirtualObjectStatusTable
             + OutboxTable
@@ -437,41 +725,73 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         }
 
         let previous_invocation_status = async {
-            let mut invocation_status = ctx.get_invocation_status(&invocation_id).await?;
+            let invocation_status = ctx.get_invocation_status(&invocation_id).await?;
             if invocation_status != InvocationStatus::Free {
                 // Deduplicated invocation with the new deterministic invocation id
-              Ok::<_, Error>(invocation_status)
-            } else {
-                // We might still need to deduplicate based on the idempotency table for old invocation ids
-                // TODO get rid of this code when we remove the idempotency table
-                if has_idempotency_key {
-                    let idempotency_id = service_invocation
-                        .compute_idempotency_id()
-                        .expect("Idempotency key must be present");
+                return Ok::<_, Error>(invocation_status);
+            }
 
-                    if let Some(idempotency_metadata) = ctx.storage.get_idempotency_metadata(&idempotency_id).await? {
-                        invocation_status = ctx.get_invocation_status(&idempotency_metadata.invocation_id).await?;
-                    }
+            // The invocation might have been indexed under a different invocation id. Only the
+            // status of the last indexed invocation is read, since it's the one we deduplicate on.
+            let mut indexed_invocation_id = None;
+            for lookup_key in InvocationLookupKey::for_invocation(
+                invocation_id,
+                &service_invocation.invocation_target,
+                service_invocation.idempotency_key.as_ref(),
+            ) {
+                if let Some(entry) = ctx.storage.get_invocation_index_entry(&lookup_key).await? {
+                    indexed_invocation_id = Some(entry.invocation_id);
                 }
-                // Or on lock status for workflow runs with old invocation ids
-                // TODO get rid of this code when we remove the usage of the virtual object table for workflows
-                if is_workflow_run {
-                    let keyed_service_id = service_invocation
-                        .invocation_target
-                        .as_keyed_service_id()
-                        .expect("When the handler type is Workflow, the invocation target must have a key");
+            }
+            if let Some(indexed_invocation_id) = indexed_invocation_id {
+                let invocation_status = ctx.get_invocation_status(&indexed_invocation_id).await?;
+                if invocation_status != InvocationStatus::Free {
+                    return Ok(invocation_status);
+                }
+            }
 
-                    if let VirtualObjectStatus::Locked(locked_invocation_id) = ctx
-                        .storage
-                        .get_virtual_object_status(&keyed_service_id)
-                        .await? {
-                        invocation_status = ctx.get_invocation_status(&locked_invocation_id).await?;
-                    }
+            let mut previous_invocation_id = None;
+            // We might still need to deduplicate based on the idempotency table for old invocation ids
+            // TODO get rid of this code when we remove the idempotency table
+            if has_idempotency_key {
+                let idempotency_id = service_invocation
+                    .compute_idempotency_id()
+                    .expect("Idempotency key must be present");
+
+                if let Some(idempotency_metadata) = ctx
+                    .storage
+                    .get_idempotency_metadata(&idempotency_id)
+                    .await?
+                {
+                    previous_invocation_id = Some(idempotency_metadata.invocation_id);
                 }
-                Ok(invocation_status)
             }
+            // Or on lock status for workflow runs with old invocation ids
+            // TODO get rid of this code when we remove the usage of the virtual object table for workflows
+            if is_workflow_run {
+                let keyed_service_id = service_invocation
+                    .invocation_target
+                    .as_keyed_service_id()
+                    .expect(
+                        "When the handler type is Workflow, the invocation target must have a key",
+                    );
 
-        }.await?;
+                if let VirtualObjectStatus::Locked(locked_invocation_id) = ctx
+                    .storage
+                    .get_virtual_object_status(&keyed_service_id)
+                    .await?
+                {
+                    previous_invocation_id = Some(locked_invocation_id);
+                }
+            }
+            match previous_invocation_id {
+                Some(previous_invocation_id) => {
+                    ctx.get_invocation_status(&previous_invocation_id).await
+                }
+                None => Ok(InvocationStatus::Free),
+            }
+        }
+        .await?;
 
         if previous_invocation_status == InvocationStatus::Free {
             // --- New invocation
@@ -480,6 +800,14 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 "First time we see this invocation id, invocation will be processed"
             );
 
+            Self::do_index_invocation(
+                ctx,
+                invocation_id,
+                &service_invocation.invocation_target,
+                service_invocation.idempotency_key.as_ref(),
+            )
+            .await;
+
             // Store the invocation id mapping if we have to and continue the processing
             // TODO get rid of this code when we remove the usage of the virtual object table for workflows
             if is_workflow_run && !self.disable_idempotency_table {
@@ -591,18 +919,29 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         metadata: PreFlightInvocationMetadata,
+        execution_wall_clock_time: Option<ZonedDateTime>,
     ) -> Result<Option<PreFlightInvocationMetadata>, Error> {
         if let Some(execution_time) = metadata.execution_time {
             let span_context = metadata.span_context.clone();
             debug_if_leader!(ctx.is_leader, "Store scheduled invocation");
 
-            Self::register_timer(
-                ctx,
-                TimerKeyValue::neo_invoke(execution_time, invocation_id),
-                span_context,
-            )
-            .await?;
+            // Keep the wall-clock time next to the resolved wake up time so that it can be
+            // re-resolved if the timezone rules change.
+            let timer = match execution_wall_clock_time {
+                Some(local_time) => TimerKeyValue::neo_invoke_at_local_time(
+                    execution_time,
+                    invocation_id,
+                    local_time,
+                ),
+                None => TimerKeyValue::neo_invoke(execution_time, invocation_id),
+            };
 
+            Self::register_timer(ctx, timer, span_context).await?;
+
+            ctx.record_invocation_event(
+                invocation_id,
+                InvocationEventKind::Scheduled { execution_time },
+            );
             ctx.storage
                 .put_invocation_status(
                     &invocation_id.clone(),
@@ -618,8 +957,63 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(Some(metadata))
     }
 
+    /// Returns the invocation in case its service is not paused. Otherwise, the invocation is
+    /// stored as inboxed in the paused invocations of the service, and it starts once the
+    /// service is resumed.
+    async fn handle_service_invocation_paused_service<
+        State: InvocationStatusTable + InboxTable + FsmTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        metadata: PreFlightInvocationMetadata,
+    ) -> Result<Option<PreFlightInvocationMetadata>, Error> {
+        if !ctx
+            .storage
+            .get_paused_services()
+            .await?
+            .services
+            .contains(metadata.invocation_target.service_name())
+        {
+            return Ok(Some(metadata));
+        }
+
+        let inbox_seq_number = self.inbox_seq_number;
+        ctx.storage.put_inbox_seq_number(inbox_seq_number + 1).await;
+        self.inbox_seq_number += 1;
+
+        debug_if_leader!(
+            ctx.is_leader,
+            rpc.service = %metadata.invocation_target.service_name(),
+            restate.inbox.seq = inbox_seq_number,
+            "Service is paused, store pending invocation"
+        );
+        ctx.storage
+            .put_paused_invocation(
+                inbox_seq_number,
+                &InboxEntry::Invocation(
+                    paused_service_id(invocation_id, &metadata.invocation_target),
+                    invocation_id,
+                    metadata.priority,
+                ),
+            )
+            .await;
+        ctx.record_invocation_event(invocation_id, InvocationEventKind::Inboxed);
+        ctx.storage
+            .put_invocation_status(
+                &invocation_id,
+                &InvocationStatus::Inboxed(InboxedInvocation::from_pre_flight_invocation_metadata(
+                    metadata,
+                    inbox_seq_number,
+                )),
+            )
+            .await;
+
+        Ok(None)
+    }
+
     /// Returns the invocation in case the invocation was not inboxed
-    async fn handle_service_invocation_exclusive_handler<
+    async fn handle_service_invocation_virtual_object_handler<
         State: VirtualObjectStatusTable + InvocationStatusTable + InboxTable + FsmTable,
     >(
         &mut self,
@@ -627,6 +1021,14 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         invocation_id: InvocationId,
         metadata: PreFlightInvocationMetadata,
     ) -> Result<Option<PreFlightInvocationMetadata>, Error> {
+        if metadata.invocation_target.invocation_target_ty()
+            == InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Shared)
+        {
+            return self
+                .handle_service_invocation_shared_handler(ctx, invocation_id, metadata)
+                .await;
+        }
+
         if metadata.invocation_target.invocation_target_ty()
             == InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Exclusive)
         {
@@ -644,7 +1046,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 let inbox_seq_number = self
                     .enqueue_into_inbox(
                         ctx,
-                        InboxEntry::Invocation(keyed_service_id, invocation_id),
+                        InboxEntry::Invocation(keyed_service_id, invocation_id, metadata.priority),
                     )
                     .await?;
 
@@ -653,6 +1055,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     restate.outbox.seq = inbox_seq_number,
                     "Store inboxed invocation"
                 );
+                ctx.record_invocation_event(invocation_id, InvocationEventKind::Inboxed);
                 ctx.storage
                     .put_invocation_status(
                         &invocation_id,
@@ -685,7 +1088,74 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(Some(metadata))
     }
 
-    async fn init_journal_and_invoke<State: JournalTable + InvocationStatusTable>(
+    /// Returns the invocation in case the shared concurrency limit of the virtual object key
+    /// allows to start it. Otherwise, the invocation is stored as inboxed without an inbox entry,
+    /// and it starts once a running invocation of a shared handler of the same key completes.
+    async fn handle_service_invocation_shared_handler<
+        State: VirtualObjectStatusTable + InvocationStatusTable + FsmTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        metadata: PreFlightInvocationMetadata,
+    ) -> Result<Option<PreFlightInvocationMetadata>, Error> {
+        let Some(shared_concurrency_limit) = metadata.shared_concurrency_limit else {
+            return Ok(Some(metadata));
+        };
+        let keyed_service_id = metadata
+            .invocation_target
+            .as_keyed_service_id()
+            .expect("When the handler type is Shared, the invocation target must have a key");
+
+        let mut executions = ctx
+            .storage
+            .get_shared_handler_executions(&keyed_service_id)
+            .await?;
+
+        // Pending invocations start first
+        if executions.pending.is_empty()
+            && executions.running.len() < shared_concurrency_limit.get() as usize
+        {
+            executions.running.push(invocation_id);
+            ctx.storage
+                .put_shared_handler_executions(&keyed_service_id, &executions)
+                .await;
+            return Ok(Some(metadata));
+        }
+
+        // The sequence number is reserved only to identify the inboxed invocation
+        let inbox_seq_number = self.inbox_seq_number;
+        ctx.storage.put_inbox_seq_number(inbox_seq_number + 1).await;
+        self.inbox_seq_number += 1;
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.service.id = %keyed_service_id,
+            "Shared handler concurrency limit {} reached, store pending invocation",
+            shared_concurrency_limit
+        );
+
+        executions.pending.push_back(invocation_id);
+        ctx.storage
+            .put_shared_handler_executions(&keyed_service_id, &executions)
+            .await;
+        ctx.record_invocation_event(invocation_id, InvocationEventKind::Inboxed);
+        ctx.storage
+            .put_invocation_status(
+                &invocation_id,
+                &InvocationStatus::Inboxed(InboxedInvocation::from_pre_flight_invocation_metadata(
+                    metadata,
+                    inbox_seq_number,
+                )),
+            )
+            .await;
+
+        Ok(None)
+    }
+
+    async fn init_journal_and_invoke<
+        State: JournalTable + journal_table_v2::JournalTableV2 + InvocationStatusTable,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         mut in_flight_invocation_metadata: InFlightInvocationMetadata,
@@ -708,7 +1178,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         .await
     }
 
-    async fn init_journal<State: JournalTable>(
+    async fn init_journal<State: JournalTable + journal_table_v2::JournalTableV2>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         in_flight_invocation_metadata: &mut InFlightInvocationMetadata,
@@ -716,17 +1186,30 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     ) -> Result<InvokeInputJournal, Error> {
         debug_if_leader!(ctx.is_leader, "Init journal with input entry");
 
+        // The invocation starts running now, hence so does its execution timeout. The timeout is
+        // resolved with the schema known to this replica, which is fine since only the invoker of
+        // the leader enforces it.
+        let execution_timeout = Metadata::try_with_current(|metadata| {
+            let invocation_target = &in_flight_invocation_metadata.invocation_target;
+            metadata.schema_ref().resolve_execution_timeout(
+                invocation_target.service_name(),
+                invocation_target.handler_name(),
+            )
+        })
+        .flatten();
+        in_flight_invocation_metadata.start_execution_timeout(execution_timeout);
+
         // In our current data model, ServiceInvocation has always an input, so initial length is 1
+        let journal_version = ctx.journal_version;
         in_flight_invocation_metadata.journal_metadata.length = 1;
+        in_flight_invocation_metadata.journal_metadata.version = journal_version;
 
         let input_entry = JournalEntry::Entry(Codec::serialize_as_input_entry(
             invocation_input.headers,
             invocation_input.argument,
         ));
 
-        ctx.storage
-            .put_journal_entry(&invocation_id, 0, &input_entry)
-            .await;
+        Self::write_journal_entry(ctx, journal_version, &invocation_id, 0, &input_entry).await?;
 
         let_assert!(JournalEntry::Entry(input_entry) = input_entry);
 
@@ -737,9 +1220,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     .journal_metadata
                     .span_context
                     .clone(),
-                None,
+                in_flight_invocation_metadata.pinned_deployment.clone(),
                 // This is safe to do as only the leader will execute the invoker command
                 MillisSinceEpoch::now(),
+                in_flight_invocation_metadata.dry_run,
             ),
             vec![input_entry.erase_enrichment()],
         ))
@@ -753,9 +1237,21 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     ) -> Result<(), Error> {
         debug_if_leader!(ctx.is_leader, "Invoke");
 
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::Started {
+                attempt: in_flight_invocation_metadata.retry_count + 1,
+                deployment_id: in_flight_invocation_metadata
+                    .pinned_deployment
+                    .as_ref()
+                    .map(|pinned_deployment| pinned_deployment.deployment_id),
+            },
+        );
         ctx.action_collector.push(Action::Invoke {
             invocation_id,
             invocation_target: in_flight_invocation_metadata.invocation_target.clone(),
+            invocation_epoch: in_flight_invocation_metadata.invocation_epoch,
+            execution_deadline: in_flight_invocation_metadata.execution_deadline,
             invoke_input_journal,
         });
         ctx.storage
@@ -817,8 +1313,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + FsmTable
             + StateTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
-            + TimerTable,
+            + TimerTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -833,35 +1332,152 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         }
     }
 
-    async fn try_kill_invocation<
+    /// Terminates all invoked, suspended and inboxed invocations of this partition whose target
+    /// matches the filter.
+    async fn terminate_invocations_by_target<
         State: VirtualObjectStatusTable
             + InvocationStatusTable
             + InboxTable
             + FsmTable
             + StateTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
-            + FsmTable,
+            + TimerTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
-        invocation_id: InvocationId,
+        BulkInvocationTermination { filter, flavor }: BulkInvocationTermination,
     ) -> Result<(), Error> {
-        let status = ctx.get_invocation_status(&invocation_id).await?;
+        // Collect the matching invocations first, the termination mutates the status table
+        let invocation_ids: Vec<_> = ctx
+            .storage
+            .all_invocation_statuses(self.partition_key_range.clone())
+            .try_filter_map(|(invocation_id, status)| {
+                let matches = matches!(
+                    status,
+                    InvocationStatus::Invoked(_)
+                        | InvocationStatus::Suspended { .. }
+                        | InvocationStatus::Inboxed(_)
+                ) && status
+                    .invocation_target()
+                    .is_some_and(|target| filter.matches(target));
+                futures::future::ready(Ok(matches.then_some(invocation_id)))
+            })
+            .try_collect()
+            .await?;
 
-        match status {
-            InvocationStatus::Invoked(metadata) | InvocationStatus::Suspended { metadata, .. } => {
-                self.kill_invocation(ctx, invocation_id, metadata).await?;
-            }
-            InvocationStatus::Inboxed(inboxed) => {
-                self.terminate_inboxed_invocation(
-                    ctx,
-                    TerminationFlavor::Kill,
+        debug!(
+            "Terminating {} invocations of service '{}' with flavor {:?}",
+            invocation_ids.len(),
+            filter.service_name,
+            flavor
+        );
+        for invocation_id in invocation_ids {
+            self.try_terminate_invocation(
+                ctx,
+                InvocationTermination {
                     invocation_id,
-                    inboxed,
-                )
-                .await?
-            }
+                    flavor,
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Cancels, kills or purges the given invocations. Invocations in a state the operation
+    /// doesn't apply to are ignored, like when applying the respective single command.
+    async fn apply_bulk_invocation_operation<
+        State: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InboxTable
+            + FsmTable
+            + StateTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2
+            + OutboxTable
+            + TimerTable
+            + IdempotencyTable
+            + InvocationIndexTable
+            + PromiseTable
+            + InvocationEventTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        BulkInvocationOperation {
+            invocation_ids,
+            operation,
+        }: BulkInvocationOperation,
+    ) -> Result<(), Error> {
+        debug!(
+            "Applying {:?} to {} invocations",
+            operation,
+            invocation_ids.len()
+        );
+        for invocation_id in invocation_ids {
+            if !self
+                .partition_key_range
+                .contains(&invocation_id.partition_key())
+            {
+                warn!(
+                    "Ignoring bulk operation on invocation '{invocation_id}' of another partition"
+                );
+                continue;
+            }
+            match operation {
+                InvocationOperation::Terminate(flavor) => {
+                    self.try_terminate_invocation(
+                        ctx,
+                        InvocationTermination {
+                            invocation_id,
+                            flavor,
+                        },
+                    )
+                    .await?
+                }
+                InvocationOperation::Purge => self.try_purge_invocation(ctx, invocation_id).await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn try_kill_invocation<
+        State: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InboxTable
+            + FsmTable
+            + StateTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2
+            + OutboxTable
+            + FsmTable
+            + InvocationIndexTable
+            + InvocationEventTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+    ) -> Result<(), Error> {
+        let status = ctx.get_invocation_status(&invocation_id).await?;
+
+        match status {
+            InvocationStatus::Invoked(metadata) | InvocationStatus::Suspended { metadata, .. } => {
+                self.kill_invocation(ctx, invocation_id, metadata).await?;
+            }
+            InvocationStatus::Inboxed(inboxed) => {
+                self.terminate_inboxed_invocation(
+                    ctx,
+                    TerminationFlavor::Kill,
+                    invocation_id,
+                    inboxed,
+                )
+                .await?
+            }
             _ => {
                 trace!("Received kill command for unknown invocation with id '{invocation_id}'.");
                 // We still try to send the abort signal to the invoker,
@@ -884,8 +1500,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + FsmTable
             + StateTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
-            + TimerTable,
+            + TimerTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -899,7 +1518,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     ctx,
                     invocation_id,
                     InvocationStatusProjection::Invoked,
-                    metadata.journal_metadata.length,
+                    &metadata.journal_metadata,
                 )
                 .await?;
             }
@@ -912,7 +1531,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                         ctx,
                         invocation_id,
                         InvocationStatusProjection::Suspended(waiting_for_completed_entries),
-                        metadata.journal_metadata.length,
+                        &metadata.journal_metadata,
                     )
                     .await?
                 {
@@ -944,7 +1563,12 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     }
 
     async fn terminate_inboxed_invocation<
-        State: InvocationStatusTable + InboxTable + OutboxTable + FsmTable,
+        State: InvocationStatusTable
+            + InvocationIndexTable
+            + InboxTable
+            + OutboxTable
+            + FsmTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -964,6 +1588,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     response_sinks,
                     span_context,
                     invocation_target,
+                    idempotency_key,
                     ..
                 },
         } = inboxed_invocation;
@@ -979,16 +1604,25 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         )
         .await?;
 
-        // Delete inbox entry and invocation status.
-        Self::do_delete_inbox_entry(
+        // Delete inbox entry and invocation status. Invocations of paused services are inboxed
+        // in the paused invocations of the service, and they might not have a keyed service id.
+        ctx.storage
+            .delete_paused_invocation(
+                &paused_service_id(invocation_id, &invocation_target),
+                inbox_sequence_number,
+            )
+            .await;
+        if let Some(keyed_service_id) = invocation_target.as_keyed_service_id() {
+            Self::do_delete_inbox_entry(ctx, keyed_service_id, inbox_sequence_number).await?;
+        }
+        Self::do_free_invocation(ctx, invocation_id).await?;
+        Self::do_unindex_invocation(
             ctx,
-            invocation_target
-                .as_keyed_service_id()
-                .expect("Because the invocation is inboxed, it must have a keyed service id"),
-            inbox_sequence_number,
+            invocation_id,
+            &invocation_target,
+            idempotency_key.as_ref(),
         )
         .await?;
-        Self::do_free_invocation(ctx, invocation_id).await;
 
         self.notify_invocation_result(
             ctx,
@@ -1009,15 +1643,18 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + VirtualObjectStatusTable
             + StateTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
-            + FsmTable,
+            + FsmTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         metadata: InFlightInvocationMetadata,
     ) -> Result<(), Error> {
-        self.kill_child_invocations(ctx, &invocation_id, metadata.journal_metadata.length)
+        self.kill_child_invocations(ctx, &invocation_id, &metadata.journal_metadata)
             .await?;
 
         self.fail_invocation(ctx, invocation_id, metadata, KILLED_INVOCATION_ERROR)
@@ -1026,35 +1663,37 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
-    async fn kill_child_invocations<State: OutboxTable + FsmTable + ReadOnlyJournalTable>(
+    async fn kill_child_invocations<
+        State: OutboxTable + FsmTable + ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: &InvocationId,
-        journal_length: EntryIndex,
+        journal_metadata: &JournalMetadata,
     ) -> Result<(), Error> {
-        let invocation_ids_to_kill: Vec<InvocationId> = ctx
-            .storage
-            .get_journal(invocation_id, journal_length)
-            .try_filter_map(|(_, journal_entry)| async {
-                if let JournalEntry::Entry(enriched_entry) = journal_entry {
-                    let (h, _) = enriched_entry.into_inner();
-                    match h {
-                        // we only need to kill child invocations if they are not completed and the target was resolved
-                        EnrichedEntryHeader::Call {
-                            is_completed,
-                            enrichment_result: Some(enrichment_result),
-                        } if !is_completed => return Ok(Some(enrichment_result.invocation_id)),
-                        // we neither kill background calls nor delayed calls since we are considering them detached from this
-                        // call tree. In the future we want to support a mode which also kills these calls (causally related).
-                        // See https://github.com/restatedev/restate/issues/979
-                        _ => {}
+        let invocation_ids_to_kill: Vec<InvocationId> =
+            Self::read_journal(ctx, invocation_id, journal_metadata)
+                .await?
+                .into_iter()
+                .filter_map(|(_, journal_entry)| {
+                    if let JournalEntry::Entry(enriched_entry) = journal_entry {
+                        let (h, _) = enriched_entry.into_inner();
+                        match h {
+                            // we only need to kill child invocations if they are not completed and the target was resolved
+                            EnrichedEntryHeader::Call {
+                                is_completed,
+                                enrichment_result: Some(enrichment_result),
+                            } if !is_completed => return Some(enrichment_result.invocation_id),
+                            // we neither kill background calls nor delayed calls since we are considering them detached from this
+                            // call tree. In the future we want to support a mode which also kills these calls (causally related).
+                            // See https://github.com/restatedev/restate/issues/979
+                            _ => {}
+                        }
                     }
-                }
 
-                Ok(None)
-            })
-            .try_collect()
-            .await?;
+                    None
+                })
+                .collect();
 
         for id in invocation_ids_to_kill {
             self.handle_outgoing_message(
@@ -1067,30 +1706,33 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
-    async fn cancel_journal_leaves<State: JournalTable + OutboxTable + FsmTable + TimerTable>(
+    async fn cancel_journal_leaves<
+        State: JournalTable + journal_table_v2::JournalTableV2 + OutboxTable + FsmTable + TimerTable,
+    >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         invocation_status: InvocationStatusProjection,
-        journal_length: EntryIndex,
+        journal_metadata: &JournalMetadata,
     ) -> Result<bool, Error> {
-        let journal_entries_to_cancel: Vec<(EntryIndex, EnrichedRawEntry)> = ctx
-            .storage
-            .get_journal(&invocation_id, journal_length)
-            .try_filter_map(|(journal_index, journal_entry)| async move {
-                if let JournalEntry::Entry(journal_entry) = journal_entry {
-                    if let Some(is_completed) = journal_entry.header().is_completed() {
-                        if !is_completed {
-                            // Every completable journal entry that hasn't been completed yet should be cancelled
-                            return Ok(Some((journal_index, journal_entry)));
+        let journal_version = journal_metadata.version;
+        let journal_entries_to_cancel: Vec<(EntryIndex, EnrichedRawEntry)> =
+            Self::read_journal(ctx, &invocation_id, journal_metadata)
+                .await?
+                .into_iter()
+                .filter_map(|(journal_index, journal_entry)| {
+                    if let JournalEntry::Entry(journal_entry) = journal_entry {
+                        if let Some(is_completed) = journal_entry.header().is_completed() {
+                            if !is_completed {
+                                // Every completable journal entry that hasn't been completed yet should be cancelled
+                                return Some((journal_index, journal_entry));
+                            }
                         }
                     }
-                }
 
-                Ok(None)
-            })
-            .try_collect()
-            .await?;
+                    None
+                })
+                .collect();
 
         let canceled_result = CompletionResult::from(&CANCELED_INVOCATION_ERROR);
 
@@ -1116,6 +1758,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     resume_invocation |= Self::cancel_journal_entry_with(
                         ctx,
                         invocation_id,
+                        journal_version,
                         &invocation_status,
                         journal_index,
                         canceled_result.clone(),
@@ -1137,6 +1780,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     resume_invocation |= Self::cancel_journal_entry_with(
                         ctx,
                         invocation_id,
+                        journal_version,
                         &invocation_status,
                         journal_index,
                         canceled_result.clone(),
@@ -1150,9 +1794,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     }
 
     /// Cancels a generic completable journal entry
-    async fn cancel_journal_entry_with<State: JournalTable>(
+    async fn cancel_journal_entry_with<State: JournalTable + journal_table_v2::JournalTableV2>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
+        journal_version: JournalVersion,
         invocation_status: &InvocationStatusProjection,
         journal_index: EntryIndex,
         canceled_result: CompletionResult,
@@ -1162,6 +1807,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 Self::handle_completion_for_invoked(
                     ctx,
                     invocation_id,
+                    journal_version,
                     Completion::new(journal_index, canceled_result),
                 )
                 .await?;
@@ -1171,6 +1817,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 Self::handle_completion_for_suspended(
                     ctx,
                     invocation_id,
+                    journal_version,
                     Completion::new(journal_index, canceled_result),
                     waiting_for_completed_entry,
                 )
@@ -1182,21 +1829,32 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     async fn try_purge_invocation<
         State: InvocationStatusTable
             + IdempotencyTable
+            + InvocationIndexTable
             + VirtualObjectStatusTable
             + StateTable
-            + PromiseTable,
+            + PromiseTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
     ) -> Result<(), Error> {
-        match ctx.get_invocation_status(&invocation_id).await? {
-            InvocationStatus::Completed(CompletedInvocation {
-                invocation_target,
-                idempotency_key,
-                ..
-            }) => {
-                Self::do_free_invocation(ctx, invocation_id).await;
+        // Purging only needs the target and the idempotency key, not the result
+        let view = ctx.get_invocation_status_view(&invocation_id).await?;
+        match view.kind {
+            InvocationStatusKind::Completed => {
+                let invocation_target = view
+                    .invocation_target
+                    .expect("completed invocations have an invocation target");
+                let idempotency_key = view.idempotency_key;
+                Self::do_free_invocation(ctx, invocation_id).await?;
+                Self::do_unindex_invocation(
+                    ctx,
+                    invocation_id,
+                    &invocation_target,
+                    idempotency_key.as_ref(),
+                )
+                .await?;
 
                 // Also cleanup the associated idempotency key if any
                 if let Some(idempotency_key) = idempotency_key {
@@ -1220,7 +1878,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     Self::do_clear_all_promises(ctx, service_id).await?;
                 }
             }
-            InvocationStatus::Free => {
+            InvocationStatusKind::Free => {
                 trace!("Received purge command for unknown invocation with id '{invocation_id}'.");
                 // Nothing to do
             }
@@ -1234,56 +1892,519 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
-    async fn on_timer<
+    /// Re-pins the invoked and suspended invocations of the deployment to the target deployment
+    /// of their service. Invoked invocations are retried right away on the new deployment, while
+    /// suspended ones use it once resumed.
+    async fn on_migrate_deployment<
+        State: InvocationStatusTable + journal_table_v2::JournalTableV2,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        DeploymentMigration {
+            deployment_id,
+            targets,
+        }: DeploymentMigration,
+    ) -> Result<(), Error> {
+        // Collect the pinned invocations first, the migration mutates the status table
+        let invocations: Vec<_> = ctx
+            .storage
+            .all_invocation_statuses(self.partition_key_range.clone())
+            .try_filter_map(|(invocation_id, status)| {
+                let is_pinned = match &status {
+                    InvocationStatus::Invoked(metadata)
+                    | InvocationStatus::Suspended { metadata, .. } => metadata
+                        .pinned_deployment
+                        .as_ref()
+                        .is_some_and(|pinned| pinned.deployment_id == deployment_id),
+                    _ => false,
+                };
+                futures::future::ready(Ok(is_pinned.then_some((invocation_id, status))))
+            })
+            .try_collect()
+            .await?;
+
+        let mut not_migrated = 0;
+        for (invocation_id, mut status) in invocations {
+            let Some(metadata) = status.get_invocation_metadata_mut() else {
+                continue;
+            };
+            let new_pinned_deployment = targets
+                .iter()
+                .find(|target| target.service_name == *metadata.invocation_target.service_name())
+                .zip(metadata.pinned_deployment.as_ref())
+                .and_then(|(target, pinned_deployment)| target.repin(pinned_deployment));
+            let Some(new_pinned_deployment) = new_pinned_deployment else {
+                not_migrated += 1;
+                continue;
+            };
+
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.invocation.id = %invocation_id,
+                restate.deployment.id = %new_pinned_deployment.deployment_id,
+                "Effect: Re-pin invocation to deployment"
+            );
+            metadata.pinned_deployment = Some(new_pinned_deployment);
+            match status {
+                InvocationStatus::Invoked(metadata) => {
+                    Self::do_send_abort_invocation_to_invoker(ctx, invocation_id);
+                    Self::do_resume_service(ctx, invocation_id, metadata).await?;
+                }
+                status => {
+                    ctx.storage
+                        .put_invocation_status(&invocation_id, &status)
+                        .await;
+                }
+            }
+        }
+
+        if not_migrated > 0 {
+            warn!(
+                "{not_migrated} invocations pinned to the deployment '{deployment_id}' could not be migrated, as no other deployment of their service supports their service protocol version"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Applies the repair of a benign inconsistency between the tables, unless the inconsistency
+    /// doesn't hold anymore.
+    async fn on_repair_consistency<State: InvocationStatusTable + InboxTable + IdempotencyTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        repair: ConsistencyRepair,
+    ) -> Result<(), Error> {
+        match repair {
+            ConsistencyRepair::DeleteInboxEntry {
+                service_id,
+                inbox_sequence_number,
+                invocation_id,
+            } => {
+                if ctx.get_invocation_status_view(&invocation_id).await?.kind
+                    == InvocationStatusKind::Inboxed
+                {
+                    trace!(
+                        "Ignoring inbox entry repair, the invocation '{invocation_id}' is inboxed."
+                    );
+                    return Ok(());
+                }
+                // The entry might be an invocation enqueued while its service is paused
+                ctx.storage
+                    .delete_paused_invocation(&service_id, inbox_sequence_number)
+                    .await;
+                Self::do_delete_inbox_entry(ctx, service_id, inbox_sequence_number).await?;
+            }
+            ConsistencyRepair::DeleteIdempotencyKey {
+                idempotency_id,
+                invocation_id,
+            } => {
+                let points_at_invocation = ctx
+                    .storage
+                    .get_idempotency_metadata(&idempotency_id)
+                    .await?
+                    .is_some_and(|metadata| metadata.invocation_id == invocation_id);
+                if !points_at_invocation
+                    || !ctx
+                        .get_invocation_status_view(&invocation_id)
+                        .await?
+                        .is_free()
+                {
+                    trace!(
+                        "Ignoring idempotency key repair, the invocation '{invocation_id}' exists."
+                    );
+                    return Ok(());
+                }
+                Self::do_delete_idempotency_id(ctx, idempotency_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hands off the message to the partition owning its partition key, see
+    /// [`restate_storage_api::prepared_message_table`].
+    async fn prepare_message<State: PreparedMessageTable + FsmTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        message: OutboxMessage,
+    ) -> Result<(), Error> {
+        let sequence_number = ctx.storage.get_prepared_message_seq_number().await?;
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.prepared_message.seq = sequence_number,
+            "Effect: Hand off message to the partition owning partition key {}",
+            message.partition_key()
+        );
+
+        ctx.storage
+            .put_prepared_message(sequence_number, &message)
+            .await;
+        ctx.storage
+            .put_prepared_message_seq_number(sequence_number + 1)
+            .await;
+        ctx.action_collector.push(Action::DeliverPreparedMessages);
+
+        Ok(())
+    }
+
+    async fn on_acknowledge_prepared_message<State: PreparedMessageTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        sequence_number: MessageIndex,
+    ) -> Result<(), Error> {
+        if ctx
+            .storage
+            .get_prepared_message(sequence_number)
+            .await?
+            .is_none()
+        {
+            trace!("Ignoring acknowledgement of unknown prepared message {sequence_number}");
+            return Ok(());
+        }
+        ctx.storage.delete_prepared_message(sequence_number).await;
+
+        Ok(())
+    }
+
+    /// Retries an invoked or suspended invocation right away. When restarting, the journal is
+    /// truncated to the input entry first. State changes and calls of the previous attempts are
+    /// not rolled back.
+    async fn try_retry_invocation<
+        State: InvocationStatusTable + JournalTable + journal_table_v2::JournalTableV2,
+    >(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        RetryInvocationRequest {
+            invocation_id,
+            restart,
+        }: RetryInvocationRequest,
+    ) -> Result<(), Error> {
+        let (mut metadata, is_invoked) = match ctx.get_invocation_status(&invocation_id).await? {
+            InvocationStatus::Invoked(metadata) => (metadata, true),
+            InvocationStatus::Suspended { metadata, .. } => (metadata, false),
+            InvocationStatus::Free => {
+                trace!("Received retry command for unknown invocation with id '{invocation_id}'.");
+                return Ok(());
+            }
+            _ => {
+                trace!(
+                    "Ignoring retry command as the invocation '{invocation_id}' is neither invoked nor suspended."
+                );
+                return Ok(());
+            }
+        };
+
+        if restart && !Self::do_truncate_journal_to_input(ctx, invocation_id, &mut metadata).await?
+        {
+            return Ok(());
+        }
+
+        if is_invoked {
+            // The invoker must drop the current attempt before it's invoked again
+            Self::do_send_abort_invocation_to_invoker(ctx, invocation_id);
+        }
+        // Effects of the discarded attempt which are still in flight are fenced off by the new
+        // epoch, as they would otherwise be applied to the journal of the new attempt
+        metadata.bump_invocation_epoch();
+        Self::do_resume_service(ctx, invocation_id, metadata).await
+    }
+
+    /// Truncates the journal to its input entry, unpinning the deployment so that the invocation
+    /// restarts on the latest one. Returns false, leaving the journal untouched, if some journal
+    /// entry is still waiting for its completion, as the completion could not be delivered anymore.
+    async fn do_truncate_journal_to_input<
+        State: JournalTable + journal_table_v2::JournalTableV2,
+    >(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        metadata: &mut InFlightInvocationMetadata,
+    ) -> Result<bool, Error> {
+        let journal_length = metadata.journal_metadata.length;
+        let journal_version = metadata.journal_metadata.version;
+        let journal = Self::read_journal(ctx, &invocation_id, &metadata.journal_metadata).await?;
+
+        if journal
+            .iter()
+            .any(|(_, journal_entry)| !journal_entry.is_resumable())
+        {
+            warn!(
+                "Ignoring restart command as the invocation '{invocation_id}' is waiting for the completion of some journal entry."
+            );
+            return Ok(false);
+        }
+        let Some((_, input_entry)) = journal.into_iter().find(|(index, _)| *index == 0) else {
+            warn!(
+                "Ignoring restart command as the invocation '{invocation_id}' has no input entry."
+            );
+            return Ok(false);
+        };
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.journal.length = journal_length,
+            "Effect: Truncate journal to input entry"
+        );
+
+        Self::remove_journal(ctx, journal_version, &invocation_id, journal_length).await?;
+        Self::write_journal_entry(ctx, journal_version, &invocation_id, 0, &input_entry).await?;
+        metadata.journal_metadata.length = 1;
+        metadata.pinned_deployment = None;
+
+        Ok(true)
+    }
+
+    async fn on_timer<
+        State: IdempotencyTable
+            + InvocationIndexTable
+            + InvocationStatusTable
+            + OutboxTable
+            + FsmTable
+            + VirtualObjectStatusTable
+            + TimerTable
+            + InboxTable
+            + FsmTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2
+            + TimerTable
+            + PromiseTable
+            + StateTable
+            + ScheduleTable
+            + InvocationEventTable,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        timer_value: TimerKeyValue,
+    ) -> Result<(), Error> {
+        let wake_up_time = timer_value.wake_up_time();
+        let (key, value) = timer_value.into_inner();
+        Self::do_delete_timer(ctx, key).await?;
+
+        match value {
+            Timer::CompleteJournalEntry(invocation_id, entry_index) => {
+                Self::handle_completion(
+                    ctx,
+                    invocation_id,
+                    Completion {
+                        entry_index,
+                        result: CompletionResult::Empty,
+                    },
+                )
+                .await
+            }
+            Timer::Invoke(mut service_invocation) => {
+                // Remove the execution time from the service invocation request
+                service_invocation.execution_time = None;
+                service_invocation.execution_wall_clock_time = None;
+
+                // ServiceInvocations scheduled with a timer are always owned by the same partition processor
+                // where the invocation should be executed
+                self.on_service_invocation(ctx, service_invocation).await
+            }
+            Timer::CleanInvocationStatus(invocation_id) => {
+                self.try_purge_invocation(ctx, invocation_id).await
+            }
+            Timer::NeoInvoke(invocation_id) | Timer::NeoInvokeAtLocalTime(invocation_id, _) => {
+                self.on_neo_invoke_timer(ctx, invocation_id).await
+            }
+            // Timers of schedules fired before the fire time was agreed are considered on time
+            Timer::FireSchedule(schedule_id) => {
+                self.on_fire_schedule(ctx, schedule_id, wake_up_time, wake_up_time)
+                    .await
+            }
+        }
+    }
+
+    async fn on_upsert_schedule<State: ScheduleTable + TimerTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        schedule: Schedule,
+    ) -> Result<(), Error> {
+        let schedule_id = schedule.id;
+        let previous = ctx.storage.get_schedule(&schedule_id).await?;
+        if let Some(previous) = &previous {
+            // The replaced schedule must not fire anymore
+            let (timer_key, _) =
+                Timer::fire_schedule(previous.next_fire_time.as_u64(), schedule_id);
+            Self::do_delete_timer(ctx, timer_key).await?;
+        }
+
+        let Some(next_fire_time) = schedule.first_fire_time() else {
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.schedule.id = %schedule_id,
+                "Ignoring schedule without occurrences"
+            );
+            ctx.storage.delete_schedule(&schedule_id).await;
+            return Ok(());
+        };
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.schedule.id = %schedule_id,
+            restate.invocation.target = %schedule.invocation.header.target,
+            "Effect: Upsert schedule '{}'",
+            schedule.cron
+        );
+        Self::register_timer(
+            ctx,
+            TimerKeyValue::fire_schedule(next_fire_time, schedule_id),
+            Default::default(),
+        )
+        .await?;
+        ctx.storage
+            .put_schedule(&ScheduleStatus {
+                schedule,
+                next_fire_time,
+                last_fire_time: previous.as_ref().and_then(|s| s.last_fire_time),
+                last_invocation_id: previous.and_then(|s| s.last_invocation_id),
+            })
+            .await;
+
+        Ok(())
+    }
+
+    async fn on_delete_schedule<State: ScheduleTable + TimerTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        schedule_id: ScheduleId,
+    ) -> Result<(), Error> {
+        let Some(schedule) = ctx.storage.get_schedule(&schedule_id).await? else {
+            trace!("Received delete command for unknown schedule with id '{schedule_id}'.");
+            return Ok(());
+        };
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.schedule.id = %schedule_id,
+            "Effect: Delete schedule"
+        );
+        let (timer_key, _) = Timer::fire_schedule(schedule.next_fire_time.as_u64(), schedule_id);
+        Self::do_delete_timer(ctx, timer_key).await?;
+        ctx.storage.delete_schedule(&schedule_id).await;
+
+        Ok(())
+    }
+
+    /// Hands the key range of the split over to the new partition, whose partition store was
+    /// created from the state of this partition right before the split. The timers of the key
+    /// range were copied to the new partition and are therefore removed from this partition.
+    async fn on_split_partition<State: TimerTable>(
+        &self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        split: SplitPartition,
+    ) -> Result<(), Error> {
+        if !self
+            .partition_key_range
+            .contains(split.child_key_range.start())
+            || !self
+                .partition_key_range
+                .contains(split.child_key_range.end())
+        {
+            debug_if_leader!(
+                ctx.is_leader,
+                "Ignoring split of key range {:?} which is not served by this partition",
+                split.child_key_range
+            );
+            return Ok(());
+        }
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.partition.child_id = %split.child_partition_id,
+            "Effect: Split off key range {:?}",
+            split.child_key_range
+        );
+        let timer_keys: Vec<_> = ctx
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .try_filter_map(|(timer_key, timer)| {
+                std::future::ready(Ok(split
+                    .child_key_range
+                    .contains(&timer.partition_key())
+                    .then_some(timer_key)))
+            })
+            .try_collect()
+            .await?;
+        for timer_key in timer_keys {
+            Self::do_delete_timer(ctx, timer_key).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Fires the invocation of the schedule occurrence at `fire_time`, and registers the timer
+    /// of the next occurrence. If the timer `fired_at` too late, the occurrences missed in the
+    /// meantime are handled according to the
+    /// [`MisfirePolicy`](restate_types::schedule::MisfirePolicy) of the schedule.
+    async fn on_fire_schedule<
         State: IdempotencyTable
+            + InvocationIndexTable
             + InvocationStatusTable
             + OutboxTable
             + FsmTable
             + VirtualObjectStatusTable
             + TimerTable
             + InboxTable
-            + FsmTable
             + JournalTable
-            + TimerTable
+            + journal_table_v2::JournalTableV2
             + PromiseTable
-            + StateTable,
+            + StateTable
+            + ScheduleTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
-        timer_value: TimerKeyValue,
+        schedule_id: ScheduleId,
+        fire_time: MillisSinceEpoch,
+        fired_at: MillisSinceEpoch,
     ) -> Result<(), Error> {
-        let (key, value) = timer_value.into_inner();
-        Self::do_delete_timer(ctx, key).await?;
+        let Some(mut schedule) = ctx.storage.get_schedule(&schedule_id).await? else {
+            warn!("Fired a timer for an unknown schedule. The schedule might have been deleted previously.");
+            return Ok(());
+        };
 
-        match value {
-            Timer::CompleteJournalEntry(invocation_id, entry_index) => {
-                Self::handle_completion(
+        let (fire, next_after) = schedule.schedule.on_fire(fire_time, fired_at);
+        let service_invocation = if fire {
+            let service_invocation = schedule.schedule.invocation_at(fire_time);
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.schedule.id = %schedule_id,
+                restate.invocation.id = %service_invocation.invocation_id,
+                "Fire schedule occurrence at {fire_time}"
+            );
+            schedule.last_fire_time = Some(fire_time);
+            schedule.last_invocation_id = Some(service_invocation.invocation_id);
+            Some(service_invocation)
+        } else {
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.schedule.id = %schedule_id,
+                "Skip schedule occurrence at {fire_time} missed until {fired_at}"
+            );
+            None
+        };
+
+        match schedule.schedule.cron.next_after(next_after) {
+            Some(next_fire_time) => {
+                schedule.next_fire_time = next_fire_time;
+                Self::register_timer(
                     ctx,
-                    invocation_id,
-                    Completion {
-                        entry_index,
-                        result: CompletionResult::Empty,
-                    },
+                    TimerKeyValue::fire_schedule(next_fire_time, schedule_id),
+                    Default::default(),
                 )
-                .await
+                .await?;
+                ctx.storage.put_schedule(&schedule).await;
             }
-            Timer::Invoke(mut service_invocation) => {
-                // Remove the execution time from the service invocation request
-                service_invocation.execution_time = None;
+            None => ctx.storage.delete_schedule(&schedule_id).await,
+        }
 
-                // ServiceInvocations scheduled with a timer are always owned by the same partition processor
-                // where the invocation should be executed
-                self.on_service_invocation(ctx, service_invocation).await
-            }
-            Timer::CleanInvocationStatus(invocation_id) => {
-                self.try_purge_invocation(ctx, invocation_id).await
-            }
-            Timer::NeoInvoke(invocation_id) => self.on_neo_invoke_timer(ctx, invocation_id).await,
+        // Schedules are owned by the same partition processor of the invocations they fire
+        if let Some(service_invocation) = service_invocation {
+            self.on_service_invocation(ctx, service_invocation).await?;
         }
+        Ok(())
     }
 
     async fn on_neo_invoke_timer<
-        State: VirtualObjectStatusTable + InvocationStatusTable + InboxTable + FsmTable + JournalTable,
+        State: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InboxTable
+            + FsmTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -1308,9 +2429,9 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
         // Scheduled invocations have been deduplicated already in on_service_invocation, and they already sent back the submit notification.
 
-        // 3. Check if we need to inbox it (only for exclusive methods of virtual objects)
+        // 3. Check if we need to inbox it (paused services, and virtual objects)
         let Some(pre_flight_invocation_metadata) = self
-            .handle_service_invocation_exclusive_handler(
+            .handle_service_invocation_paused_service(
                 ctx,
                 invocation_id,
                 scheduled_invocation.metadata,
@@ -1320,6 +2441,17 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             // Invocation was inboxed, nothing else to do here
             return Ok(());
         };
+        let Some(pre_flight_invocation_metadata) = self
+            .handle_service_invocation_virtual_object_handler(
+                ctx,
+                invocation_id,
+                pre_flight_invocation_metadata,
+            )
+            .await?
+        else {
+            // Invocation was inboxed, nothing else to do here
+            return Ok(());
+        };
 
         // 4. Execute it
         let (in_flight_invocation_metadata, invocation_input) =
@@ -1336,27 +2468,142 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         .await
     }
 
+    async fn on_pause_service<State: FsmTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        service_name: ByteString,
+    ) -> Result<(), Error> {
+        debug_if_leader!(ctx.is_leader, rpc.service = %service_name, "Pause service");
+
+        let mut paused_services = ctx.storage.get_paused_services().await?;
+        if paused_services.services.insert(service_name) {
+            ctx.storage.put_paused_services(&paused_services).await;
+        }
+        Ok(())
+    }
+
+    /// Starts the invocations enqueued while the service was paused, in the order they were
+    /// enqueued.
+    async fn on_resume_service<
+        State: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + InboxTable
+            + FsmTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2,
+    >(
+        &mut self,
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        service_name: ByteString,
+    ) -> Result<(), Error> {
+        let mut paused_services = ctx.storage.get_paused_services().await?;
+        if !paused_services.services.remove(&service_name) {
+            // The service was not paused
+            return Ok(());
+        }
+        ctx.storage.put_paused_services(&paused_services).await;
+
+        let paused_invocations: Vec<_> = ctx
+            .storage
+            .paused_invocations(&service_name)
+            .try_collect()
+            .await?;
+        debug_if_leader!(
+            ctx.is_leader,
+            rpc.service = %service_name,
+            "Resume service with {} pending invocations",
+            paused_invocations.len()
+        );
+        for paused_invocation in paused_invocations {
+            let InboxEntry::Invocation(service_id, invocation_id, _) =
+                paused_invocation.inbox_entry
+            else {
+                continue;
+            };
+            ctx.storage
+                .delete_paused_invocation(&service_id, paused_invocation.inbox_sequence_number)
+                .await;
+
+            let InvocationStatus::Inboxed(inboxed_invocation) =
+                ctx.get_invocation_status(&invocation_id).await?
+            else {
+                // The pending invocation was killed or cancelled in the meantime
+                continue;
+            };
+
+            let Some(pre_flight_invocation_metadata) = self
+                .handle_service_invocation_virtual_object_handler(
+                    ctx,
+                    invocation_id,
+                    inboxed_invocation.metadata,
+                )
+                .await?
+            else {
+                // Invocation was inboxed, it starts once the virtual object is unlocked
+                continue;
+            };
+
+            let (in_flight_invocation_metadata, invocation_input) =
+                InFlightInvocationMetadata::from_pre_flight_invocation_metadata(
+                    pre_flight_invocation_metadata,
+                );
+            Self::init_journal_and_invoke(
+                ctx,
+                invocation_id,
+                in_flight_invocation_metadata,
+                invocation_input,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     async fn try_invoker_effect<
         State: InvocationStatusTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + StateTable
             + PromiseTable
             + OutboxTable
             + FsmTable
             + TimerTable
             + InboxTable
-            + VirtualObjectStatusTable,
+            + VirtualObjectStatusTable
+            + ReadOnlyDeduplicationTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invoker_effect: InvokerEffect,
     ) -> Result<(), Error> {
         let start = Instant::now();
+        if Self::is_fenced_invoker_effect(ctx, &invoker_effect).await? {
+            debug!(
+                restate.invocation.id = %invoker_effect.invocation_id,
+                "Dropping invoker effect produced under the outdated leader epoch {:?}",
+                invoker_effect.leader_epoch
+            );
+            return Ok(());
+        }
+
         let status = ctx
             .get_invocation_status(&invoker_effect.invocation_id)
             .await?;
 
         match status {
+            InvocationStatus::Invoked(invocation_metadata)
+                if invocation_metadata.invocation_epoch != invoker_effect.invocation_epoch =>
+            {
+                // The attempt which produced the effect was discarded, e.g. by a restart, and the
+                // invoker was asked to abort it already
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.invocation.id = %invoker_effect.invocation_id,
+                    "Dropping invoker effect produced under the outdated invocation epoch {}",
+                    invoker_effect.invocation_epoch
+                );
+            }
             InvocationStatus::Invoked(invocation_metadata) => {
                 self.on_invoker_effect(ctx, invoker_effect, invocation_metadata)
                     .await?
@@ -1371,22 +2618,49 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    /// Returns true if the effect was produced under a leader epoch older than the latest leader
+    /// epoch this partition has observed. Such effects stem from a deposed leader whose invoker
+    /// kept running, and must not be applied since the new leader owns the invocation by now.
+    async fn is_fenced_invoker_effect<State: ReadOnlyDeduplicationTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invoker_effect: &InvokerEffect,
+    ) -> Result<bool, Error> {
+        let Some(leader_epoch) = invoker_effect.leader_epoch else {
+            return Ok(false);
+        };
+
+        let last_seen_leader_epoch = match ctx
+            .storage
+            .get_dedup_sequence_number(&ProducerId::self_producer())
+            .await?
+        {
+            Some(DedupSequenceNumber::Esn(esn)) => Some(esn.leader_epoch),
+            _ => None,
+        };
+
+        Ok(last_seen_leader_epoch.is_some_and(|last_seen| leader_epoch < last_seen))
+    }
+
     async fn on_invoker_effect<
         State: InvocationStatusTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + StateTable
             + PromiseTable
             + OutboxTable
             + FsmTable
             + TimerTable
             + InboxTable
-            + VirtualObjectStatusTable,
+            + VirtualObjectStatusTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         InvokerEffect {
             invocation_id,
             kind,
+            ..
         }: InvokerEffect,
         invocation_metadata: InFlightInvocationMetadata,
     ) -> Result<(), Error> {
@@ -1419,16 +2693,19 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 );
                 let mut any_completed = false;
                 for entry_index in &waiting_for_completed_entries {
-                    if ctx
-                        .storage
-                        .get_journal_entry(&invocation_id, *entry_index)
-                        .await?
-                        .map(|entry| entry.is_resumable())
-                        .unwrap_or_default()
+                    if Self::read_journal_entry(
+                        ctx,
+                        invocation_metadata.journal_metadata.version,
+                        &invocation_id,
+                        *entry_index,
+                    )
+                    .await?
+                    .map(|entry| entry.is_resumable())
+                    .unwrap_or_default()
                     {
                         trace!(
                             rpc.service = %invocation_metadata.invocation_target.service_name(),
-                            rectx.storage.invocation.id = %invocation_id,
+                            restate.invocation.id = %invocation_id,
                             "Resuming instead of suspending service because an awaited entry is completed/acked.");
                         any_completed = true;
                         break;
@@ -1454,6 +2731,9 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 self.fail_invocation(ctx, invocation_id, invocation_metadata, e)
                     .await?;
             }
+            InvokerEffectKind::RetryScheduled { retries } => {
+                Self::do_record_retries(ctx, invocation_id, invocation_metadata, retries).await;
+            }
         }
 
         Ok(())
@@ -1463,10 +2743,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         State: InboxTable
             + VirtualObjectStatusTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
             + FsmTable
             + InvocationStatusTable
-            + StateTable,
+            + StateTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -1474,7 +2757,14 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         invocation_metadata: InFlightInvocationMetadata,
     ) -> Result<(), Error> {
         let journal_length = invocation_metadata.journal_metadata.length;
+        let journal_version = invocation_metadata.journal_metadata.version;
         let completion_retention_time = invocation_metadata.completion_retention_duration;
+        let invocation_target = invocation_metadata.invocation_target.clone();
+        let idempotency_key = invocation_metadata.idempotency_key.clone();
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::Completed { failure: None },
+        );
 
         self.notify_invocation_result(
             ctx,
@@ -1487,12 +2777,18 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
         // Pop from inbox
         Self::consume_inbox(ctx, &invocation_metadata.invocation_target).await?;
+        Self::release_shared_handler_execution(
+            ctx,
+            invocation_id,
+            &invocation_metadata.invocation_target,
+        )
+        .await?;
 
         // If there are any response sinks, or we need to store back the completed status,
         //  we need to find the latest output entry
         if !invocation_metadata.response_sinks.is_empty() || !completion_retention_time.is_zero() {
             let result = if let Some(output_entry) = self
-                .read_last_output_entry(ctx, &invocation_id, journal_length)
+                .read_last_output_entry(ctx, &invocation_id, journal_version, journal_length)
                 .await?
             {
                 ResponseResult::from(output_entry.result)
@@ -1525,9 +2821,16 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
         // If no retention, immediately cleanup the invocation status
         if completion_retention_time.is_zero() {
-            Self::do_free_invocation(ctx, invocation_id).await;
+            Self::do_free_invocation(ctx, invocation_id).await?;
+            Self::do_unindex_invocation(
+                ctx,
+                invocation_id,
+                &invocation_target,
+                idempotency_key.as_ref(),
+            )
+            .await?;
         }
-        Self::do_drop_journal(ctx, invocation_id, journal_length).await;
+        Self::do_drop_journal(ctx, invocation_id, journal_version, journal_length).await?;
 
         Ok(())
     }
@@ -1539,8 +2842,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + VirtualObjectStatusTable
             + StateTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + OutboxTable
-            + FsmTable,
+            + FsmTable
+            + InvocationIndexTable
+            + InvocationEventTable,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
@@ -1549,6 +2855,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         error: InvocationError,
     ) -> Result<(), Error> {
         let journal_length = invocation_metadata.journal_metadata.length;
+        let journal_version = invocation_metadata.journal_metadata.version;
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::Completed {
+                failure: Some(error.to_string()),
+            },
+        );
 
         self.notify_invocation_result(
             ctx,
@@ -1574,6 +2887,12 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
         // Pop from inbox
         Self::consume_inbox(ctx, &invocation_metadata.invocation_target).await?;
+        Self::release_shared_handler_execution(
+            ctx,
+            invocation_id,
+            &invocation_metadata.invocation_target,
+        )
+        .await?;
 
         // Store the completed status or free it
         if !invocation_metadata.completion_retention_duration.is_zero() {
@@ -1583,10 +2902,17 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             );
             Self::do_store_completed_invocation(ctx, invocation_id, completed_invocation).await;
         } else {
-            Self::do_free_invocation(ctx, invocation_id).await;
+            Self::do_free_invocation(ctx, invocation_id).await?;
+            Self::do_unindex_invocation(
+                ctx,
+                invocation_id,
+                &invocation_metadata.invocation_target,
+                invocation_metadata.idempotency_key.as_ref(),
+            )
+            .await?;
         }
 
-        Self::do_drop_journal(ctx, invocation_id, journal_length).await;
+        Self::do_drop_journal(ctx, invocation_id, journal_version, journal_length).await?;
 
         Ok(())
     }
@@ -1648,7 +2974,8 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + InvocationStatusTable
             + VirtualObjectStatusTable
             + StateTable
-            + JournalTable,
+            + JournalTable
+            + journal_table_v2::JournalTableV2,
     >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_target: &InvocationTarget,
@@ -1671,7 +2998,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             // Note: the inbox seq numbers can have gaps.
             while let Some(inbox_entry) = ctx.storage.pop_inbox(&keyed_service_id).await? {
                 match inbox_entry.inbox_entry {
-                    InboxEntry::Invocation(_, invocation_id) => {
+                    InboxEntry::Invocation(_, invocation_id, _) => {
                         let inboxed_status = ctx.get_invocation_status(&invocation_id).await?;
 
                         let_assert!(
@@ -1722,6 +3049,79 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    async fn release_shared_handler_execution<
+        State: VirtualObjectStatusTable
+            + InvocationStatusTable
+            + JournalTable
+            + journal_table_v2::JournalTableV2,
+    >(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+    ) -> Result<(), Error> {
+        if invocation_target.invocation_target_ty()
+            != InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Shared)
+        {
+            return Ok(());
+        }
+        let keyed_service_id = invocation_target
+            .as_keyed_service_id()
+            .expect("When the handler type is Shared, the invocation target must have a key");
+
+        let mut executions = ctx
+            .storage
+            .get_shared_handler_executions(&keyed_service_id)
+            .await?;
+        let Some(position) = executions
+            .running
+            .iter()
+            .position(|running| *running == invocation_id)
+        else {
+            // The concurrency of this invocation was not limited
+            return Ok(());
+        };
+        executions.running.remove(position);
+
+        while let Some(pending_invocation_id) = executions.pending.pop_front() {
+            let InvocationStatus::Inboxed(inboxed_invocation) =
+                ctx.get_invocation_status(&pending_invocation_id).await?
+            else {
+                // The pending invocation was killed or cancelled in the meantime
+                continue;
+            };
+            if inboxed_invocation
+                .metadata
+                .shared_concurrency_limit
+                .is_some_and(|limit| executions.running.len() >= limit.get() as usize)
+            {
+                executions.pending.push_front(pending_invocation_id);
+                break;
+            }
+
+            debug_if_leader!(
+                ctx.is_leader,
+                rpc.service = %keyed_service_id,
+                "Invoke pending shared handler invocation"
+            );
+
+            executions.running.push(pending_invocation_id);
+            let (in_flight_invocation_meta, invocation_input) =
+                InFlightInvocationMetadata::from_inboxed_invocation(inboxed_invocation);
+            Self::init_journal_and_invoke(
+                ctx,
+                pending_invocation_id,
+                in_flight_invocation_meta,
+                invocation_input,
+            )
+            .await?;
+        }
+
+        ctx.storage
+            .put_shared_handler_executions(&keyed_service_id, &executions)
+            .await;
+        Ok(())
+    }
+
     async fn handle_journal_entry<
         State: StateTable
             + PromiseTable
@@ -1729,6 +3129,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             + FsmTable
             + TimerTable
             + JournalTable
+            + journal_table_v2::JournalTableV2
             + InvocationStatusTable,
     >(
         &mut self,
@@ -1742,8 +3143,36 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             entry_index, invocation_metadata.journal_metadata.length,
             "Expect to receive next journal entry for {invocation_id}"
         );
+        let journal_version = invocation_metadata.journal_metadata.version;
 
         match journal_entry.header() {
+            // Dry-run invocations must not affect other invocations: their calls fail, while
+            // their one-way calls, including the delayed ones, awakeable completions and
+            // cancellations are dropped.
+            EnrichedEntryHeader::Call {
+                enrichment_result: Some(_),
+                ..
+            } if invocation_metadata.dry_run => {
+                let completion_result = CompletionResult::from(&DRY_RUN_CALL_INVOCATION_ERROR);
+                Codec::write_completion(&mut journal_entry, completion_result.clone())?;
+                Self::forward_completion(
+                    ctx,
+                    invocation_id,
+                    Completion::new(entry_index, completion_result),
+                );
+            }
+            EnrichedEntryHeader::OneWayCall { .. }
+            | EnrichedEntryHeader::CompleteAwakeable { .. }
+            | EntryHeader::CancelInvocation
+                if invocation_metadata.dry_run =>
+            {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.invocation.id = %invocation_id,
+                    "Dropping the {} entry of the dry-run invocation",
+                    journal_entry.header().as_entry_type()
+                );
+            }
             // nothing to do
             EnrichedEntryHeader::Input { .. } => {}
             EnrichedEntryHeader::Output { .. } => {
@@ -2137,6 +3566,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     invocation_id: callee_invocation_id,
                     invocation_target: callee_invocation_target,
                     completion_retention_time,
+                    shared_concurrency_limit,
                 }) = enrichment_result
                 {
                     let_assert!(
@@ -2159,8 +3589,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                         span_context: span_context.clone(),
                         headers: request.headers,
                         execution_time: None,
+                        execution_wall_clock_time: None,
                         completion_retention_duration: *completion_retention_time,
                         idempotency_key: request.idempotency_key,
+                        pinned_deployment: None,
+                        dry_run: false,
+                        priority: Default::default(),
+                        shared_concurrency_limit: *shared_concurrency_limit,
                         submit_notification_sink: None,
                     };
 
@@ -2181,6 +3616,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     invocation_target: callee_invocation_target,
                     span_context,
                     completion_retention_time,
+                    shared_concurrency_limit,
                 } = enrichment_result;
 
                 let_assert!(
@@ -2213,42 +3649,57 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     span.add_link(ctx, Vec::default());
                 }
 
-                let service_invocation = ServiceInvocation {
-                    invocation_id: *callee_invocation_id,
-                    invocation_target: callee_invocation_target.clone(),
-                    argument: request.parameter,
-                    source: Source::Service(
-                        invocation_id,
-                        invocation_metadata.invocation_target.clone(),
-                    ),
-                    response_sink: None,
-                    span_context: span_context.clone(),
-                    headers: request.headers,
-                    execution_time: delay,
-                    completion_retention_duration: *completion_retention_time,
-                    idempotency_key: request.idempotency_key,
-                    submit_notification_sink: None,
+                let message = if let Some(cluster) = callee_invocation_target.kafka_egress_cluster()
+                {
+                    OutboxMessage::KafkaEvent(KafkaEgressEvent {
+                        cluster: cluster.to_owned(),
+                        topic: callee_invocation_target.handler_name().to_string(),
+                        key: callee_invocation_target
+                            .key()
+                            .filter(|key| !key.is_empty())
+                            .map(|key| key.as_bytes().clone()),
+                        payload: request.parameter,
+                        headers: request.headers,
+                        source_invocation_id: invocation_id,
+                    })
+                } else {
+                    OutboxMessage::ServiceInvocation(ServiceInvocation {
+                        invocation_id: *callee_invocation_id,
+                        invocation_target: callee_invocation_target.clone(),
+                        argument: request.parameter,
+                        source: Source::Service(
+                            invocation_id,
+                            invocation_metadata.invocation_target.clone(),
+                        ),
+                        response_sink: None,
+                        span_context: span_context.clone(),
+                        headers: request.headers,
+                        execution_time: delay,
+                        execution_wall_clock_time: None,
+                        completion_retention_duration: *completion_retention_time,
+                        idempotency_key: request.idempotency_key,
+                        pinned_deployment: None,
+                        dry_run: false,
+                        priority: Default::default(),
+                        shared_concurrency_limit: *shared_concurrency_limit,
+                        submit_notification_sink: None,
+                    })
                 };
 
-                self.handle_outgoing_message(
-                    ctx,
-                    OutboxMessage::ServiceInvocation(service_invocation),
-                )
-                .await?;
+                self.handle_outgoing_message(ctx, message).await?;
             }
             EnrichedEntryHeader::Awakeable { is_completed, .. } => {
                 debug_assert!(!is_completed, "Awakeable entry must not be completed.");
                 // Check the awakeable_completion_received_before_entry test in state_machine/server for more details
 
                 // If completion is already here, let's merge it and forward it.
-                if let Some(completion_result) = ctx
-                    .storage
-                    .get_journal_entry(&invocation_id, entry_index)
-                    .await?
-                    .and_then(|journal_entry| match journal_entry {
-                        JournalEntry::Entry(_) => None,
-                        JournalEntry::Completion(completion_result) => Some(completion_result),
-                    })
+                if let Some(completion_result) =
+                    Self::read_journal_entry(ctx, journal_version, &invocation_id, entry_index)
+                        .await?
+                        .and_then(|journal_entry| match journal_entry {
+                            JournalEntry::Entry(_) => None,
+                            JournalEntry::Completion(completion_result) => Some(completion_result),
+                        })
                 {
                     Codec::write_completion(&mut journal_entry, completion_result.clone())?;
 
@@ -2309,8 +3760,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     Entry::CancelInvocation(entry) =
                         journal_entry.deserialize_entry_ref::<Codec>()?
                 );
-                self.apply_cancel_invocation_journal_entry_action(ctx, &invocation_id, entry)
-                    .await?;
+                self.apply_cancel_invocation_journal_entry_action(
+                    ctx,
+                    &invocation_id,
+                    journal_version,
+                    entry,
+                )
+                .await?;
             }
             EntryHeader::GetCallInvocationId { is_completed } => {
                 if !is_completed {
@@ -2321,6 +3777,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     let callee_invocation_id = Self::get_journal_entry_callee_invocation_id(
                         ctx,
                         &invocation_id,
+                        journal_version,
                         entry.call_entry_index,
                     )
                     .await?;
@@ -2357,6 +3814,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                         Self::get_invocation_query_from_attach_invocation_target(
                             ctx,
                             &invocation_id,
+                            journal_version,
                             entry.target,
                         )
                         .await?
@@ -2387,6 +3845,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                         Self::get_invocation_query_from_attach_invocation_target(
                             ctx,
                             &invocation_id,
+                            journal_version,
                             entry.target,
                         )
                         .await?
@@ -2415,7 +3874,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             entry_index,
             &JournalEntry::Entry(journal_entry),
         )
-        .await;
+        .await?;
         ctx.action_collector.push(Action::AckStoredEntry {
             invocation_id,
             entry_index,
@@ -2425,11 +3884,12 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     }
 
     async fn apply_cancel_invocation_journal_entry_action<
-        State: OutboxTable + FsmTable + ReadOnlyJournalTable,
+        State: OutboxTable + FsmTable + ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
     >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: &InvocationId,
+        journal_version: JournalVersion,
         entry: CancelInvocationEntry,
     ) -> Result<(), Error> {
         let target_invocation_id = match entry.target {
@@ -2447,8 +3907,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             }
             CancelInvocationTarget::CallEntryIndex(call_entry_index) => {
                 // Look for the given entry index, then resolve the invocation id.
-                Self::get_journal_entry_callee_invocation_id(ctx, invocation_id, call_entry_index)
-                    .await?
+                Self::get_journal_entry_callee_invocation_id(
+                    ctx,
+                    invocation_id,
+                    journal_version,
+                    call_entry_index,
+                )
+                .await?
             }
         };
 
@@ -2465,9 +3930,12 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
-    async fn get_invocation_query_from_attach_invocation_target<State: ReadOnlyJournalTable>(
+    async fn get_invocation_query_from_attach_invocation_target<
+        State: ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: &InvocationId,
+        journal_version: JournalVersion,
         target: AttachInvocationTarget,
     ) -> Result<Option<InvocationQuery>, Error> {
         Ok(match target {
@@ -2485,9 +3953,14 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             }
             AttachInvocationTarget::CallEntryIndex(call_entry_index) => {
                 // Look for the given entry index, then resolve the invocation id.
-                Self::get_journal_entry_callee_invocation_id(ctx, invocation_id, call_entry_index)
-                    .await?
-                    .map(InvocationQuery::Invocation)
+                Self::get_journal_entry_callee_invocation_id(
+                    ctx,
+                    invocation_id,
+                    journal_version,
+                    call_entry_index,
+                )
+                .await?
+                .map(InvocationQuery::Invocation)
             }
             AttachInvocationTarget::IdempotentRequest(idempotency_id) => {
                 Some(InvocationQuery::IdempotencyId(idempotency_id))
@@ -2498,15 +3971,16 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         })
     }
 
-    async fn get_journal_entry_callee_invocation_id<State: ReadOnlyJournalTable>(
+    async fn get_journal_entry_callee_invocation_id<
+        State: ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: &InvocationId,
+        journal_version: JournalVersion,
         call_entry_index: EntryIndex,
     ) -> Result<Option<InvocationId>, Error> {
         Ok(
-            match ctx
-                .storage
-                .get_journal_entry(invocation_id, call_entry_index)
+            match Self::read_journal_entry(ctx, journal_version, invocation_id, call_entry_index)
                 .await?
             {
                 Some(JournalEntry::Entry(e)) => {
@@ -2545,17 +4019,32 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         )
     }
 
-    async fn handle_completion<State: JournalTable + InvocationStatusTable>(
+    async fn handle_completion<
+        State: JournalTable + journal_table_v2::JournalTableV2 + InvocationStatusTable,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         completion: Completion,
     ) -> Result<(), Error> {
-        let status = ctx.get_invocation_status(&invocation_id).await?;
+        // Completions of invoked invocations only need the journal version, hence the full
+        // status is read only for suspended invocations, which might be resumed
+        let view = ctx.get_invocation_status_view(&invocation_id).await?;
+        let status = match view.kind {
+            InvocationStatusKind::Invoked => {
+                Self::handle_completion_for_invoked(
+                    ctx,
+                    invocation_id,
+                    view.journal_version.unwrap_or_default(),
+                    completion,
+                )
+                .await?;
+                return Ok(());
+            }
+            InvocationStatusKind::Suspended => ctx.get_invocation_status(&invocation_id).await?,
+            _ => InvocationStatus::Free,
+        };
 
         match status {
-            InvocationStatus::Invoked(_) => {
-                Self::handle_completion_for_invoked(ctx, invocation_id, completion).await?;
-            }
             InvocationStatus::Suspended {
                 metadata,
                 waiting_for_completed_entries,
@@ -2563,6 +4052,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                 if Self::handle_completion_for_suspended(
                     ctx,
                     invocation_id,
+                    metadata.journal_metadata.version,
                     completion,
                     &waiting_for_completed_entries,
                 )
@@ -2573,7 +4063,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             }
             _ => {
                 debug!(
-                    rectx.storage.invocation.id = %invocation_id,
+                    restate.invocation.id = %invocation_id,
                     ?completion,
                     "Ignoring completion for invocation that is no longer running."
                 )
@@ -2583,43 +4073,53 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
-    async fn handle_completion_for_suspended<State: JournalTable>(
+    async fn handle_completion_for_suspended<
+        State: JournalTable + journal_table_v2::JournalTableV2,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
+        journal_version: JournalVersion,
         completion: Completion,
         waiting_for_completed_entries: &HashSet<EntryIndex>,
     ) -> Result<bool, Error> {
         let resume_invocation = waiting_for_completed_entries.contains(&completion.entry_index);
-        Self::store_completion(ctx, invocation_id, completion).await?;
+        Self::store_completion(ctx, invocation_id, journal_version, completion).await?;
 
         Ok(resume_invocation)
     }
 
-    async fn handle_completion_for_invoked<State: JournalTable>(
+    async fn handle_completion_for_invoked<
+        State: JournalTable + journal_table_v2::JournalTableV2,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
+        journal_version: JournalVersion,
         completion: Completion,
     ) -> Result<(), Error> {
-        if let Some(completion) = Self::store_completion(ctx, invocation_id, completion).await? {
+        if let Some(completion) =
+            Self::store_completion(ctx, invocation_id, journal_version, completion).await?
+        {
             Self::forward_completion(ctx, invocation_id, completion);
         }
         Ok(())
     }
 
-    async fn read_last_output_entry<State: ReadOnlyJournalTable>(
+    async fn read_last_output_entry<
+        State: ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
         &mut self,
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: &InvocationId,
+        journal_version: JournalVersion,
         journal_length: EntryIndex,
     ) -> Result<Option<OutputEntry>, Error> {
         // Find last output entry
         let mut output_entry = None;
         for i in (0..journal_length).rev() {
-            if let JournalEntry::Entry(e) = ctx
-                .storage
-                .get_journal_entry(invocation_id, i)
-                .await?
-                .unwrap_or_else(|| panic!("There should be a journal entry at index {}", i))
+            if let JournalEntry::Entry(e) =
+                Self::read_journal_entry(ctx, journal_version, invocation_id, i)
+                    .await?
+                    .unwrap_or_else(|| panic!("There should be a journal entry at index {}", i))
             {
                 if e.ty() == EntryType::Output {
                     output_entry = Some(e);
@@ -2695,6 +4195,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
     async fn handle_attach_invocation_request<
         State: ReadOnlyIdempotencyTable
+            + ReadOnlyInvocationIndexTable
             + InvocationStatusTable
             + ReadOnlyVirtualObjectStatusTable
             + OutboxTable
@@ -2710,27 +4211,9 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             attach_invocation_request.partition_key(),
             self.partition_key_range);
 
-        let invocation_id = match attach_invocation_request.invocation_query {
-            InvocationQuery::Invocation(iid) => iid,
-            ref q @ InvocationQuery::Workflow(ref sid) => {
-                match ctx.storage.get_virtual_object_status(sid).await? {
-                    VirtualObjectStatus::Locked(iid) => iid,
-                    VirtualObjectStatus::Unlocked => {
-                        // Try the deterministic id
-                        q.to_invocation_id()
-                    }
-                }
-            }
-            ref q @ InvocationQuery::IdempotencyId(ref iid) => {
-                match ctx.storage.get_idempotency_metadata(iid).await? {
-                    Some(idempotency_metadata) => idempotency_metadata.invocation_id,
-                    None => {
-                        // Try the deterministic id
-                        q.to_invocation_id()
-                    }
-                }
-            }
-        };
+        let invocation_id =
+            resolve_invocation_query(ctx.storage, &attach_invocation_request.invocation_query)
+                .await?;
         match ctx.get_invocation_status(&invocation_id).await? {
             InvocationStatus::Free => {
                 self.send_response_to_sinks(
@@ -2858,7 +4341,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         }
     }
 
-    async fn do_resume_service<State: InvocationStatusTable>(
+    async fn do_resume_service<State: InvocationStatusTable + journal_table_v2::JournalTableV2>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         mut metadata: InFlightInvocationMetadata,
@@ -2868,9 +4351,32 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             restate.journal.length = metadata.journal_metadata.length,
             "Effect: Resume service"
         );
+        ctx.record_invocation_event(invocation_id, InvocationEventKind::Resumed);
+
+        // The resumed attempt reads the journal from storage, so the journals of invocations
+        // started before the journal table v2 was enabled are moved over at this point. The
+        // status is written with the new journal version in the same transaction.
+        if metadata.journal_metadata.version == JournalVersion::V1
+            && ctx.journal_version == JournalVersion::V2
+        {
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.journal.length = metadata.journal_metadata.length,
+                "Migrate journal to the journal table v2"
+            );
+            journal_table_v2::JournalTableV2::migrate_journal(
+                ctx.storage,
+                &invocation_id,
+                metadata.journal_metadata.length,
+            )
+            .await?;
+            metadata.journal_metadata.version = JournalVersion::V2;
+        }
 
         metadata.timestamps.update();
         let invocation_target = metadata.invocation_target.clone();
+        let invocation_epoch = metadata.invocation_epoch;
+        let execution_deadline = metadata.execution_deadline;
         ctx.storage
             .put_invocation_status(&invocation_id, &InvocationStatus::Invoked(metadata))
             .await;
@@ -2878,6 +4384,8 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         ctx.action_collector.push(Action::Invoke {
             invocation_id,
             invocation_target,
+            invocation_epoch,
+            execution_deadline,
             invoke_input_journal: InvokeInputJournal::NoCachedJournal,
         });
 
@@ -2905,6 +4413,15 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             "Effect: Suspend service waiting on entries {:?}",
             waiting_for_completed_entries
         );
+        let mut waiting_for_entries: Vec<_> =
+            waiting_for_completed_entries.iter().copied().collect();
+        waiting_for_entries.sort_unstable();
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::Suspended {
+                waiting_for_entries,
+            },
+        );
 
         metadata.timestamps.update();
         ctx.storage
@@ -2937,10 +4454,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             .await;
     }
 
-    async fn do_free_invocation<State: InvocationStatusTable>(
+    async fn do_free_invocation<State: InvocationStatusTable + InvocationEventTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
-    ) {
+    ) -> Result<(), Error> {
         debug_if_leader!(
             ctx.is_leader,
             restate.invocation.id = %invocation_id,
@@ -2950,6 +4467,11 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         ctx.storage
             .put_invocation_status(&invocation_id, &InvocationStatus::Free)
             .await;
+        // Events recorded by the current command would otherwise outlive the invocation
+        ctx.invocation_events
+            .retain(|(recorded_invocation_id, _)| *recorded_invocation_id != invocation_id);
+        ctx.storage.delete_invocation_events(&invocation_id).await?;
+        Ok(())
     }
 
     async fn do_delete_inbox_entry<State: InboxTable>(
@@ -2966,7 +4488,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
 
         ctx.storage
             .delete_inbox_entry(&service_id, sequence_number)
-            .await;
+            .await?;
 
         Ok(())
     }
@@ -3034,6 +4556,16 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     invocation_query,
                 )
             }
+            OutboxMessage::KafkaEvent(kafka_event) => {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.invocation.id = %kafka_event.source_invocation_id,
+                    restate.outbox.seq = seq_number,
+                    "Effect: Publish event to Kafka topic '{}' of cluster '{}'",
+                    kafka_event.topic,
+                    kafka_event.cluster
+                )
+            }
         };
 
         ctx.storage.put_outbox_message(seq_number, &message).await;
@@ -3177,7 +4709,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     "Register background invoke timer"
                 )
             }
-            Timer::NeoInvoke(invocation_id) => {
+            Timer::NeoInvoke(invocation_id) | Timer::NeoInvokeAtLocalTime(invocation_id, _) => {
                 // no span necessary; there will already be a background_invoke span
                 debug_if_leader!(
                     ctx.is_leader,
@@ -3195,6 +4727,15 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
                     "Register cleanup invocation status timer"
                 )
             }
+            Timer::FireSchedule(schedule_id) => {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.schedule.id = %schedule_id,
+                    restate.timer.wake_up_time = %timer_value.wake_up_time(),
+                    restate.timer.key = %TimerKeyDisplay(timer_value.key()),
+                    "Register schedule timer"
+                )
+            }
         };
 
         ctx.storage
@@ -3235,6 +4776,12 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             restate.deployment.service_protocol_version = %pinned_deployment.service_protocol_version.as_repr(),
             "Effect: Store chosen deployment to storage"
         );
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::DeploymentPinned {
+                deployment_id: pinned_deployment.deployment_id,
+            },
+        );
 
         metadata.set_pinned_deployment(pinned_deployment);
 
@@ -3245,7 +4792,34 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             .await;
     }
 
-    async fn append_journal_entry<State: JournalTable + InvocationStatusTable>(
+    async fn do_record_retries<State: InvocationStatusTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        mut metadata: InFlightInvocationMetadata,
+        retries: u32,
+    ) {
+        metadata.record_retries(retries);
+
+        debug_if_leader!(
+            ctx.is_leader,
+            restate.invocation.retry_count = metadata.retry_count,
+            "Effect: Record {retries} retries of the invocation"
+        );
+        ctx.record_invocation_event(
+            invocation_id,
+            InvocationEventKind::RetryScheduled {
+                attempt: metadata.retry_count,
+            },
+        );
+
+        ctx.storage
+            .put_invocation_status(&invocation_id, &InvocationStatus::Invoked(metadata))
+            .await;
+    }
+
+    async fn append_journal_entry<
+        State: JournalTable + journal_table_v2::JournalTableV2 + InvocationStatusTable,
+    >(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
         // We pass around the invocation_status here to avoid an additional read.
@@ -3254,7 +4828,7 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         mut previous_invocation_status: InvocationStatus,
         entry_index: EntryIndex,
         journal_entry: &JournalEntry,
-    ) {
+    ) -> Result<(), Error> {
         debug_if_leader!(
             ctx.is_leader,
             restate.journal.index = entry_index,
@@ -3263,21 +4837,27 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             journal_entry.entry_type()
         );
 
-        // Store journal entry
-        ctx.storage
-            .put_journal_entry(&invocation_id, entry_index, journal_entry)
-            .await;
-
         // update the journal metadata length
         let journal_meta = previous_invocation_status
             .get_journal_metadata_mut()
             .expect("At this point there must be a journal");
+        let journal_version = journal_meta.version;
         debug_assert_eq!(
             journal_meta.length, entry_index,
             "journal should not have gaps"
         );
         journal_meta.length = entry_index + 1;
 
+        // Store journal entry
+        Self::write_journal_entry(
+            ctx,
+            journal_version,
+            &invocation_id,
+            entry_index,
+            journal_entry,
+        )
+        .await?;
+
         // Update timestamps
         if let Some(timestamps) = previous_invocation_status.get_timestamps_mut() {
             timestamps.update();
@@ -3287,13 +4867,15 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         ctx.storage
             .put_invocation_status(&invocation_id, &previous_invocation_status)
             .await;
+        Ok(())
     }
 
-    async fn do_drop_journal<State: JournalTable>(
+    async fn do_drop_journal<State: JournalTable + journal_table_v2::JournalTableV2>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
+        journal_version: JournalVersion,
         journal_length: EntryIndex,
-    ) {
+    ) -> Result<(), Error> {
         debug_if_leader!(
             ctx.is_leader,
             restate.journal.length = journal_length,
@@ -3301,9 +4883,139 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         );
 
         // TODO: Only drop journals if the inbox is empty; this requires that keep track of the max journal length: https://github.com/restatedev/restate/issues/272
-        ctx.storage
-            .delete_journal(&invocation_id, journal_length)
-            .await;
+        Self::remove_journal(ctx, journal_version, &invocation_id, journal_length).await
+    }
+
+    async fn read_journal_entry<
+        State: ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        journal_version: JournalVersion,
+        invocation_id: &InvocationId,
+        entry_index: EntryIndex,
+    ) -> Result<Option<JournalEntry>, Error> {
+        Ok(match journal_version {
+            JournalVersion::V1 => {
+                ReadOnlyJournalTable::get_journal_entry(ctx.storage, invocation_id, entry_index)
+                    .await?
+            }
+            JournalVersion::V2 => journal_table_v2::ReadOnlyJournalTableV2::get_journal_entry(
+                ctx.storage,
+                invocation_id,
+                entry_index,
+            )
+            .await?
+            .map(|stored_entry| stored_entry.entry),
+        })
+    }
+
+    async fn read_journal<
+        State: ReadOnlyJournalTable + journal_table_v2::ReadOnlyJournalTableV2,
+    >(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: &InvocationId,
+        journal_metadata: &JournalMetadata,
+    ) -> Result<Vec<(EntryIndex, JournalEntry)>, Error> {
+        Ok(match journal_metadata.version {
+            JournalVersion::V1 => {
+                ReadOnlyJournalTable::get_journal(
+                    ctx.storage,
+                    invocation_id,
+                    journal_metadata.length,
+                )
+                .try_collect()
+                .await?
+            }
+            JournalVersion::V2 => {
+                journal_table_v2::ReadOnlyJournalTableV2::get_journal(
+                    ctx.storage,
+                    invocation_id,
+                    journal_metadata.length,
+                )
+                .map_ok(|(entry_index, stored_entry)| (entry_index, stored_entry.entry))
+                .try_collect()
+                .await?
+            }
+        })
+    }
+
+    async fn write_journal_entry<State: JournalTable + journal_table_v2::JournalTableV2>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        journal_version: JournalVersion,
+        invocation_id: &InvocationId,
+        entry_index: EntryIndex,
+        journal_entry: &JournalEntry,
+    ) -> Result<(), Error> {
+        match journal_version {
+            JournalVersion::V1 => {
+                JournalTable::put_journal_entry(
+                    ctx.storage,
+                    invocation_id,
+                    entry_index,
+                    journal_entry,
+                )
+                .await;
+            }
+            JournalVersion::V2 => {
+                // Entries are still completed by journal index, hence the completion id of the
+                // completable entries is their journal index
+                let completion_id = match journal_entry {
+                    JournalEntry::Entry(entry) => {
+                        entry.header().is_completed().map(|_| entry_index)
+                    }
+                    JournalEntry::Completion(_) => None,
+                };
+                // Completing an entry rewrites it, which must keep the time it was appended at,
+                // including it being unknown for migrated entries
+                let stored_entry =
+                    match journal_table_v2::ReadOnlyJournalTableV2::get_journal_entry(
+                        ctx.storage,
+                        invocation_id,
+                        entry_index,
+                    )
+                    .await?
+                    {
+                        Some(stored_entry) => {
+                            stored_entry.with_entry(journal_entry.clone(), completion_id)
+                        }
+                        None => StoredEntry::new(
+                            journal_entry.clone(),
+                            completion_id,
+                            ctx.record_created_at,
+                        ),
+                    };
+                journal_table_v2::JournalTableV2::put_journal_entry(
+                    ctx.storage,
+                    invocation_id,
+                    entry_index,
+                    &stored_entry,
+                )
+                .await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_journal<State: JournalTable + journal_table_v2::JournalTableV2>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        journal_version: JournalVersion,
+        invocation_id: &InvocationId,
+        journal_length: EntryIndex,
+    ) -> Result<(), Error> {
+        match journal_version {
+            JournalVersion::V1 => {
+                JournalTable::delete_journal(ctx.storage, invocation_id, journal_length).await
+            }
+            JournalVersion::V2 => {
+                journal_table_v2::JournalTableV2::delete_journal(
+                    ctx.storage,
+                    invocation_id,
+                    journal_length,
+                )
+                .await?
+            }
+        }
+        Ok(())
     }
 
     async fn do_truncate_outbox<State: OutboxTable>(
@@ -3322,9 +5034,10 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
     }
 
     /// Returns the completion if it should be forwarded.
-    async fn store_completion<State: JournalTable>(
+    async fn store_completion<State: JournalTable + journal_table_v2::JournalTableV2>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         invocation_id: InvocationId,
+        journal_version: JournalVersion,
         mut completion: Completion,
     ) -> Result<Option<Completion>, Error> {
         debug_if_leader!(
@@ -3334,14 +5047,13 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             CompletionResultFmt(&completion.result)
         );
 
-        if let Some(mut journal_entry) = ctx
-            .storage
-            .get_journal_entry(&invocation_id, completion.entry_index)
-            .await?
-            .and_then(|journal_entry| match journal_entry {
-                JournalEntry::Entry(entry) => Some(entry),
-                JournalEntry::Completion(_) => None,
-            })
+        if let Some(mut journal_entry) =
+            Self::read_journal_entry(ctx, journal_version, &invocation_id, completion.entry_index)
+                .await?
+                .and_then(|journal_entry| match journal_entry {
+                    JournalEntry::Entry(entry) => Some(entry),
+                    JournalEntry::Completion(_) => None,
+                })
         {
             if journal_entry.ty() == EntryType::Awakeable
                 && journal_entry.header().is_completed() == Some(true)
@@ -3377,24 +5089,26 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
             }
 
             Codec::write_completion(&mut journal_entry, completion.result.clone())?;
-            ctx.storage
-                .put_journal_entry(
-                    &invocation_id,
-                    completion.entry_index,
-                    &JournalEntry::Entry(journal_entry),
-                )
-                .await;
+            Self::write_journal_entry(
+                ctx,
+                journal_version,
+                &invocation_id,
+                completion.entry_index,
+                &JournalEntry::Entry(journal_entry),
+            )
+            .await?;
             Ok(Some(completion))
         } else {
             // In case we don't have the journal entry (only awakeables case),
             // we'll send the completion afterward once we receive the entry.
-            ctx.storage
-                .put_journal_entry(
-                    &invocation_id,
-                    completion.entry_index,
-                    &JournalEntry::Completion(completion.result),
-                )
-                .await;
+            Self::write_journal_entry(
+                ctx,
+                journal_version,
+                &invocation_id,
+                completion.entry_index,
+                &JournalEntry::Completion(completion.result),
+            )
+            .await?;
             Ok(None)
         }
     }
@@ -3467,6 +5181,58 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         Ok(())
     }
 
+    async fn do_index_invocation<State: InvocationIndexTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        idempotency_key: Option<&ByteString>,
+    ) {
+        for lookup_key in
+            InvocationLookupKey::for_invocation(invocation_id, invocation_target, idempotency_key)
+        {
+            debug_if_leader!(
+                ctx.is_leader,
+                restate.invocation.id = %invocation_id,
+                "Effect: Index invocation under {:?}",
+                lookup_key
+            );
+
+            ctx.storage
+                .put_invocation_index_entry(&lookup_key, &InvocationIndexEntry { invocation_id })
+                .await;
+        }
+    }
+
+    async fn do_unindex_invocation<State: InvocationIndexTable>(
+        ctx: &mut StateMachineApplyContext<'_, State>,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        idempotency_key: Option<&ByteString>,
+    ) -> Result<(), Error> {
+        for lookup_key in
+            InvocationLookupKey::for_invocation(invocation_id, invocation_target, idempotency_key)
+        {
+            // The lookup key might have been taken over by another invocation in the meantime
+            if ctx
+                .storage
+                .get_invocation_index_entry(&lookup_key)
+                .await?
+                .is_some_and(|entry| entry.invocation_id == invocation_id)
+            {
+                debug_if_leader!(
+                    ctx.is_leader,
+                    restate.invocation.id = %invocation_id,
+                    "Effect: Remove invocation index entry {:?}",
+                    lookup_key
+                );
+
+                ctx.storage.delete_invocation_index_entry(&lookup_key).await;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn do_delete_idempotency_id<State: IdempotencyTable>(
         ctx: &mut StateMachineApplyContext<'_, State>,
         idempotency_id: IdempotencyId,
@@ -3590,6 +5356,55 @@ impl<'a> fmt::Display for CompletionResultFmt<'a> {
     }
 }
 
+/// Resolves the invocation id the given query refers to. Queries by idempotency id or workflow key
+/// are resolved through the invocation index, falling back to the deterministic invocation id.
+pub(crate) async fn resolve_invocation_query<
+    State: ReadOnlyInvocationIndexTable + ReadOnlyIdempotencyTable + ReadOnlyVirtualObjectStatusTable,
+>(
+    storage: &mut State,
+    invocation_query: &InvocationQuery,
+) -> StorageResult<InvocationId> {
+    let Some(lookup_key) = InvocationLookupKey::from_query(invocation_query) else {
+        return Ok(invocation_query.to_invocation_id());
+    };
+
+    if let Some(entry) = storage.get_invocation_index_entry(&lookup_key).await? {
+        return Ok(entry.invocation_id);
+    }
+
+    // TODO We need these lookups for invocations submitted before the invocation index was introduced,
+    //  remove them when we remove the idempotency table
+    let legacy_invocation_id = match invocation_query {
+        InvocationQuery::Invocation(iid) => Some(*iid),
+        InvocationQuery::Workflow(sid) => match storage.get_virtual_object_status(sid).await? {
+            VirtualObjectStatus::Locked(iid) => Some(iid),
+            VirtualObjectStatus::Unlocked => None,
+        },
+        InvocationQuery::IdempotencyId(iid) => storage
+            .get_idempotency_metadata(iid)
+            .await?
+            .map(|idempotency_metadata| idempotency_metadata.invocation_id),
+    };
+
+    // Otherwise try the deterministic id
+    Ok(legacy_invocation_id.unwrap_or_else(|| invocation_query.to_invocation_id()))
+}
+
+/// Returns the service id under which an invocation of a paused service is enqueued. Invocations
+/// of services without a key are enqueued under the partition key of the invocation.
+fn paused_service_id(
+    invocation_id: InvocationId,
+    invocation_target: &InvocationTarget,
+) -> ServiceId {
+    invocation_target.as_keyed_service_id().unwrap_or_else(|| {
+        ServiceId::with_partition_key(
+            invocation_id.partition_key(),
+            invocation_target.service_name().clone(),
+            "",
+        )
+    })
+}
+
 /// Projected [`InvocationStatus`] for cancellation purposes.
 enum InvocationStatusProjection {
     Invoked,
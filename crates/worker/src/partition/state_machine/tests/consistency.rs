@@ -0,0 +1,148 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{fixtures, *};
+
+use assert2::let_assert;
+use restate_storage_api::consistency::ConsistencyRepair;
+use restate_storage_api::idempotency_table::{
+    IdempotencyMetadata, IdempotencyTable, ReadOnlyIdempotencyTable,
+};
+use restate_storage_api::inbox_table::{InboxEntry, InboxTable};
+use restate_types::identifiers::{IdempotencyId, InvocationUuid, WithPartitionKey};
+use test_log::test;
+
+#[test(restate_core::test)]
+async fn repair_orphaned_inbox_entry() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    // The second invocation of the object is inboxed behind the first one
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let service_id = invocation_target.as_keyed_service_id().unwrap();
+    let _ = fixtures::mock_start_invocation_with_invocation_target(
+        &mut test_env,
+        invocation_target.clone(),
+    )
+    .await;
+    let inboxed_id = InvocationId::mock_generate(&invocation_target);
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id: inboxed_id,
+            invocation_target: invocation_target.clone(),
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    let_assert!(
+        InvocationStatus::Inboxed(inboxed) =
+            test_env.storage.get_invocation_status(&inboxed_id).await?
+    );
+
+    // The orphaned inbox entry behind it is deleted, while the entry of the inboxed invocation
+    // is kept
+    let orphaned_id = InvocationId::mock_generate(&invocation_target);
+    let orphaned_sequence_number = inboxed.inbox_sequence_number + 1;
+    let mut txn = test_env.storage().transaction();
+    txn.put_inbox_entry(
+        orphaned_sequence_number,
+        &InboxEntry::Invocation(service_id.clone(), orphaned_id, Default::default()),
+    )
+    .await;
+    txn.commit().await?;
+
+    for (inbox_sequence_number, invocation_id) in [
+        (inboxed.inbox_sequence_number, inboxed_id),
+        (orphaned_sequence_number, orphaned_id),
+    ] {
+        let _ = test_env
+            .apply(Command::RepairConsistency(
+                ConsistencyRepair::DeleteInboxEntry {
+                    service_id: service_id.clone(),
+                    inbox_sequence_number,
+                    invocation_id,
+                },
+            ))
+            .await;
+    }
+    let inbox: Vec<_> = test_env
+        .storage
+        .inbox(&service_id)
+        .map_ok(|entry| entry.inbox_sequence_number)
+        .try_collect()
+        .await?;
+    assert_eq!(inbox, vec![inboxed.inbox_sequence_number]);
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn repair_dangling_idempotency_key() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let idempotency_id =
+        IdempotencyId::unkeyed(invocation_id.partition_key(), "greeter", "greet", "my-key");
+    let dangling_idempotency_id = IdempotencyId::unkeyed(
+        invocation_id.partition_key(),
+        "greeter",
+        "greet",
+        "my-other-key",
+    );
+    let dangling_id =
+        InvocationId::from_parts(invocation_id.partition_key(), InvocationUuid::mock_random());
+
+    let mut txn = test_env.storage().transaction();
+    txn.put_idempotency_metadata(&idempotency_id, &IdempotencyMetadata { invocation_id })
+        .await;
+    txn.put_idempotency_metadata(
+        &dangling_idempotency_id,
+        &IdempotencyMetadata {
+            invocation_id: dangling_id,
+        },
+    )
+    .await;
+    txn.commit().await?;
+
+    let _ = test_env
+        .apply(Command::RepairConsistency(
+            ConsistencyRepair::DeleteIdempotencyKey {
+                idempotency_id: idempotency_id.clone(),
+                invocation_id,
+            },
+        ))
+        .await;
+    let _ = test_env
+        .apply(Command::RepairConsistency(
+            ConsistencyRepair::DeleteIdempotencyKey {
+                idempotency_id: dangling_idempotency_id.clone(),
+                invocation_id: dangling_id,
+            },
+        ))
+        .await;
+
+    // Only the idempotency key of the invocation which doesn't exist is deleted
+    assert_eq!(
+        test_env
+            .storage
+            .get_idempotency_metadata(&idempotency_id)
+            .await?,
+        Some(IdempotencyMetadata { invocation_id })
+    );
+    assert_eq!(
+        test_env
+            .storage
+            .get_idempotency_metadata(&dangling_idempotency_id)
+            .await?,
+        None
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
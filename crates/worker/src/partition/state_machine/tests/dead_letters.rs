@@ -0,0 +1,98 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::dead_letter_table::{
+    DeadLetter, DeadLetterDecision, DeadLetterTable, ReadOnlyDeadLetterTable,
+};
+use restate_types::logs::Lsn;
+use restate_types::time::MillisSinceEpoch;
+use restate_wal_protocol::{Destination, Envelope, Header, Source};
+use test_log::test;
+
+fn dead_letter(lsn: Lsn, command: Command) -> DeadLetter {
+    let envelope = Envelope::new(
+        Header {
+            source: Source::ControlPlane {},
+            dest: Destination::Processor {
+                partition_key: 0,
+                dedup: None,
+            },
+        },
+        command,
+    );
+    DeadLetter {
+        partition_id: PartitionId::MIN,
+        lsn,
+        partition_key: 0,
+        command: envelope.command.name().to_owned(),
+        error: "failed to deserialize entry".to_owned(),
+        attempts: 3,
+        dead_lettered_at: MillisSinceEpoch::now(),
+        envelope: envelope.to_bytes().unwrap(),
+    }
+}
+
+#[test(restate_core::test)]
+async fn reinject_dead_letter() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = InvocationId::mock_random();
+    let lsn = Lsn::from(42);
+
+    let mut txn = test_env.storage.transaction();
+    txn.put_dead_letter(&dead_letter(
+        lsn,
+        Command::Invoke(ServiceInvocation {
+            invocation_id,
+            ..ServiceInvocation::mock()
+        }),
+    ))
+    .await;
+    txn.commit().await?;
+
+    let actions = test_env.apply(Command::ReinjectDeadLetter(lsn)).await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+    assert_that!(test_env.storage.get_dead_letter(lsn).await?, none());
+
+    // Re-injecting again is a no-op
+    let actions = test_env.apply(Command::ReinjectDeadLetter(lsn)).await;
+    assert_that!(actions, empty());
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn dead_letter_decision_is_no_op_when_applied() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    // The partition processor dead-letters the record when reaching it, before reaching the
+    // record containing the decision
+    let actions = test_env
+        .apply(Command::DeadLetterRecord(DeadLetterDecision {
+            lsn: Lsn::from(42),
+            error: "failed to deserialize entry".to_owned(),
+            attempts: 3,
+            dead_lettered_at: MillisSinceEpoch::now(),
+        }))
+        .await;
+    assert_that!(actions, empty());
+    assert_that!(
+        test_env.storage.get_dead_letter(Lsn::from(42)).await?,
+        none()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
@@ -12,7 +12,7 @@ use super::*;
 
 use restate_storage_api::inbox_table::ReadOnlyInboxTable;
 use restate_types::invocation::SubmitNotificationSink;
-use restate_types::time::MillisSinceEpoch;
+use restate_types::time::{MillisSinceEpoch, ZonedDateTime};
 use std::time::{Duration, SystemTime};
 use test_log::test;
 
@@ -223,3 +223,49 @@ async fn send_with_delay_and_idempotency_key() {
     );
     test_env.shutdown().await;
 }
+
+#[test(restate_core::test)]
+async fn send_at_local_time() {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let local_time: ZonedDateTime = "2099-03-30T09:00:00[Europe/Berlin]".parse().unwrap();
+    let wake_up_time = local_time.resolve();
+
+    let actions = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            execution_time: Some(wake_up_time),
+            execution_wall_clock_time: Some(local_time),
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            not(contains(matchers::actions::invoke_for_id(invocation_id))),
+            contains(eq(Action::RegisterTimer {
+                timer_value: TimerKeyValue::neo_invoke_at_local_time(
+                    wake_up_time,
+                    invocation_id,
+                    local_time
+                )
+            }))
+        )
+    );
+
+    // Now fire the timer
+    let actions = test_env
+        .apply(Command::Timer(TimerKeyValue::neo_invoke_at_local_time(
+            wake_up_time,
+            invocation_id,
+            local_time,
+        )))
+        .await;
+
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+    test_env.shutdown().await;
+}
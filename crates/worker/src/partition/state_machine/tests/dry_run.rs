@@ -0,0 +1,217 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_types::deployment::PinnedDeployment;
+use restate_types::errors::DRY_RUN_CALL_INVOCATION_ERROR;
+use restate_types::identifiers::DeploymentId;
+use restate_types::invocation::{InvocationRequest, InvocationRequestHeader};
+use restate_types::journal::OneWayCallEntry;
+use restate_types::service_protocol::ServiceProtocolVersion;
+
+async fn start_dry_run_invocation(test_env: &mut TestEnv) -> InvocationId {
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+
+    let actions = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            invocation_target,
+            dry_run: true,
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+
+    invocation_id
+}
+
+#[test(restate_core::test)]
+async fn dry_run_call_fails_without_invoking_the_callee() {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = start_dry_run_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::invoke(
+                    InvokeRequest {
+                        service_name: "OtherService".into(),
+                        handler_name: "MyMethod".into(),
+                        parameter: Bytes::default(),
+                        headers: vec![],
+                        key: Default::default(),
+                        idempotency_key: None,
+                    },
+                    None,
+                )),
+            },
+        }))
+        .await;
+
+    assert_that!(
+        actions,
+        all!(
+            not(contains(pat!(Action::NewOutboxMessage { .. }))),
+            contains(matchers::actions::forward_completion(
+                invocation_id,
+                eq(Completion::new(
+                    1,
+                    CompletionResult::from(&DRY_RUN_CALL_INVOCATION_ERROR)
+                ))
+            ))
+        )
+    );
+    test_env.shutdown().await;
+}
+
+#[test(restate_core::test)]
+async fn dry_run_drops_one_way_calls_and_awakeable_completions() {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = start_dry_run_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply_multiple(vec![
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 1,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::OneWayCall(
+                        OneWayCallEntry {
+                            request: InvokeRequest {
+                                service_name: "OtherService".into(),
+                                handler_name: "MyMethod".into(),
+                                parameter: Bytes::default(),
+                                headers: vec![],
+                                key: Default::default(),
+                                idempotency_key: None,
+                            },
+                            // delayed sends are dropped as well
+                            invoke_time: 1337,
+                        },
+                    )),
+                },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 2,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::CompleteAwakeable(
+                        CompleteAwakeableEntry {
+                            id: AwakeableIdentifier::new(InvocationId::mock_random(), 1)
+                                .to_string()
+                                .into(),
+                            result: EntryResult::Success(Bytes::default()),
+                        },
+                    )),
+                },
+            }),
+        ])
+        .await;
+
+    assert_that!(
+        actions,
+        all!(
+            not(contains(pat!(Action::NewOutboxMessage { .. }))),
+            not(contains(pat!(Action::RegisterTimer { .. })))
+        )
+    );
+
+    // The entries are still stored, so the invocation can be replayed
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await
+            .unwrap()
+            .get_journal_metadata()
+            .unwrap()
+            .length,
+        eq(3)
+    );
+    test_env.shutdown().await;
+}
+
+#[test(restate_core::test)]
+async fn mirrored_invocation_has_no_side_effects() {
+    let mut test_env = TestEnv::create().await;
+
+    // Mirrored requests are dry-runs pinned to the shadow deployment, see the ingress
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let mut header = InvocationRequestHeader::initialize(invocation_id, invocation_target);
+    header.pinned_deployment = Some(PinnedDeployment::new(
+        DeploymentId::new(),
+        ServiceProtocolVersion::V3,
+    ));
+    header.dry_run = true;
+    let actions = test_env
+        .apply(Command::Invoke(ServiceInvocation::from_request(
+            InvocationRequest::new(header, Bytes::default()),
+            Source::Ingress(PartitionProcessorRpcRequestId::new()),
+        )))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+
+    let actions = test_env
+        .apply_multiple(vec![
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 1,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::invoke(
+                        InvokeRequest {
+                            service_name: "OtherService".into(),
+                            handler_name: "MyMethod".into(),
+                            parameter: Bytes::default(),
+                            headers: vec![],
+                            key: Default::default(),
+                            idempotency_key: None,
+                        },
+                        None,
+                    )),
+                },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::End,
+            }),
+        ])
+        .await;
+
+    // Neither the call nor a response leaves the partition
+    assert_that!(
+        actions,
+        all!(
+            not(contains(pat!(Action::NewOutboxMessage { .. }))),
+            not(contains(pat!(Action::IngressResponse { .. })))
+        )
+    );
+    test_env.shutdown().await;
+}
@@ -31,6 +31,7 @@ pub fn completed_invoke_entry(invocation_id: InvocationId) -> JournalEntry {
                 invocation_id,
                 invocation_target: InvocationTarget::mock_service(),
                 completion_retention_time: None,
+                shared_concurrency_limit: None,
                 span_context: ServiceInvocationSpanContext::empty(),
             }),
         },
@@ -45,6 +46,7 @@ pub fn background_invoke_entry(invocation_id: InvocationId) -> JournalEntry {
                 invocation_id,
                 invocation_target: InvocationTarget::mock_service(),
                 completion_retention_time: None,
+                shared_concurrency_limit: None,
                 span_context: ServiceInvocationSpanContext::empty(),
             },
         },
@@ -60,6 +62,7 @@ pub fn incomplete_invoke_entry(invocation_id: InvocationId) -> JournalEntry {
                 invocation_id,
                 invocation_target: InvocationTarget::mock_service(),
                 completion_retention_time: None,
+                shared_concurrency_limit: None,
                 span_context: ServiceInvocationSpanContext::empty(),
             }),
         },
@@ -94,8 +97,13 @@ pub async fn mock_start_invocation_with_invocation_target(
             span_context: Default::default(),
             headers: vec![],
             execution_time: None,
+            execution_wall_clock_time: None,
             completion_retention_duration: None,
             idempotency_key: None,
+            pinned_deployment: None,
+            dry_run: false,
+            priority: Default::default(),
+            shared_concurrency_limit: None,
             submit_notification_sink: None,
         }))
         .await;
@@ -15,8 +15,11 @@ use restate_storage_api::idempotency_table::{
     IdempotencyMetadata, IdempotencyTable, ReadOnlyIdempotencyTable,
 };
 use restate_storage_api::inbox_table::{InboxEntry, ReadOnlyInboxTable, SequenceNumberInboxEntry};
+use restate_storage_api::invocation_index_table::{
+    InvocationIndexEntry, InvocationIndexTable, InvocationLookupKey, ReadOnlyInvocationIndexTable,
+};
 use restate_storage_api::invocation_status_table::{CompletedInvocation, StatusTimestamps};
-use restate_types::identifiers::{IdempotencyId, PartitionProcessorRpcRequestId};
+use restate_types::identifiers::{IdempotencyId, InvocationUuid, PartitionProcessorRpcRequestId};
 use restate_types::invocation::{
     AttachInvocationRequest, InvocationQuery, InvocationTarget, PurgeInvocationRequest,
     SubmitNotificationSink,
@@ -88,6 +91,8 @@ async fn start_and_complete_idempotent_invocation(#[case] disable_idempotency_ta
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -97,6 +102,8 @@ async fn start_and_complete_idempotent_invocation(#[case] disable_idempotency_ta
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -196,6 +203,8 @@ async fn start_and_complete_idempotent_invocation_neo_table(
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -205,6 +214,8 @@ async fn start_and_complete_idempotent_invocation_neo_table(
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -271,6 +282,8 @@ async fn complete_already_completed_invocation(#[case] disable_idempotency_table
             timestamps: StatusTimestamps::now(),
             response_result: ResponseResult::Success(response_bytes.clone()),
             completion_retention_duration: Default::default(),
+            pinned_deployment: None,
+            retry_count: 0,
         }),
     )
     .await;
@@ -359,6 +372,8 @@ async fn attach_with_service_invocation_command_while_executing(
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -368,6 +383,8 @@ async fn attach_with_service_invocation_command_while_executing(
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -475,6 +492,8 @@ async fn attach_with_send_service_invocation(
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -484,6 +503,8 @@ async fn attach_with_send_service_invocation(
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -584,7 +605,8 @@ async fn attach_inboxed_with_send_service_invocation(#[case] disable_idempotency
         some(pat!(SequenceNumberInboxEntry {
             inbox_entry: eq(InboxEntry::Invocation(
                 invocation_target.as_keyed_service_id().unwrap(),
-                invocation_id
+                invocation_id,
+                Default::default()
             ))
         }))
     );
@@ -676,6 +698,8 @@ async fn attach_command(#[case] disable_idempotency_table: bool) {
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -685,6 +709,8 @@ async fn attach_command(#[case] disable_idempotency_table: bool) {
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -785,10 +811,15 @@ async fn purge_completed_idempotent_invocation(#[case] disable_idempotency_table
     let idempotency_id =
         IdempotencyId::combine(invocation_id, &invocation_target, idempotency_key.clone());
 
-    // Prepare idempotency metadata and completed status
+    // Prepare idempotency metadata, index entry and completed status
     let mut txn = test_env.storage().transaction();
     txn.put_idempotency_metadata(&idempotency_id, &IdempotencyMetadata { invocation_id })
         .await;
+    txn.put_invocation_index_entry(
+        &InvocationLookupKey::Idempotency(idempotency_id.clone()),
+        &InvocationIndexEntry { invocation_id },
+    )
+    .await;
     txn.put_invocation_status(
         &invocation_id,
         &InvocationStatus::Completed(CompletedInvocation {
@@ -822,5 +853,145 @@ async fn purge_completed_idempotent_invocation(#[case] disable_idempotency_table
             .unwrap(),
         none()
     );
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_index_entry(&InvocationLookupKey::Idempotency(idempotency_id))
+            .await
+            .unwrap(),
+        none()
+    );
+    test_env.shutdown().await;
+}
+
+#[restate_core::test]
+async fn attach_by_idempotency_id_through_invocation_index() {
+    let mut test_env = TestEnv::create_with_options(true).await;
+
+    let idempotency_key = ByteString::from_static("my-idempotency-key");
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    // Use an invocation id which is not derived from the idempotency key, as generated by older versions
+    let invocation_id = InvocationId::from_parts(
+        InvocationId::generate(&invocation_target, Some(&idempotency_key)).partition_key(),
+        InvocationUuid::mock_random(),
+    );
+    let idempotency_id =
+        IdempotencyId::combine(invocation_id, &invocation_target, idempotency_key.clone());
+
+    // Send fresh invocation with idempotency key
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            idempotency_key: Some(idempotency_key.clone()),
+            completion_retention_duration: Some(Duration::from_secs(60)),
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_index_entry(&InvocationLookupKey::Idempotency(idempotency_id.clone()))
+            .await
+            .unwrap(),
+        some(eq(InvocationIndexEntry { invocation_id }))
+    );
+
+    // Attaching by idempotency id finds the running invocation
+    let caller_invocation_id = InvocationId::mock_random();
+    let actions = test_env
+        .apply(Command::AttachInvocation(AttachInvocationRequest {
+            invocation_query: InvocationQuery::IdempotencyId(idempotency_id),
+            block_on_inflight: false,
+            response_sink: ServiceInvocationResponseSink::PartitionProcessor {
+                caller: caller_invocation_id,
+                entry_index: 1,
+            },
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(invocation_response_to_partition_processor(
+            caller_invocation_id,
+            1,
+            eq(ResponseResult::from(NOT_READY_INVOCATION_ERROR))
+        ))
+    );
+
+    test_env.shutdown().await;
+}
+
+#[restate_core::test]
+async fn complete_idempotent_invocation_without_retention_removes_index_entry() {
+    let mut test_env = TestEnv::create_with_options(true).await;
+
+    let idempotency_key = ByteString::from_static("my-idempotency-key");
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let invocation_id = InvocationId::generate(&invocation_target, Some(&idempotency_key));
+    let lookup_key = InvocationLookupKey::Idempotency(IdempotencyId::combine(
+        invocation_id,
+        &invocation_target,
+        idempotency_key.clone(),
+    ));
+
+    // Send fresh invocation with idempotency key, but without completion retention
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            idempotency_key: Some(idempotency_key),
+            completion_retention_duration: None,
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_index_entry(&lookup_key)
+            .await
+            .unwrap(),
+        some(eq(InvocationIndexEntry { invocation_id }))
+    );
+
+    // Send output, then end
+    let _ = test_env
+        .apply_multiple([
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 1,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
+                        EntryResult::Success(Bytes::from_static(b"123")),
+                    )),
+                },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::End,
+            }),
+        ])
+        .await;
+
+    // The invocation is freed together with its index entry
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await
+            .unwrap(),
+        eq(InvocationStatus::Free)
+    );
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_index_entry(&lookup_key)
+            .await
+            .unwrap(),
+        none()
+    );
     test_env.shutdown().await;
 }
@@ -0,0 +1,202 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use assert2::let_assert;
+use restate_storage_api::journal_table_v2::ReadOnlyJournalTableV2;
+use test_log::test;
+
+#[test(restate_core::test)]
+async fn journal_v2_lifecycle() -> TestResult {
+    let mut test_env = TestEnv::create_with_journal_version(JournalVersion::V2).await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let_assert!(
+        InvocationStatus::Invoked(in_flight_meta) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(in_flight_meta.journal_metadata.version, JournalVersion::V2);
+
+    // The input entry is stored in the journal table v2 only
+    assert!(
+        ReadOnlyJournalTableV2::get_journal_entry(&mut test_env.storage, &invocation_id, 0)
+            .await?
+            .is_some()
+    );
+    assert!(
+        ReadOnlyJournalTable::get_journal_entry(&mut test_env.storage, &invocation_id, 0)
+            .await?
+            .is_none()
+    );
+
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(Entry::awakeable(None)),
+            },
+        }))
+        .await;
+
+    let append_time =
+        ReadOnlyJournalTableV2::get_journal_entry(&mut test_env.storage, &invocation_id, 1)
+            .await?
+            .unwrap()
+            .append_time;
+    assert!(append_time.is_some());
+
+    // The awakeable awaits its completion, which is indexed by journal index
+    assert_eq!(
+        test_env
+            .storage
+            .get_pending_completion(&invocation_id, 1)
+            .await?,
+        Some(1)
+    );
+
+    let actions = test_env
+        .apply(Command::InvocationResponse(InvocationResponse {
+            id: invocation_id,
+            entry_index: 1,
+            result: ResponseResult::Success(Bytes::default()),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::ForwardCompletion {
+            invocation_id: eq(invocation_id),
+            completion: eq(Completion::new(
+                1,
+                CompletionResult::Success(Bytes::default())
+            ))
+        }))
+    );
+
+    let stored_entry =
+        ReadOnlyJournalTableV2::get_journal_entry(&mut test_env.storage, &invocation_id, 1)
+            .await?
+            .unwrap();
+    assert!(!stored_entry.is_pending());
+    // Completing the entry keeps the time it was appended at
+    assert_eq!(stored_entry.append_time, append_time);
+    assert_eq!(
+        test_env
+            .storage
+            .get_pending_completion(&invocation_id, 1)
+            .await?,
+        None
+    );
+
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+
+    // Ending the invocation drops its journal from the journal table v2
+    assert!(
+        ReadOnlyJournalTableV2::get_journal(&mut test_env.storage, &invocation_id, 2)
+            .try_collect::<Vec<_>>()
+            .await?
+            .is_empty()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn migrate_journal_v1_on_resume() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let _ = test_env
+        .apply_multiple([
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::JournalEntry {
+                    entry_index: 1,
+                    entry: ProtobufRawEntryCodec::serialize_enriched(Entry::awakeable(None)),
+                },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::Suspended {
+                    waiting_for_completed_entries: HashSet::from([1]),
+                },
+            }),
+        ])
+        .await;
+
+    // The journal table v2 is enabled while the invocation is suspended
+    test_env.state_machine.journal_version = JournalVersion::V2;
+
+    let actions = test_env
+        .apply(Command::InvocationResponse(InvocationResponse {
+            id: invocation_id,
+            entry_index: 1,
+            result: ResponseResult::Success(Bytes::default()),
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::Invoke {
+            invocation_id: eq(invocation_id)
+        }))
+    );
+
+    // The resumed invocation reads its journal from the journal table v2
+    let_assert!(
+        InvocationStatus::Invoked(in_flight_meta) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(in_flight_meta.journal_metadata.version, JournalVersion::V2);
+
+    for journal_index in 0..2 {
+        assert!(ReadOnlyJournalTable::get_journal_entry(
+            &mut test_env.storage,
+            &invocation_id,
+            journal_index
+        )
+        .await?
+        .is_none());
+    }
+    assert!(
+        ReadOnlyJournalTableV2::get_journal_entry(&mut test_env.storage, &invocation_id, 0)
+            .await?
+            .is_some()
+    );
+    let stored_entry =
+        ReadOnlyJournalTableV2::get_journal_entry(&mut test_env.storage, &invocation_id, 1)
+            .await?
+            .unwrap();
+    assert!(!stored_entry.is_pending());
+    assert_eq!(stored_entry.completion_id, Some(1));
+    // The append time of migrated entries stays unknown, also once they are completed
+    assert_eq!(stored_entry.append_time, None);
+
+    test_env.shutdown().await;
+    Ok(())
+}
@@ -17,7 +17,10 @@ use prost::Message;
 use restate_storage_api::journal_table::JournalTable;
 use restate_storage_api::timer_table::{Timer, TimerKey, TimerKeyKind, TimerTable};
 use restate_types::identifiers::EntryIndex;
-use restate_types::invocation::TerminationFlavor;
+use restate_types::invocation::{
+    BulkInvocationOperation, BulkInvocationTermination, InvocationOperation,
+    InvocationTargetFilter, TerminationFlavor,
+};
 use restate_types::journal::enriched::EnrichedEntryHeader;
 use restate_types::service_protocol;
 use test_log::test;
@@ -218,6 +221,117 @@ async fn kill_call_tree() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(restate_core::test)]
+async fn bulk_kill_invocations_by_target() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let matching_target = InvocationTarget::virtual_object(
+        "Counter",
+        "user-1",
+        "add",
+        VirtualObjectHandlerType::Exclusive,
+    );
+    let other_key_target = InvocationTarget::virtual_object(
+        "Counter",
+        "admin",
+        "add",
+        VirtualObjectHandlerType::Exclusive,
+    );
+    let other_handler_target = InvocationTarget::virtual_object(
+        "Counter",
+        "user-2",
+        "get",
+        VirtualObjectHandlerType::Shared,
+    );
+
+    let matching_id =
+        fixtures::mock_start_invocation_with_invocation_target(&mut test_env, matching_target)
+            .await;
+    let other_key_id =
+        fixtures::mock_start_invocation_with_invocation_target(&mut test_env, other_key_target)
+            .await;
+    let other_handler_id =
+        fixtures::mock_start_invocation_with_invocation_target(&mut test_env, other_handler_target)
+            .await;
+
+    let actions = test_env
+        .apply(Command::BulkTerminateInvocations(
+            BulkInvocationTermination {
+                filter: InvocationTargetFilter::service("Counter")
+                    .with_handler_name("add")
+                    .with_key_prefix("user-"),
+                flavor: TerminationFlavor::Kill,
+            },
+        ))
+        .await;
+
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation(eq(matching_id)))),
+            not(contains(pat!(Action::AbortInvocation(any!(
+                eq(other_key_id),
+                eq(other_handler_id)
+            )))))
+        )
+    );
+    assert_that!(
+        test_env.storage.get_invocation_status(&matching_id).await?,
+        pat!(InvocationStatus::Free)
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&other_key_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&other_handler_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn bulk_kill_invocations_by_id() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let killed_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let other_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::BulkInvocationOperation(BulkInvocationOperation {
+            invocation_ids: vec![killed_id],
+            operation: InvocationOperation::Terminate(TerminationFlavor::Kill),
+        }))
+        .await;
+
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation(eq(killed_id)))),
+            not(contains(pat!(Action::AbortInvocation(eq(other_id)))))
+        )
+    );
+    assert_that!(
+        test_env.storage.get_invocation_status(&killed_id).await?,
+        pat!(InvocationStatus::Free)
+    );
+    assert_that!(
+        test_env.storage.get_invocation_status(&other_id).await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
 #[test(restate_core::test)]
 async fn cancel_invoked_invocation() -> Result<(), Error> {
     let mut test_env = TestEnv::create().await;
@@ -491,6 +605,8 @@ async fn cancel_invocation_entry_referring_to_previous_entry() {
         .apply_multiple(vec![
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 3,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::cancel_invocation(
@@ -500,6 +616,8 @@ async fn cancel_invocation_entry_referring_to_previous_entry() {
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 4,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::cancel_invocation(
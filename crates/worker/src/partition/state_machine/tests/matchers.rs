@@ -35,7 +35,8 @@ pub mod storage {
         pat!(SequenceNumberInboxEntry {
             inbox_entry: pat!(InboxEntry::Invocation(
                 eq(invocation_target.as_keyed_service_id().unwrap()),
-                eq(invocation_id)
+                eq(invocation_id),
+                anything()
             ))
         })
     }
@@ -0,0 +1,94 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{fixtures, matchers, *};
+
+use assert2::let_assert;
+use restate_types::deployment::{DeploymentMigration, MigrationTarget, PinnedDeployment};
+use restate_types::identifiers::DeploymentId;
+use restate_types::service_protocol::ServiceProtocolVersion;
+use test_log::test;
+
+#[test(restate_core::test)]
+async fn migrate_invocations_of_deployment() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let old_deployment_id = DeploymentId::new();
+    let new_deployment_id = DeploymentId::new();
+
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    let service_name = metadata.invocation_target.service_name().clone();
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::PinnedDeployment(PinnedDeployment::new(
+                old_deployment_id,
+                ServiceProtocolVersion::V2,
+            )),
+        }))
+        .await;
+
+    // The target deployment doesn't support the protocol version of the invocation
+    let actions = test_env
+        .apply(Command::MigrateDeployment(DeploymentMigration {
+            deployment_id: old_deployment_id,
+            targets: vec![MigrationTarget {
+                service_name: service_name.clone(),
+                deployment_id: new_deployment_id,
+                supported_protocol_versions: 3..=4,
+            }],
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+
+    let actions = test_env
+        .apply(Command::MigrateDeployment(DeploymentMigration {
+            deployment_id: old_deployment_id,
+            targets: vec![MigrationTarget {
+                service_name,
+                deployment_id: new_deployment_id,
+                supported_protocol_versions: 1..=4,
+            }],
+        }))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation(eq(invocation_id)))),
+            contains(matchers::actions::invoke_for_id(invocation_id))
+        )
+    );
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(
+        metadata.pinned_deployment,
+        Some(PinnedDeployment::new(
+            new_deployment_id,
+            ServiceProtocolVersion::V2
+        ))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
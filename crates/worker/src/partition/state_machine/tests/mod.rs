@@ -10,11 +10,21 @@
 
 use super::*;
 
+mod consistency;
+mod dead_letters;
 mod delayed_send;
+mod dry_run;
 mod fixtures;
 mod idempotency;
+mod journal_v2;
 mod kill_cancel;
 mod matchers;
+mod migrate;
+mod pause;
+mod prepared_messages;
+mod retry;
+mod schedule;
+mod split;
 mod workflow;
 
 use crate::partition::state_machine::tests::fixtures::{
@@ -35,7 +45,11 @@ use restate_partition_store::{OpenMode, PartitionStore, PartitionStoreManager};
 use restate_rocksdb::RocksDbManager;
 use restate_service_protocol::awakeable_id::AwakeableIdentifier;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_storage_api::deduplication_table::{
+    DedupSequenceNumber, DeduplicationTable, EpochSequenceNumber, ProducerId,
+};
 use restate_storage_api::inbox_table::ReadOnlyInboxTable;
+use restate_storage_api::invocation_event_table::ReadOnlyInvocationEventTable;
 use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
     ReadOnlyInvocationStatusTable,
@@ -43,7 +57,8 @@ use restate_storage_api::invocation_status_table::{
 use restate_storage_api::journal_table::{JournalEntry, ReadOnlyJournalTable};
 use restate_storage_api::outbox_table::OutboxTable;
 use restate_storage_api::service_status_table::{
-    ReadOnlyVirtualObjectStatusTable, VirtualObjectStatus, VirtualObjectStatusTable,
+    ReadOnlyVirtualObjectStatusTable, SharedHandlerExecutions, VirtualObjectStatus,
+    VirtualObjectStatusTable,
 };
 use restate_storage_api::state_table::{ReadOnlyStateTable, StateTable};
 use restate_storage_api::Transaction;
@@ -51,7 +66,7 @@ use restate_test_util::matchers::*;
 use restate_types::config::{CommonOptions, WorkerOptions};
 use restate_types::errors::{codes, InvocationError, KILLED_INVOCATION_ERROR};
 use restate_types::identifiers::{
-    InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, ServiceId,
+    InvocationId, LeaderEpoch, PartitionId, PartitionKey, PartitionProcessorRpcRequestId, ServiceId,
 };
 use restate_types::invocation::{
     Header, InvocationResponse, InvocationTarget, InvocationTermination, ResponseResult,
@@ -65,6 +80,7 @@ use restate_types::journal::{Entry, EntryType};
 use restate_types::live::{Constant, Live};
 use restate_types::state_mut::ExternalStateMutation;
 use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
 use test_log::test;
 use tracing_subscriber::fmt::format::FmtSpan;
 
@@ -92,6 +108,19 @@ impl TestEnv {
             None, /* outbox_head_seq_number */
             PartitionKey::MIN..=PartitionKey::MAX,
             disable_idempotency_table,
+            JournalVersion::V1,
+        ))
+        .await
+    }
+
+    pub async fn create_with_journal_version(journal_version: JournalVersion) -> Self {
+        Self::create_with_state_machine(StateMachine::new(
+            0,    /* inbox_seq_number */
+            0,    /* outbox_seq_number */
+            None, /* outbox_head_seq_number */
+            PartitionKey::MIN..=PartitionKey::MAX,
+            false,
+            journal_version,
         ))
         .await
     }
@@ -154,7 +183,13 @@ impl TestEnv {
         let mut transaction = self.storage.transaction();
         let mut action_collector = ActionCollector::default();
         self.state_machine
-            .apply(command, &mut transaction, &mut action_collector, true)
+            .apply(
+                command,
+                MillisSinceEpoch::now(),
+                &mut transaction,
+                &mut action_collector,
+                true,
+            )
             .await
             .unwrap();
 
@@ -268,6 +303,8 @@ async fn awakeable_completion_received_before_entry() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::awakeable(None)),
@@ -325,6 +362,8 @@ async fn awakeable_completion_received_before_entry() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::Suspended {
                 waiting_for_completed_entries: HashSet::from([1]),
             },
@@ -360,6 +399,8 @@ async fn complete_awakeable_with_success() {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: EffectKind::JournalEntry {
                 entry_index: 1,
                 entry,
@@ -403,6 +444,8 @@ async fn complete_awakeable_with_failure() {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: EffectKind::JournalEntry {
                 entry_index: 1,
                 entry,
@@ -440,6 +483,8 @@ async fn invoke_with_headers() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::invoke(
@@ -526,6 +571,8 @@ async fn mutate_state() -> anyhow::Result<()> {
     test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::End,
         }))
         .await;
@@ -561,6 +608,8 @@ async fn clear_all_user_states() -> anyhow::Result<()> {
     test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::clear_all_state()),
@@ -595,6 +644,8 @@ async fn get_state_keys() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::get_state_keys(None)),
@@ -645,6 +696,8 @@ async fn get_invocation_id_entry() {
         .apply_multiple(vec![
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 3,
                     entry: ProtobufRawEntryCodec::serialize_enriched(
@@ -654,6 +707,8 @@ async fn get_invocation_id_entry() {
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 4,
                     entry: ProtobufRawEntryCodec::serialize_enriched(
@@ -719,6 +774,8 @@ async fn attach_invocation_entry() {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: EffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::AttachInvocation(
@@ -763,6 +820,8 @@ async fn get_invocation_output_entry() {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: EffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::GetInvocationOutput(
@@ -836,8 +895,13 @@ async fn send_ingress_response_to_multiple_targets() -> TestResult {
             span_context: Default::default(),
             headers: vec![],
             execution_time: None,
+            execution_wall_clock_time: None,
             completion_retention_duration: None,
             idempotency_key: None,
+            pinned_deployment: None,
+            dry_run: false,
+            priority: Default::default(),
+            shared_concurrency_limit: None,
             submit_notification_sink: None,
         }))
         .await;
@@ -871,6 +935,8 @@ async fn send_ingress_response_to_multiple_targets() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::JournalEntry {
                 entry_index: 1,
                 entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -886,6 +952,8 @@ async fn send_ingress_response_to_multiple_targets() -> TestResult {
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::End,
         }))
         .await;
@@ -956,6 +1024,7 @@ async fn truncate_outbox_with_gap() -> Result<(), Error> {
             Some(outbox_head_index),
             PartitionKey::MIN..=PartitionKey::MAX,
             false,
+            JournalVersion::V1,
         ))
         .await;
 
@@ -1044,6 +1113,8 @@ async fn consecutive_exclusive_handler_invocations_will_use_inbox() -> TestResul
     let actions = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id: first_invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::End,
         }))
         .await;
@@ -1063,6 +1134,8 @@ async fn consecutive_exclusive_handler_invocations_will_use_inbox() -> TestResul
     let _ = test_env
         .apply(Command::InvokerEffect(InvokerEffect {
             invocation_id: second_invocation_id,
+            leader_epoch: None,
+            invocation_epoch: 0,
             kind: InvokerEffectKind::End,
         }))
         .await;
@@ -1088,6 +1161,245 @@ async fn consecutive_exclusive_handler_invocations_will_use_inbox() -> TestResul
     Ok(())
 }
 
+#[test(restate_core::test)]
+async fn shared_handler_invocations_are_limited_per_key() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::virtual_object(
+        "MySvc",
+        "MyKey",
+        "MyHandler",
+        VirtualObjectHandlerType::Shared,
+    );
+    let keyed_service_id = invocation_target.as_keyed_service_id().unwrap();
+    let invocation_ids: Vec<_> = (0..3)
+        .map(|_| InvocationId::mock_generate(&invocation_target))
+        .collect();
+
+    // Only two invocations can run at the same time
+    for invocation_id in &invocation_ids {
+        let _ = test_env
+            .apply(Command::Invoke(ServiceInvocation {
+                invocation_id: *invocation_id,
+                invocation_target: invocation_target.clone(),
+                shared_concurrency_limit: NonZeroU32::new(2),
+                ..ServiceInvocation::mock()
+            }))
+            .await;
+    }
+    for invocation_id in &invocation_ids[..2] {
+        assert_that!(
+            test_env.storage.get_invocation_status(invocation_id).await,
+            ok(pat!(InvocationStatus::Invoked(_)))
+        );
+    }
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_ids[2])
+            .await,
+        ok(pat!(InvocationStatus::Inboxed(_)))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_shared_handler_executions(&keyed_service_id)
+            .await,
+        ok(eq(SharedHandlerExecutions {
+            running: invocation_ids[..2].to_vec(),
+            pending: [invocation_ids[2]].into(),
+        }))
+    );
+
+    // Completing the first invocation starts the pending one
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id: invocation_ids[0],
+            leader_epoch: None,
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_ids[2]))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_shared_handler_executions(&keyed_service_id)
+            .await,
+        ok(eq(SharedHandlerExecutions {
+            running: invocation_ids[1..].to_vec(),
+            pending: Default::default(),
+        }))
+    );
+
+    // Once all the invocations are completed, nothing is tracked anymore
+    for invocation_id in &invocation_ids[1..] {
+        let _ = test_env
+            .apply(Command::InvokerEffect(InvokerEffect {
+                invocation_id: *invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::End,
+            }))
+            .await;
+    }
+    assert_that!(
+        test_env
+            .storage
+            .get_shared_handler_executions(&keyed_service_id)
+            .await,
+        ok(predicate(SharedHandlerExecutions::is_empty))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn record_retries_of_the_invocation() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    for retries in [1, 3] {
+        test_env
+            .apply(Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::RetryScheduled { retries },
+            }))
+            .await;
+    }
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await,
+        ok(pat!(InvocationStatus::Invoked(pat!(
+            InFlightInvocationMetadata { retry_count: eq(4) }
+        ))))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn record_invocation_timeline() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    test_env
+        .apply_multiple([
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::RetryScheduled { retries: 2 },
+            }),
+            Command::InvokerEffect(InvokerEffect {
+                invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
+                kind: InvokerEffectKind::Suspended {
+                    waiting_for_completed_entries: HashSet::from([2, 1]),
+                },
+            }),
+        ])
+        .await;
+
+    let events: Vec<_> = test_env
+        .storage()
+        .all_invocation_events(PartitionKey::MIN..=PartitionKey::MAX)
+        .try_collect()
+        .await?;
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (event.seq_number, event.kind.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, InvocationEventKind::Created),
+            (
+                1,
+                InvocationEventKind::Started {
+                    attempt: 1,
+                    deployment_id: None
+                }
+            ),
+            (2, InvocationEventKind::RetryScheduled { attempt: 2 }),
+            (
+                3,
+                InvocationEventKind::Suspended {
+                    waiting_for_entries: vec![1, 2]
+                }
+            )
+        ]
+    );
+    assert!(events
+        .iter()
+        .all(|event| event.invocation_id == invocation_id));
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn drop_invoker_effects_of_deposed_leader() -> TestResult {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    // A new leader with epoch 2 has taken over the partition
+    let mut tx = test_env.storage().transaction();
+    tx.put_dedup_seq_number(
+        ProducerId::self_producer(),
+        &DedupSequenceNumber::Esn(EpochSequenceNumber::new(LeaderEpoch::from(2))),
+    )
+    .await;
+    tx.commit().await.unwrap();
+
+    // Effects of the deposed leader are dropped
+    let actions = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: Some(LeaderEpoch::from(1)),
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+    assert_that!(actions, empty());
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await,
+        ok(pat!(InvocationStatus::Invoked(_)))
+    );
+
+    // Effects of the current leader are applied
+    test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: Some(LeaderEpoch::from(2)),
+            invocation_epoch: 0,
+            kind: InvokerEffectKind::End,
+        }))
+        .await;
+    assert_that!(
+        test_env
+            .storage()
+            .get_invocation_status(&invocation_id)
+            .await,
+        ok(not(pat!(InvocationStatus::Invoked(_))))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
 #[test(restate_core::test)]
 async fn deduplicate_requests_with_same_pp_rpc_request_id() -> TestResult {
     let mut test_env = TestEnv::create().await;
@@ -0,0 +1,139 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::inbox_table::{InboxEntry, ReadOnlyInboxTable};
+use restate_types::identifiers::PartitionKey;
+use test_log::test;
+
+async fn all_inbox_entries(test_env: &mut TestEnv) -> Vec<InboxEntry> {
+    test_env
+        .storage
+        .all_inboxes(0..=PartitionKey::MAX)
+        .map_ok(|entry| entry.inbox_entry)
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+#[test(restate_core::test)]
+async fn pause_and_resume_service() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+
+    let _ = test_env
+        .apply(Command::PauseService(
+            invocation_target.service_name().clone(),
+        ))
+        .await;
+
+    // The invocation is enqueued without being executed
+    let actions = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Inboxed(_))
+    );
+    // The enqueued invocation is listed with the inbox entries
+    assert_that!(
+        all_inbox_entries(&mut test_env).await,
+        elements_are![pat!(InboxEntry::Invocation(
+            anything(),
+            eq(invocation_id),
+            anything()
+        ))]
+    );
+
+    // Resuming executes it
+    let actions = test_env
+        .apply(Command::ResumeService(
+            invocation_target.service_name().clone(),
+        ))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+    assert_that!(all_inbox_entries(&mut test_env).await, empty());
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn kill_invocation_of_paused_service() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+
+    let _ = test_env
+        .apply(Command::PauseService(
+            invocation_target.service_name().clone(),
+        ))
+        .await;
+    let _ = test_env
+        .apply(Command::Invoke(ServiceInvocation {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            ..ServiceInvocation::mock()
+        }))
+        .await;
+
+    let _ = test_env
+        .apply(Command::TerminateInvocation(InvocationTermination::kill(
+            invocation_id,
+        )))
+        .await;
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Free)
+    );
+    assert_that!(all_inbox_entries(&mut test_env).await, empty());
+
+    // The killed invocation is not executed once the service is resumed
+    let actions = test_env
+        .apply(Command::ResumeService(
+            invocation_target.service_name().clone(),
+        ))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
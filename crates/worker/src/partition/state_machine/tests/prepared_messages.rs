@@ -0,0 +1,130 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_storage_api::prepared_message_table::{
+    PreparedMessageDelivery, ReadOnlyPreparedMessageTable,
+};
+use restate_types::identifiers::InvocationUuid;
+use test_log::test;
+
+#[test(restate_core::test)]
+async fn hand_off_invocation_owned_by_another_partition() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create_with_state_machine(StateMachine::new(
+        0,    /* inbox_seq_number */
+        0,    /* outbox_seq_number */
+        None, /* outbox_head_seq_number */
+        PartitionKey::MIN..=PartitionKey::MAX / 2,
+        false,
+        JournalVersion::V1,
+    ))
+    .await;
+
+    // The invocation id hashes to a partition key outside the key range of this partition
+    let invocation_id = InvocationId::from_parts(PartitionKey::MAX, InvocationUuid::mock_random());
+    let service_invocation = ServiceInvocation {
+        invocation_id,
+        ..ServiceInvocation::mock()
+    };
+    let actions = test_env
+        .apply(Command::Invoke(service_invocation.clone()))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(eq(Action::DeliverPreparedMessages)),
+            not(contains(matchers::actions::invoke_for_id(invocation_id)))
+        )
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Free)
+    );
+    let prepared: Vec<_> = test_env
+        .storage
+        .get_prepared_messages()
+        .try_collect()
+        .await?;
+    assert_eq!(
+        prepared,
+        vec![(0, OutboxMessage::ServiceInvocation(service_invocation))]
+    );
+
+    // The message is deleted once the destination acknowledged it
+    let _ = test_env.apply(Command::AcknowledgePreparedMessage(0)).await;
+    let prepared: Vec<_> = test_env
+        .storage
+        .get_prepared_messages()
+        .try_collect()
+        .await?;
+    assert!(prepared.is_empty());
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn apply_prepared_message_once() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+
+    let source = PartitionId::from(1);
+    let invocation_target = InvocationTarget::mock_service();
+    let invocation_id = InvocationId::mock_generate(&invocation_target);
+    let delivery = PreparedMessageDelivery {
+        source,
+        sequence_number: 0,
+        message: OutboxMessage::ServiceInvocation(ServiceInvocation {
+            invocation_id,
+            invocation_target,
+            ..ServiceInvocation::mock()
+        }),
+    };
+
+    let actions = test_env
+        .apply(Command::ApplyPreparedMessage(delivery.clone()))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(matchers::actions::invoke_for_id(invocation_id)),
+            contains(eq(Action::AcknowledgePreparedMessage {
+                source,
+                sequence_number: 0
+            }))
+        )
+    );
+
+    // Re-delivered messages are acknowledged again without being applied
+    let actions = test_env
+        .apply(Command::ApplyPreparedMessage(delivery))
+        .await;
+    assert_eq!(
+        actions,
+        vec![Action::AcknowledgePreparedMessage {
+            source,
+            sequence_number: 0
+        }]
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?,
+        pat!(InvocationStatus::Invoked(_))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
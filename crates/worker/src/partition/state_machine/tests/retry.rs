@@ -0,0 +1,190 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::{fixtures, matchers, *};
+
+use assert2::let_assert;
+use restate_types::invocation::{InvocationEpoch, RetryInvocationRequest};
+use test_log::test;
+
+async fn append_entry(
+    test_env: &mut TestEnv,
+    invocation_id: InvocationId,
+    invocation_epoch: InvocationEpoch,
+    entry: Entry,
+) {
+    let _ = test_env
+        .apply(Command::InvokerEffect(InvokerEffect {
+            invocation_id,
+            leader_epoch: None,
+            invocation_epoch,
+            kind: InvokerEffectKind::JournalEntry {
+                entry_index: 1,
+                entry: ProtobufRawEntryCodec::serialize_enriched(entry),
+            },
+        }))
+        .await;
+}
+
+#[test(restate_core::test)]
+async fn retry_invoked_invocation() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    append_entry(&mut test_env, invocation_id, 0, Entry::clear_all_state()).await;
+
+    let actions = test_env
+        .apply(Command::RetryInvocation(RetryInvocationRequest {
+            invocation_id,
+            restart: false,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(pat!(Action::AbortInvocation(eq(invocation_id)))),
+            contains(matchers::actions::invoke_for_id(invocation_id))
+        )
+    );
+
+    // The journal is left untouched
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.journal_metadata.length, 2);
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_invocation_truncates_journal() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    append_entry(&mut test_env, invocation_id, 0, Entry::clear_all_state()).await;
+
+    let actions = test_env
+        .apply(Command::RetryInvocation(RetryInvocationRequest {
+            invocation_id,
+            restart: true,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(matchers::actions::invoke_for_id(invocation_id))
+    );
+
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.journal_metadata.length, 1);
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 0)
+            .await?,
+        some(anything())
+    );
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 1)
+            .await?,
+        none()
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn restart_is_ignored_with_pending_completions() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+    append_entry(&mut test_env, invocation_id, 0, Entry::awakeable(None)).await;
+
+    let actions = test_env
+        .apply(Command::RetryInvocation(RetryInvocationRequest {
+            invocation_id,
+            restart: true,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(invocation_id)))
+    );
+
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.journal_metadata.length, 2);
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn effects_of_restarted_attempt_are_dropped() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let invocation_id = fixtures::mock_start_invocation(&mut test_env).await;
+
+    let actions = test_env
+        .apply(Command::RetryInvocation(RetryInvocationRequest {
+            invocation_id,
+            restart: true,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        contains(pat!(Action::Invoke {
+            invocation_id: eq(invocation_id),
+            invocation_epoch: eq(1)
+        }))
+    );
+
+    // The discarded attempt still produces an effect before being aborted
+    append_entry(&mut test_env, invocation_id, 0, Entry::clear_all_state()).await;
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.invocation_epoch, 1);
+    assert_eq!(metadata.journal_metadata.length, 1);
+    assert_that!(
+        test_env
+            .storage
+            .get_journal_entry(&invocation_id, 1)
+            .await?,
+        none()
+    );
+
+    // The effects of the new attempt are applied
+    append_entry(&mut test_env, invocation_id, 1, Entry::clear_all_state()).await;
+    let_assert!(
+        InvocationStatus::Invoked(metadata) = test_env
+            .storage
+            .get_invocation_status(&invocation_id)
+            .await?
+    );
+    assert_eq!(metadata.journal_metadata.length, 2);
+
+    test_env.shutdown().await;
+    Ok(())
+}
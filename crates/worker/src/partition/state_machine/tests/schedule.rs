@@ -0,0 +1,179 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::schedule_table::ReadOnlyScheduleTable;
+use restate_types::invocation::{InvocationRequest, InvocationRequestHeader};
+use restate_types::schedule::{MisfirePolicy, Schedule};
+use restate_types::time::MillisSinceEpoch;
+use test_log::test;
+
+#[test(restate_core::test)]
+async fn fire_schedule() {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_service();
+    let schedule_id = ScheduleId::generate(&invocation_target);
+    let schedule = Schedule {
+        id: schedule_id,
+        cron: "*/5 * * * *".parse().unwrap(),
+        misfire_policy: Default::default(),
+        invocation: InvocationRequest::new(
+            InvocationRequestHeader::initialize(
+                InvocationId::mock_random(),
+                invocation_target.clone(),
+            ),
+            Bytes::from_static(b"input"),
+        ),
+        created_at: MillisSinceEpoch::new(60_000),
+    };
+    let first_fire_time = MillisSinceEpoch::new(5 * 60_000);
+    let second_fire_time = MillisSinceEpoch::new(10 * 60_000);
+
+    let actions = test_env
+        .apply(Command::UpsertSchedule(schedule.clone()))
+        .await;
+    assert_that!(
+        actions,
+        contains(eq(Action::RegisterTimer {
+            timer_value: TimerKeyValue::fire_schedule(first_fire_time, schedule_id)
+        }))
+    );
+
+    // Fire the first occurrence
+    let first_invocation_id = schedule_id.invocation_id_at(first_fire_time);
+    let actions = test_env
+        .apply(Command::Timer(TimerKeyValue::fire_schedule(
+            first_fire_time,
+            schedule_id,
+        )))
+        .await;
+    assert_that!(
+        actions,
+        all!(
+            contains(matchers::actions::invoke_for_id(first_invocation_id)),
+            contains(eq(Action::RegisterTimer {
+                timer_value: TimerKeyValue::fire_schedule(second_fire_time, schedule_id)
+            }))
+        )
+    );
+    let status = test_env
+        .storage
+        .get_schedule(&schedule_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.next_fire_time, second_fire_time);
+    assert_eq!(status.last_invocation_id, Some(first_invocation_id));
+
+    // Deleting the schedule cancels the next occurrence
+    let actions = test_env.apply(Command::DeleteSchedule(schedule_id)).await;
+    assert_that!(
+        actions,
+        contains(eq(Action::DeleteTimer {
+            timer_key: TimerKeyValue::fire_schedule(second_fire_time, schedule_id)
+                .into_inner()
+                .0
+        }))
+    );
+    assert_that!(
+        test_env.storage.get_schedule(&schedule_id).await,
+        ok(none())
+    );
+
+    // A timer of the deleted schedule is ignored
+    let actions = test_env
+        .apply(Command::Timer(TimerKeyValue::fire_schedule(
+            second_fire_time,
+            schedule_id,
+        )))
+        .await;
+    assert_that!(
+        actions,
+        not(contains(matchers::actions::invoke_for_id(
+            schedule_id.invocation_id_at(second_fire_time)
+        )))
+    );
+
+    test_env.shutdown().await;
+}
+
+#[test(restate_core::test)]
+async fn fire_schedule_after_misfire() {
+    let mut test_env = TestEnv::create().await;
+
+    let invocation_target = InvocationTarget::mock_service();
+    let schedule = |misfire_policy| Schedule {
+        id: ScheduleId::generate(&invocation_target),
+        cron: "*/5 * * * *".parse().unwrap(),
+        misfire_policy,
+        invocation: InvocationRequest::new(
+            InvocationRequestHeader::initialize(
+                InvocationId::mock_random(),
+                invocation_target.clone(),
+            ),
+            Bytes::new(),
+        ),
+        created_at: MillisSinceEpoch::new(60_000),
+    };
+    let fire_time = MillisSinceEpoch::new(5 * 60_000);
+    // The timer fires an hour late, after the partition was unavailable
+    let fired_at = MillisSinceEpoch::new(62 * 60_000);
+    let next_fire_time = MillisSinceEpoch::new(65 * 60_000);
+
+    for (misfire_policy, fires, next_fire_time) in [
+        (MisfirePolicy::Skip, false, next_fire_time),
+        (MisfirePolicy::FireOnce, true, next_fire_time),
+        (
+            MisfirePolicy::FireAll,
+            true,
+            MillisSinceEpoch::new(10 * 60_000),
+        ),
+    ] {
+        let schedule = schedule(misfire_policy);
+        let schedule_id = schedule.id;
+        test_env.apply(Command::UpsertSchedule(schedule)).await;
+
+        let actions = test_env
+            .apply(Command::FireSchedule(FiredScheduleTimer {
+                timer: TimerKeyValue::fire_schedule(fire_time, schedule_id),
+                fired_at,
+            }))
+            .await;
+        let fired = contains(matchers::actions::invoke_for_id(
+            schedule_id.invocation_id_at(fire_time),
+        ));
+        if fires {
+            assert_that!(actions, fired);
+        } else {
+            assert_that!(actions, not(fired));
+        }
+        assert_that!(
+            actions,
+            contains(eq(Action::RegisterTimer {
+                timer_value: TimerKeyValue::fire_schedule(next_fire_time, schedule_id)
+            }))
+        );
+        assert_eq!(
+            test_env
+                .storage
+                .get_schedule(&schedule_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .next_fire_time,
+            next_fire_time,
+            "{misfire_policy:?}"
+        );
+    }
+
+    test_env.shutdown().await;
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+
+use restate_storage_api::timer_table::{Timer, TimerKey, TimerKeyKind, TimerTable};
+use restate_types::identifiers::InvocationUuid;
+use restate_wal_protocol::control::SplitPartition;
+use test_log::test;
+
+fn sleep_timer(partition_key: PartitionKey) -> (TimerKey, Timer) {
+    let invocation_id = InvocationId::from_parts(partition_key, InvocationUuid::mock_random());
+    (
+        TimerKey {
+            timestamp: 1337,
+            kind: TimerKeyKind::CompleteJournalEntry {
+                invocation_uuid: invocation_id.invocation_uuid(),
+                journal_index: 1,
+            },
+        },
+        Timer::CompleteJournalEntry(invocation_id, 1),
+    )
+}
+
+#[test(restate_core::test)]
+async fn split_removes_timers_of_child_key_range() -> anyhow::Result<()> {
+    let mut test_env = TestEnv::create().await;
+    let split_key = PartitionKey::MAX / 2;
+
+    let (retained_key, retained_timer) = sleep_timer(split_key - 1);
+    let (handed_over_key, handed_over_timer) = sleep_timer(split_key);
+    let mut tx = test_env.storage.transaction();
+    tx.put_timer(&retained_key, &retained_timer).await;
+    tx.put_timer(&handed_over_key, &handed_over_timer).await;
+    tx.commit().await?;
+
+    let actions = test_env
+        .apply(Command::SplitPartition(SplitPartition {
+            child_partition_id: PartitionId::from(1),
+            child_key_range: split_key..=PartitionKey::MAX,
+        }))
+        .await;
+    assert_that!(
+        actions,
+        elements_are![eq(Action::DeleteTimer {
+            timer_key: handed_over_key
+        })]
+    );
+    assert_that!(
+        test_env
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .try_collect::<Vec<_>>()
+            .await?,
+        elements_are![eq((retained_key, retained_timer))]
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
+
+#[test(restate_core::test)]
+async fn split_of_foreign_key_range_is_ignored() -> anyhow::Result<()> {
+    let split_key = PartitionKey::MAX / 2;
+    let mut test_env = TestEnv::create_with_state_machine(StateMachine::new(
+        0,    /* inbox_seq_number */
+        0,    /* outbox_seq_number */
+        None, /* outbox_head_seq_number */
+        PartitionKey::MIN..=split_key - 1,
+        false,
+        JournalVersion::V1,
+    ))
+    .await;
+
+    let (timer_key, timer) = sleep_timer(split_key);
+    let mut tx = test_env.storage.transaction();
+    tx.put_timer(&timer_key, &timer).await;
+    tx.commit().await?;
+
+    let actions = test_env
+        .apply(Command::SplitPartition(SplitPartition {
+            child_partition_id: PartitionId::from(1),
+            child_key_range: split_key..=PartitionKey::MAX,
+        }))
+        .await;
+    assert_that!(actions, empty());
+    assert_that!(
+        test_env
+            .storage
+            .next_timers_greater_than(None, usize::MAX)
+            .try_collect::<Vec<_>>()
+            .await?,
+        len(eq(1))
+    );
+
+    test_env.shutdown().await;
+    Ok(())
+}
@@ -107,6 +107,8 @@ async fn start_workflow_method(#[case] disable_idempotency_table: bool) {
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -116,6 +118,8 @@ async fn start_workflow_method(#[case] disable_idempotency_table: bool) {
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
@@ -245,6 +249,8 @@ async fn attach_by_workflow_key(#[case] disable_idempotency_table: bool) {
         .apply_multiple([
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::JournalEntry {
                     entry_index: 1,
                     entry: ProtobufRawEntryCodec::serialize_enriched(Entry::output(
@@ -254,6 +260,8 @@ async fn attach_by_workflow_key(#[case] disable_idempotency_table: bool) {
             }),
             Command::InvokerEffect(InvokerEffect {
                 invocation_id,
+                leader_epoch: None,
+                invocation_epoch: 0,
                 kind: InvokerEffectKind::End,
             }),
         ])
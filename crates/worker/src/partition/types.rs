@@ -46,6 +46,9 @@ impl OutboxMessageExt for OutboxMessage {
             OutboxMessage::ServiceResponse(sr) => Command::InvocationResponse(sr),
             OutboxMessage::InvocationTermination(it) => Command::TerminateInvocation(it),
             OutboxMessage::AttachInvocation(ai) => Command::AttachInvocation(ai),
+            OutboxMessage::KafkaEvent(_) => {
+                unreachable!("Kafka events are published by the shuffle, not appended to bifrost")
+            }
         }
     }
 }
@@ -13,6 +13,7 @@ mod persisted_lsn_watchdog;
 mod processor_state;
 mod snapshot_task;
 mod spawn_processor_task;
+mod split_task;
 
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
@@ -32,7 +33,7 @@ use restate_bifrost::Bifrost;
 use restate_core::network::{Incoming, MessageRouterBuilder, MessageStream};
 use restate_core::worker_api::{
     ProcessorsManagerCommand, ProcessorsManagerHandle, SnapshotCreated, SnapshotError,
-    SnapshotResult,
+    SnapshotResult, SplitPartitionError,
 };
 use restate_core::{
     cancellation_watcher, my_node_id, Metadata, ShutdownError, TaskCenterFutureExt, TaskHandle,
@@ -58,7 +59,8 @@ use restate_types::net::partition_processor::{
     PartitionProcessorRpcError, PartitionProcessorRpcRequest,
 };
 use restate_types::net::partition_processor_manager::{
-    ControlProcessor, ControlProcessors, ProcessorCommand,
+    self, ControlProcessor, ControlProcessors, ProcessorCommand, SplitPartitionRequest,
+    SplitPartitionResponse,
 };
 use restate_types::partition_table::PartitionTable;
 use restate_types::protobuf::common::WorkerStatus;
@@ -72,6 +74,12 @@ use crate::metric_definitions::PARTITION_LAST_APPLIED_LOG_LSN;
 use crate::metric_definitions::PARTITION_LAST_PERSISTED_LOG_LSN;
 use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_RECORD;
 use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_STATUS_UPDATE;
+use crate::metric_definitions::{
+    PARTITION_APPLY_LAG, PARTITION_INBOXED_INVOCATIONS, PARTITION_INVOKER_QUEUE_DEPTH,
+    PARTITION_LOG_TAIL_LSN,
+};
+use crate::partition::snapshots::SnapshotRepository;
+use crate::partition::TrimGapEncountered;
 use crate::partition_processor_manager::message_handler::PartitionProcessorManagerMessageHandler;
 use crate::partition_processor_manager::persisted_lsn_watchdog::PersistedLogLsnWatchdog;
 use crate::partition_processor_manager::processor_state::{
@@ -79,6 +87,7 @@ use crate::partition_processor_manager::processor_state::{
 };
 use crate::partition_processor_manager::snapshot_task::SnapshotPartitionTask;
 use crate::partition_processor_manager::spawn_processor_task::SpawnPartitionProcessorTask;
+use crate::partition_processor_manager::split_task::SplitPartitionTask;
 
 pub struct PartitionProcessorManager {
     health_status: HealthStatus<WorkerStatus>,
@@ -90,6 +99,7 @@ pub struct PartitionProcessorManager {
     partition_store_manager: PartitionStoreManager,
     incoming_update_processors: MessageStream<ControlProcessors>,
     incoming_partition_processor_rpc: MessageStream<PartitionProcessorRpcRequest>,
+    incoming_split_partition: MessageStream<SplitPartitionRequest>,
     bifrost: Bifrost,
     rx: mpsc::Receiver<ProcessorsManagerCommand>,
     tx: mpsc::Sender<ProcessorsManagerCommand>,
@@ -103,6 +113,7 @@ pub struct PartitionProcessorManager {
 
     pending_snapshots: HashMap<PartitionId, PendingSnapshotTask>,
     snapshot_export_tasks: FuturesUnordered<TaskHandle<SnapshotResultInternal>>,
+    snapshot_repository: Option<SnapshotRepository>,
 }
 
 struct PendingSnapshotTask {
@@ -174,9 +185,11 @@ impl PartitionProcessorManager {
         partition_store_manager: PartitionStoreManager,
         router_builder: &mut MessageRouterBuilder,
         bifrost: Bifrost,
+        snapshot_repository: Option<SnapshotRepository>,
     ) -> Self {
         let incoming_update_processors = router_builder.subscribe_to_stream(2);
         let incoming_partition_processor_rpc = router_builder.subscribe_to_stream(128);
+        let incoming_split_partition = router_builder.subscribe_to_stream(2);
 
         let (tx, rx) = mpsc::channel(updateable_config.pinned().worker.internal_queue_length());
         Self {
@@ -188,6 +201,7 @@ impl PartitionProcessorManager {
             partition_store_manager,
             incoming_update_processors,
             incoming_partition_processor_rpc,
+            incoming_split_partition,
             bifrost,
             rx,
             tx,
@@ -198,6 +212,7 @@ impl PartitionProcessorManager {
             asynchronous_operations: JoinSet::default(),
             snapshot_export_tasks: FuturesUnordered::default(),
             pending_snapshots: HashMap::default(),
+            snapshot_repository,
         }
     }
 
@@ -266,6 +281,9 @@ impl PartitionProcessorManager {
                 Some(partition_processor_rpc) = self.incoming_partition_processor_rpc.next() => {
                     self.on_partition_processor_rpc(partition_processor_rpc);
                 }
+                Some(split_partition) = self.incoming_split_partition.next() => {
+                    self.on_split_partition(split_partition);
+                }
                 Some(result) = self.snapshot_export_tasks.next() => {
                     if let Ok(result) = result {
                         self.on_create_snapshot_task_completed(result);
@@ -419,10 +437,44 @@ impl PartitionProcessorManager {
                         ProcessorState::Starting { .. } => {
                             warn!(%partition_id, "Partition processor failed to start: {result:?}");
                         }
-                        ProcessorState::Started { processor, .. } => {
+                        ProcessorState::Started { ref processor, .. } => {
                             self.invokers_status_reader
                                 .remove(processor.as_ref().expect("must be some").key_range());
-                            warn!(%partition_id, "Partition processor exited unexpectedly: {result:?}");
+                            if result.is_ok() {
+                                // the partition processor stops after applying a split of its
+                                // key range to continue with the remaining key range
+                                if let Some(status) = processor_state.partition_processor_status() {
+                                    debug!(%partition_id, "Restarting partition processor after split");
+                                    self.on_control_processor(
+                                        ControlProcessor {
+                                            partition_id,
+                                            command: ProcessorCommand::from(status.planned_mode),
+                                        },
+                                        &Metadata::with_current(|m| m.partition_table_ref()),
+                                    );
+                                }
+                            } else if let Some(trim_gap) = result
+                                .as_ref()
+                                .err()
+                                .and_then(|err| err.downcast_ref::<TrimGapEncountered>())
+                            {
+                                if self.snapshot_repository.is_none() {
+                                    error!(%partition_id, "Partition processor encountered a trim gap up to lsn {} but no snapshot repository is configured to catch up from", trim_gap.trim_gap_end);
+                                } else if let Some(status) =
+                                    processor_state.partition_processor_status()
+                                {
+                                    info!(%partition_id, "Restarting partition processor from a snapshot to catch up with the trimmed log");
+                                    self.on_control_processor(
+                                        ControlProcessor {
+                                            partition_id,
+                                            command: ProcessorCommand::from(status.planned_mode),
+                                        },
+                                        &Metadata::with_current(|m| m.partition_table_ref()),
+                                    );
+                                }
+                            } else {
+                                warn!(%partition_id, "Partition processor exited unexpectedly: {result:?}");
+                            }
                         }
                         ProcessorState::Stopping {
                             processor,
@@ -478,6 +530,32 @@ impl PartitionProcessorManager {
                     debug!("Partition processor is no longer running. Ignoring new leader epoch result.");
                 }
             }
+            EventKind::SplitCompleted { request, result } => {
+                let response = SplitPartitionResponse {
+                    result: result.map_err(|err| match err {
+                        SplitPartitionError::Incomplete(..) => {
+                            partition_processor_manager::SplitPartitionError::Incomplete(
+                                err.to_string(),
+                            )
+                        }
+                        err => partition_processor_manager::SplitPartitionError::SplitFailed(
+                            err.to_string(),
+                        ),
+                    }),
+                };
+                // ignore shutdown errors
+                let _ = TaskCenter::spawn(
+                    TaskKind::Disposable,
+                    "split-partition-response",
+                    async move {
+                        request
+                            .to_rpc_response(response)
+                            .send()
+                            .await
+                            .map_err(Into::into)
+                    },
+                );
+            }
         }
     }
 
@@ -563,6 +641,26 @@ impl PartitionProcessorManager {
                         .set(last_record_applied_at.elapsed());
                     }
 
+                    if let Some(log_tail_lsn) = status.log_tail_lsn {
+                        gauge!(PARTITION_LOG_TAIL_LSN,
+                        PARTITION_LABEL => partition_id.to_string())
+                        .set(log_tail_lsn.as_u64() as f64);
+                    }
+
+                    if let Some(apply_lag) = status.apply_lag {
+                        gauge!(PARTITION_APPLY_LAG,
+                        PARTITION_LABEL => partition_id.to_string())
+                        .set(apply_lag);
+                    }
+
+                    gauge!(PARTITION_INBOXED_INVOCATIONS,
+                        PARTITION_LABEL => partition_id.to_string())
+                    .set(status.num_inboxed_invocations as f64);
+
+                    gauge!(PARTITION_INVOKER_QUEUE_DEPTH,
+                        PARTITION_LABEL => partition_id.to_string())
+                    .set(status.invoker_queue_depth as f64);
+
                     // it is a bit unfortunate that we share PartitionProcessorStatus between the
                     // PP and the PPManager :-(. Maybe at some point we want to split the struct for it.
                     status.last_persisted_log_lsn = persisted_lsns
@@ -707,6 +805,61 @@ impl PartitionProcessorManager {
         self.spawn_create_snapshot_task(partition_id, Some(sender));
     }
 
+    fn on_split_partition(&mut self, split_partition: Incoming<SplitPartitionRequest>) {
+        let request = split_partition.body();
+        let partition_id = request.partition_id;
+
+        let state_check = match self.processor_states.get(&partition_id) {
+            None => Err(SplitPartitionError::PartitionNotFound(partition_id)),
+            Some(processor_state) if !processor_state.should_publish_snapshots() => {
+                Err(SplitPartitionError::InvalidState(partition_id))
+            }
+            Some(_) => Ok(()),
+        };
+
+        if let Err(err) = state_check {
+            self.asynchronous_operations
+                .spawn(std::future::ready(AsynchronousEvent {
+                    partition_id,
+                    inner: EventKind::SplitCompleted {
+                        request: split_partition,
+                        result: Err(err),
+                    },
+                }));
+            return;
+        }
+
+        let config = self.updateable_config.live_load();
+        let split_task = SplitPartitionTask {
+            partition_id,
+            child_partition_id: request.child_partition_id,
+            min_partition_table_version: request.min_partition_table_version,
+            child_snapshot_base_path: config
+                .worker
+                .snapshots
+                .snapshots_dir(request.child_partition_id),
+            partition_store_manager: self.partition_store_manager.clone(),
+            bifrost: self.bifrost.clone(),
+            cluster_name: config.common.cluster_name().into(),
+            node_name: config.common.node_name().into(),
+            snapshot_repository: self.snapshot_repository.clone(),
+        };
+
+        self.asynchronous_operations.spawn(
+            async move {
+                let result = split_task.run().await;
+                AsynchronousEvent {
+                    partition_id,
+                    inner: EventKind::SplitCompleted {
+                        request: split_partition,
+                        result,
+                    },
+                }
+            }
+            .in_current_tc(),
+        );
+    }
+
     fn on_create_snapshot_task_completed(&mut self, result: SnapshotResultInternal) {
         let (partition_id, response) = match result {
             Ok(metadata) => {
@@ -798,6 +951,7 @@ impl PartitionProcessorManager {
                     partition_store_manager: self.partition_store_manager.clone(),
                     cluster_name: config.common.cluster_name().into(),
                     node_name: config.common.node_name().into(),
+                    snapshot_repository: self.snapshot_repository.clone(),
                 };
 
                 let spawn_task_result = TaskCenter::spawn_unmanaged(
@@ -857,6 +1011,7 @@ impl PartitionProcessorManager {
             self.updateable_config.clone(),
             self.bifrost.clone(),
             self.partition_store_manager.clone(),
+            self.snapshot_repository.clone(),
         )
     }
 
@@ -908,6 +1063,10 @@ enum EventKind {
         leader_epoch_token: LeaderEpochToken,
         result: anyhow::Result<LeaderEpoch>,
     },
+    SplitCompleted {
+        request: Incoming<SplitPartitionRequest>,
+        result: Result<Lsn, SplitPartitionError>,
+    },
 }
 
 #[cfg(test)]
@@ -973,6 +1132,7 @@ mod tests {
             partition_store_manager,
             &mut env_builder.router_builder,
             bifrost,
+            None,
         );
 
         let env = env_builder.build().await;
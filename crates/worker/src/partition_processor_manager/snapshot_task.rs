@@ -20,7 +20,10 @@ use restate_partition_store::snapshots::{
 use restate_partition_store::PartitionStoreManager;
 use restate_types::identifiers::{PartitionId, SnapshotId};
 
-/// Creates a partition store snapshot along with Restate snapshot metadata.
+use crate::partition::snapshots::SnapshotRepository;
+
+/// Creates a partition store snapshot along with Restate snapshot metadata. If a snapshot
+/// repository is configured, the snapshot is uploaded to it.
 pub struct SnapshotPartitionTask {
     pub snapshot_id: SnapshotId,
     pub partition_id: PartitionId,
@@ -28,6 +31,7 @@ pub struct SnapshotPartitionTask {
     pub partition_store_manager: PartitionStoreManager,
     pub cluster_name: String,
     pub node_name: String,
+    pub snapshot_repository: Option<SnapshotRepository>,
 }
 
 impl SnapshotPartitionTask {
@@ -59,9 +63,15 @@ impl SnapshotPartitionTask {
             )
             .await?;
 
+        let snapshot_dir = snapshot.base_dir.clone();
         let metadata = self.write_snapshot_metadata_header(snapshot).await?;
 
-        // todo(pavel): SnapshotRepository integration will go in here in a future PR
+        if let Some(snapshot_repository) = &self.snapshot_repository {
+            snapshot_repository
+                .put(&metadata, &snapshot_dir)
+                .await
+                .map_err(|e| SnapshotError::RepositoryError(self.partition_id, e.into()))?;
+        }
 
         Ok(metadata)
     }
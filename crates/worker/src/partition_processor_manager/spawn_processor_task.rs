@@ -9,26 +9,36 @@
 // by the Apache License, Version 2.0.
 
 use std::ops::RangeInclusive;
+use std::path::Path;
 
+use anyhow::Context;
 use tokio::sync::{mpsc, watch};
-use tracing::instrument;
+use tracing::{debug, info, instrument, warn};
 
 use restate_bifrost::Bifrost;
 use restate_core::{Metadata, RuntimeTaskHandle, TaskCenter, TaskKind};
 use restate_invoker_impl::Service as InvokerService;
 use restate_partition_store::{OpenMode, PartitionStore, PartitionStoreManager};
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
+use restate_storage_api::fsm_table::{FsmTable, ReadOnlyFsmTable};
+use restate_storage_api::Transaction;
 use restate_types::cluster::cluster_state::PartitionProcessorStatus;
-use restate_types::config::Configuration;
+use restate_types::config::{Configuration, WorkerOptions};
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::live::Live;
+use restate_types::logs::{LogId, Lsn};
 use restate_types::schema::Schema;
 
 use crate::invoker_integration::EntryEnricher;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
+use crate::partition::snapshots::SnapshotRepository;
 use crate::partition_processor_manager::processor_state::StartedProcessor;
 use crate::PartitionProcessorBuilder;
 
+/// Directory below the partition's snapshots directory into which snapshots are downloaded before
+/// they are imported.
+const SNAPSHOT_IMPORT_DIR: &str = "import";
+
 pub struct SpawnPartitionProcessorTask {
     task_name: &'static str,
     partition_id: PartitionId,
@@ -36,6 +46,7 @@ pub struct SpawnPartitionProcessorTask {
     configuration: Live<Configuration>,
     bifrost: Bifrost,
     partition_store_manager: PartitionStoreManager,
+    snapshot_repository: Option<SnapshotRepository>,
 }
 
 impl SpawnPartitionProcessorTask {
@@ -47,6 +58,7 @@ impl SpawnPartitionProcessorTask {
         configuration: Live<Configuration>,
         bifrost: Bifrost,
         partition_store_manager: PartitionStoreManager,
+        snapshot_repository: Option<SnapshotRepository>,
     ) -> Self {
         Self {
             task_name,
@@ -55,6 +67,7 @@ impl SpawnPartitionProcessorTask {
             configuration,
             bifrost,
             partition_store_manager,
+            snapshot_repository,
         }
     }
 
@@ -72,6 +85,7 @@ impl SpawnPartitionProcessorTask {
             configuration,
             bifrost,
             partition_store_manager,
+            snapshot_repository,
         } = self;
 
         let config = configuration.pinned();
@@ -105,6 +119,7 @@ impl SpawnPartitionProcessorTask {
             rpc_rx,
             watch_tx,
             invoker.handle(),
+            status_reader.clone(),
         );
 
         let invoker_name = Box::leak(Box::new(format!("invoker-{}", partition_id)));
@@ -114,26 +129,52 @@ impl SpawnPartitionProcessorTask {
             TaskKind::PartitionProcessor,
             task_name,
             Some(pp_builder.partition_id),
+            None,
             {
                 let options = options.clone();
                 let key_range = key_range.clone();
                 move || async move {
-                    let partition_store = partition_store_manager
-                        .open_partition_store(
-                            partition_id,
-                            key_range,
-                            OpenMode::CreateIfMissing,
-                            &options.storage.rocksdb,
-                        )
-                        .await?;
+                    let mut pp_builder = pp_builder;
+                    let mut partition_store = open_partition_store(
+                        partition_id,
+                        key_range.clone(),
+                        &bifrost,
+                        &partition_store_manager,
+                        snapshot_repository,
+                        &options,
+                    )
+                    .await?;
+
+                    let serving_key_range =
+                        key_range_to_serve(partition_id, key_range, &mut partition_store).await?;
+                    if serving_key_range != pp_builder.partition_key_range {
+                        info!(
+                            ?serving_key_range,
+                            "Serving the key range of a split which has not been applied yet"
+                        );
+                        partition_store = partition_store_manager
+                            .open_partition_store(
+                                partition_id,
+                                serving_key_range.clone(),
+                                OpenMode::OpenExisting,
+                                &options.storage.rocksdb,
+                            )
+                            .await?;
+                        pp_builder.partition_key_range = serving_key_range;
+                    }
+
                     TaskCenter::spawn_child(
-                        TaskKind::SystemService,
+                        TaskKind::Invoker,
                         invoker_name,
                         invoker.run(invoker_config),
                     )?;
 
                     pp_builder
-                        .build::<ProtobufRawEntryCodec>(bifrost, partition_store)
+                        .build::<ProtobufRawEntryCodec>(
+                            bifrost,
+                            partition_store,
+                            partition_store_manager,
+                        )
                         .await?
                         .run()
                         .await
@@ -153,3 +194,302 @@ impl SpawnPartitionProcessorTask {
         Ok((state, root_task_handle))
     }
 }
+
+/// Opens the partition store of the partition. If the node has no data for the partition yet, the
+/// store is initialized from the latest snapshot in the snapshot repository, so that the partition
+/// processor only needs to replay the log from the snapshot's applied LSN onwards. If the log was
+/// trimmed beyond the applied LSN of the node's data, the data is replaced by the latest snapshot
+/// provided that it covers the trim gap.
+async fn open_partition_store(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    bifrost: &Bifrost,
+    partition_store_manager: &PartitionStoreManager,
+    snapshot_repository: Option<SnapshotRepository>,
+    options: &WorkerOptions,
+) -> anyhow::Result<PartitionStore> {
+    drop_interrupted_split(
+        partition_id,
+        key_range.clone(),
+        partition_store_manager,
+        options,
+    )
+    .await?;
+
+    if let Some(snapshot_repository) = snapshot_repository {
+        let trim_gap_end = if partition_store_manager.has_partition_store(partition_id) {
+            find_trim_gap(
+                partition_id,
+                key_range.clone(),
+                bifrost,
+                partition_store_manager,
+                options,
+            )
+            .await?
+        } else {
+            None
+        };
+
+        if trim_gap_end.is_some() || !partition_store_manager.has_partition_store(partition_id) {
+            let staging_dir = options
+                .snapshots
+                .snapshots_dir(partition_id)
+                .join(SNAPSHOT_IMPORT_DIR);
+            let partition_store = import_latest_snapshot(
+                partition_id,
+                key_range.clone(),
+                trim_gap_end,
+                &snapshot_repository,
+                &staging_dir,
+                partition_store_manager,
+                options,
+            )
+            .await;
+            // the files were copied into the database on import, or are not needed anymore
+            match tokio::fs::remove_dir_all(&staging_dir).await {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    debug!(
+                        "Failed to remove the downloaded snapshot {}: {}",
+                        staging_dir.display(),
+                        err
+                    );
+                }
+                _ => {}
+            }
+
+            if let Some(partition_store) = partition_store? {
+                return Ok(partition_store);
+            }
+        }
+    }
+
+    if !partition_store_manager.has_partition_store(partition_id) {
+        // a partition which was split off needs to start from the state handed over by its
+        // parent; starting it with an empty partition store would lose that state.
+        if let Some(parent_partition_id) = Metadata::with_current(|m| {
+            m.partition_table_ref()
+                .get_partition(&partition_id)
+                .and_then(|partition| partition.split_from)
+        }) {
+            anyhow::bail!(
+                "partition {partition_id} was split off from partition {parent_partition_id} but \
+                neither a local partition store nor a snapshot of it is available"
+            );
+        }
+    }
+
+    Ok(partition_store_manager
+        .open_partition_store(
+            partition_id,
+            key_range,
+            OpenMode::CreateIfMissing,
+            &options.storage.rocksdb,
+        )
+        .await?)
+}
+
+/// Imports the latest snapshot of the partition, which is downloaded to `staging_dir`. Returns
+/// `None` if no snapshot can be imported and the partition store can be initialized empty instead.
+///
+/// If the log was trimmed up to `trim_gap_end`, the snapshot replaces the node's partition store
+/// and it must cover the trim gap.
+async fn import_latest_snapshot(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    trim_gap_end: Option<Lsn>,
+    snapshot_repository: &SnapshotRepository,
+    staging_dir: &Path,
+    partition_store_manager: &PartitionStoreManager,
+    options: &WorkerOptions,
+) -> anyhow::Result<Option<PartitionStore>> {
+    let snapshot = snapshot_repository
+        .get_latest(partition_id, staging_dir)
+        .await
+        .with_context(|| {
+            format!("failed to fetch the latest snapshot of partition {partition_id}")
+        })?;
+
+    let Some(snapshot) = snapshot else {
+        if let Some(trim_gap_end) = trim_gap_end {
+            anyhow::bail!(
+                "the log of partition {partition_id} was trimmed up to lsn {trim_gap_end} but no \
+                snapshot is available"
+            );
+        }
+        debug!("No snapshot found, initializing an empty partition store");
+        return Ok(None);
+    };
+
+    // the key range of the partition shrinks if it was split after the snapshot
+    if snapshot.key_range.start() != key_range.start() || snapshot.key_range.end() < key_range.end()
+    {
+        if let Some(trim_gap_end) = trim_gap_end {
+            anyhow::bail!(
+                "the log of partition {partition_id} was trimmed up to lsn {trim_gap_end} but the \
+                key range {:?} of the latest snapshot doesn't cover the partition's key range {:?}",
+                snapshot.key_range,
+                key_range
+            );
+        }
+        warn!(
+            snapshot_key_range = ?snapshot.key_range,
+            "The key range of the latest snapshot doesn't cover the partition's key range {:?}, ignoring the snapshot",
+            key_range
+        );
+        return Ok(None);
+    }
+
+    if let Some(trim_gap_end) = trim_gap_end {
+        if snapshot.min_applied_lsn < trim_gap_end {
+            anyhow::bail!(
+                "the log of partition {partition_id} was trimmed up to lsn {trim_gap_end} but the \
+                latest snapshot only covers it up to lsn {}",
+                snapshot.min_applied_lsn
+            );
+        }
+        info!(
+            %trim_gap_end,
+            "Replacing the partition store since the log was trimmed beyond its applied lsn"
+        );
+        partition_store_manager.drop_partition(partition_id).await;
+    }
+
+    info!(
+        min_applied_lsn = %snapshot.min_applied_lsn,
+        "Initializing partition store from the latest snapshot"
+    );
+    let min_applied_lsn = snapshot.min_applied_lsn;
+    let mut partition_store = partition_store_manager
+        .open_partition_store_from_snapshot(
+            partition_id,
+            key_range,
+            snapshot,
+            &options.storage.rocksdb,
+        )
+        .await?;
+
+    // the partition processor continues reading the log after the applied lsn of the snapshot
+    let applied_lsn = partition_store.get_applied_lsn().await?;
+    if applied_lsn.is_none_or(|applied_lsn| applied_lsn < min_applied_lsn) {
+        warn!(
+            ?applied_lsn,
+            %min_applied_lsn,
+            "The imported snapshot lacks its applied lsn, continuing after the snapshot's lsn"
+        );
+        let mut transaction = partition_store.transaction();
+        transaction.put_applied_lsn(min_applied_lsn).await;
+        transaction.commit().await?;
+    }
+
+    Ok(Some(partition_store))
+}
+
+/// Returns the end of the trim gap which the partition processor would encounter when continuing
+/// from the node's partition store, if the log was trimmed beyond the store's applied LSN.
+async fn find_trim_gap(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    bifrost: &Bifrost,
+    partition_store_manager: &PartitionStoreManager,
+    options: &WorkerOptions,
+) -> anyhow::Result<Option<Lsn>> {
+    let applied_lsn = partition_store_manager
+        .open_partition_store(
+            partition_id,
+            key_range,
+            OpenMode::OpenExisting,
+            &options.storage.rocksdb,
+        )
+        .await?
+        .get_applied_lsn()
+        .await?
+        .unwrap_or(Lsn::INVALID);
+    let trim_point = bifrost.get_trim_point(LogId::from(partition_id)).await?;
+
+    Ok((trim_point > applied_lsn).then_some(trim_point))
+}
+
+/// Drops the partition store of a partition which is split off from a partition of this node if
+/// the split has not been applied by the parent partition on this node yet. Such a partition
+/// store is the remainder of a split which was interrupted before it was completed, and is
+/// created again when the parent partition applies the split.
+async fn drop_interrupted_split(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    partition_store_manager: &PartitionStoreManager,
+    options: &WorkerOptions,
+) -> anyhow::Result<()> {
+    let Some((parent_partition_id, parent_key_range)) = Metadata::with_current(|m| {
+        let partition_table = m.partition_table_ref();
+        let parent_partition_id = partition_table.get_partition(&partition_id)?.split_from?;
+        let parent = partition_table.get_partition(&parent_partition_id)?;
+        Some((parent_partition_id, parent.key_range.clone()))
+    }) else {
+        return Ok(());
+    };
+
+    if partition_store_manager.has_partition(partition_id).await
+        || !partition_store_manager.has_partition_store(partition_id)
+        || !partition_store_manager.has_partition_store(parent_partition_id)
+    {
+        return Ok(());
+    }
+
+    let mut parent_store = match partition_store_manager
+        .get_partition_store(parent_partition_id)
+        .await
+    {
+        Some(parent_store) => parent_store,
+        None => {
+            partition_store_manager
+                .open_partition_store(
+                    parent_partition_id,
+                    *parent_key_range.start()..=*key_range.end(),
+                    OpenMode::OpenExisting,
+                    &options.storage.rocksdb,
+                )
+                .await?
+        }
+    };
+    let split_applied = parent_store
+        .get_partition_split()
+        .await?
+        .is_some_and(|split| split.child_partition_id >= partition_id);
+
+    if !split_applied {
+        info!(
+            %parent_partition_id,
+            "Dropping the partition store of an interrupted split"
+        );
+        partition_store_manager.drop_partition(partition_id).await;
+    }
+
+    Ok(())
+}
+
+/// Returns the key range which the partition processor serves. Until the partition processor
+/// applies the split of a partition from its key range, it keeps serving the key range of the
+/// new partition, which is already removed from the partition table.
+async fn key_range_to_serve(
+    partition_id: PartitionId,
+    key_range: RangeInclusive<PartitionKey>,
+    partition_store: &mut PartitionStore,
+) -> anyhow::Result<RangeInclusive<PartitionKey>> {
+    let last_split = partition_store
+        .get_partition_split()
+        .await?
+        .map(|split| split.child_partition_id);
+
+    let end = Metadata::with_current(|m| {
+        m.partition_table_ref()
+            .partitions()
+            .filter(|(child_partition_id, child)| {
+                child.split_from == Some(partition_id)
+                    && last_split.is_none_or(|last_split| **child_partition_id > last_split)
+            })
+            .map(|(_, child)| *child.key_range.end())
+            .fold(*key_range.end(), PartitionKey::max)
+    });
+
+    Ok(*key_range.start()..=end)
+}
@@ -0,0 +1,166 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, info, instrument, warn};
+
+use restate_bifrost::Bifrost;
+use restate_core::worker_api::SplitPartitionError;
+use restate_core::Metadata;
+use restate_partition_store::PartitionStoreManager;
+use restate_storage_api::fsm_table::ReadOnlyFsmTable;
+use restate_types::identifiers::{PartitionId, SnapshotId};
+use restate_types::logs::{LogId, Lsn};
+use restate_types::Version;
+use restate_wal_protocol::control::SplitPartition;
+use restate_wal_protocol::{Command, Destination, Envelope, Header, Source};
+
+use crate::partition::snapshots::SnapshotRepository;
+use crate::partition_processor_manager::snapshot_task::SnapshotPartitionTask;
+
+/// How long the parent partition processor may take to apply the split before the split is
+/// reported as incomplete.
+const APPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Splits a partition running on this node by appending the split to the partition's log. Every
+/// replica of the partition creates the partition store of the new partition when applying the
+/// split, so the split is performed at the same LSN on all of them. If a snapshot repository is
+/// configured, a snapshot of the new partition store is uploaded to it so that other nodes can
+/// bootstrap the new partition from it.
+///
+/// Running the task again for the same split resumes it.
+pub struct SplitPartitionTask {
+    pub partition_id: PartitionId,
+    pub child_partition_id: PartitionId,
+    pub min_partition_table_version: Version,
+    pub child_snapshot_base_path: PathBuf,
+    pub partition_store_manager: PartitionStoreManager,
+    pub bifrost: Bifrost,
+    pub cluster_name: String,
+    pub node_name: String,
+    pub snapshot_repository: Option<SnapshotRepository>,
+}
+
+impl SplitPartitionTask {
+    #[instrument(level = "info", skip_all, fields(partition_id = %self.partition_id, child_partition_id = %self.child_partition_id))]
+    pub async fn run(self) -> Result<Lsn, SplitPartitionError> {
+        debug!("Splitting partition");
+
+        self.split_partition_inner()
+            .await
+            .inspect(|split_lsn| info!(%split_lsn, "Partition split"))
+            .inspect_err(|err| warn!("Failed to split partition: {}", err))
+    }
+
+    async fn split_partition_inner(&self) -> Result<Lsn, SplitPartitionError> {
+        let partition_table = Metadata::current()
+            .wait_for_partition_table(self.min_partition_table_version)
+            .await
+            .map_err(|err| SplitPartitionError::Internal(self.partition_id, err.to_string()))?;
+
+        let child_key_range = partition_table
+            .get_partition(&self.child_partition_id)
+            .filter(|child| child.splitting && child.split_from == Some(self.partition_id))
+            .map(|child| child.key_range.clone());
+        // don't hold on to the pinned partition table across await points
+        drop(partition_table);
+        let child_key_range = child_key_range.ok_or(SplitPartitionError::NotSplitting(
+            self.child_partition_id,
+            self.partition_id,
+        ))?;
+
+        if self.applied_split_lsn().await?.is_none() {
+            // The split key range is already routed to the new partition, hence the split is
+            // appended to the log of the parent partition explicitly.
+            let split_lsn = self
+                .bifrost
+                .append(
+                    LogId::from(self.partition_id),
+                    Arc::new(Envelope::new(
+                        Header {
+                            source: Source::ControlPlane {},
+                            dest: Destination::Processor {
+                                partition_key: *child_key_range.start(),
+                                dedup: None,
+                            },
+                        },
+                        Command::SplitPartition(SplitPartition {
+                            child_partition_id: self.child_partition_id,
+                            child_key_range,
+                        }),
+                    )),
+                )
+                .await
+                .map_err(|err| self.incomplete(err))?;
+            debug!(%split_lsn, "Appended split to the log");
+        }
+
+        let split_lsn = self.await_split_applied().await?;
+
+        if self.snapshot_repository.is_some() {
+            SnapshotPartitionTask {
+                snapshot_id: SnapshotId::new(),
+                partition_id: self.child_partition_id,
+                snapshot_base_path: self.child_snapshot_base_path.clone(),
+                partition_store_manager: self.partition_store_manager.clone(),
+                cluster_name: self.cluster_name.clone(),
+                node_name: self.node_name.clone(),
+                snapshot_repository: self.snapshot_repository.clone(),
+            }
+            .run()
+            .await
+            .map_err(|err| self.incomplete(err))?;
+        }
+
+        Ok(split_lsn)
+    }
+
+    /// Returns the LSN at which the split was applied by the parent partition on this node, if
+    /// it was applied already.
+    async fn applied_split_lsn(&self) -> Result<Option<Lsn>, SplitPartitionError> {
+        let mut partition_store = self
+            .partition_store_manager
+            .get_partition_store(self.partition_id)
+            .await
+            .ok_or(SplitPartitionError::PartitionNotFound(self.partition_id))?;
+
+        Ok(partition_store
+            .get_partition_split()
+            .await
+            .map_err(|err| SplitPartitionError::Storage(self.partition_id, err.into()))?
+            .filter(|split| split.child_partition_id == self.child_partition_id)
+            .map(|split| split.lsn))
+    }
+
+    async fn await_split_applied(&self) -> Result<Lsn, SplitPartitionError> {
+        tokio::time::timeout(APPLY_TIMEOUT, async {
+            loop {
+                if let Some(split_lsn) = self
+                    .applied_split_lsn()
+                    .await
+                    .map_err(|err| self.incomplete(err))?
+                {
+                    return Ok(split_lsn);
+                }
+
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .map_err(|_| self.incomplete("the partition processor did not apply the split in time"))?
+    }
+
+    fn incomplete(&self, err: impl ToString) -> SplitPartitionError {
+        SplitPartitionError::Incomplete(self.partition_id, err.to_string())
+    }
+}
@@ -11,22 +11,32 @@
 use std::ops::Deref;
 use std::sync::Arc;
 
-use restate_ingress_kafka::SubscriptionCommandSender;
 use restate_types::config::IngressOptions;
 use restate_types::identifiers::SubscriptionId;
-use restate_types::schema::subscriptions::{Subscription, SubscriptionValidator};
+use restate_types::schema::subscriptions::{Source, Subscription, SubscriptionValidator};
 
 use crate::{SubscriptionController, WorkerHandleError};
 
+/// Routes the subscriptions with an SQS source to the SQS ingress, and all the others to the
+/// Kafka ingress.
 #[derive(Debug, Clone)]
-pub struct SubscriptionControllerHandle(Arc<IngressOptions>, SubscriptionCommandSender);
+pub struct SubscriptionControllerHandle(
+    Arc<IngressOptions>,
+    restate_ingress_kafka::SubscriptionCommandSender,
+    restate_ingress_sqs::SubscriptionCommandSender,
+);
 
 impl SubscriptionControllerHandle {
     pub(crate) fn new(
         ingress_options: IngressOptions,
-        commands_tx: SubscriptionCommandSender,
+        kafka_commands_tx: restate_ingress_kafka::SubscriptionCommandSender,
+        sqs_commands_tx: restate_ingress_sqs::SubscriptionCommandSender,
     ) -> Self {
-        Self(Arc::new(ingress_options), commands_tx)
+        Self(
+            Arc::new(ingress_options),
+            kafka_commands_tx,
+            sqs_commands_tx,
+        )
     }
 }
 
@@ -43,18 +53,32 @@ impl SubscriptionController for SubscriptionControllerHandle {
         &self,
         subscription: Subscription,
     ) -> Result<(), WorkerHandleError> {
-        self.1
-            .send(restate_ingress_kafka::Command::StartSubscription(
-                subscription,
-            ))
-            .await
-            .map_err(|_| WorkerHandleError::Unreachable)
+        if matches!(subscription.source(), Source::Sqs { .. }) {
+            self.2
+                .send(restate_ingress_sqs::Command::StartSubscription(
+                    subscription,
+                ))
+                .await
+                .map_err(|_| WorkerHandleError::Unreachable)
+        } else {
+            self.1
+                .send(restate_ingress_kafka::Command::StartSubscription(
+                    subscription,
+                ))
+                .await
+                .map_err(|_| WorkerHandleError::Unreachable)
+        }
     }
 
     async fn stop_subscription(&self, id: SubscriptionId) -> Result<(), WorkerHandleError> {
+        // The source of the subscription is unknown, each ingress ignores the ids it doesn't run
         self.1
             .send(restate_ingress_kafka::Command::StopSubscription(id))
             .await
+            .map_err(|_| WorkerHandleError::Unreachable)?;
+        self.2
+            .send(restate_ingress_sqs::Command::StopSubscription(id))
+            .await
             .map_err(|_| WorkerHandleError::Unreachable)
     }
 
@@ -62,11 +86,20 @@ impl SubscriptionController for SubscriptionControllerHandle {
         &self,
         subscriptions: Vec<Subscription>,
     ) -> Result<(), WorkerHandleError> {
+        let (sqs_subscriptions, subscriptions): (Vec<_>, Vec<_>) = subscriptions
+            .into_iter()
+            .partition(|subscription| matches!(subscription.source(), Source::Sqs { .. }));
         self.1
             .send(restate_ingress_kafka::Command::UpdateSubscriptions(
                 subscriptions,
             ))
             .await
+            .map_err(|_| WorkerHandleError::Unreachable)?;
+        self.2
+            .send(restate_ingress_sqs::Command::UpdateSubscriptions(
+                sqs_subscriptions,
+            ))
+            .await
             .map_err(|_| WorkerHandleError::Unreachable)
     }
 }
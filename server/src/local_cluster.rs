@@ -0,0 +1,154 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use enumset::enum_set;
+use futures_util::stream::{self, StreamExt};
+use regex::Regex;
+
+use restate_local_cluster_runner::cluster::Cluster;
+use restate_local_cluster_runner::node::{BinarySource, Node};
+use restate_local_cluster_runner::shutdown;
+use restate_types::config::{Configuration, LogFormat};
+use restate_types::logs::metadata::ProviderKind;
+use restate_types::nodes_config::Role;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Starts a local multi-node cluster for development.
+///
+/// Every node runs as a child process of this binary with its own data directory and randomly
+/// assigned ports. An additional node runs the admin and metadata store roles, which are shared by
+/// all other nodes. Logs are replicated across the nodes and the logs of all nodes are combined
+/// on stdout, prefixed with the node name.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct LocalClusterArguments {
+    /// Number of worker and log-server nodes to start.
+    #[arg(long, default_value_t = 3)]
+    nodes: u32,
+
+    /// Directory holding the data directories of all nodes. Defaults to
+    /// `restate-local-cluster` in the current working directory.
+    #[arg(long, value_name = "DIR")]
+    base_dir: Option<PathBuf>,
+
+    /// Name of the cluster.
+    #[arg(long, default_value = "local-cluster")]
+    cluster_name: String,
+}
+
+/// Runs the local cluster until a shutdown signal is received. The given configuration is used as
+/// base configuration of all nodes.
+pub fn run(args: LocalClusterArguments, mut base_config: Configuration) -> i32 {
+    base_config.common.log_format = LogFormat::Compact;
+    base_config.common.log_disable_ansi_codes = true;
+    base_config.bifrost.default_provider = ProviderKind::Replicated;
+
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(err) => {
+            eprintln!("Failed to determine the path of the restate-server binary: {err}");
+            return 1;
+        }
+    };
+    let base_dir = match args.base_dir {
+        Some(base_dir) => base_dir,
+        None => match std::env::current_dir() {
+            Ok(current_dir) => current_dir.join("restate-local-cluster"),
+            Err(err) => {
+                eprintln!("Failed to determine the current working directory: {err}");
+                return 1;
+            }
+        },
+    };
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime builds");
+
+    runtime.block_on(async move {
+        let nodes = Node::new_test_nodes_with_metadata(
+            base_config,
+            BinarySource::Path(binary.into_os_string()),
+            enum_set!(Role::Worker | Role::LogServer),
+            args.nodes,
+        );
+
+        // Subscribe to the logs before starting the nodes to not miss any lines
+        let match_all = Regex::new(".*").expect("valid regex");
+        let mut combined_logs = stream::select_all(nodes.iter().map(|node| {
+            let node_name = node.node_name().to_owned();
+            node.lines(match_all.clone())
+                .map(move |line| format!("{node_name:>13} | {line}"))
+                .boxed()
+        }));
+
+        // start capturing signals
+        let mut shutdown_signal = std::pin::pin!(shutdown());
+
+        let cluster = Cluster::builder()
+            .cluster_name(args.cluster_name)
+            .nodes(nodes)
+            .base_dir(base_dir)
+            .build();
+
+        let mut cluster = tokio::select! {
+            started = cluster.start() => match started {
+                Ok(cluster) => cluster,
+                Err(err) => {
+                    eprintln!("Failed to start the local cluster: {err}");
+                    return 1;
+                }
+            },
+            _ = &mut shutdown_signal => return 0,
+        };
+
+        println!(
+            "Started cluster '{}' in {}",
+            cluster.cluster_name(),
+            cluster.base_dir().display()
+        );
+        for node in &cluster.nodes {
+            if let Some(admin_address) = node.admin_address() {
+                println!("{:>13} | admin: http://{admin_address}", node.node_name());
+            }
+            if let Some(ingress_address) = node.ingress_address() {
+                println!(
+                    "{:>13} | ingress: http://{ingress_address}",
+                    node.node_name()
+                );
+            }
+        }
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_signal => break,
+                line = combined_logs.next() => match line {
+                    Some(line) => println!("{line}"),
+                    None => {
+                        eprintln!("All nodes of the local cluster have exited");
+                        return 1;
+                    }
+                }
+            }
+        }
+
+        match cluster.graceful_shutdown(SHUTDOWN_TIMEOUT).await {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("Failed to shut down the local cluster: {err}");
+                1
+            }
+        }
+    })
+}
@@ -34,6 +34,9 @@ use restate_types::config::CommonOptionCliOverride;
 use restate_types::config::{node_dir, Configuration};
 use restate_types::config_loader::ConfigLoaderBuilder;
 
+#[cfg(feature = "local-cluster")]
+mod local_cluster;
+mod provision;
 mod signal;
 
 use restate_node::Node;
@@ -70,6 +73,18 @@ struct RestateArguments {
 
     #[clap(flatten)]
     opts_overrides: CommonOptionCliOverride,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Provisions the metadata of a new cluster and exits. Safe to run on every boot.
+    Provision(provision::ProvisionArguments),
+    /// Starts a local multi-node cluster for development.
+    #[cfg(feature = "local-cluster")]
+    LocalCluster(local_cluster::LocalClusterArguments),
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -137,6 +152,16 @@ fn main() {
         println!("{}", config.dump().expect("config is toml serializable"));
         std::process::exit(0);
     }
+    match cli_args.command {
+        Some(Command::Provision(provision_args)) => {
+            std::process::exit(provision::run(provision_args, &config));
+        }
+        #[cfg(feature = "local-cluster")]
+        Some(Command::LocalCluster(local_cluster_args)) => {
+            std::process::exit(local_cluster::run(local_cluster_args, config));
+        }
+        None => {}
+    }
     if std::io::stdout().is_terminal() {
         let mut stdout = std::io::stdout().lock();
         let _ = writeln!(
@@ -214,6 +239,12 @@ fn main() {
             // This starts node roles and the rest of the system async under tasks managed by
             // the TaskCenter.
             let _ = TaskCenter::spawn(TaskKind::SystemBoot, "init", node.unwrap().start());
+            // Unmanaged, so that the tasks can still be dumped while shutting down
+            let _ = TaskCenter::spawn_unmanaged(
+                TaskKind::Disposable,
+                "sigquit-dump-tasks",
+                signal::sigquit_dump_tasks(),
+            );
 
             let task_center_watch = TaskCenter::current().shutdown_token();
             tokio::pin!(task_center_watch);
@@ -228,19 +259,21 @@ fn main() {
                         let signal_reason = format!("received signal {}", signal_name);
 
 
-                        let shutdown_with_timeout = tokio::time::timeout(
-                            Configuration::pinned().common.shutdown_grace_period(),
-                            async {
-                                TaskCenter::shutdown_node(&signal_reason, 0).await;
-                                rocksdb_manager.shutdown().await;
-                            }
-                        );
+                        // Every phase of the shutdown has its own deadline
+                        TaskCenter::shutdown_node(&signal_reason, 0).await;
 
-                        // ignore the result because we are shutting down
-                        let shutdown_result = shutdown_with_timeout.await;
+                        // RocksDB is stopped last, once nothing writes to it anymore
+                        let rocksdb_timeout: Duration =
+                            Configuration::pinned().common.shutdown_phases.rocksdb_timeout.into();
+                        info!("** Shutdown phase rocksdb started");
+                        let shutdown_result =
+                            tokio::time::timeout(rocksdb_timeout, rocksdb_manager.shutdown()).await;
 
                         if shutdown_result.is_err() {
-                            warn!("Could not gracefully shut down Restate, terminating now.");
+                            warn!(
+                                "Could not shut down RocksDB within {:?}, terminating now.",
+                                rocksdb_timeout
+                            );
                         } else {
                             info!("Restate has been gracefully shut down.");
                         }
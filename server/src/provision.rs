@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroU32;
+
+use restate_core::metadata_store::ReadError;
+use restate_node::{provision_cluster, ClusterProvisioning};
+use restate_types::cluster_controller::ReplicationStrategy;
+use restate_types::config::{Configuration, MetadataStoreClient};
+use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
+use restate_types::nodes_config::{NodesConfiguration, Role};
+
+/// Provisions the metadata of a new cluster and exits.
+///
+/// The cluster name, the number of partitions and the log provider are taken from the
+/// configuration, e.g. `--cluster-name` and `--bootstrap-num-partitions`. Running it again on a
+/// provisioned cluster succeeds if the existing metadata matches, and fails without modifying
+/// anything otherwise.
+///
+/// The metadata store must be reachable: an embedded metadata store is only served while its node
+/// is running, which provisions the cluster on boot anyway.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ProvisionArguments {
+    /// Number of replicas of every partition processor. Defaults to the configured
+    /// `admin.default-replication-strategy`.
+    #[arg(long)]
+    replication_factor: Option<NonZeroU32>,
+}
+
+pub fn run(args: ProvisionArguments, config: &Configuration) -> i32 {
+    let mut provisioning = ClusterProvisioning::from_configuration(config);
+    if let Some(replication_factor) = args.replication_factor {
+        provisioning.replication_strategy = ReplicationStrategy::Factor(replication_factor);
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime builds");
+
+    runtime.block_on(async {
+        if let Err(err) = restate_core::network::tls::init(&config.networking) {
+            eprintln!("Failed to initialize the network TLS: {err}");
+            return 1;
+        }
+
+        let metadata_store_client = match restate_metadata_store::local::create_client(
+            config.common.metadata_store_client.clone(),
+        )
+        .await
+        {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("Failed to create the metadata store client: {err}");
+                return 1;
+            }
+        };
+
+        // Don't retry for long against the embedded metadata store of this very node, which isn't
+        // served unless the node is running
+        if config.has_role(Role::MetadataStore)
+            && matches!(
+                config.common.metadata_store_client.metadata_store_client,
+                MetadataStoreClient::Embedded { .. }
+            )
+        {
+            if let Err(ReadError::Network(err)) = metadata_store_client
+                .get::<NodesConfiguration>(NODES_CONFIG_KEY.clone())
+                .await
+            {
+                eprintln!(
+                    "Failed to reach the metadata store embedded in this node: {err}. Either run \
+                    this command while the node is running, or start the node with \
+                    `--allow-bootstrap true`, which provisions the cluster from the same configuration."
+                );
+                return 1;
+            }
+        }
+
+        match provision_cluster(
+            &metadata_store_client,
+            &provisioning,
+            config.common.network_error_retry_policy.clone(),
+        )
+        .await
+        {
+            Ok(()) => {
+                println!("Cluster '{}' is provisioned", provisioning.cluster_name);
+                0
+            }
+            Err(err) => {
+                eprintln!("Failed to provision the cluster: {err}");
+                1
+            }
+        }
+    })
+}
@@ -13,6 +13,7 @@ use std::io::Write;
 use tokio::signal::unix::{signal, SignalKind};
 use tracing::{info, warn};
 
+use restate_core::TaskCenter;
 use restate_types::config::Configuration;
 
 pub(super) async fn shutdown() -> &'static str {
@@ -44,6 +45,25 @@ pub(super) async fn sigusr_dump_config() {
     }
 }
 
+/// Dump the tasks managed by task-center as JSON to stderr on SIGQUIT, e.g. to find out which
+/// tasks hold up a shutdown
+pub(super) async fn sigquit_dump_tasks() {
+    let mut stream = signal(SignalKind::quit()).expect("failed to register handler for SIGQUIT");
+
+    loop {
+        stream.recv().await;
+        warn!("Received SIGQUIT, dumping tasks");
+        let tasks = TaskCenter::current().dump_tasks().await;
+        match serde_json::to_string_pretty(&tasks) {
+            Err(e) => warn!("Failed to dump tasks: {}", e),
+            Ok(tasks) => {
+                let mut stderr = std::io::stderr().lock();
+                let _ = writeln!(&mut stderr, "{}", tasks);
+            }
+        }
+    }
+}
+
 async fn await_signal(kind: SignalKind) {
     signal(kind)
         .expect("failed to register signal handler")
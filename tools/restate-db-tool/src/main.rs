@@ -0,0 +1,196 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use clap::{Parser, Subcommand};
+use strum::VariantArray;
+
+use restate_partition_store::inspect::{DecodedRecord, ReadOnlyPartitionStoreDb};
+use restate_partition_store::keys::KeyKind;
+use restate_storage_api::StorageError;
+use restate_types::identifiers::{InvocationId, PartitionId};
+
+/// Inspects the partition store of a node offline. The database is opened read-only, hence it
+/// can be inspected even if the node doesn't start.
+#[derive(Debug, Clone, Parser)]
+#[command(author, version, about)]
+struct Arguments {
+    /// The data dir of the node, which contains the `db` directory of the partition store
+    #[arg(long, env = "RESTATE_DB_TOOL_DATA_DIR")]
+    data_dir: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Lists the partitions and the column families of the database
+    List,
+    /// Dumps the decoded records of a partition
+    Dump {
+        partition_id: u16,
+        /// Only dump the records of this key kind, e.g. `journal` or `invocation-status`
+        #[arg(long, value_parser = parse_key_kind)]
+        key_kind: Option<KeyKind>,
+        /// Hex encoded key to start from, inclusive
+        #[arg(long, value_parser = parse_hex)]
+        from: Option<HexKey>,
+        /// Hex encoded key to stop at, exclusive
+        #[arg(long, value_parser = parse_hex)]
+        to: Option<HexKey>,
+        /// Maximum number of records to dump
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Prints the invocation status and the journal of an invocation
+    Invocation { invocation_id: InvocationId },
+    /// Prints the key kinds of the records of the partitions, and the codecs of their values
+    Schema {
+        /// Only print the schema of this partition
+        partition_id: Option<u16>,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Arguments::parse();
+    let db = ReadOnlyPartitionStoreDb::open(&args.data_dir).with_context(|| {
+        format!(
+            "cannot open the partition store in {}",
+            args.data_dir.display()
+        )
+    })?;
+
+    match args.command {
+        Command::List => list(&db),
+        Command::Dump {
+            partition_id,
+            key_kind,
+            from,
+            to,
+            limit,
+        } => {
+            let from = from
+                .map(|key| key.0)
+                .or_else(|| key_kind.map(|key_kind| key_kind.as_bytes().to_vec()));
+            let to = to
+                .map(|key| key.0)
+                .or_else(|| key_kind.map(|key_kind| key_kind.exclusive_upper_bound().to_vec()));
+            let records = db.scan(
+                PartitionId::from(partition_id),
+                from.as_deref(),
+                to.as_deref(),
+                limit,
+            )?;
+            for record in records {
+                print_record(record);
+            }
+            Ok(())
+        }
+        Command::Invocation { invocation_id } => {
+            let Some((partition_id, records)) = db.invocation(&invocation_id)? else {
+                return Err(anyhow!("invocation {invocation_id} not found"));
+            };
+            println!("Partition {partition_id}");
+            for record in records {
+                print_record(record);
+            }
+            Ok(())
+        }
+        Command::Schema { partition_id } => schema(&db, partition_id.map(PartitionId::from)),
+    }
+}
+
+fn list(db: &ReadOnlyPartitionStoreDb) -> anyhow::Result<()> {
+    println!(
+        "{:<24} {:>10} {:>16} {:>16}",
+        "COLUMN-FAMILY", "PARTITION", "ESTIMATED-KEYS", "SST-FILES-SIZE"
+    );
+    for cf in db.column_families()? {
+        println!(
+            "{:<24} {:>10} {:>16} {:>16}",
+            cf.name,
+            optional(cf.partition_id),
+            optional(cf.estimated_num_keys),
+            optional(cf.live_sst_files_size),
+        );
+    }
+    Ok(())
+}
+
+fn schema(db: &ReadOnlyPartitionStoreDb, partition_id: Option<PartitionId>) -> anyhow::Result<()> {
+    let partition_ids = match partition_id {
+        Some(partition_id) => vec![partition_id],
+        None => db.partition_ids(),
+    };
+
+    for partition_id in partition_ids {
+        println!("Partition {partition_id}");
+        for stats in db.key_kind_stats(partition_id)? {
+            let codecs: Vec<_> = stats.codecs.iter().map(ToString::to_string).collect();
+            print!(
+                "  {:<28} {:>12} records  codecs: [{}]",
+                stats.key_kind.to_string(),
+                stats.records,
+                codecs.join(", ")
+            );
+            match stats.superseded_by {
+                Some(key_kind) => println!("  (superseded by {key_kind}, pending migration)"),
+                None => println!(),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_record(record: Result<DecodedRecord, StorageError>) {
+    match record {
+        Ok(record) => println!("{} {}\n    {}", record.key_kind, record.key, record.value),
+        Err(err) => println!("<failed decoding the record: {err}>"),
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn parse_key_kind(value: &str) -> Result<KeyKind, String> {
+    let normalized = value.replace(['-', '_'], "");
+    KeyKind::VARIANTS
+        .iter()
+        .find(|key_kind| key_kind.to_string().eq_ignore_ascii_case(&normalized))
+        .copied()
+        .ok_or_else(|| {
+            let key_kinds: Vec<_> = KeyKind::VARIANTS.iter().map(ToString::to_string).collect();
+            format!(
+                "unknown key kind, expected one of: {}",
+                key_kinds.join(", ")
+            )
+        })
+}
+
+#[derive(Debug, Clone)]
+struct HexKey(Vec<u8>);
+
+fn parse_hex(value: &str) -> Result<HexKey, String> {
+    if !value.is_ascii() || value.len() % 2 != 0 {
+        return Err("expected an even number of hex digits".to_owned());
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|idx| {
+            u8::from_str_radix(&value[idx..idx + 2], 16)
+                .map_err(|err| format!("invalid hex: {err}"))
+        })
+        .collect::<Result<_, _>>()
+        .map(HexKey)
+}
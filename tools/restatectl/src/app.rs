@@ -17,6 +17,8 @@ use restate_cli_util::CommonOpts;
 use restate_types::net::AdvertisedAddress;
 
 use crate::commands::cluster::overview::ClusterStatusOpts;
+use crate::commands::cluster_spec::ClusterSpec;
+use crate::commands::debug::NodeDebug;
 use crate::commands::log::Logs;
 use crate::commands::metadata::Metadata;
 use crate::commands::node::Nodes;
@@ -69,9 +71,15 @@ pub enum Command {
     /// Partition processor snapshots
     #[clap(subcommand)]
     Snapshots(Snapshot),
+    /// Export and apply the deployments, services and subscriptions of the cluster
+    #[clap(subcommand)]
+    Spec(ClusterSpec),
     /// Commands that operate on replicated loglets
     #[clap(subcommand)]
     ReplicatedLoglet(ReplicatedLoglet),
+    /// Inspect storage and bifrost internals of a node
+    #[clap(subcommand)]
+    Debug(NodeDebug),
 }
 
 fn init(common_opts: &CommonOpts) {
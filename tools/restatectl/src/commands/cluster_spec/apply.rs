@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use clap_stdin::FileOrStdin;
+use cling::prelude::*;
+use reqwest::header::CONTENT_TYPE;
+
+use restate_cli_util::c_success;
+
+use super::{http_client, read_spec, AdminOpts};
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "apply_spec")]
+pub struct ApplyOpts {
+    #[clap(flatten)]
+    admin: AdminOpts,
+
+    /// The YAML document to apply, can be read from stdin or a file path. Deployments and
+    /// subscriptions which already exist are left untouched, hence a document can be applied
+    /// multiple times.
+    doc: FileOrStdin,
+}
+
+async fn apply_spec(opts: &ApplyOpts) -> anyhow::Result<()> {
+    let doc = opts.doc.clone().contents()?;
+
+    let url = opts.admin.cluster_spec_url()?;
+    let response = http_client()?
+        .put(url.clone())
+        .header(CONTENT_TYPE, "application/yaml")
+        .body(doc)
+        .send()
+        .await
+        .with_context(|| format!("cannot connect to the admin API at {url}"))?;
+    read_spec(response).await?;
+
+    c_success!("Applied the cluster spec");
+    Ok(())
+}
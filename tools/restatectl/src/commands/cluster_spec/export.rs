@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use cling::prelude::*;
+
+use restate_cli_util::c_print;
+
+use super::{http_client, read_spec, AdminOpts};
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "export_spec")]
+pub struct ExportOpts {
+    #[clap(flatten)]
+    admin: AdminOpts,
+
+    /// Write the document to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+async fn export_spec(opts: &ExportOpts) -> anyhow::Result<()> {
+    let url = opts.admin.cluster_spec_url()?;
+    let response = http_client()?
+        .get(url.clone())
+        .send()
+        .await
+        .with_context(|| format!("cannot connect to the admin API at {url}"))?;
+    let spec = read_spec(response).await?;
+
+    match &opts.output {
+        Some(path) => std::fs::write(path, spec)
+            .with_context(|| format!("cannot write the cluster spec to {}", path.display()))?,
+        None => c_print!("{spec}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod apply;
+mod export;
+
+use anyhow::{bail, Context};
+use cling::prelude::*;
+use url::Url;
+
+use restate_cli_util::CliContext;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum ClusterSpec {
+    /// Print the deployments, services and subscriptions of the cluster as a YAML document
+    Export(export::ExportOpts),
+    /// Apply a YAML document describing deployments, services and subscriptions
+    Apply(apply::ApplyOpts),
+}
+
+#[derive(Args, Clone, Debug)]
+#[clap()]
+pub struct AdminOpts {
+    /// Admin API address of a node running the admin role
+    #[arg(
+        long,
+        value_hint = clap::ValueHint::Url,
+        default_value = "http://localhost:9070/",
+        env = "RESTATE_ADMIN_URL"
+    )]
+    admin_url: Url,
+}
+
+impl AdminOpts {
+    fn cluster_spec_url(&self) -> anyhow::Result<Url> {
+        self.admin_url
+            .join("cluster-spec")
+            .with_context(|| format!("invalid admin url {}", self.admin_url))
+    }
+}
+
+fn http_client() -> anyhow::Result<reqwest::Client> {
+    let ctx = CliContext::get();
+    reqwest::Client::builder()
+        .connect_timeout(ctx.connect_timeout())
+        .timeout(ctx.request_timeout())
+        .build()
+        .context("cannot create the http client")
+}
+
+/// Returns the YAML document of a successful response, or the error message of the admin API.
+async fn read_spec(response: reqwest::Response) -> anyhow::Result<String> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .context("cannot read the response of the admin API")?;
+    if !status.is_success() {
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|error| Some(error.get("message")?.as_str()?.to_owned()))
+            .unwrap_or(body);
+        bail!("the admin API responded with {status}: {message}");
+    }
+    Ok(body)
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use cling::prelude::*;
+
+use restate_cli_util::_comfy_table::{Cell, Table};
+use restate_cli_util::c_println;
+use restate_cli_util::ui::console::StyledTable;
+use restate_core::network::protobuf::node_svc::GetBifrostStateRequest;
+
+use crate::app::ConnectionInfo;
+use crate::commands::debug::{connect, optional};
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "get_bifrost_state")]
+pub struct BifrostOpts {
+    /// Only print the state of these logs
+    #[arg(long)]
+    log_id: Vec<u32>,
+
+    /// Find the tail of each log. This may involve other nodes of the cluster.
+    #[arg(long)]
+    find_tail: bool,
+}
+
+async fn get_bifrost_state(connection: &ConnectionInfo, opts: &BifrostOpts) -> anyhow::Result<()> {
+    let mut client = connect(connection).await?;
+
+    let response = client
+        .get_bifrost_state(GetBifrostStateRequest {
+            log_ids: opts.log_id.clone(),
+            find_tail: opts.find_tail,
+        })
+        .await?
+        .into_inner();
+
+    c_println!(
+        "Enabled providers: {}",
+        response.enabled_providers.join(", ")
+    );
+    c_println!("Log Configuration ({})", response.logs_version);
+
+    let mut logs_table = Table::new_styled();
+    logs_table.set_styled_header(vec![
+        "L-ID",
+        "SEGMENTS",
+        "KIND",
+        "FROM-LSN",
+        "TRIM-POINT",
+        "TAIL-LSN",
+        "SEALED",
+        "ERROR",
+    ]);
+
+    for log in response.logs {
+        logs_table.add_row(vec![
+            Cell::new(log.log_id),
+            Cell::new(log.num_segments),
+            Cell::new(log.tail_segment_provider),
+            Cell::new(log.tail_segment_base_lsn),
+            Cell::new(optional(log.trim_point)),
+            Cell::new(optional(log.tail_lsn)),
+            Cell::new(optional(log.sealed)),
+            Cell::new(log.error.unwrap_or_default()),
+        ]);
+    }
+    c_println!("{}", logs_table);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod bifrost;
+mod partition_stores;
+mod rocksdb;
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+use tonic::transport::Channel;
+
+use restate_core::network::protobuf::node_svc::node_debug_svc_client::NodeDebugSvcClient;
+
+use crate::app::ConnectionInfo;
+use crate::util::grpc_connect;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum NodeDebug {
+    /// Print the statistics of the node's RocksDB databases and column families
+    Rocksdb(rocksdb::RocksDbOpts),
+    /// Print the state of the logs as seen by bifrost on the node
+    Bifrost(bifrost::BifrostOpts),
+    /// List the partition stores which are open on the node
+    PartitionStores(partition_stores::PartitionStoresOpts),
+}
+
+async fn connect(connection: &ConnectionInfo) -> anyhow::Result<NodeDebugSvcClient<Channel>> {
+    let channel = grpc_connect(connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to node at {}",
+                connection.cluster_controller
+            )
+        })?;
+
+    Ok(NodeDebugSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip))
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use cling::prelude::*;
+
+use restate_cli_util::_comfy_table::{Cell, Table};
+use restate_cli_util::c_println;
+use restate_cli_util::ui::console::StyledTable;
+
+use crate::app::ConnectionInfo;
+use crate::commands::debug::{connect, optional};
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "list_partition_stores")]
+pub struct PartitionStoresOpts {}
+
+async fn list_partition_stores(
+    connection: &ConnectionInfo,
+    _opts: &PartitionStoresOpts,
+) -> anyhow::Result<()> {
+    let mut client = connect(connection).await?;
+
+    let response = client.list_partition_stores(()).await?.into_inner();
+
+    let mut partition_stores_table = Table::new_styled();
+    partition_stores_table.set_styled_header(vec![
+        "P-ID",
+        "START-KEY",
+        "END-KEY",
+        "CF",
+        "APPLIED-LSN",
+        "PERSISTED-LSN",
+        "ARCHIVED-LSN",
+    ]);
+
+    for partition_store in response.partition_stores {
+        partition_stores_table.add_row(vec![
+            Cell::new(partition_store.partition_id),
+            Cell::new(partition_store.start_key),
+            Cell::new(partition_store.end_key),
+            Cell::new(partition_store.cf_name),
+            Cell::new(optional(partition_store.last_applied_lsn)),
+            Cell::new(optional(partition_store.last_persisted_lsn)),
+            Cell::new(optional(partition_store.last_archived_lsn)),
+        ]);
+    }
+    c_println!("{}", partition_stores_table);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+
+use cling::prelude::*;
+
+use restate_cli_util::_comfy_table::{Cell, Table};
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::{c_indentln, c_println};
+use restate_core::network::protobuf::node_svc::GetRocksDbStatsRequest;
+
+use crate::app::ConnectionInfo;
+use crate::commands::debug::connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "get_rocksdb_stats")]
+pub struct RocksDbOpts {
+    /// Only print the statistics of this database
+    #[arg(long)]
+    db: Option<String>,
+}
+
+async fn get_rocksdb_stats(connection: &ConnectionInfo, opts: &RocksDbOpts) -> anyhow::Result<()> {
+    let mut client = connect(connection).await?;
+
+    let response = client
+        .get_rocks_db_stats(GetRocksDbStatsRequest {
+            db: opts.db.clone(),
+        })
+        .await?
+        .into_inner();
+
+    c_println!(
+        "Write buffer manager: {} / {} bytes",
+        response.write_buffer_manager_usage,
+        response.write_buffer_manager_capacity
+    );
+
+    for db in response.dbs {
+        c_println!();
+        c_println!("Database '{}'", db.name);
+        for (property, value) in BTreeMap::from_iter(db.properties) {
+            c_indentln!(2, "{}: {}", property, value);
+        }
+
+        let mut cfs_table = Table::new_styled();
+        cfs_table.set_styled_header(vec!["CF", "PROPERTY", "VALUE"]);
+        for cf in db.cfs {
+            for (property, value) in BTreeMap::from_iter(cf.properties) {
+                cfs_table.add_row(vec![
+                    Cell::new(&cf.name),
+                    Cell::new(property),
+                    Cell::new(value),
+                ]);
+            }
+        }
+        c_println!("{}", cfs_table);
+    }
+
+    Ok(())
+}
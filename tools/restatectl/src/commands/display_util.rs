@@ -8,10 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeDelta};
 use restate_cli_util::_comfy_table::{Cell, Color};
-use restate_cli_util::ui::{timestamp_as_human_duration, Tense};
-use std::time::SystemTime;
+use restate_cli_util::ui::{duration_to_human_rough, timestamp_as_human_duration, Tense};
+use std::time::{Duration, SystemTime};
 
 pub fn render_as_duration(ts: Option<prost_types::Timestamp>, tense: Tense) -> Cell {
     let ts: Option<SystemTime> = ts
@@ -25,3 +25,17 @@ pub fn render_as_duration(ts: Option<prost_types::Timestamp>, tense: Tense) -> C
         Cell::new("-").fg(Color::Red)
     }
 }
+
+pub fn render_lag(lag: Option<prost_types::Duration>) -> Cell {
+    let Some(lag) = lag.and_then(|lag| Duration::try_from(lag).ok()) else {
+        return Cell::new("-");
+    };
+    if lag.is_zero() {
+        Cell::new("0s")
+    } else {
+        Cell::new(duration_to_human_rough(
+            TimeDelta::from_std(lag).unwrap_or(TimeDelta::MAX),
+            Tense::Present,
+        ))
+    }
+}
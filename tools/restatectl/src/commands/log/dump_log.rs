@@ -23,7 +23,7 @@ use restate_rocksdb::RocksDbManager;
 use restate_types::config::Configuration;
 use restate_types::live::Live;
 use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber};
-use restate_wal_protocol::Envelope;
+use restate_wal_protocol::{command_kind_filter, CommandDiscriminants, Envelope};
 
 use crate::environment::metadata_store;
 use crate::environment::task_center::run_in_task_center;
@@ -49,6 +49,18 @@ pub struct DumpLogOpts {
     /// Start LSN, if unset it'll read from the oldest record in the log.
     #[arg(long)]
     from_lsn: Option<u64>,
+
+    /// Only dump records for partition keys greater or equal to this key.
+    #[arg(long)]
+    from_partition_key: Option<u64>,
+
+    /// Only dump records for partition keys less or equal to this key.
+    #[arg(long)]
+    to_partition_key: Option<u64>,
+
+    /// Only dump records with the given command type, e.g. `Invoke`. Can be repeated.
+    #[arg(long = "command")]
+    commands: Vec<CommandDiscriminants>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -118,8 +130,17 @@ async fn dump_log(opts: &DumpLogOpts) -> anyhow::Result<()> {
             to_lsn = ?tail.offset().prev(),
             "Creating Bifrost log reader",
         );
-        let mut reader =
-            bifrost.create_reader(log_id, KeyFilter::Any, from_lsn, tail.offset().prev())?;
+        let filter = if opts.from_partition_key.is_some() || opts.to_partition_key.is_some() {
+            KeyFilter::Within(
+                opts.from_partition_key.unwrap_or(0)..=opts.to_partition_key.unwrap_or(u64::MAX),
+            )
+        } else {
+            KeyFilter::Any
+        };
+        let mut reader = bifrost.create_reader(log_id, filter, from_lsn, tail.offset().prev())?;
+        if !opts.commands.is_empty() {
+            reader = reader.with_record_filter(command_kind_filter(opts.commands.clone()));
+        }
 
         while let Some(record) = reader.next().await {
             debug!("Got record: {:?}", record);
@@ -14,6 +14,7 @@ mod find_tail;
 mod gen_metadata;
 pub mod list_logs;
 mod reconfigure;
+mod tail_log;
 mod trim_log;
 
 use std::{ops::RangeInclusive, str::FromStr};
@@ -36,6 +37,8 @@ pub enum Logs {
     Describe(describe_log::DescribeLogIdOpts),
     /// Dump the contents of a bifrost log
     Dump(dump_log::DumpLogOpts),
+    /// Follow a log through the cluster controller and print its decoded records as JSON
+    Tail(tail_log::TailLogOpts),
     /// Trim a log to a particular Log Sequence Number (LSN)
     Trim(trim_log::TrimLogOpts),
     /// Reconfigure a log by sealing the tail segment
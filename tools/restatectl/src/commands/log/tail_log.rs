@@ -0,0 +1,130 @@
+// Copyright (c) 2023 - 2025 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use cling::prelude::*;
+use tonic::codec::CompressionEncoding;
+
+use restate_admin::cluster_controller::protobuf::cluster_ctrl_svc_client::ClusterCtrlSvcClient;
+use restate_admin::cluster_controller::protobuf::tail_log_response::Record;
+use restate_admin::cluster_controller::protobuf::TailLogRequest;
+use restate_cli_util::{c_eprintln, c_println};
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+use restate_types::logs::{LogId, Lsn};
+use restate_types::storage::StorageCodec;
+use restate_wal_protocol::{CommandDiscriminants, Envelope};
+
+use crate::app::ConnectionInfo;
+use crate::util::grpc_connect;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "tail_log")]
+pub struct TailLogOpts {
+    /// The log id to tail
+    log_id: u32,
+
+    /// Start LSN, if unset it'll read from the oldest record in the log
+    #[arg(long)]
+    from_lsn: Option<u64>,
+
+    /// Only print records of the given invocation
+    #[arg(long)]
+    invocation_id: Option<InvocationId>,
+
+    /// Only print records with the given command type, e.g. `Invoke`. Can be repeated.
+    #[arg(long = "command")]
+    commands: Vec<CommandDiscriminants>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DecodedLogRecord {
+    log_id: LogId,
+    lsn: Lsn,
+    envelope: Envelope,
+}
+
+async fn tail_log(connection: &ConnectionInfo, opts: &TailLogOpts) -> anyhow::Result<()> {
+    let channel = grpc_connect(connection.cluster_controller.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to cluster controller at {}",
+                connection.cluster_controller
+            )
+        })?;
+    let mut client =
+        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+
+    // all records of an invocation carry its partition key
+    let partition_key = opts
+        .invocation_id
+        .as_ref()
+        .map(WithPartitionKey::partition_key);
+    let request = TailLogRequest {
+        log_id: opts.log_id,
+        from_lsn: opts.from_lsn,
+        from_partition_key: partition_key,
+        to_partition_key: partition_key,
+    };
+
+    let log_id = LogId::from(opts.log_id);
+    let mut stream = client
+        .tail_log(request)
+        .await
+        .with_context(|| format!("failed to tail log {}", log_id))?
+        .into_inner();
+
+    while let Some(response) = stream
+        .message()
+        .await
+        .with_context(|| format!("failed reading log {}", log_id))?
+    {
+        let lsn = Lsn::from(response.lsn);
+        let data = match response.record {
+            Some(Record::Data(data)) => data,
+            Some(Record::TrimGapToLsn(trim_gap_to)) => {
+                c_eprintln!("Trim gap found, skipping until after {}", trim_gap_to);
+                continue;
+            }
+            None => continue,
+        };
+
+        let envelope =
+            StorageCodec::decode::<Envelope, _>(&mut data.as_ref()).with_context(|| {
+                format!(
+                    "Error decoding record at lsn={} from log_id={}",
+                    lsn, log_id
+                )
+            })?;
+
+        if opts
+            .invocation_id
+            .is_some_and(|invocation_id| envelope.command.invocation_id() != Some(invocation_id))
+        {
+            continue;
+        }
+        if !opts.commands.is_empty()
+            && !opts
+                .commands
+                .contains(&CommandDiscriminants::from(&envelope.command))
+        {
+            continue;
+        }
+
+        let decoded_log_record = DecodedLogRecord {
+            log_id,
+            lsn,
+            envelope,
+        };
+        c_println!("{}", serde_json::to_string(&decoded_log_record)?);
+    }
+
+    Ok(())
+}
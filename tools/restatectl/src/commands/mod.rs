@@ -9,6 +9,8 @@
 // by the Apache License, Version 2.0.
 
 pub mod cluster;
+pub mod cluster_spec;
+pub mod debug;
 mod display_util;
 pub mod dump;
 pub mod log;
@@ -31,7 +31,7 @@ use restate_types::storage::StorageCodec;
 use restate_types::{GenerationalNodeId, PlainNodeId, Version};
 
 use crate::app::ConnectionInfo;
-use crate::commands::display_util::render_as_duration;
+use crate::commands::display_util::{render_as_duration, render_lag};
 use crate::commands::log::deserialize_replicated_log_params;
 use crate::util::grpc_connect;
 
@@ -145,6 +145,8 @@ pub async fn list_partitions(
         "EPOCH",
         "SEQUENCER",
         "APPLIED-LSN",
+        "TAIL-LSN",
+        "APPLY-LAG",
         "PERSISTED-LSN",
         "SKIPPED-RECORDS",
         "ARCHIVED-LSN",
@@ -268,6 +270,14 @@ pub async fn list_partitions(
                         .map(|x| x.to_string())
                         .unwrap_or("-".to_owned()),
                 ),
+                Cell::new(
+                    processor
+                        .status
+                        .log_tail_lsn
+                        .map(|x| x.to_string())
+                        .unwrap_or("-".to_owned()),
+                ),
+                render_lag(processor.status.apply_lag),
                 Cell::new(
                     processor
                         .status
@@ -10,6 +10,7 @@
 
 mod gen_metadata;
 pub mod list;
+mod split;
 
 use cling::prelude::*;
 
@@ -20,4 +21,6 @@ pub enum Partitions {
     List(list::ListPartitionsOpts),
     /// Prints a generated partition table in JSON format
     GenerateMetadata(gen_metadata::GeneratePartitionTableOpts),
+    /// Split a partition into two partitions
+    Split(split::SplitPartitionOpts),
 }